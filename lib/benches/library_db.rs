@@ -0,0 +1,324 @@
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use tempfile::TempDir;
+use termusiclib::__bench::TrackInsertable;
+use termusiclib::config::ServerOverlay;
+use termusiclib::new_database::track_ops::{self, RowOrdering, TrackRead};
+use termusiclib::new_database::{Database, ScanProgress, album_ops, artist_ops};
+use termusiclib::track::TrackMetadata;
+use wildmatch::WildMatch;
+
+/// Amount of synthetic tracks in the dataset.
+const TRACKS: usize = 50_000;
+/// Amount of tracks per album.
+const TRACKS_PER_ALBUM: usize = 20;
+/// Amount of albums per artist.
+const ALBUMS_PER_ARTIST: usize = 5;
+/// Amount of distinct genres.
+const GENRES: usize = 40;
+/// Amount of files in the generated directory tree scanned by [`bench_scan`].
+const SCAN_FILES: usize = 5_000;
+
+/// Get the path of the synthetic track at index `idx`, relative to the music directory.
+fn track_file(idx: usize) -> PathBuf {
+    let album = idx / TRACKS_PER_ALBUM;
+    let artist = album / ALBUMS_PER_ARTIST;
+
+    PathBuf::from(format!("Artist {artist}/Album {album}/Track {idx}.mp3"))
+}
+
+/// Get the path of the synthetic track at index `idx`.
+fn track_path(idx: usize) -> PathBuf {
+    Path::new("/music").join(track_file(idx))
+}
+
+/// Get the synthetic metadata of the track at index `idx`.
+fn track_metadata(idx: usize) -> TrackMetadata {
+    let album = idx / TRACKS_PER_ALBUM;
+    let artist = album / ALBUMS_PER_ARTIST;
+
+    TrackMetadata {
+        album: Some(format!("Album {album}")),
+        album_artist: Some(format!("Artist {artist}")),
+        artist: Some(format!("Artist {artist}")),
+        title: Some(format!("Track {idx}")),
+        duration: Some(Duration::from_secs(180 + (idx % 120) as u64)),
+        genre: Some(format!("Genre {}", idx % GENRES)),
+        ..Default::default()
+    }
+}
+
+/// Create a new in-memory database filled with [`TRACKS`] synthetic tracks.
+fn gen_dataset() -> Database {
+    // sqlite treats this path as a new in-memory database
+    let db = Database::new(Path::new(":memory:")).unwrap();
+
+    {
+        let mut conn = db.get_connection();
+        // insert everything in one transaction, otherwise the setup takes much longer than the benchmarks
+        let tx = conn.transaction().unwrap();
+        for idx in 0..TRACKS {
+            let path = track_path(idx);
+            let metadata = track_metadata(idx);
+            TrackInsertable::try_from_track(&path, &metadata)
+                .unwrap()
+                .try_insert_or_update(&tx)
+                .unwrap();
+        }
+        tx.commit().unwrap();
+    }
+
+    db
+}
+
+/// Create a directory tree with [`SCAN_FILES`] empty track files in the temp directory,
+/// and a database which already knows all of them, like after a previous scan.
+///
+/// Returns the directory, which is removed when dropped, and the canonicalized root of the tree,
+/// as that is what the database stores.
+fn gen_scan_tree() -> (TempDir, PathBuf, Database) {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let root = tmp_dir.path();
+    for idx in 0..SCAN_FILES {
+        let path = root.join(track_file(idx));
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, b"").unwrap();
+    }
+    let root = root.canonicalize().unwrap();
+
+    let db = Database::new(Path::new(":memory:")).unwrap();
+    {
+        let mut conn = db.get_connection();
+        let tx = conn.transaction().unwrap();
+        for idx in 0..SCAN_FILES {
+            let path = root.join(track_file(idx));
+            TrackInsertable::try_from_track(&path, &track_metadata(idx))
+                .unwrap()
+                .try_insert_or_update(&tx)
+                .unwrap();
+        }
+        tx.commit().unwrap();
+    }
+
+    (tmp_dir, root, db)
+}
+
+/// The same matching the TUI does for the database search.
+fn match_track(track: &TrackRead, search: &WildMatch) -> bool {
+    let artist_match = track
+        .artist_display
+        .as_ref()
+        .is_some_and(|v| search.matches(&v.to_lowercase()));
+    let title_match = track
+        .title
+        .as_ref()
+        .is_some_and(|v| search.matches(&v.to_lowercase()));
+    let album_match = track
+        .album
+        .as_ref()
+        .is_some_and(|v| search.matches(&v.title.to_lowercase()));
+
+    artist_match || title_match || album_match
+}
+
+fn bench_change_detection(c: &mut Criterion, db: &Database) {
+    let mut group = c.benchmark_group("library-scan");
+    group.sample_size(10);
+
+    // the check done for every file on a non-full scan
+    group.bench_function("track_exists-50k", |b| {
+        let paths: Vec<PathBuf> = (0..TRACKS).map(track_path).collect();
+        b.iter(|| {
+            let conn = db.get_connection();
+            for path in &paths {
+                black_box(track_ops::track_exists(&conn, path).unwrap());
+            }
+        });
+    });
+
+    group.bench_function("try_insert_or_update-1k", |b| {
+        b.iter_batched(
+            || {
+                (0..1000)
+                    .map(|idx| (track_path(idx), track_metadata(idx)))
+                    .collect::<Vec<_>>()
+            },
+            |tracks| {
+                let mut conn = db.get_connection();
+                let tx = conn.transaction().unwrap();
+                for (path, metadata) in &tracks {
+                    TrackInsertable::try_from_track(path, metadata)
+                        .unwrap()
+                        .try_insert_or_update(&tx)
+                        .unwrap();
+                }
+                // do not actually change the dataset for other benchmarks
+                tx.rollback().unwrap();
+            },
+            BatchSize::SmallInput,
+        );
+    });
+
+    group.finish();
+}
+
+/// Benchmark a whole [`Database::scan_path`] of a unchanged directory tree,
+/// which walks the tree, skips all known files and checks all known tracks for missing files.
+fn bench_scan(c: &mut Criterion) {
+    let (_tmp_dir, root, db) = gen_scan_tree();
+    let config = ServerOverlay::default();
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    // the scan spawns its workers on the current runtime
+    let _guard = rt.enter();
+
+    let mut group = c.benchmark_group("library-scan");
+    group.sample_size(10);
+
+    group.bench_function("scan_path-unchanged-5k", |b| {
+        b.iter(|| {
+            let (tx, rx) = mpsc::channel();
+            db.scan_path(
+                &root,
+                &config,
+                false,
+                Some(Box::new(move |progress: ScanProgress| {
+                    if progress.finished {
+                        let _ = tx.send(progress);
+                    }
+                })),
+            )
+            .unwrap();
+
+            black_box(rx.recv().unwrap())
+        });
+    });
+
+    group.finish();
+}
+
+fn bench_criteria(c: &mut Criterion, db: &Database) {
+    let mut group = c.benchmark_group("library-criteria");
+
+    group.bench_function("get_tracks_from_artist", |b| {
+        b.iter(|| {
+            track_ops::get_tracks_from_artist(
+                &db.get_connection(),
+                black_box("Artist 250"),
+                RowOrdering::IdAsc,
+            )
+            .unwrap()
+        });
+    });
+
+    group.bench_function("get_tracks_from_album", |b| {
+        b.iter(|| {
+            track_ops::get_tracks_from_album(
+                &db.get_connection(),
+                black_box("Album 1250"),
+                black_box("Artist 250"),
+                RowOrdering::IdAsc,
+            )
+            .unwrap()
+        });
+    });
+
+    group.bench_function("get_tracks_from_genre", |b| {
+        b.iter(|| {
+            track_ops::get_tracks_from_genre(
+                &db.get_connection(),
+                black_box(Some("Genre 20")),
+                RowOrdering::IdAsc,
+            )
+            .unwrap()
+        });
+    });
+
+    group.bench_function("get_tracks_from_directory", |b| {
+        b.iter(|| {
+            track_ops::get_tracks_from_directory(
+                &db.get_connection(),
+                black_box(Path::new("/music/Artist 250/Album 1250")),
+                RowOrdering::IdAsc,
+            )
+            .unwrap()
+        });
+    });
+
+    group.bench_function("all_distinct_genres", |b| {
+        b.iter(|| track_ops::all_distinct_genres(&db.get_connection()).unwrap());
+    });
+
+    group.bench_function("all_distinct_directories", |b| {
+        b.iter(|| track_ops::all_distinct_directories(&db.get_connection()).unwrap());
+    });
+
+    group.finish();
+}
+
+fn bench_search(c: &mut Criterion, db: &Database) {
+    let mut group = c.benchmark_group("library-search");
+    group.sample_size(10);
+
+    group.bench_function("get_tracks_from_genre_like", |b| {
+        b.iter(|| {
+            track_ops::get_tracks_from_genre_like(
+                &db.get_connection(),
+                black_box("%re 2%"),
+                RowOrdering::IdAsc,
+            )
+            .unwrap()
+        });
+    });
+
+    group.bench_function("get_all_artists_like", |b| {
+        b.iter(|| {
+            artist_ops::get_all_artists_like(
+                &db.get_connection(),
+                black_box("%st 25%"),
+                artist_ops::RowOrdering::IdAsc,
+            )
+            .unwrap()
+        });
+    });
+
+    group.bench_function("get_all_albums_like", |b| {
+        b.iter(|| {
+            album_ops::get_all_albums_like(
+                &db.get_connection(),
+                black_box("%um 125%"),
+                album_ops::RowOrdering::IdAsc,
+            )
+            .unwrap()
+        });
+    });
+
+    let all_tracks = track_ops::get_all_tracks(&db.get_connection(), RowOrdering::IdAsc).unwrap();
+
+    group.bench_function("wildmatch-all-tracks", |b| {
+        let search = WildMatch::new("*track 12*");
+        b.iter(|| {
+            all_tracks
+                .iter()
+                .filter(|v| match_track(v, &search))
+                .count()
+        });
+    });
+
+    group.finish();
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let db = gen_dataset();
+
+    bench_change_detection(c, &db);
+    bench_scan(c);
+    bench_criteria(c, &db);
+    bench_search(c, &db);
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
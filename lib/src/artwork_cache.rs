@@ -0,0 +1,235 @@
+//! A disk cache for artwork fetched from the network, like podcast and episode images.
+//! It is also used to cache other generated data, like the waveforms of tracks.
+//!
+//! Every image is stored as a file named after the md5 hash of its url, and the least recently used
+//! images are removed once the cache grows beyond its size limit.
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::{Context, Result};
+
+use crate::utils::get_app_cache_path;
+
+/// The artwork cache, see the [module docs](self).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArtworkCache {
+    dir: PathBuf,
+    max_size: u64,
+}
+
+impl ArtworkCache {
+    /// The size limit of the cache in the app cache directory, in bytes.
+    pub const DEFAULT_MAX_SIZE: u64 = 100 * 1024 * 1024;
+
+    /// Create a cache storing images in `dir`, limited to `max_size` bytes.
+    #[must_use]
+    pub fn new(dir: PathBuf, max_size: u64) -> Self {
+        Self { dir, max_size }
+    }
+
+    /// Create the cache in `artwork` of the [app cache directory](get_app_cache_path).
+    pub fn new_default() -> Result<Self> {
+        Ok(Self::new(
+            get_app_cache_path()?.join("artwork"),
+            Self::DEFAULT_MAX_SIZE,
+        ))
+    }
+
+    /// Get the path the image of `url` is stored at, regardless of whether it is cached.
+    fn path_for(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{:x}", md5::compute(url)))
+    }
+
+    /// Get the path of the cached image of `url`, if it is cached.
+    ///
+    /// This marks the image as recently used.
+    #[must_use]
+    pub fn get(&self, url: &str) -> Option<PathBuf> {
+        let path = self.path_for(url);
+        if !path.is_file() {
+            return None;
+        }
+
+        // only used for eviction, so failing to update it is not worth failing for
+        let _ = File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(SystemTime::now()));
+
+        Some(path)
+    }
+
+    /// Store `data` as the image of `url`, and evict the least recently used images if above the size limit.
+    ///
+    /// Images larger than the whole cache are not stored, in which case `None` is returned.
+    pub fn insert(&self, url: &str, data: &[u8]) -> Result<Option<PathBuf>> {
+        if data.len() as u64 > self.max_size {
+            return Ok(None);
+        }
+
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("create directory {}", self.dir.display()))?;
+
+        // write to a temporary file first, so that a partial image is never served
+        let path = self.path_for(url);
+        let part_path = path.with_extension("part");
+        std::fs::write(&part_path, data)
+            .with_context(|| format!("write {}", part_path.display()))?;
+        std::fs::rename(&part_path, &path)
+            .with_context(|| format!("rename to {}", path.display()))?;
+
+        self.evict(&path)?;
+
+        Ok(Some(path))
+    }
+
+    /// Get the image of `url` from the cache, or download and cache it if it is not cached yet.
+    pub async fn fetch(&self, url: &str) -> Result<Vec<u8>> {
+        if let Some(path) = self.get(url) {
+            match tokio::fs::read(&path).await {
+                Ok(data) => return Ok(data),
+                Err(err) => warn!("Reading cached artwork {} failed: {err}", path.display()),
+            }
+        }
+
+        let data = reqwest::get(url)
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .with_context(|| format!("fetch {url}"))?
+            .bytes()
+            .await
+            .with_context(|| format!("read response of {url}"))?;
+
+        if let Err(err) = self.insert(url, &data) {
+            warn!("Caching artwork of {url} failed: {err:#}");
+        }
+
+        Ok(data.to_vec())
+    }
+
+    /// Remove the least recently used images until the cache is within its size limit, never removing `keep`.
+    fn evict(&self, keep: &Path) -> Result<()> {
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)
+            .with_context(|| format!("read directory {}", self.dir.display()))?
+        {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            // skip directories and images still being written
+            if !metadata.is_file() || entry.path().extension().is_some_and(|v| v == "part") {
+                continue;
+            }
+
+            entries.push(CacheEntry {
+                path: entry.path(),
+                size: metadata.len(),
+                used: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            });
+        }
+
+        for path in select_evictions(entries, self.max_size, keep) {
+            if let Err(err) = std::fs::remove_file(&path) {
+                warn!("Evicting cached artwork {} failed: {err}", path.display());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A single file in the cache directory.
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    path: PathBuf,
+    size: u64,
+    /// The last time the image was used
+    used: SystemTime,
+}
+
+/// Get the least recently used `entries` to remove to get the total size to at most `max_size`, never including `keep`.
+fn select_evictions(mut entries: Vec<CacheEntry>, max_size: u64, keep: &Path) -> Vec<PathBuf> {
+    // most recently used first, with the one to keep always counted
+    entries.sort_by(|a, b| (b.path == keep, b.used).cmp(&(a.path == keep, a.used)));
+
+    let mut total = 0u64;
+    entries
+        .into_iter()
+        .filter_map(|entry| {
+            total = total.saturating_add(entry.size);
+            (total > max_size && entry.path != keep).then_some(entry.path)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    use pretty_assertions::assert_eq;
+
+    use super::{ArtworkCache, CacheEntry, select_evictions};
+
+    fn entry(name: &str, size: u64, used_secs: u64) -> CacheEntry {
+        CacheEntry {
+            path: PathBuf::from(name),
+            size,
+            used: SystemTime::UNIX_EPOCH + Duration::from_secs(used_secs),
+        }
+    }
+
+    #[test]
+    fn should_evict_least_recently_used() {
+        let entries = vec![
+            entry("a", 40, 3),
+            entry("b", 40, 1),
+            entry("c", 40, 2),
+            entry("new", 40, 0),
+        ];
+
+        assert_eq!(
+            select_evictions(entries, 100, &PathBuf::from("new")),
+            [PathBuf::from("c"), PathBuf::from("b")]
+        );
+    }
+
+    #[test]
+    fn should_not_evict_within_limit() {
+        let entries = vec![entry("a", 40, 1), entry("b", 60, 2)];
+
+        assert!(select_evictions(entries, 100, &PathBuf::from("b")).is_empty());
+    }
+
+    #[test]
+    fn should_store_and_get() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ArtworkCache::new(dir.path().to_path_buf(), 10);
+
+        assert_eq!(cache.get("https://example.com/a.jpg"), None);
+        let path = cache
+            .insert("https://example.com/a.jpg", b"image")
+            .unwrap()
+            .unwrap();
+        assert_eq!(cache.get("https://example.com/a.jpg"), Some(path.clone()));
+        assert_eq!(std::fs::read(&path).unwrap(), b"image");
+        assert_ne!(cache.path_for("https://example.com/b.jpg"), path);
+
+        // too large for the whole cache
+        assert_eq!(
+            cache
+                .insert("https://example.com/b.jpg", b"large image")
+                .unwrap(),
+            None
+        );
+
+        // evicts the older image to fit the new one
+        let other = cache
+            .insert("https://example.com/c.jpg", b"others")
+            .unwrap()
+            .unwrap();
+        assert_eq!(cache.get("https://example.com/c.jpg"), Some(other));
+        assert_eq!(cache.get("https://example.com/a.jpg"), None);
+    }
+}
@@ -0,0 +1,248 @@
+//! Timestamped backups of the termusic databases.
+//!
+//! Backups are done via the SQLite backup API, which makes it safe to back up and restore while the
+//! databases are in use by a running server.
+
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use chrono::Local;
+use rusqlite::backup::Progress;
+use rusqlite::{Connection, MAIN_DB, OpenFlags};
+
+/// The library database, in the app data directory.
+const LIBRARY_DATABASE: &str = "library2.db";
+
+/// The podcast database, in the app data directory.
+const PODCAST_DATABASE: &str = "data.db";
+
+/// The databases (in the app data directory) that are included in a backup.
+pub const DATABASE_FILES: &[&str] = &[LIBRARY_DATABASE, PODCAST_DATABASE];
+
+/// Name of the directory in the app data directory containing all backups.
+const BACKUP_DIR: &str = "backups";
+
+/// Format of the backup names, sortable by time.
+const BACKUP_NAME_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+
+/// A single backup, which is a directory containing copies of [`DATABASE_FILES`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupInfo {
+    /// The name of the backup, which is its creation time
+    pub name: String,
+    /// The directory of the backup
+    pub path: PathBuf,
+    /// The databases contained in the backup
+    pub databases: Vec<String>,
+}
+
+/// Get the directory all backups for `data_dir` are stored in.
+#[must_use]
+pub fn get_backup_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(BACKUP_DIR)
+}
+
+/// Create a new backup of all existing [`DATABASE_FILES`] in `data_dir`, named after the current time.
+pub fn create_backup(data_dir: &Path) -> Result<BackupInfo> {
+    let name = Local::now().format(BACKUP_NAME_FORMAT).to_string();
+    let path = get_backup_dir(data_dir).join(&name);
+
+    std::fs::create_dir_all(get_backup_dir(data_dir))
+        .with_context(|| get_backup_dir(data_dir).display().to_string())?;
+    // not "create_dir_all" to not overwrite a backup from the same second
+    std::fs::create_dir(&path).with_context(|| path.display().to_string())?;
+
+    let mut databases = Vec::new();
+    for db in DATABASE_FILES {
+        let db_path = data_dir.join(db);
+        if !db_path.exists() {
+            continue;
+        }
+
+        copy_database(&db_path, &path.join(db))?;
+        databases.push((*db).to_string());
+    }
+
+    info!(
+        "Created backup \"{name}\" with {} databases",
+        databases.len()
+    );
+
+    Ok(BackupInfo {
+        name,
+        path,
+        databases,
+    })
+}
+
+/// List all backups of `data_dir`, newest first.
+pub fn list_backups(data_dir: &Path) -> Result<Vec<BackupInfo>> {
+    let backup_dir = get_backup_dir(data_dir);
+    if !backup_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups = Vec::new();
+    for entry in std::fs::read_dir(&backup_dir).with_context(|| backup_dir.display().to_string())? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+
+        let path = entry.path();
+        let databases: Vec<String> = DATABASE_FILES
+            .iter()
+            .filter(|db| path.join(db).is_file())
+            .map(ToString::to_string)
+            .collect();
+        if databases.is_empty() {
+            continue;
+        }
+
+        backups.push(BackupInfo {
+            name: entry.file_name().to_string_lossy().to_string(),
+            path,
+            databases,
+        });
+    }
+    backups.sort_by(|a, b| b.name.cmp(&a.name));
+
+    Ok(backups)
+}
+
+/// Restore the backup `name` into `data_dir`, replacing the content of the databases contained in the backup.
+///
+/// Backups from an older termusic are migrated to the current schema first, so that a running server can keep
+/// using the databases. Fails without changing anything if a database of the backup is from a newer termusic.
+///
+/// Returns the number of restored databases.
+pub fn restore_backup(data_dir: &Path, name: &str) -> Result<usize> {
+    let Some(backup) = list_backups(data_dir)?.into_iter().find(|v| v.name == name) else {
+        bail!("No backup named \"{name}\"");
+    };
+
+    // migrate all databases before restoring any, to not restore only some of them
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("termusic-restore-")
+        .tempdir()
+        .context("create temporary directory")?;
+    for db in &backup.databases {
+        let path = tmp_dir.path().join(db);
+        copy_database(&backup.path.join(db), &path)?;
+        migrate_database(&path).with_context(|| format!("{db} of backup \"{name}\""))?;
+    }
+
+    for db in &backup.databases {
+        restore_database(&data_dir.join(db), &tmp_dir.path().join(db))?;
+    }
+
+    info!(
+        "Restored backup \"{name}\" with {} databases",
+        backup.databases.len()
+    );
+
+    Ok(backup.databases.len())
+}
+
+/// Migrate the database at `path`, named like one of [`DATABASE_FILES`], to the current schema.
+///
+/// Fails if the database is from a newer termusic.
+pub(crate) fn migrate_database(path: &Path) -> Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    // opening migrates and checks the version
+    match path.file_name().and_then(OsStr::to_str) {
+        Some(LIBRARY_DATABASE) => drop(crate::new_database::Database::new(path)?),
+        Some(PODCAST_DATABASE) => drop(crate::podcast::db::Database::new(dir)?),
+        _ => bail!("Unknown database \"{}\"", path.display()),
+    }
+
+    Ok(())
+}
+
+/// Copy the database at `from` to a new file at `to`, while `from` may be in use.
+pub(crate) fn copy_database(from: &Path, to: &Path) -> Result<()> {
+    let conn = Connection::open_with_flags(from, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .with_context(|| from.display().to_string())?;
+    conn.backup(MAIN_DB, to, None)
+        .with_context(|| format!("backup {}", from.display()))?;
+
+    Ok(())
+}
+
+/// Replace the content of the database at `into` with the database at `from`, while `into` may be in use.
+pub(crate) fn restore_database(into: &Path, from: &Path) -> Result<()> {
+    let mut conn = Connection::open(into).with_context(|| into.display().to_string())?;
+    conn.restore(MAIN_DB, from, None::<fn(Progress)>)
+        .with_context(|| format!("restore {}", into.display()))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rusqlite::Connection;
+
+    use super::{create_backup, list_backups, restore_backup};
+
+    fn get_value(path: &std::path::Path) -> String {
+        Connection::open(path)
+            .unwrap()
+            .query_row("SELECT value FROM test;", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    fn get_user_version(path: &std::path::Path) -> u32 {
+        Connection::open(path)
+            .unwrap()
+            .query_row("SELECT user_version FROM pragma_user_version", [], |row| {
+                row.get(0)
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn should_backup_and_restore() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let data_dir = tmp_dir.path();
+
+        let db_path = data_dir.join("data.db");
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch("CREATE TABLE test (value TEXT); INSERT INTO test VALUES ('before');")
+            .unwrap();
+
+        let backup = create_backup(data_dir).unwrap();
+        assert_eq!(backup.databases, ["data.db"]);
+        assert_eq!(list_backups(data_dir).unwrap(), [backup.clone()]);
+
+        // connection is kept open, like a running server would
+        conn.execute("UPDATE test SET value = 'after';", [])
+            .unwrap();
+        assert_eq!(get_value(&db_path), "after");
+
+        assert_eq!(restore_backup(data_dir, &backup.name).unwrap(), 1);
+        assert_eq!(get_value(&db_path), "before");
+        // the backup was from before any migration
+        assert_ne!(get_user_version(&db_path), 0);
+        assert!(restore_backup(data_dir, "does-not-exist").is_err());
+    }
+
+    #[test]
+    fn should_refuse_newer_backup() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let db_path = data_dir.path().join("data.db");
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE test (value TEXT); INSERT INTO test VALUES ('before'); PRAGMA user_version = 1000;",
+        )
+        .unwrap();
+
+        let backup = create_backup(data_dir.path()).unwrap();
+        conn.execute("UPDATE test SET value = 'after';", [])
+            .unwrap();
+
+        assert!(restore_backup(data_dir.path(), &backup.name).is_err());
+        assert_eq!(get_value(&db_path), "after");
+    }
+}
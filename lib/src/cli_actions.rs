@@ -0,0 +1,138 @@
+//! Command line actions shared by the server and the tui binaries, reporting their results on stdout.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::new_database::Database;
+use crate::new_database::smart_playlist_ops::{self, SmartQuery};
+use crate::{backup, state_bundle, utils};
+
+/// Ask the user on stdin to confirm `question`, defaulting to "no".
+pub fn confirm(question: &str) -> Result<bool> {
+    print!("{question} [y/N]: ");
+    std::io::Write::flush(&mut std::io::stdout())?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+/// Create a new backup of the databases.
+pub fn backup() -> Result<()> {
+    let data_dir_path = utils::get_app_data_path().context("getting app-data-path")?;
+    let backup = backup::create_backup(&data_dir_path).context("create backup")?;
+    println!(
+        "created backup \"{}\" at {}",
+        backup.name,
+        backup.path.display()
+    );
+
+    Ok(())
+}
+
+/// List all backups, newest first.
+pub fn list_backups() -> Result<()> {
+    let data_dir_path = utils::get_app_data_path().context("getting app-data-path")?;
+    let backups = backup::list_backups(&data_dir_path).context("list backups")?;
+    if backups.is_empty() {
+        println!("no backups found");
+    }
+    for backup in backups {
+        println!("{}\t{}", backup.name, backup.databases.join(", "));
+    }
+
+    Ok(())
+}
+
+/// Restore the backup `name`, asking for confirmation first unless `yes`.
+pub fn restore_backup(name: &str, yes: bool) -> Result<()> {
+    if !yes
+        && !confirm(&format!(
+            "Restore backup \"{name}\"? This replaces the current databases"
+        ))?
+    {
+        println!("restore cancelled");
+        return Ok(());
+    }
+
+    let data_dir_path = utils::get_app_data_path().context("getting app-data-path")?;
+    let count = backup::restore_backup(&data_dir_path, name).context("restore backup")?;
+    println!("restored {count} databases from backup \"{name}\"");
+
+    Ok(())
+}
+
+/// Export the whole application state into a new archive at `file`.
+pub fn export_state(file: &Path) -> Result<()> {
+    let config_dir_path = utils::get_app_config_path().context("getting app-config-path")?;
+    let data_dir_path = utils::get_app_data_path().context("getting app-data-path")?;
+    let exported = state_bundle::export_bundle(&config_dir_path, &data_dir_path, file)
+        .context("export state")?;
+    for path in exported {
+        println!("exported {}", path.display());
+    }
+    println!("state exported to {}", file.display());
+
+    Ok(())
+}
+
+/// Import the application state from the archive at `file`, asking for confirmation first unless `yes`.
+pub fn import_state(file: &Path, yes: bool) -> Result<()> {
+    if !yes
+        && !confirm(&format!(
+            "Import \"{}\"? This replaces the current config, themes, databases and playlist",
+            file.display()
+        ))?
+    {
+        println!("import cancelled");
+        return Ok(());
+    }
+
+    let config_dir_path = utils::get_app_config_path().context("getting app-config-path")?;
+    let data_dir_path = utils::get_app_data_path().context("getting app-data-path")?;
+    let imported = state_bundle::import_bundle(&config_dir_path, &data_dir_path, file)
+        .context("import state")?;
+    for path in imported {
+        println!("imported {}", path.display());
+    }
+    println!(
+        "state imported, check \"music_dir\" in the config if the music is at a different path"
+    );
+
+    Ok(())
+}
+
+/// Add the smart playlist `name`, see [`SmartQuery`] for the syntax of `query`.
+pub fn smart_playlist_add(name: &str, query: &str) -> Result<()> {
+    let query: SmartQuery = query.parse().context("parse smart playlist query")?;
+    let db = Database::new_default_path().context("open library database")?;
+    smart_playlist_ops::insert_smart_playlist(&db.get_connection(), name, &query)?;
+    println!("added smart playlist \"{name}\": {query}");
+
+    Ok(())
+}
+
+/// List all smart playlists with their query.
+pub fn smart_playlist_list() -> Result<()> {
+    let db = Database::new_default_path().context("open library database")?;
+    let playlists = smart_playlist_ops::get_all_smart_playlists(&db.get_connection())?;
+    if playlists.is_empty() {
+        println!("no smart playlists found");
+    }
+    for playlist in playlists {
+        println!("{}\t{}", playlist.name, playlist.query);
+    }
+
+    Ok(())
+}
+
+/// Remove the smart playlist `name`.
+pub fn smart_playlist_remove(name: &str) -> Result<()> {
+    let db = Database::new_default_path().context("open library database")?;
+    smart_playlist_ops::delete_smart_playlist(&db.get_connection(), name)?;
+    println!("removed smart playlist \"{name}\"");
+
+    Ok(())
+}
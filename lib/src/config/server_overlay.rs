@@ -1,6 +1,7 @@
 use std::path::{Path, PathBuf};
 
 use super::v2::server::ScanDepth;
+use crate::parental::ParentalFilter;
 
 /// The Server Settings to use, with possible overwrite (like from CLI)
 #[derive(Debug, Clone, PartialEq, Default)]
@@ -19,6 +20,8 @@ pub struct ServerOverlay {
     pub disable_discord_status: bool,
     /// Overwrite the Metadata scan depth
     pub metadata_scan_depth: Option<ScanDepth>,
+    /// The content filter, which is read from its own file and never saved, see [`parental`](crate::parental)
+    pub parental: ParentalFilter,
 }
 
 impl ServerOverlay {
@@ -18,6 +18,11 @@ pub struct TuiOverlay {
     ///
     /// If `false`, will treat as if no cover features are compiled-in.
     pub cover_features: bool,
+
+    /// Hide and disable actions that delete, remove or move something, like deleting files or clearing the playlist.
+    ///
+    /// Set with `--guest`, or when the server reports that the token is `com.guest_token`.
+    pub guest: bool,
 }
 
 impl TuiOverlay {
@@ -1,3 +1,6 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
 use bytesize::ByteSize;
 use serde::{Deserialize, Serialize};
 
@@ -30,6 +33,12 @@ pub const DECODEDBUF_SIZE_DEFAULT: u64 = 192_000 * size_of::<f32>() as u64;
 pub struct RustyBackendSettings {
     /// Enable or disable `soundtouch`; only has a effect if `rusty-soundtouch` is compiled-in
     pub soundtouch: bool,
+    /// Keep the pitch when playing faster or slower, instead of making voices higher or lower.
+    ///
+    /// This uses a built-in time-stretching if `soundtouch` is disabled or not compiled-in.
+    ///
+    /// Default `true`
+    pub pitch_correction: bool,
     /// Set the buffer size for the raw file.
     /// This value will be clamped to the actual file's size.
     /// Note this only applies to local files like music or downloaded podcasts. Does not apply to streamed podcasts or radio.
@@ -48,15 +57,44 @@ pub struct RustyBackendSettings {
     /// Default `48_000`
     /// Recommeded Values: `44_100`, `48_000`, `96_000` `192_000`.
     pub output_sample_rate: u32,
+    /// Set the output device by name, like it is listed in the config editor.
+    ///
+    /// If unset or if the device does not exist, the default output device is used.
+    ///
+    /// Default: unset
+    pub output_device: Option<String>,
+    /// Write the audio as a raw PCM stream instead of playing it on a output device,
+    /// so that it can be played elsewhere on the network by for example Snapcast or ffmpeg.
+    ///
+    /// The stream is signed 16 bit little-endian stereo at `output_sample_rate`,
+    /// which is Snapcast's `sampleformat` `48000:16:2` with the default sample rate.
+    ///
+    /// Default: unset
+    pub stream_output: Option<StreamOutput>,
+}
+
+/// Where the rusty backend writes the raw PCM stream to, see [`RustyBackendSettings::stream_output`].
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum StreamOutput {
+    /// Write to the named pipe (FIFO) at `path`, like Snapcast's `pipe` source reads from
+    ///
+    /// Audio is discarded while there is no reader.
+    Pipe { path: PathBuf },
+    /// Send to every client connected to `address`, like Snapcast's `tcp` source in client mode or `ffmpeg -i tcp://...`
+    Tcp { address: SocketAddr },
 }
 
 impl Default for RustyBackendSettings {
     fn default() -> Self {
         Self {
             soundtouch: true,
+            pitch_correction: true,
             file_buffer_size: ByteSize::b(FILEBUF_SIZE_DEFAULT),
             decoded_buffer_size: ByteSize::b(DECODEDBUF_SIZE_DEFAULT),
             output_sample_rate: 48_000,
+            output_device: None,
+            stream_output: None,
         }
     }
 }
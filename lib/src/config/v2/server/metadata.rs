@@ -1,3 +1,5 @@
+use std::num::NonZeroU32;
+
 use serde::{Deserialize, Serialize};
 
 use crate::config::v2::server::ScanDepth;
@@ -21,6 +23,29 @@ pub struct MetadataSettings {
     ///
     /// After split, the Artist values are trimmed.
     pub artist_separators: Vec<String>,
+    /// When the music directories should be scanned for new or changed tracks.
+    ///
+    /// Regardless of this option, a scan can always be requested manually.
+    pub scan_policy: ScanPolicy,
+    /// The amount of minutes between scans, only used with [`ScanPolicy::Interval`].
+    pub scan_interval_mins: NonZeroU32,
+}
+
+/// When the library scanner should run automatically.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScanPolicy {
+    /// Scan once on startup
+    #[default]
+    Startup,
+    /// Only scan when manually requested
+    Manual,
+    /// Scan on startup and then every `scan_interval_mins`
+    Interval,
+    /// Scan on startup and then watch the music directories, updating the library as files change
+    ///
+    /// Changing to or from this policy requires a restart of the server.
+    Watch,
 }
 
 /// The default and most common separators used for artists.
@@ -35,6 +60,8 @@ impl Default for MetadataSettings {
                 .iter()
                 .map(ToString::to_string)
                 .collect(),
+            scan_policy: ScanPolicy::default(),
+            scan_interval_mins: NonZeroU32::new(60).unwrap(),
         }
     }
 }
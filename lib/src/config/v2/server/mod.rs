@@ -3,18 +3,23 @@ use std::{
     net::{IpAddr, SocketAddr},
     num::{NonZeroU8, NonZeroU32},
     path::PathBuf,
+    time::Duration,
 };
 
+use bytesize::ByteSize;
 use serde::{Deserialize, Serialize};
 
-use crate::track::MediaTypesSimple;
+use crate::organize::DEFAULT_ORGANIZE_PATTERN;
+use crate::track::{MediaTypesSimple, title_format::DEFAULT_TITLE_FORMAT};
 use backends::BackendSettings;
 use metadata::MetadataSettings;
+use scrobble::ScrobbleSettings;
 
 pub mod backends;
 /// Extra things necessary for a config file, like wrappers for versioning
 pub mod config_extra;
 pub mod metadata;
+pub mod scrobble;
 
 pub type MusicDirsOwned = Vec<PathBuf>;
 
@@ -27,6 +32,7 @@ pub struct ServerSettings {
     pub podcast: PodcastSettings,
     pub backends: BackendSettings,
     pub metadata: MetadataSettings,
+    pub scrobble: ScrobbleSettings,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
@@ -40,6 +46,143 @@ pub struct PodcastSettings {
     pub max_download_retries: u8,
     /// Directory for downloaded Podcasts
     pub download_dir: PathBuf,
+    /// Max total size of all downloaded Podcasts, unlimited if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_quota: Option<ByteSize>,
+    /// Order of the podcast feeds list
+    pub feed_sort: FeedSortOrder,
+    /// How long unplayed episodes are shown as new
+    pub new_episodes: NewEpisodeWindow,
+    /// Hide feeds marked as explicit from the feeds list
+    ///
+    /// Episodes marked as explicit in other feeds are still shown, only with their marker.
+    pub hide_explicit: bool,
+    /// Rewind a episode a little when resuming it after a long pause
+    pub resume_rewind: ResumeRewind,
+    /// Sync played status and positions with a gpodder compatible server, disabled if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sync: Option<PodcastSyncSettings>,
+}
+
+/// Credentials and options for syncing with a gpodder compatible server.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)] // allow missing fields and fill them with the `..Self::default()` in this struct
+pub struct PodcastSyncSettings {
+    /// Which API the server speaks
+    pub flavor: GpodderFlavor,
+    /// Base url of the server, like `https://gpodder.net` or the nextcloud instance root
+    pub server: String,
+    pub username: String,
+    /// Password, or for nextcloud preferably an app password
+    pub password: String,
+    /// Name this device reports its actions as, only used by [`GpodderFlavor::Gpodder`]
+    pub device_id: String,
+}
+
+impl Default for PodcastSyncSettings {
+    fn default() -> Self {
+        Self {
+            flavor: GpodderFlavor::default(),
+            server: String::new(),
+            username: String::new(),
+            password: String::new(),
+            device_id: "termusic".to_string(),
+        }
+    }
+}
+
+/// The API a podcast sync server speaks.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GpodderFlavor {
+    /// The `gpodder.net` API v2, also implemented by other servers like `opodsync`
+    #[default]
+    Gpodder,
+    /// The `nextcloud-gpodder` app
+    Nextcloud,
+}
+
+/// Orders the podcast feeds can be listed in.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedSortOrder {
+    /// By title, ignoring leading articles like "The"
+    #[default]
+    Alphabetical,
+    /// By the publish date of the newest episode, newest first
+    Recent,
+    /// By the amount of unplayed episodes, most first
+    Unplayed,
+}
+
+impl FeedSortOrder {
+    /// Get the order to switch to next.
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::Alphabetical => Self::Recent,
+            Self::Recent => Self::Unplayed,
+            Self::Unplayed => Self::Alphabetical,
+        }
+    }
+
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Alphabetical => "alphabetical",
+            Self::Recent => "most recent episode",
+            Self::Unplayed => "most unplayed",
+        }
+    }
+}
+
+/// How long a unplayed episode counts as new, older unplayed episodes are shown differently.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)] // allow missing fields and fill them with the `..Self::default()` in this struct
+pub struct NewEpisodeWindow {
+    /// Days a episode counts as new, `0` to count all unplayed episodes as new
+    pub days: u32,
+    /// What the days are counted from
+    pub since: NewEpisodeSince,
+}
+
+impl Default for NewEpisodeWindow {
+    fn default() -> Self {
+        Self {
+            days: 7,
+            since: NewEpisodeSince::default(),
+        }
+    }
+}
+
+/// How far to rewind a episode when it is resumed after being paused for a while, to get back into it.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)] // allow missing fields and fill them with the `..Self::default()` in this struct
+pub struct ResumeRewind {
+    /// Minutes a episode needs to have been paused for to be rewound
+    pub after_mins: u32,
+    /// Seconds to rewind, `0` to disable
+    pub secs: u32,
+}
+
+impl Default for ResumeRewind {
+    fn default() -> Self {
+        Self {
+            after_mins: 10,
+            secs: 15,
+        }
+    }
+}
+
+/// The date a episode is counted as new from.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NewEpisodeSince {
+    /// The publish date in the feed
+    #[default]
+    Pubdate,
+    /// The date the episode was first fetched from the feed
+    Fetch,
 }
 
 /// Get the default podcast dir, which uses OS-specific paths, or home/Music/podcast
@@ -59,6 +202,12 @@ impl Default for PodcastSettings {
             concurrent_downloads_max: NonZeroU8::new(3).unwrap(),
             max_download_retries: 3,
             download_dir: default_podcast_dir(),
+            download_quota: None,
+            feed_sort: FeedSortOrder::default(),
+            new_episodes: NewEpisodeWindow::default(),
+            hide_explicit: false,
+            resume_rewind: ResumeRewind::default(),
+            sync: None,
         }
     }
 }
@@ -252,8 +401,21 @@ pub struct PlayerSettings {
     /// Max depth the TUI will scan for the music library tree
     #[serde(skip_serializing)]
     pub library_scan_depth: ScanDepth,
+    /// Music directories (or directories inside them) which are on removable or network drives.
+    ///
+    /// Tracks in these directories are not marked as missing while the directory is not mounted or reachable.
+    pub removable_dirs: Vec<PathBuf>,
     /// Set if the position should be remembered for tracks
     pub remember_position: RememberLastPosition,
+    /// Restore the play queue, including the current track and position, when the server starts
+    ///
+    /// The queue is always stored, so that it can still be restored manually if this is disabled.
+    pub restore_queue: bool,
+    /// Start playing when the server starts, or when play is pressed while stopped, continuing the current track
+    /// of the queue (or the last played track if the queue is empty) at its last position
+    ///
+    /// Music is only continued if it was more than a minute in, podcasts always are.
+    pub resume_on_start: bool,
 
     /// Playlist loop mode
     pub loop_mode: LoopMode,
@@ -266,6 +428,21 @@ pub struct PlayerSettings {
     pub speed: i32,
     /// Enable gapless decoding & prefetching the next track
     pub gapless: bool,
+    /// Normalize the loudness of tracks via their ReplayGain tags
+    pub replay_gain: ReplayGainMode,
+    /// Fade the end of the current track out while the next track fades in
+    ///
+    /// Only supported by the rusty backend and only while `gapless` is enabled.
+    pub crossfade: CrossfadeSettings,
+    /// Skip the part of silences beyond a minimal length, like pauses in podcasts
+    ///
+    /// Also used for audiobooks set to skip silence, even if disabled here. Only supported by the rusty backend.
+    pub skip_silence: SkipSilenceSettings,
+    /// Start buffering the next track this many seconds before the current track ends, if the next track is streamed
+    /// (a podcast episode which is not downloaded, or a radio station), `0` to disable
+    ///
+    /// Other tracks are prepared shortly before the current track ends. Only used while `gapless` is enabled.
+    pub stream_prefetch_secs: u16,
     /// How much to seek on a seek event
     pub seek_step: SeekStep,
 
@@ -273,11 +450,23 @@ pub struct PlayerSettings {
     pub use_mediacontrols: bool,
     /// Controls if discord status setting is enabled
     pub set_discord_status: bool,
+    /// How tracks are titled in the playlist, the media controls and the "Currently Playing" notification,
+    /// like `{artist} - {title}[ ({album})]`
+    ///
+    /// See [`title_format`](crate::track::title_format) for the supported fields.
+    pub title_format: String,
+    /// Where "organize library" moves tracks to, relative to the music directory they are in,
+    /// like `{artist}/{album}/[{track} - ]{title}`; the file extension is kept
+    ///
+    /// See [`organize`](crate::organize) for the supported fields.
+    pub organize_pattern: String,
 
     /// Amount of tracks to add on "random track add"
     pub random_track_quantity: NonZeroU32,
     /// Minimal amount of tracks a album needs to have before being chosen for "random album add"
     pub random_album_min_quantity: NonZeroU32,
+    /// Keep the playlist filled with tracks from the library
+    pub auto_queue: AutoQueueSettings,
 
     /// The backend to use
     pub backend: Backend,
@@ -295,20 +484,30 @@ impl Default for PlayerSettings {
         Self {
             music_dirs: default_music_dirs(),
             library_scan_depth: ScanDepth::Limited(0),
+            removable_dirs: Vec::new(),
             remember_position: RememberLastPosition::default(),
+            restore_queue: true,
+            resume_on_start: false,
 
             loop_mode: LoopMode::default(),
             // rather use a lower value than a high so that ears dont get blown off
             volume: 30,
             speed: 10,
             gapless: true,
+            replay_gain: ReplayGainMode::default(),
+            crossfade: CrossfadeSettings::default(),
+            skip_silence: SkipSilenceSettings::default(),
+            stream_prefetch_secs: 15,
             seek_step: SeekStep::default(),
 
             use_mediacontrols: true,
             set_discord_status: true,
+            title_format: DEFAULT_TITLE_FORMAT.to_string(),
+            organize_pattern: DEFAULT_ORGANIZE_PATTERN.to_string(),
 
             random_track_quantity: NonZeroU32::new(20).unwrap(),
             random_album_min_quantity: NonZeroU32::new(5).unwrap(),
+            auto_queue: AutoQueueSettings::default(),
 
             backend: Backend::default(),
         }
@@ -324,6 +523,8 @@ pub enum Backend {
     Mpv,
     #[default]
     Rusty,
+    /// Play nothing and only advance the position, for development, testing and demos
+    Null,
 }
 
 impl Backend {
@@ -333,6 +534,7 @@ impl Backend {
             Backend::Gstreamer => "gst",
             Backend::Mpv => "mpv",
             Backend::Rusty => "rusty",
+            Backend::Null => "null",
         }
     }
 }
@@ -343,6 +545,122 @@ impl Display for Backend {
     }
 }
 
+/// Which ReplayGain value to use to normalize the loudness of tracks
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReplayGainMode {
+    /// Do not apply any gain
+    #[default]
+    Off,
+    /// Use the gain of each track, for consistent loudness across all tracks
+    Track,
+    /// Use the gain of the album, keeping the loudness differences within a album
+    Album,
+}
+
+impl ReplayGainMode {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReplayGainMode::Off => "off",
+            ReplayGainMode::Track => "track",
+            ReplayGainMode::Album => "album",
+        }
+    }
+}
+
+/// Settings for crossfading between music tracks
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)] // allow missing fields and fill them with the `..Self::default()` in this struct
+pub struct CrossfadeSettings {
+    /// Enable crossfading
+    pub enabled: bool,
+    /// How many seconds the end of the current track and the start of the next track overlap
+    pub secs: NonZeroU8,
+}
+
+impl CrossfadeSettings {
+    /// Get the overlap duration, or `None` if crossfading is disabled.
+    #[must_use]
+    pub fn duration(&self) -> Option<Duration> {
+        self.enabled
+            .then(|| Duration::from_secs(u64::from(self.secs.get())))
+    }
+}
+
+impl Default for CrossfadeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            secs: NonZeroU8::new(5).unwrap(),
+        }
+    }
+}
+
+/// Settings for skipping silences while playing
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)] // allow missing fields and fill them with the `..Self::default()` in this struct
+pub struct SkipSilenceSettings {
+    /// Enable skipping silences for all tracks
+    pub enabled: bool,
+    /// How many milliseconds of each silence are still played, only the rest of longer silences is skipped
+    pub min_ms: u16,
+    /// How many decibels below full scale audio has to be to count as silence
+    pub threshold_db: u8,
+}
+
+impl SkipSilenceSettings {
+    /// Get the length of silence that is still played.
+    #[must_use]
+    pub fn min_duration(&self) -> Duration {
+        Duration::from_millis(u64::from(self.min_ms))
+    }
+
+    /// Get the amplitude below which audio counts as silence, from `0.0` to `1.0`.
+    #[must_use]
+    pub fn threshold(&self) -> f32 {
+        10f32.powf(-f32::from(self.threshold_db) / 20.0)
+    }
+}
+
+impl Default for SkipSilenceSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_ms: 1000,
+            threshold_db: 50,
+        }
+    }
+}
+
+/// Settings for keeping the playlist filled with tracks from the library
+///
+/// Each track gets a weight of `1`, plus `artist_weight` if it has the same artist and `genre_weight` if it has the same genre
+/// as the last track in the playlist, and is chosen randomly by that weight.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)] // allow missing fields and fill them with the `..Self::default()` in this struct
+pub struct AutoQueueSettings {
+    /// Enable auto-queue
+    pub enabled: bool,
+    /// Add tracks whenever fewer than this many tracks come after the current track
+    pub min_upcoming: NonZeroU8,
+    /// How much more likely tracks of the same artist are chosen, `0` to not prefer them
+    pub artist_weight: u8,
+    /// How much more likely tracks of the same genre are chosen, `0` to not prefer them
+    pub genre_weight: u8,
+}
+
+impl Default for AutoQueueSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_upcoming: NonZeroU8::new(5).unwrap(),
+            artist_weight: 4,
+            genre_weight: 2,
+        }
+    }
+}
+
 /// Playlist loop modes
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -454,16 +772,95 @@ pub struct ComSettings {
 
     // Below are HTTP settings
     /// gRPC server Port
+    ///
+    /// Defaults to a port derived from the name for [profiles](crate::utils::set_app_profile), see [`default_port`].
     pub port: u16,
     /// gRPC server interface / address
     pub address: IpAddr,
+
+    // Below are security settings
+    /// Token every client has to send, requests without it are rejected, disabled if unset.
+    ///
+    /// Should be set when `address` is reachable from other machines.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_token: Option<String>,
+    /// Token for guests, who may control playback with it, but not delete or remove anything,
+    /// like deleting files, removing podcasts or clearing the playlist. Disabled if unset.
+    ///
+    /// Requires `auth_token`, as otherwise no token is needed at all and guests would have full control;
+    /// the server refuses to start without it, see [`ComSettings::check_tokens`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guest_token: Option<String>,
+    /// Serve HTTP over TLS, disabled if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<ComTlsSettings>,
+
+    // Below are monitoring settings
+    /// Address to serve Prometheus metrics at over plain HTTP, like `127.0.0.1:9185`, disabled if unset.
+    ///
+    /// The metrics are served at `/metrics`, independent of `protocol`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<SocketAddr>,
+
+    // Below are remote control settings
+    /// Address to serve a HTTP/JSON api to control playback at, like `127.0.0.1:9186`, disabled if unset.
+    ///
+    /// Served over HTTPS if `tls` is set, independent of `protocol`.
+    /// For clients that cannot use gRPC, like home-automation systems. Requires `auth_token` (or `guest_token`) as `Bearer` token if set.
+    /// Also serves a web page to control the server from a browser, like on a phone.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_api: Option<SocketAddr>,
+}
+
+/// TLS for the HTTP protocol of the gRPC server.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(default)] // allow missing fields and fill them with the `..Self::default()` in this struct
+pub struct ComTlsSettings {
+    /// PEM certificate the server identifies with.
+    ///
+    /// Clients trust this certificate, so for them it can also be the certificate of the CA that issued the server's.
+    pub cert: PathBuf,
+    /// PEM private key of `cert`, only needed by the server
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<PathBuf>,
+    /// The name `cert` is issued for, which clients verify, defaults to `address`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
 }
 
 /// Helper function to get the default UDS socker path.
+///
+/// Each [profile](crate::utils::set_app_profile) has its own socket, as it has its own server.
 #[must_use]
 pub fn default_uds_socket_path() -> PathBuf {
     // TODO: maybe default to include user id like "termusic-1000.socket"?
-    std::env::temp_dir().join("termusic.socket")
+    match crate::utils::get_app_profile() {
+        Some(profile) => std::env::temp_dir().join(format!("termusic-{profile}.socket")),
+        None => std::env::temp_dir().join("termusic.socket"),
+    }
+}
+
+/// The default gRPC server port of the default profile.
+pub const DEFAULT_PORT: u16 = 50101;
+
+/// Helper function to get the default gRPC server port.
+///
+/// Each [profile](crate::utils::set_app_profile) gets a port derived from its name in the 1000 ports after
+/// [`DEFAULT_PORT`], as it has its own server. Two profiles may still get the same port, in which case the `com.port`
+/// of one has to be changed.
+#[must_use]
+pub fn default_port() -> u16 {
+    crate::utils::get_app_profile().map_or(DEFAULT_PORT, profile_port)
+}
+
+/// Get the port for the profile `name`, see [`default_port`].
+fn profile_port(name: &str) -> u16 {
+    // FNV-1a, to always get the same port for a name, unlike with the std hasher
+    let hash = name.bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    });
+
+    DEFAULT_PORT + 1 + u16::try_from(hash % 1000).expect("to be below 1000")
 }
 
 impl Default for ComSettings {
@@ -476,8 +873,16 @@ impl Default for ComSettings {
 
             socket_path: default_uds_socket_path(),
 
-            port: 50101,
+            port: default_port(),
             address: "::1".parse().unwrap(),
+
+            auth_token: None,
+            guest_token: None,
+            tls: None,
+
+            metrics: None,
+
+            http_api: None,
         }
     }
 }
@@ -488,15 +893,44 @@ impl From<&ComSettings> for SocketAddr {
     }
 }
 
+impl ComSettings {
+    /// Check that the tokens actually restrict clients.
+    ///
+    /// # Errors
+    ///
+    /// - if `guest_token` is set without `auth_token`, as anyone would then have full control
+    /// - if `guest_token` is the same as `auth_token`, as everyone would then be a guest
+    pub fn check_tokens(&self) -> anyhow::Result<()> {
+        let Some(guest_token) = &self.guest_token else {
+            return Ok(());
+        };
+
+        match &self.auth_token {
+            None => anyhow::bail!(
+                "\"com.guest_token\" is set without \"com.auth_token\", which would give anyone full control; set \"com.auth_token\" too"
+            ),
+            Some(auth_token) if auth_token == guest_token => {
+                anyhow::bail!("\"com.guest_token\" has to differ from \"com.auth_token\"")
+            }
+            Some(_) => Ok(()),
+        }
+    }
+}
+
 mod v1_interop {
     use std::num::TryFromIntError;
 
     use super::{
-        Backend, ComSettings, LoopMode, NonZeroU8, NonZeroU32, PlayerSettings, PodcastSettings,
-        PositionYesNo, PositionYesNoLower, RememberLastPosition, ScanDepth, SeekStep,
-        ServerSettings, backends::BackendSettings,
+        AutoQueueSettings, Backend, ComSettings, CrossfadeSettings, DEFAULT_ORGANIZE_PATTERN,
+        DEFAULT_TITLE_FORMAT, FeedSortOrder, LoopMode, NewEpisodeWindow, NonZeroU8, NonZeroU32,
+        PlayerSettings, PodcastSettings, PositionYesNo, PositionYesNoLower, RememberLastPosition,
+        ReplayGainMode, ResumeRewind, ScanDepth, SeekStep, ServerSettings, SkipSilenceSettings,
+        backends::BackendSettings,
+    };
+    use crate::config::{
+        v1,
+        v2::server::{metadata::MetadataSettings, scrobble::ScrobbleSettings},
     };
-    use crate::config::{v1, v2::server::metadata::MetadataSettings};
 
     impl From<v1::Loop> for LoopMode {
         fn from(value: v1::Loop) -> Self {
@@ -576,6 +1010,12 @@ mod v1_interop {
                 })?,
                 max_download_retries: value.podcast_max_retries.clamp(0, u8::MAX as usize) as u8,
                 download_dir: value.podcast_dir,
+                download_quota: None,
+                feed_sort: FeedSortOrder::default(),
+                new_episodes: NewEpisodeWindow::default(),
+                hide_explicit: false,
+                resume_rewind: ResumeRewind::default(),
+                sync: None,
             };
 
             let player_settings = PlayerSettings {
@@ -583,15 +1023,24 @@ mod v1_interop {
                 // not converting old scan_depth as that is not stored in the config, but set via CLI, using default instead
                 // library_scan_depth: ScanDepth::Limited(value.max_depth_cli),
                 library_scan_depth: ScanDepth::Limited(10),
+                removable_dirs: Vec::new(),
                 remember_position: value.player_remember_last_played_position.into(),
+                restore_queue: true,
+                resume_on_start: false,
                 loop_mode: value.player_loop_mode.into(),
                 volume: value.player_volume,
                 speed: value.player_speed,
                 gapless: value.player_gapless,
+                replay_gain: ReplayGainMode::default(),
+                crossfade: CrossfadeSettings::default(),
+                skip_silence: SkipSilenceSettings::default(),
+                stream_prefetch_secs: 15,
                 seek_step: value.player_seek_step.into(),
 
                 use_mediacontrols: value.player_use_mpris,
                 set_discord_status: value.player_use_discord,
+                title_format: DEFAULT_TITLE_FORMAT.to_string(),
+                organize_pattern: DEFAULT_ORGANIZE_PATTERN.to_string(),
 
                 random_track_quantity: NonZeroU32::try_from(
                     value.playlist_select_random_track_quantity,
@@ -609,6 +1058,7 @@ mod v1_interop {
                     new_key: "player.random_album_min_quantity",
                     source: err,
                 })?,
+                auto_queue: AutoQueueSettings::default(),
 
                 backend: Backend::default(),
             };
@@ -619,6 +1069,7 @@ mod v1_interop {
                 podcast: podcast_settings,
                 backends: BackendSettings::default(),
                 metadata: MetadataSettings::default(),
+                scrobble: ScrobbleSettings::default(),
             })
         }
     }
@@ -648,7 +1099,13 @@ mod v1_interop {
                 PodcastSettings {
                     concurrent_downloads_max: NonZeroU8::new(3).unwrap(),
                     max_download_retries: 3,
-                    download_dir: PathBuf::new()
+                    download_dir: PathBuf::new(),
+                    download_quota: None,
+                    feed_sort: FeedSortOrder::default(),
+                    new_episodes: NewEpisodeWindow::default(),
+                    hide_explicit: false,
+                    resume_rewind: ResumeRewind::default(),
+                    sync: None,
                 }
             );
 
@@ -676,25 +1133,65 @@ mod v1_interop {
                 PlayerSettings {
                     music_dirs: Vec::new(),
                     library_scan_depth: ScanDepth::Limited(10),
+                    removable_dirs: Vec::new(),
                     remember_position: RememberLastPosition::Depends {
                         music: PositionYesNo::Simple(PositionYesNoLower::No),
                         podcast: PositionYesNo::Simple(PositionYesNoLower::Yes),
                     },
+                    restore_queue: true,
+                    resume_on_start: false,
                     loop_mode: LoopMode::Random,
                     volume: 70,
                     speed: 10,
                     gapless: true,
+                    replay_gain: ReplayGainMode::Off,
+                    crossfade: CrossfadeSettings::default(),
+                    skip_silence: SkipSilenceSettings::default(),
+                    stream_prefetch_secs: 15,
                     seek_step: SeekStep::Depends {
                         short_tracks: NonZeroU32::new(5).unwrap(),
                         long_tracks: NonZeroU32::new(30).unwrap(),
                     },
                     use_mediacontrols: true,
                     set_discord_status: true,
+                    title_format: DEFAULT_TITLE_FORMAT.to_string(),
+                    organize_pattern: DEFAULT_ORGANIZE_PATTERN.to_string(),
                     random_track_quantity: NonZeroU32::new(20).unwrap(),
                     random_album_min_quantity: NonZeroU32::new(5).unwrap(),
+                    auto_queue: AutoQueueSettings::default(),
                     backend: Backend::default(),
                 }
             );
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ComSettings;
+
+    fn com(auth_token: Option<&str>, guest_token: Option<&str>) -> ComSettings {
+        ComSettings {
+            auth_token: auth_token.map(ToString::to_string),
+            guest_token: guest_token.map(ToString::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_allow_tokens() {
+        assert!(com(None, None).check_tokens().is_ok());
+        assert!(com(Some("secret"), None).check_tokens().is_ok());
+        assert!(com(Some("secret"), Some("guest")).check_tokens().is_ok());
+    }
+
+    #[test]
+    fn should_reject_guest_token_without_auth_token() {
+        assert!(com(None, Some("guest")).check_tokens().is_err());
+    }
+
+    #[test]
+    fn should_reject_same_tokens() {
+        assert!(com(Some("secret"), Some("secret")).check_tokens().is_err());
+    }
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+/// Settings for submitting played tracks ("scrobbling") to listening history services.
+///
+/// Only music tracks with a artist and title are submitted, podcasts and radio are never submitted.
+///
+/// Changes require a server restart.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(default)] // allow missing fields and fill them with the `..Self::default()` in this struct
+pub struct ScrobbleSettings {
+    /// Submit to Last.fm, disabled if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lastfm: Option<LastfmSettings>,
+    /// Submit to ListenBrainz, disabled if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub listenbrainz: Option<ListenBrainzSettings>,
+}
+
+/// Credentials for Last.fm.
+///
+/// A API account can be created at `https://www.last.fm/api/account/create`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(default)] // allow missing fields and fill them with the `..Self::default()` in this struct
+pub struct LastfmSettings {
+    /// The "API key" of the API account
+    pub api_key: String,
+    /// The "Shared secret" of the API account
+    pub api_secret: String,
+    /// The Last.fm user to submit as
+    pub username: String,
+    pub password: String,
+    /// Also "love" tracks on Last.fm when they are marked as favorite, and "unlove" them when unmarked
+    pub sync_loved: bool,
+}
+
+/// Credentials and options for ListenBrainz.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)] // allow missing fields and fill them with the `..Self::default()` in this struct
+pub struct ListenBrainzSettings {
+    /// The user token, found at `https://listenbrainz.org/settings/`
+    pub token: String,
+    /// Base url of the API, only needs to be changed for self-hosted instances
+    pub api_url: String,
+}
+
+impl Default for ListenBrainzSettings {
+    fn default() -> Self {
+        Self {
+            token: String::new(),
+            api_url: "https://api.listenbrainz.org".to_string(),
+        }
+    }
+}
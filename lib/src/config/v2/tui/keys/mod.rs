@@ -76,6 +76,8 @@ pub struct Keys {
     pub database_keys: KeysDatabase,
     #[serde(rename = "podcast")]
     pub podcast_keys: KeysPodcast,
+    #[serde(rename = "radio")]
+    pub radio_keys: KeysRadio,
     #[serde(rename = "adjust_cover_art")]
     pub move_cover_art_keys: KeysMoveCoverArt,
     #[serde(rename = "config")]
@@ -106,6 +108,7 @@ impl Default for Keys {
             playlist_keys: KeysPlaylist::default(),
             database_keys: KeysDatabase::default(),
             podcast_keys: KeysPodcast::default(),
+            radio_keys: KeysRadio::default(),
             move_cover_art_keys: KeysMoveCoverArt::default(),
             config_keys: KeysConfigEditor::default(),
         }
@@ -217,6 +220,11 @@ impl CheckConflict for Keys {
             conflicts.extend(new);
         }
         key_path.pop();
+        key_path.push("radio");
+        if let Err(new) = self.radio_keys.check_conflict(key_path, global_keys) {
+            conflicts.extend(new);
+        }
+        key_path.pop();
 
         // -------------
         if !conflicts.is_empty() {
@@ -237,11 +245,15 @@ pub struct KeysSelectView {
     pub view_database: KeyBinding,
     /// Key to switch to the Podcast view
     pub view_podcasts: KeyBinding,
+    /// Key to switch to the Radio view
+    pub view_radio: KeyBinding,
 
     /// Key to open the Config view
     pub open_config: KeyBinding,
     /// Key to open the Help-Popup
     pub open_help: KeyBinding,
+    /// Key to open the list of recent errors
+    pub open_error_history: KeyBinding,
 }
 
 impl Default for KeysSelectView {
@@ -250,6 +262,7 @@ impl Default for KeysSelectView {
             view_library: tuievents::Key::Char('1').into(),
             view_database: tuievents::Key::Char('2').into(),
             view_podcasts: tuievents::Key::Char('3').into(),
+            view_radio: tuievents::Key::Char('4').into(),
             open_config: tuievents::KeyEvent::new(
                 tuievents::Key::Char('C'),
                 tuievents::KeyModifiers::SHIFT,
@@ -260,6 +273,11 @@ impl Default for KeysSelectView {
                 tuievents::KeyModifiers::CONTROL,
             )
             .into(),
+            open_error_history: tuievents::KeyEvent::new(
+                tuievents::Key::Char('E'),
+                tuievents::KeyModifiers::SHIFT,
+            )
+            .into(),
         }
     }
 }
@@ -270,9 +288,11 @@ impl CheckConflict for KeysSelectView {
             (&self.view_library, "view_library"),
             (&self.view_database, "view_database"),
             (&self.view_podcasts, "view_podcasts"),
+            (&self.view_radio, "view_radio"),
 
             (&self.open_config, "open_config"),
-            (&self.open_help, "open_help")
+            (&self.open_help, "open_help"),
+            (&self.open_error_history, "open_error_history")
         }
     }
 
@@ -356,6 +376,18 @@ pub struct KeysPlayer {
     ///
     /// Will only apply in specific widgets (like the Playlist, but not in Config)
     pub speed_down: KeyBinding,
+    /// Key to keep the current speed for the current track or its whole podcast, or go back to the global speed for it
+    ///
+    /// Will only apply in specific widgets (like the Playlist, but not in Config)
+    pub track_speed: KeyBinding,
+    /// Key to set the start, then the end of the section of the current track to loop, and then to stop looping it
+    ///
+    /// Will only apply in specific widgets (like the Playlist, but not in Config)
+    pub ab_repeat: KeyBinding,
+    /// Key to open the bookmarks of the current track
+    ///
+    /// Will only apply in specific widgets (like the Playlist, but not in Config)
+    pub bookmarks: KeyBinding,
     /// Key to toggle if track-prefetching should be enabled
     ///
     /// Will only apply in specific widgets (like the Playlist, but not in Config)
@@ -364,6 +396,18 @@ pub struct KeysPlayer {
 
     /// Key to save the current playlist as a "m3u" playlist
     pub save_playlist: KeyBinding,
+    /// Key to open the list of chapters of the current track, to jump to one
+    pub chapters: KeyBinding,
+    /// Key to jump to the next chapter of the current track
+    pub next_chapter: KeyBinding,
+    /// Key to jump to the start of the current chapter, or the previous chapter if just at the start
+    pub previous_chapter: KeyBinding,
+    /// Key to enter a time to seek the current track to
+    pub goto_time: KeyBinding,
+    /// Key to choose a network renderer (DLNA / UPnP) to cast playback to
+    pub cast: KeyBinding,
+    /// Key to show how the server outputs audio, to troubleshoot audio issues
+    pub audio_info: KeyBinding,
 }
 
 impl Default for KeysPlayer {
@@ -390,6 +434,21 @@ impl Default for KeysPlayer {
                 tuievents::KeyModifiers::CONTROL,
             )
             .into(),
+            track_speed: tuievents::KeyEvent::new(
+                tuievents::Key::Char('p'),
+                tuievents::KeyModifiers::CONTROL,
+            )
+            .into(),
+            ab_repeat: tuievents::KeyEvent::new(
+                tuievents::Key::Char('a'),
+                tuievents::KeyModifiers::CONTROL,
+            )
+            .into(),
+            bookmarks: tuievents::KeyEvent::new(
+                tuievents::Key::Char('x'),
+                tuievents::KeyModifiers::CONTROL,
+            )
+            .into(),
             toggle_prefetch: tuievents::KeyEvent::new(
                 tuievents::Key::Char('g'),
                 tuievents::KeyModifiers::CONTROL,
@@ -400,6 +459,28 @@ impl Default for KeysPlayer {
                 tuievents::KeyModifiers::CONTROL,
             )
             .into(),
+            chapters: tuievents::KeyEvent::new(
+                tuievents::Key::Char('t'),
+                tuievents::KeyModifiers::CONTROL,
+            )
+            .into(),
+            next_chapter: tuievents::Key::Char(']').into(),
+            previous_chapter: tuievents::Key::Char('[').into(),
+            goto_time: tuievents::KeyEvent::new(
+                tuievents::Key::Char('k'),
+                tuievents::KeyModifiers::CONTROL,
+            )
+            .into(),
+            cast: tuievents::KeyEvent::new(
+                tuievents::Key::Char('o'),
+                tuievents::KeyModifiers::CONTROL,
+            )
+            .into(),
+            audio_info: tuievents::KeyEvent::new(
+                tuievents::Key::Char('d'),
+                tuievents::KeyModifiers::CONTROL,
+            )
+            .into(),
         }
     }
 }
@@ -416,9 +497,18 @@ impl CheckConflict for KeysPlayer {
             (&self.seek_backward, "seek_backward"),
             (&self.speed_up, "speed_up"),
             (&self.speed_down, "speed_down"),
+            (&self.track_speed, "track_speed"),
+            (&self.ab_repeat, "ab_repeat"),
+            (&self.bookmarks, "bookmarks"),
             (&self.toggle_prefetch, "toggle_prefetch"),
 
             (&self.save_playlist, "save_playlist"),
+            (&self.chapters, "chapters"),
+            (&self.next_chapter, "next_chapter"),
+            (&self.previous_chapter, "previous_chapter"),
+            (&self.goto_time, "goto_time"),
+            (&self.cast, "cast"),
+            (&self.audio_info, "audio_info"),
         }
     }
 
@@ -478,6 +568,14 @@ pub struct KeysLyric {
     ///
     /// Will only apply in specific widgets (like the Playlist, but not in Config)
     pub cycle_frames: KeyBinding,
+    /// Key to insert a caption at the current position into the lyrics, to create synced lyrics
+    ///
+    /// Will only apply in specific widgets (like the Playlist, but not in Config)
+    pub capture_caption: KeyBinding,
+    /// Key to open the editor for the synced lyrics of the current track
+    ///
+    /// Will only apply in specific widgets (like the Playlist, but not in Config)
+    pub open_editor: KeyBinding,
 }
 
 impl Default for KeysLyric {
@@ -498,6 +596,16 @@ impl Default for KeysLyric {
                 tuievents::KeyModifiers::SHIFT,
             )
             .into(),
+            capture_caption: tuievents::KeyEvent::new(
+                tuievents::Key::Char('I'),
+                tuievents::KeyModifiers::SHIFT,
+            )
+            .into(),
+            open_editor: tuievents::KeyEvent::new(
+                tuievents::Key::Char('O'),
+                tuievents::KeyModifiers::SHIFT,
+            )
+            .into(),
         }
     }
 }
@@ -508,6 +616,8 @@ impl CheckConflict for KeysLyric {
             (&self.adjust_offset_forwards, "adjust_offset_forwards"),
             (&self.adjust_offset_backwards, "adjust_offset_backwards"),
             (&self.cycle_frames, "cycle_frames"),
+            (&self.capture_caption, "capture_caption"),
+            (&self.open_editor, "open_editor"),
         }
     }
 
@@ -658,6 +768,12 @@ pub struct KeysLibrary {
     pub add_root: KeyBinding,
     /// Key to remove the currently entered node as music root
     pub remove_root: KeyBinding,
+    /// Key to rescan all music directories for new or changed tracks
+    pub rescan: KeyBinding,
+    /// Key to cycle through the filters for which files are shown in the tree
+    pub cycle_filter: KeyBinding,
+    /// Key to open the selected file with the external program from `external_open.command`
+    pub open_external: KeyBinding,
 
     /// Key to open local search (root being the selected `music_dir` root)
     pub search: KeyBinding,
@@ -665,6 +781,18 @@ pub struct KeysLibrary {
     pub youtube_search: KeyBinding,
     /// Key to open the tag editor on that node (only works for files)
     pub open_tag_editor: KeyBinding,
+    /// Key to open the menu of all actions for the currently selected node
+    pub track_menu: KeyBinding,
+    /// Key to cycle the rating of the currently selected track, from unrated to 5 and back
+    pub cycle_rating: KeyBinding,
+    /// Key to toggle whether the currently selected track is a favorite
+    pub toggle_favorite: KeyBinding,
+    /// Key to mark the currently selected track, to edit the tags of all marked tracks at once
+    pub mark: KeyBinding,
+    /// Key to preview and move all library tracks to the path of the server's `organize_pattern`
+    pub organize: KeyBinding,
+    /// Key to toggle whether the currently selected directory is played as a audiobook, continuing where it was left
+    pub toggle_audiobook: KeyBinding,
 }
 
 impl Default for KeysLibrary {
@@ -686,9 +814,34 @@ impl Default for KeysLibrary {
                 tuievents::KeyModifiers::SHIFT,
             )
             .into(),
+            rescan: tuievents::KeyEvent::new(
+                tuievents::Key::Char('R'),
+                tuievents::KeyModifiers::SHIFT,
+            )
+            .into(),
+            cycle_filter: tuievents::Key::Char('c').into(),
+            open_external: tuievents::Key::Char('e').into(),
             search: tuievents::Key::Char('/').into(),
             youtube_search: tuievents::Key::Char('s').into(),
             open_tag_editor: tuievents::Key::Char('t').into(),
+            track_menu: tuievents::KeyEvent::new(
+                tuievents::Key::Char('M'),
+                tuievents::KeyModifiers::SHIFT,
+            )
+            .into(),
+            cycle_rating: tuievents::KeyEvent::new(
+                tuievents::Key::Char('V'),
+                tuievents::KeyModifiers::SHIFT,
+            )
+            .into(),
+            toggle_favorite: tuievents::Key::Char('v').into(),
+            mark: tuievents::Key::Char('m').into(),
+            organize: tuievents::KeyEvent::new(
+                tuievents::Key::Char('Z'),
+                tuievents::KeyModifiers::SHIFT,
+            )
+            .into(),
+            toggle_audiobook: tuievents::Key::Char('u').into(),
         }
     }
 }
@@ -704,10 +857,19 @@ impl CheckConflict for KeysLibrary {
             (&self.cycle_root, "cycle_root"),
             (&self.add_root, "add_root"),
             (&self.remove_root, "remove_root"),
+            (&self.rescan, "rescan"),
+            (&self.cycle_filter, "cycle_filter"),
+            (&self.open_external, "open_external"),
 
             (&self.search, "search"),
             (&self.youtube_search, "youtube_search"),
             (&self.open_tag_editor, "open_tag_editor"),
+            (&self.track_menu, "track_menu"),
+            (&self.cycle_rating, "cycle_rating"),
+            (&self.toggle_favorite, "toggle_favorite"),
+            (&self.mark, "mark"),
+            (&self.organize, "organize"),
+            (&self.toggle_audiobook, "toggle_audiobook"),
         }
     }
 
@@ -779,6 +941,25 @@ pub struct KeysPlaylist {
     /// previously known as `cmus_lqueue`
     // NOTE: currently this can be somewhat broken sometimes, cause unknown
     pub add_random_album: KeyBinding,
+
+    /// Key to export the playlist to a file in a chosen format (M3U, M3U8, PLS, XSPF)
+    pub export: KeyBinding,
+    /// Key to import a playlist file or a url list (`.txt`, one url or path per line) into the playlist
+    pub import: KeyBinding,
+    /// Key to open the menu of all actions for the currently selected track
+    pub track_menu: KeyBinding,
+    /// Key to store the queue in the server's database
+    pub save_queue: KeyBinding,
+    /// Key to replace the queue with the one stored in the server's database
+    pub restore_queue: KeyBinding,
+    /// Key to toggle the server keeping the queue filled with tracks from the library
+    pub toggle_auto_queue: KeyBinding,
+    /// Key to show the named queues of the server, to switch, create or delete them
+    pub queues: KeyBinding,
+    /// Key to cycle the rating of the currently selected track, from unrated to 5 and back
+    pub cycle_rating: KeyBinding,
+    /// Key to toggle whether the currently selected track is a favorite
+    pub toggle_favorite: KeyBinding,
 }
 
 impl Default for KeysPlaylist {
@@ -810,6 +991,40 @@ impl Default for KeysPlaylist {
                 tuievents::KeyModifiers::SHIFT,
             )
             .into(),
+
+            export: tuievents::Key::Char('x').into(),
+            import: tuievents::Key::Char('i').into(),
+            track_menu: tuievents::KeyEvent::new(
+                tuievents::Key::Char('M'),
+                tuievents::KeyModifiers::SHIFT,
+            )
+            .into(),
+            save_queue: tuievents::KeyEvent::new(
+                tuievents::Key::Char('W'),
+                tuievents::KeyModifiers::SHIFT,
+            )
+            .into(),
+            restore_queue: tuievents::KeyEvent::new(
+                tuievents::Key::Char('R'),
+                tuievents::KeyModifiers::SHIFT,
+            )
+            .into(),
+            toggle_auto_queue: tuievents::KeyEvent::new(
+                tuievents::Key::Char('U'),
+                tuievents::KeyModifiers::SHIFT,
+            )
+            .into(),
+            queues: tuievents::KeyEvent::new(
+                tuievents::Key::Char('Q'),
+                tuievents::KeyModifiers::SHIFT,
+            )
+            .into(),
+            cycle_rating: tuievents::KeyEvent::new(
+                tuievents::Key::Char('V'),
+                tuievents::KeyModifiers::SHIFT,
+            )
+            .into(),
+            toggle_favorite: tuievents::Key::Char('v').into(),
         }
     }
 }
@@ -828,6 +1043,16 @@ impl CheckConflict for KeysPlaylist {
 
             (&self.add_random_songs, "add_random_songs"),
             (&self.add_random_album, "add_random_album"),
+
+            (&self.export, "export"),
+            (&self.import, "import"),
+            (&self.track_menu, "track_menu"),
+            (&self.save_queue, "save_queue"),
+            (&self.restore_queue, "restore_queue"),
+            (&self.toggle_auto_queue, "toggle_auto_queue"),
+            (&self.queues, "queues"),
+            (&self.cycle_rating, "cycle_rating"),
+            (&self.toggle_favorite, "toggle_favorite"),
         }
     }
 
@@ -883,14 +1108,30 @@ pub struct KeysPodcast {
     pub refresh_feed: KeyBinding,
     /// Key to refresh all added feeds
     pub refresh_all_feeds: KeyBinding,
+    /// Key to add the currently selected episode to the playlist for streaming, regardless of `behavior.episode_enter`
+    pub stream_episode: KeyBinding,
     /// Key to download the currently selected episode
     pub download_episode: KeyBinding,
+    /// Key to download all unplayed episodes of the current feed, after confirming their estimated size
+    pub download_all_unplayed: KeyBinding,
     /// Key to delete the downloaded local file of the currently selected episode
     pub delete_local_episode: KeyBinding,
     /// Key to delete the currently selected feed
     pub delete_feed: KeyBinding,
     /// Key to delete all the added feeds
     pub delete_all_feeds: KeyBinding,
+    /// Key to switch to the next order of the feeds list
+    pub cycle_feed_sort: KeyBinding,
+    /// Key to only show the feeds of the next category
+    pub cycle_category: KeyBinding,
+    /// Key to open the menu of all actions for the currently selected episode
+    pub episode_menu: KeyBinding,
+    /// Key to edit the auto-download and retention settings of the currently selected feed
+    pub feed_settings: KeyBinding,
+    /// Key to show the author, last check and download size of the currently selected feed
+    pub feed_info: KeyBinding,
+    /// Key to merge the currently selected feed into another feed, keeping played status, positions and downloads
+    pub merge_feed: KeyBinding,
 }
 
 impl Default for KeysPodcast {
@@ -909,7 +1150,13 @@ impl Default for KeysPodcast {
                 tuievents::KeyModifiers::SHIFT,
             )
             .into(),
+            stream_episode: tuievents::Key::Char('p').into(),
             download_episode: tuievents::Key::Char('d').into(),
+            download_all_unplayed: tuievents::KeyEvent::new(
+                tuievents::Key::Char('A'),
+                tuievents::KeyModifiers::SHIFT,
+            )
+            .into(),
             delete_local_episode: tuievents::KeyEvent::new(
                 tuievents::Key::Char('D'),
                 tuievents::KeyModifiers::SHIFT,
@@ -921,6 +1168,20 @@ impl Default for KeysPodcast {
                 tuievents::KeyModifiers::SHIFT,
             )
             .into(),
+            cycle_feed_sort: tuievents::Key::Char('o').into(),
+            cycle_category: tuievents::Key::Char('c').into(),
+            episode_menu: tuievents::Key::Char('e').into(),
+            feed_settings: tuievents::KeyEvent::new(
+                tuievents::Key::Char('S'),
+                tuievents::KeyModifiers::SHIFT,
+            )
+            .into(),
+            feed_info: tuievents::Key::Char('i').into(),
+            merge_feed: tuievents::KeyEvent::new(
+                tuievents::Key::Char('J'),
+                tuievents::KeyModifiers::SHIFT,
+            )
+            .into(),
         }
     }
 }
@@ -934,9 +1195,83 @@ impl CheckConflict for KeysPodcast {
             (&self.refresh_feed, "refresh_feed"),
             (&self.refresh_all_feeds, "refresh_all_feeds"),
             (&self.download_episode, "download_episode"),
+            (&self.stream_episode, "stream_episode"),
+            (&self.download_all_unplayed, "download_all_unplayed"),
             (&self.delete_local_episode, "delete_local_episode"),
             (&self.delete_feed, "delete_feed"),
             (&self.delete_all_feeds, "delete_all_feeds"),
+            (&self.cycle_feed_sort, "cycle_feed_sort"),
+            (&self.cycle_category, "cycle_category"),
+            (&self.episode_menu, "episode_menu"),
+            (&self.feed_settings, "feed_settings"),
+            (&self.feed_info, "feed_info"),
+            (&self.merge_feed, "merge_feed"),
+        }
+    }
+
+    fn check_conflict(
+        &self,
+        key_path: &mut KeyPath,
+        global_keys: &mut KeyHashMapOwned,
+    ) -> Result<(), Vec<KeyConflictError>> {
+        let mut conflicts: Vec<KeyConflictError> = Vec::new();
+        let mut current_keys = KeyHashMap::new();
+
+        for (key, path) in self.iter() {
+            // check global first
+            if let Some(existing_path) = global_keys.get(key) {
+                conflicts.push(KeyConflictError {
+                    key_path_first: existing_path.clone(),
+                    key_path_second: key_path.join_with_field(path),
+                    key: key.clone(),
+                });
+                continue;
+            }
+
+            if let Some(existing_path) = current_keys.get(key) {
+                conflicts.push(KeyConflictError {
+                    key_path_first: key_path.join_with_field(existing_path),
+                    key_path_second: key_path.join_with_field(path),
+                    key: key.clone(),
+                });
+                continue;
+            }
+
+            current_keys.insert(key, path);
+        }
+
+        if !conflicts.is_empty() {
+            return Err(conflicts);
+        }
+
+        Ok(())
+    }
+}
+
+/// Keys for the Radio view
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)] // allow missing fields and fill them with the `..Self::default()` in this struct
+pub struct KeysRadio {
+    /// Key to open the search for new stations
+    pub search: KeyBinding,
+    /// Key to add or remove the currently selected station from the favorites
+    pub toggle_favorite: KeyBinding,
+}
+
+impl Default for KeysRadio {
+    fn default() -> Self {
+        Self {
+            search: tuievents::Key::Char('s').into(),
+            toggle_favorite: tuievents::Key::Char('a').into(),
+        }
+    }
+}
+
+impl CheckConflict for KeysRadio {
+    fn iter(&self) -> impl Iterator<Item = (&KeyBinding, &'static str)> {
+        once_chain! {
+            (&self.search, "search"),
+            (&self.toggle_favorite, "toggle_favorite"),
         }
     }
 
@@ -1104,6 +1439,8 @@ impl CheckConflict for KeysMoveCoverArt {
 pub struct KeysConfigEditor {
     /// Save the config to disk
     pub save: KeyBinding,
+    /// Open the selected lyric of the tag editor in `$EDITOR`
+    pub open_editor: KeyBinding,
 }
 
 impl Default for KeysConfigEditor {
@@ -1114,6 +1451,11 @@ impl Default for KeysConfigEditor {
                 tuievents::KeyModifiers::CONTROL,
             )
             .into(),
+            open_editor: tuievents::KeyEvent::new(
+                tuievents::Key::Char('e'),
+                tuievents::KeyModifiers::CONTROL,
+            )
+            .into(),
         }
     }
 }
@@ -1122,6 +1464,7 @@ impl CheckConflict for KeysConfigEditor {
     fn iter(&self) -> impl Iterator<Item = (&KeyBinding, &'static str)> {
         once_chain! {
             (&self.save, "save"),
+            (&self.open_editor, "open_editor"),
         }
     }
 
@@ -1172,6 +1515,10 @@ pub struct KeysDatabase {
     pub add_selected: KeyBinding,
     /// Add all tracks in the Database view "Tracks" section
     pub add_all: KeyBinding,
+    /// Open the database maintenance popup, to create and restore backups
+    pub maintenance: KeyBinding,
+    /// Play a short preview of the selected track in the "Tracks" section, without changing the playlist
+    pub preview: KeyBinding,
 }
 
 impl Default for KeysDatabase {
@@ -1183,6 +1530,12 @@ impl Default for KeysDatabase {
                 tuievents::KeyModifiers::SHIFT,
             )
             .into(),
+            maintenance: tuievents::KeyEvent::new(
+                tuievents::Key::Char('M'),
+                tuievents::KeyModifiers::SHIFT,
+            )
+            .into(),
+            preview: tuievents::Key::Char('p').into(),
         }
     }
 }
@@ -1191,6 +1544,8 @@ impl CheckConflict for KeysDatabase {
     fn iter(&self) -> impl Iterator<Item = (&KeyBinding, &'static str)> {
         once_chain! {
             (&self.add_all, "add_all"),
+            (&self.maintenance, "maintenance"),
+            (&self.preview, "preview"),
         }
     }
 
@@ -1811,7 +2166,8 @@ impl From<SupportedModifiers> for tuievents::KeyModifiers {
 mod v1_interop {
     use super::{
         KeyBinding, Keys, KeysConfigEditor, KeysDatabase, KeysLibrary, KeysLyric, KeysMoveCoverArt,
-        KeysNavigation, KeysPlayer, KeysPlaylist, KeysPodcast, KeysSelectView, tuievents,
+        KeysNavigation, KeysPlayer, KeysPlaylist, KeysPodcast, KeysRadio, KeysSelectView,
+        tuievents,
     };
     use crate::config::v1;
 
@@ -1894,8 +2250,12 @@ mod v1_interop {
                     view_library: value.global_layout_treeview.into(),
                     view_database: value.global_layout_database.into(),
                     view_podcasts: value.global_layout_podcast.into(),
+                    // does not exist in v1
+                    view_radio: KeysSelectView::default().view_radio,
                     open_config: value.global_config_open.into(),
                     open_help: value.global_help.into(),
+                    // does not exist in v1
+                    open_error_history: KeysSelectView::default().open_error_history,
                 },
                 navigation_keys: KeysNavigation {
                     up: value.global_up.into(),
@@ -1917,11 +2277,25 @@ mod v1_interop {
                     speed_down: value.global_player_speed_down.into(),
                     toggle_prefetch: value.global_player_toggle_gapless.into(),
                     save_playlist: value.global_save_playlist.into(),
+                    // does not exist in v1
+                    track_speed: KeysPlayer::default().track_speed,
+                    ab_repeat: KeysPlayer::default().ab_repeat,
+                    bookmarks: KeysPlayer::default().bookmarks,
+                    chapters: KeysPlayer::default().chapters,
+                    next_chapter: KeysPlayer::default().next_chapter,
+                    previous_chapter: KeysPlayer::default().previous_chapter,
+                    goto_time: KeysPlayer::default().goto_time,
+                    cast: KeysPlayer::default().cast,
+                    audio_info: KeysPlayer::default().audio_info,
                 },
                 lyric_keys: KeysLyric {
                     adjust_offset_forwards: value.global_lyric_adjust_forward.into(),
                     adjust_offset_backwards: value.global_lyric_adjust_backward.into(),
                     cycle_frames: value.global_lyric_cycle.into(),
+                    // does not exist in v1
+                    capture_caption: KeysLyric::default().capture_caption,
+                    // does not exist in v1
+                    open_editor: KeysLyric::default().open_editor,
                 },
                 library_keys: KeysLibrary {
                     // this is weird, but the previous implementation used "global_right" as the loading key to not conflict
@@ -1933,9 +2307,25 @@ mod v1_interop {
                     cycle_root: value.library_switch_root.into(),
                     add_root: value.library_add_root.into(),
                     remove_root: value.library_remove_root.into(),
+                    // does not exist in v1
+                    rescan: KeysLibrary::default().rescan,
+                    cycle_filter: KeysLibrary::default().cycle_filter,
+                    open_external: KeysLibrary::default().open_external,
                     search: value.library_search.into(),
                     youtube_search: value.library_search_youtube.into(),
                     open_tag_editor: value.library_tag_editor_open.into(),
+                    // does not exist in v1
+                    track_menu: KeysLibrary::default().track_menu,
+                    // does not exist in v1
+                    cycle_rating: KeysLibrary::default().cycle_rating,
+                    // does not exist in v1
+                    toggle_favorite: KeysLibrary::default().toggle_favorite,
+                    // does not exist in v1
+                    mark: KeysLibrary::default().mark,
+                    // does not exist in v1
+                    organize: KeysLibrary::default().organize,
+                    // does not exist in v1
+                    toggle_audiobook: KeysLibrary::default().toggle_audiobook,
                 },
                 playlist_keys: KeysPlaylist {
                     delete: value.playlist_delete.into(),
@@ -1948,11 +2338,32 @@ mod v1_interop {
                     swap_down: value.playlist_swap_down.into(),
                     add_random_songs: value.playlist_add_random_tracks.into(),
                     add_random_album: value.playlist_add_random_album.into(),
+                    // does not exist in v1
+                    export: KeysPlaylist::default().export,
+                    // does not exist in v1
+                    import: KeysPlaylist::default().import,
+                    // does not exist in v1
+                    track_menu: KeysPlaylist::default().track_menu,
+                    // does not exist in v1
+                    save_queue: KeysPlaylist::default().save_queue,
+                    // does not exist in v1
+                    restore_queue: KeysPlaylist::default().restore_queue,
+                    // does not exist in v1
+                    toggle_auto_queue: KeysPlaylist::default().toggle_auto_queue,
+                    // does not exist in v1
+                    queues: KeysPlaylist::default().queues,
+                    // does not exist in v1
+                    cycle_rating: KeysPlaylist::default().cycle_rating,
+                    // does not exist in v1
+                    toggle_favorite: KeysPlaylist::default().toggle_favorite,
                 },
                 database_keys: KeysDatabase {
                     // this is weird, but the previous implementation used "global_right" as the loading key to not conflict
                     add_selected: value.global_right.into(),
                     add_all: value.database_add_all.into(),
+                    // does not exist in v1
+                    maintenance: KeysDatabase::default().maintenance,
+                    preview: KeysDatabase::default().preview,
                 },
                 podcast_keys: KeysPodcast {
                     search: value.podcast_search_add_feed.into(),
@@ -1960,11 +2371,29 @@ mod v1_interop {
                     mark_all_played: value.podcast_mark_all_played.into(),
                     refresh_feed: value.podcast_refresh_feed.into(),
                     refresh_all_feeds: value.podcast_refresh_all_feeds.into(),
+                    // does not exist in v1
+                    stream_episode: KeysPodcast::default().stream_episode,
                     download_episode: value.podcast_episode_download.into(),
+                    // does not exist in v1
+                    download_all_unplayed: KeysPodcast::default().download_all_unplayed,
                     delete_local_episode: podcast_delete_episode_key,
                     delete_feed: podcast_delete_feed_key,
                     delete_all_feeds: podcast_delete_all_feeds_key,
+                    // does not exist in v1
+                    cycle_feed_sort: KeysPodcast::default().cycle_feed_sort,
+                    // does not exist in v1
+                    cycle_category: KeysPodcast::default().cycle_category,
+                    // does not exist in v1
+                    episode_menu: KeysPodcast::default().episode_menu,
+                    // does not exist in v1
+                    feed_settings: KeysPodcast::default().feed_settings,
+                    // does not exist in v1
+                    feed_info: KeysPodcast::default().feed_info,
+                    // does not exist in v1
+                    merge_feed: KeysPodcast::default().merge_feed,
                 },
+                // does not exist in v1
+                radio_keys: KeysRadio::default(),
                 move_cover_art_keys: KeysMoveCoverArt {
                     move_left: value.global_xywh_move_left.into(),
                     move_right: value.global_xywh_move_right.into(),
@@ -1976,6 +2405,7 @@ mod v1_interop {
                 },
                 config_keys: KeysConfigEditor {
                     save: value.config_save.into(),
+                    open_editor: KeysConfigEditor::default().open_editor,
                 },
             }
         }
@@ -1997,6 +2427,7 @@ mod v1_interop {
                 view_library: tuievents::Key::Char('1').into(),
                 view_database: tuievents::Key::Char('2').into(),
                 view_podcasts: tuievents::Key::Char('3').into(),
+                view_radio: tuievents::Key::Char('4').into(),
                 open_config: tuievents::KeyEvent::new(
                     tuievents::Key::Char('C'),
                     tuievents::KeyModifiers::SHIFT,
@@ -2007,6 +2438,11 @@ mod v1_interop {
                     tuievents::KeyModifiers::CONTROL,
                 )
                 .into(),
+                open_error_history: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('E'),
+                    tuievents::KeyModifiers::SHIFT,
+                )
+                .into(),
             };
             assert_eq!(converted.select_view_keys, expected_select_view_keys);
 
@@ -2055,6 +2491,21 @@ mod v1_interop {
                     tuievents::KeyModifiers::CONTROL,
                 )
                 .into(),
+                track_speed: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('p'),
+                    tuievents::KeyModifiers::CONTROL,
+                )
+                .into(),
+                ab_repeat: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('a'),
+                    tuievents::KeyModifiers::CONTROL,
+                )
+                .into(),
+                bookmarks: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('x'),
+                    tuievents::KeyModifiers::CONTROL,
+                )
+                .into(),
                 toggle_prefetch: tuievents::KeyEvent::new(
                     tuievents::Key::Char('g'),
                     tuievents::KeyModifiers::CONTROL,
@@ -2065,6 +2516,28 @@ mod v1_interop {
                     tuievents::KeyModifiers::CONTROL,
                 )
                 .into(),
+                chapters: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('t'),
+                    tuievents::KeyModifiers::CONTROL,
+                )
+                .into(),
+                next_chapter: tuievents::Key::Char(']').into(),
+                previous_chapter: tuievents::Key::Char('[').into(),
+                goto_time: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('k'),
+                    tuievents::KeyModifiers::CONTROL,
+                )
+                .into(),
+                cast: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('o'),
+                    tuievents::KeyModifiers::CONTROL,
+                )
+                .into(),
+                audio_info: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('d'),
+                    tuievents::KeyModifiers::CONTROL,
+                )
+                .into(),
             };
             assert_eq!(converted.player_keys, expected_player_keys);
 
@@ -2084,6 +2557,16 @@ mod v1_interop {
                     tuievents::KeyModifiers::SHIFT,
                 )
                 .into(),
+                capture_caption: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('I'),
+                    tuievents::KeyModifiers::SHIFT,
+                )
+                .into(),
+                open_editor: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('O'),
+                    tuievents::KeyModifiers::SHIFT,
+                )
+                .into(),
             };
             assert_eq!(converted.lyric_keys, expected_lyric_keys);
 
@@ -2104,9 +2587,34 @@ mod v1_interop {
                     tuievents::KeyModifiers::SHIFT,
                 )
                 .into(),
+                rescan: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('R'),
+                    tuievents::KeyModifiers::SHIFT,
+                )
+                .into(),
+                cycle_filter: tuievents::Key::Char('c').into(),
+                open_external: tuievents::Key::Char('e').into(),
                 search: tuievents::Key::Char('/').into(),
                 youtube_search: tuievents::Key::Char('s').into(),
                 open_tag_editor: tuievents::Key::Char('t').into(),
+                track_menu: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('M'),
+                    tuievents::KeyModifiers::SHIFT,
+                )
+                .into(),
+                cycle_rating: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('V'),
+                    tuievents::KeyModifiers::SHIFT,
+                )
+                .into(),
+                toggle_favorite: tuievents::Key::Char('v').into(),
+                mark: tuievents::Key::Char('m').into(),
+                organize: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('Z'),
+                    tuievents::KeyModifiers::SHIFT,
+                )
+                .into(),
+                toggle_audiobook: tuievents::Key::Char('u').into(),
             };
             assert_eq!(converted.library_keys, expected_library_keys);
 
@@ -2137,6 +2645,39 @@ mod v1_interop {
                     tuievents::KeyModifiers::SHIFT,
                 )
                 .into(),
+                export: tuievents::Key::Char('x').into(),
+                import: tuievents::Key::Char('i').into(),
+                track_menu: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('M'),
+                    tuievents::KeyModifiers::SHIFT,
+                )
+                .into(),
+                save_queue: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('W'),
+                    tuievents::KeyModifiers::SHIFT,
+                )
+                .into(),
+                restore_queue: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('R'),
+                    tuievents::KeyModifiers::SHIFT,
+                )
+                .into(),
+                toggle_auto_queue: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('U'),
+                    tuievents::KeyModifiers::SHIFT,
+                )
+                .into(),
+                queues: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('Q'),
+                    tuievents::KeyModifiers::SHIFT,
+                )
+                .into(),
+                cycle_rating: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('V'),
+                    tuievents::KeyModifiers::SHIFT,
+                )
+                .into(),
+                toggle_favorite: tuievents::Key::Char('v').into(),
             };
             assert_eq!(converted.playlist_keys, expected_playlist_keys);
 
@@ -2147,6 +2688,12 @@ mod v1_interop {
                     tuievents::KeyModifiers::SHIFT,
                 )
                 .into(),
+                maintenance: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('M'),
+                    tuievents::KeyModifiers::SHIFT,
+                )
+                .into(),
+                preview: tuievents::Key::Char('p').into(),
             };
             assert_eq!(converted.database_keys, expected_database_keys);
 
@@ -2164,7 +2711,13 @@ mod v1_interop {
                     tuievents::KeyModifiers::SHIFT,
                 )
                 .into(),
+                stream_episode: tuievents::Key::Char('p').into(),
                 download_episode: tuievents::Key::Char('d').into(),
+                download_all_unplayed: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('A'),
+                    tuievents::KeyModifiers::SHIFT,
+                )
+                .into(),
                 delete_local_episode: tuievents::KeyEvent::new(
                     tuievents::Key::Char('D'),
                     tuievents::KeyModifiers::SHIFT,
@@ -2176,9 +2729,29 @@ mod v1_interop {
                     tuievents::KeyModifiers::SHIFT,
                 )
                 .into(),
+                cycle_feed_sort: tuievents::Key::Char('o').into(),
+                cycle_category: tuievents::Key::Char('c').into(),
+                episode_menu: tuievents::Key::Char('e').into(),
+                feed_settings: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('S'),
+                    tuievents::KeyModifiers::SHIFT,
+                )
+                .into(),
+                feed_info: tuievents::Key::Char('i').into(),
+                merge_feed: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('J'),
+                    tuievents::KeyModifiers::SHIFT,
+                )
+                .into(),
             };
             assert_eq!(converted.podcast_keys, expected_podcast_keys);
 
+            let expected_radio_keys = KeysRadio {
+                search: tuievents::Key::Char('s').into(),
+                toggle_favorite: tuievents::Key::Char('a').into(),
+            };
+            assert_eq!(converted.radio_keys, expected_radio_keys);
+
             let expected_move_cover_art_keys = KeysMoveCoverArt {
                 move_left: tuievents::KeyEvent::new(
                     tuievents::Key::Left,
@@ -2224,6 +2797,11 @@ mod v1_interop {
                     tuievents::KeyModifiers::CONTROL,
                 )
                 .into(),
+                open_editor: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('e'),
+                    tuievents::KeyModifiers::CONTROL,
+                )
+                .into(),
             };
             assert_eq!(converted.config_keys, expected_config_editor_keys);
 
@@ -2238,6 +2816,7 @@ mod v1_interop {
                 playlist_keys: expected_playlist_keys,
                 database_keys: expected_database_keys,
                 podcast_keys: expected_podcast_keys,
+                radio_keys: expected_radio_keys,
                 move_cover_art_keys: expected_move_cover_art_keys,
                 config_keys: expected_config_editor_keys,
             };
@@ -2292,6 +2871,21 @@ mod v1_interop {
                     tuievents::KeyModifiers::CONTROL,
                 )
                 .into(),
+                track_speed: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('p'),
+                    tuievents::KeyModifiers::CONTROL,
+                )
+                .into(),
+                ab_repeat: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('a'),
+                    tuievents::KeyModifiers::CONTROL,
+                )
+                .into(),
+                bookmarks: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('x'),
+                    tuievents::KeyModifiers::CONTROL,
+                )
+                .into(),
                 toggle_prefetch: tuievents::KeyEvent::new(
                     tuievents::Key::Char('g'),
                     tuievents::KeyModifiers::CONTROL,
@@ -2302,6 +2896,28 @@ mod v1_interop {
                     tuievents::KeyModifiers::CONTROL,
                 )
                 .into(),
+                chapters: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('t'),
+                    tuievents::KeyModifiers::CONTROL,
+                )
+                .into(),
+                next_chapter: tuievents::Key::Char(']').into(),
+                previous_chapter: tuievents::Key::Char('[').into(),
+                goto_time: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('k'),
+                    tuievents::KeyModifiers::CONTROL,
+                )
+                .into(),
+                cast: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('o'),
+                    tuievents::KeyModifiers::CONTROL,
+                )
+                .into(),
+                audio_info: tuievents::KeyEvent::new(
+                    tuievents::Key::Char('d'),
+                    tuievents::KeyModifiers::CONTROL,
+                )
+                .into(),
             };
             assert_eq!(converted.player_keys, expected_player_keys);
         }
@@ -4,6 +4,7 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
 use super::server::ComSettings;
+use crate::songtag::ServiceProvider;
 
 pub mod config_extra;
 pub mod keys;
@@ -23,6 +24,8 @@ pub struct TuiSettings {
     pub theme: theme::ThemeWrap,
     pub keys: keys::Keys,
     pub ytdlp: Ytdlp,
+    pub external_open: ExternalOpen,
+    pub lyrics: LyricsSettings,
 }
 
 impl TuiSettings {
@@ -64,11 +67,18 @@ impl TuiSettings {
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)] // allow missing fields and fill them with the `..Self::default()` in this struct
 pub struct BehaviorSettings {
-    /// Stop / Exit the Server on TUI quit
+    /// Stop / Exit the Server on TUI quit, updated by the choice made in the quit popup
     pub quit_server_on_exit: bool,
     /// Ask before exiting the TUI (popup)
     pub confirm_quit: bool,
+    /// Move deleted library files and podcast episodes to the OS trash instead of permanently deleting them
+    pub delete_to_trash: bool,
+    /// What `Enter` does on a podcast episode, the key `podcast.stream_episode` always streams
+    pub episode_enter: EpisodeEnterAction,
+    /// Also write track ratings to the file tags (`POPM` / `RATING`), not just to the database
+    pub write_rating_tags: bool,
 }
 
 impl Default for BehaviorSettings {
@@ -76,10 +86,24 @@ impl Default for BehaviorSettings {
         Self {
             quit_server_on_exit: true,
             confirm_quit: true,
+            delete_to_trash: true,
+            episode_enter: EpisodeEnterAction::default(),
+            write_rating_tags: false,
         }
     }
 }
 
+/// What to do when a podcast episode is selected with `Enter`.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EpisodeEnterAction {
+    /// Add the episode to the playlist, streaming it if it is not downloaded
+    #[default]
+    Stream,
+    /// Download the episode first, and add it to the playlist once the download finished
+    Download,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum MaybeComSettings {
@@ -91,14 +115,17 @@ pub enum MaybeComSettings {
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(default)] // allow missing fields and fill them with the `..Self::default()` in this struct
-#[derive(Default)]
 pub struct CoverArt {
     /// Alignment of the Cover-Art in the tui
     // TODO: clarify whether it is about the whole terminal size or just a specific component
     pub align: Alignment,
-    /// Scale of the image
+    /// Width of the image in percent of the terminal width, from 1 to 100
+    ///
+    /// Changed and kept by the keys to increase / decrease the size.
     pub size_scale: i8,
     /// Whether to show or hide the coverart if it is compiled in
+    ///
+    /// Changed and kept by the key to toggle it.
     pub hidden: bool,
 
     /// Enabled coverart display protocols. Protocols not compiled-in will not have a effect.
@@ -107,6 +134,17 @@ pub struct CoverArt {
     pub protocols: CoverArtProtocolsSet,
 }
 
+impl Default for CoverArt {
+    fn default() -> Self {
+        Self {
+            align: Alignment::default(),
+            size_scale: 20,
+            hidden: false,
+            protocols: CoverArtProtocolsSet::default(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(transparent)]
 pub struct CoverArtProtocolsSet(HashSet<CoverArtProtocol>);
@@ -169,8 +207,65 @@ pub struct Ytdlp {
     pub extra_args: String,
 }
 
+/// The program to open the selected library file with, see key `keys.library.open_external`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)] // allow missing fields and fill them with the `..Self::default()` in this struct
+pub struct ExternalOpen {
+    /// The command to run, split into arguments like a shell would, but without running a shell.
+    ///
+    /// `{path}` is replaced with the path of the selected file and `{dir}` with its parent directory.
+    /// Replacements never split or join arguments, so paths do not need to be quoted.
+    ///
+    /// The command is started in the background without a terminal, so it should be a graphical program.
+    pub command: String,
+}
+
+impl Default for ExternalOpen {
+    fn default() -> Self {
+        let command = if cfg!(target_os = "windows") {
+            "explorer {path}"
+        } else if cfg!(target_os = "macos") {
+            "open {path}"
+        } else {
+            "xdg-open {path}"
+        };
+
+        Self {
+            command: command.to_string(),
+        }
+    }
+}
+
+/// Where the tag editor searches for lyrics.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)] // allow missing fields and fill them with the `..Self::default()` in this struct
+pub struct LyricsSettings {
+    /// The providers to search, in order of priority. Remove a provider to not search it at all.
+    ///
+    /// Search results are listed in this order, and if the chosen result has no lyrics,
+    /// the next providers are searched for the same track.
+    pub providers: Vec<ServiceProvider>,
+    /// The [AcoustID](https://acoustid.org/new-application) application API key to identify the edited track by its
+    /// acoustic fingerprint with, if `musicbrainz` is one of the `providers`.
+    ///
+    /// Requires `fpcalc` from Chromaprint. Empty to disable.
+    pub acoustid_key: String,
+}
+
+impl Default for LyricsSettings {
+    fn default() -> Self {
+        Self {
+            providers: ServiceProvider::ALL.to_vec(),
+            acoustid_key: String::new(),
+        }
+    }
+}
+
 mod v1_interop {
-    use super::{Alignment, BehaviorSettings, CoverArt, MaybeComSettings, TuiSettings, Ytdlp};
+    use super::{
+        Alignment, BehaviorSettings, CoverArt, EpisodeEnterAction, ExternalOpen, LyricsSettings,
+        MaybeComSettings, TuiSettings, Ytdlp,
+    };
     use crate::config::{v1, v2::tui::CoverArtProtocolsSet};
 
     impl From<v1::Alignment> for Alignment {
@@ -207,11 +302,16 @@ mod v1_interop {
                 behavior: BehaviorSettings {
                     quit_server_on_exit: value.kill_daemon_when_quit,
                     confirm_quit: value.enable_exit_confirmation,
+                    delete_to_trash: true,
+                    episode_enter: EpisodeEnterAction::default(),
+                    write_rating_tags: false,
                 },
                 coverart: value.album_photo_xywh.into(),
                 theme,
                 keys: value.keys.into(),
                 ytdlp: Ytdlp::default(),
+                external_open: ExternalOpen::default(),
+                lyrics: LyricsSettings::default(),
             }
         }
     }
@@ -229,7 +329,10 @@ mod v1_interop {
                 converted.behavior,
                 BehaviorSettings {
                     quit_server_on_exit: true,
-                    confirm_quit: true
+                    confirm_quit: true,
+                    delete_to_trash: true,
+                    episode_enter: EpisodeEnterAction::Stream,
+                    write_rating_tags: false,
                 }
             );
 
@@ -197,6 +197,15 @@ pub struct StyleProgress {
     pub background_color: ColorTermusic,
     /// Track Progressbar border (always)
     pub border_color: ColorTermusic,
+
+    /// If enabled show a line below the Progressbar with the previous and next track of the playlist
+    ///
+    /// Example: "prev: Artist - Title | next: Artist - Title"
+    pub show_queue_preview: bool,
+    /// If enabled show the waveform of the current track inside the Progressbar
+    ///
+    /// The waveform is generated by the server the first time a track is played, which may take a few seconds.
+    pub show_waveform: bool,
 }
 
 impl Default for StyleProgress {
@@ -205,6 +214,9 @@ impl Default for StyleProgress {
             foreground_color: ColorTermusic::LightBlack,
             background_color: ColorTermusic::Reset,
             border_color: ColorTermusic::Blue,
+
+            show_queue_preview: false,
+            show_waveform: false,
         }
     }
 }
@@ -334,6 +346,9 @@ mod v1_interop {
                 foreground_color: value.progress_foreground.into(),
                 background_color: value.progress_background.into(),
                 border_color: value.progress_border.into(),
+                // does not exist in v1
+                show_queue_preview: false,
+                show_waveform: false,
             }
         }
     }
@@ -415,6 +430,8 @@ mod v1_interop {
                 foreground_color: ColorTermusic::LightBlack,
                 background_color: ColorTermusic::Reset,
                 border_color: ColorTermusic::Blue,
+                show_queue_preview: false,
+                show_waveform: false,
             };
             assert_eq!(converted.progress, expected_progress);
 
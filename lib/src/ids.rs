@@ -0,0 +1,64 @@
+//! Typed database ids, to not accidentally mix up ids of different tables or with indexes.
+
+use std::fmt::Display;
+
+use rusqlite::types::{FromSql, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+
+/// Define a newtype around a `i64` table row id, which can be used directly as a sql parameter and result.
+macro_rules! db_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+        pub struct $name(pub i64);
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl ToSql for $name {
+            fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+                self.0.to_sql()
+            }
+        }
+
+        impl FromSql for $name {
+            fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+                i64::column_result(value).map(Self)
+            }
+        }
+    };
+}
+
+db_id!(
+    /// Id of a podcast feed in the podcast database
+    PodcastId
+);
+db_id!(
+    /// Id of a podcast episode in the podcast database
+    EpisodeId
+);
+db_id!(
+    /// Id of a track in the library database
+    TrackId
+);
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rusqlite::Connection;
+
+    use super::{EpisodeId, PodcastId};
+
+    #[test]
+    fn should_roundtrip_through_sql() {
+        let conn = Connection::open_in_memory().unwrap();
+
+        let id: EpisodeId = conn
+            .query_row("SELECT ?;", [EpisodeId(42)], |row| row.get(0))
+            .unwrap();
+        assert_eq!(id, EpisodeId(42));
+        assert_eq!(PodcastId(7).to_string(), "7");
+    }
+}
@@ -6,19 +6,38 @@
 // TODO: work to remove the following lints
 #![allow(clippy::missing_errors_doc)]
 
+pub mod artwork_cache;
+pub mod backup;
+pub mod cli_actions;
 pub mod common;
 pub mod config;
+pub mod ids;
 pub mod invidious;
 pub mod new_database;
+pub mod organize;
+pub mod parental;
 pub mod player;
 pub mod playlist;
 pub mod podcast;
+pub mod radio_browser;
+pub mod rating_tags;
+pub mod scrobbler;
 pub mod songtag;
+pub mod state_bundle;
 pub mod taskpool;
 pub mod track;
+pub mod url_list;
 pub mod utils;
+pub mod waveform;
 pub mod xywh;
 
+/// Private module for benchmarking only, should never be used outside.
+///
+/// This is necessary as benchmarking via criterion can only access public lib(crate) level function, like any other outside binary / crate.
+pub mod __bench {
+    pub use super::new_database::track_insert::TrackInsertable;
+}
+
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 use include_dir::{Dir, include_dir};
@@ -0,0 +1,295 @@
+//! Directories played as audiobooks, which keep their speed and where they were left across their files.
+//!
+//! A directory is a audiobook if it is marked as one, or if it is detected as one by [`detect_audiobook`].
+//! Unmarking a detected audiobook is stored too, so that it is not detected again.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use indoc::indoc;
+use rusqlite::{Connection, OptionalExtension, named_params};
+
+use crate::new_database::Integer;
+
+/// The settings and progress of a audiobook directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Audiobook {
+    /// The directory of the audiobook, including its sub-directories
+    pub dir: PathBuf,
+    /// Whether the directory is played as a audiobook, `false` if it was explicitly unmarked
+    pub enabled: bool,
+    /// The playback speed in tenths like `player.speed`, `None` to use the global speed
+    pub speed: Option<i32>,
+    /// Whether silence should be skipped while playing the audiobook
+    pub skip_silence: bool,
+    /// The track and position the audiobook was last played at
+    pub last_played: Option<(PathBuf, Duration)>,
+}
+
+/// Get the audiobook stored for exactly `dir`, regardless of whether it is enabled.
+pub fn get_audiobook(conn: &Connection, dir: &Path) -> Result<Option<Audiobook>> {
+    let mut stmt = conn.prepare_cached(indoc! {"
+        SELECT enabled, speed, skip_silence, last_track, last_position FROM audiobooks
+        WHERE dir=:dir;
+    "})?;
+
+    let audiobook = stmt
+        .query_row(named_params! {":dir": dir.to_string_lossy()}, |row| {
+            let last_track: Option<String> = row.get(3)?;
+            let last_position: Option<Integer> = row.get(4)?;
+
+            Ok(Audiobook {
+                dir: dir.to_path_buf(),
+                enabled: row.get(0)?,
+                // invalid values are treated as no speed
+                speed: row
+                    .get::<_, Option<Integer>>(1)?
+                    .and_then(|v| i32::try_from(v).ok()),
+                skip_silence: row.get(2)?,
+                last_played: last_track.map(|track| {
+                    let position =
+                        u64::try_from(last_position.unwrap_or_default().max(0)).unwrap_or_default();
+                    (PathBuf::from(track), Duration::from_secs(position))
+                }),
+            })
+        })
+        .optional()?;
+
+    Ok(audiobook)
+}
+
+/// Get the audiobook `track` is part of, being the closest of its parent directories with a stored audiobook.
+///
+/// This does not [detect](detect_audiobook) audiobooks, and also returns disabled ones.
+pub fn find_audiobook(conn: &Connection, track: &Path) -> Result<Option<Audiobook>> {
+    for dir in track.ancestors().skip(1) {
+        if let Some(audiobook) = get_audiobook(conn, dir)? {
+            return Ok(Some(audiobook));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Get the enabled audiobook `track` is part of.
+///
+/// If no audiobook is stored for it, its directory is [detected](detect_audiobook) and stored as one if it looks like a audiobook.
+pub fn audiobook_for_track(conn: &Connection, track: &Path) -> Result<Option<Audiobook>> {
+    if let Some(audiobook) = find_audiobook(conn, track)? {
+        return Ok(audiobook.enabled.then_some(audiobook));
+    }
+
+    let Some(dir) = track.parent() else {
+        return Ok(None);
+    };
+    if !detect_audiobook(conn, dir)? {
+        return Ok(None);
+    }
+    set_audiobook(conn, dir, true)?;
+
+    get_audiobook(conn, dir)
+}
+
+/// Get whether exactly `dir` is played as a audiobook, by being marked or [detected](detect_audiobook) as one.
+pub fn is_audiobook_dir(conn: &Connection, dir: &Path) -> Result<bool> {
+    match get_audiobook(conn, dir)? {
+        Some(audiobook) => Ok(audiobook.enabled),
+        None => detect_audiobook(conn, dir),
+    }
+}
+
+/// Get whether the tracks of exactly `dir` look like a audiobook, by being `m4b` files or having a "Audiobook" genre.
+pub fn detect_audiobook(conn: &Connection, dir: &Path) -> Result<bool> {
+    let mut stmt = conn.prepare_cached(indoc! {"
+        SELECT EXISTS(
+            SELECT 1 FROM tracks
+            LEFT JOIN tracks_metadata ON tracks.id=tracks_metadata.track
+            WHERE tracks.file_dir=:dir AND (
+                lower(tracks.file_ext)='m4b'
+                OR lower(tracks_metadata.genre) LIKE '%audiobook%'
+                OR lower(tracks_metadata.genre) LIKE '%audio book%'
+            )
+        );
+    "})?;
+
+    let detected = stmt.query_row(named_params! {":dir": dir.to_string_lossy()}, |row| {
+        row.get(0)
+    })?;
+
+    Ok(detected)
+}
+
+/// Mark `dir` as a audiobook, or unmark it to not be detected as one with `enabled: false`.
+///
+/// The speed and progress are kept when toggling.
+pub fn set_audiobook(conn: &Connection, dir: &Path, enabled: bool) -> Result<()> {
+    conn.execute(
+        indoc! {"
+            INSERT INTO audiobooks (dir, enabled) VALUES (:dir, :enabled)
+            ON CONFLICT(dir) DO UPDATE SET enabled=excluded.enabled;
+        "},
+        named_params! {":dir": dir.to_string_lossy(), ":enabled": enabled},
+    )?;
+
+    Ok(())
+}
+
+/// Set the playback `speed` of the audiobook `dir`, `None` to use the global speed again.
+pub fn set_speed(conn: &Connection, dir: &Path, speed: Option<i32>) -> Result<()> {
+    update(
+        conn,
+        dir,
+        "UPDATE audiobooks SET speed=:value WHERE dir=:dir;",
+        speed,
+    )
+}
+
+/// Set whether silence should be skipped while playing the audiobook `dir`.
+pub fn set_skip_silence(conn: &Connection, dir: &Path, skip_silence: bool) -> Result<()> {
+    update(
+        conn,
+        dir,
+        "UPDATE audiobooks SET skip_silence=:value WHERE dir=:dir;",
+        skip_silence,
+    )
+}
+
+/// Store that the audiobook `dir` was last played at `position` of `track`.
+pub fn set_last_played(
+    conn: &Connection,
+    dir: &Path,
+    track: &Path,
+    position: Duration,
+) -> Result<()> {
+    let position = Integer::try_from(position.as_secs()).unwrap_or(Integer::MAX);
+    let affected = conn.execute(
+        indoc! {"
+            UPDATE audiobooks SET last_track=:track, last_position=:position
+            WHERE dir=:dir;
+        "},
+        named_params! {
+            ":dir": dir.to_string_lossy(),
+            ":track": track.to_string_lossy(),
+            ":position": position,
+        },
+    )?;
+
+    // update would otherwise fail silently
+    if affected == 0 {
+        bail!("Audiobook not found");
+    }
+
+    Ok(())
+}
+
+/// Run the `UPDATE` `sql` with `:value` for the audiobook `dir`.
+fn update<T: rusqlite::ToSql>(conn: &Connection, dir: &Path, sql: &str, value: T) -> Result<()> {
+    let affected = conn.execute(
+        sql,
+        named_params! {":dir": dir.to_string_lossy(), ":value": value},
+    )?;
+
+    // update would otherwise fail silently
+    if affected == 0 {
+        bail!("Audiobook not found");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::new_database::audiobook_ops::{
+        Audiobook, audiobook_for_track, detect_audiobook, find_audiobook, get_audiobook,
+        is_audiobook_dir, set_audiobook, set_last_played, set_speed,
+    };
+    use crate::new_database::test_utils::{gen_database, test_path};
+    use crate::new_database::track_insert::TrackInsertable;
+    use crate::track::TrackMetadata;
+
+    #[test]
+    fn should_mark_and_find_audiobooks() {
+        let db = gen_database();
+        let conn = db.get_connection();
+        let dir = Path::new("/books/Some Book");
+
+        assert_eq!(get_audiobook(&conn, dir).unwrap(), None);
+        // only existing audiobooks can be changed
+        assert!(set_speed(&conn, dir, Some(15)).is_err());
+
+        set_audiobook(&conn, dir, true).unwrap();
+        set_speed(&conn, dir, Some(15)).unwrap();
+        set_last_played(
+            &conn,
+            dir,
+            Path::new("/books/Some Book/CD2/02.mp3"),
+            Duration::from_secs(90),
+        )
+        .unwrap();
+        // toggling keeps the settings
+        set_audiobook(&conn, dir, false).unwrap();
+        set_audiobook(&conn, dir, true).unwrap();
+
+        let expected = Audiobook {
+            dir: dir.to_path_buf(),
+            enabled: true,
+            speed: Some(15),
+            skip_silence: false,
+            last_played: Some((
+                PathBuf::from("/books/Some Book/CD2/02.mp3"),
+                Duration::from_secs(90),
+            )),
+        };
+        assert_eq!(get_audiobook(&conn, dir).unwrap(), Some(expected.clone()));
+        assert_eq!(
+            find_audiobook(&conn, Path::new("/books/Some Book/CD2/01.mp3")).unwrap(),
+            Some(expected)
+        );
+        assert_eq!(
+            find_audiobook(&conn, Path::new("/books/Other Book/01.mp3")).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn should_detect_audiobooks() {
+        let db = gen_database();
+        let conn = db.get_connection();
+
+        let book = test_path(Path::new("/books/Some Book/Some Book.m4b"));
+        let metadata = TrackMetadata {
+            genre: Some("Audiobook".to_string()),
+            ..Default::default()
+        };
+        let insertable = TrackInsertable::try_from_track(&book, &metadata).unwrap();
+        let _ = insertable.try_insert_or_update(&conn).unwrap();
+
+        let music = test_path(Path::new("/music/Some Album/01.mp3"));
+        let insertable =
+            TrackInsertable::try_from_track(&music, &TrackMetadata::default()).unwrap();
+        let _ = insertable.try_insert_or_update(&conn).unwrap();
+
+        assert!(detect_audiobook(&conn, book.parent().unwrap()).unwrap());
+        assert!(!detect_audiobook(&conn, music.parent().unwrap()).unwrap());
+
+        // detected audiobooks are stored on first play
+        assert!(audiobook_for_track(&conn, &book).unwrap().is_some());
+        assert!(
+            get_audiobook(&conn, book.parent().unwrap())
+                .unwrap()
+                .is_some()
+        );
+        assert_eq!(audiobook_for_track(&conn, &music).unwrap(), None);
+
+        // unmarked audiobooks are not detected again
+        set_audiobook(&conn, book.parent().unwrap(), false).unwrap();
+        assert!(!is_audiobook_dir(&conn, book.parent().unwrap()).unwrap());
+        assert_eq!(audiobook_for_track(&conn, &book).unwrap(), None);
+    }
+}
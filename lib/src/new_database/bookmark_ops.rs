@@ -0,0 +1,210 @@
+//! Named positions within tracks, to find a place again in long tracks like audiobooks and podcast episodes.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use indoc::indoc;
+use rusqlite::{Connection, OptionalExtension, named_params};
+
+use crate::new_database::Integer;
+use crate::new_database::queue_ops::{columns_to_source, source_to_columns};
+use crate::player::playlist_helpers::PlaylistTrackSource;
+
+/// A stored bookmark, without the track it belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bookmark {
+    pub id: Integer,
+    /// The position in the track
+    pub position: Duration,
+    /// The name given by the user, may be empty
+    pub name: String,
+}
+
+/// Add a bookmark at `position` in `track`.
+///
+/// Returns the id of the new bookmark.
+pub fn insert_bookmark(
+    conn: &Connection,
+    track: &PlaylistTrackSource,
+    position: Duration,
+    name: &str,
+) -> Result<Integer> {
+    let (kind, source) = source_to_columns(track);
+    let position =
+        Integer::try_from(position.as_millis()).context("convert position to integer")?;
+    let now = chrono::Utc::now().to_rfc3339();
+
+    conn.execute(
+        indoc! {"
+            INSERT INTO bookmarks (kind, source, position, name, added_at)
+            VALUES (:kind, :source, :position, :name, :added_at);
+        "},
+        named_params! {
+            ":kind": kind,
+            ":source": source,
+            ":position": position,
+            ":name": name,
+            ":added_at": now,
+        },
+    )
+    .with_context(|| format!("Bookmark in \"{source}\""))?;
+
+    Ok(conn.last_insert_rowid())
+}
+
+/// Get all bookmarks of `track`, ordered by position.
+pub fn get_bookmarks(conn: &Connection, track: &PlaylistTrackSource) -> Result<Vec<Bookmark>> {
+    let (kind, source) = source_to_columns(track);
+    let mut stmt = conn.prepare(indoc! {"
+        SELECT id, position, name FROM bookmarks
+        WHERE kind=:kind AND source=:source
+        ORDER BY position ASC, id ASC;
+    "})?;
+
+    let bookmarks = stmt
+        .query_map(
+            named_params! {":kind": kind, ":source": source},
+            row_to_bookmark,
+        )?
+        .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+
+    Ok(bookmarks)
+}
+
+/// Get the bookmark with `id` and the track it belongs to.
+pub fn get_bookmark(
+    conn: &Connection,
+    id: Integer,
+) -> Result<Option<(PlaylistTrackSource, Bookmark)>> {
+    let found = conn
+        .query_row(
+            "SELECT id, position, name, kind, source FROM bookmarks WHERE id=:id;",
+            named_params! {":id": id},
+            |row| {
+                let kind: String = row.get(3)?;
+                let source: String = row.get(4)?;
+                Ok((kind, source, row_to_bookmark(row)?))
+            },
+        )
+        .optional()?;
+
+    let Some((kind, source, bookmark)) = found else {
+        return Ok(None);
+    };
+
+    Ok(Some((columns_to_source(&kind, source)?, bookmark)))
+}
+
+/// Remove the bookmark with `id`.
+pub fn delete_bookmark(conn: &Connection, id: Integer) -> Result<()> {
+    let affected = conn.execute(
+        "DELETE FROM bookmarks WHERE id=:id;",
+        named_params! {":id": id},
+    )?;
+
+    // delete would otherwise fail silently
+    if affected == 0 {
+        bail!("Bookmark {id} does not exist");
+    }
+
+    Ok(())
+}
+
+/// Change all bookmarks of local tracks at path `from` to `to`.
+///
+/// Returns the amount of changed bookmarks.
+pub fn rename_path(conn: &Connection, from: &str, to: &str) -> Result<usize> {
+    let affected = conn.execute(
+        "UPDATE bookmarks SET source=:to WHERE kind='path' AND source=:from;",
+        named_params! {":from": from, ":to": to},
+    )?;
+
+    Ok(affected)
+}
+
+/// Read a [`Bookmark`] from the first 3 columns `id, position, name`.
+fn row_to_bookmark(row: &rusqlite::Row<'_>) -> Result<Bookmark, rusqlite::Error> {
+    let position: Integer = row.get(1)?;
+
+    Ok(Bookmark {
+        id: row.get(0)?,
+        // negative positions are never stored
+        position: Duration::from_millis(u64::try_from(position).unwrap_or_default()),
+        name: row.get(2)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::new_database::bookmark_ops::{
+        Bookmark, delete_bookmark, get_bookmark, get_bookmarks, insert_bookmark, rename_path,
+    };
+    use crate::new_database::test_utils::gen_database;
+    use crate::player::playlist_helpers::PlaylistTrackSource;
+
+    #[test]
+    fn should_insert_and_delete_bookmarks() {
+        let db = gen_database();
+        let conn = db.get_connection();
+        let book = PlaylistTrackSource::Path("/music/book.mp3".to_string());
+        let episode = PlaylistTrackSource::PodcastUrl("http://example.com/1.mp3".to_string());
+
+        let second = insert_bookmark(&conn, &book, Duration::from_secs(600), "Chapter 2").unwrap();
+        let first = insert_bookmark(&conn, &book, Duration::from_secs(60), "").unwrap();
+        insert_bookmark(&conn, &episode, Duration::from_secs(30), "Intro").unwrap();
+
+        // ordered by position
+        assert_eq!(
+            get_bookmarks(&conn, &book).unwrap(),
+            [
+                Bookmark {
+                    id: first,
+                    position: Duration::from_secs(60),
+                    name: String::new(),
+                },
+                Bookmark {
+                    id: second,
+                    position: Duration::from_secs(600),
+                    name: "Chapter 2".to_string(),
+                }
+            ]
+        );
+        assert_eq!(
+            get_bookmark(&conn, second).unwrap().map(|v| v.0),
+            Some(book.clone())
+        );
+
+        delete_bookmark(&conn, first).unwrap();
+        assert_eq!(get_bookmarks(&conn, &book).unwrap().len(), 1);
+        assert_eq!(get_bookmark(&conn, first).unwrap(), None);
+        assert!(delete_bookmark(&conn, first).is_err());
+        assert_eq!(get_bookmarks(&conn, &episode).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn should_rename_bookmarked_paths() {
+        let db = gen_database();
+        let conn = db.get_connection();
+        let book = PlaylistTrackSource::Path("/music/book.mp3".to_string());
+
+        insert_bookmark(&conn, &book, Duration::from_secs(60), "").unwrap();
+        assert_eq!(
+            rename_path(&conn, "/music/book.mp3", "/music/Author/book.mp3").unwrap(),
+            1
+        );
+        assert!(get_bookmarks(&conn, &book).unwrap().is_empty());
+        assert_eq!(
+            get_bookmarks(
+                &conn,
+                &PlaylistTrackSource::Path("/music/Author/book.mp3".to_string())
+            )
+            .unwrap()
+            .len(),
+            1
+        );
+    }
+}
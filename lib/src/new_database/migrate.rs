@@ -1,8 +1,9 @@
-use anyhow::{Context, Result, bail};
-use rusqlite::{Connection, named_params};
+use rusqlite::{Connection, Result, named_params};
+
+use super::DatabaseError;
 
 /// The Current Database schema version this application is meant to run against
-pub(super) const DB_VERSION: u32 = 1;
+pub(super) const DB_VERSION: u32 = 13;
 
 /// Helper function to get the `user_version` with a single function call.
 #[inline]
@@ -10,7 +11,6 @@ fn get_user_version(conn: &Connection) -> Result<u32> {
     conn.query_row("SELECT user_version FROM pragma_user_version", [], |r| {
         r.get(0)
     })
-    .context("get pragma \"user_version\"")
 }
 
 /// Helper function to set the `user_version` with a single function call.
@@ -18,20 +18,29 @@ fn get_user_version(conn: &Connection) -> Result<u32> {
 /// Returns the passed version for re-use.
 #[inline]
 fn set_user_version(conn: &Connection, version: u32) -> Result<u32> {
-    conn.pragma_update(None, "user_version", version)
-        .context("update user_version error")?;
+    conn.pragma_update(None, "user_version", version)?;
 
     Ok(version)
 }
 
+/// Apply the migration `sql` and set the `user_version` to `version`.
+///
+/// Returns the passed version for re-use.
+fn apply_migration(conn: &Connection, version: u32, sql: &str) -> Result<u32, DatabaseError> {
+    conn.execute_batch(sql)
+        .and_then(|()| set_user_version(conn, version))
+        .map_err(|source| DatabaseError::Migration { version, source })
+}
+
 /// Check and update the database to be at [`DB_VERSION`].
-pub(super) fn migrate(conn: &Connection) -> Result<()> {
-    let user_version: u32 = get_user_version(conn)?;
+pub(super) fn migrate(conn: &Connection) -> Result<(), DatabaseError> {
+    let user_version: u32 = get_user_version(conn).map_err(DatabaseError::Version)?;
 
     if user_version > DB_VERSION {
-        bail!(
-            "Expected Database version to be lower or equal to {DB_VERSION}, found {user_version}!"
-        );
+        return Err(DatabaseError::NewerVersion {
+            expected: DB_VERSION,
+            found: user_version,
+        });
     }
 
     // only execute migrations if not already done so
@@ -44,18 +53,68 @@ pub(super) fn migrate(conn: &Connection) -> Result<()> {
 
 /// Apply migrations to be at [`DB_VERSION`].
 #[allow(unused_assignments)] // for future possible migrations
-fn apply_migrations(conn: &Connection, mut user_version: u32) -> Result<()> {
+fn apply_migrations(conn: &Connection, mut user_version: u32) -> Result<(), DatabaseError> {
     if user_version == 0 {
         // Version 2 is the base version, so there are basically no migrations, only creations
-        conn.execute_batch(include_str!("./migrations/001.sql"))
-            .context("Database version 1 could not be created")?;
-        user_version = set_user_version(conn, 1)?;
+        user_version = apply_migration(conn, 1, include_str!("./migrations/001.sql"))?;
+
+        set_db_created_at(conn)
+            .and_then(|()| set_db_created_with(conn))
+            .map_err(|source| DatabaseError::Migration { version: 1, source })?;
+    }
+
+    if user_version == 1 {
+        user_version = apply_migration(conn, 2, include_str!("./migrations/002.sql"))?;
+    }
 
-        set_db_created_at(conn)?;
-        set_db_created_with(conn)?;
+    if user_version == 2 {
+        user_version = apply_migration(conn, 3, include_str!("./migrations/003.sql"))?;
     }
 
-    set_last_updated_at(conn)?;
+    if user_version == 3 {
+        user_version = apply_migration(conn, 4, include_str!("./migrations/004.sql"))?;
+    }
+
+    if user_version == 4 {
+        user_version = apply_migration(conn, 5, include_str!("./migrations/005.sql"))?;
+    }
+
+    if user_version == 5 {
+        user_version = apply_migration(conn, 6, include_str!("./migrations/006.sql"))?;
+    }
+
+    if user_version == 6 {
+        user_version = apply_migration(conn, 7, include_str!("./migrations/007.sql"))?;
+    }
+
+    if user_version == 7 {
+        user_version = apply_migration(conn, 8, include_str!("./migrations/008.sql"))?;
+    }
+
+    if user_version == 8 {
+        user_version = apply_migration(conn, 9, include_str!("./migrations/009.sql"))?;
+    }
+
+    if user_version == 9 {
+        user_version = apply_migration(conn, 10, include_str!("./migrations/010.sql"))?;
+    }
+
+    if user_version == 10 {
+        user_version = apply_migration(conn, 11, include_str!("./migrations/011.sql"))?;
+    }
+
+    if user_version == 11 {
+        user_version = apply_migration(conn, 12, include_str!("./migrations/012.sql"))?;
+    }
+
+    if user_version == 12 {
+        user_version = apply_migration(conn, 13, include_str!("./migrations/013.sql"))?;
+    }
+
+    set_last_updated_at(conn).map_err(|source| DatabaseError::Migration {
+        version: user_version,
+        source,
+    })?;
 
     Ok(())
 }
@@ -106,7 +165,8 @@ fn set_db_created_with(conn: &Connection) -> Result<()> {
 mod tests {
     use pretty_assertions::assert_eq;
 
-    use crate::new_database::migrate::{DB_VERSION, get_user_version, migrate};
+    use crate::new_database::DatabaseError;
+    use crate::new_database::migrate::{DB_VERSION, get_user_version, migrate, set_user_version};
 
     use super::super::test_utils::gen_database_raw;
 
@@ -140,6 +200,21 @@ mod tests {
                 "tracks_artists",
                 "albums",
                 "albums_artists",
+                "smart_playlists",
+                "smart_playlist_rules",
+                "radio_favorites",
+                "queues",
+                "queue_active",
+                "queue_tracks",
+                "bookmarks",
+                "audiobooks",
+                // the fts5 table and its shadow tables
+                "tracks_fts",
+                "tracks_fts_config",
+                "tracks_fts_content",
+                "tracks_fts_data",
+                "tracks_fts_docsize",
+                "tracks_fts_idx",
             ];
 
             #[allow(clippy::stable_sort_primitive)]
@@ -149,4 +224,20 @@ mod tests {
 
         assert_eq!(&all_tracks, &expected);
     }
+
+    #[test]
+    fn should_reject_newer_version() {
+        let conn = gen_database_raw();
+
+        set_user_version(&conn, DB_VERSION + 1).unwrap();
+
+        let err = migrate(&conn).unwrap_err();
+        assert!(matches!(
+            err,
+            DatabaseError::NewerVersion {
+                expected: DB_VERSION,
+                found
+            } if found == DB_VERSION + 1
+        ));
+    }
 }
@@ -0,0 +1,6 @@
+--- SECTION: local music files
+
+-- the date the file was last found missing, NULL if the file exists
+-- missing tracks are kept (but hidden) instead of being deleted, so that their data survives
+-- temporarily unavailable files, like on a unmounted network drive
+ALTER TABLE tracks ADD COLUMN missing_since DATE;
@@ -0,0 +1,30 @@
+--- SECTION: local music files
+
+-- the date the file was last started playing, NULL if it was never played
+ALTER TABLE tracks ADD COLUMN last_played DATE;
+
+--- SECTION: smart playlists
+
+-- the table for all rule-based playlists, which are evaluated against the tracks on demand
+CREATE TABLE IF NOT EXISTS smart_playlists(
+    id INTEGER PRIMARY KEY,
+    -- the name is used as a identifier for the user
+    name TEXT NOT NULL UNIQUE,
+    -- whether all rules need to match ("AND"), or just any of them ("OR")
+    match_all BOOLEAN NOT NULL,
+    -- the date this playlist was added to the database
+    added_at DATE
+);
+
+-- the rules of a smart playlist
+-- entry will get deleted if the smart playlist is dropped
+CREATE TABLE IF NOT EXISTS smart_playlist_rules(
+    id INTEGER PRIMARY KEY,
+    playlist INTEGER NOT NULL REFERENCES smart_playlists(id) ON DELETE CASCADE,
+    -- the field to match against, see "RuleField" in code
+    field TEXT NOT NULL,
+    -- the comparison, see "RuleOperator" in code
+    operator TEXT NOT NULL,
+    -- the value to compare with, interpretation depends on the field
+    value TEXT NOT NULL
+);
@@ -0,0 +1,93 @@
+--- SECTION: local music files
+
+-- full-text index over the searchable fields of all tracks, the rowid is the "tracks.id"
+-- the content is kept in sync by the triggers below, so it should never be modified directly
+CREATE VIRTUAL TABLE IF NOT EXISTS tracks_fts USING fts5(
+    title,
+    artist,
+    album,
+    genre,
+    -- the file's name, for tracks without metadata
+    file,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+-- index all already existing tracks
+INSERT INTO tracks_fts (rowid, title, artist, album, genre, file)
+    SELECT tracks.id, tracks_metadata.title, tracks_metadata.artist_display, albums.title, tracks_metadata.genre, tracks.file_stem
+    FROM tracks
+    LEFT JOIN tracks_metadata ON tracks.id = tracks_metadata.track
+    LEFT JOIN albums ON tracks.album = albums.id;
+
+-- the triggers all re-index the whole row, as the indexed fields come from multiple tables
+
+CREATE TRIGGER IF NOT EXISTS tracks_fts_tracks_insert AFTER INSERT ON tracks BEGIN
+    INSERT INTO tracks_fts (rowid, title, artist, album, genre, file)
+        SELECT tracks.id, tracks_metadata.title, tracks_metadata.artist_display, albums.title, tracks_metadata.genre, tracks.file_stem
+        FROM tracks
+        LEFT JOIN tracks_metadata ON tracks.id = tracks_metadata.track
+        LEFT JOIN albums ON tracks.album = albums.id
+        WHERE tracks.id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS tracks_fts_tracks_update AFTER UPDATE OF file_stem, album ON tracks BEGIN
+    DELETE FROM tracks_fts WHERE rowid = OLD.id;
+    INSERT INTO tracks_fts (rowid, title, artist, album, genre, file)
+        SELECT tracks.id, tracks_metadata.title, tracks_metadata.artist_display, albums.title, tracks_metadata.genre, tracks.file_stem
+        FROM tracks
+        LEFT JOIN tracks_metadata ON tracks.id = tracks_metadata.track
+        LEFT JOIN albums ON tracks.album = albums.id
+        WHERE tracks.id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS tracks_fts_tracks_delete AFTER DELETE ON tracks BEGIN
+    DELETE FROM tracks_fts WHERE rowid = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS tracks_fts_metadata_insert AFTER INSERT ON tracks_metadata BEGIN
+    DELETE FROM tracks_fts WHERE rowid = NEW.track;
+    INSERT INTO tracks_fts (rowid, title, artist, album, genre, file)
+        SELECT tracks.id, tracks_metadata.title, tracks_metadata.artist_display, albums.title, tracks_metadata.genre, tracks.file_stem
+        FROM tracks
+        LEFT JOIN tracks_metadata ON tracks.id = tracks_metadata.track
+        LEFT JOIN albums ON tracks.album = albums.id
+        WHERE tracks.id = NEW.track;
+END;
+
+CREATE TRIGGER IF NOT EXISTS tracks_fts_metadata_update AFTER UPDATE ON tracks_metadata BEGIN
+    DELETE FROM tracks_fts WHERE rowid = OLD.track;
+    INSERT INTO tracks_fts (rowid, title, artist, album, genre, file)
+        SELECT tracks.id, tracks_metadata.title, tracks_metadata.artist_display, albums.title, tracks_metadata.genre, tracks.file_stem
+        FROM tracks
+        LEFT JOIN tracks_metadata ON tracks.id = tracks_metadata.track
+        LEFT JOIN albums ON tracks.album = albums.id
+        WHERE tracks.id = NEW.track;
+END;
+
+CREATE TRIGGER IF NOT EXISTS tracks_fts_metadata_delete AFTER DELETE ON tracks_metadata BEGIN
+    DELETE FROM tracks_fts WHERE rowid = OLD.track;
+    INSERT INTO tracks_fts (rowid, title, artist, album, genre, file)
+        SELECT tracks.id, NULL, NULL, albums.title, NULL, tracks.file_stem
+        FROM tracks
+        LEFT JOIN albums ON tracks.album = albums.id
+        WHERE tracks.id = OLD.track;
+END;
+
+CREATE TRIGGER IF NOT EXISTS tracks_fts_albums_update AFTER UPDATE OF title ON albums BEGIN
+    DELETE FROM tracks_fts WHERE rowid IN (SELECT id FROM tracks WHERE album = NEW.id);
+    INSERT INTO tracks_fts (rowid, title, artist, album, genre, file)
+        SELECT tracks.id, tracks_metadata.title, tracks_metadata.artist_display, albums.title, tracks_metadata.genre, tracks.file_stem
+        FROM tracks
+        LEFT JOIN tracks_metadata ON tracks.id = tracks_metadata.track
+        LEFT JOIN albums ON tracks.album = albums.id
+        WHERE tracks.album = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS tracks_fts_albums_delete AFTER DELETE ON albums BEGIN
+    DELETE FROM tracks_fts WHERE rowid IN (SELECT id FROM tracks WHERE album = OLD.id);
+    INSERT INTO tracks_fts (rowid, title, artist, album, genre, file)
+        SELECT tracks.id, tracks_metadata.title, tracks_metadata.artist_display, NULL, tracks_metadata.genre, tracks.file_stem
+        FROM tracks
+        LEFT JOIN tracks_metadata ON tracks.id = tracks_metadata.track
+        WHERE tracks.album = OLD.id;
+END;
@@ -0,0 +1,19 @@
+--- SECTION: internet radio
+
+-- stations from radio-browser.info the user marked as favorite
+CREATE TABLE IF NOT EXISTS radio_favorites(
+    id INTEGER PRIMARY KEY,
+    -- the "stationuuid" on radio-browser.info
+    uuid TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    -- the stream url
+    url TEXT NOT NULL,
+    -- comma-separated tags
+    tags TEXT NOT NULL,
+    country TEXT NOT NULL,
+    codec TEXT NOT NULL,
+    -- bitrate in kbit/s, 0 if unknown
+    bitrate INTEGER NOT NULL,
+    -- the time the station was marked as favorite
+    added_at DATE NOT NULL
+);
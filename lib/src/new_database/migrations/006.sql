@@ -0,0 +1,21 @@
+--- SECTION: play queue
+
+-- the tracks of the play queue of the server, to restore it across restarts
+-- there is only one queue, so all entries belong to it
+CREATE TABLE IF NOT EXISTS queue_tracks(
+    -- the index of the track in the queue
+    position INTEGER PRIMARY KEY,
+    -- the type of source, see "PlaylistTrackSource" in code
+    kind TEXT NOT NULL,
+    -- the path or url of the track
+    source TEXT NOT NULL
+);
+
+-- the state of the play queue, this table only ever has one row
+CREATE TABLE IF NOT EXISTS queue_state(
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    -- the index into "queue_tracks" of the current track
+    current_index INTEGER NOT NULL,
+    -- the position in the current track in milliseconds, NULL if unknown
+    position INTEGER
+);
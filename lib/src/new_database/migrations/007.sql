@@ -0,0 +1,6 @@
+--- SECTION: local music files
+
+-- the user's rating of the track from 1 to 5, 0 if not rated
+ALTER TABLE tracks ADD COLUMN rating INTEGER NOT NULL DEFAULT 0;
+-- whether the user marked the track as favorite
+ALTER TABLE tracks ADD COLUMN favorite BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,6 @@
+--- SECTION: local music files
+
+-- how often the track was played until the end
+ALTER TABLE tracks ADD COLUMN play_count INTEGER NOT NULL DEFAULT 0;
+-- how often the track was changed away from before it ended
+ALTER TABLE tracks ADD COLUMN skip_count INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,44 @@
+--- SECTION: play queue
+
+-- the named play queues of the server, like "Main" or "Podcast backlog"
+CREATE TABLE IF NOT EXISTS queues(
+    id INTEGER PRIMARY KEY,
+    -- the name shown to the user
+    name TEXT NOT NULL UNIQUE,
+    -- the index into the tracks of this queue of the current track
+    current_index INTEGER NOT NULL DEFAULT 0,
+    -- the position in the current track in milliseconds, NULL if unknown
+    position INTEGER
+);
+
+-- the queue the server currently plays, this table only ever has one row
+CREATE TABLE IF NOT EXISTS queue_active(
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    queue INTEGER NOT NULL REFERENCES queues(id)
+);
+
+-- the tracks of all play queues
+CREATE TABLE IF NOT EXISTS queue_tracks_new(
+    -- the queue the track belongs to
+    queue INTEGER NOT NULL REFERENCES queues(id) ON DELETE CASCADE,
+    -- the index of the track in the queue
+    position INTEGER NOT NULL,
+    -- the type of source, see "PlaylistTrackSource" in code
+    kind TEXT NOT NULL,
+    -- the path or url of the track
+    source TEXT NOT NULL,
+    PRIMARY KEY (queue, position)
+);
+
+-- the only queue of previous versions becomes the active "Main" queue
+INSERT INTO queues (name, current_index, position)
+    SELECT 'Main', current_index, position FROM queue_state WHERE id = 0;
+INSERT INTO queue_active (id, queue)
+    SELECT 0, id FROM queues WHERE name = 'Main';
+INSERT INTO queue_tracks_new (queue, position, kind, source)
+    SELECT queues.id, queue_tracks.position, queue_tracks.kind, queue_tracks.source
+    FROM queue_tracks, queues WHERE queues.name = 'Main';
+
+DROP TABLE queue_tracks;
+DROP TABLE queue_state;
+ALTER TABLE queue_tracks_new RENAME TO queue_tracks;
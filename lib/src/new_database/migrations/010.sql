@@ -0,0 +1,4 @@
+--- SECTION: local music files
+
+-- the playback speed to always play the track at, in tenths like "player.speed", NULL to use the global speed
+ALTER TABLE tracks ADD COLUMN speed INTEGER;
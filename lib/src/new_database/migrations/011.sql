@@ -0,0 +1,4 @@
+--- SECTION: local music files
+
+-- whether the track is left out of shuffle and auto-queue, like skits and intros, but can still be played manually
+ALTER TABLE tracks ADD COLUMN no_autoplay BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,18 @@
+--- SECTION: bookmarks
+
+-- named positions within tracks, mostly for long tracks like audiobooks and podcast episodes
+CREATE TABLE IF NOT EXISTS bookmarks(
+    id INTEGER PRIMARY KEY,
+    -- the type of source, see "PlaylistTrackSource" in code
+    kind TEXT NOT NULL,
+    -- the path or url of the track
+    source TEXT NOT NULL,
+    -- the position in the track in milliseconds
+    position INTEGER NOT NULL,
+    -- the name given by the user, may be empty
+    name TEXT NOT NULL DEFAULT '',
+    -- the time the bookmark was added
+    added_at DATE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS bookmarks_source ON bookmarks(source);
@@ -0,0 +1,18 @@
+--- SECTION: audiobooks
+
+-- directories whose tracks are played as audiobooks, see "audiobook_ops" in code
+CREATE TABLE IF NOT EXISTS audiobooks(
+    id INTEGER PRIMARY KEY,
+    -- the directory of the audiobook, like "tracks.file_dir", sub-directories are part of the audiobook
+    dir TEXT NOT NULL UNIQUE,
+    -- whether the directory is played as a audiobook, false to not detect it as one again
+    enabled BOOLEAN NOT NULL,
+    -- the playback speed in tenths like "player.speed", NULL to use the global speed
+    speed INTEGER,
+    -- whether silence should be skipped while playing the audiobook
+    skip_silence BOOLEAN NOT NULL DEFAULT FALSE,
+    -- the path of the track the audiobook was last played at
+    last_track TEXT,
+    -- the position in "last_track" in seconds, like "tracks.last_position"
+    last_position INTEGER
+);
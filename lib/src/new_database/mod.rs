@@ -1,6 +1,12 @@
 #![allow(clippy::unnecessary_debug_formatting)] // for logging we want all paths's characters to be escaped
 
-use std::{fmt::Debug, path::Path, sync::Arc};
+use std::{
+    fmt::{Debug, Display},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::{Arc, mpsc},
+    time::Duration,
+};
 
 use anyhow::{Context, Result};
 use parking_lot::{Mutex, MutexGuard};
@@ -14,7 +20,8 @@ use crate::{
     new_database::{
         album_ops::delete_all_unreferenced_albums, artist_ops::delete_all_unreferenced_artists,
     },
-    track::{MetadataOptions, parse_metadata_from_file},
+    taskpool::TaskPool,
+    track::{DurationFmtShort, MetadataOptions, TrackMetadata, cue, parse_metadata_from_file},
     utils::{filetype_supported, get_app_new_database_path},
 };
 
@@ -27,9 +34,112 @@ mod album_insert;
 pub mod album_ops;
 mod artist_insert;
 pub mod artist_ops;
+pub mod audiobook_ops;
+pub mod bookmark_ops;
 mod migrate;
-mod track_insert;
+pub mod queue_ops;
+pub mod radio_ops;
+pub mod smart_playlist_ops;
+pub(crate) mod track_insert;
 pub mod track_ops;
+pub mod watcher;
+
+/// Errors that can happen when opening the library or podcast database.
+#[derive(Debug, thiserror::Error)]
+#[allow(clippy::module_name_repetitions)]
+pub enum DatabaseError {
+    /// The default database path could not be determined
+    #[error("Could not get database path")]
+    Path(#[source] std::io::Error),
+    /// The directory to store the database in could not be created
+    #[error("Could not create database directory \"{}\"", .0.display())]
+    CreateDir(PathBuf, #[source] std::io::Error),
+    /// The database file could not be opened or created
+    #[error("Could not open or create database at \"{}\"", .0.display())]
+    Open(PathBuf, #[source] rusqlite::Error),
+    /// The database was created by a newer version of the application
+    #[error("Expected Database version to be lower or equal to {expected}, found {found}!")]
+    NewerVersion { expected: u32, found: u32 },
+    /// The schema version of the database could not be read
+    #[error("Could not read the database version")]
+    Version(#[source] rusqlite::Error),
+    /// Migrating the database to `version` failed
+    #[error("Database migration to version {version}")]
+    Migration {
+        version: u32,
+        #[source]
+        source: rusqlite::Error,
+    },
+}
+
+impl DatabaseError {
+    /// Create a [`DatabaseError::Path`] from the error of [`get_app_data_path`](crate::utils::get_app_data_path),
+    /// which is either a io error or the platform not having a data directory.
+    pub(crate) fn path(err: anyhow::Error) -> Self {
+        let source = err.downcast::<std::io::Error>().unwrap_or_else(|err| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, format!("{err:#}"))
+        });
+
+        Self::Path(source)
+    }
+}
+
+/// Aggregated information about the tracks in a directory, see [`Database::dir_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DirStats {
+    /// The amount of tracks, including sub-directories
+    pub tracks: usize,
+    /// The sum of all known track durations
+    pub duration: Duration,
+    /// The sum of all track file sizes in bytes
+    pub size: u64,
+}
+
+impl Display for DirStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} tracks, {}, {}",
+            self.tracks,
+            DurationFmtShort(self.duration),
+            bytesize::ByteSize::b(self.size)
+        )
+    }
+}
+
+/// How many tracks are inserted in a single transaction while scanning.
+const SCAN_BATCH_SIZE: usize = 100;
+
+/// How many processed tracks there have to be at least between two [`ScanProgress`] reports.
+const SCAN_PROGRESS_STEP: usize = 50;
+
+/// Progress of a scan started with [`Database::scan_path`].
+///
+/// Everything is counted in tracks, so a cue sheet counts once for each of its tracks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ScanProgress {
+    /// The amount of tracks found so far, which is the total once `finished`
+    pub seen: usize,
+    /// Tracks whose metadata was read and stored
+    pub parsed: usize,
+    /// Tracks which are already known and not read again
+    pub skipped: usize,
+    /// Tracks which could not be read or stored
+    pub errors: usize,
+    /// Whether all tracks were found and processed
+    pub finished: bool,
+}
+
+impl ScanProgress {
+    /// Get the amount of found tracks which are done, regardless of the outcome.
+    #[must_use]
+    pub fn processed(&self) -> usize {
+        self.parsed + self.skipped + self.errors
+    }
+}
+
+/// Receives the [`ScanProgress`] of a scan, see [`Database::scan_path`].
+pub type ScanProgressFn = Box<dyn Fn(ScanProgress) + Send>;
 
 #[allow(clippy::doc_markdown)]
 /// The SQLite Database interface.
@@ -57,8 +167,8 @@ impl Database {
     ///
     /// - if database creation fails
     /// - if database migration fails
-    pub fn new(path: &Path) -> Result<Self> {
-        let conn = Connection::open(path).context("open/create database")?;
+    pub fn new(path: &Path) -> Result<Self, DatabaseError> {
+        let conn = Connection::open(path).map_err(|err| DatabaseError::Open(path.into(), err))?;
 
         Self::new_from_connection(conn)
     }
@@ -68,8 +178,8 @@ impl Database {
     /// # Panics
     ///
     /// see [`new`](Self::new).
-    pub fn new_default_path() -> Result<Self> {
-        Self::new(&get_app_new_database_path()?)
+    pub fn new_default_path() -> Result<Self, DatabaseError> {
+        Self::new(&get_app_new_database_path().map_err(DatabaseError::path)?)
     }
 
     /// Get a lock to the underlying connection to start operations.
@@ -78,8 +188,8 @@ impl Database {
     }
 
     /// Prepare the given Connection for usage.
-    fn new_from_connection(conn: Connection) -> Result<Self> {
-        migrate::migrate(&conn).context("Database migration")?;
+    fn new_from_connection(conn: Connection) -> Result<Self, DatabaseError> {
+        migrate::migrate(&conn)?;
 
         let conn = Arc::new(Mutex::new(conn));
         // for now limit to one worker at a time
@@ -91,12 +201,16 @@ impl Database {
     ///
     /// If `replace_metadata` is `false` then paths that already exist in the database will not be updated.
     ///
+    /// The metadata of multiple files is read in parallel, `on_progress` is called with the progress along the way
+    /// and once more when the scan is finished.
+    ///
     /// Waits for a permit before starting another worker.
     pub fn scan_path(
         &self,
         path: &Path,
         config: &ServerOverlay,
         replace_metadata: bool,
+        on_progress: Option<ScanProgressFn>,
     ) -> Result<()> {
         let path = path
             .canonicalize()
@@ -114,14 +228,39 @@ impl Database {
                 .filter_map(Result::ok)
                 // only process files which we support
                 .filter(|v| v.file_type().is_file())
-                .filter(|v| filetype_supported(v.path()))
+                .filter(|v| filetype_supported(v.path()) || cue::is_cue_sheet(v.path()))
+                // those are added through the tracks of their cue sheet
+                .filter(|v| !cue::has_cue_sheet(v.path()))
         };
 
-        let separators = config.settings.metadata.artist_separators.clone();
+        let separators: Arc<[String]> = config.settings.metadata.artist_separators.clone().into();
+        let removable_dirs = config.settings.player.removable_dirs.clone();
 
         self.spawn_worker(move |db| {
-            let separators: Vec<&str> = separators.iter().map(String::as_str).collect();
-            Self::process_iter(walker, &db, &path, replace_metadata, &separators);
+            let progress = Self::process_iter(
+                walker,
+                &db,
+                &path,
+                replace_metadata,
+                &separators,
+                on_progress.as_deref(),
+            );
+
+            let unavailable = unavailable_dirs(&removable_dirs);
+            match Self::update_missing_tracks(&db, &path, &unavailable) {
+                Ok((marked, restored)) => {
+                    info!("Marked {marked} tracks as missing and restored {restored} in {path:#?}");
+                }
+                Err(err) => warn!("Error updating missing tracks in {path:#?}: {err:#?}"),
+            }
+
+            // only report being finished once the missing tracks are up-to-date too
+            if let Some(on_progress) = on_progress {
+                on_progress(ScanProgress {
+                    finished: true,
+                    ..progress
+                });
+            }
         });
 
         Ok(())
@@ -156,79 +295,193 @@ impl Database {
 
     /// The actual function to walk the iterator of files for [`Self::scan_path`].
     ///
+    /// The metadata is read in parallel while walking, and stored in batches.
+    /// The progress is only reported to `on_progress` while scanning, the returned final progress is not.
+    ///
     /// Expects `path` to be absolute.
     fn process_iter(
         walker: impl Iterator<Item = DirEntry>,
         db: &Self,
         path: &Path,
         replace_metadata: bool,
-        separators: &[&str],
-    ) {
+        separators: &Arc<[String]>,
+        on_progress: Option<&(dyn Fn(ScanProgress) + Send)>,
+    ) -> ScanProgress {
         // keep the permit for the entirety of this function
         info!("Scanning {path:#?}");
 
-        let mut created_updated: usize = 0;
+        let pool = TaskPool::new(std::thread::available_parallelism().map_or(4, NonZeroUsize::get));
+        let (tx, rx) = mpsc::channel();
+        let mut scan = ScanRun {
+            db,
+            progress: ScanProgress::default(),
+            reported: 0,
+            batch: Vec::with_capacity(SCAN_BATCH_SIZE),
+            on_progress,
+        };
 
         // assumptions in this function:
         // - "walker" iterator is already filtered to only contain files
-        // - "walker" iterator is already filtered to only our supported file types
+        // - "walker" iterator is already filtered to only our supported file types and cue sheets
         for record in walker {
             let path = record.path();
 
-            // skip existing paths, if no full scan is requested
-            if !replace_metadata {
-                match track_ops::track_exists(&db.conn.lock(), path) {
-                    Ok(true) => continue,
+            if !cue::is_cue_sheet(path) {
+                scan.read_track(&pool, &tx, path.to_path_buf(), replace_metadata, separators);
+            } else {
+                // each track of a cue sheet is stored as its own track
+                match cue::track_paths(path) {
+                    Ok(track_paths) => {
+                        for track_path in track_paths {
+                            scan.read_track(&pool, &tx, track_path, replace_metadata, separators);
+                        }
+                    }
                     Err(err) => {
-                        warn!("Error checking if {path:#?} exists: {err:#?}");
-                        continue;
+                        warn!("Error reading cue sheet {path:#?}: {err:#?}");
+                        scan.progress.seen += 1;
+                        scan.progress.errors += 1;
                     }
-                    Ok(false) => (),
                 }
             }
 
-            let track_metadata = match parse_metadata_from_file(
-                path,
-                MetadataOptions {
-                    album: true,
-                    album_artist: true,
-                    album_artists: true,
-                    artist: true,
-                    artists: true,
-                    artist_separators: separators,
-                    title: true,
-                    duration: true,
-                    genre: true,
-                    ..Default::default()
-                },
-            ) {
-                Ok(v) => v,
-                Err(err) => {
-                    warn!("Error scanning path {path:#?}: {err:#?}");
-                    continue;
-                }
-            };
+            // store what is read already, instead of letting it pile up until the walk is done
+            scan.store(rx.try_iter());
+        }
 
-            let db_track = match TrackInsertable::try_from_track(path, &track_metadata) {
-                Ok(v) => v,
-                Err(err) => {
-                    warn!("Error converting to database track {path:#?}: {err:#?}");
-                    continue;
-                }
-            };
+        // every task has its own sender, so the channel is only closed once all tasks are done
+        drop(tx);
+        scan.store(rx.iter());
+        scan.flush();
 
-            let _id = match db_track.try_insert_or_update(&db.conn.lock()) {
-                Ok(v) => v,
+        let progress = scan.progress;
+        info!(
+            "Finished Scanning {path:#?} with {} created or updated and {} errors",
+            progress.parsed, progress.errors
+        );
+
+        progress
+    }
+
+    /// Read the metadata of the track at `path`, with everything stored in the database.
+    fn read_metadata(path: &Path, separators: &[&str]) -> Result<TrackMetadata> {
+        parse_metadata_from_file(
+            path,
+            MetadataOptions {
+                album: true,
+                album_artist: true,
+                album_artists: true,
+                artist: true,
+                artists: true,
+                artist_separators: separators,
+                title: true,
+                duration: true,
+                genre: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Read the metadata of the single track at `path` and insert or update it, for changes of single files.
+    ///
+    /// Returns whether the track was created or updated.
+    fn process_file(db: &Self, path: &Path, replace_metadata: bool, separators: &[&str]) -> bool {
+        // skip existing paths, if no full scan is requested
+        if !replace_metadata {
+            match track_ops::track_exists(&db.conn.lock(), path) {
+                Ok(true) => return false,
                 Err(err) => {
-                    warn!("Error inserting or updating {path:#?}: {err:#?}");
-                    continue;
+                    warn!("Error checking if {path:#?} exists: {err:#?}");
+                    return false;
                 }
-            };
+                Ok(false) => (),
+            }
+        }
+
+        let track_metadata = match Self::read_metadata(path, separators) {
+            Ok(v) => v,
+            Err(err) => {
+                warn!("Error scanning path {path:#?}: {err:#?}");
+                return false;
+            }
+        };
 
-            created_updated += 1;
+        let db_track = match TrackInsertable::try_from_track(path, &track_metadata) {
+            Ok(v) => v,
+            Err(err) => {
+                warn!("Error converting to database track {path:#?}: {err:#?}");
+                return false;
+            }
+        };
+
+        if let Err(err) = db_track.try_insert_or_update(&db.conn.lock()) {
+            warn!("Error inserting or updating {path:#?}: {err:#?}");
+            return false;
         }
 
-        info!("Finished Scanning {path:#?} with {created_updated} created or updated");
+        true
+    }
+
+    /// Mark tracks below `path` whose file does not exist anymore as missing, and restore missing tracks whose file exists again.
+    ///
+    /// Tracks are only marked instead of deleted, so that their data is not lost if the files only are temporarily unavailable.
+    ///
+    /// Tracks in `unavailable` directories are left as-is, as their state cannot be determined currently.
+    ///
+    /// Returns `(marked, restored)`.
+    fn update_missing_tracks(
+        db: &Self,
+        path: &Path,
+        unavailable: &[PathBuf],
+    ) -> Result<(usize, usize)> {
+        if !unavailable.is_empty() {
+            info!("Skipping missing track check for unavailable directories {unavailable:#?}");
+        }
+
+        let tracks = track_ops::get_track_paths_below(&db.conn.lock(), path)?;
+
+        // check the filesystem without holding the lock, as this may take a while
+        let changed: Vec<_> = tracks
+            .into_iter()
+            .filter(|v| !unavailable.iter().any(|dir| v.path.starts_with(dir)))
+            // cue sheet tracks exist as long as their cue sheet does
+            .filter(|v| cue::file_path(&v.path).exists() == v.missing)
+            .collect();
+
+        let mut marked = 0;
+        let mut restored = 0;
+        let conn = db.conn.lock();
+        for track in changed {
+            track_ops::set_track_missing(&conn, track.id, !track.missing)?;
+            if track.missing {
+                restored += 1;
+            } else {
+                marked += 1;
+            }
+        }
+
+        Ok((marked, restored))
+    }
+
+    /// Get aggregated information about all known tracks in `dir` and its sub-directories.
+    ///
+    /// The file sizes are read from the filesystem, files that cannot be read are not counted towards it.
+    pub fn dir_stats(&self, dir: &Path) -> Result<DirStats> {
+        let tracks = track_ops::get_track_durations_below(&self.conn.lock(), dir)?;
+
+        // read the file sizes without holding the lock, as this may take a while
+        let stats = tracks.iter().fold(
+            DirStats {
+                tracks: tracks.len(),
+                ..Default::default()
+            },
+            |acc, track| DirStats {
+                duration: acc.duration + track.duration.unwrap_or_default(),
+                size: acc.size + std::fs::metadata(&track.path).map_or(0, |v| v.len()),
+                ..acc
+            },
+        );
+
+        Ok(stats)
     }
 
     /// Spawn a worker to cleanup the database.
@@ -268,6 +521,162 @@ impl Database {
     }
 }
 
+/// The state of a single [`Database::process_iter`].
+struct ScanRun<'a> {
+    db: &'a Database,
+    progress: ScanProgress,
+    /// The processed count of the last report
+    reported: usize,
+    /// Read tracks waiting to be stored
+    batch: Vec<(PathBuf, TrackMetadata)>,
+    on_progress: Option<&'a (dyn Fn(ScanProgress) + Send)>,
+}
+
+/// The result of reading a single track in a [`TaskPool`] task.
+type ReadResult = (PathBuf, Result<TrackMetadata>);
+
+impl ScanRun<'_> {
+    /// Read the metadata of the track at `path` in `pool`, the result is sent to `tx`.
+    fn read_track(
+        &mut self,
+        pool: &TaskPool,
+        tx: &mpsc::Sender<ReadResult>,
+        path: PathBuf,
+        replace_metadata: bool,
+        separators: &Arc<[String]>,
+    ) {
+        self.progress.seen += 1;
+
+        // skip existing paths, if no full scan is requested
+        if !replace_metadata {
+            match track_ops::track_exists(&self.db.conn.lock(), &path) {
+                Ok(true) => {
+                    self.progress.skipped += 1;
+                    self.report();
+                    return;
+                }
+                Err(err) => {
+                    warn!("Error checking if {path:#?} exists: {err:#?}");
+                    self.progress.errors += 1;
+                    self.report();
+                    return;
+                }
+                Ok(false) => (),
+            }
+        }
+
+        let tx = tx.clone();
+        let separators = separators.clone();
+        pool.execute(async move {
+            // reading tags is blocking file IO
+            let res = tokio::task::spawn_blocking(move || {
+                let separators: Vec<&str> = separators.iter().map(String::as_str).collect();
+                let metadata = Database::read_metadata(&path, &separators);
+                (path, metadata)
+            })
+            .await;
+
+            match res {
+                Ok(res) => {
+                    let _ = tx.send(res);
+                }
+                Err(err) => error!("Reading metadata panicked: {err:#?}"),
+            }
+        });
+    }
+
+    /// Queue all read `results` to be stored, storing a batch once it is full.
+    fn store(&mut self, results: impl Iterator<Item = ReadResult>) {
+        for (path, metadata) in results {
+            match metadata {
+                Ok(metadata) => self.batch.push((path, metadata)),
+                Err(err) => {
+                    warn!("Error scanning path {path:#?}: {err:#?}");
+                    self.progress.errors += 1;
+                }
+            }
+
+            if self.batch.len() >= SCAN_BATCH_SIZE {
+                self.flush();
+            }
+        }
+        self.report();
+    }
+
+    /// Store all queued tracks.
+    fn flush(&mut self) {
+        if self.batch.is_empty() {
+            return;
+        }
+
+        let total = self.batch.len();
+        match insert_batch(&mut self.db.conn.lock(), &self.batch) {
+            Ok(failed) => {
+                self.progress.parsed += total - failed;
+                self.progress.errors += failed;
+            }
+            Err(err) => {
+                warn!("Error storing {total} tracks: {err:#?}");
+                self.progress.errors += total;
+            }
+        }
+        self.batch.clear();
+    }
+
+    /// Call `on_progress`, if enough changed since the last time.
+    fn report(&mut self) {
+        let Some(on_progress) = self.on_progress else {
+            return;
+        };
+        let processed = self.progress.processed();
+        if processed < self.reported + SCAN_PROGRESS_STEP {
+            return;
+        }
+
+        self.reported = processed;
+        on_progress(self.progress);
+    }
+}
+
+/// Insert or update all tracks of `batch` in a single transaction, which is a lot faster than one transaction each.
+///
+/// Returns how many tracks could not be stored, which do not leave any partial data behind.
+fn insert_batch(conn: &mut Connection, batch: &[(PathBuf, TrackMetadata)]) -> Result<usize> {
+    let mut transaction = conn.transaction()?;
+
+    let mut failed = 0;
+    for (path, metadata) in batch {
+        // rolled back on drop, if not committed
+        let savepoint = transaction.savepoint()?;
+        let res = TrackInsertable::try_from_track(path, metadata)
+            .and_then(|track| track.try_insert_or_update(&savepoint));
+        match res {
+            Ok(_) => savepoint.commit()?,
+            Err(err) => {
+                warn!("Error inserting or updating {path:#?}: {err:#?}");
+                failed += 1;
+            }
+        }
+    }
+
+    transaction.commit()?;
+
+    Ok(failed)
+}
+
+/// Get all `removable_dirs` which are currently not mounted or reachable.
+///
+/// A directory counts as unavailable if it cannot be read or is empty, as a unmounted mount point is usually a empty directory.
+fn unavailable_dirs(removable_dirs: &[PathBuf]) -> Vec<PathBuf> {
+    removable_dirs
+        .iter()
+        .map(|dir| shellexpand::path::tilde(dir))
+        .filter(|dir| !std::fs::read_dir(dir).is_ok_and(|mut v| v.next().is_some()))
+        // scanned paths are canonicalized, so the directories need to be too, if possible
+        .map(|dir| dir.canonicalize().unwrap_or_else(|_| dir.into_owned()))
+        .collect()
+}
+
 /// Run SQLite operation `PRAGMA optimize`.
 fn exec_optimize(conn: &Connection) -> Result<()> {
     let mut stmt = conn.prepare("PRAGMA optimize;")?;
@@ -278,6 +687,55 @@ fn exec_optimize(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use super::test_utils::{gen_database, test_path};
+    use super::{DirStats, insert_batch, track_ops, unavailable_dirs};
+    use crate::track::TrackMetadata;
+
+    #[test]
+    fn should_detect_unavailable_dirs() {
+        let missing = PathBuf::from("/termusic/does/not/exist");
+        let available = Path::new(env!("CARGO_MANIFEST_DIR")).to_path_buf();
+
+        assert_eq!(unavailable_dirs(&[missing.clone(), available]), &[missing]);
+    }
+
+    #[test]
+    fn should_display_dir_stats() {
+        let stats = DirStats {
+            tracks: 12,
+            duration: Duration::from_secs(3725),
+            size: 3 * 1024 * 1024,
+        };
+
+        assert_eq!(stats.to_string(), "12 tracks, 1:02:05, 3.0 MiB");
+    }
+
+    #[test]
+    fn should_insert_batch_skipping_failed_tracks() {
+        let db = gen_database();
+        let stored = test_path(Path::new("/somewhere/file.mp3"));
+        let metadata = TrackMetadata {
+            title: Some("Title".to_string()),
+            ..Default::default()
+        };
+        let batch = [
+            (stored.clone(), metadata.clone()),
+            // not absolute
+            (PathBuf::from("relative/file.mp3"), metadata),
+        ];
+
+        assert_eq!(insert_batch(&mut db.get_connection(), &batch).unwrap(), 1);
+        assert!(track_ops::track_exists(&db.get_connection(), &stored).unwrap());
+    }
+}
+
 #[cfg(test)]
 mod test_utils {
     use std::path::{Path, PathBuf};
@@ -0,0 +1,473 @@
+//! The named play queues of the server, stored so that they can be restored after a restart.
+//!
+//! Only one queue is active at a time, which is the one the server plays.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use indoc::indoc;
+use rusqlite::{Connection, OptionalExtension, named_params};
+
+use crate::new_database::Integer;
+use crate::player::playlist_helpers::PlaylistTrackSource;
+
+/// The name of the queue created when storing a queue without any queue existing yet.
+pub const DEFAULT_QUEUE: &str = "Main";
+
+/// The stored play queue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueueState {
+    pub tracks: Vec<PlaylistTrackSource>,
+    /// Index into `tracks` of the current track
+    pub current_index: usize,
+    /// The position in the current track, if known
+    pub position: Option<Duration>,
+}
+
+/// A stored play queue, without its tracks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueueInfo {
+    pub name: String,
+    /// The amount of tracks in the queue
+    pub tracks: usize,
+}
+
+/// Get the `(kind, source)` columns to store `track` as.
+pub(super) fn source_to_columns(track: &PlaylistTrackSource) -> (&'static str, &str) {
+    match track {
+        PlaylistTrackSource::Path(v) => ("path", v),
+        PlaylistTrackSource::Url(v) => ("url", v),
+        PlaylistTrackSource::PodcastUrl(v) => ("podcast", v),
+    }
+}
+
+/// Get the source from the stored `kind` and `source` columns.
+pub(super) fn columns_to_source(kind: &str, source: String) -> Result<PlaylistTrackSource> {
+    Ok(match kind {
+        "path" => PlaylistTrackSource::Path(source),
+        "url" => PlaylistTrackSource::Url(source),
+        "podcast" => PlaylistTrackSource::PodcastUrl(source),
+        _ => bail!("Unknown track kind \"{kind}\""),
+    })
+}
+
+/// Convert `position` to the stored milliseconds.
+fn position_to_column(position: Option<Duration>) -> Result<Option<Integer>> {
+    position
+        .map(|v| Integer::try_from(v.as_millis()))
+        .transpose()
+        .context("convert position to integer")
+}
+
+/// Get the id of the active queue, if there is one.
+fn active_queue_id(conn: &Connection) -> Result<Option<Integer>> {
+    let id = conn
+        .query_row("SELECT queue FROM queue_active WHERE id=0;", [], |row| {
+            row.get(0)
+        })
+        .optional()?;
+
+    Ok(id)
+}
+
+/// Get the id of the queue `name`, if it exists.
+fn queue_id(conn: &Connection, name: &str) -> Result<Option<Integer>> {
+    let id = conn
+        .query_row(
+            "SELECT id FROM queues WHERE name=:name;",
+            named_params! {":name": name},
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    Ok(id)
+}
+
+/// Replace the stored tracks, current index and position of the active queue with `state`.
+///
+/// If there is no active queue yet, [`DEFAULT_QUEUE`] is created and made active.
+pub fn set_queue(conn: &Connection, state: &QueueState) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+
+    let queue = match active_queue_id(&tx)? {
+        Some(v) => v,
+        None => {
+            tx.execute(
+                "INSERT OR IGNORE INTO queues (name) VALUES (:name);",
+                named_params! {":name": DEFAULT_QUEUE},
+            )?;
+            let id = queue_id(&tx, DEFAULT_QUEUE)?.context("default queue got created")?;
+            set_active_queue_id(&tx, id)?;
+            id
+        }
+    };
+
+    tx.execute(
+        "DELETE FROM queue_tracks WHERE queue=:queue;",
+        named_params! {":queue": queue},
+    )?;
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO queue_tracks (queue, position, kind, source) VALUES (:queue, :position, :kind, :source);",
+        )?;
+        for (position, track) in state.tracks.iter().enumerate() {
+            let (kind, source) = source_to_columns(track);
+            stmt.execute(named_params! {
+                ":queue": queue,
+                ":position": Integer::try_from(position)?,
+                ":kind": kind,
+                ":source": source,
+            })
+            .with_context(|| format!("Queue track \"{source}\""))?;
+        }
+    }
+
+    tx.execute(
+        "UPDATE queues SET current_index=:current_index, position=:position WHERE id=:queue;",
+        named_params! {
+            ":queue": queue,
+            ":current_index": Integer::try_from(state.current_index)?,
+            ":position": position_to_column(state.position)?,
+        },
+    )?;
+
+    tx.commit()?;
+
+    Ok(())
+}
+
+/// Set the position in the current track of the active queue.
+///
+/// Does nothing if no queue is stored yet.
+pub fn set_queue_position(conn: &Connection, position: Option<Duration>) -> Result<()> {
+    conn.execute(
+        "UPDATE queues SET position=:position WHERE id=(SELECT queue FROM queue_active WHERE id=0);",
+        named_params! {":position": position_to_column(position)?},
+    )?;
+
+    Ok(())
+}
+
+/// Get the active queue, or `None` if no queue has been stored yet.
+pub fn get_queue(conn: &Connection) -> Result<Option<QueueState>> {
+    let Some((queue, current_index, position)) = conn
+        .query_row(
+            indoc! {"
+                SELECT queues.id, queues.current_index, queues.position FROM queue_active
+                INNER JOIN queues ON queues.id=queue_active.queue
+                WHERE queue_active.id=0;
+            "},
+            [],
+            |row| {
+                Ok((
+                    row.get::<_, Integer>(0)?,
+                    row.get::<_, Integer>(1)?,
+                    row.get::<_, Option<Integer>>(2)?,
+                ))
+            },
+        )
+        .optional()?
+    else {
+        return Ok(None);
+    };
+
+    let mut stmt = conn.prepare(
+        "SELECT kind, source FROM queue_tracks WHERE queue=:queue ORDER BY position ASC;",
+    )?;
+    let tracks = stmt
+        .query_map(named_params! {":queue": queue}, |row| {
+            Ok((row.get::<_, String>(0)?, row.get(1)?))
+        })?
+        .map(|row| {
+            let (kind, source) = row?;
+            columns_to_source(&kind, source)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Some(QueueState {
+        tracks,
+        current_index: usize::try_from(current_index).unwrap_or_default(),
+        position: position
+            .and_then(|v| u64::try_from(v).ok())
+            .map(Duration::from_millis),
+    }))
+}
+
+/// Get all stored queues, in the order they were created.
+pub fn get_queues(conn: &Connection) -> Result<Vec<QueueInfo>> {
+    let mut stmt = conn.prepare(indoc! {"
+        SELECT queues.name, COUNT(queue_tracks.queue) FROM queues
+        LEFT JOIN queue_tracks ON queue_tracks.queue=queues.id
+        GROUP BY queues.id
+        ORDER BY queues.id ASC;
+    "})?;
+    let queues = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Integer>(1)?))
+        })?
+        .map(|row| {
+            let (name, tracks) = row?;
+            Ok(QueueInfo {
+                name,
+                tracks: usize::try_from(tracks).unwrap_or_default(),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(queues)
+}
+
+/// Get the name of the active queue, or `None` if no queue has been stored yet.
+pub fn get_active_queue(conn: &Connection) -> Result<Option<String>> {
+    let name = conn
+        .query_row(
+            indoc! {"
+                SELECT queues.name FROM queue_active
+                INNER JOIN queues ON queues.id=queue_active.queue
+                WHERE queue_active.id=0;
+            "},
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    Ok(name)
+}
+
+/// Create a new empty queue called `name`.
+///
+/// # Errors
+///
+/// - if `name` is empty
+/// - if a queue called `name` already exists
+pub fn create_queue(conn: &Connection, name: &str) -> Result<()> {
+    if name.trim().is_empty() {
+        bail!("A queue needs a name");
+    }
+    if queue_id(conn, name)?.is_some() {
+        bail!("A queue called \"{name}\" already exists");
+    }
+
+    conn.execute(
+        "INSERT INTO queues (name) VALUES (:name);",
+        named_params! {":name": name},
+    )?;
+
+    Ok(())
+}
+
+fn set_active_queue_id(conn: &Connection, queue: Integer) -> Result<()> {
+    conn.execute(
+        indoc! {"
+            INSERT INTO queue_active (id, queue) VALUES (0, :queue)
+            ON CONFLICT(id) DO UPDATE SET queue=excluded.queue;
+        "},
+        named_params! {":queue": queue},
+    )?;
+
+    Ok(())
+}
+
+/// Make the queue called `name` the active queue.
+///
+/// # Errors
+///
+/// - if there is no queue called `name`
+pub fn set_active_queue(conn: &Connection, name: &str) -> Result<()> {
+    let Some(queue) = queue_id(conn, name)? else {
+        bail!("There is no queue called \"{name}\"");
+    };
+
+    set_active_queue_id(conn, queue)
+}
+
+/// Delete the queue called `name` and all its tracks.
+///
+/// # Errors
+///
+/// - if there is no queue called `name`
+/// - if the queue is the active queue
+pub fn delete_queue(conn: &Connection, name: &str) -> Result<()> {
+    let Some(queue) = queue_id(conn, name)? else {
+        bail!("There is no queue called \"{name}\"");
+    };
+    if active_queue_id(conn)? == Some(queue) {
+        bail!("The active queue cannot be deleted, switch to another queue first");
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    // foreign keys are not enforced, so the tracks need to be deleted explicitly
+    tx.execute(
+        "DELETE FROM queue_tracks WHERE queue=:queue;",
+        named_params! {":queue": queue},
+    )?;
+    tx.execute(
+        "DELETE FROM queues WHERE id=:queue;",
+        named_params! {":queue": queue},
+    )?;
+    tx.commit()?;
+
+    Ok(())
+}
+
+/// Change all queued local tracks at path `from` to `to`, in all queues.
+///
+/// Returns the amount of changed queue entries.
+pub fn rename_path(conn: &Connection, from: &str, to: &str) -> Result<usize> {
+    let affected = conn.execute(
+        "UPDATE queue_tracks SET source=:to WHERE kind='path' AND source=:from;",
+        named_params! {":from": from, ":to": to},
+    )?;
+
+    Ok(affected)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::new_database::queue_ops::{
+        DEFAULT_QUEUE, QueueInfo, QueueState, create_queue, delete_queue, get_active_queue,
+        get_queue, get_queues, rename_path, set_active_queue, set_queue, set_queue_position,
+    };
+    use crate::new_database::test_utils::gen_database;
+    use crate::player::playlist_helpers::PlaylistTrackSource;
+
+    #[test]
+    fn should_store_and_replace_queue() {
+        let db = gen_database();
+        let conn = db.get_connection();
+
+        assert_eq!(get_queue(&conn).unwrap(), None);
+        // nothing to update yet
+        set_queue_position(&conn, Some(Duration::from_secs(1))).unwrap();
+        assert_eq!(get_queue(&conn).unwrap(), None);
+
+        let state = QueueState {
+            tracks: vec![
+                PlaylistTrackSource::Path("/music/a.mp3".to_string()),
+                PlaylistTrackSource::Url("http://example.com/radio".to_string()),
+                PlaylistTrackSource::PodcastUrl("http://example.com/episode.mp3".to_string()),
+            ],
+            current_index: 1,
+            position: None,
+        };
+        set_queue(&conn, &state).unwrap();
+        assert_eq!(get_queue(&conn).unwrap().as_ref(), Some(&state));
+
+        set_queue_position(&conn, Some(Duration::from_millis(61_500))).unwrap();
+        assert_eq!(
+            get_queue(&conn).unwrap().unwrap().position,
+            Some(Duration::from_millis(61_500))
+        );
+
+        // replacing removes all old tracks
+        let state = QueueState {
+            tracks: vec![PlaylistTrackSource::Path("/music/b.mp3".to_string())],
+            current_index: 0,
+            position: Some(Duration::from_secs(3)),
+        };
+        set_queue(&conn, &state).unwrap();
+        assert_eq!(get_queue(&conn).unwrap(), Some(state));
+
+        // only local paths are renamed
+        assert_eq!(
+            rename_path(&conn, "/music/b.mp3", "/music/Artist/b.mp3").unwrap(),
+            1
+        );
+        assert_eq!(
+            rename_path(&conn, "http://example.com/radio", "/music/c.mp3").unwrap(),
+            0
+        );
+        assert_eq!(
+            get_queue(&conn).unwrap().unwrap().tracks,
+            vec![PlaylistTrackSource::Path("/music/Artist/b.mp3".to_string())]
+        );
+
+        // a empty queue is still a stored queue
+        let state = QueueState {
+            tracks: Vec::new(),
+            current_index: 0,
+            position: None,
+        };
+        set_queue(&conn, &state).unwrap();
+        assert_eq!(get_queue(&conn).unwrap(), Some(state));
+    }
+
+    #[test]
+    fn should_keep_named_queues_separate() {
+        let db = gen_database();
+        let conn = db.get_connection();
+
+        assert_eq!(get_active_queue(&conn).unwrap(), None);
+
+        // storing without any queue creates the default queue
+        let main = QueueState {
+            tracks: vec![PlaylistTrackSource::Path("/music/a.mp3".to_string())],
+            current_index: 0,
+            position: Some(Duration::from_secs(10)),
+        };
+        set_queue(&conn, &main).unwrap();
+        assert_eq!(
+            get_active_queue(&conn).unwrap().as_deref(),
+            Some(DEFAULT_QUEUE)
+        );
+
+        create_queue(&conn, "Podcast backlog").unwrap();
+        assert!(create_queue(&conn, "Podcast backlog").is_err());
+        assert!(create_queue(&conn, " ").is_err());
+        assert!(set_active_queue(&conn, "Missing").is_err());
+
+        set_active_queue(&conn, "Podcast backlog").unwrap();
+        // a new queue is empty
+        assert_eq!(
+            get_queue(&conn).unwrap(),
+            Some(QueueState {
+                tracks: Vec::new(),
+                current_index: 0,
+                position: None,
+            })
+        );
+        let backlog = QueueState {
+            tracks: vec![
+                PlaylistTrackSource::PodcastUrl("http://example.com/1.mp3".to_string()),
+                PlaylistTrackSource::PodcastUrl("http://example.com/2.mp3".to_string()),
+            ],
+            current_index: 1,
+            position: None,
+        };
+        set_queue(&conn, &backlog).unwrap();
+
+        assert_eq!(
+            get_queues(&conn).unwrap(),
+            vec![
+                QueueInfo {
+                    name: DEFAULT_QUEUE.to_string(),
+                    tracks: 1,
+                },
+                QueueInfo {
+                    name: "Podcast backlog".to_string(),
+                    tracks: 2,
+                },
+            ]
+        );
+
+        // the active queue cannot be deleted
+        assert!(delete_queue(&conn, "Podcast backlog").is_err());
+
+        set_active_queue(&conn, DEFAULT_QUEUE).unwrap();
+        assert_eq!(get_queue(&conn).unwrap(), Some(main));
+
+        delete_queue(&conn, "Podcast backlog").unwrap();
+        assert!(delete_queue(&conn, "Podcast backlog").is_err());
+        assert_eq!(
+            get_queues(&conn).unwrap(),
+            vec![QueueInfo {
+                name: DEFAULT_QUEUE.to_string(),
+                tracks: 1,
+            }]
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use anyhow::{Context, Result, bail};
+use indoc::indoc;
+use rusqlite::{Connection, named_params};
+
+use crate::radio_browser::RadioStation;
+
+/// Store `station` as a favorite.
+///
+/// If the station is already a favorite, its data is updated.
+pub fn insert_radio_favorite(conn: &Connection, station: &RadioStation) -> Result<()> {
+    let now = chrono::Utc::now().to_rfc3339();
+
+    conn.execute(
+        indoc! {"
+            INSERT INTO radio_favorites (uuid, name, url, tags, country, codec, bitrate, added_at)
+            VALUES (:uuid, :name, :url, :tags, :country, :codec, :bitrate, :added_at)
+            ON CONFLICT(uuid) DO UPDATE SET
+                name=excluded.name, url=excluded.url, tags=excluded.tags,
+                country=excluded.country, codec=excluded.codec, bitrate=excluded.bitrate;
+        "},
+        named_params! {
+            ":uuid": station.uuid,
+            ":name": station.name,
+            ":url": station.url,
+            ":tags": station.tags,
+            ":country": station.country,
+            ":codec": station.codec,
+            ":bitrate": station.bitrate,
+            ":added_at": now,
+        },
+    )
+    .with_context(|| format!("Radio station \"{}\"", station.name))?;
+
+    Ok(())
+}
+
+/// Remove the station with `uuid` from the favorites.
+pub fn delete_radio_favorite(conn: &Connection, uuid: &str) -> Result<()> {
+    let affected = conn.execute(
+        "DELETE FROM radio_favorites WHERE uuid=:uuid;",
+        named_params! {":uuid": uuid},
+    )?;
+
+    // delete would otherwise fail silently
+    if affected == 0 {
+        bail!("Radio station \"{uuid}\" is not a favorite");
+    }
+
+    Ok(())
+}
+
+/// Get all favorite stations, ordered by name.
+pub fn get_all_radio_favorites(conn: &Connection) -> Result<Vec<RadioStation>> {
+    let mut stmt = conn.prepare(indoc! {"
+        SELECT uuid, name, url, tags, country, codec, bitrate FROM radio_favorites
+        ORDER BY name ASC;
+    "})?;
+
+    let stations = stmt
+        .query_map([], |row| {
+            Ok(RadioStation {
+                uuid: row.get(0)?,
+                name: row.get(1)?,
+                url: row.get(2)?,
+                tags: row.get(3)?,
+                country: row.get(4)?,
+                codec: row.get(5)?,
+                bitrate: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+
+    Ok(stations)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        new_database::{
+            radio_ops::{delete_radio_favorite, get_all_radio_favorites, insert_radio_favorite},
+            test_utils::gen_database,
+        },
+        radio_browser::RadioStation,
+    };
+
+    fn station(uuid: &str, name: &str) -> RadioStation {
+        RadioStation {
+            uuid: uuid.to_string(),
+            name: name.to_string(),
+            url: format!("http://example.com/{uuid}.mp3"),
+            tags: "jazz".to_string(),
+            country: "Germany".to_string(),
+            codec: "MP3".to_string(),
+            bitrate: 128,
+        }
+    }
+
+    #[test]
+    fn should_insert_and_delete_favorites() {
+        let db = gen_database();
+        let conn = db.get_connection();
+
+        insert_radio_favorite(&conn, &station("b", "Second")).unwrap();
+        insert_radio_favorite(&conn, &station("a", "First")).unwrap();
+        assert_eq!(
+            get_all_radio_favorites(&conn).unwrap(),
+            [station("a", "First"), station("b", "Second")]
+        );
+
+        // inserting again updates the existing entry
+        insert_radio_favorite(&conn, &station("b", "Renamed")).unwrap();
+        assert_eq!(
+            get_all_radio_favorites(&conn).unwrap(),
+            [station("a", "First"), station("b", "Renamed")]
+        );
+
+        delete_radio_favorite(&conn, "a").unwrap();
+        assert_eq!(
+            get_all_radio_favorites(&conn).unwrap(),
+            [station("b", "Renamed")]
+        );
+        assert!(delete_radio_favorite(&conn, "a").is_err());
+    }
+}
@@ -0,0 +1,622 @@
+//! Rule-based "smart" playlists, stored in the database and evaluated against the tracks on demand.
+//!
+//! A smart playlist is written as a query of rules combined by either `AND` or `OR`, for example
+//! `genre = Rock AND last_played > 30`.
+
+use std::{fmt::Display, str::FromStr};
+
+use anyhow::{Context, Result, bail};
+use indoc::{formatdoc, indoc};
+use rusqlite::{Connection, ToSql, named_params, types::Value};
+
+use crate::new_database::{
+    Integer,
+    track_ops::{RowOrdering, TrackRead, common_row_to_trackread},
+};
+
+/// The track property a [`SmartRule`] matches against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleField {
+    Title,
+    Artist,
+    Album,
+    Genre,
+    Directory,
+    /// The duration of the track in seconds
+    Duration,
+    /// The days since the track was added to the database
+    Added,
+    /// The days since the track was last played, never played tracks count as played infinitely long ago
+    LastPlayed,
+}
+
+/// How the value of a [`RuleField`] is interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    Text,
+    Seconds,
+    DaysAgo,
+}
+
+impl RuleField {
+    const ALL: [Self; 8] = [
+        Self::Title,
+        Self::Artist,
+        Self::Album,
+        Self::Genre,
+        Self::Directory,
+        Self::Duration,
+        Self::Added,
+        Self::LastPlayed,
+    ];
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Title => "title",
+            Self::Artist => "artist",
+            Self::Album => "album",
+            Self::Genre => "genre",
+            Self::Directory => "directory",
+            Self::Duration => "duration",
+            Self::Added => "added",
+            Self::LastPlayed => "last_played",
+        }
+    }
+
+    const fn kind(self) -> FieldKind {
+        match self {
+            Self::Title | Self::Artist | Self::Album | Self::Genre | Self::Directory => {
+                FieldKind::Text
+            }
+            Self::Duration => FieldKind::Seconds,
+            Self::Added | Self::LastPlayed => FieldKind::DaysAgo,
+        }
+    }
+
+    /// The column of the query in [`get_tracks_from_smart_playlist`] this field maps to.
+    const fn as_sql(self) -> &'static str {
+        match self {
+            Self::Title => "tracks_metadata.title",
+            Self::Artist => "tracks_metadata.artist_display",
+            Self::Album => "albums.title",
+            Self::Genre => "tracks_metadata.genre",
+            Self::Directory => "tracks.file_dir",
+            Self::Duration => "tracks.duration",
+            Self::Added => "tracks.added_at",
+            Self::LastPlayed => "tracks.last_played",
+        }
+    }
+}
+
+impl FromStr for RuleField {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|v| v.as_str().eq_ignore_ascii_case(s))
+            .with_context(|| format!("Unknown field \"{s}\""))
+    }
+}
+
+/// The comparison of a [`SmartRule`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleOperator {
+    Is,
+    IsNot,
+    Contains,
+    GreaterThan,
+    LessThan,
+}
+
+impl RuleOperator {
+    const ALL: [Self; 5] = [
+        Self::Is,
+        Self::IsNot,
+        Self::Contains,
+        Self::GreaterThan,
+        Self::LessThan,
+    ];
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Is => "=",
+            Self::IsNot => "!=",
+            Self::Contains => "~",
+            Self::GreaterThan => ">",
+            Self::LessThan => "<",
+        }
+    }
+}
+
+impl FromStr for RuleOperator {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|v| v.as_str() == s)
+            .with_context(|| format!("Unknown operator \"{s}\""))
+    }
+}
+
+/// A single rule of a [`SmartPlaylist`], like `genre = Rock`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmartRule {
+    pub field: RuleField,
+    pub operator: RuleOperator,
+    pub value: String,
+}
+
+impl SmartRule {
+    /// Check that the operator and value can be used with the field.
+    fn validate(&self) -> Result<()> {
+        match (self.field.kind(), self.operator) {
+            (FieldKind::Text, RuleOperator::GreaterThan | RuleOperator::LessThan) => bail!(
+                "Operator \"{}\" cannot be used with text field \"{}\"",
+                self.operator.as_str(),
+                self.field.as_str()
+            ),
+            (FieldKind::Seconds, RuleOperator::Contains)
+            | (
+                FieldKind::DaysAgo,
+                RuleOperator::Is | RuleOperator::IsNot | RuleOperator::Contains,
+            ) => {
+                bail!(
+                    "Operator \"{}\" cannot be used with field \"{}\"",
+                    self.operator.as_str(),
+                    self.field.as_str()
+                )
+            }
+            (FieldKind::Seconds | FieldKind::DaysAgo, _) => {
+                self.number()?;
+            }
+            (FieldKind::Text, _) => (),
+        }
+
+        Ok(())
+    }
+
+    /// Get the value as a number, for non-text fields.
+    fn number(&self) -> Result<u32> {
+        self.value.parse().with_context(|| {
+            format!(
+                "Value for field \"{}\" has to be a number, got \"{}\"",
+                self.field.as_str(),
+                self.value
+            )
+        })
+    }
+
+    /// Convert this rule to a sql condition, with the value bound to `param`.
+    fn to_sql(&self, param: &str) -> Result<(String, Value)> {
+        self.validate()?;
+        let column = self.field.as_sql();
+
+        let res = match (self.field.kind(), self.operator) {
+            (FieldKind::Text, RuleOperator::Is) => (
+                format!("{column} = {param} COLLATE NOCASE"),
+                Value::Text(self.value.clone()),
+            ),
+            (FieldKind::Text, RuleOperator::IsNot) => (
+                format!("({column} IS NULL OR {column} != {param} COLLATE NOCASE)"),
+                Value::Text(self.value.clone()),
+            ),
+            (FieldKind::Text, RuleOperator::Contains) => (
+                format!("{column} LIKE {param}"),
+                Value::Text(format!("%{}%", self.value)),
+            ),
+            // the operators are valid sql for all non-"Contains" cases
+            (FieldKind::Seconds, op) => (
+                format!("{column} {} {param}", op.as_str()),
+                Value::Integer(self.number()?.into()),
+            ),
+            (FieldKind::DaysAgo, op) => {
+                let cutoff = chrono::Utc::now() - chrono::Duration::days(self.number()?.into());
+                let cutoff = Value::Text(cutoff.to_rfc3339());
+                if op == RuleOperator::GreaterThan {
+                    (
+                        format!("({column} IS NULL OR julianday({column}) < julianday({param}))"),
+                        cutoff,
+                    )
+                } else {
+                    (format!("julianday({column}) > julianday({param})"), cutoff)
+                }
+            }
+            // already covered by "validate"
+            (FieldKind::Text, RuleOperator::GreaterThan | RuleOperator::LessThan) => {
+                unreachable!()
+            }
+        };
+
+        Ok(res)
+    }
+}
+
+impl Display for SmartRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.field.as_str(),
+            self.operator.as_str(),
+            self.value
+        )
+    }
+}
+
+impl FromStr for SmartRule {
+    type Err = anyhow::Error;
+
+    /// Parse a rule in the format `field operator value`, the value may contain spaces.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (field, rest) = s
+            .split_once(char::is_whitespace)
+            .with_context(|| format!("Rule \"{s}\" is missing a operator"))?;
+        let (operator, value) = rest
+            .trim_start()
+            .split_once(char::is_whitespace)
+            .with_context(|| format!("Rule \"{s}\" is missing a value"))?;
+
+        let rule = Self {
+            field: field.parse()?,
+            operator: operator.parse()?,
+            value: value.trim().to_string(),
+        };
+        rule.validate()?;
+
+        Ok(rule)
+    }
+}
+
+/// The rules of a smart playlist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmartQuery {
+    /// Whether all rules need to match, or just any of them
+    pub match_all: bool,
+    pub rules: Vec<SmartRule>,
+}
+
+impl Display for SmartQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = if self.match_all { " AND " } else { " OR " };
+        for (idx, rule) in self.rules.iter().enumerate() {
+            if idx > 0 {
+                f.write_str(join)?;
+            }
+            write!(f, "{rule}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for SmartQuery {
+    type Err = anyhow::Error;
+
+    /// Parse rules joined by either ` AND ` or ` OR `, mixing both is not supported.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let has_and = s.contains(" AND ");
+        let has_or = s.contains(" OR ");
+        if has_and && has_or {
+            bail!("Mixing \"AND\" and \"OR\" is not supported");
+        }
+
+        let rules = s
+            .split(if has_or { " OR " } else { " AND " })
+            .map(SmartRule::from_str)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            match_all: !has_or,
+            rules,
+        })
+    }
+}
+
+/// A smart playlist as stored in the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmartPlaylist {
+    pub id: Integer,
+
+    pub name: String,
+    pub query: SmartQuery,
+}
+
+/// Store a new smart playlist `name` with `query`.
+///
+/// Fails if there is already a smart playlist with the same name.
+pub fn insert_smart_playlist(conn: &Connection, name: &str, query: &SmartQuery) -> Result<Integer> {
+    let now = chrono::Utc::now().to_rfc3339();
+
+    let id: Integer = conn
+        .query_row(
+            indoc! {"
+                INSERT INTO smart_playlists (name, match_all, added_at)
+                VALUES (:name, :match_all, :added_at)
+                RETURNING id;
+            "},
+            named_params! {":name": name, ":match_all": query.match_all, ":added_at": now},
+            |row| row.get(0),
+        )
+        .with_context(|| format!("Smart playlist \"{name}\""))?;
+
+    let mut stmt = conn.prepare_cached(indoc! {"
+        INSERT INTO smart_playlist_rules (playlist, field, operator, value)
+        VALUES (:playlist, :field, :operator, :value);
+    "})?;
+    for rule in &query.rules {
+        stmt.execute(named_params! {
+            ":playlist": id,
+            ":field": rule.field.as_str(),
+            ":operator": rule.operator.as_str(),
+            ":value": rule.value,
+        })?;
+    }
+
+    Ok(id)
+}
+
+/// Delete the smart playlist `name` and its rules.
+pub fn delete_smart_playlist(conn: &Connection, name: &str) -> Result<()> {
+    // foreign keys are not enforced, so delete the rules manually
+    conn.execute(
+        indoc! {"
+            DELETE FROM smart_playlist_rules
+            WHERE playlist IN (SELECT id FROM smart_playlists WHERE name=:name);
+        "},
+        named_params! {":name": name},
+    )?;
+    let affected = conn.execute(
+        "DELETE FROM smart_playlists WHERE name=:name;",
+        named_params! {":name": name},
+    )?;
+
+    // delete would otherwise fail silently
+    if affected == 0 {
+        bail!("Smart playlist \"{name}\" not found");
+    }
+
+    Ok(())
+}
+
+/// Get all smart playlists, ordered by name.
+pub fn get_all_smart_playlists(conn: &Connection) -> Result<Vec<SmartPlaylist>> {
+    let mut stmt = conn.prepare(indoc! {"
+        SELECT id, name, match_all FROM smart_playlists
+        ORDER BY name ASC;
+    "})?;
+
+    let playlists: Vec<(Integer, String, bool)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+
+    let mut result = Vec::with_capacity(playlists.len());
+    for (id, name, match_all) in playlists {
+        let rules = get_rules(conn, id).with_context(|| format!("Smart playlist \"{name}\""))?;
+        result.push(SmartPlaylist {
+            id,
+            name,
+            query: SmartQuery { match_all, rules },
+        });
+    }
+
+    Ok(result)
+}
+
+/// Get the smart playlist `name`.
+pub fn get_smart_playlist(conn: &Connection, name: &str) -> Result<SmartPlaylist> {
+    let (id, match_all): (Integer, bool) = conn
+        .query_row(
+            "SELECT id, match_all FROM smart_playlists WHERE name=:name;",
+            named_params! {":name": name},
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .with_context(|| format!("Smart playlist \"{name}\""))?;
+    let rules = get_rules(conn, id).with_context(|| format!("Smart playlist \"{name}\""))?;
+
+    Ok(SmartPlaylist {
+        id,
+        name: name.to_string(),
+        query: SmartQuery { match_all, rules },
+    })
+}
+
+/// Get all rules of the smart playlist with `id`.
+fn get_rules(conn: &Connection, id: Integer) -> Result<Vec<SmartRule>> {
+    let mut stmt = conn.prepare_cached(indoc! {"
+        SELECT field, operator, value FROM smart_playlist_rules
+        WHERE playlist=:playlist
+        ORDER BY id ASC;
+    "})?;
+
+    let rows: Vec<(String, String, String)> = stmt
+        .query_map(named_params! {":playlist": id}, |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?
+        .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+
+    rows.into_iter()
+        .map(|(field, operator, value)| {
+            Ok(SmartRule {
+                field: field.parse()?,
+                operator: operator.parse()?,
+                value,
+            })
+        })
+        .collect()
+}
+
+/// Evaluate the `playlist` and get all matching tracks.
+///
+/// # Panics
+///
+/// If the database schema does not match what is expected.
+pub fn get_tracks_from_smart_playlist(
+    conn: &Connection,
+    playlist: &SmartPlaylist,
+    order: RowOrdering,
+) -> Result<Vec<TrackRead>> {
+    let mut conditions = Vec::with_capacity(playlist.query.rules.len());
+    let mut params = Vec::with_capacity(playlist.query.rules.len());
+    for (idx, rule) in playlist.query.rules.iter().enumerate() {
+        let param = format!(":v{idx}");
+        let (condition, value) = rule.to_sql(&param)?;
+        conditions.push(condition);
+        params.push((param, value));
+    }
+
+    let where_clause = if conditions.is_empty() {
+        "1".to_string()
+    } else {
+        conditions.join(if playlist.query.match_all {
+            " AND "
+        } else {
+            " OR "
+        })
+    };
+
+    let stmt = formatdoc! {"
+        SELECT
+            tracks.id AS track_id, tracks.file_dir, tracks.file_stem, tracks.file_ext, tracks.duration, tracks.last_position,
+            tracks_metadata.title AS track_title, tracks_metadata.artist_display, tracks_metadata.genre,
+            albums.id AS album_id, albums.title AS album_title
+        FROM tracks
+        LEFT JOIN tracks_metadata ON tracks.id = tracks_metadata.track
+        LEFT JOIN albums ON tracks.album = albums.id
+        WHERE ({where_clause}) AND tracks.missing_since IS NULL
+        ORDER BY {};
+        ",
+        order.as_sql()
+    };
+    let mut stmt = conn.prepare(&stmt)?;
+
+    let params: Vec<(&str, &dyn ToSql)> = params
+        .iter()
+        .map(|(name, value)| (name.as_str(), value as &dyn ToSql))
+        .collect();
+
+    let result: Vec<TrackRead> = stmt
+        .query_map(params.as_slice(), |row| {
+            let trackread = common_row_to_trackread(conn, row);
+
+            Ok(trackread)
+        })?
+        .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        ffi::{OsStr, OsString},
+        path::Path,
+        time::Duration,
+    };
+
+    use pretty_assertions::assert_eq;
+
+    use crate::new_database::{
+        smart_playlist_ops::{
+            RuleField, RuleOperator, SmartQuery, SmartRule, delete_smart_playlist,
+            get_all_smart_playlists, get_smart_playlist, get_tracks_from_smart_playlist,
+            insert_smart_playlist,
+        },
+        test_utils::{gen_database, test_path},
+        track_insert::TrackInsertable,
+        track_ops::{RowOrdering, set_last_played},
+    };
+
+    #[test]
+    fn should_parse_query() {
+        let query: SmartQuery = "genre = Rock AND last_played > 30 AND title ~ some title"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            query,
+            SmartQuery {
+                match_all: true,
+                rules: vec![
+                    SmartRule {
+                        field: RuleField::Genre,
+                        operator: RuleOperator::Is,
+                        value: "Rock".to_string(),
+                    },
+                    SmartRule {
+                        field: RuleField::LastPlayed,
+                        operator: RuleOperator::GreaterThan,
+                        value: "30".to_string(),
+                    },
+                    SmartRule {
+                        field: RuleField::Title,
+                        operator: RuleOperator::Contains,
+                        value: "some title".to_string(),
+                    },
+                ],
+            }
+        );
+        assert_eq!(
+            query.to_string(),
+            "genre = Rock AND last_played > 30 AND title ~ some title"
+        );
+
+        let query: SmartQuery = "artist = A OR duration < 60".parse().unwrap();
+        assert!(!query.match_all);
+
+        assert!(
+            "genre = Rock AND genre = Pop OR genre = Jazz"
+                .parse::<SmartQuery>()
+                .is_err()
+        );
+        assert!("genre > Rock".parse::<SmartQuery>().is_err());
+        assert!("last_played = 5".parse::<SmartQuery>().is_err());
+        assert!("duration > long".parse::<SmartQuery>().is_err());
+        assert!("unknown = 5".parse::<SmartQuery>().is_err());
+        assert!("genre".parse::<SmartQuery>().is_err());
+    }
+
+    #[test]
+    fn should_evaluate_smart_playlist() {
+        let db = gen_database();
+        let conn = db.get_connection();
+
+        let file_dir = test_path(Path::new("/somewhere"));
+        for (stem, genre) in [("rock", "Rock"), ("pop", "Pop"), ("rock2", "rock")] {
+            TrackInsertable {
+                file_dir: &file_dir,
+                file_stem: OsStr::new(stem),
+                file_ext: OsStr::new("mp3"),
+                duration: Some(Duration::from_secs(10)),
+                last_position: None,
+                album: None,
+                title: None,
+                genre: Some(genre),
+                artist_display: None,
+                artists: Vec::new(),
+            }
+            .try_insert_or_update(&conn)
+            .unwrap();
+        }
+        set_last_played(&conn, &test_path(Path::new("/somewhere/rock.mp3"))).unwrap();
+
+        let query: SmartQuery = "genre = rock AND last_played > 30".parse().unwrap();
+        insert_smart_playlist(&conn, "Old Rock", &query).unwrap();
+        assert!(insert_smart_playlist(&conn, "Old Rock", &query).is_err());
+
+        let playlist = get_smart_playlist(&conn, "Old Rock").unwrap();
+        assert_eq!(playlist.query, query);
+        assert_eq!(get_all_smart_playlists(&conn).unwrap(), [playlist.clone()]);
+
+        let tracks = get_tracks_from_smart_playlist(&conn, &playlist, RowOrdering::IdAsc).unwrap();
+        let stems: Vec<_> = tracks.iter().map(|v| v.file_stem.clone()).collect();
+        assert_eq!(stems, [OsString::from("rock2")]);
+
+        delete_smart_playlist(&conn, "Old Rock").unwrap();
+        assert!(get_all_smart_playlists(&conn).unwrap().is_empty());
+        assert!(delete_smart_playlist(&conn, "Old Rock").is_err());
+    }
+}
@@ -6,6 +6,7 @@ use indoc::indoc;
 use rusqlite::{Connection, named_params};
 
 use crate::{
+    ids::TrackId,
     new_database::{
         Integer, album_insert::AlbumInsertable, artist_insert::ArtistInsertable,
         track_ops::delete_tracks_artists_mapping_for,
@@ -128,7 +129,7 @@ impl<'a> TrackInsertable<'a> {
     ///
     /// This will also insert all metadata, album and artists.
     /// Note that this function will remove old mapping data for the given track, if it exists.
-    pub fn try_insert_or_update(&self, conn: &Connection) -> Result<Integer> {
+    pub fn try_insert_or_update(&self, conn: &Connection) -> Result<TrackId> {
         let album = if let Some(album) = &self.album {
             let ret = match album {
                 Either::Left(insertable) => {
@@ -239,12 +240,12 @@ struct InsertTrack<'a> {
 
 impl InsertTrack<'_> {
     /// Insert or update the current data with the file paths as identifiers.
-    fn upsert(&self, conn: &Connection) -> Result<Integer> {
+    fn upsert(&self, conn: &Connection) -> Result<TrackId> {
         let mut stmt = conn.prepare_cached(indoc!{"
             INSERT INTO tracks (file_dir, file_stem, file_ext, duration, last_position, added_at, album)
             VALUES (:file_dir, :file_stem, :file_ext, :duration, :last_position, :added_at, :album)
             ON CONFLICT(file_dir, file_stem, file_ext) DO UPDATE SET 
-                duration=excluded.duration, album=excluded.album
+                duration=excluded.duration, album=excluded.album, missing_since=NULL
             RETURNING id;
         "})?;
 
@@ -273,7 +274,7 @@ impl InsertTrack<'_> {
 #[derive(Debug, PartialEq)]
 struct InsertTrackMetadata<'a> {
     // Track identifier
-    track: Integer,
+    track: TrackId,
 
     // Direct data on `tracks_metadata`
     title: Option<&'a str>,
@@ -283,7 +284,7 @@ struct InsertTrackMetadata<'a> {
 
 impl InsertTrackMetadata<'_> {
     /// Insert or update the current data with the file as identifier.
-    fn upsert(&self, conn: &Connection) -> Result<Integer> {
+    fn upsert(&self, conn: &Connection) -> Result<TrackId> {
         let mut stmt = conn.prepare_cached(indoc! {"
             INSERT INTO tracks_metadata (track, title, genre, artist_display)
             VALUES (:track, :title, :genre, :artist_display)
@@ -309,7 +310,7 @@ impl InsertTrackMetadata<'_> {
 /// Stores references for insertion into `tracks_artists` directly
 #[derive(Debug, PartialEq)]
 struct InsertTrackArtistMapping {
-    track: Integer,
+    track: TrackId,
     artist: Integer,
 }
 
@@ -336,6 +337,7 @@ mod tests {
     use std::{path::Path, time::Duration};
 
     use crate::{
+        ids::TrackId,
         new_database::{
             artist_insert::ArtistInsertable,
             test_utils::{gen_database, test_path},
@@ -437,7 +439,7 @@ mod tests {
 
         let db = db.conn.lock();
         let track_id = data.upsert(&db).unwrap();
-        assert_eq!(track_id, 1);
+        assert_eq!(track_id, TrackId(1));
 
         let artist = ArtistInsertable { artist: "ArtistA" };
 
@@ -1,14 +1,18 @@
 use std::{
+    collections::HashSet,
     ffi::OsString,
-    path::{Path, PathBuf},
+    fmt::Display,
+    path::{MAIN_SEPARATOR, Path, PathBuf},
     time::Duration,
 };
 
 use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
 use either::Either;
 use indoc::{formatdoc, indoc};
 use rusqlite::{Connection, OptionalExtension, Row, ToSql, named_params};
 
+use crate::ids::TrackId;
 use crate::new_database::{
     artist_ops::{ArtistRead, common_row_to_artistread},
     track_insert::{path_to_db_comp, validate_path},
@@ -50,7 +54,7 @@ pub struct AlbumRead {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct TrackRead {
-    pub id: Integer,
+    pub id: TrackId,
 
     // Track identifier
     pub file_dir: PathBuf,
@@ -97,7 +101,7 @@ pub enum RowOrdering {
 
 impl RowOrdering {
     /// Represent it as the data for a `ORDER BY` clause.
-    fn as_sql(self) -> &'static str {
+    pub(super) fn as_sql(self) -> &'static str {
         match self {
             RowOrdering::IdAsc => "tracks.id ASC",
             RowOrdering::IdDesc => "tracks.id DESC",
@@ -121,6 +125,7 @@ pub fn get_all_tracks(conn: &Connection, order: RowOrdering) -> Result<Vec<Track
         FROM tracks
         LEFT JOIN tracks_metadata ON tracks.id = tracks_metadata.track
         LEFT JOIN albums ON tracks.album = albums.id
+        WHERE tracks.missing_since IS NULL
         ORDER BY {};
         ",
         order.as_sql()
@@ -144,7 +149,7 @@ pub fn get_all_tracks(conn: &Connection, order: RowOrdering) -> Result<Vec<Track
 ///
 /// If the database schema does not match what is expected.
 // maybe this should be in "artist_ops" instead?
-pub fn get_all_artists_for_track(conn: &Connection, track_id: Integer) -> Result<Vec<ArtistRead>> {
+pub fn get_all_artists_for_track(conn: &Connection, track_id: TrackId) -> Result<Vec<ArtistRead>> {
     let mut stmt = conn.prepare(indoc! {"
         SELECT artists.id AS artist_id, artists.artist FROM artists
         INNER JOIN tracks_artists ON tracks_artists.track=:track_id
@@ -215,6 +220,449 @@ pub fn set_last_position(conn: &Connection, track: &Path, to: Option<Duration>)
     Ok(())
 }
 
+/// Set the `last_played` for the given `track` to the current time.
+pub fn set_last_played(conn: &Connection, track: &Path) -> Result<()> {
+    let (file_dir, file_stem, file_ext) = path_to_db_comp(track)?;
+    let file_dir = file_dir.to_string_lossy();
+    let file_stem = file_stem.to_string_lossy();
+    let file_ext = file_ext.to_string_lossy();
+
+    let now = chrono::Utc::now().to_rfc3339();
+
+    let mut stmt = conn.prepare_cached(indoc!{"
+        UPDATE tracks SET last_played=:last_played
+        WHERE tracks.file_dir=:file_dir AND tracks.file_stem=:file_stem AND tracks.file_ext=:file_ext;
+    "})?;
+
+    let affected = stmt.execute(named_params! {":file_dir": file_dir, ":file_stem": file_stem, ":file_ext": file_ext, ":last_played": now})?;
+
+    // update would otherwise fail silently
+    if affected == 0 {
+        bail!("Track not found");
+    }
+
+    Ok(())
+}
+
+/// The highest rating a track can have, see [`TrackRating::rating`].
+pub const MAX_RATING: u8 = 5;
+
+/// The rating and favorite flag the user gave a track.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TrackRating {
+    /// From `1` to [`MAX_RATING`], `0` if not rated
+    pub rating: u8,
+    pub favorite: bool,
+}
+
+impl TrackRating {
+    /// Get the rating after this one when cycling through them, going back to unrated after [`MAX_RATING`].
+    #[must_use]
+    pub fn next_rating(self) -> u8 {
+        if self.rating >= MAX_RATING {
+            0
+        } else {
+            self.rating + 1
+        }
+    }
+}
+
+/// Get the `rating` and `favorite` for the given `track`.
+pub fn get_track_rating(conn: &Connection, track: &Path) -> Result<TrackRating> {
+    let (file_dir, file_stem, file_ext) = path_to_db_comp(track)?;
+    let file_dir = file_dir.to_string_lossy();
+    let file_stem = file_stem.to_string_lossy();
+    let file_ext = file_ext.to_string_lossy();
+
+    let mut stmt = conn.prepare_cached(indoc!{"
+        SELECT rating, favorite FROM tracks
+        WHERE tracks.file_dir=:file_dir AND tracks.file_stem=:file_stem AND tracks.file_ext=:file_ext;
+    "})?;
+
+    let result: Option<(Integer, bool)> = stmt
+        .query_row(
+            named_params! {":file_dir": file_dir, ":file_stem": file_stem, ":file_ext": file_ext},
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+
+    let Some((rating, favorite)) = result else {
+        bail!("Track not found");
+    };
+
+    Ok(TrackRating {
+        rating: u8::try_from(rating).unwrap_or_default().min(MAX_RATING),
+        favorite,
+    })
+}
+
+/// Set the `rating` for the given `track`, see [`TrackRating::rating`].
+pub fn set_track_rating(conn: &Connection, track: &Path, rating: u8) -> Result<()> {
+    if rating > MAX_RATING {
+        bail!("Rating {rating} is above the maximum of {MAX_RATING}");
+    }
+
+    let (file_dir, file_stem, file_ext) = path_to_db_comp(track)?;
+    let file_dir = file_dir.to_string_lossy();
+    let file_stem = file_stem.to_string_lossy();
+    let file_ext = file_ext.to_string_lossy();
+
+    let mut stmt = conn.prepare_cached(indoc!{"
+        UPDATE tracks SET rating=:rating
+        WHERE tracks.file_dir=:file_dir AND tracks.file_stem=:file_stem AND tracks.file_ext=:file_ext;
+    "})?;
+
+    let affected = stmt.execute(named_params! {":file_dir": file_dir, ":file_stem": file_stem, ":file_ext": file_ext, ":rating": rating})?;
+
+    // update would otherwise fail silently
+    if affected == 0 {
+        bail!("Track not found");
+    }
+
+    Ok(())
+}
+
+/// Set whether the given `track` is a favorite.
+pub fn set_track_favorite(conn: &Connection, track: &Path, favorite: bool) -> Result<()> {
+    let (file_dir, file_stem, file_ext) = path_to_db_comp(track)?;
+    let file_dir = file_dir.to_string_lossy();
+    let file_stem = file_stem.to_string_lossy();
+    let file_ext = file_ext.to_string_lossy();
+
+    let mut stmt = conn.prepare_cached(indoc!{"
+        UPDATE tracks SET favorite=:favorite
+        WHERE tracks.file_dir=:file_dir AND tracks.file_stem=:file_stem AND tracks.file_ext=:file_ext;
+    "})?;
+
+    let affected = stmt.execute(named_params! {":file_dir": file_dir, ":file_stem": file_stem, ":file_ext": file_ext, ":favorite": favorite})?;
+
+    // update would otherwise fail silently
+    if affected == 0 {
+        bail!("Track not found");
+    }
+
+    Ok(())
+}
+
+/// Get the playback `speed` override for the given `track`, in tenths like `player.speed`.
+///
+/// `None` means the track plays at the global speed.
+pub fn get_track_speed(conn: &Connection, track: &Path) -> Result<Option<i32>> {
+    let (file_dir, file_stem, file_ext) = path_to_db_comp(track)?;
+    let file_dir = file_dir.to_string_lossy();
+    let file_stem = file_stem.to_string_lossy();
+    let file_ext = file_ext.to_string_lossy();
+
+    let mut stmt = conn.prepare_cached(indoc!{"
+        SELECT speed FROM tracks
+        WHERE tracks.file_dir=:file_dir AND tracks.file_stem=:file_stem AND tracks.file_ext=:file_ext;
+    "})?;
+
+    let result: Option<Integer> = stmt.query_row(
+        named_params! {":file_dir": file_dir, ":file_stem": file_stem, ":file_ext": file_ext},
+        |row| row.get(0),
+    )?;
+
+    // invalid values are treated as no override
+    Ok(result.and_then(|v| i32::try_from(v).ok()))
+}
+
+/// Set the playback `speed` override for the given `track`, `None` to use the global speed again.
+pub fn set_track_speed(conn: &Connection, track: &Path, speed: Option<i32>) -> Result<()> {
+    let (file_dir, file_stem, file_ext) = path_to_db_comp(track)?;
+    let file_dir = file_dir.to_string_lossy();
+    let file_stem = file_stem.to_string_lossy();
+    let file_ext = file_ext.to_string_lossy();
+
+    let mut stmt = conn.prepare_cached(indoc!{"
+        UPDATE tracks SET speed=:speed
+        WHERE tracks.file_dir=:file_dir AND tracks.file_stem=:file_stem AND tracks.file_ext=:file_ext;
+    "})?;
+
+    let affected = stmt.execute(named_params! {":file_dir": file_dir, ":file_stem": file_stem, ":file_ext": file_ext, ":speed": speed})?;
+
+    // update would otherwise fail silently
+    if affected == 0 {
+        bail!("Track not found");
+    }
+
+    Ok(())
+}
+
+/// Get whether the given `track` is never played automatically, like by shuffle or auto-queue.
+pub fn get_track_no_autoplay(conn: &Connection, track: &Path) -> Result<bool> {
+    let (file_dir, file_stem, file_ext) = path_to_db_comp(track)?;
+    let file_dir = file_dir.to_string_lossy();
+    let file_stem = file_stem.to_string_lossy();
+    let file_ext = file_ext.to_string_lossy();
+
+    let mut stmt = conn.prepare_cached(indoc!{"
+        SELECT no_autoplay FROM tracks
+        WHERE tracks.file_dir=:file_dir AND tracks.file_stem=:file_stem AND tracks.file_ext=:file_ext;
+    "})?;
+
+    let no_autoplay = stmt.query_row(
+        named_params! {":file_dir": file_dir, ":file_stem": file_stem, ":file_ext": file_ext},
+        |row| row.get(0),
+    )?;
+
+    Ok(no_autoplay)
+}
+
+/// Set whether the given `track` is never played automatically, it can still be played manually.
+pub fn set_track_no_autoplay(conn: &Connection, track: &Path, no_autoplay: bool) -> Result<()> {
+    let (file_dir, file_stem, file_ext) = path_to_db_comp(track)?;
+    let file_dir = file_dir.to_string_lossy();
+    let file_stem = file_stem.to_string_lossy();
+    let file_ext = file_ext.to_string_lossy();
+
+    let mut stmt = conn.prepare_cached(indoc!{"
+        UPDATE tracks SET no_autoplay=:no_autoplay
+        WHERE tracks.file_dir=:file_dir AND tracks.file_stem=:file_stem AND tracks.file_ext=:file_ext;
+    "})?;
+
+    let affected = stmt.execute(named_params! {":file_dir": file_dir, ":file_stem": file_stem, ":file_ext": file_ext, ":no_autoplay": no_autoplay})?;
+
+    // update would otherwise fail silently
+    if affected == 0 {
+        bail!("Track not found");
+    }
+
+    Ok(())
+}
+
+/// Get the paths of all non-missing tracks that are never played automatically, see [`set_track_no_autoplay`].
+pub fn get_no_autoplay_paths(conn: &Connection) -> Result<HashSet<PathBuf>> {
+    let mut stmt = conn.prepare_cached(indoc! {"
+        SELECT tracks.file_dir, tracks.file_stem, tracks.file_ext
+        FROM tracks
+        WHERE tracks.no_autoplay AND tracks.missing_since IS NULL
+        ",
+    })?;
+
+    let result = stmt
+        .query_map(named_params! {}, |row| {
+            let file_dir: String = row.get(0)?;
+            let mut file_name = OsString::from(row.get::<_, String>(1)?);
+            file_name.push(".");
+            file_name.push(row.get::<_, String>(2)?);
+
+            let mut path = PathBuf::from(file_dir);
+            path.push(file_name);
+
+            Ok(path)
+        })?
+        .collect::<Result<HashSet<_>, rusqlite::Error>>()?;
+
+    Ok(result)
+}
+
+/// Get all tracks marked as favorite.
+///
+/// # Panics
+///
+/// If the database schema does not match what is expected.
+pub fn get_favorite_tracks(conn: &Connection, order: RowOrdering) -> Result<Vec<TrackRead>> {
+    let stmt = formatdoc! {"
+        SELECT
+            tracks.id AS track_id, tracks.file_dir, tracks.file_stem, tracks.file_ext, tracks.duration, tracks.last_position,
+            tracks_metadata.title AS track_title, tracks_metadata.artist_display, tracks_metadata.genre,
+            albums.id AS album_id, albums.title AS album_title
+        FROM tracks
+        LEFT JOIN tracks_metadata ON tracks.id=tracks_metadata.track
+        LEFT JOIN albums ON tracks.album = albums.id
+        WHERE tracks.favorite AND tracks.missing_since IS NULL
+        ORDER BY {};
+        ",
+        order.as_sql()
+    };
+    let mut stmt = conn.prepare(&stmt)?;
+
+    let result: Vec<TrackRead> = stmt
+        .query_map(named_params! {}, |row| {
+            let trackread = common_row_to_trackread(conn, row);
+
+            Ok(trackread)
+        })?
+        .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+
+    Ok(result)
+}
+
+/// Get all tracks rated `min_rating` or higher.
+///
+/// # Panics
+///
+/// If the database schema does not match what is expected.
+pub fn get_tracks_rated_at_least(
+    conn: &Connection,
+    min_rating: u8,
+    order: RowOrdering,
+) -> Result<Vec<TrackRead>> {
+    let stmt = formatdoc! {"
+        SELECT
+            tracks.id AS track_id, tracks.file_dir, tracks.file_stem, tracks.file_ext, tracks.duration, tracks.last_position,
+            tracks_metadata.title AS track_title, tracks_metadata.artist_display, tracks_metadata.genre,
+            albums.id AS album_id, albums.title AS album_title
+        FROM tracks
+        LEFT JOIN tracks_metadata ON tracks.id=tracks_metadata.track
+        LEFT JOIN albums ON tracks.album = albums.id
+        WHERE tracks.rating >= :min_rating AND tracks.missing_since IS NULL
+        ORDER BY {};
+        ",
+        order.as_sql()
+    };
+    let mut stmt = conn.prepare(&stmt)?;
+
+    let result: Vec<TrackRead> = stmt
+        .query_map(named_params! {":min_rating": min_rating}, |row| {
+            let trackread = common_row_to_trackread(conn, row);
+
+            Ok(trackread)
+        })?
+        .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+
+    Ok(result)
+}
+
+/// How playing a track ended, see [`record_play_outcome`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayOutcome {
+    /// The track was played until its end
+    Completed,
+    /// Another track was played before the track ended
+    Skipped,
+}
+
+/// Count a play of the given `track` that ended as `outcome`.
+pub fn record_play_outcome(conn: &Connection, track: &Path, outcome: PlayOutcome) -> Result<()> {
+    let (file_dir, file_stem, file_ext) = path_to_db_comp(track)?;
+    let file_dir = file_dir.to_string_lossy();
+    let file_stem = file_stem.to_string_lossy();
+    let file_ext = file_ext.to_string_lossy();
+
+    let column = match outcome {
+        PlayOutcome::Completed => "play_count",
+        PlayOutcome::Skipped => "skip_count",
+    };
+
+    let stmt = formatdoc! {"
+        UPDATE tracks SET {column}={column}+1
+        WHERE tracks.file_dir=:file_dir AND tracks.file_stem=:file_stem AND tracks.file_ext=:file_ext;
+    "};
+    let mut stmt = conn.prepare_cached(&stmt)?;
+
+    let affected = stmt.execute(
+        named_params! {":file_dir": file_dir, ":file_stem": file_stem, ":file_ext": file_ext},
+    )?;
+
+    // update would otherwise fail silently
+    if affected == 0 {
+        bail!("Track not found");
+    }
+
+    Ok(())
+}
+
+/// The play statistics of a track.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TrackPlayStats {
+    /// How often the track was played until the end
+    pub play_count: u32,
+    /// How often another track was played before the track ended
+    pub skip_count: u32,
+    /// When the track was last started
+    pub last_played: Option<DateTime<Utc>>,
+}
+
+impl Display for TrackPlayStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "plays: {}, skips: {}", self.play_count, self.skip_count)?;
+        if let Some(last_played) = self.last_played {
+            write!(f, ", last: {}", last_played.format("%F %R"))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Which tracks to get with [`get_tracks_by_play_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayStatsOrder {
+    /// Tracks played until the end at least once, most often first
+    MostPlayed,
+    /// Tracks started at least once, latest first
+    RecentlyPlayed,
+    /// Tracks skipped at least once, most often first
+    MostSkipped,
+}
+
+impl PlayStatsOrder {
+    /// Represent it as the data for a `WHERE` and `ORDER BY` clause.
+    fn as_sql(self) -> (&'static str, &'static str) {
+        match self {
+            PlayStatsOrder::MostPlayed => (
+                "tracks.play_count > 0",
+                "tracks.play_count DESC, tracks.last_played DESC",
+            ),
+            PlayStatsOrder::RecentlyPlayed => (
+                "tracks.last_played IS NOT NULL",
+                "julianday(tracks.last_played) DESC",
+            ),
+            PlayStatsOrder::MostSkipped => (
+                "tracks.skip_count > 0",
+                "tracks.skip_count DESC, tracks.last_played DESC",
+            ),
+        }
+    }
+}
+
+/// Get up to `limit` tracks with their play statistics, chosen and ordered by `order`.
+///
+/// # Panics
+///
+/// If the database schema does not match what is expected.
+pub fn get_tracks_by_play_stats(
+    conn: &Connection,
+    order: PlayStatsOrder,
+    limit: u32,
+) -> Result<Vec<(TrackRead, TrackPlayStats)>> {
+    let (filter, order) = order.as_sql();
+    let stmt = formatdoc! {"
+        SELECT
+            tracks.id AS track_id, tracks.file_dir, tracks.file_stem, tracks.file_ext, tracks.duration, tracks.last_position,
+            tracks.play_count, tracks.skip_count, tracks.last_played,
+            tracks_metadata.title AS track_title, tracks_metadata.artist_display, tracks_metadata.genre,
+            albums.id AS album_id, albums.title AS album_title
+        FROM tracks
+        LEFT JOIN tracks_metadata ON tracks.id=tracks_metadata.track
+        LEFT JOIN albums ON tracks.album = albums.id
+        WHERE {filter} AND tracks.missing_since IS NULL
+        ORDER BY {order}
+        LIMIT :limit;
+        "
+    };
+    let mut stmt = conn.prepare(&stmt)?;
+
+    let result: Vec<(TrackRead, TrackPlayStats)> = stmt
+        .query_map(named_params! {":limit": limit}, |row| {
+            let trackread = common_row_to_trackread(conn, row);
+            let last_played: Option<String> = row.get("last_played")?;
+            let stats = TrackPlayStats {
+                play_count: row.get("play_count")?,
+                skip_count: row.get("skip_count")?,
+                last_played: last_played
+                    .and_then(|v| DateTime::parse_from_rfc3339(&v).ok())
+                    .map(|v| v.with_timezone(&Utc)),
+            };
+
+            Ok((trackread, stats))
+        })?
+        .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+
+    Ok(result)
+}
+
 /// Get all tracks associated with the given album.
 ///
 /// # Panics
@@ -234,7 +682,7 @@ pub fn get_tracks_from_album(
         FROM tracks
         LEFT JOIN tracks_metadata ON tracks.id=tracks_metadata.track
         LEFT JOIN albums ON tracks.album = albums.id
-        WHERE albums.title=:album_title AND albums.artist_display=:album_artist
+        WHERE albums.title=:album_title AND albums.artist_display=:album_artist AND tracks.missing_since IS NULL
         ORDER BY {};
         ",
         order.as_sql()
@@ -255,6 +703,35 @@ pub fn get_tracks_from_album(
     Ok(result)
 }
 
+/// Get the path of the first non-missing track of all albums titled `album_title`, sorted by path, like to show the album cover.
+pub fn get_first_album_track(conn: &Connection, album_title: &str) -> Result<Option<PathBuf>> {
+    let mut stmt = conn.prepare_cached(indoc! {"
+        SELECT tracks.file_dir, tracks.file_stem, tracks.file_ext
+        FROM tracks
+        INNER JOIN albums ON tracks.album = albums.id
+        WHERE albums.title=:album_title AND tracks.missing_since IS NULL
+        ORDER BY tracks.file_dir ASC, tracks.file_stem ASC
+        LIMIT 1;
+        ",
+    })?;
+
+    let result = stmt
+        .query_row(named_params! {":album_title": album_title}, |row| {
+            let file_dir: String = row.get(0)?;
+            let mut file_name = OsString::from(row.get::<_, String>(1)?);
+            file_name.push(".");
+            file_name.push(row.get::<_, String>(2)?);
+
+            let mut path = PathBuf::from(file_dir);
+            path.push(file_name);
+
+            Ok(path)
+        })
+        .optional()?;
+
+    Ok(result)
+}
+
 /// Get all tracks associated with the given artist.
 ///
 /// # Panics
@@ -275,7 +752,7 @@ pub fn get_tracks_from_artist(
         LEFT JOIN albums ON tracks.album = albums.id
         INNER JOIN tracks_artists ON tracks.id = tracks_artists.track
         INNER JOIN artists ON artists.id=tracks_artists.artist
-        WHERE artists.artist=:artist
+        WHERE artists.artist=:artist AND tracks.missing_since IS NULL
         ORDER BY {};
         ",
         order.as_sql()
@@ -319,7 +796,7 @@ pub fn get_tracks_from_genre(
         FROM tracks
         INNER JOIN tracks_metadata ON tracks.id=tracks_metadata.track
         LEFT JOIN albums ON tracks.album = albums.id
-        WHERE {where_clause}
+        WHERE {where_clause} AND tracks.missing_since IS NULL
         ORDER BY {};
         ",
         order.as_sql()
@@ -358,7 +835,7 @@ pub fn get_tracks_from_directory(
         FROM tracks
         LEFT JOIN tracks_metadata ON tracks.id=tracks_metadata.track
         LEFT JOIN albums ON tracks.album = albums.id
-        WHERE tracks.file_dir=:dir
+        WHERE tracks.file_dir=:dir AND tracks.missing_since IS NULL
         ORDER BY {};
         ",
         order.as_sql()
@@ -394,7 +871,7 @@ pub fn get_tracks_from_genre_like(
         FROM tracks
         INNER JOIN tracks_metadata ON tracks.id = tracks_metadata.track
         LEFT JOIN albums ON tracks.album = albums.id
-        WHERE tracks_metadata.genre LIKE :genre_like
+        WHERE tracks_metadata.genre LIKE :genre_like AND tracks.missing_since IS NULL
         ORDER BY {};
         ",
         order.as_sql()
@@ -412,6 +889,62 @@ pub fn get_tracks_from_genre_like(
     Ok(result)
 }
 
+/// Search all tracks via the full-text index over title, artist, album, genre and file name, best matches first.
+///
+/// Every whitespace separated word in `input` has to match the start of a word in any of the fields.
+/// If `input` contains no words (like `*`), all tracks are returned.
+///
+/// # Panics
+///
+/// If the database schema does not match what is expected.
+pub fn search_tracks_fts(conn: &Connection, input: &str) -> Result<Vec<TrackRead>> {
+    let Some(query) = to_fts_query(input) else {
+        return get_all_tracks(conn, RowOrdering::IdAsc);
+    };
+
+    let mut stmt = conn.prepare(indoc! {"
+        SELECT
+            tracks.id AS track_id, tracks.file_dir, tracks.file_stem, tracks.file_ext, tracks.duration, tracks.last_position,
+            tracks_metadata.title AS track_title, tracks_metadata.artist_display, tracks_metadata.genre,
+            albums.id AS album_id, albums.title AS album_title
+        FROM tracks_fts
+        INNER JOIN tracks ON tracks.id = tracks_fts.rowid
+        LEFT JOIN tracks_metadata ON tracks.id = tracks_metadata.track
+        LEFT JOIN albums ON tracks.album = albums.id
+        WHERE tracks_fts MATCH :query AND tracks.missing_since IS NULL
+        ORDER BY tracks_fts.rank;
+    "})?;
+
+    let result: Vec<TrackRead> = stmt
+        .query_map(named_params! {":query": query}, |row| {
+            let trackread = common_row_to_trackread(conn, row);
+
+            Ok(trackread)
+        })?
+        .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+
+    Ok(result)
+}
+
+/// Convert user input to a FTS5 query, where every word is a quoted prefix query.
+///
+/// Returns [`None`] if there are no words to search for.
+fn to_fts_query(input: &str) -> Option<String> {
+    let words: Vec<String> = input
+        .split_whitespace()
+        .map(|word| word.replace('*', ""))
+        .filter(|word| !word.is_empty())
+        // quoting makes FTS5 syntax like "AND" or "-" literal
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+
+    if words.is_empty() {
+        return None;
+    }
+
+    Some(words.join(" "))
+}
+
 /// Get all tracks associated with a genre.
 ///
 /// # Panics
@@ -450,7 +983,7 @@ pub fn get_track_from_path(conn: &Connection, path: &Path) -> Result<TrackRead>
 /// Common function that converts a well-known named row to a [`TrackRead`].
 ///
 /// For row names look at [`get_all_tracks`].
-fn common_row_to_trackread(conn: &Connection, row: &Row<'_>) -> TrackRead {
+pub(super) fn common_row_to_trackread(conn: &Connection, row: &Row<'_>) -> TrackRead {
     let file_dir = row
         .get("file_dir")
         .map(|v: String| PathBuf::from(v))
@@ -512,28 +1045,184 @@ fn common_row_to_trackread(conn: &Connection, row: &Row<'_>) -> TrackRead {
     }
 }
 
-/// Check if a entry for the given `track` exists.
-///
-/// # Panics
+/// Check if a entry for the given `track` exists.
+///
+/// # Panics
+///
+/// If sqlite somehow does not return what is expected.
+pub fn track_exists(conn: &Connection, track: &Path) -> Result<bool> {
+    let (file_dir, file_stem, file_ext) = path_to_db_comp(track)?;
+    let file_dir = file_dir.to_string_lossy();
+    let file_stem = file_stem.to_string_lossy();
+    let file_ext = file_ext.to_string_lossy();
+
+    let mut stmt = conn.prepare(indoc!{"
+        SELECT tracks.id
+        FROM tracks
+        WHERE tracks.file_dir=:file_dir AND tracks.file_stem=:file_stem AND tracks.file_ext=:file_ext;
+    "})?;
+
+    let exists = stmt.exists(
+        named_params! {":file_dir": file_dir, ":file_stem": file_stem, ":file_ext": file_ext},
+    )?;
+
+    Ok(exists)
+}
+
+/// The path and missing state of a track, see [`get_track_paths_below`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackPathState {
+    pub id: TrackId,
+    pub path: PathBuf,
+    /// Whether the track is currently marked as missing
+    pub missing: bool,
+}
+
+/// Get the paths and missing state of all tracks in or below directory `root`, including missing tracks.
+///
+/// # Panics
+///
+/// If sqlite somehow does not return what is expected.
+pub fn get_track_paths_below(conn: &Connection, root: &Path) -> Result<Vec<TrackPathState>> {
+    let mut stmt = conn.prepare(indoc! {"
+        SELECT tracks.id, tracks.file_dir, tracks.file_stem, tracks.file_ext, tracks.missing_since IS NOT NULL
+        FROM tracks
+        ",
+    })?;
+
+    let mut result: Vec<TrackPathState> = stmt
+        .query_map(named_params! {}, |row| {
+            let file_dir: String = row.get(1)?;
+            let mut file_name = OsString::from(row.get::<_, String>(2)?);
+            file_name.push(".");
+            file_name.push(row.get::<_, String>(3)?);
+
+            let mut path = PathBuf::from(file_dir);
+            path.push(file_name);
+
+            Ok(TrackPathState {
+                id: row.get(0)?,
+                path,
+                missing: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+
+    result.retain(|v| v.path.starts_with(root));
+
+    Ok(result)
+}
+
+/// A track's path and its duration, see [`get_track_durations_below`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackPathDuration {
+    pub path: PathBuf,
+    pub duration: Option<Duration>,
+}
+
+/// Get the path and duration of all non-missing tracks in `root` and its sub-directories.
+pub fn get_track_durations_below(conn: &Connection, root: &Path) -> Result<Vec<TrackPathDuration>> {
+    let (root, lower, upper) = dir_range_below(root);
+
+    let mut stmt = conn.prepare(indoc! {"
+        SELECT tracks.file_dir, tracks.file_stem, tracks.file_ext, tracks.duration
+        FROM tracks
+        WHERE tracks.missing_since IS NULL
+            AND (tracks.file_dir = :root OR (tracks.file_dir >= :lower AND tracks.file_dir < :upper))
+        ",
+    })?;
+
+    let params = named_params! {":root": root, ":lower": lower, ":upper": upper};
+    let result: Vec<TrackPathDuration> = stmt
+        .query_map(params, |row| {
+            let file_dir: String = row.get(0)?;
+            let mut file_name = OsString::from(row.get::<_, String>(1)?);
+            file_name.push(".");
+            file_name.push(row.get::<_, String>(2)?);
+
+            let mut path = PathBuf::from(file_dir);
+            path.push(file_name);
+
+            let duration = row
+                .get::<_, Option<Integer>>(3)?
+                .map(|v| Duration::from_secs(u64::try_from(v.max(0)).unwrap()));
+
+            Ok(TrackPathDuration { path, duration })
+        })?
+        .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+
+    Ok(result)
+}
+
+/// Get `root` as stored in `file_dir`, and the range of the `file_dir`s of its sub-directories.
+///
+/// Sub-directories start with `root` and a separator, so they sort at or after `lower` and before `upper`,
+/// which has the character after the separator instead.
+fn dir_range_below(root: &Path) -> (String, String, String) {
+    // without a trailing separator, like "file_dir" is stored
+    let root: PathBuf = root.components().collect();
+    let root = root.to_string_lossy().to_string();
+
+    let mut lower = root.clone();
+    if !lower.ends_with(MAIN_SEPARATOR) {
+        lower.push(MAIN_SEPARATOR);
+    }
+    let mut upper = lower.clone();
+    upper.pop();
+    upper.push(char::from_u32(u32::from(MAIN_SEPARATOR) + 1).expect("separator to be ascii"));
+
+    (root, lower, upper)
+}
+
+/// Mark the track with `id` as missing or un-mark it.
 ///
-/// If sqlite somehow does not return what is expected.
-pub fn track_exists(conn: &Connection, track: &Path) -> Result<bool> {
-    let (file_dir, file_stem, file_ext) = path_to_db_comp(track)?;
+/// Missing tracks are kept in the database, but are not returned by functions listing tracks, like [`get_all_tracks`].
+pub fn set_track_missing(conn: &Connection, id: TrackId, missing: bool) -> Result<()> {
+    let missing_since = missing.then(|| chrono::Utc::now().to_rfc3339());
+
+    let mut stmt = conn.prepare_cached(indoc! {"
+        UPDATE tracks SET missing_since=:missing_since
+        WHERE tracks.id=:id;
+    "})?;
+
+    let affected = stmt.execute(named_params! {":id": id, ":missing_since": missing_since})?;
+
+    // update would otherwise fail silently
+    if affected == 0 {
+        bail!("Track not found");
+    }
+
+    Ok(())
+}
+
+/// Move the track at `from` to `to`, keeping all its data like metadata and play statistics.
+pub fn set_track_path(conn: &Connection, from: &Path, to: &Path) -> Result<()> {
+    let (file_dir, file_stem, file_ext) = path_to_db_comp(from)?;
     let file_dir = file_dir.to_string_lossy();
     let file_stem = file_stem.to_string_lossy();
     let file_ext = file_ext.to_string_lossy();
 
-    let mut stmt = conn.prepare(indoc!{"
-        SELECT tracks.id
-        FROM tracks
+    let (new_dir, new_stem, new_ext) = path_to_db_comp(to)?;
+    let new_dir = new_dir.to_string_lossy();
+    let new_stem = new_stem.to_string_lossy();
+    let new_ext = new_ext.to_string_lossy();
+
+    let mut stmt = conn.prepare_cached(indoc!{"
+        UPDATE tracks SET file_dir=:new_dir, file_stem=:new_stem, file_ext=:new_ext
         WHERE tracks.file_dir=:file_dir AND tracks.file_stem=:file_stem AND tracks.file_ext=:file_ext;
     "})?;
 
-    let exists = stmt.exists(
-        named_params! {":file_dir": file_dir, ":file_stem": file_stem, ":file_ext": file_ext},
-    )?;
+    let affected = stmt.execute(named_params! {
+        ":file_dir": file_dir, ":file_stem": file_stem, ":file_ext": file_ext,
+        ":new_dir": new_dir, ":new_stem": new_stem, ":new_ext": new_ext,
+    })?;
 
-    Ok(exists)
+    // update would otherwise fail silently
+    if affected == 0 {
+        bail!("Track not found");
+    }
+
+    Ok(())
 }
 
 /// Get all genres that are currently in the database.
@@ -546,6 +1235,8 @@ pub fn all_distinct_genres(conn: &Connection) -> Result<Vec<String>> {
     let mut stmt = conn.prepare(indoc! {"
         SELECT DISTINCT tracks_metadata.genre
         FROM tracks_metadata
+        INNER JOIN tracks ON tracks.id = tracks_metadata.track
+        WHERE tracks.missing_since IS NULL
         ",
     })?;
 
@@ -568,6 +1259,7 @@ pub fn all_distinct_directories(conn: &Connection) -> Result<Vec<String>> {
     let mut stmt = conn.prepare(indoc! {"
         SELECT DISTINCT tracks.file_dir
         FROM tracks
+        WHERE tracks.missing_since IS NULL
         ",
     })?;
 
@@ -590,7 +1282,7 @@ pub fn all_distinct_directories(conn: &Connection) -> Result<Vec<String>> {
 /// If the database schema does not match what is expected.
 pub fn delete_tracks_artists_mapping_for(
     conn: &Connection,
-    track: Either<&Path, Integer>,
+    track: Either<&Path, TrackId>,
 ) -> Result<usize> {
     let (where_clause, params): (&str, &[(&str, &dyn ToSql)]) = match track {
         Either::Left(path) => {
@@ -631,6 +1323,7 @@ pub fn delete_tracks_artists_mapping_for(
 #[cfg(test)]
 mod tests {
     use std::{
+        collections::HashSet,
         ffi::{OsStr, OsString},
         path::{Path, PathBuf},
         time::Duration,
@@ -640,18 +1333,25 @@ mod tests {
     use pretty_assertions::assert_eq;
 
     use crate::{
+        ids::TrackId,
         new_database::{
             album_insert::AlbumInsertable,
             artist_insert::ArtistInsertable,
             test_utils::{gen_database, test_path},
             track_insert::TrackInsertable,
             track_ops::{
-                AlbumRead, ArtistRead, RowOrdering, TrackRead, all_distinct_directories,
+                AlbumRead, ArtistRead, PlayOutcome, PlayStatsOrder, RowOrdering, TrackPathDuration,
+                TrackPathState, TrackRating, TrackRead, all_distinct_directories,
                 all_distinct_genres, count_all_track_artist_mapping,
-                delete_tracks_artists_mapping_for, get_all_tracks, get_last_position,
-                get_track_from_path, get_tracks_from_album, get_tracks_from_artist,
-                get_tracks_from_directory, get_tracks_from_genre, get_tracks_from_genre_like,
-                set_last_position, track_exists,
+                delete_tracks_artists_mapping_for, get_all_tracks, get_favorite_tracks,
+                get_first_album_track, get_last_position, get_no_autoplay_paths,
+                get_track_durations_below, get_track_from_path, get_track_no_autoplay,
+                get_track_paths_below, get_track_rating, get_track_speed, get_tracks_by_play_stats,
+                get_tracks_from_album, get_tracks_from_artist, get_tracks_from_directory,
+                get_tracks_from_genre, get_tracks_from_genre_like, get_tracks_rated_at_least,
+                record_play_outcome, search_tracks_fts, set_last_played, set_last_position,
+                set_track_favorite, set_track_missing, set_track_no_autoplay, set_track_path,
+                set_track_rating, set_track_speed, track_exists,
             },
         },
         track::TrackMetadata,
@@ -729,7 +1429,7 @@ mod tests {
         assert_eq!(
             all_tracks,
             &[TrackRead {
-                id: 1,
+                id: TrackId(1),
                 file_dir: PathBuf::from("/somewhere"),
                 file_stem: OsString::from("file"),
                 file_ext: OsString::from("ext"),
@@ -904,6 +1604,15 @@ mod tests {
         let res: Vec<String> = res.into_iter().map(|v| v.title.unwrap()).collect();
 
         assert_eq!(&res, &["FileA1", "FileA2"]);
+
+        assert_eq!(
+            get_first_album_track(&db.get_connection(), "AlbumA").unwrap(),
+            Some(test_path(Path::new("/somewhere/fileA1.ext")))
+        );
+        assert_eq!(
+            get_first_album_track(&db.get_connection(), "AlbumC").unwrap(),
+            None
+        );
     }
 
     #[test]
@@ -1071,6 +1780,83 @@ mod tests {
         assert_eq!(&res, &["FileA2"]);
     }
 
+    #[test]
+    fn tracks_search_fts() {
+        let db = gen_database();
+
+        let metadata = TrackMetadata {
+            album: Some("Überalbum".to_string()),
+            album_artist: Some("ArtistA".to_string()),
+            artist: Some("ArtistA".to_string()),
+            artists: Some(vec!["ArtistA".to_string()]),
+            title: Some("FileA1".to_string()),
+            duration: Some(Duration::from_secs(10)),
+            genre: Some("Rock".to_string()),
+            ..Default::default()
+        };
+        let path = &test_path(Path::new("/somewhere/fileA1.ext"));
+        let insertable = TrackInsertable::try_from_track(path, &metadata).unwrap();
+        let track_a1 = insertable
+            .try_insert_or_update(&db.get_connection())
+            .unwrap();
+
+        let metadata = TrackMetadata {
+            artist: Some("ArtistB".to_string()),
+            artists: Some(vec!["ArtistB".to_string()]),
+            title: Some("Second Song".to_string()),
+            duration: Some(Duration::from_secs(10)),
+            genre: Some("Pop".to_string()),
+            ..Default::default()
+        };
+        let path_a2 = &test_path(Path::new("/somewhere/fileA2.ext"));
+        let insertable = TrackInsertable::try_from_track(path_a2, &metadata).unwrap();
+        let _ = insertable
+            .try_insert_or_update(&db.get_connection())
+            .unwrap();
+
+        // sorted, as the order of equally good matches is not defined
+        let search = |input: &str| -> Vec<String> {
+            let mut res: Vec<String> = search_tracks_fts(&db.get_connection(), input)
+                .unwrap()
+                .into_iter()
+                .map(|v| v.title.unwrap())
+                .collect();
+            res.sort();
+            res
+        };
+
+        assert_eq!(search("rock"), ["FileA1"]);
+        assert_eq!(search("art"), ["FileA1", "Second Song"]);
+        assert_eq!(search("pop sec"), ["Second Song"]);
+        assert_eq!(search("uberalbum"), ["FileA1"]);
+        // file name for tracks without a matching title
+        assert_eq!(search("filea2"), ["Second Song"]);
+        assert_eq!(search("*"), ["FileA1", "Second Song"]);
+        // fts syntax is not interpreted
+        assert!(search("\"rock\" AND -").is_empty());
+
+        // index is updated with the metadata
+        let metadata = TrackMetadata {
+            artist: Some("ArtistB".to_string()),
+            artists: Some(vec!["ArtistB".to_string()]),
+            title: Some("Second Song".to_string()),
+            duration: Some(Duration::from_secs(10)),
+            genre: Some("Rock".to_string()),
+            ..Default::default()
+        };
+        let insertable = TrackInsertable::try_from_track(path_a2, &metadata).unwrap();
+        let _ = insertable
+            .try_insert_or_update(&db.get_connection())
+            .unwrap();
+
+        assert!(search("pop").is_empty());
+        assert_eq!(search("rock"), ["FileA1", "Second Song"]);
+
+        set_track_missing(&db.get_connection(), track_a1, true).unwrap();
+
+        assert_eq!(search("rock"), ["Second Song"]);
+    }
+
     #[test]
     fn tracks_by_genre_null() {
         let db = gen_database();
@@ -1245,10 +2031,38 @@ mod tests {
         assert_eq!(err, rusqlite::Error::QueryReturnedNoRows);
     }
 
+    #[test]
+    fn move_track() {
+        let db = gen_database();
+
+        let metadata = TrackMetadata {
+            title: Some("FileA1".to_string()),
+            duration: Some(Duration::from_secs(10)),
+            ..Default::default()
+        };
+        let path = &test_path(Path::new("/somewhere/fileA1.ext"));
+        let insertable = TrackInsertable::try_from_track(path, &metadata).unwrap();
+        let _ = insertable
+            .try_insert_or_update(&db.get_connection())
+            .unwrap();
+        set_last_position(&db.get_connection(), path, Some(Duration::from_secs(5))).unwrap();
+
+        let to = &test_path(Path::new("/elsewhere/Artist/FileA1.ext"));
+        set_track_path(&db.get_connection(), path, to).unwrap();
+
+        assert!(!track_exists(&db.get_connection(), path).unwrap());
+        let res = get_track_from_path(&db.get_connection(), to).unwrap();
+        assert_eq!(res.title, Some("FileA1".to_string()));
+        assert_eq!(res.last_position, Some(Duration::from_secs(5)));
+
+        let err = set_track_path(&db.get_connection(), path, to).unwrap_err();
+        assert_eq!(err.to_string(), "Track not found");
+    }
+
     #[test]
     fn track_read_to_path() {
         let read = TrackRead {
-            id: 0,
+            id: TrackId(0),
             file_dir: PathBuf::from("/path/to/somewhere"),
             file_stem: OsString::from("filename"),
             file_ext: OsString::from("ext"),
@@ -1446,4 +2260,344 @@ mod tests {
 
         assert_eq!(mapping_counts, 0);
     }
+
+    #[test]
+    fn missing_tracks_are_hidden() {
+        let db = gen_database();
+
+        let metadata = TrackMetadata {
+            title: Some("FileA1".to_string()),
+            genre: Some("Rock".to_string()),
+            duration: Some(Duration::from_secs(10)),
+            ..Default::default()
+        };
+        let path_a1 = &test_path(Path::new("/somewhere/dirA/fileA1.ext"));
+        let insertable = TrackInsertable::try_from_track(path_a1, &metadata).unwrap();
+        let track_a1 = insertable
+            .try_insert_or_update(&db.get_connection())
+            .unwrap();
+
+        let metadata = TrackMetadata {
+            title: Some("FileB1".to_string()),
+            duration: Some(Duration::from_secs(10)),
+            ..Default::default()
+        };
+        let path_b1 = &test_path(Path::new("/somewhere/dirB/fileB1.ext"));
+        let insertable = TrackInsertable::try_from_track(path_b1, &metadata).unwrap();
+        let _ = insertable
+            .try_insert_or_update(&db.get_connection())
+            .unwrap();
+
+        set_track_missing(&db.get_connection(), track_a1, true).unwrap();
+
+        let all: Vec<PathBuf> = get_all_tracks(&db.get_connection(), RowOrdering::IdAsc)
+            .unwrap()
+            .iter()
+            .map(TrackRead::as_pathbuf)
+            .collect();
+        assert_eq!(all, &[path_b1.clone()]);
+        assert_eq!(
+            all_distinct_directories(&db.get_connection()).unwrap(),
+            &[test_path(Path::new("/somewhere/dirB")).to_string_lossy()]
+        );
+        assert!(
+            all_distinct_genres(&db.get_connection())
+                .unwrap()
+                .iter()
+                .all(|v| v != "Rock")
+        );
+        // the tombstone still exists
+        assert!(track_exists(&db.get_connection(), path_a1).unwrap());
+        assert_eq!(
+            get_track_paths_below(
+                &db.get_connection(),
+                &test_path(Path::new("/somewhere/dirA"))
+            )
+            .unwrap(),
+            &[TrackPathState {
+                id: track_a1,
+                path: path_a1.clone(),
+                missing: true
+            }]
+        );
+
+        // re-scanning the file restores it
+        let metadata = TrackMetadata {
+            title: Some("FileA1".to_string()),
+            ..Default::default()
+        };
+        let insertable = TrackInsertable::try_from_track(path_a1, &metadata).unwrap();
+        let _ = insertable
+            .try_insert_or_update(&db.get_connection())
+            .unwrap();
+
+        assert_eq!(
+            get_all_tracks(&db.get_connection(), RowOrdering::IdAsc)
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn track_durations_below() {
+        let db = gen_database();
+
+        let mut expected = Vec::new();
+        for (path, duration) in [
+            ("/somewhere/dirA/fileA1.ext", Some(Duration::from_secs(10))),
+            ("/somewhere/dirA/sub/fileA2.ext", None),
+            (
+                "/somewhere/dirAB/fileAB1.ext",
+                Some(Duration::from_secs(20)),
+            ),
+        ] {
+            let path = test_path(Path::new(path));
+            let metadata = TrackMetadata {
+                duration,
+                ..Default::default()
+            };
+            let insertable = TrackInsertable::try_from_track(&path, &metadata).unwrap();
+            let _ = insertable
+                .try_insert_or_update(&db.get_connection())
+                .unwrap();
+
+            expected.push(TrackPathDuration { path, duration });
+        }
+
+        // "dirAB" only shares a prefix and is not a sub-directory
+        expected.truncate(2);
+
+        assert_eq!(
+            get_track_durations_below(
+                &db.get_connection(),
+                &test_path(Path::new("/somewhere/dirA"))
+            )
+            .unwrap(),
+            expected
+        );
+        // with a trailing separator
+        assert_eq!(
+            get_track_durations_below(
+                &db.get_connection(),
+                &test_path(Path::new("/somewhere/dirA")).join("")
+            )
+            .unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn rating_and_favorite() {
+        let db = gen_database();
+
+        let mut paths = Vec::new();
+        for path in ["/somewhere/fileA.ext", "/somewhere/fileB.ext"] {
+            let path = test_path(Path::new(path));
+            let metadata = TrackMetadata {
+                duration: Some(Duration::from_secs(10)),
+                ..Default::default()
+            };
+            let insertable = TrackInsertable::try_from_track(&path, &metadata).unwrap();
+            let _ = insertable
+                .try_insert_or_update(&db.get_connection())
+                .unwrap();
+            paths.push(path);
+        }
+        let conn = db.get_connection();
+
+        assert_eq!(
+            get_track_rating(&conn, &paths[0]).unwrap(),
+            TrackRating::default()
+        );
+
+        set_track_rating(&conn, &paths[0], 4).unwrap();
+        set_track_rating(&conn, &paths[1], 2).unwrap();
+        set_track_favorite(&conn, &paths[1], true).unwrap();
+        assert_eq!(
+            get_track_rating(&conn, &paths[1]).unwrap(),
+            TrackRating {
+                rating: 2,
+                favorite: true
+            }
+        );
+
+        // above the maximum
+        assert!(set_track_rating(&conn, &paths[0], 6).is_err());
+        // not in the database
+        assert!(
+            set_track_favorite(&conn, &test_path(Path::new("/somewhere/else.ext")), true).is_err()
+        );
+
+        let res: Vec<PathBuf> = get_favorite_tracks(&conn, RowOrdering::IdAsc)
+            .unwrap()
+            .into_iter()
+            .map(|v| v.as_pathbuf())
+            .collect();
+        assert_eq!(res, [paths[1].clone()]);
+
+        let res: Vec<PathBuf> = get_tracks_rated_at_least(&conn, 3, RowOrdering::IdAsc)
+            .unwrap()
+            .into_iter()
+            .map(|v| v.as_pathbuf())
+            .collect();
+        assert_eq!(res, [paths[0].clone()]);
+        assert_eq!(
+            get_tracks_rated_at_least(&conn, 1, RowOrdering::IdAsc)
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn play_stats() {
+        let db = gen_database();
+
+        let mut paths = Vec::new();
+        for path in [
+            "/somewhere/fileA.ext",
+            "/somewhere/fileB.ext",
+            "/somewhere/fileC.ext",
+        ] {
+            let path = test_path(Path::new(path));
+            let metadata = TrackMetadata {
+                duration: Some(Duration::from_secs(10)),
+                ..Default::default()
+            };
+            let insertable = TrackInsertable::try_from_track(&path, &metadata).unwrap();
+            let _ = insertable
+                .try_insert_or_update(&db.get_connection())
+                .unwrap();
+            paths.push(path);
+        }
+        let conn = db.get_connection();
+
+        let get = |order| -> Vec<(PathBuf, u32, u32)> {
+            get_tracks_by_play_stats(&conn, order, 10)
+                .unwrap()
+                .into_iter()
+                .map(|(track, stats)| (track.as_pathbuf(), stats.play_count, stats.skip_count))
+                .collect()
+        };
+
+        // nothing played yet
+        assert!(get(PlayStatsOrder::MostPlayed).is_empty());
+        assert!(get(PlayStatsOrder::RecentlyPlayed).is_empty());
+
+        for path in [&paths[1], &paths[0]] {
+            set_last_played(&conn, path).unwrap();
+        }
+        record_play_outcome(&conn, &paths[0], PlayOutcome::Completed).unwrap();
+        record_play_outcome(&conn, &paths[1], PlayOutcome::Completed).unwrap();
+        record_play_outcome(&conn, &paths[1], PlayOutcome::Completed).unwrap();
+        record_play_outcome(&conn, &paths[1], PlayOutcome::Skipped).unwrap();
+        // not in the database
+        assert!(
+            record_play_outcome(
+                &conn,
+                &test_path(Path::new("/somewhere/else.ext")),
+                PlayOutcome::Completed
+            )
+            .is_err()
+        );
+
+        assert_eq!(
+            get(PlayStatsOrder::MostPlayed),
+            [(paths[1].clone(), 2, 1), (paths[0].clone(), 1, 0)]
+        );
+        assert_eq!(get(PlayStatsOrder::MostSkipped), [(paths[1].clone(), 2, 1)]);
+        assert_eq!(get(PlayStatsOrder::RecentlyPlayed).len(), 2);
+        assert_eq!(
+            get_tracks_by_play_stats(&conn, PlayStatsOrder::MostPlayed, 1)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(
+            get_tracks_by_play_stats(&conn, PlayStatsOrder::RecentlyPlayed, 1).unwrap()[0]
+                .1
+                .last_played
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn speed_override() {
+        let db = gen_database();
+
+        let path = &test_path(Path::new("/somewhere/file.ext"));
+        let metadata = TrackMetadata {
+            duration: Some(Duration::from_secs(10)),
+            ..Default::default()
+        };
+        let insertable = TrackInsertable::try_from_track(path, &metadata).unwrap();
+        let _ = insertable
+            .try_insert_or_update(&db.get_connection())
+            .unwrap();
+        let conn = db.get_connection();
+
+        assert_eq!(get_track_speed(&conn, path).unwrap(), None);
+
+        set_track_speed(&conn, path, Some(7)).unwrap();
+        assert_eq!(get_track_speed(&conn, path).unwrap(), Some(7));
+
+        set_track_speed(&conn, path, None).unwrap();
+        assert_eq!(get_track_speed(&conn, path).unwrap(), None);
+
+        // not in the database
+        let other = &test_path(Path::new("/somewhere/else.ext"));
+        assert!(get_track_speed(&conn, other).is_err());
+        assert!(set_track_speed(&conn, other, Some(7)).is_err());
+    }
+
+    #[test]
+    fn no_autoplay() {
+        let db = gen_database();
+
+        let metadata = TrackMetadata {
+            duration: Some(Duration::from_secs(10)),
+            ..Default::default()
+        };
+        let path = &test_path(Path::new("/somewhere/intro.ext"));
+        let other = &test_path(Path::new("/somewhere/song.ext"));
+        for path in [path, other] {
+            let insertable = TrackInsertable::try_from_track(path, &metadata).unwrap();
+            let _ = insertable
+                .try_insert_or_update(&db.get_connection())
+                .unwrap();
+        }
+        let conn = db.get_connection();
+
+        assert!(!get_track_no_autoplay(&conn, path).unwrap());
+        assert!(get_no_autoplay_paths(&conn).unwrap().is_empty());
+
+        set_track_no_autoplay(&conn, path, true).unwrap();
+        assert!(get_track_no_autoplay(&conn, path).unwrap());
+        assert!(!get_track_no_autoplay(&conn, other).unwrap());
+        assert_eq!(
+            get_no_autoplay_paths(&conn).unwrap(),
+            HashSet::from([path.clone()])
+        );
+
+        set_track_no_autoplay(&conn, path, false).unwrap();
+        assert!(get_no_autoplay_paths(&conn).unwrap().is_empty());
+
+        // not in the database
+        let missing = &test_path(Path::new("/somewhere/else.ext"));
+        assert!(get_track_no_autoplay(&conn, missing).is_err());
+        assert!(set_track_no_autoplay(&conn, missing, true).is_err());
+    }
+
+    #[test]
+    fn rating_cycles() {
+        let rating = |rating| TrackRating {
+            rating,
+            favorite: false,
+        };
+
+        assert_eq!(rating(0).next_rating(), 1);
+        assert_eq!(rating(4).next_rating(), 5);
+        assert_eq!(rating(5).next_rating(), 0);
+    }
 }
@@ -0,0 +1,172 @@
+//! Watch the music directories for changes and update the database incrementally, see [`ScanPolicy::Watch`].
+//!
+//! [`ScanPolicy::Watch`]: crate::config::v2::server::metadata::ScanPolicy::Watch
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::runtime::Handle;
+use tokio::sync::mpsc;
+
+use super::{Database, unavailable_dirs};
+use crate::config::SharedServerSettings;
+use crate::track::cue;
+use crate::utils::filetype_supported;
+
+/// How long to wait for further changes before updating the database, as files are often written in multiple steps.
+const DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// Watches the music directories for as long as it is alive.
+pub struct LibraryWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+impl std::fmt::Debug for LibraryWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LibraryWatcher").finish_non_exhaustive()
+    }
+}
+
+impl LibraryWatcher {
+    /// Start watching all configured music directories recursively.
+    ///
+    /// Added and modified tracks are (re-)read, tracks whose files got removed are marked as missing.
+    /// `on_change` is called after changes got applied to `db`.
+    ///
+    /// Requires that the function is called on a thread with a entered tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// - if the watcher cannot be created
+    /// - if a music directory cannot be watched, like when it does not exist
+    pub fn new<F>(db: Database, config: SharedServerSettings, on_change: F) -> Result<Self>
+    where
+        F: Fn() + Send + Sync + Clone + 'static,
+    {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        let mut watcher =
+            notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+                // reading a file does not change it
+                Ok(ev) if matches!(ev.kind, EventKind::Access(_)) => (),
+                Ok(ev) => {
+                    let _ = tx.send(ev.paths);
+                }
+                Err(err) => warn!("Error watching music directories: {err:#}"),
+            })
+            .context("create watcher")?;
+
+        let music_dirs = config.read().settings.player.music_dirs.clone();
+        for dir in music_dirs {
+            // the database stores canonical paths, see "scan_path"
+            let dir = shellexpand::path::tilde(&dir)
+                .canonicalize()
+                .with_context(|| dir.display().to_string())?;
+            watcher
+                .watch(&dir, RecursiveMode::Recursive)
+                .with_context(|| format!("watch {}", dir.display()))?;
+            info!("Watching {dir:#?} for changes");
+        }
+
+        Handle::current().spawn(async move {
+            // ends once the watcher, and with it the sender, is dropped
+            while let Some(paths) = rx.recv().await {
+                let mut changed: HashSet<PathBuf> = paths.into_iter().collect();
+                while let Ok(Some(paths)) = tokio::time::timeout(DEBOUNCE, rx.recv()).await {
+                    changed.extend(paths);
+                }
+
+                let config = config.clone();
+                let on_change = on_change.clone();
+                db.spawn_worker(move |db| {
+                    if Self::apply_changes(&db, &config, changed) {
+                        on_change();
+                    }
+                });
+            }
+        });
+
+        Ok(Self { _watcher: watcher })
+    }
+
+    /// Update the database for all `changed` paths, which may be files or directories.
+    ///
+    /// Returns whether any track could have changed.
+    fn apply_changes(
+        db: &Database,
+        config: &SharedServerSettings,
+        changed: HashSet<PathBuf>,
+    ) -> bool {
+        let (separators, removable_dirs) = {
+            let config = config.read();
+            (
+                config.settings.metadata.artist_separators.clone(),
+                config.settings.player.removable_dirs.clone(),
+            )
+        };
+        let separators: Arc<[String]> = separators.into();
+        let separator_strs: Vec<&str> = separators.iter().map(String::as_str).collect();
+        let unavailable = unavailable_dirs(&removable_dirs);
+
+        let mut any_changed = false;
+        for path in changed {
+            if path.is_dir() {
+                // directories moved into a music directory only have a event for themselves
+                let walker = walkdir::WalkDir::new(&path)
+                    .follow_links(true)
+                    .into_iter()
+                    .filter_map(Result::ok)
+                    .filter(|v| v.file_type().is_file())
+                    .filter(|v| is_library_file(v.path()));
+                Database::process_iter(walker, db, &path, false, &separators, None);
+                any_changed = true;
+            } else if path.is_file() {
+                if !is_library_file(&path) {
+                    continue;
+                }
+                any_changed |= Self::process_changed_file(db, &path, &separator_strs);
+                continue;
+            }
+
+            // the path was removed, or files below a directory may have been replaced
+            match Database::update_missing_tracks(db, &path, &unavailable) {
+                Ok((0, 0)) => (),
+                Ok((marked, restored)) => {
+                    info!("Marked {marked} tracks as missing and restored {restored} in {path:#?}");
+                    any_changed = true;
+                }
+                Err(err) => warn!("Error updating missing tracks in {path:#?}: {err:#?}"),
+            }
+        }
+
+        any_changed
+    }
+
+    /// Read the metadata of a added or modified file, which may be a cue sheet.
+    ///
+    /// Returns whether any track was created or updated.
+    fn process_changed_file(db: &Database, path: &Path, separators: &[&str]) -> bool {
+        if !cue::is_cue_sheet(path) {
+            return Database::process_file(db, path, true, separators);
+        }
+
+        match cue::track_paths(path) {
+            Ok(track_paths) => track_paths.iter().fold(false, |acc, track_path| {
+                Database::process_file(db, track_path, true, separators) | acc
+            }),
+            Err(err) => {
+                warn!("Error reading cue sheet {path:#?}: {err:#?}");
+                false
+            }
+        }
+    }
+}
+
+/// Check if `path` is a file that is added to the library, the same as in [`Database::scan_path`].
+fn is_library_file(path: &Path) -> bool {
+    (filetype_supported(path) || cue::is_cue_sheet(path)) && !cue::has_cue_sheet(path)
+}
@@ -0,0 +1,326 @@
+//! Organize the library by moving tracks to a path built from their tags, with a pattern like
+//! `{artist}/{album}/[{track} - ]{title}`.
+//!
+//! Supported fields are `{artist}`, `{albumartist}` (falls back to the artist), `{album}`, `{title}` (falls back to
+//! the file name), `{track}` (the track number, with at least 2 digits) and `{genre}`.
+//! Missing fields are shown as unknown, unless they are inside an optional section in square brackets, like in
+//! [`title_format`](crate::track::title_format).
+//! Characters that are not allowed in file names on some systems, like `/` and `:`, are replaced with `_` in values.
+//!
+//! The pattern is relative to the music directory a track is in, and the file extension of a track is always kept.
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+
+use crate::common::const_unknown::{UNKNOWN_ALBUM, UNKNOWN_ARTIST, UNKNOWN_TITLE};
+use crate::new_database::Database;
+use crate::new_database::track_ops::{self, RowOrdering};
+use crate::new_database::{bookmark_ops, queue_ops};
+use crate::track::title_format::render_template;
+use crate::track::{MetadataOptions, TrackMetadata, cue, parse_metadata_from_file};
+
+/// The default pattern, which sorts tracks into a directory per artist and album.
+pub const DEFAULT_ORGANIZE_PATTERN: &str = "{artist}/{album}/[{track} - ]{title}";
+
+/// A single track to move.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrganizeMove {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+/// All tracks to move, see [`plan_organize`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct OrganizePlan {
+    pub moves: Vec<OrganizeMove>,
+    /// Tracks which are not moved, with the reason why
+    pub skipped: Vec<(PathBuf, String)>,
+}
+
+/// Make `value` usable as (part of) a single path component.
+///
+/// Returns `None` if nothing usable is left.
+fn sanitize(value: &str) -> Option<String> {
+    let value: String = value
+        .chars()
+        .map(|ch| match ch {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            ch if ch.is_control() => '_',
+            ch => ch,
+        })
+        .collect();
+    // leading dots would hide the file, trailing dots are dropped on windows
+    let value = value.trim_matches(|ch: char| ch.is_whitespace() || ch == '.');
+
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Get the value of the pattern field `name`, see [`render_template`].
+fn organize_field(
+    metadata: &TrackMetadata,
+    file_stem: &str,
+    name: &str,
+) -> Option<Option<Cow<'static, str>>> {
+    let value = match name {
+        "artist" => metadata.artist.as_deref().and_then(sanitize),
+        "albumartist" => metadata
+            .album_artist
+            .as_deref()
+            .and_then(sanitize)
+            .or_else(|| metadata.artist.as_deref().and_then(sanitize)),
+        "album" => metadata.album.as_deref().and_then(sanitize),
+        "title" => metadata
+            .title
+            .as_deref()
+            .and_then(sanitize)
+            .or_else(|| sanitize(file_stem)),
+        "track" => metadata.track_number.map(|v| format!("{v:02}")),
+        "genre" => metadata.genre.as_deref().and_then(sanitize),
+        _ => return None,
+    };
+
+    Some(value.map(Cow::Owned))
+}
+
+/// Get the path in `root` the track at `path` with `metadata` should be at, according to `pattern`.
+///
+/// Returns `None` if `pattern` does not result in a file name.
+#[must_use]
+pub fn target_path(
+    root: &Path,
+    path: &Path,
+    metadata: &TrackMetadata,
+    pattern: &str,
+) -> Option<PathBuf> {
+    let file_stem = path.file_stem()?.to_string_lossy();
+    let rendered = render_template(
+        pattern,
+        |name| organize_field(metadata, &file_stem, name),
+        |name| match name {
+            "artist" | "albumartist" => UNKNOWN_ARTIST,
+            "album" => UNKNOWN_ALBUM,
+            "title" => UNKNOWN_TITLE,
+            "genre" => "Unknown Genre",
+            _ => "00",
+        },
+    );
+
+    let mut target = root.to_path_buf();
+    let mut has_file_name = false;
+    // never leave the root, regardless of what is in the pattern
+    for component in rendered.split('/').map(str::trim) {
+        if component.is_empty() || component == "." || component == ".." {
+            continue;
+        }
+        target.push(component);
+        has_file_name = true;
+    }
+    if !has_file_name {
+        return None;
+    }
+
+    if let Some(ext) = path.extension() {
+        let mut file_name = target.file_name()?.to_os_string();
+        file_name.push(".");
+        file_name.push(ext);
+        target.set_file_name(file_name);
+    }
+
+    Some(target)
+}
+
+/// Expand `~` in the configured `music_dirs`.
+fn expand_dirs(music_dirs: &[PathBuf]) -> Vec<PathBuf> {
+    music_dirs
+        .iter()
+        .map(|dir| shellexpand::path::tilde(dir).into_owned())
+        .collect()
+}
+
+/// Get the music directory `path` is in, the most specific one if they are nested.
+fn music_root<'a>(music_dirs: &'a [PathBuf], path: &Path) -> Option<&'a Path> {
+    music_dirs
+        .iter()
+        .filter(|dir| path.starts_with(dir))
+        .max_by_key(|dir| dir.components().count())
+        .map(PathBuf::as_path)
+}
+
+/// Get where all tracks in the database should be moved to according to `pattern`, without changing anything.
+///
+/// Tracks which are already at their target are left out.
+///
+/// # Errors
+///
+/// - if the tracks cannot be read from the database
+pub fn plan_organize(db: &Database, music_dirs: &[PathBuf], pattern: &str) -> Result<OrganizePlan> {
+    let tracks = track_ops::get_all_tracks(&db.get_connection(), RowOrdering::IdAsc)
+        .context("get all tracks")?;
+    let music_dirs = expand_dirs(music_dirs);
+    let options = MetadataOptions {
+        album: true,
+        album_artist: true,
+        artist: true,
+        title: true,
+        track_number: true,
+        genre: true,
+        ..Default::default()
+    };
+
+    let mut plan = OrganizePlan::default();
+    let mut targets = HashSet::new();
+    for track in tracks {
+        let path = track.as_pathbuf();
+        let mut skip = |reason: &str| plan.skipped.push((path.clone(), reason.to_string()));
+
+        // the tracks of a cue sheet share a single file
+        if cue::split_track_path(&path).is_some() {
+            skip("part of a cue sheet");
+            continue;
+        }
+        let Some(root) = music_root(&music_dirs, &path) else {
+            skip("not in a music directory");
+            continue;
+        };
+        if !path.is_file() {
+            skip("file does not exist");
+            continue;
+        }
+        let metadata = match parse_metadata_from_file(&path, options) {
+            Ok(v) => v,
+            Err(err) => {
+                skip(&format!("cannot read tags: {err:#}"));
+                continue;
+            }
+        };
+        let Some(target) = target_path(root, &path, &metadata, pattern) else {
+            skip("pattern results in no file name");
+            continue;
+        };
+
+        if target == path {
+            continue;
+        }
+        if target.exists() {
+            skip(&format!("\"{}\" already exists", target.display()));
+            continue;
+        }
+        if !targets.insert(target.clone()) {
+            skip(&format!(
+                "\"{}\" is the target of another track",
+                target.display()
+            ));
+            continue;
+        }
+
+        plan.moves.push(OrganizeMove {
+            from: path,
+            to: target,
+        });
+    }
+
+    Ok(plan)
+}
+
+/// Move a single track on disk and in the database.
+fn move_track(db: &Database, mv: &OrganizeMove) -> Result<()> {
+    if mv.to.exists() {
+        bail!("\"{}\" already exists", mv.to.display());
+    }
+    if let Some(parent) = mv.to.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("create directory \"{}\"", parent.display()))?;
+    }
+    std::fs::rename(&mv.from, &mv.to).context("move file")?;
+
+    let conn = db.get_connection();
+    if let Err(err) = track_ops::set_track_path(&conn, &mv.from, &mv.to) {
+        // keep the file where the database expects it
+        let _ = std::fs::rename(&mv.to, &mv.from);
+        return Err(err.context("update database"));
+    }
+    let (from, to) = (mv.from.to_string_lossy(), mv.to.to_string_lossy());
+    queue_ops::rename_path(&conn, &from, &to).context("update stored queues")?;
+    bookmark_ops::rename_path(&conn, &from, &to).context("update bookmarks")?;
+
+    Ok(())
+}
+
+/// Remove `dir` and its parents up to `root`, as long as they are empty.
+fn remove_empty_dirs(root: &Path, dir: Option<&Path>) {
+    let mut dir = dir;
+    while let Some(current) = dir {
+        if current == root || !current.starts_with(root) {
+            break;
+        }
+        // fails if the directory is not empty
+        if std::fs::remove_dir(current).is_err() {
+            break;
+        }
+        dir = current.parent();
+    }
+}
+
+/// Move all tracks of `moves` on disk and update their paths in the database, including stored queues.
+///
+/// Directories left empty are removed. Returns the moves that failed, with the error.
+pub fn apply_organize(
+    db: &Database,
+    music_dirs: &[PathBuf],
+    moves: &[OrganizeMove],
+) -> Vec<(OrganizeMove, String)> {
+    let music_dirs = expand_dirs(music_dirs);
+    let mut failed = Vec::new();
+    for mv in moves {
+        if let Err(err) = move_track(db, mv) {
+            failed.push((mv.clone(), format!("{err:#}")));
+            continue;
+        }
+        if let Some(root) = music_root(&music_dirs, &mv.from) {
+            remove_empty_dirs(root, mv.from.parent());
+        }
+    }
+
+    failed
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use pretty_assertions::assert_eq;
+
+    use super::{DEFAULT_ORGANIZE_PATTERN, target_path};
+    use crate::track::TrackMetadata;
+
+    #[test]
+    fn should_build_target_paths() {
+        let root = Path::new("/music");
+        let path = Path::new("/music/unsorted/some file.mp3");
+        let mut metadata = TrackMetadata {
+            artist: Some("AC/DC".to_string()),
+            album: Some("Live: 1992".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            target_path(root, path, &metadata, DEFAULT_ORGANIZE_PATTERN),
+            Some(PathBuf::from("/music/AC_DC/Live_ 1992/some file.mp3"))
+        );
+
+        metadata.title = Some("...Title.".to_string());
+        metadata.track_number = Some(3);
+        assert_eq!(
+            target_path(root, path, &metadata, DEFAULT_ORGANIZE_PATTERN),
+            Some(PathBuf::from("/music/AC_DC/Live_ 1992/03 - Title.mp3"))
+        );
+        assert_eq!(
+            target_path(root, path, &metadata, "{genre}/../{albumartist}/{title}"),
+            Some(PathBuf::from("/music/Unknown Genre/AC_DC/Title.mp3"))
+        );
+        assert_eq!(target_path(root, path, &metadata, "[{genre}]/"), None);
+    }
+}
@@ -0,0 +1,257 @@
+//! A content filter for shared family devices, which hides explicit podcasts and tracks of some genres
+//! from all views and keeps them from being queued.
+//!
+//! The filter is configured in its own file [`FILE_NAME`] in the main config directory, which applies to all profiles.
+//! termusic only ever reads that file, so it can be protected from changes by making it read-only for the user
+//! termusic runs as, unlike the other config files, which termusic writes itself.
+//! Changes to it only apply after restarting the server and the TUI.
+//!
+//! Once the server ran with the file, its library database is marked and the server refuses to start without the file,
+//! so that deleting it does not disable the filter. To disable the filter, keep the file with nothing hidden.
+//!
+//! Example:
+//!
+//! ```toml
+//! hide_explicit = true
+//! genres = ["Horror", "Metal"]
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use rusqlite::{Connection, OptionalExtension, named_params};
+use serde::{Deserialize, Serialize};
+
+use crate::track::{MetadataOptions, parse_metadata_from_file};
+
+/// The filename of the filter in the main config directory
+pub const FILE_NAME: &str = "parental.toml";
+
+/// The key in the `config` table of the library database marking that the server ran with the filter file
+const DB_MARKER_KEY: &str = "parental_filter_since";
+
+/// What to hide, see the [module](self) documentation.
+///
+/// Nothing is hidden by default, or if there is no filter file.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(default)] // allow missing fields and fill them with the `..Self::default()` in this struct
+pub struct ParentalFilter {
+    /// Hide podcasts and episodes marked as explicit
+    pub hide_explicit: bool,
+    /// Hide tracks with any of these genres, compared case-insensitively to each genre of a track
+    pub genres: Vec<String>,
+}
+
+impl ParentalFilter {
+    /// Read the filter from `path`, the default if the file does not exist.
+    ///
+    /// # Errors
+    ///
+    /// - if the file exists, but cannot be read or parsed, as silently ignoring the filter would defeat its purpose
+    pub fn from_file(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("read parental filter \"{}\"", path.display()))?;
+        let filter = toml::from_str(&data)
+            .with_context(|| format!("parse parental filter \"{}\"", path.display()))?;
+        info!("Parental filter from \"{}\" is active", path.display());
+
+        Ok(filter)
+    }
+
+    /// Read the filter from the main config directory, regardless of the [profile](crate::utils::set_app_profile).
+    pub fn from_config_path() -> Result<Self> {
+        Self::from_file(&config_path()?)
+    }
+
+    /// Fail closed once the filter is set up, so that deleting the filter file does not disable it.
+    ///
+    /// Marks the library database `conn` while the file in the main config directory exists.
+    /// Once marked, the file is required, see the [module](self) documentation.
+    ///
+    /// # Errors
+    ///
+    /// - if the database is marked, but the file does not exist
+    /// - if the database cannot be read or written
+    pub fn check_set_up(conn: &Connection) -> Result<()> {
+        check_set_up_at(&config_path()?, conn)
+    }
+
+    /// Get whether anything is hidden.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.hide_explicit || !self.genres.is_empty()
+    }
+
+    /// Get whether something marked as `explicit` may be shown.
+    #[must_use]
+    pub fn allows_explicit(&self, explicit: Option<bool>) -> bool {
+        !(self.hide_explicit && explicit == Some(true))
+    }
+
+    /// Get whether a track tagged with `genre` may be shown.
+    ///
+    /// A genre tag can contain multiple genres, separated by `;`, `/` or `,`.
+    #[must_use]
+    pub fn allows_genre(&self, genre: Option<&str>) -> bool {
+        let Some(genre) = genre else {
+            return true;
+        };
+
+        !genre
+            .split([';', '/', ','])
+            .map(|part| part.trim().to_lowercase())
+            .any(|part| self.genres.iter().any(|v| v.to_lowercase() == part))
+    }
+
+    /// Get whether the track file at `path` may be shown, by reading its genre.
+    ///
+    /// Files whose tags cannot be read are allowed, like they are shown with their file name only.
+    #[must_use]
+    pub fn allows_file(&self, path: &Path) -> bool {
+        if self.genres.is_empty() {
+            return true;
+        }
+
+        let genre = parse_metadata_from_file(
+            path,
+            MetadataOptions {
+                genre: true,
+                ..Default::default()
+            },
+        )
+        .ok()
+        .and_then(|v| v.genre);
+
+        self.allows_genre(genre.as_deref())
+    }
+}
+
+/// Get the path of the filter file in the main config directory.
+fn config_path() -> Result<PathBuf> {
+    Ok(dirs::config_dir()
+        .ok_or_else(|| anyhow!("failed to find os config dir."))?
+        .join("termusic")
+        .join(FILE_NAME))
+}
+
+/// [`ParentalFilter::check_set_up`], with the filter file at `path`.
+fn check_set_up_at(path: &Path, conn: &Connection) -> Result<()> {
+    if path.exists() {
+        let now = chrono::Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO config(key, value) VALUES (:key, :value) ON CONFLICT(key) DO NOTHING;",
+            named_params! {":key": DB_MARKER_KEY, ":value": now},
+        )
+        .context("mark the library database as having a parental filter")?;
+
+        return Ok(());
+    }
+
+    let since: Option<String> = conn
+        .query_row(
+            "SELECT value FROM config WHERE key = :key;",
+            named_params! {":key": DB_MARKER_KEY},
+            |row| row.get(0),
+        )
+        .optional()
+        .context("read the parental filter marker of the library database")?;
+    if let Some(since) = since {
+        bail!(
+            "The parental filter is set up since {since}, but \"{}\" is missing; restore it to start, or keep it with nothing hidden to disable the filter",
+            path.display()
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+    use rusqlite::Connection;
+
+    use super::{ParentalFilter, check_set_up_at};
+
+    /// Open a in-memory database with only the `config` table of the library database.
+    fn gen_config_database() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE config(key TEXT NOT NULL PRIMARY KEY, value TEXT NOT NULL);",
+        )
+        .unwrap();
+
+        conn
+    }
+
+    #[test]
+    fn should_allow_everything_by_default() {
+        let filter = ParentalFilter::default();
+
+        assert!(!filter.is_enabled());
+        assert!(filter.allows_explicit(Some(true)));
+        assert!(filter.allows_genre(Some("Horror")));
+    }
+
+    #[test]
+    fn should_hide_explicit() {
+        let filter = ParentalFilter {
+            hide_explicit: true,
+            ..Default::default()
+        };
+
+        assert!(!filter.allows_explicit(Some(true)));
+        assert!(filter.allows_explicit(Some(false)));
+        // feeds that do not say are shown
+        assert!(filter.allows_explicit(None));
+    }
+
+    #[test]
+    fn should_hide_genres() {
+        let filter = ParentalFilter {
+            hide_explicit: false,
+            genres: vec!["Horror".to_string(), "Metal".to_string()],
+        };
+
+        assert!(!filter.allows_genre(Some("horror")));
+        assert!(!filter.allows_genre(Some("Rock; Metal")));
+        assert!(!filter.allows_genre(Some("Pop/Metal")));
+        // only whole genres
+        assert!(filter.allows_genre(Some("Heavy Metal")));
+        assert!(filter.allows_genre(Some("Pop")));
+        assert!(filter.allows_genre(None));
+    }
+
+    #[test]
+    fn should_parse_filter() {
+        let filter: ParentalFilter = toml::from_str("genres = [\"Horror\"]").unwrap();
+
+        assert_eq!(
+            filter,
+            ParentalFilter {
+                hide_explicit: false,
+                genres: vec!["Horror".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn should_require_file_once_set_up() {
+        let conn = gen_config_database();
+        let existing = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        let missing = Path::new(env!("CARGO_MANIFEST_DIR")).join("parental-missing.toml");
+
+        // never set up
+        assert!(check_set_up_at(&missing, &conn).is_ok());
+
+        assert!(check_set_up_at(&existing, &conn).is_ok());
+        // set up, the file was deleted
+        assert!(check_set_up_at(&missing, &conn).is_err());
+        assert!(check_set_up_at(&existing, &conn).is_ok());
+    }
+}
@@ -1,4 +1,6 @@
 #![allow(clippy::module_name_repetitions)]
+use std::path::PathBuf;
+
 use anyhow::{Context, anyhow, bail};
 
 // using lower mod to restrict clippy
@@ -10,6 +12,10 @@ mod protobuf {
 pub use protobuf::*;
 
 use crate::config::v2::server::LoopMode;
+use crate::ids::{EpisodeId, PodcastId};
+use crate::podcast::episode::{Chapter as EpisodeChapter, Episode};
+use crate::podcast::feed_settings::FeedSettings;
+use crate::podcast::{EpData, Podcast};
 
 // implement transform function for easy use
 impl From<protobuf::Duration> for std::time::Duration {
@@ -74,6 +80,8 @@ pub struct PlayerProgress {
     pub position: Option<PlayerTimeUnit>,
     /// Total duration of the currently playing track, if there is a known total duration
     pub total_duration: Option<PlayerTimeUnit>,
+    /// The section of the current track that is looped
+    pub ab_repeat: AbRepeat,
 }
 
 impl From<protobuf::PlayerTime> for PlayerProgress {
@@ -81,6 +89,7 @@ impl From<protobuf::PlayerTime> for PlayerProgress {
         Self {
             position: value.position.map(Into::into),
             total_duration: value.total_duration.map(Into::into),
+            ab_repeat: value.ab_repeat.map(Into::into).unwrap_or_default(),
         }
     }
 }
@@ -90,6 +99,62 @@ impl From<PlayerProgress> for protobuf::PlayerTime {
         Self {
             position: value.position.map(Into::into),
             total_duration: value.total_duration.map(Into::into),
+            ab_repeat: Some(value.ab_repeat.into()),
+        }
+    }
+}
+
+/// The section of the current track to play in a loop, see [`AbRepeat::set_a`] and [`AbRepeat::set_b`].
+///
+/// Positions are relative to the current track, like [`PlayerProgress::position`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AbRepeat {
+    /// Start of the section
+    pub a: Option<PlayerTimeUnit>,
+    /// End of the section, only ever set together with `a`
+    pub b: Option<PlayerTimeUnit>,
+}
+
+impl AbRepeat {
+    /// Start a new section at `position`, the end has to be set again.
+    pub fn set_a(&mut self, position: PlayerTimeUnit) {
+        self.a = Some(position);
+        self.b = None;
+    }
+
+    /// End the section at `position`.
+    ///
+    /// The section starts at the beginning of the track if there is no start yet,
+    /// and both points are swapped if `position` is before the start.
+    pub fn set_b(&mut self, position: PlayerTimeUnit) {
+        let a = self.a.unwrap_or_default();
+        self.a = Some(a.min(position));
+        self.b = Some(a.max(position));
+    }
+
+    /// Get the start and end of the section, if both are set and the section is not empty.
+    #[must_use]
+    pub fn section(&self) -> Option<(PlayerTimeUnit, PlayerTimeUnit)> {
+        let (a, b) = (self.a?, self.b?);
+
+        (a < b).then_some((a, b))
+    }
+}
+
+impl From<protobuf::AbRepeatPoints> for AbRepeat {
+    fn from(value: protobuf::AbRepeatPoints) -> Self {
+        Self {
+            a: value.a.map(Into::into),
+            b: value.b.map(Into::into),
+        }
+    }
+}
+
+impl From<AbRepeat> for protobuf::AbRepeatPoints {
+    fn from(value: AbRepeat) -> Self {
+        Self {
+            a: value.a.map(Into::into),
+            b: value.b.map(Into::into),
         }
     }
 }
@@ -124,18 +189,255 @@ pub struct TrackChangedInfo {
     pub title: Option<String>,
     /// Current progress of the track
     pub progress: Option<PlayerProgress>,
+    /// Chapters of the current track, sorted by start
+    pub chapters: Vec<EpisodeChapter>,
+    /// Encoding information of the current track
+    pub stream_info: StreamInfo,
+}
+
+/// The coarse [waveform](crate::waveform) of a track.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackWaveform {
+    /// The track the waveform is of
+    pub trackid: playlist_helpers::PlaylistTrackSource,
+    /// Loudness levels from `0` (silence) to `255`, empty if not known yet
+    pub levels: Vec<u8>,
+}
+
+impl From<TrackWaveform> for protobuf::Waveform {
+    fn from(value: TrackWaveform) -> Self {
+        Self {
+            id: Some(value.trackid.into()),
+            levels: value.levels,
+        }
+    }
+}
+
+impl TryFrom<protobuf::Waveform> for TrackWaveform {
+    type Error = anyhow::Error;
+
+    fn try_from(value: protobuf::Waveform) -> Result<Self, Self::Error> {
+        Ok(Self {
+            trackid: unwrap_msg(value.id, "Waveform.id")?.try_into()?,
+            levels: value.levels,
+        })
+    }
+}
+
+/// Encoding information of a track, each value may be unknown depending on the backend and source.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct StreamInfo {
+    /// Short name of the codec, like `mp3` or `flac`
+    pub codec: Option<String>,
+    /// Sample rate in Hz
+    pub sample_rate: Option<u32>,
+    /// Bitrate in bits per second, the current one for streams and the average for files
+    pub bitrate: Option<u32>,
+}
+
+impl From<StreamInfo> for protobuf::StreamInfo {
+    fn from(value: StreamInfo) -> Self {
+        Self {
+            codec: value.codec.unwrap_or_default(),
+            sample_rate: value.sample_rate.unwrap_or_default(),
+            bitrate: value.bitrate.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<protobuf::StreamInfo> for StreamInfo {
+    fn from(value: protobuf::StreamInfo) -> Self {
+        Self {
+            codec: (!value.codec.is_empty()).then_some(value.codec),
+            sample_rate: (value.sample_rate != 0).then_some(value.sample_rate),
+            bitrate: (value.bitrate != 0).then_some(value.bitrate),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum UpdateEvents {
-    MissedEvents { amount: u64 },
-    VolumeChanged { volume: u16 },
-    SpeedChanged { speed: i32 },
-    PlayStateChanged { playing: u32 },
+    MissedEvents {
+        amount: u64,
+    },
+    VolumeChanged {
+        volume: u16,
+    },
+    SpeedChanged {
+        speed: i32,
+    },
+    PlayStateChanged {
+        playing: u32,
+    },
     TrackChanged(TrackChangedInfo),
-    GaplessChanged { gapless: bool },
+    GaplessChanged {
+        gapless: bool,
+    },
     PlaylistChanged(UpdatePlaylistEvents),
     Progress(PlayerProgress),
+    /// Podcast feeds or episodes changed on the server
+    PodcastsChanged,
+    /// Tracks in the library database changed on the server
+    LibraryChanged,
+    /// A library track was marked or unmarked as favorite
+    TrackFavoriteChanged {
+        path: PathBuf,
+        favorite: bool,
+    },
+    /// The waveform of a track got generated
+    WaveformChanged(TrackWaveform),
+    /// Background podcast work on the server progressed
+    PodcastProgress(PodcastProgress),
+}
+
+/// A episode downloaded in the background, see [`PodcastProgress`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PodcastDownloadInfo {
+    pub id: EpisodeId,
+    pub url: String,
+    pub title: String,
+}
+
+impl From<&EpData> for PodcastDownloadInfo {
+    fn from(value: &EpData) -> Self {
+        Self {
+            id: value.id,
+            url: value.url.clone(),
+            title: value.title.clone(),
+        }
+    }
+}
+
+/// Progress of background podcast work on the server, like fetching feeds, downloading episodes or syncing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PodcastProgress {
+    /// Started fetching the feed with the url
+    FetchStart(String),
+    /// Fetched the feed with `url`, which was `added` instead of refreshed,
+    /// or merged into the already subscribed feed with the title `merged_into`
+    FetchDone {
+        url: String,
+        added: bool,
+        merged_into: Option<String>,
+    },
+    /// Fetching the feed with `url` failed, `title` is only known if it was fetched before
+    FetchFailed {
+        url: String,
+        title: Option<String>,
+    },
+    DownloadStart(PodcastDownloadInfo),
+    DownloadDone(PodcastDownloadInfo),
+    /// The server of the episode responded with a error
+    DownloadResponseError(PodcastDownloadInfo),
+    DownloadFileCreateError(PodcastDownloadInfo),
+    DownloadFileWriteError(PodcastDownloadInfo),
+    SyncStart,
+    /// Syncing uploaded `pushed` local changes and updated `changed` episodes
+    SyncDone {
+        pushed: u64,
+        changed: u64,
+    },
+    /// Syncing failed with the error
+    SyncFailed(String),
+}
+
+type ProgressTypes = protobuf::update_podcast_progress::Type;
+
+impl From<PodcastDownloadInfo> for protobuf::PodcastDownload {
+    fn from(value: PodcastDownloadInfo) -> Self {
+        Self {
+            episode_id: value.id.0,
+            url: value.url,
+            title: value.title,
+        }
+    }
+}
+
+impl From<protobuf::PodcastDownload> for PodcastDownloadInfo {
+    fn from(value: protobuf::PodcastDownload) -> Self {
+        Self {
+            id: EpisodeId(value.episode_id),
+            url: value.url,
+            title: value.title,
+        }
+    }
+}
+
+// mainly for server to grpc
+impl From<PodcastProgress> for protobuf::UpdatePodcastProgress {
+    fn from(value: PodcastProgress) -> Self {
+        let val = match value {
+            PodcastProgress::FetchStart(url) => ProgressTypes::FetchStart(url),
+            PodcastProgress::FetchDone {
+                url,
+                added,
+                merged_into,
+            } => ProgressTypes::FetchDone(protobuf::PodcastFetchDone {
+                url,
+                merged_into: merged_into.unwrap_or_default(),
+                added,
+            }),
+            PodcastProgress::FetchFailed { url, title } => {
+                ProgressTypes::FetchFailed(protobuf::PodcastFetchFailed {
+                    url,
+                    title: title.unwrap_or_default(),
+                })
+            }
+            PodcastProgress::DownloadStart(info) => ProgressTypes::DownloadStart(info.into()),
+            PodcastProgress::DownloadDone(info) => ProgressTypes::DownloadDone(info.into()),
+            PodcastProgress::DownloadResponseError(info) => {
+                ProgressTypes::DownloadResponseError(info.into())
+            }
+            PodcastProgress::DownloadFileCreateError(info) => {
+                ProgressTypes::DownloadFileCreateError(info.into())
+            }
+            PodcastProgress::DownloadFileWriteError(info) => {
+                ProgressTypes::DownloadFileWriteError(info.into())
+            }
+            PodcastProgress::SyncStart => ProgressTypes::SyncStart(Empty {}),
+            PodcastProgress::SyncDone { pushed, changed } => {
+                ProgressTypes::SyncDone(protobuf::PodcastSyncDone { pushed, changed })
+            }
+            PodcastProgress::SyncFailed(err) => ProgressTypes::SyncFailed(err),
+        };
+
+        Self { r#type: Some(val) }
+    }
+}
+
+// mainly for grpc to client(tui)
+impl TryFrom<protobuf::UpdatePodcastProgress> for PodcastProgress {
+    type Error = anyhow::Error;
+
+    fn try_from(value: protobuf::UpdatePodcastProgress) -> Result<Self, Self::Error> {
+        let value = unwrap_msg(value.r#type, "UpdatePodcastProgress.type")?;
+
+        let res = match value {
+            ProgressTypes::FetchStart(url) => Self::FetchStart(url),
+            ProgressTypes::FetchDone(ev) => Self::FetchDone {
+                url: ev.url,
+                added: ev.added,
+                merged_into: (!ev.merged_into.is_empty()).then_some(ev.merged_into),
+            },
+            ProgressTypes::FetchFailed(ev) => Self::FetchFailed {
+                url: ev.url,
+                title: (!ev.title.is_empty()).then_some(ev.title),
+            },
+            ProgressTypes::DownloadStart(ev) => Self::DownloadStart(ev.into()),
+            ProgressTypes::DownloadDone(ev) => Self::DownloadDone(ev.into()),
+            ProgressTypes::DownloadResponseError(ev) => Self::DownloadResponseError(ev.into()),
+            ProgressTypes::DownloadFileCreateError(ev) => Self::DownloadFileCreateError(ev.into()),
+            ProgressTypes::DownloadFileWriteError(ev) => Self::DownloadFileWriteError(ev.into()),
+            ProgressTypes::SyncStart(_) => Self::SyncStart,
+            ProgressTypes::SyncDone(ev) => Self::SyncDone {
+                pushed: ev.pushed,
+                changed: ev.changed,
+            },
+            ProgressTypes::SyncFailed(err) => Self::SyncFailed(err),
+        };
+
+        Ok(res)
+    }
 }
 
 // might not be fully true, but necessary for Msg
@@ -172,6 +474,8 @@ impl From<UpdateEvents> for protobuf::StreamUpdates {
                     .title
                     .map(protobuf::update_track_changed::OptionalTitle::Title),
                 progress: info.progress.map(Into::into),
+                chapters: info.chapters.into_iter().map(Into::into).collect(),
+                stream_info: Some(info.stream_info.into()),
             }),
             UpdateEvents::GaplessChanged { gapless } => {
                 StreamTypes::GaplessChanged(UpdateGaplessChanged {
@@ -180,6 +484,28 @@ impl From<UpdateEvents> for protobuf::StreamUpdates {
             }
             UpdateEvents::PlaylistChanged(ev) => StreamTypes::PlaylistChanged(ev.into()),
             UpdateEvents::Progress(ev) => StreamTypes::ProgressChanged(ev.into()),
+            UpdateEvents::PodcastsChanged => {
+                StreamTypes::PodcastsChanged(protobuf::UpdatePodcastsChanged {})
+            }
+            UpdateEvents::LibraryChanged => {
+                StreamTypes::LibraryChanged(protobuf::UpdateLibraryChanged {})
+            }
+            UpdateEvents::TrackFavoriteChanged { path, favorite } => {
+                StreamTypes::TrackFavoriteChanged(protobuf::UpdateTrackFavoriteChanged {
+                    msg: Some(TrackFavorite {
+                        path: path.to_string_lossy().to_string(),
+                        favorite,
+                    }),
+                })
+            }
+            UpdateEvents::WaveformChanged(waveform) => {
+                StreamTypes::WaveformChanged(protobuf::UpdateWaveformChanged {
+                    msg: Some(waveform.into()),
+                })
+            }
+            UpdateEvents::PodcastProgress(progress) => {
+                StreamTypes::PodcastProgress(progress.into())
+            }
         };
 
         Self { r#type: Some(val) }
@@ -214,6 +540,8 @@ impl TryFrom<protobuf::StreamUpdates> for UpdateEvents {
                     v
                 }),
                 progress: ev.progress.map(Into::into),
+                chapters: ev.chapters.into_iter().map(Into::into).collect(),
+                stream_info: ev.stream_info.map(Into::into).unwrap_or_default(),
             }),
             StreamTypes::GaplessChanged(ev) => Self::GaplessChanged {
                 gapless: unwrap_msg(ev.msg, "StreamUpdates.types.gapless_changed.msg")?.gapless,
@@ -226,12 +554,142 @@ impl TryFrom<protobuf::StreamUpdates> for UpdateEvents {
                 ev.try_into()
                     .context("In \"StreamUpdates.types.progress_changed\"")?,
             ),
+            StreamTypes::PodcastsChanged(_) => Self::PodcastsChanged,
+            StreamTypes::LibraryChanged(_) => Self::LibraryChanged,
+            StreamTypes::TrackFavoriteChanged(ev) => {
+                let msg = unwrap_msg(ev.msg, "StreamUpdates.types.track_favorite_changed.msg")?;
+                Self::TrackFavoriteChanged {
+                    path: PathBuf::from(msg.path),
+                    favorite: msg.favorite,
+                }
+            }
+            StreamTypes::WaveformChanged(ev) => Self::WaveformChanged(
+                unwrap_msg(ev.msg, "StreamUpdates.types.waveform_changed.msg")?
+                    .try_into()
+                    .context("In \"StreamUpdates.types.waveform_changed\"")?,
+            ),
+            StreamTypes::PodcastProgress(ev) => Self::PodcastProgress(
+                ev.try_into()
+                    .context("In \"StreamUpdates.types.podcast_progress\"")?,
+            ),
         };
 
         Ok(res)
     }
 }
 
+impl From<EpisodeChapter> for protobuf::Chapter {
+    fn from(value: EpisodeChapter) -> Self {
+        Self {
+            start: Some(value.start.into()),
+            title: value.title,
+            url: value.url.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<protobuf::Chapter> for EpisodeChapter {
+    fn from(value: protobuf::Chapter) -> Self {
+        Self {
+            start: value.start.map(Into::into).unwrap_or_default(),
+            title: value.title,
+            url: (!value.url.is_empty()).then_some(value.url),
+        }
+    }
+}
+
+impl From<&Podcast> for protobuf::PodcastFeedInfo {
+    fn from(value: &Podcast) -> Self {
+        Self {
+            id: value.id.0,
+            title: value.title.clone(),
+            url: value.url.clone(),
+            description: value.description.clone().unwrap_or_default(),
+            author: value.author.clone().unwrap_or_default(),
+            image_url: value.image_url.clone().unwrap_or_default(),
+            episodes: u64::try_from(value.episodes.len()).unwrap_or(u64::MAX),
+            unplayed: u64::try_from(value.num_unplayed()).unwrap_or(u64::MAX),
+        }
+    }
+}
+
+impl From<&Episode> for protobuf::PodcastEpisodeInfo {
+    fn from(value: &Episode) -> Self {
+        Self {
+            id: value.id.0,
+            podcast_id: value.pod_id.0,
+            title: value.title.clone(),
+            url: value.url.clone(),
+            description: value.description.clone(),
+            optional_pubdate: value
+                .pubdate
+                .map(|v| protobuf::podcast_episode_info::OptionalPubdate::Pubdate(v.timestamp())),
+            optional_duration: value
+                .duration
+                .map(protobuf::podcast_episode_info::OptionalDuration::Duration),
+            played: value.played,
+            downloaded: value.path.is_some(),
+            last_position: value.last_position.unwrap_or_default(),
+        }
+    }
+}
+
+impl PodcastFeedSettings {
+    /// Create the message for the `settings` of the podcast `id`.
+    #[must_use]
+    pub fn new(id: PodcastId, settings: &FeedSettings) -> Self {
+        use protobuf::podcast_feed_settings::{
+            OptionalAutoDownload, OptionalKeepDownloaded, OptionalMaxAgeDays, OptionalSkipIntro,
+            OptionalSkipOutro,
+        };
+
+        Self {
+            id: id.0,
+            optional_auto_download: settings
+                .auto_download
+                .map(OptionalAutoDownload::AutoDownload),
+            optional_keep_downloaded: settings
+                .keep_downloaded
+                .map(OptionalKeepDownloaded::KeepDownloaded),
+            optional_max_age_days: settings.max_age_days.map(OptionalMaxAgeDays::MaxAgeDays),
+            optional_skip_intro: settings.skip_intro.map(OptionalSkipIntro::SkipIntro),
+            optional_skip_outro: settings.skip_outro.map(OptionalSkipOutro::SkipOutro),
+        }
+    }
+
+    /// Get the settings, without the podcast id.
+    #[must_use]
+    pub fn settings(&self) -> FeedSettings {
+        use protobuf::podcast_feed_settings::{
+            OptionalAutoDownload, OptionalKeepDownloaded, OptionalMaxAgeDays, OptionalSkipIntro,
+            OptionalSkipOutro,
+        };
+
+        FeedSettings {
+            auto_download: self
+                .optional_auto_download
+                .as_ref()
+                .map(|OptionalAutoDownload::AutoDownload(v)| *v),
+            keep_downloaded: self
+                .optional_keep_downloaded
+                .as_ref()
+                .map(|OptionalKeepDownloaded::KeepDownloaded(v)| *v),
+            max_age_days: self
+                .optional_max_age_days
+                .as_ref()
+                .map(|OptionalMaxAgeDays::MaxAgeDays(v)| *v),
+            skip_intro: self
+                .optional_skip_intro
+                .as_ref()
+                .map(|OptionalSkipIntro::SkipIntro(v)| *v),
+            skip_outro: self
+                .optional_skip_outro
+                .as_ref()
+                .map(|OptionalSkipOutro::SkipOutro(v)| *v),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct PlaylistAddTrackInfo {
     /// The Index at which a track was added at.
@@ -276,6 +734,18 @@ pub struct PlaylistShuffledInfo {
     pub tracks: PlaylistTracks,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaylistReplacedInfo {
+    pub tracks: PlaylistTracks,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaylistQueueSwitchedInfo {
+    /// The name of the now active queue
+    pub queue: String,
+    pub tracks: PlaylistTracks,
+}
+
 /// Separate nested enum to handle all playlist related events
 #[derive(Debug, Clone, PartialEq)]
 pub enum UpdatePlaylistEvents {
@@ -285,6 +755,8 @@ pub enum UpdatePlaylistEvents {
     PlaylistLoopMode(PlaylistLoopModeInfo),
     PlaylistSwapTracks(PlaylistSwapInfo),
     PlaylistShuffled(PlaylistShuffledInfo),
+    PlaylistReplaced(PlaylistReplacedInfo),
+    PlaylistQueueSwitched(PlaylistQueueSwitchedInfo),
 }
 
 type PPlaylistTypes = protobuf::update_playlist::Type;
@@ -324,6 +796,17 @@ impl From<UpdatePlaylistEvents> for protobuf::UpdatePlaylist {
                     shuffled: Some(vals.tracks),
                 })
             }
+            UpdatePlaylistEvents::PlaylistReplaced(vals) => {
+                PPlaylistTypes::Replaced(protobuf::PlaylistReplaced {
+                    tracks: Some(vals.tracks),
+                })
+            }
+            UpdatePlaylistEvents::PlaylistQueueSwitched(vals) => {
+                PPlaylistTypes::QueueSwitched(protobuf::PlaylistQueueSwitched {
+                    queue: vals.queue,
+                    tracks: Some(vals.tracks),
+                })
+            }
         };
 
         Self { r#type: Some(val) }
@@ -371,6 +854,17 @@ impl TryFrom<protobuf::UpdatePlaylist> for UpdatePlaylistEvents {
                 let shuffled = unwrap_msg(ev.shuffled, "UpdatePlaylist.type.shuffled.shuffled")?;
                 Self::PlaylistShuffled(PlaylistShuffledInfo { tracks: shuffled })
             }
+            PPlaylistTypes::Replaced(ev) => {
+                let tracks = unwrap_msg(ev.tracks, "UpdatePlaylist.type.replaced.tracks")?;
+                Self::PlaylistReplaced(PlaylistReplacedInfo { tracks })
+            }
+            PPlaylistTypes::QueueSwitched(ev) => {
+                let tracks = unwrap_msg(ev.tracks, "UpdatePlaylist.type.queue_switched.tracks")?;
+                Self::PlaylistQueueSwitched(PlaylistQueueSwitchedInfo {
+                    queue: ev.queue,
+                    tracks,
+                })
+            }
         };
 
         Ok(res)
@@ -393,7 +887,7 @@ fn clamp_u16(val: u32) -> u16 {
 pub mod playlist_helpers {
     use anyhow::Context;
 
-    use super::{PlaylistTracksToRemoveClear, protobuf, unwrap_msg};
+    use super::{PlayerTimeUnit, PlaylistTracksToRemoveClear, protobuf, unwrap_msg};
 
     /// A Id / Source for a given Track
     #[derive(Debug, Clone, PartialEq)]
@@ -441,11 +935,45 @@ pub mod playlist_helpers {
         }
     }
 
+    /// Metadata known about a track before it is added, like from a playlist file.
+    ///
+    /// Only used if the track itself does not provide the values, like for radio urls.
+    #[derive(Debug, Clone, PartialEq, Eq, Default)]
+    pub struct TrackAddMetadata {
+        pub title: Option<String>,
+        pub duration: Option<PlayerTimeUnit>,
+    }
+
+    impl From<TrackAddMetadata> for protobuf::TrackAddMetadata {
+        fn from(value: TrackAddMetadata) -> Self {
+            Self {
+                optional_title: value
+                    .title
+                    .map(protobuf::track_add_metadata::OptionalTitle::Title),
+                duration: value.duration.map(Into::into),
+            }
+        }
+    }
+
+    impl From<protobuf::TrackAddMetadata> for TrackAddMetadata {
+        fn from(value: protobuf::TrackAddMetadata) -> Self {
+            Self {
+                title: value.optional_title.map(|v| {
+                    let protobuf::track_add_metadata::OptionalTitle::Title(v) = v;
+                    v
+                }),
+                duration: value.duration.map(Into::into),
+            }
+        }
+    }
+
     /// Data for requesting some tracks to be added in the server
     #[derive(Debug, Clone, PartialEq)]
     pub struct PlaylistAddTrack {
         pub at_index: u64,
         pub tracks: Vec<PlaylistTrackSource>,
+        /// Metadata for `tracks`, in the same order, may be shorter than `tracks`
+        pub metadata: Vec<TrackAddMetadata>,
     }
 
     impl PlaylistAddTrack {
@@ -454,12 +982,34 @@ pub mod playlist_helpers {
             Self {
                 at_index,
                 tracks: vec![track],
+                metadata: Vec::new(),
             }
         }
 
         #[must_use]
         pub fn new_vec(at_index: u64, tracks: Vec<PlaylistTrackSource>) -> Self {
-            Self { at_index, tracks }
+            Self {
+                at_index,
+                tracks,
+                metadata: Vec::new(),
+            }
+        }
+
+        /// Set the metadata for the tracks, see [`Self::metadata`]
+        #[must_use]
+        pub fn with_metadata(mut self, metadata: Vec<TrackAddMetadata>) -> Self {
+            self.metadata = metadata;
+            self
+        }
+
+        /// Iterate over the tracks with their metadata, if any
+        pub fn into_iter_with_metadata(
+            self,
+        ) -> impl Iterator<Item = (PlaylistTrackSource, TrackAddMetadata)> {
+            let mut metadata = self.metadata.into_iter();
+            self.tracks
+                .into_iter()
+                .map(move |v| (v, metadata.next().unwrap_or_default()))
         }
     }
 
@@ -468,6 +1018,7 @@ pub mod playlist_helpers {
             Self {
                 at_index: value.at_index,
                 tracks: value.tracks.into_iter().map(Into::into).collect(),
+                metadata: value.metadata.into_iter().map(Into::into).collect(),
             }
         }
     }
@@ -485,6 +1036,7 @@ pub mod playlist_helpers {
             Ok(Self {
                 at_index: value.at_index,
                 tracks,
+                metadata: value.metadata.into_iter().map(Into::into).collect(),
             })
         }
     }
@@ -630,3 +1182,353 @@ pub mod playlist_helpers {
         }
     }
 }
+
+/// Token authentication of the gRPC requests, see `com.auth_token` and `com.guest_token`.
+pub mod auth {
+    use anyhow::Context;
+    use tonic::metadata::{Ascii, MetadataValue};
+    use tonic::service::Interceptor;
+    use tonic::service::interceptor::InterceptedService;
+    use tonic::transport::Channel;
+    use tonic::{Request, Status};
+
+    use super::music_player_client::MusicPlayerClient;
+
+    /// The metadata key the token is sent in.
+    const AUTHORIZATION: &str = "authorization";
+
+    /// A client that sends the token with every request.
+    pub type AuthClient = MusicPlayerClient<InterceptedService<Channel, ClientAuth>>;
+
+    /// Adds the token to every request of a client.
+    #[derive(Debug, Clone, Default)]
+    pub struct ClientAuth {
+        header: Option<MetadataValue<Ascii>>,
+    }
+
+    impl ClientAuth {
+        /// Send `token` with every request, or nothing if `None`.
+        ///
+        /// # Errors
+        ///
+        /// - if the token contains characters that cannot be sent in metadata
+        pub fn new(token: Option<&str>) -> anyhow::Result<Self> {
+            let header = token
+                .map(|token| format!("Bearer {token}").parse::<MetadataValue<Ascii>>())
+                .transpose()
+                .context("auth token contains invalid characters")?
+                .map(|mut header| {
+                    // dont show the token in logs
+                    header.set_sensitive(true);
+                    header
+                });
+
+            Ok(Self { header })
+        }
+    }
+
+    impl Interceptor for ClientAuth {
+        fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+            if let Some(header) = &self.header {
+                request.metadata_mut().insert(AUTHORIZATION, header.clone());
+            }
+
+            Ok(request)
+        }
+    }
+
+    /// Marks requests made with the guest token, which may control playback, but not delete or remove anything.
+    ///
+    /// Added to the extensions of the request by [`ServerAuth`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Guest;
+
+    /// Get whether `request` was made with the guest token.
+    #[must_use]
+    pub fn is_guest<T>(request: &Request<T>) -> bool {
+        request.extensions().get::<Guest>().is_some()
+    }
+
+    /// Rejects every request that does not have the token, if one is set.
+    #[derive(Clone, Default)]
+    pub struct ServerAuth {
+        /// The full expected metadata value
+        expected: Option<String>,
+        /// The full metadata value of guests
+        guest: Option<String>,
+    }
+
+    impl ServerAuth {
+        /// Require `token` for every request, or allow all requests if `None`.
+        #[must_use]
+        pub fn new(token: Option<&str>) -> Self {
+            Self {
+                expected: token.map(|token| format!("Bearer {token}")),
+                guest: None,
+            }
+        }
+
+        /// Also allow requests with `token`, but mark them as [`Guest`].
+        #[must_use]
+        pub fn with_guest(mut self, token: Option<&str>) -> Self {
+            self.guest = token.map(|token| format!("Bearer {token}"));
+            self
+        }
+
+        /// Check the value of a `authorization` header, which may also come from other protocols than gRPC.
+        ///
+        /// Returns `Some(true)` for guests.
+        #[must_use]
+        pub fn check(&self, given: Option<&[u8]>) -> Option<bool> {
+            let given = given.unwrap_or_default();
+            // checked first, as guests are marked even if no "expected" token is set
+            if let Some(guest) = &self.guest {
+                if constant_time_eq(given, guest.as_bytes()) {
+                    return Some(true);
+                }
+            }
+
+            let Some(expected) = &self.expected else {
+                return Some(false);
+            };
+
+            constant_time_eq(given, expected.as_bytes()).then_some(false)
+        }
+
+        /// Check the value of a `authorization` header, which may also come from other protocols than gRPC.
+        #[must_use]
+        pub fn is_authorized(&self, given: Option<&[u8]>) -> bool {
+            self.check(given).is_some()
+        }
+    }
+
+    impl Interceptor for ServerAuth {
+        fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+            let given = request
+                .metadata()
+                .get(AUTHORIZATION)
+                .map(MetadataValue::as_bytes);
+
+            match self.check(given) {
+                Some(false) => Ok(request),
+                Some(true) => {
+                    request.extensions_mut().insert(Guest);
+                    Ok(request)
+                }
+                None => {
+                    warn!("Rejected a request with a missing or wrong auth token");
+                    Err(Status::unauthenticated("missing or wrong auth token"))
+                }
+            }
+        }
+    }
+
+    /// Compare without returning early, to not leak through the timing how much of the token was right.
+    fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+        if a.len() != b.len() {
+            return false;
+        }
+
+        a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use pretty_assertions::assert_eq;
+        use tonic::service::Interceptor;
+        use tonic::{Code, Request};
+
+        use super::{ClientAuth, ServerAuth, is_guest};
+
+        fn call(client: &mut ClientAuth, server: &mut ServerAuth) -> Result<(), Code> {
+            let request = client.call(Request::new(())).unwrap();
+            server.call(request).map(|_| ()).map_err(|v| v.code())
+        }
+
+        /// Get whether the request was made as a guest, if it was accepted.
+        fn call_guest(client: &mut ClientAuth, server: &mut ServerAuth) -> Result<bool, Code> {
+            let request = client.call(Request::new(())).unwrap();
+            server
+                .call(request)
+                .map(|v| is_guest(&v))
+                .map_err(|v| v.code())
+        }
+
+        #[test]
+        fn should_require_token() {
+            let mut server = ServerAuth::new(Some("secret"));
+
+            assert_eq!(
+                call(&mut ClientAuth::new(Some("secret")).unwrap(), &mut server),
+                Ok(())
+            );
+            assert_eq!(
+                call(&mut ClientAuth::new(Some("secreT")).unwrap(), &mut server),
+                Err(Code::Unauthenticated)
+            );
+            assert_eq!(
+                call(&mut ClientAuth::new(None).unwrap(), &mut server),
+                Err(Code::Unauthenticated)
+            );
+            assert_eq!(
+                call(
+                    &mut ClientAuth::new(None).unwrap(),
+                    &mut ServerAuth::new(None)
+                ),
+                Ok(())
+            );
+            assert!(ClientAuth::new(Some("new\nline")).is_err());
+        }
+
+        #[test]
+        fn should_mark_guests() {
+            let mut server = ServerAuth::new(Some("secret")).with_guest(Some("guest"));
+
+            assert_eq!(
+                call_guest(&mut ClientAuth::new(Some("secret")).unwrap(), &mut server),
+                Ok(false)
+            );
+            assert_eq!(
+                call_guest(&mut ClientAuth::new(Some("guest")).unwrap(), &mut server),
+                Ok(true)
+            );
+            assert_eq!(
+                call_guest(&mut ClientAuth::new(None).unwrap(), &mut server),
+                Err(Code::Unauthenticated)
+            );
+
+            // without a auth token, only clients sending the guest token are guests
+            let mut server = ServerAuth::new(None).with_guest(Some("guest"));
+            assert_eq!(
+                call_guest(&mut ClientAuth::new(Some("guest")).unwrap(), &mut server),
+                Ok(true)
+            );
+            assert_eq!(
+                call_guest(&mut ClientAuth::new(None).unwrap(), &mut server),
+                Ok(false)
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{
+        AbRepeat, PlayerProgress, PodcastDownloadInfo, PodcastFeedSettings, PodcastProgress,
+        protobuf,
+    };
+    use crate::ids::{EpisodeId, PodcastId};
+    use crate::podcast::feed_settings::FeedSettings;
+
+    #[test]
+    fn should_set_ab_repeat_points() {
+        let mut ab_repeat = AbRepeat::default();
+        assert_eq!(ab_repeat.section(), None);
+
+        ab_repeat.set_a(Duration::from_secs(10));
+        assert_eq!(ab_repeat.section(), None);
+        ab_repeat.set_b(Duration::from_secs(20));
+        assert_eq!(
+            ab_repeat.section(),
+            Some((Duration::from_secs(10), Duration::from_secs(20)))
+        );
+
+        // a new start clears the end
+        ab_repeat.set_a(Duration::from_secs(30));
+        assert_eq!(
+            ab_repeat,
+            AbRepeat {
+                a: Some(Duration::from_secs(30)),
+                b: None,
+            }
+        );
+
+        // a end before the start swaps both
+        ab_repeat.set_b(Duration::from_secs(5));
+        assert_eq!(
+            ab_repeat.section(),
+            Some((Duration::from_secs(5), Duration::from_secs(30)))
+        );
+
+        // without a start, the section starts at the beginning
+        let mut ab_repeat = AbRepeat::default();
+        ab_repeat.set_b(Duration::from_secs(5));
+        assert_eq!(
+            ab_repeat.section(),
+            Some((Duration::ZERO, Duration::from_secs(5)))
+        );
+    }
+
+    #[test]
+    fn should_convert_progress_with_ab_repeat() {
+        let progress = PlayerProgress {
+            position: Some(Duration::from_secs(12)),
+            total_duration: Some(Duration::from_secs(60)),
+            ab_repeat: AbRepeat {
+                a: Some(Duration::from_secs(10)),
+                b: Some(Duration::from_secs(20)),
+            },
+        };
+
+        let converted: protobuf::PlayerTime = progress.into();
+        assert_eq!(PlayerProgress::from(converted), progress);
+
+        // from clients that do not know about A-B repeat
+        let converted = protobuf::PlayerTime {
+            position: None,
+            total_duration: None,
+            ab_repeat: None,
+        };
+        assert_eq!(
+            PlayerProgress::from(converted).ab_repeat,
+            AbRepeat::default()
+        );
+    }
+
+    #[test]
+    fn should_convert_podcast_progress() {
+        let download = PodcastDownloadInfo {
+            id: EpisodeId(3),
+            url: "https://example.com/ep.mp3".to_string(),
+            title: "Episode".to_string(),
+        };
+        for progress in [
+            PodcastProgress::FetchDone {
+                url: "https://example.com/feed".to_string(),
+                added: false,
+                merged_into: Some("Feed".to_string()),
+            },
+            PodcastProgress::FetchFailed {
+                url: "https://example.com/feed".to_string(),
+                title: None,
+            },
+            PodcastProgress::DownloadFileWriteError(download),
+            PodcastProgress::SyncDone {
+                pushed: 1,
+                changed: 2,
+            },
+        ] {
+            let converted: protobuf::UpdatePodcastProgress = progress.clone().into();
+            assert_eq!(PodcastProgress::try_from(converted).unwrap(), progress);
+        }
+    }
+
+    #[test]
+    fn should_convert_feed_settings() {
+        let settings = FeedSettings {
+            auto_download: Some(2),
+            keep_downloaded: None,
+            max_age_days: Some(30),
+            skip_intro: None,
+            skip_outro: Some(10),
+        };
+
+        let converted = PodcastFeedSettings::new(PodcastId(5), &settings);
+        assert_eq!(converted.id, 5);
+        assert_eq!(converted.settings(), settings);
+    }
+}
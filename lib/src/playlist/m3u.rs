@@ -2,28 +2,46 @@
 
 // TODO: resolve relative paths
 
-use super::PlaylistValue;
+use std::fmt::Write as _;
+use std::time::Duration;
+
+use super::{PlaylistEntry, PlaylistValue};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct M3UItem {
     pub url: PlaylistValue,
+    /// The title from a preceding `#EXTINF` line
+    pub title: Option<String>,
+    /// The duration from a preceding `#EXTINF` line
+    pub duration: Option<Duration>,
 }
 
 /// M3U(8) is a de-facto standart (meaning there is no formal standard), where each line that does not start with `#` is a entry, separated by newlines
 ///
+/// The Extended M3U `#EXTINF` directive is parsed for the title and duration of the following entry.
+///
 /// <https://en.wikipedia.org/wiki/M3U#File_format>
 pub fn decode(content: &str) -> Vec<M3UItem> {
     let lines = content.lines();
     let mut list = vec![];
+    // the last "#EXTINF" that has not been applied to a entry yet
+    let mut extinf: Option<(Option<Duration>, Option<String>)> = None;
     for line in lines {
         if line.is_empty() {
             continue;
         }
 
+        if let Some(remainder) = line.strip_prefix("#EXTINF:") {
+            extinf = Some(parse_extinf(remainder));
+            continue;
+        }
+
         if line.starts_with('#') {
             continue;
         }
 
+        let (duration, title) = extinf.take().unwrap_or_default();
+
         let mut p_value = match PlaylistValue::try_from_str(line) {
             Ok(v) => v,
             Err(err) => {
@@ -36,11 +54,69 @@ pub fn decode(content: &str) -> Vec<M3UItem> {
             continue;
         }
 
-        list.push(M3UItem { url: p_value });
+        list.push(M3UItem {
+            url: p_value,
+            title,
+            duration,
+        });
     }
     list
 }
 
+/// Parse the value of a `#EXTINF:` line, in the format of `DURATION [ATTRIBUTES],TITLE`.
+///
+/// A negative duration means "unknown".
+fn parse_extinf(val: &str) -> (Option<Duration>, Option<String>) {
+    // attributes may contain quoted commas, so find the first unquoted one
+    let mut in_quotes = false;
+    let split = val.char_indices().find(|(_, ch)| {
+        if *ch == '"' {
+            in_quotes = !in_quotes;
+        }
+        *ch == ',' && !in_quotes
+    });
+    let (info, title) = match split {
+        Some((idx, _)) => (&val[..idx], Some(val[idx + 1..].trim())),
+        None => (val, None),
+    };
+
+    let duration = info
+        .split_whitespace()
+        .next()
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|v| v.is_finite() && *v >= 0.0)
+        .map(Duration::from_secs_f64);
+    let title = title.filter(|v| !v.is_empty()).map(ToString::to_string);
+
+    (duration, title)
+}
+
+/// Encode the given entries as a Extended M3U playlist.
+///
+/// Entries without a title and duration are written without a `#EXTINF` line.
+pub fn encode(entries: &[PlaylistEntry]) -> String {
+    let mut out = String::from("#EXTM3U\n");
+    for entry in entries {
+        if entry.title.is_some() || entry.duration.is_some() {
+            // "-1" is the common value for "unknown"
+            let duration = entry
+                .duration
+                .map_or_else(|| "-1".to_string(), |v| v.as_secs().to_string());
+            // a newline in the title would start a new entry
+            let title = entry
+                .title
+                .as_deref()
+                .unwrap_or_default()
+                .replace('\n', " ");
+            let _ = writeln!(out, "#EXTINF:{duration},{title}");
+        }
+
+        let _ = writeln!(out, "{}", entry.value);
+    }
+
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -67,4 +143,54 @@ https://somewhere.url/path";
             PlaylistValue::Url(Url::parse("https://somewhere.url/path").unwrap())
         );
     }
+
+    #[test]
+    fn should_parse_extinf() {
+        let playlist = r#"#EXTM3U
+#EXTINF:123,Artist - Title
+/some/path.mp3
+#EXTINF:-1 tvg-name="Some, Radio",Some Radio
+https://somewhere.url/stream
+relative.mp3"#;
+
+        let results = decode(playlist);
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].title.as_deref(), Some("Artist - Title"));
+        assert_eq!(results[0].duration, Some(Duration::from_secs(123)));
+        assert_eq!(results[1].title.as_deref(), Some("Some Radio"));
+        assert_eq!(results[1].duration, None);
+        // a "#EXTINF" only applies to the entry directly after it
+        assert_eq!(results[2].title, None);
+        assert_eq!(results[2].duration, None);
+    }
+
+    #[test]
+    fn should_roundtrip() {
+        let entries = vec![
+            PlaylistEntry {
+                value: PlaylistValue::Path("/some/path.mp3".into()),
+                title: Some("Title".to_string()),
+                duration: Some(Duration::from_secs(61)),
+            },
+            PlaylistEntry {
+                value: PlaylistValue::Url(Url::parse("https://somewhere.url/stream").unwrap()),
+                title: Some("Radio".to_string()),
+                duration: None,
+            },
+            PlaylistEntry {
+                value: PlaylistValue::Path("relative.mp3".into()),
+                title: None,
+                duration: None,
+            },
+        ];
+
+        let encoded = encode(&entries);
+        assert_eq!(
+            encoded,
+            "#EXTM3U\n#EXTINF:61,Title\n/some/path.mp3\n#EXTINF:-1,Radio\nhttps://somewhere.url/stream\nrelative.mp3\n"
+        );
+
+        let decoded: Vec<PlaylistEntry> = decode(&encoded).into_iter().map(Into::into).collect();
+        assert_eq!(decoded, entries);
+    }
 }
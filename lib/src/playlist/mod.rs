@@ -1,6 +1,8 @@
 //! This is a very simple url extractor for different kinds of playlist formats: M3U, PLS, ASX, XSPF
 //!
 //! It is not optimized yet and does create a lot of strings on the way.
+//!
+//! M3U(8), PLS and XSPF can also be written, see [`encode`].
 
 mod asx;
 mod m3u;
@@ -12,6 +14,7 @@ use std::{
     fmt::Display,
     path::{Path, PathBuf},
     str::FromStr,
+    time::Duration,
 };
 
 use anyhow::{Context, Result, anyhow};
@@ -96,6 +99,118 @@ impl PlaylistValue {
     }
 }
 
+/// A entry of a playlist, with the metadata some formats can store alongside the location.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[allow(clippy::module_name_repetitions)]
+pub struct PlaylistEntry {
+    pub value: PlaylistValue,
+    pub title: Option<String>,
+    pub duration: Option<Duration>,
+}
+
+impl From<PlaylistValue> for PlaylistEntry {
+    fn from(value: PlaylistValue) -> Self {
+        Self {
+            value,
+            title: None,
+            duration: None,
+        }
+    }
+}
+
+impl From<m3u::M3UItem> for PlaylistEntry {
+    fn from(value: m3u::M3UItem) -> Self {
+        Self {
+            value: value.url,
+            title: value.title,
+            duration: value.duration,
+        }
+    }
+}
+
+impl From<pls::PLSItem> for PlaylistEntry {
+    fn from(value: pls::PLSItem) -> Self {
+        Self {
+            value: value.url,
+            title: value.title,
+            duration: value.duration,
+        }
+    }
+}
+
+impl From<xspf::XSPFItem> for PlaylistEntry {
+    fn from(value: xspf::XSPFItem) -> Self {
+        Self {
+            value: value.location,
+            title: value.title,
+            duration: value.duration,
+        }
+    }
+}
+
+impl From<asx::ASXItem> for PlaylistEntry {
+    fn from(value: asx::ASXItem) -> Self {
+        Self {
+            value: value.location,
+            title: Some(value.title),
+            duration: None,
+        }
+    }
+}
+
+/// The playlist formats that can be written with [`encode`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[allow(clippy::module_name_repetitions)]
+pub enum PlaylistFormat {
+    /// Extended M3U
+    M3u,
+    /// Extended M3U, explicitly UTF-8
+    M3u8,
+    Pls,
+    Xspf,
+}
+
+impl PlaylistFormat {
+    pub const ALL: [Self; 4] = [Self::M3u, Self::M3u8, Self::Pls, Self::Xspf];
+
+    /// Get the file extension for the format, without the leading dot
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::M3u => "m3u",
+            Self::M3u8 => "m3u8",
+            Self::Pls => "pls",
+            Self::Xspf => "xspf",
+        }
+    }
+}
+
+impl Display for PlaylistFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::M3u => "M3U",
+            Self::M3u8 => "M3U8",
+            Self::Pls => "PLS",
+            Self::Xspf => "XSPF",
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// Encode the given entries in the given playlist format.
+///
+/// Paths are written as-is, so they should already be relative to where the playlist will be saved, if wanted.
+#[must_use]
+pub fn encode(format: PlaylistFormat, entries: &[PlaylistEntry]) -> String {
+    match format {
+        // rust strings are always UTF-8, so there is no difference
+        PlaylistFormat::M3u | PlaylistFormat::M3u8 => m3u::encode(entries),
+        PlaylistFormat::Pls => pls::encode(entries),
+        PlaylistFormat::Xspf => xspf::encode(entries),
+    }
+}
+
 /// Decode playlist content string. It checks for M3U, PLS, XSPF and ASX content in the string.
 ///
 /// Returns the parsed entries from the playlist, in playlist order.
@@ -126,34 +241,28 @@ impl PlaylistValue {
 /// }
 /// ```
 pub fn decode(content: &str) -> Result<Vec<PlaylistValue>> {
-    let mut set: Vec<PlaylistValue> = vec![];
+    let entries = decode_entries(content)?;
+
+    Ok(entries.into_iter().map(|v| v.value).collect())
+}
+
+/// Decode playlist content string, like [`decode`], but also keep the titles and durations if the format has them.
+///
+/// # Errors
+///
+/// If the XML-based formats fail to parse
+pub fn decode_entries(content: &str) -> Result<Vec<PlaylistEntry>> {
     let content_small = content.to_lowercase();
 
-    if content_small.contains("<playlist") {
-        let items = xspf::decode(content)?;
-        set.reserve(items.len());
-        for item in items {
-            set.push(item.location);
-        }
+    let set = if content_small.contains("<playlist") {
+        xspf::decode(content)?.into_iter().map(Into::into).collect()
     } else if content_small.contains("<asx") {
-        let items = asx::decode(content)?;
-        set.reserve(items.len());
-        for item in items {
-            set.push(item.location);
-        }
+        asx::decode(content)?.into_iter().map(Into::into).collect()
     } else if content_small.contains("[playlist]") {
-        let items = pls::decode(content);
-        set.reserve(items.len());
-        for item in items {
-            set.push(item.url);
-        }
+        pls::decode(content).into_iter().map(Into::into).collect()
     } else {
-        let items = m3u::decode(content);
-        set.reserve(items.len());
-        for item in items {
-            set.push(item.url);
-        }
-    }
+        m3u::decode(content).into_iter().map(Into::into).collect()
+    };
 
     Ok(set)
 }
@@ -225,6 +334,45 @@ Title1=mytitle
         );
     }
 
+    #[test]
+    fn should_keep_metadata() {
+        let items = decode_entries(
+            "#EXTM3U
+#EXTINF:61,Some Title
+/some/path.mp3",
+        )
+        .unwrap();
+        assert_eq!(
+            items,
+            vec![PlaylistEntry {
+                value: PlaylistValue::Path("/some/path.mp3".into()),
+                title: Some("Some Title".to_string()),
+                duration: Some(Duration::from_secs(61)),
+            }]
+        );
+    }
+
+    #[test]
+    fn should_roundtrip_all_formats() {
+        let entries = vec![
+            PlaylistEntry {
+                value: PlaylistValue::Path("/some/path.mp3".into()),
+                title: Some("Title".to_string()),
+                duration: Some(Duration::from_secs(61)),
+            },
+            PlaylistEntry {
+                value: PlaylistValue::Url(Url::parse("http://this.is.an.example/").unwrap()),
+                title: Some("Radio".to_string()),
+                duration: None,
+            },
+        ];
+
+        for format in PlaylistFormat::ALL {
+            let encoded = encode(format, &entries);
+            assert_eq!(decode_entries(&encoded).unwrap(), entries, "{format}");
+        }
+    }
+
     mod playlist_value {
         use std::path::Path;
 
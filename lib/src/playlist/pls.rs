@@ -1,19 +1,24 @@
-//! Decode File and Title parts from simple playlist PLS files
+//! Decode and encode File, Title and Length parts from simple playlist PLS files
 
 use std::collections::{HashMap, hash_map::Entry};
+use std::fmt::Write as _;
+use std::time::Duration;
 
-use super::PlaylistValue;
+use super::{PlaylistEntry, PlaylistValue};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct PLSItem {
     pub title: Option<String>,
     pub url: PlaylistValue,
+    /// The `Length` of the entry, if set and not `-1`
+    pub duration: Option<Duration>,
 }
 
 #[derive(Debug, Clone, PartialEq, Default)]
 struct PrivateItem {
     pub title: Option<String>,
     pub url: Option<PlaylistValue>,
+    pub duration: Option<Duration>,
 }
 
 /// PLS is a file format similar in style to INI (but does not have a official standard).
@@ -101,6 +106,19 @@ pub fn decode(content: &str) -> Vec<PLSItem> {
             }
         }
 
+        if let Some(remainder) = line.strip_prefix("Length") {
+            let Some((num, length)) = parse_id(remainder, line) else {
+                continue;
+            };
+
+            // "-1" is used for unknown lengths, like streams
+            let Ok(length) = length.trim().parse::<u64>() else {
+                continue;
+            };
+
+            list.entry(num).or_default().duration = Some(Duration::from_secs(length));
+        }
+
         if let Some(remainder) = line.strip_prefix("NumberOfEntries") {
             let Some((_, remainder)) = remainder.split_once('=') else {
                 warn!("Malformed line: {line:#?}");
@@ -133,6 +151,7 @@ pub fn decode(content: &str) -> Vec<PLSItem> {
                     title: v.1.title,
                     // Safe unwrap, because of the filter
                     url: v.1.url.unwrap(),
+                    duration: v.1.duration,
                 },
             )
         })
@@ -155,6 +174,33 @@ pub fn decode(content: &str) -> Vec<PLSItem> {
     list.into_iter().map(|v| v.1).collect()
 }
 
+/// Encode the given entries as a PLS playlist (version 2).
+pub fn encode(entries: &[PlaylistEntry]) -> String {
+    let mut out = String::from("[playlist]\n");
+    for (idx, entry) in entries.iter().enumerate() {
+        // PLS entries start at 1
+        let num = idx + 1;
+        // a newline would start a new key
+        let _ = writeln!(
+            out,
+            "File{num}={}",
+            entry.value.to_string().replace('\n', " ")
+        );
+        if let Some(title) = &entry.title {
+            let _ = writeln!(out, "Title{num}={}", title.replace('\n', " "));
+        }
+        // "-1" is used for unknown lengths
+        let length = entry
+            .duration
+            .map_or_else(|| "-1".to_string(), |v| v.as_secs().to_string());
+        let _ = writeln!(out, "Length{num}={length}");
+    }
+    let _ = writeln!(out, "NumberOfEntries={}", entries.len());
+    let _ = writeln!(out, "Version=2");
+
+    out
+}
+
 /// Parse a Entry id from the start of the value until the first `=`.
 ///
 /// Returns the parsed number and the remainder after the first `=`.
@@ -257,4 +303,43 @@ Title1=mytitle
         );
         assert_eq!(items[0].title, Some("mytitle".to_string()));
     }
+
+    #[test]
+    fn length() {
+        let items = decode(
+            "[playlist]
+File1=/a.mp3
+Length1=61
+File2=http://c.mp3
+Length2=-1",
+        );
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].duration, Some(Duration::from_secs(61)));
+        assert_eq!(items[1].duration, None);
+    }
+
+    #[test]
+    fn roundtrip() {
+        let entries = vec![
+            PlaylistEntry {
+                value: PlaylistValue::Path("/a.mp3".into()),
+                title: Some("Title".to_string()),
+                duration: Some(Duration::from_secs(61)),
+            },
+            PlaylistEntry {
+                value: PlaylistValue::Url(Url::parse("http://this.is.an.example").unwrap()),
+                title: None,
+                duration: None,
+            },
+        ];
+
+        let encoded = encode(&entries);
+        assert_eq!(
+            encoded,
+            "[playlist]\nFile1=/a.mp3\nTitle1=Title\nLength1=61\nFile2=http://this.is.an.example/\nLength2=-1\nNumberOfEntries=2\nVersion=2\n"
+        );
+
+        let decoded: Vec<PlaylistEntry> = decode(&encoded).into_iter().map(Into::into).collect();
+        assert_eq!(decoded, entries);
+    }
 }
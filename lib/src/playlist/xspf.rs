@@ -1,9 +1,13 @@
+use std::fmt::Write as _;
+use std::time::Duration;
+
 use anyhow::Result;
 use quick_xml::Reader;
-use quick_xml::escape::unescape;
+use quick_xml::escape::{escape, resolve_predefined_entity};
 use quick_xml::events::Event;
+use reqwest::Url;
 
-use super::PlaylistValue;
+use super::{PlaylistEntry, PlaylistValue};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct XSPFItem {
@@ -13,6 +17,8 @@ pub struct XSPFItem {
     pub location: PlaylistValue,
     /// According to the spec, a `track` MAY contain zero or more `identifier` (only last will be used here though)
     pub identifier: Option<String>,
+    /// According to the spec, a `track` MAY contain exactly one `duration`, in milliseconds
+    pub duration: Option<Duration>,
 }
 
 /// A temporary storage to build a [`XSPFItem`] while still being in a element and not having all values
@@ -21,6 +27,7 @@ struct PrivateItem {
     pub title: Option<String>,
     pub location: Option<PlaylistValue>,
     pub identifier: Option<String>,
+    pub duration: Option<Duration>,
 }
 
 impl PrivateItem {
@@ -31,6 +38,7 @@ impl PrivateItem {
                 title: self.title.take(),
                 location,
                 identifier: self.identifier.take(),
+                duration: self.duration.take(),
             });
         }
 
@@ -54,8 +62,9 @@ pub fn decode(content: &str) -> Result<Vec<XSPFItem>> {
     let mut current_item = PrivateItem::default();
 
     let mut reader = Reader::from_str(content);
-    reader.config_mut().trim_text(true);
     let mut xml_stack = Vec::with_capacity(4);
+    // the text of the current element, as entity references are reported separately from the surrounding text
+    let mut text = String::new();
     let mut buf = Vec::new();
     let decoder = reader.decoder();
     loop {
@@ -63,9 +72,30 @@ pub fn decode(content: &str) -> Result<Vec<XSPFItem>> {
             // Ok(Event::Empty(ref e)) => {}
             Ok(Event::Start(ref e)) => {
                 xml_stack.push(decoder.decode(e.name().as_ref())?.to_lowercase());
+                text.clear();
             }
             Ok(Event::End(_)) => {
                 let path = xml_stack.join("/");
+                let value = text.trim();
+                if path == "playlist/tracklist/track/title" {
+                    current_item.title.replace(value.to_string());
+                }
+                if path == "playlist/tracklist/track/location" {
+                    let mut p_value = PlaylistValue::try_from_str(value)?;
+                    p_value.file_url_to_path()?;
+                    current_item.location.replace(p_value);
+                }
+                if path == "playlist/tracklist/track/identifier" {
+                    current_item.identifier.replace(value.to_string());
+                }
+                if path == "playlist/tracklist/track/duration" {
+                    match value.parse::<u64>() {
+                        Ok(millis) => {
+                            current_item.duration.replace(Duration::from_millis(millis));
+                        }
+                        Err(err) => warn!("Failed to parse duration, ignoring! Error: {err:#?}"),
+                    }
+                }
                 if path == "playlist/tracklist/track" {
                     if let Some(transformed) = current_item.try_into_xspf_item_and_reset() {
                         list.push(transformed);
@@ -74,24 +104,18 @@ pub fn decode(content: &str) -> Result<Vec<XSPFItem>> {
                     }
                 }
                 xml_stack.pop();
+                text.clear();
             }
             Ok(Event::Text(e)) => {
-                let path = xml_stack.join("/");
-                if path == "playlist/tracklist/track/title" {
-                    current_item
-                        .title
-                        .replace(unescape(&decoder.decode(&e)?)?.to_string());
-                }
-                if path == "playlist/tracklist/track/location" {
-                    let mut p_value =
-                        PlaylistValue::try_from_str(&unescape(&decoder.decode(&e)?)?)?;
-                    p_value.file_url_to_path()?;
-                    current_item.location.replace(p_value);
-                }
-                if path == "playlist/tracklist/track/identifier" {
-                    current_item
-                        .identifier
-                        .replace(unescape(&decoder.decode(&e)?)?.to_string());
+                text.push_str(&decoder.decode(&e)?);
+            }
+            Ok(Event::GeneralRef(e)) => {
+                if let Some(ch) = e.resolve_char_ref()? {
+                    text.push(ch);
+                } else if let Some(resolved) = resolve_predefined_entity(&decoder.decode(&e)?) {
+                    text.push_str(resolved);
+                } else {
+                    warn!("Unknown entity reference, ignoring!");
                 }
             }
             Ok(Event::Eof) => break,
@@ -107,6 +131,35 @@ pub fn decode(content: &str) -> Result<Vec<XSPFItem>> {
     Ok(list)
 }
 
+/// Encode the given entries as a XSPF playlist.
+///
+/// Absolute paths are written as `file://` urls, relative paths are written as-is.
+pub fn encode(entries: &[PlaylistEntry]) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<playlist version=\"1\" xmlns=\"http://xspf.org/ns/0/\">\n  <trackList>\n",
+    );
+    for entry in entries {
+        let location = match &entry.value {
+            PlaylistValue::Path(path) => Url::from_file_path(path)
+                .map_or_else(|()| path.display().to_string(), |v| v.to_string()),
+            PlaylistValue::Url(url) => url.to_string(),
+        };
+
+        out.push_str("    <track>\n");
+        let _ = writeln!(out, "      <location>{}</location>", escape(&location));
+        if let Some(title) = &entry.title {
+            let _ = writeln!(out, "      <title>{}</title>", escape(title));
+        }
+        if let Some(duration) = entry.duration {
+            let _ = writeln!(out, "      <duration>{}</duration>", duration.as_millis());
+        }
+        out.push_str("    </track>\n");
+    }
+    out.push_str("  </trackList>\n</playlist>\n");
+
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -172,4 +225,48 @@ mod tests {
         let items = decode(s).unwrap();
         assert_eq!(items.len(), 2);
     }
+
+    #[test]
+    fn should_parse_duration() {
+        let s = r#"<?xml version="1.0" encoding="UTF-8"?>
+<playlist version="1" xmlns="http://xspf.org/ns/0/">
+    <trackList>
+    <track>
+        <location>http://this.is.an.example</location>
+        <duration>61500</duration>
+    </track>
+    </trackList>
+</playlist>"#;
+        let items = decode(s).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].duration, Some(Duration::from_millis(61500)));
+    }
+
+    #[test]
+    #[cfg(target_family = "unix")]
+    fn roundtrip() {
+        let entries = vec![
+            PlaylistEntry {
+                value: PlaylistValue::Path("/some/a & b.mp3".into()),
+                title: Some("<Title>".to_string()),
+                duration: Some(Duration::from_millis(61500)),
+            },
+            PlaylistEntry {
+                value: PlaylistValue::Url(Url::parse("http://this.is.an.example").unwrap()),
+                title: None,
+                duration: None,
+            },
+        ];
+
+        let encoded = encode(&entries);
+        assert!(encoded.contains("<location>file:///some/a%20&amp;%20b.mp3</location>"));
+        assert!(encoded.contains("<title>&lt;Title&gt;</title>"));
+
+        let decoded: Vec<PlaylistEntry> = decode(&encoded)
+            .unwrap()
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        assert_eq!(decoded, entries);
+    }
 }
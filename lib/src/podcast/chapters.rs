@@ -0,0 +1,216 @@
+//! Parsing of episode chapters, from [Podlove Simple Chapters](https://podlove.org/simple-chapters/) in the feed
+//! or from `CHAP` frames embedded in downloaded files.
+//! Also finding the chapter to jump to for chapter navigation.
+
+use std::path::Path;
+use std::time::Duration;
+
+use rss::Item;
+use rss::extension::Extension;
+
+use super::episode::Chapter;
+
+/// Get all Podlove Simple Chapters (`<psc:chapters>`) of a feed item, sorted by start.
+#[must_use]
+pub fn from_feed_item(item: &Item) -> Vec<Chapter> {
+    let Some(chapters) = item.extensions().get("psc").and_then(|v| v.get("chapters")) else {
+        return Vec::new();
+    };
+
+    let mut chapters: Vec<Chapter> = chapters
+        .iter()
+        .filter_map(|v| v.children.get("chapter"))
+        .flatten()
+        .filter_map(from_psc_chapter)
+        .collect();
+    chapters.sort_by_key(|v| v.start);
+
+    chapters
+}
+
+/// Convert a single `<psc:chapter>` element, ignoring chapters without a valid start.
+fn from_psc_chapter(ext: &Extension) -> Option<Chapter> {
+    let start = parse_npt(ext.attrs.get("start")?)?;
+
+    Some(Chapter {
+        start,
+        title: ext.attrs.get("title").cloned().unwrap_or_default(),
+        url: ext.attrs.get("href").filter(|v| !v.is_empty()).cloned(),
+    })
+}
+
+/// Parse a [Normal Play Time](https://www.ietf.org/rfc/rfc2326.txt) as used by Podlove Simple Chapters.
+///
+/// Covers formats `HH:MM:SS.mmm`, `MM:SS.mmm` and `SS.mmm`, the milliseconds being optional.
+fn parse_npt(value: &str) -> Option<Duration> {
+    let mut parts = value.trim().rsplit(':');
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    let minutes: u64 = parts.next().map_or(Ok(0), str::parse::<u64>).ok()?;
+    let hours: u64 = parts.next().map_or(Ok(0), str::parse::<u64>).ok()?;
+
+    if parts.next().is_some() {
+        return None;
+    }
+
+    // rejects negative, infinite and too big values, which "from_secs_f64" would panic on
+    let seconds = Duration::try_from_secs_f64(seconds).ok()?;
+    let whole = hours
+        .checked_mul(60 * 60)?
+        .checked_add(minutes.checked_mul(60)?)?;
+
+    Duration::from_secs(whole).checked_add(seconds)
+}
+
+/// Get all chapters embedded as ID3 `CHAP` frames in the file at `path`, sorted by start.
+///
+/// Files without a ID3 tag or without chapters return no chapters.
+#[must_use]
+pub fn from_file(path: &Path) -> Vec<Chapter> {
+    let Ok(tag) = id3::Tag::read_from_path(path) else {
+        return Vec::new();
+    };
+
+    let mut chapters: Vec<Chapter> = tag
+        .chapters()
+        .map(|chapter| {
+            let title = chapter
+                .frames
+                .iter()
+                .find(|v| v.id() == "TIT2")
+                .and_then(|v| v.content().text())
+                .map_or_else(|| chapter.element_id.clone(), ToString::to_string);
+            let url = chapter
+                .frames
+                .iter()
+                .find_map(|v| v.content().extended_link())
+                .map(|v| v.link.clone());
+
+            Chapter {
+                start: Duration::from_millis(u64::from(chapter.start_time)),
+                title,
+                url,
+            }
+        })
+        .collect();
+    chapters.sort_by_key(|v| v.start);
+
+    chapters
+}
+
+/// Jumping back within this time after a chapter started goes to the chapter before it, instead of restarting it.
+pub const PREVIOUS_CHAPTER_THRESHOLD: Duration = Duration::from_secs(3);
+
+/// Get the index of the chapter to jump to from `position`, in `chapters` sorted by start.
+///
+/// Jumping `forward` goes to the next chapter, otherwise to the start of the current chapter, or the one before it
+/// if the current chapter just started, see [`PREVIOUS_CHAPTER_THRESHOLD`].
+/// Returns `None` if there is no chapter to jump to.
+#[must_use]
+pub fn jump_target(chapters: &[Chapter], position: Duration, forward: bool) -> Option<usize> {
+    if forward {
+        return chapters.iter().position(|v| v.start > position);
+    }
+
+    let current = chapters.iter().rposition(|v| v.start <= position)?;
+    if current > 0 && position - chapters[current].start < PREVIOUS_CHAPTER_THRESHOLD {
+        return Some(current - 1);
+    }
+
+    Some(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+    use rss::Channel;
+
+    use super::{from_feed_item, jump_target, parse_npt};
+    use crate::podcast::episode::Chapter;
+
+    #[test]
+    fn should_parse_npt() {
+        assert_eq!(
+            parse_npt("01:02:03.500"),
+            Some(Duration::from_millis(3_723_500))
+        );
+        assert_eq!(parse_npt("02:03"), Some(Duration::from_secs(123)));
+        assert_eq!(parse_npt("12.25"), Some(Duration::from_millis(12_250)));
+        assert_eq!(parse_npt("1:2:3:4"), None);
+        assert_eq!(parse_npt("abc"), None);
+        assert_eq!(parse_npt("-1"), None);
+    }
+
+    #[test]
+    fn should_not_panic_on_invalid_npt() {
+        assert_eq!(parse_npt("1e30"), None);
+        assert_eq!(parse_npt("inf"), None);
+        assert_eq!(parse_npt("NaN"), None);
+        assert_eq!(parse_npt("-0.5"), None);
+        assert_eq!(parse_npt("01:-2.5"), None);
+        // overflows multiplying the hours to seconds
+        assert_eq!(parse_npt("18446744073709551615:00:00"), None);
+        // overflows adding the seconds
+        assert_eq!(parse_npt("5124095576030431:00:59"), None);
+    }
+
+    #[test]
+    fn should_parse_psc_chapters() {
+        let feed = indoc::indoc! {r#"
+            <rss version="2.0" xmlns:psc="http://podlove.org/simple-chapters">
+            <channel>
+                <title>Feed</title>
+                <item>
+                    <title>Episode</title>
+                    <psc:chapters version="1.2">
+                        <psc:chapter start="00:05:00" title="Second" href="https://example.com"/>
+                        <psc:chapter start="00:00:00.000" title="Intro"/>
+                        <psc:chapter title="No start"/>
+                    </psc:chapters>
+                </item>
+            </channel>
+            </rss>
+        "#};
+        let channel = Channel::read_from(feed.as_bytes()).unwrap();
+
+        assert_eq!(
+            from_feed_item(&channel.items()[0]),
+            [
+                Chapter {
+                    start: Duration::ZERO,
+                    title: "Intro".to_string(),
+                    url: None,
+                },
+                Chapter {
+                    start: Duration::from_secs(300),
+                    title: "Second".to_string(),
+                    url: Some("https://example.com".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_find_chapter_to_jump_to() {
+        let chapters: Vec<Chapter> = [10, 60, 120]
+            .into_iter()
+            .map(|start| Chapter {
+                start: Duration::from_secs(start),
+                title: String::new(),
+                url: None,
+            })
+            .collect();
+        let secs = Duration::from_secs;
+
+        assert_eq!(jump_target(&chapters, secs(0), true), Some(0));
+        assert_eq!(jump_target(&chapters, secs(60), true), Some(2));
+        assert_eq!(jump_target(&chapters, secs(130), true), None);
+
+        assert_eq!(jump_target(&chapters, secs(90), false), Some(1));
+        assert_eq!(jump_target(&chapters, secs(61), false), Some(0));
+        assert_eq!(jump_target(&chapters, secs(11), false), Some(0));
+        assert_eq!(jump_target(&chapters, secs(5), false), None);
+        assert_eq!(jump_target(&[], secs(5), true), None);
+    }
+}
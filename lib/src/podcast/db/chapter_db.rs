@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use indoc::indoc;
+use rusqlite::{Connection, Row, named_params, params};
+
+use crate::ids::EpisodeId;
+use crate::podcast::episode::Chapter;
+
+/// Try to convert a given row to a [`Chapter`] instance, using column names to resolve the values
+fn chapter_from_row_named(row: &Row<'_>) -> Result<Chapter, rusqlite::Error> {
+    // NOTE: all the names in "get" below are the *column names* as defined in migrations/003.sql#table_chapters (pseudo link)
+    Ok(Chapter {
+        start: Duration::from_millis(row.get("start")?),
+        title: row.get("title")?,
+        url: row.get("url")?,
+    })
+}
+
+/// Replace all chapters of `episode_id` with `chapters`
+pub fn set_chapters(
+    episode_id: EpisodeId,
+    chapters: &[Chapter],
+    con: &Connection,
+) -> Result<(), rusqlite::Error> {
+    con.execute(
+        "DELETE FROM chapters WHERE episode_id = ?;",
+        params![episode_id],
+    )?;
+
+    let mut stmt = con.prepare_cached(indoc! {"
+        INSERT INTO chapters (episode_id, start, title, url)
+        VALUES (:epid, :start, :title, :url);
+    "})?;
+    for chapter in chapters {
+        stmt.execute(named_params![
+            ":epid": episode_id,
+            ":start": u64::try_from(chapter.start.as_millis()).unwrap_or(u64::MAX),
+            ":title": chapter.title,
+            ":url": chapter.url,
+        ])?;
+    }
+
+    Ok(())
+}
+
+/// Get all chapters of `episode_id`, sorted by start
+pub fn get_chapters(
+    episode_id: EpisodeId,
+    con: &Connection,
+) -> Result<Vec<Chapter>, rusqlite::Error> {
+    let mut stmt = con.prepare_cached(
+        "SELECT start, title, url FROM chapters WHERE episode_id = ? ORDER BY start;",
+    )?;
+
+    stmt.query_map(params![episode_id], chapter_from_row_named)?
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use super::super::migration;
+    use super::super::test_utils::gen_database;
+    use super::{get_chapters, set_chapters};
+    use crate::ids::EpisodeId;
+    use crate::podcast::episode::Chapter;
+
+    fn chapter(start: u64, title: &str) -> Chapter {
+        Chapter {
+            start: Duration::from_secs(start),
+            title: title.to_string(),
+            url: None,
+        }
+    }
+
+    #[test]
+    fn should_replace_chapters() {
+        let conn = gen_database();
+        migration::migrate(&conn).unwrap();
+
+        conn.execute_batch(indoc::indoc! {"
+            INSERT INTO podcasts (id, title, url) VALUES (1, 'Feed', 'https://example.com/feed');
+            INSERT INTO episodes (id, podcast_id, title, url) VALUES (1, 1, 'Episode', 'https://example.com/1.mp3');
+        "})
+        .unwrap();
+
+        let id = EpisodeId(1);
+        assert!(get_chapters(id, &conn).unwrap().is_empty());
+
+        set_chapters(id, &[chapter(60, "Second"), chapter(0, "Intro")], &conn).unwrap();
+        assert_eq!(
+            get_chapters(id, &conn).unwrap(),
+            [chapter(0, "Intro"), chapter(60, "Second")]
+        );
+
+        set_chapters(id, &[chapter(10, "Only")], &conn).unwrap();
+        assert_eq!(get_chapters(id, &conn).unwrap(), [chapter(10, "Only")]);
+    }
+}
@@ -4,13 +4,14 @@ use rusqlite::{Connection, Row, named_params, params};
 
 use crate::podcast::episode::EpisodeNoId;
 
-use super::{PodcastDBId, convert_date};
+use super::convert_date;
+use crate::ids::{EpisodeId, PodcastId};
 
 /// A struct representing a episode in a podcast in the database
 #[derive(Debug, Clone)]
 pub struct EpisodeDB {
-    pub id: PodcastDBId,
-    pub pod_id: PodcastDBId,
+    pub id: EpisodeId,
+    pub pod_id: PodcastId,
     pub title: String,
     pub url: String,
     pub guid: String,
@@ -22,6 +23,8 @@ pub struct EpisodeDB {
     pub hidden: bool,
     pub last_position: Option<i64>,
     pub image_url: Option<String>,
+    pub fetched: Option<DateTime<Utc>>,
+    pub explicit: Option<bool>,
 }
 
 impl EpisodeDB {
@@ -42,6 +45,8 @@ impl EpisodeDB {
             hidden: row.get("hidden")?,
             last_position: row.get("last_position")?,
             image_url: row.get("image_url")?,
+            fetched: convert_date(&row.get("fetched")),
+            explicit: row.get("explicit")?,
         })
     }
 
@@ -61,6 +66,8 @@ impl EpisodeDB {
             hidden: row.get("hidden")?,
             last_position: row.get("last_position")?,
             image_url: row.get("image_url")?,
+            fetched: convert_date(&row.get("fetched")),
+            explicit: row.get("explicit")?,
         })
     }
 }
@@ -71,8 +78,8 @@ impl EpisodeDB {
 #[derive(Debug, Clone)]
 pub struct EpisodeDBInsertable<'a> {
     // generated by the database
-    // pub id: PodcastDBId,
-    pub pod_id: PodcastDBId,
+    // pub id: EpisodeId,
+    pub pod_id: PodcastId,
     pub title: &'a str,
     pub url: &'a str,
     pub guid: &'a str,
@@ -83,11 +90,13 @@ pub struct EpisodeDBInsertable<'a> {
     pub hidden: bool,
     pub last_position: Option<i64>,
     pub image_url: Option<&'a str>,
+    pub fetched: DateTime<Utc>,
+    pub explicit: Option<bool>,
 }
 
 impl<'a> EpisodeDBInsertable<'a> {
     /// Basically the [`From`] implementation, but more data than [`EpisodeNoId`] has is needed
-    pub fn new(value: &'a EpisodeNoId, pod_id: PodcastDBId) -> Self {
+    pub fn new(value: &'a EpisodeNoId, pod_id: PodcastId) -> Self {
         Self {
             pod_id,
             title: &value.title,
@@ -100,6 +109,8 @@ impl<'a> EpisodeDBInsertable<'a> {
             hidden: false,
             last_position: Some(0),
             image_url: value.image_url.as_deref(),
+            fetched: Utc::now(),
+            explicit: value.explicit,
         }
     }
 
@@ -108,8 +119,8 @@ impl<'a> EpisodeDBInsertable<'a> {
     pub fn insert_episode(&self, con: &Connection) -> Result<usize, rusqlite::Error> {
         let mut stmt = con.prepare_cached(indoc! {"
             INSERT INTO episodes (podcast_id, title, url, guid,
-                description, pubdate, duration, played, hidden, last_position, image_url)
-            VALUES (:podid, :title, :url, :guid, :description, :pubdate, :duration, :played, :hidden, :last_position, :image_url);
+                description, pubdate, duration, played, hidden, last_position, image_url, fetched, explicit)
+            VALUES (:podid, :title, :url, :guid, :description, :pubdate, :duration, :played, :hidden, :last_position, :image_url, :fetched, :explicit);
         "})?;
         stmt.execute(named_params![
             ":podid": self.pod_id,
@@ -123,6 +134,8 @@ impl<'a> EpisodeDBInsertable<'a> {
             ":hidden": self.hidden,
             ":last_position": self.last_position,
             ":image_url": self.image_url,
+            ":fetched": self.fetched.timestamp(),
+            ":explicit": self.explicit,
         ])
     }
 
@@ -136,13 +149,13 @@ impl<'a> EpisodeDBInsertable<'a> {
     #[inline]
     pub fn update_episode(
         &self,
-        id: PodcastDBId,
+        id: EpisodeId,
         con: &Connection,
     ) -> Result<usize, rusqlite::Error> {
         let mut stmt = con.prepare_cached(indoc! {"
             UPDATE episodes SET title = :title, url = :url,
                 guid = :guid, description = :description, pubdate = :pubdate,
-            duration = :duration, image_url = :image_url WHERE id = :epid;
+            duration = :duration, image_url = :image_url, explicit = :explicit WHERE id = :epid;
         "})?;
         stmt.execute(named_params![
             ":title": self.title,
@@ -152,6 +165,7 @@ impl<'a> EpisodeDBInsertable<'a> {
             ":pubdate": self.pubdate.map(|v| v.timestamp()),
             ":duration": self.duration,
             ":image_url": self.duration,
+            ":explicit": self.explicit,
             ":epid": id,
         ])
     }
@@ -161,7 +175,7 @@ impl<'a> EpisodeDBInsertable<'a> {
 ///
 /// This also deletes all associated files (not removing the actual files)!
 #[allow(dead_code)]
-pub fn delete_episode(id: PodcastDBId, con: &Connection) -> Result<usize, rusqlite::Error> {
+pub fn delete_episode(id: EpisodeId, con: &Connection) -> Result<usize, rusqlite::Error> {
     let mut stmt = con.prepare_cached("DELETE FROM episodes WHERE id = ?;")?;
     stmt.execute(params![id])
 }
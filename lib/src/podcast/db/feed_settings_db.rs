@@ -0,0 +1,119 @@
+use indoc::indoc;
+use rusqlite::{Connection, OptionalExtension, named_params, params};
+
+use crate::ids::PodcastId;
+use crate::podcast::feed_settings::FeedSettings;
+
+/// Get the settings of `podcast_id`, or the default if none are stored.
+pub fn get_feed_settings(
+    podcast_id: PodcastId,
+    con: &Connection,
+) -> Result<FeedSettings, rusqlite::Error> {
+    let settings = con
+        .query_row(
+            indoc! {"
+                SELECT auto_download, keep_downloaded, max_age_days, skip_intro, skip_outro
+                FROM feed_settings
+                WHERE podcast_id = ?;
+            "},
+            params![podcast_id],
+            |row| {
+                Ok(FeedSettings {
+                    auto_download: row.get("auto_download")?,
+                    keep_downloaded: row.get("keep_downloaded")?,
+                    max_age_days: row.get("max_age_days")?,
+                    skip_intro: row.get("skip_intro")?,
+                    skip_outro: row.get("skip_outro")?,
+                })
+            },
+        )
+        .optional()?;
+
+    Ok(settings.unwrap_or_default())
+}
+
+/// Store the settings of `podcast_id`, removing the row if all are disabled.
+pub fn set_feed_settings(
+    podcast_id: PodcastId,
+    settings: &FeedSettings,
+    con: &Connection,
+) -> Result<(), rusqlite::Error> {
+    if settings.is_disabled() {
+        con.execute(
+            "DELETE FROM feed_settings WHERE podcast_id = ?;",
+            params![podcast_id],
+        )?;
+
+        return Ok(());
+    }
+
+    con.execute(
+        indoc! {"
+            INSERT INTO feed_settings
+                (podcast_id, auto_download, keep_downloaded, max_age_days, skip_intro, skip_outro)
+            VALUES (:id, :download, :keep, :age, :intro, :outro)
+            ON CONFLICT(podcast_id) DO UPDATE SET
+                auto_download = :download, keep_downloaded = :keep, max_age_days = :age,
+                skip_intro = :intro, skip_outro = :outro;
+        "},
+        named_params! {
+            ":id": podcast_id,
+            ":download": settings.auto_download,
+            ":keep": settings.keep_downloaded,
+            ":age": settings.max_age_days,
+            ":intro": settings.skip_intro,
+            ":outro": settings.skip_outro,
+        },
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::super::migration;
+    use super::super::test_utils::gen_database;
+    use super::{get_feed_settings, set_feed_settings};
+    use crate::ids::PodcastId;
+    use crate::podcast::feed_settings::FeedSettings;
+
+    #[test]
+    fn should_store_feed_settings() {
+        let conn = gen_database();
+        migration::migrate(&conn).unwrap();
+
+        conn.execute_batch(
+            "INSERT INTO podcasts (id, title, url) VALUES (1, 'Feed', 'https://example.com/feed');",
+        )
+        .unwrap();
+
+        let id = PodcastId(1);
+        assert_eq!(
+            get_feed_settings(id, &conn).unwrap(),
+            FeedSettings::default()
+        );
+
+        let settings = FeedSettings {
+            auto_download: Some(3),
+            keep_downloaded: Some(5),
+            max_age_days: None,
+            skip_intro: Some(30),
+            skip_outro: None,
+        };
+        set_feed_settings(id, &settings, &conn).unwrap();
+        set_feed_settings(id, &settings, &conn).unwrap();
+        assert_eq!(get_feed_settings(id, &conn).unwrap(), settings);
+
+        set_feed_settings(id, &FeedSettings::default(), &conn).unwrap();
+        assert_eq!(
+            get_feed_settings(id, &conn).unwrap(),
+            FeedSettings::default()
+        );
+        let rows: i64 = conn
+            .query_row("SELECT COUNT(*) FROM feed_settings;", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(rows, 0);
+    }
+}
@@ -1,16 +1,16 @@
 use std::path::{Path, PathBuf};
 
 use indoc::indoc;
-use rusqlite::{Connection, Row, named_params, params};
+use rusqlite::{Connection, OptionalExtension, Row, named_params, params};
 
-use super::PodcastDBId;
+use crate::ids::EpisodeId;
 
 /// A struct representing a episode file (downloaded) in the database
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct FileDB {
-    pub id: PodcastDBId,
-    pub episode_id: PodcastDBId,
+    pub id: i64,
+    pub episode_id: EpisodeId,
     pub path: PathBuf,
 }
 
@@ -45,13 +45,13 @@ impl FileDB {
 #[derive(Debug, Clone)]
 pub struct FileDBInsertable<'a> {
     // generated by the database
-    // pub id: PodcastDBId,
-    pub episode_id: PodcastDBId,
+    // pub id: i64,
+    pub episode_id: EpisodeId,
     pub path: &'a Path,
 }
 
 impl<'a> FileDBInsertable<'a> {
-    pub fn new(episode_id: PodcastDBId, path: &'a Path) -> Self {
+    pub fn new(episode_id: EpisodeId, path: &'a Path) -> Self {
         Self { episode_id, path }
     }
 
@@ -69,10 +69,20 @@ impl<'a> FileDBInsertable<'a> {
     }
 }
 
+/// Get the id of the episode that references `path`, if any
+pub fn episode_for_path(
+    path: &Path,
+    con: &Connection,
+) -> Result<Option<EpisodeId>, rusqlite::Error> {
+    let mut stmt = con.prepare_cached("SELECT episode_id FROM files WHERE path = ?;")?;
+    stmt.query_row(params![path.to_string_lossy()], |row| row.get(0))
+        .optional()
+}
+
 /// Delete a file by episode id
 ///
 /// This does *not* remove the actual (on disk) files!
-pub fn delete_file(id: PodcastDBId, con: &Connection) -> Result<usize, rusqlite::Error> {
+pub fn delete_file(id: EpisodeId, con: &Connection) -> Result<usize, rusqlite::Error> {
     let mut stmt = con.prepare_cached("DELETE FROM files WHERE episode_id = ?;")?;
     stmt.execute(params![id])
 }
@@ -80,7 +90,7 @@ pub fn delete_file(id: PodcastDBId, con: &Connection) -> Result<usize, rusqlite:
 /// Delete multiple files by episode id
 ///
 /// This does *not* remove the actual (on disk) files!
-pub fn delete_files(ids: &[PodcastDBId], con: &Connection) -> Result<usize, rusqlite::Error> {
+pub fn delete_files(ids: &[EpisodeId], con: &Connection) -> Result<usize, rusqlite::Error> {
     // convert list of episode ids into a comma-separated String
     let episode_list: Vec<String> = ids.iter().map(std::string::ToString::to_string).collect();
     let episodes = episode_list.join(", ");
@@ -88,3 +98,33 @@ pub fn delete_files(ids: &[PodcastDBId], con: &Connection) -> Result<usize, rusq
     let mut stmt = con.prepare_cached("DELETE FROM files WHERE episode_id = (?);")?;
     stmt.execute(params![episodes])
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use super::super::migration::migrate;
+    use super::super::test_utils::gen_database;
+    use super::{FileDBInsertable, episode_for_path};
+    use crate::ids::EpisodeId;
+
+    #[test]
+    fn should_find_episode_for_path() {
+        let conn = gen_database();
+        migrate(&conn).unwrap();
+
+        let path = Path::new("/podcasts/feed/episode.mp3");
+        assert_eq!(episode_for_path(path, &conn).unwrap(), None);
+
+        FileDBInsertable::new(EpisodeId(1), path)
+            .insert_file(&conn)
+            .unwrap();
+        assert_eq!(episode_for_path(path, &conn).unwrap(), Some(EpisodeId(1)));
+        assert_eq!(
+            episode_for_path(Path::new("/podcasts/feed/other.mp3"), &conn).unwrap(),
+            None
+        );
+    }
+}
@@ -1,10 +1,11 @@
-use anyhow::{Context, Result, bail};
 use indoc::indoc;
-use rusqlite::{Connection, params};
+use rusqlite::{Connection, Result, params};
 use semver::Version;
 
+use crate::new_database::DatabaseError;
+
 /// The Current Database schema version this application is meant to run against
-pub(super) const DB_VERSION: u32 = 1;
+pub(super) const DB_VERSION: u32 = 10;
 
 /// Helper function to get the `user_version` with a single function call
 #[inline]
@@ -12,7 +13,6 @@ fn get_user_version(conn: &Connection) -> Result<u32> {
     conn.query_row("SELECT user_version FROM pragma_user_version", [], |r| {
         r.get(0)
     })
-    .context("get pragma \"user_version\"")
 }
 
 /// Helper function to set the `user_version` with a single function call
@@ -20,20 +20,29 @@ fn get_user_version(conn: &Connection) -> Result<u32> {
 /// Returns the passed version for re-use
 #[inline]
 fn set_user_version(conn: &Connection, version: u32) -> Result<u32> {
-    conn.pragma_update(None, "user_version", version)
-        .context("update user_version error")?;
+    conn.pragma_update(None, "user_version", version)?;
 
     Ok(version)
 }
 
+/// Apply the migration `sql` and set the `user_version` to `version`
+///
+/// Returns the passed version for re-use
+fn apply_migration(conn: &Connection, version: u32, sql: &str) -> Result<u32, DatabaseError> {
+    conn.execute_batch(sql)
+        .and_then(|()| set_user_version(conn, version))
+        .map_err(|source| DatabaseError::Migration { version, source })
+}
+
 /// Create / Migrate everything in the database, if necessary
-pub(super) fn migrate(conn: &Connection) -> Result<()> {
-    let user_version: u32 = get_user_version(conn)?;
+pub(super) fn migrate(conn: &Connection) -> Result<(), DatabaseError> {
+    let user_version: u32 = get_user_version(conn).map_err(DatabaseError::Version)?;
 
     if user_version > DB_VERSION {
-        bail!(
-            "Expected Database version to be lower or equal to {DB_VERSION}, found {user_version}!"
-        );
+        return Err(DatabaseError::NewerVersion {
+            expected: DB_VERSION,
+            found: user_version,
+        });
     }
 
     // only execute migrations if not already done so
@@ -41,7 +50,10 @@ pub(super) fn migrate(conn: &Connection) -> Result<()> {
         apply_migrations(conn, user_version)?;
     }
 
-    update_version_col(conn)?;
+    update_version_col(conn).map_err(|source| DatabaseError::Migration {
+        version: DB_VERSION,
+        source,
+    })?;
 
     Ok(())
 }
@@ -50,13 +62,47 @@ pub(super) fn migrate(conn: &Connection) -> Result<()> {
 ///
 /// Migrates from `0` to [`DB_VERSION`]
 #[allow(unused_assignments)] // for future possible migrations
-fn apply_migrations(conn: &Connection, mut user_version: u32) -> Result<()> {
+fn apply_migrations(conn: &Connection, mut user_version: u32) -> Result<(), DatabaseError> {
     // do all migrations in steps, this way everyone is in the same state and had the same things applied, even for new things
     if user_version == 0 {
         // Version 1 is the base version, so there are basically no migrations, only creations
-        conn.execute_batch(include_str!("./migrations/001.sql"))
-            .context("PodcastDatabase version 1 could not be created")?;
-        user_version = set_user_version(conn, 1)?;
+        user_version = apply_migration(conn, 1, include_str!("./migrations/001.sql"))?;
+    }
+
+    if user_version == 1 {
+        user_version = apply_migration(conn, 2, include_str!("./migrations/002.sql"))?;
+    }
+
+    if user_version == 2 {
+        user_version = apply_migration(conn, 3, include_str!("./migrations/003.sql"))?;
+    }
+
+    if user_version == 3 {
+        user_version = apply_migration(conn, 4, include_str!("./migrations/004.sql"))?;
+    }
+
+    if user_version == 4 {
+        user_version = apply_migration(conn, 5, include_str!("./migrations/005.sql"))?;
+    }
+
+    if user_version == 5 {
+        user_version = apply_migration(conn, 6, include_str!("./migrations/006.sql"))?;
+    }
+
+    if user_version == 6 {
+        user_version = apply_migration(conn, 7, include_str!("./migrations/007.sql"))?;
+    }
+
+    if user_version == 7 {
+        user_version = apply_migration(conn, 8, include_str!("./migrations/008.sql"))?;
+    }
+
+    if user_version == 8 {
+        user_version = apply_migration(conn, 9, include_str!("./migrations/009.sql"))?;
+    }
+
+    if user_version == 9 {
+        user_version = apply_migration(conn, 10, include_str!("./migrations/010.sql"))?;
     }
 
     Ok(())
@@ -71,12 +117,12 @@ fn update_version_col(conn: &Connection) -> Result<()> {
     let vstr: Result<String, rusqlite::Error> = stmt.query_row([], |row| row.get("version"));
 
     // compare to current app version
-    let curr_ver = Version::parse(crate::VERSION)?;
+    let curr_ver = Version::parse(crate::VERSION).expect("crate version to be valid semver");
 
     match vstr {
         Ok(vstr) => {
-            let db_version = Version::parse(&vstr)?;
-            if db_version < curr_ver {
+            // a unparsable version is treated like the current version, as there are no version based migrations
+            if Version::parse(&vstr).is_ok_and(|db_version| db_version < curr_ver) {
                 // any version checks for DB migrations should
                 // go here first, before we update the version
 
@@ -130,7 +176,7 @@ mod tests {
 
         assert_eq!(0, get_user_version(&conn).unwrap());
         migrate(&conn).unwrap();
-        assert_eq!(1, get_user_version(&conn).unwrap());
+        assert_eq!(10, get_user_version(&conn).unwrap());
 
         let all_tracks: Vec<String> = {
             let mut prep = conn.prepare("SELECT name FROM sqlite_schema WHERE type ='table' AND name NOT LIKE 'sqlite_%';").unwrap();
@@ -140,6 +186,33 @@ mod tests {
                 .collect()
         };
 
-        assert_eq!(&all_tracks, &["podcasts", "episodes", "files", "version"]);
+        assert_eq!(
+            &all_tracks,
+            &[
+                "podcasts",
+                "episodes",
+                "files",
+                "version",
+                "sync_state",
+                "chapters",
+                "feed_settings"
+            ]
+        );
+    }
+
+    #[test]
+    fn should_reject_newer_version() {
+        let conn = gen_database();
+
+        set_user_version(&conn, DB_VERSION + 1).unwrap();
+
+        let err = migrate(&conn).unwrap_err();
+        assert!(matches!(
+            err,
+            DatabaseError::NewerVersion {
+                expected: DB_VERSION,
+                found
+            } if found == DB_VERSION + 1
+        ));
     }
 }
@@ -0,0 +1,11 @@
+-- unix timestamp of the last local change to "played" or "last_position", used to find changes to sync
+ALTER TABLE episodes ADD COLUMN state_changed INTEGER;
+
+-- bookkeeping for syncing with a gpodder compatible server, only has a single row with id 1
+CREATE TABLE IF NOT EXISTS sync_state (
+    id INTEGER PRIMARY KEY NOT NULL,
+    -- local unix timestamp of the last successful upload
+    last_push INTEGER NOT NULL,
+    -- server timestamp to request further changes from
+    last_pull INTEGER NOT NULL
+);
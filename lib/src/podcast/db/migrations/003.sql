@@ -0,0 +1,12 @@
+-- chapters of a episode as listed in the feed, replaced on every feed update
+CREATE TABLE IF NOT EXISTS chapters (
+    id INTEGER PRIMARY KEY NOT NULL,
+    episode_id INTEGER NOT NULL,
+    -- start of the chapter in milliseconds
+    start INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    url TEXT,
+    FOREIGN KEY (episode_id) REFERENCES episodes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS chapters_episode_id ON chapters(episode_id);
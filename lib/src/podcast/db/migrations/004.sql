@@ -0,0 +1,2 @@
+-- the top-level category of a feed, like "Technology", used to group feeds
+ALTER TABLE podcasts ADD COLUMN category TEXT;
@@ -0,0 +1,2 @@
+-- unix timestamp of when the episode was first fetched from its feed, unknown for episodes fetched before this column existed
+ALTER TABLE episodes ADD COLUMN fetched INTEGER;
@@ -0,0 +1,11 @@
+-- per-feed settings applied after every refresh of the feed, a missing row or NULL value means disabled
+CREATE TABLE IF NOT EXISTS feed_settings (
+    podcast_id INTEGER PRIMARY KEY NOT NULL,
+    -- download this many of the newest episodes
+    auto_download INTEGER,
+    -- delete the oldest downloaded episodes beyond this many
+    keep_downloaded INTEGER,
+    -- do not auto-download episodes older than this many days
+    max_age_days INTEGER,
+    FOREIGN KEY (podcast_id) REFERENCES podcasts(id) ON DELETE CASCADE
+);
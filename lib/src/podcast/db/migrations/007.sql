@@ -0,0 +1,2 @@
+-- whether the episode is marked as explicit in its feed, NULL if the feed does not say
+ALTER TABLE episodes ADD COLUMN explicit INTEGER;
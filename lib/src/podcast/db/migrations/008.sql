@@ -0,0 +1,2 @@
+-- the "podcast:guid" of the feed, which stays the same if the feed moves, NULL if the feed does not have one
+ALTER TABLE podcasts ADD COLUMN guid TEXT;
@@ -0,0 +1,2 @@
+-- the playback speed of all episodes of the feed in tenths like "player.speed", NULL to use the global speed
+ALTER TABLE podcasts ADD COLUMN speed INTEGER;
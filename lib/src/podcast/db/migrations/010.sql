@@ -0,0 +1,3 @@
+-- seconds to skip at the start and end of every episode of the feed, NULL to play all of it
+ALTER TABLE feed_settings ADD COLUMN skip_intro INTEGER;
+ALTER TABLE feed_settings ADD COLUMN skip_outro INTEGER;
@@ -1,25 +1,29 @@
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use std::time::Duration;
 
 use ahash::AHashMap;
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Context, Result, anyhow, bail};
 use chrono::{DateTime, Utc};
 use episode_db::{EpisodeDB, EpisodeDBInsertable};
 use file_db::{FileDB, FileDBInsertable};
 use indoc::indoc;
-use rusqlite::{Connection, params};
+use rusqlite::{Connection, OpenFlags, OptionalExtension, params};
 
+use super::episode::Chapter;
+use super::feed_settings::FeedSettings;
 use super::{Episode, EpisodeNoId, Podcast, PodcastNoId, RE_ARTICLES};
+use crate::ids::{EpisodeId, PodcastId};
+use crate::new_database::DatabaseError;
 use crate::track::Track;
 use podcast_db::{PodcastDB, PodcastDBInsertable};
 
+mod chapter_db;
 mod episode_db;
+mod feed_settings_db;
 mod file_db;
 mod migration;
 mod podcast_db;
-
-/// The id type used in the podcast database
-pub type PodcastDBId = i64;
+mod sync_db;
 
 #[derive(Debug)]
 pub struct SyncResult {
@@ -27,11 +31,58 @@ pub struct SyncResult {
     pub updated: u64,
 }
 
+/// The amount of rows removed by [`Database::repair_orphans`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepairResult {
+    pub episodes: usize,
+    pub files: usize,
+}
+
+impl RepairResult {
+    /// Get whether anything was removed.
+    #[must_use]
+    pub fn any(&self) -> bool {
+        self.episodes > 0 || self.files > 0
+    }
+}
+
+/// The amount of episodes and files moved by [`Database::merge_podcasts`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MergeResult {
+    /// Episodes of the source that matched a episode of the target
+    pub episodes: usize,
+    /// Downloaded files moved to the target
+    pub files: usize,
+}
+
+/// The state of a single episode exchanged with a sync server, see [`super::gpodder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpisodeSyncState {
+    pub podcast_url: String,
+    pub episode_url: String,
+    pub guid: String,
+    pub played: bool,
+    /// Playback position in seconds
+    pub position: i64,
+    /// Duration in seconds, if known
+    pub duration: Option<i64>,
+    /// Unix timestamp of when this state was set
+    pub changed: i64,
+}
+
+/// Bookkeeping of the last sync with a sync server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SyncState {
+    /// Local unix timestamp of the last successful upload
+    pub last_push: i64,
+    /// Server timestamp to request further changes from
+    pub last_pull: i64,
+}
+
 /// Struct holding a sqlite database connection, with methods to interact
 /// with this connection.
 #[derive(Debug)]
 pub struct Database {
-    path: PathBuf,
     conn: Connection,
 }
 
@@ -43,33 +94,62 @@ impl Database {
     ///
     /// - if creating / opening the database fails
     /// - if migration fails
-    pub fn new(path: &Path) -> Result<Database> {
+    pub fn new(path: &Path) -> Result<Database, DatabaseError> {
         let mut db_path = path.to_path_buf();
-        std::fs::create_dir_all(&db_path).context("Unable to create subdirectory for database.")?;
+        std::fs::create_dir_all(&db_path)
+            .map_err(|err| DatabaseError::CreateDir(db_path.clone(), err))?;
         db_path.push("data.db");
-        let conn = Connection::open(&db_path)?;
+        let conn =
+            Connection::open(&db_path).map_err(|err| DatabaseError::Open(db_path.clone(), err))?;
 
-        migration::migrate(&conn).context("Database creation / migration")?;
+        migration::migrate(&conn)?;
 
         // SQLite defaults to foreign key support off
         conn.execute("PRAGMA foreign_keys=ON;", [])
-            .context("Could not set database parameters.")?;
+            .map_err(|err| DatabaseError::Open(db_path.clone(), err))?;
 
-        Ok(Database {
-            path: db_path,
-            conn,
-        })
+        Ok(Database { conn })
+    }
+
+    /// Create a new, migrated database in memory.
+    #[cfg(test)]
+    pub(crate) fn new_in_memory() -> Database {
+        let conn = test_utils::gen_database();
+        migration::migrate(&conn).expect("migrate db failed");
+        conn.execute("PRAGMA foreign_keys=ON;", [])
+            .expect("enable foreign keys failed");
+
+        Database { conn }
+    }
+
+    /// Open the existing database in `path` without migrating it, for clients that only read
+    /// while the server owns the database.
+    ///
+    /// Only methods that read from the database may be used on the returned database.
+    ///
+    /// # Errors
+    ///
+    /// - if the database does not exist yet
+    /// - if opening the database fails
+    pub fn open_read_only(path: &Path) -> Result<Database, DatabaseError> {
+        let db_path = path.join("data.db");
+        let conn = Connection::open_with_flags(
+            &db_path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .map_err(|err| DatabaseError::Open(db_path.clone(), err))?;
+
+        Ok(Database { conn })
     }
 
     /// Inserts a new podcast and list of podcast episodes into the
     /// database.
-    pub fn insert_podcast(&self, podcast: &PodcastNoId) -> Result<u64> {
-        let mut conn = Connection::open(&self.path).context("Error connecting to database.")?;
-        let tx = conn.transaction()?;
+    pub fn insert_podcast(&self, podcast: &PodcastNoId) -> Result<u64, rusqlite::Error> {
+        let tx = self.conn.unchecked_transaction()?;
 
         PodcastDBInsertable::from(podcast).insert_podcast(&tx)?;
 
-        let pod_id: PodcastDBId = {
+        let pod_id: PodcastId = {
             let mut stmt = tx.prepare_cached("SELECT id FROM podcasts WHERE url = ?")?;
             stmt.query_row(params![podcast.url], |row| row.get(0))?
         };
@@ -86,16 +166,32 @@ impl Database {
     /// Inserts a podcast episode into the database.
     pub fn insert_episode(
         conn: &Connection,
-        podcast_id: PodcastDBId,
+        podcast_id: PodcastId,
         episode: &EpisodeNoId,
-    ) -> Result<PodcastDBId> {
+    ) -> Result<EpisodeId, rusqlite::Error> {
         EpisodeDBInsertable::new(episode, podcast_id).insert_episode(conn)?;
+        let id = EpisodeId(conn.last_insert_rowid());
 
-        Ok(conn.last_insert_rowid())
+        if !episode.chapters.is_empty() {
+            chapter_db::set_chapters(id, &episode.chapters, conn)?;
+        }
+
+        Ok(id)
     }
 
     /// Inserts a filepath to a downloaded episode.
-    pub fn insert_file(&self, episode_id: PodcastDBId, path: &Path) -> Result<()> {
+    ///
+    /// Fails if `path` is already referenced by another episode.
+    pub fn insert_file(&self, episode_id: EpisodeId, path: &Path) -> Result<()> {
+        if let Some(other_id) = file_db::episode_for_path(path, &self.conn)? {
+            if other_id != episode_id {
+                bail!(
+                    "Path \"{}\" is already used by episode {other_id}",
+                    path.display()
+                );
+            }
+        }
+
         FileDBInsertable::new(episode_id, path).insert_file(&self.conn)?;
 
         Ok(())
@@ -103,33 +199,61 @@ impl Database {
 
     /// Removes a file listing for an episode from the database when the
     /// user has chosen to delete the file.
-    pub fn remove_file(&self, episode_id: PodcastDBId) -> Result<()> {
+    pub fn remove_file(&self, episode_id: EpisodeId) -> Result<()> {
         file_db::delete_file(episode_id, &self.conn)?;
 
         Ok(())
     }
 
     /// Removes all file listings for the selected episode ids.
-    pub fn remove_files(&self, episode_ids: &[PodcastDBId]) -> Result<()> {
+    pub fn remove_files(&self, episode_ids: &[EpisodeId]) -> Result<()> {
         file_db::delete_files(episode_ids, &self.conn)?;
 
         Ok(())
     }
 
     /// Removes a podcast, all episodes, and files from the database.
-    pub fn remove_podcast(&self, podcast_id: PodcastDBId) -> Result<()> {
+    pub fn remove_podcast(&self, podcast_id: PodcastId) -> Result<()> {
         podcast_db::delete_podcast(podcast_id, &self.conn)?;
 
         Ok(())
     }
 
+    /// Merge the podcast `source` into `target`, for example when a show moved to a different host.
+    ///
+    /// The played status, position and downloaded file of every episode of `source` are moved to the
+    /// episode of `target` with the same guid, or the same title if there is no guid match.
+    /// Afterwards `source` is removed with all its episodes.
+    pub fn merge_podcasts(&self, source: PodcastId, target: PodcastId) -> Result<MergeResult> {
+        if source == target {
+            bail!("Cannot merge a podcast into itself");
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        let result = merge_podcasts(&tx, source, target).context("merge podcasts")?;
+        tx.commit()?;
+
+        Ok(result)
+    }
+
     /// Updates an existing podcast in the database, where metadata is
     /// changed if necessary, and episodes are updated (modified episodes
     /// are updated, new episodes are inserted).
-    pub fn update_podcast(&self, pod_id: PodcastDBId, podcast: &PodcastNoId) -> Result<SyncResult> {
-        PodcastDBInsertable::from(podcast).update_podcast(pod_id, &self.conn)?;
+    ///
+    /// All changes are done in one transaction, so a interrupted sync does not leave partial state.
+    pub fn update_podcast(
+        &self,
+        pod_id: PodcastId,
+        podcast: &PodcastNoId,
+    ) -> Result<SyncResult, rusqlite::Error> {
+        let old_episodes = self.get_episodes(pod_id, true)?;
+
+        let tx = self.conn.unchecked_transaction()?;
+
+        PodcastDBInsertable::from(podcast).update_podcast(pod_id, &tx)?;
+        let result = Self::update_episodes(&tx, pod_id, &old_episodes, &podcast.episodes)?;
 
-        let result = self.update_episodes(pod_id, &podcast.episodes)?;
+        tx.commit()?;
         Ok(result)
     }
 
@@ -142,21 +266,18 @@ impl Database {
     /// a "new" episode. The old version will still remain in the
     /// database.
     fn update_episodes(
-        &self,
-        podcast_id: PodcastDBId,
+        tx: &Connection,
+        podcast_id: PodcastId,
+        old_episodes: &[Episode],
         episodes: &[EpisodeNoId],
-    ) -> Result<SyncResult> {
-        let old_episodes = self.get_episodes(podcast_id, true)?;
+    ) -> Result<SyncResult, rusqlite::Error> {
         let mut old_ep_map = AHashMap::new();
-        for ep in &old_episodes {
+        for ep in old_episodes {
             if !ep.guid.is_empty() {
                 old_ep_map.insert(&ep.guid, ep);
             }
         }
 
-        let mut conn = Connection::open(&self.path).context("Error connecting to database.")?;
-        let tx = conn.transaction()?;
-
         let mut inserted = 0;
         let mut updated = 0;
         for new_ep in episodes.iter().rev() {
@@ -200,17 +321,21 @@ impl Database {
 
             if let Some(id) = existing_id {
                 if update {
-                    EpisodeDBInsertable::new(new_ep, podcast_id).update_episode(id, &tx)?;
+                    EpisodeDBInsertable::new(new_ep, podcast_id).update_episode(id, tx)?;
 
                     updated += 1;
                 }
+
+                // chapters are not compared in "check_for_updates", so they are always replaced if there are any
+                if update || !new_ep.chapters.is_empty() {
+                    chapter_db::set_chapters(id, &new_ep.chapters, tx)?;
+                }
             } else {
-                Self::insert_episode(&tx, podcast_id, new_ep)?;
+                Self::insert_episode(tx, podcast_id, new_ep)?;
 
                 inserted += 1;
             }
         }
-        tx.commit()?;
         Ok(SyncResult {
             added: inserted,
             updated,
@@ -241,26 +366,24 @@ impl Database {
     }
 
     /// Updates an episode to mark it as played or unplayed.
-    pub fn set_played_status(&self, episode_id: PodcastDBId, played: bool) -> Result<()> {
+    pub fn set_played_status(&self, episode_id: EpisodeId, played: bool) -> Result<()> {
         let mut stmt = self
             .conn
-            .prepare_cached("UPDATE episodes SET played = ? WHERE id = ?;")?;
-        stmt.execute(params![played, episode_id])?;
+            .prepare_cached("UPDATE episodes SET played = ?, state_changed = ? WHERE id = ?;")?;
+        stmt.execute(params![played, Utc::now().timestamp(), episode_id])?;
         Ok(())
     }
 
     /// Updates an episode to mark it as played or unplayed.
-    pub fn set_all_played_status(
-        &self,
-        episode_id_vec: &[PodcastDBId],
-        played: bool,
-    ) -> Result<()> {
-        let mut conn = Connection::open(&self.path).context("Error connecting to database.")?;
-        let tx = conn.transaction()?;
+    pub fn set_all_played_status(&self, episode_id_vec: &[EpisodeId], played: bool) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        let now = Utc::now().timestamp();
 
         for episode_id in episode_id_vec {
-            let mut stmt = tx.prepare_cached("UPDATE episodes SET played = ? WHERE id = ?;")?;
-            stmt.execute(params![played, episode_id])?;
+            let mut stmt = tx.prepare_cached(
+                "UPDATE episodes SET played = ?, state_changed = ? WHERE id = ?;",
+            )?;
+            stmt.execute(params![played, now, episode_id])?;
         }
         tx.commit()?;
         Ok(())
@@ -269,7 +392,7 @@ impl Database {
     /// Updates an episode to "remove" it by hiding it. "Removed"
     /// episodes need to stay in the database so that they don't get
     /// re-added when the podcast is synced again.
-    pub fn hide_episode(&self, episode_id: PodcastDBId, hide: bool) -> Result<()> {
+    pub fn hide_episode(&self, episode_id: EpisodeId, hide: bool) -> Result<()> {
         let mut stmt = self
             .conn
             .prepare_cached("UPDATE episodes SET hidden = ? WHERE id = ?;")?;
@@ -279,7 +402,7 @@ impl Database {
 
     /// Generates list of all podcasts in database.
     /// TODO: This should probably use a JOIN statement instead.
-    pub fn get_podcasts(&self) -> Result<Vec<Podcast>> {
+    pub fn get_podcasts(&self) -> Result<Vec<Podcast>, rusqlite::Error> {
         let mut stmt = self.conn.prepare_cached("SELECT * FROM podcasts;")?;
         let podcasts = stmt
             .query_map([], PodcastDB::try_from_row_named)?
@@ -304,6 +427,8 @@ impl Database {
                     last_checked: podcast.last_checked,
                     episodes,
                     image_url: podcast.image_url,
+                    category: podcast.category,
+                    guid: podcast.guid,
                 })
             })
             .collect::<Result<_, rusqlite::Error>>()?;
@@ -312,7 +437,11 @@ impl Database {
     }
 
     /// Generates list of episodes for a given podcast.
-    pub fn get_episodes(&self, pod_id: PodcastDBId, include_hidden: bool) -> Result<Vec<Episode>> {
+    pub fn get_episodes(
+        &self,
+        pod_id: PodcastId,
+        include_hidden: bool,
+    ) -> Result<Vec<Episode>, rusqlite::Error> {
         let mut stmt = if include_hidden {
             self.conn.prepare_cached(indoc! {
                 "SELECT episodes.id as epid, files.id as fileid, * FROM episodes
@@ -348,6 +477,8 @@ impl Database {
                     played: episode.played,
                     last_position: episode.last_position,
                     image_url: episode.image_url,
+                    fetched: episode.fetched,
+                    explicit: episode.explicit,
                 })
             })?
             .flatten()
@@ -383,6 +514,8 @@ impl Database {
                     played: episode.played,
                     last_position: episode.last_position,
                     image_url: episode.image_url,
+                    fetched: episode.fetched,
+                    explicit: episode.explicit,
                 })
             })?
             .flatten()
@@ -391,14 +524,162 @@ impl Database {
         episode.ok_or(anyhow!("No Episode found with url \"{ep_uri}\""))
     }
 
+    /// Get whether the episode with url `ep_uri` or its podcast is marked as explicit.
+    ///
+    /// Unknown episodes are not explicit.
+    pub fn is_episode_explicit(&self, ep_uri: &str) -> Result<bool> {
+        let mut stmt = self.conn.prepare_cached(indoc! {"
+            SELECT episodes.explicit, podcasts.explicit FROM episodes
+            INNER JOIN podcasts ON podcasts.id = episodes.podcast_id
+            WHERE episodes.url = ?;
+        "})?;
+
+        let explicit = stmt
+            .query_row(params![ep_uri], |row| {
+                let episode: Option<bool> = row.get(0)?;
+                let podcast: Option<bool> = row.get(1)?;
+                Ok(episode == Some(true) || podcast == Some(true))
+            })
+            .optional()?;
+
+        Ok(explicit.unwrap_or_default())
+    }
+
+    /// Get the chapters of a episode as listed in the feed, sorted by start.
+    pub fn get_chapters(&self, episode_id: EpisodeId) -> Result<Vec<Chapter>> {
+        let chapters = chapter_db::get_chapters(episode_id, &self.conn)?;
+
+        Ok(chapters)
+    }
+
     /// Deletes all rows in all tables
     pub fn clear_db(&self) -> Result<()> {
+        self.conn.execute("DELETE FROM chapters;", [])?;
+        self.conn.execute("DELETE FROM feed_settings;", [])?;
         self.conn.execute("DELETE FROM files;", [])?;
         self.conn.execute("DELETE FROM episodes;", [])?;
         self.conn.execute("DELETE FROM podcasts;", [])?;
+        self.conn.execute("DELETE FROM sync_state;", [])?;
+        Ok(())
+    }
+
+    /// Get the sync state of all episodes whose played status or position changed locally after `since`.
+    pub fn get_episodes_changed_since(&self, since: i64) -> Result<Vec<EpisodeSyncState>> {
+        let states = sync_db::get_changed_since(since, &self.conn)?;
+
+        Ok(states)
+    }
+
+    /// Apply `states` from a sync server to the episodes with the same url, unless they changed locally since.
+    ///
+    /// Returns the amount of episodes changed.
+    pub fn apply_sync_states(&self, states: &[EpisodeSyncState]) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+
+        let mut changed = 0;
+        for state in states {
+            if sync_db::apply_state(state, &tx)? {
+                changed += 1;
+            }
+        }
+        tx.commit()?;
+
+        Ok(changed)
+    }
+
+    /// Get a number that changes whenever another connection, like another process, commits to the database.
+    ///
+    /// Commits through this connection do not change it.
+    pub fn data_version(&self) -> Result<i64, rusqlite::Error> {
+        self.conn
+            .query_row("PRAGMA data_version;", [], |row| row.get(0))
+    }
+
+    /// Get the bookkeeping of the last sync.
+    pub fn get_sync_state(&self) -> Result<SyncState> {
+        let state = sync_db::get_sync_state(&self.conn)?;
+
+        Ok(state)
+    }
+
+    /// Store the bookkeeping of the last sync.
+    pub fn set_sync_state(&self, state: SyncState) -> Result<()> {
+        sync_db::set_sync_state(state, &self.conn)?;
+
+        Ok(())
+    }
+
+    /// Get the settings of the podcast `podcast_id`, all disabled if never set.
+    pub fn get_feed_settings(&self, podcast_id: PodcastId) -> Result<FeedSettings> {
+        let settings = feed_settings_db::get_feed_settings(podcast_id, &self.conn)?;
+
+        Ok(settings)
+    }
+
+    /// Store the settings of the podcast `podcast_id`.
+    pub fn set_feed_settings(&self, podcast_id: PodcastId, settings: &FeedSettings) -> Result<()> {
+        feed_settings_db::set_feed_settings(podcast_id, settings, &self.conn)?;
+
         Ok(())
     }
 
+    /// Get the settings of the podcast the episode `track` belongs to.
+    ///
+    /// # Errors
+    ///
+    /// - if `track` is not a episode of a known podcast
+    /// - if the query fails
+    pub fn get_episode_feed_settings(&self, track: &Track) -> Result<FeedSettings> {
+        let podcast_data = track
+            .as_podcast()
+            .ok_or(anyhow!("Track is not a Podcast track!"))?;
+        let episode = self.get_episode_by_url(podcast_data.url())?;
+
+        self.get_feed_settings(episode.pod_id)
+    }
+
+    /// Get the speed of the podcast the episode `track` belongs to, `None` if it uses the global speed.
+    pub fn get_feed_speed(&self, track: &Track) -> Result<Option<i32>> {
+        let podcast_data = track
+            .as_podcast()
+            .ok_or(anyhow!("Track is not a Podcast track!"))?;
+        let speed = podcast_db::get_feed_speed(podcast_data.url(), &self.conn)?;
+
+        Ok(speed)
+    }
+
+    /// Set the speed of the podcast the episode `track` belongs to, `None` to use the global speed.
+    ///
+    /// # Errors
+    ///
+    /// - if `track` is not a episode of a known podcast
+    /// - if the query fails
+    pub fn set_feed_speed(&self, track: &Track, speed: Option<i32>) -> Result<()> {
+        let podcast_data = track
+            .as_podcast()
+            .ok_or(anyhow!("Track is not a Podcast track!"))?;
+        let affected = podcast_db::set_feed_speed(podcast_data.url(), speed, &self.conn)?;
+
+        // update would otherwise fail silently
+        if affected == 0 {
+            bail!(
+                "Episode \"{}\" is not part of a known podcast",
+                podcast_data.url()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Remove episodes whose podcast does not exist anymore and files whose episode does not exist anymore.
+    ///
+    /// This can happen if a previous run was interrupted while foreign keys were not enforced.
+    pub fn repair_orphans(&self) -> Result<RepairResult> {
+        let result = repair_orphans(&self.conn).context("repair orphaned episodes and files")?;
+
+        Ok(result)
+    }
+
     pub fn get_last_position(&mut self, track: &Track) -> Result<Duration> {
         let podcast_data = track
             .as_podcast()
@@ -425,9 +706,16 @@ impl Database {
         let podcast_data = track
             .as_podcast()
             .ok_or(anyhow!("Track is not a Podcast track!"))?;
-        let query = "UPDATE episodes SET last_position = ?1 WHERE url = ?2";
+        let query = "UPDATE episodes SET last_position = ?1, state_changed = ?2 WHERE url = ?3";
         self.conn
-            .execute(query, params![last_position.as_secs(), podcast_data.url(),])
+            .execute(
+                query,
+                params![
+                    last_position.as_secs(),
+                    Utc::now().timestamp(),
+                    podcast_data.url(),
+                ],
+            )
             .context("update last position failed.")?;
         // error!("set last position as {}", last_position.as_secs());
 
@@ -435,6 +723,123 @@ impl Database {
     }
 }
 
+/// Delete all episodes without a podcast and all files without a episode, see [`Database::repair_orphans`].
+fn repair_orphans(conn: &Connection) -> Result<RepairResult, rusqlite::Error> {
+    let episodes = conn.execute(
+        "DELETE FROM episodes WHERE podcast_id NOT IN (SELECT id FROM podcasts);",
+        [],
+    )?;
+    let files = conn.execute(
+        "DELETE FROM files WHERE episode_id NOT IN (SELECT id FROM episodes);",
+        [],
+    )?;
+
+    Ok(RepairResult { episodes, files })
+}
+
+/// The state of a episode relevant for [`merge_podcasts`].
+#[derive(Debug)]
+struct MergeEpisode {
+    id: EpisodeId,
+    guid: Option<String>,
+    title: String,
+    played: bool,
+    last_position: Option<i64>,
+    has_file: bool,
+}
+
+/// Get the state of all episodes of `podcast_id`, see [`MergeEpisode`].
+fn get_merge_episodes(
+    podcast_id: PodcastId,
+    conn: &Connection,
+) -> Result<Vec<MergeEpisode>, rusqlite::Error> {
+    let mut stmt = conn.prepare_cached(indoc! {"
+        SELECT episodes.id, episodes.guid, episodes.title, episodes.played, episodes.last_position,
+            files.id IS NOT NULL
+        FROM episodes
+        LEFT JOIN files ON files.episode_id = episodes.id
+        WHERE episodes.podcast_id = ?;
+    "})?;
+    stmt.query_map(params![podcast_id], |row| {
+        Ok(MergeEpisode {
+            id: row.get(0)?,
+            guid: row.get::<_, Option<String>>(1)?.filter(|v| !v.is_empty()),
+            title: row.get(2)?,
+            played: row.get::<_, Option<bool>>(3)?.unwrap_or_default(),
+            last_position: row.get(4)?,
+            has_file: row.get(5)?,
+        })
+    })?
+    .collect()
+}
+
+/// Find the episode in `targets` matching `episode`, first by guid, then by title.
+///
+/// Episodes in `targets` already in `taken` are never matched again.
+fn find_merge_target<'a>(
+    episode: &MergeEpisode,
+    targets: &'a [MergeEpisode],
+    taken: &[EpisodeId],
+) -> Option<&'a MergeEpisode> {
+    let mut candidates = targets.iter().filter(|v| !taken.contains(&v.id));
+
+    if episode.guid.is_some() {
+        if let Some(found) = candidates
+            .clone()
+            .find(|v| v.guid.is_some() && v.guid == episode.guid)
+        {
+            return Some(found);
+        }
+    }
+
+    candidates.find(|v| v.title == episode.title)
+}
+
+/// Move the state of the episodes of `source` to `target` and delete `source`, see [`Database::merge_podcasts`].
+fn merge_podcasts(
+    conn: &Connection,
+    source: PodcastId,
+    target: PodcastId,
+) -> Result<MergeResult, rusqlite::Error> {
+    let sources = get_merge_episodes(source, conn)?;
+    let targets = get_merge_episodes(target, conn)?;
+    let now = Utc::now().timestamp();
+
+    let mut taken = Vec::new();
+    let mut result = MergeResult {
+        episodes: 0,
+        files: 0,
+    };
+    for episode in &sources {
+        let Some(found) = find_merge_target(episode, &targets, &taken) else {
+            continue;
+        };
+        taken.push(found.id);
+        result.episodes += 1;
+
+        let last_position = found.last_position.max(episode.last_position);
+        conn.prepare_cached(
+            "UPDATE episodes SET played = ?, last_position = ?, state_changed = ? WHERE id = ?;",
+        )?
+        .execute(params![
+            found.played || episode.played,
+            last_position,
+            now,
+            found.id
+        ])?;
+
+        if episode.has_file && !found.has_file {
+            conn.prepare_cached("UPDATE files SET episode_id = ? WHERE episode_id = ?;")?
+                .execute(params![found.id, episode.id])?;
+            result.files += 1;
+        }
+    }
+
+    podcast_db::delete_podcast(source, conn)?;
+
+    Ok(result)
+}
+
 /// Helper function converting an (optional) Unix timestamp to a
 /// `DateTime`<Utc> object
 fn convert_date(result: &Result<i64, rusqlite::Error>) -> Option<DateTime<Utc>> {
@@ -453,3 +858,161 @@ mod test_utils {
         Connection::open_in_memory().expect("open db failed")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::podcast_db::{get_feed_speed, set_feed_speed};
+    use super::test_utils::gen_database;
+    use super::{MergeResult, RepairResult, merge_podcasts, migration, repair_orphans};
+    use crate::ids::PodcastId;
+
+    #[test]
+    fn should_repair_orphans() {
+        let conn = gen_database();
+        migration::migrate(&conn).unwrap();
+
+        conn.execute_batch(indoc::indoc! {"
+            INSERT INTO podcasts (id, title, url) VALUES (1, 'Feed', 'https://example.com/feed');
+            INSERT INTO episodes (id, podcast_id, title, url) VALUES (1, 1, 'Kept', 'https://example.com/1');
+            INSERT INTO episodes (id, podcast_id, title, url) VALUES (2, 2, 'Orphan', 'https://example.com/2');
+            INSERT INTO files (episode_id, path) VALUES (1, '/podcasts/kept.mp3');
+            INSERT INTO files (episode_id, path) VALUES (2, '/podcasts/orphan.mp3');
+            INSERT INTO files (episode_id, path) VALUES (3, '/podcasts/missing.mp3');
+        "})
+        .unwrap();
+
+        assert_eq!(
+            repair_orphans(&conn).unwrap(),
+            RepairResult {
+                episodes: 1,
+                files: 2
+            }
+        );
+        assert_eq!(
+            repair_orphans(&conn).unwrap(),
+            RepairResult {
+                episodes: 0,
+                files: 0
+            }
+        );
+
+        let remaining_files: Vec<String> = conn
+            .prepare("SELECT path FROM files;")
+            .unwrap()
+            .query_map([], |r| r.get(0))
+            .unwrap()
+            .flatten()
+            .collect();
+        assert_eq!(remaining_files, ["/podcasts/kept.mp3"]);
+    }
+
+    #[test]
+    fn should_store_feed_speed() {
+        let conn = gen_database();
+        migration::migrate(&conn).unwrap();
+
+        conn.execute_batch(indoc::indoc! {"
+            INSERT INTO podcasts (id, title, url) VALUES (1, 'Feed', 'https://example.com/feed');
+            INSERT INTO episodes (id, podcast_id, title, url) VALUES (1, 1, 'Episode 1', 'https://example.com/1');
+            INSERT INTO episodes (id, podcast_id, title, url) VALUES (2, 1, 'Episode 2', 'https://example.com/2');
+        "})
+        .unwrap();
+
+        assert_eq!(
+            get_feed_speed("https://example.com/1", &conn).unwrap(),
+            None
+        );
+        assert_eq!(
+            set_feed_speed("https://example.com/1", Some(15), &conn).unwrap(),
+            1
+        );
+        // shared by all episodes of the feed
+        assert_eq!(
+            get_feed_speed("https://example.com/2", &conn).unwrap(),
+            Some(15)
+        );
+        assert_eq!(
+            set_feed_speed("https://example.com/unknown", Some(15), &conn).unwrap(),
+            0
+        );
+        assert_eq!(
+            get_feed_speed("https://example.com/unknown", &conn).unwrap(),
+            None
+        );
+
+        set_feed_speed("https://example.com/2", None, &conn).unwrap();
+        assert_eq!(
+            get_feed_speed("https://example.com/1", &conn).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn should_merge_podcasts() {
+        let conn = gen_database();
+        migration::migrate(&conn).unwrap();
+        conn.execute("PRAGMA foreign_keys=ON;", []).unwrap();
+
+        conn.execute_batch(indoc::indoc! {"
+            INSERT INTO podcasts (id, title, url) VALUES (1, 'Old', 'https://old.example.com/feed');
+            INSERT INTO podcasts (id, title, url) VALUES (2, 'New', 'https://new.example.com/feed');
+            INSERT INTO episodes (id, podcast_id, title, url, guid, played, last_position)
+                VALUES (1, 1, 'Episode 1', 'https://old.example.com/1', 'guid-1', 1, 0);
+            INSERT INTO episodes (id, podcast_id, title, url, guid, played, last_position)
+                VALUES (2, 1, 'Episode 2', 'https://old.example.com/2', '', 0, 120);
+            INSERT INTO episodes (id, podcast_id, title, url, guid, played, last_position)
+                VALUES (3, 1, 'Only old', 'https://old.example.com/3', 'guid-3', 1, 0);
+            INSERT INTO episodes (id, podcast_id, title, url, guid, played, last_position)
+                VALUES (4, 2, 'Episode 1 (renamed)', 'https://new.example.com/1', 'guid-1', 0, 0);
+            INSERT INTO episodes (id, podcast_id, title, url, guid, played, last_position)
+                VALUES (5, 2, 'Episode 2', 'https://new.example.com/2', 'other-guid', 0, 30);
+            INSERT INTO files (episode_id, path) VALUES (1, '/podcasts/old/1.mp3');
+            INSERT INTO files (episode_id, path) VALUES (2, '/podcasts/old/2.mp3');
+            INSERT INTO files (episode_id, path) VALUES (5, '/podcasts/new/2.mp3');
+        "})
+            .unwrap();
+
+        assert_eq!(
+            merge_podcasts(&conn, PodcastId(1), PodcastId(2)).unwrap(),
+            MergeResult {
+                episodes: 2,
+                files: 1
+            }
+        );
+
+        let episodes: Vec<(i64, bool, i64)> = conn
+            .prepare("SELECT id, played, last_position FROM episodes ORDER BY id;")
+            .unwrap()
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+            .unwrap()
+            .flatten()
+            .collect();
+        assert_eq!(episodes, [(4, true, 0), (5, false, 120)]);
+
+        let files: Vec<(i64, String)> = conn
+            .prepare("SELECT episode_id, path FROM files ORDER BY episode_id;")
+            .unwrap()
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap()
+            .flatten()
+            .collect();
+        assert_eq!(
+            files,
+            [
+                (4, "/podcasts/old/1.mp3".to_string()),
+                (5, "/podcasts/new/2.mp3".to_string())
+            ]
+        );
+
+        let podcasts: Vec<i64> = conn
+            .prepare("SELECT id FROM podcasts;")
+            .unwrap()
+            .query_map([], |r| r.get(0))
+            .unwrap()
+            .flatten()
+            .collect();
+        assert_eq!(podcasts, [2]);
+    }
+}
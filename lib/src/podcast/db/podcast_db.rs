@@ -1,14 +1,15 @@
 use chrono::{DateTime, Utc};
 use indoc::indoc;
-use rusqlite::{Connection, Row, named_params, params};
+use rusqlite::{Connection, OptionalExtension, Row, named_params, params};
 
-use super::{PodcastDBId, convert_date};
+use super::convert_date;
+use crate::ids::PodcastId;
 use crate::podcast::PodcastNoId;
 
 /// A struct representing a podcast feed in the database
 #[derive(Debug, Clone)]
 pub struct PodcastDB {
-    pub id: PodcastDBId,
+    pub id: PodcastId,
     pub title: String,
     pub url: String,
     pub description: Option<String>,
@@ -16,6 +17,8 @@ pub struct PodcastDB {
     pub explicit: Option<bool>,
     pub last_checked: DateTime<Utc>,
     pub image_url: Option<String>,
+    pub category: Option<String>,
+    pub guid: Option<String>,
 }
 
 impl PodcastDB {
@@ -33,6 +36,8 @@ impl PodcastDB {
             explicit: row.get("explicit")?,
             last_checked,
             image_url: row.get("image_url")?,
+            category: row.get("category")?,
+            guid: row.get("guid")?,
         })
     }
 }
@@ -43,7 +48,7 @@ impl PodcastDB {
 #[derive(Debug, Clone)]
 pub struct PodcastDBInsertable<'a> {
     // generated by the database
-    // pub id: PodcastDBId,
+    // pub id: PodcastId,
     pub title: &'a str,
     pub url: &'a str,
     pub description: Option<&'a str>,
@@ -51,6 +56,8 @@ pub struct PodcastDBInsertable<'a> {
     pub explicit: Option<bool>,
     pub last_checked: DateTime<Utc>,
     pub image_url: Option<&'a str>,
+    pub category: Option<&'a str>,
+    pub guid: Option<&'a str>,
 }
 
 impl<'a> From<&'a PodcastNoId> for PodcastDBInsertable<'a> {
@@ -63,6 +70,8 @@ impl<'a> From<&'a PodcastNoId> for PodcastDBInsertable<'a> {
             explicit: value.explicit,
             last_checked: value.last_checked,
             image_url: value.image_url.as_deref(),
+            category: value.category.as_deref(),
+            guid: value.guid.as_deref(),
         }
     }
 }
@@ -72,8 +81,8 @@ impl PodcastDBInsertable<'_> {
     #[inline]
     pub fn insert_podcast(&self, con: &Connection) -> Result<usize, rusqlite::Error> {
         let mut stmt = con.prepare_cached(indoc! {"
-            INSERT INTO podcasts (title, url, description, author, explicit, last_checked, image_url)
-            VALUES (:title, :url, :description, :author, :explicit, :last_checked, :image_url);
+            INSERT INTO podcasts (title, url, description, author, explicit, last_checked, image_url, category, guid)
+            VALUES (:title, :url, :description, :author, :explicit, :last_checked, :image_url, :category, :guid);
         "})?;
         stmt.execute(named_params![
             ":title": self.title,
@@ -82,7 +91,9 @@ impl PodcastDBInsertable<'_> {
             ":author": self.author,
             ":explicit": self.explicit,
             ":last_checked": self.last_checked.timestamp(),
-            ":image_url": self.image_url
+            ":image_url": self.image_url,
+            ":category": self.category,
+            ":guid": self.guid
         ])
     }
 
@@ -90,12 +101,13 @@ impl PodcastDBInsertable<'_> {
     #[inline]
     pub fn update_podcast(
         &self,
-        id: PodcastDBId,
+        id: PodcastId,
         con: &Connection,
     ) -> Result<usize, rusqlite::Error> {
         let mut stmt = con.prepare_cached(indoc! {"
             UPDATE podcasts SET title = :title, url = :url, description = :description,
-                author = :author, explicit = :explicit, last_checked = :last_checked
+                author = :author, explicit = :explicit, last_checked = :last_checked,
+                category = :category, guid = :guid
             WHERE id = :id;
         "})?;
         stmt.execute(named_params![
@@ -105,15 +117,46 @@ impl PodcastDBInsertable<'_> {
             ":author": self.author,
             ":explicit": self.explicit,
             ":last_checked": self.last_checked.timestamp(),
+            ":category": self.category,
+            ":guid": self.guid,
             ":id": id,
         ])
     }
 }
 
+/// Get the speed of the podcast of the episode with url `ep_url`, `None` if it uses the global speed.
+pub fn get_feed_speed(ep_url: &str, con: &Connection) -> Result<Option<i32>, rusqlite::Error> {
+    let mut stmt = con.prepare_cached(indoc! {"
+        SELECT podcasts.speed FROM episodes
+        INNER JOIN podcasts ON podcasts.id = episodes.podcast_id
+        WHERE episodes.url = ?;
+    "})?;
+
+    stmt.query_row(params![ep_url], |row| row.get(0))
+        .optional()
+        .map(Option::flatten)
+}
+
+/// Set the speed of the podcast of the episode with url `ep_url`, `None` to use the global speed.
+///
+/// Returns the amount of changed podcasts.
+pub fn set_feed_speed(
+    ep_url: &str,
+    speed: Option<i32>,
+    con: &Connection,
+) -> Result<usize, rusqlite::Error> {
+    let mut stmt = con.prepare_cached(indoc! {"
+        UPDATE podcasts SET speed = :speed
+        WHERE id = (SELECT podcast_id FROM episodes WHERE url = :url);
+    "})?;
+
+    stmt.execute(named_params! {":speed": speed, ":url": ep_url})
+}
+
 /// Delete a podcast by id
 ///
 /// This also deletes all associated episodes and files (not removing the actual files)!
-pub fn delete_podcast(id: PodcastDBId, con: &Connection) -> Result<usize, rusqlite::Error> {
+pub fn delete_podcast(id: PodcastId, con: &Connection) -> Result<usize, rusqlite::Error> {
     // Note: Because of the foreign key constraints on `episodes`
     // and `files` tables, all associated episodes for this podcast
     // will also be deleted, and all associated file entries for
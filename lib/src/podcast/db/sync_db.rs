@@ -0,0 +1,159 @@
+use indoc::indoc;
+use rusqlite::{Connection, OptionalExtension, Row, named_params, params};
+
+use super::{EpisodeSyncState, SyncState};
+
+impl EpisodeSyncState {
+    /// Try to convert a given row to a [`EpisodeSyncState`] instance, using column names to resolve the values
+    fn try_from_row_named(row: &Row<'_>) -> Result<Self, rusqlite::Error> {
+        // NOTE: all the names in "get" below are the *column names* as defined in the query of "get_changed_since"
+        Ok(Self {
+            podcast_url: row.get("podcast_url")?,
+            episode_url: row.get("url")?,
+            guid: row.get::<_, Option<String>>("guid")?.unwrap_or_default(),
+            played: row.get::<_, Option<bool>>("played")?.unwrap_or_default(),
+            position: row
+                .get::<_, Option<i64>>("last_position")?
+                .unwrap_or_default(),
+            duration: row.get("duration")?,
+            changed: row
+                .get::<_, Option<i64>>("state_changed")?
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// Get the sync state of all episodes whose played status or position changed after `since`.
+pub fn get_changed_since(
+    since: i64,
+    con: &Connection,
+) -> Result<Vec<EpisodeSyncState>, rusqlite::Error> {
+    let mut stmt = con.prepare_cached(indoc! {"
+        SELECT podcasts.url as podcast_url, episodes.url, episodes.guid, episodes.played,
+            episodes.last_position, episodes.duration, episodes.state_changed
+        FROM episodes
+        INNER JOIN podcasts ON episodes.podcast_id = podcasts.id
+        WHERE episodes.state_changed > ?;
+    "})?;
+
+    stmt.query_map(params![since], EpisodeSyncState::try_from_row_named)?
+        .collect()
+}
+
+/// Apply a state from the sync server to the episode with the same url, if it is newer than the local state.
+///
+/// Returns whether a episode was changed.
+pub fn apply_state(state: &EpisodeSyncState, con: &Connection) -> Result<bool, rusqlite::Error> {
+    let mut stmt = con.prepare_cached(indoc! {"
+        UPDATE episodes SET played = :played, last_position = :position, state_changed = :changed
+        WHERE url = :url AND (state_changed IS NULL OR state_changed < :changed);
+    "})?;
+
+    let changed = stmt.execute(named_params! {
+        ":played": state.played,
+        ":position": state.position,
+        ":changed": state.changed,
+        ":url": state.episode_url,
+    })?;
+
+    Ok(changed > 0)
+}
+
+/// Get the sync bookkeeping, or the default if there was no sync yet.
+pub fn get_sync_state(con: &Connection) -> Result<SyncState, rusqlite::Error> {
+    let state = con
+        .query_row(
+            "SELECT last_push, last_pull FROM sync_state WHERE id = 1;",
+            [],
+            |row| {
+                Ok(SyncState {
+                    last_push: row.get("last_push")?,
+                    last_pull: row.get("last_pull")?,
+                })
+            },
+        )
+        .optional()?;
+
+    Ok(state.unwrap_or_default())
+}
+
+/// Store the sync bookkeeping.
+pub fn set_sync_state(state: SyncState, con: &Connection) -> Result<(), rusqlite::Error> {
+    con.execute(
+        indoc! {"
+            INSERT INTO sync_state (id, last_push, last_pull)
+            VALUES (1, :push, :pull)
+            ON CONFLICT(id) DO UPDATE SET last_push = :push, last_pull = :pull;
+        "},
+        named_params! {
+            ":push": state.last_push,
+            ":pull": state.last_pull,
+        },
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::super::migration;
+    use super::super::test_utils::gen_database;
+    use super::{apply_state, get_changed_since, get_sync_state, set_sync_state};
+    use crate::podcast::db::{EpisodeSyncState, SyncState};
+
+    fn state(played: bool, position: i64, changed: i64) -> EpisodeSyncState {
+        EpisodeSyncState {
+            podcast_url: "https://example.com/feed".to_string(),
+            episode_url: "https://example.com/1.mp3".to_string(),
+            guid: "guid-1".to_string(),
+            played,
+            position,
+            duration: Some(600),
+            changed,
+        }
+    }
+
+    #[test]
+    fn should_only_apply_newer_states() {
+        let conn = gen_database();
+        migration::migrate(&conn).unwrap();
+
+        conn.execute_batch(indoc::indoc! {"
+            INSERT INTO podcasts (id, title, url) VALUES (1, 'Feed', 'https://example.com/feed');
+            INSERT INTO episodes (id, podcast_id, title, url, guid, duration, played, last_position, state_changed)
+                VALUES (1, 1, 'Episode', 'https://example.com/1.mp3', 'guid-1', 600, 0, 10, 100);
+        "})
+        .unwrap();
+
+        assert!(get_changed_since(100, &conn).unwrap().is_empty());
+        assert_eq!(
+            get_changed_since(99, &conn).unwrap(),
+            [state(false, 10, 100)]
+        );
+
+        assert!(!apply_state(&state(true, 600, 50), &conn).unwrap());
+        assert!(apply_state(&state(false, 300, 200), &conn).unwrap());
+        assert_eq!(
+            get_changed_since(0, &conn).unwrap(),
+            [state(false, 300, 200)]
+        );
+    }
+
+    #[test]
+    fn should_store_sync_state() {
+        let conn = gen_database();
+        migration::migrate(&conn).unwrap();
+
+        assert_eq!(get_sync_state(&conn).unwrap(), SyncState::default());
+
+        let new_state = SyncState {
+            last_push: 10,
+            last_pull: 20,
+        };
+        set_sync_state(new_state, &conn).unwrap();
+        set_sync_state(new_state, &conn).unwrap();
+        assert_eq!(get_sync_state(&conn).unwrap(), new_state);
+    }
+}
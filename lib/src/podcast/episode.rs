@@ -1,7 +1,10 @@
 use std::path::PathBuf;
+use std::time::Duration;
 
 use chrono::{DateTime, Utc};
 
+use crate::config::v2::server::{NewEpisodeSince, NewEpisodeWindow};
+use crate::ids::{EpisodeId, PodcastId};
 use crate::utils::StringUtils;
 
 use super::{EPISODE_DURATION_LENGTH, EPISODE_PUBDATE_LENGTH, Menuable};
@@ -12,8 +15,8 @@ use super::{EPISODE_DURATION_LENGTH, EPISODE_PUBDATE_LENGTH, Menuable};
 /// indicates whether the podcast has been marked as played or unplayed.
 #[derive(Debug, Clone, Default)]
 pub struct Episode {
-    pub id: i64,
-    pub pod_id: i64,
+    pub id: EpisodeId,
+    pub pod_id: PodcastId,
     pub title: String,
     pub url: String,
     pub guid: String,
@@ -24,6 +27,10 @@ pub struct Episode {
     pub played: bool,
     pub last_position: Option<i64>,
     pub image_url: Option<String>,
+    /// When the episode was first fetched from the feed, unknown for episodes from older versions
+    pub fetched: Option<DateTime<Utc>>,
+    /// Whether the feed marks the episode as explicit, `None` if it does not say
+    pub explicit: Option<bool>,
 }
 
 impl Episode {
@@ -42,12 +49,36 @@ impl Episode {
             None => "--:--:--".to_string(),
         }
     }
+
+    /// Check if the episode is unplayed and its date is within `window` of now.
+    ///
+    /// If the date the window counts from is unknown, the other date is used instead.
+    #[must_use]
+    pub fn is_new(&self, window: NewEpisodeWindow) -> bool {
+        self.is_new_at(window, Utc::now())
+    }
+
+    /// [`is_new`](Self::is_new), but with `now` given.
+    fn is_new_at(&self, window: NewEpisodeWindow, now: DateTime<Utc>) -> bool {
+        if self.played {
+            return false;
+        }
+        if window.days == 0 {
+            return true;
+        }
+
+        let since = match window.since {
+            NewEpisodeSince::Pubdate => self.pubdate.or(self.fetched),
+            NewEpisodeSince::Fetch => self.fetched.or(self.pubdate),
+        };
+        since.is_some_and(|since| now - since < chrono::Duration::days(window.days.into()))
+    }
 }
 
 impl Menuable for Episode {
     /// Returns the database ID for the episode.
     fn get_id(&self) -> i64 {
-        self.id
+        self.id.0
     }
 
     /// Returns the title for the episode, up to length characters.
@@ -100,6 +131,16 @@ impl Menuable for Episode {
     }
 }
 
+/// A chapter of a episode, either from the feed or embedded in the downloaded file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chapter {
+    /// Start of the chapter, relative to the start of the episode
+    pub start: Duration,
+    pub title: String,
+    /// Link to more information about the chapter
+    pub url: Option<String>,
+}
+
 /// Struct holding data about an individual podcast episode, before it
 /// has been inserted into the database.
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -111,4 +152,61 @@ pub struct EpisodeNoId {
     pub pubdate: Option<DateTime<Utc>>,
     pub duration: Option<i64>,
     pub image_url: Option<String>,
+    /// Chapters as listed in the feed
+    pub chapters: Vec<Chapter>,
+    pub explicit: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+
+    use super::Episode;
+    use crate::config::v2::server::{NewEpisodeSince, NewEpisodeWindow};
+
+    const DAY: i64 = 60 * 60 * 24;
+
+    fn episode(pubdate: Option<i64>, fetched: Option<i64>, played: bool) -> Episode {
+        Episode {
+            pubdate: pubdate.and_then(|v| DateTime::from_timestamp(v, 0)),
+            fetched: fetched.and_then(|v| DateTime::from_timestamp(v, 0)),
+            played,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_check_new() {
+        let now: DateTime<Utc> = DateTime::from_timestamp(100 * DAY, 0).unwrap();
+        let by_pubdate = NewEpisodeWindow {
+            days: 7,
+            since: NewEpisodeSince::Pubdate,
+        };
+        let by_fetch = NewEpisodeWindow {
+            days: 7,
+            since: NewEpisodeSince::Fetch,
+        };
+
+        // published long ago, but only just fetched
+        let ep = episode(Some(10 * DAY), Some(99 * DAY), false);
+        assert!(!ep.is_new_at(by_pubdate, now));
+        assert!(ep.is_new_at(by_fetch, now));
+
+        // played episodes are never new
+        let ep = episode(Some(99 * DAY), Some(99 * DAY), true);
+        assert!(!ep.is_new_at(by_pubdate, now));
+
+        // fallback to the other date if one is unknown
+        let ep = episode(Some(99 * DAY), None, false);
+        assert!(ep.is_new_at(by_fetch, now));
+        let ep = episode(None, None, false);
+        assert!(!ep.is_new_at(by_pubdate, now));
+
+        // no window counts all unplayed as new
+        let all = NewEpisodeWindow {
+            days: 0,
+            since: NewEpisodeSince::Pubdate,
+        };
+        assert!(ep.is_new_at(all, now));
+    }
 }
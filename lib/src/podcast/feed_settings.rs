@@ -0,0 +1,202 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+
+use super::episode::Episode;
+use crate::ids::EpisodeId;
+
+/// Settings of a single feed, applied after every refresh of it, see [`plan`],
+/// and when playing its episodes for [`skip_intro`](Self::skip_intro) and [`skip_outro`](Self::skip_outro).
+///
+/// Every setting is disabled if `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FeedSettings {
+    /// Download this many of the newest episodes
+    pub auto_download: Option<u32>,
+    /// Delete the oldest downloaded episodes beyond this many
+    pub keep_downloaded: Option<u32>,
+    /// Do not auto-download episodes published more than this many days ago
+    pub max_age_days: Option<u32>,
+    /// Skip this many seconds at the start of every episode
+    pub skip_intro: Option<u32>,
+    /// Skip this many seconds at the end of every episode
+    pub skip_outro: Option<u32>,
+}
+
+impl FeedSettings {
+    /// Get whether all settings are disabled, in which case applying them does nothing.
+    #[must_use]
+    pub fn is_disabled(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// What applying [`FeedSettings`] to the episodes of a feed requires, see [`plan`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FeedSettingsPlan {
+    /// Episodes to download
+    pub download: Vec<EpisodeId>,
+    /// Downloaded episodes whose files to delete
+    pub remove: Vec<(EpisodeId, PathBuf)>,
+}
+
+impl FeedSettingsPlan {
+    /// Get whether there is nothing to do.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.download.is_empty() && self.remove.is_empty()
+    }
+}
+
+/// Get what `settings` require for `episodes`, which have to be sorted newest first, like in [`Podcast`](super::Podcast).
+///
+/// Played episodes are never downloaded, and episodes to be downloaded count towards
+/// [`keep_downloaded`](FeedSettings::keep_downloaded), so that they are not deleted again on the next refresh.
+#[must_use]
+pub fn plan(settings: &FeedSettings, episodes: &[Episode]) -> FeedSettingsPlan {
+    plan_at(settings, episodes, Utc::now())
+}
+
+/// [`plan`], but with `now` given.
+fn plan_at(settings: &FeedSettings, episodes: &[Episode], now: DateTime<Utc>) -> FeedSettingsPlan {
+    let auto_download = settings
+        .auto_download
+        .map_or(0, |v| usize::try_from(v).unwrap_or(usize::MAX));
+    let keep = settings
+        .keep_downloaded
+        .map_or(usize::MAX, |v| usize::try_from(v).unwrap_or(usize::MAX));
+    let too_old = |ep: &Episode| {
+        settings.max_age_days.is_some_and(|days| {
+            ep.pubdate
+                .or(ep.fetched)
+                .is_some_and(|date| now - date > chrono::Duration::days(days.into()))
+        })
+    };
+
+    let mut result = FeedSettingsPlan::default();
+    let mut kept = 0;
+    for (idx, ep) in episodes.iter().enumerate() {
+        let download = idx < auto_download && ep.path.is_none() && !ep.played && !too_old(ep);
+        if ep.path.is_none() && !download {
+            continue;
+        }
+
+        kept += 1;
+        if kept <= keep {
+            if download {
+                result.download.push(ep.id);
+            }
+        } else if let Some(path) = &ep.path {
+            result.remove.push((ep.id, path.clone()));
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use chrono::{DateTime, Utc};
+    use pretty_assertions::assert_eq;
+
+    use super::{FeedSettings, FeedSettingsPlan, plan_at};
+    use crate::ids::EpisodeId;
+    use crate::podcast::episode::Episode;
+
+    const NOW: i64 = 1_700_000_000;
+    const DAY: i64 = 60 * 60 * 24;
+
+    fn episode(id: i64, days_ago: i64, played: bool, downloaded: bool) -> Episode {
+        Episode {
+            id: EpisodeId(id),
+            pubdate: DateTime::from_timestamp(NOW - days_ago * DAY, 0),
+            played,
+            path: downloaded.then(|| PathBuf::from(format!("/podcasts/{id}.mp3"))),
+            ..Default::default()
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::from_timestamp(NOW, 0).unwrap()
+    }
+
+    #[test]
+    fn should_do_nothing_if_disabled() {
+        let episodes = [episode(2, 0, false, true), episode(1, 1, false, false)];
+
+        assert!(plan_at(&FeedSettings::default(), &episodes, now()).is_empty());
+    }
+
+    #[test]
+    fn should_download_newest_unplayed() {
+        let settings = FeedSettings {
+            auto_download: Some(3),
+            keep_downloaded: None,
+            max_age_days: Some(7),
+            ..Default::default()
+        };
+        let episodes = [
+            episode(5, 0, false, false),
+            episode(4, 1, true, false),
+            episode(3, 10, false, false),
+            episode(2, 2, false, false),
+        ];
+
+        assert_eq!(
+            plan_at(&settings, &episodes, now()),
+            FeedSettingsPlan {
+                download: vec![EpisodeId(5)],
+                remove: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn should_remove_oldest_downloads() {
+        let settings = FeedSettings {
+            auto_download: Some(1),
+            keep_downloaded: Some(2),
+            max_age_days: None,
+            ..Default::default()
+        };
+        let episodes = [
+            episode(4, 0, false, false),
+            episode(3, 1, false, true),
+            episode(2, 2, true, false),
+            episode(1, 3, true, true),
+        ];
+
+        assert_eq!(
+            plan_at(&settings, &episodes, now()),
+            FeedSettingsPlan {
+                download: vec![EpisodeId(4)],
+                remove: vec![(EpisodeId(1), PathBuf::from("/podcasts/1.mp3"))],
+            }
+        );
+    }
+
+    #[test]
+    fn should_not_download_beyond_keep() {
+        let settings = FeedSettings {
+            auto_download: Some(3),
+            keep_downloaded: Some(1),
+            max_age_days: None,
+            ..Default::default()
+        };
+        let episodes = [
+            episode(3, 0, false, false),
+            episode(2, 1, false, false),
+            episode(1, 2, false, false),
+        ];
+
+        assert_eq!(
+            plan_at(&settings, &episodes, now()),
+            FeedSettingsPlan {
+                download: vec![EpisodeId(3)],
+                remove: Vec::new(),
+            }
+        );
+    }
+}
@@ -0,0 +1,349 @@
+//! Syncing episode played status and positions with a `gpodder.net` compatible server.
+//!
+//! Both the `gpodder.net` API v2 and the `nextcloud-gpodder` app are supported, see [`GpodderFlavor`].
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDateTime};
+use reqwest::{Client, ClientBuilder, RequestBuilder};
+use serde::{Deserialize, Serialize};
+
+use super::db::EpisodeSyncState;
+use crate::config::v2::server::{GpodderFlavor, PodcastSyncSettings};
+
+/// Timestamp format used in episode actions.
+const ACTION_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// Errors that can happen while syncing.
+#[derive(Debug, thiserror::Error)]
+pub enum GpodderError {
+    #[error("Podcast sync is not fully configured, \"server\" and \"username\" are required")]
+    NotConfigured,
+    #[error("Request to sync server failed: {0}")]
+    Request(#[from] reqwest::Error),
+}
+
+/// Messages about the progress of a sync, see [`sync`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GpodderSyncResult {
+    Start,
+    /// The upload and download was successful, the data still needs to be applied to the database
+    Complete(GpodderSyncData),
+    Error(String),
+}
+
+/// The result of a successful sync, to be applied to the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpodderSyncData {
+    /// Amount of local changes uploaded
+    pub pushed: usize,
+    /// Latest remote state for each episode changed since the last sync
+    pub states: Vec<EpisodeSyncState>,
+    /// Local unix timestamp of when the local changes were collected
+    pub push_time: i64,
+    /// Server timestamp to request further changes from
+    pub pull_timestamp: i64,
+}
+
+/// A single episode action, as exchanged with the server.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct EpisodeAction {
+    podcast: String,
+    episode: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    guid: Option<String>,
+    action: String,
+    timestamp: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    device: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    started: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    position: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    total: Option<i64>,
+}
+
+/// Response to requesting episode actions.
+#[derive(Debug, Deserialize)]
+struct ActionsResponse {
+    actions: Vec<EpisodeAction>,
+    timestamp: i64,
+}
+
+impl EpisodeAction {
+    /// Convert a local state to the action describing it.
+    fn from_state(state: &EpisodeSyncState, device: Option<&str>) -> Self {
+        let timestamp = DateTime::from_timestamp(state.changed, 0)
+            .unwrap_or_default()
+            .format(ACTION_TIME_FORMAT)
+            .to_string();
+        let guid = (!state.guid.is_empty()).then(|| state.guid.clone());
+
+        // "new" resets a episode to unplayed, "play" with the position at the end marks it as played,
+        // which needs a positive total, also if the duration is unknown and the position was reset
+        let (action, position, total) = if state.played {
+            let end = state.duration.unwrap_or(state.position).max(1);
+            ("play", Some(end), Some(end))
+        } else if state.position > 0 {
+            // without a duration, leave out the total, as the position would otherwise be the end
+            ("play", Some(state.position), state.duration)
+        } else {
+            ("new", None, None)
+        };
+
+        Self {
+            podcast: state.podcast_url.clone(),
+            episode: state.episode_url.clone(),
+            guid,
+            action: action.to_string(),
+            timestamp,
+            device: device.map(ToString::to_string),
+            started: position.map(|_| 0),
+            position,
+            total,
+        }
+    }
+
+    /// Parse the timestamp of the action, some servers include fractional seconds or a timezone.
+    fn parse_timestamp(&self) -> Option<i64> {
+        if let Ok(time) = DateTime::parse_from_rfc3339(&self.timestamp) {
+            return Some(time.timestamp());
+        }
+
+        NaiveDateTime::parse_from_str(&self.timestamp, "%Y-%m-%dT%H:%M:%S%.f")
+            .ok()
+            .map(|v| v.and_utc().timestamp())
+    }
+
+    /// Convert the action to the state it describes, if it is relevant to played status or position.
+    fn to_state(&self) -> Option<EpisodeSyncState> {
+        let (played, position) = match self.action.to_lowercase().as_str() {
+            "play" => {
+                let position = self.position.unwrap_or_default();
+                let played = self
+                    .total
+                    .is_some_and(|total| total > 0 && position >= total);
+                (played, position)
+            }
+            "new" => (false, 0),
+            _ => return None,
+        };
+
+        Some(EpisodeSyncState {
+            podcast_url: self.podcast.clone(),
+            episode_url: self.episode.clone(),
+            guid: self.guid.clone().unwrap_or_default(),
+            played,
+            position,
+            duration: self.total.filter(|v| *v > 0),
+            changed: self.parse_timestamp()?,
+        })
+    }
+}
+
+/// Get the latest relevant state for each episode in `actions`.
+fn aggregate_actions(actions: &[EpisodeAction]) -> Vec<EpisodeSyncState> {
+    let mut latest: HashMap<String, EpisodeSyncState> = HashMap::new();
+
+    for state in actions.iter().filter_map(EpisodeAction::to_state) {
+        match latest.get(&state.episode_url) {
+            Some(existing) if existing.changed >= state.changed => (),
+            _ => {
+                latest.insert(state.episode_url.clone(), state);
+            }
+        }
+    }
+
+    let mut states: Vec<EpisodeSyncState> = latest.into_values().collect();
+    states.sort_by(|a, b| a.episode_url.cmp(&b.episode_url));
+
+    states
+}
+
+/// The endpoints of a sync server.
+struct Endpoints {
+    upload: String,
+    download: String,
+}
+
+impl Endpoints {
+    fn new(settings: &PodcastSyncSettings) -> Self {
+        let server = settings.server.trim_end_matches('/');
+        match settings.flavor {
+            GpodderFlavor::Gpodder => {
+                let url = format!("{server}/api/2/episodes/{}.json", settings.username);
+                Self {
+                    upload: url.clone(),
+                    download: url,
+                }
+            }
+            GpodderFlavor::Nextcloud => Self {
+                upload: format!("{server}/index.php/apps/gpoddersync/episode_action/create"),
+                download: format!("{server}/index.php/apps/gpoddersync/episode_action"),
+            },
+        }
+    }
+}
+
+fn with_auth(request: RequestBuilder, settings: &PodcastSyncSettings) -> RequestBuilder {
+    request.basic_auth(&settings.username, Some(&settings.password))
+}
+
+/// Upload `local` changes and download all changes since `since` (a server timestamp).
+///
+/// `push_time` is stored in the result for the caller to record as the last upload.
+pub async fn sync(
+    settings: &PodcastSyncSettings,
+    local: &[EpisodeSyncState],
+    push_time: i64,
+    since: i64,
+) -> Result<GpodderSyncData, GpodderError> {
+    if settings.server.is_empty() || settings.username.is_empty() {
+        return Err(GpodderError::NotConfigured);
+    }
+
+    let client: Client = ClientBuilder::new()
+        .connect_timeout(Duration::from_secs(5))
+        .build()?;
+    let endpoints = Endpoints::new(settings);
+
+    if !local.is_empty() {
+        // nextcloud does not have devices
+        let device = match settings.flavor {
+            GpodderFlavor::Gpodder => Some(settings.device_id.as_str()),
+            GpodderFlavor::Nextcloud => None,
+        };
+        let actions: Vec<EpisodeAction> = local
+            .iter()
+            .map(|v| EpisodeAction::from_state(v, device))
+            .collect();
+
+        with_auth(client.post(&endpoints.upload), settings)
+            .json(&actions)
+            .send()
+            .await?
+            .error_for_status()?;
+    }
+
+    let response: ActionsResponse = with_auth(client.get(&endpoints.download), settings)
+        .query(&[("since", since)])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(GpodderSyncData {
+        pushed: local.len(),
+        states: aggregate_actions(&response.actions),
+        push_time,
+        pull_timestamp: response.timestamp,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{EpisodeAction, aggregate_actions};
+    use crate::podcast::db::EpisodeSyncState;
+
+    fn action(action: &str, timestamp: &str, position: Option<i64>) -> EpisodeAction {
+        EpisodeAction {
+            podcast: "https://example.com/feed".to_string(),
+            episode: "https://example.com/1.mp3".to_string(),
+            guid: None,
+            action: action.to_string(),
+            timestamp: timestamp.to_string(),
+            device: None,
+            started: position.map(|_| 0),
+            position,
+            total: position.map(|_| 600),
+        }
+    }
+
+    #[test]
+    fn should_use_latest_action() {
+        let actions = [
+            action("play", "2024-01-01T10:00:00", Some(100)),
+            action("play", "2024-01-01T12:00:00.5", Some(600)),
+            action("download", "2024-01-01T13:00:00", None),
+            action("play", "2024-01-01T11:00:00Z", Some(300)),
+        ];
+
+        assert_eq!(
+            aggregate_actions(&actions),
+            [EpisodeSyncState {
+                podcast_url: "https://example.com/feed".to_string(),
+                episode_url: "https://example.com/1.mp3".to_string(),
+                guid: String::new(),
+                played: true,
+                position: 600,
+                duration: Some(600),
+                changed: 1_704_110_400,
+            }]
+        );
+    }
+
+    #[test]
+    fn should_roundtrip_local_state() {
+        let state = EpisodeSyncState {
+            podcast_url: "https://example.com/feed".to_string(),
+            episode_url: "https://example.com/1.mp3".to_string(),
+            guid: "guid-1".to_string(),
+            played: false,
+            position: 120,
+            duration: Some(600),
+            changed: 1_704_110_400,
+        };
+
+        let action = EpisodeAction::from_state(&state, Some("termusic"));
+        assert_eq!(action.action, "play");
+        assert_eq!(action.timestamp, "2024-01-01T12:00:00");
+        assert_eq!(action.to_state(), Some(state.clone()));
+
+        let unplayed = EpisodeSyncState {
+            position: 0,
+            duration: None,
+            ..state
+        };
+        let action = EpisodeAction::from_state(&unplayed, None);
+        assert_eq!(action.action, "new");
+        assert_eq!(action.to_state(), Some(unplayed));
+    }
+
+    #[test]
+    fn should_roundtrip_played_without_duration() {
+        let state = EpisodeSyncState {
+            podcast_url: "https://example.com/feed".to_string(),
+            episode_url: "https://example.com/1.mp3".to_string(),
+            guid: String::new(),
+            played: true,
+            position: 0,
+            duration: None,
+            changed: 1_704_110_400,
+        };
+
+        let action = EpisodeAction::from_state(&state, None);
+        assert_eq!(action.action, "play");
+        assert_eq!((action.position, action.total), (Some(1), Some(1)));
+        assert_eq!(action.to_state().map(|v| v.played), Some(true));
+
+        let played = EpisodeSyncState {
+            position: 120,
+            ..state.clone()
+        };
+        let action = EpisodeAction::from_state(&played, None);
+        assert_eq!(action.to_state().map(|v| v.played), Some(true));
+
+        let started = EpisodeSyncState {
+            played: false,
+            position: 120,
+            ..state
+        };
+        let action = EpisodeAction::from_state(&started, None);
+        assert_eq!(action.to_state(), Some(started));
+    }
+}
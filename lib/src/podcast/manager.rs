@@ -0,0 +1,862 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::Utc;
+use sanitize_filename::{Options, sanitize_with_options};
+
+use super::db::{Database, MergeResult, SyncState};
+use super::episode::Episode;
+use super::feed_settings::{self, FeedSettings};
+use super::gpodder::{self, GpodderSyncData, GpodderSyncResult};
+use super::space::check_download_space;
+use super::{
+    EpData, Podcast, PodcastDLResult, PodcastError, PodcastFeed, PodcastNoId, PodcastSyncResult,
+    check_feed, download_list, find_duplicate, remove_orphan_part_files, sort_podcasts,
+};
+use crate::config::{ServerOverlay, SharedServerSettings};
+use crate::ids::{EpisodeId, PodcastId};
+use crate::parental::ParentalFilter;
+use crate::taskpool::TaskPool;
+use crate::utils::{create_podcast_dir, get_app_data_path, get_podcast_save_path};
+
+/// Results of background work started by a [`PodcastManager`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PodcastEvent {
+    Sync(PodcastSyncResult),
+    Download(PodcastDLResult),
+    Gpodder(GpodderSyncResult),
+}
+
+/// Callback the [`PodcastManager`] reports [`PodcastEvent`]s to.
+///
+/// Called from background tasks, so it should not block.
+pub type PodcastEventHandler = Arc<dyn Fn(PodcastEvent) + Send + Sync>;
+
+/// Owns the podcast database and the loaded podcasts and implements adding, refreshing,
+/// downloading and marking episodes as played, independent of any client.
+pub struct PodcastManager {
+    db: Database,
+    podcasts: Vec<Podcast>,
+    config: SharedServerSettings,
+    /// Taskpool to limit number of active network requests
+    taskpool: TaskPool,
+    events: PodcastEventHandler,
+    /// The [`data_version`](Database::data_version) of `db` when `podcasts` were last loaded
+    data_version: i64,
+}
+
+impl std::fmt::Debug for PodcastManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PodcastManager")
+            .field("db", &self.db)
+            .field("podcasts", &self.podcasts.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl PodcastManager {
+    /// Open the podcast database in the default location, repair it and load all podcasts.
+    ///
+    /// Also removes incomplete downloads left over from a previous run.
+    pub fn new(config: SharedServerSettings, events: PodcastEventHandler) -> Result<Self> {
+        let db_path = get_app_data_path().context("get podcast db path")?;
+        let db = Database::new(&db_path).context("connect to podcast db")?;
+
+        Self::with_database(db, config, events)
+    }
+
+    /// Like [`new`](Self::new), but with an already opened database.
+    pub fn with_database(
+        db: Database,
+        config: SharedServerSettings,
+        events: PodcastEventHandler,
+    ) -> Result<Self> {
+        match db.repair_orphans() {
+            Ok(result) if result.any() => info!(
+                "Removed {} orphaned podcast episode(s) and {} orphaned file(s)",
+                result.episodes, result.files
+            ),
+            Ok(_) => (),
+            Err(err) => warn!("{err:#}"),
+        }
+
+        let config_read = config.read();
+        let data_version = db.data_version().context("get podcast db data version")?;
+        let podcasts = load_podcasts(&db, &config_read).context("get podcasts from db")?;
+        match get_podcast_save_path(&config_read) {
+            Ok(download_dir) => {
+                let removed = remove_orphan_part_files(&download_dir);
+                if removed > 0 {
+                    info!("Removed {removed} incomplete podcast download(s)");
+                }
+            }
+            Err(err) => warn!("Could not get the podcast download directory: {err:#}"),
+        }
+        let taskpool = TaskPool::new(usize::from(
+            config_read.settings.podcast.concurrent_downloads_max.get(),
+        ));
+        drop(config_read);
+
+        Ok(Self {
+            db,
+            podcasts,
+            config,
+            taskpool,
+            events,
+            data_version,
+        })
+    }
+
+    /// The podcast database.
+    #[must_use]
+    pub fn db(&self) -> &Database {
+        &self.db
+    }
+
+    /// All loaded podcasts, without those hidden by the [parental filter](crate::parental), in the configured [`feed_sort`](crate::config::v2::server::PodcastSettings::feed_sort) order.
+    #[must_use]
+    pub fn podcasts(&self) -> &[Podcast] {
+        &self.podcasts
+    }
+
+    /// Get the index of the podcast with `id`.
+    #[must_use]
+    pub fn podcast_index(&self, id: PodcastId) -> Option<usize> {
+        self.podcasts.iter().position(|pod| pod.id == id)
+    }
+
+    /// Get the podcast and episode index of the episode with `id`.
+    #[must_use]
+    pub fn episode_index(&self, id: EpisodeId) -> Option<(usize, usize)> {
+        self.podcasts
+            .iter()
+            .enumerate()
+            .find_map(|(pod_index, pod)| {
+                pod.episodes
+                    .iter()
+                    .position(|ep| ep.id == id)
+                    .map(|ep_index| (pod_index, ep_index))
+            })
+    }
+
+    /// Reload all podcasts from the database.
+    pub fn reload(&mut self) -> Result<(), PodcastError> {
+        // read before loading, so that a change while loading is picked up by the next check
+        self.data_version = self.db.data_version()?;
+        self.podcasts = load_podcasts(&self.db, &self.config.read())?;
+
+        Ok(())
+    }
+
+    /// Reload all podcasts if the database was changed outside of this manager since they were last loaded,
+    /// like by the command line interface importing feeds or restoring a backup.
+    ///
+    /// Returns whether the podcasts were reloaded.
+    pub fn reload_if_changed(&mut self) -> Result<bool, PodcastError> {
+        if self.db.data_version()? == self.data_version {
+            return Ok(false);
+        }
+        self.reload()?;
+
+        Ok(true)
+    }
+
+    /// Sort the loaded podcasts again, like after the configured order changed.
+    ///
+    /// This changes the podcast indexes.
+    pub fn sort(&mut self) {
+        let order = self.config.read().settings.podcast.feed_sort;
+        sort_podcasts(&mut self.podcasts, order);
+    }
+
+    fn max_retries(&self) -> usize {
+        usize::from(self.config.read().settings.podcast.max_download_retries)
+    }
+
+    /// Fetch `feed` in the background and report the result as [`PodcastEvent::Sync`].
+    fn check_feed(&self, feed: PodcastFeed) {
+        let events = self.events.clone();
+
+        check_feed(feed, self.max_retries(), &self.taskpool, move |msg| {
+            events(PodcastEvent::Sync(msg));
+        });
+    }
+
+    /// Fetch a new podcast from `url`, which is added once [`sync_data`](Self::sync_data) is called with the result.
+    pub fn add_feed(&self, url: String) {
+        self.check_feed(PodcastFeed::new(None, url, None));
+    }
+
+    /// Fetch the feed of the podcast at `index`, or of all podcasts if `None`.
+    pub fn refresh(&self, index: Option<usize>) -> Result<()> {
+        let feeds: Vec<PodcastFeed> = match index {
+            Some(i) => {
+                let Some(pod) = self.podcasts.get(i) else {
+                    if self.podcasts.is_empty() {
+                        return Ok(());
+                    }
+                    bail!("get podcast selected failed.");
+                };
+                vec![PodcastFeed::new(
+                    Some(pod.id),
+                    pod.url.clone(),
+                    Some(pod.title.clone()),
+                )]
+            }
+            None => self
+                .podcasts
+                .iter()
+                .map(|pod| PodcastFeed::new(Some(pod.id), pod.url.clone(), Some(pod.title.clone())))
+                .collect(),
+        };
+
+        for feed in feeds {
+            self.check_feed(feed);
+        }
+
+        Ok(())
+    }
+
+    /// Add a new podcast, or update an existing one if `pod_id` is set, and reload all podcasts.
+    pub fn sync_data(
+        &mut self,
+        pod: &PodcastNoId,
+        pod_id: Option<PodcastId>,
+    ) -> Result<(), PodcastError> {
+        if let Some(id) = pod_id {
+            self.db.update_podcast(id, pod)?;
+        } else {
+            self.db.insert_podcast(pod)?;
+        }
+
+        self.reload()
+    }
+
+    /// Get the already subscribed podcast that the newly fetched `pod` is the same feed as, see [`find_duplicate`].
+    #[must_use]
+    pub fn find_duplicate(&self, pod: &PodcastNoId) -> Option<&Podcast> {
+        find_duplicate(&self.podcasts, pod)
+    }
+
+    /// Update the subscribed podcast `pod_id` with the newly fetched `pod` instead of adding it again,
+    /// keeping the url of the subscribed podcast.
+    pub fn merge_duplicate(&mut self, pod: &PodcastNoId, pod_id: PodcastId) -> Result<()> {
+        let url = self
+            .podcast_index(pod_id)
+            .map(|index| self.podcasts[index].url.clone())
+            .ok_or_else(|| anyhow!("Podcast {pod_id} not found"))?;
+        let pod = PodcastNoId { url, ..pod.clone() };
+
+        Ok(self.sync_data(&pod, Some(pod_id))?)
+    }
+
+    /// Merge the podcast `source` into `target` and remove `source`, keeping the played status, positions and
+    /// downloaded files of matching episodes, see [`Database::merge_podcasts`].
+    ///
+    /// Downloaded files of episodes that could not be moved stay on disk.
+    pub fn merge(&mut self, source: PodcastId, target: PodcastId) -> Result<MergeResult> {
+        let result = self.db.merge_podcasts(source, target)?;
+        self.reload()?;
+
+        Ok(result)
+    }
+
+    /// Get the settings of the podcast `pod_id`.
+    pub fn feed_settings(&self, pod_id: PodcastId) -> Result<FeedSettings> {
+        self.db.get_feed_settings(pod_id)
+    }
+
+    /// Store the settings of the podcast `pod_id`, they are applied with [`apply_feed_settings`](Self::apply_feed_settings).
+    pub fn set_feed_settings(&self, pod_id: PodcastId, settings: &FeedSettings) -> Result<()> {
+        self.db.set_feed_settings(pod_id, settings)
+    }
+
+    /// Apply the [`FeedSettings`] of the podcast `pod_id`: delete the files of downloads beyond the kept amount
+    /// with `delete_file` and start downloading the newest episodes, see [`feed_settings::plan`].
+    ///
+    /// Meant to be called after every refresh of the feed.
+    /// Episodes for which `is_downloading` returns `true` are not downloaded again.
+    pub fn apply_feed_settings(
+        &mut self,
+        pod_id: PodcastId,
+        is_downloading: impl Fn(&str) -> bool,
+        delete_file: impl Fn(&Path) -> Result<()>,
+    ) -> Result<()> {
+        let settings = self.db.get_feed_settings(pod_id)?;
+        if settings.is_disabled() {
+            return Ok(());
+        }
+        let Some(pod_index) = self.podcast_index(pod_id) else {
+            return Ok(());
+        };
+
+        let plan = feed_settings::plan(&settings, &self.podcasts[pod_index].episodes);
+
+        // delete first, so that the space is available for the new downloads
+        let mut removed = Vec::with_capacity(plan.remove.len());
+        for (ep_id, path) in &plan.remove {
+            match delete_file(path) {
+                Ok(()) => removed.push(*ep_id),
+                Err(err) => warn!("Could not delete \"{}\": {err:#}", path.display()),
+            }
+        }
+        if !removed.is_empty() {
+            self.remove_files(pod_index, &removed)?;
+        }
+
+        if !plan.download.is_empty() {
+            self.download_ids(pod_index, &plan.download, is_downloading)?;
+        }
+
+        Ok(())
+    }
+
+    /// Store `path` as the downloaded file of episode `ep_id` and reload the podcasts to show it.
+    ///
+    /// Fails if `path` is already used by another episode.
+    pub fn insert_file(&mut self, ep_id: EpisodeId, path: &Path) -> Result<()> {
+        self.db.insert_file(ep_id, path)?;
+        self.reload()?;
+
+        Ok(())
+    }
+
+    /// Toggle the played status of episode `ep_index` of podcast `pod_index`.
+    pub fn toggle_played(&mut self, pod_index: usize, ep_index: usize) -> Result<()> {
+        let ep = self
+            .podcasts
+            .get_mut(pod_index)
+            .ok_or_else(|| anyhow!("get podcast selected failed."))?
+            .episodes
+            .get_mut(ep_index)
+            .ok_or_else(|| anyhow!("get episode selected failed"))?;
+
+        ep.played = !ep.played;
+        self.db.set_played_status(ep.id, ep.played)?;
+
+        Ok(())
+    }
+
+    /// Set the played status of all episodes of podcast `pod_index`.
+    pub fn set_all_played(&mut self, pod_index: usize, played: bool) -> Result<()> {
+        let podcast = self
+            .podcasts
+            .get_mut(pod_index)
+            .ok_or_else(|| anyhow!("get podcast selected failed."))?;
+
+        let mut ids: Vec<EpisodeId> = Vec::with_capacity(podcast.episodes.len());
+        for ep in &mut podcast.episodes {
+            ids.push(ep.id);
+            ep.played = played;
+        }
+        self.db.set_all_played_status(&ids, played)?;
+
+        Ok(())
+    }
+
+    /// Mark the first episode with `url` as played.
+    pub fn mark_played_by_url(&mut self, url: &str) -> Result<()> {
+        let Some(ep) = self
+            .podcasts
+            .iter_mut()
+            .flat_map(|pod| pod.episodes.iter_mut())
+            .find(|ep| ep.url == url)
+        else {
+            return Ok(());
+        };
+
+        if !ep.played {
+            ep.played = true;
+            self.db.set_played_status(ep.id, ep.played)?;
+        }
+
+        Ok(())
+    }
+
+    /// Download episode `ep_index` of podcast `pod_index`, or all of its episodes if `None`,
+    /// and report the progress as [`PodcastEvent::Download`].
+    ///
+    /// Episodes that are already downloaded or for which `is_downloading` returns `true` are skipped.
+    pub fn download(
+        &self,
+        pod_index: usize,
+        ep_index: Option<usize>,
+        is_downloading: impl Fn(&str) -> bool,
+    ) -> Result<()> {
+        let podcast = self
+            .podcasts
+            .get(pod_index)
+            .ok_or_else(|| anyhow!("get podcast selected failed."))?;
+
+        let episodes = match ep_index {
+            Some(idx) => std::slice::from_ref(
+                podcast
+                    .episodes
+                    .get(idx)
+                    .ok_or_else(|| anyhow!("get episode selected failed"))?,
+            ),
+            None => podcast.episodes.as_slice(),
+        };
+
+        self.download_episodes(podcast, episodes.iter(), is_downloading)
+    }
+
+    /// Get the unplayed episodes of podcast `pod_index` which are not downloaded yet.
+    ///
+    /// Episodes for which `is_downloading` returns `true` are skipped.
+    pub fn unplayed_to_download(
+        &self,
+        pod_index: usize,
+        is_downloading: impl Fn(&str) -> bool,
+    ) -> Result<Vec<&Episode>> {
+        let podcast = self
+            .podcasts
+            .get(pod_index)
+            .ok_or_else(|| anyhow!("get podcast selected failed."))?;
+
+        Ok(podcast
+            .episodes
+            .iter()
+            .filter(|ep| !ep.played && ep.path.is_none() && !is_downloading(&ep.url))
+            .collect())
+    }
+
+    /// Download the episodes with `ids` of podcast `pod_index`, and report the progress as [`PodcastEvent::Download`].
+    ///
+    /// Episodes that are already downloaded or for which `is_downloading` returns `true` are skipped.
+    pub fn download_ids(
+        &self,
+        pod_index: usize,
+        ids: &[EpisodeId],
+        is_downloading: impl Fn(&str) -> bool,
+    ) -> Result<()> {
+        let podcast = self
+            .podcasts
+            .get(pod_index)
+            .ok_or_else(|| anyhow!("get podcast selected failed."))?;
+
+        let episodes = podcast.episodes.iter().filter(|ep| ids.contains(&ep.id));
+
+        self.download_episodes(podcast, episodes, is_downloading)
+    }
+
+    /// Start downloading `episodes` of `podcast`, see [`download`](Self::download).
+    fn download_episodes<'a>(
+        &self,
+        podcast: &Podcast,
+        episodes: impl Iterator<Item = &'a Episode>,
+        is_downloading: impl Fn(&str) -> bool,
+    ) -> Result<()> {
+        let ep_data: Vec<EpData> = episodes
+            .filter(|ep| ep.path.is_none() && !is_downloading(&ep.url))
+            .map(|ep| EpData {
+                id: ep.id,
+                pod_id: ep.pod_id,
+                title: ep.title.clone(),
+                url: ep.url.clone(),
+                guid: ep.guid.clone(),
+                pubdate: ep.pubdate,
+                file_path: None,
+            })
+            .collect();
+
+        if ep_data.is_empty() {
+            return Ok(());
+        }
+
+        let config = self.config.read();
+
+        // refuse to start downloads which would likely not fit
+        let download_root = get_podcast_save_path(&config)?;
+        check_download_space(
+            &download_root,
+            config.settings.podcast.download_quota,
+            u64::try_from(ep_data.len()).unwrap_or(u64::MAX),
+        )?;
+
+        // add directory for podcast, create if it does not exist
+        let dir_name = sanitize_with_options(
+            &podcast.title,
+            Options {
+                truncate: true,
+                windows: true, // for simplicity, we'll just use Windows-friendly paths for everyone
+                replacement: "",
+            },
+        );
+        let Ok(path) = create_podcast_dir(&config, dir_name) else {
+            bail!("Could not create dir: {}", podcast.title);
+        };
+        drop(config);
+
+        let events = self.events.clone();
+        download_list(
+            ep_data,
+            &path,
+            self.max_retries(),
+            &self.taskpool,
+            move |msg| {
+                events(PodcastEvent::Download(msg));
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Record the downloaded file of `ep_data` and reload all podcasts.
+    pub fn download_complete(&mut self, ep_data: EpData) -> Result<()> {
+        let file_path = ep_data
+            .file_path
+            .ok_or_else(|| anyhow!("Downloaded episode does not have a path"))?;
+        if let Err(err) = self.db.insert_file(ep_data.id, &file_path) {
+            bail!(
+                "Could not add episode file to database: {}: {err:#}",
+                file_path.to_string_lossy()
+            );
+        }
+
+        Ok(self.reload()?)
+    }
+
+    /// Upload local played status and position changes to the configured sync server and download
+    /// the remote changes, reported as [`PodcastEvent::Gpodder`].
+    ///
+    /// The downloaded changes have to be applied with [`apply_gpodder_sync`](Self::apply_gpodder_sync).
+    pub fn gpodder_sync(&self) -> Result<()> {
+        let Some(settings) = self.config.read().settings.podcast.sync.clone() else {
+            bail!("Podcast sync is not configured");
+        };
+
+        let state = self.db.get_sync_state()?;
+        // one second earlier, so that changes later in the current second are uploaded again next time
+        let push_time = Utc::now().timestamp() - 1;
+        let local = self.db.get_episodes_changed_since(state.last_push)?;
+
+        let events = self.events.clone();
+        self.taskpool.execute(async move {
+            events(PodcastEvent::Gpodder(GpodderSyncResult::Start));
+            let result = match gpodder::sync(&settings, &local, push_time, state.last_pull).await {
+                Ok(data) => GpodderSyncResult::Complete(data),
+                Err(err) => {
+                    error!("podcast sync had a Error: {err:#?}");
+                    GpodderSyncResult::Error(err.to_string())
+                }
+            };
+            events(PodcastEvent::Gpodder(result));
+        });
+
+        Ok(())
+    }
+
+    /// Apply the result of a [`gpodder_sync`](Self::gpodder_sync) and reload all podcasts if anything changed.
+    ///
+    /// Returns the amount of episodes changed.
+    pub fn apply_gpodder_sync(&mut self, data: &GpodderSyncData) -> Result<usize> {
+        let changed = self.db.apply_sync_states(&data.states)?;
+        self.db.set_sync_state(SyncState {
+            last_push: data.push_time,
+            last_pull: data.pull_timestamp,
+        })?;
+
+        if changed > 0 {
+            self.reload()?;
+        }
+
+        Ok(changed)
+    }
+
+    /// Forget the downloaded files of the episodes `ep_ids` of podcast `pod_index`.
+    ///
+    /// The files themself have to be deleted by the caller.
+    pub fn remove_files(&mut self, pod_index: usize, ep_ids: &[EpisodeId]) -> Result<()> {
+        let podcast = self
+            .podcasts
+            .get_mut(pod_index)
+            .ok_or_else(|| anyhow!("get podcast selected failed."))?;
+
+        self.db.remove_files(ep_ids)?;
+        for ep in &mut podcast.episodes {
+            if ep_ids.contains(&ep.id) {
+                ep.path = None;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove the podcast at `index` with all its episodes from the database.
+    ///
+    /// Downloaded files are not touched.
+    pub fn remove(&mut self, index: usize) -> Result<Podcast> {
+        if index >= self.podcasts.len() {
+            bail!("failed to find the podcast selected for removal.");
+        }
+
+        let podcast = self.podcasts.remove(index);
+        self.db.remove_podcast(podcast.id)?;
+
+        Ok(podcast)
+    }
+
+    /// Remove all podcasts, episodes and file entries from the database.
+    ///
+    /// Downloaded files are not touched.
+    pub fn remove_all(&mut self) -> Result<()> {
+        self.db.clear_db()?;
+        self.podcasts = Vec::new();
+
+        Ok(())
+    }
+}
+
+/// Get all podcasts from `db`, without those hidden by the [parental filter](crate::parental), in the configured
+/// [`feed_sort`](crate::config::v2::server::PodcastSettings::feed_sort) order.
+///
+/// This only reads from `db`, so clients can use it with a database opened by [`Database::open_read_only`].
+pub fn load_podcasts(db: &Database, config: &ServerOverlay) -> Result<Vec<Podcast>, PodcastError> {
+    let mut podcasts = db.get_podcasts()?;
+    hide_parental(&mut podcasts, &config.parental);
+    sort_podcasts(&mut podcasts, config.settings.podcast.feed_sort);
+
+    Ok(podcasts)
+}
+
+/// Remove the podcasts and episodes `filter` hides from `podcasts`.
+///
+/// Hidden podcasts are also not refreshed, as they are not part of the loaded podcasts.
+fn hide_parental(podcasts: &mut Vec<Podcast>, filter: &ParentalFilter) {
+    if !filter.hide_explicit {
+        return;
+    }
+
+    podcasts.retain(|pod| filter.allows_explicit(pod.explicit));
+    for pod in podcasts {
+        pod.episodes.retain(|v| filter.allows_explicit(v.explicit));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use chrono::DateTime;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    use super::{PodcastEvent, PodcastManager};
+    use crate::config::{ServerOverlay, new_shared_server_settings};
+    use crate::podcast::db::Database;
+    use crate::podcast::episode::EpisodeNoId;
+    use crate::podcast::{EpData, PodcastNoId};
+
+    /// A episode of a feed, published at `pubdate` seconds.
+    fn episode(title: &str, pubdate: i64) -> EpisodeNoId {
+        EpisodeNoId {
+            title: title.to_string(),
+            url: format!("https://example.com/{title}.mp3"),
+            guid: title.to_string(),
+            description: String::new(),
+            pubdate: DateTime::from_timestamp(pubdate, 0),
+            duration: None,
+            image_url: None,
+            chapters: Vec::new(),
+            explicit: None,
+        }
+    }
+
+    /// A fetched feed with `episodes`, oldest first.
+    fn podcast(title: &str, episodes: &[&str]) -> PodcastNoId {
+        PodcastNoId {
+            title: title.to_string(),
+            url: format!("https://example.com/{title}.xml"),
+            description: None,
+            author: None,
+            explicit: None,
+            last_checked: DateTime::UNIX_EPOCH,
+            episodes: (0..)
+                .zip(episodes)
+                .map(|(pubdate, title)| episode(title, pubdate))
+                .collect(),
+            image_url: None,
+            category: None,
+            guid: None,
+            resolved_url: None,
+        }
+    }
+
+    /// Create a manager on a empty in-memory database, downloading into the returned directory.
+    fn manager(mut config: ServerOverlay) -> (TempDir, PodcastManager) {
+        let download_dir = tempfile::tempdir().unwrap();
+        config.settings.podcast.download_dir = download_dir.path().to_path_buf();
+        let manager = PodcastManager::with_database(
+            Database::new_in_memory(),
+            new_shared_server_settings(config),
+            Arc::new(|_: PodcastEvent| ()),
+        )
+        .unwrap();
+
+        (download_dir, manager)
+    }
+
+    /// Get the titles of the loaded episodes of podcast `pod_index`.
+    fn episode_titles(manager: &PodcastManager, pod_index: usize) -> Vec<&str> {
+        manager.podcasts()[pod_index]
+            .episodes
+            .iter()
+            .map(|ep| ep.title.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn should_add_and_update_podcasts() {
+        let (_download_dir, mut manager) = manager(ServerOverlay::default());
+        assert!(manager.podcasts().is_empty());
+
+        manager.sync_data(&podcast("Beta", &["b1"]), None).unwrap();
+        manager
+            .sync_data(&podcast("Alpha", &["a1", "a2"]), None)
+            .unwrap();
+        let titles: Vec<&str> = manager
+            .podcasts()
+            .iter()
+            .map(|pod| pod.title.as_str())
+            .collect();
+        assert_eq!(titles, ["Alpha", "Beta"]);
+        assert_eq!(episode_titles(&manager, 0), ["a2", "a1"]);
+
+        // a refresh only adds the new episodes
+        let alpha = manager.podcasts()[0].id;
+        manager
+            .sync_data(&podcast("Alpha", &["a1", "a2", "a3"]), Some(alpha))
+            .unwrap();
+        assert_eq!(manager.podcast_index(alpha), Some(0));
+        assert_eq!(episode_titles(&manager, 0), ["a3", "a2", "a1"]);
+
+        let a1 = manager.podcasts()[0].episodes[2].id;
+        assert_eq!(manager.episode_index(a1), Some((0, 2)));
+    }
+
+    #[test]
+    fn should_mark_played() {
+        let (_download_dir, mut manager) = manager(ServerOverlay::default());
+        manager
+            .sync_data(&podcast("Alpha", &["a1", "a2"]), None)
+            .unwrap();
+        manager.sync_data(&podcast("Beta", &["b1"]), None).unwrap();
+
+        manager.toggle_played(0, 0).unwrap();
+        manager
+            .mark_played_by_url("https://example.com/b1.mp3")
+            .unwrap();
+        assert!(manager.toggle_played(0, 5).is_err());
+
+        // the loaded podcasts are kept up to date without a reload
+        let played = |manager: &PodcastManager| -> Vec<bool> {
+            manager
+                .podcasts()
+                .iter()
+                .flat_map(|pod| pod.episodes.iter().map(|ep| ep.played))
+                .collect()
+        };
+        assert_eq!(played(&manager), [true, false, true]);
+        manager.reload().unwrap();
+        assert_eq!(played(&manager), [true, false, true]);
+
+        manager.set_all_played(0, false).unwrap();
+        manager.reload().unwrap();
+        assert_eq!(played(&manager), [false, false, true]);
+    }
+
+    #[test]
+    fn should_track_downloaded_files() {
+        let (_download_dir, mut manager) = manager(ServerOverlay::default());
+        manager
+            .sync_data(&podcast("Alpha", &["a1", "a2"]), None)
+            .unwrap();
+
+        let path = PathBuf::from("/podcasts/Alpha/a2.mp3");
+        let ep = &manager.podcasts()[0].episodes[0];
+        let ep_data = EpData {
+            id: ep.id,
+            pod_id: ep.pod_id,
+            title: ep.title.clone(),
+            url: ep.url.clone(),
+            guid: ep.guid.clone(),
+            pubdate: ep.pubdate,
+            file_path: Some(path.clone()),
+        };
+        manager.download_complete(ep_data).unwrap();
+        assert_eq!(manager.podcasts()[0].episodes[0].path, Some(path.clone()));
+
+        let unplayed: Vec<&str> = manager
+            .unplayed_to_download(0, |_| false)
+            .unwrap()
+            .into_iter()
+            .map(|ep| ep.title.as_str())
+            .collect();
+        assert_eq!(unplayed, ["a1"]);
+        assert!(
+            manager
+                .unplayed_to_download(0, |url| url.ends_with("a1.mp3"))
+                .unwrap()
+                .is_empty()
+        );
+
+        // a file can only belong to one episode
+        let (downloaded, other) = {
+            let episodes = &manager.podcasts()[0].episodes;
+            (episodes[0].id, episodes[1].id)
+        };
+        assert!(manager.insert_file(other, &path).is_err());
+
+        manager.remove_files(0, &[downloaded]).unwrap();
+        assert_eq!(manager.podcasts()[0].episodes[0].path, None);
+        manager.insert_file(other, &path).unwrap();
+        assert_eq!(manager.podcasts()[0].episodes[1].path, Some(path));
+    }
+
+    #[test]
+    fn should_remove_podcasts() {
+        let (_download_dir, mut manager) = manager(ServerOverlay::default());
+        manager.sync_data(&podcast("Alpha", &["a1"]), None).unwrap();
+        manager.sync_data(&podcast("Beta", &["b1"]), None).unwrap();
+
+        assert_eq!(manager.remove(0).unwrap().title, "Alpha");
+        assert!(manager.remove(1).is_err());
+        manager.reload().unwrap();
+        assert_eq!(manager.podcasts().len(), 1);
+        assert_eq!(manager.podcasts()[0].title, "Beta");
+
+        manager.remove_all().unwrap();
+        manager.reload().unwrap();
+        assert!(manager.podcasts().is_empty());
+    }
+
+    #[test]
+    fn should_hide_explicit() {
+        let mut config = ServerOverlay::default();
+        config.parental.hide_explicit = true;
+        let (_download_dir, mut manager) = manager(config);
+
+        let mut explicit = podcast("Alpha", &["a1"]);
+        explicit.explicit = Some(true);
+        manager.sync_data(&explicit, None).unwrap();
+        let mut mixed = podcast("Beta", &["b1", "b2"]);
+        mixed.episodes[1].explicit = Some(true);
+        manager.sync_data(&mixed, None).unwrap();
+
+        assert_eq!(manager.podcasts().len(), 1);
+        assert_eq!(episode_titles(&manager, 0), ["b1"]);
+    }
+
+    #[test]
+    fn should_not_reload_after_own_changes() {
+        let (_download_dir, mut manager) = manager(ServerOverlay::default());
+        manager.sync_data(&podcast("Alpha", &["a1"]), None).unwrap();
+        manager.toggle_played(0, 0).unwrap();
+        manager.remove_files(0, &[]).unwrap();
+
+        assert!(!manager.reload_if_changed().unwrap());
+    }
+}
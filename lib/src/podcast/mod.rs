@@ -1,19 +1,22 @@
 // Thanks to the author of shellcaster(https://github.com/jeff-hughes/shellcaster). Most parts of following code are taken from it.
 
+pub mod chapters;
 pub mod db;
 #[allow(clippy::module_name_repetitions)]
 pub mod episode;
+pub mod feed_settings;
+pub mod gpodder;
+pub mod manager;
 // repetetive name, but will do for now
 #[allow(clippy::module_inception)]
 mod podcast;
+pub mod space;
 
 use std::fs::File;
-use std::io::Write as _;
 use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
 use std::time::Duration;
 
-use anyhow::{Context, Result, bail};
 use bytes::Buf;
 use chrono::{DateTime, Utc};
 use opml::{Body, Head, OPML, Outline};
@@ -24,7 +27,9 @@ use rss::{Channel, Item};
 use sanitize_filename::{Options, sanitize_with_options};
 use tokio::sync::mpsc::unbounded_channel;
 
-use crate::config::v2::server::PodcastSettings;
+use crate::config::v2::server::{FeedSortOrder, PodcastSettings};
+use crate::ids::{EpisodeId, PodcastId};
+use crate::new_database::DatabaseError;
 use crate::taskpool::TaskPool;
 use db::Database;
 use episode::{Episode, EpisodeNoId};
@@ -63,27 +68,132 @@ pub trait Menuable {
 #[derive(Debug, Clone, Eq, PartialEq)]
 #[allow(clippy::module_name_repetitions)]
 pub struct PodcastFeed {
-    pub id: Option<i64>,
+    pub id: Option<PodcastId>,
     pub url: String,
     pub title: Option<String>,
 }
 
 impl PodcastFeed {
     #[must_use]
-    pub const fn new(id: Option<i64>, url: String, title: Option<String>) -> Self {
+    pub const fn new(id: Option<PodcastId>, url: String, title: Option<String>) -> Self {
         Self { id, url, title }
     }
 }
 
+/// Errors that can happen while fetching feeds or importing / exporting them.
+#[derive(Debug, thiserror::Error)]
+#[allow(clippy::module_name_repetitions)]
+pub enum PodcastError {
+    /// Building the client or receiving the response failed
+    #[error("Network error")]
+    Network(#[from] reqwest::Error),
+    /// The feed did not respond within the allowed retries
+    #[error("No response from feed \"{0}\"")]
+    NoResponse(String),
+    /// The response could not be parsed as a RSS feed
+    #[error("Could not parse RSS feed")]
+    FeedParse(#[from] rss::Error),
+    /// The given OPML could not be parsed
+    #[error("Could not properly parse OPML file -- file may be formatted improperly or corrupted.")]
+    OpmlParse(#[source] opml::Error),
+    /// The OPML could not be serialized
+    #[error("Could not create OPML format")]
+    OpmlCreate(#[source] opml::Error),
+    /// Reading or writing a file failed
+    #[error("Could not access file \"{}\"", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    /// The podcast database could not be opened
+    #[error("Could not open the podcast database")]
+    Open(#[from] DatabaseError),
+    /// Any operation on the podcast database failed
+    #[error("Podcast database error")]
+    Database(#[from] rusqlite::Error),
+    /// Not all feeds could be imported, details are logged
+    #[error("Process finished with errors.")]
+    ImportFailed,
+}
+
+impl PodcastError {
+    /// Create a [`PodcastError::Io`] for the given `path`.
+    fn io(path: &Path) -> impl FnOnce(std::io::Error) -> Self + '_ {
+        move |source| Self::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+
+    /// Get whether this error is likely temporary and may succeed on retry.
+    #[must_use]
+    pub fn is_network(&self) -> bool {
+        matches!(self, Self::Network(_) | Self::NoResponse(_))
+    }
+}
+
+/// Sort `podcasts` by `order`, podcasts that are equal in `order` are sorted by their [`sort_title`](Podcast::sort_title).
+pub fn sort_podcasts(podcasts: &mut [Podcast], order: FeedSortOrder) {
+    match order {
+        FeedSortOrder::Alphabetical => podcasts.sort(),
+        FeedSortOrder::Recent => podcasts.sort_by(|a, b| {
+            b.newest_pubdate()
+                .cmp(&a.newest_pubdate())
+                .then_with(|| a.cmp(b))
+        }),
+        FeedSortOrder::Unplayed => podcasts.sort_by(|a, b| {
+            b.num_unplayed()
+                .cmp(&a.num_unplayed())
+                .then_with(|| a.cmp(b))
+        }),
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum PodcastSyncResult {
     FetchPodcastStart(String),
 
-    SyncData((i64, PodcastNoId)),
+    SyncData((PodcastId, PodcastNoId)),
     NewData(PodcastNoId),
     Error(PodcastFeed),
 }
 
+/// Get `url` in a form that is the same for urls that only differ in ways that still lead to the same feed,
+/// like the scheme, a `www.` prefix, the case of the host or a trailing `/`.
+fn canonical_url(url: &str) -> String {
+    let url = url.trim();
+    let url = url
+        .split_once("://")
+        .map_or(url, |(_, rest)| rest)
+        .trim_end_matches('/');
+    let (host, path) = url.split_once('/').unwrap_or((url, ""));
+    let host = host.to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+
+    if path.is_empty() {
+        host.to_string()
+    } else {
+        format!("{host}/{path}")
+    }
+}
+
+/// Find the podcast in `podcasts` that `pod` is the same feed as, by its `podcast:guid` or its (resolved) url.
+///
+/// This is meant to be used before adding `pod`, to not subscribe to the same feed twice.
+#[must_use]
+pub fn find_duplicate<'a>(podcasts: &'a [Podcast], pod: &PodcastNoId) -> Option<&'a Podcast> {
+    let urls: Vec<String> = std::iter::once(&pod.url)
+        .chain(pod.resolved_url.as_ref())
+        .map(|v| canonical_url(v))
+        .collect();
+
+    podcasts.iter().find(|existing| {
+        let same_guid = existing.guid.is_some() && existing.guid == pod.guid;
+        same_guid || urls.contains(&canonical_url(&existing.url))
+    })
+}
+
 /// Spawns a new task to check a feed and retrieve podcast data.
 ///
 /// If `tx_to_main` is closed, no errors will be throws and the task will continue
@@ -105,7 +215,12 @@ pub fn check_feed(
                 }
             },
             Err(err) => {
-                error!("get_feed_data had a Error: {err:#?}");
+                // unreachable feeds are expected from time to time, anything else is a bad feed
+                if err.is_network() {
+                    warn!("Feed \"{}\" could not be reached: {err:#}", feed.url);
+                } else {
+                    error!("get_feed_data had a Error: {err:#?}");
+                }
                 tx_to_main(PodcastSyncResult::Error(feed));
             }
         }
@@ -114,7 +229,7 @@ pub fn check_feed(
 
 /// Given a URL, this attempts to pull the data about a podcast and its
 /// episodes from an RSS feed.
-async fn get_feed_data(url: &str, mut max_retries: usize) -> Result<PodcastNoId> {
+async fn get_feed_data(url: &str, mut max_retries: usize) -> Result<PodcastNoId, PodcastError> {
     let agent = ClientBuilder::new()
         .connect_timeout(Duration::from_secs(5))
         .build()?;
@@ -126,12 +241,17 @@ async fn get_feed_data(url: &str, mut max_retries: usize) -> Result<PodcastNoId>
         }
         max_retries -= 1;
         if max_retries == 0 {
-            bail!("No response from feed");
+            return Err(PodcastError::NoResponse(url.to_string()));
         }
     };
 
+    // reqwest follows redirects, so this is where the feed actually is
+    let resolved_url = Some(resp.url().to_string()).filter(|v| v != url);
     let channel = Channel::read_from(resp.bytes().await?.reader())?;
-    Ok(parse_feed_data(channel, url))
+    let mut pod = parse_feed_data(channel, url);
+    pod.resolved_url = resolved_url;
+
+    Ok(pod)
 }
 
 /// Given a Channel with the RSS feed data, this parses the data about a
@@ -148,18 +268,32 @@ fn parse_feed_data(channel: Channel, url: &str) -> PodcastNoId {
     let mut author = None;
     let mut explicit = None;
     let mut image_url = None;
+    let mut category = None;
     if let Some(itunes) = channel.itunes_ext() {
         author = itunes.author().map(std::string::ToString::to_string);
-        explicit = itunes.explicit().and_then(|s| {
-            let ss = s.to_lowercase();
-            match &ss[..] {
-                "yes" | "explicit" | "true" => Some(true),
-                "no" | "clean" | "false" => Some(false),
-                _ => None,
-            }
-        });
+        explicit = itunes.explicit().and_then(parse_explicit);
         image_url = itunes.image().map(std::string::ToString::to_string);
+        category = itunes
+            .categories()
+            .first()
+            .map(|v| v.text().trim().to_string());
+    }
+    // fallback to the plain rss categories, which are less common in podcast feeds
+    if category.is_none() {
+        category = channel
+            .categories()
+            .first()
+            .map(|v| v.name().trim().to_string());
     }
+    let category = category.filter(|v| !v.is_empty());
+    let guid = channel
+        .extensions()
+        .get("podcast")
+        .and_then(|v| v.get("guid"))
+        .and_then(|v| v.first())
+        .and_then(|v| v.value())
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
 
     let mut episodes = Vec::new();
     let items = channel.into_items();
@@ -178,6 +312,9 @@ fn parse_feed_data(channel: Channel, url: &str) -> PodcastNoId {
         last_checked,
         episodes,
         image_url,
+        category,
+        guid,
+        resolved_url: None,
     }
 }
 
@@ -204,9 +341,11 @@ fn parse_episode_data(item: &Item) -> EpisodeNoId {
 
     let mut duration = None;
     let mut image_url = None;
+    let mut explicit = None;
     if let Some(itunes) = item.itunes_ext() {
         duration = duration_to_int(itunes.duration()).map(i64::from);
         image_url = itunes.image().map(std::string::ToString::to_string);
+        explicit = itunes.explicit().and_then(parse_explicit);
     }
 
     EpisodeNoId {
@@ -217,6 +356,17 @@ fn parse_episode_data(item: &Item) -> EpisodeNoId {
         pubdate,
         duration,
         image_url,
+        chapters: chapters::from_feed_item(item),
+        explicit,
+    }
+}
+
+/// Parse the value of a iTunes `explicit` tag, `None` if it is not a known value.
+fn parse_explicit(value: &str) -> Option<bool> {
+    match &value.to_lowercase()[..] {
+        "yes" | "explicit" | "true" => Some(true),
+        "no" | "clean" | "false" => Some(false),
+        _ => None,
     }
 }
 
@@ -263,13 +413,14 @@ fn duration_to_int(duration: Option<&str>) -> Option<i32> {
 
 /// Imports a list of podcasts from OPML format, reading from a file. If the `replace` flag is set, this replaces all
 /// existing data in the database.
-pub async fn import_from_opml(db_path: &Path, config: &PodcastSettings, file: &Path) -> Result<()> {
-    let xml = std::fs::read_to_string(file)
-        .with_context(|| format!("Could not open OPML file: {}", file.display()))?;
+pub async fn import_from_opml(
+    db_path: &Path,
+    config: &PodcastSettings,
+    file: &Path,
+) -> Result<(), PodcastError> {
+    let xml = std::fs::read_to_string(file).map_err(PodcastError::io(file))?;
 
-    let mut podcast_list = import_opml_feeds(&xml).with_context(
-        || "Could not properly parse OPML file -- file may be formatted improperly or corrupted.",
-    )?;
+    let mut podcast_list = import_opml_feeds(&xml).map_err(PodcastError::OpmlParse)?;
 
     if podcast_list.is_empty() {
         println!("No podcasts to import.");
@@ -288,12 +439,8 @@ pub async fn import_from_opml(db_path: &Path, config: &PodcastSettings, file: &P
 
     // if URL is already in database, remove it from import
     podcast_list.retain(|pod| {
-        for op in &old_podcasts {
-            if pod.url == op.url {
-                return false;
-            }
-        }
-        true
+        let url = canonical_url(&pod.url);
+        !old_podcasts.iter().any(|op| canonical_url(&op.url) == url)
     });
     // }
 
@@ -330,14 +477,19 @@ pub async fn import_from_opml(db_path: &Path, config: &PodcastSettings, file: &P
             PodcastSyncResult::NewData(pod) => {
                 msg_counter += 1;
                 let title = &pod.title;
-                let db_result = db_inst.insert_podcast(&pod);
-                match db_result {
-                    Ok(_) => {
-                        println!("Added {title}");
-                    }
-                    Err(err) => {
-                        failure = true;
-                        error!("Error adding {title}, err: {err}");
+                // the url may still redirect to, or be a new url of, a existing feed
+                if let Some(existing) = find_duplicate(&old_podcasts, &pod) {
+                    println!("Skipped {title}, already subscribed as {}", existing.title);
+                } else {
+                    let db_result = db_inst.insert_podcast(&pod);
+                    match db_result {
+                        Ok(_) => {
+                            println!("Added {title}");
+                        }
+                        Err(err) => {
+                            failure = true;
+                            error!("Error adding {title}, err: {err}");
+                        }
                     }
                 }
             }
@@ -359,7 +511,7 @@ pub async fn import_from_opml(db_path: &Path, config: &PodcastSettings, file: &P
     }
 
     if failure {
-        bail!("Process finished with errors.");
+        return Err(PodcastError::ImportFailed);
     }
     println!("Import successful.");
 
@@ -368,27 +520,20 @@ pub async fn import_from_opml(db_path: &Path, config: &PodcastSettings, file: &P
 
 /// Exports all podcasts to OPML format, either printing to stdout or
 /// exporting to a file.
-pub fn export_to_opml(db_path: &Path, file: &Path) -> Result<()> {
+pub fn export_to_opml(db_path: &Path, file: &Path) -> Result<(), PodcastError> {
     let db_inst = Database::new(db_path)?;
     let podcast_list = db_inst.get_podcasts()?;
     let opml = export_opml_feeds(&podcast_list);
 
-    let xml = opml.to_string().context("Could not create OPML format")?;
+    let xml = opml.to_string().map_err(PodcastError::OpmlCreate)?;
 
-    let mut dst = File::create(file)
-        .with_context(|| format!("Could not create output file: {}", file.display()))?;
-    dst.write_all(xml.as_bytes()).with_context(|| {
-        format!(
-            "Could not copy OPML data to output file: {}",
-            file.display()
-        )
-    })?;
+    std::fs::write(file, xml).map_err(PodcastError::io(file))?;
     Ok(())
 }
 
 /// Import a list of podcast feeds from an OPML file. Supports
 /// v1.0, v1.1, and v2.0 OPML files.
-fn import_opml_feeds(xml: &str) -> Result<Vec<PodcastFeed>> {
+fn import_opml_feeds(xml: &str) -> Result<Vec<PodcastFeed>, opml::Error> {
     let opml = OPML::from_str(xml)?;
     let mut feeds = Vec::new();
     for pod in opml.body.outlines {
@@ -441,10 +586,11 @@ fn export_opml_feeds(podcasts: &[Podcast]) -> OPML {
 /// Enum used to communicate relevant data to the taskpool.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct EpData {
-    pub id: i64,
-    pub pod_id: i64,
+    pub id: EpisodeId,
+    pub pod_id: PodcastId,
     pub title: String,
     pub url: String,
+    pub guid: String,
     pub pubdate: Option<DateTime<Utc>>,
     pub file_path: Option<PathBuf>,
 }
@@ -543,21 +689,349 @@ async fn download_file(
         file_name = format!("{file_name}_{}", pubdate.format("%Y%m%d_%H%M%S"));
     }
 
-    let mut file_path = destination_path;
-    file_path.push(format!("{file_name}.{ext}"));
+    let Ok((mut dst, file_path)) =
+        create_unique_file(&destination_path, &file_name, ext, &ep_data.guid)
+    else {
+        return PodcastDLResult::DLFileCreateError(ep_data);
+    };
+    let part_path = part_path(&file_path);
 
-    let Ok(mut dst) = File::create(&file_path) else {
+    let Ok(bytes) = response.bytes().await else {
+        let _ = std::fs::remove_file(&part_path);
         return PodcastDLResult::DLFileCreateError(ep_data);
     };
 
+    if std::io::copy(&mut bytes.reader(), &mut dst).is_err() {
+        let _ = std::fs::remove_file(&part_path);
+        return PodcastDLResult::DLFileWriteError(ep_data);
+    }
+    drop(dst);
+
+    // only give the file its final name once complete, so interrupted downloads never leave truncated files behind
+    if std::fs::rename(&part_path, &file_path).is_err() {
+        let _ = std::fs::remove_file(&part_path);
+        return PodcastDLResult::DLFileWriteError(ep_data);
+    }
+
     ep_data.file_path = Some(file_path);
 
-    let Ok(bytes) = response.bytes().await else {
-        return PodcastDLResult::DLFileCreateError(ep_data);
+    PodcastDLResult::DLComplete(ep_data)
+}
+
+/// The extension appended to files which are still being downloaded.
+pub const PART_EXTENSION: &str = "part";
+
+/// Get the path a download to `path` is written to until it is complete.
+fn part_path(path: &Path) -> PathBuf {
+    let mut part = path.as_os_str().to_owned();
+    part.push(".");
+    part.push(PART_EXTENSION);
+
+    PathBuf::from(part)
+}
+
+/// Remove all leftover [`PART_EXTENSION`] files in `dir` (recursively), like from a crash during a download.
+///
+/// As files are only added to the database once complete, no database references need to be removed.
+///
+/// Returns the amount of files removed.
+pub fn remove_orphan_part_files(dir: &Path) -> usize {
+    let mut removed = 0;
+
+    for entry in walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(Result::ok)
+    {
+        let path = entry.path();
+        if !entry.file_type().is_file() || path.extension().is_none_or(|v| v != PART_EXTENSION) {
+            continue;
+        }
+
+        match std::fs::remove_file(path) {
+            Ok(()) => removed += 1,
+            Err(err) => warn!("Could not remove \"{}\": {err}", path.display()),
+        }
+    }
+
+    removed
+}
+
+/// Maximal numeric suffix tried by [`file_name_candidates`].
+const MAX_FILE_NAME_SUFFIX: usize = 1000;
+
+/// Get all file names to try for a episode download, in order of preference.
+///
+/// The order is `name.ext`, then `name_{guid fragment}.ext` and then `name_{1..}.ext`.
+fn file_name_candidates<'a>(
+    file_name: &'a str,
+    ext: &'a str,
+    guid: &str,
+) -> impl Iterator<Item = String> + 'a {
+    // the end of a guid is usually the most unique part, as guids are often urls
+    let mut guid_fragment: Vec<char> = guid
+        .chars()
+        .rev()
+        .filter(char::is_ascii_alphanumeric)
+        .take(8)
+        .collect();
+    guid_fragment.reverse();
+    let guid_fragment: String = guid_fragment.into_iter().collect();
+
+    std::iter::once(format!("{file_name}.{ext}"))
+        .chain((!guid_fragment.is_empty()).then(|| format!("{file_name}_{guid_fragment}.{ext}")))
+        .chain((1..=MAX_FILE_NAME_SUFFIX).map(move |num| format!("{file_name}_{num}.{ext}")))
+}
+
+/// Create a new [`part file`](part_path) in `dir` for a episode download, without overwriting any existing file.
+///
+/// Returns the opened part file and the final path. See [`file_name_candidates`] for the names tried.
+fn create_unique_file(
+    dir: &Path,
+    file_name: &str,
+    ext: &str,
+    guid: &str,
+) -> std::io::Result<(File, PathBuf)> {
+    for candidate in file_name_candidates(file_name, ext, guid) {
+        let path = dir.join(candidate);
+        if path.exists() {
+            continue;
+        }
+        // "create_new" fails if the file exists, which also guards against parallel downloads with the same name
+        match File::create_new(part_path(&path)) {
+            Ok(file) => return Ok((file, path)),
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => (),
+            Err(err) => return Err(err),
+        }
+    }
+
+    Err(std::io::Error::new(
+        std::io::ErrorKind::AlreadyExists,
+        format!("No free file name found for \"{file_name}.{ext}\""),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use std::path::Path;
+
+    use chrono::DateTime;
+
+    use rss::Channel;
+
+    use super::episode::Episode;
+    use super::{
+        Podcast, PodcastNoId, canonical_url, file_name_candidates, find_duplicate, parse_feed_data,
+        part_path, sort_podcasts,
     };
+    use crate::config::v2::server::FeedSortOrder;
+    use crate::ids::PodcastId;
+
+    #[test]
+    fn should_suffix_with_guid_then_number() {
+        let candidates: Vec<String> =
+            file_name_candidates("Episode", "mp3", "https://example.com/ep/1234-abcd")
+                .take(4)
+                .collect();
+
+        assert_eq!(
+            candidates,
+            [
+                "Episode.mp3",
+                "Episode_1234abcd.mp3",
+                "Episode_1.mp3",
+                "Episode_2.mp3"
+            ]
+        );
+    }
+
+    #[test]
+    fn should_skip_empty_guid() {
+        let candidates: Vec<String> = file_name_candidates("Episode", "m4a", "--")
+            .take(2)
+            .collect();
+
+        assert_eq!(candidates, ["Episode.m4a", "Episode_1.m4a"]);
+    }
 
-    match std::io::copy(&mut bytes.reader(), &mut dst) {
-        Ok(_) => PodcastDLResult::DLComplete(ep_data),
-        Err(_) => PodcastDLResult::DLFileWriteError(ep_data),
+    #[test]
+    fn should_append_part_extension() {
+        assert_eq!(
+            part_path(Path::new("/podcasts/feed/Episode.mp3")),
+            Path::new("/podcasts/feed/Episode.mp3.part")
+        );
+    }
+
+    #[test]
+    fn should_parse_feed_category() {
+        let feed = r#"<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+            <channel>
+                <title>Feed</title>
+                <category>Plain</category>
+                <itunes:category text="Technology"><itunes:category text="Podcasting"/></itunes:category>
+            </channel>
+        </rss>"#;
+        let channel = Channel::read_from(feed.as_bytes()).unwrap();
+        let podcast = parse_feed_data(channel, "http://example.com/feed.xml");
+        assert_eq!(podcast.category.as_deref(), Some("Technology"));
+
+        // the rss category is only used without a itunes category
+        let feed = r#"<rss version="2.0">
+            <channel>
+                <title>Feed</title>
+                <category> News </category>
+            </channel>
+        </rss>"#;
+        let channel = Channel::read_from(feed.as_bytes()).unwrap();
+        let podcast = parse_feed_data(channel, "http://example.com/feed.xml");
+        assert_eq!(podcast.category.as_deref(), Some("News"));
+    }
+
+    #[test]
+    fn should_parse_explicit() {
+        let feed = r#"<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+            <channel>
+                <title>Feed</title>
+                <itunes:explicit>Yes</itunes:explicit>
+                <item><title>Explicit</title><itunes:explicit>true</itunes:explicit></item>
+                <item><title>Clean</title><itunes:explicit>clean</itunes:explicit></item>
+                <item><title>Unknown</title><itunes:explicit>maybe</itunes:explicit></item>
+                <item><title>Missing</title></item>
+            </channel>
+        </rss>"#;
+        let channel = Channel::read_from(feed.as_bytes()).unwrap();
+        let podcast = parse_feed_data(channel, "http://example.com/feed.xml");
+        assert_eq!(podcast.explicit, Some(true));
+        assert_eq!(
+            podcast
+                .episodes
+                .iter()
+                .map(|v| v.explicit)
+                .collect::<Vec<_>>(),
+            [Some(true), Some(false), None, None]
+        );
+    }
+
+    /// Create a podcast with a episode for each of `episodes`, which are `(pubdate, played)`.
+    fn podcast(id: i64, sort_title: &str, episodes: &[(i64, bool)]) -> Podcast {
+        Podcast {
+            id: PodcastId(id),
+            title: sort_title.to_string(),
+            sort_title: sort_title.to_string(),
+            url: String::new(),
+            description: None,
+            author: None,
+            explicit: None,
+            last_checked: DateTime::UNIX_EPOCH,
+            episodes: episodes
+                .iter()
+                .map(|(pubdate, played)| Episode {
+                    pubdate: DateTime::from_timestamp(*pubdate, 0),
+                    played: *played,
+                    ..Default::default()
+                })
+                .collect(),
+            image_url: None,
+            category: None,
+            guid: None,
+        }
+    }
+
+    fn sorted_ids(podcasts: &mut [Podcast], order: FeedSortOrder) -> Vec<i64> {
+        sort_podcasts(podcasts, order);
+        podcasts.iter().map(|v| v.id.0).collect()
+    }
+
+    #[test]
+    fn should_sort_podcasts() {
+        let mut podcasts = vec![
+            podcast(1, "show c", &[(100, false), (300, true)]),
+            podcast(2, "show a", &[(200, false)]),
+            podcast(3, "show b", &[(200, false), (50, false)]),
+            podcast(4, "show d", &[]),
+        ];
+
+        assert_eq!(
+            sorted_ids(&mut podcasts, FeedSortOrder::Alphabetical),
+            [2, 3, 1, 4]
+        );
+        assert_eq!(
+            sorted_ids(&mut podcasts, FeedSortOrder::Recent),
+            [1, 2, 3, 4]
+        );
+        assert_eq!(
+            sorted_ids(&mut podcasts, FeedSortOrder::Unplayed),
+            [3, 2, 1, 4]
+        );
+    }
+
+    #[test]
+    fn should_parse_feed_guid() {
+        let feed = r#"<rss version="2.0" xmlns:podcast="https://podcastindex.org/namespace/1.0">
+            <channel>
+                <title>Feed</title>
+                <podcast:guid> 917393e3-1b1e-5cef-ace4-edaa54e1f810 </podcast:guid>
+            </channel>
+        </rss>"#;
+        let channel = Channel::read_from(feed.as_bytes()).unwrap();
+        let podcast = parse_feed_data(channel, "http://example.com/feed.xml");
+        assert_eq!(
+            podcast.guid.as_deref(),
+            Some("917393e3-1b1e-5cef-ace4-edaa54e1f810")
+        );
+    }
+
+    #[test]
+    fn should_canonicalize_urls() {
+        assert_eq!(
+            canonical_url("https://www.Example.com/Feed.xml/"),
+            "example.com/Feed.xml"
+        );
+        assert_eq!(canonical_url("http://example.com"), "example.com");
+        assert_ne!(
+            canonical_url("http://example.com/a.xml"),
+            canonical_url("http://example.com/b.xml")
+        );
+    }
+
+    #[test]
+    fn should_find_duplicate_feed() {
+        let mut existing = podcast(1, "a", &[]);
+        existing.url = "https://example.com/feed.xml".to_string();
+        let mut other = podcast(2, "b", &[]);
+        other.url = "https://example.com/other.xml".to_string();
+        other.guid = Some("guid-b".to_string());
+        let podcasts = [existing, other];
+
+        let mut new = PodcastNoId {
+            title: "New".to_string(),
+            url: "http://example.org/redirect".to_string(),
+            description: None,
+            author: None,
+            explicit: None,
+            last_checked: DateTime::UNIX_EPOCH,
+            episodes: Vec::new(),
+            image_url: None,
+            category: None,
+            guid: None,
+            resolved_url: None,
+        };
+        assert!(find_duplicate(&podcasts, &new).is_none());
+
+        // redirects to a subscribed feed
+        new.resolved_url = Some("http://www.example.com/feed.xml".to_string());
+        assert_eq!(
+            find_duplicate(&podcasts, &new).map(|v| v.id),
+            Some(PodcastId(1))
+        );
+
+        // moved to a different url, but still the same feed
+        new.resolved_url = None;
+        new.guid = Some("guid-b".to_string());
+        assert_eq!(
+            find_duplicate(&podcasts, &new).map(|v| v.id),
+            Some(PodcastId(2))
+        );
     }
 }
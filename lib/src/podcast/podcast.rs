@@ -2,6 +2,7 @@ use std::cmp::Ordering;
 
 use chrono::{DateTime, Utc};
 
+use crate::ids::PodcastId;
 use crate::utils::StringUtils;
 
 use super::{
@@ -13,7 +14,7 @@ use super::{
 /// (possibly empty) vector of episodes.
 #[derive(Debug, Clone)]
 pub struct Podcast {
-    pub id: i64,
+    pub id: PodcastId,
     pub title: String,
     pub sort_title: String,
     pub url: String,
@@ -23,6 +24,10 @@ pub struct Podcast {
     pub last_checked: DateTime<Utc>,
     pub episodes: Vec<Episode>,
     pub image_url: Option<String>,
+    /// The top-level category of the feed, like `Technology`
+    pub category: Option<String>,
+    /// The `podcast:guid` of the feed, which stays the same if the feed moves
+    pub guid: Option<String>,
 }
 
 impl Podcast {
@@ -34,12 +39,18 @@ impl Podcast {
             .map(|ep| usize::from(!ep.is_played()))
             .sum()
     }
+
+    /// Get the publish date of the newest episode, if any episode has one.
+    #[must_use]
+    pub fn newest_pubdate(&self) -> Option<DateTime<Utc>> {
+        self.episodes.iter().filter_map(|ep| ep.pubdate).max()
+    }
 }
 
 impl Menuable for Podcast {
     /// Returns the database ID for the podcast.
     fn get_id(&self) -> i64 {
-        self.id
+        self.id.0
     }
 
     /// Returns the title for the podcast, up to length characters.
@@ -101,4 +112,10 @@ pub struct PodcastNoId {
     pub last_checked: DateTime<Utc>,
     pub episodes: Vec<EpisodeNoId>,
     pub image_url: Option<String>,
+    /// The top-level category of the feed, like `Technology`
+    pub category: Option<String>,
+    /// The `podcast:guid` of the feed, which stays the same if the feed moves
+    pub guid: Option<String>,
+    /// The url the feed was fetched from after following redirects, if different from `url`
+    pub resolved_url: Option<String>,
 }
@@ -0,0 +1,309 @@
+use std::fmt::Display;
+use std::path::Path;
+use std::time::Duration;
+
+use bytesize::ByteSize;
+use futures_util::StreamExt as _;
+use reqwest::ClientBuilder;
+use reqwest::header::CONTENT_LENGTH;
+use sysinfo::Disks;
+
+/// Size to assume for a single episode when there are no existing downloads to estimate from.
+pub const EPISODE_SIZE_FALLBACK: u64 = 64 * 1024 * 1024;
+
+/// Errors for when a download would not fit, see [`check_download_space`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum DownloadSpaceError {
+    /// The disk the download directory is on does not have enough free space
+    #[error("Not enough free disk space: estimated {needed}, available {available}")]
+    InsufficientSpace {
+        needed: ByteSize,
+        available: ByteSize,
+    },
+    /// The download directory would grow beyond the configured quota
+    #[error("Download quota exceeded: estimated {needed}, already used {used} of {quota}")]
+    QuotaExceeded {
+        needed: ByteSize,
+        used: ByteSize,
+        quota: ByteSize,
+    },
+}
+
+/// Sum of file sizes and the amount of files in a directory, see [`dir_usage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DirUsage {
+    pub bytes: u64,
+    pub files: u64,
+}
+
+impl DirUsage {
+    /// Estimate the size of `episodes` new downloads based on the average size of existing files.
+    ///
+    /// Uses [`EPISODE_SIZE_FALLBACK`] if there are no existing files.
+    #[must_use]
+    pub fn estimate(&self, episodes: u64) -> u64 {
+        let per_episode = self
+            .bytes
+            .checked_div(self.files)
+            .filter(|v| *v > 0)
+            .unwrap_or(EPISODE_SIZE_FALLBACK);
+
+        per_episode.saturating_mul(episodes)
+    }
+}
+
+impl Display for DirUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} in {} files", ByteSize::b(self.bytes), self.files)
+    }
+}
+
+/// Total size of remote files, see [`estimate_remote_size`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RemoteSize {
+    /// Sum of the sizes the servers reported
+    pub bytes: u64,
+    /// Amount of files whose size could not be determined
+    pub unknown: usize,
+}
+
+impl Display for RemoteSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", ByteSize::b(self.bytes))?;
+        if self.unknown > 0 {
+            write!(f, " + {} of unknown size", self.unknown)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// How many `HEAD` requests [`estimate_remote_size`] runs at the same time.
+const REMOTE_SIZE_CONCURRENCY: usize = 8;
+
+/// Estimate the total size of downloading all `urls`, via `HEAD` requests.
+///
+/// Files whose request fails or which do not report a `content-length` are counted as [`unknown`](RemoteSize::unknown).
+pub async fn estimate_remote_size(urls: Vec<String>) -> RemoteSize {
+    let agent = ClientBuilder::new()
+        .connect_timeout(Duration::from_secs(10))
+        .build()
+        .expect("reqwest client build failed");
+
+    futures_util::stream::iter(urls)
+        .map(|url| {
+            let agent = agent.clone();
+            async move {
+                let response = agent.head(&url).send().await.ok()?;
+                if !response.status().is_success() {
+                    return None;
+                }
+                // not using "content_length()" as that is the length of the (empty) body of a HEAD response
+                response
+                    .headers()
+                    .get(CONTENT_LENGTH)?
+                    .to_str()
+                    .ok()?
+                    .parse::<u64>()
+                    .ok()
+            }
+        })
+        .buffer_unordered(REMOTE_SIZE_CONCURRENCY)
+        .fold(RemoteSize::default(), |mut acc, size| async move {
+            match size {
+                Some(v) => acc.bytes = acc.bytes.saturating_add(v),
+                None => acc.unknown += 1,
+            }
+            acc
+        })
+        .await
+}
+
+/// Recursively sum up the size of all files in `path`.
+///
+/// Entries which cannot be read are ignored.
+#[must_use]
+pub fn dir_usage(path: &Path) -> DirUsage {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|v| v.file_type().is_file())
+        .filter_map(|v| v.metadata().ok())
+        .fold(DirUsage::default(), |acc, v| DirUsage {
+            bytes: acc.bytes.saturating_add(v.len()),
+            files: acc.files + 1,
+        })
+}
+
+/// Sum up the size of all `paths` which are existing files.
+///
+/// Paths which cannot be read are ignored.
+#[must_use]
+pub fn files_usage<'a>(paths: impl IntoIterator<Item = &'a Path>) -> DirUsage {
+    paths
+        .into_iter()
+        .filter_map(|v| std::fs::metadata(v).ok())
+        .filter(std::fs::Metadata::is_file)
+        .fold(DirUsage::default(), |acc, v| DirUsage {
+            bytes: acc.bytes.saturating_add(v.len()),
+            files: acc.files + 1,
+        })
+}
+
+/// Get the available space on the disk `path` resides on.
+///
+/// Returns `None` if no disk could be matched.
+#[must_use]
+pub fn available_space(path: &Path) -> Option<u64> {
+    let path = path.canonicalize().ok()?;
+    let disks = Disks::new_with_refreshed_list();
+
+    // the disk with the longest mount point containing the path is the one the path is actually on
+    disks
+        .list()
+        .iter()
+        .filter(|v| path.starts_with(v.mount_point()))
+        .max_by_key(|v| v.mount_point().as_os_str().len())
+        .map(sysinfo::Disk::available_space)
+}
+
+/// Check that downloading `episodes` more episodes into `download_dir` fits on the disk and into `quota`.
+///
+/// If the free space cannot be determined, only the quota is checked.
+pub fn check_download_space(
+    download_dir: &Path,
+    quota: Option<ByteSize>,
+    episodes: u64,
+) -> Result<(), DownloadSpaceError> {
+    let usage = dir_usage(download_dir);
+    let needed = usage.estimate(episodes);
+
+    check_space(usage, needed, available_space(download_dir), quota)
+}
+
+/// The pure part of [`check_download_space`].
+fn check_space(
+    usage: DirUsage,
+    needed: u64,
+    available: Option<u64>,
+    quota: Option<ByteSize>,
+) -> Result<(), DownloadSpaceError> {
+    if let Some(available) = available {
+        if needed > available {
+            return Err(DownloadSpaceError::InsufficientSpace {
+                needed: ByteSize::b(needed),
+                available: ByteSize::b(available),
+            });
+        }
+    }
+
+    if let Some(quota) = quota {
+        if usage.bytes.saturating_add(needed) > quota.as_u64() {
+            return Err(DownloadSpaceError::QuotaExceeded {
+                needed: ByteSize::b(needed),
+                used: ByteSize::b(usage.bytes),
+                quota,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use bytesize::ByteSize;
+    use pretty_assertions::assert_eq;
+
+    use super::{
+        DirUsage, DownloadSpaceError, EPISODE_SIZE_FALLBACK, RemoteSize, check_space, files_usage,
+    };
+
+    #[test]
+    fn should_estimate_from_average() {
+        let usage = DirUsage {
+            bytes: 300,
+            files: 3,
+        };
+        assert_eq!(usage.estimate(2), 200);
+
+        assert_eq!(DirUsage::default().estimate(2), EPISODE_SIZE_FALLBACK * 2);
+    }
+
+    #[test]
+    fn should_display_remote_size() {
+        let size = RemoteSize {
+            bytes: 2048,
+            unknown: 0,
+        };
+        assert_eq!(size.to_string(), ByteSize::b(2048).to_string());
+
+        let size = RemoteSize {
+            bytes: 2048,
+            unknown: 2,
+        };
+        assert_eq!(
+            size.to_string(),
+            format!("{} + 2 of unknown size", ByteSize::b(2048))
+        );
+    }
+
+    #[test]
+    fn should_sum_existing_files() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let dir = tmp_dir.path();
+        let a = dir.join("a.mp3");
+        let b = dir.join("b.mp3");
+        std::fs::write(&a, b"episode").unwrap();
+        std::fs::write(&b, b"other").unwrap();
+
+        // missing files and directories are not counted
+        let usage = files_usage([
+            a.as_path(),
+            b.as_path(),
+            dir.join("missing.mp3").as_path(),
+            dir,
+        ]);
+        assert_eq!(
+            usage,
+            DirUsage {
+                bytes: 12,
+                files: 2
+            }
+        );
+        assert_eq!(usage.to_string(), format!("{} in 2 files", ByteSize::b(12)));
+    }
+
+    #[test]
+    fn should_check_free_space() {
+        let usage = DirUsage::default();
+
+        assert_eq!(check_space(usage, 100, Some(100), None), Ok(()));
+        assert_eq!(check_space(usage, 100, None, None), Ok(()));
+        assert_eq!(
+            check_space(usage, 101, Some(100), None),
+            Err(DownloadSpaceError::InsufficientSpace {
+                needed: ByteSize::b(101),
+                available: ByteSize::b(100)
+            })
+        );
+    }
+
+    #[test]
+    fn should_check_quota() {
+        let usage = DirUsage {
+            bytes: 50,
+            files: 1,
+        };
+
+        assert_eq!(check_space(usage, 50, None, Some(ByteSize::b(100))), Ok(()));
+        assert_eq!(
+            check_space(usage, 51, Some(1000), Some(ByteSize::b(100))),
+            Err(DownloadSpaceError::QuotaExceeded {
+                needed: ByteSize::b(51),
+                used: ByteSize::b(50),
+                quota: ByteSize::b(100)
+            })
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! Search internet radio stations via the [radio-browser.info](https://www.radio-browser.info) API.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use rand::seq::SliceRandom;
+use reqwest::ClientBuilder;
+use serde::Deserialize;
+
+/// The public API servers, tried in random order until one responds.
+const API_SERVERS: [&str; 3] = [
+    "https://de1.api.radio-browser.info",
+    "https://de2.api.radio-browser.info",
+    "https://fi1.api.radio-browser.info",
+];
+
+/// Maximum number of stations returned by a search.
+const SEARCH_LIMIT: &str = "100";
+
+/// A single internet radio station.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RadioStation {
+    /// The unique id of the station on radio-browser.info
+    pub uuid: String,
+    pub name: String,
+    /// The url of the stream, with playlists (like `.pls`) already resolved
+    pub url: String,
+    /// Comma-separated tags, like genres
+    pub tags: String,
+    pub country: String,
+    pub codec: String,
+    /// The bitrate in kbit/s, `0` if unknown
+    pub bitrate: u32,
+}
+
+/// A station as returned by the API, only containing the fields we use.
+#[derive(Debug, Deserialize)]
+struct ApiStation {
+    stationuuid: String,
+    name: String,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    url_resolved: String,
+    #[serde(default)]
+    tags: String,
+    #[serde(default)]
+    country: String,
+    #[serde(default)]
+    codec: String,
+    #[serde(default)]
+    bitrate: u32,
+}
+
+impl From<ApiStation> for RadioStation {
+    fn from(value: ApiStation) -> Self {
+        let url = if value.url_resolved.is_empty() {
+            value.url
+        } else {
+            value.url_resolved
+        };
+
+        Self {
+            uuid: value.stationuuid,
+            name: value.name.trim().to_string(),
+            url,
+            tags: value.tags,
+            country: value.country,
+            codec: value.codec,
+            bitrate: value.bitrate,
+        }
+    }
+}
+
+/// What to search stations by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StationSearch {
+    Name(String),
+    Tag(String),
+    Country(String),
+}
+
+impl StationSearch {
+    /// Parse a search from user input.
+    ///
+    /// Input prefixed with `tag:` or `country:` searches by that field, everything else searches by name.
+    #[must_use]
+    pub fn parse(input: &str) -> Self {
+        let input = input.trim();
+
+        if let Some(tag) = input.strip_prefix("tag:") {
+            Self::Tag(tag.trim().to_string())
+        } else if let Some(country) = input.strip_prefix("country:") {
+            Self::Country(country.trim().to_string())
+        } else {
+            Self::Name(input.to_string())
+        }
+    }
+
+    /// Get the API query parameter and value for this search.
+    fn query_param(&self) -> (&'static str, &str) {
+        match self {
+            Self::Name(v) => ("name", v),
+            Self::Tag(v) => ("tag", v),
+            Self::Country(v) => ("country", v),
+        }
+    }
+}
+
+/// Search for stations, ordered by votes, skipping stations that are known to be broken.
+pub async fn search_stations(search: &StationSearch) -> Result<Vec<RadioStation>> {
+    let client = ClientBuilder::new()
+        .timeout(Duration::from_secs(10))
+        .user_agent(format!("termusic/{}", crate::VERSION))
+        .build()?;
+
+    let mut servers = API_SERVERS;
+    servers.shuffle(&mut rand::rng());
+
+    let (param, value) = search.query_param();
+    let mut last_err = anyhow!("No radio-browser server available");
+    for server in servers {
+        let res = client
+            .get(format!("{server}/json/stations/search"))
+            .query(&[
+                (param, value),
+                ("limit", SEARCH_LIMIT),
+                ("hidebroken", "true"),
+                ("order", "votes"),
+                ("reverse", "true"),
+            ])
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status);
+
+        match res {
+            Ok(res) => {
+                let text = res.text().await?;
+                return parse_stations(&text).with_context(|| server.to_string());
+            }
+            Err(err) => {
+                warn!("radio-browser server {server} failed: {err}");
+                last_err = err.into();
+            }
+        }
+    }
+
+    Err(last_err)
+}
+
+/// Parse the JSON station list returned by the API, skipping stations without a stream url.
+fn parse_stations(text: &str) -> Result<Vec<RadioStation>> {
+    let stations: Vec<ApiStation> = serde_json::from_str(text)?;
+
+    Ok(stations
+        .into_iter()
+        .map(RadioStation::from)
+        .filter(|v| !v.url.is_empty())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{RadioStation, StationSearch, parse_stations};
+
+    #[test]
+    fn should_parse_search() {
+        assert_eq!(
+            StationSearch::parse(" jazz fm "),
+            StationSearch::Name("jazz fm".to_string())
+        );
+        assert_eq!(
+            StationSearch::parse("tag: jazz"),
+            StationSearch::Tag("jazz".to_string())
+        );
+        assert_eq!(
+            StationSearch::parse("country:Germany"),
+            StationSearch::Country("Germany".to_string())
+        );
+    }
+
+    #[test]
+    fn should_parse_stations() {
+        let text = r#"[
+            {
+                "stationuuid": "960e57c5-0601-11e8-ae97-52543be04c81",
+                "name": " Some Radio ",
+                "url": "http://example.com/radio.pls",
+                "url_resolved": "http://example.com/radio.mp3",
+                "tags": "jazz,smooth jazz",
+                "country": "Germany",
+                "codec": "MP3",
+                "bitrate": 128,
+                "votes": 10
+            },
+            {
+                "stationuuid": "no-url",
+                "name": "Broken",
+                "url": "",
+                "url_resolved": ""
+            }
+        ]"#;
+
+        assert_eq!(
+            parse_stations(text).unwrap(),
+            [RadioStation {
+                uuid: "960e57c5-0601-11e8-ae97-52543be04c81".to_string(),
+                name: "Some Radio".to_string(),
+                url: "http://example.com/radio.mp3".to_string(),
+                tags: "jazz,smooth jazz".to_string(),
+                country: "Germany".to_string(),
+                codec: "MP3".to_string(),
+                bitrate: 128,
+            }]
+        );
+    }
+}
@@ -0,0 +1,108 @@
+//! Write track ratings back to the tags of the file, so that other players see them too.
+//!
+//! MP3 files get a `POPM` frame, FLAC, Vorbis and Opus files get a `RATING` comment from `0` to `100`.
+
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use lofty::{
+    config::WriteOptions,
+    file::{FileType, TaggedFileExt},
+    probe::Probe,
+    tag::{ItemKey, TagExt},
+};
+
+use crate::new_database::track_ops::MAX_RATING;
+
+/// The email / user the `POPM` frame is stored under.
+const POPM_USER: &str = "termusic";
+
+/// Write `rating` (`0` to [`MAX_RATING`], `0` being unrated) to the tags of the file at `path`.
+pub fn write_rating(path: &Path, rating: u8) -> Result<()> {
+    if rating > MAX_RATING {
+        bail!("Rating {rating} is above the maximum of {MAX_RATING}");
+    }
+
+    let file_type = Probe::open(path)?
+        .guess_file_type()?
+        .file_type()
+        .context("unknown file type")?;
+
+    match file_type {
+        FileType::Mpeg => write_popm(path, rating),
+        FileType::Flac | FileType::Vorbis | FileType::Opus => write_vorbis_rating(path, rating),
+        _ => bail!("Writing ratings to {file_type:?} files is not supported"),
+    }
+}
+
+/// Write the rating as a `POPM` frame, keeping all other frames as-is.
+fn write_popm(path: &Path, rating: u8) -> Result<()> {
+    let mut tag = match id3::Tag::read_from_path(path) {
+        Ok(tag) => tag,
+        Err(err) if matches!(err.kind, id3::ErrorKind::NoTag) => id3::Tag::new(),
+        Err(err) => return Err(err).context("read id3 tag"),
+    };
+
+    tag.add_frame(id3::frame::Popularimeter {
+        user: POPM_USER.to_string(),
+        rating: popm_rating(rating),
+        counter: 0,
+    });
+    tag.write_to_path(path, tag.version())
+        .context("write id3 tag")?;
+
+    Ok(())
+}
+
+/// Write the rating as a `RATING` vorbis comment.
+fn write_vorbis_rating(path: &Path, rating: u8) -> Result<()> {
+    let mut tagged_file = Probe::open(path)?.read()?;
+    let Some(tag) = tagged_file.primary_tag_mut() else {
+        bail!("File has no vorbis comments");
+    };
+
+    tag.insert_text(
+        ItemKey::Unknown("RATING".to_string()),
+        vorbis_rating(rating).to_string(),
+    );
+    tag.save_to_path(path, WriteOptions::new())
+        .context("write vorbis comments")?;
+
+    Ok(())
+}
+
+/// Map a rating to the `0` to `255` of a `POPM` frame, the same way Windows Media Player does.
+fn popm_rating(rating: u8) -> u8 {
+    match rating {
+        0 => 0,
+        1 => 1,
+        2 => 64,
+        3 => 128,
+        4 => 196,
+        _ => 255,
+    }
+}
+
+/// Map a rating to the `0` to `100` commonly used for the `RATING` vorbis comment.
+fn vorbis_rating(rating: u8) -> u8 {
+    rating.min(MAX_RATING) * 20
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{popm_rating, vorbis_rating};
+
+    #[test]
+    fn should_map_ratings() {
+        assert_eq!(
+            (0..=5).map(popm_rating).collect::<Vec<_>>(),
+            [0, 1, 64, 128, 196, 255]
+        );
+        assert_eq!(
+            (0..=5).map(vorbis_rating).collect::<Vec<_>>(),
+            [0, 20, 40, 60, 80, 100]
+        );
+    }
+}
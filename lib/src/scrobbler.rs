@@ -0,0 +1,436 @@
+//! Submitting played tracks ("scrobbling") to Last.fm and ListenBrainz.
+//!
+//! A track is submitted as "now playing" once it starts, and as a listen once it has been played for half its duration
+//! or 4 minutes, whichever comes first, see [`should_scrobble`].
+
+use std::time::Duration;
+
+use reqwest::{Client, ClientBuilder};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::config::v2::server::scrobble::{LastfmSettings, ListenBrainzSettings, ScrobbleSettings};
+use crate::track::{MediaTypes, Track};
+
+/// Tracks shorter than this are never submitted.
+pub const MIN_TRACK_DURATION: Duration = Duration::from_secs(30);
+/// A track is submitted after being played this long, even if that is less than half its duration.
+pub const MAX_LISTEN_THRESHOLD: Duration = Duration::from_secs(4 * 60);
+
+const LASTFM_API_URL: &str = "https://ws.audioscrobbler.com/2.0/";
+/// Last.fm error code for a invalid session key.
+const LASTFM_ERROR_INVALID_SESSION: u32 = 9;
+
+/// Errors that can happen while submitting.
+#[derive(Debug, thiserror::Error)]
+pub enum ScrobbleError {
+    #[error("Request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("Last.fm returned error {code}: {message}")]
+    Lastfm { code: u32, message: String },
+    #[error("Unexpected response: {0}")]
+    Response(#[from] serde_json::Error),
+}
+
+/// The metadata of a track to submit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScrobbleTrack {
+    pub artist: String,
+    pub title: String,
+    pub album: Option<String>,
+    pub duration: Option<Duration>,
+}
+
+impl ScrobbleTrack {
+    /// Get the metadata to submit for `track`.
+    ///
+    /// Returns [`None`] for radio and podcasts, and for music tracks without a artist or title.
+    #[must_use]
+    pub fn from_track(track: &Track) -> Option<Self> {
+        let MediaTypes::Track(track_data) = track.inner() else {
+            return None;
+        };
+
+        Some(Self {
+            artist: track.artist().filter(|v| !v.is_empty())?.to_string(),
+            title: track.title().filter(|v| !v.is_empty())?.to_string(),
+            album: track_data.album().map(ToString::to_string),
+            duration: track.duration(),
+        })
+    }
+}
+
+/// Check whether a track of `duration` has been `played` long enough to be submitted as a listen.
+///
+/// Tracks of unknown duration are submitted once played for [`MAX_LISTEN_THRESHOLD`].
+#[must_use]
+pub fn should_scrobble(duration: Option<Duration>, played: Duration) -> bool {
+    match duration {
+        Some(duration) if duration < MIN_TRACK_DURATION => false,
+        Some(duration) => played >= (duration / 2).min(MAX_LISTEN_THRESHOLD),
+        None => played >= MAX_LISTEN_THRESHOLD,
+    }
+}
+
+/// Submits to all services configured in [`ScrobbleSettings`].
+///
+/// Errors of a service are logged and do not affect the other services.
+pub struct Scrobbler {
+    client: Client,
+    lastfm: Option<Lastfm>,
+    listenbrainz: Option<ListenBrainzSettings>,
+}
+
+impl Scrobbler {
+    pub fn new(settings: &ScrobbleSettings) -> Result<Self, ScrobbleError> {
+        let client = ClientBuilder::new()
+            .connect_timeout(Duration::from_secs(5))
+            .timeout(Duration::from_secs(20))
+            .build()?;
+
+        Ok(Self {
+            client,
+            lastfm: settings.lastfm.clone().map(|settings| Lastfm {
+                settings,
+                session_key: None,
+            }),
+            listenbrainz: settings.listenbrainz.clone(),
+        })
+    }
+
+    /// Whether any service is configured.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.lastfm.is_some() || self.listenbrainz.is_some()
+    }
+
+    /// Submit that `track` has started playing.
+    pub async fn now_playing(&mut self, track: &ScrobbleTrack) {
+        if let Some(lastfm) = &mut self.lastfm {
+            if let Err(err) = lastfm.submit(&self.client, track, None).await {
+                warn!("Submitting now playing to Last.fm failed: {err}");
+            }
+        }
+
+        if let Some(listenbrainz) = &self.listenbrainz {
+            if let Err(err) = listenbrainz_submit(&self.client, listenbrainz, track, None).await {
+                warn!("Submitting now playing to ListenBrainz failed: {err}");
+            }
+        }
+    }
+
+    /// Submit a listen of `track`, which started at unix timestamp `started_at`.
+    pub async fn scrobble(&mut self, track: &ScrobbleTrack, started_at: u64) {
+        if let Some(lastfm) = &mut self.lastfm {
+            if let Err(err) = lastfm.submit(&self.client, track, Some(started_at)).await {
+                warn!("Submitting listen to Last.fm failed: {err}");
+            }
+        }
+
+        if let Some(listenbrainz) = &self.listenbrainz {
+            if let Err(err) =
+                listenbrainz_submit(&self.client, listenbrainz, track, Some(started_at)).await
+            {
+                warn!("Submitting listen to ListenBrainz failed: {err}");
+            }
+        }
+    }
+
+    /// Submit that `track` was marked as favorite if `loved`, otherwise that it was unmarked.
+    ///
+    /// Only Last.fm supports this, and only if [`LastfmSettings::sync_loved`] is set.
+    pub async fn love(&mut self, track: &ScrobbleTrack, loved: bool) {
+        let Some(lastfm) = self.lastfm.as_mut().filter(|v| v.settings.sync_loved) else {
+            return;
+        };
+
+        if let Err(err) = lastfm.love(&self.client, track, loved).await {
+            warn!("Submitting love to Last.fm failed: {err}");
+        }
+    }
+}
+
+/// Last.fm settings and the session key, which is requested on first use.
+struct Lastfm {
+    settings: LastfmSettings,
+    session_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LastfmErrorResponse {
+    error: u32,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LastfmSessionResponse {
+    session: LastfmSession,
+}
+
+#[derive(Debug, Deserialize)]
+struct LastfmSession {
+    key: String,
+}
+
+impl Lastfm {
+    /// Submit `track` as now playing, or as a listen if `started_at` is set.
+    async fn submit(
+        &mut self,
+        client: &Client,
+        track: &ScrobbleTrack,
+        started_at: Option<u64>,
+    ) -> Result<(), ScrobbleError> {
+        let method = if started_at.is_some() {
+            "track.scrobble"
+        } else {
+            "track.updateNowPlaying"
+        };
+        let mut params = vec![
+            ("method", method.to_string()),
+            ("artist", track.artist.clone()),
+            ("track", track.title.clone()),
+            ("api_key", self.settings.api_key.clone()),
+        ];
+        if let Some(album) = &track.album {
+            params.push(("album", album.clone()));
+        }
+        if let Some(duration) = track.duration {
+            params.push(("duration", duration.as_secs().to_string()));
+        }
+        if let Some(started_at) = started_at {
+            params.push(("timestamp", started_at.to_string()));
+        }
+
+        self.call_with_session(client, params).await
+    }
+
+    /// Love `track`, or unlove it if not `loved`.
+    async fn love(
+        &mut self,
+        client: &Client,
+        track: &ScrobbleTrack,
+        loved: bool,
+    ) -> Result<(), ScrobbleError> {
+        let method = if loved { "track.love" } else { "track.unlove" };
+        let params = vec![
+            ("method", method.to_string()),
+            ("artist", track.artist.clone()),
+            ("track", track.title.clone()),
+            ("api_key", self.settings.api_key.clone()),
+        ];
+
+        self.call_with_session(client, params).await
+    }
+
+    /// Add the session key to `params` and send the call, requesting a session first if there is none yet.
+    async fn call_with_session(
+        &mut self,
+        client: &Client,
+        mut params: Vec<(&'static str, String)>,
+    ) -> Result<(), ScrobbleError> {
+        let session_key = match &self.session_key {
+            Some(v) => v.clone(),
+            None => {
+                let key = self.request_session(client).await?;
+                self.session_key = Some(key.clone());
+                key
+            }
+        };
+        params.push(("sk", session_key));
+
+        let res = self.call(client, params).await;
+        // request a new session on the next call, the user may have revoked access
+        if let Err(ScrobbleError::Lastfm {
+            code: LASTFM_ERROR_INVALID_SESSION,
+            ..
+        }) = res
+        {
+            self.session_key = None;
+        }
+
+        res.map(|_| ())
+    }
+
+    /// Request a session key with the configured username and password.
+    async fn request_session(&self, client: &Client) -> Result<String, ScrobbleError> {
+        let params = vec![
+            ("method", "auth.getMobileSession".to_string()),
+            ("username", self.settings.username.clone()),
+            ("password", self.settings.password.clone()),
+            ("api_key", self.settings.api_key.clone()),
+        ];
+        let body = self.call(client, params).await?;
+
+        let response: LastfmSessionResponse = serde_json::from_str(&body)?;
+
+        Ok(response.session.key)
+    }
+
+    /// Sign and send a API call, returning the response body.
+    async fn call(
+        &self,
+        client: &Client,
+        mut params: Vec<(&'static str, String)>,
+    ) -> Result<String, ScrobbleError> {
+        let signature = lastfm_signature(&params, &self.settings.api_secret);
+        params.push(("api_sig", signature));
+        // not part of the signature
+        params.push(("format", "json".to_string()));
+
+        let body = client
+            .post(LASTFM_API_URL)
+            .form(&params)
+            .send()
+            .await?
+            .text()
+            .await?;
+
+        // errors may come with any status code
+        if let Ok(err) = serde_json::from_str::<LastfmErrorResponse>(&body) {
+            return Err(ScrobbleError::Lastfm {
+                code: err.error,
+                message: err.message,
+            });
+        }
+
+        Ok(body)
+    }
+}
+
+/// Calculate the `api_sig` for `params`: the md5 of all sorted `keyvalue` pairs followed by the secret.
+fn lastfm_signature(params: &[(&str, String)], secret: &str) -> String {
+    let mut sorted: Vec<&(&str, String)> = params.iter().collect();
+    sorted.sort_by_key(|(key, _)| *key);
+
+    let mut data = String::new();
+    for (key, value) in sorted {
+        data.push_str(key);
+        data.push_str(value);
+    }
+    data.push_str(secret);
+
+    format!("{:x}", md5::compute(data))
+}
+
+/// Build the ListenBrainz submission of `track` as now playing, or as a listen if `started_at` is set.
+fn listenbrainz_payload(track: &ScrobbleTrack, started_at: Option<u64>) -> serde_json::Value {
+    let mut additional_info = json!({
+        "submission_client": "termusic",
+        "submission_client_version": crate::VERSION,
+    });
+    if let Some(duration) = track.duration {
+        additional_info["duration_ms"] = json!(duration.as_millis());
+    }
+
+    let mut metadata = json!({
+        "artist_name": track.artist,
+        "track_name": track.title,
+        "additional_info": additional_info,
+    });
+    if let Some(album) = &track.album {
+        metadata["release_name"] = json!(album);
+    }
+
+    let mut listen = json!({ "track_metadata": metadata });
+    let listen_type = match started_at {
+        Some(started_at) => {
+            listen["listened_at"] = json!(started_at);
+            "single"
+        }
+        None => "playing_now",
+    };
+
+    json!({
+        "listen_type": listen_type,
+        "payload": [listen],
+    })
+}
+
+async fn listenbrainz_submit(
+    client: &Client,
+    settings: &ListenBrainzSettings,
+    track: &ScrobbleTrack,
+    started_at: Option<u64>,
+) -> Result<(), ScrobbleError> {
+    let url = format!(
+        "{}/1/submit-listens",
+        settings.api_url.trim_end_matches('/')
+    );
+
+    client
+        .post(url)
+        .header("Authorization", format!("Token {}", settings.token))
+        .json(&listenbrainz_payload(track, started_at))
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::{ScrobbleTrack, lastfm_signature, listenbrainz_payload, should_scrobble};
+
+    #[test]
+    fn should_follow_listen_rules() {
+        let secs = Duration::from_secs;
+
+        // too short to ever count
+        assert!(!should_scrobble(Some(secs(20)), secs(20)));
+        // half the duration
+        assert!(!should_scrobble(Some(secs(200)), secs(99)));
+        assert!(should_scrobble(Some(secs(200)), secs(100)));
+        // 4 minutes for long tracks
+        assert!(!should_scrobble(Some(secs(3600)), secs(239)));
+        assert!(should_scrobble(Some(secs(3600)), secs(240)));
+        // unknown duration
+        assert!(!should_scrobble(None, secs(100)));
+        assert!(should_scrobble(None, secs(240)));
+    }
+
+    #[test]
+    fn should_sign_sorted_params() {
+        let params = [
+            ("method", "track.scrobble".to_string()),
+            ("artist", "Artist".to_string()),
+            ("track", "Title".to_string()),
+            ("timestamp", "1700000000".to_string()),
+            ("api_key", "key".to_string()),
+            ("sk", "session".to_string()),
+        ];
+
+        assert_eq!(
+            lastfm_signature(&params, "secret"),
+            "0b2a18a2bcd47b7823cabbb95a88ec01"
+        );
+    }
+
+    #[test]
+    fn should_build_listenbrainz_payload() {
+        let track = ScrobbleTrack {
+            artist: "Artist".to_string(),
+            title: "Title".to_string(),
+            album: None,
+            duration: Some(Duration::from_secs(200)),
+        };
+
+        let payload = listenbrainz_payload(&track, None);
+        assert_eq!(payload["listen_type"], json!("playing_now"));
+        assert_eq!(payload["payload"][0].get("listened_at"), None);
+
+        let payload = listenbrainz_payload(&track, Some(1_700_000_000));
+        assert_eq!(payload["listen_type"], json!("single"));
+        assert_eq!(payload["payload"][0]["listened_at"], json!(1_700_000_000));
+        let metadata = &payload["payload"][0]["track_metadata"];
+        assert_eq!(metadata["artist_name"], json!("Artist"));
+        assert_eq!(metadata["track_name"], json!("Title"));
+        assert_eq!(metadata.get("release_name"), None);
+        assert_eq!(metadata["additional_info"]["duration_ms"], json!(200_000));
+    }
+}
@@ -21,7 +21,7 @@ use std::time::Duration;
 use crate::utils::display_with;
 
 /// The struct to hold all the metadata and the lyric frames
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
 pub struct Lyric {
     /// Offset in milliseconds
     ///
@@ -144,6 +144,38 @@ impl Lyric {
         result
     }
 
+    /// Insert a caption with `text` at song time `time`, keeping the captions sorted.
+    ///
+    /// This function takes `self.offset` into account, so that the caption is shown at `time`.
+    /// Returns the index the caption was inserted at.
+    ///
+    /// # Panics
+    ///
+    /// if `time` cannot be represented as a [`i64`]
+    pub fn insert_caption(&mut self, time: Duration, text: &str) -> usize {
+        let time = i64::try_from(time.as_millis()).expect("Cannot represent input time as i64");
+        let timestamp = (time + self.offset).max(0);
+        // insert after captions with the same timestamp, so that repeated captures keep their order
+        let index = self
+            .captions
+            .partition_point(|caption| caption.timestamp <= timestamp);
+        self.captions.insert(index, Caption::new(timestamp, text));
+
+        index
+    }
+
+    /// Get the lines of `text` which are not LRC captions or tags, trimmed and without empty lines.
+    ///
+    /// This is used to get the lines of plain (unsynced) lyrics to create captions from.
+    #[must_use]
+    pub fn plain_lines(text: &str) -> Vec<String> {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('['))
+            .map(str::to_string)
+            .collect()
+    }
+
     /// Merge captions that are less than 2 seconds apart
     pub fn merge_adjacent(&mut self) {
         let mut merged_captions = self.captions.clone();
@@ -164,6 +196,38 @@ impl Lyric {
 }
 
 impl Caption {
+    /// Create a new caption at `timestamp` (in milliseconds), with `text` trimmed.
+    #[must_use]
+    pub fn new(timestamp: i64, text: &str) -> Self {
+        Self {
+            timestamp,
+            text: text.trim().to_string(),
+        }
+    }
+
+    /// Get the timestamp in milliseconds
+    #[must_use]
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    /// Get the text of the caption
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replace the text of the caption, trimmed.
+    pub fn set_text(&mut self, text: &str) {
+        text.trim().clone_into(&mut self.text);
+    }
+
+    /// Format the timestamp as a LRC time: `mm:ss.xx`
+    #[must_use]
+    pub fn display_time(&self) -> impl std::fmt::Display {
+        time_lrc(self.timestamp.try_into().unwrap_or(0))
+    }
+
     /// Try to parse a single [`Caption`]
     fn parse_line(line: &str) -> Option<Self> {
         //[00:12.00]Line 1 lyrics
@@ -555,4 +619,35 @@ mod tests {
             "text4"
         );
     }
+
+    #[test]
+    fn should_insert_caption() {
+        let mut lyrics = Lyric {
+            offset: 500,
+            captions: vec![Caption::new(1500, "first")],
+        };
+
+        assert_eq!(lyrics.insert_caption(Duration::from_secs(3), " third "), 1);
+        assert_eq!(lyrics.insert_caption(Duration::from_secs(1), "second"), 1);
+
+        assert_eq!(
+            lyrics.captions.as_slice(),
+            &[
+                Caption::new(1500, "first"),
+                Caption::new(1500, "second"),
+                Caption::new(3500, "third"),
+            ]
+        );
+        assert_eq!(
+            lyrics.as_lrc_text(),
+            "[offset:500]\n[00:01.50]first\n[00:01.50]second\n[00:03.50]third\n"
+        );
+    }
+
+    #[test]
+    fn should_get_plain_lines() {
+        let txt = "[ti:Song Title]\nFirst line\n\n  Second line  \n[00:12.00]Synced line";
+
+        assert_eq!(Lyric::plain_lines(txt), ["First line", "Second line"]);
+    }
 }
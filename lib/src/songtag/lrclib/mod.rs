@@ -0,0 +1,128 @@
+//! Lyrics from [lrclib](https://lrclib.net), which only provides lyrics, no pictures or downloads.
+
+mod model;
+
+use std::time::Duration;
+
+use anyhow::anyhow;
+use lofty::picture::Picture;
+use model::{to_lyric, to_song_info};
+use reqwest::{Client, ClientBuilder};
+
+use super::{
+    ServiceProvider, SongTag,
+    service::{SongTagService, SongTagServiceError, SongTagServiceErrorWhere},
+};
+
+const URL_SEARCH_LRCLIB: &str = "https://lrclib.net/api/search";
+const URL_LYRIC_LRCLIB: &str = "https://lrclib.net/api/get";
+/// lrclib asks clients to identify themselves
+const USER_AGENT: &str = concat!(
+    "termusic ",
+    env!("CARGO_PKG_VERSION"),
+    " (https://github.com/tramhao/termusic)"
+);
+
+pub struct Api {
+    client: Client,
+}
+
+impl Api {
+    pub fn new() -> Self {
+        let client = ClientBuilder::new()
+            .timeout(Duration::from_secs(10))
+            .user_agent(USER_AGENT)
+            .build()
+            .expect("failed to build reqwest client.");
+
+        Self { client }
+    }
+}
+
+impl SongTagService for Api {
+    type Error = anyhow::Error;
+
+    fn display_name() -> &'static str
+    where
+        Self: Sized,
+    {
+        "lrclib"
+    }
+
+    async fn search_recording(
+        &self,
+        keywords: &str,
+        offset: u32,
+        limit: u32,
+    ) -> std::result::Result<Vec<SongTag>, SongTagServiceError<Self::Error>> {
+        let result = self
+            .client
+            .get(URL_SEARCH_LRCLIB)
+            .query(&[("q", keywords)])
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(anyhow::Error::from)?
+            .text()
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        // the api does not support paging, it always returns all results
+        let songs = to_song_info(&result).map_err(|err| {
+            SongTagServiceError::Other(anyhow!(err).context("Parse result into SongTag Array"))
+        })?;
+
+        Ok(songs
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect())
+    }
+
+    async fn get_lyrics(
+        &self,
+        song: &SongTag,
+    ) -> std::result::Result<String, SongTagServiceError<Self::Error>> {
+        if song.service_provider() != ServiceProvider::Lrclib {
+            return Err(SongTagServiceError::IncorrectService(
+                song.service_provider().to_string(),
+                Self::display_name(),
+            ));
+        }
+
+        let result = self
+            .client
+            .get(format!("{URL_LYRIC_LRCLIB}/{}", song.song_id))
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(anyhow::Error::from)?
+            .text()
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        to_lyric(&result).map_err(|err| {
+            SongTagServiceError::Other(anyhow!(err).context("Extract Lyric text from result"))
+        })
+    }
+
+    async fn get_picture(
+        &self,
+        _song: &SongTag,
+    ) -> std::result::Result<Picture, SongTagServiceError<Self::Error>> {
+        Err(SongTagServiceError::NotSupported(
+            SongTagServiceErrorWhere::GetPicture,
+            Self::display_name(),
+        ))
+    }
+
+    async fn download_recording(
+        &self,
+        _song: &SongTag,
+    ) -> std::result::Result<String, SongTagServiceError<Self::Error>> {
+        Err(SongTagServiceError::NotSupported(
+            SongTagServiceErrorWhere::DownloadRecording,
+            Self::display_name(),
+        ))
+    }
+}
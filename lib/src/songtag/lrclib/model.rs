@@ -0,0 +1,148 @@
+use serde_json::{Value, from_str};
+
+use super::super::{ServiceProvider, SongTag};
+
+#[derive(Debug, thiserror::Error)]
+pub enum LrclibParseError {
+    #[error("Expected property \"{0}\" to exist")]
+    MissingProperty(&'static str),
+
+    #[error("Expected a Array")]
+    NotArray,
+
+    #[error(transparent)]
+    ParseError(#[from] serde_json::Error),
+}
+
+type Result<T> = std::result::Result<T, LrclibParseError>;
+
+/// Try to get the lyric lrc content from the given result, preferring synced lyrics
+///
+/// Returns a empty string if the track has no lyrics, like instrumentals.
+pub fn to_lyric(json: &str) -> Result<String> {
+    let value = from_str::<Value>(json)?;
+
+    if value.get("id").is_none() {
+        return Err(LrclibParseError::MissingProperty("id"));
+    }
+
+    let lyric = ["syncedLyrics", "plainLyrics"]
+        .into_iter()
+        .filter_map(|field| value.get(field).and_then(Value::as_str))
+        .find(|v| !v.trim().is_empty())
+        .unwrap_or_default()
+        .to_owned();
+
+    Ok(lyric)
+}
+
+/// Try to get all tracks from the search result
+pub fn to_song_info(json: &str) -> Result<Vec<SongTag>> {
+    let value = from_str::<Value>(json)?;
+    let array = value.as_array().ok_or(LrclibParseError::NotArray)?;
+
+    Ok(array.iter().filter_map(parse_song_info).collect())
+}
+
+/// Try to parse a single [`SongTag`] from a given lrclib value
+fn parse_song_info(v: &Value) -> Option<SongTag> {
+    let song_id = v.get("id").and_then(Value::as_u64).map(|v| v.to_string())?;
+
+    let get_str = |field: &str| v.get(field).and_then(Value::as_str).map(ToOwned::to_owned);
+
+    Some(SongTag {
+        lyric_id: Some(song_id.clone()),
+        song_id,
+        title: get_str("trackName"),
+        artist: get_str("artistName"),
+        album: get_str("albumName"),
+        pic_id: None,
+        lang_ext: Some("lrclib".to_string()),
+        service_provider: ServiceProvider::Lrclib,
+        // lrclib only provides lyrics
+        url: None,
+        album_id: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_parse_songinfo() {
+        let sample_data = r#"[
+            {
+                "id": 3396226,
+                "trackName": "Track A",
+                "artistName": "Some Artist",
+                "albumName": "Some Album",
+                "duration": 233,
+                "instrumental": false,
+                "plainLyrics": "First line\nSecond line",
+                "syncedLyrics": "[00:17.12] First line\n[00:20.00] Second line"
+            },
+            {
+                "id": 3396227,
+                "trackName": "Track B",
+                "artistName": "Some Artist",
+                "albumName": null,
+                "duration": 120,
+                "instrumental": true,
+                "plainLyrics": null,
+                "syncedLyrics": null
+            }
+        ]"#;
+
+        let res = to_song_info(sample_data).unwrap();
+
+        assert_eq!(
+            res,
+            vec![
+                SongTag {
+                    service_provider: ServiceProvider::Lrclib,
+                    song_id: "3396226".to_string(),
+                    artist: Some("Some Artist".to_string()),
+                    title: Some("Track A".to_string()),
+                    album: Some("Some Album".to_string()),
+                    lang_ext: Some("lrclib".to_string()),
+                    lyric_id: Some("3396226".to_string()),
+                    url: None,
+                    pic_id: None,
+                    album_id: None,
+                },
+                SongTag {
+                    service_provider: ServiceProvider::Lrclib,
+                    song_id: "3396227".to_string(),
+                    artist: Some("Some Artist".to_string()),
+                    title: Some("Track B".to_string()),
+                    album: None,
+                    lang_ext: Some("lrclib".to_string()),
+                    lyric_id: Some("3396227".to_string()),
+                    url: None,
+                    pic_id: None,
+                    album_id: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_prefer_synced_lyric() {
+        let synced = r#"{
+            "id": 1,
+            "plainLyrics": "First line",
+            "syncedLyrics": "[00:17.12] First line"
+        }"#;
+        assert_eq!(to_lyric(synced).unwrap(), "[00:17.12] First line");
+
+        let plain = r#"{"id": 1, "plainLyrics": "First line", "syncedLyrics": null}"#;
+        assert_eq!(to_lyric(plain).unwrap(), "First line");
+
+        let instrumental = r#"{"id": 1, "plainLyrics": null, "syncedLyrics": null}"#;
+        assert_eq!(to_lyric(instrumental).unwrap(), "");
+
+        assert!(to_lyric(r#"{"code": 404, "name": "TrackNotFound"}"#).is_err());
+    }
+}
@@ -1,12 +1,13 @@
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use anyhow::{Result, anyhow, bail};
+use anyhow::anyhow;
 use lofty::TextEncoding;
 use lofty::config::WriteOptions;
 use lofty::id3::v2::{Frame, Id3v2Tag, UnsynchronizedTextFrame};
 use lofty::picture::Picture;
 use lofty::prelude::{Accessor, TagExt};
+use serde::{Deserialize, Serialize};
 use service::SongTagService;
 use ytd_rs::{Arg, YoutubeDL};
 
@@ -15,7 +16,9 @@ use crate::utils::get_parent_folder;
 
 mod kugou;
 pub mod lrc;
+mod lrclib;
 mod migu;
+mod musicbrainz;
 mod netease_v2;
 mod service;
 
@@ -45,11 +48,66 @@ pub enum UrlTypes {
     FreeDownloadable(String),
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// A service to search tracks and their lyrics on.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ServiceProvider {
     Netease,
     Kugou,
     Migu,
+    /// Only provides lyrics, no pictures or downloads
+    Lrclib,
+    /// Only provides tags and pictures, no lyrics or downloads
+    ///
+    /// Can also identify files by their acoustic fingerprint, see [`IdentifyFile`].
+    MusicBrainz,
+}
+
+impl ServiceProvider {
+    /// All providers, in the default order of priority
+    pub const ALL: [Self; 5] = [
+        Self::Netease,
+        Self::Migu,
+        Self::Kugou,
+        Self::Lrclib,
+        Self::MusicBrainz,
+    ];
+
+    /// Search this provider for recordings matching `keywords`.
+    pub async fn search_recording(
+        self,
+        keywords: &str,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<SongTag>, SongTagError> {
+        match self {
+            Self::Netease => {
+                let neteasev2_api = netease_v2::Api::new();
+                neteasev2_api
+                    .search_recording(keywords, offset, limit)
+                    .await
+            }
+            Self::Kugou => {
+                let kugou_api = kugou::Api::new();
+                kugou_api.search_recording(keywords, offset, limit).await
+            }
+            Self::Migu => {
+                let migu_api = migu::Api::new();
+                migu_api.search_recording(keywords, offset, limit).await
+            }
+            Self::Lrclib => {
+                let lrclib_api = lrclib::Api::new();
+                lrclib_api.search_recording(keywords, offset, limit).await
+            }
+            Self::MusicBrainz => {
+                let musicbrainz_api = musicbrainz::Api::new();
+                musicbrainz_api
+                    .search_recording(keywords, offset, limit)
+                    .await
+            }
+        }
+        .map_err(|v| SongTagError::Service(self, anyhow!(v)))
+    }
 }
 
 impl std::fmt::Display for ServiceProvider {
@@ -58,52 +116,99 @@ impl std::fmt::Display for ServiceProvider {
             Self::Netease => "Netease",
             Self::Kugou => "Kugou",
             Self::Migu => "Migu",
+            Self::Lrclib => "lrclib",
+            Self::MusicBrainz => "MusicBrainz",
         };
         write!(f, "{service_provider}")
     }
 }
 
+/// Errors that can happen when fetching data for a [`SongTag`] or downloading it.
+#[derive(Debug, thiserror::Error)]
+#[allow(clippy::module_name_repetitions)]
+pub enum SongTagError {
+    /// The song is protected by DRM or a fee
+    #[error("The item is protected by copyright, please select another one.")]
+    Protected,
+    /// The service did not provide a url to download from
+    #[error("failed to fetch url, please, try another item.")]
+    NoUrl,
+    /// The request to the service failed, like a broken connection or a unexpected response
+    #[error("Service \"{0}\" request failed")]
+    Service(ServiceProvider, #[source] anyhow::Error),
+    /// Removing the previous output file failed
+    #[error("Could not remove old file \"{}\"", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    /// Preparing the download via youtube-dl failed
+    #[error("Could not prepare download")]
+    YoutubeDl(#[from] ytd_rs::error::YoutubeDLError),
+}
+
 /// All events that can happen in [`search`].
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SongtagSearchResult {
     Finish(Vec<SongTag>),
 }
 
-// Search function of 3 servers. Run in parallel to get results faster.
-pub async fn search(search_str: &str, tx_done: impl Fn(SongtagSearchResult) + Send + 'static) {
-    let mut results: Vec<SongTag> = Vec::new();
-
-    let handle_netease = async {
-        let neteasev2_api = netease_v2::Api::new();
-        neteasev2_api.search_recording(search_str, 0, 30).await
-    };
-
-    let handle_migu = async {
-        let migu_api = migu::Api::new();
-        migu_api.search_recording(search_str, 0, 30).await
-    };
+/// A file to identify by its acoustic fingerprint in [`search`].
+#[derive(Debug, Clone, Copy)]
+pub struct IdentifyFile<'a> {
+    pub path: &'a Path,
+    /// The [AcoustID](https://acoustid.org/new-application) application API key to look up the fingerprint with
+    pub acoustid_key: &'a str,
+}
 
-    let handle_kugou = async {
-        let kugou_api = kugou::Api::new();
-        kugou_api.search_recording(search_str, 0, 30).await
+/// Search all given `providers`. Run in parallel to get results faster.
+///
+/// If `identify` is given and [`ServiceProvider::MusicBrainz`] is one of the `providers`, the file is identified by its
+/// acoustic fingerprint too.
+///
+/// The results are in the order of `providers`, with the identified results first as they are the most accurate.
+pub async fn search(
+    search_str: &str,
+    providers: &[ServiceProvider],
+    identify: Option<IdentifyFile<'_>>,
+    tx_done: impl Fn(SongtagSearchResult) + Send + 'static,
+) {
+    let identified = async {
+        let identify = identify.filter(|_| providers.contains(&ServiceProvider::MusicBrainz))?;
+        let musicbrainz_api = musicbrainz::Api::new();
+        match musicbrainz_api
+            .identify(identify.path, identify.acoustid_key)
+            .await
+        {
+            Ok(vec) => Some(vec),
+            Err(err) => {
+                error!(
+                    "{} fingerprint Error: {err:#}",
+                    ServiceProvider::MusicBrainz
+                );
+                None
+            }
+        }
     };
 
-    let (netease_res, migu_res, kugou_res) =
-        futures_util::join!(handle_netease, handle_migu, handle_kugou);
-
-    match netease_res {
-        Ok(vec) => results.extend(vec),
-        Err(err) => error!("Netease Error: {err:#}"),
-    }
-
-    match migu_res {
-        Ok(vec) => results.extend(vec),
-        Err(err) => error!("Migu Error: {err:#}"),
-    }
-
-    match kugou_res {
-        Ok(vec) => results.extend(vec),
-        Err(err) => error!("Kogou Error: {err:#}"),
+    let (identified, responses) = futures_util::future::join(
+        identified,
+        futures_util::future::join_all(
+            providers
+                .iter()
+                .map(|provider| provider.search_recording(search_str, 0, 30)),
+        ),
+    )
+    .await;
+
+    let mut results: Vec<SongTag> = identified.unwrap_or_default();
+
+    for (provider, res) in providers.iter().zip(responses) {
+        match res {
+            Ok(vec) => results.extend(vec),
+            Err(err) => error!("{provider} Error: {err:#}"),
+        }
     }
 
     tx_done(SongtagSearchResult::Finish(results));
@@ -174,49 +279,135 @@ impl SongTag {
         &self.song_id
     }
 
-    // get lyric by lyric_id
-    pub async fn fetch_lyric(&self) -> Result<Option<String>> {
+    /// Get whether `other` is likely the same song as this one, regardless of provider.
+    ///
+    /// Titles have to match, artists only if both are known.
+    fn is_same_song(&self, other: &Self) -> bool {
+        let eq = |a: &str, b: &str| a.trim().eq_ignore_ascii_case(b.trim());
+
+        let same_title = self
+            .title()
+            .zip(other.title())
+            .is_some_and(|(a, b)| eq(a, b));
+        let same_artist = self
+            .artist()
+            .zip(other.artist())
+            .is_none_or(|(a, b)| eq(a, b));
+
+        same_title && same_artist
+    }
+
+    /// Get lyric by `lyric_id`
+    ///
+    /// Returns `None` if the provider has no lyrics for this song.
+    pub async fn fetch_lyric(&self) -> Result<Option<String>, SongTagError> {
         let lyric_string = match self.service_provider {
             ServiceProvider::Kugou => {
                 let kugou_api = kugou::Api::new();
-                kugou_api.get_lyrics(self).await.map_err(|v| anyhow!(v))?
+                kugou_api.get_lyrics(self).await
             }
             ServiceProvider::Netease => {
                 let neteasev2_api = netease_v2::Api::new();
-                neteasev2_api
-                    .get_lyrics(self)
-                    .await
-                    .map_err(|v| anyhow!(v))?
+                neteasev2_api.get_lyrics(self).await
             }
             ServiceProvider::Migu => {
                 let migu_api = migu::Api::new();
-                migu_api.get_lyrics(self).await.map_err(|v| anyhow!(v))?
+                migu_api.get_lyrics(self).await
             }
-        };
+            ServiceProvider::Lrclib => {
+                let lrclib_api = lrclib::Api::new();
+                lrclib_api.get_lyrics(self).await
+            }
+            ServiceProvider::MusicBrainz => {
+                let musicbrainz_api = musicbrainz::Api::new();
+                musicbrainz_api.get_lyrics(self).await
+            }
+        }
+        .map_err(|v| SongTagError::Service(self.service_provider, anyhow!(v)))?;
+
+        if lyric_string.trim().is_empty() {
+            return Ok(None);
+        }
 
         Ok(Some(lyric_string))
     }
 
+    /// Fetch the lyric of this song, or if its provider has none, search the other `providers` in order for the same song
+    /// and use the first lyric found.
+    pub async fn fetch_lyric_with_fallback(
+        &self,
+        providers: &[ServiceProvider],
+    ) -> Result<Option<String>, SongTagError> {
+        let first_err = match self.fetch_lyric().await {
+            Ok(Some(lyric)) => return Ok(Some(lyric)),
+            Ok(None) => None,
+            Err(err) => {
+                warn!(
+                    "Fetching lyric from {} failed: {err:#}",
+                    self.service_provider
+                );
+                Some(err)
+            }
+        };
+
+        let Some(title) = self.title() else {
+            return first_err.map_or(Ok(None), Err);
+        };
+        let keywords = match self.artist() {
+            Some(artist) => format!("{artist} {title}"),
+            None => title.to_string(),
+        };
+
+        for provider in providers.iter().filter(|v| **v != self.service_provider) {
+            let candidates = match provider.search_recording(&keywords, 0, 5).await {
+                Ok(v) => v,
+                Err(err) => {
+                    warn!("Searching fallback lyric failed: {err:#}");
+                    continue;
+                }
+            };
+
+            for candidate in candidates.iter().filter(|v| self.is_same_song(v)) {
+                match candidate.fetch_lyric().await {
+                    Ok(Some(lyric)) => {
+                        info!("Using lyric from {provider} as fallback");
+                        return Ok(Some(lyric));
+                    }
+                    Ok(None) => (),
+                    Err(err) => warn!("Fetching fallback lyric failed: {err:#}"),
+                }
+            }
+        }
+
+        first_err.map_or(Ok(None), Err)
+    }
+
     /// Fetch a picture for the current song
     /// For kugou & netease `pic_id()` or for migu `song_id` is used
-    pub async fn fetch_photo(&self) -> Result<Picture> {
+    pub async fn fetch_photo(&self) -> Result<Picture, SongTagError> {
         match self.service_provider {
             ServiceProvider::Kugou => {
                 let kugou_api = kugou::Api::new();
-                Ok(kugou_api.get_picture(self).await.map_err(|v| anyhow!(v))?)
+                kugou_api.get_picture(self).await
             }
             ServiceProvider::Netease => {
                 let neteasev2_api = netease_v2::Api::new();
-                Ok(neteasev2_api
-                    .get_picture(self)
-                    .await
-                    .map_err(|v| anyhow!(v))?)
+                neteasev2_api.get_picture(self).await
             }
             ServiceProvider::Migu => {
                 let migu_api = migu::Api::new();
-                Ok(migu_api.get_picture(self).await.map_err(|v| anyhow!(v))?)
+                migu_api.get_picture(self).await
+            }
+            ServiceProvider::Lrclib => {
+                let lrclib_api = lrclib::Api::new();
+                lrclib_api.get_picture(self).await
+            }
+            ServiceProvider::MusicBrainz => {
+                let musicbrainz_api = musicbrainz::Api::new();
+                musicbrainz_api.get_picture(self).await
             }
         }
+        .map_err(|v| SongTagError::Service(self.service_provider, anyhow!(v)))
     }
 
     /// Try to download the currently selected item in the tag editor list.
@@ -226,9 +417,9 @@ impl SongTag {
         &self,
         file: &Path,
         tx: impl Fn(TrackDLMsg) + Send + 'static,
-    ) -> Result<()> {
+    ) -> Result<(), SongTagError> {
         if self.url().is_some_and(|v| *v == UrlTypes::Protected) {
-            bail!("The item is protected by copyright, please select another one.");
+            return Err(SongTagError::Protected);
         }
 
         let artist = self
@@ -261,7 +452,10 @@ impl SongTag {
         let out_path = p_parent.join(format!("{artist}-{title}.mp3"));
         match std::fs::remove_file(&out_path) {
             Err(err) if err.kind() == std::io::ErrorKind::NotFound => (),
-            v => v?,
+            v => v.map_err(|source| SongTagError::Io {
+                path: out_path.clone(),
+                source,
+            })?,
         }
 
         let mut url = if let Some(UrlTypes::FreeDownloadable(url)) = &self.url {
@@ -276,20 +470,20 @@ impl SongTag {
                 url = neteasev2_api
                     .download_recording(self)
                     .await
-                    .map_err(|v| anyhow!(v))?;
+                    .map_err(|v| SongTagError::Service(self.service_provider, anyhow!(v)))?;
             }
-            ServiceProvider::Migu => {}
+            ServiceProvider::Migu | ServiceProvider::Lrclib | ServiceProvider::MusicBrainz => {}
             ServiceProvider::Kugou => {
                 let kugou_api = kugou::Api::new();
                 url = kugou_api
                     .download_recording(self)
                     .await
-                    .map_err(|v| anyhow!(v))?;
+                    .map_err(|v| SongTagError::Service(self.service_provider, anyhow!(v)))?;
             }
         }
 
         if url.is_empty() {
-            bail!("failed to fetch url, please, try another item.");
+            return Err(SongTagError::NoUrl);
         }
 
         let filename = format!("{artist}-{title}.%(ext)s");
@@ -367,3 +561,45 @@ impl SongTag {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ServiceProvider, SongTag};
+
+    fn song(service_provider: ServiceProvider, artist: Option<&str>, title: &str) -> SongTag {
+        SongTag {
+            service_provider,
+            song_id: "1".to_string(),
+            artist: artist.map(ToString::to_string),
+            title: Some(title.to_string()),
+            album: None,
+            lang_ext: None,
+            lyric_id: None,
+            url: None,
+            pic_id: None,
+            album_id: None,
+        }
+    }
+
+    #[test]
+    fn should_match_same_song() {
+        let original = song(ServiceProvider::Netease, Some("Some Artist"), "Track A");
+
+        assert!(original.is_same_song(&song(
+            ServiceProvider::Lrclib,
+            Some("some artist"),
+            " Track A"
+        )));
+        assert!(original.is_same_song(&song(ServiceProvider::Lrclib, None, "track a")));
+        assert!(!original.is_same_song(&song(
+            ServiceProvider::Lrclib,
+            Some("Other Artist"),
+            "Track A"
+        )));
+        assert!(!original.is_same_song(&song(
+            ServiceProvider::Lrclib,
+            Some("Some Artist"),
+            "Track B"
+        )));
+    }
+}
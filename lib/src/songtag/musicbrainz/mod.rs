@@ -0,0 +1,180 @@
+//! Tags and covers from [MusicBrainz](https://musicbrainz.org), which provides no lyrics or downloads.
+//!
+//! Files can also be identified by their acoustic fingerprint, calculated by `fpcalc` from
+//! [Chromaprint](https://acoustid.org/chromaprint) and looked up on [AcoustID](https://acoustid.org).
+
+mod model;
+
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{Context, anyhow, bail};
+use bytes::Buf;
+use lofty::picture::Picture;
+use model::{to_fingerprint, to_identified_songs, to_song_info};
+use reqwest::{Client, ClientBuilder};
+
+use super::{
+    ServiceProvider, SongTag,
+    service::{SongTagService, SongTagServiceError, SongTagServiceErrorWhere},
+};
+
+const URL_SEARCH_MUSICBRAINZ: &str = "https://musicbrainz.org/ws/2/recording";
+const URL_LOOKUP_ACOUSTID: &str = "https://api.acoustid.org/v2/lookup";
+const URL_COVER_ART_ARCHIVE: &str = "https://coverartarchive.org/release-group";
+/// MusicBrainz asks clients to identify themselves
+const USER_AGENT: &str = concat!(
+    "termusic ",
+    env!("CARGO_PKG_VERSION"),
+    " (https://github.com/tramhao/termusic)"
+);
+
+pub struct Api {
+    client: Client,
+}
+
+impl Api {
+    pub fn new() -> Self {
+        let client = ClientBuilder::new()
+            .timeout(Duration::from_secs(10))
+            .user_agent(USER_AGENT)
+            .build()
+            .expect("failed to build reqwest client.");
+
+        Self { client }
+    }
+
+    /// Identify the file at `path` by its acoustic fingerprint, looked up with the AcoustID application key `acoustid_key`.
+    ///
+    /// Requires `fpcalc` from Chromaprint to be installed.
+    pub async fn identify(&self, path: &Path, acoustid_key: &str) -> anyhow::Result<Vec<SongTag>> {
+        let output = tokio::process::Command::new("fpcalc")
+            .arg("-json")
+            .arg(path)
+            .output()
+            .await
+            .context("run fpcalc, is chromaprint installed?")?;
+        if !output.status.success() {
+            bail!(
+                "fpcalc failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        let fingerprint = to_fingerprint(&String::from_utf8_lossy(&output.stdout))
+            .context("parse fpcalc output")?;
+
+        // AcoustID only accepts whole seconds
+        let duration = format!("{:.0}", fingerprint.duration);
+        // fingerprints are too long for a query
+        let result = self
+            .client
+            .post(URL_LOOKUP_ACOUSTID)
+            .form(&[
+                ("client", acoustid_key),
+                ("meta", "recordings releasegroups"),
+                ("duration", duration.as_str()),
+                ("fingerprint", fingerprint.fingerprint.as_str()),
+            ])
+            .send()
+            .await?
+            // errors like a invalid key are described in the body
+            .text()
+            .await?;
+
+        to_identified_songs(&result).context("Parse lookup result into SongTag Array")
+    }
+}
+
+impl SongTagService for Api {
+    type Error = anyhow::Error;
+
+    fn display_name() -> &'static str
+    where
+        Self: Sized,
+    {
+        "MusicBrainz"
+    }
+
+    async fn search_recording(
+        &self,
+        keywords: &str,
+        offset: u32,
+        limit: u32,
+    ) -> std::result::Result<Vec<SongTag>, SongTagServiceError<Self::Error>> {
+        let offset = offset.to_string();
+        let limit = limit.to_string();
+
+        let result = self
+            .client
+            .get(URL_SEARCH_MUSICBRAINZ)
+            .query(&[
+                ("query", keywords),
+                ("fmt", "json"),
+                ("offset", offset.as_str()),
+                ("limit", limit.as_str()),
+            ])
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(anyhow::Error::from)?
+            .text()
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        to_song_info(&result).map_err(|err| {
+            SongTagServiceError::Other(anyhow!(err).context("Parse result into SongTag Array"))
+        })
+    }
+
+    async fn get_lyrics(
+        &self,
+        _song: &SongTag,
+    ) -> std::result::Result<String, SongTagServiceError<Self::Error>> {
+        Err(SongTagServiceError::NotSupported(
+            SongTagServiceErrorWhere::GetLyrics,
+            Self::display_name(),
+        ))
+    }
+
+    async fn get_picture(
+        &self,
+        song: &SongTag,
+    ) -> std::result::Result<Picture, SongTagServiceError<Self::Error>> {
+        if song.service_provider() != ServiceProvider::MusicBrainz {
+            return Err(SongTagServiceError::IncorrectService(
+                song.service_provider().to_string(),
+                Self::display_name(),
+            ));
+        }
+
+        let Some(pic_id) = song.pic_id.as_ref() else {
+            return Err(SongTagServiceError::Other(anyhow!(
+                "Provided songtag does not have a pic_id!"
+            )));
+        };
+
+        // covers are stored per release group on the Cover Art Archive
+        let result = self
+            .client
+            .get(format!("{URL_COVER_ART_ARCHIVE}/{pic_id}/front-500"))
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(anyhow::Error::from)?;
+
+        let mut reader = result.bytes().await.map_err(anyhow::Error::from)?.reader();
+        let picture = Picture::from_reader(&mut reader).map_err(anyhow::Error::from)?;
+
+        Ok(picture)
+    }
+
+    async fn download_recording(
+        &self,
+        _song: &SongTag,
+    ) -> std::result::Result<String, SongTagServiceError<Self::Error>> {
+        Err(SongTagServiceError::NotSupported(
+            SongTagServiceErrorWhere::DownloadRecording,
+            Self::display_name(),
+        ))
+    }
+}
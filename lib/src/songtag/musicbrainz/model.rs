@@ -0,0 +1,303 @@
+use serde_json::{Value, from_str};
+
+use super::super::{ServiceProvider, SongTag};
+
+#[derive(Debug, thiserror::Error)]
+pub enum MusicBrainzParseError {
+    #[error("Expected property \"{0}\" to exist")]
+    MissingProperty(&'static str),
+
+    #[error("AcoustID lookup failed: {0}")]
+    AcoustId(String),
+
+    #[error(transparent)]
+    ParseError(#[from] serde_json::Error),
+}
+
+type Result<T> = std::result::Result<T, MusicBrainzParseError>;
+
+/// The acoustic fingerprint of a file, as calculated by `fpcalc`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fingerprint {
+    /// Duration of the file in seconds
+    pub duration: f64,
+    pub fingerprint: String,
+}
+
+/// Try to get the fingerprint from the output of `fpcalc -json`
+pub fn to_fingerprint(json: &str) -> Result<Fingerprint> {
+    let value = from_str::<Value>(json)?;
+
+    let duration = value
+        .get("duration")
+        .and_then(Value::as_f64)
+        .ok_or(MusicBrainzParseError::MissingProperty("duration"))?;
+    let fingerprint = value
+        .get("fingerprint")
+        .and_then(Value::as_str)
+        .ok_or(MusicBrainzParseError::MissingProperty("fingerprint"))?
+        .to_owned();
+
+    Ok(Fingerprint {
+        duration,
+        fingerprint,
+    })
+}
+
+/// Try to get all tracks from the MusicBrainz recording search result
+pub fn to_song_info(json: &str) -> Result<Vec<SongTag>> {
+    let value = from_str::<Value>(json)?;
+    let recordings = value
+        .get("recordings")
+        .and_then(Value::as_array)
+        .ok_or(MusicBrainzParseError::MissingProperty("recordings"))?;
+
+    Ok(recordings.iter().filter_map(parse_song_info).collect())
+}
+
+/// Try to parse a single [`SongTag`] from a given MusicBrainz recording
+fn parse_song_info(v: &Value) -> Option<SongTag> {
+    let release = v
+        .get("releases")
+        .and_then(Value::as_array)
+        .and_then(|v| v.first());
+
+    Some(SongTag {
+        album: release
+            .and_then(|v| v.get("title"))
+            .and_then(Value::as_str)
+            .map(ToOwned::to_owned),
+        pic_id: release
+            .and_then(|v| v.pointer("/release-group/id"))
+            .and_then(Value::as_str)
+            .map(ToOwned::to_owned),
+        album_id: release
+            .and_then(|v| v.get("id"))
+            .and_then(Value::as_str)
+            .map(ToOwned::to_owned),
+        ..recording_song_tag(v, "artist-credit")?
+    })
+}
+
+/// Try to get all recordings from the AcoustID lookup result, best matches first
+///
+/// Recordings without a title are left out, as AcoustID may know a recording without knowing its metadata.
+pub fn to_identified_songs(json: &str) -> Result<Vec<SongTag>> {
+    let value = from_str::<Value>(json)?;
+
+    if value.get("status").and_then(Value::as_str) != Some("ok") {
+        let message = value
+            .pointer("/error/message")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown error");
+        return Err(MusicBrainzParseError::AcoustId(message.to_owned()));
+    }
+
+    let results = value
+        .get("results")
+        .and_then(Value::as_array)
+        .ok_or(MusicBrainzParseError::MissingProperty("results"))?;
+
+    let mut songs: Vec<SongTag> = Vec::new();
+    // the results are already sorted by their score
+    let recordings = results
+        .iter()
+        .filter_map(|v| v.get("recordings").and_then(Value::as_array))
+        .flatten();
+    for song in recordings.filter_map(parse_identified_song) {
+        // the same recording may be found for multiple fingerprints
+        if !songs.iter().any(|v| v.song_id == song.song_id) {
+            songs.push(song);
+        }
+    }
+
+    Ok(songs)
+}
+
+/// Try to parse a single [`SongTag`] from a given AcoustID recording
+fn parse_identified_song(v: &Value) -> Option<SongTag> {
+    let release_group = v
+        .get("releasegroups")
+        .and_then(Value::as_array)
+        .and_then(|v| v.first());
+
+    Some(SongTag {
+        album: release_group
+            .and_then(|v| v.get("title"))
+            .and_then(Value::as_str)
+            .map(ToOwned::to_owned),
+        pic_id: release_group
+            .and_then(|v| v.get("id"))
+            .and_then(Value::as_str)
+            .map(ToOwned::to_owned),
+        ..recording_song_tag(v, "artists")?
+    })
+}
+
+/// Get the [`SongTag`] of a recording with a title, with the artists in field `artists`
+fn recording_song_tag(v: &Value, artists: &str) -> Option<SongTag> {
+    let song_id = v.get("id").and_then(Value::as_str)?.to_owned();
+    let title = v.get("title").and_then(Value::as_str)?.to_owned();
+
+    Some(SongTag {
+        service_provider: ServiceProvider::MusicBrainz,
+        song_id,
+        artist: v.get(artists).and_then(artist_credit),
+        title: Some(title),
+        album: None,
+        lang_ext: None,
+        lyric_id: None,
+        // musicbrainz only provides metadata
+        url: None,
+        pic_id: None,
+        album_id: None,
+    })
+}
+
+/// Join the names of a artist credit, like `Artist A feat. Artist B`
+fn artist_credit(credits: &Value) -> Option<String> {
+    let credits = credits.as_array()?;
+
+    let mut artist = String::new();
+    for (idx, credit) in credits.iter().enumerate() {
+        let Some(name) = credit.get("name").and_then(Value::as_str) else {
+            continue;
+        };
+        artist.push_str(name);
+
+        match credit.get("joinphrase").and_then(Value::as_str) {
+            Some(joinphrase) => artist.push_str(joinphrase),
+            None if idx + 1 < credits.len() => artist.push_str(", "),
+            None => (),
+        }
+    }
+
+    let artist = artist.trim();
+    (!artist.is_empty()).then(|| artist.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn song(song_id: &str, artist: &str, album: &str, pic_id: &str) -> SongTag {
+        SongTag {
+            service_provider: ServiceProvider::MusicBrainz,
+            song_id: song_id.to_string(),
+            artist: Some(artist.to_string()),
+            title: Some("Track A".to_string()),
+            album: Some(album.to_string()),
+            lang_ext: None,
+            lyric_id: None,
+            url: None,
+            pic_id: Some(pic_id.to_string()),
+            album_id: None,
+        }
+    }
+
+    #[test]
+    fn should_parse_songinfo() {
+        let sample_data = r#"{
+            "created": "2024-01-01T00:00:00.000Z",
+            "count": 2,
+            "offset": 0,
+            "recordings": [
+                {
+                    "id": "rec-1",
+                    "score": 100,
+                    "title": "Track A",
+                    "length": 233000,
+                    "artist-credit": [
+                        {"name": "Some Artist", "joinphrase": " feat. ", "artist": {"id": "art-1", "name": "Some Artist"}},
+                        {"name": "Other Artist", "artist": {"id": "art-2", "name": "Other Artist"}}
+                    ],
+                    "releases": [
+                        {"id": "rel-1", "title": "Some Album", "release-group": {"id": "rg-1", "primary-type": "Album"}},
+                        {"id": "rel-2", "title": "Some Compilation", "release-group": {"id": "rg-2"}}
+                    ]
+                },
+                {
+                    "id": "rec-2",
+                    "score": 90
+                }
+            ]
+        }"#;
+
+        let res = to_song_info(sample_data).unwrap();
+
+        assert_eq!(
+            res,
+            vec![SongTag {
+                album_id: Some("rel-1".to_string()),
+                ..song(
+                    "rec-1",
+                    "Some Artist feat. Other Artist",
+                    "Some Album",
+                    "rg-1"
+                )
+            }]
+        );
+    }
+
+    #[test]
+    fn should_parse_identified_songs() {
+        let sample_data = r#"{
+            "status": "ok",
+            "results": [
+                {
+                    "id": "acoustid-1",
+                    "score": 0.97,
+                    "recordings": [
+                        {
+                            "id": "rec-1",
+                            "title": "Track A",
+                            "duration": 233,
+                            "artists": [{"id": "art-1", "name": "Some Artist"}],
+                            "releasegroups": [{"id": "rg-1", "title": "Some Album", "type": "Album"}]
+                        },
+                        {"id": "rec-2"}
+                    ]
+                },
+                {
+                    "id": "acoustid-2",
+                    "score": 0.5,
+                    "recordings": [
+                        {
+                            "id": "rec-1",
+                            "title": "Track A",
+                            "artists": [{"id": "art-1", "name": "Some Artist"}],
+                            "releasegroups": [{"id": "rg-1", "title": "Some Album", "type": "Album"}]
+                        }
+                    ]
+                }
+            ]
+        }"#;
+
+        assert_eq!(
+            to_identified_songs(sample_data).unwrap(),
+            vec![song("rec-1", "Some Artist", "Some Album", "rg-1")]
+        );
+
+        let invalid_key =
+            r#"{"status": "error", "error": {"code": 4, "message": "invalid API key"}}"#;
+        assert_eq!(
+            to_identified_songs(invalid_key).unwrap_err().to_string(),
+            "AcoustID lookup failed: invalid API key"
+        );
+    }
+
+    #[test]
+    fn should_parse_fingerprint() {
+        let sample_data = r#"{"duration": 233.47, "fingerprint": "AQADtEmUSUmSJEqS"}"#;
+
+        assert_eq!(
+            to_fingerprint(sample_data).unwrap(),
+            Fingerprint {
+                duration: 233.47,
+                fingerprint: "AQADtEmUSUmSJEqS".to_string(),
+            }
+        );
+        assert!(to_fingerprint(r#"{"duration": 233.47}"#).is_err());
+    }
+}
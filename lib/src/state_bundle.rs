@@ -0,0 +1,292 @@
+//! Export and import of the whole application state (config, themes, databases and playlist) as a
+//! single tar archive, to move termusic to another machine.
+//!
+//! The databases are copied via the SQLite backup API, so exporting and importing is safe while
+//! a server is running.
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use tempfile::TempDir;
+
+use crate::backup::{self, DATABASE_FILES};
+use crate::config::v2::{server, tui};
+
+/// Marker file at the root of every bundle, containing [`BUNDLE_VERSION`].
+const BUNDLE_MARKER: &str = "termusic-bundle";
+
+/// The current bundle layout version.
+const BUNDLE_VERSION: &str = "1";
+
+/// Directory in the bundle for files from the config directory.
+const CONFIG_DIR: &str = "config";
+
+/// Directory in the bundle for files from the data directory.
+const DATA_DIR: &str = "data";
+
+/// Directory of custom themes, in the config directory.
+const THEMES_DIR: &str = "themes";
+
+/// Plain files from the config directory that are included in a bundle.
+const CONFIG_FILES: &[&str] = &[
+    server::config_extra::FILE_NAME,
+    tui::config_extra::FILE_NAME,
+];
+
+/// Plain files (not databases) from the data directory that are included in a bundle.
+const DATA_FILES: &[&str] = &["playlist.log"];
+
+/// Export the state from `config_dir` and `data_dir` into a new archive at `file`.
+///
+/// Returns the paths, relative to the archive root, of everything that was exported.
+pub fn export_bundle(config_dir: &Path, data_dir: &Path, file: &Path) -> Result<Vec<PathBuf>> {
+    let tmp_dir = get_tmp_dir("export")?;
+
+    export_bundle_inner(config_dir, data_dir, file, tmp_dir.path())
+}
+
+fn export_bundle_inner(
+    config_dir: &Path,
+    data_dir: &Path,
+    file: &Path,
+    tmp_dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    let out = File::create_new(file).with_context(|| file.display().to_string())?;
+    let mut builder = tar::Builder::new(out);
+    let mut exported = Vec::new();
+
+    let marker_path = tmp_dir.join(BUNDLE_MARKER);
+    std::fs::write(&marker_path, BUNDLE_VERSION)?;
+    builder.append_path_with_name(&marker_path, BUNDLE_MARKER)?;
+
+    for name in CONFIG_FILES {
+        let path = config_dir.join(name);
+        if !path.is_file() {
+            continue;
+        }
+        let archive_path = Path::new(CONFIG_DIR).join(name);
+        builder
+            .append_path_with_name(&path, &archive_path)
+            .with_context(|| path.display().to_string())?;
+        exported.push(archive_path);
+    }
+
+    let themes_path = config_dir.join(THEMES_DIR);
+    if themes_path.is_dir() {
+        let archive_path = Path::new(CONFIG_DIR).join(THEMES_DIR);
+        builder
+            .append_dir_all(&archive_path, &themes_path)
+            .with_context(|| themes_path.display().to_string())?;
+        exported.push(archive_path);
+    }
+
+    for name in DATABASE_FILES {
+        let path = data_dir.join(name);
+        if !path.is_file() {
+            continue;
+        }
+        // copy first to get a consistent snapshot of a database that may be in use
+        let snapshot_path = tmp_dir.join(name);
+        backup::copy_database(&path, &snapshot_path)?;
+        let archive_path = Path::new(DATA_DIR).join(name);
+        builder.append_path_with_name(&snapshot_path, &archive_path)?;
+        exported.push(archive_path);
+    }
+
+    for name in DATA_FILES {
+        let path = data_dir.join(name);
+        if !path.is_file() {
+            continue;
+        }
+        let archive_path = Path::new(DATA_DIR).join(name);
+        builder
+            .append_path_with_name(&path, &archive_path)
+            .with_context(|| path.display().to_string())?;
+        exported.push(archive_path);
+    }
+
+    builder.into_inner()?.sync_all()?;
+
+    info!("Exported {} entries to {}", exported.len(), file.display());
+
+    Ok(exported)
+}
+
+/// Import the archive at `file` (created by [`export_bundle`]) into `config_dir` and `data_dir`,
+/// overwriting existing files.
+///
+/// Returns the paths, relative to the archive root, of everything that was imported.
+pub fn import_bundle(config_dir: &Path, data_dir: &Path, file: &Path) -> Result<Vec<PathBuf>> {
+    let tmp_dir = get_tmp_dir("import")?;
+
+    import_bundle_inner(config_dir, data_dir, file, tmp_dir.path())
+}
+
+fn import_bundle_inner(
+    config_dir: &Path,
+    data_dir: &Path,
+    file: &Path,
+    tmp_dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    let input = File::open(file).with_context(|| file.display().to_string())?;
+    tar::Archive::new(input)
+        .unpack(tmp_dir)
+        .with_context(|| format!("unpack {}", file.display()))?;
+
+    match std::fs::read_to_string(tmp_dir.join(BUNDLE_MARKER)) {
+        Ok(version) if version.trim() == BUNDLE_VERSION => (),
+        Ok(version) => bail!("Unsupported bundle version \"{}\"", version.trim()),
+        Err(_) => bail!("\"{}\" is not a termusic bundle", file.display()),
+    }
+
+    let mut imported = Vec::new();
+    let bundle_config = tmp_dir.join(CONFIG_DIR);
+    let bundle_data = tmp_dir.join(DATA_DIR);
+
+    // migrate the databases before importing anything, to not import only some of the state
+    for name in DATABASE_FILES {
+        let path = bundle_data.join(name);
+        if path.is_file() {
+            backup::migrate_database(&path).with_context(|| format!("{name} of the bundle"))?;
+        }
+    }
+
+    std::fs::create_dir_all(config_dir).with_context(|| config_dir.display().to_string())?;
+    std::fs::create_dir_all(data_dir).with_context(|| data_dir.display().to_string())?;
+
+    for name in CONFIG_FILES {
+        if copy_if_exists(&bundle_config.join(name), &config_dir.join(name))? {
+            imported.push(Path::new(CONFIG_DIR).join(name));
+        }
+    }
+
+    let bundle_themes = bundle_config.join(THEMES_DIR);
+    if bundle_themes.is_dir() {
+        let themes_path = config_dir.join(THEMES_DIR);
+        std::fs::create_dir_all(&themes_path).with_context(|| themes_path.display().to_string())?;
+        for entry in std::fs::read_dir(&bundle_themes)? {
+            let entry = entry?;
+            if copy_if_exists(&entry.path(), &themes_path.join(entry.file_name()))? {
+                imported.push(
+                    Path::new(CONFIG_DIR)
+                        .join(THEMES_DIR)
+                        .join(entry.file_name()),
+                );
+            }
+        }
+    }
+
+    for name in DATABASE_FILES {
+        let path = bundle_data.join(name);
+        if !path.is_file() {
+            continue;
+        }
+        // restore instead of copy, as a running server may have the database open
+        backup::restore_database(&data_dir.join(name), &path)?;
+        imported.push(Path::new(DATA_DIR).join(name));
+    }
+
+    for name in DATA_FILES {
+        if copy_if_exists(&bundle_data.join(name), &data_dir.join(name))? {
+            imported.push(Path::new(DATA_DIR).join(name));
+        }
+    }
+
+    info!(
+        "Imported {} entries from {}",
+        imported.len(),
+        file.display()
+    );
+
+    Ok(imported)
+}
+
+/// Copy `from` to `to` if `from` is a file, returning whether it was copied.
+fn copy_if_exists(from: &Path, to: &Path) -> Result<bool> {
+    if !from.is_file() {
+        return Ok(false);
+    }
+    std::fs::copy(from, to).with_context(|| to.display().to_string())?;
+
+    Ok(true)
+}
+
+/// Create a new, empty temporary directory for `purpose`, only accessible by the current user.
+///
+/// The directory and its contents are removed when it is dropped.
+fn get_tmp_dir(purpose: &str) -> Result<TempDir> {
+    tempfile::Builder::new()
+        .prefix(&format!("termusic-bundle-{purpose}-"))
+        .tempdir()
+        .context("create temporary directory")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+    use rusqlite::Connection;
+
+    use super::{export_bundle, import_bundle};
+
+    fn get_value(path: &Path) -> String {
+        Connection::open(path)
+            .unwrap()
+            .query_row("SELECT value FROM test;", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn should_export_and_import() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let root = tmp_dir.path();
+        let (config_dir, data_dir) = (root.join("config"), root.join("data"));
+        std::fs::create_dir_all(config_dir.join("themes")).unwrap();
+        std::fs::create_dir_all(&data_dir).unwrap();
+
+        std::fs::write(config_dir.join("tui.toml"), "tui").unwrap();
+        std::fs::write(config_dir.join("themes/Custom.yml"), "theme").unwrap();
+        std::fs::write(data_dir.join("playlist.log"), "0\n/music/a.mp3").unwrap();
+        Connection::open(data_dir.join("library2.db"))
+            .unwrap()
+            .execute_batch("CREATE TABLE test (value TEXT); INSERT INTO test VALUES ('library');")
+            .unwrap();
+
+        let file = root.join("state.tar");
+        let exported = export_bundle(&config_dir, &data_dir, &file).unwrap();
+        assert_eq!(
+            exported,
+            [
+                Path::new("config/tui.toml"),
+                Path::new("config/themes"),
+                Path::new("data/library2.db"),
+                Path::new("data/playlist.log")
+            ]
+        );
+        // never overwrite a existing bundle
+        assert!(export_bundle(&config_dir, &data_dir, &file).is_err());
+
+        let (new_config_dir, new_data_dir) = (root.join("new_config"), root.join("new_data"));
+        let imported = import_bundle(&new_config_dir, &new_data_dir, &file).unwrap();
+        assert_eq!(imported.len(), 4);
+        assert_eq!(
+            std::fs::read_to_string(new_config_dir.join("tui.toml")).unwrap(),
+            "tui"
+        );
+        assert_eq!(
+            std::fs::read_to_string(new_config_dir.join("themes/Custom.yml")).unwrap(),
+            "theme"
+        );
+        assert_eq!(
+            std::fs::read_to_string(new_data_dir.join("playlist.log")).unwrap(),
+            "0\n/music/a.mp3"
+        );
+        assert_eq!(get_value(&new_data_dir.join("library2.db")), "library");
+
+        assert!(
+            import_bundle(&new_config_dir, &new_data_dir, &config_dir.join("tui.toml")).is_err()
+        );
+    }
+}
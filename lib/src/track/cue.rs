@@ -0,0 +1,469 @@
+//! Cue sheets, which split a single audio file (like a whole album image) into tracks.
+//!
+//! Each track of a cue sheet is addressed by a virtual path: the path of the cue sheet, followed by `#` and the track number,
+//! like `/music/album.cue#3`.
+//! This way they can be stored and played like any other track path.
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{Context, Result, bail};
+
+/// Separator between the cue sheet path and the track number of a virtual track path.
+const TRACK_SEPARATOR: char = '#';
+
+/// Frames per second of the `INDEX` timestamps, which are `mm:ss:ff`.
+const FRAMES_PER_SECOND: u64 = 75;
+
+/// A parsed cue sheet, only containing the fields we use.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CueSheet {
+    /// The album title
+    pub title: Option<String>,
+    /// The album artist
+    pub performer: Option<String>,
+    /// All audio tracks, in the order of the sheet
+    pub tracks: Vec<CueTrack>,
+}
+
+/// A single audio track of a [`CueSheet`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CueTrack {
+    pub number: u8,
+    /// The `FILE` the track is in, as written in the sheet (usually relative to the sheet)
+    pub file: String,
+    pub title: Option<String>,
+    pub performer: Option<String>,
+    /// Position of `INDEX 01` in `file`
+    pub start: Duration,
+}
+
+/// The part of a audio file a cue sheet track plays.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CueSpan {
+    /// The audio file to play
+    pub file: PathBuf,
+    /// Where the track starts in `file`
+    pub start: Duration,
+    /// Where the track ends in `file`, or [`None`] if it plays until the end of the file
+    pub end: Option<Duration>,
+}
+
+impl CueSpan {
+    /// Get the length of the track, using `file_duration` for tracks playing until the end of the file.
+    #[must_use]
+    pub fn length(&self, file_duration: Option<Duration>) -> Option<Duration> {
+        self.end
+            .or(file_duration)
+            .map(|end| end.saturating_sub(self.start))
+    }
+}
+
+impl CueSheet {
+    /// Parse the text of a cue sheet.
+    ///
+    /// # Errors
+    ///
+    /// - if a `TRACK` comes before any `FILE`
+    /// - if a track number or `INDEX` timestamp cannot be parsed
+    /// - if a audio track has no `INDEX 01`
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut parser = Parser::default();
+        for (idx, line) in text.lines().enumerate() {
+            parser
+                .line(line.trim())
+                .with_context(|| format!("Line {}", idx + 1))?;
+        }
+
+        parser.finish()
+    }
+
+    /// Read and parse the cue sheet at `path`.
+    ///
+    /// Cue sheets that are not UTF-8 are read lossy.
+    pub fn read_from_path(path: &Path) -> Result<Self> {
+        let bytes = std::fs::read(path).with_context(|| path.display().to_string())?;
+        let text = String::from_utf8_lossy(&bytes);
+
+        Self::parse(text.trim_start_matches('\u{feff}'))
+            .with_context(|| format!("Parse cue sheet \"{}\"", path.display()))
+    }
+
+    /// Get the track with `number`.
+    #[must_use]
+    pub fn track(&self, number: u8) -> Option<&CueTrack> {
+        self.tracks.iter().find(|v| v.number == number)
+    }
+
+    /// Get the part of its file the track `number` plays, with the file resolved relative to `dir`.
+    ///
+    /// A track ends where the next track in the same file starts.
+    #[must_use]
+    pub fn span(&self, number: u8, dir: &Path) -> Option<CueSpan> {
+        let index = self.tracks.iter().position(|v| v.number == number)?;
+        let track = &self.tracks[index];
+        let end = self
+            .tracks
+            .get(index + 1)
+            .filter(|next| next.file == track.file)
+            .map(|next| next.start);
+
+        Some(CueSpan {
+            file: dir.join(&track.file),
+            start: track.start,
+            end,
+        })
+    }
+}
+
+/// A audio track that is still being parsed.
+#[derive(Debug)]
+struct PartialTrack {
+    number: u8,
+    title: Option<String>,
+    performer: Option<String>,
+    start: Option<Duration>,
+}
+
+/// State while parsing a [`CueSheet`] line by line.
+#[derive(Debug, Default)]
+struct Parser {
+    sheet: CueSheet,
+    /// The last `FILE`
+    file: Option<String>,
+    /// The current audio track, if any
+    track: Option<PartialTrack>,
+    /// Whether the lines belong to a track, which may not be a audio track
+    in_track: bool,
+}
+
+impl Parser {
+    /// Parse a single trimmed line.
+    fn line(&mut self, line: &str) -> Result<()> {
+        let (command, value) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let value = value.trim();
+
+        match command.to_ascii_uppercase().as_str() {
+            "TITLE" => {
+                let value = Some(unquote(value).to_string());
+                match &mut self.track {
+                    Some(track) => track.title = value,
+                    None if !self.in_track => self.sheet.title = value,
+                    // belongs to a non-audio track
+                    None => (),
+                }
+            }
+            "PERFORMER" => {
+                let value = Some(unquote(value).to_string());
+                match &mut self.track {
+                    Some(track) => track.performer = value,
+                    None if !self.in_track => self.sheet.performer = value,
+                    None => (),
+                }
+            }
+            "FILE" => {
+                self.finish_track()?;
+                self.in_track = false;
+                self.file = Some(file_name(value).to_string());
+            }
+            "TRACK" => {
+                if self.file.is_none() {
+                    bail!("\"TRACK\" before any \"FILE\"");
+                }
+                self.finish_track()?;
+                self.in_track = true;
+
+                let mut parts = value.split_whitespace();
+                let number = parts.next().unwrap_or_default();
+                let number: u8 = number
+                    .parse()
+                    .with_context(|| format!("Track number \"{number}\""))?;
+                if parts
+                    .next()
+                    .is_some_and(|kind| kind.eq_ignore_ascii_case("AUDIO"))
+                {
+                    self.track = Some(PartialTrack {
+                        number,
+                        title: None,
+                        performer: None,
+                        start: None,
+                    });
+                }
+            }
+            "INDEX" => {
+                let Some((index, time)) = value.split_once(char::is_whitespace) else {
+                    bail!("\"INDEX\" without a timestamp");
+                };
+                // "00" is the pregap, which is played as part of the previous track
+                if index == "01" {
+                    if let Some(track) = &mut self.track {
+                        track.start = Some(parse_timestamp(time.trim())?);
+                    }
+                }
+            }
+            // "REM", "CATALOG", "FLAGS", "ISRC", etc
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    /// Add the current audio track to the sheet, if there is one.
+    fn finish_track(&mut self) -> Result<()> {
+        let Some(track) = self.track.take() else {
+            return Ok(());
+        };
+        let Some(start) = track.start else {
+            bail!("Track {} has no \"INDEX 01\"", track.number);
+        };
+
+        self.sheet.tracks.push(CueTrack {
+            number: track.number,
+            // tracks are only started after a "FILE"
+            file: self.file.clone().unwrap_or_default(),
+            title: track.title,
+            performer: track.performer,
+            start,
+        });
+
+        Ok(())
+    }
+
+    /// Finish the last track and get the parsed sheet.
+    fn finish(mut self) -> Result<CueSheet> {
+        self.finish_track()?;
+
+        Ok(self.sheet)
+    }
+}
+
+/// Get the value of a `TITLE` or `PERFORMER` line, which may be quoted.
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.split_once('"'))
+        .map_or(value, |(v, _)| v)
+}
+
+/// Get the file name of a `FILE` line, which is followed by the file type.
+fn file_name(value: &str) -> &str {
+    if value.starts_with('"') {
+        return unquote(value);
+    }
+
+    value.rsplit_once(' ').map_or(value, |(v, _)| v.trim_end())
+}
+
+/// Parse a `mm:ss:ff` timestamp, where `ff` are frames of which there are [`FRAMES_PER_SECOND`].
+fn parse_timestamp(time: &str) -> Result<Duration> {
+    let parse = || -> Option<Duration> {
+        let mut parts = time.split(':').map(|v| v.parse::<u64>().ok());
+        let (minutes, seconds, frames) = (parts.next()??, parts.next()??, parts.next()??);
+        if parts.next().is_some() || seconds >= 60 || frames >= FRAMES_PER_SECOND {
+            return None;
+        }
+
+        Some(
+            Duration::from_secs(minutes * 60 + seconds)
+                + Duration::from_nanos(frames * 1_000_000_000 / FRAMES_PER_SECOND),
+        )
+    };
+
+    parse().with_context(|| format!("Invalid timestamp \"{time}\""))
+}
+
+/// Check if `path` is a cue sheet file.
+#[must_use]
+pub fn is_cue_sheet(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("cue"))
+}
+
+/// Check if there is a cue sheet next to the audio file `path` with the same name, like `album.flac` and `album.cue`.
+///
+/// Such files are played through the tracks of the cue sheet instead.
+#[must_use]
+pub fn has_cue_sheet(path: &Path) -> bool {
+    !is_cue_sheet(path) && path.with_extension("cue").is_file()
+}
+
+/// Get the virtual path of the track `number` of the cue sheet at `cue_path`.
+#[must_use]
+pub fn track_path(cue_path: &Path, number: u8) -> PathBuf {
+    let mut path = cue_path.as_os_str().to_owned();
+    path.push(format!("{TRACK_SEPARATOR}{number}"));
+
+    PathBuf::from(path)
+}
+
+/// Split a virtual cue sheet track path into the path of the cue sheet and the track number.
+///
+/// Returns [`None`] if `path` is not a virtual cue sheet track path.
+#[must_use]
+pub fn split_track_path(path: &Path) -> Option<(&Path, u8)> {
+    let (cue_path, number) = path.to_str()?.rsplit_once(TRACK_SEPARATOR)?;
+    let cue_path = Path::new(cue_path);
+    if !is_cue_sheet(cue_path) {
+        return None;
+    }
+
+    Some((cue_path, number.parse().ok()?))
+}
+
+/// Get the file on disk for `path`, which is the cue sheet for virtual cue sheet track paths and `path` itself otherwise.
+#[must_use]
+pub fn file_path(path: &Path) -> &Path {
+    split_track_path(path).map_or(path, |(cue_path, _)| cue_path)
+}
+
+/// Get the virtual paths of all tracks of the cue sheet at `cue_path`.
+pub fn track_paths(cue_path: &Path) -> Result<Vec<PathBuf>> {
+    let sheet = CueSheet::read_from_path(cue_path)?;
+
+    Ok(sheet
+        .tracks
+        .iter()
+        .map(|track| track_path(cue_path, track.number))
+        .collect())
+}
+
+/// Read the [`CueSpan`] of the virtual cue sheet track `path`.
+///
+/// Returns `Ok(None)` if `path` is not a virtual cue sheet track path.
+pub fn read_span(path: &Path) -> Result<Option<CueSpan>> {
+    let Some((cue_path, number)) = split_track_path(path) else {
+        return Ok(None);
+    };
+
+    let sheet = CueSheet::read_from_path(cue_path)?;
+    let dir = cue_path.parent().unwrap_or(Path::new(""));
+    let Some(span) = sheet.span(number, dir) else {
+        bail!("Cue sheet \"{}\" has no track {number}", cue_path.display());
+    };
+
+    Ok(Some(span))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{CueSheet, CueSpan, CueTrack, file_path, split_track_path, track_path};
+
+    const SHEET: &str = r#"REM GENRE Rock
+PERFORMER "Some Artist"
+TITLE "Some Album"
+FILE "Some Album.flac" WAVE
+  TRACK 01 AUDIO
+    TITLE "First"
+    INDEX 01 00:00:00
+  TRACK 02 AUDIO
+    TITLE "Second"
+    PERFORMER "Guest Artist"
+    INDEX 00 03:10:00
+    INDEX 01 03:12:30
+FILE bonus.wav WAVE
+  TRACK 03 AUDIO
+    TITLE Bonus
+    INDEX 01 00:01:00
+"#;
+
+    #[test]
+    fn should_parse_sheet() {
+        let sheet = CueSheet::parse(SHEET).unwrap();
+
+        assert_eq!(sheet.title.as_deref(), Some("Some Album"));
+        assert_eq!(sheet.performer.as_deref(), Some("Some Artist"));
+        assert_eq!(
+            sheet.tracks,
+            [
+                CueTrack {
+                    number: 1,
+                    file: "Some Album.flac".to_string(),
+                    title: Some("First".to_string()),
+                    performer: None,
+                    start: Duration::ZERO,
+                },
+                CueTrack {
+                    number: 2,
+                    file: "Some Album.flac".to_string(),
+                    title: Some("Second".to_string()),
+                    performer: Some("Guest Artist".to_string()),
+                    start: Duration::from_millis(192_400),
+                },
+                CueTrack {
+                    number: 3,
+                    file: "bonus.wav".to_string(),
+                    title: Some("Bonus".to_string()),
+                    performer: None,
+                    start: Duration::from_secs(1),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_get_spans() {
+        let sheet = CueSheet::parse(SHEET).unwrap();
+        let dir = Path::new("/music/album");
+
+        let span = sheet.span(1, dir).unwrap();
+        assert_eq!(
+            span,
+            CueSpan {
+                file: PathBuf::from("/music/album/Some Album.flac"),
+                start: Duration::ZERO,
+                end: Some(Duration::from_millis(192_400)),
+            }
+        );
+        assert_eq!(span.length(None), Some(Duration::from_millis(192_400)));
+
+        // the last track of a file plays until the end of the file
+        let span = sheet.span(2, dir).unwrap();
+        assert_eq!(span.end, None);
+        assert_eq!(span.length(None), None);
+        assert_eq!(
+            span.length(Some(Duration::from_secs(200))),
+            Some(Duration::from_millis(7600))
+        );
+
+        assert_eq!(sheet.span(4, dir), None);
+    }
+
+    #[test]
+    fn should_error_on_invalid_sheets() {
+        assert!(CueSheet::parse("TRACK 01 AUDIO\nINDEX 01 00:00:00").is_err());
+        assert!(CueSheet::parse("FILE a.flac WAVE\nTRACK 01 AUDIO\nTITLE a").is_err());
+        assert!(CueSheet::parse("FILE a.flac WAVE\nTRACK 01 AUDIO\nINDEX 01 00:61:00").is_err());
+        // non-audio tracks are skipped
+        assert_eq!(
+            CueSheet::parse("FILE a.bin BINARY\nTRACK 01 MODE1/2352\nTITLE data")
+                .unwrap()
+                .tracks,
+            []
+        );
+    }
+
+    #[test]
+    fn should_split_track_paths() {
+        let path = track_path(Path::new("/music/album #1.cue"), 3);
+        assert_eq!(path, PathBuf::from("/music/album #1.cue#3"));
+        assert_eq!(
+            split_track_path(&path),
+            Some((Path::new("/music/album #1.cue"), 3))
+        );
+        assert_eq!(file_path(&path), Path::new("/music/album #1.cue"));
+
+        assert_eq!(split_track_path(Path::new("/music/track #3.flac")), None);
+        assert_eq!(split_track_path(Path::new("/music/album.cue")), None);
+        assert_eq!(
+            file_path(Path::new("/music/album.flac")),
+            Path::new("/music/album.flac")
+        );
+    }
+}
@@ -0,0 +1,340 @@
+//! DSD (SACD) audio files, in the Sony `DSF` (`.dsf`) and Philips `DSDIFF` (`.dff`) containers.
+//!
+//! Only the headers are parsed here, for the duration and the position of the audio data and tags.
+//! Playing them requires a conversion to PCM, which is up to the backend.
+
+use std::{
+    ffi::OsStr,
+    io::{Read, Seek, SeekFrom},
+    path::Path,
+    time::Duration,
+};
+
+use anyhow::{Context, Result, bail};
+
+/// The parsed header of a DSD file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DsdHeader {
+    /// Samples (bits) per second of each channel, like `2822400` for DSD64
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Samples (bits) of each channel, without the padding of the last block
+    pub sample_count: u64,
+    /// Position of the audio data in the file
+    pub data_offset: u64,
+    /// Length of the audio data in bytes, including the padding of the last block
+    pub data_len: u64,
+    /// Bytes of a channel stored together before the next channel follows, `1` for byte-interleaved data
+    pub block_size: u32,
+    /// Whether the oldest sample of each byte is the least significant bit
+    pub lsb_first: bool,
+    /// Position of a ID3v2 tag in the file, if there is one
+    pub id3_offset: Option<u64>,
+}
+
+impl DsdHeader {
+    /// Get the duration of the audio data.
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        let nanos =
+            u128::from(self.sample_count) * 1_000_000_000 / u128::from(self.sample_rate.max(1));
+        Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+    }
+}
+
+/// Check if `path` has the extension of a DSD file.
+#[must_use]
+pub fn is_dsd(path: &Path) -> bool {
+    path.extension()
+        .and_then(OsStr::to_str)
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("dsf") || ext.eq_ignore_ascii_case("dff"))
+}
+
+/// Read the header of a DSF or DSDIFF file from the start of `reader`.
+///
+/// Afterwards `reader` is positioned at the start of the audio data.
+pub fn read_header<R: Read + Seek>(reader: &mut R) -> Result<DsdHeader> {
+    let id: [u8; 4] = read_array(reader)?;
+    let header = match &id {
+        b"DSD " => read_dsf_header(reader)?,
+        b"FRM8" => read_dff_header(reader)?,
+        _ => bail!("Not a DSF or DSDIFF file"),
+    };
+
+    if header.sample_rate == 0 || header.channels == 0 || header.block_size == 0 {
+        bail!(
+            "Invalid DSD format: {} channels at {}Hz",
+            header.channels,
+            header.sample_rate
+        );
+    }
+
+    Ok(header)
+}
+
+/// Read the header of a DSF file, after the `DSD ` id.
+///
+/// See <https://dsd-guide.com/sites/default/files/white-papers/DSFFileFormatSpec_E.pdf>.
+fn read_dsf_header<R: Read + Seek>(reader: &mut R) -> Result<DsdHeader> {
+    // "DSD " chunk
+    let chunk_size = u64::from_le_bytes(read_array(reader)?);
+    let _file_size = u64::from_le_bytes(read_array(reader)?);
+    let metadata_offset = u64::from_le_bytes(read_array(reader)?);
+    skip(reader, chunk_size.saturating_sub(28))?;
+
+    // "fmt " chunk
+    expect_id(reader, b"fmt ")?;
+    let chunk_size = u64::from_le_bytes(read_array(reader)?);
+    let _version = u32::from_le_bytes(read_array(reader)?);
+    let format_id = u32::from_le_bytes(read_array(reader)?);
+    if format_id != 0 {
+        bail!("Unsupported DSF format {format_id}, only raw DSD is supported");
+    }
+    let _channel_type = u32::from_le_bytes(read_array(reader)?);
+    let channels = u32::from_le_bytes(read_array(reader)?);
+    let sample_rate = u32::from_le_bytes(read_array(reader)?);
+    let bits_per_sample = u32::from_le_bytes(read_array(reader)?);
+    let sample_count = u64::from_le_bytes(read_array(reader)?);
+    let block_size = u32::from_le_bytes(read_array(reader)?);
+    skip(reader, chunk_size.saturating_sub(48))?;
+
+    let lsb_first = match bits_per_sample {
+        1 => true,
+        8 => false,
+        _ => bail!("Invalid DSF bits per sample {bits_per_sample}"),
+    };
+
+    // "data" chunk
+    expect_id(reader, b"data")?;
+    let chunk_size = u64::from_le_bytes(read_array(reader)?);
+    let data_offset = reader.stream_position()?;
+
+    Ok(DsdHeader {
+        sample_rate,
+        channels: u16::try_from(channels).context("DSF channel count")?,
+        sample_count,
+        data_offset,
+        data_len: chunk_size.saturating_sub(12),
+        block_size,
+        lsb_first,
+        id3_offset: (metadata_offset != 0).then_some(metadata_offset),
+    })
+}
+
+/// Read the header of a DSDIFF file, after the `FRM8` id.
+///
+/// Only uncompressed DSD is supported, not DST.
+/// See <https://dsd-guide.com/sites/default/files/white-papers/DSDIFF_1.5_Spec.pdf>.
+fn read_dff_header<R: Read + Seek>(reader: &mut R) -> Result<DsdHeader> {
+    let _form_size = u64::from_be_bytes(read_array(reader)?);
+    expect_id(reader, b"DSD ")?;
+
+    let mut sample_rate = 0;
+    let mut channels = 0;
+
+    let (data_offset, data_len) = loop {
+        let (id, size) = read_dff_chunk_header(reader)?;
+        match &id {
+            b"PROP" => {
+                expect_id(reader, b"SND ")?;
+                let mut remaining = size.saturating_sub(4);
+                while remaining >= 12 {
+                    let (id, size) = read_dff_chunk_header(reader)?;
+                    let read = match &id {
+                        b"FS  " => {
+                            sample_rate = u32::from_be_bytes(read_array(reader)?);
+                            4
+                        }
+                        b"CHNL" => {
+                            channels = u16::from_be_bytes(read_array(reader)?);
+                            2
+                        }
+                        b"CMPR" => {
+                            let compression: [u8; 4] = read_array(reader)?;
+                            if &compression != b"DSD " {
+                                bail!("Compressed (DST) DSDIFF files are not supported");
+                            }
+                            4
+                        }
+                        _ => 0,
+                    };
+                    skip(reader, padded(size).saturating_sub(read))?;
+                    remaining = remaining.saturating_sub(12 + padded(size));
+                }
+                skip(reader, remaining)?;
+            }
+            b"DSD " => break (reader.stream_position()?, size),
+            b"DST " => bail!("Compressed (DST) DSDIFF files are not supported"),
+            _ => skip(reader, padded(size))?,
+        }
+    };
+
+    // some taggers add a non-standard "ID3 " chunk after the audio data
+    let id3_offset = find_dff_id3(reader, data_offset + padded(data_len))
+        .ok()
+        .flatten();
+    reader.seek(SeekFrom::Start(data_offset))?;
+
+    let sample_count = data_len * 8 / u64::from(channels.max(1));
+
+    Ok(DsdHeader {
+        sample_rate,
+        channels,
+        sample_count,
+        data_offset,
+        data_len,
+        block_size: 1,
+        lsb_first: false,
+        id3_offset,
+    })
+}
+
+/// Find the position of the data of a `ID3 ` chunk, in the chunks starting at `from`.
+fn find_dff_id3<R: Read + Seek>(reader: &mut R, from: u64) -> Result<Option<u64>> {
+    reader.seek(SeekFrom::Start(from))?;
+    loop {
+        let (id, size) = match read_dff_chunk_header(reader) {
+            Ok(v) => v,
+            // end of the file
+            Err(_) => return Ok(None),
+        };
+        if &id == b"ID3 " {
+            return Ok(Some(reader.stream_position()?));
+        }
+        skip(reader, padded(size))?;
+    }
+}
+
+/// Read the id and data size of a DSDIFF chunk.
+fn read_dff_chunk_header<R: Read>(reader: &mut R) -> Result<([u8; 4], u64)> {
+    let id = read_array(reader)?;
+    let size = u64::from_be_bytes(read_array(reader)?);
+
+    Ok((id, size))
+}
+
+/// DSDIFF chunks are padded to a even size, which is not included in their size.
+fn padded(size: u64) -> u64 {
+    size + (size & 1)
+}
+
+fn expect_id<R: Read>(reader: &mut R, expected: &[u8; 4]) -> Result<()> {
+    let id: [u8; 4] = read_array(reader)?;
+    if &id != expected {
+        bail!(
+            "Expected chunk \"{}\", found \"{}\"",
+            expected.escape_ascii(),
+            id.escape_ascii()
+        );
+    }
+
+    Ok(())
+}
+
+fn read_array<const N: usize, R: Read>(reader: &mut R) -> Result<[u8; N]> {
+    let mut buf = [0; N];
+    reader.read_exact(&mut buf)?;
+
+    Ok(buf)
+}
+
+fn skip<R: Seek>(reader: &mut R, len: u64) -> Result<()> {
+    if len > 0 {
+        reader.seek(SeekFrom::Current(i64::try_from(len)?))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{DsdHeader, read_header};
+
+    #[test]
+    fn should_read_dsf_header() {
+        let mut file = Vec::new();
+        file.extend_from_slice(b"DSD ");
+        file.extend_from_slice(&28u64.to_le_bytes());
+        file.extend_from_slice(&0u64.to_le_bytes());
+        file.extend_from_slice(&4200u64.to_le_bytes());
+        file.extend_from_slice(b"fmt ");
+        file.extend_from_slice(&52u64.to_le_bytes());
+        for v in [1u32, 0, 2, 2, 2_822_400, 1] {
+            file.extend_from_slice(&v.to_le_bytes());
+        }
+        file.extend_from_slice(&5_644_800u64.to_le_bytes());
+        file.extend_from_slice(&4096u32.to_le_bytes());
+        file.extend_from_slice(&0u32.to_le_bytes());
+        file.extend_from_slice(b"data");
+        file.extend_from_slice(&(12u64 + 8192).to_le_bytes());
+
+        let header = read_header(&mut Cursor::new(file)).unwrap();
+        assert_eq!(
+            header,
+            DsdHeader {
+                sample_rate: 2_822_400,
+                channels: 2,
+                sample_count: 5_644_800,
+                data_offset: 92,
+                data_len: 8192,
+                block_size: 4096,
+                lsb_first: true,
+                id3_offset: Some(4200),
+            }
+        );
+        assert_eq!(header.duration(), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn should_read_dff_header() {
+        let chunk = |id: &[u8; 4], data: &[u8]| {
+            let mut chunk = id.to_vec();
+            chunk.extend_from_slice(&(data.len() as u64).to_be_bytes());
+            chunk.extend_from_slice(data);
+            if data.len() % 2 == 1 {
+                chunk.push(0);
+            }
+            chunk
+        };
+
+        let mut prop = b"SND ".to_vec();
+        prop.extend(chunk(b"FS  ", &2_822_400u32.to_be_bytes()));
+        prop.extend(chunk(
+            b"CHNL",
+            &[0, 2, b'S', b'L', b'F', b'T', b'S', b'R', b'G', b'T'],
+        ));
+        prop.extend(chunk(b"CMPR", b"DSD \x0enot compressed"));
+
+        let mut form = b"DSD ".to_vec();
+        form.extend(chunk(b"FVER", &[1, 5, 0, 0]));
+        form.extend(chunk(b"PROP", &prop));
+        form.extend(chunk(b"DSD ", &[0x69; 10]));
+        form.extend(chunk(b"ID3 ", b"ID3"));
+
+        let mut file = b"FRM8".to_vec();
+        file.extend_from_slice(&(form.len() as u64).to_be_bytes());
+        file.extend(form);
+
+        let mut reader = Cursor::new(file);
+        let header = read_header(&mut reader).unwrap();
+        assert_eq!(
+            header,
+            DsdHeader {
+                sample_rate: 2_822_400,
+                channels: 2,
+                sample_count: 40,
+                data_offset: 130,
+                data_len: 10,
+                block_size: 1,
+                lsb_first: false,
+                id3_offset: Some(152),
+            }
+        );
+        assert_eq!(reader.position(), 130);
+    }
+}
@@ -3,7 +3,7 @@ use std::{
     cell::RefCell,
     fmt::Display,
     fs::File,
-    io::BufReader,
+    io::{BufReader, Seek, SeekFrom},
     num::NonZeroUsize,
     path::{Path, PathBuf},
     str::FromStr,
@@ -12,7 +12,7 @@ use std::{
 };
 
 use anyhow::{Context, Result, anyhow, bail};
-use id3::frame::Lyrics as Id3Lyrics;
+use id3::{TagLike, frame::Lyrics as Id3Lyrics};
 use lofty::{
     config::ParseOptions,
     file::{AudioFile, FileType, TaggedFileExt},
@@ -23,9 +23,14 @@ use lofty::{
 use lru::LruCache;
 
 use crate::{
-    player::playlist_helpers::PlaylistTrackSource, podcast::episode::Episode, songtag::lrc::Lyric,
-    utils::SplitArrayIter,
+    config::v2::server::ReplayGainMode, player::playlist_helpers::PlaylistTrackSource,
+    podcast::episode::Episode, songtag::lrc::Lyric, utils::SplitArrayIter,
 };
+use cue::{CueSheet, CueSpan};
+
+pub mod cue;
+pub mod dsd;
+pub mod title_format;
 
 /// A simple no-value representation of [`MediaTypes`].
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -118,6 +123,11 @@ pub struct TrackData {
     album: Option<String>,
 
     file_type: Option<FileType>,
+
+    replay_gain: Option<ReplayGain>,
+
+    /// The part of the audio file to play, for cue sheet tracks
+    cue: Option<CueSpan>,
 }
 
 impl PartialEq for TrackData {
@@ -133,6 +143,20 @@ impl TrackData {
         &self.path
     }
 
+    /// Get the path of the audio file to play.
+    ///
+    /// This is the same as [`Self::path`], except for cue sheet tracks, where it is the file the cue sheet refers to.
+    #[must_use]
+    pub fn audio_path(&self) -> &Path {
+        self.cue.as_ref().map_or(&self.path, |v| &v.file)
+    }
+
+    /// The part of the audio file to play, if this is a cue sheet track.
+    #[must_use]
+    pub fn cue_span(&self) -> Option<&CueSpan> {
+        self.cue.as_ref()
+    }
+
     #[must_use]
     pub fn album(&self) -> Option<&str> {
         self.album.as_deref()
@@ -146,6 +170,12 @@ impl TrackData {
         self.file_type
     }
 
+    /// The ReplayGain values from the tags, if any were present.
+    #[must_use]
+    pub fn replay_gain(&self) -> Option<ReplayGain> {
+        self.replay_gain
+    }
+
     /// Create new [`TrackData`] with only the path.
     ///
     /// This should mainly be used for tests only.
@@ -155,6 +185,8 @@ impl TrackData {
             path,
             album: None,
             file_type: None,
+            replay_gain: None,
+            cue: None,
         }
     }
 }
@@ -254,6 +286,7 @@ impl Track {
                 artist: true,
                 title: true,
                 duration: true,
+                replay_gain: true,
                 ..Default::default()
             },
         ) {
@@ -269,10 +302,14 @@ impl Track {
             }
         };
 
+        let cue = cue::read_span(&path)?;
+
         let track_data = TrackData {
             path,
             album: metadata.album,
             file_type: metadata.file_type,
+            replay_gain: metadata.replay_gain,
+            cue,
         };
 
         Ok(Self {
@@ -298,6 +335,16 @@ impl Track {
         self.duration
     }
 
+    /// Set the title and duration from another source (like a playlist file), if the track does not have them already.
+    pub fn fill_missing(&mut self, title: Option<String>, duration: Option<Duration>) {
+        if self.title.is_none() {
+            self.title = title;
+        }
+        if self.duration.is_none() {
+            self.duration = duration;
+        }
+    }
+
     /// Format the Track's duration to a short-form.
     ///
     /// see [`DurationFmtShort`] for formatting.
@@ -332,6 +379,16 @@ impl Track {
         }
     }
 
+    /// Get whether the track is streamed from the network, instead of read from a local file.
+    #[must_use]
+    pub fn is_stream(&self) -> bool {
+        match &self.inner {
+            MediaTypes::Track(_) => false,
+            MediaTypes::Radio(_) => true,
+            MediaTypes::Podcast(podcast_track_data) => podcast_track_data.localfile().is_none(),
+        }
+    }
+
     #[must_use]
     pub fn as_track(&self) -> Option<&TrackData> {
         if let MediaTypes::Track(track_data) = &self.inner {
@@ -646,11 +703,13 @@ pub struct MetadataOptions<'a> {
     /// See [`DEFAULT_ARTIST_SEPARATORS`].
     pub artist_separators: &'a [&'a str],
     pub title: bool,
+    pub track_number: bool,
     pub duration: bool,
     pub genre: bool,
     pub cover: bool,
     pub lyrics: bool,
     pub file_times: bool,
+    pub replay_gain: bool,
 }
 
 impl MetadataOptions<'_> {
@@ -665,11 +724,13 @@ impl MetadataOptions<'_> {
             artists: true,
             artist_separators: &[],
             title: true,
+            track_number: true,
             duration: true,
             genre: true,
             cover: true,
             lyrics: true,
             file_times: true,
+            replay_gain: true,
         }
     }
 }
@@ -692,6 +753,8 @@ pub struct TrackMetadata {
     pub artists: Option<Vec<String>>,
     /// ID3v2 tag `TIT2` or equivalent
     pub title: Option<String>,
+    /// ID3v2 tag `TRCK` or equivalent, without the total
+    pub track_number: Option<u32>,
     /// Total duration, this may or may not come from a tag
     pub duration: Option<Duration>,
     /// ID3v2 tag `TCON` or equivalent
@@ -701,10 +764,64 @@ pub struct TrackMetadata {
     /// ID3v2 tags `USLT` or equivalent
     pub lyric_frames: Option<Vec<Id3Lyrics>>,
     pub file_times: Option<FileTimes>,
+    /// `REPLAYGAIN_*` tags or equivalent, only set if any of them are present
+    pub replay_gain: Option<ReplayGain>,
 
     pub file_type: Option<FileType>,
 }
 
+/// ReplayGain values of a track, see <https://wiki.hydrogenaud.io/index.php?title=ReplayGain_2.0_specification>.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ReplayGain {
+    /// Gain in dB to apply to play the track at the reference loudness
+    pub track_gain: Option<f32>,
+    /// Highest sample amplitude of the track, where `1.0` is full scale
+    pub track_peak: Option<f32>,
+    /// Gain in dB to apply to play the whole album at the reference loudness
+    pub album_gain: Option<f32>,
+    /// Highest sample amplitude of the whole album, where `1.0` is full scale
+    pub album_peak: Option<f32>,
+}
+
+impl ReplayGain {
+    /// Get the linear factor to multiply samples with in `mode`, limited so that the peak does not clip.
+    ///
+    /// Falls back to the other gain if the one for `mode` is missing.
+    /// Returns [`None`] for [`ReplayGainMode::Off`] or if there is no gain at all.
+    #[must_use]
+    pub fn factor(&self, mode: ReplayGainMode) -> Option<f32> {
+        let track = self.track_gain.map(|gain| (gain, self.track_peak));
+        let album = self.album_gain.map(|gain| (gain, self.album_peak));
+        let (gain, peak) = match mode {
+            ReplayGainMode::Off => None,
+            ReplayGainMode::Track => track.or(album),
+            ReplayGainMode::Album => album.or(track),
+        }?;
+
+        let factor = 10f32.powf(gain / 20.0);
+        match peak {
+            Some(peak) if peak > 0.0 => Some(factor.min(1.0 / peak)),
+            _ => Some(factor),
+        }
+    }
+
+    /// Parse a gain tag value like `-6.54 dB`.
+    fn parse_gain(value: &str) -> Option<f32> {
+        value
+            .trim()
+            .trim_end_matches(|c: char| c.is_ascii_alphabetic())
+            .trim()
+            .parse()
+            .ok()
+            .filter(|v: &f32| v.is_finite())
+    }
+
+    /// Parse a peak tag value like `0.988547`.
+    fn parse_peak(value: &str) -> Option<f32> {
+        value.trim().parse().ok().filter(|v: &f32| v.is_finite())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct FileTimes {
     pub modified: Option<SystemTime>,
@@ -716,6 +833,13 @@ pub fn parse_metadata_from_file(
     path: &Path,
     options: MetadataOptions<'_>,
 ) -> Result<TrackMetadata> {
+    if let Some((cue_path, number)) = cue::split_track_path(path) {
+        return parse_metadata_from_cue_track(cue_path, number, options);
+    }
+    if dsd::is_dsd(path) {
+        return parse_metadata_from_dsd(path, options);
+    }
+
     let mut parse_options = ParseOptions::new();
 
     parse_options = parse_options.read_cover_art(options.cover);
@@ -740,16 +864,50 @@ pub fn parse_metadata_from_file(
     }
 
     if options.file_times {
-        if let Ok(metadata) = std::fs::metadata(path) {
-            let filetimes = FileTimes {
-                modified: metadata.modified().ok(),
-                created: metadata.created().ok(),
-            };
+        res.file_times = read_file_times(path);
+    }
+
+    Ok(res)
+}
+
+/// Get the modified and created times of the file at `path`.
+fn read_file_times(path: &Path) -> Option<FileTimes> {
+    let metadata = std::fs::metadata(path).ok()?;
+
+    Some(FileTimes {
+        modified: metadata.modified().ok(),
+        created: metadata.created().ok(),
+    })
+}
+
+/// [`parse_metadata_from_file`] for DSD files, which lofty cannot read.
+///
+/// The duration comes from the header and the tags from a embedded ID3v2 tag, if there is one.
+fn parse_metadata_from_dsd(path: &Path, options: MetadataOptions<'_>) -> Result<TrackMetadata> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let header = dsd::read_header(&mut reader)?;
+
+    let mut res = TrackMetadata::default();
 
-            res.file_times = Some(filetimes);
+    if options.duration {
+        res.duration = Some(header.duration());
+    }
+
+    if let Some(offset) = header.id3_offset {
+        reader.seek(SeekFrom::Start(offset))?;
+        match id3::Tag::read_from2(&mut reader) {
+            Ok(tag) => handle_id3_tag(&tag, options, &mut res),
+            Err(err) => warn!(
+                "Failed to read the ID3 tag of \"{}\": {err}",
+                path.display()
+            ),
         }
     }
 
+    if options.file_times {
+        res.file_times = read_file_times(path);
+    }
+
     Ok(res)
 }
 
@@ -818,6 +976,9 @@ fn handle_tag(tag: &LoftyTag, options: MetadataOptions<'_>, res: &mut TrackMetad
     if options.title {
         res.title = tag.title().map(Cow::into_owned);
     }
+    if options.track_number {
+        res.track_number = tag.track();
+    }
     if options.genre {
         res.genre = tag.genre().map(Cow::into_owned);
     }
@@ -836,6 +997,109 @@ fn handle_tag(tag: &LoftyTag, options: MetadataOptions<'_>, res: &mut TrackMetad
         get_lyrics_from_tags(tag, &mut lyric_frames);
         res.lyric_frames = Some(lyric_frames);
     }
+
+    if options.replay_gain {
+        let replay_gain = ReplayGain {
+            track_gain: tag
+                .get_string(&ItemKey::ReplayGainTrackGain)
+                .and_then(ReplayGain::parse_gain),
+            track_peak: tag
+                .get_string(&ItemKey::ReplayGainTrackPeak)
+                .and_then(ReplayGain::parse_peak),
+            album_gain: tag
+                .get_string(&ItemKey::ReplayGainAlbumGain)
+                .and_then(ReplayGain::parse_gain),
+            album_peak: tag
+                .get_string(&ItemKey::ReplayGainAlbumPeak)
+                .and_then(ReplayGain::parse_peak),
+        };
+
+        if replay_gain != ReplayGain::default() {
+            res.replay_gain = Some(replay_gain);
+        }
+    }
+}
+
+/// [`handle_tag`] for a [`id3::Tag`] that was read without lofty.
+fn handle_id3_tag(tag: &id3::Tag, options: MetadataOptions<'_>, res: &mut TrackMetadata) {
+    let extended_texts = |name: &str| {
+        tag.extended_texts()
+            .filter(|v| v.description.eq_ignore_ascii_case(name))
+            .flat_map(|v| v.value.split('\0'))
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+    };
+
+    if options.artist {
+        res.artist = tag.artist().map(ToString::to_string);
+    }
+    if options.artists {
+        let mut artists = extended_texts("ARTISTS");
+        if artists.is_empty() && !options.artist_separators.is_empty() {
+            if let Some(artist) = tag.artist() {
+                artists.extend(split_artists(artist, options));
+            }
+        }
+
+        res.artists = Some(artists);
+    }
+    if options.album {
+        res.album = tag.album().map(ToString::to_string);
+    }
+    if options.album_artist {
+        res.album_artist = tag.album_artist().map(ToString::to_string);
+    }
+    if options.album_artists {
+        let mut album_artists = extended_texts("ALBUMARTISTS");
+        if album_artists.is_empty() && !options.artist_separators.is_empty() {
+            if let Some(album_artist) = tag.album_artist() {
+                album_artists.extend(split_artists(album_artist, options));
+            }
+        }
+
+        res.album_artists = Some(album_artists);
+    }
+    if options.title {
+        res.title = tag.title().map(ToString::to_string);
+    }
+    if options.track_number {
+        res.track_number = tag.track();
+    }
+    if options.genre {
+        res.genre = tag.genre_parsed().map(Cow::into_owned);
+    }
+
+    if options.cover {
+        res.cover = tag
+            .pictures()
+            .find(|pic| pic.picture_type == id3::frame::PictureType::CoverFront)
+            .or_else(|| tag.pictures().next())
+            .and_then(|pic| Picture::from_reader(&mut pic.data.as_slice()).ok());
+    }
+
+    if options.lyrics {
+        let mut lyric_frames: Vec<Id3Lyrics> = tag.lyrics().cloned().collect();
+        lyric_frames.sort_by(|a, b| {
+            a.description
+                .to_lowercase()
+                .cmp(&b.description.to_lowercase())
+        });
+        res.lyric_frames = Some(lyric_frames);
+    }
+
+    if options.replay_gain {
+        let first = |name: &str| extended_texts(name).into_iter().next();
+        let replay_gain = ReplayGain {
+            track_gain: first("REPLAYGAIN_TRACK_GAIN").and_then(|v| ReplayGain::parse_gain(&v)),
+            track_peak: first("REPLAYGAIN_TRACK_PEAK").and_then(|v| ReplayGain::parse_peak(&v)),
+            album_gain: first("REPLAYGAIN_ALBUM_GAIN").and_then(|v| ReplayGain::parse_gain(&v)),
+            album_peak: first("REPLAYGAIN_ALBUM_PEAK").and_then(|v| ReplayGain::parse_peak(&v)),
+        };
+
+        if replay_gain != ReplayGain::default() {
+            res.replay_gain = Some(replay_gain);
+        }
+    }
 }
 
 /// Create a iterator which separates `artist` with options from `options`
@@ -850,6 +1114,60 @@ fn split_artists<'a>(
         .map(ToString::to_string)
 }
 
+/// [`parse_metadata_from_file`] for a virtual cue sheet track path, see [`cue`].
+///
+/// The tags of the audio file are used for everything the cue sheet does not set.
+fn parse_metadata_from_cue_track(
+    cue_path: &Path,
+    number: u8,
+    options: MetadataOptions<'_>,
+) -> Result<TrackMetadata> {
+    let sheet = CueSheet::read_from_path(cue_path)?;
+    let dir = cue_path.parent().unwrap_or(Path::new(""));
+    let (Some(track), Some(span)) = (sheet.track(number), sheet.span(number, dir)) else {
+        bail!("Cue sheet \"{}\" has no track {number}", cue_path.display());
+    };
+
+    // the file duration is necessary for the length of the last track in it
+    let mut res = parse_metadata_from_file(
+        &span.file,
+        MetadataOptions {
+            duration: true,
+            ..options
+        },
+    )?;
+
+    res.duration = options
+        .duration
+        .then(|| span.length(res.duration))
+        .flatten();
+    // embedded lyrics are for the whole file
+    res.lyric_frames = None;
+
+    if options.title {
+        res.title.clone_from(&track.title);
+    }
+    if options.track_number {
+        res.track_number = Some(u32::from(number));
+    }
+    if let Some(performer) = track.performer.as_ref().or(sheet.performer.as_ref()) {
+        if options.artist {
+            res.artist = Some(performer.clone());
+        }
+        if options.artists {
+            res.artists = Some(vec![performer.clone()]);
+        }
+    }
+    if options.album && sheet.title.is_some() {
+        res.album.clone_from(&sheet.title);
+    }
+    if options.album_artist && sheet.performer.is_some() {
+        res.album_artist.clone_from(&sheet.performer);
+    }
+
+    Ok(res)
+}
+
 /// Fetch all lyrics from the given Lofty tag into the given array.
 fn get_lyrics_from_tags(tag: &LoftyTag, lyric_frames: &mut Vec<Id3Lyrics>) {
     let lyrics = tag.get_items(&ItemKey::Lyrics);
@@ -893,4 +1211,85 @@ mod tests {
             );
         }
     }
+
+    mod replay_gain {
+        use pretty_assertions::assert_eq;
+
+        use crate::config::v2::server::ReplayGainMode;
+        use crate::track::ReplayGain;
+
+        #[test]
+        fn should_parse_tag_values() {
+            assert_eq!(ReplayGain::parse_gain("-6.54 dB"), Some(-6.54));
+            assert_eq!(ReplayGain::parse_gain("+1.5dB"), Some(1.5));
+            assert_eq!(ReplayGain::parse_gain("3"), Some(3.0));
+            assert_eq!(ReplayGain::parse_gain("loud"), None);
+            assert_eq!(ReplayGain::parse_peak(" 0.988547 "), Some(0.988_547));
+            assert_eq!(ReplayGain::parse_peak("NaN"), None);
+        }
+
+        /// Get the factor for `mode`, rounded to avoid float precision issues.
+        fn factor(replay_gain: ReplayGain, mode: ReplayGainMode) -> Option<f32> {
+            replay_gain
+                .factor(mode)
+                .map(|v| (v * 1000.0).round() / 1000.0)
+        }
+
+        #[test]
+        fn should_select_gain_for_mode() {
+            let replay_gain = ReplayGain {
+                track_gain: Some(-20.0),
+                track_peak: None,
+                album_gain: Some(-40.0),
+                album_peak: None,
+            };
+
+            assert_eq!(factor(replay_gain, ReplayGainMode::Off), None);
+            assert_eq!(factor(replay_gain, ReplayGainMode::Track), Some(0.1));
+            assert_eq!(factor(replay_gain, ReplayGainMode::Album), Some(0.01));
+
+            // fallback to the other gain
+            let track_only = ReplayGain {
+                album_gain: None,
+                ..replay_gain
+            };
+            assert_eq!(factor(track_only, ReplayGainMode::Album), Some(0.1));
+            assert_eq!(factor(ReplayGain::default(), ReplayGainMode::Track), None);
+        }
+
+        #[test]
+        fn should_not_clip_peak() {
+            let replay_gain = ReplayGain {
+                track_gain: Some(20.0),
+                track_peak: Some(0.5),
+                album_gain: None,
+                album_peak: None,
+            };
+
+            assert_eq!(factor(replay_gain, ReplayGainMode::Track), Some(2.0));
+        }
+    }
+
+    mod stream {
+        use crate::podcast::episode::Episode;
+        use crate::track::Track;
+
+        #[test]
+        fn should_detect_streamed_tracks() {
+            assert!(Track::new_radio("https://example.com/radio").is_stream());
+
+            let episode = Episode {
+                url: "https://example.com/episode.mp3".to_string(),
+                ..Default::default()
+            };
+            assert!(Track::from_podcast_episode(&episode).is_stream());
+
+            // the downloaded file has to exist to be used
+            let downloaded = Episode {
+                path: Some(std::env::current_exe().unwrap()),
+                ..episode
+            };
+            assert!(!Track::from_podcast_episode(&downloaded).is_stream());
+        }
+    }
 }
@@ -0,0 +1,135 @@
+//! Format the title of a [`Track`] from a template like `{artist} - {title}[ ({album})]`.
+//!
+//! Supported fields are `{title}`, `{artist}`, `{album}` and `{duration}`. `{title}` falls back to the
+//! file name or url, other missing fields are shown as unknown, unless they are inside an optional section
+//! in square brackets, which is left out entirely if any field inside it is missing.
+//! Unknown fields are kept as written.
+
+use std::borrow::Cow;
+
+use super::Track;
+use crate::common::const_unknown::{UNKNOWN_ALBUM, UNKNOWN_ARTIST};
+
+/// The default template, which only shows the title.
+pub const DEFAULT_TITLE_FORMAT: &str = "{title}";
+
+impl Track {
+    /// Get the value of the template field `name`.
+    ///
+    /// Returns `None` if the field does not exist, and `Some(None)` if the track does not have a value for it.
+    fn title_field(&self, name: &str) -> Option<Option<Cow<'_, str>>> {
+        let value = match name {
+            "title" => Some(self.title().map_or_else(|| self.id_str(), Into::into)),
+            "artist" => self.artist().map(Into::into),
+            "album" => self.as_track().and_then(|v| v.album()).map(Into::into),
+            "duration" => self.duration_str_short().map(|v| v.to_string().into()),
+            _ => return None,
+        };
+
+        Some(value)
+    }
+
+    /// Format the title of this track with `template`, see the [module docs](crate::track::title_format).
+    #[must_use]
+    pub fn format_title(&self, template: &str) -> String {
+        render_template(
+            template,
+            |name| self.title_field(name),
+            |name| match name {
+                "artist" => UNKNOWN_ARTIST,
+                "album" => UNKNOWN_ALBUM,
+                _ => "--:--",
+            },
+        )
+    }
+}
+
+/// Render `template` with the values of `field`, see the [module docs](crate::track::title_format).
+///
+/// `field` returns `None` for fields that do not exist, which are kept as written, and `Some(None)` for
+/// fields without a value, which are replaced with `missing` outside of optional sections.
+pub(crate) fn render_template<'a>(
+    template: &str,
+    field: impl Fn(&str) -> Option<Option<Cow<'a, str>>>,
+    missing: impl Fn(&str) -> &'static str,
+) -> String {
+    let mut out = String::new();
+    // the text of the currently open optional section
+    let mut section = String::new();
+    let mut in_section = false;
+    let mut section_complete = true;
+    let mut rest = template;
+
+    while let Some(ch) = rest.chars().next() {
+        rest = &rest[ch.len_utf8()..];
+        let target = if in_section { &mut section } else { &mut out };
+
+        match ch {
+            '[' if !in_section => {
+                in_section = true;
+                section_complete = true;
+                section.clear();
+            }
+            ']' if in_section => {
+                in_section = false;
+                if section_complete {
+                    out.push_str(&section);
+                }
+            }
+            '{' => {
+                let Some((name, after)) = rest.split_once('}') else {
+                    target.push(ch);
+                    continue;
+                };
+                rest = after;
+
+                match field(name) {
+                    Some(Some(value)) => target.push_str(&value),
+                    Some(None) if in_section => section_complete = false,
+                    Some(None) => target.push_str(missing(name)),
+                    None => {
+                        target.push('{');
+                        target.push_str(name);
+                        target.push('}');
+                    }
+                }
+            }
+            _ => target.push(ch),
+        }
+    }
+
+    // a section that is never closed is shown with its bracket
+    if in_section && section_complete {
+        out.push('[');
+        out.push_str(&section);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::track::Track;
+
+    #[test]
+    fn should_format_fields_and_sections() {
+        let mut track = Track::new_radio("http://radio.example/stream");
+        assert_eq!(track.format_title("{title}"), "http://radio.example/stream");
+        assert_eq!(
+            track.format_title("{artist} - {title}[ ({album})]"),
+            "Unknown Artist - http://radio.example/stream"
+        );
+
+        track.fill_missing(Some("Song".to_string()), Some(Duration::from_secs(61)));
+        assert_eq!(
+            track.format_title("{title}[ by {artist}][ ({duration})]"),
+            "Song (01:01)"
+        );
+        assert_eq!(track.format_title("{title} {other} {"), "Song {other} {");
+        assert_eq!(track.format_title("{title} [{duration}"), "Song [01:01");
+    }
+}
@@ -0,0 +1,140 @@
+//! Import a plain text list of urls and paths, one per line, into the playlist.
+//!
+//! Empty lines and lines starting with `#` are ignored, relative paths are relative to the list file.
+
+use std::path::Path;
+
+use anyhow::Result;
+use reqwest::Url;
+
+use crate::player::playlist_helpers::PlaylistTrackSource;
+use crate::playlist::PlaylistValue;
+use crate::utils::playlist_get_entries;
+
+/// A entry of a url list, resolved to how it can be added to the playlist.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UrlListItem {
+    /// Can be added to the playlist as-is
+    Track(PlaylistTrackSource),
+    /// A youtube video, which needs to be downloaded before it can be played
+    Youtube(String),
+}
+
+/// Check if `path` is a url list, which is any `.txt` file.
+#[must_use]
+pub fn is_url_list(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("txt"))
+}
+
+/// Read the url list at `path` and resolve each entry, see [`resolve`].
+///
+/// `is_episode` is used to check if a url is a known podcast episode.
+pub fn read_url_list(path: &Path, is_episode: impl Fn(&str) -> bool) -> Result<Vec<UrlListItem>> {
+    let entries = playlist_get_entries(path)?;
+
+    Ok(entries
+        .into_iter()
+        .map(|v| resolve(v.value, &is_episode))
+        .collect())
+}
+
+/// Resolve `value` to how it is added to the playlist:
+/// youtube urls need a download, known podcast episodes are added as episodes
+/// (to use a downloaded file and remember the position) and everything else as a plain url or path.
+pub fn resolve(value: PlaylistValue, is_episode: impl Fn(&str) -> bool) -> UrlListItem {
+    let url = match value {
+        PlaylistValue::Path(path) => {
+            return UrlListItem::Track(PlaylistTrackSource::Path(
+                path.to_string_lossy().to_string(),
+            ));
+        }
+        PlaylistValue::Url(url) => url,
+    };
+
+    if is_youtube_url(&url) {
+        return UrlListItem::Youtube(url.into());
+    }
+
+    let url = String::from(url);
+    if is_episode(&url) {
+        return UrlListItem::Track(PlaylistTrackSource::PodcastUrl(url));
+    }
+
+    UrlListItem::Track(PlaylistTrackSource::Url(url))
+}
+
+/// Check if `url` is a video on youtube or youtube music.
+#[must_use]
+pub fn is_youtube_url(url: &Url) -> bool {
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    let host = host.strip_prefix("www.").unwrap_or(host);
+
+    match host {
+        "youtu.be" => url.path().len() > 1,
+        "youtube.com" | "m.youtube.com" | "music.youtube.com" => {
+            url.path() == "/watch" || url.path().starts_with("/shorts/")
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use reqwest::Url;
+
+    use super::{UrlListItem, is_youtube_url, resolve};
+    use crate::player::playlist_helpers::PlaylistTrackSource;
+    use crate::playlist::PlaylistValue;
+
+    #[test]
+    fn should_detect_youtube_urls() {
+        for url in [
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+            "https://music.youtube.com/watch?v=dQw4w9WgXcQ",
+            "https://youtu.be/dQw4w9WgXcQ",
+            "https://youtube.com/shorts/dQw4w9WgXcQ",
+        ] {
+            assert!(is_youtube_url(&Url::parse(url).unwrap()), "{url}");
+        }
+
+        for url in [
+            "https://www.youtube.com/",
+            "https://www.youtube.com/@channel",
+            "https://youtu.be/",
+            "https://example.com/watch?v=dQw4w9WgXcQ",
+        ] {
+            assert!(!is_youtube_url(&Url::parse(url).unwrap()), "{url}");
+        }
+    }
+
+    #[test]
+    fn should_resolve_entries() {
+        let is_episode = |url: &str| url == "https://example.com/episode.mp3";
+        let url = |v: &str| PlaylistValue::Url(Url::parse(v).unwrap());
+
+        assert_eq!(
+            resolve(PlaylistValue::Path("/music/a.mp3".into()), is_episode),
+            UrlListItem::Track(PlaylistTrackSource::Path("/music/a.mp3".to_string()))
+        );
+        assert_eq!(
+            resolve(url("https://youtu.be/dQw4w9WgXcQ"), is_episode),
+            UrlListItem::Youtube("https://youtu.be/dQw4w9WgXcQ".to_string())
+        );
+        assert_eq!(
+            resolve(url("https://example.com/episode.mp3"), is_episode),
+            UrlListItem::Track(PlaylistTrackSource::PodcastUrl(
+                "https://example.com/episode.mp3".to_string()
+            ))
+        );
+        assert_eq!(
+            resolve(url("https://example.com/radio"), is_episode),
+            UrlListItem::Track(PlaylistTrackSource::Url(
+                "https://example.com/radio".to_string()
+            ))
+        );
+    }
+}
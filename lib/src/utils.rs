@@ -2,13 +2,15 @@ use std::borrow::Cow;
 use std::ffi::OsStr;
 use std::iter::FusedIterator;
 use std::path::{Path, PathBuf};
+use std::sync::{Once, OnceLock};
 
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Context, Result, anyhow, bail};
 use pinyin::ToPinyin;
 use rand::Rng;
 use unicode_segmentation::UnicodeSegmentation;
 
 use crate::config::ServerOverlay;
+use crate::track::cue;
 
 #[must_use]
 pub fn get_pin_yin(input: &str) -> String {
@@ -28,33 +30,32 @@ pub fn get_pin_yin(input: &str) -> String {
     b
 }
 
-// TODO: decide filetype supported by backend instead of in library
+/// Extensions of the audio files that at least one backend can play.
+///
+/// Which of them the current backend can actually play is reported by the server,
+/// for example WavPack, Musepack, APE and TAK are only played by the mpv and gstreamer backends.
+pub const SUPPORTED_EXTENSIONS: &[&str] = &[
+    "mkv", "mka", "mp3", "aiff", "aif", "aifc", "flac", "m4a", "aac", "opus", "ogg", "wav", "webm",
+    "dsf", "dff", "wv", "mpc", "ape", "tak",
+];
+
+/// Check if `path` is a url or a audio file with one of the [`SUPPORTED_EXTENSIONS`].
 #[must_use]
 pub fn filetype_supported(path: &Path) -> bool {
     if path.starts_with("http") {
         return true;
     }
 
+    // the cue sheet itself is not playable, only its tracks
+    if cue::split_track_path(path).is_some() {
+        return true;
+    }
+
     let Some(ext) = path.extension().and_then(OsStr::to_str) else {
         return false;
     };
 
-    matches!(
-        ext,
-        "mkv"
-            | "mka"
-            | "mp3"
-            | "aiff"
-            | "aif"
-            | "aifc"
-            | "flac"
-            | "m4a"
-            | "aac"
-            | "opus"
-            | "ogg"
-            | "wav"
-            | "webm"
-    )
+    SUPPORTED_EXTENSIONS.contains(&ext)
 }
 
 /// Check if the given path has a extension that matches well-known playlists that are supported by us.
@@ -79,9 +80,185 @@ pub fn get_parent_folder(path: &Path) -> Cow<'_, Path> {
     }
 }
 
+/// The directory in the config and data directories containing a directory per [profile](set_app_profile).
+pub const PROFILES_DIR: &str = "profiles";
+
+/// The name of the main profile, which uses the config and data directories themselves.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Profile set via `--profile`, which has its own config and databases.
+static APP_PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Use the profile `name`, which has its own config, library and podcast databases and playlist,
+/// for example to keep audiobooks apart from the main library.
+///
+/// Has to be called before any other path function, later calls are ignored.
+/// [`DEFAULT_PROFILE`] keeps using the main profile.
+///
+/// # Errors
+///
+/// if `name` is not a valid profile name, see [`check_profile_name`]
+pub fn set_app_profile(name: String) -> Result<()> {
+    check_profile_name(&name)?;
+    if name == DEFAULT_PROFILE {
+        return Ok(());
+    }
+    if APP_PROFILE.set(name).is_err() {
+        warn!("App profile was already set, ignoring new value");
+    }
+
+    Ok(())
+}
+
+/// Get the profile set via [`set_app_profile`], `None` for the default profile.
+#[must_use]
+pub fn get_app_profile() -> Option<&'static str> {
+    APP_PROFILE.get().map(String::as_str)
+}
+
+/// Check that `name` can be used as a profile, which is a directory name.
+///
+/// # Errors
+///
+/// if `name` is empty or contains anything other than letters, digits, `-` and `_`
+pub fn check_profile_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        bail!("Profile name is empty");
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_'))
+    {
+        bail!("Profile name \"{name}\" may only contain letters, digits, \"-\" and \"_\"");
+    }
+
+    Ok(())
+}
+
+/// List the names of all existing profiles, sorted, without the default profile.
+///
+/// # Errors
+///
+/// if the profiles directory exists, but cannot be read
+pub fn list_app_profiles() -> Result<Vec<String>> {
+    let dir = dirs::config_dir()
+        .ok_or_else(|| anyhow!("failed to find os config dir."))?
+        .join("termusic")
+        .join(PROFILES_DIR);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut profiles = Vec::new();
+    for entry in std::fs::read_dir(&dir).with_context(|| format!("read \"{}\"", dir.display()))? {
+        let entry = entry?;
+        let Ok(name) = entry.file_name().into_string() else {
+            continue;
+        };
+        if entry.path().is_dir() && name != DEFAULT_PROFILE && check_profile_name(&name).is_ok() {
+            profiles.push(name);
+        }
+    }
+    profiles.sort();
+
+    Ok(profiles)
+}
+
+/// Get the config directory, resolved and created.
+///
+/// This is `$XDG_CONFIG_HOME/termusic` (or the platform equivalent), or `profiles/<name>` in it for a [profile](set_app_profile).
 pub fn get_app_config_path() -> Result<PathBuf> {
     let mut path = dirs::config_dir().ok_or_else(|| anyhow!("failed to find os config dir."))?;
     path.push("termusic");
+    if let Some(profile) = get_app_profile() {
+        path.push(PROFILES_DIR);
+        path.push(profile);
+    }
+
+    if !path.exists() {
+        std::fs::create_dir_all(&path)?;
+    }
+    Ok(path)
+}
+
+/// Data directory set via `--data-dir`, overwriting the platform default.
+static APP_DATA_DIR_OVERWRITE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Files that were previously stored in the config directory and now belong in the data directory.
+const MIGRATE_DATA_FILES: &[&str] = &[
+    "library2.db",
+    "library2.db-wal",
+    "library2.db-shm",
+    "data.db",
+    "data.db-wal",
+    "data.db-shm",
+    "playlist.log",
+];
+
+/// Store databases, caches and the playlist in `path` instead of the platform default directories,
+/// for example for a portable installation.
+///
+/// Has to be called before any other path function, later calls are ignored.
+pub fn set_app_data_path(path: PathBuf) {
+    if APP_DATA_DIR_OVERWRITE.set(path).is_err() {
+        warn!("App data path was already set, ignoring new value");
+    }
+}
+
+/// Get the directory for databases and the playlist, resolved and created.
+///
+/// This is `$XDG_DATA_HOME/termusic` (or the platform equivalent), unless overwritten via [`set_app_data_path`],
+/// with `profiles/<name>` in it for a [profile](set_app_profile).
+/// Files from before the config and data directories were separate get moved on first access.
+pub fn get_app_data_path() -> Result<PathBuf> {
+    static MIGRATE: Once = Once::new();
+
+    let mut path = if let Some(path) = APP_DATA_DIR_OVERWRITE.get() {
+        absolute_path(path)?.into_owned()
+    } else {
+        let mut path = dirs::data_dir().ok_or_else(|| anyhow!("failed to find os data dir."))?;
+        path.push("termusic");
+        path
+    };
+    if let Some(profile) = get_app_profile() {
+        path.push(PROFILES_DIR);
+        path.push(profile);
+    }
+
+    if !path.exists() {
+        std::fs::create_dir_all(&path)?;
+    }
+
+    MIGRATE.call_once(|| {
+        let config_path = match get_app_config_path() {
+            Ok(v) => v,
+            Err(err) => {
+                warn!("Could not get config path for data migration: {err:#}");
+                return;
+            }
+        };
+        if let Err(err) = migrate_data_files(&config_path, &path) {
+            warn!(
+                "Migrating data files to \"{}\" failed: {err:#}",
+                path.display()
+            );
+        }
+    });
+
+    Ok(path)
+}
+
+/// Get the directory for caches like cover art, resolved and created.
+///
+/// This is `$XDG_CACHE_HOME/termusic` (or the platform equivalent), or `cache` in the overwritten data path.
+pub fn get_app_cache_path() -> Result<PathBuf> {
+    let path = if APP_DATA_DIR_OVERWRITE.get().is_some() {
+        get_app_data_path()?.join("cache")
+    } else {
+        let mut path = dirs::cache_dir().unwrap_or_else(std::env::temp_dir);
+        path.push("termusic");
+        path
+    };
 
     if !path.exists() {
         std::fs::create_dir_all(&path)?;
@@ -89,9 +266,37 @@ pub fn get_app_config_path() -> Result<PathBuf> {
     Ok(path)
 }
 
+/// Move all [`MIGRATE_DATA_FILES`] from `from` to `to`, unless they already exist in `to`.
+///
+/// Returns the amount of moved files.
+fn migrate_data_files(from: &Path, to: &Path) -> Result<usize> {
+    if from == to {
+        return Ok(0);
+    }
+
+    let mut moved = 0;
+    for name in MIGRATE_DATA_FILES {
+        let old = from.join(name);
+        let new = to.join(name);
+        if !old.is_file() || new.exists() {
+            continue;
+        }
+
+        // "rename" does not work across filesystems
+        if std::fs::rename(&old, &new).is_err() {
+            std::fs::copy(&old, &new).with_context(|| format!("copy \"{}\"", old.display()))?;
+            std::fs::remove_file(&old).with_context(|| format!("remove \"{}\"", old.display()))?;
+        }
+        info!("Moved \"{}\" to \"{}\"", old.display(), new.display());
+        moved += 1;
+    }
+
+    Ok(moved)
+}
+
 /// Get the termusic database path for [`new_database`](crate::new_database).
 pub fn get_app_new_database_path() -> Result<PathBuf> {
-    let mut db_path = get_app_config_path().context("failed to get app configuration path")?;
+    let mut db_path = get_app_data_path().context("failed to get app data path")?;
     // for the lack of a better name, just adding a "2" compared to the old database
     db_path.push("library2.db");
 
@@ -99,7 +304,7 @@ pub fn get_app_new_database_path() -> Result<PathBuf> {
 }
 
 /// Get the podcast directoy resolved and created
-fn get_podcast_save_path(config: &ServerOverlay) -> Result<PathBuf> {
+pub fn get_podcast_save_path(config: &ServerOverlay) -> Result<PathBuf> {
     let full_path = shellexpand::path::tilde(&config.settings.podcast.download_dir);
     if !full_path.exists() {
         std::fs::create_dir_all(&full_path)?;
@@ -118,6 +323,15 @@ pub fn create_podcast_dir(config: &ServerOverlay, pod_title: String) -> Result<P
 
 /// Parse the playlist at `current_node`(from the tui tree) and return the media paths
 pub fn playlist_get_vec(playlist_path: &Path) -> Result<Vec<String>> {
+    let items = playlist_get_entries(playlist_path)?;
+    // TODO: refactor to return better values
+    Ok(items.into_iter().map(|v| v.value.to_string()).collect())
+}
+
+/// Parse the playlist at `playlist_path` and return the entries with their titles and durations.
+///
+/// Relative paths are made absolute to the directory of the playlist.
+pub fn playlist_get_entries(playlist_path: &Path) -> Result<Vec<crate::playlist::PlaylistEntry>> {
     // get the directory the playlist is in
     let playlist_directory = absolute_path(
         playlist_path
@@ -125,16 +339,12 @@ pub fn playlist_get_vec(playlist_path: &Path) -> Result<Vec<String>> {
             .ok_or_else(|| anyhow!("cannot get directory from playlist path"))?,
     )?;
     let playlist_str = std::fs::read_to_string(playlist_path)?;
-    let items = crate::playlist::decode(&playlist_str)
+    let mut items = crate::playlist::decode_entries(&playlist_str)
         .with_context(|| playlist_path.display().to_string())?;
-    let mut vec = Vec::with_capacity(items.len());
-    for mut item in items {
-        item.absoluteize(&playlist_directory);
-
-        // TODO: refactor to return better values
-        vec.push(item.to_string());
+    for item in &mut items {
+        item.value.absoluteize(&playlist_directory);
     }
-    Ok(vec)
+    Ok(items)
 }
 
 /// Some helper functions for dealing with Unicode strings.
@@ -364,6 +574,39 @@ mod tests {
         assert_eq!("陈一发儿".substr(3, 1), "儿");
     }
 
+    #[test]
+    fn should_migrate_data_files() {
+        let base = tempfile::tempdir().unwrap();
+        let from = base.path().join("config");
+        let to = base.path().join("data");
+        std::fs::create_dir_all(&from).unwrap();
+        std::fs::create_dir_all(&to).unwrap();
+
+        std::fs::write(from.join("data.db"), "old podcasts").unwrap();
+        std::fs::write(from.join("playlist.log"), "old playlist").unwrap();
+        std::fs::write(from.join("config.toml"), "config").unwrap();
+        std::fs::write(to.join("playlist.log"), "new playlist").unwrap();
+
+        assert_eq!(migrate_data_files(&from, &to).unwrap(), 1);
+        assert_eq!(
+            std::fs::read_to_string(to.join("data.db")).unwrap(),
+            "old podcasts"
+        );
+        assert!(!from.join("data.db").exists());
+        // existing files are not overwritten
+        assert_eq!(
+            std::fs::read_to_string(to.join("playlist.log")).unwrap(),
+            "new playlist"
+        );
+        assert!(from.join("playlist.log").exists());
+        // config stays in place
+        assert!(from.join("config.toml").exists());
+        assert!(!to.join("config.toml").exists());
+
+        assert_eq!(migrate_data_files(&from, &to).unwrap(), 0);
+        assert_eq!(migrate_data_files(&to, &to).unwrap(), 0);
+    }
+
     #[test]
     fn display_with_to_string() {
         fn nested() -> impl Display {
@@ -456,4 +699,16 @@ mod tests {
         assert_eq!(iter.next(), None);
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn should_check_profile_names() {
+        assert!(check_profile_name("work").is_ok());
+        assert!(check_profile_name("kids_music-2").is_ok());
+
+        assert!(check_profile_name("").is_err());
+        // no paths
+        assert!(check_profile_name("../work").is_err());
+        assert!(check_profile_name("a/b").is_err());
+        assert!(check_profile_name("my profile").is_err());
+    }
 }
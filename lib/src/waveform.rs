@@ -0,0 +1,107 @@
+//! Coarse waveforms of tracks, shown behind the progress bar to find quiet and loud parts when seeking.
+//!
+//! A waveform is the loudness of consecutive parts of a track, each as a level from `0` (silence)
+//! to `255` (the loudest part of the file).
+
+use std::time::Duration;
+
+/// The amount of levels in the waveform of a track, regardless of its length.
+pub const WAVEFORM_LEN: usize = 256;
+
+/// The length of audio each level of a file covers, before being [resampled](resample) to a track.
+pub const LEVEL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Convert the RMS `levels` of a file to levels relative to its loudest part.
+#[must_use]
+pub fn normalize(levels: &[f32]) -> Vec<u8> {
+    let max = levels.iter().copied().fold(0.0f32, f32::max);
+    if max <= 0.0 {
+        return vec![0; levels.len()];
+    }
+
+    levels
+        .iter()
+        .map(|v| {
+            // always in range, as "v / max" is at most 1
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let level = (v / max * 255.0).round() as u8;
+            level
+        })
+        .collect()
+}
+
+/// Get the [`LEVEL_INTERVAL`] `levels` of a file between `start` and `end`, like for a cue sheet track.
+///
+/// `end` being `None` means until the end of the file.
+#[must_use]
+pub fn slice(levels: &[u8], start: Duration, end: Option<Duration>) -> &[u8] {
+    let index = |time: Duration| {
+        usize::try_from(time.as_millis() / LEVEL_INTERVAL.as_millis())
+            .unwrap_or(usize::MAX)
+            .min(levels.len())
+    };
+    let start = index(start);
+    let end = end.map_or(levels.len(), index).max(start);
+
+    &levels[start..end]
+}
+
+/// Resample `levels` to `len` levels, keeping the loudest level of the parts that are merged.
+#[must_use]
+pub fn resample(levels: &[u8], len: usize) -> Vec<u8> {
+    if levels.is_empty() {
+        return Vec::new();
+    }
+
+    (0..len)
+        .map(|idx| {
+            let start = idx * levels.len() / len;
+            let end = ((idx + 1) * levels.len() / len).max(start + 1);
+            levels[start..end].iter().copied().max().unwrap_or_default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{normalize, resample, slice};
+
+    #[test]
+    fn should_normalize_to_loudest() {
+        assert_eq!(normalize(&[0.0, 0.25, 0.5]), [0, 128, 255]);
+        assert_eq!(normalize(&[0.0, 0.0]), [0, 0]);
+        assert!(normalize(&[]).is_empty());
+    }
+
+    #[test]
+    fn should_slice_by_time() {
+        let levels: Vec<u8> = (0..50).collect();
+
+        assert_eq!(
+            slice(
+                &levels,
+                Duration::from_secs(1),
+                Some(Duration::from_secs(2))
+            ),
+            (10..20).collect::<Vec<u8>>()
+        );
+        assert_eq!(
+            slice(&levels, Duration::from_secs(4), None),
+            (40..50).collect::<Vec<u8>>()
+        );
+        // beyond the end of the file
+        assert!(slice(&levels, Duration::from_secs(10), None).is_empty());
+    }
+
+    #[test]
+    fn should_resample() {
+        assert_eq!(resample(&[1, 5, 2, 0, 7, 3], 3), [5, 2, 7]);
+        // stretched
+        assert_eq!(resample(&[1, 5], 4), [1, 1, 5, 5]);
+        assert!(resample(&[], 4).is_empty());
+    }
+}
@@ -45,10 +45,17 @@ pub struct Xywh {
 
 impl From<&CoverArt> for Xywh {
     fn from(value: &CoverArt) -> Self {
-        // TODO: actually apply scale
+        let default = Self::default();
+        // configs from before the scale was applied may have any value
+        let width_between_1_100 = u32::try_from(value.size_scale)
+            .ok()
+            .filter(|v| (1..=100).contains(v))
+            .unwrap_or(default.width_between_1_100);
+
         Self {
+            width_between_1_100,
             align: AlignmentWrap(value.align),
-            ..Default::default()
+            ..default
         }
     }
 }
@@ -98,7 +105,13 @@ impl Xywh {
     }
 
     pub fn zoom_out(&mut self) {
-        self.width_between_1_100 = self.width_between_1_100.saturating_sub(1);
+        self.width_between_1_100 = self.width_between_1_100.saturating_sub(1).max(1);
+    }
+
+    /// Get the width to store as [`CoverArt::size_scale`].
+    #[must_use]
+    pub fn size_scale(&self) -> i8 {
+        i8::try_from(self.width_between_1_100).unwrap_or(i8::MAX)
     }
 
     pub fn update_size(&self, image: &DynamicImage) -> Result<Self> {
@@ -0,0 +1,95 @@
+//! Audiobook mode, which continues a audiobook where it was left, even when it is started from its first file.
+//!
+//! Directories are marked as audiobooks in the library view or detected on first play,
+//! see [`audiobook_ops`](termusiclib::new_database::audiobook_ops).
+
+use std::path::Path;
+
+use termusiclib::new_database::Database;
+use termusiclib::new_database::audiobook_ops::{self, Audiobook};
+use termusiclib::track::{MediaTypes, Track};
+
+/// Get the enabled audiobook `track` is part of, detecting new audiobooks.
+#[must_use]
+pub fn load(db: &Database, track: &Track) -> Option<Audiobook> {
+    let path = track_path(track)?;
+
+    audiobook_ops::audiobook_for_track(&db.get_connection(), path)
+        .inspect_err(|err| warn!("Error loading the audiobook of {}: {err:#}", path.display()))
+        .ok()
+        .flatten()
+}
+
+/// Get the index in `queue` to continue the audiobook `dir` at, when starting the track at `index`.
+///
+/// The audiobook is only continued if it is started fresh, meaning no earlier track of the queue is part of it,
+/// and the track it was last played at is queued.
+#[must_use]
+pub fn continue_index(
+    queue: &[Track],
+    index: usize,
+    dir: &Path,
+    last_track: &Path,
+) -> Option<usize> {
+    let current = queue.get(index).and_then(track_path)?;
+    if current == last_track {
+        return None;
+    }
+
+    let in_book = |track: &Track| track_path(track).is_some_and(|path| path.starts_with(dir));
+    if !in_book(&queue[index]) || queue[..index].iter().any(in_book) {
+        return None;
+    }
+
+    queue
+        .iter()
+        .position(|track| track_path(track) == Some(last_track))
+}
+
+/// Get the path of `track`, if it is a local file.
+#[must_use]
+pub fn track_path(track: &Track) -> Option<&Path> {
+    match track.inner() {
+        MediaTypes::Track(data) => Some(data.path()),
+        MediaTypes::Radio(_) | MediaTypes::Podcast(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+    use termusiclib::track::Track;
+
+    use super::continue_index;
+
+    #[test]
+    fn should_continue_fresh_audiobooks() {
+        let dir = Path::new("/books/Some Book");
+        let queue: Vec<Track> = [
+            "/music/song.mp3",
+            "/books/Some Book/01.mp3",
+            "/books/Some Book/02.mp3",
+            "/books/Some Book/03.mp3",
+        ]
+        .into_iter()
+        // not existing files only lack metadata
+        .map(|path| Track::read_track_from_path(path).unwrap())
+        .collect();
+        let last_track = Path::new("/books/Some Book/03.mp3");
+
+        assert_eq!(continue_index(&queue, 1, dir, last_track), Some(3));
+        // already at the last played track
+        assert_eq!(continue_index(&queue, 3, dir, last_track), None);
+        // the audiobook is already being played
+        assert_eq!(continue_index(&queue, 2, dir, last_track), None);
+        // not part of the audiobook
+        assert_eq!(continue_index(&queue, 0, dir, last_track), None);
+        // the last played track is not queued
+        assert_eq!(
+            continue_index(&queue, 1, dir, Path::new("/books/Some Book/04.mp3")),
+            None
+        );
+    }
+}
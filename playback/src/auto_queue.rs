@@ -0,0 +1,224 @@
+//! Auto-queue, which keeps the playlist filled with tracks from the library database.
+//!
+//! See [`AutoQueueSettings`] for how tracks are chosen.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use rand::Rng;
+use termusiclib::config::v2::server::AutoQueueSettings;
+use termusiclib::new_database::Database;
+use termusiclib::new_database::track_ops::{self, RowOrdering, TrackRead};
+use termusiclib::track::{Track, TrackData};
+
+use crate::playlist::Playlist;
+
+/// Add tracks from the library to `playlist` until at least `settings.min_upcoming` tracks come after the current track.
+///
+/// Tracks already in the playlist, marked to never auto-play, or hidden by the parental filter are not added. Returns how many tracks were added.
+pub fn refill(
+    playlist: &mut Playlist,
+    db: &Database,
+    settings: &AutoQueueSettings,
+) -> Result<usize> {
+    let upcoming = playlist
+        .len()
+        .saturating_sub(playlist.get_current_track_index() + 1);
+    let amount = usize::from(settings.min_upcoming.get()).saturating_sub(upcoming);
+    if amount == 0 {
+        return Ok(0);
+    }
+
+    let mut candidates = track_ops::get_all_tracks(&db.get_connection(), RowOrdering::IdAsc)
+        .context("get library tracks")?;
+    let no_autoplay = track_ops::get_no_autoplay_paths(&db.get_connection())
+        .context("get never auto-played tracks")?;
+
+    let last = {
+        let queued: HashSet<&Path> = playlist
+            .tracks()
+            .iter()
+            .filter_map(Track::as_track)
+            .map(TrackData::path)
+            .collect();
+        let last_path = playlist
+            .tracks()
+            .last()
+            .and_then(Track::as_track)
+            .map(TrackData::path);
+        let last = last_path
+            .and_then(|path| candidates.iter().find(|v| v.as_pathbuf() == path))
+            .cloned();
+
+        let parental = playlist.parental();
+        candidates.retain(|v| {
+            let path = v.as_pathbuf();
+            !queued.contains(path.as_path())
+                && !no_autoplay.contains(&path)
+                && parental.allows_genre(v.genre.as_deref())
+        });
+
+        last
+    };
+
+    let paths: Vec<String> = choose_tracks(
+        candidates,
+        last.as_ref(),
+        settings,
+        amount,
+        &mut rand::rng(),
+    )
+    .into_iter()
+    .map(|v| v.to_string_lossy().to_string())
+    .collect();
+    if paths.is_empty() {
+        return Ok(0);
+    }
+
+    let added = paths.len();
+    if let Err(err) = playlist.add_playlist(&paths) {
+        warn!("Error adding auto-queue tracks: {err}");
+    }
+
+    Ok(added)
+}
+
+/// Choose up to `amount` distinct tracks of `candidates`, randomly by their [`track_weight`] against `last`.
+fn choose_tracks<R: Rng>(
+    mut candidates: Vec<TrackRead>,
+    last: Option<&TrackRead>,
+    settings: &AutoQueueSettings,
+    amount: usize,
+    rng: &mut R,
+) -> Vec<PathBuf> {
+    let mut weights: Vec<u32> = candidates
+        .iter()
+        .map(|v| track_weight(v, last, settings))
+        .collect();
+    let mut chosen = Vec::with_capacity(amount.min(candidates.len()));
+
+    while chosen.len() < amount && !candidates.is_empty() {
+        let total: u32 = weights.iter().sum();
+        let mut pick = rng.random_range(0..total);
+        let index = weights
+            .iter()
+            .position(|weight| {
+                if pick < *weight {
+                    return true;
+                }
+                pick -= weight;
+                false
+            })
+            .unwrap_or_default();
+
+        weights.swap_remove(index);
+        chosen.push(candidates.swap_remove(index).as_pathbuf());
+    }
+
+    chosen
+}
+
+/// Get how likely `track` is chosen to follow `last`, see [`AutoQueueSettings`].
+fn track_weight(track: &TrackRead, last: Option<&TrackRead>, settings: &AutoQueueSettings) -> u32 {
+    let mut weight = 1;
+    let Some(last) = last else {
+        return weight;
+    };
+
+    if same_tag(
+        track.artist_display.as_deref(),
+        last.artist_display.as_deref(),
+    ) {
+        weight += u32::from(settings.artist_weight);
+    }
+    if same_tag(track.genre.as_deref(), last.genre.as_deref()) {
+        weight += u32::from(settings.genre_weight);
+    }
+
+    weight
+}
+
+/// Check if both tags are set and the same, ignoring case.
+fn same_tag(a: Option<&str>, b: Option<&str>) -> bool {
+    a.zip(b).is_some_and(|(a, b)| a.eq_ignore_ascii_case(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::path::PathBuf;
+
+    use pretty_assertions::assert_eq;
+    use termusiclib::config::v2::server::AutoQueueSettings;
+    use termusiclib::ids::TrackId;
+    use termusiclib::new_database::track_ops::TrackRead;
+
+    use super::{choose_tracks, track_weight};
+
+    fn track(name: &str, artist: Option<&str>, genre: Option<&str>) -> TrackRead {
+        TrackRead {
+            id: TrackId(0),
+            file_dir: PathBuf::from("/music"),
+            file_stem: name.into(),
+            file_ext: "mp3".into(),
+            duration: None,
+            last_position: None,
+            album: None,
+            title: None,
+            genre: genre.map(ToString::to_string),
+            artist_display: artist.map(ToString::to_string),
+            artists: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn should_weight_by_artist_and_genre() {
+        let settings = AutoQueueSettings::default();
+        let last = track("last", Some("Artist"), Some("Rock"));
+
+        let both = track("both", Some("artist"), Some("Rock"));
+        let artist = track("artist", Some("Artist"), Some("Pop"));
+        let genre = track("genre", None, Some("rock"));
+        let none = track("none", Some("Other"), None);
+
+        assert_eq!(track_weight(&both, Some(&last), &settings), 7);
+        assert_eq!(track_weight(&artist, Some(&last), &settings), 5);
+        assert_eq!(track_weight(&genre, Some(&last), &settings), 3);
+        assert_eq!(track_weight(&none, Some(&last), &settings), 1);
+        // random without a track to compare to
+        assert_eq!(track_weight(&both, None, &settings), 1);
+
+        let settings = AutoQueueSettings {
+            artist_weight: 0,
+            genre_weight: 0,
+            ..Default::default()
+        };
+        assert_eq!(track_weight(&both, Some(&last), &settings), 1);
+    }
+
+    #[test]
+    fn should_choose_distinct_tracks() {
+        let settings = AutoQueueSettings::default();
+        let candidates = vec![
+            track("a", Some("Artist"), None),
+            track("b", None, Some("Rock")),
+            track("c", None, None),
+        ];
+
+        let chosen = choose_tracks(candidates.clone(), None, &settings, 2, &mut rand::rng());
+        assert_eq!(chosen.len(), 2);
+        assert_eq!(chosen.iter().collect::<HashSet<_>>().len(), 2);
+
+        // never more than there are candidates
+        let chosen = choose_tracks(candidates, None, &settings, 5, &mut rand::rng());
+        assert_eq!(
+            chosen.into_iter().collect::<HashSet<_>>(),
+            HashSet::from([
+                PathBuf::from("/music/a.mp3"),
+                PathBuf::from("/music/b.mp3"),
+                PathBuf::from("/music/c.mp3"),
+            ])
+        );
+    }
+}
@@ -14,6 +14,8 @@ use gstreamer::{ClockTime, StateChangeError, StateChangeSuccess};
 use gstreamer::{Element, SeekFlags, SeekType, event::Seek};
 use parking_lot::Mutex;
 use termusiclib::config::ServerOverlay;
+use termusiclib::config::v2::server::ReplayGainMode;
+use termusiclib::player::{AbRepeat, StreamInfo};
 use termusiclib::track::{MediaTypes, Track};
 use tokio::sync::mpsc;
 
@@ -171,6 +173,7 @@ pub struct GStreamerBackend {
     gapless: bool,
     icmd_tx: mpsc::Sender<PlayerInternalCmd>,
     media_title: Arc<Mutex<String>>,
+    stream_info: Arc<Mutex<StreamInfo>>,
     _bus_watch_guard: BusWatchGuard,
 }
 
@@ -223,12 +226,27 @@ impl GStreamerBackend {
             .build()
             .expect("make audio sink error");
 
+        // "rgvolume" applies the gain from the ReplayGain tags in the stream
+        let replay_gain = match config.settings.player.replay_gain {
+            ReplayGainMode::Off => None,
+            mode => gst::ElementFactory::make("rgvolume")
+                .name("replaygain")
+                .property("album-mode", mode == ReplayGainMode::Album)
+                .build()
+                .inspect_err(|err| warn!("Failed to make rgvolume, ReplayGain is disabled: {err}"))
+                .ok(),
+        };
+
+        let elements: Vec<&Element> = replay_gain.iter().chain([&tempo, &sink]).collect();
+
         let bin = gst::Bin::with_name("audiosink");
-        bin.add_many([&tempo, &sink]).expect("add many failed");
-        gst::Element::link_many([&tempo, &sink]).expect("link many failed");
-        tempo.sync_state_with_parent().expect("sync state failed");
+        bin.add_many(&elements).expect("add many failed");
+        gst::Element::link_many(&elements).expect("link many failed");
+        for element in &elements[..elements.len() - 1] {
+            element.sync_state_with_parent().expect("sync state failed");
+        }
 
-        let pad = tempo
+        let pad = elements[0]
             .static_pad("sink")
             .expect("Failed to get a static pad from equalizer.");
 
@@ -259,6 +277,8 @@ impl GStreamerBackend {
 
         let media_title = Arc::new(Mutex::new(String::new()));
         let media_title_internal = media_title.clone();
+        let stream_info = Arc::new(Mutex::new(StreamInfo::default()));
+        let stream_info_internal = stream_info.clone();
         let playbin = PlaybinWrap::new(playbin);
         let playbin_clone = playbin.clone();
         let main_tx_watcher = icmd_tx.clone();
@@ -277,6 +297,7 @@ impl GStreamerBackend {
                     &eos_watcher,
                     &send_atf_watcher,
                     &media_title_internal,
+                    &stream_info_internal,
                     &error_watcher,
                 )
             })
@@ -302,6 +323,7 @@ impl GStreamerBackend {
             gapless,
             icmd_tx: icmd_tx_c,
             media_title,
+            stream_info,
             _bus_watch_guard: bus_watch,
         };
 
@@ -320,7 +342,40 @@ impl GStreamerBackend {
         this
     }
 
+    /// Update the stored [`StreamInfo`] from the codec and bitrate in `tags`, if present.
+    ///
+    /// `GStreamer` does not provide the sample rate as a tag, so it stays unknown.
+    fn update_stream_info(
+        tags: &gst::TagList,
+        stream_info: &Arc<Mutex<StreamInfo>>,
+        main_tx: &mpsc::Sender<PlayerInternalCmd>,
+    ) {
+        let codec = tags
+            .get::<gst::tags::AudioCodec>()
+            .map(|v| v.get().to_string());
+        let bitrate = tags
+            .get::<gst::tags::Bitrate>()
+            .or_else(|| tags.get::<gst::tags::NominalBitrate>())
+            .map(|v| v.get())
+            .filter(|v| *v > 0);
+
+        let mut stream_info_w = stream_info.lock();
+        let old = stream_info_w.clone();
+        if let Some(codec) = codec {
+            stream_info_w.codec = Some(codec);
+        }
+        if let Some(bitrate) = bitrate {
+            stream_info_w.bitrate = Some(bitrate);
+        }
+
+        if *stream_info_w != old {
+            drop(stream_info_w);
+            let _ = main_tx.blocking_send(PlayerInternalCmd::MetadataChanged);
+        }
+    }
+
     /// Check the given events(Messages) and process them
+    #[allow(clippy::too_many_arguments)]
     fn watch_fn(
         msg: &gst::Message,
         playbin: &PlaybinWrap,
@@ -328,6 +383,7 @@ impl GStreamerBackend {
         eos_watcher: &Arc<AtomicBool>,
         send_atf_watcher: &Arc<AtomicBool>,
         media_title: &Arc<Mutex<String>>,
+        stream_info: &Arc<Mutex<StreamInfo>>,
         error_watcher: &Arc<Mutex<Option<String>>>,
     ) -> ControlFlow {
         match msg.view() {
@@ -339,8 +395,9 @@ impl GStreamerBackend {
                     .expect("Unable to send message to main()");
                 eos_watcher.store(true, std::sync::atomic::Ordering::SeqCst);
 
-                // clear stored title on end
+                // clear stored title and stream info on end
                 media_title.lock().clear();
+                *stream_info.lock() = StreamInfo::default();
                 // let _ = main_tx.blocking_send(PlayerInternalCmd::MetadataChanged);
             }
             gst::MessageView::StreamStart(_e) => {
@@ -354,8 +411,9 @@ impl GStreamerBackend {
                 send_atf_watcher.store(false, Ordering::SeqCst);
                 eos_watcher.store(false, std::sync::atomic::Ordering::SeqCst);
 
-                // clear stored title on stream start (should work without conflicting in ::Tag)
+                // clear stored title and stream info on stream start (should work without conflicting in ::Tag)
                 media_title.lock().clear();
+                *stream_info.lock() = StreamInfo::default();
                 // let _ = main_tx.blocking_send(PlayerInternalCmd::MetadataChanged);
 
                 // HACK: gstreamer does not handle seek events before some undocumented time, see other note in main_rx handler
@@ -408,6 +466,7 @@ impl GStreamerBackend {
                     *media_title.lock() = title.get().into();
                     let _ = main_tx.blocking_send(PlayerInternalCmd::MetadataChanged);
                 }
+                Self::update_stream_info(&tag.tags(), stream_info, main_tx);
                 // if let Some(artist) = tag.tags().get::<gst::tags::Artist>() {
                 //     info!("Artist: {}", artist.get());
                 //     // *media_title.lock() = artist.get().to_string();
@@ -607,6 +666,7 @@ impl PlayerTrait for GStreamerBackend {
         Some(PlayerProgress {
             position,
             total_duration,
+            ab_repeat: AbRepeat::default(),
         })
     }
 
@@ -628,12 +688,9 @@ impl PlayerTrait for GStreamerBackend {
 
     fn media_info(&self) -> MediaInfo {
         let media_title_r = self.media_title.lock();
-        if media_title_r.is_empty() {
-            MediaInfo::default()
-        } else {
-            MediaInfo {
-                media_title: Some(media_title_r.clone()),
-            }
+        MediaInfo {
+            media_title: (!media_title_r.is_empty()).then(|| media_title_r.clone()),
+            stream_info: self.stream_info.lock().clone(),
         }
     }
 }
@@ -650,7 +707,7 @@ impl Drop for GStreamerBackend {
 /// Helper function to consistently set the `uri` on `playbin` from a [`Track`]
 fn set_uri_from_track(playbin: &PlaybinWrap, track: &Track) {
     match track.inner() {
-        MediaTypes::Track(track_data) => playbin.set_uri(track_data.path().to_uri()),
+        MediaTypes::Track(track_data) => playbin.set_uri(track_data.audio_path().to_uri()),
         MediaTypes::Radio(radio_track_data) => playbin.set_uri(radio_track_data.url()),
         MediaTypes::Podcast(podcast_track_data) => playbin.set_uri(podcast_track_data.url()),
     }
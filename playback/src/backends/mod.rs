@@ -8,6 +8,7 @@ use crate::{PlayerCmdSender, PlayerTrait};
 mod gstreamer;
 #[cfg(feature = "mpv")]
 mod mpv;
+mod null;
 // public for benching lower modules
 pub(crate) mod rusty;
 
@@ -19,6 +20,25 @@ pub enum BackendSelect {
     GStreamer,
     #[default]
     Rusty,
+    /// Plays nothing, for development, testing and demos
+    Null,
+}
+
+impl BackendSelect {
+    /// Get the extensions of the files this backend can play, lowercase and without the dot.
+    #[must_use]
+    pub fn supported_extensions(self) -> &'static [&'static str] {
+        match self {
+            // both use plugins that cover all of the known formats
+            #[cfg(feature = "mpv")]
+            Self::Mpv => termusiclib::utils::SUPPORTED_EXTENSIONS,
+            #[cfg(feature = "gst")]
+            Self::GStreamer => termusiclib::utils::SUPPORTED_EXTENSIONS,
+            Self::Rusty => rusty::SUPPORTED_EXTENSIONS,
+            // pretends to play everything
+            Self::Null => termusiclib::utils::SUPPORTED_EXTENSIONS,
+        }
+    }
 }
 
 /// Error for when [`ThemeColor`] parsing fails
@@ -49,6 +69,7 @@ impl TryFrom<ConfigBackend> for BackendSelect {
             #[cfg(feature = "mpv")]
             ConfigBackend::Mpv => Self::Mpv,
             ConfigBackend::Rusty => Self::Rusty,
+            ConfigBackend::Null => Self::Null,
             #[allow(unreachable_patterns)] // allow as a catch-all because of feature gates
             _ => {
                 return Err(BackendSelectConvertError::UnavailableBackend(
@@ -67,6 +88,7 @@ pub enum Backend {
     Rusty(rusty::RustyBackend),
     #[cfg(feature = "gst")]
     GStreamer(gstreamer::GStreamerBackend),
+    Null(null::NullBackend),
 }
 
 impl Backend {
@@ -82,6 +104,7 @@ impl Backend {
             #[cfg(feature = "gst")]
             BackendSelect::GStreamer => Self::new_gstreamer(&config, cmd_tx),
             BackendSelect::Rusty => Self::new_rusty(config, cmd_tx),
+            BackendSelect::Null => Self::new_null(&config, cmd_tx),
         }
     }
 
@@ -119,6 +142,26 @@ impl Backend {
         Self::Mpv(mpv::MpvBackend::new(&config_read, cmd_tx))
     }
 
+    /// Explicitly choose Backend [`NullBackend`](null::NullBackend)
+    fn new_null(config: &SharedServerSettings, cmd_tx: PlayerCmdSender) -> Self {
+        info!("Using Backend \"null\", nothing will be played");
+        let config_read = config.read();
+        Self::Null(null::NullBackend::new(&config_read, cmd_tx))
+    }
+
+    /// Get the name of the backend, like it is set in the config.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "mpv")]
+            Backend::Mpv(_) => "mpv",
+            #[cfg(feature = "gst")]
+            Backend::GStreamer(_) => "gst",
+            Backend::Rusty(_) => "rusty",
+            Backend::Null(_) => "null",
+        }
+    }
+
     #[must_use]
     pub fn as_player(&self) -> &dyn PlayerTrait {
         match self {
@@ -127,6 +170,7 @@ impl Backend {
             #[cfg(feature = "gst")]
             Backend::GStreamer(v) => v,
             Backend::Rusty(v) => v,
+            Backend::Null(v) => v,
         }
     }
 
@@ -138,6 +182,7 @@ impl Backend {
             #[cfg(feature = "gst")]
             Backend::GStreamer(v) => v,
             Backend::Rusty(v) => v,
+            Backend::Null(v) => v,
         }
     }
 }
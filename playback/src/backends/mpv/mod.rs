@@ -12,9 +12,11 @@ use libmpv::{
 };
 use parking_lot::Mutex;
 use termusiclib::config::ServerOverlay;
+use termusiclib::config::v2::server::ReplayGainMode;
+use termusiclib::player::{AbRepeat, StreamInfo};
 use termusiclib::track::{MediaTypes, Track};
 
-use crate::{MediaInfo, PlayerCmd, PlayerProgress, PlayerTrait, Speed, Volume};
+use crate::{MediaInfo, OutputInfo, PlayerCmd, PlayerProgress, PlayerTrait, Speed, Volume};
 
 pub type ArcTotalDuration = Arc<Mutex<Option<Duration>>>;
 
@@ -27,6 +29,9 @@ pub struct MpvBackend {
     position: Arc<Mutex<Duration>>,
     total_duration: ArcTotalDuration,
     media_title: Arc<Mutex<String>>,
+    stream_info: Arc<Mutex<StreamInfo>>,
+    /// The configured `audio-device`
+    audio_device: String,
     // cmd_tx: crate::PlayerCmdSender,
 }
 
@@ -54,9 +59,11 @@ impl MpvBackend {
         let position = Arc::new(Mutex::new(Duration::default()));
         let total_duration = Arc::new(Mutex::new(None));
         let media_title = Arc::new(Mutex::new(String::new()));
+        let stream_info = Arc::new(Mutex::new(StreamInfo::default()));
         let position_inside = position.clone();
         let total_duration_inside = total_duration.clone();
         let media_title_inside = media_title.clone();
+        let stream_info_inside = stream_info.clone();
 
         let mpv = Mpv::new().expect("Couldn't initialize MpvHandlerBuilder");
         mpv.set_property("vo", "null")
@@ -74,6 +81,15 @@ impl MpvBackend {
         let gapless_setting = if gapless { "yes" } else { "no" };
         mpv.set_property("gapless-audio", gapless_setting)
             .expect("gapless setting failed");
+        // mpv reads the ReplayGain tags itself
+        let replay_gain_setting = match config.settings.player.replay_gain {
+            ReplayGainMode::Off => "no",
+            ReplayGainMode::Track => "track",
+            ReplayGainMode::Album => "album",
+        };
+        if let Err(err) = mpv.set_property("replaygain", replay_gain_setting) {
+            warn!("Failed to set \"replaygain\" property: {err:?}");
+        }
 
         let icmd_tx = command_tx.clone();
         std::thread::Builder::new()
@@ -85,6 +101,7 @@ impl MpvBackend {
                     &command_rx,
                     &cmd_tx,
                     &media_title_inside,
+                    &stream_info_inside,
                     &position_inside,
                     &total_duration_inside,
                 );
@@ -99,6 +116,8 @@ impl MpvBackend {
             position,
             total_duration,
             media_title,
+            stream_info,
+            audio_device: config.settings.backends.mpv.audio_device.clone(),
         }
     }
 
@@ -109,6 +128,7 @@ impl MpvBackend {
         icmd_rx: &Receiver<PlayerInternalCmd>,
         cmd_tx: &crate::PlayerCmdSender,
         media_title: &Arc<Mutex<String>>,
+        stream_info: &Arc<Mutex<StreamInfo>>,
         position: &Arc<Mutex<Duration>>,
         total_duration: &ArcTotalDuration,
     ) {
@@ -125,11 +145,21 @@ impl MpvBackend {
         ev_ctx
             .observe_property("media-title", Format::String, 2)
             .expect("failed to watch media-title");
+        ev_ctx
+            .observe_property("audio-codec-name", Format::String, 3)
+            .expect("failed to watch audio-codec-name");
+        ev_ctx
+            .observe_property("audio-params/samplerate", Format::Int64, 4)
+            .expect("failed to watch audio-params/samplerate");
+        ev_ctx
+            .observe_property("audio-bitrate", Format::Double, 5)
+            .expect("failed to watch audio-bitrate");
 
         let mut args = MpvEventArgs {
             icmd_tx,
             cmd_tx,
             media_title,
+            stream_info,
             position,
             total_duration,
             send_atf: false,
@@ -187,8 +217,9 @@ impl MpvBackend {
                     let _ = args.icmd_tx.send(PlayerInternalCmd::Eos);
                 }
 
-                // clear stored title on end
+                // clear stored title and stream info on end
                 args.media_title.lock().clear();
+                *args.stream_info.lock() = StreamInfo::default();
                 args.send_atf = false;
             }
             Event::Seek => {
@@ -235,6 +266,27 @@ impl MpvBackend {
                         let _ = args.cmd_tx.send(PlayerCmd::MetadataChanged);
                     }
                 }
+                "audio-codec-name" => {
+                    if let PropertyData::Str(codec) = change {
+                        let codec = (!codec.is_empty()).then(|| codec.to_string());
+                        args.update_stream_info(|info| info.codec = codec);
+                    }
+                }
+                "audio-params/samplerate" => {
+                    if let PropertyData::Int64(sample_rate) = change {
+                        let sample_rate = u32::try_from(sample_rate).ok().filter(|v| *v > 0);
+                        args.update_stream_info(|info| info.sample_rate = sample_rate);
+                    }
+                }
+                "audio-bitrate" => {
+                    if let PropertyData::Double(bitrate) = change {
+                        // only keep kbps precision, as mpv updates the bitrate very often for VBR
+                        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                        let bitrate = ((bitrate / 1000.0).round() as u32).saturating_mul(1000);
+                        let bitrate = (bitrate > 0).then_some(bitrate);
+                        args.update_stream_info(|info| info.bitrate = bitrate);
+                    }
+                }
                 &_ => {
                     // left for debug
                     // error!(
@@ -306,6 +358,7 @@ struct MpvEventArgs<'a> {
     icmd_tx: &'a Sender<PlayerInternalCmd>,
     cmd_tx: &'a crate::PlayerCmdSender,
     media_title: &'a Arc<Mutex<String>>,
+    stream_info: &'a Arc<Mutex<StreamInfo>>,
     position: &'a Arc<Mutex<Duration>>,
     total_duration: &'a ArcTotalDuration,
 
@@ -315,6 +368,20 @@ struct MpvEventArgs<'a> {
     send_atf: bool,
 }
 
+impl MpvEventArgs<'_> {
+    /// Apply `update` to the stored [`StreamInfo`] and send [`PlayerCmd::MetadataChanged`] if it changed.
+    fn update_stream_info(&self, update: impl FnOnce(&mut StreamInfo)) {
+        let mut stream_info = self.stream_info.lock();
+        let old = stream_info.clone();
+        update(&mut stream_info);
+
+        if *stream_info != old {
+            drop(stream_info);
+            let _ = self.cmd_tx.send(PlayerCmd::MetadataChanged);
+        }
+    }
+}
+
 /// Format a duration in "SS.mm" format
 ///
 /// Note that mpv supports "HH:MM:SS.mmmm" format, but only the second and millisecond part is used
@@ -327,7 +394,7 @@ fn format_duration(dur: Duration) -> String {
 
 fn track_to_string(track: &Track) -> String {
     match track.inner() {
-        MediaTypes::Track(track_data) => track_data.path().to_string_lossy().to_string(),
+        MediaTypes::Track(track_data) => track_data.audio_path().to_string_lossy().to_string(),
         MediaTypes::Radio(radio_track_data) => radio_track_data.url().to_string(),
         MediaTypes::Podcast(podcast_track_data) => podcast_track_data.url().to_string(),
     }
@@ -396,6 +463,7 @@ impl PlayerTrait for MpvBackend {
         Some(PlayerProgress {
             position: Some(*self.position.lock()),
             total_duration: *self.total_duration.lock(),
+            ab_repeat: AbRepeat::default(),
         })
     }
 
@@ -419,14 +487,18 @@ impl PlayerTrait for MpvBackend {
             .expect("failed to queue next");
     }
 
+    fn output_info(&self) -> OutputInfo {
+        OutputInfo {
+            device: Some(self.audio_device.clone()),
+            ..Default::default()
+        }
+    }
+
     fn media_info(&self) -> MediaInfo {
         let media_title_r = self.media_title.lock();
-        if media_title_r.is_empty() {
-            MediaInfo::default()
-        } else {
-            MediaInfo {
-                media_title: Some(media_title_r.clone()),
-            }
+        MediaInfo {
+            media_title: (!media_title_r.is_empty()).then(|| media_title_r.clone()),
+            stream_info: self.stream_info.lock().clone(),
         }
     }
 }
@@ -0,0 +1,350 @@
+//! A backend that plays nothing and only advances a position clock, as if the tracks were played.
+//!
+//! Useful to develop the TUI, to test in CI and to record demos without any audio hardware.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use termusiclib::config::ServerOverlay;
+use termusiclib::player::AbRepeat;
+use termusiclib::track::Track;
+
+use crate::{
+    MediaInfo, OutputInfo, PlayerCmd, PlayerCmdSender, PlayerProgress, PlayerTrait, Speed, Volume,
+};
+
+/// How often the clock is checked for the end of the current track.
+const TICK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long before the end of a track [`PlayerCmd::AboutToFinish`] is sent.
+const ABOUT_TO_FINISH: Duration = Duration::from_secs(2);
+
+/// The speed at which the clock runs in real time.
+const NORMAL_SPEED: Speed = 10;
+
+/// A playback position that advances in real time, scaled by the speed, while not paused.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Clock {
+    /// The position at `resumed_at`, or the position while paused
+    base: Duration,
+    /// When the clock was last (re)started, `None` while paused
+    resumed_at: Option<Instant>,
+    speed: Speed,
+}
+
+impl Clock {
+    fn new(speed: Speed) -> Self {
+        Self {
+            base: Duration::ZERO,
+            resumed_at: None,
+            speed,
+        }
+    }
+
+    fn position(&self, now: Instant) -> Duration {
+        let Some(resumed_at) = self.resumed_at else {
+            return self.base;
+        };
+        let elapsed = now.saturating_duration_since(resumed_at);
+
+        self.base + elapsed.mul_f64(f64::from(self.speed) / f64::from(NORMAL_SPEED))
+    }
+
+    fn is_paused(&self) -> bool {
+        self.resumed_at.is_none()
+    }
+
+    fn pause(&mut self, now: Instant) {
+        self.base = self.position(now);
+        self.resumed_at = None;
+    }
+
+    fn resume(&mut self, now: Instant) {
+        if self.resumed_at.is_none() {
+            self.resumed_at = Some(now);
+        }
+    }
+
+    fn set_position(&mut self, position: Duration, now: Instant) {
+        self.base = position;
+        if self.resumed_at.is_some() {
+            self.resumed_at = Some(now);
+        }
+    }
+
+    fn set_speed(&mut self, speed: Speed, now: Instant) {
+        self.set_position(self.position(now), now);
+        self.speed = speed;
+    }
+}
+
+#[derive(Debug)]
+struct NullState {
+    clock: Clock,
+    /// Whether a track is loaded
+    active: bool,
+    /// The length of the current track, tracks without one play until skipped
+    duration: Option<Duration>,
+    /// The length of the enqueued next track, if one was enqueued
+    next: Option<Option<Duration>>,
+    /// Whether [`PlayerCmd::AboutToFinish`] was already sent for the current track
+    sent_about_to_finish: bool,
+}
+
+impl NullState {
+    /// Start the next track if one was enqueued, otherwise stop.
+    ///
+    /// Returns whether the next track was started.
+    fn finish_track(&mut self, now: Instant) -> bool {
+        self.sent_about_to_finish = false;
+        self.clock.set_position(Duration::ZERO, now);
+        if let Some(duration) = self.next.take() {
+            self.duration = duration;
+            return true;
+        }
+
+        self.active = false;
+        self.duration = None;
+        self.clock.pause(now);
+
+        false
+    }
+}
+
+/// Plays nothing, see the [module docs](self).
+pub struct NullBackend {
+    state: Arc<Mutex<NullState>>,
+    volume: Volume,
+    gapless: bool,
+    /// Stops the clock thread once dropped
+    stop: Arc<AtomicBool>,
+}
+
+impl NullBackend {
+    pub fn new(config: &ServerOverlay, cmd_tx: PlayerCmdSender) -> Self {
+        let state = Arc::new(Mutex::new(NullState {
+            clock: Clock::new(config.settings.player.speed),
+            active: false,
+            duration: None,
+            next: None,
+            sent_about_to_finish: false,
+        }));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        {
+            let state = state.clone();
+            let stop = stop.clone();
+            std::thread::Builder::new()
+                .name("playback null clock".into())
+                .spawn(move || clock_loop(&state, &cmd_tx, &stop))
+                .expect("failed to spawn thread");
+        }
+
+        Self {
+            state,
+            volume: config.settings.player.volume,
+            gapless: config.settings.player.gapless,
+            stop,
+        }
+    }
+}
+
+impl Drop for NullBackend {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Report the end of tracks the same way the other backends do, until `stop` is set.
+fn clock_loop(state: &Mutex<NullState>, cmd_tx: &PlayerCmdSender, stop: &AtomicBool) {
+    while !stop.load(Ordering::SeqCst) {
+        std::thread::sleep(TICK_INTERVAL);
+
+        let now = Instant::now();
+        let mut state = state.lock();
+        let Some(duration) = state.duration.filter(|_| state.active) else {
+            continue;
+        };
+        let position = state.clock.position(now);
+
+        let cmd = if position >= duration {
+            state.finish_track(now);
+            PlayerCmd::Eos
+        } else if !state.sent_about_to_finish
+            && position >= duration / 2
+            && duration - position < ABOUT_TO_FINISH
+        {
+            state.sent_about_to_finish = true;
+            PlayerCmd::AboutToFinish
+        } else {
+            continue;
+        };
+        drop(state);
+
+        if cmd_tx.send(cmd).is_err() {
+            break;
+        }
+    }
+}
+
+/// The length of `track` the clock ends it at.
+///
+/// Cue sheet tracks are ended by the player at their end, as the length of the whole file is unknown.
+fn track_duration(track: &Track) -> Option<Duration> {
+    if track.as_track().is_some_and(|v| v.cue_span().is_some()) {
+        return None;
+    }
+
+    track.duration()
+}
+
+#[async_trait]
+impl PlayerTrait for NullBackend {
+    async fn add_and_play(&mut self, track: &Track) {
+        info!("Null backend \"playing\" {track:#?}");
+        let now = Instant::now();
+        let mut state = self.state.lock();
+        state.active = true;
+        state.duration = track_duration(track);
+        state.next = None;
+        state.sent_about_to_finish = false;
+        state.clock.set_position(Duration::ZERO, now);
+        state.clock.resume(now);
+    }
+
+    fn volume(&self) -> Volume {
+        self.volume
+    }
+
+    fn set_volume(&mut self, volume: Volume) -> Volume {
+        self.volume = volume.min(100);
+
+        self.volume
+    }
+
+    fn pause(&mut self) {
+        self.state.lock().clock.pause(Instant::now());
+    }
+
+    fn resume(&mut self) {
+        let mut state = self.state.lock();
+        if state.active {
+            state.clock.resume(Instant::now());
+        }
+    }
+
+    fn is_paused(&self) -> bool {
+        self.state.lock().clock.is_paused()
+    }
+
+    fn seek(&mut self, secs: i64) -> Result<()> {
+        let position = self.position().unwrap_or_default();
+        let offset = Duration::from_secs(secs.unsigned_abs());
+        let position = if secs < 0 {
+            position.saturating_sub(offset)
+        } else {
+            position + offset
+        };
+        self.seek_to(position);
+
+        Ok(())
+    }
+
+    fn seek_to(&mut self, position: Duration) {
+        let mut state = self.state.lock();
+        state.sent_about_to_finish = false;
+        state.clock.set_position(position, Instant::now());
+    }
+
+    fn get_progress(&self) -> Option<PlayerProgress> {
+        let state = self.state.lock();
+
+        Some(PlayerProgress {
+            position: Some(state.clock.position(Instant::now())),
+            total_duration: state.duration,
+            ab_repeat: AbRepeat::default(),
+        })
+    }
+
+    fn set_speed(&mut self, speed: Speed) -> Speed {
+        self.state.lock().clock.set_speed(speed, Instant::now());
+
+        speed
+    }
+
+    fn speed(&self) -> Speed {
+        self.state.lock().clock.speed
+    }
+
+    fn stop(&mut self) {
+        let now = Instant::now();
+        let mut state = self.state.lock();
+        state.next = None;
+        state.finish_track(now);
+    }
+
+    fn gapless(&self) -> bool {
+        self.gapless
+    }
+
+    fn set_gapless(&mut self, to: bool) {
+        self.gapless = to;
+    }
+
+    fn skip_one(&mut self) {
+        let mut state = self.state.lock();
+        if state.active {
+            // the same as reaching the end of the track, the clock thread reports it
+            state.duration = Some(Duration::ZERO);
+        }
+    }
+
+    fn enqueue_next(&mut self, track: &Track) {
+        self.state.lock().next = Some(track_duration(track));
+    }
+
+    fn media_info(&self) -> MediaInfo {
+        MediaInfo::default()
+    }
+
+    fn output_info(&self) -> OutputInfo {
+        OutputInfo {
+            device: Some("none, nothing is played".to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use pretty_assertions::assert_eq;
+
+    use super::Clock;
+
+    #[test]
+    fn should_advance_clock_while_playing() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut clock = Clock::new(10);
+        assert_eq!(clock.position(at(5)), Duration::ZERO);
+
+        clock.resume(at(0));
+        assert_eq!(clock.position(at(5)), Duration::from_secs(5));
+
+        clock.pause(at(5));
+        assert_eq!(clock.position(at(10)), Duration::from_secs(5));
+
+        clock.resume(at(10));
+        clock.set_speed(20, at(12));
+        assert_eq!(clock.position(at(14)), Duration::from_secs(11));
+
+        clock.set_position(Duration::from_secs(60), at(14));
+        assert_eq!(clock.position(at(15)), Duration::from_secs(62));
+    }
+}
@@ -0,0 +1,441 @@
+//! Play DSD (SACD) files by converting them to PCM.
+//!
+//! [`DsdReader`] reads DSF and DSDIFF files into packets of planar, most significant bit first DSD bytes,
+//! which [`DsdDecoder`] low-pass filters and decimates to PCM, for example DSD64 to 88.2kHz.
+
+use std::io::{ErrorKind, Seek, SeekFrom};
+use std::sync::LazyLock;
+
+use symphonia::core::{
+    audio::{AsAudioBufferRef, AudioBuffer, AudioBufferRef, Channels, Signal, SignalSpec},
+    codecs::{
+        CodecDescriptor, CodecParameters, CodecType, Decoder, DecoderOptions, FinalizeResult,
+        decl_codec_type,
+    },
+    errors::{Error, Result, SeekErrorKind, decode_error, seek_error, unsupported_error},
+    formats::{Cue, FormatOptions, FormatReader, Packet, SeekMode, SeekTo, SeekedTo, Track},
+    io::{MediaSourceStream, ReadBytes},
+    meta::{Metadata, MetadataLog},
+    probe::{Descriptor, Instantiate, QueryDescriptor},
+    support_codec, support_format,
+    units::TimeBase,
+};
+use termusiclib::track::dsd::{self, DsdHeader};
+
+/// The codec of the packets of [`DsdReader`].
+pub const CODEC_TYPE_DSD: CodecType = decl_codec_type(b"dsd");
+
+/// DSD bytes of each channel that become one PCM sample.
+const DECIMATION: usize = 4;
+/// Length of the low-pass filter in DSD bytes.
+const FILTER_BYTES: usize = 64;
+/// Cutoff of the low-pass filter, relative to the nyquist frequency of the PCM output.
+const FILTER_CUTOFF: f64 = 0.8;
+/// DSD bytes of each channel in a packet of a DSDIFF file, which has no blocks of its own.
+const DFF_PACKET_BYTES: u64 = 4096;
+/// A DSD byte that encodes silence.
+const DSD_SILENCE: u8 = 0x69;
+
+/// For each byte of the filter window, the filter output for all 256 values that byte can have.
+///
+/// This way the filter only needs one lookup per byte instead of one multiplication per bit.
+static FILTER_TABLE: LazyLock<Vec<[f32; 256]>> = LazyLock::new(filter_table);
+
+/// Build [`FILTER_TABLE`] from a blackman windowed sinc low-pass filter.
+#[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+fn filter_table() -> Vec<[f32; 256]> {
+    use std::f64::consts::PI;
+
+    let taps = (FILTER_BYTES * 8) as f64;
+    let cutoff = FILTER_CUTOFF * 0.5 / (DECIMATION * 8) as f64;
+
+    let mut coefficients: Vec<f64> = (0..FILTER_BYTES * 8)
+        .map(|n| {
+            let n = n as f64;
+            // the amount of taps is even, so this is never 0
+            let x = n - (taps - 1.0) / 2.0;
+            let sinc = (2.0 * PI * cutoff * x).sin() / (PI * x);
+            let phase = 2.0 * PI * n / (taps - 1.0);
+            let window = 0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos();
+
+            sinc * window
+        })
+        .collect();
+    let sum: f64 = coefficients.iter().sum();
+    for coefficient in &mut coefficients {
+        *coefficient /= sum;
+    }
+
+    coefficients
+        .chunks_exact(8)
+        .map(|byte_coefficients| {
+            let mut table = [0.0; 256];
+            for (value, out) in table.iter_mut().enumerate() {
+                let sum: f64 = byte_coefficients
+                    .iter()
+                    .enumerate()
+                    .map(|(bit, coefficient)| {
+                        // the most significant bit is the oldest sample, a set bit is +1 and a unset bit -1
+                        if value & (0x80 >> bit) == 0 {
+                            -coefficient
+                        } else {
+                            *coefficient
+                        }
+                    })
+                    .sum();
+                *out = sum as f32;
+            }
+            table
+        })
+        .collect()
+}
+
+/// Get the channels for `count` channels, in the order of the DSF and DSDIFF defaults.
+fn channels(count: u16) -> Option<Channels> {
+    if count == 0 || count > 8 {
+        return None;
+    }
+
+    Channels::from_bits((1 << count) - 1)
+}
+
+/// Format reader for DSF and DSDIFF files, see the module documentation.
+pub struct DsdReader {
+    reader: MediaSourceStream,
+    header: DsdHeader,
+    tracks: Vec<Track>,
+    cues: Vec<Cue>,
+    metadata: MetadataLog,
+    /// Bytes of each channel in a packet, except for the last one
+    packet_bytes: u64,
+    /// Bytes of each channel that can be played, rounded down to whole PCM samples
+    total_bytes: u64,
+    /// Bytes of each channel already read
+    position: u64,
+}
+
+impl QueryDescriptor for DsdReader {
+    fn query() -> &'static [Descriptor] {
+        &[
+            support_format!(
+                "dsf",
+                "Sony DSD Stream File",
+                &["dsf"],
+                &["audio/dsf", "audio/x-dsf"],
+                &[b"DSD "]
+            ),
+            support_format!(
+                "dsdiff",
+                "Philips DSD Interchange File Format",
+                &["dff"],
+                &["audio/dff", "audio/x-dff"],
+                &[b"FRM8"]
+            ),
+        ]
+    }
+
+    fn score(_context: &[u8]) -> u8 {
+        255
+    }
+}
+
+impl FormatReader for DsdReader {
+    fn try_new(mut source: MediaSourceStream, _options: &FormatOptions) -> Result<Self> {
+        let header = match dsd::read_header(&mut source) {
+            Ok(v) => v,
+            Err(err) => {
+                warn!("Error reading DSD header: {err:#}");
+                return unsupported_error("dsd: invalid or unsupported header");
+            }
+        };
+
+        let Some(channels) = channels(header.channels) else {
+            return unsupported_error("dsd: unsupported channel count");
+        };
+        let packet_bytes = if header.block_size == 1 {
+            DFF_PACKET_BYTES
+        } else {
+            u64::from(header.block_size)
+        };
+        if packet_bytes % DECIMATION as u64 != 0 {
+            return unsupported_error("dsd: unsupported block size");
+        }
+
+        let total_bytes = (header.sample_count / 8)
+            .min(header.data_len / u64::from(header.channels))
+            / DECIMATION as u64
+            * DECIMATION as u64;
+        #[allow(clippy::cast_possible_truncation)]
+        let sample_rate = header.sample_rate / (DECIMATION * 8) as u32;
+        if sample_rate == 0 {
+            return unsupported_error("dsd: unsupported sample rate");
+        }
+
+        let mut codec_params = CodecParameters::new();
+        codec_params
+            .for_codec(CODEC_TYPE_DSD)
+            .with_sample_rate(sample_rate)
+            .with_time_base(TimeBase::new(1, sample_rate))
+            .with_n_frames(total_bytes / DECIMATION as u64)
+            .with_max_frames_per_packet(packet_bytes / DECIMATION as u64)
+            .with_channels(channels)
+            .with_bits_per_coded_sample(1);
+
+        Ok(Self {
+            reader: source,
+            header,
+            tracks: vec![Track::new(0, codec_params)],
+            cues: Vec::new(),
+            metadata: MetadataLog::default(),
+            packet_bytes,
+            total_bytes,
+            position: 0,
+        })
+    }
+
+    fn next_packet(&mut self) -> Result<Packet> {
+        let len = self
+            .packet_bytes
+            .min(self.total_bytes.saturating_sub(self.position));
+        if len == 0 {
+            return Err(Error::IoError(std::io::Error::new(
+                ErrorKind::UnexpectedEof,
+                "end of stream",
+            )));
+        }
+
+        let channels = usize::from(self.header.channels);
+        let block_size = self.header.block_size as usize;
+        #[allow(clippy::cast_possible_truncation)] // at most "packet_bytes"
+        let len = len as usize;
+
+        // the last block of a DSF file is padded to the full size
+        let mut raw = vec![0; len.div_ceil(block_size) * block_size * channels];
+        self.reader.read_buf_exact(&mut raw)?;
+
+        let mut data = vec![0; len * channels];
+        for (channel, out) in data.chunks_exact_mut(len).enumerate() {
+            for (i, byte) in out.iter_mut().enumerate() {
+                let raw_byte = raw[i / block_size * block_size * channels
+                    + channel * block_size
+                    + i % block_size];
+                *byte = if self.header.lsb_first {
+                    raw_byte.reverse_bits()
+                } else {
+                    raw_byte
+                };
+            }
+        }
+
+        let ts = self.position / DECIMATION as u64;
+        self.position += len as u64;
+
+        Ok(Packet::new_from_boxed_slice(
+            0,
+            ts,
+            (len / DECIMATION) as u64,
+            data.into_boxed_slice(),
+        ))
+    }
+
+    fn metadata(&mut self) -> Metadata<'_> {
+        self.metadata.metadata()
+    }
+
+    fn cues(&self) -> &[Cue] {
+        &self.cues
+    }
+
+    fn tracks(&self) -> &[Track] {
+        &self.tracks
+    }
+
+    fn seek(&mut self, _mode: SeekMode, to: SeekTo) -> Result<SeekedTo> {
+        let params = &self.tracks[0].codec_params;
+        let ts = match to {
+            SeekTo::TimeStamp { ts, .. } => ts,
+            SeekTo::Time { time, .. } => match params.time_base {
+                Some(time_base) => time_base.calc_timestamp(time),
+                None => return seek_error(SeekErrorKind::Unseekable),
+            },
+        };
+        if params.n_frames.is_some_and(|n_frames| ts > n_frames) {
+            return seek_error(SeekErrorKind::OutOfRange);
+        }
+
+        // seek to the start of the packet containing "ts", so that packets always start at the same positions
+        let frames_per_packet = self.packet_bytes / DECIMATION as u64;
+        let packet = ts / frames_per_packet;
+        let position = packet * self.packet_bytes;
+        self.reader.seek(SeekFrom::Start(
+            self.header.data_offset + position * u64::from(self.header.channels),
+        ))?;
+        self.position = position;
+
+        Ok(SeekedTo {
+            track_id: 0,
+            required_ts: ts,
+            actual_ts: packet * frames_per_packet,
+        })
+    }
+
+    fn into_inner(self: Box<Self>) -> MediaSourceStream {
+        self.reader
+    }
+}
+
+/// Decoder for the packets of [`DsdReader`], see the module documentation.
+pub struct DsdDecoder {
+    params: CodecParameters,
+    buf: AudioBuffer<f32>,
+    /// The last [`FILTER_BYTES`] of each channel, as the filter for the start of a packet needs them
+    history: Vec<[u8; FILTER_BYTES]>,
+}
+
+impl DsdDecoder {
+    fn decode_inner(&mut self, packet: &Packet) -> Result<()> {
+        let data = packet.buf();
+        let channels = self.history.len();
+        if data.len() % (channels * DECIMATION) != 0 {
+            return decode_error("dsd: packet is not made of whole samples");
+        }
+        let len = data.len() / channels;
+        let frames = len / DECIMATION;
+        if frames > self.buf.capacity() {
+            return decode_error("dsd: packet is larger than the maximum");
+        }
+
+        self.buf.clear();
+        self.buf.render_reserved(Some(frames));
+
+        let mut window = Vec::with_capacity(FILTER_BYTES + len);
+        for (channel, history) in self.history.iter_mut().enumerate() {
+            window.clear();
+            window.extend_from_slice(history);
+            window.extend_from_slice(&data[channel * len..(channel + 1) * len]);
+
+            for (frame, sample) in self.buf.chan_mut(channel).iter_mut().enumerate() {
+                let end = FILTER_BYTES + (frame + 1) * DECIMATION;
+                let sum: f32 = window[end - FILTER_BYTES..end]
+                    .iter()
+                    .zip(FILTER_TABLE.iter())
+                    .map(|(byte, table)| table[usize::from(*byte)])
+                    .sum();
+                *sample = sum.clamp(-1.0, 1.0);
+            }
+
+            history.copy_from_slice(&window[window.len() - FILTER_BYTES..]);
+        }
+
+        Ok(())
+    }
+}
+
+impl Decoder for DsdDecoder {
+    fn try_new(params: &CodecParameters, _options: &DecoderOptions) -> Result<Self> {
+        if params.codec != CODEC_TYPE_DSD {
+            return unsupported_error("dsd: invalid codec type");
+        }
+        let (Some(sample_rate), Some(channels), Some(max_frames)) = (
+            params.sample_rate,
+            params.channels,
+            params.max_frames_per_packet,
+        ) else {
+            return unsupported_error(
+                "dsd: sample rate, channels and maximum frames per packet are required",
+            );
+        };
+
+        Ok(Self {
+            params: params.clone(),
+            buf: AudioBuffer::new(max_frames, SignalSpec::new(sample_rate, channels)),
+            history: vec![[DSD_SILENCE; FILTER_BYTES]; channels.count()],
+        })
+    }
+
+    fn supported_codecs() -> &'static [CodecDescriptor] {
+        &[support_codec!(
+            CODEC_TYPE_DSD,
+            "dsd",
+            "Direct Stream Digital"
+        )]
+    }
+
+    fn reset(&mut self) {
+        for history in &mut self.history {
+            history.fill(DSD_SILENCE);
+        }
+    }
+
+    fn codec_params(&self) -> &CodecParameters {
+        &self.params
+    }
+
+    fn decode(&mut self, packet: &Packet) -> Result<AudioBufferRef<'_>> {
+        if let Err(err) = self.decode_inner(packet) {
+            self.buf.clear();
+            return Err(err);
+        }
+
+        Ok(self.buf.as_audio_buffer_ref())
+    }
+
+    fn finalize(&mut self) -> FinalizeResult {
+        FinalizeResult::default()
+    }
+
+    fn last_decoded(&self) -> AudioBufferRef<'_> {
+        self.buf.as_audio_buffer_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use symphonia::core::{
+        audio::{Channels, Signal},
+        codecs::{CodecParameters, Decoder, DecoderOptions},
+        formats::Packet,
+    };
+
+    use super::{CODEC_TYPE_DSD, DSD_SILENCE, DsdDecoder};
+
+    fn decoder() -> DsdDecoder {
+        let mut params = CodecParameters::new();
+        params
+            .for_codec(CODEC_TYPE_DSD)
+            .with_sample_rate(88200)
+            .with_channels(Channels::FRONT_LEFT | Channels::FRONT_RIGHT)
+            .with_max_frames_per_packet(1024);
+
+        DsdDecoder::try_new(&params, &DecoderOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn should_decode_silence() {
+        let mut decoder = decoder();
+        let packet = Packet::new_from_slice(0, 0, 1024, &[DSD_SILENCE; 4096 * 2]);
+
+        let decoded = decoder.decode(&packet).unwrap();
+        assert_eq!(decoded.frames(), 1024);
+        let mut buf = decoded.make_equivalent::<f32>();
+        decoded.convert(&mut buf);
+        for channel in 0..2 {
+            assert!(buf.chan(channel).iter().all(|v| v.abs() < 0.01));
+        }
+    }
+
+    #[test]
+    fn should_decode_full_scale() {
+        let mut decoder = decoder();
+        // all ones is the positive maximum, after the filter has settled on it
+        let mut data = vec![0xFF; 4096];
+        data.extend([0x00; 4096]);
+        let packet = Packet::new_from_slice(0, 0, 1024, &data);
+
+        let decoded = decoder.decode(&packet).unwrap();
+        let mut buf = decoded.make_equivalent::<f32>();
+        decoded.convert(&mut buf);
+        assert!(buf.chan(0)[100..].iter().all(|v| (v - 1.0).abs() < 0.01));
+        assert!(buf.chan(1)[100..].iter().all(|v| (v + 1.0).abs() < 0.01));
+    }
+}
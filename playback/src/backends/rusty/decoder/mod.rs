@@ -6,18 +6,20 @@ use symphonia::{
         codecs::{self, CODEC_TYPE_NULL, CodecParameters, CodecRegistry},
         errors::Error,
         formats::{FormatOptions, FormatReader, SeekMode, SeekTo, Track},
-        io::MediaSourceStream,
+        io::{MediaSource, MediaSourceStream},
         meta::{MetadataOptions, MetadataRevision, StandardTagKey, Value},
-        probe::{Hint, ProbeResult, ProbedMetadata},
+        probe::{Hint, Probe, ProbeResult, ProbedMetadata},
         units::TimeBase,
     },
-    default::{get_probe, register_enabled_codecs},
+    default::{register_enabled_codecs, register_enabled_formats},
 };
+use termusiclib::player::StreamInfo;
 use tokio::sync::mpsc;
 
 use super::{Source, source::SampleType};
 
 pub mod buffered_source;
+pub mod dsd;
 pub mod read_seek_source;
 
 fn is_codec_null(track: &Track) -> bool {
@@ -81,9 +83,18 @@ static CODEC_REGISTRY: LazyLock<CodecRegistry> = LazyLock::new(|| {
     register_enabled_codecs(&mut registry);
     #[cfg(feature = "rusty-libopus")]
     registry.register_all::<symphonia_adapter_libopus::OpusDecoder>();
+    registry.register_all::<dsd::DsdDecoder>();
     registry
 });
 
+/// Custom probe for termusic, with the formats symphonia does not support itself
+static PROBE: LazyLock<Probe> = LazyLock::new(|| {
+    let mut probe = Probe::default();
+    register_enabled_formats(&mut probe);
+    probe.register_all::<dsd::DsdReader>();
+    probe
+});
+
 pub struct Symphonia {
     decoder: Box<dyn codecs::Decoder>,
     current_frame_offset: usize,
@@ -94,6 +105,8 @@ pub struct Symphonia {
     track_id: u32,
     time_base: Option<TimeBase>,
     seek_required_ts: Option<NonZeroU64>,
+    /// The length of the whole source in bytes, if known
+    byte_len: Option<u64>,
 
     media_title_tx: MediaTitleTxWrap,
 }
@@ -116,7 +129,8 @@ impl Symphonia {
         gapless: bool,
         media_title: bool,
     ) -> Result<(Self, Option<MediaTitleRx>), SymphoniaDecoderError> {
-        let mut probed = get_probe().format(
+        let byte_len = mss.byte_len();
+        let mut probed = PROBE.format(
             &Hint::default(),
             mss,
             &FormatOptions {
@@ -187,6 +201,7 @@ impl Symphonia {
                 track_id,
                 time_base,
                 seek_required_ts: None,
+                byte_len,
 
                 media_title_tx,
             },
@@ -203,6 +218,29 @@ impl Symphonia {
         })
     }
 
+    /// Get the codec and sample rate of the track, and its average bitrate if the size and duration are known.
+    pub fn stream_info(&self) -> StreamInfo {
+        let params = self.decoder.codec_params();
+        let bitrate = self
+            .byte_len
+            .zip(self.duration)
+            .filter(|(_, duration)| !duration.is_zero())
+            .and_then(|(byte_len, duration)| {
+                #[allow(clippy::cast_precision_loss)]
+                let bitrate = byte_len as f64 * 8.0 / duration.as_secs_f64();
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                u32::try_from(bitrate as u64).ok()
+            });
+
+        StreamInfo {
+            codec: CODEC_REGISTRY
+                .get_codec(params.codec)
+                .map(|v| v.short_name.to_string()),
+            sample_rate: params.sample_rate,
+            bitrate,
+        }
+    }
+
     /// Copy passed [`AudioBufferRef`] into a new [`SampleBuffer`]
     ///
     /// also see [`Self::maybe_reuse_buffer`]
@@ -337,7 +375,10 @@ impl Source for Symphonia {
 
                 // some decoders need to be reset after a seek, but not all can be reset without unexpected behavior (like mka seeking to 0 again)
                 // see https://github.com/pdeljanov/Symphonia/issues/274
-                if self.decoder.codec_params().codec == codecs::CODEC_TYPE_MP3 {
+                if matches!(
+                    self.decoder.codec_params().codec,
+                    codecs::CODEC_TYPE_MP3 | dsd::CODEC_TYPE_DSD
+                ) {
                     self.decoder.reset();
                 }
 
@@ -6,10 +6,9 @@ use std::sync::atomic::{AtomicU16, Ordering};
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::time::Duration;
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use async_trait::async_trait;
 use parking_lot::Mutex;
-use rodio::OutputStreamBuilder;
 use rodio::Source;
 use std::num::{NonZeroU16, NonZeroUsize};
 use stream_download::http::{
@@ -28,28 +27,42 @@ use symphonia::core::io::{
     MediaSource, MediaSourceStream, MediaSourceStreamOptions, ReadOnlySource,
 };
 use termusiclib::config::SharedServerSettings;
+use termusiclib::config::v2::server::ReplayGainMode;
+use termusiclib::config::v2::server::backends::StreamOutput;
+use termusiclib::player::{AbRepeat, StreamInfo};
 use termusiclib::track::{MediaTypes, Track};
+use termusiclib::waveform::LEVEL_INTERVAL;
 use tokio::runtime::Handle;
 use tokio::select;
 use tokio::sync::oneshot;
 
 use crate::backends::rusty::decoder::SymphoniaDecoderError;
 use crate::{
-    MediaInfo, PlayerCmd, PlayerCmdCallbackSender, PlayerCmdSender, PlayerProgress, PlayerTrait,
-    Speed, Volume,
+    MediaInfo, OutputInfo, PlayerCmd, PlayerCmdCallbackSender, PlayerCmdSender, PlayerProgress,
+    PlayerTrait, Speed, Volume,
 };
 use decoder::buffered_source::BufferedSource;
 use decoder::read_seek_source::ReadSeekSource;
 use decoder::{MediaTitleRx, MediaTitleType, Symphonia};
+use output::{Output, SwitchableQueue};
 use sink::{Sink, SourceOptions};
 use source::async_ring::{AsyncRingSource, AsyncRingSourceProvider, SeekData};
+use source::skip_silence::SkipSilenceOptions;
 
 mod decoder;
 mod icy_metadata;
+mod output;
+mod pcm_stream;
 mod sink;
 // public to bench lower modules
 pub(crate) mod source;
 
+/// Extensions of the files this backend can play, DSD is converted to PCM by [`decoder::dsd`].
+pub const SUPPORTED_EXTENSIONS: &[&str] = &[
+    "mkv", "mka", "mp3", "aiff", "aif", "aifc", "flac", "m4a", "aac", "opus", "ogg", "wav", "webm",
+    "dsf", "dff",
+];
+
 pub type TotalDuration = Option<Duration>;
 pub type ArcTotalDuration = Arc<Mutex<TotalDuration>>;
 
@@ -62,10 +75,14 @@ enum PlayerInternalCmd {
     SeekAbsolute(Duration),
     SeekRelative(i64),
     Skip,
+    /// Shorten silences with the given options, or play everything if `None`.
+    SkipSilence(Option<SkipSilenceOptions>),
     Speed(i32),
     Stop,
     TogglePause,
     Volume(u16),
+    /// Move playback to the given output device, or the default device if `None`.
+    SetOutputDevice(Option<String>),
     Eos,
 }
 
@@ -77,9 +94,14 @@ pub struct RustyBackend {
     position: Arc<Mutex<Duration>>,
     total_duration: ArcTotalDuration,
     media_title: Arc<Mutex<String>>,
+    stream_info: Arc<Mutex<StreamInfo>>,
     pub radio_downloaded: Arc<Mutex<u64>>,
     // cmd_tx_outside: crate::PlayerCmdSender,
     config: SharedServerSettings,
+    /// Whether a PCM stream is written instead of playing on a output device
+    is_streaming: bool,
+    /// The info of the current output, updated by the player thread when opening a output
+    output_info: Arc<Mutex<OutputInfo>>,
 }
 
 impl RustyBackend {
@@ -94,7 +116,10 @@ impl RustyBackend {
         let speed = config_read.settings.player.speed;
         let gapless = config_read.settings.player.gapless;
         let output_sample_rate = config_read.settings.backends.rusty.output_sample_rate;
+        let output_device = config_read.settings.backends.rusty.output_device.clone();
+        let stream_output = config_read.settings.backends.rusty.stream_output.clone();
         drop(config_read);
+        let is_streaming = stream_output.is_some();
 
         let position = Arc::new(Mutex::new(Duration::default()));
         let total_duration = Arc::new(Mutex::new(None));
@@ -103,10 +128,15 @@ impl RustyBackend {
         let pcmd_tx_local = cmd_tx;
         let media_title = Arc::new(Mutex::new(String::new()));
         let media_title_local = media_title.clone();
+        let stream_info = Arc::new(Mutex::new(StreamInfo::default()));
+        let stream_info_local = stream_info.clone();
+        let output_info = Arc::new(Mutex::new(OutputInfo::default()));
+        let output_info_local = output_info.clone();
         let radio_downloaded = Arc::new(Mutex::new(100_u64));
         // let radio_downloaded_local = radio_downloaded.clone();
         // this should likely be a parameter, but works for now
         let tokio_handle = Handle::current();
+        let config_local = config.clone();
 
         std::thread::Builder::new()
             .name("playback player loop".into())
@@ -117,11 +147,16 @@ impl RustyBackend {
                     picmd_tx: picmd_tx_local,
                     picmd_rx,
                     media_title: media_title_local,
+                    stream_info: stream_info_local,
                     // radio_downloaded_local,
                     position: position_local,
                     volume_inside: volume_local,
                     speed_inside: speed,
                     output_sample_rate,
+                    output_device,
+                    stream_output,
+                    output_info: output_info_local,
+                    config: config_local,
                 }));
             })
             .expect("failed to spawn thread");
@@ -134,9 +169,12 @@ impl RustyBackend {
             command_tx: picmd_tx,
             position,
             media_title,
+            stream_info,
             radio_downloaded,
             // cmd_tx_outside: cmd_tx,
             config,
+            is_streaming,
+            output_info,
         }
     }
 
@@ -156,6 +194,8 @@ impl PlayerTrait for RustyBackend {
         let query_options = {
             let config_read = self.config.read_recursive();
             let soundtouch = config_read.settings.backends.rusty.soundtouch;
+            let pitch_correction = config_read.settings.backends.rusty.pitch_correction;
+            let replay_gain = config_read.settings.player.replay_gain;
             let file_buf_size = usize::try_from(
                 config_read
                     .settings
@@ -178,8 +218,12 @@ impl PlayerTrait for RustyBackend {
             QueueNextOptions {
                 gapless_decode: self.gapless,
                 soundtouch,
+                pitch_correction,
                 file_buf_size,
                 ringbuf_size,
+                replay_gain,
+                // directly played tracks should start immediately
+                crossfade: None,
                 enqueue: false,
             }
         };
@@ -248,6 +292,7 @@ impl PlayerTrait for RustyBackend {
         Some(PlayerProgress {
             position: Some(*self.position.lock()),
             total_duration: *self.total_duration.lock(),
+            ab_repeat: AbRepeat::default(),
         })
     }
 
@@ -263,9 +308,23 @@ impl PlayerTrait for RustyBackend {
         self.command(PlayerInternalCmd::Skip);
     }
 
+    fn set_skip_silence(&mut self, enabled: bool) {
+        let options = enabled.then(|| {
+            let settings = self.config.read().settings.player.skip_silence;
+            SkipSilenceOptions {
+                min_duration: settings.min_duration(),
+                threshold: settings.threshold(),
+            }
+        });
+        self.command(PlayerInternalCmd::SkipSilence(options));
+    }
+
     fn enqueue_next(&mut self, track: &Track) {
         let config_read = self.config.read_recursive();
         let soundtouch = config_read.settings.backends.rusty.soundtouch;
+        let pitch_correction = config_read.settings.backends.rusty.pitch_correction;
+        let replay_gain = config_read.settings.player.replay_gain;
+        let crossfade = config_read.settings.player.crossfade.duration();
         let file_buf_size = usize::try_from(
             config_read
                 .settings
@@ -292,37 +351,83 @@ impl PlayerTrait for RustyBackend {
             QueueNextOptions {
                 gapless_decode: self.gapless,
                 soundtouch,
+                pitch_correction,
                 file_buf_size,
                 ringbuf_size,
+                replay_gain,
+                crossfade,
                 enqueue: true,
             },
             PlayerCmdCallbackSender(None),
         ));
     }
 
+    fn output_devices(&self) -> Vec<String> {
+        output::output_device_names()
+    }
+
+    fn set_output_device(&mut self, name: Option<String>) -> Result<()> {
+        if self.is_streaming {
+            bail!("Output devices are not used while \"backends.rusty.stream_output\" is set");
+        }
+        if let Some(name) = &name {
+            if !output::output_device_names().contains(name) {
+                bail!("No output device named {name:#?}");
+            }
+        }
+        self.command(PlayerInternalCmd::SetOutputDevice(name));
+
+        Ok(())
+    }
+
+    fn output_info(&self) -> OutputInfo {
+        let mut info = self.output_info.lock().clone();
+        info.decoded_buffer_size = Some(
+            self.config
+                .read_recursive()
+                .settings
+                .backends
+                .rusty
+                .decoded_buffer_size
+                .as_u64(),
+        );
+        // the decoded audio is converted by the mixer of the output
+        info.resampling = self
+            .stream_info
+            .lock()
+            .sample_rate
+            .zip(info.sample_rate)
+            .map(|(source, output)| source != output);
+
+        info
+    }
+
     fn media_info(&self) -> MediaInfo {
         let media_title_r = self.media_title.lock();
-        if media_title_r.is_empty() {
-            MediaInfo::default()
-        } else {
-            MediaInfo {
-                media_title: Some(media_title_r.clone()),
-            }
+        MediaInfo {
+            media_title: (!media_title_r.is_empty()).then(|| media_title_r.clone()),
+            stream_info: self.stream_info.lock().clone(),
         }
     }
 }
 
 /// Common options across the `append_to_sink*` functions
-#[derive(Debug, Default)]
+#[derive(Debug)]
 struct CommonAppendOptions {
     /// Enable or disable gapless decoding
     gapless_decode: bool,
     /// Enable or disable soundtouch speed modifier or with `false` use rodio's speed modifier
     soundtouch: bool,
+    /// Keep the pitch when changing the speed without soundtouch, instead of using rodio's speed modifier
+    pitch_correction: bool,
     /// Enable or disable async decoding (decode to happen on a different thread than the playback)
     async_decode: bool,
     /// The size for the ring buffer.
     ringbuf_size: usize,
+    /// Factor to apply to the source, `1.0` to keep it unchanged.
+    gain: f32,
+    /// Crossfade from the previous source to this source over the given duration.
+    crossfade: Option<Duration>,
 }
 
 /// Extra options specific to [`append_to_sink_test`]
@@ -340,7 +445,7 @@ fn append_to_sink_inner<TD: Display, F: FnOnce(&mut Symphonia, Option<MediaTitle
     common_options: &CommonAppendOptions,
     specific_options: &SpecificAppendOptions,
     func: F,
-) -> Result<(), SymphoniaDecoderError> {
+) -> Result<StreamInfo, SymphoniaDecoderError> {
     let mss = MediaSourceStream::new(media_source, MediaSourceStreamOptions::default());
     let (mut decoder, rx) = match Symphonia::new(
         mss,
@@ -355,6 +460,7 @@ fn append_to_sink_inner<TD: Display, F: FnOnce(&mut Symphonia, Option<MediaTitle
     };
 
     (func)(&mut decoder, rx);
+    let stream_info = decoder.stream_info();
 
     if common_options.async_decode {
         let handle = tokio::runtime::Handle::current();
@@ -377,6 +483,9 @@ fn append_to_sink_inner<TD: Display, F: FnOnce(&mut Symphonia, Option<MediaTitle
             cons,
             &SourceOptions {
                 soundtouch: common_options.soundtouch,
+                pitch_correction: common_options.pitch_correction,
+                gain: common_options.gain,
+                crossfade: common_options.crossfade,
             },
         );
     } else {
@@ -384,11 +493,14 @@ fn append_to_sink_inner<TD: Display, F: FnOnce(&mut Symphonia, Option<MediaTitle
             decoder,
             &SourceOptions {
                 soundtouch: common_options.soundtouch,
+                pitch_correction: common_options.pitch_correction,
+                gain: common_options.gain,
+                crossfade: common_options.crossfade,
             },
         );
     }
 
-    Ok(())
+    Ok(stream_info)
 }
 
 /// The task that runs the decoder and writes to the ringbuffer, until a error or the consumer closes.
@@ -466,7 +578,7 @@ fn append_to_sink<MT: Fn(MediaTitleType) + Send + 'static, TD: Display>(
     options: &CommonAppendOptions,
     total_duration_local: &ArcTotalDuration,
     media_title_fn: MT,
-) -> Result<(), SymphoniaDecoderError> {
+) -> Result<StreamInfo, SymphoniaDecoderError> {
     append_to_sink_inner(
         media_source,
         trace,
@@ -497,7 +609,7 @@ fn append_to_sink_no_duration(
     sink: &Sink,
     options: &CommonAppendOptions,
     total_duration_local: &ArcTotalDuration,
-) -> Result<(), SymphoniaDecoderError> {
+) -> Result<StreamInfo, SymphoniaDecoderError> {
     append_to_sink_inner(
         media_source,
         trace,
@@ -524,7 +636,7 @@ fn append_to_sink_queue<MT: Fn(MediaTitleType) + Send + 'static, TD: Display>(
     // total_duration_local: &ArcTotalDuration,
     next_duration_opt: &mut Option<Duration>,
     media_title_fn: MT,
-) -> Result<(), SymphoniaDecoderError> {
+) -> Result<StreamInfo, SymphoniaDecoderError> {
     append_to_sink_inner(
         media_source,
         trace,
@@ -559,7 +671,7 @@ fn append_to_sink_queue_no_duration(
     options: &CommonAppendOptions,
     // total_duration_local: &ArcTotalDuration,
     next_duration_opt: &mut Option<Duration>,
-) -> Result<(), SymphoniaDecoderError> {
+) -> Result<StreamInfo, SymphoniaDecoderError> {
     append_to_sink_inner(
         media_source,
         trace,
@@ -588,6 +700,52 @@ fn common_media_title_cb(
     }
 }
 
+/// Decode the whole local file at `path` and get the RMS of every [`LEVEL_INTERVAL`] of it, for a waveform.
+pub(crate) fn decode_levels(path: &Path) -> Result<Vec<f32>> {
+    let file = File::open(path).context("Failed to open music file")?;
+    let mss = MediaSourceStream::new(Box::new(file), MediaSourceStreamOptions::default());
+    let (mut decoder, _) = Symphonia::new(mss, false, false)
+        .with_context(|| format!("Failed to decode {}", path.display()))?;
+
+    let mut levels = Vec::new();
+    let mut sum = 0.0f32;
+    let mut count = 0usize;
+    while decoder.decode_once().is_some() {
+        // the spec may change within a file, so calculate it for every buffer
+        let samples_per_level = u128::from(decoder.sample_rate())
+            * u128::from(decoder.channels())
+            * LEVEL_INTERVAL.as_millis()
+            / 1000;
+        let samples_per_level = usize::try_from(samples_per_level)
+            .unwrap_or(usize::MAX)
+            .max(1);
+
+        let buffer = decoder.get_buffer();
+        let len = buffer.len();
+        for sample in buffer {
+            sum += sample * sample;
+            count += 1;
+            if count >= samples_per_level {
+                levels.push(rms(sum, count));
+                sum = 0.0;
+                count = 0;
+            }
+        }
+        decoder.advance_offset(len);
+    }
+    if count > 0 {
+        levels.push(rms(sum, count));
+    }
+
+    Ok(levels)
+}
+
+/// Get the root mean square of `count` samples with the squares summed up to `sum`.
+#[allow(clippy::cast_precision_loss)]
+fn rms(sum: f32, count: usize) -> f32 {
+    (sum / count as f32).sqrt()
+}
+
 #[derive(Debug)]
 struct PlayerThreadArgs {
     total_duration: ArcTotalDuration,
@@ -598,6 +756,7 @@ struct PlayerThreadArgs {
     /// Reciever for the Internal Player Command
     picmd_rx: Receiver<PlayerInternalCmd>,
     media_title: Arc<Mutex<String>>,
+    stream_info: Arc<Mutex<StreamInfo>>,
     // radio_downloaded: Arc<Mutex<u64>>,
     position: Arc<Mutex<Duration>>,
 
@@ -605,6 +764,12 @@ struct PlayerThreadArgs {
     speed_inside: i32,
 
     output_sample_rate: u32,
+    output_device: Option<String>,
+    /// Write a PCM stream instead of playing on `output_device`
+    stream_output: Option<StreamOutput>,
+    output_info: Arc<Mutex<OutputInfo>>,
+
+    config: SharedServerSettings,
 }
 
 /// Player thread loop
@@ -620,19 +785,24 @@ async fn player_thread(mut args: PlayerThreadArgs) {
     // option to store enqueued's duration
     // note that the current implementation is only meant to have 1 enqueued next after the current playing song
     let mut next_duration_opt = None;
+    // option to store enqueued's stream info, see "next_duration_opt"
+    let mut next_stream_info = None;
     // Tracks whether a "About to Finish" message had already been send or not, to not spam the messages.
     // This needs to be reset on many occasions like Seek or Stream Start.
     let mut send_atf = false;
 
-    let stream = {
-        let builder = OutputStreamBuilder::from_default_device().unwrap();
-        builder
-            .with_sample_rate(args.output_sample_rate)
-            .open_stream_or_fallback()
-            .unwrap()
-    };
-    let handle = stream.mixer();
-    let sink = Sink::try_new(handle, args.picmd_tx.clone(), args.pcmd_tx.clone());
+    let mut stream = Output::open(
+        args.stream_output.as_ref(),
+        args.output_device.as_deref(),
+        args.output_sample_rate,
+    )
+    .unwrap();
+    *args.output_info.lock() = stream.info();
+    let (sink, queue_rx) = Sink::new_idle(args.picmd_tx.clone(), args.pcmd_tx.clone());
+    // the queue is shared, so that it can be moved to another output device without losing the current sources
+    let queue_rx = SwitchableQueue::new(queue_rx);
+    stream.mixer().add(queue_rx.output());
+    sink.set_tail_mixer(stream.mixer().clone());
     sink.set_speed(args.speed_inside as f32 / 10.0);
     sink.set_volume(f32::from(args.volume_inside.load(Ordering::SeqCst)) / 100.0);
     loop {
@@ -642,7 +812,16 @@ async fn player_thread(mut args: PlayerThreadArgs) {
         };
 
         match cmd {
-            PlayerInternalCmd::Play(track, options, cb) => {
+            PlayerInternalCmd::Play(track, mut options, cb) => {
+                // never fade for longer than the current track has left
+                if let Some(crossfade) = options.crossfade {
+                    let remaining = args
+                        .total_duration
+                        .lock()
+                        .map(|d| d.saturating_sub(*args.position.lock()));
+                    options.crossfade =
+                        remaining.map(|v| v.min(crossfade)).filter(|v| !v.is_zero());
+                }
                 if let Err(err) = queue_next(
                     &track,
                     &sink,
@@ -651,6 +830,8 @@ async fn player_thread(mut args: PlayerThreadArgs) {
                     &args.total_duration,
                     &mut next_duration_opt,
                     &args.media_title,
+                    &args.stream_info,
+                    &mut next_stream_info,
                     // &radio_downloaded,
                     &args.pcmd_tx,
                 )
@@ -677,6 +858,9 @@ async fn player_thread(mut args: PlayerThreadArgs) {
             PlayerInternalCmd::Resume => {
                 sink.play();
             }
+            PlayerInternalCmd::SkipSilence(options) => {
+                sink.set_skip_silence(options);
+            }
             PlayerInternalCmd::Speed(speed) => {
                 args.speed_inside = speed;
                 sink.set_speed(args.speed_inside as f32 / 10.0);
@@ -688,6 +872,21 @@ async fn player_thread(mut args: PlayerThreadArgs) {
                 sink.set_volume(f32::from(volume) / 100.0);
                 args.volume_inside.store(volume, Ordering::SeqCst);
             }
+            PlayerInternalCmd::SetOutputDevice(name) => {
+                match Output::open(None, name.as_deref(), args.output_sample_rate) {
+                    Ok(new_stream) => {
+                        // ends the output on the old stream
+                        new_stream.mixer().add(queue_rx.output());
+                        // sources that are still fading out stay on the old device until they end
+                        sink.set_tail_mixer(new_stream.mixer().clone());
+                        let mut old_stream = std::mem::replace(&mut stream, new_stream);
+                        old_stream.log_on_drop(false);
+                        *args.output_info.lock() = stream.info();
+                        info!("Switched output device to {name:#?}");
+                    }
+                    Err(err) => error!("Failed to switch output device to {name:#?}: {err:#}"),
+                }
+            }
             PlayerInternalCmd::Skip => {
                 // the sink can be empty, if for example nothing could be enqueued, so a "skip_one" would be a no-op and never send EOS, which is required to go to the next track
                 if sink.is_empty() {
@@ -709,9 +908,16 @@ async fn player_thread(mut args: PlayerThreadArgs) {
                 if !is_radio && !send_atf {
                     if let Some(d) = *args.total_duration.lock() {
                         let progress = new_position.as_secs_f64() / d.as_secs_f64();
-                        if progress >= 0.5
-                            && d.saturating_sub(new_position) < Duration::from_secs(2)
-                        {
+                        // the next track needs to be enqueued before the crossfade should start
+                        let about_to_finish = args
+                            .config
+                            .read_recursive()
+                            .settings
+                            .player
+                            .crossfade
+                            .duration()
+                            .map_or(Duration::from_secs(2), |v| v + Duration::from_secs(1));
+                        if progress >= 0.5 && d.saturating_sub(new_position) < about_to_finish {
                             if let Err(e) = args.pcmd_tx.send(PlayerCmd::AboutToFinish) {
                                 error!("command AboutToFinish sent failed: {e}");
                             }
@@ -759,6 +965,10 @@ async fn player_thread(mut args: PlayerThreadArgs) {
                 if next_duration_opt.is_some() {
                     *args.total_duration.lock() = next_duration_opt;
                 }
+                if let Some(info) = next_stream_info.take() {
+                    *args.stream_info.lock() = info;
+                    let _ = args.pcmd_tx.send(PlayerCmd::MetadataChanged);
+                }
             }
         }
     }
@@ -770,16 +980,22 @@ struct QueueNextOptions {
     gapless_decode: bool,
     /// Enable or disable soundtouch speed modifier or with `false` use rodio's speed modifier
     soundtouch: bool,
+    /// Keep the pitch when changing the speed without soundtouch, instead of using rodio's speed modifier
+    pitch_correction: bool,
     /// Determines which append function and which duration type to use.
     enqueue: bool,
     /// Determines the size of the [`BufferedSource`].
     file_buf_size: usize,
     /// Determines the size of the [`AsyncRingSource`].
     ringbuf_size: usize,
+    /// Determines which ReplayGain value to apply to music tracks.
+    replay_gain: ReplayGainMode,
+    /// Crossfade from the current music track to the enqueued one over the given duration.
+    crossfade: Option<Duration>,
 }
 
 /// Queue the given track into the [`Sink`], while also setting all of the other variables
-#[allow(clippy::too_many_arguments, clippy::too_many_lines)]
+#[allow(clippy::too_many_arguments)]
 async fn queue_next(
     track: &Track,
     sink: &Sink,
@@ -789,8 +1005,45 @@ async fn queue_next(
     total_duration: &ArcTotalDuration,
     next_duration_opt: &mut Option<Duration>,
     media_title: &Arc<Mutex<String>>,
+    stream_info: &Arc<Mutex<StreamInfo>>,
+    next_stream_info: &mut Option<StreamInfo>,
     pcmd_tx: &PlayerCmdSender,
 ) -> Result<()> {
+    let info = queue_next_source(
+        track,
+        sink,
+        options,
+        is_radio,
+        total_duration,
+        next_duration_opt,
+        media_title,
+        pcmd_tx,
+    )
+    .await?;
+
+    if options.enqueue {
+        *next_stream_info = Some(info);
+    } else {
+        *stream_info.lock() = info;
+        let _ = pcmd_tx.send(PlayerCmd::MetadataChanged);
+    }
+
+    Ok(())
+}
+
+/// Create the decoder for the given track and append it to the [`Sink`], returning its [`StreamInfo`]
+#[allow(clippy::too_many_arguments, clippy::too_many_lines)]
+async fn queue_next_source(
+    track: &Track,
+    sink: &Sink,
+    options: QueueNextOptions,
+
+    is_radio: &mut bool,
+    total_duration: &ArcTotalDuration,
+    next_duration_opt: &mut Option<Duration>,
+    media_title: &Arc<Mutex<String>>,
+    pcmd_tx: &PlayerCmdSender,
+) -> Result<StreamInfo> {
     // clear out the sources when we dont "enqueue" as we want to directly play it
     if !options.enqueue && !sink.is_empty() {
         // dont have the source that are cleared-out send a EOS as that the player-trait does not know about those sources
@@ -802,10 +1055,14 @@ async fn queue_next(
     match track.inner() {
         MediaTypes::Track(track_data) => {
             *is_radio = false;
-            let file_path = track_data.path();
+            let file_path = track_data.audio_path();
             let file = File::open(file_path).context("Failed to open music file")?;
+            let gain = track_data
+                .replay_gain()
+                .and_then(|v| v.factor(options.replay_gain))
+                .unwrap_or(1.0);
 
-            if options.enqueue {
+            let info = if options.enqueue {
                 append_to_sink_queue(
                     Box::new(BufferedSource::new(file, options.file_buf_size)),
                     file_path.display(),
@@ -813,12 +1070,15 @@ async fn queue_next(
                     &CommonAppendOptions {
                         gapless_decode: options.gapless_decode,
                         soundtouch: options.soundtouch,
+                        pitch_correction: options.pitch_correction,
                         ringbuf_size: options.ringbuf_size,
                         async_decode: true,
+                        gain,
+                        crossfade: options.crossfade,
                     },
                     next_duration_opt,
                     common_media_title_cb(media_title.clone(), pcmd_tx),
-                )?;
+                )?
             } else {
                 append_to_sink(
                     Box::new(BufferedSource::new(file, options.file_buf_size)),
@@ -827,15 +1087,18 @@ async fn queue_next(
                     &CommonAppendOptions {
                         gapless_decode: options.gapless_decode,
                         soundtouch: options.soundtouch,
+                        pitch_correction: options.pitch_correction,
                         ringbuf_size: options.ringbuf_size,
                         async_decode: true,
+                        gain,
+                        crossfade: None,
                     },
                     total_duration,
                     common_media_title_cb(media_title.clone(), pcmd_tx),
-                )?;
-            }
+                )?
+            };
 
-            Ok(())
+            Ok(info)
         }
         MediaTypes::Radio(radio_track_data) => {
             *is_radio = true;
@@ -853,6 +1116,12 @@ async fn queue_next(
                 .and_then(|v| v.parse().ok())
                 .and_then(NonZeroU16::new);
             let icy_description = stream.header("icy-description").map(ToString::to_string);
+            // the bitrate in kbps, some servers send multiple values separated by ","
+            let icy_bitrate = stream
+                .header("icy-br")
+                .and_then(|v| v.split(',').next())
+                .and_then(|v| v.trim().parse::<u32>().ok())
+                .map(|v| v.saturating_mul(1000));
 
             let reader = StreamDownload::from_stream(
                 stream,
@@ -900,7 +1169,7 @@ async fn queue_next(
                 Box::new(ReadOnlySource::new(reader))
             };
 
-            if options.enqueue {
+            let mut info = if options.enqueue {
                 append_to_sink_queue_no_duration(
                     media_source,
                     url,
@@ -908,11 +1177,14 @@ async fn queue_next(
                     &CommonAppendOptions {
                         gapless_decode: options.gapless_decode,
                         soundtouch: options.soundtouch,
+                        pitch_correction: options.pitch_correction,
                         ringbuf_size: options.ringbuf_size,
                         async_decode: false,
+                        gain: 1.0,
+                        crossfade: None,
                     },
                     next_duration_opt,
-                )?;
+                )?
             } else {
                 append_to_sink_no_duration(
                     media_source,
@@ -921,21 +1193,25 @@ async fn queue_next(
                     &CommonAppendOptions {
                         gapless_decode: options.gapless_decode,
                         soundtouch: options.soundtouch,
+                        pitch_correction: options.pitch_correction,
                         ringbuf_size: options.ringbuf_size,
                         async_decode: false,
+                        gain: 1.0,
+                        crossfade: None,
                     },
                     total_duration,
-                )?;
-            }
+                )?
+            };
+            info.bitrate = icy_bitrate;
 
-            Ok(())
+            Ok(info)
         }
         MediaTypes::Podcast(podcast_track_data) => {
             *is_radio = false;
             if let Some(file_path) = podcast_track_data.localfile() {
                 let file = File::open(Path::new(&file_path))
                     .context("Failed to open local podcast file")?;
-                if options.enqueue {
+                let info = if options.enqueue {
                     append_to_sink_queue(
                         Box::new(BufferedSource::new(file, options.file_buf_size)),
                         file_path.display(),
@@ -943,12 +1219,15 @@ async fn queue_next(
                         &CommonAppendOptions {
                             gapless_decode: options.gapless_decode,
                             soundtouch: options.soundtouch,
+                            pitch_correction: options.pitch_correction,
                             ringbuf_size: options.ringbuf_size,
                             async_decode: true,
+                            gain: 1.0,
+                            crossfade: None,
                         },
                         next_duration_opt,
                         common_media_title_cb(media_title.clone(), pcmd_tx),
-                    )?;
+                    )?
                 } else {
                     append_to_sink(
                         Box::new(BufferedSource::new(file, options.file_buf_size)),
@@ -957,14 +1236,17 @@ async fn queue_next(
                         &CommonAppendOptions {
                             gapless_decode: options.gapless_decode,
                             soundtouch: options.soundtouch,
+                            pitch_correction: options.pitch_correction,
                             ringbuf_size: options.ringbuf_size,
                             async_decode: true,
+                            gain: 1.0,
+                            crossfade: None,
                         },
                         total_duration,
                         common_media_title_cb(media_title.clone(), pcmd_tx),
-                    )?;
-                }
-                return Ok(());
+                    )?
+                };
+                return Ok(info);
             }
 
             let url = podcast_track_data.url();
@@ -981,7 +1263,7 @@ async fn queue_next(
             )
             .await?;
 
-            if options.enqueue {
+            let info = if options.enqueue {
                 append_to_sink_queue(
                     Box::new(ReadSeekSource::new(reader, file_len)),
                     url,
@@ -989,12 +1271,15 @@ async fn queue_next(
                     &CommonAppendOptions {
                         gapless_decode: options.gapless_decode,
                         soundtouch: options.soundtouch,
+                        pitch_correction: options.pitch_correction,
                         ringbuf_size: options.ringbuf_size,
                         async_decode: false,
+                        gain: 1.0,
+                        crossfade: None,
                     },
                     next_duration_opt,
                     common_media_title_cb(media_title.clone(), pcmd_tx),
-                )?;
+                )?
             } else {
                 append_to_sink(
                     Box::new(ReadSeekSource::new(reader, file_len)),
@@ -1003,14 +1288,17 @@ async fn queue_next(
                     &CommonAppendOptions {
                         gapless_decode: options.gapless_decode,
                         soundtouch: options.soundtouch,
+                        pitch_correction: options.pitch_correction,
                         ringbuf_size: options.ringbuf_size,
                         async_decode: false,
+                        gain: 1.0,
+                        crossfade: None,
                     },
                     total_duration,
                     common_media_title_cb(media_title.clone(), pcmd_tx),
-                )?;
-            }
-            Ok(())
+                )?
+            };
+            Ok(info)
         }
     }
 }
@@ -0,0 +1,204 @@
+//! Output device handling for the rusty backend.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use rodio::cpal::BufferSize;
+use rodio::cpal::traits::HostTrait as _;
+use rodio::mixer::Mixer;
+use rodio::queue::SourcesQueueOutput;
+use rodio::{
+    ChannelCount, Device, DeviceTrait as _, OutputStream, OutputStreamBuilder, Sample, SampleRate,
+    Source,
+};
+use termusiclib::config::v2::server::backends::StreamOutput;
+
+use super::pcm_stream::PcmStream;
+use crate::OutputInfo;
+
+/// Where the rusty backend plays to.
+pub enum Output {
+    /// A output device of the default host, with its name
+    Device(OutputStream, String),
+    /// A raw PCM stream, see [`StreamOutput`]
+    Stream(PcmStream),
+}
+
+impl Output {
+    /// Open the PCM stream to `stream_output` if set, otherwise the output device named `name`, see [`open_output_stream`].
+    pub fn open(
+        stream_output: Option<&StreamOutput>,
+        name: Option<&str>,
+        sample_rate: u32,
+    ) -> Result<Self> {
+        match stream_output {
+            Some(target) => Ok(Self::Stream(PcmStream::open(target, sample_rate)?)),
+            None => {
+                let (stream, name) = open_output_stream(name, sample_rate)?;
+                Ok(Self::Device(stream, name))
+            }
+        }
+    }
+
+    /// The mixer to add the sources to play to.
+    pub fn mixer(&self) -> &Mixer {
+        match self {
+            Self::Device(stream, _) => stream.mixer(),
+            Self::Stream(stream) => stream.mixer(),
+        }
+    }
+
+    /// Do not log when the output device is closed, as it is expected when switching devices.
+    pub fn log_on_drop(&mut self, enabled: bool) {
+        if let Self::Device(stream, _) = self {
+            stream.log_on_drop(enabled);
+        }
+    }
+
+    /// Get the device and format of the output.
+    pub fn info(&self) -> OutputInfo {
+        match self {
+            Self::Device(stream, name) => {
+                let config = stream.config();
+                let buffer_frames = match config.buffer_size() {
+                    BufferSize::Fixed(frames) => Some(*frames),
+                    BufferSize::Default => None,
+                };
+
+                OutputInfo {
+                    device: Some(name.clone()),
+                    sample_rate: Some(config.sample_rate()),
+                    channels: Some(config.channel_count()),
+                    buffer_frames,
+                    ..Default::default()
+                }
+            }
+            Self::Stream(stream) => stream.info(),
+        }
+    }
+}
+
+/// Get the names of all output devices of the default host.
+///
+/// Devices whose name cannot be read are skipped.
+pub fn output_device_names() -> Vec<String> {
+    let devices = match rodio::cpal::default_host().output_devices() {
+        Ok(v) => v,
+        Err(err) => {
+            warn!("Could not list output devices: {err}");
+            return Vec::new();
+        }
+    };
+
+    devices.filter_map(|device| device.name().ok()).collect()
+}
+
+/// Find the output device of the default host named `name`.
+fn find_output_device(name: &str) -> Option<Device> {
+    rodio::cpal::default_host()
+        .output_devices()
+        .ok()?
+        .find(|device| device.name().is_ok_and(|v| v == name))
+}
+
+/// Open a output stream on the device named `name`, returning the stream and the name of the device used.
+///
+/// Uses the default output device if `name` is `None` or there is no device with that name.
+pub fn open_output_stream(name: Option<&str>, sample_rate: u32) -> Result<(OutputStream, String)> {
+    let device = name.and_then(|name| {
+        let device = find_output_device(name);
+        if device.is_none() {
+            warn!("Output device {name:#?} not found, using the default device");
+        }
+        device
+    });
+    let device = match device {
+        Some(device) => device,
+        None => rodio::cpal::default_host()
+            .default_output_device()
+            .context("get default output device")?,
+    };
+    let name = device.name().unwrap_or_else(|_| "unknown".to_string());
+
+    let stream = OutputStreamBuilder::from_device(device)
+        .context("get output device")?
+        .with_sample_rate(sample_rate)
+        .open_stream_or_fallback()
+        .context("open output stream")?;
+
+    Ok((stream, name))
+}
+
+/// A [`SourcesQueueOutput`] that can be moved to another output stream without losing the queued sources.
+///
+/// Every call to [`SwitchableQueue::output`] ends all previously returned outputs.
+#[derive(Clone)]
+pub struct SwitchableQueue {
+    queue: Arc<Mutex<SourcesQueueOutput>>,
+    generation: Arc<AtomicUsize>,
+}
+
+impl SwitchableQueue {
+    pub fn new(queue: SourcesQueueOutput) -> Self {
+        Self {
+            queue: Arc::new(Mutex::new(queue)),
+            generation: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Get a new [`Source`] to add to a mixer, ending all previously returned ones.
+    pub fn output(&self) -> SwitchableQueueOutput {
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+
+        SwitchableQueueOutput {
+            inner: self.clone(),
+            generation,
+        }
+    }
+}
+
+/// A [`Source`] reading from a [`SwitchableQueue`], until a newer output is requested.
+pub struct SwitchableQueueOutput {
+    inner: SwitchableQueue,
+    generation: usize,
+}
+
+impl SwitchableQueueOutput {
+    /// Whether a newer output has been requested, in which case this one should end.
+    fn is_replaced(&self) -> bool {
+        self.inner.generation.load(Ordering::SeqCst) != self.generation
+    }
+}
+
+impl Iterator for SwitchableQueueOutput {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_replaced() {
+            return None;
+        }
+
+        self.inner.queue.lock().next()
+    }
+}
+
+impl Source for SwitchableQueueOutput {
+    fn current_span_len(&self) -> Option<usize> {
+        self.inner.queue.lock().current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.inner.queue.lock().channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.inner.queue.lock().sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
@@ -0,0 +1,199 @@
+//! Write the output of the rusty backend as a raw PCM stream instead of playing it on a output device,
+//! see [`StreamOutput`] for where it is written to.
+//!
+//! The stream is written in real time, so the playback position and controls behave as if played locally.
+
+use std::fs::File;
+use std::io::Write;
+use std::net::TcpListener;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use rodio::mixer::{Mixer, MixerSource};
+use rodio::{ChannelCount, Source};
+use termusiclib::config::v2::server::backends::StreamOutput;
+
+use crate::OutputInfo;
+
+/// The stream is always stereo, as expected by Snapcast's default `sampleformat`.
+const CHANNELS: ChannelCount = 2;
+
+/// How often to write per second, which writes 20ms at once.
+const CHUNKS_PER_SEC: u32 = 50;
+
+/// How long to wait before trying to open the pipe again, if opening failed.
+const REOPEN_DELAY: Duration = Duration::from_secs(1);
+
+/// The readers currently receiving the stream, added by a thread waiting for them.
+type Writers = Arc<Mutex<Vec<Box<dyn Write + Send>>>>;
+
+/// A raw PCM stream of signed 16 bit little-endian samples, stops writing once dropped.
+pub struct PcmStream {
+    mixer: Mixer,
+    stop: Arc<AtomicBool>,
+    /// Where the stream is written to, to show the user
+    target: String,
+    sample_rate: u32,
+}
+
+impl PcmStream {
+    /// Start writing to `target`, at `sample_rate`.
+    ///
+    /// # Errors
+    ///
+    /// - if the tcp address cannot be bound
+    pub fn open(target: &StreamOutput, sample_rate: u32) -> Result<Self> {
+        let writers = Writers::default();
+        let stop = Arc::new(AtomicBool::new(false));
+
+        match target {
+            StreamOutput::Pipe { path } => {
+                let writers = writers.clone();
+                let stop = stop.clone();
+                let path = path.clone();
+                std::thread::Builder::new()
+                    .name("playback pcm pipe".into())
+                    .spawn(move || open_pipe_loop(&path, &writers, &stop))?;
+            }
+            StreamOutput::Tcp { address } => {
+                let listener = TcpListener::bind(address)
+                    .with_context(|| format!("bind pcm stream to {address}"))?;
+                let writers = writers.clone();
+                let stop = stop.clone();
+                std::thread::Builder::new()
+                    .name("playback pcm tcp".into())
+                    .spawn(move || accept_loop(&listener, &writers, &stop))?;
+            }
+        }
+
+        let (mixer, source) = rodio::mixer::mixer(CHANNELS, sample_rate);
+        {
+            let stop = stop.clone();
+            std::thread::Builder::new()
+                .name("playback pcm stream".into())
+                .spawn(move || write_loop(source, &writers, &stop))?;
+        }
+        let target = match target {
+            StreamOutput::Pipe { path } => format!("pipe {}", path.display()),
+            StreamOutput::Tcp { address } => format!("tcp {address}"),
+        };
+        info!("Writing pcm stream at {sample_rate}Hz to {target}");
+
+        Ok(Self {
+            mixer,
+            stop,
+            target,
+            sample_rate,
+        })
+    }
+
+    /// The mixer to add the sources to play to.
+    pub fn mixer(&self) -> &Mixer {
+        &self.mixer
+    }
+
+    /// Get where and in which format the stream is written.
+    pub fn info(&self) -> OutputInfo {
+        OutputInfo {
+            device: Some(self.target.clone()),
+            sample_rate: Some(self.sample_rate),
+            channels: Some(CHANNELS),
+            buffer_frames: Some(self.sample_rate / CHUNKS_PER_SEC),
+            ..Default::default()
+        }
+    }
+}
+
+impl Drop for PcmStream {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Open the pipe at `path` whenever there is no writer for it.
+///
+/// Opening a named pipe for writing blocks until a reader opens it.
+fn open_pipe_loop(path: &Path, writers: &Writers, stop: &AtomicBool) {
+    while !stop.load(Ordering::SeqCst) {
+        if !writers.lock().is_empty() {
+            std::thread::sleep(REOPEN_DELAY);
+            continue;
+        }
+
+        match File::options().write(true).open(path) {
+            Ok(file) => {
+                info!("Pcm stream reader opened {}", path.display());
+                writers.lock().push(Box::new(file));
+            }
+            Err(err) => {
+                warn!("Could not open pcm stream pipe {}: {err}", path.display());
+                std::thread::sleep(REOPEN_DELAY);
+            }
+        }
+    }
+}
+
+/// Add every client connecting to `listener`.
+fn accept_loop(listener: &TcpListener, writers: &Writers, stop: &AtomicBool) {
+    for stream in listener.incoming() {
+        if stop.load(Ordering::SeqCst) {
+            break;
+        }
+
+        match stream {
+            Ok(stream) => {
+                info!("Pcm stream client connected: {:?}", stream.peer_addr());
+                // a stalled client should not stall the other clients for long
+                let _ = stream.set_write_timeout(Some(Duration::from_secs(1)));
+                let _ = stream.set_nodelay(true);
+                writers.lock().push(Box::new(stream));
+            }
+            Err(err) => warn!("Error accepting pcm stream client: {err}"),
+        }
+    }
+}
+
+/// Write `source` to all `writers` in real time, discarding it while there are none.
+fn write_loop(mut source: MixerSource, writers: &Writers, stop: &AtomicBool) {
+    let sample_rate = source.sample_rate();
+    let chunk_frames = (sample_rate / CHUNKS_PER_SEC).max(1);
+    let chunk_samples = usize::try_from(chunk_frames).unwrap_or(usize::MAX) * usize::from(CHANNELS);
+    let mut buf = Vec::with_capacity(chunk_samples * size_of::<i16>());
+    let start = Instant::now();
+    let mut written_frames: u64 = 0;
+
+    while !stop.load(Ordering::SeqCst) {
+        buf.clear();
+        for _ in 0..chunk_samples {
+            // the mixer has no samples while nothing is playing
+            let sample = source.next().unwrap_or(0.0);
+            buf.extend_from_slice(&to_i16(sample).to_le_bytes());
+        }
+
+        writers
+            .lock()
+            .retain_mut(|writer| match writer.write_all(&buf) {
+                Ok(()) => true,
+                Err(err) => {
+                    info!("Pcm stream reader disconnected: {err}");
+                    false
+                }
+            });
+
+        written_frames += u64::from(chunk_frames);
+        let written = Duration::from_micros(written_frames * 1_000_000 / u64::from(sample_rate));
+        if let Some(wait) = written.checked_sub(start.elapsed()) {
+            std::thread::sleep(wait);
+        }
+    }
+}
+
+/// Convert a sample in the range `-1.0..=1.0` to a signed 16 bit sample.
+#[allow(clippy::cast_possible_truncation)] // clamped to the range of i16
+fn to_i16(sample: f32) -> i16 {
+    (sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)).round() as i16
+}
@@ -11,6 +11,8 @@ use rodio::{Source, queue};
 
 use super::PlayerInternalCmd;
 use super::source::SourceExt as _;
+use super::source::detachable::{self, BoxedSource, DetachHandle};
+use super::source::skip_silence::SkipSilenceOptions;
 use super::source::{SampleType, SpecificType};
 use crate::PlayerCmd;
 
@@ -27,6 +29,10 @@ pub struct Sink {
     controls: Arc<Controls>,
     /// Indicates how many sources are currently in the queue.
     sound_count: Arc<AtomicUsize>,
+    /// The mixer a crossfaded-out source continues to play on, next to the queue.
+    tail_mixer: Mutex<Option<Mixer>>,
+    /// Handle to take the last added source out of the queue, for crossfading.
+    last_detach: Mutex<Option<DetachHandle>>,
 
     picmd_tx: Sender<PlayerInternalCmd>,
     pcmd_tx: crate::PlayerCmdSender,
@@ -50,6 +56,8 @@ struct Controls {
     ///
     /// Automatically gets reset to `false` once cleared.
     stopped_no_eos: AtomicBool,
+    /// Stores how to shorten silences, `None` to play everything.
+    skip_silence: Mutex<Option<SkipSilenceOptions>>,
     /// Stores the speed to be applied.
     speed: Mutex<f32>,
     /// Stores how many sources should be skipped.
@@ -61,14 +69,25 @@ struct Controls {
 }
 
 /// Options to apply to a specific source
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct SourceOptions {
     pub soundtouch: bool,
+    /// Keep the pitch when the speed changes without soundtouch, instead of resampling
+    pub pitch_correction: bool,
+    /// Factor to apply to the source on top of the volume, like from ReplayGain
+    pub gain: f32,
+    /// Fade the previous source out, while this source fades in, over the given duration
+    pub crossfade: Option<Duration>,
 }
 
 impl Default for SourceOptions {
     fn default() -> Self {
-        Self { soundtouch: true }
+        Self {
+            soundtouch: true,
+            pitch_correction: true,
+            gain: 1.0,
+            crossfade: None,
+        }
     }
 }
 
@@ -83,6 +102,7 @@ impl Sink {
     ) -> Self {
         let (sink, queue_rx) = Self::new_idle(picmd_tx, pcmd_tx);
         mixer.add(queue_rx);
+        sink.set_tail_mixer(mixer.clone());
         sink
     }
 
@@ -102,12 +122,15 @@ impl Sink {
                 volume: Mutex::new(1.0),
                 stopped: AtomicBool::new(false),
                 stopped_no_eos: AtomicBool::new(false),
+                skip_silence: Mutex::new(None),
                 seek: Mutex::new(None),
                 speed: Mutex::new(1.0),
                 to_clear: Mutex::new(0),
                 position: RwLock::new(Duration::from_secs(0)),
             }),
             sound_count: Arc::new(AtomicUsize::new(0)),
+            tail_mixer: Mutex::new(None),
+            last_detach: Mutex::new(None),
             picmd_tx,
             pcmd_tx,
         };
@@ -132,19 +155,37 @@ impl Sink {
         }
 
         let controls = self.controls.clone();
+        // set once the source got crossfaded out, after which it should not control the sink anymore
+        let detached = Arc::new(AtomicBool::new(false));
+
+        let source: BoxedSource = match options.crossfade {
+            Some(duration) => Box::new(source.fade_in(duration)),
+            None => Box::new(source),
+        };
 
         let progress_tx = self.picmd_tx.clone();
+        let detached_progress = detached.clone();
+        let detached_controls = detached.clone();
         let source = source
+            .amplify(options.gain)
             .track_position()
-            .custom_speed(1.0, SpecificType::soundtouch(options.soundtouch))
+            // after the position, so that the skipped silences still count towards it
+            .skip_silence()
+            .custom_speed(
+                1.0,
+                SpecificType::with_pitch_correction(options.soundtouch, options.pitch_correction),
+            )
             .amplify(1.0)
             .pausable(false)
             .skippable()
             // as of rodio 0.20.x, "stoppable" is the same as "skippable"
             // .stoppable()
             .periodic_access(Duration::from_millis(500), move |src| {
+                if detached_progress.load(Ordering::SeqCst) {
+                    return;
+                }
                 let _ = progress_tx.send(PlayerInternalCmd::Progress(
-                    src.inner().inner().inner().inner().get_pos(),
+                    src.inner().inner().inner().inner().inner().get_pos(),
                 ));
             })
             .periodic_access(Duration::from_millis(5), move |src| {
@@ -153,6 +194,15 @@ impl Sink {
                     src.skip();
                     // reset position to be at 0, otherwise the position could be stale if there is no new source
                     *controls.position.write() = Duration::ZERO;
+                } else if detached_controls.load(Ordering::SeqCst) {
+                    // the queue has already moved on to the next source, only follow the playback controls
+                    let amp = src.inner_mut();
+                    amp.inner_mut().set_factor(*controls.volume.lock());
+                    amp.set_paused(controls.pause.load(Ordering::SeqCst));
+
+                    amp.inner_mut()
+                        .inner_mut()
+                        .set_factor(*controls.speed.lock());
                 } else {
                     if let Some(seek_time) = controls.seek.lock().take() {
                         let _ = src.try_seek(seek_time);
@@ -168,18 +218,21 @@ impl Sink {
                             return;
                         }
                     }
-                    *controls.position.write() = src.inner().inner().inner().inner().get_pos();
+                    *controls.position.write() =
+                        src.inner().inner().inner().inner().inner().get_pos();
 
                     let amp = src.inner_mut();
                     amp.inner_mut().set_factor(*controls.volume.lock());
                     amp.set_paused(controls.pause.load(Ordering::SeqCst));
 
-                    amp.inner_mut()
-                        .inner_mut()
-                        .set_factor(*controls.speed.lock());
+                    let speed = amp.inner_mut().inner_mut();
+                    speed.set_factor(*controls.speed.lock());
+                    speed.inner_mut().set_options(*controls.skip_silence.lock());
                 }
             });
 
+        let (source, detach_handle) = detachable::detachable(Box::new(source), detached);
+
         self.sound_count.fetch_add(1, Ordering::Relaxed);
 
         // the following allows us to avoid having to have a thread that waits for each sound to end to send a signal
@@ -205,6 +258,35 @@ impl Sink {
         });
 
         *self.sleep_until_end.lock() = Some(self.queue_tx.append_with_signal(source));
+
+        let previous = self.last_detach.lock().replace(detach_handle);
+        if let Some(duration) = options.crossfade {
+            self.crossfade_out(previous, duration);
+        }
+    }
+
+    /// Take the `previous` source out of the queue and let it fade out on the tail mixer.
+    ///
+    /// This ends the `previous` source in the queue, so the queue continues with the next source.
+    fn crossfade_out(&self, previous: Option<DetachHandle>, duration: Duration) {
+        let tail_mixer = self.tail_mixer.lock();
+        let Some(tail_mixer) = &*tail_mixer else {
+            return;
+        };
+        // the previous source may have already ended
+        let Some(previous) = previous.and_then(|v| v.detach()) else {
+            return;
+        };
+
+        tail_mixer.add(previous.fade_out(duration).take_duration(duration));
+    }
+
+    /// Set the mixer crossfaded-out sources continue to play on.
+    ///
+    /// This should be the same mixer the queue is played on.
+    #[inline]
+    pub fn set_tail_mixer(&self, mixer: Mixer) {
+        *self.tail_mixer.lock() = Some(mixer);
     }
 
     /// Gets the volume of the sound.
@@ -243,6 +325,12 @@ impl Sink {
         *self.controls.speed.lock() = value;
     }
 
+    /// Shortens silences with `options`, or plays everything with `None`.
+    #[inline]
+    pub fn set_skip_silence(&self, options: Option<SkipSilenceOptions>) {
+        *self.controls.skip_silence.lock() = options;
+    }
+
     /// Resumes playback of a paused sink.
     ///
     /// No effect if not paused.
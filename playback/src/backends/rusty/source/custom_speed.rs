@@ -7,6 +7,7 @@ use super::SampleType;
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum SpecificType {
     Rodio,
+    TimeStretch,
     #[cfg(feature = "rusty-soundtouch")]
     Soundtouch,
 }
@@ -23,6 +24,15 @@ impl SpecificType {
 
         Self::Rodio
     }
+
+    /// Like [`Self::soundtouch`], but keep the pitch without soundtouch too with `pitch_correction`.
+    #[must_use]
+    pub fn with_pitch_correction(soundtouch: bool, pitch_correction: bool) -> Self {
+        match Self::soundtouch(soundtouch) {
+            Self::Rodio if pitch_correction => Self::TimeStretch,
+            specific => specific,
+        }
+    }
 }
 
 #[allow(clippy::needless_return)]
@@ -32,6 +42,10 @@ where
 {
     match specific {
         SpecificType::Rodio => CustomSpeed::Rodio(input.speed(initial_speed)),
+        SpecificType::TimeStretch => {
+            trace!("Using time-stretching");
+            CustomSpeed::TimeStretch(super::time_stretch::time_stretch(input, initial_speed))
+        }
         #[cfg(feature = "rusty-soundtouch")]
         SpecificType::Soundtouch => {
             trace!("Using soundtouch");
@@ -45,6 +59,7 @@ where
 #[allow(dead_code)]
 pub enum CustomSpeed<I> {
     Rodio(rodio::source::Speed<I>),
+    TimeStretch(super::time_stretch::TimeStretch<I>),
     #[cfg(feature = "rusty-soundtouch")]
     SoundTouch(super::soundtouch::SoundTouchSource<I>),
 }
@@ -96,6 +111,7 @@ where
     fn as_source(&self) -> &dyn Source<Item = SampleType> {
         match self {
             CustomSpeed::Rodio(speed) => speed,
+            CustomSpeed::TimeStretch(stretch) => stretch,
             #[cfg(feature = "rusty-soundtouch")]
             CustomSpeed::SoundTouch(soundtouch) => soundtouch,
         }
@@ -105,6 +121,7 @@ where
     fn as_source_mut(&mut self) -> &mut dyn Source<Item = SampleType> {
         match self {
             CustomSpeed::Rodio(speed) => speed,
+            CustomSpeed::TimeStretch(stretch) => stretch,
             #[cfg(feature = "rusty-soundtouch")]
             CustomSpeed::SoundTouch(soundtouch) => soundtouch,
         }
@@ -115,6 +132,7 @@ where
     pub fn inner(&self) -> &I {
         match self {
             CustomSpeed::Rodio(speed) => speed.inner(),
+            CustomSpeed::TimeStretch(stretch) => stretch.inner(),
             #[cfg(feature = "rusty-soundtouch")]
             CustomSpeed::SoundTouch(soundtouch) => soundtouch.inner(),
         }
@@ -126,6 +144,7 @@ where
     pub fn inner_mut(&mut self) -> &mut I {
         match self {
             CustomSpeed::Rodio(speed) => speed.inner_mut(),
+            CustomSpeed::TimeStretch(stretch) => stretch.inner_mut(),
             #[cfg(feature = "rusty-soundtouch")]
             CustomSpeed::SoundTouch(soundtouch) => soundtouch.inner_mut(),
         }
@@ -136,6 +155,7 @@ where
     pub fn set_factor(&mut self, factor: f32) {
         match self {
             CustomSpeed::Rodio(speed) => speed.set_factor(factor),
+            CustomSpeed::TimeStretch(stretch) => stretch.set_factor(factor),
             #[cfg(feature = "rusty-soundtouch")]
             CustomSpeed::SoundTouch(soundtouch) => soundtouch.set_factor(f64::from(factor)),
         }
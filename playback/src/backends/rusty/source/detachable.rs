@@ -0,0 +1,126 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use rodio::{Source, source::SeekError};
+
+use super::SampleType;
+
+/// The boxed source type a [`Detachable`] wraps.
+pub type BoxedSource = Box<dyn Source + Send>;
+
+/// A source which can be taken out of wherever it is currently played (like a queue) via its [`DetachHandle`].
+///
+/// Once detached, this source ends, while the taken source continues where it left off.
+pub struct Detachable {
+    input: Arc<Mutex<Option<BoxedSource>>>,
+    // reported once the inner source is gone
+    channels: u16,
+    sample_rate: u32,
+}
+
+/// Handle to take the source out of a [`Detachable`].
+#[derive(Clone)]
+pub struct DetachHandle {
+    input: Arc<Mutex<Option<BoxedSource>>>,
+    detached: Arc<AtomicBool>,
+}
+
+impl std::fmt::Debug for DetachHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DetachHandle")
+            .field("detached", &self.detached)
+            .finish_non_exhaustive()
+    }
+}
+
+impl DetachHandle {
+    /// Take the source out of the [`Detachable`], ending it.
+    ///
+    /// Returns [`None`] if the source had already ended or was already taken.
+    pub fn detach(&self) -> Option<BoxedSource> {
+        let input = self.input.lock().take();
+        if input.is_some() {
+            self.detached.store(true, Ordering::SeqCst);
+        }
+
+        input
+    }
+}
+
+/// Wrap the `input` source in a [`Detachable`].
+///
+/// `detached` gets set once the source is taken out, for parts of the source that should behave differently afterwards.
+pub fn detachable(input: BoxedSource, detached: Arc<AtomicBool>) -> (Detachable, DetachHandle) {
+    let channels = input.channels();
+    let sample_rate = input.sample_rate();
+    let input = Arc::new(Mutex::new(Some(input)));
+
+    let handle = DetachHandle {
+        input: input.clone(),
+        detached,
+    };
+
+    (
+        Detachable {
+            input,
+            channels,
+            sample_rate,
+        },
+        handle,
+    )
+}
+
+impl Iterator for Detachable {
+    type Item = SampleType;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut input = self.input.lock();
+        let next = input.as_mut()?.next();
+        if next.is_none() {
+            // drop the ended source, so that it cannot be detached anymore
+            *input = None;
+        }
+
+        next
+    }
+}
+
+impl Source for Detachable {
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        match &*self.input.lock() {
+            Some(source) => source.current_span_len(),
+            None => Some(0),
+        }
+    }
+
+    fn channels(&self) -> u16 {
+        self.input
+            .lock()
+            .as_ref()
+            .map_or(self.channels, |v| v.channels())
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input
+            .lock()
+            .as_ref()
+            .map_or(self.sample_rate, |v| v.sample_rate())
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.lock().as_ref().and_then(|v| v.total_duration())
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        match &mut *self.input.lock() {
+            Some(source) => source.try_seek(pos),
+            None => Ok(()),
+        }
+    }
+}
@@ -9,6 +9,9 @@ pub mod soundtouch;
 pub mod async_ring;
 mod cb_done;
 mod custom_speed;
+pub mod detachable;
+pub mod skip_silence;
+pub mod time_stretch;
 
 /// Our sample type we choose to use across all places
 pub type SampleType = f32;
@@ -30,6 +33,15 @@ pub trait SourceExt: Source {
         custom_speed::custom_speed(self, initial_speed, specific)
     }
 
+    /// Shorten silences, which is disabled until [`set_options`](skip_silence::SkipSilence::set_options) is called.
+    fn skip_silence(self) -> skip_silence::SkipSilence<Self>
+    where
+        Self: Sized,
+        Self: Source<Item = f32>,
+    {
+        skip_silence::skip_silence(self)
+    }
+
     /// Run a function once at the end of a source.
     fn cbdone<Fn: FnOnce()>(self, fun: Fn) -> cb_done::CbDone<Self, Fn>
     where
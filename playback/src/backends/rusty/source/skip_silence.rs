@@ -0,0 +1,216 @@
+//! Shorten silences, like the pauses in podcasts.
+//!
+//! Of each silence, only the first [`min_duration`](SkipSilenceOptions::min_duration) is played and the rest is dropped.
+//! The inner source is still read through the dropped part, so its position keeps matching the file.
+
+use std::time::Duration;
+
+use rodio::Source;
+use rodio::source::SeekError;
+
+use super::SampleType;
+
+/// How much silence may be dropped at once, before a silent frame is output anyway,
+/// so that a long silence does not stall the output while it is being read through.
+const MAX_SKIP: Duration = Duration::from_secs(1);
+
+/// How to detect and shorten silences.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SkipSilenceOptions {
+    /// How much of each silence is still played
+    pub min_duration: Duration,
+    /// The amplitude from `0.0` to `1.0` all channels have to be below to count as silent
+    pub threshold: f32,
+}
+
+/// Shorten the silences of `input`, which is disabled until [`set_options`](SkipSilence::set_options) is called.
+pub fn skip_silence<I>(input: I) -> SkipSilence<I>
+where
+    I: Source<Item = SampleType>,
+{
+    SkipSilence {
+        input,
+        options: None,
+        frame: Vec::new(),
+        frame_pos: 0,
+        silent_frames: 0,
+    }
+}
+
+#[derive(Debug)]
+pub struct SkipSilence<I> {
+    /// The inner source where we get the original samples from
+    input: I,
+    /// How to shorten silences, `None` to play everything
+    options: Option<SkipSilenceOptions>,
+    /// The samples of the current frame, which is only output once it is known whether it is silent
+    frame: Vec<SampleType>,
+    /// The index of the next sample of `frame` to output
+    frame_pos: usize,
+    /// How many silent frames in a row were read
+    silent_frames: u64,
+}
+
+impl<I> Iterator for SkipSilence<I>
+where
+    I: Source<Item = SampleType>,
+{
+    type Item = SampleType;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(sample) = self.frame.get(self.frame_pos) {
+            self.frame_pos += 1;
+            return Some(*sample);
+        }
+
+        let Some(options) = self.options else {
+            return self.input.next();
+        };
+
+        let sample_rate = u64::from(self.input.sample_rate());
+        let to_frames =
+            |duration: Duration| duration.as_micros() * u128::from(sample_rate) / 1_000_000;
+        let min_frames = to_frames(options.min_duration);
+        let max_skip = to_frames(MAX_SKIP);
+
+        let mut skipped = 0;
+        loop {
+            if !self.read_frame() {
+                return None;
+            }
+
+            if self.frame.iter().any(|v| v.abs() >= options.threshold) {
+                self.silent_frames = 0;
+                break;
+            }
+
+            self.silent_frames += 1;
+            if u128::from(self.silent_frames) <= min_frames || skipped >= max_skip {
+                break;
+            }
+            skipped += 1;
+        }
+
+        self.frame_pos = 1;
+        self.frame.first().copied()
+    }
+}
+
+impl<I> ExactSizeIterator for SkipSilence<I> where I: Source<Item = SampleType> + ExactSizeIterator {}
+
+impl<I> Source for SkipSilence<I>
+where
+    I: Source<Item = SampleType>,
+{
+    fn current_span_len(&self) -> Option<usize> {
+        self.input
+            .current_span_len()
+            .map(|len| len + self.frame.len() - self.frame_pos)
+    }
+
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+
+        self.frame.clear();
+        self.frame_pos = 0;
+        self.silent_frames = 0;
+
+        Ok(())
+    }
+}
+
+impl<I> SkipSilence<I>
+where
+    I: Source<Item = SampleType>,
+{
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Shorten silences with `options`, or play everything with `None`.
+    #[inline]
+    pub fn set_options(&mut self, options: Option<SkipSilenceOptions>) {
+        if options.is_none() {
+            self.silent_frames = 0;
+        }
+        self.options = options;
+    }
+
+    /// Read the next frame of all channels into `frame`.
+    ///
+    /// Returns `false` if the input has ended.
+    fn read_frame(&mut self) -> bool {
+        let channels = usize::from(self.input.channels()).max(1);
+        self.frame.clear();
+        self.frame_pos = 0;
+        self.frame.extend(self.input.by_ref().take(channels));
+
+        !self.frame.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+    use rodio::buffer::SamplesBuffer;
+
+    use super::{SkipSilenceOptions, skip_silence};
+
+    /// `secs` of stereo samples at 1000 Hz, all of `value`.
+    fn part(secs: usize, value: f32) -> Vec<f32> {
+        vec![value; secs * 1000 * 2]
+    }
+
+    #[test]
+    fn should_shorten_long_silences() {
+        let samples = [part(1, 0.5), part(2, 0.0), part(1, 0.5)].concat();
+
+        // disabled by default
+        let source = skip_silence(SamplesBuffer::new(2, 1000, samples.clone()));
+        assert_eq!(source.count(), samples.len());
+
+        let mut source = skip_silence(SamplesBuffer::new(2, 1000, samples));
+        source.set_options(Some(SkipSilenceOptions {
+            min_duration: Duration::from_secs(1),
+            threshold: 0.01,
+        }));
+        let output: Vec<f32> = source.collect();
+
+        assert_eq!(output, [part(1, 0.5), part(1, 0.0), part(1, 0.5)].concat());
+    }
+
+    #[test]
+    fn should_keep_short_silences() {
+        let samples = [part(1, 0.5), part(1, 0.001), part(1, 0.5)].concat();
+        let mut source = skip_silence(SamplesBuffer::new(2, 1000, samples.clone()));
+        source.set_options(Some(SkipSilenceOptions {
+            min_duration: Duration::from_secs(2),
+            threshold: 0.01,
+        }));
+
+        assert_eq!(source.collect::<Vec<f32>>(), samples);
+    }
+}
@@ -0,0 +1,403 @@
+//! Change the speed without changing the pitch, in pure Rust.
+//!
+//! This uses WSOLA (waveform similarity overlap-add): the output is made of overlapping windows of the input,
+//! each taken from around the position it should have at the current speed, at the offset that best continues the previous window.
+//! Unlike rodio's speed, which resamples, voices do not get higher or lower.
+
+use std::collections::VecDeque;
+use std::f32::consts::PI;
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use rodio::Source;
+use rodio::source::SeekError;
+
+use super::SampleType;
+
+/// The length of a window, in milliseconds.
+const WINDOW_MS: u32 = 30;
+/// How far around its position a window may be taken from to continue the previous one, in milliseconds.
+const SEARCH_MS: u32 = 10;
+/// Only compare every n-th frame when searching, which is still enough to find a similar waveform.
+const COMPARE_STRIDE: usize = 4;
+
+/// Change the speed of `input` to `factor`, while keeping its pitch.
+pub fn time_stretch<I>(input: I, factor: f32) -> TimeStretch<I>
+where
+    I: Source<Item = SampleType>,
+{
+    let mut source = TimeStretch {
+        input,
+        factor,
+        stretching: false,
+        channels: 0,
+        sample_rate: 0,
+        window: Vec::new(),
+        search: 0,
+        in_buffer: VecDeque::new(),
+        in_buffer_start: 0,
+        position: 0.0,
+        prev_start: None,
+        overlap: Vec::new(),
+        out_buffer: VecDeque::new(),
+    };
+    source.reset();
+
+    source
+}
+
+#[derive(Debug)]
+pub struct TimeStretch<I> {
+    /// The inner source where we get the original samples from
+    input: I,
+    /// The timescale factor. `1.0` means no change from the source.
+    factor: f32,
+    /// Whether the last sample was stretched, to continue seamlessly when switching from or to `1.0`
+    stretching: bool,
+    /// The channels `window` and `search` were calculated for
+    channels: usize,
+    /// The sample rate `window` and `search` were calculated for
+    sample_rate: u32,
+    /// The hann window applied to each window, which sums up to `1.0` when overlapping by half
+    window: Vec<f32>,
+    /// How many frames around its position a window may be taken from
+    search: usize,
+    /// Input samples that may still be part of a window
+    in_buffer: VecDeque<SampleType>,
+    /// The frame of the input since the last reset that `in_buffer` starts at
+    in_buffer_start: u64,
+    /// The frame of the input the next window should be taken from at the current speed
+    position: f64,
+    /// The frame of the input the previous window was taken from
+    prev_start: Option<u64>,
+    /// The sum of the windows overlapping the output that is not complete yet
+    overlap: Vec<SampleType>,
+    /// Already processed samples that still need to be output
+    out_buffer: VecDeque<SampleType>,
+}
+
+impl<I> Iterator for TimeStretch<I>
+where
+    I: Source<Item = SampleType>,
+{
+    type Item = SampleType;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // This is to skip calculation if speed is not changed
+        if (self.factor - 1.0).abs() < 0.01 {
+            if self.stretching {
+                self.stretching = false;
+                self.continue_unstretched();
+            }
+
+            return self
+                .out_buffer
+                .pop_front()
+                .or_else(|| self.in_buffer.pop_front())
+                .or_else(|| self.input.next());
+        }
+
+        if !self.stretching {
+            self.stretching = true;
+            self.reset();
+        }
+
+        if self.out_buffer.is_empty() {
+            self.get_new_samples();
+        }
+
+        self.out_buffer.pop_front()
+    }
+}
+
+impl<I> ExactSizeIterator for TimeStretch<I> where I: Source<Item = SampleType> + ExactSizeIterator {}
+
+impl<I> Source for TimeStretch<I>
+where
+    I: Source<Item = SampleType>,
+{
+    fn current_span_len(&self) -> Option<usize> {
+        if self.stretching {
+            // the format of the input is checked once every half window
+            return Some(self.window.len() / 2 * self.channels);
+        }
+
+        self.input
+            .current_span_len()
+            .map(|len| len + self.out_buffer.len() + self.in_buffer.len())
+    }
+
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+
+        self.out_buffer.clear();
+        self.in_buffer.clear();
+        self.reset();
+
+        Ok(())
+    }
+}
+
+impl<I> TimeStretch<I>
+where
+    I: Source<Item = SampleType>,
+{
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Modifies the speed factor.
+    #[inline]
+    pub fn set_factor(&mut self, factor: f32) {
+        self.factor = factor;
+    }
+
+    /// Start stretching again at the start of `in_buffer`, like after a seek.
+    ///
+    /// Buffered input is dropped if the format of the input changed, as it cannot be mixed with the new format.
+    fn reset(&mut self) {
+        let channels = usize::from(self.input.channels()).max(1);
+        let sample_rate = self.input.sample_rate();
+
+        if channels != self.channels || sample_rate != self.sample_rate {
+            self.in_buffer.clear();
+            self.channels = channels;
+            self.sample_rate = sample_rate;
+            self.window = hann_window(ms_to_frames(sample_rate, WINDOW_MS).max(2) / 2 * 2);
+            self.search = ms_to_frames(sample_rate, SEARCH_MS);
+        }
+
+        self.in_buffer_start = 0;
+        self.position = 0.0;
+        self.prev_start = None;
+        self.overlap.clear();
+        self.overlap.resize(self.window.len() * channels, 0.0);
+    }
+
+    /// Continue with the unstretched input where the output of the last window ends.
+    fn continue_unstretched(&mut self) {
+        if let Some(prev_start) = self.prev_start {
+            let hop = self.window.len() / 2;
+            let frames = (prev_start + hop as u64).saturating_sub(self.in_buffer_start);
+            let skip = usize::try_from(frames)
+                .unwrap_or(usize::MAX)
+                .saturating_mul(self.channels);
+            self.in_buffer.drain(..skip.min(self.in_buffer.len()));
+        }
+
+        self.prev_start = None;
+    }
+
+    /// Get the samples of the next half window into the `out_buffer`.
+    ///
+    /// Once the input has ended, the remaining input that is not a full window is dropped.
+    fn get_new_samples(&mut self) {
+        // in rodio and symphonia, any of these factors could have changed since the last time
+        if usize::from(self.input.channels()).max(1) != self.channels
+            || self.input.sample_rate() != self.sample_rate
+        {
+            self.reset();
+        }
+
+        let channels = self.channels;
+        let len = self.window.len();
+        let hop = len / 2;
+
+        // always positive and far from "u64::MAX"
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let nominal = self.position.round() as u64;
+        let to_frame = |frame: u64| usize::try_from(frame - self.in_buffer_start).unwrap();
+
+        let needed = (to_frame(nominal) + self.search + len) * channels;
+        let missing = needed.saturating_sub(self.in_buffer.len());
+        self.in_buffer.extend(self.input.by_ref().take(missing));
+
+        // this could only mean the inner source has ended
+        if self.in_buffer.len() < needed {
+            // fade out the last window
+            if self.prev_start.is_some() {
+                self.out_buffer.extend(self.overlap.drain(..hop * channels));
+            }
+            self.prev_start = None;
+            self.in_buffer.clear();
+
+            return;
+        }
+
+        let samples = self.in_buffer.make_contiguous();
+        let start = match self.prev_start {
+            Some(prev_start) => {
+                let candidates = to_frame(
+                    nominal
+                        .saturating_sub(self.search as u64)
+                        .max(self.in_buffer_start),
+                )..=to_frame(nominal) + self.search;
+                // the previous window continues at "prev_start + hop", which is always buffered
+                let target = to_frame(prev_start + hop as u64);
+                best_start(samples, channels, target, candidates, hop)
+            }
+            None => {
+                // without a previous window, the first half is completed to the unchanged input
+                for (idx, weight) in self.window[..hop].iter().enumerate() {
+                    for channel in 0..channels {
+                        let sample = idx * channels + channel;
+                        self.overlap[sample] += samples[sample] * (1.0 - weight);
+                    }
+                }
+
+                0
+            }
+        };
+
+        for (idx, weight) in self.window.iter().enumerate() {
+            for channel in 0..channels {
+                self.overlap[idx * channels + channel] +=
+                    samples[(start + idx) * channels + channel] * weight;
+            }
+        }
+
+        // the first half is complete, as no later window overlaps it
+        self.out_buffer.extend(self.overlap.drain(..hop * channels));
+        self.overlap.resize(len * channels, 0.0);
+
+        let start = self.in_buffer_start + start as u64;
+        self.prev_start = Some(start);
+        #[allow(clippy::cast_precision_loss)] // a window is a few thousand frames at most
+        let step = hop as f64 * f64::from(self.factor);
+        self.position += step;
+
+        // drop the input that neither the next window nor its continuation can be taken from
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let keep_from = (self.position.round() as u64)
+            .saturating_sub(self.search as u64)
+            .min(start + hop as u64)
+            .max(self.in_buffer_start);
+        let drop = to_frame(keep_from) * channels;
+        self.in_buffer.drain(..drop.min(self.in_buffer.len()));
+        self.in_buffer_start = keep_from;
+    }
+}
+
+/// Get the frame of `candidates` in `samples` whose following `len` frames are the most similar to the ones at `target`.
+fn best_start(
+    samples: &[SampleType],
+    channels: usize,
+    target: usize,
+    candidates: RangeInclusive<usize>,
+    len: usize,
+) -> usize {
+    let similarity = |start: usize| {
+        let mut correlation = 0.0;
+        let mut energy = 0.0;
+        for frame in (0..len).step_by(COMPARE_STRIDE) {
+            for channel in 0..channels {
+                let candidate = samples[(start + frame) * channels + channel];
+                correlation += candidate * samples[(target + frame) * channels + channel];
+                energy += candidate * candidate;
+            }
+        }
+
+        // normalized, to not prefer louder parts
+        correlation / f32::max(energy, f32::EPSILON).sqrt()
+    };
+    let best = |candidates: &mut dyn Iterator<Item = usize>| {
+        candidates
+            .map(|start| (start, similarity(start)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(start, _)| start)
+    };
+
+    // search every other frame first, then refine around the best of them
+    let (min, max) = (*candidates.start(), *candidates.end());
+    let coarse = best(&mut candidates.step_by(2)).unwrap_or(min);
+
+    best(&mut (coarse.saturating_sub(1).max(min)..=(coarse + 1).min(max))).unwrap_or(coarse)
+}
+
+/// Get a periodic hann window of `len` frames.
+fn hann_window(len: usize) -> Vec<f32> {
+    #[allow(clippy::cast_precision_loss)] // a window is a few thousand frames at most
+    let window = (0..len)
+        .map(|idx| 0.5 - 0.5 * (2.0 * PI * idx as f32 / len as f32).cos())
+        .collect();
+
+    window
+}
+
+/// Get the amount of frames `ms` milliseconds are at `sample_rate`.
+fn ms_to_frames(sample_rate: u32, ms: u32) -> usize {
+    usize::try_from(u64::from(sample_rate) * u64::from(ms) / 1000).unwrap_or(usize::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+    use rodio::Source;
+    use rodio::source::SineWave;
+
+    use super::time_stretch;
+
+    fn sine() -> impl Source<Item = f32> {
+        SineWave::new(440.0).take_duration(Duration::from_secs(1))
+    }
+
+    #[test]
+    fn should_keep_unchanged_speed() {
+        let output: Vec<f32> = time_stretch(sine(), 1.0).collect();
+
+        assert_eq!(output, sine().collect::<Vec<f32>>());
+    }
+
+    #[test]
+    fn should_keep_pitch() {
+        let sample_rate = usize::try_from(sine().sample_rate()).unwrap();
+
+        for factor in [0.5, 2.0] {
+            let output: Vec<f32> = time_stretch(sine(), factor).collect();
+
+            // up to a window and its search range of the end is dropped, which is 80ms at 0.5x
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let expected = (sample_rate as f32 / factor) as usize;
+            assert!(
+                output.len().abs_diff(expected) < sample_rate / 8,
+                "{factor}x: {} samples instead of {expected}",
+                output.len()
+            );
+
+            // a 440Hz sine crosses zero 880 times a second
+            let crossings = output
+                .windows(2)
+                .filter(|v| (v[0] < 0.0) != (v[1] < 0.0))
+                .count();
+            #[allow(clippy::cast_precision_loss)]
+            let per_second = crossings as f32 * sample_rate as f32 / output.len() as f32;
+            assert!(
+                (per_second - 880.0).abs() < 880.0 * 0.05,
+                "{factor}x: {per_second} zero crossings a second"
+            );
+        }
+    }
+}
@@ -1,32 +1,48 @@
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use async_trait::async_trait;
 use parking_lot::RwLock;
 pub use playlist::Playlist;
 use termusiclib::config::SharedServerSettings;
+use termusiclib::config::v2::server::CrossfadeSettings;
 use termusiclib::config::v2::server::config_extra::ServerConfigVersionedDefaulted;
-use termusiclib::new_database::{Database, track_ops};
+use termusiclib::new_database::Database;
+use termusiclib::new_database::audiobook_ops::{self, Audiobook};
+use termusiclib::new_database::track_ops::{self, PlayOutcome};
+use termusiclib::organize::OrganizeMove;
 use termusiclib::player::playlist_helpers::{
     PlaylistAddTrack, PlaylistPlaySpecific, PlaylistRemoveTrackIndexed, PlaylistSwapTrack,
 };
 use termusiclib::player::{
-    PlayerProgress, PlayerTimeUnit, RunningStatus, TrackChangedInfo, UpdateEvents,
+    AbRepeat, ChapterJump, PlayerProgress, PlayerTimeUnit, RunningStatus, StreamInfo,
+    TrackChangedInfo, TrackSpeed, UpdateEvents,
 };
+use termusiclib::podcast::chapters;
 use termusiclib::podcast::db::Database as DBPod;
-use termusiclib::track::{MediaTypes, Track};
-use termusiclib::utils::get_app_config_path;
+use termusiclib::podcast::episode::Chapter;
+use termusiclib::podcast::feed_settings::FeedSettings;
+use termusiclib::track::cue::CueSpan;
+use termusiclib::track::{MediaTypes, Track, TrackData};
+use termusiclib::utils::get_app_data_path;
 use tokio::runtime::Handle;
 use tokio::sync::mpsc::error::SendError;
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use tokio::sync::{broadcast, oneshot};
 
 pub use backends::{Backend, BackendSelect};
+use preview::Preview;
 
+mod audiobook;
+mod auto_queue;
 mod discord;
 mod mpris;
 pub mod playlist;
+mod preview;
+mod resume;
+pub mod waveform;
 
 #[macro_use]
 extern crate log;
@@ -127,15 +143,32 @@ pub enum PlayerCmd {
     Quit,
     ReloadConfig,
     ReloadPlaylist,
+    RescanLibrary,
     SeekBackward,
     SeekForward,
+    /// Seek to a absolute position in the current track
+    SeekTo(Duration),
+    /// Jump to a chapter of the current track, the next one if `true`
+    JumpChapter(bool),
     SkipNext,
     SpeedDown,
     SpeedUp,
+    /// Keep the current speed for the current track, or use the global speed for it again
+    ToggleTrackSpeed,
     ToggleGapless,
     TogglePause,
     VolumeDown,
     VolumeUp,
+    /// Re-read the available audio output devices of the backend
+    RefreshOutputDevices,
+    /// Switch to the given audio output device, or the default device if `None`
+    SetOutputDevice(Option<String>),
+    /// Re-read the current audio output information of the backend
+    RefreshOutputInfo,
+    /// Change the crossfade between music tracks, applies from the next enqueued track on
+    SetCrossfade(CrossfadeSettings),
+    /// Cast playback to the network renderer with the given id, or play on the backend again if `None`
+    SetCastRenderer(Option<String>),
 
     PlaylistPlaySpecific(PlaylistPlaySpecific),
     PlaylistAddTrack(PlaylistAddTrack),
@@ -144,6 +177,22 @@ pub enum PlayerCmd {
     PlaylistSwapTrack(PlaylistSwapTrack),
     PlaylistShuffle,
     PlaylistRemoveDeletedTracks,
+    /// Change the tracks moved by organizing the library to their new path
+    PlaylistRenamePaths(Vec<OrganizeMove>),
+    /// Stop and replace the playlist with the queue stored in the database
+    PlaylistRestore,
+    /// Stop, store the playlist and replace it with the stored queue of the given name
+    PlaylistSwitchQueue(String),
+    /// Toggle keeping the playlist filled with tracks from the library
+    PlaylistToggleAutoQueue,
+    /// Play a short part of the track at the path without changing the playlist, see [`GeneralPlayer::start_preview`]
+    PreviewTrack(PathBuf),
+    /// Set the start of the section of the current track to loop at the current position
+    SetAbRepeatA,
+    /// Set the end of the section of the current track to loop at the current position
+    SetAbRepeatB,
+    /// Stop looping a section of the current track
+    ClearAbRepeat,
 }
 
 pub type StreamTX = broadcast::Sender<UpdateEvents>;
@@ -157,6 +206,8 @@ pub struct GeneralPlayer {
     pub current_track_updated: bool,
     pub mpris: Option<mpris::Mpris>,
     pub discord: Option<discord::Rpc>,
+    /// The network renderer playback is cast to, which is used instead of the backend while set
+    pub cast: Option<Box<dyn PlayerTrait + Send>>,
     pub db: Database,
     pub db_podcast: DBPod,
     pub cmd_tx: PlayerCmdSender,
@@ -164,6 +215,18 @@ pub struct GeneralPlayer {
 
     /// Keep track of continues backend errors (like `NotFound`) to not keep trying infinitely.
     pub errors_since_last_progress: usize,
+    /// When playback was last paused, to rewind podcasts resumed after a long pause
+    paused_at: Option<Instant>,
+    /// The speed override of the current track, which is used instead of `player.speed`
+    track_speed: Option<Speed>,
+    /// The playback to continue once the playing preview is over
+    preview: Option<Preview>,
+    /// The section of the current track to loop
+    ab_repeat: AbRepeat,
+    /// The audiobook the current track is part of
+    audiobook: Option<Audiobook>,
+    /// Skip to the next track once this much of the current podcast episode is left
+    skip_outro: Option<Duration>,
 }
 
 impl GeneralPlayer {
@@ -182,16 +245,16 @@ impl GeneralPlayer {
     ) -> Result<Self> {
         let backend = Backend::new_select(backend, config.clone(), cmd_tx.clone());
 
-        let db_path = get_app_config_path().with_context(|| "failed to get podcast db path.")?;
+        let db_path = get_app_data_path().with_context(|| "failed to get podcast db path.")?;
 
         let db_podcast = DBPod::new(&db_path).with_context(|| "error connecting to podcast db.")?;
         let config_read = config.read();
         let db = Database::new_default_path()?;
 
         let mpris = if config.read().settings.player.use_mediacontrols {
-            let mut mpris = mpris::Mpris::new(cmd_tx.clone());
+            let mut mpris = mpris::Mpris::new(cmd_tx.clone(), playlist.clone(), db.clone());
 
-            // set volume on start, as souvlaki (0.8.2) defaults to 1.0 until set by us
+            // set volume on start, as the media controls default to 1.0 until set by us
             // also otherwise we only set this once the volume actually changes or mpris is re-started via config reload
             mpris.update_volume(backend.as_player().volume());
 
@@ -213,6 +276,7 @@ impl GeneralPlayer {
             config,
             mpris,
             discord,
+            cast: None,
             db,
             db_podcast,
             cmd_tx,
@@ -220,6 +284,12 @@ impl GeneralPlayer {
             current_track_updated: false,
 
             errors_since_last_progress: 0,
+            paused_at: None,
+            track_speed: None,
+            preview: None,
+            ab_repeat: AbRepeat::default(),
+            audiobook: None,
+            skip_outro: None,
         })
     }
 
@@ -267,10 +337,11 @@ impl GeneralPlayer {
 
         if config.settings.player.use_mediacontrols && self.mpris.is_none() {
             // start mpris if new config has it enabled, but is not active yet
-            let mut mpris = mpris::Mpris::new(self.cmd_tx.clone());
+            let mut mpris =
+                mpris::Mpris::new(self.cmd_tx.clone(), self.playlist.clone(), self.db.clone());
             // actually set the metadata of the currently playing track, otherwise the controls will work but no title or coverart will be set until next track
             if let Some(track) = self.playlist.read().current_track() {
-                mpris.add_and_play(track);
+                mpris.add_and_play(track, &config.settings.player.title_format);
             }
             // the same for volume
             mpris.update_volume(self.volume());
@@ -300,12 +371,71 @@ impl GeneralPlayer {
         Ok(())
     }
 
+    /// Start a background scan of all configured music directories.
+    ///
+    /// Requires that the function is called on a thread with a entered tokio runtime.
+    pub fn rescan_library(&self) {
+        let config = self.config.read();
+
+        for dir in &config.settings.player.music_dirs {
+            let dir = shellexpand::path::tilde(dir);
+            info!("Rescanning {:#?}", dir.display());
+            if let Err(err) = self.db.scan_path(&dir, &config, false, None) {
+                error!("Error scanning path {:#?}: {err:#?}", dir.display());
+            }
+        }
+    }
+
     fn get_player(&self) -> &dyn PlayerTrait {
-        self.backend.as_player()
+        match &self.cast {
+            Some(cast) => &**cast,
+            None => self.backend.as_player(),
+        }
     }
 
     fn get_player_mut(&mut self) -> &mut (dyn PlayerTrait + Send) {
-        self.backend.as_player_mut()
+        match &mut self.cast {
+            Some(cast) => &mut **cast,
+            None => self.backend.as_player_mut(),
+        }
+    }
+
+    /// Cast playback to `cast`, or play on the backend again if `None`.
+    ///
+    /// The current track continues at the same position and volume on the new output.
+    ///
+    /// Requires that the function is called on a thread with a entered tokio runtime.
+    pub fn set_cast(&mut self, cast: Option<Box<dyn PlayerTrait + Send>>) {
+        let position = self.position();
+        let volume = self.volume();
+        let status = self.playlist.read().status();
+
+        // the backend is kept paused while casting, a replaced cast output stops once dropped
+        if self.cast.is_none() {
+            self.backend.as_player_mut().pause();
+        }
+        self.cast = cast;
+        self.get_player_mut().set_volume(volume);
+
+        if status == RunningStatus::Stopped {
+            return;
+        }
+        let Some(track) = self.playlist.read().current_track().cloned() else {
+            return;
+        };
+        let wait = async {
+            self.add_and_play(&track).await;
+        };
+        Handle::current().block_on(wait);
+        if let Some(position) = position {
+            self.seek_to(position);
+        }
+        if status == RunningStatus::Paused {
+            self.get_player_mut().pause();
+        } else if self.cast.is_none() {
+            // the backend was paused while casting
+            self.get_player_mut().resume();
+        }
     }
 
     pub fn toggle_gapless(&mut self) -> bool {
@@ -315,6 +445,38 @@ impl GeneralPlayer {
         new_gapless
     }
 
+    /// Toggle auto-queue and fill the playlist right away if it got enabled, returns the new state.
+    pub fn toggle_auto_queue(&mut self) -> bool {
+        let enabled = {
+            let mut config = self.config.write();
+            let auto_queue = &mut config.settings.player.auto_queue;
+            auto_queue.enabled = !auto_queue.enabled;
+            auto_queue.enabled
+        };
+
+        self.auto_queue_refill();
+
+        enabled
+    }
+
+    /// Add tracks from the library if auto-queue is enabled and too few tracks are left in the playlist.
+    pub fn auto_queue_refill(&mut self) {
+        let settings = self.config.read().settings.player.auto_queue;
+        if !settings.enabled {
+            return;
+        }
+
+        match auto_queue::refill(&mut self.playlist.write(), &self.db, &settings) {
+            Ok(0) => return,
+            Ok(added) => info!("Auto-queue added {added} tracks"),
+            Err(err) => {
+                error!("Error refilling the playlist: {err:#}");
+                return;
+            }
+        }
+        self.mpris_tracklist_update();
+    }
+
     /// Requires that the function is called on a thread with a entered tokio runtime
     ///
     /// # Panics
@@ -335,25 +497,101 @@ impl GeneralPlayer {
                 playlist.set_next_track(None);
                 drop(playlist);
                 self.current_track_updated = true;
+                self.ab_repeat = AbRepeat::default();
                 info!("gapless next track played");
                 self.add_and_play_mpris_discord();
+                self.set_last_played(&track);
+                self.audiobook = audiobook::load(&self.db, &track);
+                self.set_audiobook_last_played(&track, Duration::ZERO);
+                self.apply_track_speed(&track);
+                self.apply_track_skips(&track, Duration::ZERO);
 
                 self.send_track_changed();
+                self.auto_queue_refill();
 
                 return;
             }
             drop(playlist);
 
             self.current_track_updated = true;
+            self.ab_repeat = AbRepeat::default();
+            self.audiobook = audiobook::load(&self.db, &track);
+            let track = self.continue_audiobook(track);
             let wait = async {
                 self.add_and_play(&track).await;
             };
             Handle::current().block_on(wait);
 
             self.add_and_play_mpris_discord();
-            self.player_restore_last_position();
+            // the time stored with a restored queue takes precedence over the per-track position
+            let restore_position = self.playlist.write().take_restore_position(&track);
+            let position = if let Some(position) = restore_position {
+                // a zero position only starts from the beginning, without restoring the per-track position
+                if !position.is_zero() {
+                    self.seek_to(position);
+                }
+                Some(position)
+            } else {
+                self.player_restore_last_position()
+            };
+            self.set_last_played(&track);
+            self.set_audiobook_last_played(&track, position.unwrap_or_default());
+            self.apply_track_speed(&track);
+            self.apply_track_skips(&track, position.unwrap_or_default());
 
             self.send_track_changed();
+            self.auto_queue_refill();
+        }
+    }
+
+    /// Continue the audiobook of `track` at the track it was left at, if it is started fresh, see [`continue_index`](audiobook::continue_index).
+    ///
+    /// Returns the track to start.
+    fn continue_audiobook(&mut self, track: Track) -> Track {
+        let Some((dir, (last_track, position))) = self
+            .audiobook
+            .as_ref()
+            .and_then(|v| Some((v.dir.as_path(), v.last_played.as_ref()?)))
+        else {
+            return track;
+        };
+
+        let mut playlist = self.playlist.write();
+        let index = playlist.get_current_track_index();
+        // a track started outside of the queue, like a preview
+        if playlist.tracks().get(index) != Some(&track) {
+            return track;
+        }
+        let Some(index) = audiobook::continue_index(playlist.tracks(), index, dir, last_track)
+        else {
+            return track;
+        };
+
+        info!(
+            "Continuing audiobook {} at {}",
+            dir.display(),
+            last_track.display()
+        );
+        let track = playlist.tracks()[index].clone();
+        playlist.set_current_track_index(index);
+        playlist.set_restore_position(&track, *position);
+
+        track
+    }
+
+    /// Store that the audiobook of the current track was last played at `position` of `track`.
+    fn set_audiobook_last_played(&self, track: &Track, position: Duration) {
+        let (Some(audiobook), Some(path)) = (&self.audiobook, audiobook::track_path(track)) else {
+            return;
+        };
+
+        if let Err(err) = audiobook_ops::set_last_played(
+            &self.db.get_connection(),
+            &audiobook.dir,
+            path,
+            position,
+        ) {
+            warn!("Error storing the audiobook progress: {err:#}");
         }
     }
 
@@ -364,19 +602,75 @@ impl GeneralPlayer {
 
     /// Send event [`UpdateEvents::TrackChanged`]. In a function to de-duplicate calls.
     fn send_track_changed(&mut self) {
+        let media_info = self.media_info();
         self.send_stream_ev(UpdateEvents::TrackChanged(TrackChangedInfo {
             current_track_index: u64::try_from(self.playlist.read().get_current_track_index())
                 .unwrap(),
             current_track_updated: self.current_track_updated,
-            title: self.media_info().media_title,
+            title: media_info.media_title,
             progress: self.get_progress(),
+            chapters: self.current_chapters(),
+            stream_info: media_info.stream_info,
         }));
     }
 
+    /// Get the chapters of the current track, from the podcast feed or embedded in the file.
+    fn current_chapters(&self) -> Vec<Chapter> {
+        let Some(track) = self.playlist.read().current_track().cloned() else {
+            return Vec::new();
+        };
+
+        match track.inner() {
+            MediaTypes::Track(track_data) => chapters::from_file(track_data.path()),
+            MediaTypes::Radio(_) => Vec::new(),
+            MediaTypes::Podcast(podcast_track_data) => {
+                let Ok(episode) = self.db_podcast.get_episode_by_url(podcast_track_data.url())
+                else {
+                    return Vec::new();
+                };
+
+                match self.db_podcast.get_chapters(episode.id) {
+                    Ok(chapters) if !chapters.is_empty() => chapters,
+                    // feeds without chapters may still have them embedded in the downloaded file
+                    Ok(_) => episode
+                        .path
+                        .as_deref()
+                        .map(chapters::from_file)
+                        .unwrap_or_default(),
+                    Err(err) => {
+                        warn!("Error getting chapters: {err:#}");
+                        Vec::new()
+                    }
+                }
+            }
+        }
+    }
+
+    /// Jump to the next chapter of the current track if `forward`, otherwise back, see [`chapters::jump_target`].
+    ///
+    /// Returns the chapter jumped to, if there was one.
+    pub fn jump_chapter(&mut self, forward: bool) -> ChapterJump {
+        let mut chapters = self.current_chapters();
+        let position = self.position().unwrap_or_default();
+        let Some(index) = chapters::jump_target(&chapters, position, forward) else {
+            return ChapterJump::default();
+        };
+
+        let total = chapters.len();
+        let chapter = chapters.swap_remove(index);
+        self.seek_to(chapter.start);
+
+        ChapterJump {
+            chapter: Some(chapter.into()),
+            index: u64::try_from(index).unwrap_or(u64::MAX),
+            total: u64::try_from(total).unwrap_or(u64::MAX),
+        }
+    }
+
     fn add_and_play_mpris_discord(&mut self) {
         if let Some(track) = self.playlist.read().current_track() {
             if let Some(ref mut mpris) = self.mpris {
-                mpris.add_and_play(track);
+                mpris.add_and_play(track, &self.config.read().settings.player.title_format);
             }
 
             if let Some(ref discord) = self.discord {
@@ -385,6 +679,10 @@ impl GeneralPlayer {
         }
     }
     pub fn enqueue_next_from_playlist(&mut self) {
+        // renderers get each track once it starts
+        if self.cast.is_some() {
+            return;
+        }
         let mut playlist = self.playlist.write();
         if playlist.has_next_track() {
             return;
@@ -393,6 +691,11 @@ impl GeneralPlayer {
         let Some(track) = playlist.fetch_next_track().cloned() else {
             return;
         };
+        // cue sheet tracks have to be started at their offset, which only "add_and_play" does
+        if track.as_track().is_some_and(|v| v.cue_span().is_some()) {
+            playlist.set_next_track(None);
+            return;
+        }
         drop(playlist);
 
         self.enqueue_next(&track);
@@ -400,6 +703,37 @@ impl GeneralPlayer {
         info!("Next track enqueued: {track:#?}");
     }
 
+    /// Enqueue the next track early if it is streamed, to give it `player.stream_prefetch_secs` to buffer.
+    ///
+    /// Other tracks are enqueued once the backend sends [`PlayerCmd::AboutToFinish`].
+    pub fn enqueue_next_stream(&mut self, progress: &PlayerProgress) {
+        let prefetch = {
+            let config = self.config.read();
+            if !config.settings.player.gapless {
+                return;
+            }
+            Duration::from_secs(config.settings.player.stream_prefetch_secs.into())
+        };
+        // radio stations have no duration and never end
+        let (Some(position), Some(total)) = (progress.position, progress.total_duration) else {
+            return;
+        };
+        if prefetch.is_zero() || total.saturating_sub(position) > prefetch {
+            return;
+        }
+
+        let playlist = self.playlist.read();
+        if playlist.status() != RunningStatus::Running
+            || playlist.has_next_track()
+            || !playlist.peek_next_track().is_some_and(Track::is_stream)
+        {
+            return;
+        }
+        drop(playlist);
+
+        self.enqueue_next_from_playlist();
+    }
+
     /// Skip to the next track, if there is one
     pub fn next(&mut self) {
         if self.playlist.read().current_track().is_some() {
@@ -430,13 +764,72 @@ impl GeneralPlayer {
             RunningStatus::Running => {
                 <Self as PlayerTrait>::pause(self);
             }
-            RunningStatus::Stopped => {}
+            RunningStatus::Stopped => {
+                self.resume_last_played();
+            }
             RunningStatus::Paused => {
                 <Self as PlayerTrait>::resume(self);
             }
         }
     }
 
+    /// Start playing the current track of the queue, or the last played track if the queue is empty,
+    /// at its last position, if enabled with `player.resume_on_start`.
+    ///
+    /// See [`resume_position`](resume::resume_position) for when the position is used.
+    /// Returns whether playback was started.
+    pub fn resume_last_played(&mut self) -> bool {
+        if !self.config.read().settings.player.resume_on_start {
+            return false;
+        }
+
+        let mut playlist = self.playlist.write();
+        let added = playlist.is_empty();
+        if added {
+            match resume::add_last_played(&mut playlist, &self.db) {
+                Ok(true) => playlist.set_current_track_index(0),
+                Ok(false) => {
+                    info!("Not resuming as there is no last played track");
+                    return false;
+                }
+                Err(err) => {
+                    error!("Error adding the last played track to resume: {err:#}");
+                    return false;
+                }
+            }
+        }
+        let Some(track) = playlist.current_track().cloned() else {
+            return false;
+        };
+        let queue_position = playlist.take_restore_position(&track);
+        // start the current track again instead of advancing past it
+        playlist.proceed_false();
+        drop(playlist);
+
+        // the time stored with a restored queue takes precedence over the per-track position
+        let position = queue_position.or_else(|| {
+            let position = self.get_last_position(&track);
+            if position.is_some() {
+                if let Err(err) = self.set_last_position(&track, None) {
+                    error!("Resetting last_position failed. Error: {err:#?}");
+                }
+            }
+            position
+        });
+        let position = resume::resume_position(track.media_type(), position);
+        info!("Resuming {track:#?} at {position:?}");
+
+        self.playlist
+            .write()
+            .set_restore_position(&track, position.unwrap_or_default());
+        if added {
+            self.mpris_tracklist_update();
+        }
+        self.start_play();
+
+        true
+    }
+
     /// Pause playback if running
     pub fn pause(&mut self) {
         // NOTE: if this ".read()" call is in a match's statement, it will not be unlocked until the end of the match
@@ -450,6 +843,27 @@ impl GeneralPlayer {
         }
     }
 
+    /// Rewind the current podcast episode if it was paused for long, see [`rewind_position`](resume::rewind_position).
+    fn rewind_after_pause(&mut self) {
+        let Some(paused_at) = self.paused_at.take() else {
+            return;
+        };
+        let Some(media_type) = self.playlist.read().current_track().map(Track::media_type) else {
+            return;
+        };
+        let Some(position) = self.position() else {
+            return;
+        };
+
+        let rewind = self.config.read().settings.podcast.resume_rewind;
+        if let Some(position) =
+            resume::rewind_position(media_type, position, paused_at.elapsed(), rewind)
+        {
+            info!("Rewinding to {position:?} after a long pause");
+            self.seek_to(position);
+        }
+    }
+
     /// Resume playback if paused
     pub fn play(&mut self) {
         // NOTE: if this ".read()" call is in a match's statement, it will not be unlocked until the end of the match
@@ -489,6 +903,21 @@ impl GeneralPlayer {
         self.seek(offset).expect("Error in player seek.");
     }
 
+    /// Helper function to de-duplicate getting the last position of a given track.
+    fn get_last_position(&mut self, track: &Track) -> Option<Duration> {
+        match track.inner() {
+            MediaTypes::Track(track_data) => {
+                track_ops::get_last_position(&self.db.get_connection(), track_data.path())
+                    .ok()
+                    .flatten()
+            }
+            MediaTypes::Radio(_) => None,
+            MediaTypes::Podcast(_podcast_track_data) => {
+                self.db_podcast.get_last_position(track).ok()
+            }
+        }
+    }
+
     /// Helper function to de-duplicate setting last position for a given track.
     fn set_last_position(&self, track: &Track, to: Option<Duration>) -> Result<()> {
         match track.inner() {
@@ -508,6 +937,316 @@ impl GeneralPlayer {
         Ok(())
     }
 
+    /// Mark `track` as played now, for library tracks.
+    fn set_last_played(&self, track: &Track) {
+        let MediaTypes::Track(track_data) = track.inner() else {
+            return;
+        };
+
+        // tracks outside of the music directories are not in the database
+        if let Err(err) = track_ops::set_last_played(&self.db.get_connection(), track_data.path()) {
+            debug!("Not setting last_played: {err:#}");
+        }
+    }
+
+    /// Play `track` at its speed override if it has one, otherwise at the speed of its audiobook or the global speed.
+    ///
+    /// The override of a podcast episode is the speed of its whole podcast.
+    fn apply_track_speed(&mut self, track: &Track) {
+        self.track_speed = match track.inner() {
+            // tracks outside of the music directories are not in the database
+            MediaTypes::Track(track_data) => {
+                track_ops::get_track_speed(&self.db.get_connection(), track_data.path())
+                    .ok()
+                    .flatten()
+            }
+            MediaTypes::Podcast(_) => self
+                .db_podcast
+                .get_feed_speed(track)
+                .inspect_err(|err| warn!("Error loading the podcast speed: {err:#}"))
+                .ok()
+                .flatten(),
+            MediaTypes::Radio(_) => None,
+        };
+
+        let speed = self.track_speed.unwrap_or_else(|| self.default_speed());
+        if speed != self.speed() {
+            self.set_speed(speed);
+        }
+    }
+
+    /// Skip silences in `track` if enabled globally or for its audiobook,
+    /// and the intro and outro of its feed if it is a podcast episode.
+    ///
+    /// The intro is only skipped if `track` starts at a `position` before its end, and not when continuing later in it.
+    fn apply_track_skips(&mut self, track: &Track, position: Duration) {
+        let skip_silence = self.config.read().settings.player.skip_silence.enabled
+            || self.audiobook.as_ref().is_some_and(|v| v.skip_silence);
+        self.set_skip_silence(skip_silence);
+
+        let settings = match track.inner() {
+            MediaTypes::Podcast(_) => self
+                .db_podcast
+                .get_episode_feed_settings(track)
+                .inspect_err(|err| warn!("Error loading the podcast settings: {err:#}"))
+                .unwrap_or_default(),
+            MediaTypes::Track(_) | MediaTypes::Radio(_) => FeedSettings::default(),
+        };
+        let to_duration = |secs: u32| Duration::from_secs(u64::from(secs));
+        self.skip_outro = settings.skip_outro.map(to_duration);
+
+        if let Some(intro) = settings.skip_intro.map(to_duration) {
+            if position < intro {
+                info!("Skipping the first {}s of the episode", intro.as_secs());
+                self.seek_to(intro);
+            }
+        }
+    }
+
+    /// Skip to the next track once only the outro of the current podcast episode is left.
+    fn check_skip_outro(&mut self, progress: &PlayerProgress) {
+        let Some(outro) = self.skip_outro else {
+            return;
+        };
+        let (Some(position), Some(total)) = (progress.position, progress.total_duration) else {
+            return;
+        };
+
+        if total > outro && position >= total - outro {
+            info!("Skipping the last {}s of the episode", outro.as_secs());
+            // only once, the next track sets it again
+            self.skip_outro = None;
+            self.next();
+        }
+    }
+
+    /// Get the speed of the current audiobook if it has one, otherwise the global speed.
+    fn default_speed(&self) -> Speed {
+        self.audiobook
+            .as_ref()
+            .and_then(|v| v.speed)
+            .unwrap_or_else(|| self.config.read().settings.player.speed)
+    }
+
+    /// Change the speed by `step`, only for the current track if it has a speed override,
+    /// for the current audiobook if the track is part of one, otherwise globally.
+    ///
+    /// Returns the new speed.
+    pub fn change_speed(&mut self, step: SpeedSigned) -> Speed {
+        let speed = self.add_speed(step);
+
+        if self.track_speed.is_none() {
+            if let Some(audiobook) = &mut self.audiobook {
+                audiobook.speed = Some(speed);
+                if let Err(err) =
+                    audiobook_ops::set_speed(&self.db.get_connection(), &audiobook.dir, Some(speed))
+                {
+                    warn!("Error storing the audiobook speed: {err:#}");
+                }
+            } else {
+                self.config.write().settings.player.speed = speed;
+            }
+            return speed;
+        }
+
+        self.track_speed = Some(speed);
+        if let Err(err) = self.store_track_speed(Some(speed)) {
+            warn!("Error storing the track speed: {err:#}");
+        }
+
+        speed
+    }
+
+    /// Keep the current speed for the current library track or podcast, or go back to the audiobook or global speed if it already has a override.
+    ///
+    /// Returns the new state for the current track.
+    pub fn toggle_track_speed(&mut self) -> TrackSpeed {
+        let to = match self.track_speed {
+            Some(_) => None,
+            None => Some(self.speed()),
+        };
+
+        match self.store_track_speed(to) {
+            Ok(()) => {
+                self.track_speed = to;
+                let default = self.default_speed();
+                if to.is_none() && default != self.speed() {
+                    self.set_speed(default);
+                }
+            }
+            Err(err) => warn!("Not toggling the track speed: {err:#}"),
+        }
+
+        TrackSpeed {
+            pinned: self.track_speed.is_some(),
+            speed: self.speed(),
+        }
+    }
+
+    /// Store the speed override `speed` of the current track, for podcast episodes on their podcast.
+    fn store_track_speed(&self, speed: Option<Speed>) -> Result<()> {
+        let playlist = self.playlist.read();
+        let Some(track) = playlist.current_track() else {
+            bail!("No track is playing");
+        };
+
+        match track.inner() {
+            MediaTypes::Track(track_data) => {
+                track_ops::set_track_speed(&self.db.get_connection(), track_data.path(), speed)
+            }
+            MediaTypes::Podcast(_) => self.db_podcast.set_feed_speed(track, speed),
+            MediaTypes::Radio(_) => {
+                bail!("Only library tracks and podcasts can have a speed override")
+            }
+        }
+    }
+
+    /// Play [`PREVIEW_LENGTH`](preview::PREVIEW_LENGTH) of the track at `path` from 30% into it, without changing the playlist.
+    ///
+    /// The playback from before continues once the preview is over, see [`Self::update_preview`].
+    ///
+    /// # Errors
+    ///
+    /// - if the track cannot be read
+    pub fn start_preview(&mut self, path: &Path) -> Result<()> {
+        let track = Track::read_track_from_path(path)?;
+
+        // a preview replacing another one continues the playback from before the first
+        let (position, status) = match self.preview {
+            Some(preview) => (preview.position, preview.status),
+            None => (self.position(), self.playlist.read().status()),
+        };
+        self.preview = Some(Preview {
+            started: Instant::now(),
+            position,
+            status,
+        });
+        info!("Previewing {path:#?}");
+
+        // the backend drops the track enqueued for gapless playback
+        self.playlist.write().set_next_track(None);
+        let start = track
+            .as_track()
+            .and_then(TrackData::cue_span)
+            .map(|v| v.start)
+            .unwrap_or_default();
+        let player = self.get_player_mut();
+        Handle::current().block_on(player.add_and_play(&track));
+        player.seek_to(start + preview::start_position(track.duration()));
+
+        Ok(())
+    }
+
+    /// Get whether a preview is playing, see [`Self::start_preview`].
+    #[must_use]
+    pub fn is_previewing(&self) -> bool {
+        self.preview.is_some()
+    }
+
+    /// End the preview once it is over, should be called regularly.
+    ///
+    /// Returns whether a preview is still playing.
+    pub fn update_preview(&mut self) -> bool {
+        if self.preview.is_some_and(|v| v.is_over()) {
+            self.end_preview();
+        }
+
+        self.is_previewing()
+    }
+
+    /// End the preview and continue the playback from before it, does nothing if there is no preview.
+    pub fn end_preview(&mut self) {
+        let Some(preview) = self.preview.take() else {
+            return;
+        };
+        let current = self.playlist.read().current_track().cloned();
+        let Some(track) = current.filter(|_| preview.status != RunningStatus::Stopped) else {
+            self.get_player_mut().stop();
+            return;
+        };
+
+        Handle::current().block_on(<Self as PlayerTrait>::add_and_play(self, &track));
+        if let Some(position) = preview.position {
+            self.seek_to(position);
+        }
+        if preview.status == RunningStatus::Paused {
+            self.get_player_mut().pause();
+        }
+    }
+
+    /// Set the start of the section of the current track to loop at the current position, see [`AbRepeat::set_a`].
+    pub fn set_ab_repeat_a(&mut self) {
+        let Some(position) = self.position() else {
+            return;
+        };
+        self.ab_repeat.set_a(position);
+        self.send_ab_repeat_changed();
+    }
+
+    /// Set the end of the section of the current track to loop at the current position, see [`AbRepeat::set_b`].
+    pub fn set_ab_repeat_b(&mut self) {
+        let Some(position) = self.position() else {
+            return;
+        };
+        self.ab_repeat.set_b(position);
+        self.send_ab_repeat_changed();
+
+        // the position may be before the start, if both points were swapped
+        if let Some((a, _)) = self.ab_repeat.section() {
+            if position < a {
+                self.seek_to(a);
+            }
+        }
+    }
+
+    /// Stop looping a section of the current track.
+    pub fn clear_ab_repeat(&mut self) {
+        self.ab_repeat = AbRepeat::default();
+        self.send_ab_repeat_changed();
+    }
+
+    /// Let clients show the new section right away, instead of with the next progress update.
+    fn send_ab_repeat_changed(&self) {
+        info!("A-B repeat: {:?}", self.ab_repeat);
+        if let Some(progress) = self.get_progress() {
+            self.send_stream_ev(UpdateEvents::Progress(progress));
+        }
+    }
+
+    /// Go back to the start of the looped section once its end is reached.
+    fn check_ab_repeat(&mut self, progress: &PlayerProgress) {
+        let Some((a, b)) = progress.ab_repeat.section() else {
+            return;
+        };
+
+        if progress.position.is_some_and(|v| v >= b) {
+            self.seek_to(a);
+        }
+    }
+
+    /// Count the current track as played until the end or as skipped, for library tracks.
+    ///
+    /// Does nothing if playback is stopped, as then nothing was skipped.
+    pub fn record_play_outcome(&self, outcome: PlayOutcome) {
+        let playlist = self.playlist.read();
+        if playlist.is_stopped() {
+            return;
+        }
+        let Some(MediaTypes::Track(track_data)) = playlist.current_track().map(Track::inner) else {
+            return;
+        };
+
+        // tracks outside of the music directories are not in the database
+        if let Err(err) =
+            track_ops::record_play_outcome(&self.db.get_connection(), track_data.path(), outcome)
+        {
+            debug!("Not recording play outcome: {err:#}");
+        }
+    }
+
+    /// Save the position in the current track, to continue it there the next time it is played.
+    ///
+    /// Audiobook tracks always save it, regardless of `player.remember_position`.
     #[allow(clippy::cast_sign_loss)]
     pub fn player_save_last_position(&mut self) {
         let playlist = self.playlist.read();
@@ -520,6 +1259,14 @@ impl GeneralPlayer {
             return;
         };
 
+        if self.audiobook.is_some() {
+            self.set_audiobook_last_played(track, position);
+            if let Err(err) = self.set_last_position(track, Some(position)) {
+                error!("Saving last_position failed. Error: {err:#?}");
+            }
+            return;
+        }
+
         let Some(time_before_save) = self
             .config
             .read()
@@ -544,40 +1291,33 @@ impl GeneralPlayer {
         }
     }
 
-    pub fn player_restore_last_position(&mut self) {
+    /// Continue the current track at its saved position.
+    ///
+    /// Audiobook tracks are always continued, regardless of `player.remember_position`.
+    ///
+    /// Returns the restored position, if any.
+    pub fn player_restore_last_position(&mut self) -> Option<Duration> {
         let playlist = self.playlist.read();
         let Some(track) = playlist.current_track().cloned() else {
             info!("Not restoring Last position as there is no current track");
-            return;
+            return None;
         };
         drop(playlist);
 
-        let mut restored = false;
+        let mut restored = None;
 
-        if self
-            .config
-            .read()
-            .settings
-            .player
-            .remember_position
-            .is_enabled_for(track.media_type())
+        if self.audiobook.is_some()
+            || self
+                .config
+                .read()
+                .settings
+                .player
+                .remember_position
+                .is_enabled_for(track.media_type())
         {
-            match track.inner() {
-                MediaTypes::Track(track_data) => {
-                    let res =
-                        track_ops::get_last_position(&self.db.get_connection(), track_data.path());
-                    if let Ok(Some(last_pos)) = res {
-                        self.seek_to(last_pos);
-                        restored = true;
-                    }
-                }
-                MediaTypes::Radio(_) => (),
-                MediaTypes::Podcast(_podcast_track_data) => {
-                    if let Ok(last_pos) = self.db_podcast.get_last_position(&track) {
-                        self.seek_to(last_pos);
-                        restored = true;
-                    }
-                }
+            if let Some(last_pos) = self.get_last_position(&track) {
+                self.seek_to(last_pos);
+                restored = Some(last_pos);
             }
         } else {
             info!(
@@ -587,16 +1327,53 @@ impl GeneralPlayer {
         }
 
         // should we really reset here already instead of just waiting until either next track or exit?
-        if restored {
+        if restored.is_some() {
             if let Err(err) = self.set_last_position(&track, None) {
                 error!("Resetting last_position failed. Error: {err:#?}");
             }
         }
+
+        restored
+    }
+
+    /// Get the [`CueSpan`] of the current track, if it is a cue sheet track.
+    fn current_cue_span(&self) -> Option<CueSpan> {
+        // recursive, as this is also called while the playlist is locked for reading
+        self.playlist
+            .read_recursive()
+            .current_track()
+            .and_then(Track::as_track)
+            .and_then(TrackData::cue_span)
+            .cloned()
+    }
+
+    /// Finish the current cue sheet track once its end is reached, as the backend only knows about the end of the whole file.
+    fn check_cue_end(&self, progress: &PlayerProgress) {
+        let Some(span) = self.current_cue_span() else {
+            return;
+        };
+        if span.end.is_none() {
+            return;
+        }
+
+        if let (Some(position), Some(total)) = (progress.position, progress.total_duration) {
+            if position >= total {
+                if let Err(err) = self.cmd_tx.send(PlayerCmd::Eos) {
+                    error!("Error sending Eos for the cue sheet track: {err}");
+                }
+            }
+        }
     }
 
     /// Update all the places that should be updated on a new Progress report.
     pub fn update_progress(&mut self, progress: &PlayerProgress) {
+        self.check_cue_end(progress);
+        self.check_ab_repeat(progress);
+        self.check_skip_outro(progress);
         self.mpris_update_progress(progress);
+        self.playlist
+            .write()
+            .set_current_track_pos(progress.position);
 
         self.send_stream_ev_no_err(UpdateEvents::Progress(*progress));
     }
@@ -621,6 +1398,10 @@ impl GeneralPlayer {
 impl PlayerTrait for GeneralPlayer {
     async fn add_and_play(&mut self, track: &Track) {
         self.get_player_mut().add_and_play(track).await;
+        // cue sheet tracks start somewhere in their audio file
+        if let Some(span) = track.as_track().and_then(TrackData::cue_span) {
+            self.get_player_mut().seek_to(span.start);
+        }
     }
     fn volume(&self) -> Volume {
         self.get_player().volume()
@@ -641,6 +1422,7 @@ impl PlayerTrait for GeneralPlayer {
     fn pause(&mut self) {
         self.playlist.write().set_status(RunningStatus::Paused);
         self.get_player_mut().pause();
+        self.paused_at = Some(Instant::now());
         if let Some(ref mut mpris) = self.mpris {
             mpris.pause();
         }
@@ -650,6 +1432,7 @@ impl PlayerTrait for GeneralPlayer {
     }
     /// This function should not be used directly, use `GeneralPlayer::play`
     fn resume(&mut self) {
+        self.rewind_after_pause();
         self.playlist.write().set_status(RunningStatus::Running);
         self.get_player_mut().resume();
         if let Some(ref mut mpris) = self.mpris {
@@ -664,10 +1447,25 @@ impl PlayerTrait for GeneralPlayer {
         self.get_player().is_paused()
     }
     fn seek(&mut self, secs: i64) -> Result<()> {
+        if self.current_cue_span().is_some() {
+            // seek through "seek_to" to stay within the cue sheet track
+            let position = self.position().unwrap_or_default();
+            let offset = Duration::from_secs(secs.unsigned_abs());
+            let position = if secs < 0 {
+                position.saturating_sub(offset)
+            } else {
+                position + offset
+            };
+            self.seek_to(position);
+
+            return Ok(());
+        }
+
         self.get_player_mut().seek(secs)
     }
     fn seek_to(&mut self, position: Duration) {
-        self.get_player_mut().seek_to(position);
+        let start = self.current_cue_span().map(|v| v.start).unwrap_or_default();
+        self.get_player_mut().seek_to(start + position);
     }
 
     fn set_speed(&mut self, speed: Speed) -> Speed {
@@ -694,7 +1492,20 @@ impl PlayerTrait for GeneralPlayer {
     }
 
     fn get_progress(&self) -> Option<PlayerProgress> {
-        self.get_player().get_progress()
+        let progress = self.get_player().get_progress()?;
+        let Some(span) = self.current_cue_span() else {
+            return Some(PlayerProgress {
+                ab_repeat: self.ab_repeat,
+                ..progress
+            });
+        };
+
+        // make the progress relative to the cue sheet track instead of the whole file
+        Some(PlayerProgress {
+            position: progress.position.map(|v| v.saturating_sub(span.start)),
+            total_duration: span.length(progress.total_duration),
+            ab_repeat: self.ab_repeat,
+        })
     }
 
     fn gapless(&self) -> bool {
@@ -710,8 +1521,15 @@ impl PlayerTrait for GeneralPlayer {
         self.get_player_mut().skip_one();
     }
 
+    fn set_skip_silence(&mut self, enabled: bool) {
+        self.get_player_mut().set_skip_silence(enabled);
+    }
+
     fn position(&self) -> Option<PlayerTimeUnit> {
-        self.get_player().position()
+        let position = self.get_player().position()?;
+        let start = self.current_cue_span().map(|v| v.start).unwrap_or_default();
+
+        Some(position.saturating_sub(start))
     }
 
     fn enqueue_next(&mut self, track: &Track) {
@@ -721,6 +1539,18 @@ impl PlayerTrait for GeneralPlayer {
     fn media_info(&self) -> MediaInfo {
         self.get_player().media_info()
     }
+
+    fn output_devices(&self) -> Vec<String> {
+        self.get_player().output_devices()
+    }
+
+    fn set_output_device(&mut self, name: Option<String>) -> Result<()> {
+        self.get_player_mut().set_output_device(name)
+    }
+
+    fn output_info(&self) -> OutputInfo {
+        self.get_player().output_info()
+    }
 }
 
 /// Some information that may be available from the backend
@@ -730,6 +1560,27 @@ impl PlayerTrait for GeneralPlayer {
 pub struct MediaInfo {
     /// The title of the current media playing (if present)
     pub media_title: Option<String>,
+    /// The codec, sample rate and bitrate of the current media, as far as they are known
+    pub stream_info: StreamInfo,
+}
+
+/// How the backend outputs audio, to troubleshoot audio issues.
+///
+/// Each value may be unknown depending on the backend.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct OutputInfo {
+    /// The output device or where else the audio goes
+    pub device: Option<String>,
+    /// Sample rate of the output in Hz
+    pub sample_rate: Option<u32>,
+    /// Channels of the output
+    pub channels: Option<u16>,
+    /// Size of the output buffer in frames
+    pub buffer_frames: Option<u32>,
+    /// Size of the buffer of decoded audio in bytes
+    pub decoded_buffer_size: Option<u64>,
+    /// Whether the current track is resampled to the output sample rate
+    pub resampling: Option<bool>,
 }
 
 pub type Volume = u16;
@@ -792,6 +1643,12 @@ pub trait PlayerTrait {
     fn gapless(&self) -> bool;
     fn set_gapless(&mut self, to: bool);
     fn skip_one(&mut self);
+    /// Skip the part of silences beyond the minimal length of `player.skip_silence`, or play everything with `false`.
+    ///
+    /// Backends that do not support skipping silence always play everything.
+    fn set_skip_silence(&mut self, enabled: bool) {
+        let _ = enabled;
+    }
     /// Quickly access the position.
     ///
     /// This should ALWAYS match up with [`PlayerTrait::get_progress`]'s `.position`!
@@ -802,4 +1659,24 @@ pub trait PlayerTrait {
     fn enqueue_next(&mut self, track: &Track);
     /// Get info of the current media
     fn media_info(&self) -> MediaInfo;
+    /// Get the names of all audio output devices that can be selected.
+    ///
+    /// Backends that do not support selecting a output device return a empty list.
+    fn output_devices(&self) -> Vec<String> {
+        Vec::new()
+    }
+    /// Switch playback to the audio output device named `name`, or to the default device if `None`.
+    ///
+    /// # Errors
+    ///
+    /// - if the backend does not support selecting a output device
+    /// - if there is no output device named `name`
+    fn set_output_device(&mut self, name: Option<String>) -> Result<()> {
+        let _ = name;
+        bail!("Selecting a output device is not supported by this backend")
+    }
+    /// Get how the audio is currently output.
+    fn output_info(&self) -> OutputInfo {
+        OutputInfo::default()
+    }
 }
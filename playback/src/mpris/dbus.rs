@@ -0,0 +1,799 @@
+//! A MPRIS D-Bus server for linux, used instead of [`souvlaki`]'s own one to additionally provide
+//! the `TrackList` and `Playlists` interfaces.
+//!
+//! The API mirrors [`souvlaki::MediaControls`] so that the rest of the mpris module does not need to
+//! care which one is in use.
+//!
+//! Track ids are derived from the index in the playlist, so they change whenever the playlist changes;
+//! this is announced to clients via `TrackListReplaced`, see [`MediaControls::update_tracklist`].
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use souvlaki::{
+    MediaControlEvent, MediaMetadata, MediaPlayback, MediaPosition, PlatformConfig, SeekDirection,
+};
+use termusiclib::new_database::{Database, Integer, smart_playlist_ops, track_ops};
+use termusiclib::player::playlist_helpers::{
+    PlaylistAddTrack, PlaylistPlaySpecific, PlaylistRemoveTrackIndexed, PlaylistTrackSource,
+};
+use termusiclib::track::Track;
+use zbus::blocking::{Connection, ConnectionBuilder};
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
+use zbus::{SignalContext, dbus_interface, fdo};
+
+use crate::{PlayerCmd, PlayerCmdSender, SharedPlaylist};
+
+/// The object path all MPRIS interfaces are served at.
+const MPRIS_PATH: &str = "/org/mpris/MediaPlayer2";
+
+/// The special track id meaning "no track", for example to add a track at the start.
+const NO_TRACK: &str = "/org/mpris/MediaPlayer2/TrackList/NoTrack";
+
+/// Prefix of track ids, followed by the index of the track in the playlist.
+const TRACK_ID_PREFIX: &str = "/org/termusic/track/";
+
+/// Prefix of playlist ids, followed by the id of the smart playlist in the database.
+const PLAYLIST_ID_PREFIX: &str = "/org/termusic/playlist/";
+
+/// The only ordering supported for `GetPlaylists`, as the database returns them ordered by name.
+const ORDERING_ALPHABETICAL: &str = "Alphabetical";
+
+type EventHandler = Arc<dyn Fn(MediaControlEvent) + Send + Sync>;
+
+/// Owned version of [`MediaMetadata`].
+#[derive(Debug, Clone, Default)]
+struct Metadata {
+    title: Option<String>,
+    artist: Option<String>,
+    album: Option<String>,
+    cover_url: Option<String>,
+    duration: Option<Duration>,
+}
+
+/// State set via [`MediaControls`] and reported by the interfaces.
+#[derive(Debug, Clone)]
+struct State {
+    playback: MediaPlayback,
+    metadata: Metadata,
+    volume: f64,
+    /// The id and name of the last activated smart playlist
+    active_playlist: Option<(Integer, String)>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            playback: MediaPlayback::Stopped,
+            metadata: Metadata::default(),
+            volume: 1.0,
+            active_playlist: None,
+        }
+    }
+}
+
+/// Everything the interfaces need to answer requests.
+#[derive(Clone)]
+struct Shared {
+    state: Arc<Mutex<State>>,
+    playlist: SharedPlaylist,
+    db: Database,
+    cmd_tx: PlayerCmdSender,
+}
+
+impl Shared {
+    /// Send `cmd` to the player, logging if that fails.
+    fn send(&self, cmd: PlayerCmd) {
+        if let Err(err) = self.cmd_tx.send(cmd) {
+            error!("Sending mpris command failed: {err}");
+        }
+    }
+
+    /// Get the index and the source of the playlist track identified by `track_id`.
+    fn find_track(&self, track_id: &ObjectPath<'_>) -> fdo::Result<(usize, PlaylistTrackSource)> {
+        let playlist = self.playlist.read_recursive();
+        track_id_to_index(track_id)
+            .and_then(|index| playlist.tracks().get(index).map(|v| (index, v)))
+            .map(|(index, track)| (index, track.as_track_source()))
+            .ok_or_else(|| fdo::Error::InvalidArgs(format!("Unknown track id \"{track_id}\"")))
+    }
+
+    /// Get the ids of all tracks in the playlist and the id of the current track.
+    fn track_ids(&self) -> (Vec<OwnedObjectPath>, OwnedObjectPath) {
+        let playlist = self.playlist.read_recursive();
+        let tracks = (0..playlist.len()).map(index_to_track_id).collect();
+        let current = if playlist.current_track().is_some() {
+            index_to_track_id(playlist.get_current_track_index())
+        } else {
+            no_track()
+        };
+
+        (tracks, current)
+    }
+}
+
+/// Convert a playlist index to a MPRIS track id.
+fn index_to_track_id(index: usize) -> OwnedObjectPath {
+    ObjectPath::from_string_unchecked(format!("{TRACK_ID_PREFIX}{index}")).into()
+}
+
+/// Convert a MPRIS track id to a playlist index, if it is one of ours.
+fn track_id_to_index(track_id: &ObjectPath<'_>) -> Option<usize> {
+    track_id
+        .as_str()
+        .strip_prefix(TRACK_ID_PREFIX)?
+        .parse()
+        .ok()
+}
+
+fn no_track() -> OwnedObjectPath {
+    ObjectPath::from_static_str_unchecked(NO_TRACK).into()
+}
+
+/// Convert a smart playlist id to a MPRIS playlist id.
+fn playlist_id_to_path(id: Integer) -> OwnedObjectPath {
+    ObjectPath::from_string_unchecked(format!("{PLAYLIST_ID_PREFIX}{id}")).into()
+}
+
+/// Convert a MPRIS playlist id to a smart playlist id, if it is one of ours.
+fn path_to_playlist_id(path: &ObjectPath<'_>) -> Option<Integer> {
+    path.as_str().strip_prefix(PLAYLIST_ID_PREFIX)?.parse().ok()
+}
+
+/// Convert a uri given by a client to a track source.
+///
+/// Local files are expected as `file://` uris, everything else is treated as a radio stream.
+fn uri_to_track_source(uri: &str) -> PlaylistTrackSource {
+    if let Some(path) = uri.strip_prefix("file://") {
+        let path = percent_encoding::percent_decode_str(path).decode_utf8_lossy();
+        PlaylistTrackSource::Path(path.to_string())
+    } else {
+        PlaylistTrackSource::Url(uri.to_string())
+    }
+}
+
+/// Insert the common fields of `xesam` / `mpris` metadata into `map`.
+fn insert_metadata(
+    map: &mut HashMap<String, OwnedValue>,
+    title: Option<&str>,
+    artist: Option<&str>,
+    album: Option<&str>,
+    duration: Option<Duration>,
+) {
+    if let Some(title) = title {
+        map.insert("xesam:title".to_string(), Value::from(title).into());
+    }
+    if let Some(artist) = artist {
+        map.insert("xesam:artist".to_string(), Value::from(vec![artist]).into());
+    }
+    if let Some(album) = album {
+        map.insert("xesam:album".to_string(), Value::from(album).into());
+    }
+    if let Some(duration) = duration {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        let length = duration.as_micros() as i64;
+        map.insert("mpris:length".to_string(), Value::from(length).into());
+    }
+}
+
+/// Build the MPRIS metadata of the playlist `track` at `index`.
+fn track_metadata(index: usize, track: &Track) -> HashMap<String, OwnedValue> {
+    let mut map = HashMap::new();
+    map.insert(
+        "mpris:trackid".to_string(),
+        Value::from(index_to_track_id(index).into_inner()).into(),
+    );
+    insert_metadata(
+        &mut map,
+        track.title(),
+        track.artist(),
+        track.as_track().and_then(|v| v.album()),
+        track.duration(),
+    );
+
+    let url = match track.path() {
+        Some(path) => Some(format!("file://{}", path.display())),
+        None => track.url().map(ToString::to_string),
+    };
+    if let Some(url) = url {
+        map.insert("xesam:url".to_string(), Value::from(url).into());
+    }
+
+    map
+}
+
+/// Interface `org.mpris.MediaPlayer2`.
+struct RootInterface {
+    display_name: String,
+    handler: EventHandler,
+}
+
+#[dbus_interface(name = "org.mpris.MediaPlayer2")]
+impl RootInterface {
+    fn raise(&self) {
+        (self.handler)(MediaControlEvent::Raise);
+    }
+
+    fn quit(&self) {
+        (self.handler)(MediaControlEvent::Quit);
+    }
+
+    #[dbus_interface(property)]
+    fn can_quit(&self) -> bool {
+        true
+    }
+
+    #[dbus_interface(property)]
+    fn can_raise(&self) -> bool {
+        false
+    }
+
+    #[dbus_interface(property)]
+    fn has_track_list(&self) -> bool {
+        true
+    }
+
+    #[dbus_interface(property)]
+    fn identity(&self) -> String {
+        self.display_name.clone()
+    }
+
+    #[dbus_interface(property)]
+    fn supported_uri_schemes(&self) -> Vec<String> {
+        vec!["file".to_string(), "http".to_string(), "https".to_string()]
+    }
+
+    #[dbus_interface(property)]
+    fn supported_mime_types(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Interface `org.mpris.MediaPlayer2.Player`.
+struct PlayerInterface {
+    shared: Shared,
+    handler: EventHandler,
+}
+
+#[dbus_interface(name = "org.mpris.MediaPlayer2.Player")]
+impl PlayerInterface {
+    fn next(&self) {
+        (self.handler)(MediaControlEvent::Next);
+    }
+
+    fn previous(&self) {
+        (self.handler)(MediaControlEvent::Previous);
+    }
+
+    fn pause(&self) {
+        (self.handler)(MediaControlEvent::Pause);
+    }
+
+    fn play_pause(&self) {
+        (self.handler)(MediaControlEvent::Toggle);
+    }
+
+    fn stop(&self) {
+        (self.handler)(MediaControlEvent::Stop);
+    }
+
+    fn play(&self) {
+        (self.handler)(MediaControlEvent::Play);
+    }
+
+    /// Seek by `offset` micro-seconds.
+    fn seek(&self, offset: i64) {
+        let direction = if offset < 0 {
+            SeekDirection::Backward
+        } else {
+            SeekDirection::Forward
+        };
+        (self.handler)(MediaControlEvent::SeekBy(
+            direction,
+            Duration::from_micros(offset.unsigned_abs()),
+        ));
+    }
+
+    /// Seek to `position` micro-seconds, if `track_id` is still the current track.
+    fn set_position(&self, track_id: ObjectPath<'_>, position: i64) {
+        let (_, current) = self.shared.track_ids();
+        if current.as_ref() != track_id || position < 0 {
+            return;
+        }
+        (self.handler)(MediaControlEvent::SetPosition(MediaPosition(
+            Duration::from_micros(position.unsigned_abs()),
+        )));
+    }
+
+    fn open_uri(&self, uri: String) {
+        (self.handler)(MediaControlEvent::OpenUri(uri));
+    }
+
+    #[dbus_interface(property)]
+    fn playback_status(&self) -> &'static str {
+        match self.shared.state.lock().playback {
+            MediaPlayback::Playing { .. } => "Playing",
+            MediaPlayback::Paused { .. } => "Paused",
+            MediaPlayback::Stopped => "Stopped",
+        }
+    }
+
+    #[dbus_interface(property)]
+    fn rate(&self) -> f64 {
+        1.0
+    }
+
+    #[dbus_interface(property)]
+    fn minimum_rate(&self) -> f64 {
+        1.0
+    }
+
+    #[dbus_interface(property)]
+    fn maximum_rate(&self) -> f64 {
+        1.0
+    }
+
+    #[dbus_interface(property)]
+    fn metadata(&self) -> HashMap<String, OwnedValue> {
+        let metadata = self.shared.state.lock().metadata.clone();
+        let (_, current) = self.shared.track_ids();
+
+        let mut map = HashMap::new();
+        map.insert(
+            "mpris:trackid".to_string(),
+            Value::from(current.into_inner()).into(),
+        );
+        insert_metadata(
+            &mut map,
+            metadata.title.as_deref(),
+            metadata.artist.as_deref(),
+            metadata.album.as_deref(),
+            metadata.duration,
+        );
+        if let Some(cover_url) = metadata.cover_url {
+            map.insert("mpris:artUrl".to_string(), Value::from(cover_url).into());
+        }
+
+        map
+    }
+
+    #[dbus_interface(property)]
+    fn volume(&self) -> f64 {
+        self.shared.state.lock().volume
+    }
+
+    #[dbus_interface(property)]
+    fn set_volume(&self, volume: f64) {
+        (self.handler)(MediaControlEvent::SetVolume(volume));
+    }
+
+    /// The position in micro-seconds.
+    #[dbus_interface(property)]
+    fn position(&self) -> i64 {
+        let position = match &self.shared.state.lock().playback {
+            MediaPlayback::Playing { progress } | MediaPlayback::Paused { progress } => {
+                progress.as_ref().map(|v| v.0)
+            }
+            MediaPlayback::Stopped => None,
+        };
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        position.map_or(0, |v| v.as_micros() as i64)
+    }
+
+    #[dbus_interface(property)]
+    fn can_go_next(&self) -> bool {
+        true
+    }
+
+    #[dbus_interface(property)]
+    fn can_go_previous(&self) -> bool {
+        true
+    }
+
+    #[dbus_interface(property)]
+    fn can_play(&self) -> bool {
+        true
+    }
+
+    #[dbus_interface(property)]
+    fn can_pause(&self) -> bool {
+        true
+    }
+
+    #[dbus_interface(property)]
+    fn can_seek(&self) -> bool {
+        true
+    }
+
+    #[dbus_interface(property)]
+    fn can_control(&self) -> bool {
+        true
+    }
+}
+
+/// Interface `org.mpris.MediaPlayer2.TrackList`, exposing the server playlist.
+struct TrackListInterface {
+    shared: Shared,
+}
+
+#[dbus_interface(name = "org.mpris.MediaPlayer2.TrackList")]
+impl TrackListInterface {
+    fn get_tracks_metadata(
+        &self,
+        track_ids: Vec<ObjectPath<'_>>,
+    ) -> Vec<HashMap<String, OwnedValue>> {
+        let playlist = self.shared.playlist.read_recursive();
+        track_ids
+            .iter()
+            .filter_map(track_id_to_index)
+            .filter_map(|index| {
+                playlist
+                    .tracks()
+                    .get(index)
+                    .map(|track| track_metadata(index, track))
+            })
+            .collect()
+    }
+
+    /// Add `uri` after `after_track`, or at the start if it is `NoTrack`.
+    fn add_track(
+        &self,
+        uri: &str,
+        after_track: ObjectPath<'_>,
+        set_as_current: bool,
+    ) -> fdo::Result<()> {
+        let at_index = if after_track.as_str() == NO_TRACK {
+            0
+        } else {
+            self.shared.find_track(&after_track)?.0 + 1
+        };
+        let source = uri_to_track_source(uri);
+
+        self.shared
+            .send(PlayerCmd::PlaylistAddTrack(PlaylistAddTrack::new_single(
+                at_index as u64,
+                source.clone(),
+            )));
+        if set_as_current {
+            self.shared
+                .send(PlayerCmd::PlaylistPlaySpecific(PlaylistPlaySpecific {
+                    track_index: at_index as u64,
+                    id: source,
+                }));
+        }
+
+        Ok(())
+    }
+
+    fn remove_track(&self, track_id: ObjectPath<'_>) -> fdo::Result<()> {
+        let (index, source) = self.shared.find_track(&track_id)?;
+        self.shared.send(PlayerCmd::PlaylistRemoveTrack(
+            PlaylistRemoveTrackIndexed::new_single(index as u64, source),
+        ));
+
+        Ok(())
+    }
+
+    fn go_to(&self, track_id: ObjectPath<'_>) -> fdo::Result<()> {
+        let (index, source) = self.shared.find_track(&track_id)?;
+        self.shared
+            .send(PlayerCmd::PlaylistPlaySpecific(PlaylistPlaySpecific {
+                track_index: index as u64,
+                id: source,
+            }));
+
+        Ok(())
+    }
+
+    #[dbus_interface(signal)]
+    async fn track_list_replaced(
+        ctxt: &SignalContext<'_>,
+        tracks: Vec<OwnedObjectPath>,
+        current_track: OwnedObjectPath,
+    ) -> zbus::Result<()>;
+
+    #[dbus_interface(property)]
+    fn tracks(&self) -> Vec<OwnedObjectPath> {
+        self.shared.track_ids().0
+    }
+
+    #[dbus_interface(property)]
+    fn can_edit_tracks(&self) -> bool {
+        true
+    }
+}
+
+/// Interface `org.mpris.MediaPlayer2.Playlists`, exposing the smart playlists.
+struct PlaylistsInterface {
+    shared: Shared,
+}
+
+impl PlaylistsInterface {
+    /// Load the tracks of smart playlist `id` into the server playlist and start playing it.
+    fn activate(&self, id: Integer) -> Result<String> {
+        let conn = self.shared.db.get_connection();
+        let playlist = smart_playlist_ops::get_all_smart_playlists(&conn)?
+            .into_iter()
+            .find(|v| v.id == id)
+            .with_context(|| format!("No smart playlist with id {id}"))?;
+        let parental = self.shared.playlist.read_recursive().parental().clone();
+        let tracks: Vec<PlaylistTrackSource> = smart_playlist_ops::get_tracks_from_smart_playlist(
+            &conn,
+            &playlist,
+            track_ops::RowOrdering::IdAsc,
+        )?
+        .iter()
+        .filter(|v| parental.allows_genre(v.genre.as_deref()))
+        .map(|v| PlaylistTrackSource::Path(v.as_pathbuf().to_string_lossy().to_string()))
+        .collect();
+        drop(conn);
+
+        self.shared.send(PlayerCmd::PlaylistClear);
+        if let Some(first) = tracks.first().cloned() {
+            self.shared
+                .send(PlayerCmd::PlaylistAddTrack(PlaylistAddTrack::new_vec(
+                    0, tracks,
+                )));
+            self.shared
+                .send(PlayerCmd::PlaylistPlaySpecific(PlaylistPlaySpecific {
+                    track_index: 0,
+                    id: first,
+                }));
+        }
+
+        Ok(playlist.name)
+    }
+}
+
+#[dbus_interface(name = "org.mpris.MediaPlayer2.Playlists")]
+impl PlaylistsInterface {
+    async fn activate_playlist(
+        &self,
+        playlist_id: ObjectPath<'_>,
+        #[zbus(signal_context)] ctxt: SignalContext<'_>,
+    ) -> fdo::Result<()> {
+        let id = path_to_playlist_id(&playlist_id).ok_or_else(|| {
+            fdo::Error::InvalidArgs(format!("Unknown playlist id \"{playlist_id}\""))
+        })?;
+        let name = self
+            .activate(id)
+            .map_err(|err| fdo::Error::Failed(format!("{err:#}")))?;
+
+        self.shared.state.lock().active_playlist = Some((id, name));
+        self.active_playlist_changed(&ctxt).await?;
+
+        Ok(())
+    }
+
+    fn get_playlists(
+        &self,
+        index: u32,
+        max_count: u32,
+        order: &str,
+        reverse_order: bool,
+    ) -> fdo::Result<Vec<(OwnedObjectPath, String, String)>> {
+        if order != ORDERING_ALPHABETICAL {
+            return Err(fdo::Error::InvalidArgs(format!(
+                "Unsupported ordering \"{order}\""
+            )));
+        }
+
+        let mut playlists =
+            smart_playlist_ops::get_all_smart_playlists(&self.shared.db.get_connection())
+                .map_err(|err| fdo::Error::Failed(format!("{err:#}")))?;
+        if reverse_order {
+            playlists.reverse();
+        }
+
+        Ok(playlists
+            .into_iter()
+            .skip(index as usize)
+            .take(max_count as usize)
+            .map(|v| (playlist_id_to_path(v.id), v.name, String::new()))
+            .collect())
+    }
+
+    #[dbus_interface(property)]
+    fn playlist_count(&self) -> u32 {
+        smart_playlist_ops::get_all_smart_playlists(&self.shared.db.get_connection())
+            .map_or(0, |v| u32::try_from(v.len()).unwrap_or(u32::MAX))
+    }
+
+    #[dbus_interface(property)]
+    fn orderings(&self) -> Vec<String> {
+        vec![ORDERING_ALPHABETICAL.to_string()]
+    }
+
+    #[dbus_interface(property)]
+    fn active_playlist(&self) -> (bool, (OwnedObjectPath, String, String)) {
+        match self.shared.state.lock().active_playlist.clone() {
+            Some((id, name)) => (true, (playlist_id_to_path(id), name, String::new())),
+            None => (
+                false,
+                (
+                    ObjectPath::from_static_str_unchecked("/").into(),
+                    String::new(),
+                    String::new(),
+                ),
+            ),
+        }
+    }
+}
+
+/// MPRIS media controls with the same API as [`souvlaki::MediaControls`].
+pub struct MediaControls {
+    dbus_name: String,
+    display_name: String,
+    shared: Shared,
+    /// The connection serving the interfaces, only set once [`attach`](Self::attach) was called
+    connection: Option<Connection>,
+}
+
+impl MediaControls {
+    pub fn new(
+        config: PlatformConfig<'_>,
+        playlist: SharedPlaylist,
+        db: Database,
+        cmd_tx: PlayerCmdSender,
+    ) -> Result<Self> {
+        Ok(Self {
+            dbus_name: config.dbus_name.to_string(),
+            display_name: config.display_name.to_string(),
+            shared: Shared {
+                state: Arc::default(),
+                playlist,
+                db,
+                cmd_tx,
+            },
+            connection: None,
+        })
+    }
+
+    /// Start serving the interfaces, calling `event_handler` for every player event.
+    pub fn attach<F>(&mut self, event_handler: F) -> Result<()>
+    where
+        F: Fn(MediaControlEvent) + Send + Sync + 'static,
+    {
+        let handler: EventHandler = Arc::new(event_handler);
+        let connection = ConnectionBuilder::session()?
+            .name(format!("org.mpris.MediaPlayer2.{}", self.dbus_name))?
+            .serve_at(
+                MPRIS_PATH,
+                RootInterface {
+                    display_name: self.display_name.clone(),
+                    handler: handler.clone(),
+                },
+            )?
+            .serve_at(
+                MPRIS_PATH,
+                PlayerInterface {
+                    shared: self.shared.clone(),
+                    handler,
+                },
+            )?
+            .serve_at(
+                MPRIS_PATH,
+                TrackListInterface {
+                    shared: self.shared.clone(),
+                },
+            )?
+            .serve_at(
+                MPRIS_PATH,
+                PlaylistsInterface {
+                    shared: self.shared.clone(),
+                },
+            )?
+            .build()
+            .context("connecting to the D-Bus session bus")?;
+
+        self.connection = Some(connection);
+
+        Ok(())
+    }
+
+    pub fn set_playback(&mut self, playback: MediaPlayback) -> Result<()> {
+        self.shared.state.lock().playback = playback;
+        self.player_changed(|iface, ctxt| {
+            zbus::block_on(async {
+                iface.playback_status_changed(ctxt).await?;
+                iface.position_changed(ctxt).await
+            })
+        })
+    }
+
+    pub fn set_metadata(&mut self, metadata: MediaMetadata<'_>) -> Result<()> {
+        self.shared.state.lock().metadata = Metadata {
+            title: metadata.title.map(ToString::to_string),
+            artist: metadata.artist.map(ToString::to_string),
+            album: metadata.album.map(ToString::to_string),
+            cover_url: metadata.cover_url.map(ToString::to_string),
+            duration: metadata.duration,
+        };
+        self.player_changed(|iface, ctxt| zbus::block_on(iface.metadata_changed(ctxt)))
+    }
+
+    pub fn set_volume(&mut self, volume: f64) -> Result<()> {
+        self.shared.state.lock().volume = volume;
+        self.player_changed(|iface, ctxt| zbus::block_on(iface.volume_changed(ctxt)))
+    }
+
+    /// Announce that the playlist changed, which also changes all track ids.
+    pub fn update_tracklist(&mut self) -> Result<()> {
+        let Some(connection) = &self.connection else {
+            return Ok(());
+        };
+        let iface_ref = connection
+            .object_server()
+            .interface::<_, TrackListInterface>(MPRIS_PATH)?;
+        let (tracks, current) = self.shared.track_ids();
+        zbus::block_on(TrackListInterface::track_list_replaced(
+            iface_ref.signal_context(),
+            tracks,
+            current,
+        ))?;
+
+        // the current track id is part of the player metadata
+        self.player_changed(|iface, ctxt| zbus::block_on(iface.metadata_changed(ctxt)))
+    }
+
+    /// Run `emit` to signal changed properties of the [`PlayerInterface`], if attached.
+    fn player_changed<F>(&self, emit: F) -> Result<()>
+    where
+        F: FnOnce(&PlayerInterface, &SignalContext<'static>) -> zbus::Result<()>,
+    {
+        let Some(connection) = &self.connection else {
+            return Ok(());
+        };
+        let iface_ref = connection
+            .object_server()
+            .interface::<_, PlayerInterface>(MPRIS_PATH)?;
+        emit(&iface_ref.get(), iface_ref.signal_context())?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use termusiclib::player::playlist_helpers::PlaylistTrackSource;
+    use zbus::zvariant::ObjectPath;
+
+    use super::{
+        index_to_track_id, path_to_playlist_id, playlist_id_to_path, track_id_to_index,
+        uri_to_track_source,
+    };
+
+    #[test]
+    fn should_convert_track_ids() {
+        let id = index_to_track_id(12);
+        assert_eq!(id.as_str(), "/org/termusic/track/12");
+        assert_eq!(track_id_to_index(&id), Some(12));
+        assert_eq!(
+            track_id_to_index(&ObjectPath::from_static_str_unchecked(
+                "/org/mpris/MediaPlayer2/TrackList/NoTrack"
+            )),
+            None
+        );
+
+        let id = playlist_id_to_path(3);
+        assert_eq!(id.as_str(), "/org/termusic/playlist/3");
+        assert_eq!(path_to_playlist_id(&id), Some(3));
+        assert_eq!(path_to_playlist_id(&index_to_track_id(3)), None);
+    }
+
+    #[test]
+    fn should_convert_uris() {
+        assert_eq!(
+            uri_to_track_source("file:///music/some%20artist/track.mp3"),
+            PlaylistTrackSource::Path("/music/some artist/track.mp3".to_string())
+        );
+        assert_eq!(
+            uri_to_track_source("https://example.com/stream"),
+            PlaylistTrackSource::Url("https://example.com/stream".to_string())
+        );
+    }
+}
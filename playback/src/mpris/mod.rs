@@ -1,23 +1,34 @@
 use std::sync::mpsc::{self, Receiver};
 
 use base64::Engine;
-use souvlaki::{MediaControlEvent, MediaControls, MediaMetadata, MediaPlayback, PlatformConfig};
+use reqwest::Url;
+#[cfg(not(target_os = "linux"))]
+use souvlaki::MediaControls;
+use souvlaki::{MediaControlEvent, MediaMetadata, MediaPlayback, PlatformConfig};
 use termusiclib::{
-    common::const_unknown::{UNKNOWN_ARTIST, UNKNOWN_TITLE},
+    artwork_cache::ArtworkCache, common::const_unknown::UNKNOWN_ARTIST, new_database::Database,
     track::Track,
 };
+use tokio::runtime::Handle;
 
+#[cfg(target_os = "linux")]
+use self::dbus::MediaControls;
 use crate::{
-    GeneralPlayer, PlayerCmd, PlayerProgress, PlayerTimeUnit, PlayerTrait, RunningStatus, Volume,
+    GeneralPlayer, PlayerCmd, PlayerProgress, PlayerTimeUnit, PlayerTrait, RunningStatus,
+    SharedPlaylist, Volume,
 };
 
+#[cfg(target_os = "linux")]
+mod dbus;
+
 pub struct Mpris {
     controls: MediaControls,
     pub rx: Receiver<MediaControlEvent>,
 }
 
 impl Mpris {
-    pub fn new(cmd_tx: crate::PlayerCmdSender) -> Self {
+    #[allow(unused_variables)] // only linux makes use of "playlist" and "db"
+    pub fn new(cmd_tx: crate::PlayerCmdSender, playlist: SharedPlaylist, db: Database) -> Self {
         // #[cfg(not(target_os = "windows"))]
         // let hwnd = None;
 
@@ -45,6 +56,10 @@ impl Mpris {
             hwnd,
         };
 
+        // on linux our own mpris server is used, which additionally provides the "TrackList" and "Playlists" interfaces
+        #[cfg(target_os = "linux")]
+        let mut controls = MediaControls::new(config, playlist, db, cmd_tx.clone()).unwrap();
+        #[cfg(not(target_os = "linux"))]
         let mut controls = MediaControls::new(config).unwrap();
 
         let (tx, rx) = mpsc::sync_channel(32);
@@ -62,8 +77,44 @@ impl Mpris {
     }
 }
 
+/// Get the url of the artwork of a podcast `track`, pointing to the [artwork cache](ArtworkCache) if it is cached.
+///
+/// If it is not cached yet, the remote url is used and the artwork is cached in the background for the next time.
+fn podcast_cover_url(track: &Track) -> Option<String> {
+    let url = track.as_podcast()?.image_url()?;
+    if url.is_empty() {
+        return None;
+    }
+
+    let cache = match ArtworkCache::new_default() {
+        Ok(v) => v,
+        Err(err) => {
+            error!("Opening the artwork cache failed: {err:#}");
+            return Some(url.to_string());
+        }
+    };
+
+    if let Some(path) = cache.get(url) {
+        if let Ok(file_url) = Url::from_file_path(&path) {
+            return Some(file_url.to_string());
+        }
+    }
+
+    if let Ok(handle) = Handle::try_current() {
+        let url = url.to_string();
+        handle.spawn(async move {
+            if let Err(err) = cache.fetch(&url).await {
+                warn!("Caching the podcast artwork failed: {err:#}");
+            }
+        });
+    }
+
+    Some(url.to_string())
+}
+
 impl Mpris {
-    pub fn add_and_play(&mut self, track: &Track) {
+    /// Show `track` as playing, titled with the `title_format` template.
+    pub fn add_and_play(&mut self, track: &Track, title_format: &str) {
         // This is to fix a bug that the first track is not updated
         std::thread::sleep(std::time::Duration::from_millis(100));
         self.controls
@@ -88,13 +139,15 @@ impl Mpris {
                 error!("Fetching the cover failed: {err:#?}");
                 None
             }
-        };
+        }
+        .or_else(|| podcast_cover_url(track));
 
         let album = track.as_track().and_then(|v| v.album());
+        let title = track.format_title(title_format);
 
         self.controls
             .set_metadata(MediaMetadata {
-                title: Some(track.title().unwrap_or(UNKNOWN_TITLE)),
+                title: Some(title.as_str()),
                 artist: Some(track.artist().unwrap_or(UNKNOWN_ARTIST)),
                 album: Some(album.unwrap_or("")),
                 cover_url: cover_art.as_deref(),
@@ -151,6 +204,17 @@ impl Mpris {
             let _ = self.controls.set_volume(vol);
         }
     }
+
+    /// Update the playlist reported by Media-Controls
+    ///
+    /// currently only does something on linux (mpris)
+    #[allow(clippy::unused_self)] // non-linux targets will complain about unused self
+    pub fn update_tracklist(&mut self) {
+        #[cfg(target_os = "linux")]
+        if let Err(err) = self.controls.update_tracklist() {
+            error!("Updating the mpris tracklist failed: {err:#}");
+        }
+    }
 }
 
 impl GeneralPlayer {
@@ -263,6 +327,14 @@ impl GeneralPlayer {
             mpris.update_volume(volume);
         }
     }
+
+    /// Update Media-Controls reported playlist, if enabled to be reporting
+    #[inline]
+    pub fn mpris_tracklist_update(&mut self) {
+        if let Some(ref mut mpris) = self.mpris {
+            mpris.update_tracklist();
+        }
+    }
 }
 
 // demonstrates how to make a minimal window to allow use of media keys on the command line
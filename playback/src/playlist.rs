@@ -1,18 +1,27 @@
+use std::collections::HashSet;
 use std::error::Error;
 use std::fmt::{Display, Write as _};
 use std::fs::File;
-use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{Context, Result, bail};
 use parking_lot::RwLock;
 use pathdiff::diff_paths;
 use rand::Rng;
-use rand::seq::SliceRandom;
+use rand::seq::{IndexedRandom, SliceRandom};
 use termusiclib::config::SharedServerSettings;
 use termusiclib::config::v2::server::LoopMode;
+use termusiclib::new_database::Database;
+use termusiclib::new_database::queue_ops::{self, DEFAULT_QUEUE, QueueInfo, QueueState};
+use termusiclib::new_database::track_ops;
+use termusiclib::organize::OrganizeMove;
+use termusiclib::parental::ParentalFilter;
 use termusiclib::player::PlaylistLoopModeInfo;
+use termusiclib::player::PlaylistQueueSwitchedInfo;
+use termusiclib::player::PlaylistReplacedInfo;
 use termusiclib::player::PlaylistShuffledInfo;
 use termusiclib::player::PlaylistSwapInfo;
 use termusiclib::player::PlaylistTracks;
@@ -25,8 +34,8 @@ use termusiclib::player::playlist_helpers::{PlaylistAddTrack, PlaylistRemoveTrac
 use termusiclib::player::{self, RunningStatus};
 use termusiclib::player::{PlaylistAddTrackInfo, PlaylistRemoveTrackInfo};
 use termusiclib::podcast::{db::Database as DBPod, episode::Episode};
-use termusiclib::track::{MediaTypes, Track, TrackData};
-use termusiclib::utils::{filetype_supported, get_app_config_path, get_parent_folder};
+use termusiclib::track::{MediaTypes, Track, TrackData, cue};
+use termusiclib::utils::{filetype_supported, get_app_data_path, get_parent_folder};
 
 use crate::SharedPlaylist;
 use crate::StreamTX;
@@ -52,17 +61,39 @@ pub struct Playlist {
     /// Indicator if the playlist should advance the `current_*` and `next_*` values
     need_proceed_to_next: bool,
     stream_tx: StreamTX,
+    /// The database the queue is stored in
+    db: Database,
+    /// The name of the stored queue this playlist is saved as
+    queue: String,
+    /// The time in the current track, stored with the queue
+    current_track_pos: Option<Duration>,
+    /// The time to start the current track at, if it was just restored
+    restore_position: Option<(PlaylistTrackSource, Duration)>,
 
     /// Indicator if we need to save the playlist for interval saving
     is_modified: bool,
+    /// Indicator if only `current_track_pos` needs to be saved for interval saving
+    is_pos_modified: bool,
+    /// The content filter tracks have to pass to be added
+    parental: ParentalFilter,
 }
 
 impl Playlist {
     /// Create a new playlist instance with 0 tracks
-    pub fn new(config: &SharedServerSettings, stream_tx: StreamTX) -> Self {
+    pub fn new(config: &SharedServerSettings, stream_tx: StreamTX, db: Database) -> Self {
+        let config_read = config.read();
         // TODO: shouldnt "loop_mode" be combined with the config ones?
-        let loop_mode = config.read().settings.player.loop_mode;
+        let loop_mode = config_read.settings.player.loop_mode;
+        let parental = config_read.parental.clone();
+        drop(config_read);
         let current_track = None;
+        let queue = match queue_ops::get_active_queue(&db.get_connection()) {
+            Ok(v) => v.unwrap_or_else(|| DEFAULT_QUEUE.to_string()),
+            Err(err) => {
+                warn!("Could not get the active queue: {err:#}");
+                DEFAULT_QUEUE.to_string()
+            }
+        };
 
         Self {
             tracks: Vec::new(),
@@ -74,21 +105,33 @@ impl Playlist {
             next_track_index: None,
             need_proceed_to_next: false,
             stream_tx,
+            db,
+            queue,
+            current_track_pos: None,
+            restore_position: None,
             is_modified: false,
+            is_pos_modified: false,
+            parental,
         }
     }
 
     /// Create a new Playlist instance that is directly shared
     ///
+    /// The stored queue is only loaded if enabled in the config.
+    ///
     /// # Errors
     ///
     /// see [`load`](Self::load)
     pub fn new_shared(
         config: &SharedServerSettings,
         stream_tx: StreamTX,
+        db: Database,
     ) -> Result<SharedPlaylist> {
-        let mut playlist = Self::new(config, stream_tx);
-        playlist.load_apply()?;
+        let restore_queue = config.read().settings.player.restore_queue;
+        let mut playlist = Self::new(config, stream_tx, db);
+        if restore_queue {
+            playlist.load_apply()?;
+        }
 
         Ok(Arc::new(RwLock::new(playlist)))
     }
@@ -109,22 +152,69 @@ impl Playlist {
         self.need_proceed_to_next = false;
     }
 
-    /// Load the playlist from the file.
+    /// Load the queue stored in the database.
+    ///
+    /// If no queue has been stored in the database yet, the playlist of older versions in `$data$/playlist.log` is loaded instead.
+    /// Tracks which cannot be loaded anymore, like deleted files, are skipped.
     ///
-    /// Path in `$config$/playlist.log`.
+    /// Returns `(Position, Tracks[], Time in the current track)`.
+    ///
+    /// # Errors
+    /// - When the database cannot be read
+    /// - When podcasts cannot be loaded
+    pub fn load(db: &Database) -> Result<(usize, Vec<Track>, Option<Duration>)> {
+        let Some(state) = queue_ops::get_queue(&db.get_connection())? else {
+            let (current_track_index, tracks) = Self::load_legacy_file()?;
+            return Ok((current_track_index, tracks, None));
+        };
+
+        let db_podcast = DBPod::new(&get_app_data_path()?)?;
+        let mut current_track_index = state.current_index;
+        let mut position = state.position;
+        let mut tracks = Vec::with_capacity(state.tracks.len());
+        for (idx, source) in state.tracks.into_iter().enumerate() {
+            let track = match &source {
+                PlaylistTrackSource::Path(v) => Track::read_track_from_path(v),
+                PlaylistTrackSource::Url(v) => Ok(Track::new_radio(v)),
+                PlaylistTrackSource::PodcastUrl(v) => db_podcast
+                    .get_episode_by_url(v)
+                    .map(|episode| Track::from_podcast_episode(&episode)),
+            };
+
+            match track {
+                Ok(track) => tracks.push(track),
+                Err(err) => {
+                    warn!("Skipping stored queue track {source:?}: {err:#}");
+                    // keep the index pointing at the same track
+                    if idx < state.current_index {
+                        current_track_index = current_track_index.saturating_sub(1);
+                    } else if idx == state.current_index {
+                        position = None;
+                    }
+                }
+            }
+        }
+
+        // protect against the stored index not matching the loaded tracks
+        let current_track_index = current_track_index.min(tracks.len().saturating_sub(1));
+
+        Ok((current_track_index, tracks, position))
+    }
+
+    /// Load the playlist from the file of older versions.
+    ///
+    /// Path in `$data$/playlist.log`.
     ///
     /// Returns `(Position, Tracks[])`.
     ///
     /// # Errors
-    /// - When the playlist path is not write-able
+    /// - When the playlist file cannot be read
     /// - When podcasts cannot be loaded
-    pub fn load() -> Result<(usize, Vec<Track>)> {
+    fn load_legacy_file() -> Result<(usize, Vec<Track>)> {
         let path = get_playlist_path()?;
 
         let Ok(file) = File::open(&path) else {
-            // new file, nothing to parse from it
-            File::create(&path)?;
-
+            // no file, nothing to parse from it
             return Ok((0, Vec::new()));
         };
 
@@ -143,7 +233,7 @@ impl Playlist {
         }
 
         let mut playlist_items = Vec::new();
-        let db_path = get_app_config_path()?;
+        let db_path = get_app_data_path()?;
         let db_podcast = DBPod::new(&db_path)?;
         let podcasts = db_podcast
             .get_podcasts()
@@ -191,18 +281,142 @@ impl Playlist {
 
     /// Run [`load`](Self::load), but also apply the values directly to the current instance.
     ///
+    /// The stored time in the current track is applied once that track is started with [`take_restore_position`](Self::take_restore_position).
+    ///
     /// # Errors
     ///
     /// See [`load`](Self::load)
     pub fn load_apply(&mut self) -> Result<()> {
-        let (current_track_index, tracks) = Self::load()?;
+        let (current_track_index, tracks, position) = Self::load(&self.db)?;
+        self.restore_position = position.and_then(|position| {
+            tracks
+                .get(current_track_index)
+                .map(|track| (track.as_track_source(), position))
+        });
+        self.current_track_pos = position;
         self.current_track_index = current_track_index;
         self.tracks = tracks;
         self.is_modified = false;
+        self.is_pos_modified = false;
 
         Ok(())
     }
 
+    /// Replace the playlist with the queue stored in the database, see [`load`](Self::load).
+    ///
+    /// This should only be done while stopped.
+    ///
+    /// # Errors
+    ///
+    /// See [`load`](Self::load)
+    pub fn restore(&mut self) -> Result<()> {
+        self.load_apply()?;
+        self.reset_after_load();
+
+        self.send_stream_ev_pl(UpdatePlaylistEvents::PlaylistReplaced(
+            PlaylistReplacedInfo {
+                tracks: self.as_grpc_playlist_tracks()?,
+            },
+        ));
+
+        Ok(())
+    }
+
+    /// Reset the playback state that refers to the tracks before they got replaced by [`load_apply`](Self::load_apply).
+    fn reset_after_load(&mut self) {
+        self.played_index.clear();
+        self.next_track_index.take();
+        self.current_track = None;
+        self.need_proceed_to_next = false;
+    }
+
+    /// The name of the stored queue this playlist is saved as.
+    #[must_use]
+    pub fn queue(&self) -> &str {
+        &self.queue
+    }
+
+    /// Get all stored queues.
+    ///
+    /// # Errors
+    ///
+    /// - When the database cannot be read
+    pub fn queues(&self) -> Result<Vec<QueueInfo>> {
+        queue_ops::get_queues(&self.db.get_connection())
+    }
+
+    /// Create a new empty stored queue called `name`.
+    ///
+    /// # Errors
+    ///
+    /// See [`queue_ops::create_queue`]
+    pub fn create_queue(&self, name: &str) -> Result<()> {
+        queue_ops::create_queue(&self.db.get_connection(), name)
+    }
+
+    /// Delete the stored queue called `name`, which cannot be the queue of this playlist.
+    ///
+    /// # Errors
+    ///
+    /// See [`queue_ops::delete_queue`]
+    pub fn delete_queue(&self, name: &str) -> Result<()> {
+        if name == self.queue {
+            bail!("The active queue cannot be deleted, switch to another queue first");
+        }
+
+        queue_ops::delete_queue(&self.db.get_connection(), name)
+    }
+
+    /// Store the playlist in the current queue, then replace it with the stored queue called `name`.
+    ///
+    /// This should only be done while stopped.
+    ///
+    /// # Errors
+    ///
+    /// - When there is no queue called `name`
+    /// - See [`save`](Self::save) and [`load`](Self::load)
+    pub fn switch_queue(&mut self, name: &str) -> Result<()> {
+        self.save()?;
+        queue_ops::set_active_queue(&self.db.get_connection(), name)?;
+        self.queue = name.to_string();
+
+        self.load_apply()?;
+        self.reset_after_load();
+
+        self.send_stream_ev_pl(UpdatePlaylistEvents::PlaylistQueueSwitched(
+            PlaylistQueueSwitchedInfo {
+                queue: self.queue.clone(),
+                tracks: self.as_grpc_playlist_tracks()?,
+            },
+        ));
+
+        Ok(())
+    }
+
+    /// Take the time to start at, if `track` is the current track of a just restored queue.
+    ///
+    /// Only the first track started after restoring is considered.
+    pub fn take_restore_position(&mut self, track: &Track) -> Option<Duration> {
+        let (source, position) = self.restore_position.take()?;
+
+        (track.as_track_source() == source).then_some(position)
+    }
+
+    /// Set the time to start `track` at, once it is the next track started.
+    ///
+    /// Replaces the time of a just restored queue, see [`take_restore_position`](Self::take_restore_position).
+    pub fn set_restore_position(&mut self, track: &Track, position: Duration) {
+        self.restore_position = Some((track.as_track_source(), position));
+    }
+
+    /// Set the time in the current track, to be stored with the queue.
+    pub fn set_current_track_pos(&mut self, position: Option<Duration>) {
+        if self.current_track_pos != position {
+            self.current_track_pos = position;
+            self.is_pos_modified = true;
+        }
+    }
+
     /// Load Tracks from a GRPC response.
     ///
     /// Returns `(Position, Tracks[])`.
@@ -249,7 +463,7 @@ impl Playlist {
         Ok(())
     }
 
-    /// Reload the current playlist from the file. This function does not save beforehand.
+    /// Reload the current playlist from the database. This function does not save beforehand.
     ///
     /// This is currently 1:1 the same as [`Self::load_apply`],
     /// but has some slight different semantic meaning in that [`Self::load_apply`] is meant for a new Playlist instance.
@@ -258,51 +472,29 @@ impl Playlist {
     ///
     /// See [`Self::load`]
     pub fn reload_tracks(&mut self) -> Result<()> {
-        let (current_track_index, tracks) = Self::load()?;
-        self.tracks = tracks;
-        self.current_track_index = current_track_index;
-        self.is_modified = false;
-
-        Ok(())
+        self.load_apply()
     }
 
-    /// Save the current playlist and playing index to the playlist log
-    ///
-    /// Path in `$config$/playlist.log`
+    /// Store the current playlist, playing index and time in the current track in the database.
     ///
     /// # Errors
     ///
-    /// Errors could happen when writing files
+    /// Errors could happen when writing to the database
     pub fn save(&mut self) -> Result<()> {
-        let path = get_playlist_path()?;
-
-        let file = File::create(&path)?;
-
-        // If the playlist is empty, truncate the file, but dont write anything else (like a index number)
-        if self.is_empty() {
-            self.is_modified = false;
-            return Ok(());
-        }
-
-        let mut writer = BufWriter::new(file);
-        writer.write_all(self.current_track_index.to_string().as_bytes())?;
-        writer.write_all(b"\n")?;
-        for track in &self.tracks {
-            let id = match track.inner() {
-                MediaTypes::Track(track_data) => track_data.path().to_string_lossy(),
-                MediaTypes::Radio(radio_track_data) => radio_track_data.url().into(),
-                MediaTypes::Podcast(podcast_track_data) => podcast_track_data.url().into(),
-            };
-            writeln!(writer, "{id}")?;
-        }
+        let state = QueueState {
+            tracks: self.tracks.iter().map(Track::as_track_source).collect(),
+            current_index: self.current_track_index,
+            position: self.current_track_pos,
+        };
+        queue_ops::set_queue(&self.db.get_connection(), &state)?;
 
-        writer.flush()?;
         self.is_modified = false;
+        self.is_pos_modified = false;
 
         Ok(())
     }
 
-    /// Run [`Self::save`] only if [`Self::is_modified`] is `true`.
+    /// Run [`Self::save`] only if [`Self::is_modified`] is `true`, or only store the time in the current track if only that changed.
     ///
     /// This is mainly used for saving in intervals and not writing if nothing changed.
     ///
@@ -318,6 +510,13 @@ impl Playlist {
             return Ok(true);
         }
 
+        if self.is_pos_modified {
+            queue_ops::set_queue_position(&self.db.get_connection(), self.current_track_pos)?;
+            self.is_pos_modified = false;
+
+            return Ok(true);
+        }
+
         Ok(false)
     }
 
@@ -540,6 +739,18 @@ impl Playlist {
         result
     }
 
+    /// Get the track [`fetch_next_track`](Self::fetch_next_track) would return, without choosing it.
+    ///
+    /// Always `None` in [`LoopMode::Random`], as the next track is only chosen once fetched.
+    #[must_use]
+    pub fn peek_next_track(&self) -> Option<&Track> {
+        if self.loop_mode == LoopMode::Random {
+            return None;
+        }
+
+        self.tracks.get(self.get_next_track_index())
+    }
+
     /// Get the next track index and return a reference to it.
     pub fn fetch_next_track(&mut self) -> Option<&Track> {
         let next_index = self.get_next_track_index();
@@ -708,6 +919,11 @@ impl Playlist {
             return Ok(());
         }
 
+        if !self.parental.allows_file(Path::new(track_str)) {
+            return Err(PlaylistAddError::HiddenByParentalFilter(PathBuf::from(
+                track_str,
+            )));
+        }
         let track = Self::track_from_path(track_str)?;
 
         self.send_stream_ev_pl(UpdatePlaylistEvents::PlaylistAddTrack(
@@ -738,6 +954,28 @@ impl Playlist {
         Ok(track)
     }
 
+    /// Create a Track from `track_location` like [`Self::source_to_track`], if the parental filter allows it.
+    fn allowed_track(&self, track_location: &PlaylistTrackSource, db_pod: &DBPod) -> Result<Track> {
+        let allowed = match track_location {
+            PlaylistTrackSource::Path(path) => self.parental.allows_file(Path::new(path)),
+            PlaylistTrackSource::Url(_) => true,
+            PlaylistTrackSource::PodcastUrl(uri) => {
+                !self.parental.hide_explicit || !db_pod.is_episode_explicit(uri)?
+            }
+        };
+        if !allowed {
+            bail!("{track_location:?} is hidden by the parental filter");
+        }
+
+        Self::source_to_track(track_location, db_pod)
+    }
+
+    /// Get the content filter tracks have to pass to be added.
+    #[must_use]
+    pub fn parental(&self) -> &ParentalFilter {
+        &self.parental
+    }
+
     /// Add Paths / Urls from the music service
     ///
     /// # Errors
@@ -768,8 +1006,8 @@ impl Playlist {
 
         if at_index >= self.len() {
             // insert tracks at the end
-            for track_location in tracks.tracks {
-                let track = match Self::source_to_track(&track_location, db_pod) {
+            for (track_location, metadata) in tracks.into_iter_with_metadata() {
+                let mut track = match self.allowed_track(&track_location, db_pod) {
                     Ok(v) => v,
                     Err(err) => {
                         warn!("Error adding track: {err}");
@@ -777,6 +1015,7 @@ impl Playlist {
                         continue;
                     }
                 };
+                track.fill_missing(metadata.title, metadata.duration);
 
                 self.send_stream_ev_pl(UpdatePlaylistEvents::PlaylistAddTrack(
                     PlaylistAddTrackInfo {
@@ -794,8 +1033,8 @@ impl Playlist {
         } else {
             let mut at_index = at_index;
             // insert tracks at position
-            for track_location in tracks.tracks {
-                let track = match Self::source_to_track(&track_location, db_pod) {
+            for (track_location, metadata) in tracks.into_iter_with_metadata() {
+                let mut track = match self.allowed_track(&track_location, db_pod) {
                     Ok(v) => v,
                     Err(err) => {
                         warn!("Error adding track: {err}");
@@ -803,6 +1042,7 @@ impl Playlist {
                         continue;
                     }
                 };
+                track.fill_missing(metadata.title, metadata.duration);
 
                 self.send_stream_ev_pl(UpdatePlaylistEvents::PlaylistAddTrack(
                     PlaylistAddTrackInfo {
@@ -892,7 +1132,8 @@ impl Playlist {
             return Err(PlaylistAddError::UnsupportedFileType(ext, p));
         }
 
-        if !path.exists() {
+        // cue sheet tracks exist as long as their cue sheet does
+        if !cue::file_path(path).exists() {
             return Err(PlaylistAddError::PathDoesNotExist(path.to_path_buf()));
         }
 
@@ -1059,6 +1300,8 @@ impl Playlist {
     }
 
     /// Get a random index in the playlist.
+    ///
+    /// Tracks marked to never auto-play are skipped, unless there is nothing else left.
     fn get_random_index(&self) -> usize {
         let mut random_index = self.current_track_index;
 
@@ -1067,6 +1310,30 @@ impl Playlist {
         }
 
         let mut rng = rand::rng();
+
+        let no_autoplay = match track_ops::get_no_autoplay_paths(&self.db.get_connection()) {
+            Ok(v) => v,
+            Err(err) => {
+                warn!("Error getting never auto-played tracks: {err:#}");
+                HashSet::new()
+            }
+        };
+        let candidates: Vec<usize> = self
+            .tracks
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| *idx != self.current_track_index)
+            .filter(|(_, track)| {
+                track
+                    .as_track()
+                    .is_none_or(|v| !no_autoplay.contains(v.path()))
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+        if let Some(index) = candidates.choose(&mut rng) {
+            return *index;
+        }
+
         while self.current_track_index == random_index {
             random_index = rng.random_range(0..self.len());
         }
@@ -1119,6 +1386,50 @@ impl Playlist {
         }
     }
 
+    /// Change all tracks moved by organizing the library to their new path, see [`termusiclib::organize`].
+    ///
+    /// # Errors
+    ///
+    /// - if the changed playlist cannot be sent to clients
+    pub fn rename_paths(&mut self, moves: &[OrganizeMove]) -> Result<()> {
+        let renamed = |track: &Track| -> Option<Track> {
+            let path = track.as_track().map(TrackData::path)?;
+            let mv = moves.iter().find(|mv| mv.from == path)?;
+
+            match Self::track_from_path(&mv.to.to_string_lossy()) {
+                Ok(v) => Some(v),
+                Err(err) => {
+                    warn!("Error reading moved track {}: {err:#}", mv.to.display());
+                    None
+                }
+            }
+        };
+
+        let mut changed = false;
+        for track in &mut self.tracks {
+            if let Some(new_track) = renamed(track) {
+                *track = new_track;
+                changed = true;
+            }
+        }
+        if let Some(new_track) = self.current_track.as_ref().and_then(renamed) {
+            self.current_track = Some(new_track);
+        }
+
+        if !changed {
+            return Ok(());
+        }
+        self.is_modified = true;
+
+        self.send_stream_ev_pl(UpdatePlaylistEvents::PlaylistReplaced(
+            PlaylistReplacedInfo {
+                tracks: self.as_grpc_playlist_tracks()?,
+            },
+        ));
+
+        Ok(())
+    }
+
     /// Stop the current playlist by setting [`RunningStatus::Stopped`], preventing going to the next track
     /// and finally, stop the currently playing track.
     pub fn stop(&mut self) {
@@ -1191,7 +1502,7 @@ impl Playlist {
 const PLAYLIST_SAVE_FILENAME: &str = "playlist.log";
 
 fn get_playlist_path() -> Result<PathBuf> {
-    let mut path = get_app_config_path()?;
+    let mut path = get_app_data_path()?;
     path.push(PLAYLIST_SAVE_FILENAME);
 
     Ok(path)
@@ -1240,6 +1551,8 @@ pub enum PlaylistAddError {
     /// Generic Error for when reading the track fails
     /// `(OriginalError, Path)`
     ReadError(anyhow::Error, PathBuf),
+    /// `(Path)`
+    HiddenByParentalFilter(PathBuf),
 }
 
 impl Display for PlaylistAddError {
@@ -1262,6 +1575,9 @@ impl Display for PlaylistAddError {
                 Self::ReadError(err, path) => {
                     format!("{err} at \"{}\"", path.display())
                 }
+                Self::HiddenByParentalFilter(path) => {
+                    format!("Hidden by the parental filter: \"{}\"", path.display())
+                }
             }
         )
     }
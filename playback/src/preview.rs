@@ -0,0 +1,62 @@
+//! Preview, which plays a short part of a track without changing the playlist, see [`GeneralPlayer::start_preview`].
+//!
+//! The preview is normalized like any other track, see `player.replay_gain`.
+//!
+//! [`GeneralPlayer::start_preview`]: crate::GeneralPlayer::start_preview
+
+use std::time::{Duration, Instant};
+
+use termusiclib::player::RunningStatus;
+
+/// How long a preview plays before the playback from before continues.
+pub const PREVIEW_LENGTH: Duration = Duration::from_secs(10);
+/// How far into the track a preview starts, in percent of its duration
+const PREVIEW_START_PERCENT: u32 = 30;
+
+/// The playback to continue after a preview.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Preview {
+    /// When the preview started playing
+    pub started: Instant,
+    /// The position in the current track of the playlist
+    pub position: Option<Duration>,
+    pub status: RunningStatus,
+}
+
+impl Preview {
+    /// Get whether the preview played for [`PREVIEW_LENGTH`].
+    #[must_use]
+    pub fn is_over(&self) -> bool {
+        self.started.elapsed() >= PREVIEW_LENGTH
+    }
+}
+
+/// Get the position to start the preview of a track with `duration` at, the beginning if unknown.
+#[must_use]
+pub fn start_position(duration: Option<Duration>) -> Duration {
+    duration
+        .map(|v| v * PREVIEW_START_PERCENT / 100)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use super::start_position;
+
+    #[test]
+    fn should_start_at_30_percent() {
+        assert_eq!(
+            start_position(Some(Duration::from_secs(200))),
+            Duration::from_secs(60)
+        );
+        assert_eq!(
+            start_position(Some(Duration::from_secs(5))),
+            Duration::from_millis(1500)
+        );
+        assert_eq!(start_position(None), Duration::ZERO);
+    }
+}
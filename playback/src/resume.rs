@@ -0,0 +1,143 @@
+//! Resume, which continues the last played track when the server starts or play is pressed while stopped.
+//!
+//! Enabled with `player.resume_on_start`.
+//! Also rewinding podcast episodes resumed after a long pause, see `podcast.resume_rewind`.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use termusiclib::config::v2::server::ResumeRewind;
+use termusiclib::new_database::Database;
+use termusiclib::new_database::track_ops::{self, PlayStatsOrder};
+use termusiclib::track::MediaTypesSimple;
+
+use crate::playlist::Playlist;
+
+/// Music is only continued at its last position if it was further in than this, otherwise it starts from the beginning.
+pub const MIN_MUSIC_RESUME_POSITION: Duration = Duration::from_secs(60);
+
+/// Get the position to continue a track of `media_type` at, from its last `position`.
+///
+/// `None` means the track starts from the beginning.
+#[must_use]
+pub fn resume_position(
+    media_type: MediaTypesSimple,
+    position: Option<Duration>,
+) -> Option<Duration> {
+    let position = position.filter(|v| !v.is_zero())?;
+
+    match media_type {
+        MediaTypesSimple::Music => (position > MIN_MUSIC_RESUME_POSITION).then_some(position),
+        MediaTypesSimple::Podcast => Some(position),
+        // liveradio cannot be continued at a position
+        MediaTypesSimple::LiveRadio => None,
+    }
+}
+
+/// Add the most recently played library track to `playlist`, for when there is nothing queued to resume.
+///
+/// Returns whether a track was added.
+///
+/// # Errors
+///
+/// - if the database cannot be read
+/// - if the track cannot be added, like when it was deleted since
+pub fn add_last_played(playlist: &mut Playlist, db: &Database) -> Result<bool> {
+    let last = track_ops::get_tracks_by_play_stats(
+        &db.get_connection(),
+        PlayStatsOrder::RecentlyPlayed,
+        1,
+    )
+    .context("get the last played track")?;
+    let Some((track, _)) = last.into_iter().next() else {
+        return Ok(false);
+    };
+
+    playlist.add_track(&track.as_pathbuf().to_string_lossy())?;
+
+    Ok(true)
+}
+
+/// Get the position to resume a track of `media_type` at, which was paused at `position` for `paused_for`.
+///
+/// `None` means the track is resumed where it was paused.
+#[must_use]
+pub fn rewind_position(
+    media_type: MediaTypesSimple,
+    position: Duration,
+    paused_for: Duration,
+    rewind: ResumeRewind,
+) -> Option<Duration> {
+    if media_type != MediaTypesSimple::Podcast
+        || rewind.secs == 0
+        || paused_for < Duration::from_secs(u64::from(rewind.after_mins) * 60)
+    {
+        return None;
+    }
+
+    Some(position.saturating_sub(Duration::from_secs(u64::from(rewind.secs))))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+    use termusiclib::config::v2::server::ResumeRewind;
+    use termusiclib::track::MediaTypesSimple;
+
+    use super::{resume_position, rewind_position};
+
+    #[test]
+    fn should_only_resume_music_after_a_minute() {
+        let secs = |v| Some(Duration::from_secs(v));
+
+        assert_eq!(resume_position(MediaTypesSimple::Music, secs(30)), None);
+        assert_eq!(resume_position(MediaTypesSimple::Music, secs(61)), secs(61));
+        assert_eq!(
+            resume_position(MediaTypesSimple::Podcast, secs(30)),
+            secs(30)
+        );
+        assert_eq!(resume_position(MediaTypesSimple::Podcast, secs(0)), None);
+        assert_eq!(resume_position(MediaTypesSimple::Podcast, None), None);
+        assert_eq!(
+            resume_position(MediaTypesSimple::LiveRadio, secs(120)),
+            None
+        );
+    }
+
+    #[test]
+    fn should_only_rewind_podcasts_after_long_pause() {
+        let rewind = ResumeRewind {
+            after_mins: 10,
+            secs: 15,
+        };
+        let secs = Duration::from_secs;
+
+        assert_eq!(
+            rewind_position(MediaTypesSimple::Podcast, secs(100), secs(600), rewind),
+            Some(secs(85))
+        );
+        assert_eq!(
+            rewind_position(MediaTypesSimple::Podcast, secs(10), secs(3600), rewind),
+            Some(secs(0))
+        );
+        assert_eq!(
+            rewind_position(MediaTypesSimple::Podcast, secs(100), secs(599), rewind),
+            None
+        );
+        assert_eq!(
+            rewind_position(MediaTypesSimple::Music, secs(100), secs(3600), rewind),
+            None
+        );
+        assert_eq!(
+            rewind_position(
+                MediaTypesSimple::Podcast,
+                secs(100),
+                secs(3600),
+                ResumeRewind { secs: 0, ..rewind }
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! Generating and caching the [waveforms](termusiclib::waveform) of local tracks.
+//!
+//! The levels of a whole audio file are cached, so that all tracks of a cue sheet share one decode.
+
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use anyhow::{Context, Result};
+use termusiclib::artwork_cache::ArtworkCache;
+use termusiclib::track::cue::CueSpan;
+use termusiclib::track::{MediaTypes, Track};
+use termusiclib::utils::get_app_cache_path;
+use termusiclib::waveform::{self, WAVEFORM_LEN};
+
+use crate::backends::rusty::decode_levels;
+
+/// The size limit of the waveform cache, in bytes.
+///
+/// With a level every 100ms, this is about 145 hours of audio.
+const CACHE_MAX_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Get the waveform of `track` if it was already generated.
+///
+/// Returns `None` for tracks without a waveform, like radio streams.
+#[must_use]
+pub fn get_cached(track: &Track) -> Option<Vec<u8>> {
+    let (path, span) = audio_file(track)?;
+    let cache = cache().ok()?;
+    let cached = cache.get(&cache_key(path)?)?;
+    let levels = std::fs::read(&cached)
+        .inspect_err(|err| warn!("Reading cached waveform {} failed: {err}", cached.display()))
+        .ok()?;
+
+    Some(for_track(&levels, span))
+}
+
+/// Decode `track` to generate its waveform and cache it.
+///
+/// This takes a while, as the whole file has to be decoded.
+/// Returns `None` for tracks without a waveform, like radio streams.
+pub fn generate(track: &Track) -> Result<Option<Vec<u8>>> {
+    let Some((path, span)) = audio_file(track) else {
+        return Ok(None);
+    };
+
+    let levels = waveform::normalize(&decode_levels(path)?);
+    let cached = cache().and_then(|cache| {
+        let key = cache_key(path).context("get modification time")?;
+        cache.insert(&key, &levels)
+    });
+    if let Err(err) = cached {
+        warn!("Caching waveform of {} failed: {err:#}", path.display());
+    }
+
+    Ok(Some(for_track(&levels, span)))
+}
+
+/// Get the local audio file of `track` and the span of it the track plays.
+fn audio_file(track: &Track) -> Option<(&Path, Option<&CueSpan>)> {
+    match track.inner() {
+        MediaTypes::Track(data) => Some((data.audio_path(), data.cue_span())),
+        // only downloaded episodes, to not download them for this
+        MediaTypes::Podcast(data) => data.localfile().map(|path| (path, None)),
+        MediaTypes::Radio(_) => None,
+    }
+}
+
+/// Resample the `levels` of a whole file to the waveform of the track playing `span` of it.
+fn for_track(levels: &[u8], span: Option<&CueSpan>) -> Vec<u8> {
+    let levels = match span {
+        Some(span) => waveform::slice(levels, span.start, span.end),
+        None => levels,
+    };
+
+    waveform::resample(levels, WAVEFORM_LEN)
+}
+
+fn cache() -> Result<ArtworkCache> {
+    Ok(ArtworkCache::new(
+        get_app_cache_path()?.join("waveform"),
+        CACHE_MAX_SIZE,
+    ))
+}
+
+/// Get the key of the file at `path`, which changes when the file gets modified.
+fn cache_key(path: &Path) -> Option<String> {
+    let modified = std::fs::metadata(path).and_then(|v| v.modified()).ok()?;
+    let modified = modified.duration_since(UNIX_EPOCH).unwrap_or_default();
+
+    Some(format!("{}:{}", path.display(), modified.as_secs()))
+}
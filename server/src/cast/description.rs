@@ -0,0 +1,186 @@
+//! The device description of UPnP devices, which names them and lists the services they offer.
+
+use anyhow::{Context as _, Result, bail};
+use quick_xml::Reader;
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::Event;
+use reqwest::Url;
+
+/// Service type of the `AVTransport` service, without the version.
+const AV_TRANSPORT: &str = "urn:schemas-upnp-org:service:AVTransport:";
+/// Service type of the `RenderingControl` service, without the version.
+const RENDERING_CONTROL: &str = "urn:schemas-upnp-org:service:RenderingControl:";
+
+/// A service of a [`Renderer`], which is controlled with SOAP requests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Service {
+    /// The full service type including the version, like `urn:schemas-upnp-org:service:AVTransport:1`
+    pub service_type: String,
+    pub control_url: Url,
+}
+
+/// A media renderer on the network, like a network speaker or a TV.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Renderer {
+    /// The unique device name, which stays the same across restarts of the device
+    pub id: String,
+    /// The name of the device to show to the user
+    pub name: String,
+    /// The service to play, pause and seek
+    pub av_transport: Service,
+    /// The service to change the volume, not all renderers have one
+    pub rendering_control: Option<Service>,
+}
+
+/// Parse the device description `xml`, which was fetched from `location`.
+///
+/// # Errors
+///
+/// - if the xml cannot be parsed
+/// - if the device is not a media renderer, as it has no `AVTransport` service
+pub fn parse_description(xml: &str, location: &Url) -> Result<Renderer> {
+    let mut reader = Reader::from_str(xml);
+    let mut xml_stack: Vec<String> = Vec::with_capacity(6);
+    // the text of the current element, as entity references are reported separately from the surrounding text
+    let mut text = String::new();
+    let mut buf = Vec::new();
+    let decoder = reader.decoder();
+
+    let mut id = None;
+    let mut name = None;
+    let mut url_base = None;
+    let mut service_type = None;
+    let mut control_url = None;
+    let mut services: Vec<(String, String)> = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => {
+                xml_stack.push(decoder.decode(e.local_name().as_ref())?.into_owned());
+                text.clear();
+            }
+            Event::End(_) => {
+                let value = text.trim().to_string();
+                // the root device comes before any embedded devices
+                match xml_stack.last().map(String::as_str) {
+                    Some("friendlyName") if name.is_none() => name = Some(value),
+                    Some("UDN") if id.is_none() => id = Some(value),
+                    Some("URLBase") => url_base = Some(value),
+                    Some("serviceType") => service_type = Some(value),
+                    Some("controlURL") => control_url = Some(value),
+                    Some("service") => {
+                        if let (Some(ty), Some(url)) = (service_type.take(), control_url.take()) {
+                            services.push((ty, url));
+                        }
+                    }
+                    _ => (),
+                }
+                xml_stack.pop();
+                text.clear();
+            }
+            Event::Text(e) => {
+                text.push_str(&decoder.decode(&e)?);
+            }
+            Event::GeneralRef(e) => {
+                if let Some(ch) = e.resolve_char_ref()? {
+                    text.push(ch);
+                } else if let Some(resolved) = resolve_predefined_entity(&decoder.decode(&e)?) {
+                    text.push_str(resolved);
+                }
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+        buf.clear();
+    }
+
+    // control urls are relative to "URLBase", or to the description itself if there is none
+    let base = match url_base.filter(|v| !v.is_empty()) {
+        Some(url_base) => Url::parse(&url_base).context("parse URLBase")?,
+        None => location.clone(),
+    };
+    let find_service = |prefix: &str| -> Result<Option<Service>> {
+        let Some((service_type, control_url)) =
+            services.iter().find(|(ty, _)| ty.starts_with(prefix))
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(Service {
+            service_type: service_type.clone(),
+            control_url: base
+                .join(control_url)
+                .with_context(|| format!("parse controlURL {control_url:#?}"))?,
+        }))
+    };
+
+    let Some(av_transport) = find_service(AV_TRANSPORT)? else {
+        bail!("Device has no AVTransport service");
+    };
+    let Some(id) = id else {
+        bail!("Device has no UDN");
+    };
+
+    Ok(Renderer {
+        name: name.unwrap_or_else(|| id.clone()),
+        id,
+        av_transport,
+        rendering_control: find_service(RENDERING_CONTROL)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use reqwest::Url;
+
+    use super::{Renderer, Service, parse_description};
+
+    #[test]
+    fn should_parse_description() {
+        let xml = r#"<?xml version="1.0"?>
+<root xmlns="urn:schemas-upnp-org:device-1-0">
+  <specVersion><major>1</major><minor>0</minor></specVersion>
+  <device>
+    <deviceType>urn:schemas-upnp-org:device:MediaRenderer:1</deviceType>
+    <friendlyName>Living Room &amp; Kitchen</friendlyName>
+    <UDN>uuid:5f9ec1b3-ed59-49d4-9a5c-3b2a1f0e2d4c</UDN>
+    <serviceList>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:RenderingControl:1</serviceType>
+        <serviceId>urn:upnp-org:serviceId:RenderingControl</serviceId>
+        <controlURL>/RenderingControl/ctrl</controlURL>
+      </service>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:AVTransport:2</serviceType>
+        <serviceId>urn:upnp-org:serviceId:AVTransport</serviceId>
+        <controlURL>AVTransport/ctrl</controlURL>
+      </service>
+    </serviceList>
+  </device>
+</root>"#;
+        let location = Url::parse("http://192.168.1.20:49152/dev/description.xml").unwrap();
+
+        assert_eq!(
+            parse_description(xml, &location).unwrap(),
+            Renderer {
+                id: "uuid:5f9ec1b3-ed59-49d4-9a5c-3b2a1f0e2d4c".to_string(),
+                name: "Living Room & Kitchen".to_string(),
+                av_transport: Service {
+                    service_type: "urn:schemas-upnp-org:service:AVTransport:2".to_string(),
+                    control_url: Url::parse("http://192.168.1.20:49152/dev/AVTransport/ctrl")
+                        .unwrap(),
+                },
+                rendering_control: Some(Service {
+                    service_type: "urn:schemas-upnp-org:service:RenderingControl:1".to_string(),
+                    control_url: Url::parse("http://192.168.1.20:49152/RenderingControl/ctrl")
+                        .unwrap(),
+                }),
+            }
+        );
+
+        // media servers (libraries) cannot play anything
+        let xml = xml.replace("AVTransport:2", "ContentDirectory:1");
+        assert!(parse_description(&xml, &location).is_err());
+    }
+}
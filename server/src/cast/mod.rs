@@ -0,0 +1,486 @@
+//! Cast playback to DLNA / UPnP media renderers on the local network, like network speakers.
+//!
+//! While casting, the [`CastPlayer`] takes the place of the backend in the [`GeneralPlayer`](termusicplayback::GeneralPlayer),
+//! so all player commands (play, pause, seek, volume, next track) control the renderer instead.
+//! Local files are served to the renderer over HTTP, streams are passed on by url.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use reqwest::{Client, Url};
+use termusiclib::player::{AbRepeat, PlayerProgress, PlayerTimeUnit};
+use termusiclib::track::{MediaTypes, Track};
+use termusicplayback::{
+    MediaInfo, OutputInfo, PlayerCmd, PlayerCmdSender, PlayerErrorType, PlayerTrait, Speed, Volume,
+};
+use tokio::select;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+
+mod description;
+mod serve;
+mod soap;
+mod ssdp;
+
+pub use description::Renderer;
+use serve::FileServer;
+
+/// The renderers found by the last [`discover`], shared between the grpc service and the player loop.
+pub type SharedRenderers = Arc<Mutex<Vec<Renderer>>>;
+
+/// The device type of media renderers.
+const MEDIA_RENDERER: &str = "urn:schemas-upnp-org:device:MediaRenderer:1";
+
+/// How long to wait for renderers to answer a search.
+const DISCOVER_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How often to ask the renderer for the position and whether the track ended.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Speed as reported to the player, casting always plays at normal speed.
+const NORMAL_SPEED: Speed = 10;
+
+/// Find the media renderers on the local network, which takes [`DISCOVER_TIMEOUT`].
+pub async fn discover() -> Result<Vec<Renderer>> {
+    let locations = ssdp::search(MEDIA_RENDERER, DISCOVER_TIMEOUT).await?;
+    let client = http_client()?;
+
+    let mut renderers: Vec<Renderer> = Vec::new();
+    for location in locations {
+        match fetch_renderer(&client, &location).await {
+            // devices with multiple network interfaces answer on each
+            Ok(renderer) if renderers.iter().any(|v| v.id == renderer.id) => (),
+            Ok(renderer) => renderers.push(renderer),
+            Err(err) => warn!("Ignoring UPnP device at {location}: {err:#}"),
+        }
+    }
+    info!("Found {} cast renderers", renderers.len());
+
+    Ok(renderers)
+}
+
+/// Fetch and parse the device description at `location`.
+async fn fetch_renderer(client: &Client, location: &str) -> Result<Renderer> {
+    let url = Url::parse(location).context("parse location")?;
+    let xml = client
+        .get(url.clone())
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    description::parse_description(&xml, &url)
+}
+
+fn http_client() -> Result<Client> {
+    Ok(Client::builder().timeout(Duration::from_secs(5)).build()?)
+}
+
+/// Get the address of this machine in the network the renderer at `control_url` is in.
+fn local_addr_for(control_url: &Url) -> Result<IpAddr> {
+    let host = control_url.host_str().context("control url has no host")?;
+    let port = control_url.port_or_known_default().unwrap_or(80);
+    // "connecting" a udp socket sends nothing, but selects the interface to reach the host from
+    let socket = UdpSocket::bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)))?;
+    socket.connect((host, port))?;
+
+    Ok(socket.local_addr()?.ip())
+}
+
+/// What to play on the renderer.
+#[derive(Debug, Clone)]
+enum CastSource {
+    /// A local file, which is served to the renderer
+    File(PathBuf),
+    /// A stream the renderer fetches itself
+    Url(String),
+}
+
+/// A track to play on the renderer.
+#[derive(Debug, Clone)]
+struct CastMedia {
+    source: CastSource,
+    title: String,
+    artist: Option<String>,
+}
+
+impl CastMedia {
+    fn new(track: &Track) -> Self {
+        let source = match track.inner() {
+            MediaTypes::Track(track_data) => {
+                CastSource::File(track_data.audio_path().to_path_buf())
+            }
+            MediaTypes::Radio(radio_track_data) => {
+                CastSource::Url(radio_track_data.url().to_string())
+            }
+            MediaTypes::Podcast(podcast_track_data) => podcast_track_data.localfile().map_or_else(
+                || CastSource::Url(podcast_track_data.url().to_string()),
+                |path| CastSource::File(path.to_path_buf()),
+            ),
+        };
+
+        Self {
+            source,
+            title: track.title().unwrap_or("Unknown Title").to_string(),
+            artist: track.artist().map(ToString::to_string),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum CastCmd {
+    Play(CastMedia),
+    Pause,
+    Resume,
+    Stop,
+    /// Stop and report the end of the track, to continue with the next one
+    Skip,
+    SeekTo(Duration),
+    Volume(Volume),
+}
+
+/// The state of the renderer, as of the last poll.
+#[derive(Debug, Default)]
+struct CastStatus {
+    position: Option<Duration>,
+    duration: Option<Duration>,
+    paused: bool,
+    /// The volume last set, renderers are set to the volume of the backend when casting starts
+    volume: Volume,
+}
+
+/// Plays on a [`Renderer`] instead of the backend.
+///
+/// The renderer is controlled by a task, which stops the renderer once this is dropped.
+#[derive(Debug)]
+pub struct CastPlayer {
+    command_tx: UnboundedSender<CastCmd>,
+    status: Arc<Mutex<CastStatus>>,
+    /// The name of the renderer
+    name: String,
+}
+
+impl CastPlayer {
+    /// Start controlling `renderer`.
+    ///
+    /// Requires that the function is called on a thread with a entered tokio runtime.
+    pub fn new(renderer: Renderer, cmd_tx: PlayerCmdSender) -> Result<Self> {
+        let (command_tx, command_rx) = unbounded_channel();
+        let status = Arc::new(Mutex::new(CastStatus::default()));
+        let name = renderer.name.clone();
+
+        let task = CastTask {
+            client: http_client()?,
+            renderer,
+            status: status.clone(),
+            cmd_tx,
+            file_server: None,
+            track_active: false,
+            seen_playing: false,
+        };
+        tokio::spawn(task.run(command_rx));
+
+        Ok(Self {
+            command_tx,
+            status,
+            name,
+        })
+    }
+
+    fn command(&self, cmd: CastCmd) {
+        if let Err(err) = self.command_tx.send(cmd) {
+            error!("Cast task has stopped: {err}");
+        }
+    }
+}
+
+#[async_trait]
+impl PlayerTrait for CastPlayer {
+    async fn add_and_play(&mut self, track: &Track) {
+        {
+            let mut status = self.status.lock();
+            status.position = Some(Duration::ZERO);
+            status.duration = track.duration();
+            status.paused = false;
+        }
+        self.command(CastCmd::Play(CastMedia::new(track)));
+    }
+
+    fn volume(&self) -> Volume {
+        self.status.lock().volume
+    }
+
+    fn set_volume(&mut self, volume: Volume) -> Volume {
+        let volume = volume.min(100);
+        self.status.lock().volume = volume;
+        self.command(CastCmd::Volume(volume));
+
+        volume
+    }
+
+    fn pause(&mut self) {
+        self.status.lock().paused = true;
+        self.command(CastCmd::Pause);
+    }
+
+    fn resume(&mut self) {
+        self.status.lock().paused = false;
+        self.command(CastCmd::Resume);
+    }
+
+    fn is_paused(&self) -> bool {
+        self.status.lock().paused
+    }
+
+    fn seek(&mut self, secs: i64) -> Result<()> {
+        let position = self.position().unwrap_or_default();
+        let offset = Duration::from_secs(secs.unsigned_abs());
+        let position = if secs < 0 {
+            position.saturating_sub(offset)
+        } else {
+            position + offset
+        };
+        self.seek_to(position);
+
+        Ok(())
+    }
+
+    fn seek_to(&mut self, position: Duration) {
+        self.status.lock().position = Some(position);
+        self.command(CastCmd::SeekTo(position));
+    }
+
+    fn get_progress(&self) -> Option<PlayerProgress> {
+        let status = self.status.lock();
+
+        Some(PlayerProgress {
+            position: status.position,
+            total_duration: status.duration,
+            ab_repeat: AbRepeat::default(),
+        })
+    }
+
+    fn set_speed(&mut self, _speed: Speed) -> Speed {
+        NORMAL_SPEED
+    }
+
+    fn speed(&self) -> Speed {
+        NORMAL_SPEED
+    }
+
+    fn stop(&mut self) {
+        self.command(CastCmd::Stop);
+    }
+
+    fn gapless(&self) -> bool {
+        false
+    }
+
+    fn set_gapless(&mut self, _to: bool) {}
+
+    fn skip_one(&mut self) {
+        self.command(CastCmd::Skip);
+    }
+
+    fn position(&self) -> Option<PlayerTimeUnit> {
+        self.status.lock().position
+    }
+
+    fn enqueue_next(&mut self, _track: &Track) {
+        // each track is sent to the renderer once it starts, as not all renderers support "SetNextAVTransportURI"
+    }
+
+    fn media_info(&self) -> MediaInfo {
+        MediaInfo::default()
+    }
+
+    fn output_info(&self) -> OutputInfo {
+        OutputInfo {
+            device: Some(format!("cast to {:#?}", self.name)),
+            ..Default::default()
+        }
+    }
+}
+
+/// The task controlling the renderer for a [`CastPlayer`].
+struct CastTask {
+    client: Client,
+    renderer: Renderer,
+    status: Arc<Mutex<CastStatus>>,
+    cmd_tx: PlayerCmdSender,
+    /// Started once the first local file is cast
+    file_server: Option<FileServer>,
+    /// Whether a track was started and has not ended yet
+    track_active: bool,
+    /// Whether the renderer reported playing the current track, as it reports "STOPPED" while still loading it
+    seen_playing: bool,
+}
+
+impl CastTask {
+    async fn run(mut self, mut command_rx: UnboundedReceiver<CastCmd>) {
+        let mut timer = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            select! {
+                cmd = command_rx.recv() => {
+                    // the player stopped casting
+                    let Some(cmd) = cmd else {
+                        if let Err(err) = self.transport("Stop", &[]).await {
+                            warn!("Error stopping {:#?}: {err:#}", self.renderer.name);
+                        }
+                        break;
+                    };
+                    let is_play = matches!(cmd, CastCmd::Play(_));
+                    if let Err(err) = self.handle_cmd(cmd).await {
+                        error!("Error casting to {:#?}: {err:#}", self.renderer.name);
+                        if is_play {
+                            let _ = self.cmd_tx.send(PlayerCmd::Error(PlayerErrorType::Current));
+                        }
+                    }
+                },
+                _ = timer.tick() => {
+                    if let Err(err) = self.poll().await {
+                        debug!("Error polling {:#?}: {err:#}", self.renderer.name);
+                    }
+                },
+            }
+        }
+    }
+
+    async fn handle_cmd(&mut self, cmd: CastCmd) -> Result<()> {
+        match cmd {
+            CastCmd::Play(media) => self.play(media).await?,
+            CastCmd::Pause => {
+                self.transport("Pause", &[]).await?;
+            }
+            CastCmd::Resume => {
+                self.transport("Play", &[("Speed", "1")]).await?;
+            }
+            CastCmd::Stop => {
+                self.track_active = false;
+                self.transport("Stop", &[]).await?;
+            }
+            CastCmd::Skip => {
+                self.track_active = false;
+                // the next track is started regardless
+                let res = self.transport("Stop", &[]).await;
+                let _ = self.cmd_tx.send(PlayerCmd::Eos);
+                res?;
+            }
+            CastCmd::SeekTo(position) => {
+                let target = soap::format_time(position);
+                self.transport("Seek", &[("Unit", "REL_TIME"), ("Target", target.as_str())])
+                    .await?;
+            }
+            CastCmd::Volume(volume) => {
+                let Some(service) = &self.renderer.rendering_control else {
+                    return Ok(());
+                };
+                let volume = volume.to_string();
+                soap::call(
+                    &self.client,
+                    service,
+                    "SetVolume",
+                    &[
+                        ("InstanceID", "0"),
+                        ("Channel", "Master"),
+                        ("DesiredVolume", volume.as_str()),
+                    ],
+                )
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Load `media` on the renderer and start playing it.
+    async fn play(&mut self, media: CastMedia) -> Result<()> {
+        self.track_active = false;
+        self.seen_playing = false;
+
+        let (url, mime) = match media.source {
+            CastSource::File(path) => {
+                let host = local_addr_for(&self.renderer.av_transport.control_url)?;
+                let mut file_server = match self.file_server.take() {
+                    Some(file_server) => file_server,
+                    None => FileServer::start().await?,
+                };
+                let mime = serve::mime_type(&path);
+                let url = file_server.serve(path, host);
+                self.file_server = Some(file_server);
+                (url, mime)
+            }
+            CastSource::Url(url) => {
+                let mime = Url::parse(&url)
+                    .ok()
+                    .map_or("audio/mpeg", |v| serve::mime_type(Path::new(v.path())));
+                (url, mime)
+            }
+        };
+        let metadata = soap::didl_metadata(&url, mime, &media.title, media.artist.as_deref());
+
+        info!("Casting {url:#?} to {:#?}", self.renderer.name);
+        self.transport(
+            "SetAVTransportURI",
+            &[
+                ("CurrentURI", url.as_str()),
+                ("CurrentURIMetaData", metadata.as_str()),
+            ],
+        )
+        .await?;
+        self.transport("Play", &[("Speed", "1")]).await?;
+        self.track_active = true;
+
+        Ok(())
+    }
+
+    /// Update the [`CastStatus`] and report the end of the track.
+    async fn poll(&mut self) -> Result<()> {
+        if !self.track_active {
+            return Ok(());
+        }
+
+        let info = self.transport("GetTransportInfo", &[]).await?;
+        let position = self.transport("GetPositionInfo", &[]).await?;
+
+        {
+            let mut status = self.status.lock();
+            if let Some(time) = soap::get(&position, "RelTime").and_then(soap::parse_time) {
+                status.position = Some(time);
+            }
+            // streams have no duration
+            if let Some(time) = soap::get(&position, "TrackDuration")
+                .and_then(soap::parse_time)
+                .filter(|v| !v.is_zero())
+            {
+                status.duration = Some(time);
+            }
+        }
+
+        match soap::get(&info, "CurrentTransportState").unwrap_or_default() {
+            "PLAYING" => self.seen_playing = true,
+            "STOPPED" | "NO_MEDIA_PRESENT" if self.seen_playing => {
+                info!("Renderer {:#?} finished the track", self.renderer.name);
+                self.track_active = false;
+                self.seen_playing = false;
+                let _ = self.cmd_tx.send(PlayerCmd::Eos);
+            }
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    /// Call `action` of the `AVTransport` service of the renderer.
+    async fn transport(&self, action: &str, args: &[(&str, &str)]) -> Result<soap::ActionOutput> {
+        let mut all_args = vec![("InstanceID", "0")];
+        all_args.extend_from_slice(args);
+
+        soap::call(&self.client, &self.renderer.av_transport, action, &all_args).await
+    }
+}
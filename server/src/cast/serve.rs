@@ -0,0 +1,248 @@
+//! A minimal HTTP server for renderers to fetch the local file that is cast to them.
+
+use std::ffi::OsStr;
+use std::fmt::Write as _;
+use std::io::SeekFrom;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result, bail};
+use parking_lot::Mutex;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// Maximum length of a request line and headers, renderers send only a few headers.
+const MAX_HEAD_LEN: usize = 8 * 1024;
+
+/// Serves a single file at a time, stops when dropped.
+#[derive(Debug)]
+pub struct FileServer {
+    port: u16,
+    /// The file currently served and the path of the url it is served at
+    current: Arc<Mutex<Option<(String, PathBuf)>>>,
+    /// Counter to give each served file a different url, as renderers may cache by url
+    served: u64,
+    handle: JoinHandle<()>,
+}
+
+impl FileServer {
+    /// Start the server on a random port of all interfaces, as renderers have to reach it from the network.
+    pub async fn start() -> Result<Self> {
+        let listener = TcpListener::bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)))
+            .await
+            .context("bind cast file server")?;
+        let port = listener.local_addr()?.port();
+        let current: Arc<Mutex<Option<(String, PathBuf)>>> = Arc::new(Mutex::new(None));
+
+        let current_c = current.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                let (stream, addr) = match listener.accept().await {
+                    Ok(v) => v,
+                    Err(err) => {
+                        warn!("Error accepting cast file server connection: {err}");
+                        continue;
+                    }
+                };
+                let current = current_c.lock().clone();
+                tokio::spawn(async move {
+                    if let Err(err) = handle_connection(stream, current).await {
+                        debug!("Error serving cast file to {addr}: {err:#}");
+                    }
+                });
+            }
+        });
+        info!("Cast file server listening on port {port}");
+
+        Ok(Self {
+            port,
+            current,
+            served: 0,
+            handle,
+        })
+    }
+
+    /// Serve `file` instead of the previous file.
+    ///
+    /// Returns the url to fetch it from, with `host` being the address renderers reach this server at.
+    pub fn serve(&mut self, file: PathBuf, host: IpAddr) -> String {
+        self.served += 1;
+        // renderers guess the format from the extension if they ignore the content type
+        let extension = file
+            .extension()
+            .and_then(OsStr::to_str)
+            .unwrap_or("bin")
+            .to_ascii_lowercase();
+        let path = format!("/track{}.{extension}", self.served);
+        let url = format!("http://{}{path}", SocketAddr::new(host, self.port));
+        *self.current.lock() = Some((path, file));
+
+        url
+    }
+}
+
+impl Drop for FileServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// Get the mime type of the audio file `path` for renderers, from its extension.
+pub fn mime_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(OsStr::to_str)
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    match extension.as_str() {
+        "mp3" => "audio/mpeg",
+        "flac" => "audio/flac",
+        "ogg" | "opus" => "audio/ogg",
+        "m4a" | "mp4" | "aac" => "audio/mp4",
+        "wav" => "audio/wav",
+        "aif" | "aiff" => "audio/aiff",
+        "wma" => "audio/x-ms-wma",
+        "webm" => "audio/webm",
+        "dsf" => "audio/x-dsf",
+        "dff" => "audio/x-dff",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Answer a single request for `current`, the connection is closed afterwards.
+async fn handle_connection(
+    mut stream: TcpStream,
+    current: Option<(String, PathBuf)>,
+) -> Result<()> {
+    let head = read_head(&mut stream).await?;
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (method, path) = (
+        request_line.next().unwrap_or_default(),
+        request_line.next().unwrap_or_default(),
+    );
+    let range = lines.find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim()
+            .eq_ignore_ascii_case("range")
+            .then(|| value.trim())
+    });
+
+    let Some((_, file_path)) = current.filter(|(served, _)| served == path) else {
+        stream
+            .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+            .await?;
+        return Ok(());
+    };
+    if method != "GET" && method != "HEAD" {
+        stream
+            .write_all(b"HTTP/1.1 405 Method Not Allowed\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+            .await?;
+        return Ok(());
+    }
+
+    let mut file = tokio::fs::File::open(&file_path)
+        .await
+        .with_context(|| file_path.display().to_string())?;
+    let len = file.metadata().await?.len();
+
+    let (status, range) = match range {
+        None => ("200 OK", 0..len),
+        Some(range) => {
+            let Some(range) = parse_range(range, len) else {
+                let response = format!(
+                    "HTTP/1.1 416 Range Not Satisfiable\r\nContent-Range: bytes */{len}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                );
+                stream.write_all(response.as_bytes()).await?;
+                return Ok(());
+            };
+            ("206 Partial Content", range)
+        }
+    };
+
+    let mut response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {}\r\nContent-Length: {}\r\nAccept-Ranges: bytes\r\ntransferMode.dlna.org: Streaming\r\nConnection: close\r\n",
+        mime_type(&file_path),
+        range.end - range.start
+    );
+    if status.starts_with("206") {
+        let _ = write!(
+            response,
+            "Content-Range: bytes {}-{}/{len}\r\n",
+            range.start,
+            range.end - 1
+        );
+    }
+    response.push_str("\r\n");
+    stream.write_all(response.as_bytes()).await?;
+
+    if method == "GET" {
+        file.seek(SeekFrom::Start(range.start)).await?;
+        tokio::io::copy(&mut file.take(range.end - range.start), &mut stream).await?;
+    }
+
+    Ok(())
+}
+
+/// Read the request line and headers of a request.
+async fn read_head(stream: &mut TcpStream) -> Result<String> {
+    let mut head = Vec::new();
+    let mut buf = [0; 1024];
+    while !head.windows(4).any(|v| v == b"\r\n\r\n") {
+        if head.len() > MAX_HEAD_LEN {
+            bail!("Request head is longer than {MAX_HEAD_LEN} bytes");
+        }
+        let read = stream.read(&mut buf).await?;
+        if read == 0 {
+            bail!("Connection closed before the end of the request head");
+        }
+        head.extend_from_slice(&buf[..read]);
+    }
+
+    Ok(String::from_utf8_lossy(&head).into_owned())
+}
+
+/// Parse a `Range` header like `bytes=100-`, `bytes=100-199` or `bytes=-100` for a file of `len` bytes.
+///
+/// Returns [`None`] if the range cannot be satisfied, multiple ranges are not supported.
+fn parse_range(header: &str, len: u64) -> Option<Range<u64>> {
+    let (start, end) = header.strip_prefix("bytes=")?.trim().split_once('-')?;
+
+    let range = if start.is_empty() {
+        // the last "end" bytes
+        let suffix: u64 = end.parse().ok()?;
+        len.saturating_sub(suffix)..len
+    } else {
+        let start: u64 = start.parse().ok()?;
+        let end = if end.is_empty() {
+            len
+        } else {
+            end.parse::<u64>().ok()?.saturating_add(1).min(len)
+        };
+        start..end
+    };
+
+    (range.start < range.end).then_some(range)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::parse_range;
+
+    #[test]
+    fn should_parse_ranges() {
+        assert_eq!(parse_range("bytes=0-", 1000), Some(0..1000));
+        assert_eq!(parse_range("bytes=100-199", 1000), Some(100..200));
+        assert_eq!(parse_range("bytes=900-2000", 1000), Some(900..1000));
+        assert_eq!(parse_range("bytes=-100", 1000), Some(900..1000));
+        assert_eq!(parse_range("bytes=1000-", 1000), None);
+        assert_eq!(parse_range("bytes=0-1,5-9", 1000), None);
+        assert_eq!(parse_range("items=0-1", 1000), None);
+    }
+}
@@ -0,0 +1,203 @@
+//! Call actions of UPnP services with SOAP requests, and the formats used in their arguments.
+
+use std::fmt::Write as _;
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use quick_xml::Reader;
+use quick_xml::escape::{escape, resolve_predefined_entity};
+use quick_xml::events::Event;
+use reqwest::Client;
+use reqwest::header::CONTENT_TYPE;
+
+use super::description::Service;
+
+/// The output arguments of a action, by name.
+pub type ActionOutput = Vec<(String, String)>;
+
+/// Call `action` of `service` with the input arguments `args`.
+///
+/// # Errors
+///
+/// - if the request fails
+/// - if the device answers with a error, which is usually a UPnP error like "Transition not available"
+pub async fn call(
+    client: &Client,
+    service: &Service,
+    action: &str,
+    args: &[(&str, &str)],
+) -> Result<ActionOutput> {
+    let response = client
+        .post(service.control_url.clone())
+        .header(CONTENT_TYPE, "text/xml; charset=\"utf-8\"")
+        .header(
+            "SOAPACTION",
+            format!("\"{}#{action}\"", service.service_type),
+        )
+        .body(request_body(&service.service_type, action, args))
+        .send()
+        .await?;
+    let status = response.status();
+    let output = parse_response(&response.text().await?)?;
+
+    if !status.is_success() {
+        let description = get(&output, "errorDescription").unwrap_or_default();
+        bail!("{action} failed with {status}: {description}");
+    }
+
+    Ok(output)
+}
+
+/// Get the argument `name` of the output of a action.
+pub fn get<'a>(output: &'a ActionOutput, name: &str) -> Option<&'a str> {
+    output
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// Build the SOAP envelope to call `action` with `args`.
+fn request_body(service_type: &str, action: &str, args: &[(&str, &str)]) -> String {
+    let mut body = String::from(
+        r#"<?xml version="1.0" encoding="utf-8"?><s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/" s:encodingStyle="http://schemas.xmlsoap.org/soap/encoding/"><s:Body>"#,
+    );
+    let _ = write!(body, r#"<u:{action} xmlns:u="{service_type}">"#);
+    for (name, value) in args {
+        let _ = write!(body, "<{name}>{}</{name}>", escape(*value));
+    }
+    let _ = write!(body, "</u:{action}></s:Body></s:Envelope>");
+
+    body
+}
+
+/// Get all elements without children of a SOAP response, which are the output arguments or the error.
+fn parse_response(xml: &str) -> Result<ActionOutput> {
+    let mut reader = Reader::from_str(xml);
+    let mut output = Vec::new();
+    // the element that was started last, which has no children if it ends next
+    let mut leaf = None;
+    // the text of the current element, as entity references are reported separately from the surrounding text
+    let mut text = String::new();
+    let mut buf = Vec::new();
+    let decoder = reader.decoder();
+
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => {
+                leaf = Some(decoder.decode(e.local_name().as_ref())?.into_owned());
+                text.clear();
+            }
+            Event::Empty(e) => {
+                output.push((
+                    decoder.decode(e.local_name().as_ref())?.into_owned(),
+                    String::new(),
+                ));
+            }
+            Event::End(_) => {
+                if let Some(name) = leaf.take() {
+                    output.push((name, text.trim().to_string()));
+                }
+                text.clear();
+            }
+            Event::Text(e) => {
+                text.push_str(&decoder.decode(&e)?);
+            }
+            Event::GeneralRef(e) => {
+                if let Some(ch) = e.resolve_char_ref()? {
+                    text.push(ch);
+                } else if let Some(resolved) = resolve_predefined_entity(&decoder.decode(&e)?) {
+                    text.push_str(resolved);
+                }
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+        buf.clear();
+    }
+
+    Ok(output)
+}
+
+/// Format `time` as the `H+:MM:SS` used to seek and report positions.
+pub fn format_time(time: Duration) -> String {
+    let secs = time.as_secs();
+
+    format!("{}:{:02}:{:02}", secs / 3600, (secs / 60) % 60, secs % 60)
+}
+
+/// Parse a `H+:MM:SS[.F+]` time, returning [`None`] for invalid times and "NOT_IMPLEMENTED".
+pub fn parse_time(time: &str) -> Option<Duration> {
+    let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+    let mut parts = time.split(':').map(|v| v.parse::<u64>().ok());
+    let (hours, minutes, seconds) = (parts.next()??, parts.next()??, parts.next()??);
+    if parts.next().is_some() || minutes >= 60 || seconds >= 60 {
+        return None;
+    }
+
+    // the fraction may also be given as "F0/F1", which nobody uses
+    let millis = format!("{fraction:0<3}")
+        .get(..3)?
+        .parse::<u64>()
+        .unwrap_or(0);
+
+    Some(Duration::from_secs(hours * 3600 + minutes * 60 + seconds) + Duration::from_millis(millis))
+}
+
+/// Build the DIDL-Lite metadata renderers show for a track at `url`.
+pub fn didl_metadata(url: &str, mime: &str, title: &str, artist: Option<&str>) -> String {
+    let mut didl = String::from(
+        r#"<DIDL-Lite xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:upnp="urn:schemas-upnp-org:metadata-1-0/upnp/"><item id="0" parentID="-1" restricted="1">"#,
+    );
+    let _ = write!(didl, "<dc:title>{}</dc:title>", escape(title));
+    if let Some(artist) = artist {
+        let _ = write!(didl, "<dc:creator>{}</dc:creator>", escape(artist));
+    }
+    let _ = write!(
+        didl,
+        r#"<upnp:class>object.item.audioItem.musicTrack</upnp:class><res protocolInfo="http-get:*:{mime}:*">{}</res></item></DIDL-Lite>"#,
+        escape(url)
+    );
+
+    didl
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{format_time, get, parse_response, parse_time};
+
+    #[test]
+    fn should_parse_response() {
+        let xml = r#"<?xml version="1.0"?>
+<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/">
+  <s:Body>
+    <u:GetPositionInfoResponse xmlns:u="urn:schemas-upnp-org:service:AVTransport:1">
+      <Track>1</Track>
+      <TrackDuration>0:03:21</TrackDuration>
+      <TrackMetaData>&lt;DIDL-Lite/&gt;</TrackMetaData>
+      <TrackURI/>
+      <RelTime>0:01:05.500</RelTime>
+    </u:GetPositionInfoResponse>
+  </s:Body>
+</s:Envelope>"#;
+        let output = parse_response(xml).unwrap();
+
+        assert_eq!(get(&output, "TrackDuration"), Some("0:03:21"));
+        assert_eq!(get(&output, "TrackMetaData"), Some("<DIDL-Lite/>"));
+        assert_eq!(get(&output, "TrackURI"), Some(""));
+        assert_eq!(get(&output, "RelTime"), Some("0:01:05.500"));
+        assert_eq!(get(&output, "GetPositionInfoResponse"), None);
+    }
+
+    #[test]
+    fn should_convert_times() {
+        assert_eq!(format_time(Duration::from_millis(3_725_900)), "1:02:05");
+        assert_eq!(parse_time("1:02:05"), Some(Duration::from_secs(3725)));
+        assert_eq!(parse_time("0:00:07.25"), Some(Duration::from_millis(7250)));
+        assert_eq!(parse_time("NOT_IMPLEMENTED"), None);
+        assert_eq!(parse_time("0:61:00"), None);
+    }
+}
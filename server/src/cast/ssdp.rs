@@ -0,0 +1,106 @@
+//! Find UPnP devices on the local network with SSDP (Simple Service Discovery Protocol).
+//!
+//! See the "UPnP Device Architecture" specification, section "Discovery".
+
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::time::Duration;
+
+use anyhow::{Context as _, Result};
+use tokio::net::UdpSocket;
+use tokio::time::Instant;
+
+/// The multicast address all SSDP devices listen on.
+const SSDP_ADDR: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(239, 255, 255, 250), 1900);
+
+/// Search for devices of type `search_target` for `timeout`.
+///
+/// Returns the urls of the device descriptions of all devices that answered, without duplicates.
+pub async fn search(search_target: &str, timeout: Duration) -> Result<Vec<String>> {
+    let socket = UdpSocket::bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)))
+        .await
+        .context("bind SSDP socket")?;
+
+    let request = search_request(search_target, timeout);
+    // UDP may drop packets, so ask twice like most control points do
+    for _ in 0..2 {
+        socket
+            .send_to(request.as_bytes(), SSDP_ADDR)
+            .await
+            .context("send SSDP search")?;
+    }
+
+    let deadline = Instant::now() + timeout;
+    let mut locations: Vec<String> = Vec::new();
+    let mut buf = [0; 2048];
+    // a timeout is the regular end of the search
+    while let Ok(received) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await {
+        let (len, from) = match received {
+            Ok(v) => v,
+            Err(err) => {
+                warn!("Error receiving SSDP response: {err}");
+                break;
+            }
+        };
+
+        let response = String::from_utf8_lossy(&buf[..len]);
+        let Some(location) = parse_location(&response) else {
+            trace!("Ignoring SSDP response from {from}");
+            continue;
+        };
+        if !locations.iter().any(|v| v == location) {
+            debug!("Found UPnP device at {location}");
+            locations.push(location.to_string());
+        }
+    }
+
+    Ok(locations)
+}
+
+/// Build the `M-SEARCH` request for `search_target`.
+fn search_request(search_target: &str, timeout: Duration) -> String {
+    // "MX" is the maximum seconds devices wait before answering, to spread out the answers
+    let mx = timeout.as_secs().clamp(1, 5);
+
+    format!(
+        "M-SEARCH * HTTP/1.1\r\nHOST: {SSDP_ADDR}\r\nMAN: \"ssdp:discover\"\r\nMX: {mx}\r\nST: {search_target}\r\n\r\n"
+    )
+}
+
+/// Get the `LOCATION` header of a successful search response.
+fn parse_location(response: &str) -> Option<&str> {
+    let mut lines = response.lines();
+    let status = lines.next()?;
+    if !status.starts_with("HTTP/1.1 200") {
+        return None;
+    }
+
+    lines.find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim()
+            .eq_ignore_ascii_case("location")
+            .then(|| value.trim())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::parse_location;
+
+    #[test]
+    fn should_parse_location() {
+        let response = "HTTP/1.1 200 OK\r\nCACHE-CONTROL: max-age=1800\r\nEXT:\r\nLocation: http://192.168.1.20:49152/description.xml\r\nST: urn:schemas-upnp-org:device:MediaRenderer:1\r\n\r\n";
+        assert_eq!(
+            parse_location(response),
+            Some("http://192.168.1.20:49152/description.xml")
+        );
+
+        // announcements of other control points searching
+        assert_eq!(
+            parse_location("M-SEARCH * HTTP/1.1\r\nLOCATION: http://a/\r\n\r\n"),
+            None
+        );
+        assert_eq!(parse_location("HTTP/1.1 200 OK\r\nST: a\r\n\r\n"), None);
+    }
+}
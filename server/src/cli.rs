@@ -24,6 +24,13 @@ pub struct Args {
     /// Select the backend, default is `rusty`
     #[arg(short, long, env = "TMS_BACKEND")]
     pub backend: Option<Backend>,
+    /// Store databases, caches and the playlist in `DATA_DIR` instead of the platform data directory,
+    /// for example for a portable setup.
+    #[arg(long, env = "TMS_DATA_DIR")]
+    pub data_dir: Option<PathBuf>,
+    /// Use the profile `PROFILE`, which has its own config, databases and playlist, "default" for the main one.
+    #[arg(long, env = "TMS_PROFILE")]
+    pub profile: Option<String>,
     #[clap(flatten)]
     pub log_options: LogOptions,
 }
@@ -36,6 +43,8 @@ pub enum Backend {
     #[value(alias = "gst", name = "gstreamer")]
     GStreamer,
     Rusty,
+    /// Play nothing and only advance the position, for development, testing and demos
+    Null,
 }
 
 impl From<Backend> for BackendSelect {
@@ -46,6 +55,7 @@ impl From<Backend> for BackendSelect {
             #[cfg(feature = "gst")]
             Backend::GStreamer => BackendSelect::GStreamer,
             Backend::Rusty => BackendSelect::Rusty,
+            Backend::Null => BackendSelect::Null,
         }
     }
 }
@@ -61,6 +71,7 @@ impl std::fmt::Display for Backend {
                 #[cfg(feature = "gst")]
                 Backend::GStreamer => "gstreamer",
                 Backend::Rusty => "rusty",
+                Backend::Null => "null",
             }
         )
     }
@@ -79,6 +90,69 @@ pub enum Action {
         #[arg(value_name = "FILE")]
         file: PathBuf,
     },
+    /// Create a timestamped backup of the databases.
+    Backup,
+    /// List all database backups, newest first.
+    ListBackups,
+    /// Restore the databases from a backup, see "list-backups" for available names.
+    Restore {
+        #[arg(value_name = "NAME")]
+        name: String,
+        /// Do not ask for confirmation before restoring.
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Export config, themes, databases and the playlist into a single archive, to move to another machine.
+    ExportState {
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+    /// Import a archive created by "export-state", replacing the current config, themes, databases and playlist.
+    ImportState {
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+        /// Do not ask for confirmation before importing.
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Manage smart playlists, which are shown in the database view under "Playlists".
+    #[command(subcommand)]
+    SmartPlaylist(SmartPlaylistAction),
+    /// Move all library tracks to the path built from their tags by "player.organize_pattern".
+    ///
+    /// Use this while no server is running, otherwise use "organize library" in the TUI.
+    Organize {
+        /// Only list what would be moved, without moving anything.
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+        /// Do not ask for confirmation before moving.
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+/// Subcommands for smart playlists
+#[derive(Subcommand, Debug)]
+pub enum SmartPlaylistAction {
+    /// Add a smart playlist, for example `add "Old Rock" "genre = Rock AND last_played > 30"`.
+    Add {
+        #[arg(value_name = "NAME")]
+        name: String,
+        /// Rules in the format "field operator value", joined by either " AND " or " OR ".
+        ///
+        /// Fields: title, artist, album, genre, directory, duration (in seconds),
+        /// added and last_played (in days ago).
+        /// Operators: "=", "!=", "~" (contains), ">" and "<".
+        #[arg(value_name = "QUERY")]
+        query: String,
+    },
+    /// List all smart playlists with their rules.
+    List,
+    /// Remove a smart playlist.
+    Remove {
+        #[arg(value_name = "NAME")]
+        name: String,
+    },
 }
 
 const DEFAULT_LOGFILE_FILENAME: &str = "termusic-server.log";
@@ -0,0 +1,626 @@
+//! A small HTTP/JSON api to control playback, served if `com.http_api` is set.
+//!
+//! For clients that cannot use gRPC, like home-automation systems and web dashboards:
+//!
+//! - `GET /`: a web page showing the queue, cover and transport controls, to use a phone as remote
+//! - `GET /now-playing`: the status, progress and current track
+//! - `GET /queue`: the tracks in the playlist
+//! - `GET /cover`: the cover of the current track
+//! - `GET /events`: a websocket sending updates, see [`websocket`]
+//! - `POST /play`, `/pause`, `/toggle-pause`, `/next` and `/previous`, answered with the now playing document
+//! - `POST /seek` with `{"position": 90}` in seconds, answered with the now playing document
+//! - `POST /queue` with `{"path": "/music/track.mp3"}` of a file in `player.music_dirs` or `{"url": "https://..."}`
+//!   to add a track at the end
+//!
+//! Requests have to send `com.auth_token` as `Authorization: Bearer <token>` if it is set, except for the page
+//! and the websocket, which gets the token as its first message, as browsers cannot set headers for websockets.
+//! The page itself is served to anyone and passes on the token from its fragment, like `/#token=<token>`.
+//!
+//! `POST` requests have to be sent as `Content-Type: application/json`, and requests with a `Origin` header are only
+//! answered for the page itself, so that other web sites cannot control the server through a browser.
+//! Served over TLS if `com.tls` is set.
+
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context as _, Result};
+use axum::extract::rejection::JsonRejection;
+use axum::extract::ws::WebSocketUpgrade;
+use axum::extract::ws::rejection::WebSocketUpgradeRejection;
+use axum::extract::{DefaultBodyLimit, Request, State};
+use axum::http::{HeaderName, HeaderValue, Method, StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::{MethodRouter, get, post};
+use axum::{Json, Router};
+use hyper::server::conn::http1;
+use hyper_util::rt::{TokioIo, TokioTimer};
+use hyper_util::service::TowerToHyperService;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use termusiclib::config::v2::server::ComTlsSettings;
+use termusiclib::player::auth::ServerAuth;
+use termusiclib::player::playlist_helpers::{PlaylistAddTrack, PlaylistTrackSource};
+use termusiclib::track::{Track, TrackData};
+use termusicplayback::{PlayerCmd, PlayerCmdSender, SharedPlaylist, StreamTX};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+use tokio::runtime::Handle;
+use tokio::select;
+use tokio_rustls::TlsAcceptor;
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::rustls::crypto::ring;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_util::sync::CancellationToken;
+
+use crate::PlayerStats;
+
+mod websocket;
+
+/// The web page of `GET /`.
+const WEB_UI: &str = include_str!("web_ui.html");
+
+/// Maximum length of a request body, bodies are only small json objects.
+const MAX_BODY_LEN: usize = 64 * 1024;
+/// Time a client has for the TLS handshake and for sending the request line and headers.
+const HEAD_TIMEOUT: Duration = Duration::from_secs(10);
+/// Time a request may take, including reading the body.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Everything needed to answer requests.
+#[derive(Clone)]
+pub struct ApiState {
+    pub(crate) cmd_tx: PlayerCmdSender,
+    pub(crate) player_stats: Arc<Mutex<PlayerStats>>,
+    pub(crate) playlist: SharedPlaylist,
+    pub(crate) auth: ServerAuth,
+    pub(crate) stream_tx: StreamTX,
+    /// The directories `POST /queue` may add files from, resolved by [`start_http_api`]
+    pub(crate) music_dirs: Vec<PathBuf>,
+}
+
+/// Status, progress and current track, see `GET /now-playing`.
+#[derive(Debug, Serialize)]
+struct NowPlaying {
+    /// `running`, `paused` or `stopped`
+    status: String,
+    /// Position in the current track in seconds
+    position: Option<f64>,
+    /// Duration of the current track in seconds
+    duration: Option<f64>,
+    volume: u16,
+    /// Playback speed in tenths, like `player.speed`
+    speed: i32,
+    /// Index of the current track in the queue
+    index: u64,
+    /// The title of the radio stream, if one plays
+    radio_title: Option<String>,
+    track: Option<TrackInfo>,
+}
+
+/// A track in the queue, see `GET /queue`.
+#[derive(Debug, Serialize, PartialEq)]
+struct TrackInfo {
+    title: Option<String>,
+    artist: Option<String>,
+    album: Option<String>,
+    /// The local file, or the url of radio stations and podcast episodes
+    location: String,
+    /// Duration in seconds, if known
+    duration: Option<f64>,
+}
+
+impl From<&Track> for TrackInfo {
+    fn from(track: &Track) -> Self {
+        let location = match track.path() {
+            Some(path) => path.to_string_lossy().to_string(),
+            None => track.url().unwrap_or_default().to_string(),
+        };
+
+        Self {
+            title: track.title().map(ToOwned::to_owned),
+            artist: track.artist().map(ToOwned::to_owned),
+            album: track
+                .as_track()
+                .and_then(TrackData::album)
+                .map(ToOwned::to_owned),
+            location,
+            duration: track.duration().map(|v| v.as_secs_f64()),
+        }
+    }
+}
+
+/// The tracks in the queue, see `GET /queue`.
+#[derive(Debug, Serialize)]
+struct Queue {
+    /// Index of the current track
+    index: usize,
+    tracks: Vec<TrackInfo>,
+}
+
+/// Body of `POST /seek`.
+#[derive(Debug, Deserialize, PartialEq)]
+struct SeekBody {
+    /// Position to seek to in seconds
+    position: f64,
+}
+
+/// Body of `POST /queue`.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum QueueAddBody {
+    Path(String),
+    Url(String),
+}
+
+/// Serve the api at `address` until `cancel_token` is cancelled, over TLS if `tls` is set.
+///
+/// # Errors
+///
+/// - if `address` cannot be bound
+/// - if the certificate or key of `tls` cannot be read
+pub async fn start_http_api(
+    handle: &Handle,
+    cancel_token: CancellationToken,
+    address: SocketAddr,
+    tls: Option<&ComTlsSettings>,
+    mut state: ApiState,
+) -> Result<()> {
+    let tls = tls.map(tls_acceptor).transpose()?;
+    let listener = TcpListener::bind(address)
+        .await
+        .with_context(|| format!("bind http api to {address}"))?;
+    let scheme = if tls.is_some() { "https" } else { "http" };
+    info!("Serving the http api at {scheme}://{address}");
+    if !address.ip().is_loopback() {
+        if state.auth.is_authorized(None) {
+            warn!(
+                "Serving the http api at {address} without \"com.auth_token\", anyone that can reach it can control the server"
+            );
+        }
+        if tls.is_none() {
+            warn!(
+                "Serving the http api at {address} without \"com.tls\", requests are sent unencrypted"
+            );
+        }
+    }
+
+    state.music_dirs = resolve_dirs(&state.music_dirs);
+    spawn_server(
+        handle,
+        cancel_token,
+        listener,
+        tls,
+        router(state),
+        "http api",
+    );
+
+    Ok(())
+}
+
+/// Build the acceptor for `com.tls`, which needs the key of the certificate for the server.
+fn tls_acceptor(tls: &ComTlsSettings) -> Result<TlsAcceptor> {
+    let key_path = tls
+        .key
+        .as_ref()
+        .context("\"com.tls.key\" is required for the server")?;
+    let certs = CertificateDer::pem_file_iter(&tls.cert)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .with_context(|| format!("Error reading tls cert {}", tls.cert.display()))?;
+    let key = PrivateKeyDer::from_pem_file(key_path)
+        .with_context(|| format!("Error reading tls key {}", key_path.display()))?;
+
+    let config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .context("tls cert and key do not match")?;
+
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// Serve `router` to every connection of `listener` until `cancel_token` is cancelled, over TLS if `tls` is set.
+///
+/// Connections not sending their request head in time are closed, `name` is used in logs.
+pub(crate) fn spawn_server(
+    handle: &Handle,
+    cancel_token: CancellationToken,
+    listener: TcpListener,
+    tls: Option<TlsAcceptor>,
+    router: Router,
+    name: &'static str,
+) {
+    handle.spawn(async move {
+        loop {
+            let (stream, addr) = select! {
+                _ = cancel_token.cancelled() => break,
+                res = listener.accept() => match res {
+                    Ok(v) => v,
+                    Err(err) => {
+                        warn!("Error accepting {name} connection: {err}");
+                        continue;
+                    }
+                },
+            };
+            let (router, tls) = (router.clone(), tls.clone());
+            tokio::spawn(async move {
+                let res = match tls {
+                    Some(tls) => match tokio::time::timeout(HEAD_TIMEOUT, tls.accept(stream)).await
+                    {
+                        Ok(Ok(stream)) => serve_connection(stream, router).await,
+                        Ok(Err(err)) => Err(anyhow::Error::from(err).context("TLS handshake")),
+                        Err(_) => Err(anyhow::anyhow!("TLS handshake timed out")),
+                    },
+                    None => serve_connection(stream, router).await,
+                };
+                if let Err(err) = res {
+                    debug!("Error answering {name} request of {addr}: {err:#}");
+                }
+            });
+        }
+    });
+}
+
+/// Answer the requests of a single connection until either side closes it.
+async fn serve_connection<S>(stream: S, router: Router) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let mut builder = http1::Builder::new();
+    builder
+        .timer(TokioTimer::new())
+        .header_read_timeout(HEAD_TIMEOUT);
+    builder
+        .serve_connection(TokioIo::new(stream), TowerToHyperService::new(router))
+        .with_upgrades()
+        .await?;
+
+    Ok(())
+}
+
+fn router(state: ApiState) -> Router {
+    Router::new()
+        .route("/", get(|| async { Html(WEB_UI) }))
+        .route("/now-playing", get(get_now_playing))
+        .route("/queue", get(get_queue).post(queue_add))
+        .route("/cover", get(cover))
+        .route("/events", get(events))
+        .route("/play", command_route(PlayerCmd::Play))
+        .route("/pause", command_route(PlayerCmd::Pause))
+        .route("/toggle-pause", command_route(PlayerCmd::TogglePause))
+        .route("/next", command_route(PlayerCmd::SkipNext))
+        .route("/previous", command_route(PlayerCmd::SkipPrevious))
+        .route("/seek", post(seek))
+        .fallback(|| async { error_response(StatusCode::NOT_FOUND, "not found") })
+        .method_not_allowed_fallback(|| async {
+            error_response(StatusCode::METHOD_NOT_ALLOWED, "method not allowed")
+        })
+        .layer(middleware::from_fn_with_state(state.clone(), guard))
+        .layer(middleware::from_fn(timeout))
+        .layer(DefaultBodyLimit::max(MAX_BODY_LEN))
+        .with_state(state)
+}
+
+/// Reject requests of other web sites, without the auth token or with a body that is not json.
+async fn guard(State(state): State<ApiState>, request: Request, next: Next) -> Response {
+    let headers = request.headers();
+    let header_str = |name: HeaderName| headers.get(name).and_then(|v| v.to_str().ok());
+
+    if let Some(origin) = headers.get(header::ORIGIN) {
+        let host = header_str(header::HOST).unwrap_or_default();
+        if !origin
+            .to_str()
+            .is_ok_and(|origin| same_origin(origin, host))
+        {
+            warn!("Rejected a http api request from another web site");
+            return error_response(
+                StatusCode::FORBIDDEN,
+                "requests from other origins are not allowed",
+            );
+        }
+    }
+
+    // the websocket gets the token as its first message
+    let public = request.method() == Method::GET && matches!(request.uri().path(), "/" | "/events");
+    let authorization = headers
+        .get(header::AUTHORIZATION)
+        .map(HeaderValue::as_bytes);
+    if !public && !state.auth.is_authorized(authorization) {
+        warn!("Rejected a http api request with a missing or wrong auth token");
+        return error_response(StatusCode::UNAUTHORIZED, "missing or wrong auth token");
+    }
+
+    // a simple cross-site form cannot send json, see "same_origin" for the other requests
+    if request.method() == Method::POST && !is_json(header_str(header::CONTENT_TYPE)) {
+        return error_response(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "expected \"Content-Type: application/json\"",
+        );
+    }
+
+    next.run(request).await
+}
+
+/// Answer requests that take too long, like ones sending their body slowly, with a error.
+async fn timeout(request: Request, next: Next) -> Response {
+    match tokio::time::timeout(REQUEST_TIMEOUT, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => error_response(StatusCode::REQUEST_TIMEOUT, "request took too long"),
+    }
+}
+
+/// Get whether the `Origin` header `origin` is the page of this api, reached at the `Host` header `host`.
+fn same_origin(origin: &str, host: &str) -> bool {
+    origin
+        .strip_prefix("http://")
+        .or_else(|| origin.strip_prefix("https://"))
+        .is_some_and(|origin| !host.is_empty() && origin.eq_ignore_ascii_case(host))
+}
+
+/// Get whether the `Content-Type` header `content_type` is json, with or without parameters like the charset.
+fn is_json(content_type: Option<&str>) -> bool {
+    content_type
+        .and_then(|v| v.split(';').next())
+        .is_some_and(|v| v.trim().eq_ignore_ascii_case("application/json"))
+}
+
+/// Expand `~` and resolve links in the configured `music_dirs`, leaving out those that do not exist.
+fn resolve_dirs(music_dirs: &[PathBuf]) -> Vec<PathBuf> {
+    music_dirs
+        .iter()
+        .filter_map(|dir| shellexpand::path::tilde(dir).canonicalize().ok())
+        .collect()
+}
+
+/// Get whether the resolved `path` is in one of the resolved `music_dirs`.
+fn in_music_dirs(path: &Path, music_dirs: &[PathBuf]) -> bool {
+    music_dirs.iter().any(|dir| path.starts_with(dir))
+}
+
+/// Route `POST` to send `cmd` to the player, answered with the now playing document.
+fn command_route(cmd: PlayerCmd) -> MethodRouter<ApiState> {
+    post(move |State(state): State<ApiState>| async move { player_command(&state, cmd).await })
+}
+
+/// Send `cmd` to the player, answered with the now playing document.
+async fn player_command(state: &ApiState, cmd: PlayerCmd) -> Response {
+    if !command(state, cmd).await {
+        return error_response(StatusCode::SERVICE_UNAVAILABLE, "player is not running");
+    }
+
+    ok_response(&now_playing(state))
+}
+
+async fn get_now_playing(State(state): State<ApiState>) -> Response {
+    ok_response(&now_playing(&state))
+}
+
+async fn get_queue(State(state): State<ApiState>) -> Response {
+    ok_response(&queue(&state))
+}
+
+async fn seek(
+    State(state): State<ApiState>,
+    body: Result<Json<SeekBody>, JsonRejection>,
+) -> Response {
+    let position = body
+        .ok()
+        .and_then(|Json(v)| Duration::try_from_secs_f64(v.position).ok());
+    let Some(position) = position else {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "expected {\"position\": <seconds>}",
+        );
+    };
+
+    player_command(&state, PlayerCmd::SeekTo(position)).await
+}
+
+async fn events(
+    State(state): State<ApiState>,
+    upgrade: Result<WebSocketUpgrade, WebSocketUpgradeRejection>,
+) -> Response {
+    let Ok(upgrade) = upgrade else {
+        return error_response(StatusCode::BAD_REQUEST, "expected a websocket upgrade");
+    };
+
+    upgrade
+        .max_message_size(MAX_BODY_LEN)
+        .on_upgrade(move |socket| async move {
+            if let Err(err) = websocket::serve(socket, &state).await {
+                debug!("Error sending http api events: {err:#}");
+            }
+        })
+}
+
+/// Send `cmd` to the player and wait until it was processed.
+///
+/// Returns `false` if the player is not running anymore.
+async fn command(state: &ApiState, cmd: PlayerCmd) -> bool {
+    match state.cmd_tx.send_cb(cmd) {
+        Ok(rx) => rx.await.is_ok(),
+        Err(err) => {
+            error!("error sending command from the http api: {err}");
+            false
+        }
+    }
+}
+
+fn now_playing(state: &ApiState) -> NowPlaying {
+    let playlist = state.playlist.read();
+    let track = playlist.current_track().map(TrackInfo::from);
+    let status = playlist.status().to_string().to_lowercase();
+    drop(playlist);
+
+    let stats = state.player_stats.lock();
+    NowPlaying {
+        status,
+        position: stats.progress.position.map(|v| v.as_secs_f64()),
+        duration: stats.progress.total_duration.map(|v| v.as_secs_f64()),
+        volume: stats.volume,
+        speed: stats.speed,
+        index: stats.current_track_index,
+        radio_title: (!stats.radio_title.is_empty()).then(|| stats.radio_title.clone()),
+        track,
+    }
+}
+
+fn queue(state: &ApiState) -> Queue {
+    let playlist = state.playlist.read();
+
+    Queue {
+        index: playlist.get_current_track_index(),
+        tracks: playlist.tracks().iter().map(TrackInfo::from).collect(),
+    }
+}
+
+/// Get the cover of the current track.
+async fn cover(State(state): State<ApiState>) -> Response {
+    let Some(track) = state.playlist.read().current_track().cloned() else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    // reads the file or the directory of the track
+    match tokio::task::spawn_blocking(move || track.get_picture()).await {
+        Ok(Ok(Some(picture))) => {
+            let content_type = picture
+                .mime_type()
+                .map_or("application/octet-stream", |v| v.as_str())
+                .to_string();
+            (
+                [(header::CONTENT_TYPE, content_type)],
+                picture.data().to_vec(),
+            )
+                .into_response()
+        }
+        Ok(Ok(None)) => StatusCode::NOT_FOUND.into_response(),
+        Ok(Err(err)) => {
+            debug!("Error reading the cover for the http api: {err:#}");
+            StatusCode::NOT_FOUND.into_response()
+        }
+        Err(err) => {
+            error!("Error reading the cover for the http api: {err}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Add the track in `body` to the end of the queue, answered with the new queue.
+async fn queue_add(
+    State(state): State<ApiState>,
+    body: Result<Json<QueueAddBody>, JsonRejection>,
+) -> Response {
+    let source = match body {
+        Ok(Json(QueueAddBody::Path(path))) => {
+            // resolves ".." and links, which could otherwise lead out of the music directories
+            let allowed = tokio::fs::canonicalize(&path)
+                .await
+                .is_ok_and(|path| in_music_dirs(&path, &state.music_dirs));
+            if !allowed {
+                return error_response(
+                    StatusCode::FORBIDDEN,
+                    "only files in \"player.music_dirs\" can be added",
+                );
+            }
+            PlaylistTrackSource::Path(path)
+        }
+        Ok(Json(QueueAddBody::Url(url))) => PlaylistTrackSource::Url(url),
+        Err(_) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "expected {\"path\": <file>} or {\"url\": <url>}",
+            );
+        }
+    };
+    let at_index = u64::try_from(state.playlist.read().len()).unwrap_or(u64::MAX);
+
+    let cmd = PlayerCmd::PlaylistAddTrack(PlaylistAddTrack::new_single(at_index, source));
+    if !command(&state, cmd).await {
+        return error_response(StatusCode::SERVICE_UNAVAILABLE, "player is not running");
+    }
+
+    ok_response(&queue(&state))
+}
+
+fn ok_response<T: Serialize>(value: &T) -> Response {
+    match serde_json::to_value(value) {
+        Ok(body) => Json(body).into_response(),
+        Err(err) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &err.to_string()),
+    }
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response {
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use pretty_assertions::assert_eq;
+
+    use super::{QueueAddBody, SeekBody, in_music_dirs, is_json, same_origin};
+
+    #[test]
+    fn should_only_allow_same_origin() {
+        assert!(same_origin("http://192.168.1.2:9186", "192.168.1.2:9186"));
+        assert!(same_origin("https://Music.local:9186", "music.local:9186"));
+        assert!(!same_origin("http://evil.example", "192.168.1.2:9186"));
+        assert!(!same_origin("http://192.168.1.2:9187", "192.168.1.2:9186"));
+        assert!(!same_origin("null", "192.168.1.2:9186"));
+        assert!(!same_origin("http://", ""));
+    }
+
+    #[test]
+    fn should_require_json() {
+        assert!(is_json(Some("application/json")));
+        assert!(is_json(Some("application/json; charset=utf-8")));
+        assert!(!is_json(Some("text/plain")));
+        assert!(!is_json(Some("application/x-www-form-urlencoded")));
+        assert!(!is_json(None));
+    }
+
+    #[test]
+    fn should_only_add_files_in_music_dirs() {
+        let music_dirs = [
+            PathBuf::from("/home/user/Music"),
+            PathBuf::from("/mnt/music"),
+        ];
+
+        assert!(in_music_dirs(
+            Path::new("/home/user/Music/a.mp3"),
+            &music_dirs
+        ));
+        assert!(in_music_dirs(
+            Path::new("/mnt/music/album/b.flac"),
+            &music_dirs
+        ));
+        assert!(!in_music_dirs(
+            Path::new("/home/user/Musical/c.mp3"),
+            &music_dirs
+        ));
+        assert!(!in_music_dirs(Path::new("/etc/passwd"), &music_dirs));
+        assert!(!in_music_dirs(Path::new("/etc/passwd"), &[]));
+    }
+
+    #[test]
+    fn should_parse_request_bodies() {
+        assert_eq!(
+            serde_json::from_str::<SeekBody>(r#"{"position": 90.5}"#).unwrap(),
+            SeekBody { position: 90.5 }
+        );
+        assert_eq!(
+            serde_json::from_str::<QueueAddBody>(r#"{"path": "/music/track.mp3"}"#).unwrap(),
+            QueueAddBody::Path("/music/track.mp3".to_string())
+        );
+        assert_eq!(
+            serde_json::from_str::<QueueAddBody>(r#"{"url": "https://example.com/stream"}"#)
+                .unwrap(),
+            QueueAddBody::Url("https://example.com/stream".to_string())
+        );
+        assert!(serde_json::from_str::<QueueAddBody>(r#"{"id": 1}"#).is_err());
+    }
+}
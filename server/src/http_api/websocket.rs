@@ -0,0 +1,148 @@
+//! A websocket bridge of [`UpdateEvents`] for the web ui, see `GET /events`.
+//!
+//! The client has to send `com.auth_token` as the first text message, or anything if it is not set.
+//! Every relevant event is then sent as a text message like `{"event": "track", "now_playing": {..}}`,
+//! with the now playing document from after the event. Commands are sent through the other endpoints.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use axum::extract::ws::{CloseFrame, Message, Utf8Bytes, WebSocket, close_code};
+use serde::Serialize;
+use termusiclib::player::UpdateEvents;
+use termusiclib::player::auth::ServerAuth;
+use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
+
+use super::{ApiState, NowPlaying, now_playing};
+
+/// Time the client has to send the token.
+const AUTH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A message sent to the page.
+#[derive(Debug, Serialize)]
+struct Update<'a> {
+    /// `connected`, `missed`, `status`, `track`, `progress`, `queue`, `volume` or `speed`
+    event: &'a str,
+    now_playing: NowPlaying,
+}
+
+/// Wait for the token of the client and send events until either side closes the connection.
+pub(super) async fn serve(mut socket: WebSocket, state: &ApiState) -> Result<()> {
+    let first = tokio::time::timeout(AUTH_TIMEOUT, socket.recv())
+        .await
+        .ok()
+        .flatten()
+        .and_then(Result::ok);
+    if !is_authorized(&state.auth, first.as_ref()) {
+        warn!("Rejected a http api websocket with a missing or wrong auth token");
+        let frame = CloseFrame {
+            code: close_code::POLICY,
+            reason: Utf8Bytes::from_static("missing or wrong auth token"),
+        };
+        socket.send(Message::Close(Some(frame))).await?;
+        return Ok(());
+    }
+
+    // subscribe before sending the first update, to not miss events in between
+    let mut events = state.stream_tx.subscribe();
+    send_update(&mut socket, "connected", state).await?;
+
+    loop {
+        select! {
+            event = events.recv() => {
+                let event = match event {
+                    Ok(event) => match event_name(&event) {
+                        Some(name) => name,
+                        None => continue,
+                    },
+                    // the page has to refresh everything
+                    Err(RecvError::Lagged(_)) => "missed",
+                    Err(RecvError::Closed) => break,
+                };
+                send_update(&mut socket, event, state).await?;
+            }
+            // pings are answered by the websocket itself
+            msg = socket.recv() => match msg {
+                Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                Some(Ok(_)) => (),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Get whether the `first` message of the client is the token.
+fn is_authorized(auth: &ServerAuth, first: Option<&Message>) -> bool {
+    let Some(Message::Text(token)) = first else {
+        return false;
+    };
+
+    auth.is_authorized(Some(format!("Bearer {}", token.as_str()).as_bytes()))
+}
+
+/// Get the name the page knows `event` by, `None` if it does not change what the page shows.
+fn event_name(event: &UpdateEvents) -> Option<&'static str> {
+    let name = match event {
+        UpdateEvents::MissedEvents { .. } => "missed",
+        UpdateEvents::PlayStateChanged { .. } => "status",
+        UpdateEvents::TrackChanged(_) => "track",
+        UpdateEvents::Progress(_) => "progress",
+        UpdateEvents::PlaylistChanged(_) => "queue",
+        UpdateEvents::VolumeChanged { .. } => "volume",
+        UpdateEvents::SpeedChanged { .. } => "speed",
+        UpdateEvents::GaplessChanged { .. }
+        | UpdateEvents::PodcastsChanged
+        | UpdateEvents::LibraryChanged
+        | UpdateEvents::TrackFavoriteChanged { .. }
+        | UpdateEvents::WaveformChanged(_)
+        | UpdateEvents::PodcastProgress(_) => return None,
+    };
+
+    Some(name)
+}
+
+async fn send_update(socket: &mut WebSocket, event: &str, state: &ApiState) -> Result<()> {
+    let update = Update {
+        event,
+        now_playing: now_playing(state),
+    };
+    let payload = serde_json::to_string(&update)?;
+    socket.send(Message::Text(payload.into())).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::extract::ws::Message;
+    use termusiclib::player::auth::ServerAuth;
+
+    use super::is_authorized;
+
+    #[test]
+    fn should_require_token_as_first_message() {
+        let auth = ServerAuth::new(Some("secret")).with_guest(Some("guest"));
+
+        assert!(is_authorized(&auth, Some(&Message::Text("secret".into()))));
+        assert!(is_authorized(&auth, Some(&Message::Text("guest".into()))));
+        assert!(!is_authorized(&auth, Some(&Message::Text("wrong".into()))));
+        assert!(!is_authorized(
+            &auth,
+            Some(&Message::Binary("secret".into()))
+        ));
+        assert!(!is_authorized(&auth, None));
+    }
+
+    #[test]
+    fn should_allow_anything_without_token() {
+        let auth = ServerAuth::new(None);
+
+        assert!(is_authorized(
+            &auth,
+            Some(&Message::Text(String::new().into()))
+        ));
+        assert!(!is_authorized(&auth, None));
+    }
+}
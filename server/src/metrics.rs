@@ -0,0 +1,215 @@
+//! Counters and gauges of the server, served in the Prometheus text format if `com.metrics` is set.
+
+use std::fmt::Write as _;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::{Context as _, Result};
+use axum::Router;
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use termusiclib::backup::DATABASE_FILES;
+use termusiclib::utils::get_app_data_path;
+use tokio::net::TcpListener;
+use tokio::runtime::Handle;
+use tokio_util::sync::CancellationToken;
+
+use crate::http_api::spawn_server;
+
+/// The metrics of this server, counted since it was started.
+pub static METRICS: Metrics = Metrics::new();
+
+#[derive(Debug)]
+pub struct Metrics {
+    /// Tracks played until their end
+    tracks_played: AtomicU64,
+    /// Errors the backend reported while playing or enqueuing a track
+    stream_errors: AtomicU64,
+    /// Podcast episodes downloaded
+    downloads_finished: AtomicU64,
+    /// Podcast episode downloads that failed
+    downloads_failed: AtomicU64,
+    /// Clients currently subscribed to the update stream
+    update_stream_clients: AtomicU64,
+}
+
+impl Metrics {
+    const fn new() -> Self {
+        Self {
+            tracks_played: AtomicU64::new(0),
+            stream_errors: AtomicU64::new(0),
+            downloads_finished: AtomicU64::new(0),
+            downloads_failed: AtomicU64::new(0),
+            update_stream_clients: AtomicU64::new(0),
+        }
+    }
+
+    pub fn track_played(&self) {
+        self.tracks_played.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn stream_error(&self) {
+        self.stream_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn download_finished(&self) {
+        self.downloads_finished.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn download_failed(&self) {
+        self.downloads_failed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a client subscribed to the update stream until the returned guard is dropped.
+    pub fn update_stream_client(&'static self) -> UpdateStreamClient {
+        self.update_stream_clients.fetch_add(1, Ordering::Relaxed);
+
+        UpdateStreamClient(self)
+    }
+
+    /// Render all metrics in the Prometheus text format, with `databases` as `(file name, size in bytes)`.
+    fn render(&self, databases: &[(&str, u64)]) -> String {
+        let mut out = String::new();
+        let counters = [
+            (
+                "termusic_tracks_played_total",
+                "Tracks played until their end.",
+                &self.tracks_played,
+            ),
+            (
+                "termusic_stream_errors_total",
+                "Errors while playing or enqueuing a track.",
+                &self.stream_errors,
+            ),
+            (
+                "termusic_podcast_downloads_total",
+                "Podcast episodes downloaded.",
+                &self.downloads_finished,
+            ),
+            (
+                "termusic_podcast_download_errors_total",
+                "Podcast episode downloads that failed.",
+                &self.downloads_failed,
+            ),
+        ];
+        for (name, help, value) in counters {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} counter");
+            let _ = writeln!(out, "{name} {}", value.load(Ordering::Relaxed));
+        }
+
+        let name = "termusic_update_stream_clients";
+        let _ = writeln!(
+            out,
+            "# HELP {name} Clients subscribed to the update stream."
+        );
+        let _ = writeln!(out, "# TYPE {name} gauge");
+        let _ = writeln!(
+            out,
+            "{name} {}",
+            self.update_stream_clients.load(Ordering::Relaxed)
+        );
+
+        let name = "termusic_database_size_bytes";
+        let _ = writeln!(out, "# HELP {name} Size of the database files.");
+        let _ = writeln!(out, "# TYPE {name} gauge");
+        for (database, size) in databases {
+            let _ = writeln!(out, "{name}{{database=\"{database}\"}} {size}");
+        }
+
+        out
+    }
+}
+
+/// Counts as a subscribed client, see [`Metrics::update_stream_client`].
+#[derive(Debug)]
+pub struct UpdateStreamClient(&'static Metrics);
+
+impl Drop for UpdateStreamClient {
+    fn drop(&mut self) {
+        self.0.update_stream_clients.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Get the size of the existing database files, read on every scrape as they grow outside of the server's control.
+fn database_sizes(data_dir: &Path) -> Vec<(&'static str, u64)> {
+    DATABASE_FILES
+        .iter()
+        .filter_map(|file| {
+            let size = std::fs::metadata(data_dir.join(file)).ok()?.len();
+            Some((*file, size))
+        })
+        .collect()
+}
+
+/// Serve [`METRICS`] at `address` until `cancel_token` is cancelled.
+///
+/// # Errors
+///
+/// - if `address` cannot be bound
+pub async fn start_metrics_server(
+    handle: &Handle,
+    cancel_token: CancellationToken,
+    address: SocketAddr,
+) -> Result<()> {
+    let listener = TcpListener::bind(address)
+        .await
+        .with_context(|| format!("bind metrics server to {address}"))?;
+    let data_dir = Arc::new(get_app_data_path()?);
+    info!("Serving metrics at http://{address}/metrics");
+
+    // served like the http api, but always without TLS, as scrapers expect plain HTTP
+    let router = Router::new()
+        .route("/metrics", get(scrape))
+        .with_state(data_dir);
+    spawn_server(handle, cancel_token, listener, None, router, "metrics");
+
+    Ok(())
+}
+
+async fn scrape(State(data_dir): State<Arc<PathBuf>>) -> impl IntoResponse {
+    let body = METRICS.render(&database_sizes(&data_dir));
+
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::Metrics;
+
+    #[test]
+    fn should_render_text_format() {
+        static METRICS: Metrics = Metrics::new();
+        METRICS.track_played();
+        METRICS.track_played();
+        METRICS.download_failed();
+        let client = METRICS.update_stream_client();
+        let _other = METRICS.update_stream_client();
+        drop(client);
+
+        let rendered = METRICS.render(&[("library2.db", 4096)]);
+        let lines: Vec<&str> = rendered
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .collect();
+
+        assert_eq!(
+            lines,
+            [
+                "termusic_tracks_played_total 2",
+                "termusic_stream_errors_total 0",
+                "termusic_podcast_downloads_total 0",
+                "termusic_podcast_download_errors_total 1",
+                "termusic_update_stream_clients 1",
+                "termusic_database_size_bytes{database=\"library2.db\"} 4096",
+            ]
+        );
+        assert!(rendered.contains("# TYPE termusic_tracks_played_total counter\n"));
+    }
+}
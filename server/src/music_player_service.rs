@@ -1,22 +1,48 @@
 use anyhow::Result;
 use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::num::NonZeroU8;
+use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::sync::Arc;
 use termusiclib::config::SharedServerSettings;
+use termusiclib::config::v2::server::CrossfadeSettings;
+use termusiclib::ids::{EpisodeId, PodcastId};
+use termusiclib::new_database::{Database, bookmark_ops, track_ops};
+use termusiclib::organize;
+use termusiclib::player::auth::is_guest;
 use termusiclib::player::music_player_server::MusicPlayer;
-use termusiclib::player::playlist_helpers::{PlaylistPlaySpecific, PlaylistRemoveTrackType};
+use termusiclib::player::playlist_helpers::{
+    PlaylistPlaySpecific, PlaylistRemoveTrackType, PlaylistTrackSource,
+};
 use termusiclib::player::{
-    self, Empty, GaplessState, GetProgressResponse, PlayState, PlayerTime, PlaylistLoopMode,
-    PlaylistSwapTracks, PlaylistTracks, PlaylistTracksToAdd, PlaylistTracksToRemove, SpeedReply,
-    StreamUpdates, UpdateMissedEvents, VolumeReply, stream_updates,
+    self, AudioInfo, AutoQueueState, Bookmark, BookmarkAdd, BookmarkId, Bookmarks, Capabilities,
+    CastRenderer, CastRendererInfo, CastRenderers, ChapterJump, CrossfadeState, Duration, Empty,
+    GaplessState, GetProgressResponse, OrganizeRequest, OrganizeResult, OrganizeSkipped,
+    OutputDevice, OutputDevices, PlayQueue, PlayQueueName, PlayQueues, PlayState, PlayerTime,
+    PlaylistLoopMode, PlaylistSwapTracks, PlaylistTracks, PlaylistTracksToAdd,
+    PlaylistTracksToRemove, PodcastAdd, PodcastEpisodeFile, PodcastEpisodeId, PodcastEpisodeIds,
+    PodcastEpisodePlayed, PodcastEpisodes, PodcastFeedId, PodcastFeedPlayed, PodcastFeedSettings,
+    PodcastFeeds, PodcastMerge, PodcastMergeResult, PodcastRefresh, SpeedReply, StreamUpdates,
+    TrackFavorite, TrackPreview, TrackSpeed, TrackWaveform, UpdateEvents, UpdateMissedEvents,
+    VolumeReply, Waveform, cast_renderer, cast_renderers, output_device, output_devices,
+    podcast_refresh, stream_updates,
+};
+use termusiclib::podcast::PodcastError;
+use termusiclib::podcast::manager::PodcastManager;
+use termusiclib::track::MediaTypesSimple;
+use termusicplayback::{
+    PlayerCmd, PlayerCmdCallback, PlayerCmdSender, SharedPlaylist, StreamTX, waveform,
 };
-use termusicplayback::{PlayerCmd, PlayerCmdCallback, PlayerCmdSender, SharedPlaylist, StreamTX};
 use tokio_stream::wrappers::BroadcastStream;
 use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
 use tokio_stream::{Stream, StreamExt};
 use tonic::{Request, Response, Status};
 
 use crate::PlayerStats;
+use crate::cast::{self, SharedRenderers};
+use crate::metrics::METRICS;
+use crate::podcasts::{SharedPodcastManager, delete_file, notify_changed};
 
 #[derive(Debug)]
 pub struct MusicPlayerService {
@@ -24,7 +50,15 @@ pub struct MusicPlayerService {
     stream_tx: StreamTX,
     config: SharedServerSettings,
     playlist: SharedPlaylist,
+    podcasts: SharedPodcastManager,
+    db: Database,
     pub(crate) player_stats: Arc<Mutex<PlayerStats>>,
+    /// The renderers found by the last [`cast::discover`]
+    cast_renderers: SharedRenderers,
+    /// The extensions of the files the backend can play
+    supported_extensions: &'static [&'static str],
+    /// The tracks a waveform is currently generated for, to not decode a track twice at the same time
+    waveforms_generating: Arc<Mutex<Vec<PlaylistTrackSource>>>,
 }
 
 impl MusicPlayerService {
@@ -33,6 +67,10 @@ impl MusicPlayerService {
         stream_tx: StreamTX,
         config: SharedServerSettings,
         playlist: SharedPlaylist,
+        podcasts: SharedPodcastManager,
+        db: Database,
+        cast_renderers: SharedRenderers,
+        supported_extensions: &'static [&'static str],
     ) -> Self {
         let mut player_stats = PlayerStats::new();
         let config_read = config.read();
@@ -48,7 +86,12 @@ impl MusicPlayerService {
             player_stats,
             stream_tx,
             playlist,
+            podcasts,
+            db,
             config,
+            cast_renderers,
+            supported_extensions,
+            waveforms_generating: Arc::default(),
         }
     }
 }
@@ -68,6 +111,114 @@ impl MusicPlayerService {
 
         Ok(rx)
     }
+
+    /// Run `f` with the locked podcasts on a blocking thread, as it may read and write the podcast database.
+    ///
+    /// The podcasts are reloaded first if the database was changed outside of the server, like by the command line
+    /// interface importing feeds or restoring a backup.
+    async fn with_podcasts<T, F>(&self, f: F) -> Result<T, Status>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut PodcastManager) -> Result<T, Status> + Send + 'static,
+    {
+        let podcasts = self.podcasts.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut manager = podcasts.lock();
+            manager.reload_if_changed().map_err(podcast_status)?;
+            f(&mut manager)
+        })
+        .await
+        .map_err(|err| Status::internal(format!("podcast task failed: {err}")))?
+    }
+
+    /// Get the source of the current track to store bookmarks for, or a "failed precondition" status.
+    fn bookmark_track(&self) -> Result<PlaylistTrackSource, Status> {
+        let playlist = self.playlist.read();
+        let Some(track) = playlist.current_track() else {
+            return Err(Status::failed_precondition("No track is playing"));
+        };
+        // a position in a live stream can not be found again
+        if track.media_type() == MediaTypesSimple::LiveRadio {
+            return Err(Status::failed_precondition(
+                "Live radio can not be bookmarked",
+            ));
+        }
+
+        Ok(track.as_track_source())
+    }
+}
+
+/// Convert a stored bookmark to its grpc message.
+fn bookmark_to_grpc(bookmark: bookmark_ops::Bookmark) -> Bookmark {
+    Bookmark {
+        id: bookmark.id,
+        position: Some(bookmark.position.into()),
+        name: bookmark.name,
+    }
+}
+
+/// Get the podcast index of `id`, or a "not found" status.
+fn podcast_index(manager: &PodcastManager, id: i64) -> Result<usize, Status> {
+    manager
+        .podcast_index(PodcastId(id))
+        .ok_or_else(|| Status::not_found(format!("No podcast with id {id}")))
+}
+
+/// Get the podcast and episode index of `id`, or a "not found" status.
+fn episode_index(manager: &PodcastManager, id: i64) -> Result<(usize, usize), Status> {
+    manager
+        .episode_index(EpisodeId(id))
+        .ok_or_else(|| Status::not_found(format!("No episode with id {id}")))
+}
+
+/// Group the episodes `ids` by the index of their podcast, or a "not found" status for a unknown episode.
+fn episodes_by_podcast(
+    manager: &PodcastManager,
+    ids: &[i64],
+) -> Result<BTreeMap<usize, Vec<EpisodeId>>, Status> {
+    let mut grouped: BTreeMap<usize, Vec<EpisodeId>> = BTreeMap::new();
+    for id in ids {
+        let (pod_index, _) = episode_index(manager, *id)?;
+        grouped.entry(pod_index).or_default().push(EpisodeId(*id));
+    }
+
+    Ok(grouped)
+}
+
+/// Convert a podcast error to a status, "unavailable" if the podcast database is at fault.
+fn podcast_status(err: PodcastError) -> Status {
+    let database = matches!(err, PodcastError::Open(_) | PodcastError::Database(_));
+    let msg = format!("{:#}", anyhow::Error::from(err));
+    if database {
+        Status::unavailable(msg)
+    } else {
+        Status::internal(msg)
+    }
+}
+
+/// Reject `request` with a "permission denied" status if it was made with `com.guest_token`.
+fn deny_guest<T>(request: &Request<T>) -> Result<(), Status> {
+    if is_guest(request) {
+        return Err(Status::permission_denied("Not allowed for guests"));
+    }
+
+    Ok(())
+}
+
+/// Convert a request to remove tracks from the playlist into the command for the player.
+///
+/// Removing anything is not allowed for guests, not even single tracks.
+fn remove_command(request: Request<PlaylistTracksToRemove>) -> Result<PlayerCmd, Status> {
+    deny_guest(&request)?;
+    let converted: PlaylistRemoveTrackType = request
+        .into_inner()
+        .try_into()
+        .map_err(|err: anyhow::Error| Status::from_error(err.into()))?;
+
+    Ok(match converted {
+        PlaylistRemoveTrackType::Indexed(v) => PlayerCmd::PlaylistRemoveTrack(v),
+        PlaylistRemoveTrackType::Clear => PlayerCmd::PlaylistClear,
+    })
 }
 
 #[tonic::async_trait]
@@ -122,6 +273,304 @@ impl MusicPlayer for MusicPlayerService {
         Ok(Response::new(reply))
     }
 
+    async fn rescan_library(&self, _request: Request<Empty>) -> Result<Response<Empty>, Status> {
+        let reply = Empty {};
+        self.command(PlayerCmd::RescanLibrary);
+
+        Ok(Response::new(reply))
+    }
+
+    async fn set_track_favorite(
+        &self,
+        request: Request<TrackFavorite>,
+    ) -> Result<Response<Empty>, Status> {
+        let request = request.into_inner();
+        let path = PathBuf::from(request.path);
+        track_ops::set_track_favorite(&self.db.get_connection(), &path, request.favorite)
+            .map_err(|err| Status::from_error(err.into()))?;
+
+        // there is only one error case: no receivers
+        let _ = self.stream_tx.send(UpdateEvents::TrackFavoriteChanged {
+            path,
+            favorite: request.favorite,
+        });
+        let reply = Empty {};
+
+        Ok(Response::new(reply))
+    }
+
+    async fn preview_track(
+        &self,
+        request: Request<TrackPreview>,
+    ) -> Result<Response<Empty>, Status> {
+        let path = PathBuf::from(request.into_inner().path);
+        let rx = self.command_cb(PlayerCmd::PreviewTrack(path))?;
+        // wait until the event was processed
+        let _ = rx.await;
+        let reply = Empty {};
+
+        Ok(Response::new(reply))
+    }
+
+    async fn organize_library(
+        &self,
+        request: Request<OrganizeRequest>,
+    ) -> Result<Response<OrganizeResult>, Status> {
+        let dry_run = request.get_ref().dry_run;
+        if !dry_run {
+            deny_guest(&request)?;
+        }
+        let (music_dirs, pattern) = {
+            let config = self.config.read();
+            (
+                config.settings.player.music_dirs.clone(),
+                config.settings.player.organize_pattern.clone(),
+            )
+        };
+        let db = self.db.clone();
+
+        // reading the tags of the whole library and moving the files takes a while
+        let (plan, failed) = tokio::task::spawn_blocking(move || -> Result<_> {
+            let plan = organize::plan_organize(&db, &music_dirs, &pattern)?;
+            let failed = if dry_run {
+                Vec::new()
+            } else {
+                organize::apply_organize(&db, &music_dirs, &plan.moves)
+            };
+
+            Ok((plan, failed))
+        })
+        .await
+        .map_err(|err| Status::internal(err.to_string()))?
+        .map_err(|err| Status::from_error(err.into()))?;
+
+        let moves: Vec<_> = plan
+            .moves
+            .into_iter()
+            .filter(|mv| !failed.iter().any(|(failed, _)| failed == mv))
+            .collect();
+        if !dry_run && !moves.is_empty() {
+            let rx = self.command_cb(PlayerCmd::PlaylistRenamePaths(moves.clone()))?;
+            // wait until the event was processed
+            let _ = rx.await;
+        }
+
+        let skipped = |(path, reason): (PathBuf, String)| OrganizeSkipped {
+            path: path.to_string_lossy().to_string(),
+            reason,
+        };
+        let reply = OrganizeResult {
+            moves: moves
+                .into_iter()
+                .map(|mv| player::OrganizeMove {
+                    from: mv.from.to_string_lossy().to_string(),
+                    to: mv.to.to_string_lossy().to_string(),
+                })
+                .collect(),
+            skipped: plan.skipped.into_iter().map(skipped).collect(),
+            failed: failed
+                .into_iter()
+                .map(|(mv, reason)| skipped((mv.from, reason)))
+                .collect(),
+        };
+
+        Ok(Response::new(reply))
+    }
+
+    async fn get_output_devices(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<OutputDevices>, Status> {
+        let rx = self.command_cb(PlayerCmd::RefreshOutputDevices)?;
+        // wait until the event was processed
+        let _ = rx.await;
+        let devices = self.player_stats.lock().output_devices.clone();
+        let current = self
+            .config
+            .read()
+            .settings
+            .backends
+            .rusty
+            .output_device
+            .clone();
+
+        let reply = OutputDevices {
+            devices,
+            optional_current: current.map(output_devices::OptionalCurrent::Current),
+        };
+
+        Ok(Response::new(reply))
+    }
+
+    async fn get_capabilities(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<Capabilities>, Status> {
+        let reply = Capabilities {
+            extensions: self
+                .supported_extensions
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            guest: is_guest(&request),
+        };
+
+        Ok(Response::new(reply))
+    }
+
+    async fn get_audio_info(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<AudioInfo>, Status> {
+        let rx = self.command_cb(PlayerCmd::RefreshOutputInfo)?;
+        // wait until the event was processed
+        let _ = rx.await;
+        let reply = self.player_stats.lock().audio_info.clone();
+
+        Ok(Response::new(reply))
+    }
+
+    async fn get_waveform(&self, _request: Request<Empty>) -> Result<Response<Waveform>, Status> {
+        let Some(track) = self.playlist.read().current_track().cloned() else {
+            return Ok(Response::new(Waveform::default()));
+        };
+        let trackid = track.as_track_source();
+
+        if let Some(levels) = waveform::get_cached(&track) {
+            return Ok(Response::new(TrackWaveform { trackid, levels }.into()));
+        }
+
+        // decoding the whole track takes a while, so the client gets the waveform as an update once done
+        let mut generating = self.waveforms_generating.lock();
+        if !generating.contains(&trackid) {
+            generating.push(trackid.clone());
+            let generating = self.waveforms_generating.clone();
+            let stream_tx = self.stream_tx.clone();
+            let trackid = trackid.clone();
+            tokio::task::spawn_blocking(move || {
+                match waveform::generate(&track) {
+                    Ok(Some(levels)) => {
+                        let _ = stream_tx.send(UpdateEvents::WaveformChanged(TrackWaveform {
+                            trackid: trackid.clone(),
+                            levels,
+                        }));
+                    }
+                    Ok(None) => (),
+                    Err(err) => warn!("Generating waveform of {trackid:?} failed: {err:#}"),
+                }
+                generating.lock().retain(|v| *v != trackid);
+            });
+        }
+
+        Ok(Response::new(
+            TrackWaveform {
+                trackid,
+                levels: Vec::new(),
+            }
+            .into(),
+        ))
+    }
+
+    async fn get_cast_renderers(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<CastRenderers>, Status> {
+        let renderers = cast::discover()
+            .await
+            .map_err(|err| Status::internal(format!("Error searching for renderers: {err:#}")))?;
+        let current = self.player_stats.lock().cast_renderer.clone();
+
+        let reply = CastRenderers {
+            renderers: renderers
+                .iter()
+                .map(|v| CastRendererInfo {
+                    id: v.id.clone(),
+                    name: v.name.clone(),
+                })
+                .collect(),
+            optional_current: current.map(cast_renderers::OptionalCurrent::Current),
+        };
+        *self.cast_renderers.lock() = renderers;
+
+        Ok(Response::new(reply))
+    }
+
+    async fn set_cast_renderer(
+        &self,
+        request: Request<CastRenderer>,
+    ) -> Result<Response<Empty>, Status> {
+        let id = request
+            .into_inner()
+            .optional_id
+            .map(|cast_renderer::OptionalId::Id(id)| id);
+        if let Some(id) = &id {
+            if !self.cast_renderers.lock().iter().any(|v| &v.id == id) {
+                return Err(Status::not_found(format!(
+                    "Unknown cast renderer {id:#?}, search for renderers first"
+                )));
+            }
+        }
+        let rx = self.command_cb(PlayerCmd::SetCastRenderer(id))?;
+        // wait until the event was processed
+        let _ = rx.await;
+        let reply = Empty {};
+
+        Ok(Response::new(reply))
+    }
+
+    async fn set_output_device(
+        &self,
+        request: Request<OutputDevice>,
+    ) -> Result<Response<Empty>, Status> {
+        let name = request
+            .into_inner()
+            .optional_name
+            .map(|output_device::OptionalName::Name(name)| name);
+        let rx = self.command_cb(PlayerCmd::SetOutputDevice(name.clone()))?;
+        // wait until the event was processed
+        let _ = rx.await;
+
+        // the device is only stored in the config if switching to it worked
+        if self.config.read().settings.backends.rusty.output_device != name {
+            return Err(Status::invalid_argument(format!(
+                "Could not switch to output device {name:#?}"
+            )));
+        }
+        let reply = Empty {};
+
+        Ok(Response::new(reply))
+    }
+
+    async fn set_crossfade(
+        &self,
+        request: Request<CrossfadeState>,
+    ) -> Result<Response<CrossfadeState>, Status> {
+        let request = request.into_inner();
+        let secs = u8::try_from(request.secs)
+            .ok()
+            .and_then(NonZeroU8::new)
+            .ok_or_else(|| {
+                Status::invalid_argument(format!(
+                    "Crossfade secs need to be between 1 and 255, got {}",
+                    request.secs
+                ))
+            })?;
+        let rx = self.command_cb(PlayerCmd::SetCrossfade(CrossfadeSettings {
+            enabled: request.enabled,
+            secs,
+        }))?;
+        // wait until the event was processed
+        let _ = rx.await;
+
+        let crossfade = self.config.read().settings.player.crossfade;
+        let reply = CrossfadeState {
+            enabled: crossfade.enabled,
+            secs: u32::from(crossfade.secs.get()),
+        };
+
+        Ok(Response::new(reply))
+    }
+
     async fn seek_backward(
         &self,
         _request: Request<Empty>,
@@ -146,6 +595,153 @@ impl MusicPlayer for MusicPlayerService {
         Ok(Response::new(reply))
     }
 
+    async fn seek_to(&self, request: Request<Duration>) -> Result<Response<PlayerTime>, Status> {
+        let position = request.into_inner().into();
+        let rx = self.command_cb(PlayerCmd::SeekTo(position))?;
+        // wait until the event was processed
+        let _ = rx.await;
+        let s = self.player_stats.lock();
+
+        let reply = s.as_playertime();
+
+        Ok(Response::new(reply))
+    }
+
+    async fn set_ab_repeat_a(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<PlayerTime>, Status> {
+        let rx = self.command_cb(PlayerCmd::SetAbRepeatA)?;
+        // wait until the event was processed
+        let _ = rx.await;
+        let reply = self.player_stats.lock().as_playertime();
+
+        Ok(Response::new(reply))
+    }
+
+    async fn set_ab_repeat_b(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<PlayerTime>, Status> {
+        let rx = self.command_cb(PlayerCmd::SetAbRepeatB)?;
+        // wait until the event was processed
+        let _ = rx.await;
+        let reply = self.player_stats.lock().as_playertime();
+
+        Ok(Response::new(reply))
+    }
+
+    async fn clear_ab_repeat(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<PlayerTime>, Status> {
+        let rx = self.command_cb(PlayerCmd::ClearAbRepeat)?;
+        // wait until the event was processed
+        let _ = rx.await;
+        let reply = self.player_stats.lock().as_playertime();
+
+        Ok(Response::new(reply))
+    }
+
+    async fn add_bookmark(
+        &self,
+        request: Request<BookmarkAdd>,
+    ) -> Result<Response<Bookmark>, Status> {
+        let name = request.into_inner().name;
+        let track = self.bookmark_track()?;
+        let position = self
+            .player_stats
+            .lock()
+            .progress
+            .position
+            .unwrap_or_default();
+        let id =
+            bookmark_ops::insert_bookmark(&self.db.get_connection(), &track, position, name.trim())
+                .map_err(|err| Status::from_error(err.into()))?;
+
+        let reply = bookmark_to_grpc(bookmark_ops::Bookmark {
+            id,
+            position,
+            name: name.trim().to_string(),
+        });
+
+        Ok(Response::new(reply))
+    }
+
+    async fn get_bookmarks(&self, _request: Request<Empty>) -> Result<Response<Bookmarks>, Status> {
+        let track = self.bookmark_track()?;
+        let bookmarks = bookmark_ops::get_bookmarks(&self.db.get_connection(), &track)
+            .map_err(|err| Status::from_error(err.into()))?;
+
+        let reply = Bookmarks {
+            bookmarks: bookmarks.into_iter().map(bookmark_to_grpc).collect(),
+        };
+
+        Ok(Response::new(reply))
+    }
+
+    async fn jump_to_bookmark(
+        &self,
+        request: Request<BookmarkId>,
+    ) -> Result<Response<PlayerTime>, Status> {
+        let id = request.into_inner().id;
+        let track = self.bookmark_track()?;
+        let found = bookmark_ops::get_bookmark(&self.db.get_connection(), id)
+            .map_err(|err| Status::from_error(err.into()))?;
+        let Some((source, bookmark)) = found else {
+            return Err(Status::not_found(format!("No bookmark with id {id}")));
+        };
+        if source != track {
+            return Err(Status::failed_precondition(format!(
+                "Bookmark {id} is not of the current track"
+            )));
+        }
+
+        let rx = self.command_cb(PlayerCmd::SeekTo(bookmark.position))?;
+        // wait until the event was processed
+        let _ = rx.await;
+        let reply = self.player_stats.lock().as_playertime();
+
+        Ok(Response::new(reply))
+    }
+
+    async fn remove_bookmark(
+        &self,
+        request: Request<BookmarkId>,
+    ) -> Result<Response<Empty>, Status> {
+        deny_guest(&request)?;
+        let id = request.into_inner().id;
+        bookmark_ops::delete_bookmark(&self.db.get_connection(), id)
+            .map_err(|err| Status::not_found(format!("{err:#}")))?;
+        let reply = Empty {};
+
+        Ok(Response::new(reply))
+    }
+
+    async fn next_chapter(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<ChapterJump>, Status> {
+        let rx = self.command_cb(PlayerCmd::JumpChapter(true))?;
+        // wait until the event was processed
+        let _ = rx.await;
+        let reply = self.player_stats.lock().chapter_jump.clone();
+
+        Ok(Response::new(reply))
+    }
+
+    async fn previous_chapter(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<ChapterJump>, Status> {
+        let rx = self.command_cb(PlayerCmd::JumpChapter(false))?;
+        // wait until the event was processed
+        let _ = rx.await;
+        let reply = self.player_stats.lock().chapter_jump.clone();
+
+        Ok(Response::new(reply))
+    }
+
     async fn skip_next(&self, _request: Request<Empty>) -> Result<Response<Empty>, Status> {
         let reply = Empty {};
         self.command(PlayerCmd::SkipNext);
@@ -169,6 +765,18 @@ impl MusicPlayer for MusicPlayerService {
         Ok(Response::new(reply))
     }
 
+    async fn toggle_track_speed(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<TrackSpeed>, Status> {
+        let rx = self.command_cb(PlayerCmd::ToggleTrackSpeed)?;
+        // wait until the event was processed
+        let _ = rx.await;
+        let reply = self.player_stats.lock().track_speed;
+
+        Ok(Response::new(reply))
+    }
+
     async fn speed_up(&self, _request: Request<Empty>) -> Result<Response<SpeedReply>, Status> {
         let rx = self.command_cb(PlayerCmd::SpeedUp)?;
         // wait until the event was processed
@@ -234,10 +842,15 @@ impl MusicPlayer for MusicPlayerService {
         _: Request<Empty>,
     ) -> Result<Response<Self::SubscribeServerUpdatesStream>, Status> {
         let rx = self.stream_tx.subscribe();
+        // counted until the client disconnects and the stream is dropped
+        let client = METRICS.update_stream_client();
 
         // map to the grpc types
-        let receiver_stream = BroadcastStream::new(rx).map(|res| match res {
-            Ok(ev) => Ok(ev.into()),
+        let receiver_stream = BroadcastStream::new(rx).map(move |res| match res {
+            Ok(ev) => {
+                let _ = &client;
+                Ok(ev.into())
+            }
             Err(err) => {
                 let BroadcastStreamRecvError::Lagged(amount) = err;
                 Ok(StreamUpdates {
@@ -273,15 +886,7 @@ impl MusicPlayer for MusicPlayerService {
         &self,
         request: Request<PlaylistTracksToRemove>,
     ) -> Result<Response<Empty>, Status> {
-        let converted: PlaylistRemoveTrackType = request
-            .into_inner()
-            .try_into()
-            .map_err(|err: anyhow::Error| Status::from_error(err.into()))?;
-
-        let ev = match converted {
-            PlaylistRemoveTrackType::Indexed(v) => PlayerCmd::PlaylistRemoveTrack(v),
-            PlaylistRemoveTrackType::Clear => PlayerCmd::PlaylistClear,
-        };
+        let ev = remove_command(request)?;
 
         let rx = self.command_cb(ev)?;
         // wait until the event was processed
@@ -335,4 +940,435 @@ impl MusicPlayer for MusicPlayerService {
 
         Ok(Response::new(reply))
     }
+
+    async fn save_queue(&self, request: Request<Empty>) -> Result<Response<Empty>, Status> {
+        // replaces the stored queue
+        deny_guest(&request)?;
+        self.playlist
+            .write()
+            .save()
+            .map_err(|err: anyhow::Error| Status::from_error(err.into()))?;
+        let reply = Empty {};
+
+        Ok(Response::new(reply))
+    }
+
+    async fn restore_queue(&self, request: Request<Empty>) -> Result<Response<Empty>, Status> {
+        // replaces the playlist
+        deny_guest(&request)?;
+        let rx = self.command_cb(PlayerCmd::PlaylistRestore)?;
+        // wait until the event was processed
+        let _ = rx.await;
+        let reply = Empty {};
+
+        Ok(Response::new(reply))
+    }
+
+    async fn toggle_auto_queue(
+        &self,
+        _: Request<Empty>,
+    ) -> Result<Response<AutoQueueState>, Status> {
+        let rx = self.command_cb(PlayerCmd::PlaylistToggleAutoQueue)?;
+        // wait until the event was processed
+        let _ = rx.await;
+        let reply = AutoQueueState {
+            enabled: self.config.read().settings.player.auto_queue.enabled,
+        };
+
+        Ok(Response::new(reply))
+    }
+
+    async fn get_queues(&self, _: Request<Empty>) -> Result<Response<PlayQueues>, Status> {
+        let playlist = self.playlist.read();
+        let queues = playlist
+            .queues()
+            .map_err(|err| Status::from_error(err.into()))?;
+        let reply = PlayQueues {
+            queues: queues
+                .into_iter()
+                .map(|v| PlayQueue {
+                    name: v.name,
+                    tracks: v.tracks as u64,
+                })
+                .collect(),
+            active: playlist.queue().to_string(),
+        };
+
+        Ok(Response::new(reply))
+    }
+
+    async fn create_queue(
+        &self,
+        request: Request<PlayQueueName>,
+    ) -> Result<Response<Empty>, Status> {
+        let name = request.into_inner().name;
+        self.playlist
+            .read()
+            .create_queue(name.trim())
+            .map_err(|err| Status::invalid_argument(format!("{err:#}")))?;
+        let reply = Empty {};
+
+        Ok(Response::new(reply))
+    }
+
+    async fn switch_queue(
+        &self,
+        request: Request<PlayQueueName>,
+    ) -> Result<Response<Empty>, Status> {
+        let name = request.into_inner().name;
+        let exists = self
+            .playlist
+            .read()
+            .queues()
+            .map_err(|err| Status::from_error(err.into()))?
+            .iter()
+            .any(|v| v.name == name);
+        if !exists {
+            return Err(Status::not_found(format!("No queue called \"{name}\"")));
+        }
+
+        let rx = self.command_cb(PlayerCmd::PlaylistSwitchQueue(name))?;
+        // wait until the event was processed
+        let _ = rx.await;
+        let reply = Empty {};
+
+        Ok(Response::new(reply))
+    }
+
+    async fn delete_queue(
+        &self,
+        request: Request<PlayQueueName>,
+    ) -> Result<Response<Empty>, Status> {
+        deny_guest(&request)?;
+        let name = request.into_inner().name;
+        self.playlist
+            .read()
+            .delete_queue(&name)
+            .map_err(|err| Status::failed_precondition(format!("{err:#}")))?;
+        let reply = Empty {};
+
+        Ok(Response::new(reply))
+    }
+
+    async fn get_podcasts(&self, _: Request<Empty>) -> Result<Response<PodcastFeeds>, Status> {
+        let reply = self
+            .with_podcasts(|manager| {
+                Ok(PodcastFeeds {
+                    feeds: manager.podcasts().iter().map(Into::into).collect(),
+                })
+            })
+            .await?;
+
+        Ok(Response::new(reply))
+    }
+
+    async fn get_episodes(
+        &self,
+        request: Request<PodcastFeedId>,
+    ) -> Result<Response<PodcastEpisodes>, Status> {
+        let id = request.into_inner().id;
+        let reply = self
+            .with_podcasts(move |manager| {
+                let index = podcast_index(manager, id)?;
+                Ok(PodcastEpisodes {
+                    episodes: manager.podcasts()[index]
+                        .episodes
+                        .iter()
+                        .map(Into::into)
+                        .collect(),
+                })
+            })
+            .await?;
+
+        Ok(Response::new(reply))
+    }
+
+    async fn add_podcast(&self, request: Request<PodcastAdd>) -> Result<Response<Empty>, Status> {
+        self.podcasts.lock().add_feed(request.into_inner().url);
+        let reply = Empty {};
+
+        Ok(Response::new(reply))
+    }
+
+    async fn remove_podcast(
+        &self,
+        request: Request<PodcastFeedId>,
+    ) -> Result<Response<Empty>, Status> {
+        deny_guest(&request)?;
+        let id = request.into_inner().id;
+        self.with_podcasts(move |manager| {
+            let index = podcast_index(manager, id)?;
+            manager
+                .remove(index)
+                .map_err(|err| Status::from_error(err.into()))?;
+            Ok(())
+        })
+        .await?;
+        notify_changed(&self.stream_tx);
+        let reply = Empty {};
+
+        Ok(Response::new(reply))
+    }
+
+    async fn refresh_podcasts(
+        &self,
+        request: Request<PodcastRefresh>,
+    ) -> Result<Response<Empty>, Status> {
+        let optional_id = request.into_inner().optional_id;
+        self.with_podcasts(move |manager| {
+            let index = match optional_id {
+                Some(podcast_refresh::OptionalId::Id(id)) => Some(podcast_index(manager, id)?),
+                None => None,
+            };
+            manager
+                .refresh(index)
+                .map_err(|err| Status::from_error(err.into()))
+        })
+        .await?;
+        let reply = Empty {};
+
+        Ok(Response::new(reply))
+    }
+
+    async fn mark_episode_played(
+        &self,
+        request: Request<PodcastEpisodePlayed>,
+    ) -> Result<Response<Empty>, Status> {
+        let request = request.into_inner();
+        self.with_podcasts(move |manager| {
+            let (pod_index, ep_index) = episode_index(manager, request.id)?;
+            let played = manager.podcasts()[pod_index].episodes[ep_index].played;
+            if played != request.played {
+                manager
+                    .toggle_played(pod_index, ep_index)
+                    .map_err(|err| Status::from_error(err.into()))?;
+            }
+            Ok(())
+        })
+        .await?;
+        notify_changed(&self.stream_tx);
+        let reply = Empty {};
+
+        Ok(Response::new(reply))
+    }
+
+    async fn download_episode(
+        &self,
+        request: Request<PodcastEpisodeId>,
+    ) -> Result<Response<Empty>, Status> {
+        let id = request.into_inner().id;
+        self.with_podcasts(move |manager| {
+            let (pod_index, ep_index) = episode_index(manager, id)?;
+            manager
+                .download(pod_index, Some(ep_index), |_| false)
+                .map_err(|err| Status::from_error(err.into()))
+        })
+        .await?;
+        let reply = Empty {};
+
+        Ok(Response::new(reply))
+    }
+
+    async fn mark_feed_played(
+        &self,
+        request: Request<PodcastFeedPlayed>,
+    ) -> Result<Response<Empty>, Status> {
+        let request = request.into_inner();
+        self.with_podcasts(move |manager| {
+            let index = podcast_index(manager, request.id)?;
+            manager
+                .set_all_played(index, request.played)
+                .map_err(|err| Status::from_error(err.into()))
+        })
+        .await?;
+        notify_changed(&self.stream_tx);
+        let reply = Empty {};
+
+        Ok(Response::new(reply))
+    }
+
+    async fn download_episodes(
+        &self,
+        request: Request<PodcastEpisodeIds>,
+    ) -> Result<Response<Empty>, Status> {
+        let ids = request.into_inner().ids;
+        self.with_podcasts(move |manager| {
+            for (pod_index, ids) in episodes_by_podcast(manager, &ids)? {
+                manager
+                    .download_ids(pod_index, &ids, |_| false)
+                    .map_err(|err| Status::from_error(err.into()))?;
+            }
+            Ok(())
+        })
+        .await?;
+        let reply = Empty {};
+
+        Ok(Response::new(reply))
+    }
+
+    async fn remove_episode_files(
+        &self,
+        request: Request<PodcastEpisodeIds>,
+    ) -> Result<Response<Empty>, Status> {
+        deny_guest(&request)?;
+        let ids = request.into_inner().ids;
+        self.with_podcasts(move |manager| {
+            for (pod_index, ids) in episodes_by_podcast(manager, &ids)? {
+                manager
+                    .remove_files(pod_index, &ids)
+                    .map_err(|err| Status::from_error(err.into()))?;
+            }
+            Ok(())
+        })
+        .await?;
+        notify_changed(&self.stream_tx);
+        let reply = Empty {};
+
+        Ok(Response::new(reply))
+    }
+
+    async fn restore_episode_file(
+        &self,
+        request: Request<PodcastEpisodeFile>,
+    ) -> Result<Response<Empty>, Status> {
+        deny_guest(&request)?;
+        let request = request.into_inner();
+        self.with_podcasts(move |manager| {
+            let _ = episode_index(manager, request.id)?;
+            manager
+                .insert_file(EpisodeId(request.id), Path::new(&request.path))
+                .map_err(|err| Status::from_error(err.into()))
+        })
+        .await?;
+        notify_changed(&self.stream_tx);
+        let reply = Empty {};
+
+        Ok(Response::new(reply))
+    }
+
+    async fn remove_all_podcasts(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<Empty>, Status> {
+        deny_guest(&request)?;
+        self.with_podcasts(|manager| {
+            manager
+                .remove_all()
+                .map_err(|err| Status::from_error(err.into()))
+        })
+        .await?;
+        notify_changed(&self.stream_tx);
+        let reply = Empty {};
+
+        Ok(Response::new(reply))
+    }
+
+    async fn merge_podcasts(
+        &self,
+        request: Request<PodcastMerge>,
+    ) -> Result<Response<PodcastMergeResult>, Status> {
+        deny_guest(&request)?;
+        let request = request.into_inner();
+        let result = self
+            .with_podcasts(move |manager| {
+                let _ = podcast_index(manager, request.source)?;
+                let _ = podcast_index(manager, request.target)?;
+                manager
+                    .merge(PodcastId(request.source), PodcastId(request.target))
+                    .map_err(|err| Status::from_error(err.into()))
+            })
+            .await?;
+        notify_changed(&self.stream_tx);
+        let reply = PodcastMergeResult {
+            episodes: u64::try_from(result.episodes).unwrap_or(u64::MAX),
+            files: u64::try_from(result.files).unwrap_or(u64::MAX),
+        };
+
+        Ok(Response::new(reply))
+    }
+
+    async fn set_feed_settings(
+        &self,
+        request: Request<PodcastFeedSettings>,
+    ) -> Result<Response<Empty>, Status> {
+        deny_guest(&request)?;
+        let request = request.into_inner();
+        self.with_podcasts(move |manager| {
+            let _ = podcast_index(manager, request.id)?;
+            let id = PodcastId(request.id);
+            manager
+                .set_feed_settings(id, &request.settings())
+                .map_err(|err| Status::from_error(err.into()))?;
+            manager
+                .apply_feed_settings(id, |_| false, delete_file)
+                .map_err(|err| Status::from_error(err.into()))
+        })
+        .await?;
+        notify_changed(&self.stream_tx);
+        let reply = Empty {};
+
+        Ok(Response::new(reply))
+    }
+
+    async fn sync_podcasts(&self, _: Request<Empty>) -> Result<Response<Empty>, Status> {
+        self.with_podcasts(|manager| {
+            manager
+                .gpodder_sync()
+                .map_err(|err| Status::failed_precondition(format!("{err:#}")))
+        })
+        .await?;
+        let reply = Empty {};
+
+        Ok(Response::new(reply))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use termusiclib::player::PlaylistTracksToRemove;
+    use termusiclib::player::auth::Guest;
+    use termusiclib::player::playlist_helpers::{
+        PlaylistRemoveTrackIndexed, PlaylistRemoveTrackType, PlaylistTrackSource,
+    };
+    use termusicplayback::PlayerCmd;
+    use tonic::{Code, Request};
+
+    use super::remove_command;
+
+    fn request(remove: PlaylistRemoveTrackType, guest: bool) -> Request<PlaylistTracksToRemove> {
+        let mut request = Request::new(remove.into());
+        if guest {
+            request.extensions_mut().insert(Guest);
+        }
+
+        request
+    }
+
+    fn indexed() -> PlaylistRemoveTrackType {
+        PlaylistRemoveTrackType::Indexed(PlaylistRemoveTrackIndexed::new_single(
+            0,
+            PlaylistTrackSource::Path("/music/a.mp3".to_string()),
+        ))
+    }
+
+    #[test]
+    fn should_deny_guests_removing() {
+        for remove in [indexed(), PlaylistRemoveTrackType::Clear] {
+            let res = remove_command(request(remove, true));
+            assert_eq!(
+                res.map(|_| ()).map_err(|v| v.code()),
+                Err(Code::PermissionDenied)
+            );
+        }
+    }
+
+    #[test]
+    fn should_allow_removing() {
+        let res = remove_command(request(indexed(), false)).unwrap();
+        assert!(matches!(res, PlayerCmd::PlaylistRemoveTrack(_)));
+
+        let res = remove_command(request(PlaylistRemoveTrackType::Clear, false)).unwrap();
+        assert!(matches!(res, PlayerCmd::PlaylistClear));
+    }
 }
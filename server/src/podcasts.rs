@@ -0,0 +1,223 @@
+//! The server-owned [`PodcastManager`], shared between the grpc service and the task applying its background results.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use parking_lot::Mutex;
+use termusiclib::config::SharedServerSettings;
+use termusiclib::new_database::DatabaseError;
+use termusiclib::player::{PodcastDownloadInfo, PodcastProgress, UpdateEvents};
+use termusiclib::podcast::gpodder::GpodderSyncResult;
+use termusiclib::podcast::manager::{PodcastEvent, PodcastManager};
+use termusiclib::podcast::{EpData, PodcastDLResult, PodcastSyncResult};
+use termusicplayback::StreamTX;
+use tokio::runtime::Handle;
+use tokio::select;
+use tokio::sync::mpsc::unbounded_channel;
+use tokio_util::sync::CancellationToken;
+
+use crate::metrics::METRICS;
+
+pub type SharedPodcastManager = Arc<Mutex<PodcastManager>>;
+
+/// Create the [`PodcastManager`] and spawn a task to apply its background results.
+///
+/// This is the only place the podcast database is written from, clients change it via the podcast rpcs.
+/// Clients are notified via [`UpdateEvents::PodcastsChanged`] whenever the podcasts changed
+/// and via [`UpdateEvents::PodcastProgress`] about the progress of the background work.
+pub fn start_podcast_manager(
+    handle: &Handle,
+    cancel_token: CancellationToken,
+    config: SharedServerSettings,
+    stream_tx: StreamTX,
+) -> Result<SharedPodcastManager> {
+    let (event_tx, mut event_rx) = unbounded_channel();
+    let manager = PodcastManager::new(
+        config,
+        Arc::new(move |event| {
+            let _ = event_tx.send(event);
+        }),
+    )
+    .map_err(|err| {
+        // migrating would lose data, so only a newer termusic or a older backup can open it
+        if let Some(DatabaseError::NewerVersion { .. }) = err.downcast_ref() {
+            err.context("podcast database is from a newer termusic, update termusic or restore an older backup with \"termusic restore\"")
+        } else {
+            err.context("load podcasts")
+        }
+    })?;
+    let manager = Arc::new(Mutex::new(manager));
+
+    let manager_c = manager.clone();
+    handle.spawn(async move {
+        loop {
+            select! {
+                event = event_rx.recv() => {
+                    let Some(event) = event else {
+                        break;
+                    };
+                    // applying writes to the database, so keep it off the async workers
+                    let manager = manager_c.clone();
+                    let applied =
+                        tokio::task::spawn_blocking(move || handle_event(&mut manager.lock(), event))
+                            .await;
+                    let Ok((changed, progress)) = applied else {
+                        break;
+                    };
+                    if changed {
+                        notify_changed(&stream_tx);
+                    }
+                    // there is only one error case: no receivers
+                    let _ = stream_tx.send(UpdateEvents::PodcastProgress(progress));
+                },
+                _ = cancel_token.cancelled() => {
+                    break;
+                }
+            }
+        }
+    });
+
+    Ok(manager)
+}
+
+/// Notify all clients that the podcasts changed.
+pub fn notify_changed(stream_tx: &StreamTX) {
+    // there is only one error case: no receivers
+    let _ = stream_tx.send(UpdateEvents::PodcastsChanged);
+}
+
+/// Delete `path` for [`PodcastManager::apply_feed_settings`].
+pub fn delete_file(path: &Path) -> Result<()> {
+    std::fs::remove_file(path).map_err(Into::into)
+}
+
+/// Apply a background result to `manager`.
+///
+/// Returns whether the podcasts changed and the progress to report to clients.
+fn handle_event(manager: &mut PodcastManager, event: PodcastEvent) -> (bool, PodcastProgress) {
+    match event {
+        PodcastEvent::Sync(PodcastSyncResult::FetchPodcastStart(url)) => {
+            (false, PodcastProgress::FetchStart(url))
+        }
+        PodcastEvent::Sync(PodcastSyncResult::SyncData((id, pod))) => {
+            if let Err(err) = manager.sync_data(&pod, Some(id)) {
+                warn!("Updating podcast \"{}\" failed: {err:#}", pod.url);
+                return (
+                    false,
+                    PodcastProgress::FetchFailed {
+                        url: pod.url,
+                        title: Some(pod.title),
+                    },
+                );
+            }
+            if let Err(err) = manager.apply_feed_settings(id, |_| false, delete_file) {
+                warn!(
+                    "Applying the settings of podcast \"{}\" failed: {err:#}",
+                    pod.url
+                );
+            }
+            let progress = PodcastProgress::FetchDone {
+                url: pod.url,
+                added: false,
+                merged_into: None,
+            };
+            (true, progress)
+        }
+        PodcastEvent::Sync(PodcastSyncResult::NewData(pod)) => {
+            // there is no one to ask here, so always merge instead of subscribing twice
+            let existing = manager
+                .find_duplicate(&pod)
+                .map(|existing| (existing.id, existing.title.clone()));
+            let res = if let Some((id, title)) = &existing {
+                info!(
+                    "Feed \"{}\" is already subscribed as \"{title}\", merging",
+                    pod.url
+                );
+                manager.merge_duplicate(&pod, *id)
+            } else {
+                manager.sync_data(&pod, None)
+            };
+            if let Err(err) = res {
+                warn!("Adding podcast \"{}\" failed: {err:#}", pod.url);
+                return (
+                    false,
+                    PodcastProgress::FetchFailed {
+                        url: pod.url,
+                        title: None,
+                    },
+                );
+            }
+            let progress = PodcastProgress::FetchDone {
+                url: pod.url,
+                added: existing.is_none(),
+                merged_into: existing.map(|(_, title)| title),
+            };
+            (true, progress)
+        }
+        PodcastEvent::Sync(PodcastSyncResult::Error(feed)) => {
+            warn!("Fetching podcast feed \"{}\" failed", feed.url);
+            (
+                false,
+                PodcastProgress::FetchFailed {
+                    url: feed.url,
+                    title: feed.title,
+                },
+            )
+        }
+        PodcastEvent::Download(PodcastDLResult::DLStart(ep_data)) => {
+            (false, PodcastProgress::DownloadStart((&ep_data).into()))
+        }
+        PodcastEvent::Download(PodcastDLResult::DLComplete(ep_data)) => {
+            METRICS.download_finished();
+            let info = PodcastDownloadInfo::from(&ep_data);
+            if let Err(err) = manager.download_complete(ep_data) {
+                warn!("{err:#}");
+                return (false, PodcastProgress::DownloadFileWriteError(info));
+            }
+            (true, PodcastProgress::DownloadDone(info))
+        }
+        PodcastEvent::Download(PodcastDLResult::DLResponseError(ep_data)) => (
+            false,
+            download_failed(&ep_data, PodcastProgress::DownloadResponseError),
+        ),
+        PodcastEvent::Download(PodcastDLResult::DLFileCreateError(ep_data)) => (
+            false,
+            download_failed(&ep_data, PodcastProgress::DownloadFileCreateError),
+        ),
+        PodcastEvent::Download(PodcastDLResult::DLFileWriteError(ep_data)) => (
+            false,
+            download_failed(&ep_data, PodcastProgress::DownloadFileWriteError),
+        ),
+        PodcastEvent::Gpodder(GpodderSyncResult::Start) => (false, PodcastProgress::SyncStart),
+        PodcastEvent::Gpodder(GpodderSyncResult::Complete(data)) => {
+            match manager.apply_gpodder_sync(&data) {
+                Ok(changed) => {
+                    let progress = PodcastProgress::SyncDone {
+                        pushed: u64::try_from(data.pushed).unwrap_or(u64::MAX),
+                        changed: u64::try_from(changed).unwrap_or(u64::MAX),
+                    };
+                    (changed > 0, progress)
+                }
+                Err(err) => {
+                    warn!("Applying podcast sync failed: {err:#}");
+                    (false, PodcastProgress::SyncFailed(format!("{err:#}")))
+                }
+            }
+        }
+        PodcastEvent::Gpodder(GpodderSyncResult::Error(err)) => {
+            warn!("Podcast sync failed: {err}");
+            (false, PodcastProgress::SyncFailed(err))
+        }
+    }
+}
+
+/// Count the failed download of `ep_data` and create its `progress`.
+fn download_failed(
+    ep_data: &EpData,
+    progress: fn(PodcastDownloadInfo) -> PodcastProgress,
+) -> PodcastProgress {
+    METRICS.download_failed();
+    warn!("Downloading podcast episode \"{}\" failed", ep_data.title);
+    progress(ep_data.into())
+}
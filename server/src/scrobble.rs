@@ -0,0 +1,200 @@
+//! Submitting the played tracks to the services configured in `scrobble`, driven by the player's [`UpdateEvents`].
+//!
+//! Tracks marked or unmarked as favorite are submitted as "love" too, see `scrobble.lastfm.sync_loved`.
+
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context as _, Result};
+use termusiclib::config::SharedServerSettings;
+use termusiclib::player::{TrackChangedInfo, UpdateEvents};
+use termusiclib::scrobbler::{ScrobbleTrack, Scrobbler, should_scrobble};
+use termusiclib::track::Track;
+use termusicplayback::{SharedPlaylist, StreamTX};
+use tokio::runtime::Handle;
+use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc::{UnboundedSender, unbounded_channel};
+use tokio_util::sync::CancellationToken;
+
+/// Position changes between two progress events larger than this are considered seeks and not counted as played.
+const MAX_PROGRESS_STEP: Duration = Duration::from_secs(5);
+
+/// A submission for the [`Scrobbler`] task.
+enum Submission {
+    NowPlaying(ScrobbleTrack),
+    Listen(ScrobbleTrack, u64),
+    /// The track was marked as favorite if `true`, otherwise unmarked
+    Love(ScrobbleTrack, bool),
+}
+
+/// The track currently being listened to.
+struct Listen {
+    /// See [`Track::id_str`](termusiclib::track::Track::id_str)
+    id: String,
+    track: ScrobbleTrack,
+    /// Unix timestamp of when the track started
+    started_at: u64,
+    /// How long the track has actually been played, excluding seeks
+    played: Duration,
+    last_position: Duration,
+    submitted: bool,
+}
+
+impl Listen {
+    /// Count the time played since the last progress, and return whether the listen should now be submitted.
+    fn progress(&mut self, position: Duration) -> bool {
+        if position > self.last_position && position - self.last_position <= MAX_PROGRESS_STEP {
+            self.played += position - self.last_position;
+        }
+        self.last_position = position;
+
+        if self.submitted || !should_scrobble(self.track.duration, self.played) {
+            return false;
+        }
+
+        self.submitted = true;
+        true
+    }
+}
+
+/// Spawn the tasks to submit played tracks, if any service is configured.
+///
+/// The settings are only read once, so changes require a restart.
+pub fn start_scrobbler(
+    handle: &Handle,
+    cancel_token: CancellationToken,
+    config: &SharedServerSettings,
+    stream_tx: &StreamTX,
+    playlist: SharedPlaylist,
+) -> Result<()> {
+    let mut scrobbler =
+        Scrobbler::new(&config.read().settings.scrobble).context("create scrobbler")?;
+    if !scrobbler.is_enabled() {
+        return Ok(());
+    }
+
+    info!("Scrobbling enabled");
+
+    // submit in a separate task, so that slow requests do not make the event stream lag
+    let (submit_tx, mut submit_rx) = unbounded_channel();
+    let cancel_token_c = cancel_token.clone();
+    handle.spawn(async move {
+        loop {
+            select! {
+                submission = submit_rx.recv() => {
+                    match submission {
+                        Some(Submission::NowPlaying(track)) => scrobbler.now_playing(&track).await,
+                        Some(Submission::Listen(track, started_at)) => scrobbler.scrobble(&track, started_at).await,
+                        Some(Submission::Love(track, loved)) => scrobbler.love(&track, loved).await,
+                        None => break,
+                    }
+                },
+                _ = cancel_token_c.cancelled() => {
+                    break;
+                }
+            }
+        }
+    });
+
+    let mut stream_rx = stream_tx.subscribe();
+    handle.spawn(async move {
+        let mut current: Option<Listen> = None;
+        loop {
+            select! {
+                event = stream_rx.recv() => {
+                    match event {
+                        Ok(UpdateEvents::TrackChanged(info)) => {
+                            track_changed(&info, &playlist, &mut current, &submit_tx);
+                        }
+                        Ok(UpdateEvents::Progress(progress)) => {
+                            let (Some(listen), Some(position)) = (&mut current, progress.position) else {
+                                continue;
+                            };
+                            if listen.progress(position) {
+                                let submission =
+                                    Submission::Listen(listen.track.clone(), listen.started_at);
+                                let _ = submit_tx.send(submission);
+                            }
+                        }
+                        Ok(UpdateEvents::TrackFavoriteChanged { path, favorite }) => {
+                            favorite_changed(&path, favorite, &submit_tx);
+                        }
+                        Ok(_) => (),
+                        Err(RecvError::Lagged(amount)) => {
+                            debug!("Scrobbler missed {amount} events");
+                        }
+                        Err(RecvError::Closed) => break,
+                    }
+                },
+                _ = cancel_token.cancelled() => {
+                    break;
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Submit that the track at `path` was marked as favorite, or unmarked.
+fn favorite_changed(path: &Path, favorite: bool, submit_tx: &UnboundedSender<Submission>) {
+    // the track may not be in the playlist, so read the metadata to submit from the file
+    let track = match Track::read_track_from_path(path) {
+        Ok(v) => v,
+        Err(err) => {
+            warn!("Not submitting favorite of {path:#?}: {err:#}");
+            return;
+        }
+    };
+    let Some(track) = ScrobbleTrack::from_track(&track) else {
+        debug!("Not submitting favorite of {path:#?}, it has no artist or title");
+        return;
+    };
+
+    let _ = submit_tx.send(Submission::Love(track, favorite));
+}
+
+/// Start a new [`Listen`] if another track started playing.
+fn track_changed(
+    info: &TrackChangedInfo,
+    playlist: &SharedPlaylist,
+    current: &mut Option<Listen>,
+    submit_tx: &UnboundedSender<Submission>,
+) {
+    // metadata changes of the same track
+    if !info.current_track_updated {
+        return;
+    }
+
+    let playlist = playlist.read();
+    let Some((id, track)) = playlist.current_track().and_then(|track| {
+        ScrobbleTrack::from_track(track).map(|v| (track.id_str().into_owned(), v))
+    }) else {
+        *current = None;
+        return;
+    };
+    drop(playlist);
+
+    // the flag is only reset on the next progress tick, so the same start may be reported more than once
+    if current
+        .as_ref()
+        .is_some_and(|v| v.id == id && v.last_position <= MAX_PROGRESS_STEP)
+    {
+        return;
+    }
+
+    let started_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |v| v.as_secs());
+    let _ = submit_tx.send(Submission::NowPlaying(track.clone()));
+
+    *current = Some(Listen {
+        id,
+        track,
+        started_at,
+        played: Duration::ZERO,
+        last_position: Duration::ZERO,
+        submitted: false,
+    });
+}
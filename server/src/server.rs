@@ -1,6 +1,11 @@
+mod cast;
 mod cli;
+mod http_api;
 mod logger;
+mod metrics;
 mod music_player_service;
+mod podcasts;
+mod scrobble;
 
 use std::net::SocketAddr;
 use std::num::NonZeroUsize;
@@ -9,16 +14,26 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::{Context as _, Result, bail};
+use cast::CastPlayer;
 use clap::Parser;
 use music_player_service::MusicPlayerService;
 use parking_lot::Mutex;
 use termusiclib::config::v2::server::config_extra::ServerConfigVersionedDefaulted;
-use termusiclib::config::v2::server::{ComProtocol, ScanDepth};
+use termusiclib::config::v2::server::metadata::ScanPolicy;
+use termusiclib::config::v2::server::{ComProtocol, ComTlsSettings, ScanDepth};
 use termusiclib::config::{ServerOverlay, SharedServerSettings, new_shared_server_settings};
+use termusiclib::new_database::Database;
+use termusiclib::new_database::track_ops::PlayOutcome;
+use termusiclib::new_database::watcher::LibraryWatcher;
+use termusiclib::parental::ParentalFilter;
+use termusiclib::player::auth::ServerAuth;
 use termusiclib::player::music_player_server::MusicPlayerServer;
-use termusiclib::player::{GetProgressResponse, PlayerProgress, PlayerTime, RunningStatus};
-use termusiclib::track::{MediaTypesSimple, Track};
-use termusiclib::{podcast, utils};
+use termusiclib::player::{
+    AbRepeat, AudioInfo, ChapterJump, GetProgressResponse, PlayerProgress, PlayerTime,
+    RunningStatus, TrackSpeed, UpdateEvents, audio_info,
+};
+use termusiclib::track::{MediaTypes, MediaTypesSimple, Track};
+use termusiclib::{cli_actions, organize, podcast, utils};
 use termusicplayback::{
     Backend, BackendSelect, GeneralPlayer, PlayerCmd, PlayerCmdReciever, PlayerCmdSender,
     PlayerErrorType, PlayerTrait, Playlist, SharedPlaylist, SpeedSigned, VolumeSigned,
@@ -29,8 +44,8 @@ use tokio::sync::{broadcast, oneshot};
 use tokio::task::JoinHandle;
 use tokio::time::Instant;
 use tokio_util::sync::CancellationToken;
-use tonic::transport::Server;
 use tonic::transport::server::TcpIncoming;
+use tonic::transport::{Identity, Server, ServerTlsConfig};
 
 #[macro_use]
 extern crate log;
@@ -51,6 +66,16 @@ struct PlayerStats {
     pub speed: i32,
     pub gapless: bool,
     pub radio_title: String,
+    /// The audio output devices of the backend, as of the last [`PlayerCmd::RefreshOutputDevices`]
+    pub output_devices: Vec<String>,
+    /// The id of the renderer playback is cast to, if any
+    pub cast_renderer: Option<String>,
+    /// The audio output of the backend, as of the last [`PlayerCmd::RefreshOutputInfo`]
+    pub audio_info: AudioInfo,
+    /// The chapter jumped to, as of the last [`PlayerCmd::JumpChapter`]
+    pub chapter_jump: ChapterJump,
+    /// The speed override of the current track, as of the last [`PlayerCmd::ToggleTrackSpeed`]
+    pub track_speed: TrackSpeed,
 }
 
 impl PlayerStats {
@@ -59,12 +84,18 @@ impl PlayerStats {
             progress: PlayerProgress {
                 position: None,
                 total_duration: None,
+                ab_repeat: AbRepeat::default(),
             },
             current_track_index: 0,
             volume: 0,
             speed: 10,
             gapless: true,
             radio_title: String::new(),
+            output_devices: Vec::new(),
+            cast_renderer: None,
+            audio_info: AudioInfo::default(),
+            chapter_jump: ChapterJump::default(),
+            track_speed: TrackSpeed::default(),
         }
     }
 
@@ -99,6 +130,12 @@ fn main() -> Result<()> {
 async fn actual_main() -> Result<()> {
     let args = cli::Args::parse();
     let _ = logger::setup(&args);
+    if let Some(data_dir) = &args.data_dir {
+        utils::set_app_data_path(data_dir.clone());
+    }
+    if let Some(profile) = &args.profile {
+        utils::set_app_profile(profile.clone())?;
+    }
     let config = get_config(&args)?;
 
     if let Some(action) = args.action {
@@ -107,6 +144,8 @@ async fn actual_main() -> Result<()> {
 
     info!("Server starting...");
 
+    config.settings.com.check_tokens()?;
+
     // do this before anything else so that we exit early on invalid/unavailable backends
     let backend = {
         let config_backend = config.settings.player.backend.try_into()?;
@@ -126,14 +165,31 @@ async fn actual_main() -> Result<()> {
     // Note that the channel size might quickly become too low if there is a massive delete (like removing the non-existent tracks from the playlist)
     let (stream_tx, _) = broadcast::channel(10);
 
-    let playlist =
-        Playlist::new_shared(&config, stream_tx.clone()).context("Failed to load playlist")?;
+    let db = Database::new_default_path().context("open library database")?;
+    ParentalFilter::check_set_up(&db.get_connection())?;
+    let playlist = Playlist::new_shared(&config, stream_tx.clone(), db.clone())
+        .context("Failed to load playlist")?;
+
+    let service_cancel_token = CancellationToken::new();
+
+    let podcasts = podcasts::start_podcast_manager(
+        &Handle::current(),
+        service_cancel_token.clone(),
+        config.clone(),
+        stream_tx.clone(),
+    )?;
+
+    let cast_renderers = cast::SharedRenderers::default();
 
     let music_player_service: MusicPlayerService = MusicPlayerService::new(
         cmd_tx.clone(),
         stream_tx.clone(),
         config.clone(),
         playlist.clone(),
+        podcasts,
+        db.clone(),
+        cast_renderers.clone(),
+        backend.supported_extensions(),
     );
     let playerstats = music_player_service.player_stats.clone();
 
@@ -147,8 +203,6 @@ async fn actual_main() -> Result<()> {
     })
     .expect("Error setting Ctrl-C handler");
 
-    let service_cancel_token = CancellationToken::new();
-
     let join_handle =
         start_service(&config, music_player_service, service_cancel_token.clone()).await?;
 
@@ -158,6 +212,59 @@ async fn actual_main() -> Result<()> {
     let playlist_c = playlist.clone();
     start_playlist_save_interval(tokio_handle.clone(), cancel_token, playlist_c);
 
+    scrobble::start_scrobbler(
+        &tokio_handle,
+        service_cancel_token.clone(),
+        &config,
+        &stream_tx,
+        playlist.clone(),
+    )?;
+
+    let metrics_address = config.read().settings.com.metrics;
+    if let Some(address) = metrics_address {
+        metrics::start_metrics_server(&tokio_handle, service_cancel_token.clone(), address).await?;
+    }
+
+    let (http_api_address, auth_token, guest_token, tls, music_dirs) = {
+        let config_read = config.read();
+        let com = &config_read.settings.com;
+        (
+            com.http_api,
+            com.auth_token.clone(),
+            com.guest_token.clone(),
+            com.tls.clone(),
+            config_read.settings.player.music_dirs.clone(),
+        )
+    };
+    if let Some(address) = http_api_address {
+        let state = http_api::ApiState {
+            cmd_tx: cmd_tx.clone(),
+            player_stats: playerstats.clone(),
+            playlist: playlist.clone(),
+            auth: ServerAuth::new(auth_token.as_deref()).with_guest(guest_token.as_deref()),
+            stream_tx: stream_tx.clone(),
+            music_dirs,
+        };
+        http_api::start_http_api(
+            &tokio_handle,
+            service_cancel_token.clone(),
+            address,
+            tls.as_ref(),
+            state,
+        )
+        .await?;
+    }
+
+    let cancel_token = service_cancel_token.clone();
+    start_library_scan_interval(
+        tokio_handle.clone(),
+        cancel_token,
+        config.clone(),
+        cmd_tx.clone(),
+    );
+    // watches for as long as it is alive
+    let _library_watcher = start_library_watcher(db, &config, &stream_tx);
+
     let (player_handle_os_tx, player_handle_os_rx) = oneshot::channel();
     let player_handle = std::thread::Builder::new()
         .name("main player loop".into())
@@ -171,6 +278,7 @@ async fn actual_main() -> Result<()> {
                 playerstats,
                 stream_tx,
                 playlist,
+                cast_renderers,
             );
             let _ = player_handle_os_tx.send(res);
         })?;
@@ -225,6 +333,65 @@ fn start_playlist_save_interval(
     });
 }
 
+/// Spawn a task to periodically rescan the library, if [`ScanPolicy::Interval`] is configured.
+///
+/// The policy is checked on every tick, so that config reloads take effect without a restart.
+/// Changes to the interval itself require a restart.
+fn start_library_scan_interval(
+    handle: Handle,
+    cancel_token: CancellationToken,
+    config: SharedServerSettings,
+    cmd_tx: PlayerCmdSender,
+) {
+    let interval_mins = config.read().settings.metadata.scan_interval_mins;
+    let interval = Duration::from_secs(u64::from(interval_mins.get()) * 60);
+
+    handle.spawn(async move {
+        let mut timer = tokio::time::interval_at(Instant::now() + interval, interval);
+        loop {
+            select! {
+                _ = timer.tick() => {
+                    if config.read().settings.metadata.scan_policy != ScanPolicy::Interval {
+                        continue;
+                    }
+                    debug!("Requesting library rescan in interval");
+                    if cmd_tx.send(PlayerCmd::RescanLibrary).is_err() {
+                        break;
+                    }
+                },
+                _ = cancel_token.cancelled() => {
+                    break;
+                }
+            }
+        }
+    });
+}
+
+/// Start watching the music directories for changes, if [`ScanPolicy::Watch`] is configured.
+///
+/// Clients are notified via [`UpdateEvents::LibraryChanged`] after the database got updated.
+/// Changes to the policy require a restart.
+fn start_library_watcher(
+    db: Database,
+    config: &SharedServerSettings,
+    stream_tx: &termusicplayback::StreamTX,
+) -> Option<LibraryWatcher> {
+    if config.read().settings.metadata.scan_policy != ScanPolicy::Watch {
+        return None;
+    }
+
+    let stream_tx = stream_tx.clone();
+    match LibraryWatcher::new(db, config.clone(), move || {
+        let _ = stream_tx.send(UpdateEvents::LibraryChanged);
+    }) {
+        Ok(watcher) => Some(watcher),
+        Err(err) => {
+            error!("Error starting the library watcher: {err:#?}");
+            None
+        }
+    }
+}
+
 /// Start the [`MusicPlayerService`] with the according transport protocol.
 async fn start_service(
     config: &SharedServerSettings,
@@ -232,15 +399,44 @@ async fn start_service(
     cancel_token: CancellationToken,
 ) -> Result<JoinHandle<Result<(), tonic::transport::Error>>> {
     // otherwise the MutexGuard would be held across await points
-    let protocol = config.read().settings.com.protocol;
+    let (protocol, auth_token, guest_token, tls) = {
+        let config_read = config.read();
+        let com = &config_read.settings.com;
+        (
+            com.protocol,
+            com.auth_token.clone(),
+            com.guest_token.clone(),
+            com.tls.clone(),
+        )
+    };
+    let service = MusicPlayerServer::with_interceptor(
+        music_player_service,
+        ServerAuth::new(auth_token.as_deref()).with_guest(guest_token.as_deref()),
+    );
     let handle = match protocol {
         ComProtocol::HTTP => {
             let (tcp_stream, addr) = tcp_stream(config).await?;
             info!("Server listening on {addr}");
+            if !addr.ip().is_loopback() {
+                if auth_token.is_none() {
+                    warn!(
+                        "Listening on {addr} without \"com.auth_token\", anyone that can reach it can control the server"
+                    );
+                }
+                if tls.is_none() {
+                    warn!("Listening on {addr} without \"com.tls\", requests are sent unencrypted");
+                }
+            }
+
+            let mut builder = Server::builder();
+            if let Some(tls) = tls {
+                builder = builder.tls_config(server_tls_config(&tls)?)?;
+                info!("Using TLS with certificate {}", tls.cert.display());
+            }
 
             tokio::spawn(
-                Server::builder()
-                    .add_service(MusicPlayerServer::new(music_player_service))
+                builder
+                    .add_service(service)
                     .serve_with_incoming_shutdown(tcp_stream, cancel_token.cancelled_owned()),
             )
         }
@@ -252,7 +448,7 @@ async fn start_service(
 
             tokio::spawn(
                 Server::builder()
-                    .add_service(MusicPlayerServer::new(music_player_service))
+                    .add_service(service)
                     .serve_with_incoming_shutdown(uds_stream, cancel_token.cancelled_owned()),
             )
         }
@@ -267,6 +463,20 @@ async fn start_service(
     Ok(handle)
 }
 
+/// Load the certificate and its key to serve HTTP over TLS.
+fn server_tls_config(tls: &ComTlsSettings) -> Result<ServerTlsConfig> {
+    let key_path = tls
+        .key
+        .as_ref()
+        .context("\"com.tls.key\" is required for the server")?;
+    let cert = std::fs::read(&tls.cert)
+        .with_context(|| format!("Error reading tls cert {}", tls.cert.display()))?;
+    let key = std::fs::read(key_path)
+        .with_context(|| format!("Error reading tls key {}", key_path.display()))?;
+
+    Ok(ServerTlsConfig::new().identity(Identity::from_pem(cert, key)))
+}
+
 /// Create the TCP Stream for HTTP requests.
 async fn tcp_stream(config: &SharedServerSettings) -> Result<(TcpIncoming, SocketAddr)> {
     let addr = SocketAddr::from(&config.read().settings.com);
@@ -382,12 +592,49 @@ fn player_loop(
     playerstats: Arc<Mutex<PlayerStats>>,
     stream_tx: termusicplayback::StreamTX,
     playlist: SharedPlaylist,
+    cast_renderers: cast::SharedRenderers,
 ) -> Result<()> {
     let mut player = GeneralPlayer::new_backend(backend, config, cmd_tx, stream_tx, playlist)?;
+    // continue where the last session stopped, if enabled
+    player.resume_last_played();
 
     let mut had_enqueue_error = false;
 
     while let Some((cmd, cb)) = cmd_rx.blocking_recv() {
+        if player.is_previewing() {
+            match cmd {
+                // the backend signals belong to the preview, not to the current track
+                PlayerCmd::AboutToFinish | PlayerCmd::MetadataChanged => {
+                    cb.call();
+                    continue;
+                }
+                PlayerCmd::Eos | PlayerCmd::Error(_) => {
+                    player.end_preview();
+                    cb.call();
+                    continue;
+                }
+                // changing the playback ends the preview
+                PlayerCmd::Play
+                | PlayerCmd::Pause
+                | PlayerCmd::TogglePause
+                | PlayerCmd::SkipNext
+                | PlayerCmd::SkipPrevious
+                | PlayerCmd::SeekBackward
+                | PlayerCmd::SeekForward
+                | PlayerCmd::SeekTo(_)
+                | PlayerCmd::JumpChapter(_)
+                | PlayerCmd::SetAbRepeatA
+                | PlayerCmd::SetAbRepeatB
+                | PlayerCmd::PlaylistPlaySpecific(_)
+                | PlayerCmd::PlaylistClear
+                | PlayerCmd::PlaylistRestore
+                | PlayerCmd::PlaylistSwitchQueue(_)
+                | PlayerCmd::SetCastRenderer(_)
+                | PlayerCmd::Quit => player.end_preview(),
+                _ => (),
+            }
+        }
+
         #[allow(unreachable_patterns)]
         match cmd {
             PlayerCmd::AboutToFinish => {
@@ -425,12 +672,15 @@ fn player_loop(
             }
             PlayerCmd::Eos => {
                 info!("Eos received");
+                player.record_play_outcome(PlayOutcome::Completed);
+                metrics::METRICS.track_played();
                 player_eos(&mut player, had_enqueue_error);
                 had_enqueue_error = false;
             }
             PlayerCmd::Error(ty) => {
                 info!("Error received: {ty:#?}");
                 player.increment_errors();
+                metrics::METRICS.stream_error();
 
                 if ty == PlayerErrorType::Current {
                     player_eos(&mut player, false);
@@ -444,6 +694,7 @@ fn player_loop(
                 player.reset_errors();
                 info!("skip to previous track");
                 player.player_save_last_position();
+                player.record_play_outcome(PlayOutcome::Skipped);
                 player.previous();
             }
             PlayerCmd::ReloadConfig => {
@@ -453,6 +704,10 @@ fn player_loop(
             }
             PlayerCmd::ReloadPlaylist => {
                 player.playlist.write().reload_tracks().ok();
+                player.mpris_tracklist_update();
+            }
+            PlayerCmd::RescanLibrary => {
+                player.rescan_library();
             }
             PlayerCmd::SeekBackward => {
                 player.seek_relative(false);
@@ -468,30 +723,77 @@ fn player_loop(
                     p_tick.progress = progress
                 }
             }
+            PlayerCmd::SeekTo(position) => {
+                player.seek_to(position);
+                let mut p_tick = playerstats.lock();
+                if let Some(progress) = player.get_progress() {
+                    p_tick.progress = progress
+                }
+            }
+            PlayerCmd::JumpChapter(forward) => {
+                let jump = player.jump_chapter(forward);
+                let mut p_tick = playerstats.lock();
+                p_tick.chapter_jump = jump;
+                if let Some(progress) = player.get_progress() {
+                    p_tick.progress = progress
+                }
+            }
             PlayerCmd::SkipNext => {
                 player.reset_errors();
                 info!("skip to next track.");
                 player.player_save_last_position();
+                player.record_play_outcome(PlayOutcome::Skipped);
                 player.next();
             }
             PlayerCmd::SpeedDown => {
-                let new_speed = player.add_speed(-SPEED_STEP);
+                let new_speed = player.change_speed(-SPEED_STEP);
                 info!("after speed down: {new_speed}");
-                player.config.write().settings.player.speed = new_speed;
                 let mut p_tick = playerstats.lock();
                 p_tick.speed = new_speed;
             }
 
             PlayerCmd::SpeedUp => {
-                let new_speed = player.add_speed(SPEED_STEP);
+                let new_speed = player.change_speed(SPEED_STEP);
                 info!("after speed up: {new_speed}");
-                player.config.write().settings.player.speed = new_speed;
                 let mut p_tick = playerstats.lock();
                 p_tick.speed = new_speed;
             }
+            PlayerCmd::ToggleTrackSpeed => {
+                let track_speed = player.toggle_track_speed();
+                info!("track speed: {track_speed:?}");
+                let mut p_tick = playerstats.lock();
+                p_tick.speed = track_speed.speed;
+                p_tick.track_speed = track_speed;
+            }
+            PlayerCmd::SetAbRepeatA => {
+                player.set_ab_repeat_a();
+                let mut p_tick = playerstats.lock();
+                if let Some(progress) = player.get_progress() {
+                    p_tick.progress = progress;
+                }
+            }
+            PlayerCmd::SetAbRepeatB => {
+                player.set_ab_repeat_b();
+                let mut p_tick = playerstats.lock();
+                if let Some(progress) = player.get_progress() {
+                    p_tick.progress = progress;
+                }
+            }
+            PlayerCmd::ClearAbRepeat => {
+                player.clear_ab_repeat();
+                let mut p_tick = playerstats.lock();
+                if let Some(progress) = player.get_progress() {
+                    p_tick.progress = progress;
+                }
+            }
             PlayerCmd::Tick => {
                 // info!("tick received");
                 player.mpris_handle_events();
+                if player.update_preview() {
+                    // the progress is of the current track, not of the preview
+                    cb.call();
+                    continue;
+                }
                 let mut p_tick = playerstats.lock();
                 let mut playlist = player.playlist.read();
                 // branch to auto-start playing if status is "stopped"(not paused) and playlist is not empty anymore
@@ -519,6 +821,7 @@ fn player_loop(
                     // so we have to unlock first then later re-acquire the handle for later parts
                     drop(playlist);
                     player.update_progress(&p_tick.progress);
+                    player.enqueue_next_stream(&p_tick.progress);
 
                     // only reset errors if position is either above 0 or total duration is available and is above 0
                     if pl_status == RunningStatus::Running
@@ -534,6 +837,8 @@ fn player_loop(
                     p_tick.current_track_index =
                         u64::try_from(playlist.get_current_track_index()).unwrap();
                     player.current_track_updated = false;
+                    // the speed changes with tracks that have a speed override
+                    p_tick.speed = player.speed();
                 }
                 if let Some(track) = playlist.current_track() {
                     update_metadata_changed(&mut p_tick, &player, track);
@@ -566,11 +871,60 @@ fn player_loop(
                 p_tick.volume = new_volume;
                 player.mpris_volume_update();
             }
+            PlayerCmd::RefreshOutputDevices => {
+                let devices = player.output_devices();
+                playerstats.lock().output_devices = devices;
+            }
+            PlayerCmd::SetOutputDevice(name) => {
+                info!("switching output device to {name:#?}");
+                match player.set_output_device(name.clone()) {
+                    Ok(()) => player.config.write().settings.backends.rusty.output_device = name,
+                    Err(err) => error!("Switching output device failed: {err:#}"),
+                }
+            }
+            PlayerCmd::RefreshOutputInfo => {
+                let info = get_audio_info(&player);
+                playerstats.lock().audio_info = info;
+            }
+            PlayerCmd::SetCastRenderer(id) => {
+                let renderer = id.and_then(|id| {
+                    let renderer = cast_renderers.lock().iter().find(|v| v.id == id).cloned();
+                    if renderer.is_none() {
+                        error!("Unknown cast renderer {id:#?}, playing on the backend");
+                    }
+                    renderer
+                });
+                match renderer {
+                    Some(renderer) => {
+                        info!("casting to {:#?}", renderer.name);
+                        match CastPlayer::new(renderer.clone(), player.cmd_tx.clone()) {
+                            Ok(cast) => {
+                                player.set_cast(Some(Box::new(cast)));
+                                playerstats.lock().cast_renderer = Some(renderer.id);
+                            }
+                            Err(err) => error!("Casting to {:#?} failed: {err:#}", renderer.name),
+                        }
+                    }
+                    None => {
+                        info!("stopped casting");
+                        player.set_cast(None);
+                        playerstats.lock().cast_renderer = None;
+                    }
+                }
+            }
+            PlayerCmd::SetCrossfade(crossfade) => {
+                info!("setting crossfade to {crossfade:?}");
+                player.config.write().settings.player.crossfade = crossfade;
+            }
             PlayerCmd::Pause => {
                 player.pause();
             }
             PlayerCmd::Play => {
-                player.resume();
+                // a stopped player has nothing to resume, but may continue the last played track
+                let stopped = player.playlist.read().is_stopped();
+                if !stopped || !player.resume_last_played() {
+                    player.resume();
+                }
             }
 
             PlayerCmd::PlaylistPlaySpecific(info) => {
@@ -580,6 +934,7 @@ fn player_loop(
                     info.track_index, info.id
                 );
                 player.player_save_last_position();
+                player.record_play_outcome(PlayOutcome::Skipped);
                 if let Err(err) = player.playlist.write().play_specific(&info) {
                     error!("Error setting specific track to play: {err}");
                 }
@@ -589,26 +944,63 @@ fn player_loop(
                 if let Err(err) = player.playlist.write().add_tracks(info, &player.db_podcast) {
                     error!("Error adding tracks: {err}");
                 }
+                player.mpris_tracklist_update();
             }
             PlayerCmd::PlaylistRemoveTrack(info) => {
                 if let Err(err) = player.playlist.write().remove_tracks(info) {
                     error!("Error removing tracks: {err}");
                 }
+                player.mpris_tracklist_update();
             }
             PlayerCmd::PlaylistClear => {
                 player.reset_errors();
                 player.playlist.write().clear();
+                player.mpris_tracklist_update();
             }
             PlayerCmd::PlaylistSwapTrack(info) => {
                 if let Err(err) = player.playlist.write().swap_tracks(&info) {
                     error!("Error swapping tracks: {err}");
                 }
+                player.mpris_tracklist_update();
             }
             PlayerCmd::PlaylistShuffle => {
                 player.playlist.write().shuffle();
+                player.mpris_tracklist_update();
             }
             PlayerCmd::PlaylistRemoveDeletedTracks => {
                 player.playlist.write().remove_deleted_items();
+                player.mpris_tracklist_update();
+            }
+            PlayerCmd::PlaylistRenamePaths(moves) => {
+                if let Err(err) = player.playlist.write().rename_paths(&moves) {
+                    error!("Error updating the moved tracks of the playlist: {err:#}");
+                }
+                player.mpris_tracklist_update();
+            }
+            PlayerCmd::PlaylistRestore => {
+                player.reset_errors();
+                player.stop();
+                if let Err(err) = player.playlist.write().restore() {
+                    error!("Error restoring the queue: {err:#}");
+                }
+                player.mpris_tracklist_update();
+            }
+            PlayerCmd::PlaylistSwitchQueue(name) => {
+                player.reset_errors();
+                player.stop();
+                if let Err(err) = player.playlist.write().switch_queue(&name) {
+                    error!("Error switching to queue \"{name}\": {err:#}");
+                }
+                player.mpris_tracklist_update();
+            }
+            PlayerCmd::PlaylistToggleAutoQueue => {
+                let enabled = player.toggle_auto_queue();
+                info!("auto-queue enabled: {enabled}");
+            }
+            PlayerCmd::PreviewTrack(path) => {
+                if let Err(err) = player.start_preview(&path) {
+                    warn!("Error previewing {path:#?}: {err:#}");
+                }
             }
             PlayerCmd::MetadataChanged => {
                 trace!("Metadata changed");
@@ -626,6 +1018,39 @@ fn player_loop(
     Ok(())
 }
 
+/// Collect the output of the backend and the gain applied to the current track.
+fn get_audio_info(player: &GeneralPlayer) -> AudioInfo {
+    let output = player.output_info();
+    let stream_info = player.media_info().stream_info;
+    let replay_gain_mode = player.config.read().settings.player.replay_gain;
+    let replay_gain = player
+        .playlist
+        .read()
+        .current_track()
+        .and_then(|track| match track.inner() {
+            MediaTypes::Track(track_data) => track_data.replay_gain(),
+            _ => None,
+        })
+        .and_then(|v| v.factor(replay_gain_mode))
+        .map(|factor| 20.0 * factor.log10());
+
+    AudioInfo {
+        backend: player.backend.name().to_string(),
+        device: output.device.unwrap_or_default(),
+        sample_rate: output.sample_rate.unwrap_or_default(),
+        channels: output.channels.map(u32::from).unwrap_or_default(),
+        buffer_frames: output.buffer_frames.unwrap_or_default(),
+        decoded_buffer_size: output.decoded_buffer_size.unwrap_or_default(),
+        optional_resampling: output
+            .resampling
+            .map(audio_info::OptionalResampling::Resampling),
+        stream_info: Some(stream_info.into()),
+        replay_gain_mode: replay_gain_mode.as_str().to_string(),
+        optional_replay_gain: replay_gain.map(audio_info::OptionalReplayGain::ReplayGainDb),
+        volume: u32::from(player.volume()),
+    }
+}
+
 /// Update [`PlayerStats`] on metadata change or on tick.
 ///
 /// Deduplicated
@@ -719,6 +1144,7 @@ fn get_config(args: &cli::Args) -> Result<ServerOverlay> {
         music_dir_overwrite: music_dir,
         disable_discord_status: args.disable_discord,
         metadata_scan_depth: max_depth,
+        parental: ParentalFilter::from_config_path()?,
     };
 
     Ok(overlay)
@@ -750,19 +1176,61 @@ async fn execute_action(action: cli::Action, config: &ServerOverlay) -> Result<(
             println!("need to import from file {}", file.display());
 
             let path = get_path(&file).context("import cli file-path")?;
-            let config_dir_path =
-                utils::get_app_config_path().context("getting app-config-path")?;
+            let data_dir_path = utils::get_app_data_path().context("getting app-data-path")?;
 
-            podcast::import_from_opml(&config_dir_path, &config.settings.podcast, &path)
+            podcast::import_from_opml(&data_dir_path, &config.settings.podcast, &path)
                 .await
                 .context("import opml")?;
         }
         cli::Action::Export { file } => {
             println!("need to export to file {}", file.display());
             let path = utils::absolute_path(&file)?;
-            let config_dir_path =
-                utils::get_app_config_path().context("getting app-config-path")?;
-            podcast::export_to_opml(&config_dir_path, &path).context("export opml")?;
+            let data_dir_path = utils::get_app_data_path().context("getting app-data-path")?;
+            podcast::export_to_opml(&data_dir_path, &path).context("export opml")?;
+        }
+        cli::Action::Backup => cli_actions::backup()?,
+        cli::Action::ListBackups => cli_actions::list_backups()?,
+        cli::Action::Restore { name, yes } => cli_actions::restore_backup(&name, yes)?,
+        cli::Action::ExportState { file } => cli_actions::export_state(&file)?,
+        cli::Action::ImportState { file, yes } => cli_actions::import_state(&file, yes)?,
+        cli::Action::SmartPlaylist(action) => match action {
+            cli::SmartPlaylistAction::Add { name, query } => {
+                cli_actions::smart_playlist_add(&name, &query)?;
+            }
+            cli::SmartPlaylistAction::List => cli_actions::smart_playlist_list()?,
+            cli::SmartPlaylistAction::Remove { name } => cli_actions::smart_playlist_remove(&name)?,
+        },
+        cli::Action::Organize { dry_run, yes } => {
+            let db = Database::new_default_path().context("open library database")?;
+            let music_dirs = &config.settings.player.music_dirs;
+            let plan =
+                organize::plan_organize(&db, music_dirs, &config.settings.player.organize_pattern)
+                    .context("plan organizing the library")?;
+
+            for (path, reason) in &plan.skipped {
+                println!("skipping {}: {reason}", path.display());
+            }
+            for mv in &plan.moves {
+                println!("{} -> {}", mv.from.display(), mv.to.display());
+            }
+            if plan.moves.is_empty() {
+                println!("all tracks are already organized");
+                return Ok(());
+            }
+            if dry_run {
+                println!("{} tracks would be moved", plan.moves.len());
+                return Ok(());
+            }
+            if !yes && !cli_actions::confirm(&format!("Move {} tracks?", plan.moves.len()))? {
+                println!("organize cancelled");
+                return Ok(());
+            }
+
+            let failed = organize::apply_organize(&db, music_dirs, &plan.moves);
+            for (mv, err) in &failed {
+                println!("failed to move {}: {err}", mv.from.display());
+            }
+            println!("moved {} tracks", plan.moves.len() - failed.len());
         }
     };
 
@@ -27,6 +27,24 @@ pub struct Args {
     /// Select the backend, default is `rusty`
     #[arg(short, long, env = "TMS_BACKEND")]
     pub backend: Option<Backend>,
+    /// Store databases, caches and the playlist in `DATA_DIR` instead of the platform data directory,
+    /// for example for a portable setup.
+    #[arg(long, env = "TMS_DATA_DIR")]
+    pub data_dir: Option<PathBuf>,
+    /// Use the profile `PROFILE`, which has its own config, databases and playlist, "default" for the main one.
+    ///
+    /// Without `PROFILE`, asks which of the existing profiles to use.
+    #[arg(long, env = "TMS_PROFILE", num_args = 0..=1)]
+    pub profile: Option<Option<String>>,
+    /// Start even if another TUI is running with the same config, which then stops saving the config.
+    #[arg(long)]
+    pub force: bool,
+    /// Hide and disable actions that delete, remove or move something, like deleting files,
+    /// removing podcasts or clearing the playlist, and the tag, lyric and config editors, to hand control to guests.
+    ///
+    /// Always on when the server accepted the token as `com.guest_token`.
+    #[arg(long)]
+    pub guest: bool,
     #[clap(flatten)]
     pub log_options: LogOptions,
 }
@@ -37,6 +55,8 @@ pub enum Backend {
     Mpv,
     #[value(alias = "gst", name = "gstreamer")]
     GStreamer,
+    /// Play nothing and only advance the position, for development, testing and demos
+    Null,
 }
 
 impl From<Backend> for ConfigBackend {
@@ -45,6 +65,7 @@ impl From<Backend> for ConfigBackend {
             Backend::Rusty => Self::Rusty,
             Backend::Mpv => Self::Mpv,
             Backend::GStreamer => Self::Gstreamer,
+            Backend::Null => Self::Null,
         }
     }
 }
@@ -76,6 +97,68 @@ pub enum Action {
         #[arg(value_name = "FILE")]
         file: PathBuf,
     },
+    /// Create a timestamped backup of the databases.
+    Backup,
+    /// List all database backups, newest first.
+    ListBackups,
+    /// Restore the databases from a backup, see "list-backups" for available names.
+    Restore {
+        #[arg(value_name = "NAME")]
+        name: String,
+        /// Do not ask for confirmation before restoring.
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Export config, themes, databases and the playlist into a single archive, to move to another machine.
+    ExportState {
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+    /// Import a archive created by "export-state", replacing the current config, themes, databases and playlist.
+    ImportState {
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+        /// Do not ask for confirmation before importing.
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Add the urls and paths of a text file, one per line, to the playlist of the running server.
+    ///
+    /// Youtube videos are downloaded into the first music directory first,
+    /// urls of known podcast episodes are added as episodes.
+    Enqueue {
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+    /// Manage smart playlists, which are shown in the database view under "Playlists".
+    #[command(subcommand)]
+    SmartPlaylist(SmartPlaylistAction),
+    /// List all profiles, which are created by starting with "--profile NAME".
+    ListProfiles,
+}
+
+/// Subcommands for smart playlists
+#[derive(Subcommand, Debug)]
+pub enum SmartPlaylistAction {
+    /// Add a smart playlist, for example `add "Old Rock" "genre = Rock AND last_played > 30"`.
+    Add {
+        #[arg(value_name = "NAME")]
+        name: String,
+        /// Rules in the format "field operator value", joined by either " AND " or " OR ".
+        ///
+        /// Fields: title, artist, album, genre, directory, duration (in seconds),
+        /// added and last_played (in days ago).
+        /// Operators: "=", "!=", "~" (contains), ">" and "<".
+        #[arg(value_name = "QUERY")]
+        query: String,
+    },
+    /// List all smart playlists with their rules.
+    List,
+    /// Remove a smart playlist.
+    Remove {
+        #[arg(value_name = "NAME")]
+        name: String,
+    },
 }
 
 const DEFAULT_LOGFILE_FILENAME: &str = "termusic-tui.log";
@@ -0,0 +1,147 @@
+//! A lock against running the TUI twice with the same config, as both instances would overwrite each other's config changes.
+//!
+//! The lock is a file in the config directory containing the process id of the TUI holding it,
+//! which is ignored once that process is gone, like after a crash.
+//! Starting with `--force` takes the lock over, after which the other instance keeps running as another client,
+//! but does not save the config anymore.
+
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::{Context, Result, bail};
+use sysinfo::{Pid, ProcessesToUpdate, System};
+
+/// The file name of the lock in the config directory.
+const LOCK_FILE: &str = "tui.lock";
+
+/// The lock of this instance, if one was acquired.
+static LOCK: OnceLock<PathBuf> = OnceLock::new();
+
+/// Acquire the lock in the config directory `dir`.
+///
+/// With `force`, the lock is taken over from a running instance, instead of failing.
+///
+/// # Errors
+///
+/// - if another instance holds the lock and `force` is not set
+/// - if the lock file cannot be written
+pub fn acquire(dir: &Path, force: bool) -> Result<()> {
+    let path = dir.join(LOCK_FILE);
+    let own_pid = std::process::id();
+
+    // try twice, as a stale lock gets replaced
+    for _ in 0..2 {
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                write!(file, "{own_pid}")
+                    .with_context(|| format!("write lock \"{}\"", path.display()))?;
+                let _ = LOCK.set(path);
+                return Ok(());
+            }
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => (),
+            Err(err) => {
+                return Err(err).with_context(|| format!("create lock \"{}\"", path.display()));
+            }
+        }
+
+        match read_pid(&path).filter(|pid| *pid != own_pid && is_running(*pid)) {
+            Some(pid) if force => {
+                warn!("Taking over the instance lock from termusic process {pid}");
+                std::fs::write(&path, own_pid.to_string())
+                    .with_context(|| format!("write lock \"{}\"", path.display()))?;
+                let _ = LOCK.set(path);
+                return Ok(());
+            }
+            Some(pid) => bail!(
+                "termusic is already running with the config in \"{}\" (process {pid}).\nQuit it first, or start with \"--force\" to run another instance anyway, which stops the other one from saving the config.",
+                dir.display()
+            ),
+            None => {
+                info!("Removing stale instance lock \"{}\"", path.display());
+                // another instance starting at the same time may have removed it already
+                let _ = std::fs::remove_file(&path);
+            }
+        }
+    }
+
+    bail!(
+        "Could not acquire the instance lock \"{}\", another termusic instance is starting",
+        path.display()
+    )
+}
+
+/// Check that this instance may save the config, meaning it was not taken over by another instance.
+///
+/// Always passes if no lock was acquired, like for subcommands.
+///
+/// # Errors
+///
+/// if another instance took the lock over
+pub fn ensure_held() -> Result<()> {
+    let Some(path) = LOCK.get() else {
+        return Ok(());
+    };
+
+    if read_pid(path) != Some(std::process::id()) {
+        bail!(
+            "Another termusic instance was started with \"--force\", not saving the config to not overwrite its changes"
+        );
+    }
+
+    Ok(())
+}
+
+/// Remove the lock, if this instance still holds it.
+pub fn release() {
+    let Some(path) = LOCK.get() else {
+        return;
+    };
+
+    if ensure_held().is_ok() {
+        if let Err(err) = std::fs::remove_file(path) {
+            warn!(
+                "Removing instance lock \"{}\" failed: {err}",
+                path.display()
+            );
+        }
+    }
+}
+
+/// Read the process id stored in the lock at `path`.
+fn read_pid(path: &Path) -> Option<u32> {
+    let content = std::fs::read_to_string(path).ok()?;
+
+    parse_pid(&content)
+}
+
+fn parse_pid(content: &str) -> Option<u32> {
+    content.trim().parse().ok()
+}
+
+/// Check whether `pid` is a running termusic process, and not another process that re-used the id.
+fn is_running(pid: u32) -> bool {
+    let pid = Pid::from_u32(pid);
+    let mut sys = System::new();
+    sys.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+
+    sys.process(pid)
+        .is_some_and(|proc| proc.name().to_string_lossy().contains("termusic"))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::parse_pid;
+
+    #[test]
+    fn should_parse_pid() {
+        assert_eq!(parse_pid("1234"), Some(1234));
+        assert_eq!(parse_pid("1234\n"), Some(1234));
+        // empty while being written
+        assert_eq!(parse_pid(""), None);
+        assert_eq!(parse_pid("abc"), None);
+    }
+}
@@ -1,4 +1,4 @@
-use std::ffi::OsStr;
+use std::ffi::{OsStr, OsString};
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::process::Stdio;
@@ -11,25 +11,35 @@ use anyhow::{Context, Result, bail};
 use clap::Parser;
 use flexi_logger::LogSpecification;
 use parking_lot::Mutex;
-use sysinfo::{Pid, ProcessStatus, System};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, System, UpdateKind};
 use termusiclib::config::v2::server::config_extra::ServerConfigVersionedDefaulted;
-use termusiclib::config::v2::server::{ComProtocol, ScanDepth};
+use termusiclib::config::v2::server::{ComProtocol, ComSettings, ScanDepth};
 use termusiclib::config::v2::tui::config_extra::TuiConfigVersionedDefaulted;
 use termusiclib::config::{
     ServerOverlay, SharedServerSettings, SharedTuiSettings, TuiOverlay, new_shared_server_settings,
     new_shared_tui_settings,
 };
+use termusiclib::new_database::DatabaseError;
+use termusiclib::parental::ParentalFilter;
+use termusiclib::player::PlaylistTracksToAdd;
+use termusiclib::player::auth::{AuthClient, ClientAuth};
 use termusiclib::player::music_player_client::MusicPlayerClient;
-use termusiclib::{podcast, utils};
+use termusiclib::player::playlist_helpers::{PlaylistAddTrack, PlaylistTrackSource};
+use termusiclib::url_list::{self, UrlListItem};
+use termusiclib::{cli_actions, podcast, utils};
 use tokio::io::AsyncReadExt;
 use tokio::process::{Child, Command};
 use tokio::sync::RwLock;
 use tokio::task::AbortHandle;
 use tokio_util::sync::CancellationToken;
+use tonic::transport::{Certificate, ClientTlsConfig, Endpoint};
 
 use ui::UI;
+use ui::model::Model;
+use ui::model::youtube_options::youtube_dl_blocking;
 
 mod cli;
+mod instance_lock;
 mod logger;
 mod ui;
 
@@ -64,6 +74,18 @@ pub static SERVER_PID: OnceLock<Pid> = OnceLock::new();
 async fn actual_main() -> Result<()> {
     let args = cli::Args::parse();
     let mut logger_handle = logger::setup(&args);
+    if let Some(data_dir) = &args.data_dir {
+        utils::set_app_data_path(data_dir.clone());
+    }
+    let profile = match &args.profile {
+        Some(Some(profile)) => Some(profile.clone()),
+        // subcommands are not interactive
+        Some(None) if args.action.is_none() => choose_profile()?,
+        Some(None) | None => None,
+    };
+    if let Some(profile) = profile {
+        utils::set_app_profile(profile)?;
+    }
     let config = get_config(&args)?;
 
     ctrl_c_handler().expect("Error setting Ctrl-C handler");
@@ -72,6 +94,8 @@ async fn actual_main() -> Result<()> {
         return execute_action(action, &config).await;
     }
 
+    instance_lock::acquire(&utils::get_app_config_path()?, args.force)?;
+
     // launch the daemon if it isn't already
     let (pid, child) = {
         let active_pid = find_active_server_process();
@@ -129,7 +153,9 @@ async fn actual_main() -> Result<()> {
     }
 
     let mut ui = UI::new(config, client).await?;
-    ui.run()?;
+    let res = ui.run();
+    instance_lock::release();
+    res?;
 
     info!("Bye");
 
@@ -188,7 +214,7 @@ fn collect_server_output(mut child: Child) -> Arc<ServerOutput> {
 fn launch_server(args: &cli::Args) -> Result<Child> {
     let termusic_server_prog = get_server_binary_exe()?;
 
-    let mut server_args = vec![];
+    let mut server_args: Vec<OsString> = vec![];
 
     // dont clone over "log-to-file", because default is "true" now, and otherwise can be controlled via TMS_LOGTOFILE or TMS_LOGFILE
     // server_args.push("--log-to-file");
@@ -199,12 +225,22 @@ fn launch_server(args: &cli::Args) -> Result<Child> {
     // }
 
     if args.log_options.file_color_log {
-        server_args.push("--log-filecolor");
+        server_args.push("--log-filecolor".into());
     }
 
     if let Some(backend) = args.backend {
-        server_args.push("--backend");
-        server_args.push(backend.as_str());
+        server_args.push("--backend".into());
+        server_args.push(backend.as_str().into());
+    }
+
+    if let Some(data_dir) = &args.data_dir {
+        server_args.push("--data-dir".into());
+        server_args.push(data_dir.as_os_str().to_owned());
+    }
+
+    if let Some(profile) = utils::get_app_profile() {
+        server_args.push("--profile".into());
+        server_args.push(profile.into());
     }
 
     // server can stay around after client exits (if supported by the system)
@@ -246,16 +282,23 @@ fn spawn_process<A: IntoIterator<Item = S> + Clone, S: AsRef<OsStr>>(
     cmd.spawn()
 }
 
-/// Try to find a active server process, returning its [`Pid`].
+/// Try to find a active server process of the current [profile](utils::set_app_profile), returning its [`Pid`].
 /// Otherwise if not found, returns [`None`].
 fn find_active_server_process() -> Option<Pid> {
     let mut system = System::new();
-    system.refresh_all();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing()
+            .with_exe(UpdateKind::OnlyIfNotSet)
+            .with_cmd(UpdateKind::OnlyIfNotSet),
+    );
+    let profile = utils::get_app_profile();
     for (id, proc) in system.processes() {
         let Some(exe) = proc.exe().map(|v| v.display().to_string()) else {
             continue;
         };
-        if exe.contains("termusic-server") {
+        if exe.contains("termusic-server") && server_profile(proc.cmd()) == profile {
             return Some(*id);
         }
     }
@@ -263,6 +306,21 @@ fn find_active_server_process() -> Option<Pid> {
     None
 }
 
+/// Get the profile a server was started with from its `cmd` arguments, `None` for the default profile.
+fn server_profile(cmd: &[OsString]) -> Option<&str> {
+    let mut args = cmd.iter().filter_map(|v| v.to_str());
+    let mut profile = None;
+    while let Some(arg) = args.next() {
+        if arg == "--profile" {
+            profile = args.next();
+        } else if let Some(value) = arg.strip_prefix("--profile=") {
+            profile = Some(value);
+        }
+    }
+
+    profile.filter(|v| *v != utils::DEFAULT_PROFILE)
+}
+
 /// Try to find the server binary adjacent to the current executable path.
 /// Otherwise return command to let system PATH figure it out.
 fn get_server_binary_exe() -> Result<PathBuf> {
@@ -289,14 +347,15 @@ const WAIT_MESSAGE_TIME: Duration = Duration::from_secs(5);
 const WAIT_INTERVAL: Duration = Duration::from_millis(100);
 
 /// Wait until the [`MusicPlayerClient`] is connected on the correct transport protocol.
-async fn wait_till_connected(
-    config: &CombinedSettings,
-    pid: u32,
-) -> Result<(MusicPlayerClient<tonic::transport::Channel>, String)> {
-    let protocol = config.tui.read().settings.get_com().unwrap().protocol;
+async fn wait_till_connected(config: &CombinedSettings, pid: u32) -> Result<(AuthClient, String)> {
+    let (protocol, auth) = {
+        let config_read = config.tui.read();
+        let com = config_read.settings.get_com().unwrap();
+        (com.protocol, ClientAuth::new(com.auth_token.as_deref())?)
+    };
     let player = match protocol {
-        ComProtocol::HTTP => wait_till_connected_tcp(config, pid).await?,
-        ComProtocol::UDS => wait_till_connected_uds(config, pid).await?,
+        ComProtocol::HTTP => wait_till_connected_tcp(config, pid, auth).await?,
+        ComProtocol::UDS => wait_till_connected_uds(config, pid, auth).await?,
     };
 
     Ok(player)
@@ -309,14 +368,24 @@ async fn wait_till_connected(
 async fn wait_till_connected_tcp(
     config: &CombinedSettings,
     pid: u32,
-) -> Result<(MusicPlayerClient<tonic::transport::Channel>, String)> {
-    let addr = {
+    auth: ClientAuth,
+) -> Result<(AuthClient, String)> {
+    let (addr, tls) = {
         let config_read = config.tui.read();
-        SocketAddr::from(config_read.settings.get_com().ok_or(anyhow::anyhow!(
+        let com = config_read.settings.get_com().ok_or(anyhow::anyhow!(
             "Expected tui-com settings to be resolved at this point"
-        ))?)
+        ))?;
+        (SocketAddr::from(com), client_tls_config(com)?)
+    };
+    let addr = if tls.is_some() {
+        format!("https://{addr}")
+    } else {
+        format!("http://{addr}")
     };
-    let addr = format!("http://{addr}");
+    let mut endpoint = Endpoint::from_shared(addr.clone())?;
+    if let Some(tls) = tls {
+        endpoint = endpoint.tls_config(tls)?;
+    }
 
     let mut sys = sysinfo::System::new();
     let sys_pid = Pid::from_u32(pid);
@@ -340,7 +409,7 @@ async fn wait_till_connected_tcp(
             anyhow::bail!("Process {pid} exited before being able to connect!");
         }
 
-        match MusicPlayerClient::connect(addr.clone()).await {
+        match endpoint.connect().await {
             Err(err) => {
                 // downcast "tonic::transport::Error" to a "std::io::Error"(kind: Os)
                 if let Some(os_err) = find_source::<std::io::Error>(&err) {
@@ -354,7 +423,7 @@ async fn wait_till_connected_tcp(
                 // return the error and stop if it is anything other than "Connection Refused"
                 anyhow::bail!(err);
             }
-            Ok(client) => return Ok((client, addr)),
+            Ok(channel) => return Ok((MusicPlayerClient::with_interceptor(channel, auth), addr)),
         }
     }
 }
@@ -366,7 +435,8 @@ async fn wait_till_connected_tcp(
 async fn wait_till_connected_uds(
     config: &CombinedSettings,
     pid: u32,
-) -> Result<(MusicPlayerClient<tonic::transport::Channel>, String)> {
+    auth: ClientAuth,
+) -> Result<(AuthClient, String)> {
     let addr = {
         let config_read = config.tui.read();
         let addr = config_read
@@ -377,6 +447,7 @@ async fn wait_till_connected_uds(
             .to_string_lossy();
         format!("unix://{addr}")
     };
+    let endpoint = Endpoint::from_shared(addr.clone())?;
 
     let mut sys = sysinfo::System::new();
     let sys_pid = Pid::from_u32(pid);
@@ -400,7 +471,7 @@ async fn wait_till_connected_uds(
             anyhow::bail!("Process {pid} exited before being able to connect!");
         }
 
-        match MusicPlayerClient::connect(addr.clone()).await {
+        match endpoint.connect().await {
             Err(err) => {
                 // downcast "tonic::transport::Error" to a "std::io::Error"(kind: Os)
                 if let Some(os_err) = find_source::<std::io::Error>(&err) {
@@ -419,11 +490,30 @@ async fn wait_till_connected_uds(
                 // return the error and stop if it is anything other than "Connection Refused"
                 return Err(anyhow::anyhow!(err).context(addr));
             }
-            Ok(client) => return Ok((client, addr)),
+            Ok(channel) => return Ok((MusicPlayerClient::with_interceptor(channel, auth), addr)),
         }
     }
 }
 
+/// Load the certificate to trust when connecting over TLS, if enabled.
+fn client_tls_config(com: &ComSettings) -> Result<Option<ClientTlsConfig>> {
+    let Some(tls) = &com.tls else {
+        return Ok(None);
+    };
+    let cert = std::fs::read(&tls.cert)
+        .with_context(|| format!("Error reading tls cert {}", tls.cert.display()))?;
+    let domain = tls
+        .domain
+        .clone()
+        .unwrap_or_else(|| com.address.to_string());
+
+    Ok(Some(
+        ClientTlsConfig::new()
+            .ca_certificate(Certificate::from_pem(cert))
+            .domain_name(domain),
+    ))
+}
+
 /// Start a task to print a message if the connection time is longer than [`WAIT_MESSAGE_TIME`].
 ///
 /// And cancel this task if the handle is dropped. This makes it easy to not print once the TUI is up and
@@ -478,6 +568,7 @@ fn get_config(args: &cli::Args) -> Result<CombinedSettings> {
         music_dir_overwrite: music_dir,
         disable_discord_status: args.disable_discord,
         metadata_scan_depth: max_depth,
+        parental: ParentalFilter::from_config_path()?,
     };
 
     let config_tui = TuiConfigVersionedDefaulted::from_config_path()?.into_settings();
@@ -488,6 +579,7 @@ fn get_config(args: &cli::Args) -> Result<CombinedSettings> {
         settings: config_tui,
         coverart_hidden_overwrite,
         cover_features: !args.disable_cover,
+        guest: args.guest,
     };
 
     Ok(CombinedSettings {
@@ -522,28 +614,161 @@ async fn execute_action(action: cli::Action, config: &CombinedSettings) -> Resul
             println!("need to import from file {}", file.display());
 
             let path = get_path(&file).context("import cli file-path")?;
-            let config_dir_path =
-                utils::get_app_config_path().context("getting app-config-path")?;
+            let data_dir_path = utils::get_app_data_path().context("getting app-data-path")?;
 
             // to not hold a mutexguard across await points
             let config_c = config.server.read().settings.podcast.clone();
 
-            podcast::import_from_opml(&config_dir_path, &config_c, &path)
+            podcast::import_from_opml(&data_dir_path, &config_c, &path)
                 .await
                 .context("import opml")?;
         }
         cli::Action::Export { file } => {
             println!("need to export to file {}", file.display());
             let path = utils::absolute_path(&file)?;
-            let config_dir_path =
-                utils::get_app_config_path().context("getting app-config-path")?;
-            podcast::export_to_opml(&config_dir_path, &path).context("export opml")?;
+            let data_dir_path = utils::get_app_data_path().context("getting app-data-path")?;
+            podcast::export_to_opml(&data_dir_path, &path).context("export opml")?;
+        }
+        cli::Action::Backup => cli_actions::backup()?,
+        cli::Action::ListBackups => cli_actions::list_backups()?,
+        cli::Action::Restore { name, yes } => cli_actions::restore_backup(&name, yes)?,
+        cli::Action::ExportState { file } => cli_actions::export_state(&file)?,
+        cli::Action::ImportState { file, yes } => cli_actions::import_state(&file, yes)?,
+        cli::Action::Enqueue { file } => {
+            let path = get_path(&file).context("enqueue cli file-path")?;
+            enqueue_url_list(&path, config)
+                .await
+                .context("enqueue url list")?;
+        }
+        cli::Action::SmartPlaylist(action) => match action {
+            cli::SmartPlaylistAction::Add { name, query } => {
+                cli_actions::smart_playlist_add(&name, &query)?;
+            }
+            cli::SmartPlaylistAction::List => cli_actions::smart_playlist_list()?,
+            cli::SmartPlaylistAction::Remove { name } => cli_actions::smart_playlist_remove(&name)?,
+        },
+        cli::Action::ListProfiles => {
+            let current = utils::get_app_profile().unwrap_or(utils::DEFAULT_PROFILE);
+            let profiles = utils::list_app_profiles().context("list profiles")?;
+            for profile in
+                std::iter::once(utils::DEFAULT_PROFILE).chain(profiles.iter().map(String::as_str))
+            {
+                let marker = if profile == current { "*" } else { " " };
+                println!("{marker} {profile}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Add the entries of the url list at `path` to the playlist of the running server, see [`url_list`].
+async fn enqueue_url_list(path: &Path, config: &CombinedSettings) -> Result<()> {
+    let items = {
+        let data_dir_path = utils::get_app_data_path().context("getting app-data-path")?;
+        let podcast_db = match podcast::db::Database::open_read_only(&data_dir_path) {
+            Ok(db) => Some(db),
+            // without a podcast database yet, no url can be a known episode
+            Err(DatabaseError::Open(..)) => None,
+            Err(err) => return Err(err).context("open podcast database"),
+        };
+        url_list::read_url_list(path, |url| {
+            podcast_db
+                .as_ref()
+                .is_some_and(|db| db.get_episode_by_url(url).is_ok())
+        })?
+    };
+
+    let download_dir = Model::get_full_path_from_config(&config.server.read());
+    let extra_args = config.tui.read().settings.ytdlp.extra_args.clone();
+    let mut sources = Vec::new();
+    for item in items {
+        match item {
+            UrlListItem::Track(source) => sources.push(source),
+            UrlListItem::Youtube(url) => {
+                println!("downloading {url}");
+                match youtube_dl_blocking(&download_dir, &url, &extra_args) {
+                    Ok(Some(file)) => sources.push(PlaylistTrackSource::Path(file)),
+                    Ok(None) => eprintln!("could not find the downloaded file of {url}"),
+                    Err(err) => eprintln!("download of {url} failed: {err:#}"),
+                }
+            }
         }
     }
 
+    if sources.is_empty() {
+        println!("nothing to enqueue");
+        return Ok(());
+    }
+
+    let pid = find_active_server_process()
+        .context("no running server found, start termusic or termusic-server first")?;
+    let (mut client, _addr) = wait_till_connected(config, pid.as_u32()).await?;
+
+    let amount = sources.len();
+    // a index past the end appends the tracks
+    let request = PlaylistTracksToAdd::from(PlaylistAddTrack::new_vec(u64::MAX, sources));
+    client
+        .add_to_playlist(tonic::Request::new(request))
+        .await
+        .context("add to playlist")?;
+    println!("enqueued {amount} tracks");
+
     Ok(())
 }
 
+/// Ask the user on stdin which profile to use, if there are any besides the default profile.
+///
+/// Only done for `--profile` without a name, so a normal start is never blocked by it.
+///
+/// Returns `None` for the default profile.
+fn choose_profile() -> Result<Option<String>> {
+    let profiles = utils::list_app_profiles().context("list profiles")?;
+    if profiles.is_empty() {
+        return Ok(None);
+    }
+
+    println!("0) {}", utils::DEFAULT_PROFILE);
+    for (idx, profile) in profiles.iter().enumerate() {
+        println!("{}) {profile}", idx + 1);
+    }
+
+    loop {
+        print!("Select a profile [0]: ");
+        std::io::Write::flush(&mut std::io::stdout())?;
+
+        let mut answer = String::new();
+        if std::io::stdin().read_line(&mut answer)? == 0 {
+            // no input available, like when not started from a terminal
+            return Ok(None);
+        }
+        match parse_profile_choice(&profiles, answer.trim()) {
+            Some(choice) => return Ok(choice.map(ToString::to_string)),
+            None => println!("Unknown profile \"{}\"", answer.trim()),
+        }
+    }
+}
+
+/// Get the profile selected by `answer`, being its number or name.
+///
+/// Returns `Some(None)` for the default profile and `None` if `answer` does not select any profile.
+fn parse_profile_choice<'a>(profiles: &'a [String], answer: &'a str) -> Option<Option<&'a str>> {
+    if answer.is_empty() || answer == "0" || answer == utils::DEFAULT_PROFILE {
+        return Some(None);
+    }
+
+    if let Ok(number) = answer.parse::<usize>() {
+        return profiles
+            .get(number.checked_sub(1)?)
+            .map(|v| Some(v.as_str()));
+    }
+
+    profiles
+        .iter()
+        .find(|v| *v == answer)
+        .map(|v| Some(v.as_str()))
+}
+
 /// Determines if the CTRL+C Handler may need to clean-up the terminal mode
 static TERMINAL_ALTERNATE_MODE: AtomicBool = AtomicBool::new(false);
 
@@ -573,7 +798,7 @@ fn ctrl_c_handler() -> Result<()> {
 
         // Reset the terminal mode so that the user does not have to use "reset"
         if TERMINAL_ALTERNATE_MODE.load(Ordering::SeqCst) {
-            ui::model::Model::hook_reset_terminal();
+            Model::hook_reset_terminal();
         }
 
         std::process::exit(-1);
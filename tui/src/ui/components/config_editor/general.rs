@@ -23,13 +23,17 @@
  */
 use anyhow::Result;
 use termusiclib::config::SharedTuiSettings;
+use termusiclib::config::v2::server::metadata::ScanPolicy;
 use termusiclib::config::v2::server::{Backend, ComProtocol, default_uds_socket_path};
 use termusiclib::config::v2::tui::{Alignment as XywhAlign, keys::Keys};
 use tui_realm_stdlib::Radio;
-use tuirealm::props::{Alignment, BorderType, Borders, Color, InputType, Style};
+use tuirealm::props::{
+    Alignment, BorderType, Borders, Color, InputType, PropPayload, PropValue, Style,
+};
+use tuirealm::ratatui::layout::Rect;
 use tuirealm::{
-    Component, Event, MockComponent,
-    command::{Cmd, Direction, Position},
+    AttrValue, Attribute, Component, Event, Frame, MockComponent, State, StateValue,
+    command::{Cmd, CmdResult, Direction, Position},
     event::{Key, KeyEvent},
 };
 
@@ -826,6 +830,51 @@ impl Component<Msg, UserEvent> for PlayerUseDiscord {
     }
 }
 
+#[derive(MockComponent)]
+pub struct PlayerCrossfade {
+    component: Radio,
+    config: SharedTuiSettings,
+}
+
+impl PlayerCrossfade {
+    pub fn new(config: CombinedSettings) -> Self {
+        let config_tui = config.tui.read();
+        let crossfade = config.server.read().settings.player.crossfade;
+        let component = Radio::default()
+            .borders(
+                Borders::default()
+                    .color(config_tui.settings.theme.library_border())
+                    .modifiers(BorderType::Rounded),
+            )
+            .choices(["Yes", "No"])
+            .foreground(config_tui.settings.theme.library_highlight())
+            .rewind(true)
+            .title(
+                format!(" Crossfade tracks ({}s)? ", crossfade.secs),
+                Alignment::Left,
+            )
+            .value(usize::from(!crossfade.enabled));
+
+        drop(config_tui);
+        Self {
+            component,
+            config: config.tui,
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for PlayerCrossfade {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        handle_radio_ev(
+            &mut self.component,
+            ev,
+            &self.config.read().settings.keys,
+            Msg::ConfigEditor(ConfigEditorMsg::General(KFMsg::Next)),
+            Msg::ConfigEditor(ConfigEditorMsg::General(KFMsg::Previous)),
+        )
+    }
+}
+
 #[derive(MockComponent)]
 pub struct PlayerPort {
     component: Input,
@@ -1032,6 +1081,7 @@ impl PlayerBackend {
             Backend::Rusty => 0,
             Backend::Mpv => 1,
             Backend::Gstreamer => 2,
+            Backend::Null => 3,
         };
         let component = Radio::default()
             .borders(
@@ -1039,7 +1089,7 @@ impl PlayerBackend {
                     .color(config_tui.settings.theme.library_border())
                     .modifiers(BorderType::Rounded),
             )
-            .choices(["Rusty", "MPV", "Gstreamer"])
+            .choices(["Rusty", "MPV", "Gstreamer", "Null"])
             .foreground(config_tui.settings.theme.library_highlight())
             .rewind(true)
             .title(" Playback Backend: ", Alignment::Left)
@@ -1065,6 +1115,206 @@ impl Component<Msg, UserEvent> for PlayerBackend {
     }
 }
 
+/// Select the output device of the rusty backend.
+///
+/// Only the selected device is shown, as device names are too long to show all of them at once.
+pub struct PlayerOutputDevice {
+    component: Radio,
+    config: SharedTuiSettings,
+    /// All selectable devices, `None` being the default device
+    devices: Vec<Option<String>>,
+    selected: usize,
+}
+
+impl PlayerOutputDevice {
+    pub fn new(config: CombinedSettings) -> Self {
+        let config_tui = config.tui.read();
+        let current = config
+            .server
+            .read()
+            .settings
+            .backends
+            .rusty
+            .output_device
+            .clone();
+        let component = Radio::default()
+            .borders(
+                Borders::default()
+                    .color(config_tui.settings.theme.library_border())
+                    .modifiers(BorderType::Rounded),
+            )
+            .foreground(config_tui.settings.theme.library_highlight())
+            .title(" Output Device (rusty backend): ", Alignment::Left)
+            .value(0);
+
+        drop(config_tui);
+        let mut this = Self {
+            component,
+            config: config.tui,
+            devices: vec![current],
+            selected: 0,
+        };
+        // the actual devices are only known once the server responded
+        this.set_devices(Vec::new());
+
+        this
+    }
+
+    /// Set the selectable devices, keeping the current selection even if it is not listed.
+    fn set_devices(&mut self, names: Vec<String>) {
+        let selected = self.devices[self.selected].clone();
+
+        self.devices = std::iter::once(None)
+            .chain(names.into_iter().map(Some))
+            .collect();
+        self.selected = match self.devices.iter().position(|v| *v == selected) {
+            Some(idx) => idx,
+            None => {
+                self.devices.push(selected);
+                self.devices.len() - 1
+            }
+        };
+
+        self.update_label();
+    }
+
+    /// Select the next or previous device, wrapping around at the ends.
+    fn cycle(&mut self, forward: bool) {
+        let len = self.devices.len();
+        self.selected = if forward {
+            (self.selected + 1) % len
+        } else {
+            (self.selected + len - 1) % len
+        };
+
+        self.update_label();
+    }
+
+    fn update_label(&mut self) {
+        let label = format!(
+            "{} ({}/{})",
+            self.devices[self.selected].as_deref().unwrap_or("Default"),
+            self.selected + 1,
+            self.devices.len()
+        );
+        self.component.attr(
+            Attribute::Content,
+            AttrValue::Payload(PropPayload::Vec(vec![PropValue::Str(label)])),
+        );
+    }
+}
+
+impl MockComponent for PlayerOutputDevice {
+    fn view(&mut self, frame: &mut Frame<'_>, area: Rect) {
+        self.component.view(frame, area);
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.component.query(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        match (attr, value) {
+            (Attribute::Content, AttrValue::Payload(PropPayload::Vec(names))) => {
+                let names = names
+                    .into_iter()
+                    .filter_map(|v| match v {
+                        PropValue::Str(name) => Some(name),
+                        _ => None,
+                    })
+                    .collect();
+                self.set_devices(names);
+            }
+            (attr, value) => self.component.attr(attr, value),
+        }
+    }
+
+    /// [`State::None`] for the default device, otherwise the name of the device
+    fn state(&self) -> State {
+        match &self.devices[self.selected] {
+            Some(name) => State::One(StateValue::String(name.clone())),
+            None => State::None,
+        }
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        self.component.perform(cmd)
+    }
+}
+
+impl Component<Msg, UserEvent> for PlayerOutputDevice {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => {
+                self.cycle(false);
+                Some(Msg::ConfigEditor(ConfigEditorMsg::ConfigChanged))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.cycle(true);
+                Some(Msg::ConfigEditor(ConfigEditorMsg::ConfigChanged))
+            }
+            ev => handle_radio_ev(
+                &mut self.component,
+                ev,
+                &self.config.read().settings.keys,
+                Msg::ConfigEditor(ConfigEditorMsg::General(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::General(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct LibraryScanPolicy {
+    component: Radio,
+    config: SharedTuiSettings,
+}
+
+impl LibraryScanPolicy {
+    pub fn new(config: CombinedSettings) -> Self {
+        let config_tui = config.tui.read();
+        let value = match config.server.read().settings.metadata.scan_policy {
+            ScanPolicy::Startup => 0,
+            ScanPolicy::Manual => 1,
+            ScanPolicy::Interval => 2,
+            ScanPolicy::Watch => 3,
+        };
+        let component = Radio::default()
+            .borders(
+                Borders::default()
+                    .color(config_tui.settings.theme.library_border())
+                    .modifiers(BorderType::Rounded),
+            )
+            .choices(["Startup", "Manual", "Interval", "Watch"])
+            .foreground(config_tui.settings.theme.library_highlight())
+            .rewind(true)
+            .title(" Library Scan: ", Alignment::Left)
+            .value(value);
+
+        drop(config_tui);
+        Self {
+            component,
+            config: config.tui,
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for LibraryScanPolicy {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        handle_radio_ev(
+            &mut self.component,
+            ev,
+            &self.config.read().settings.keys,
+            Msg::ConfigEditor(ConfigEditorMsg::General(KFMsg::Next)),
+            Msg::ConfigEditor(ConfigEditorMsg::General(KFMsg::Previous)),
+        )
+    }
+}
+
 #[derive(MockComponent)]
 pub struct ExtraYtdlpArgs {
     component: Input,
@@ -1231,15 +1481,50 @@ impl Model {
             Vec::new(),
         )?;
 
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::General(IdCEGeneral::PlayerOutputDevice)),
+            Box::new(PlayerOutputDevice::new(self.get_combined_settings())),
+            Vec::new(),
+        )?;
+
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::General(IdCEGeneral::PlayerCrossfade)),
+            Box::new(PlayerCrossfade::new(self.get_combined_settings())),
+            Vec::new(),
+        )?;
+
         self.app.remount(
             Id::ConfigEditor(IdConfigEditor::General(IdCEGeneral::ExtraYtdlpArgs)),
             Box::new(ExtraYtdlpArgs::new(self.get_combined_settings())),
             Vec::new(),
         )?;
 
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::General(IdCEGeneral::LibraryScanPolicy)),
+            Box::new(LibraryScanPolicy::new(self.get_combined_settings())),
+            Vec::new(),
+        )?;
+
         Ok(())
     }
 
+    /// Set the output devices the server responded with, if the General Options are still mounted.
+    pub fn config_editor_set_output_devices(&mut self, devices: Vec<String>) {
+        let id = Id::ConfigEditor(IdConfigEditor::General(IdCEGeneral::PlayerOutputDevice));
+        if !self.app.mounted(&id) {
+            return;
+        }
+
+        let devices = devices.into_iter().map(PropValue::Str).collect();
+        self.app
+            .attr(
+                &id,
+                Attribute::Content,
+                AttrValue::Payload(PropPayload::Vec(devices)),
+            )
+            .ok();
+    }
+
     /// Unmount the Config-Editor's First Page, the General Options
     pub(super) fn umount_config_general(&mut self) -> Result<()> {
         self.app.umount(&Id::ConfigEditor(IdConfigEditor::General(
@@ -1314,10 +1599,22 @@ impl Model {
             IdCEGeneral::PlayerBackend,
         )))?;
 
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::General(
+            IdCEGeneral::PlayerOutputDevice,
+        )))?;
+
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::General(
+            IdCEGeneral::PlayerCrossfade,
+        )))?;
+
         self.app.umount(&Id::ConfigEditor(IdConfigEditor::General(
             IdCEGeneral::ExtraYtdlpArgs,
         )))?;
 
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::General(
+            IdCEGeneral::LibraryScanPolicy,
+        )))?;
+
         Ok(())
     }
 }
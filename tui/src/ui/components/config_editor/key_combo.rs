@@ -994,11 +994,18 @@ impl KEModifierSelect {
             IdKey::Other(IdKeyOther::DatabaseAddSelected) => {
                 keys.database_keys.add_selected.mod_key()
             }
+            IdKey::Other(IdKeyOther::DatabaseMaintenance) => {
+                keys.database_keys.maintenance.mod_key()
+            }
+            IdKey::Other(IdKeyOther::DatabasePreview) => keys.database_keys.preview.mod_key(),
             IdKey::Global(IdKeyGlobal::Config) => keys.select_view_keys.open_config.mod_key(),
             IdKey::Global(IdKeyGlobal::Down) => keys.navigation_keys.down.mod_key(),
             IdKey::Global(IdKeyGlobal::GotoBottom) => keys.navigation_keys.goto_bottom.mod_key(),
             IdKey::Global(IdKeyGlobal::GotoTop) => keys.navigation_keys.goto_top.mod_key(),
             IdKey::Global(IdKeyGlobal::Help) => keys.select_view_keys.open_help.mod_key(),
+            IdKey::Global(IdKeyGlobal::ErrorHistory) => {
+                keys.select_view_keys.open_error_history.mod_key()
+            }
             IdKey::Global(IdKeyGlobal::LayoutTreeview) => {
                 keys.select_view_keys.view_library.mod_key()
             }
@@ -1013,6 +1020,8 @@ impl KEModifierSelect {
                 keys.lyric_keys.adjust_offset_backwards.mod_key()
             }
             IdKey::Global(IdKeyGlobal::LyricCycle) => keys.lyric_keys.cycle_frames.mod_key(),
+            IdKey::Global(IdKeyGlobal::LyricCapture) => keys.lyric_keys.capture_caption.mod_key(),
+            IdKey::Global(IdKeyGlobal::LyricEditor) => keys.lyric_keys.open_editor.mod_key(),
             IdKey::Global(IdKeyGlobal::PlayerToggleGapless) => {
                 keys.player_keys.toggle_prefetch.mod_key()
             }
@@ -1029,12 +1038,25 @@ impl KEModifierSelect {
             }
             IdKey::Global(IdKeyGlobal::PlayerSpeedUp) => keys.player_keys.speed_up.mod_key(),
             IdKey::Global(IdKeyGlobal::PlayerSpeedDown) => keys.player_keys.speed_down.mod_key(),
+            IdKey::Global(IdKeyGlobal::PlayerTrackSpeed) => keys.player_keys.track_speed.mod_key(),
+            IdKey::Global(IdKeyGlobal::PlayerAbRepeat) => keys.player_keys.ab_repeat.mod_key(),
+            IdKey::Global(IdKeyGlobal::PlayerBookmarks) => keys.player_keys.bookmarks.mod_key(),
             IdKey::Global(IdKeyGlobal::Quit) => keys.quit.mod_key(),
             IdKey::Global(IdKeyGlobal::Right) => keys.navigation_keys.right.mod_key(),
             IdKey::Global(IdKeyGlobal::Up) => keys.navigation_keys.up.mod_key(),
             IdKey::Global(IdKeyGlobal::PlayerVolumeDown) => keys.player_keys.volume_down.mod_key(),
             IdKey::Global(IdKeyGlobal::PlayerVolumeUp) => keys.player_keys.volume_up.mod_key(),
             IdKey::Global(IdKeyGlobal::SavePlaylist) => keys.player_keys.save_playlist.mod_key(),
+            IdKey::Global(IdKeyGlobal::PlayerChapters) => keys.player_keys.chapters.mod_key(),
+            IdKey::Global(IdKeyGlobal::PlayerNextChapter) => {
+                keys.player_keys.next_chapter.mod_key()
+            }
+            IdKey::Global(IdKeyGlobal::PlayerPreviousChapter) => {
+                keys.player_keys.previous_chapter.mod_key()
+            }
+            IdKey::Global(IdKeyGlobal::PlayerGotoTime) => keys.player_keys.goto_time.mod_key(),
+            IdKey::Global(IdKeyGlobal::PlayerCast) => keys.player_keys.cast.mod_key(),
+            IdKey::Global(IdKeyGlobal::PlayerAudioInfo) => keys.player_keys.audio_info.mod_key(),
             IdKey::Other(IdKeyOther::LibraryDelete) => keys.library_keys.delete.mod_key(),
             IdKey::Other(IdKeyOther::LibraryLoadDir) => keys.library_keys.load_dir.mod_key(),
             IdKey::Other(IdKeyOther::LibraryPaste) => keys.library_keys.paste.mod_key(),
@@ -1045,6 +1067,18 @@ impl KEModifierSelect {
             IdKey::Other(IdKeyOther::LibraryTagEditor) => {
                 keys.library_keys.open_tag_editor.mod_key()
             }
+            IdKey::Other(IdKeyOther::LibraryTrackMenu) => keys.library_keys.track_menu.mod_key(),
+            IdKey::Other(IdKeyOther::LibraryCycleRating) => {
+                keys.library_keys.cycle_rating.mod_key()
+            }
+            IdKey::Other(IdKeyOther::LibraryToggleFavorite) => {
+                keys.library_keys.toggle_favorite.mod_key()
+            }
+            IdKey::Other(IdKeyOther::LibraryMark) => keys.library_keys.mark.mod_key(),
+            IdKey::Other(IdKeyOther::LibraryOrganize) => keys.library_keys.organize.mod_key(),
+            IdKey::Other(IdKeyOther::LibraryToggleAudiobook) => {
+                keys.library_keys.toggle_audiobook.mod_key()
+            }
             IdKey::Other(IdKeyOther::LibraryYank) => keys.library_keys.yank.mod_key(),
             IdKey::Other(IdKeyOther::PlaylistDelete) => keys.playlist_keys.delete.mod_key(),
             IdKey::Other(IdKeyOther::PlaylistDeleteAll) => keys.playlist_keys.delete_all.mod_key(),
@@ -1064,12 +1098,37 @@ impl KEModifierSelect {
             IdKey::Other(IdKeyOther::PlaylistAddRandomTracks) => {
                 keys.playlist_keys.add_random_songs.mod_key()
             }
+            IdKey::Other(IdKeyOther::PlaylistExport) => keys.playlist_keys.export.mod_key(),
+            IdKey::Other(IdKeyOther::PlaylistImport) => keys.playlist_keys.import.mod_key(),
+            IdKey::Other(IdKeyOther::PlaylistTrackMenu) => keys.playlist_keys.track_menu.mod_key(),
+            IdKey::Other(IdKeyOther::PlaylistSaveQueue) => keys.playlist_keys.save_queue.mod_key(),
+            IdKey::Other(IdKeyOther::PlaylistRestoreQueue) => {
+                keys.playlist_keys.restore_queue.mod_key()
+            }
+            IdKey::Other(IdKeyOther::PlaylistToggleAutoQueue) => {
+                keys.playlist_keys.toggle_auto_queue.mod_key()
+            }
+            IdKey::Other(IdKeyOther::PlaylistQueues) => keys.playlist_keys.queues.mod_key(),
+            IdKey::Other(IdKeyOther::PlaylistCycleRating) => {
+                keys.playlist_keys.cycle_rating.mod_key()
+            }
+            IdKey::Other(IdKeyOther::PlaylistToggleFavorite) => {
+                keys.playlist_keys.toggle_favorite.mod_key()
+            }
             IdKey::Other(IdKeyOther::LibrarySwitchRoot) => keys.library_keys.cycle_root.mod_key(),
             IdKey::Other(IdKeyOther::LibraryAddRoot) => keys.library_keys.add_root.mod_key(),
             IdKey::Other(IdKeyOther::LibraryRemoveRoot) => keys.library_keys.remove_root.mod_key(),
+            IdKey::Other(IdKeyOther::LibraryRescan) => keys.library_keys.rescan.mod_key(),
+            IdKey::Other(IdKeyOther::LibraryCycleFilter) => {
+                keys.library_keys.cycle_filter.mod_key()
+            }
+            IdKey::Other(IdKeyOther::LibraryOpenExternal) => {
+                keys.library_keys.open_external.mod_key()
+            }
             IdKey::Global(IdKeyGlobal::LayoutPodcast) => {
                 keys.select_view_keys.view_podcasts.mod_key()
             }
+            IdKey::Global(IdKeyGlobal::LayoutRadio) => keys.select_view_keys.view_radio.mod_key(),
             IdKey::Global(IdKeyGlobal::XywhMoveLeft) => {
                 keys.move_cover_art_keys.move_left.mod_key()
             }
@@ -1091,9 +1150,13 @@ impl KEModifierSelect {
             IdKey::Other(IdKeyOther::PodcastMarkAllPlayed) => {
                 keys.podcast_keys.mark_all_played.mod_key()
             }
+            IdKey::Other(IdKeyOther::PodcastEpStream) => keys.podcast_keys.stream_episode.mod_key(),
             IdKey::Other(IdKeyOther::PodcastEpDownload) => {
                 keys.podcast_keys.download_episode.mod_key()
             }
+            IdKey::Other(IdKeyOther::PodcastEpDownloadAllUnplayed) => {
+                keys.podcast_keys.download_all_unplayed.mod_key()
+            }
             IdKey::Other(IdKeyOther::PodcastEpDeleteFile) => {
                 keys.podcast_keys.delete_local_episode.mod_key()
             }
@@ -1108,6 +1171,24 @@ impl KEModifierSelect {
             IdKey::Other(IdKeyOther::PodcastRefreshAllFeeds) => {
                 keys.podcast_keys.refresh_all_feeds.mod_key()
             }
+            IdKey::Other(IdKeyOther::PodcastCycleFeedSort) => {
+                keys.podcast_keys.cycle_feed_sort.mod_key()
+            }
+            IdKey::Other(IdKeyOther::PodcastCycleCategory) => {
+                keys.podcast_keys.cycle_category.mod_key()
+            }
+            IdKey::Other(IdKeyOther::PodcastEpisodeMenu) => {
+                keys.podcast_keys.episode_menu.mod_key()
+            }
+            IdKey::Other(IdKeyOther::PodcastFeedSettings) => {
+                keys.podcast_keys.feed_settings.mod_key()
+            }
+            IdKey::Other(IdKeyOther::PodcastFeedInfo) => keys.podcast_keys.feed_info.mod_key(),
+            IdKey::Other(IdKeyOther::PodcastMergeFeed) => keys.podcast_keys.merge_feed.mod_key(),
+            IdKey::Other(IdKeyOther::RadioSearch) => keys.radio_keys.search.mod_key(),
+            IdKey::Other(IdKeyOther::RadioToggleFavorite) => {
+                keys.radio_keys.toggle_favorite.mod_key()
+            }
         };
 
         (MyModifiers::from_modifier_list_index(mod_key.0), mod_key.1)
@@ -1569,6 +1650,32 @@ impl Component<Msg, UserEvent> for ConfigGlobalHelp {
         self.component.on(ev)
     }
 }
+
+#[derive(MockComponent)]
+pub struct ConfigGlobalErrorHistory {
+    component: KEModifierSelect,
+}
+
+impl ConfigGlobalErrorHistory {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Recent errors ",
+                IdKey::Global(IdKeyGlobal::ErrorHistory),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigGlobalErrorHistory {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
 #[derive(MockComponent)]
 pub struct ConfigGlobalVolumeUp {
     component: KEModifierSelect,
@@ -1719,6 +1826,81 @@ impl Component<Msg, UserEvent> for ConfigGlobalPlayerSpeedDown {
     }
 }
 
+#[derive(MockComponent)]
+pub struct ConfigGlobalPlayerTrackSpeed {
+    component: KEModifierSelect,
+}
+
+impl ConfigGlobalPlayerTrackSpeed {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Keep Speed For Track ",
+                IdKey::Global(IdKeyGlobal::PlayerTrackSpeed),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigGlobalPlayerTrackSpeed {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigGlobalPlayerAbRepeat {
+    component: KEModifierSelect,
+}
+
+impl ConfigGlobalPlayerAbRepeat {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " A-B Repeat ",
+                IdKey::Global(IdKeyGlobal::PlayerAbRepeat),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigGlobalPlayerAbRepeat {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigGlobalPlayerBookmarks {
+    component: KEModifierSelect,
+}
+
+impl ConfigGlobalPlayerBookmarks {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Bookmarks ",
+                IdKey::Global(IdKeyGlobal::PlayerBookmarks),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigGlobalPlayerBookmarks {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
 #[derive(MockComponent)]
 pub struct ConfigGlobalLyricAdjustForward {
     component: KEModifierSelect,
@@ -1794,6 +1976,56 @@ impl Component<Msg, UserEvent> for ConfigGlobalLyricCycle {
     }
 }
 
+#[derive(MockComponent)]
+pub struct ConfigGlobalLyricCapture {
+    component: KEModifierSelect,
+}
+
+impl ConfigGlobalLyricCapture {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Lyric Capture ",
+                IdKey::Global(IdKeyGlobal::LyricCapture),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigGlobalLyricCapture {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigGlobalLyricEditor {
+    component: KEModifierSelect,
+}
+
+impl ConfigGlobalLyricEditor {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Lyric Editor ",
+                IdKey::Global(IdKeyGlobal::LyricEditor),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigGlobalLyricEditor {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
 #[derive(MockComponent)]
 pub struct ConfigGlobalLayoutTreeview {
     component: KEModifierSelect,
@@ -2045,16 +2277,16 @@ impl Component<Msg, UserEvent> for ConfigLibraryTagEditor {
 }
 
 #[derive(MockComponent)]
-pub struct ConfigPlaylistDelete {
+pub struct ConfigLibraryTrackMenu {
     component: KEModifierSelect,
 }
 
-impl ConfigPlaylistDelete {
+impl ConfigLibraryTrackMenu {
     pub fn new(config: SharedTuiSettings) -> Self {
         Self {
             component: KEModifierSelect::new(
-                " Playlist Delete ",
-                IdKey::Other(IdKeyOther::PlaylistDelete),
+                " Library track menu ",
+                IdKey::Other(IdKeyOther::LibraryTrackMenu),
                 config,
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
@@ -2063,23 +2295,23 @@ impl ConfigPlaylistDelete {
     }
 }
 
-impl Component<Msg, UserEvent> for ConfigPlaylistDelete {
+impl Component<Msg, UserEvent> for ConfigLibraryTrackMenu {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
         self.component.on(ev)
     }
 }
 
 #[derive(MockComponent)]
-pub struct ConfigPlaylistDeleteAll {
+pub struct ConfigLibraryCycleRating {
     component: KEModifierSelect,
 }
 
-impl ConfigPlaylistDeleteAll {
+impl ConfigLibraryCycleRating {
     pub fn new(config: SharedTuiSettings) -> Self {
         Self {
             component: KEModifierSelect::new(
-                " Playlist Delete All ",
-                IdKey::Other(IdKeyOther::PlaylistDeleteAll),
+                " Library cycle rating ",
+                IdKey::Other(IdKeyOther::LibraryCycleRating),
                 config,
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
@@ -2088,23 +2320,23 @@ impl ConfigPlaylistDeleteAll {
     }
 }
 
-impl Component<Msg, UserEvent> for ConfigPlaylistDeleteAll {
+impl Component<Msg, UserEvent> for ConfigLibraryCycleRating {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
         self.component.on(ev)
     }
 }
 
 #[derive(MockComponent)]
-pub struct ConfigPlaylistShuffle {
+pub struct ConfigLibraryToggleFavorite {
     component: KEModifierSelect,
 }
 
-impl ConfigPlaylistShuffle {
+impl ConfigLibraryToggleFavorite {
     pub fn new(config: SharedTuiSettings) -> Self {
         Self {
             component: KEModifierSelect::new(
-                " Playlist Shuffle ",
-                IdKey::Other(IdKeyOther::PlaylistShuffle),
+                " Library toggle favorite ",
+                IdKey::Other(IdKeyOther::LibraryToggleFavorite),
                 config,
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
@@ -2113,23 +2345,23 @@ impl ConfigPlaylistShuffle {
     }
 }
 
-impl Component<Msg, UserEvent> for ConfigPlaylistShuffle {
+impl Component<Msg, UserEvent> for ConfigLibraryToggleFavorite {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
         self.component.on(ev)
     }
 }
 
 #[derive(MockComponent)]
-pub struct ConfigPlaylistModeCycle {
+pub struct ConfigLibraryMark {
     component: KEModifierSelect,
 }
 
-impl ConfigPlaylistModeCycle {
+impl ConfigLibraryMark {
     pub fn new(config: SharedTuiSettings) -> Self {
         Self {
             component: KEModifierSelect::new(
-                " Playlist Mode Cycle ",
-                IdKey::Other(IdKeyOther::PlaylistModeCycle),
+                " Library mark for tag editor ",
+                IdKey::Other(IdKeyOther::LibraryMark),
                 config,
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
@@ -2138,23 +2370,23 @@ impl ConfigPlaylistModeCycle {
     }
 }
 
-impl Component<Msg, UserEvent> for ConfigPlaylistModeCycle {
+impl Component<Msg, UserEvent> for ConfigLibraryMark {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
         self.component.on(ev)
     }
 }
 
 #[derive(MockComponent)]
-pub struct ConfigPlaylistPlaySelected {
+pub struct ConfigLibraryOrganize {
     component: KEModifierSelect,
 }
 
-impl ConfigPlaylistPlaySelected {
+impl ConfigLibraryOrganize {
     pub fn new(config: SharedTuiSettings) -> Self {
         Self {
             component: KEModifierSelect::new(
-                " Playlist Play Selected ",
-                IdKey::Other(IdKeyOther::PlaylistPlaySelected),
+                " Library organize files ",
+                IdKey::Other(IdKeyOther::LibraryOrganize),
                 config,
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
@@ -2163,23 +2395,23 @@ impl ConfigPlaylistPlaySelected {
     }
 }
 
-impl Component<Msg, UserEvent> for ConfigPlaylistPlaySelected {
+impl Component<Msg, UserEvent> for ConfigLibraryOrganize {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
         self.component.on(ev)
     }
 }
 
 #[derive(MockComponent)]
-pub struct ConfigPlaylistSearch {
+pub struct ConfigLibraryToggleAudiobook {
     component: KEModifierSelect,
 }
 
-impl ConfigPlaylistSearch {
+impl ConfigLibraryToggleAudiobook {
     pub fn new(config: SharedTuiSettings) -> Self {
         Self {
             component: KEModifierSelect::new(
-                " Playlist Search ",
-                IdKey::Other(IdKeyOther::PlaylistSearch),
+                " Library toggle audiobook ",
+                IdKey::Other(IdKeyOther::LibraryToggleAudiobook),
                 config,
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
@@ -2188,23 +2420,23 @@ impl ConfigPlaylistSearch {
     }
 }
 
-impl Component<Msg, UserEvent> for ConfigPlaylistSearch {
+impl Component<Msg, UserEvent> for ConfigLibraryToggleAudiobook {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
         self.component.on(ev)
     }
 }
 
 #[derive(MockComponent)]
-pub struct ConfigPlaylistSwapDown {
+pub struct ConfigPlaylistDelete {
     component: KEModifierSelect,
 }
 
-impl ConfigPlaylistSwapDown {
+impl ConfigPlaylistDelete {
     pub fn new(config: SharedTuiSettings) -> Self {
         Self {
             component: KEModifierSelect::new(
-                " Playlist Swap Down ",
-                IdKey::Other(IdKeyOther::PlaylistSwapDown),
+                " Playlist Delete ",
+                IdKey::Other(IdKeyOther::PlaylistDelete),
                 config,
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
@@ -2213,23 +2445,23 @@ impl ConfigPlaylistSwapDown {
     }
 }
 
-impl Component<Msg, UserEvent> for ConfigPlaylistSwapDown {
+impl Component<Msg, UserEvent> for ConfigPlaylistDelete {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
         self.component.on(ev)
     }
 }
 
 #[derive(MockComponent)]
-pub struct ConfigPlaylistSwapUp {
+pub struct ConfigPlaylistDeleteAll {
     component: KEModifierSelect,
 }
 
-impl ConfigPlaylistSwapUp {
+impl ConfigPlaylistDeleteAll {
     pub fn new(config: SharedTuiSettings) -> Self {
         Self {
             component: KEModifierSelect::new(
-                " Playlist Swap Up ",
-                IdKey::Other(IdKeyOther::PlaylistSwapUp),
+                " Playlist Delete All ",
+                IdKey::Other(IdKeyOther::PlaylistDeleteAll),
                 config,
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
@@ -2238,23 +2470,23 @@ impl ConfigPlaylistSwapUp {
     }
 }
 
-impl Component<Msg, UserEvent> for ConfigPlaylistSwapUp {
+impl Component<Msg, UserEvent> for ConfigPlaylistDeleteAll {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
         self.component.on(ev)
     }
 }
 
 #[derive(MockComponent)]
-pub struct ConfigDatabaseAddAll {
+pub struct ConfigPlaylistShuffle {
     component: KEModifierSelect,
 }
 
-impl ConfigDatabaseAddAll {
+impl ConfigPlaylistShuffle {
     pub fn new(config: SharedTuiSettings) -> Self {
         Self {
             component: KEModifierSelect::new(
-                " Database Add All ",
-                IdKey::Other(IdKeyOther::DatabaseAddAll),
+                " Playlist Shuffle ",
+                IdKey::Other(IdKeyOther::PlaylistShuffle),
                 config,
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
@@ -2263,23 +2495,23 @@ impl ConfigDatabaseAddAll {
     }
 }
 
-impl Component<Msg, UserEvent> for ConfigDatabaseAddAll {
+impl Component<Msg, UserEvent> for ConfigPlaylistShuffle {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
         self.component.on(ev)
     }
 }
 
 #[derive(MockComponent)]
-pub struct ConfigDatabaseAddSelected {
+pub struct ConfigPlaylistModeCycle {
     component: KEModifierSelect,
 }
 
-impl ConfigDatabaseAddSelected {
+impl ConfigPlaylistModeCycle {
     pub fn new(config: SharedTuiSettings) -> Self {
         Self {
             component: KEModifierSelect::new(
-                " Database Add Selected ",
-                IdKey::Other(IdKeyOther::DatabaseAddSelected),
+                " Playlist Mode Cycle ",
+                IdKey::Other(IdKeyOther::PlaylistModeCycle),
                 config,
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
@@ -2288,48 +2520,48 @@ impl ConfigDatabaseAddSelected {
     }
 }
 
-impl Component<Msg, UserEvent> for ConfigDatabaseAddSelected {
+impl Component<Msg, UserEvent> for ConfigPlaylistModeCycle {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
         self.component.on(ev)
     }
 }
 
 #[derive(MockComponent)]
-pub struct ConfigGlobalConfig {
+pub struct ConfigPlaylistPlaySelected {
     component: KEModifierSelect,
 }
 
-impl ConfigGlobalConfig {
+impl ConfigPlaylistPlaySelected {
     pub fn new(config: SharedTuiSettings) -> Self {
         Self {
             component: KEModifierSelect::new(
-                " Config Editor ",
-                IdKey::Global(IdKeyGlobal::Config),
+                " Playlist Play Selected ",
+                IdKey::Other(IdKeyOther::PlaylistPlaySelected),
                 config,
-                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Next)),
-                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Previous)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
             ),
         }
     }
 }
 
-impl Component<Msg, UserEvent> for ConfigGlobalConfig {
+impl Component<Msg, UserEvent> for ConfigPlaylistPlaySelected {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
         self.component.on(ev)
     }
 }
 
 #[derive(MockComponent)]
-pub struct ConfigPlaylistAddRandomAlbum {
+pub struct ConfigPlaylistSearch {
     component: KEModifierSelect,
 }
 
-impl ConfigPlaylistAddRandomAlbum {
+impl ConfigPlaylistSearch {
     pub fn new(config: SharedTuiSettings) -> Self {
         Self {
             component: KEModifierSelect::new(
-                " Playlist Select Album ",
-                IdKey::Other(IdKeyOther::PlaylistAddRandomAlbum),
+                " Playlist Search ",
+                IdKey::Other(IdKeyOther::PlaylistSearch),
                 config,
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
@@ -2338,23 +2570,23 @@ impl ConfigPlaylistAddRandomAlbum {
     }
 }
 
-impl Component<Msg, UserEvent> for ConfigPlaylistAddRandomAlbum {
+impl Component<Msg, UserEvent> for ConfigPlaylistSearch {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
         self.component.on(ev)
     }
 }
 
 #[derive(MockComponent)]
-pub struct ConfigPlaylistAddRandomTracks {
+pub struct ConfigPlaylistSwapDown {
     component: KEModifierSelect,
 }
 
-impl ConfigPlaylistAddRandomTracks {
+impl ConfigPlaylistSwapDown {
     pub fn new(config: SharedTuiSettings) -> Self {
         Self {
             component: KEModifierSelect::new(
-                " Playlist Select Tracks ",
-                IdKey::Other(IdKeyOther::PlaylistAddRandomTracks),
+                " Playlist Swap Down ",
+                IdKey::Other(IdKeyOther::PlaylistSwapDown),
                 config,
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
@@ -2363,23 +2595,23 @@ impl ConfigPlaylistAddRandomTracks {
     }
 }
 
-impl Component<Msg, UserEvent> for ConfigPlaylistAddRandomTracks {
+impl Component<Msg, UserEvent> for ConfigPlaylistSwapDown {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
         self.component.on(ev)
     }
 }
 
 #[derive(MockComponent)]
-pub struct ConfigLibrarySwitchRoot {
+pub struct ConfigPlaylistSwapUp {
     component: KEModifierSelect,
 }
 
-impl ConfigLibrarySwitchRoot {
+impl ConfigPlaylistSwapUp {
     pub fn new(config: SharedTuiSettings) -> Self {
         Self {
             component: KEModifierSelect::new(
-                " Library Switch Root ",
-                IdKey::Other(IdKeyOther::LibrarySwitchRoot),
+                " Playlist Swap Up ",
+                IdKey::Other(IdKeyOther::PlaylistSwapUp),
                 config,
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
@@ -2388,14 +2620,439 @@ impl ConfigLibrarySwitchRoot {
     }
 }
 
-impl Component<Msg, UserEvent> for ConfigLibrarySwitchRoot {
+impl Component<Msg, UserEvent> for ConfigPlaylistSwapUp {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
         self.component.on(ev)
     }
 }
 
 #[derive(MockComponent)]
-pub struct ConfigLibraryAddRoot {
+pub struct ConfigDatabaseAddAll {
+    component: KEModifierSelect,
+}
+
+impl ConfigDatabaseAddAll {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Database Add All ",
+                IdKey::Other(IdKeyOther::DatabaseAddAll),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigDatabaseAddAll {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigDatabaseAddSelected {
+    component: KEModifierSelect,
+}
+
+impl ConfigDatabaseAddSelected {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Database Add Selected ",
+                IdKey::Other(IdKeyOther::DatabaseAddSelected),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigDatabaseAddSelected {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigDatabaseMaintenance {
+    component: KEModifierSelect,
+}
+
+impl ConfigDatabaseMaintenance {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Database Maintenance ",
+                IdKey::Other(IdKeyOther::DatabaseMaintenance),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigDatabaseMaintenance {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigDatabasePreview {
+    component: KEModifierSelect,
+}
+
+impl ConfigDatabasePreview {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Database Preview ",
+                IdKey::Other(IdKeyOther::DatabasePreview),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigDatabasePreview {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigGlobalConfig {
+    component: KEModifierSelect,
+}
+
+impl ConfigGlobalConfig {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Config Editor ",
+                IdKey::Global(IdKeyGlobal::Config),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigGlobalConfig {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigPlaylistAddRandomAlbum {
+    component: KEModifierSelect,
+}
+
+impl ConfigPlaylistAddRandomAlbum {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Playlist Select Album ",
+                IdKey::Other(IdKeyOther::PlaylistAddRandomAlbum),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigPlaylistAddRandomAlbum {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigPlaylistAddRandomTracks {
+    component: KEModifierSelect,
+}
+
+impl ConfigPlaylistAddRandomTracks {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Playlist Select Tracks ",
+                IdKey::Other(IdKeyOther::PlaylistAddRandomTracks),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigPlaylistAddRandomTracks {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigPlaylistExport {
+    component: KEModifierSelect,
+}
+
+impl ConfigPlaylistExport {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Playlist Export ",
+                IdKey::Other(IdKeyOther::PlaylistExport),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigPlaylistExport {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigPlaylistImport {
+    component: KEModifierSelect,
+}
+
+impl ConfigPlaylistImport {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Playlist Import ",
+                IdKey::Other(IdKeyOther::PlaylistImport),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigPlaylistImport {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigPlaylistTrackMenu {
+    component: KEModifierSelect,
+}
+
+impl ConfigPlaylistTrackMenu {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Playlist track menu ",
+                IdKey::Other(IdKeyOther::PlaylistTrackMenu),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigPlaylistTrackMenu {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigPlaylistSaveQueue {
+    component: KEModifierSelect,
+}
+
+impl ConfigPlaylistSaveQueue {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Playlist save queue ",
+                IdKey::Other(IdKeyOther::PlaylistSaveQueue),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigPlaylistSaveQueue {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigPlaylistRestoreQueue {
+    component: KEModifierSelect,
+}
+
+impl ConfigPlaylistRestoreQueue {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Playlist restore queue ",
+                IdKey::Other(IdKeyOther::PlaylistRestoreQueue),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigPlaylistRestoreQueue {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigPlaylistToggleAutoQueue {
+    component: KEModifierSelect,
+}
+
+impl ConfigPlaylistToggleAutoQueue {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Playlist toggle auto-queue ",
+                IdKey::Other(IdKeyOther::PlaylistToggleAutoQueue),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigPlaylistToggleAutoQueue {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigPlaylistQueues {
+    component: KEModifierSelect,
+}
+
+impl ConfigPlaylistQueues {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Playlist queues ",
+                IdKey::Other(IdKeyOther::PlaylistQueues),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigPlaylistQueues {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigPlaylistCycleRating {
+    component: KEModifierSelect,
+}
+
+impl ConfigPlaylistCycleRating {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Playlist cycle rating ",
+                IdKey::Other(IdKeyOther::PlaylistCycleRating),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigPlaylistCycleRating {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigPlaylistToggleFavorite {
+    component: KEModifierSelect,
+}
+
+impl ConfigPlaylistToggleFavorite {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Playlist toggle favorite ",
+                IdKey::Other(IdKeyOther::PlaylistToggleFavorite),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigPlaylistToggleFavorite {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigLibrarySwitchRoot {
+    component: KEModifierSelect,
+}
+
+impl ConfigLibrarySwitchRoot {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Library Switch Root ",
+                IdKey::Other(IdKeyOther::LibrarySwitchRoot),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigLibrarySwitchRoot {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigLibraryAddRoot {
     component: KEModifierSelect,
 }
 
@@ -2413,48 +3070,273 @@ impl ConfigLibraryAddRoot {
     }
 }
 
-impl Component<Msg, UserEvent> for ConfigLibraryAddRoot {
+impl Component<Msg, UserEvent> for ConfigLibraryAddRoot {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigLibraryRemoveRoot {
+    component: KEModifierSelect,
+}
+
+impl ConfigLibraryRemoveRoot {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Library Remove Root ",
+                IdKey::Other(IdKeyOther::LibraryRemoveRoot),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigLibraryRemoveRoot {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigLibraryRescan {
+    component: KEModifierSelect,
+}
+
+impl ConfigLibraryRescan {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Library Rescan ",
+                IdKey::Other(IdKeyOther::LibraryRescan),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigLibraryRescan {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigLibraryCycleFilter {
+    component: KEModifierSelect,
+}
+
+impl ConfigLibraryCycleFilter {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Library Cycle Filter ",
+                IdKey::Other(IdKeyOther::LibraryCycleFilter),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigLibraryCycleFilter {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigLibraryOpenExternal {
+    component: KEModifierSelect,
+}
+
+impl ConfigLibraryOpenExternal {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Library Open External ",
+                IdKey::Other(IdKeyOther::LibraryOpenExternal),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigLibraryOpenExternal {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigGlobalSavePlaylist {
+    component: KEModifierSelect,
+}
+
+impl ConfigGlobalSavePlaylist {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Global Save Playlist ",
+                IdKey::Global(IdKeyGlobal::SavePlaylist),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigGlobalSavePlaylist {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigGlobalPlayerChapters {
+    component: KEModifierSelect,
+}
+
+impl ConfigGlobalPlayerChapters {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Chapters ",
+                IdKey::Global(IdKeyGlobal::PlayerChapters),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigGlobalPlayerChapters {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigGlobalPlayerNextChapter {
+    component: KEModifierSelect,
+}
+
+impl ConfigGlobalPlayerNextChapter {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Next Chapter ",
+                IdKey::Global(IdKeyGlobal::PlayerNextChapter),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigGlobalPlayerNextChapter {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigGlobalPlayerPreviousChapter {
+    component: KEModifierSelect,
+}
+
+impl ConfigGlobalPlayerPreviousChapter {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Previous Chapter ",
+                IdKey::Global(IdKeyGlobal::PlayerPreviousChapter),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigGlobalPlayerPreviousChapter {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigGlobalPlayerGotoTime {
+    component: KEModifierSelect,
+}
+
+impl ConfigGlobalPlayerGotoTime {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Go To Time ",
+                IdKey::Global(IdKeyGlobal::PlayerGotoTime),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigGlobalPlayerGotoTime {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
         self.component.on(ev)
     }
 }
 
 #[derive(MockComponent)]
-pub struct ConfigLibraryRemoveRoot {
+pub struct ConfigGlobalPlayerCast {
     component: KEModifierSelect,
 }
 
-impl ConfigLibraryRemoveRoot {
+impl ConfigGlobalPlayerCast {
     pub fn new(config: SharedTuiSettings) -> Self {
         Self {
             component: KEModifierSelect::new(
-                " Library Remove Root ",
-                IdKey::Other(IdKeyOther::LibraryRemoveRoot),
+                " Cast ",
+                IdKey::Global(IdKeyGlobal::PlayerCast),
                 config,
-                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
-                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Previous)),
             ),
         }
     }
 }
 
-impl Component<Msg, UserEvent> for ConfigLibraryRemoveRoot {
+impl Component<Msg, UserEvent> for ConfigGlobalPlayerCast {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
         self.component.on(ev)
     }
 }
 
 #[derive(MockComponent)]
-pub struct ConfigGlobalSavePlaylist {
+pub struct ConfigGlobalPlayerAudioInfo {
     component: KEModifierSelect,
 }
 
-impl ConfigGlobalSavePlaylist {
+impl ConfigGlobalPlayerAudioInfo {
     pub fn new(config: SharedTuiSettings) -> Self {
         Self {
             component: KEModifierSelect::new(
-                " Global Save Playlist ",
-                IdKey::Global(IdKeyGlobal::SavePlaylist),
+                " Audio Info ",
+                IdKey::Global(IdKeyGlobal::PlayerAudioInfo),
                 config,
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Next)),
                 Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Previous)),
@@ -2463,7 +3345,7 @@ impl ConfigGlobalSavePlaylist {
     }
 }
 
-impl Component<Msg, UserEvent> for ConfigGlobalSavePlaylist {
+impl Component<Msg, UserEvent> for ConfigGlobalPlayerAudioInfo {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
         self.component.on(ev)
     }
@@ -2494,6 +3376,31 @@ impl Component<Msg, UserEvent> for ConfigGlobalLayoutPodcast {
     }
 }
 
+#[derive(MockComponent)]
+pub struct ConfigGlobalLayoutRadio {
+    component: KEModifierSelect,
+}
+
+impl ConfigGlobalLayoutRadio {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Layout Radio ",
+                IdKey::Global(IdKeyGlobal::LayoutRadio),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusGlobal(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigGlobalLayoutRadio {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
 #[derive(MockComponent)]
 pub struct ConfigGlobalXywhMoveLeft {
     component: KEModifierSelect,
@@ -2741,6 +3648,56 @@ impl Component<Msg, UserEvent> for ConfigPodcastEpDownload {
     }
 }
 
+#[derive(MockComponent)]
+pub struct ConfigPodcastEpStream {
+    component: KEModifierSelect,
+}
+
+impl ConfigPodcastEpStream {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Episode stream ",
+                IdKey::Other(IdKeyOther::PodcastEpStream),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigPodcastEpStream {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigPodcastEpDownloadAllUnplayed {
+    component: KEModifierSelect,
+}
+
+impl ConfigPodcastEpDownloadAllUnplayed {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Download all unplayed ",
+                IdKey::Other(IdKeyOther::PodcastEpDownloadAllUnplayed),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigPodcastEpDownloadAllUnplayed {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
 #[derive(MockComponent)]
 pub struct ConfigPodcastEpDeleteFile {
     component: KEModifierSelect,
@@ -2891,6 +3848,206 @@ impl Component<Msg, UserEvent> for ConfigPodcastRefreshAllFeeds {
     }
 }
 
+#[derive(MockComponent)]
+pub struct ConfigPodcastCycleFeedSort {
+    component: KEModifierSelect,
+}
+
+impl ConfigPodcastCycleFeedSort {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Cycle feeds order ",
+                IdKey::Other(IdKeyOther::PodcastCycleFeedSort),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigPodcastCycleFeedSort {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigPodcastCycleCategory {
+    component: KEModifierSelect,
+}
+
+impl ConfigPodcastCycleCategory {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Cycle feed category ",
+                IdKey::Other(IdKeyOther::PodcastCycleCategory),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigPodcastCycleCategory {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigPodcastEpisodeMenu {
+    component: KEModifierSelect,
+}
+
+impl ConfigPodcastEpisodeMenu {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Episode menu ",
+                IdKey::Other(IdKeyOther::PodcastEpisodeMenu),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigPodcastEpisodeMenu {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigPodcastFeedSettings {
+    component: KEModifierSelect,
+}
+
+impl ConfigPodcastFeedSettings {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Feed settings ",
+                IdKey::Other(IdKeyOther::PodcastFeedSettings),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigPodcastFeedSettings {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigPodcastFeedInfo {
+    component: KEModifierSelect,
+}
+
+impl ConfigPodcastFeedInfo {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Feed info ",
+                IdKey::Other(IdKeyOther::PodcastFeedInfo),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigPodcastFeedInfo {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigPodcastMergeFeed {
+    component: KEModifierSelect,
+}
+
+impl ConfigPodcastMergeFeed {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Merge feed ",
+                IdKey::Other(IdKeyOther::PodcastMergeFeed),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigPodcastMergeFeed {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigRadioSearch {
+    component: KEModifierSelect,
+}
+
+impl ConfigRadioSearch {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Radio search stations ",
+                IdKey::Other(IdKeyOther::RadioSearch),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigRadioSearch {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ConfigRadioToggleFavorite {
+    component: KEModifierSelect,
+}
+
+impl ConfigRadioToggleFavorite {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        Self {
+            component: KEModifierSelect::new(
+                " Radio toggle favorite ",
+                IdKey::Other(IdKeyOther::RadioToggleFavorite),
+                config,
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Next)),
+                Msg::ConfigEditor(ConfigEditorMsg::KeyFocusOther(KFMsg::Previous)),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ConfigRadioToggleFavorite {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
 impl Model {
     /// Mount / Remount the Config-Editor's Third Page, the key-combos
     pub(super) fn remount_config_keys(&mut self) -> Result<()> {
@@ -2899,6 +4056,7 @@ impl Model {
         self.remount_config_keys_playlist()?;
         self.remount_config_keys_database()?;
         self.remount_config_keys_podcast()?;
+        self.remount_config_keys_radio()?;
 
         Ok(())
     }
@@ -2963,6 +4121,11 @@ impl Model {
             Box::new(ConfigGlobalHelp::new(self.config_tui.clone())),
             Vec::new(),
         )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyGlobal(IdKeyGlobal::ErrorHistory)),
+            Box::new(ConfigGlobalErrorHistory::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
         self.app.remount(
             Id::ConfigEditor(IdConfigEditor::KeyGlobal(IdKeyGlobal::PlayerVolumeUp)),
             Box::new(ConfigGlobalVolumeUp::new(self.config_tui.clone())),
@@ -2979,19 +4142,37 @@ impl Model {
             Vec::new(),
         )?;
         self.app.remount(
-            Id::ConfigEditor(IdConfigEditor::KeyGlobal(IdKeyGlobal::PlayerSeekBackward)),
-            Box::new(ConfigGlobalPlayerSeekBackward::new(self.config_tui.clone())),
+            Id::ConfigEditor(IdConfigEditor::KeyGlobal(IdKeyGlobal::PlayerSeekBackward)),
+            Box::new(ConfigGlobalPlayerSeekBackward::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyGlobal(IdKeyGlobal::PlayerSpeedUp)),
+            Box::new(ConfigGlobalPlayerSpeedUp::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyGlobal(IdKeyGlobal::PlayerSpeedDown)),
+            Box::new(ConfigGlobalPlayerSpeedDown::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyGlobal(IdKeyGlobal::PlayerTrackSpeed)),
+            Box::new(ConfigGlobalPlayerTrackSpeed::new(self.config_tui.clone())),
             Vec::new(),
         )?;
+
         self.app.remount(
-            Id::ConfigEditor(IdConfigEditor::KeyGlobal(IdKeyGlobal::PlayerSpeedUp)),
-            Box::new(ConfigGlobalPlayerSpeedUp::new(self.config_tui.clone())),
+            Id::ConfigEditor(IdConfigEditor::KeyGlobal(IdKeyGlobal::PlayerAbRepeat)),
+            Box::new(ConfigGlobalPlayerAbRepeat::new(self.config_tui.clone())),
             Vec::new(),
         )?;
 
         self.app.remount(
-            Id::ConfigEditor(IdConfigEditor::KeyGlobal(IdKeyGlobal::PlayerSpeedDown)),
-            Box::new(ConfigGlobalPlayerSpeedDown::new(self.config_tui.clone())),
+            Id::ConfigEditor(IdConfigEditor::KeyGlobal(IdKeyGlobal::PlayerBookmarks)),
+            Box::new(ConfigGlobalPlayerBookmarks::new(self.config_tui.clone())),
             Vec::new(),
         )?;
 
@@ -3012,6 +4193,16 @@ impl Model {
             Box::new(ConfigGlobalLyricCycle::new(self.config_tui.clone())),
             Vec::new(),
         )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyGlobal(IdKeyGlobal::LyricCapture)),
+            Box::new(ConfigGlobalLyricCapture::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyGlobal(IdKeyGlobal::LyricEditor)),
+            Box::new(ConfigGlobalLyricEditor::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
         self.app.remount(
             Id::ConfigEditor(IdConfigEditor::KeyGlobal(IdKeyGlobal::PlayerToggleGapless)),
             Box::new(ConfigGlobalPlayerToggleGapless::new(
@@ -3044,11 +4235,56 @@ impl Model {
             Vec::new(),
         )?;
 
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyGlobal(IdKeyGlobal::PlayerChapters)),
+            Box::new(ConfigGlobalPlayerChapters::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyGlobal(IdKeyGlobal::PlayerNextChapter)),
+            Box::new(ConfigGlobalPlayerNextChapter::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyGlobal(
+                IdKeyGlobal::PlayerPreviousChapter,
+            )),
+            Box::new(ConfigGlobalPlayerPreviousChapter::new(
+                self.config_tui.clone(),
+            )),
+            Vec::new(),
+        )?;
+
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyGlobal(IdKeyGlobal::PlayerGotoTime)),
+            Box::new(ConfigGlobalPlayerGotoTime::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyGlobal(IdKeyGlobal::PlayerCast)),
+            Box::new(ConfigGlobalPlayerCast::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyGlobal(IdKeyGlobal::PlayerAudioInfo)),
+            Box::new(ConfigGlobalPlayerAudioInfo::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+
         self.app.remount(
             Id::ConfigEditor(IdConfigEditor::KeyGlobal(IdKeyGlobal::LayoutPodcast)),
             Box::new(ConfigGlobalLayoutPodcast::new(self.config_tui.clone())),
             Vec::new(),
         )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyGlobal(IdKeyGlobal::LayoutRadio)),
+            Box::new(ConfigGlobalLayoutRadio::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
 
         self.app.remount(
             Id::ConfigEditor(IdConfigEditor::KeyGlobal(IdKeyGlobal::XywhMoveLeft)),
@@ -3126,6 +4362,36 @@ impl Model {
             Box::new(ConfigLibraryTagEditor::new(self.config_tui.clone())),
             Vec::new(),
         )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::LibraryTrackMenu)),
+            Box::new(ConfigLibraryTrackMenu::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::LibraryCycleRating)),
+            Box::new(ConfigLibraryCycleRating::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::LibraryToggleFavorite)),
+            Box::new(ConfigLibraryToggleFavorite::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::LibraryMark)),
+            Box::new(ConfigLibraryMark::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::LibraryOrganize)),
+            Box::new(ConfigLibraryOrganize::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::LibraryToggleAudiobook)),
+            Box::new(ConfigLibraryToggleAudiobook::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
 
         self.app.remount(
             Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::LibrarySwitchRoot)),
@@ -3145,6 +4411,24 @@ impl Model {
             Vec::new(),
         )?;
 
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::LibraryRescan)),
+            Box::new(ConfigLibraryRescan::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::LibraryCycleFilter)),
+            Box::new(ConfigLibraryCycleFilter::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::LibraryOpenExternal)),
+            Box::new(ConfigLibraryOpenExternal::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+
         Ok(())
     }
 
@@ -3203,6 +4487,53 @@ impl Model {
             Box::new(ConfigPlaylistAddRandomTracks::new(self.config_tui.clone())),
             Vec::new(),
         )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::PlaylistExport)),
+            Box::new(ConfigPlaylistExport::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::PlaylistImport)),
+            Box::new(ConfigPlaylistImport::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::PlaylistTrackMenu)),
+            Box::new(ConfigPlaylistTrackMenu::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::PlaylistSaveQueue)),
+            Box::new(ConfigPlaylistSaveQueue::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::PlaylistRestoreQueue)),
+            Box::new(ConfigPlaylistRestoreQueue::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(
+                IdKeyOther::PlaylistToggleAutoQueue,
+            )),
+            Box::new(ConfigPlaylistToggleAutoQueue::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::PlaylistQueues)),
+            Box::new(ConfigPlaylistQueues::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::PlaylistCycleRating)),
+            Box::new(ConfigPlaylistCycleRating::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::PlaylistToggleFavorite)),
+            Box::new(ConfigPlaylistToggleFavorite::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
 
         Ok(())
     }
@@ -3221,6 +4552,18 @@ impl Model {
             Vec::new(),
         )?;
 
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::DatabaseMaintenance)),
+            Box::new(ConfigDatabaseMaintenance::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::DatabasePreview)),
+            Box::new(ConfigDatabasePreview::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+
         Ok(())
     }
 
@@ -3241,6 +4584,20 @@ impl Model {
             Box::new(ConfigPodcastEpDownload::new(self.config_tui.clone())),
             Vec::new(),
         )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::PodcastEpStream)),
+            Box::new(ConfigPodcastEpStream::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(
+                IdKeyOther::PodcastEpDownloadAllUnplayed,
+            )),
+            Box::new(ConfigPodcastEpDownloadAllUnplayed::new(
+                self.config_tui.clone(),
+            )),
+            Vec::new(),
+        )?;
         self.app.remount(
             Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::PodcastEpDeleteFile)),
             Box::new(ConfigPodcastEpDeleteFile::new(self.config_tui.clone())),
@@ -3266,6 +4623,36 @@ impl Model {
             Box::new(ConfigPodcastRefreshAllFeeds::new(self.config_tui.clone())),
             Vec::new(),
         )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::PodcastCycleFeedSort)),
+            Box::new(ConfigPodcastCycleFeedSort::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::PodcastCycleCategory)),
+            Box::new(ConfigPodcastCycleCategory::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::PodcastEpisodeMenu)),
+            Box::new(ConfigPodcastEpisodeMenu::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::PodcastFeedSettings)),
+            Box::new(ConfigPodcastFeedSettings::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::PodcastFeedInfo)),
+            Box::new(ConfigPodcastFeedInfo::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::PodcastMergeFeed)),
+            Box::new(ConfigPodcastMergeFeed::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
         self.app.remount(
             Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::PodcastSearchAddFeed)),
             Box::new(ConfigPodcastSearchAddFeed::new(self.config_tui.clone())),
@@ -3275,6 +4662,22 @@ impl Model {
         Ok(())
     }
 
+    /// Mount / Remount the Config-Editor's Third Page, the Radio key-combos
+    fn remount_config_keys_radio(&mut self) -> Result<()> {
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::RadioSearch)),
+            Box::new(ConfigRadioSearch::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+        self.app.remount(
+            Id::ConfigEditor(IdConfigEditor::KeyOther(IdKeyOther::RadioToggleFavorite)),
+            Box::new(ConfigRadioToggleFavorite::new(self.config_tui.clone())),
+            Vec::new(),
+        )?;
+
+        Ok(())
+    }
+
     /// Unmount the Config-Editor's Third Page, the key-combos
     pub(super) fn umount_config_keys(&mut self) -> Result<()> {
         self.umount_config_keys_global()?;
@@ -3282,6 +4685,7 @@ impl Model {
         self.umount_config_keys_playlist()?;
         self.umount_config_keys_database()?;
         self.umount_config_keys_podcast()?;
+        self.umount_config_keys_radio()?;
 
         Ok(())
     }
@@ -3334,6 +4738,10 @@ impl Model {
             .umount(&Id::ConfigEditor(IdConfigEditor::KeyGlobal(
                 IdKeyGlobal::Help,
             )))?;
+        self.app
+            .umount(&Id::ConfigEditor(IdConfigEditor::KeyGlobal(
+                IdKeyGlobal::ErrorHistory,
+            )))?;
         self.app
             .umount(&Id::ConfigEditor(IdConfigEditor::KeyGlobal(
                 IdKeyGlobal::PlayerVolumeUp,
@@ -3359,6 +4767,18 @@ impl Model {
             .umount(&Id::ConfigEditor(IdConfigEditor::KeyGlobal(
                 IdKeyGlobal::PlayerSpeedDown,
             )))?;
+        self.app
+            .umount(&Id::ConfigEditor(IdConfigEditor::KeyGlobal(
+                IdKeyGlobal::PlayerTrackSpeed,
+            )))?;
+        self.app
+            .umount(&Id::ConfigEditor(IdConfigEditor::KeyGlobal(
+                IdKeyGlobal::PlayerAbRepeat,
+            )))?;
+        self.app
+            .umount(&Id::ConfigEditor(IdConfigEditor::KeyGlobal(
+                IdKeyGlobal::PlayerBookmarks,
+            )))?;
         self.app
             .umount(&Id::ConfigEditor(IdConfigEditor::KeyGlobal(
                 IdKeyGlobal::LyricAdjustForward,
@@ -3371,6 +4791,14 @@ impl Model {
             .umount(&Id::ConfigEditor(IdConfigEditor::KeyGlobal(
                 IdKeyGlobal::LyricCycle,
             )))?;
+        self.app
+            .umount(&Id::ConfigEditor(IdConfigEditor::KeyGlobal(
+                IdKeyGlobal::LyricCapture,
+            )))?;
+        self.app
+            .umount(&Id::ConfigEditor(IdConfigEditor::KeyGlobal(
+                IdKeyGlobal::LyricEditor,
+            )))?;
         self.app
             .umount(&Id::ConfigEditor(IdConfigEditor::KeyGlobal(
                 IdKeyGlobal::LayoutDatabase,
@@ -3392,10 +4820,38 @@ impl Model {
             .umount(&Id::ConfigEditor(IdConfigEditor::KeyGlobal(
                 IdKeyGlobal::SavePlaylist,
             )))?;
+        self.app
+            .umount(&Id::ConfigEditor(IdConfigEditor::KeyGlobal(
+                IdKeyGlobal::PlayerChapters,
+            )))?;
+        self.app
+            .umount(&Id::ConfigEditor(IdConfigEditor::KeyGlobal(
+                IdKeyGlobal::PlayerNextChapter,
+            )))?;
+        self.app
+            .umount(&Id::ConfigEditor(IdConfigEditor::KeyGlobal(
+                IdKeyGlobal::PlayerPreviousChapter,
+            )))?;
+        self.app
+            .umount(&Id::ConfigEditor(IdConfigEditor::KeyGlobal(
+                IdKeyGlobal::PlayerGotoTime,
+            )))?;
+        self.app
+            .umount(&Id::ConfigEditor(IdConfigEditor::KeyGlobal(
+                IdKeyGlobal::PlayerCast,
+            )))?;
+        self.app
+            .umount(&Id::ConfigEditor(IdConfigEditor::KeyGlobal(
+                IdKeyGlobal::PlayerAudioInfo,
+            )))?;
         self.app
             .umount(&Id::ConfigEditor(IdConfigEditor::KeyGlobal(
                 IdKeyGlobal::LayoutPodcast,
             )))?;
+        self.app
+            .umount(&Id::ConfigEditor(IdConfigEditor::KeyGlobal(
+                IdKeyGlobal::LayoutRadio,
+            )))?;
 
         self.app
             .umount(&Id::ConfigEditor(IdConfigEditor::KeyGlobal(
@@ -3452,6 +4908,24 @@ impl Model {
         self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
             IdKeyOther::LibraryTagEditor,
         )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::LibraryTrackMenu,
+        )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::LibraryCycleRating,
+        )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::LibraryToggleFavorite,
+        )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::LibraryMark,
+        )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::LibraryOrganize,
+        )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::LibraryToggleAudiobook,
+        )))?;
 
         self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
             IdKeyOther::LibrarySwitchRoot,
@@ -3465,6 +4939,18 @@ impl Model {
             IdKeyOther::LibraryRemoveRoot,
         )))?;
 
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::LibraryRescan,
+        )))?;
+
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::LibraryCycleFilter,
+        )))?;
+
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::LibraryOpenExternal,
+        )))?;
+
         Ok(())
     }
 
@@ -3502,6 +4988,33 @@ impl Model {
         self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
             IdKeyOther::PlaylistAddRandomTracks,
         )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::PlaylistExport,
+        )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::PlaylistImport,
+        )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::PlaylistTrackMenu,
+        )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::PlaylistSaveQueue,
+        )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::PlaylistRestoreQueue,
+        )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::PlaylistToggleAutoQueue,
+        )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::PlaylistQueues,
+        )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::PlaylistCycleRating,
+        )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::PlaylistToggleFavorite,
+        )))?;
 
         Ok(())
     }
@@ -3511,6 +5024,12 @@ impl Model {
         self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
             IdKeyOther::DatabaseAddAll,
         )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::DatabaseMaintenance,
+        )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::DatabasePreview,
+        )))?;
 
         Ok(())
     }
@@ -3526,6 +5045,12 @@ impl Model {
         self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
             IdKeyOther::PodcastEpDownload,
         )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::PodcastEpStream,
+        )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::PodcastEpDownloadAllUnplayed,
+        )))?;
         self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
             IdKeyOther::PodcastEpDeleteFile,
         )))?;
@@ -3541,10 +5066,40 @@ impl Model {
         self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
             IdKeyOther::PodcastRefreshAllFeeds,
         )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::PodcastCycleFeedSort,
+        )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::PodcastCycleCategory,
+        )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::PodcastEpisodeMenu,
+        )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::PodcastFeedSettings,
+        )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::PodcastFeedInfo,
+        )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::PodcastMergeFeed,
+        )))?;
         self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
             IdKeyOther::PodcastSearchAddFeed,
         )))?;
 
         Ok(())
     }
+
+    /// Unmount the Config-Editor's Third Page, the Radio key-combos
+    fn umount_config_keys_radio(&mut self) -> Result<()> {
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::RadioSearch,
+        )))?;
+        self.app.umount(&Id::ConfigEditor(IdConfigEditor::KeyOther(
+            IdKeyOther::RadioToggleFavorite,
+        )))?;
+
+        Ok(())
+    }
 }
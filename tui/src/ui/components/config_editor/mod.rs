@@ -38,6 +38,9 @@ mod key_combo;
 mod update;
 mod view;
 
+#[cfg(test)]
+pub(super) use general::{ExitConfirmation, MusicDir};
+
 #[derive(MockComponent)]
 pub struct CEHeader {
     component: Radio,
@@ -7,6 +7,7 @@ use termusiclib::config::v2::tui::theme::ThemeColors;
 use termusiclib::config::v2::tui::theme::styles::ColorTermusic;
 use termusiclib::utils::get_app_config_path;
 
+use crate::instance_lock;
 use crate::ui::Model;
 use crate::ui::ids::{Id, IdCETheme, IdConfigEditor, IdKey, IdKeyGlobal, IdKeyOther};
 use crate::ui::msg::{
@@ -26,6 +27,8 @@ impl Model {
                 self.config_editor.theme = self.config_tui.read().settings.theme.clone();
                 self.config_editor.key_config = self.config_tui.read().settings.keys.clone();
                 self.mount_config_editor();
+                // the output devices are filled in once the server responds
+                self.command(TuiCmd::GetOutputDevices);
             }
             ConfigEditorMsg::CloseCancel => {
                 self.config_editor.config_changed = false;
@@ -46,6 +49,20 @@ impl Model {
                 self.app
                     .umount(&Id::ConfigEditor(IdConfigEditor::ConfigSavePopup))
                     .ok();
+                if let Err(err) = instance_lock::ensure_held() {
+                    self.mount_error_popup(err.context("config editor save"));
+                    self.config_editor.config_changed = true;
+                    return None;
+                }
+                let old_output_device = self
+                    .config_server
+                    .read()
+                    .settings
+                    .backends
+                    .rusty
+                    .output_device
+                    .clone();
+                let old_crossfade = self.config_server.read().settings.player.crossfade;
                 match self.collect_config_data() {
                     Ok(()) => {
                         let res_server = ServerConfigVersionedDefaulted::save_config_path(
@@ -70,6 +87,24 @@ impl Model {
                         if both_ok {
                             self.command(TuiCmd::ReloadConfig);
 
+                            let output_device = self
+                                .config_server
+                                .read()
+                                .settings
+                                .backends
+                                .rusty
+                                .output_device
+                                .clone();
+                            if output_device != old_output_device {
+                                // switch now, instead of on the next server start
+                                self.command(TuiCmd::SetOutputDevice(output_device));
+                            }
+
+                            let crossfade = self.config_server.read().settings.player.crossfade;
+                            if crossfade != old_crossfade {
+                                self.command(TuiCmd::SetCrossfade(crossfade));
+                            }
+
                             // only exit config editor if saving was successful
                             self.umount_config_editor();
                         }
@@ -229,6 +264,10 @@ impl Model {
             IdKey::Other(IdKeyOther::DatabaseAddSelected) => {
                 keys.database_keys.add_selected = binding;
             }
+            IdKey::Other(IdKeyOther::DatabaseMaintenance) => {
+                keys.database_keys.maintenance = binding;
+            }
+            IdKey::Other(IdKeyOther::DatabasePreview) => keys.database_keys.preview = binding,
             IdKey::Global(IdKeyGlobal::Config) => keys.select_view_keys.open_config = binding,
             IdKey::Global(IdKeyGlobal::Down) => keys.navigation_keys.down = binding,
             IdKey::Global(IdKeyGlobal::GotoBottom) => {
@@ -236,6 +275,9 @@ impl Model {
             }
             IdKey::Global(IdKeyGlobal::GotoTop) => keys.navigation_keys.goto_top = binding,
             IdKey::Global(IdKeyGlobal::Help) => keys.select_view_keys.open_help = binding,
+            IdKey::Global(IdKeyGlobal::ErrorHistory) => {
+                keys.select_view_keys.open_error_history = binding;
+            }
             IdKey::Global(IdKeyGlobal::LayoutTreeview) => {
                 keys.select_view_keys.view_library = binding;
             }
@@ -250,6 +292,8 @@ impl Model {
                 keys.lyric_keys.adjust_offset_backwards = binding;
             }
             IdKey::Global(IdKeyGlobal::LyricCycle) => keys.lyric_keys.cycle_frames = binding,
+            IdKey::Global(IdKeyGlobal::LyricCapture) => keys.lyric_keys.capture_caption = binding,
+            IdKey::Global(IdKeyGlobal::LyricEditor) => keys.lyric_keys.open_editor = binding,
             IdKey::Global(IdKeyGlobal::PlayerToggleGapless) => {
                 keys.player_keys.toggle_prefetch = binding;
             }
@@ -270,6 +314,15 @@ impl Model {
             IdKey::Global(IdKeyGlobal::PlayerSpeedDown) => {
                 keys.player_keys.speed_down = binding;
             }
+            IdKey::Global(IdKeyGlobal::PlayerTrackSpeed) => {
+                keys.player_keys.track_speed = binding;
+            }
+            IdKey::Global(IdKeyGlobal::PlayerAbRepeat) => {
+                keys.player_keys.ab_repeat = binding;
+            }
+            IdKey::Global(IdKeyGlobal::PlayerBookmarks) => {
+                keys.player_keys.bookmarks = binding;
+            }
             IdKey::Global(IdKeyGlobal::Quit) => keys.quit = binding,
             IdKey::Global(IdKeyGlobal::Right) => keys.navigation_keys.right = binding,
             IdKey::Global(IdKeyGlobal::Up) => keys.navigation_keys.up = binding,
@@ -282,6 +335,16 @@ impl Model {
             IdKey::Global(IdKeyGlobal::SavePlaylist) => {
                 keys.player_keys.save_playlist = binding;
             }
+            IdKey::Global(IdKeyGlobal::PlayerChapters) => keys.player_keys.chapters = binding,
+            IdKey::Global(IdKeyGlobal::PlayerNextChapter) => {
+                keys.player_keys.next_chapter = binding;
+            }
+            IdKey::Global(IdKeyGlobal::PlayerPreviousChapter) => {
+                keys.player_keys.previous_chapter = binding;
+            }
+            IdKey::Global(IdKeyGlobal::PlayerGotoTime) => keys.player_keys.goto_time = binding,
+            IdKey::Global(IdKeyGlobal::PlayerCast) => keys.player_keys.cast = binding,
+            IdKey::Global(IdKeyGlobal::PlayerAudioInfo) => keys.player_keys.audio_info = binding,
             IdKey::Other(IdKeyOther::LibraryDelete) => keys.library_keys.delete = binding,
             IdKey::Other(IdKeyOther::LibraryLoadDir) => keys.library_keys.load_dir = binding,
             IdKey::Other(IdKeyOther::LibraryPaste) => keys.library_keys.paste = binding,
@@ -292,6 +355,18 @@ impl Model {
             IdKey::Other(IdKeyOther::LibraryTagEditor) => {
                 keys.library_keys.open_tag_editor = binding;
             }
+            IdKey::Other(IdKeyOther::LibraryTrackMenu) => keys.library_keys.track_menu = binding,
+            IdKey::Other(IdKeyOther::LibraryCycleRating) => {
+                keys.library_keys.cycle_rating = binding;
+            }
+            IdKey::Other(IdKeyOther::LibraryToggleFavorite) => {
+                keys.library_keys.toggle_favorite = binding;
+            }
+            IdKey::Other(IdKeyOther::LibraryMark) => keys.library_keys.mark = binding,
+            IdKey::Other(IdKeyOther::LibraryOrganize) => keys.library_keys.organize = binding,
+            IdKey::Other(IdKeyOther::LibraryToggleAudiobook) => {
+                keys.library_keys.toggle_audiobook = binding;
+            }
             IdKey::Other(IdKeyOther::LibraryYank) => keys.library_keys.yank = binding,
             IdKey::Other(IdKeyOther::PlaylistDelete) => keys.playlist_keys.delete = binding,
             IdKey::Other(IdKeyOther::PlaylistDeleteAll) => keys.playlist_keys.delete_all = binding,
@@ -311,12 +386,37 @@ impl Model {
             IdKey::Other(IdKeyOther::PlaylistAddRandomTracks) => {
                 keys.playlist_keys.add_random_songs = binding;
             }
+            IdKey::Other(IdKeyOther::PlaylistExport) => keys.playlist_keys.export = binding,
+            IdKey::Other(IdKeyOther::PlaylistImport) => keys.playlist_keys.import = binding,
+            IdKey::Other(IdKeyOther::PlaylistTrackMenu) => keys.playlist_keys.track_menu = binding,
+            IdKey::Other(IdKeyOther::PlaylistSaveQueue) => keys.playlist_keys.save_queue = binding,
+            IdKey::Other(IdKeyOther::PlaylistRestoreQueue) => {
+                keys.playlist_keys.restore_queue = binding;
+            }
+            IdKey::Other(IdKeyOther::PlaylistToggleAutoQueue) => {
+                keys.playlist_keys.toggle_auto_queue = binding;
+            }
+            IdKey::Other(IdKeyOther::PlaylistQueues) => keys.playlist_keys.queues = binding,
+            IdKey::Other(IdKeyOther::PlaylistCycleRating) => {
+                keys.playlist_keys.cycle_rating = binding;
+            }
+            IdKey::Other(IdKeyOther::PlaylistToggleFavorite) => {
+                keys.playlist_keys.toggle_favorite = binding;
+            }
             IdKey::Other(IdKeyOther::LibrarySwitchRoot) => keys.library_keys.cycle_root = binding,
             IdKey::Other(IdKeyOther::LibraryAddRoot) => keys.library_keys.add_root = binding,
             IdKey::Other(IdKeyOther::LibraryRemoveRoot) => keys.library_keys.remove_root = binding,
+            IdKey::Other(IdKeyOther::LibraryRescan) => keys.library_keys.rescan = binding,
+            IdKey::Other(IdKeyOther::LibraryCycleFilter) => {
+                keys.library_keys.cycle_filter = binding;
+            }
+            IdKey::Other(IdKeyOther::LibraryOpenExternal) => {
+                keys.library_keys.open_external = binding;
+            }
             IdKey::Global(IdKeyGlobal::LayoutPodcast) => {
                 keys.select_view_keys.view_podcasts = binding;
             }
+            IdKey::Global(IdKeyGlobal::LayoutRadio) => keys.select_view_keys.view_radio = binding,
             IdKey::Global(IdKeyGlobal::XywhMoveLeft) => {
                 keys.move_cover_art_keys.move_left = binding;
             }
@@ -342,9 +442,15 @@ impl Model {
             IdKey::Other(IdKeyOther::PodcastMarkAllPlayed) => {
                 keys.podcast_keys.mark_all_played = binding;
             }
+            IdKey::Other(IdKeyOther::PodcastEpStream) => {
+                keys.podcast_keys.stream_episode = binding;
+            }
             IdKey::Other(IdKeyOther::PodcastEpDownload) => {
                 keys.podcast_keys.download_episode = binding;
             }
+            IdKey::Other(IdKeyOther::PodcastEpDownloadAllUnplayed) => {
+                keys.podcast_keys.download_all_unplayed = binding;
+            }
             IdKey::Other(IdKeyOther::PodcastEpDeleteFile) => {
                 keys.podcast_keys.delete_local_episode = binding;
             }
@@ -359,6 +465,28 @@ impl Model {
             IdKey::Other(IdKeyOther::PodcastRefreshAllFeeds) => {
                 keys.podcast_keys.refresh_all_feeds = binding;
             }
+            IdKey::Other(IdKeyOther::PodcastCycleFeedSort) => {
+                keys.podcast_keys.cycle_feed_sort = binding;
+            }
+            IdKey::Other(IdKeyOther::PodcastCycleCategory) => {
+                keys.podcast_keys.cycle_category = binding;
+            }
+            IdKey::Other(IdKeyOther::PodcastEpisodeMenu) => {
+                keys.podcast_keys.episode_menu = binding;
+            }
+            IdKey::Other(IdKeyOther::PodcastFeedSettings) => {
+                keys.podcast_keys.feed_settings = binding;
+            }
+            IdKey::Other(IdKeyOther::PodcastFeedInfo) => {
+                keys.podcast_keys.feed_info = binding;
+            }
+            IdKey::Other(IdKeyOther::PodcastMergeFeed) => {
+                keys.podcast_keys.merge_feed = binding;
+            }
+            IdKey::Other(IdKeyOther::RadioSearch) => keys.radio_keys.search = binding,
+            IdKey::Other(IdKeyOther::RadioToggleFavorite) => {
+                keys.radio_keys.toggle_favorite = binding;
+            }
         }
     }
 
@@ -28,6 +28,7 @@ use std::path::PathBuf;
 use anyhow::{Result, bail};
 use include_dir::DirEntry;
 use termusiclib::THEME_DIR;
+use termusiclib::config::v2::server::metadata::ScanPolicy;
 use termusiclib::config::v2::server::{
     Backend, ComProtocol, PositionYesNo, PositionYesNoLower, RememberLastPosition,
 };
@@ -183,14 +184,17 @@ impl Model {
                         IdCEGeneral::PlayerProtocol => 16,
                         IdCEGeneral::PlayerUDSPath => 17,
                         IdCEGeneral::PlayerBackend => 18,
-                        IdCEGeneral::ExtraYtdlpArgs => 19,
+                        IdCEGeneral::PlayerOutputDevice => 19,
+                        IdCEGeneral::PlayerCrossfade => 20,
+                        IdCEGeneral::ExtraYtdlpArgs => 21,
+                        IdCEGeneral::LibraryScanPolicy => 22,
                     })
                 } else {
                     None
                 }
             });
 
-        let cells = UniformDynamicGrid::new(20, 3, 56 + 2)
+        let cells = UniformDynamicGrid::new(23, 3, 56 + 2)
             .draw_row_low_space()
             .distribute_row_space()
             .focus_node(focus_elem)
@@ -224,8 +228,11 @@ impl Model {
             &Id::ConfigEditor(IdConfigEditor::General(IdCEGeneral::PlayerProtocol)) => cells[16],
             &Id::ConfigEditor(IdConfigEditor::General(IdCEGeneral::PlayerUDSPath)) => cells[17],
             &Id::ConfigEditor(IdConfigEditor::General(IdCEGeneral::PlayerBackend)) => cells[18],
+            &Id::ConfigEditor(IdConfigEditor::General(IdCEGeneral::PlayerOutputDevice)) => cells[19],
+            &Id::ConfigEditor(IdConfigEditor::General(IdCEGeneral::PlayerCrossfade)) => cells[20],
 
-            &Id::ConfigEditor(IdConfigEditor::General(IdCEGeneral::ExtraYtdlpArgs)) => cells[19],
+            &Id::ConfigEditor(IdConfigEditor::General(IdCEGeneral::ExtraYtdlpArgs)) => cells[21],
+            &Id::ConfigEditor(IdConfigEditor::General(IdCEGeneral::LibraryScanPolicy)) => cells[22],
         }
     }
 
@@ -237,11 +244,7 @@ impl Model {
             f.render_widget(Clear, popup);
             app.view(&Id::ConfigEditor(IdConfigEditor::ConfigSavePopup), f, popup);
         }
-        if app.mounted(&Id::ErrorPopup) {
-            let popup = draw_area_in_absolute(f.area(), 50, 4);
-            f.render_widget(Clear, popup);
-            app.view(&Id::ErrorPopup, f, popup);
-        }
+        Self::view_error_popups(f, app);
     }
 
     /// Draw the keys for tab "Themes and Colors"
@@ -891,12 +894,43 @@ impl Model {
                 0 => Backend::Rusty,
                 1 => Backend::Mpv,
                 2 => Backend::Gstreamer,
+                3 => Backend::Null,
                 // numbers are specified in "PlayerBackend"
                 _ => unreachable!(),
             };
             config_server.settings.player.backend = backend;
         }
 
+        match self.app.state(&Id::ConfigEditor(IdConfigEditor::General(
+            IdCEGeneral::PlayerOutputDevice,
+        ))) {
+            Ok(State::One(StateValue::String(device))) => {
+                config_server.settings.backends.rusty.output_device = Some(device);
+            }
+            Ok(State::None) => config_server.settings.backends.rusty.output_device = None,
+            _ => (),
+        }
+
+        if let Ok(State::One(StateValue::Usize(crossfade))) = self.app.state(&Id::ConfigEditor(
+            IdConfigEditor::General(IdCEGeneral::PlayerCrossfade),
+        )) {
+            config_server.settings.player.crossfade.enabled = matches!(crossfade, 0);
+        }
+
+        if let Ok(State::One(StateValue::Usize(policy))) = self.app.state(&Id::ConfigEditor(
+            IdConfigEditor::General(IdCEGeneral::LibraryScanPolicy),
+        )) {
+            let policy = match policy {
+                0 => ScanPolicy::Startup,
+                1 => ScanPolicy::Manual,
+                2 => ScanPolicy::Interval,
+                3 => ScanPolicy::Watch,
+                // numbers are specified in "LibraryScanPolicy"
+                _ => unreachable!(),
+            };
+            config_server.settings.metadata.scan_policy = policy;
+        }
+
         if let Ok(State::One(StateValue::String(extra_ytdlp_args))) = self.app.state(
             &Id::ConfigEditor(IdConfigEditor::General(IdCEGeneral::ExtraYtdlpArgs)),
         ) {
@@ -6,8 +6,8 @@ use either::Either;
 use termusiclib::common::const_unknown::{UNKNOWN_ARTIST, UNKNOWN_FILE, UNKNOWN_TITLE};
 use termusiclib::config::SharedTuiSettings;
 use termusiclib::config::v2::tui::keys::Keys;
-use termusiclib::new_database::track_ops::TrackRead;
-use termusiclib::new_database::{album_ops, artist_ops, track_ops};
+use termusiclib::new_database::track_ops::{MAX_RATING, PlayStatsOrder, TrackPlayStats, TrackRead};
+use termusiclib::new_database::{album_ops, artist_ops, smart_playlist_ops, track_ops};
 use termusiclib::track::{DurationFmtShort, Track};
 use termusiclib::utils::{is_playlist, playlist_get_vec};
 use tui_realm_stdlib::List;
@@ -22,9 +22,26 @@ use tuirealm::{
 use super::popups::{YNConfirm, YNConfirmStyle};
 use crate::ui::Model;
 use crate::ui::ids::Id;
-use crate::ui::model::UserEvent;
+use crate::ui::model::{TermusicLayout, UserEvent};
 use crate::ui::msg::{DBMsg, GSMsg, Msg, SearchCriteria};
 
+/// Prefix of [`SearchCriteria::Playlist`] results that are smart playlists instead of playlist files.
+const SMART_PLAYLIST_PREFIX: &str = "smart:";
+
+/// The [`SearchCriteria::Favorite`] result for all tracks marked as favorite.
+const FAVORITES_RESULT: &str = "Favorites";
+/// Prefix of [`SearchCriteria::Favorite`] results for all tracks rated at least the number after it.
+const RATED_PREFIX: &str = "Rated ";
+
+/// The [`SearchCriteria::PlayStats`] result for the tracks played until the end most often.
+const MOST_PLAYED_RESULT: &str = "Most played";
+/// The [`SearchCriteria::PlayStats`] result for the tracks started most recently.
+const RECENTLY_PLAYED_RESULT: &str = "Recently played";
+/// The [`SearchCriteria::PlayStats`] result for the tracks skipped most often.
+const MOST_SKIPPED_RESULT: &str = "Most skipped";
+/// How many tracks a [`SearchCriteria::PlayStats`] result shows at most.
+const PLAY_STATS_LIMIT: u32 = 100;
+
 /// Helper trait to accomedate mutable access to `self` while also allowing access to other `self` properties for [`common_list_movement`].
 trait OnKeyDB {
     fn on_key_tab(&self) -> Msg;
@@ -104,6 +121,10 @@ fn common_list_movement<C: MockComponent + OnKeyDB>(
             modifiers: KeyModifiers::SHIFT,
         }) => return Some(Either::Right(comp.on_key_backtab())),
 
+        Event::Keyboard(key) if *key == keys.database_keys.maintenance.get() => {
+            return Some(Either::Right(Msg::DataBase(DBMsg::BackupsShow)));
+        }
+
         _ => return None,
     };
 
@@ -118,6 +139,8 @@ enum DBCriteria {
     Genres,
     Directories,
     Playlists,
+    Favorites,
+    PlayStats,
 }
 
 impl DBCriteria {
@@ -125,7 +148,7 @@ impl DBCriteria {
     /// This is for example used to get exact space allocation for the layout.
     ///
     /// Note: keep this in-sync with [`Self::build_table`]
-    const NUM_OPTIONS: u16 = 5;
+    const NUM_OPTIONS: u16 = 7;
 
     fn build_table() -> Table {
         TableBuilder::default()
@@ -138,6 +161,10 @@ impl DBCriteria {
             .add_col(TextSpan::from("Directory"))
             .add_row()
             .add_col(TextSpan::from("Playlists"))
+            .add_row()
+            .add_col(TextSpan::from("Favorites"))
+            .add_row()
+            .add_col(TextSpan::from("Play statistics"))
             .build()
     }
 
@@ -150,6 +177,8 @@ impl DBCriteria {
             2 => Self::Genres,
             3 => Self::Directories,
             4 => Self::Playlists,
+            5 => Self::Favorites,
+            6 => Self::PlayStats,
             _ => return None,
         };
 
@@ -165,6 +194,8 @@ impl From<DBCriteria> for SearchCriteria {
             DBCriteria::Genres => Self::Genre,
             DBCriteria::Directories => Self::Directory,
             DBCriteria::Playlists => Self::Playlist,
+            DBCriteria::Favorites => Self::Favorite,
+            DBCriteria::PlayStats => Self::PlayStats,
         }
     }
 }
@@ -389,6 +420,9 @@ impl Component<Msg, UserEvent> for DBListSearchResult {
         match cmd_result {
             Either::Left(CmdResult::None) => None,
             Either::Right(msg) => Some(msg),
+            Either::Left(CmdResult::Changed(State::One(StateValue::Usize(index)))) => {
+                Some(Msg::DataBase(DBMsg::SearchResultHighlighted(index)))
+            }
             Either::Left(_) => Some(Msg::ForceRedraw),
         }
     }
@@ -463,6 +497,26 @@ impl Component<Msg, UserEvent> for DBListSearchTracks {
                 Event::Keyboard(keyevent) if keyevent == keys.database_keys.add_all.get() => {
                     return Either::Right(Msg::DataBase(DBMsg::AddAllToPlaylist));
                 }
+                Event::Keyboard(keyevent) if keyevent == keys.library_keys.mark.get() => {
+                    if let State::One(StateValue::Usize(index)) = self.state() {
+                        return Either::Right(Msg::DataBase(DBMsg::ToggleMark(index)));
+                    }
+                    CmdResult::None
+                }
+                Event::Keyboard(keyevent)
+                    if keyevent == keys.library_keys.open_tag_editor.get() =>
+                {
+                    if let State::One(StateValue::Usize(index)) = self.state() {
+                        return Either::Right(Msg::DataBase(DBMsg::OpenTagEditor(index)));
+                    }
+                    CmdResult::None
+                }
+                Event::Keyboard(keyevent) if keyevent == keys.database_keys.preview.get() => {
+                    if let State::One(StateValue::Usize(index)) = self.state() {
+                        return Either::Right(Msg::DataBase(DBMsg::PreviewTrack(index)));
+                    }
+                    CmdResult::None
+                }
 
                 Event::Keyboard(keyevent) if keyevent == keys.library_keys.search.get() => {
                     return Either::Right(Msg::GeneralSearch(GSMsg::PopupShowDatabase));
@@ -608,6 +662,9 @@ impl Model {
                 .add_col(TextSpan::from(format!("{}", idx + 1)))
                 .add_col(TextSpan::from(" "))
                 .add_col(TextSpan::from(name));
+            if let Some(stats) = self.dw.search_tracks_stats.get(idx) {
+                table.add_col(TextSpan::from(format!("  ({stats})")));
+            }
         }
         if self.dw.search_results.is_empty() {
             table.add_col(TextSpan::from("empty results"));
@@ -630,6 +687,9 @@ impl Model {
         for (idx, record) in self.dw.search_results.iter().enumerate() {
             let mut display_name = String::new();
             match self.dw.criteria {
+                SearchCriteria::Playlist if record.starts_with(SMART_PLAYLIST_PREFIX) => {
+                    display_name = record.replacen(SMART_PLAYLIST_PREFIX, "[smart] ", 1);
+                }
                 SearchCriteria::Playlist => {
                     let path = Path::new(record);
                     let path_string = path.to_string_lossy().to_string();
@@ -727,6 +787,16 @@ impl Model {
 
                 result
             }
+            SearchCriteria::Favorite => std::iter::once(FAVORITES_RESULT.to_string())
+                .chain((1..=MAX_RATING).map(|rating| format!("{RATED_PREFIX}{rating}+")))
+                .collect(),
+            SearchCriteria::PlayStats => [
+                MOST_PLAYED_RESULT,
+                RECENTLY_PLAYED_RESULT,
+                MOST_SKIPPED_RESULT,
+            ]
+            .map(ToString::to_string)
+            .to_vec(),
         };
 
         res.sort_by(|a, b| alphanumeric_sort::compare_str(a, b));
@@ -734,11 +804,60 @@ impl Model {
         self.dw.search_results = res;
         self.database_sync_results();
         self.app.active(&Id::DBListSearchResult).ok();
+
+        let index = match self.app.state(&Id::DBListSearchResult) {
+            Ok(State::One(StateValue::Usize(index))) => index,
+            _ => 0,
+        };
+        self.database_update_album_cover(index);
+    }
+
+    /// Show the cover of the album at `index` in view `Result` instead of the one of the playing track,
+    /// if the results are albums.
+    pub fn database_update_album_cover(&mut self, index: usize) {
+        let album_cover = match (self.dw.criteria, self.dw.search_results.get(index)) {
+            (SearchCriteria::Album, Some(album)) => {
+                track_ops::get_first_album_track(&self.db.get_connection(), album).unwrap_or_else(
+                    |err| {
+                        warn!("Error getting the first track of album {album:#?}: {err:#}");
+                        None
+                    },
+                )
+            }
+            _ => None,
+        };
+        if album_cover == self.dw.album_cover {
+            return;
+        }
+
+        self.dw.album_cover = album_cover;
+        if let Err(e) = self.update_photo() {
+            self.mount_error_popup(e.context("update_photo"));
+        }
+    }
+
+    /// Get the track whose cover is shown for the album selected in the database view, if that view is shown.
+    pub fn database_album_cover(&self) -> Option<&Path> {
+        if self.layout != TermusicLayout::DataBase {
+            return None;
+        }
+
+        self.dw.album_cover.as_deref()
     }
 
+    /// Get all smart playlists and playlist files in the music directory.
     fn database_get_playlist(&self) -> Vec<String> {
         let mut vec = Vec::new();
 
+        match smart_playlist_ops::get_all_smart_playlists(&self.db.get_connection()) {
+            Ok(smart_playlists) => vec.extend(
+                smart_playlists
+                    .into_iter()
+                    .map(|v| format!("{SMART_PLAYLIST_PREFIX}{}", v.name)),
+            ),
+            Err(err) => warn!("Error getting smart playlists: {err:#}"),
+        }
+
         let root = self.library.tree.root();
         let p: &Path = Path::new(root.id());
         let all_items = walkdir::WalkDir::new(p).follow_links(true);
@@ -753,15 +872,36 @@ impl Model {
         vec
     }
 
-    /// Find all tracks for the given [`criteria`](SearchCriteria) which matches `val`.
+    /// Find all tracks for the given [`criteria`](SearchCriteria) which matches `val`, without those the parental
+    /// filter hides.
     ///
-    /// Or for the [`Playlist`](SearchCriteria::Playlist) case, `val` is the path of the playlist.
-    #[expect(clippy::too_many_lines)]
+    /// Or for the [`Playlist`](SearchCriteria::Playlist) case, `val` is the path of the playlist
+    /// or the name of a smart playlist prefixed with [`SMART_PLAYLIST_PREFIX`].
     pub fn database_get_tracks_by_criteria(
         &mut self,
         criteria: SearchCriteria,
         val: &str,
     ) -> Option<Vec<TrackRead>> {
+        let mut tracks = self.database_get_all_tracks_by_criteria(criteria, val)?;
+        let parental = self.config_server.read().parental.clone();
+        tracks.retain(|track| parental.allows_genre(track.genre.as_deref()));
+
+        Some(tracks)
+    }
+
+    /// [`Self::database_get_tracks_by_criteria`], including the tracks the parental filter hides.
+    #[expect(clippy::too_many_lines)]
+    fn database_get_all_tracks_by_criteria(
+        &mut self,
+        criteria: SearchCriteria,
+        val: &str,
+    ) -> Option<Vec<TrackRead>> {
+        if let (SearchCriteria::Playlist, Some(name)) =
+            (criteria, val.strip_prefix(SMART_PLAYLIST_PREFIX))
+        {
+            return self.database_get_smart_playlist_tracks(name);
+        }
+
         match criteria {
             SearchCriteria::Playlist => {
                 let path = Path::new(val);
@@ -872,23 +1012,98 @@ impl Model {
 
                 return Some(result);
             }
+            SearchCriteria::Favorite => {
+                let conn = self.db.get_connection();
+                let min_rating = val
+                    .strip_prefix(RATED_PREFIX)
+                    .and_then(|v| v.trim_end_matches('+').parse::<u8>().ok());
+                let all_tracks = match min_rating {
+                    Some(min_rating) => track_ops::get_tracks_rated_at_least(
+                        &conn,
+                        min_rating,
+                        track_ops::RowOrdering::IdAsc,
+                    ),
+                    None => track_ops::get_favorite_tracks(&conn, track_ops::RowOrdering::IdAsc),
+                };
+
+                let mut result = all_tracks.unwrap_or_default();
+                result.sort_by(|a, b| {
+                    alphanumeric_sort::compare_path(a.as_pathbuf(), b.as_pathbuf())
+                });
+
+                return Some(result);
+            }
+            SearchCriteria::PlayStats => {
+                return Some(
+                    self.database_get_play_stats(val)
+                        .into_iter()
+                        .map(|(track, _)| track)
+                        .collect(),
+                );
+            }
         }
 
         None
     }
 
+    /// Get the tracks of the [`SearchCriteria::PlayStats`] result `val`, with their statistics.
+    fn database_get_play_stats(&self, val: &str) -> Vec<(TrackRead, TrackPlayStats)> {
+        let order = match val {
+            RECENTLY_PLAYED_RESULT => PlayStatsOrder::RecentlyPlayed,
+            MOST_SKIPPED_RESULT => PlayStatsOrder::MostSkipped,
+            _ => PlayStatsOrder::MostPlayed,
+        };
+
+        track_ops::get_tracks_by_play_stats(&self.db.get_connection(), order, PLAY_STATS_LIMIT)
+            .unwrap_or_default()
+    }
+
+    /// Evaluate the smart playlist `name`, showing a error popup if that fails.
+    fn database_get_smart_playlist_tracks(&mut self, name: &str) -> Option<Vec<TrackRead>> {
+        let result = {
+            let conn = self.db.get_connection();
+            smart_playlist_ops::get_smart_playlist(&conn, name).and_then(|playlist| {
+                smart_playlist_ops::get_tracks_from_smart_playlist(
+                    &conn,
+                    &playlist,
+                    track_ops::RowOrdering::IdAsc,
+                )
+            })
+        };
+
+        match result {
+            Ok(tracks) => Some(tracks),
+            Err(err) => {
+                self.mount_error_popup(err.context("smart playlist"));
+                None
+            }
+        }
+    }
+
     /// Update view `Tracks` by populating it with items from the selected `Result`(view) index.
     pub fn database_update_search_tracks(&mut self, index: usize) {
         self.dw.search_tracks.clear();
+        self.dw.search_tracks_stats.clear();
         let Some(at_index) = self.dw.search_results.get(index).cloned() else {
             return;
         };
 
-        let Some(result) = self.database_get_tracks_by_criteria(self.dw.criteria, &at_index) else {
-            return;
-        };
+        let parental = self.config_server.read().parental.clone();
+        if self.dw.criteria == SearchCriteria::PlayStats {
+            // keep the statistics to show them next to the tracks
+            (self.dw.search_tracks, self.dw.search_tracks_stats) = self
+                .database_get_play_stats(&at_index)
+                .into_iter()
+                .filter(|(track, _)| parental.allows_genre(track.genre.as_deref()))
+                .unzip();
+        } else {
+            let Some(result) = self.database_get_tracks_by_criteria(self.dw.criteria, &at_index)
+            else {
+                return;
+            };
 
-        self.dw.search_tracks = result;
+            self.dw.search_tracks = result;
+        }
 
         self.database_sync_tracks();
         self.app.active(&Id::DBListSearchTracks).ok();
@@ -955,9 +1170,16 @@ impl Model {
                 .is_ok()
         );
 
+        let had_album_cover = self.database_album_cover().is_some();
         self.dw.reset_search_results();
         self.database_sync_tracks();
         self.database_sync_results();
+
+        if had_album_cover {
+            if let Err(e) = self.update_photo() {
+                self.mount_error_popup(e.context("update_photo"));
+            }
+        }
     }
 
     fn match_record<T: Matchable>(record: &T, search: &str) -> bool {
@@ -1025,16 +1247,19 @@ impl Model {
         table.build()
     }
 
+    /// Update the general search popup with the tracks matching `input`, via the database full-text index.
     pub fn database_update_search(&mut self, input: &str) {
-        let mut db_tracks = Vec::new();
-        let all_tracks =
-            track_ops::get_all_tracks(&self.db.get_connection(), track_ops::RowOrdering::IdAsc);
-        if let Ok(all_tracks) = all_tracks {
-            db_tracks = all_tracks;
-        }
+        let mut db_tracks = match track_ops::search_tracks_fts(&self.db.get_connection(), input) {
+            Ok(v) => v,
+            Err(err) => {
+                warn!("Error searching the database: {err:#}");
+                Vec::new()
+            }
+        };
+        let parental = self.config_server.read().parental.clone();
+        db_tracks.retain(|track| parental.allows_genre(track.genre.as_deref()));
 
-        let filtered_music = Model::update_search(&db_tracks, input);
-        self.general_search_update_show(Model::build_table(filtered_music));
+        self.general_search_update_show(Model::build_table(db_tracks.iter()));
     }
 
     /// Mount the [`AddAlbumConfirm`] popup
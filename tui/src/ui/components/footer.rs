@@ -59,6 +59,15 @@ impl Footer {
                     ))
                     .fg(config.settings.theme.fallback_highlight())
                     .bold(),
+                    TextSpan::new(" Radio: ")
+                        .fg(config.settings.theme.fallback_foreground())
+                        .bold(),
+                    TextSpan::new(format!(
+                        "<{}>",
+                        config.settings.keys.select_view_keys.view_radio
+                    ))
+                    .fg(config.settings.theme.fallback_highlight())
+                    .bold(),
                     TextSpan::new(" Version: ")
                         .fg(config.settings.theme.fallback_foreground())
                         .bold(),
@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use termusiclib::config::SharedTuiSettings;
 use termusiclib::config::v2::tui::keys::Keys;
+use termusiclib::playlist::PlaylistFormat;
 use tui_realm_stdlib::Phantom;
 use tuirealm::{Component, Event, MockComponent, Sub, SubClause, SubEventClause};
 
@@ -9,8 +10,8 @@ use crate::ui::Model;
 use crate::ui::ids::{Id, IdConfigEditor, IdTagEditor};
 use crate::ui::model::UserEvent;
 use crate::ui::msg::{
-    ConfigEditorMsg, HelpPopupMsg, LyricMsg, MainLayoutMsg, Msg, PLMsg, PlayerMsg, QuitPopupMsg,
-    SavePlaylistMsg, XYWHMsg,
+    ConfigEditorMsg, ErrorPopupMsg, HelpPopupMsg, LyricEditorMsg, LyricMsg, MainLayoutMsg, Msg,
+    PLMsg, PlayerMsg, QuitPopupMsg, SavePlaylistMsg, XYWHMsg,
 };
 
 #[derive(MockComponent)]
@@ -57,6 +58,11 @@ impl Component<Msg, UserEvent> for GlobalListener {
             Event::Keyboard(keyevent) if keyevent == keys.select_view_keys.open_help.get() => {
                 Some(Msg::HelpPopup(HelpPopupMsg::Show))
             }
+            Event::Keyboard(keyevent)
+                if keyevent == keys.select_view_keys.open_error_history.get() =>
+            {
+                Some(Msg::ErrorPopup(ErrorPopupMsg::HistoryShow))
+            }
             Event::Keyboard(keyevent) if keyevent == keys.player_keys.seek_forward.get() => {
                 Some(Msg::Player(PlayerMsg::SeekForward))
             }
@@ -69,6 +75,15 @@ impl Component<Msg, UserEvent> for GlobalListener {
             Event::Keyboard(keyevent) if keyevent == keys.player_keys.speed_down.get() => {
                 Some(Msg::Player(PlayerMsg::SpeedDown))
             }
+            Event::Keyboard(keyevent) if keyevent == keys.player_keys.track_speed.get() => {
+                Some(Msg::Player(PlayerMsg::ToggleTrackSpeed))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.player_keys.ab_repeat.get() => {
+                Some(Msg::Player(PlayerMsg::CycleAbRepeat))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.player_keys.bookmarks.get() => {
+                Some(Msg::Player(PlayerMsg::BookmarksShow))
+            }
 
             Event::Keyboard(keyevent)
                 if keyevent == keys.lyric_keys.adjust_offset_forwards.get() =>
@@ -83,6 +98,12 @@ impl Component<Msg, UserEvent> for GlobalListener {
             Event::Keyboard(keyevent) if keyevent == keys.lyric_keys.cycle_frames.get() => {
                 Some(Msg::LyricMessage(LyricMsg::Cycle))
             }
+            Event::Keyboard(keyevent) if keyevent == keys.lyric_keys.capture_caption.get() => {
+                Some(Msg::LyricMessage(LyricMsg::Capture))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.lyric_keys.open_editor.get() => {
+                Some(Msg::LyricEditor(LyricEditorMsg::Show))
+            }
 
             Event::Keyboard(keyevent) if keyevent == keys.select_view_keys.view_library.get() => {
                 Some(Msg::Layout(MainLayoutMsg::TreeView))
@@ -96,6 +117,10 @@ impl Component<Msg, UserEvent> for GlobalListener {
                 Some(Msg::Layout(MainLayoutMsg::Podcast))
             }
 
+            Event::Keyboard(keyevent) if keyevent == keys.select_view_keys.view_radio.get() => {
+                Some(Msg::Layout(MainLayoutMsg::Radio))
+            }
+
             Event::Keyboard(keyevent) if keyevent == keys.player_keys.toggle_prefetch.get() => {
                 Some(Msg::Player(PlayerMsg::ToggleGapless))
             }
@@ -104,8 +129,26 @@ impl Component<Msg, UserEvent> for GlobalListener {
                 Some(Msg::ConfigEditor(ConfigEditorMsg::Open))
             }
 
-            Event::Keyboard(keyevent) if keyevent == keys.player_keys.save_playlist.get() => {
-                Some(Msg::SavePlaylist(SavePlaylistMsg::PopupShow))
+            Event::Keyboard(keyevent) if keyevent == keys.player_keys.save_playlist.get() => Some(
+                Msg::SavePlaylist(SavePlaylistMsg::PopupShow(PlaylistFormat::M3u)),
+            ),
+            Event::Keyboard(keyevent) if keyevent == keys.player_keys.chapters.get() => {
+                Some(Msg::Player(PlayerMsg::ChaptersShow))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.player_keys.next_chapter.get() => {
+                Some(Msg::Player(PlayerMsg::NextChapter))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.player_keys.previous_chapter.get() => {
+                Some(Msg::Player(PlayerMsg::PreviousChapter))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.player_keys.goto_time.get() => {
+                Some(Msg::Player(PlayerMsg::GotoTimeShow))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.player_keys.cast.get() => {
+                Some(Msg::Player(PlayerMsg::CastShow))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.player_keys.audio_info.get() => {
+                Some(Msg::Player(PlayerMsg::AudioInfoShow))
             }
             Event::Keyboard(keyevent) if keyevent == keys.move_cover_art_keys.move_left.get() => {
                 Some(Msg::Xywh(XYWHMsg::MoveLeft))
@@ -176,6 +219,18 @@ impl Model {
                 SubEventClause::Keyboard(keys.player_keys.speed_down.get()),
                 no_popup_clause.clone(),
             ),
+            Sub::new(
+                SubEventClause::Keyboard(keys.player_keys.track_speed.get()),
+                no_popup_clause.clone(),
+            ),
+            Sub::new(
+                SubEventClause::Keyboard(keys.player_keys.ab_repeat.get()),
+                no_popup_clause.clone(),
+            ),
+            Sub::new(
+                SubEventClause::Keyboard(keys.player_keys.bookmarks.get()),
+                no_popup_clause.clone(),
+            ),
             Sub::new(
                 SubEventClause::Keyboard(keys.player_keys.volume_down.get()),
                 no_popup_clause.clone(),
@@ -196,6 +251,10 @@ impl Model {
                 SubEventClause::Keyboard(keys.select_view_keys.open_help.get()),
                 no_popup_clause.clone(),
             ),
+            Sub::new(
+                SubEventClause::Keyboard(keys.select_view_keys.open_error_history.get()),
+                no_popup_clause.clone(),
+            ),
             Sub::new(
                 SubEventClause::Keyboard(keys.player_keys.seek_forward.get()),
                 no_popup_clause.clone(),
@@ -216,6 +275,14 @@ impl Model {
                 SubEventClause::Keyboard(keys.lyric_keys.cycle_frames.get()),
                 no_popup_clause.clone(),
             ),
+            Sub::new(
+                SubEventClause::Keyboard(keys.lyric_keys.capture_caption.get()),
+                no_popup_clause.clone(),
+            ),
+            Sub::new(
+                SubEventClause::Keyboard(keys.lyric_keys.open_editor.get()),
+                no_popup_clause.clone(),
+            ),
             Sub::new(
                 SubEventClause::Keyboard(keys.select_view_keys.view_library.get()),
                 no_popup_clause.clone(),
@@ -236,8 +303,36 @@ impl Model {
                 SubEventClause::Keyboard(keys.player_keys.save_playlist.get()),
                 no_popup_clause.clone(),
             ),
+            Sub::new(
+                SubEventClause::Keyboard(keys.player_keys.chapters.get()),
+                no_popup_clause.clone(),
+            ),
+            Sub::new(
+                SubEventClause::Keyboard(keys.player_keys.next_chapter.get()),
+                no_popup_clause.clone(),
+            ),
+            Sub::new(
+                SubEventClause::Keyboard(keys.player_keys.previous_chapter.get()),
+                no_popup_clause.clone(),
+            ),
+            Sub::new(
+                SubEventClause::Keyboard(keys.player_keys.goto_time.get()),
+                no_popup_clause.clone(),
+            ),
+            Sub::new(
+                SubEventClause::Keyboard(keys.player_keys.cast.get()),
+                no_popup_clause.clone(),
+            ),
+            Sub::new(
+                SubEventClause::Keyboard(keys.player_keys.audio_info.get()),
+                no_popup_clause.clone(),
+            ),
             Sub::new(
                 SubEventClause::Keyboard(keys.select_view_keys.view_podcasts.get()),
+                no_popup_clause.clone(),
+            ),
+            Sub::new(
+                SubEventClause::Keyboard(keys.select_view_keys.view_radio.get()),
                 no_popup_clause,
             ),
             Sub::new(
@@ -282,6 +377,7 @@ impl Model {
         let mut collection = Vec::new();
 
         Self::podcast_popups(&mut collection);
+        Self::radio_popups(&mut collection);
         Self::general_popups(&mut collection);
 
         // dont leave much unused space, as this vec will basically stay for the entire duration of the app
@@ -298,11 +394,26 @@ impl Model {
         storage.extend([
             SubClause::IsMounted(Id::FeedDeleteConfirmRadioPopup),
             SubClause::IsMounted(Id::FeedDeleteConfirmInputPopup),
+            SubClause::IsMounted(Id::EpisodeDownloadConfirmPopup),
+            SubClause::IsMounted(Id::EpisodeMenuPopup),
+            SubClause::IsMounted(Id::EpisodeInfoPopup),
+            SubClause::IsMounted(Id::FeedInfoPopup),
+            SubClause::IsMounted(Id::FeedMergeTablePopup),
+            SubClause::IsMounted(Id::FeedMergeConfirmPopup),
+            SubClause::IsMounted(Id::FeedSettingsPopup),
             SubClause::IsMounted(Id::PodcastSearchTablePopup),
             SubClause::IsMounted(Id::PodcastAddPopup),
         ]);
     }
 
+    /// Radio related popups.
+    ///
+    /// The values added to `storage` are meant to be used in a [`SubClause::OrMany`].
+    #[inline]
+    fn radio_popups(storage: &mut Vec<SubClause<Id>>) {
+        storage.push(SubClause::IsMounted(Id::RadioSearchPopup));
+    }
+
     /// Popups that dont relate to any other place specifically.
     ///
     /// The values added to `storage` are meant to be used in a [`SubClause::OrMany`].
@@ -318,7 +429,26 @@ impl Model {
             SubClause::IsMounted(Id::ConfigEditor(IdConfigEditor::Footer)),
             SubClause::IsMounted(Id::SavePlaylistPopup),
             SubClause::IsMounted(Id::SavePlaylistConfirm),
+            SubClause::IsMounted(Id::PlaylistFormatPopup),
+            SubClause::IsMounted(Id::ImportPlaylistPopup),
+            SubClause::IsMounted(Id::TrackMenuPopup),
+            SubClause::IsMounted(Id::TrackInfoPopup),
             SubClause::IsMounted(Id::DatabaseAddConfirmPopup),
+            SubClause::IsMounted(Id::DatabaseBackupPopup),
+            SubClause::IsMounted(Id::DatabaseRestoreConfirmPopup),
+            SubClause::IsMounted(Id::ChapterListPopup),
+            SubClause::IsMounted(Id::GotoTimePopup),
+            SubClause::IsMounted(Id::CastRendererPopup),
+            SubClause::IsMounted(Id::AudioInfoPopup),
+            SubClause::IsMounted(Id::QueuePopup),
+            SubClause::IsMounted(Id::QueueNewPopup),
+            SubClause::IsMounted(Id::BookmarkPopup),
+            SubClause::IsMounted(Id::BookmarkNewPopup),
+            SubClause::IsMounted(Id::OrganizePopup),
+            SubClause::IsMounted(Id::TagEditor(IdTagEditor::BulkEditor)),
+            SubClause::IsMounted(Id::TagEditor(IdTagEditor::BulkField)),
+            SubClause::IsMounted(Id::LyricEditorPopup),
+            SubClause::IsMounted(Id::LyricCaptionInputPopup),
         ]);
     }
 
@@ -338,10 +468,11 @@ impl Model {
     ///
     /// The values returned are meant to be used in a [`SubClause::OrMany`].
     #[inline]
-    fn delete_confirm_popups() -> [SubClause<Id>; 2] {
+    fn delete_confirm_popups() -> [SubClause<Id>; 3] {
         [
             SubClause::IsMounted(Id::DeleteConfirmInputPopup),
             SubClause::IsMounted(Id::DeleteConfirmRadioPopup),
+            SubClause::IsMounted(Id::UndoDeletePopup),
         ]
     }
 
@@ -349,10 +480,11 @@ impl Model {
     ///
     /// The values returned are meant to be used in a [`SubClause::OrMany`].
     #[inline]
-    fn everywhere_popups() -> [SubClause<Id>; 3] {
+    fn everywhere_popups() -> [SubClause<Id>; 4] {
         [
             SubClause::IsMounted(Id::HelpPopup),
             SubClause::IsMounted(Id::ErrorPopup),
+            SubClause::IsMounted(Id::ErrorHistoryPopup),
             SubClause::IsMounted(Id::QuitPopup),
         ]
     }
@@ -6,6 +6,7 @@ use termusiclib::common::const_unknown::{UNKNOWN_ARTIST, UNKNOWN_TITLE};
 use termusiclib::config::SharedTuiSettings;
 use termusiclib::player::RunningStatus;
 use termusiclib::podcast::episode::Episode;
+use termusiclib::track::DurationFmtShort;
 use termusiclib::track::MediaTypes;
 use termusiclib::track::MediaTypesSimple;
 use tui_realm_stdlib::Textarea;
@@ -282,7 +283,8 @@ impl Model {
             {
                 self.current_track_lyric.take();
                 if track.as_track().is_none() {
-                    self.lyric_set_lyric(NO_LYRICS);
+                    // podcasts have no lyrics, but may have chapters
+                    self.lyric_set_chapter_or(NO_LYRICS);
                     return;
                 }
 
@@ -339,6 +341,40 @@ impl Model {
         }
     }
 
+    /// Show the current chapter and the time elapsed in it, or `fallback` if there is no chapter at the current position.
+    ///
+    /// Needs to be run each time a new playback position is available, like [`lyric_update`](Model::lyric_update).
+    fn lyric_set_chapter_or(&mut self, fallback: &str) {
+        let Some(index) = self.playback.current_chapter_index() else {
+            self.lyric_set_lyric(fallback);
+            return;
+        };
+        let chapters = self.playback.chapters();
+        let chapter = &chapters[index];
+        let elapsed = self
+            .playback
+            .current_track_pos()
+            .saturating_sub(chapter.start);
+
+        let lines = vec![
+            PropValue::TextSpan(
+                TextSpan::from(format!("Chapter {}/{}", index + 1, chapters.len())).italic(),
+            ),
+            PropValue::TextSpan(TextSpan::from(&chapter.title).bold()),
+            PropValue::TextSpan(TextSpan::from(format!(
+                "{} into the chapter",
+                DurationFmtShort(elapsed)
+            ))),
+        ];
+        self.app
+            .attr(
+                &Id::Lyric,
+                Attribute::Text,
+                AttrValue::Payload(PropPayload::Vec(lines)),
+            )
+            .ok();
+    }
+
     /// Set the given text as the current displayed lyric text.
     fn lyric_set_lyric<T: Into<String>>(&mut self, text: T) {
         let text = text.into();
@@ -357,7 +393,7 @@ impl Model {
         if let Some(extra) = self.current_track_lyric.as_mut() {
             if let Some(f) = extra.cycle_lyric().ok().flatten() {
                 let lang_ext = f.description.clone();
-                self.update_show_message_timeout(
+                self.show_toast(
                     "Lyric switch successful",
                     format!("{lang_ext} lyric is showing").as_str(),
                     None,
@@ -389,6 +425,72 @@ impl Model {
         }
     }
 
+    /// Insert a caption at the current position into the lyrics of the current track and save them to its tag.
+    ///
+    /// See [`TETrack::lyric_capture`].
+    pub fn lyric_capture(&mut self) {
+        let time_pos = self.playback.current_track_pos();
+        let Some(mut te_track) = self.lyric_te_track() else {
+            return;
+        };
+        let index = te_track.lyric_capture(time_pos);
+        self.lyric_save(te_track, index, "capture lyric timestamp");
+    }
+
+    /// Set the text of the caption at `index` of the current lyrics and save them to the tag.
+    pub fn lyric_edit_caption(&mut self, index: usize, text: &str) {
+        let Some(mut te_track) = self.lyric_te_track() else {
+            return;
+        };
+        if te_track.lyric_set_caption_text(index, text).is_some() {
+            self.lyric_save(te_track, index, "edit lyric caption");
+        }
+    }
+
+    /// Remove the caption at `index` of the current lyrics and save them to the tag.
+    pub fn lyric_remove_caption(&mut self, index: usize) {
+        let Some(mut te_track) = self.lyric_te_track() else {
+            return;
+        };
+        if te_track.lyric_remove_caption(index).is_some() {
+            self.lyric_save(te_track, index, "remove lyric caption");
+        }
+    }
+
+    /// Get the current track to edit its lyrics, with the currently shown lyrics applied.
+    fn lyric_te_track(&self) -> Option<TETrack> {
+        let track = self.playback.current_track()?;
+        let Ok(mut te_track) = TETrack::try_from(track) else {
+            debug!("Could not edit lyrics because it is not a music track!");
+            return None;
+        };
+        // there are no lyrics loaded if the track has none, in which case new ones are created
+        if self.current_track_lyric.is_some()
+            && te_track
+                .lyric_set_with_extra(self.current_track_lyric.as_ref())
+                .is_none()
+        {
+            debug!("Could not edit lyrics because of mismatching extra data and current track!");
+            return None;
+        }
+
+        Some(te_track)
+    }
+
+    /// Save the lyrics of `te_track` to its tag and show them, also in the lyric editor with `selected` selected if it is open.
+    fn lyric_save(&mut self, mut te_track: TETrack, selected: usize, context: &'static str) {
+        let res = te_track.save_tag();
+        self.current_track_lyric = Some(te_track.into_extra_lyric_data());
+        self.lyric_update();
+        if self.app.mounted(&Id::LyricEditorPopup) {
+            self.mount_lyric_editor(selected);
+        }
+
+        if let Err(e) = res {
+            self.mount_error_popup(e.context(context));
+        }
+    }
+
     const LYRIC_PODCAST_TITLE: &str = " Details: ";
 
     /// Update the Lyric Component's title.
@@ -10,6 +10,7 @@ mod playlist;
 mod podcast;
 mod popups;
 mod progress;
+mod radio;
 mod raw;
 #[allow(
     clippy::match_bool,
@@ -19,7 +20,10 @@ mod raw;
 )]
 /// Tag Editor Controls
 mod tag_editor;
+mod toasts;
 mod vendored;
+#[cfg(test)]
+mod view_snapshots;
 mod xywh;
 
 // -- export
@@ -29,9 +33,12 @@ pub use footer::Footer;
 pub use global_listener::GlobalListener;
 pub use labels::{DownloadSpinner, LabelGeneric, LabelSpan};
 pub use lyric::Lyric;
-pub use music_library::MusicLibrary;
+pub use music_library::{LibraryFilter, MusicLibrary};
 pub use playlist::Playlist;
-pub use podcast::{EpisodeList, FeedsList};
+pub use podcast::{EpisodeList, FeedCategoryTabs, FeedsList};
+pub use popups::ErrorHistory;
 pub use popups::general_search::{GSInputPopup, GSTablePopup, Source};
-pub use progress::Progress;
+pub use progress::{LibraryScanProgress, Progress, QueuePreview};
+pub use radio::RadioStationList;
 pub use tag_editor::*;
+pub use toasts::{ToastArea, Toasts};
@@ -1,22 +1,107 @@
-use std::fs::{DirEntry, remove_dir_all, remove_file, rename};
+use std::fs::{DirEntry, rename};
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result, bail};
 use termusiclib::config::SharedTuiSettings;
 use termusiclib::config::v2::server::ScanDepth;
 use termusiclib::config::v2::server::config_extra::ServerConfigVersionedDefaulted;
+use termusiclib::new_database::{
+    Database, DirStats, ScanProgress, ScanProgressFn, audiobook_ops, track_ops,
+};
+use termusiclib::parental::ParentalFilter;
+use termusiclib::track::cue;
+use termusiclib::utils::filetype_supported;
 use tui_realm_treeview::{Node, TREE_CMD_CLOSE, TREE_CMD_OPEN, TREE_INITIAL_NODE, Tree, TreeView};
 use tuirealm::command::{Cmd, CmdResult, Direction, Position};
 use tuirealm::event::{Key, KeyEvent, KeyModifiers};
 use tuirealm::props::{Alignment, BorderType, Borders, TableBuilder, TextSpan};
 use tuirealm::{AttrValue, Attribute, Component, Event, MockComponent, State, StateValue};
 
+use crate::instance_lock;
+use crate::ui::components::LibraryScanProgress;
 use crate::ui::ids::Id;
+use crate::ui::model::trash::{TrashedItems, delete_path};
 use crate::ui::model::{DownloadTracker, Model, TxToMain, UserEvent};
-use crate::ui::msg::{DeleteConfirmMsg, GSMsg, LIMsg, Msg, PLMsg, RecVec, TEMsg, YSMsg};
+use crate::ui::msg::{
+    DeleteConfirmMsg, GSMsg, LIMsg, Msg, PLMsg, RecVec, TEMsg, TrackMenuAction, TrackMenuMsg,
+    TrackMenuTarget, YSMsg,
+};
 use crate::ui::tui_cmd::TuiCmd;
 use crate::utils::get_pin_yin;
 
+/// Filters for which files are shown in the library tree, directories are always shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LibraryFilter {
+    /// Show all files
+    #[default]
+    None,
+    /// Only show supported audio files
+    AudioOnly,
+    /// Only show audio files which are not in the database
+    NotInDatabase,
+    /// Only show audio files which are in the database, but have no title or artist
+    MissingTags,
+}
+
+impl LibraryFilter {
+    /// Get the filter to switch to next.
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::None => Self::AudioOnly,
+            Self::AudioOnly => Self::NotInDatabase,
+            Self::NotInDatabase => Self::MissingTags,
+            Self::MissingTags => Self::None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::AudioOnly => "audio files only",
+            Self::NotInDatabase => "not in database",
+            Self::MissingTags => "missing tags",
+        }
+    }
+
+    /// Check whether the file at `path` should be shown with the current filter.
+    fn matches(self, path: &Path, db: &Database) -> bool {
+        if self == Self::None {
+            return true;
+        }
+        // cue sheets are shown as they can be added like a album
+        if !filetype_supported(path) && !cue::is_cue_sheet(path) {
+            return false;
+        }
+
+        match self {
+            Self::None | Self::AudioOnly => true,
+            Self::NotInDatabase => {
+                !track_ops::track_exists(&db.get_connection(), path).unwrap_or(true)
+            }
+            Self::MissingTags => {
+                let conn = db.get_connection();
+                track_ops::track_exists(&conn, path).unwrap_or(false)
+                    && !track_ops::get_track_from_path(&conn, path)
+                        .is_ok_and(|v| v.title.is_some() && v.artist_display.is_some())
+            }
+        }
+    }
+}
+
+/// Check whether the file at `path` is not hidden by `parental`, using its genre from the database if it is known.
+fn parental_allows(parental: &ParentalFilter, path: &Path, db: &Database) -> bool {
+    if parental.genres.is_empty() {
+        return true;
+    }
+
+    match track_ops::get_track_from_path(&db.get_connection(), path) {
+        Ok(track) => parental.allows_genre(track.genre.as_deref()),
+        Err(_) => parental.allows_file(path),
+    }
+}
+
 #[derive(MockComponent)]
 pub struct MusicLibrary {
     component: TreeView<String>,
@@ -221,6 +306,25 @@ impl Component<Msg, UserEvent> for MusicLibrary {
             Event::Keyboard(keyevent) if keyevent == keys.library_keys.remove_root.get() => {
                 return Some(Msg::Library(LIMsg::RemoveRoot));
             }
+            Event::Keyboard(keyevent) if keyevent == keys.library_keys.rescan.get() => {
+                return Some(Msg::Library(LIMsg::Rescan));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.library_keys.cycle_filter.get() => {
+                return Some(Msg::Library(LIMsg::CycleFilter));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.library_keys.organize.get() => {
+                return Some(Msg::Library(LIMsg::OrganizeShow));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.library_keys.toggle_audiobook.get() => {
+                let current_node = self.component.tree_state().selected().unwrap();
+                return Some(Msg::Library(LIMsg::ToggleAudiobook(
+                    current_node.to_string(),
+                )));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.library_keys.open_external.get() => {
+                let current_node = self.component.tree_state().selected().unwrap();
+                return Some(Msg::Library(LIMsg::OpenExternal(current_node.to_string())));
+            }
             Event::Keyboard(keyevent) if keyevent == keys.library_keys.search.get() => {
                 return Some(Msg::GeneralSearch(GSMsg::PopupShowLibrary));
             }
@@ -232,6 +336,32 @@ impl Component<Msg, UserEvent> for MusicLibrary {
                 let current_node = self.component.tree_state().selected().unwrap();
                 return Some(Msg::TagEditor(TEMsg::Open(current_node.to_string())));
             }
+            Event::Keyboard(keyevent) if keyevent == keys.library_keys.mark.get() => {
+                let current_node = self.component.tree_state().selected().unwrap();
+                return Some(Msg::TagEditor(TEMsg::ToggleMark(PathBuf::from(
+                    current_node,
+                ))));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.library_keys.track_menu.get() => {
+                let current_node = self.component.tree_state().selected().unwrap();
+                return Some(Msg::TrackMenu(TrackMenuMsg::Show(
+                    TrackMenuTarget::Library(PathBuf::from(current_node)),
+                )));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.library_keys.cycle_rating.get() => {
+                let current_node = self.component.tree_state().selected().unwrap();
+                return Some(Msg::TrackMenu(TrackMenuMsg::Run(
+                    TrackMenuTarget::Library(PathBuf::from(current_node)),
+                    TrackMenuAction::CycleRating,
+                )));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.library_keys.toggle_favorite.get() => {
+                let current_node = self.component.tree_state().selected().unwrap();
+                return Some(Msg::TrackMenu(TrackMenuMsg::Run(
+                    TrackMenuTarget::Library(PathBuf::from(current_node)),
+                    TrackMenuAction::ToggleFavorite,
+                )));
+            }
 
             _ => CmdResult::None,
         };
@@ -239,6 +369,7 @@ impl Component<Msg, UserEvent> for MusicLibrary {
             CmdResult::Submit(State::One(StateValue::String(node))) => {
                 Some(Msg::Library(LIMsg::TreeStepInto(node)))
             }
+            CmdResult::Changed(_) => Some(Msg::Library(LIMsg::TreeSelectionChanged)),
             CmdResult::None => None,
             _ => Some(Msg::ForceRedraw),
         }
@@ -262,6 +393,11 @@ impl Model {
             path,
             ScanDepth::Limited(2),
             focus_node,
+            (
+                self.library.filter,
+                self.config_server.read().parental.clone(),
+                self.db.clone(),
+            ),
         );
     }
 
@@ -278,13 +414,15 @@ impl Model {
         path: P,
         depth: ScanDepth,
         focus_node: Option<String>,
+        filter: (LibraryFilter, ParentalFilter, Database),
     ) {
         let path = path.into();
         std::thread::Builder::new()
             .name("library tree scan".to_string())
             .spawn(move || {
                 download_tracker.increase_one(path.to_string_lossy());
-                let root_node = Self::library_dir_tree(&path, depth);
+                let root_node =
+                    Self::library_dir_tree(&path, depth, filter.0, &filter.1, &filter.2);
 
                 let _ = tx.send(Msg::Library(LIMsg::TreeNodeReady(root_node, focus_node)));
                 download_tracker.decrease_one(&path.to_string_lossy());
@@ -294,8 +432,16 @@ impl Model {
 
     /// Scan the given `path` for up to `depth`, and return a [`Node`] tree.
     ///
+    /// Files not matching `filter` or hidden by `parental` are left out.
+    ///
     /// Note: consider using [`Self::library_scan`] instead of this directly.
-    fn library_dir_tree(path: &Path, depth: ScanDepth) -> RecVec<PathBuf, String> {
+    fn library_dir_tree(
+        path: &Path,
+        depth: ScanDepth,
+        filter: LibraryFilter,
+        parental: &ParentalFilter,
+        db: &Database,
+    ) -> RecVec<PathBuf, String> {
         let name: String = match path.file_name() {
             None => "/".to_string(),
             Some(n) => n.to_string_lossy().into_owned(),
@@ -317,14 +463,24 @@ impl Model {
                 let mut paths: Vec<(String, PathBuf)> = paths
                     .filter_map(std::result::Result::ok)
                     .filter(|p| !p.file_name().to_string_lossy().starts_with('.'))
+                    .filter(|p| {
+                        let path = p.path();
+                        path.is_dir()
+                            || (filter.matches(&path, db) && parental_allows(parental, &path, db))
+                    })
                     .map(|v| (get_pin_yin(&v.file_name().to_string_lossy()), v.path()))
                     .collect();
 
                 paths.sort_by(|a, b| alphanumeric_sort::compare_str(&a.0, &b.0));
 
                 for p in paths {
-                    node.children
-                        .push(Self::library_dir_tree(&p.1, ScanDepth::Limited(depth - 1)));
+                    node.children.push(Self::library_dir_tree(
+                        &p.1,
+                        ScanDepth::Limited(depth - 1),
+                        filter,
+                        parental,
+                        db,
+                    ));
                 }
             }
         }
@@ -343,9 +499,22 @@ impl Model {
                 paths.sort_by(|a, b| alphanumeric_sort::compare_str(&a.0, &b.0));
 
                 for (_, p) in paths {
-                    if !p.path().is_dir() {
-                        children.push(String::from(p.path().to_string_lossy()));
+                    let path = p.path();
+                    if path.is_dir() || cue::has_cue_sheet(&path) {
+                        continue;
+                    }
+
+                    // add the tracks of cue sheets instead of the audio file they split
+                    if cue::is_cue_sheet(&path) {
+                        match cue::track_paths(&path) {
+                            Ok(tracks) => children
+                                .extend(tracks.iter().map(|v| v.to_string_lossy().to_string())),
+                            Err(err) => warn!("Error reading cue sheet: {err:#}"),
+                        }
+                        continue;
                     }
+
+                    children.push(String::from(path.to_string_lossy()));
                 }
             }
         }
@@ -358,16 +527,61 @@ impl Model {
             self.library.tree_path.as_path(),
             &self.config_server.read_recursive(),
             false,
+            Some(self.library_scan_progress_fn()),
         ) {
             error!(
                 "Error scanning path {:#?}: {err:#?}",
                 self.library.tree_path.display()
             );
         }
+        self.library.dir_stats.clear();
         self.database_reload();
         self.library_scan_dir(&self.library.tree_path, node);
     }
 
+    /// Get a function sending the progress of a database scan to the main loop, see [`LIMsg::ScanProgress`].
+    pub fn library_scan_progress_fn(&self) -> ScanProgressFn {
+        let tx = self.tx_to_main.clone();
+        Box::new(move |progress| {
+            let _ = tx.send(Msg::Library(LIMsg::ScanProgress(progress)));
+        })
+    }
+
+    /// Show the progress of a database scan in the footer, or what changed once it is finished.
+    pub fn library_show_scan_progress(&mut self, progress: ScanProgress) {
+        if !progress.finished {
+            assert!(
+                self.app
+                    .remount(
+                        Id::LibraryScanProgress,
+                        Box::new(LibraryScanProgress::new(&self.config_tui.read(), progress)),
+                        vec![]
+                    )
+                    .is_ok()
+            );
+            return;
+        }
+
+        if self.app.mounted(&Id::LibraryScanProgress) {
+            assert!(self.app.umount(&Id::LibraryScanProgress).is_ok());
+        }
+        if progress.parsed == 0 && progress.errors == 0 {
+            return;
+        }
+
+        // the statistics may include new tracks now
+        self.library.dir_stats.clear();
+        self.show_message_timeout_label_help(
+            format!(
+                "Library scan finished: {} tracks read, {} could not be read",
+                progress.parsed, progress.errors
+            ),
+            None,
+            None,
+            Some(5),
+        );
+    }
+
     /// Convert a [`RecVec`] to a [`Node`].
     fn recvec_to_node(vec: RecVec<PathBuf, String>) -> Node<String> {
         let mut node = Node::new(vec.id.to_string_lossy().to_string(), vec.value);
@@ -425,6 +639,105 @@ impl Model {
                 AttrValue::String(id),
             );
         }
+
+        self.library_show_dir_stats();
+    }
+
+    /// Show the statistics of the selected directory in the library title.
+    ///
+    /// The statistics are computed in the background on first request and cached until the next library reload.
+    pub fn library_show_dir_stats(&mut self) {
+        let path = match self.app.state(&Id::Library) {
+            Ok(State::One(StateValue::String(node_id))) => PathBuf::from(node_id),
+            _ => return,
+        };
+
+        if !path.is_dir() {
+            self.library_set_title(None, false);
+            return;
+        }
+
+        let audiobook = audiobook_ops::is_audiobook_dir(&self.db.get_connection(), &path)
+            .inspect_err(|err| warn!("Error checking audiobook {}: {err:#}", path.display()))
+            .unwrap_or_default();
+        match self.library.dir_stats.get(&path) {
+            Some(Some(stats)) => {
+                let stats = *stats;
+                self.library_set_title(Some(stats), audiobook);
+            }
+            // already being computed
+            Some(None) => self.library_set_title(None, audiobook),
+            None => {
+                self.library_set_title(None, audiobook);
+                self.library.dir_stats.insert(path.clone(), None);
+
+                let db = self.db.clone();
+                let tx = self.tx_to_main.clone();
+                std::thread::Builder::new()
+                    .name("library dir stats".to_string())
+                    .spawn(move || match db.dir_stats(&path) {
+                        Ok(stats) => {
+                            let _ = tx.send(Msg::Library(LIMsg::DirStatsReady(path, stats)));
+                        }
+                        Err(err) => {
+                            warn!("Error getting stats for {:#?}: {err:#?}", path.display());
+                        }
+                    })
+                    .expect("Failed to spawn thread");
+            }
+        }
+    }
+
+    /// Set the library title, with the given directory statistics if any and whether it is a audiobook.
+    fn library_set_title(&mut self, stats: Option<DirStats>, audiobook: bool) {
+        let info = match (stats, audiobook) {
+            (Some(stats), true) => Some(format!("{stats} | audiobook")),
+            (Some(stats), false) => Some(stats.to_string()),
+            (None, true) => Some("audiobook".to_string()),
+            (None, false) => None,
+        };
+        let title = match info {
+            Some(info) => format!(" Library | {info} "),
+            None => " Library ".to_string(),
+        };
+
+        let _ = self.app.attr(
+            &Id::Library,
+            Attribute::Title,
+            AttrValue::Title((title, Alignment::Left)),
+        );
+    }
+
+    /// Toggle whether the directory `path`, or the directory of the file `path`, is played as a audiobook.
+    pub fn library_toggle_audiobook(&mut self, path: &Path) {
+        let dir = if path.is_dir() {
+            path
+        } else {
+            let Some(parent) = path.parent() else {
+                return;
+            };
+            parent
+        };
+
+        let conn = self.db.get_connection();
+        let res = audiobook_ops::is_audiobook_dir(&conn, dir).and_then(|enabled| {
+            audiobook_ops::set_audiobook(&conn, dir, !enabled).map(|()| !enabled)
+        });
+        drop(conn);
+
+        match res {
+            Ok(enabled) => {
+                let state = if enabled { "on" } else { "off" };
+                self.show_message_timeout_label_help(
+                    format!("Audiobook mode {state} for {}", dir.display()),
+                    None,
+                    None,
+                    Some(3),
+                );
+            }
+            Err(err) => self.mount_error_popup(err.context("toggle audiobook")),
+        }
+        self.library_show_dir_stats();
     }
 
     /// Handle stepping into a node on the tree
@@ -456,12 +769,11 @@ impl Model {
         if let Ok(State::One(StateValue::String(node_id))) = self.app.state(&Id::Library) {
             if let Some(mut route) = self.library.tree.root().route_by_node(&node_id) {
                 let p: &Path = Path::new(node_id.as_str());
-                if p.is_file() {
-                    remove_file(p)?;
-                } else {
+                if !p.is_file() {
                     p.canonicalize()?;
-                    remove_dir_all(p)?;
                 }
+                let to_trash = self.delete_to_trash();
+                delete_path(p, to_trash)?;
 
                 let mut tree = self.library.tree.clone();
                 tree.root_mut().remove_child(&node_id);
@@ -484,6 +796,10 @@ impl Model {
                 }
 
                 self.library_scan_dir(&self.library.tree_path, focus_node);
+
+                if to_trash {
+                    self.trash_offer_undo(TrashedItems::Library(vec![PathBuf::from(&node_id)]));
+                }
             }
             // this line remove the deleted songs from playlist
             self.playlist_update_library_delete();
@@ -591,6 +907,7 @@ impl Model {
                 bail!("Add root failed, same root already exists");
             }
         }
+        instance_lock::ensure_held()?;
         config_server
             .settings
             .player
@@ -619,6 +936,7 @@ impl Model {
         if vec.is_empty() {
             bail!("At least 1 root music directory should be kept");
         }
+        instance_lock::ensure_held()?;
 
         config_server.settings.player.music_dirs = vec;
         let res = ServerConfigVersionedDefaulted::save_config_path(&config_server.settings);
@@ -630,4 +948,27 @@ impl Model {
 
         Ok(())
     }
+
+    /// Switch to the next [`LibraryFilter`] and reload the tree with it.
+    pub fn library_cycle_filter(&mut self) {
+        self.library.filter = self.library.filter.next();
+        self.show_message_timeout_label_help(
+            format!("Library filter: {}", self.library.filter.as_str()),
+            None,
+            None,
+            None,
+        );
+
+        let focus_node = match self.app.state(&Id::Library) {
+            Ok(State::One(StateValue::String(node_id))) => Some(node_id),
+            _ => None,
+        };
+        self.library_scan_dir(&self.library.tree_path, focus_node);
+    }
+
+    /// Request the server to rescan all music directories.
+    pub fn library_rescan(&mut self) {
+        self.command(TuiCmd::RescanLibrary);
+        self.show_message_timeout_label_help("Library rescan started", None, None, None);
+    }
 }
@@ -1,26 +1,31 @@
-use std::borrow::Cow;
 use std::ffi::OsString;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use anyhow::{Context as _, Result, anyhow, bail};
 use rand::seq::IndexedRandom;
-use termusiclib::common::const_unknown::{UNKNOWN_ALBUM, UNKNOWN_ARTIST};
+use termusiclib::common::const_unknown::{UNKNOWN_ALBUM, UNKNOWN_ARTIST, UNKNOWN_TITLE};
 use termusiclib::config::SharedTuiSettings;
 use termusiclib::config::v2::server::LoopMode;
+use termusiclib::new_database::queue_ops::DEFAULT_QUEUE;
 use termusiclib::new_database::track_ops::TrackRead;
 use termusiclib::new_database::{album_ops, track_ops};
 use termusiclib::player::playlist_helpers::{
     PlaylistAddTrack, PlaylistPlaySpecific, PlaylistRemoveTrackIndexed, PlaylistSwapTrack,
-    PlaylistTrackSource,
+    PlaylistTrackSource, TrackAddMetadata,
 };
 use termusiclib::player::{
-    PlaylistAddTrackInfo, PlaylistLoopModeInfo, PlaylistRemoveTrackInfo, PlaylistShuffledInfo,
-    PlaylistSwapInfo,
+    PlayQueues, PlaylistAddTrackInfo, PlaylistLoopModeInfo, PlaylistQueueSwitchedInfo,
+    PlaylistRemoveTrackInfo, PlaylistReplacedInfo, PlaylistShuffledInfo, PlaylistSwapInfo,
 };
-use termusiclib::track::Track;
+use termusiclib::playlist::PlaylistFormat;
+use termusiclib::rating_tags;
 use termusiclib::track::{DurationFmtShort, PodcastTrackData};
-use termusiclib::utils::{filetype_supported, get_parent_folder, is_playlist, playlist_get_vec};
+use termusiclib::track::{Track, TrackData, cue};
+use termusiclib::url_list::{self, UrlListItem};
+use termusiclib::utils::{
+    filetype_supported, get_parent_folder, is_playlist, playlist_get_entries,
+};
 use tui_realm_stdlib::Table;
 use tuirealm::props::Borders;
 use tuirealm::props::{Alignment, BorderType, PropPayload, PropValue, TableBuilder, TextSpan};
@@ -35,8 +40,12 @@ use tuirealm::{
 
 use crate::ui::Model;
 use crate::ui::ids::Id;
+use crate::ui::model::external_open::copy_to_clipboard;
 use crate::ui::model::{TermusicLayout, UserEvent};
-use crate::ui::msg::{GSMsg, Msg, PLMsg};
+use crate::ui::msg::{
+    GSMsg, ImportPlaylistMsg, Msg, PLMsg, SavePlaylistMsg, TrackMenuAction, TrackMenuMsg,
+    TrackMenuTarget,
+};
 use crate::ui::tui_cmd::{PlaylistCmd, TuiCmd};
 
 #[derive(MockComponent)]
@@ -190,6 +199,50 @@ impl Component<Msg, UserEvent> for Playlist {
             Event::Keyboard(key) if key == keys.playlist_keys.add_random_songs.get() => {
                 return Some(Msg::Playlist(PLMsg::AddRandomTracks));
             }
+            Event::Keyboard(key) if key == keys.playlist_keys.export.get() => {
+                return Some(Msg::SavePlaylist(SavePlaylistMsg::FormatShow));
+            }
+            Event::Keyboard(key) if key == keys.playlist_keys.import.get() => {
+                return Some(Msg::ImportPlaylist(ImportPlaylistMsg::PopupShow));
+            }
+            Event::Keyboard(key) if key == keys.playlist_keys.save_queue.get() => {
+                return Some(Msg::Playlist(PLMsg::SaveQueue));
+            }
+            Event::Keyboard(key) if key == keys.playlist_keys.restore_queue.get() => {
+                return Some(Msg::Playlist(PLMsg::RestoreQueue));
+            }
+            Event::Keyboard(key) if key == keys.playlist_keys.toggle_auto_queue.get() => {
+                return Some(Msg::Playlist(PLMsg::ToggleAutoQueue));
+            }
+            Event::Keyboard(key) if key == keys.playlist_keys.queues.get() => {
+                return Some(Msg::Playlist(PLMsg::QueuesShow));
+            }
+            Event::Keyboard(key) if key == keys.playlist_keys.track_menu.get() => {
+                if let State::One(StateValue::Usize(index)) = self.state() {
+                    return Some(Msg::TrackMenu(TrackMenuMsg::Show(
+                        TrackMenuTarget::Playlist(index),
+                    )));
+                }
+                CmdResult::None
+            }
+            Event::Keyboard(key) if key == keys.playlist_keys.cycle_rating.get() => {
+                if let State::One(StateValue::Usize(index)) = self.state() {
+                    return Some(Msg::TrackMenu(TrackMenuMsg::Run(
+                        TrackMenuTarget::Playlist(index),
+                        TrackMenuAction::CycleRating,
+                    )));
+                }
+                CmdResult::None
+            }
+            Event::Keyboard(key) if key == keys.playlist_keys.toggle_favorite.get() => {
+                if let State::One(StateValue::Usize(index)) = self.state() {
+                    return Some(Msg::TrackMenu(TrackMenuMsg::Run(
+                        TrackMenuTarget::Playlist(index),
+                        TrackMenuAction::ToggleFavorite,
+                    )));
+                }
+                CmdResult::None
+            }
             _ => CmdResult::None,
         };
         match cmd_result {
@@ -273,31 +326,94 @@ impl Model {
         self.playlist_sync();
     }
 
-    /// Add a playlist (like m3u) to the playlist.
+    /// Add a playlist (like m3u) to the playlist, keeping the titles and durations it has.
     fn playlist_add_playlist(&mut self, playlist_path: &Path) -> Result<()> {
-        let vec = playlist_get_vec(playlist_path)?;
+        let entries = playlist_get_entries(playlist_path)?;
 
-        let sources = vec
+        let (sources, metadata) = entries
             .into_iter()
             .map(|v| {
-                if v.starts_with("http") {
-                    PlaylistTrackSource::Url(v)
+                let value = v.value.to_string();
+                let source = if value.starts_with("http") {
+                    PlaylistTrackSource::Url(value)
                 } else {
-                    PlaylistTrackSource::Path(v)
-                }
+                    PlaylistTrackSource::Path(value)
+                };
+                let metadata = TrackAddMetadata {
+                    title: v.title,
+                    duration: v.duration,
+                };
+
+                (source, metadata)
             })
-            .collect();
+            .unzip();
 
         self.command(TuiCmd::Playlist(PlaylistCmd::AddTrack(
             PlaylistAddTrack::new_vec(
                 u64::try_from(self.playback.playlist.len()).unwrap(),
                 sources,
-            ),
+            )
+            .with_metadata(metadata),
         )));
 
         Ok(())
     }
 
+    /// Import the playlist file or url list at `path`, relative to the folder of the currently selected library node.
+    pub fn playlist_import(&mut self, path: &str) -> Result<()> {
+        let path = self.library_current_folder()?.join(path);
+        if url_list::is_url_list(&path) {
+            return self.playlist_import_url_list(&path);
+        }
+        if !is_playlist(&path) {
+            bail!(
+                "\"{}\" is not a supported playlist file or url list",
+                path.display()
+            );
+        }
+
+        self.playlist_add_playlist(&path)
+    }
+
+    /// Add the entries of the url list at `path` to the playlist, see [`url_list`].
+    ///
+    /// Youtube videos are downloaded first and added once their download finished.
+    fn playlist_import_url_list(&mut self, path: &Path) -> Result<()> {
+        let db = &self.podcast.db_podcast;
+        let items = url_list::read_url_list(path, |url| db.get_episode_by_url(url).is_ok())?;
+
+        let mut sources = Vec::new();
+        let mut downloads = 0;
+        for item in items {
+            match item {
+                UrlListItem::Track(source) => sources.push(source),
+                UrlListItem::Youtube(url) => {
+                    self.youtube_dl(&url)?;
+                    self.enqueue_after_download.insert(url);
+                    downloads += 1;
+                }
+            }
+        }
+
+        let added = sources.len();
+        if !sources.is_empty() {
+            self.command(TuiCmd::Playlist(PlaylistCmd::AddTrack(
+                PlaylistAddTrack::new_vec(
+                    u64::try_from(self.playback.playlist.len()).unwrap(),
+                    sources,
+                ),
+            )));
+        }
+        self.show_message_timeout_label_help(
+            format!("Imported {added} entries, downloading {downloads} youtube videos"),
+            None,
+            None,
+            None,
+        );
+
+        Ok(())
+    }
+
     /// Add a podcast episode to the playlist.
     pub fn playlist_add_episode(&mut self, episode_index: usize) -> Result<()> {
         if self.podcast.podcasts.is_empty() {
@@ -313,15 +429,20 @@ impl Model {
             .get(episode_index)
             .ok_or_else(|| anyhow!("get episode selected failed."))?;
 
-        let source = PlaylistTrackSource::PodcastUrl(episode_selected.url.clone());
+        self.playlist_add_podcast_url(episode_selected.url.clone());
+
+        Ok(())
+    }
+
+    /// Add the podcast episode with `url` to the playlist, which is streamed if it is not downloaded.
+    pub fn playlist_add_podcast_url(&mut self, url: String) {
+        let source = PlaylistTrackSource::PodcastUrl(url);
         self.command(TuiCmd::Playlist(PlaylistCmd::AddTrack(
             PlaylistAddTrack::new_single(
                 u64::try_from(self.playback.playlist.len()).unwrap(),
                 source,
             ),
         )));
-
-        Ok(())
     }
 
     /// Add the `current_node`, regardless if it is a Track, dir, playlist, etc.
@@ -359,6 +480,20 @@ impl Model {
             self.playlist_add_playlist(path)?;
             return Ok(());
         }
+        if cue::is_cue_sheet(path) {
+            let sources = cue::track_paths(path)?
+                .into_iter()
+                .map(|v| PlaylistTrackSource::Path(v.to_string_lossy().to_string()))
+                .collect();
+            self.command(TuiCmd::Playlist(PlaylistCmd::AddTrack(
+                PlaylistAddTrack::new_vec(
+                    u64::try_from(self.playback.playlist.len()).unwrap(),
+                    sources,
+                ),
+            )));
+
+            return Ok(());
+        }
         let source = if path.starts_with("http") {
             PlaylistTrackSource::Url(path.to_string_lossy().to_string())
         } else {
@@ -419,11 +554,14 @@ impl Model {
     /// Handle when a playlist has added a track
     pub fn handle_playlist_add(&mut self, items: PlaylistAddTrackInfo) -> Result<()> {
         // piggyback off-of the server side implementation for now by re-parsing everything.
+        // the server sends the metadata it has, which may have come from outside the track (like a playlist file)
+        let metadata = TrackAddMetadata {
+            title: items.title,
+            duration: Some(items.duration).filter(|v| !v.is_zero()),
+        };
         self.playback.playlist.add_tracks(
-            PlaylistAddTrack {
-                at_index: items.at_index,
-                tracks: vec![items.trackid],
-            },
+            PlaylistAddTrack::new_single(items.at_index, items.trackid)
+                .with_metadata(vec![metadata]),
             &self.podcast.db_podcast,
         )?;
 
@@ -456,6 +594,7 @@ impl Model {
         self.playback.playlist.set_loop_mode(loop_mode);
         self.config_server.write().settings.player.loop_mode = loop_mode;
         self.playlist_update_title();
+        self.queue_preview_update();
         // Force a redraw as stream updates are not part of the "tick" event and so cant send "Msg"
         // but need a redraw because ofthe title change
         self.force_redraw();
@@ -505,6 +644,23 @@ impl Model {
         Ok(())
     }
 
+    /// Handle the whole playlist being replaced, like when the stored queue was restored.
+    ///
+    /// # Errors
+    ///
+    /// - when converting the current index or loading the tracks fails
+    pub fn handle_playlist_replaced(&mut self, replaced: PlaylistReplacedInfo) -> Result<()> {
+        let current_track_index = usize::try_from(replaced.tracks.current_track_index)
+            .context("convert current_track_index(u64) to usize")?;
+
+        self.playback
+            .load_from_grpc(replaced.tracks, &self.podcast.db_podcast)?;
+        self.playlist_sync();
+        self.handle_current_track_index(current_track_index, true);
+
+        Ok(())
+    }
+
     /// Handle setting the current track index in the TUI playlist and selecting the proper list item
     ///
     /// Note: currently this function is called twice per track change, once for `UpdateEvents::TrackChanged` and once for `run_playback::GetProgress`
@@ -543,6 +699,13 @@ impl Model {
 
     fn playlist_sync_podcasts(&mut self) {
         let mut table: TableBuilder = TableBuilder::default();
+        let title_format = self
+            .config_server
+            .read()
+            .settings
+            .player
+            .title_format
+            .clone();
 
         for (idx, track) in self.playback.playlist.tracks().iter().enumerate() {
             if idx > 0 {
@@ -555,7 +718,7 @@ impl Model {
                 "[--:--]".to_string()
             };
 
-            let mut title = track.title().unwrap_or("Unknown Title").to_string();
+            let mut title = track.format_title(&title_format);
             if track
                 .as_podcast()
                 .is_some_and(PodcastTrackData::has_localfile)
@@ -593,6 +756,7 @@ impl Model {
             .ok();
 
         self.playlist_update_title();
+        self.queue_preview_update();
     }
 
     pub fn playlist_sync(&mut self) {
@@ -602,6 +766,13 @@ impl Model {
         }
 
         let mut table: TableBuilder = TableBuilder::default();
+        let title_format = self
+            .config_server
+            .read()
+            .settings
+            .player
+            .title_format
+            .clone();
 
         for (idx, track) in self.playback.playlist.tracks().iter().enumerate() {
             if idx > 0 {
@@ -614,7 +785,7 @@ impl Model {
                 "[--:--]".to_string()
             };
 
-            let mut title: Cow<'_, str> = track.title().map_or_else(|| track.id_str(), Into::into);
+            let mut title = track.format_title(&title_format);
 
             let artist = track.artist().unwrap_or(UNKNOWN_ARTIST);
             let album = track
@@ -633,15 +804,24 @@ impl Model {
                         .style
                         .playlist
                         .current_track_symbol
-                )
-                .into();
+                );
             }
 
-            table
-                .add_col(TextSpan::new(duration_str.as_str()))
-                .add_col(TextSpan::new(artist).fg(tuirealm::ratatui::style::Color::LightYellow))
-                .add_col(TextSpan::new(title).bold())
-                .add_col(TextSpan::new(album));
+            if self.playback.is_playable(track) {
+                table
+                    .add_col(TextSpan::new(duration_str.as_str()))
+                    .add_col(TextSpan::new(artist).fg(tuirealm::ratatui::style::Color::LightYellow))
+                    .add_col(TextSpan::new(title).bold())
+                    .add_col(TextSpan::new(album));
+            } else {
+                // greyed out, as the backend cannot play it
+                let grey = tuirealm::ratatui::style::Color::DarkGray;
+                table
+                    .add_col(TextSpan::new(duration_str.as_str()).fg(grey))
+                    .add_col(TextSpan::new(artist).fg(grey))
+                    .add_col(TextSpan::new(title).fg(grey))
+                    .add_col(TextSpan::new(album).fg(grey));
+            }
         }
         if self.playback.playlist.is_empty() {
             table.add_col(TextSpan::from("0"));
@@ -660,6 +840,7 @@ impl Model {
             .ok();
 
         self.playlist_update_title();
+        self.queue_preview_update();
     }
 
     /// Delete a track at `index` from the playlist
@@ -693,6 +874,87 @@ impl Model {
         self.command(TuiCmd::Playlist(PlaylistCmd::Shuffle));
     }
 
+    /// Send command to store the queue in the server's database.
+    pub fn playlist_save_queue(&mut self) {
+        self.command(TuiCmd::Playlist(PlaylistCmd::SaveQueue));
+    }
+
+    /// Send command to replace the queue with the one stored in the server's database.
+    pub fn playlist_restore_queue(&mut self) {
+        self.command(TuiCmd::Playlist(PlaylistCmd::RestoreQueue));
+    }
+
+    pub fn playlist_toggle_auto_queue(&mut self) {
+        self.command(TuiCmd::Playlist(PlaylistCmd::ToggleAutoQueue));
+    }
+
+    /// Apply the auto-queue state the server responded with after toggling it.
+    pub fn handle_auto_queue_toggled(&mut self, enabled: bool) {
+        self.config_server
+            .write()
+            .settings
+            .player
+            .auto_queue
+            .enabled = enabled;
+        self.playlist_update_title();
+        let text = if enabled {
+            "Auto-queue enabled"
+        } else {
+            "Auto-queue disabled"
+        };
+        self.show_toast("Playlist", text, Some(3));
+    }
+
+    /// Show the named queues stored on the server, which are requested first.
+    pub fn playlist_show_queues(&mut self) {
+        self.mount_queues(None);
+        self.command(TuiCmd::Playlist(PlaylistCmd::GetQueues));
+    }
+
+    pub fn playlist_switch_queue(&mut self, name: String) {
+        self.umount_queues();
+        self.command(TuiCmd::Playlist(PlaylistCmd::SwitchQueue(name)));
+    }
+
+    pub fn playlist_create_queue(&mut self, name: String) {
+        self.umount_queue_new();
+        self.command(TuiCmd::Playlist(PlaylistCmd::CreateQueue(name)));
+    }
+
+    pub fn playlist_delete_queue(&mut self, name: String) {
+        self.command(TuiCmd::Playlist(PlaylistCmd::DeleteQueue(name)));
+    }
+
+    /// Apply the named queues the server responded with, refreshing their list if shown.
+    pub fn handle_queues(&mut self, queues: &PlayQueues) {
+        if !queues.active.is_empty() {
+            self.playback.set_queue(Some(queues.active.clone()));
+            self.playlist_update_title();
+        }
+        if self.app.mounted(&Id::QueuePopup) {
+            self.mount_queues(Some(queues));
+        }
+    }
+
+    /// Handle the server switching to another stored queue, which replaces the whole playlist.
+    ///
+    /// # Errors
+    ///
+    /// See [`handle_playlist_replaced`](Self::handle_playlist_replaced)
+    pub fn handle_playlist_queue_switched(
+        &mut self,
+        switched: PlaylistQueueSwitchedInfo,
+    ) -> Result<()> {
+        self.playback.set_queue(Some(switched.queue.clone()));
+        self.handle_playlist_replaced(PlaylistReplacedInfo {
+            tracks: switched.tracks,
+        })?;
+        let text = format!("Switched to queue \"{}\"", switched.queue);
+        self.show_toast("Playlist", &text, Some(3));
+
+        Ok(())
+    }
+
     /// Send command to swap 2 indexes. Does nothing if either index is out-of-bounds.
     ///
     /// # Panics
@@ -756,8 +1018,18 @@ impl Model {
             .playlist
             .use_loop_mode_symbol;
         let loop_mode = self.config_server.read().settings.player.loop_mode;
+        let auto_queue = if self.config_server.read().settings.player.auto_queue.enabled {
+            " | Auto-queue"
+        } else {
+            ""
+        };
+        // only named when not playing the default queue, to keep the title short
+        let queue = match self.playback.queue() {
+            Some(name) if name != DEFAULT_QUEUE => format!(" ({name})"),
+            _ => String::new(),
+        };
         let title = format!(
-            "\u{2500} Playlist \u{2500}\u{2500}\u{2524} Total {} tracks | {} | Mode: {} \u{251c}\u{2500}",
+            "\u{2500} Playlist{queue} \u{2500}\u{2500}\u{2524} Total {} tracks | {} | Mode: {}{auto_queue} \u{251c}\u{2500}",
             self.playback.playlist.len(),
             DurationFmtShort(duration),
             loop_mode.display(display_symbol),
@@ -778,6 +1050,12 @@ impl Model {
             return;
         };
 
+        if !self.playback.is_playable(track) {
+            let err = anyhow!("The backend cannot play \"{}\"", track.id_str());
+            self.mount_error_popup(err);
+            return;
+        }
+
         let track_source = track.as_track_source();
 
         self.command(TuiCmd::Playlist(PlaylistCmd::PlaySpecific(
@@ -817,21 +1095,30 @@ impl Model {
         Some(val)
     }
 
+    /// Get `quantity` random tracks from the library, leaving out tracks marked to never auto-play.
     pub fn playlist_get_random_tracks(&mut self, quantity: u32) -> Vec<TrackRead> {
         let mut result = Vec::with_capacity(usize::try_from(quantity).unwrap_or_default());
         let all_tracks =
             track_ops::get_all_tracks(&self.db.get_connection(), track_ops::RowOrdering::IdAsc);
-        if let Ok(vec) = all_tracks {
+        let no_autoplay =
+            track_ops::get_no_autoplay_paths(&self.db.get_connection()).unwrap_or_default();
+        if let Ok(mut vec) = all_tracks {
+            vec.retain(|v| {
+                let path = v.as_pathbuf();
+                filetype_supported(&path) && !no_autoplay.contains(&path)
+            });
+            // otherwise there would be nothing to choose from below
+            if vec.is_empty() {
+                return result;
+            }
+
             let mut i = 0;
             loop {
                 if let Some(record) = vec.choose(&mut rand::rng()) {
-                    let path = record.as_pathbuf();
-                    if filetype_supported(&path) {
-                        result.push(record.clone());
-                        i += 1;
-                        if i > quantity - 1 {
-                            break;
-                        }
+                    result.push(record.clone());
+                    i += 1;
+                    if i > quantity - 1 {
+                        break;
                     }
                 }
             }
@@ -867,37 +1154,299 @@ impl Model {
         result
     }
 
-    /// Save the current playlist as m3u with the given `filename`
-    pub fn playlist_save_m3u_before(&mut self, filename: &str) -> Result<()> {
+    /// Get the folder of the currently selected library node.
+    fn library_current_folder(&self) -> Result<PathBuf> {
         let current_node: String = match self.app.state(&Id::Library).ok().unwrap() {
             State::One(StateValue::String(id)) => id,
             _ => bail!("Invalid node selected in library"),
         };
 
-        let path_m3u = {
-            let mut parent_folder = get_parent_folder(Path::new(&current_node)).to_path_buf();
+        Ok(get_parent_folder(Path::new(&current_node)).to_path_buf())
+    }
+
+    /// Save the current playlist in `format` with the given `filename`
+    pub fn playlist_save_before(&mut self, filename: &str, format: PlaylistFormat) -> Result<()> {
+        let path_playlist = {
+            let mut parent_folder = self.library_current_folder()?;
             let mut filename = OsString::from(filename);
-            filename.push(".m3u");
+            filename.push(".");
+            filename.push(format.extension());
             parent_folder.push(filename);
 
             parent_folder
         };
 
-        if path_m3u.exists() {
-            self.mount_save_playlist_confirm(&path_m3u.to_string_lossy());
+        if path_playlist.exists() {
+            self.mount_save_playlist_confirm(&path_playlist.to_string_lossy(), format);
             return Ok(());
         }
 
-        self.playlist_save_m3u(&path_m3u)
+        self.playlist_save(&path_playlist, format)
     }
 
-    /// Save the current playlist as m3u in the given full path
-    pub fn playlist_save_m3u(&mut self, filename: &Path) -> Result<()> {
+    /// Save the current playlist in `format` in the given full path
+    pub fn playlist_save(&mut self, filename: &Path, format: PlaylistFormat) -> Result<()> {
         // TODO: move this to server?
-        self.playback.playlist.save_m3u(filename)?;
+        self.playback.playlist.save(filename, format)?;
 
         self.library_reload_with_node_focus(Some(filename.to_string_lossy().to_string()));
 
         Ok(())
     }
+
+    /// Get the index right after the current track, where tracks to be played next go.
+    fn playlist_next_index(&self) -> usize {
+        self.playback
+            .playlist
+            .current_track_index()
+            .map_or(0, |index| index + 1)
+    }
+
+    /// Insert the track at `path` after the current track, and play it if `play` is set.
+    fn playlist_insert_next(&mut self, path: &Path, play: bool) {
+        let index = u64::try_from(self.playlist_next_index()).unwrap();
+        let source = PlaylistTrackSource::Path(path.to_string_lossy().to_string());
+
+        self.command(TuiCmd::Playlist(PlaylistCmd::AddTrack(
+            PlaylistAddTrack::new_single(index, source.clone()),
+        )));
+        if play {
+            self.command(TuiCmd::Playlist(PlaylistCmd::PlaySpecific(
+                PlaylistPlaySpecific {
+                    track_index: index,
+                    id: source,
+                },
+            )));
+        }
+    }
+
+    /// Move the playlist entry at `index` to after the current track.
+    fn playlist_move_next(&mut self, index: usize) {
+        let next = self.playlist_next_index();
+        if index == next || self.playback.playlist.current_track_index() == Some(index) {
+            return;
+        }
+        let Some(track) = self.playback.playlist.tracks().get(index) else {
+            error!("Track {index} not in playlist!");
+            return;
+        };
+        let source = track.as_track_source();
+        // the current track moves up by one if the entry before it is removed
+        let target = if index < next { next - 1 } else { next };
+
+        self.command(TuiCmd::Playlist(PlaylistCmd::RemoveTrack(
+            PlaylistRemoveTrackIndexed::new_single(u64::try_from(index).unwrap(), source.clone()),
+        )));
+        self.command(TuiCmd::Playlist(PlaylistCmd::AddTrack(
+            PlaylistAddTrack::new_single(u64::try_from(target).unwrap(), source),
+        )));
+    }
+
+    /// Get the rows shown in the track info popup for `target`.
+    fn track_menu_info(&self, target: &TrackMenuTarget) -> Result<Vec<(&'static str, String)>> {
+        let track = match target {
+            TrackMenuTarget::Library(path) => Track::read_track_from_path(path.clone())?,
+            TrackMenuTarget::Playlist(index) => self
+                .playback
+                .playlist
+                .tracks()
+                .get(*index)
+                .ok_or_else(|| anyhow!("Track {index} not in playlist"))?
+                .clone(),
+        };
+
+        let mut info = vec![
+            ("Title", track.title().unwrap_or(UNKNOWN_TITLE).to_string()),
+            (
+                "Artist",
+                track.artist().unwrap_or(UNKNOWN_ARTIST).to_string(),
+            ),
+            (
+                "Album",
+                track
+                    .as_track()
+                    .and_then(TrackData::album)
+                    .unwrap_or(UNKNOWN_ALBUM)
+                    .to_string(),
+            ),
+            (
+                "Duration",
+                track
+                    .duration_str_short()
+                    .map(|v| v.to_string())
+                    .unwrap_or_default(),
+            ),
+            ("Location", self.track_menu_location(target)?),
+        ];
+
+        // only tracks in the library database have a rating
+        let rating = track
+            .path()
+            .and_then(|path| track_ops::get_track_rating(&self.db.get_connection(), path).ok());
+        if let Some(rating) = rating {
+            info.push(("Rating", rating_str(rating.rating)));
+            info.push((
+                "Favorite",
+                if rating.favorite { "Yes" } else { "No" }.to_string(),
+            ));
+        }
+        let no_autoplay = track.path().and_then(|path| {
+            track_ops::get_track_no_autoplay(&self.db.get_connection(), path).ok()
+        });
+        if let Some(no_autoplay) = no_autoplay {
+            info.push((
+                "Auto-play",
+                if no_autoplay { "Never" } else { "Yes" }.to_string(),
+            ));
+        }
+
+        Ok(info)
+    }
+
+    /// Cycle the rating of, or toggle `target` being a favorite, in the database.
+    ///
+    /// The rating is also written to the file tags if `behavior.write_rating_tags` is set.
+    fn track_menu_rate(&mut self, target: &TrackMenuTarget, action: TrackMenuAction) -> Result<()> {
+        if !self.track_menu_is_local_track(target)? {
+            bail!("Only local tracks can be rated");
+        }
+        let location = self.track_menu_location(target)?;
+        let path = Path::new(&location);
+
+        let (rating, favorite, message) = {
+            let conn = self.db.get_connection();
+            let current = track_ops::get_track_rating(&conn, path)
+                .context("track is not in the library database")?;
+            match action {
+                TrackMenuAction::CycleRating => {
+                    let rating = current.next_rating();
+                    track_ops::set_track_rating(&conn, path, rating)?;
+                    (
+                        Some(rating),
+                        None,
+                        format!("Rating: {}", rating_str(rating)),
+                    )
+                }
+                TrackMenuAction::ToggleFavorite => {
+                    let message = if current.favorite {
+                        "Removed from favorites"
+                    } else {
+                        "Added to favorites"
+                    };
+                    (None, Some(!current.favorite), message.to_string())
+                }
+                _ => bail!("Action \"{}\" is not a rating", action.label()),
+            }
+        };
+
+        if let Some(rating) = rating {
+            if self.config_tui.read().settings.behavior.write_rating_tags {
+                rating_tags::write_rating(path, rating).context("write rating to tags")?;
+            }
+        }
+        if let Some(favorite) = favorite {
+            // set by the server, which also syncs it to Last.fm
+            self.command(TuiCmd::SetTrackFavorite {
+                path: path.to_path_buf(),
+                favorite,
+            });
+        }
+
+        self.show_message_timeout_label_help(message, None, None, None);
+
+        Ok(())
+    }
+
+    /// Toggle whether `target` is never played by shuffle or auto-queue, in the database.
+    fn track_menu_toggle_autoplay(&mut self, target: &TrackMenuTarget) -> Result<()> {
+        if !self.track_menu_is_local_track(target)? {
+            bail!("Only local tracks can be left out of auto-play");
+        }
+        let location = self.track_menu_location(target)?;
+        let path = Path::new(&location);
+
+        let no_autoplay = {
+            let conn = self.db.get_connection();
+            let no_autoplay = !track_ops::get_track_no_autoplay(&conn, path)
+                .context("track is not in the library database")?;
+            track_ops::set_track_no_autoplay(&conn, path, no_autoplay)?;
+            no_autoplay
+        };
+
+        let message = if no_autoplay {
+            "Never auto-played"
+        } else {
+            "Auto-play allowed"
+        };
+        self.show_message_timeout_label_help(message, None, None, None);
+
+        Ok(())
+    }
+
+    /// Run `action` from the track menu on `target`.
+    pub fn track_menu_action(
+        &mut self,
+        target: &TrackMenuTarget,
+        action: TrackMenuAction,
+    ) -> Result<()> {
+        match (action, target) {
+            (TrackMenuAction::PlayNow, TrackMenuTarget::Library(path)) => {
+                self.playlist_insert_next(path, true);
+            }
+            (TrackMenuAction::PlayNow, TrackMenuTarget::Playlist(index)) => {
+                self.playlist_play_selected(*index);
+            }
+            (TrackMenuAction::PlayNext, TrackMenuTarget::Library(path)) => {
+                self.playlist_insert_next(path, false);
+            }
+            (TrackMenuAction::PlayNext, TrackMenuTarget::Playlist(index)) => {
+                self.playlist_move_next(*index);
+            }
+            (TrackMenuAction::Add, TrackMenuTarget::Library(path)) => self.playlist_add(path)?,
+            (TrackMenuAction::Remove, TrackMenuTarget::Playlist(index)) => {
+                self.playlist_delete_item(*index);
+            }
+            (TrackMenuAction::Add, TrackMenuTarget::Playlist(_))
+            | (TrackMenuAction::Remove, TrackMenuTarget::Library(_)) => {
+                bail!("Action \"{}\" is not available here", action.label());
+            }
+            (TrackMenuAction::TagEditor, _) => {
+                let location = self.track_menu_location(target)?;
+                self.mount_tageditor(&location);
+            }
+            (TrackMenuAction::Info, _) => {
+                let info = self.track_menu_info(target)?;
+                self.mount_track_info(&info);
+            }
+            (TrackMenuAction::CycleRating | TrackMenuAction::ToggleFavorite, _) => {
+                self.track_menu_rate(target, action)?;
+            }
+            (TrackMenuAction::ToggleAutoplay, _) => self.track_menu_toggle_autoplay(target)?,
+            (TrackMenuAction::OpenDirectory, _) => {
+                let location = self.track_menu_location(target)?;
+                let path = Path::new(&location);
+                if path.is_dir() {
+                    self.open_external(path)?;
+                } else {
+                    self.open_external(get_parent_folder(path))?;
+                }
+            }
+            (TrackMenuAction::CopyPath, _) => {
+                let location = self.track_menu_location(target)?;
+                copy_to_clipboard(&location)?;
+                self.show_message_timeout_label_help("Copied path", None, None, None);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Get the text to show for a track `rating`, see [`TrackRating::rating`](track_ops::TrackRating::rating).
+fn rating_str(rating: u8) -> String {
+    if rating == 0 {
+        "Not rated".to_string()
+    } else {
+        format!("{rating}/{}", track_ops::MAX_RATING)
+    }
 }
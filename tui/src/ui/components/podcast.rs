@@ -1,26 +1,42 @@
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-use anyhow::{Result, anyhow, bail};
+use anyhow::{Context, Result, anyhow, bail};
 use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
 use reqwest::ClientBuilder;
-use sanitize_filename::{Options, sanitize_with_options};
 use serde_json::Value;
-use termusiclib::config::SharedTuiSettings;
-use termusiclib::podcast::{EpData, PodcastFeed, PodcastNoId, download_list};
+use termusiclib::config::v2::server::FeedSortOrder;
+use termusiclib::config::v2::server::config_extra::ServerConfigVersionedDefaulted;
+use termusiclib::config::v2::tui::EpisodeEnterAction;
+use termusiclib::config::{SharedTuiSettings, TuiOverlay};
+use termusiclib::ids::{EpisodeId, PodcastId};
+use termusiclib::player::PodcastMergeResult;
+use termusiclib::podcast::episode::Episode;
+use termusiclib::podcast::feed_settings::FeedSettings;
+use termusiclib::podcast::space::{estimate_remote_size, files_usage};
+use termusiclib::podcast::{
+    EPISODE_DURATION_LENGTH, EPISODE_PUBDATE_LENGTH, Podcast, PodcastFeed, sort_podcasts,
+};
 use tokio::runtime::Handle;
-use tui_realm_stdlib::List;
+use tui_realm_stdlib::{List, Span};
 use tuirealm::command::{Cmd, CmdResult, Direction, Position};
-use tuirealm::props::{Alignment, BorderType, TableBuilder, TextSpan};
+use tuirealm::props::{Alignment, BorderType, Color, Table, TableBuilder, TextSpan};
 use tuirealm::props::{Borders, PropPayload, PropValue};
+use tuirealm::ratatui::layout::Rect;
 use tuirealm::{
-    AttrValue, Attribute, Component, Event, MockComponent, State, StateValue,
+    AttrValue, Attribute, Component, Event, Frame, MockComponent, State, StateValue,
     event::{Key, KeyEvent, KeyModifiers},
 };
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
+use crate::instance_lock;
 use crate::ui::Model;
 use crate::ui::ids::Id;
 use crate::ui::model::UserEvent;
-use crate::ui::msg::{GSMsg, Msg, PCMsg};
+use crate::ui::model::external_open::copy_to_clipboard;
+use crate::ui::model::trash::{TrashedItems, delete_path};
+use crate::ui::msg::{EpisodeMenuAction, GSMsg, Msg, PCMsg, UnplayedDownload};
+use crate::ui::tui_cmd::{PodcastCmd, TuiCmd};
 
 #[derive(MockComponent)]
 pub struct FeedsList {
@@ -172,6 +188,30 @@ impl Component<Msg, UserEvent> for FeedsList {
             Event::Keyboard(keyevent) if keyevent == keys.podcast_keys.delete_all_feeds.get() => {
                 return Some(Msg::Podcast(PCMsg::FeedsDeleteShow));
             }
+            Event::Keyboard(keyevent) if keyevent == keys.podcast_keys.cycle_feed_sort.get() => {
+                return Some(Msg::Podcast(PCMsg::FeedsCycleSort));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.podcast_keys.cycle_category.get() => {
+                return Some(Msg::Podcast(PCMsg::FeedsCycleCategory));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.podcast_keys.feed_settings.get() => {
+                if let State::One(StateValue::Usize(index)) = self.state() {
+                    return Some(Msg::Podcast(PCMsg::FeedSettingsShow(index)));
+                }
+                CmdResult::None
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.podcast_keys.feed_info.get() => {
+                if let State::One(StateValue::Usize(index)) = self.state() {
+                    return Some(Msg::Podcast(PCMsg::FeedInfoShow(index)));
+                }
+                CmdResult::None
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.podcast_keys.merge_feed.get() => {
+                if let State::One(StateValue::Usize(index)) = self.state() {
+                    return Some(Msg::Podcast(PCMsg::FeedMergeShow(index)));
+                }
+                CmdResult::None
+            }
 
             Event::Keyboard(keyevent) if keyevent == keys.library_keys.search.get() => {
                 return Some(Msg::GeneralSearch(GSMsg::PopupShowPodcast));
@@ -185,9 +225,107 @@ impl Component<Msg, UserEvent> for FeedsList {
     }
 }
 
+/// A single row of tabs above the feeds list, one for each feed category and the selected one highlighted.
 #[derive(MockComponent)]
+pub struct FeedCategoryTabs {
+    component: Span,
+}
+
+impl FeedCategoryTabs {
+    pub fn new(config: &TuiOverlay) -> Self {
+        Self {
+            component: Span::default()
+                .spans([TextSpan::new(" All ").bold()])
+                .alignment(Alignment::Left)
+                .background(config.settings.theme.library_background())
+                .foreground(config.settings.theme.library_foreground()),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for FeedCategoryTabs {
+    fn on(&mut self, _ev: Event<UserEvent>) -> Option<Msg> {
+        None
+    }
+}
+
+/// Get all distinct categories of `podcasts`, sorted by name.
+fn feed_categories(podcasts: &[Podcast]) -> Vec<&str> {
+    let mut categories: Vec<&str> = podcasts
+        .iter()
+        .filter_map(|pod| pod.category.as_deref())
+        .collect();
+    categories.sort_unstable();
+    categories.dedup();
+
+    categories
+}
+
+/// Get the category after `current` in `categories`.
+///
+/// `None` stands for all feeds and comes before the first and after the last category.
+fn next_category(categories: &[&str], current: Option<&str>) -> Option<String> {
+    let next = match current {
+        None => categories.first(),
+        Some(current) => categories
+            .iter()
+            .position(|v| *v == current)
+            .and_then(|idx| categories.get(idx + 1)),
+    };
+
+    next.map(|v| (*v).to_string())
+}
+
+/// Get the prefix to mark a feed or episode with `explicit` in the lists.
+fn explicit_marker(explicit: Option<bool>) -> &'static str {
+    if explicit == Some(true) { "[E] " } else { "" }
+}
+
+/// Get the details of `podcast` as rows of `(name, value)`.
+fn feed_info(podcast: &Podcast) -> Vec<(&'static str, String)> {
+    let or_dash = |v: Option<&str>| v.map_or_else(|| "-".to_string(), ToString::to_string);
+    let explicit = match podcast.explicit {
+        Some(true) => "yes",
+        Some(false) => "no",
+        None => "-",
+    };
+    // only the first paragraph fits into a single row
+    let description = podcast
+        .description
+        .as_deref()
+        .and_then(|v| v.lines().map(str::trim).find(|v| !v.is_empty()));
+    let downloaded = files_usage(podcast.episodes.iter().filter_map(|ep| ep.path.as_deref()));
+
+    vec![
+        ("Title", podcast.title.clone()),
+        ("Author", or_dash(podcast.author.as_deref())),
+        ("Category", or_dash(podcast.category.as_deref())),
+        ("Explicit", explicit.to_string()),
+        ("Description", or_dash(description)),
+        (
+            "Last checked",
+            podcast.last_checked.format("%F %R UTC").to_string(),
+        ),
+        (
+            "Episodes",
+            format!(
+                "{} ({} unplayed)",
+                podcast.episodes.len(),
+                podcast.num_unplayed()
+            ),
+        ),
+        ("Downloaded", downloaded.to_string()),
+        ("URL", podcast.url.clone()),
+    ]
+}
+
+/// The episodes of the selected podcast, with the columns of each row aligned to the available width.
 pub struct EpisodeList {
     component: List,
+    /// The rows as set via [`Attribute::Content`], with the columns badge, title, duration and pubdate
+    rows: Table,
+    /// The width [`rows`](Self::rows) were last aligned for
+    aligned_width: usize,
     on_key_tab: Msg,
     on_key_backtab: Msg,
     config: SharedTuiSettings,
@@ -221,11 +359,116 @@ impl EpisodeList {
 
         Self {
             component,
+            rows: Table::new(),
+            aligned_width: 0,
             on_key_tab,
             on_key_backtab,
             config,
         }
     }
+
+    /// Set the rows of the inner list to [`rows`](Self::rows), aligned to [`aligned_width`](Self::aligned_width).
+    fn update_aligned_rows(&mut self) {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| align_episode_row(row, self.aligned_width))
+            .collect();
+        self.component
+            .attr(Attribute::Content, AttrValue::Table(rows));
+    }
+}
+
+/// Align a row with the columns badge, title, duration and pubdate to fill `width`.
+///
+/// The duration and pubdate columns are only shown if there is enough space for them.
+/// Rows with a different amount of columns are returned as-is.
+fn align_episode_row(row: &[TextSpan], width: usize) -> Vec<TextSpan> {
+    let [badge, title, duration, pubdate] = row else {
+        return row.to_vec();
+    };
+
+    let mut meta = Vec::new();
+    if width > EPISODE_DURATION_LENGTH {
+        meta.push(duration);
+    }
+    if width > EPISODE_PUBDATE_LENGTH {
+        meta.push(pubdate);
+    }
+    let meta_width: usize = meta.iter().map(|v| v.content.width() + 1).sum();
+    let title_width = width.saturating_sub(badge.content.width() + 1 + meta_width);
+
+    let mut aligned = vec![badge.clone(), TextSpan::new(" ")];
+    let mut title = title.clone();
+    title.content = fit_to_width(&title.content, title_width);
+    aligned.push(title);
+    for column in meta {
+        aligned.push(TextSpan::new(" "));
+        aligned.push(column.clone());
+    }
+
+    aligned
+}
+
+/// Truncate or pad `text` with spaces to be exactly `width` columns wide.
+fn fit_to_width(text: &str, width: usize) -> String {
+    let mut out = String::with_capacity(width);
+    let mut out_width = 0;
+    for ch in text.chars() {
+        let char_width = ch.width().unwrap_or_default();
+        if out_width + char_width > width {
+            break;
+        }
+        out.push(ch);
+        out_width += char_width;
+    }
+    out.extend(std::iter::repeat_n(' ', width - out_width));
+
+    out
+}
+
+impl MockComponent for EpisodeList {
+    fn view(&mut self, frame: &mut Frame<'_>, area: Rect) {
+        let highlight_width = self
+            .config
+            .read()
+            .settings
+            .theme
+            .style
+            .library
+            .highlight_symbol
+            .width();
+        // minus the borders
+        let width = usize::from(area.width.saturating_sub(2)).saturating_sub(highlight_width);
+        if width != self.aligned_width {
+            self.aligned_width = width;
+            self.update_aligned_rows();
+        }
+
+        self.component.view(frame, area);
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.component.query(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        match (attr, value) {
+            (Attribute::Content, AttrValue::Table(rows)) => {
+                self.rows = rows;
+                self.update_aligned_rows();
+            }
+            (attr, value) => self.component.attr(attr, value),
+        }
+    }
+
+    fn state(&self) -> State {
+        self.component.state()
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        self.component.perform(cmd)
+    }
 }
 
 impl Component<Msg, UserEvent> for EpisodeList {
@@ -326,6 +569,20 @@ impl Component<Msg, UserEvent> for EpisodeList {
                 return Some(Msg::Podcast(PCMsg::EpisodeMarkAllPlayed));
             }
 
+            Event::Keyboard(keyevent) if keyevent == keys.podcast_keys.episode_menu.get() => {
+                if let State::One(StateValue::Usize(index)) = self.state() {
+                    return Some(Msg::Podcast(PCMsg::EpisodeMenuShow(index)));
+                }
+                CmdResult::None
+            }
+
+            Event::Keyboard(keyevent) if keyevent == keys.podcast_keys.stream_episode.get() => {
+                if let State::One(StateValue::Usize(index)) = self.state() {
+                    return Some(Msg::Podcast(PCMsg::EpisodeStream(index)));
+                }
+                CmdResult::None
+            }
+
             Event::Keyboard(keyevent) if keyevent == keys.podcast_keys.download_episode.get() => {
                 if let State::One(StateValue::Usize(index)) = self.state() {
                     return Some(Msg::Podcast(PCMsg::EpisodeDownload(index)));
@@ -333,6 +590,12 @@ impl Component<Msg, UserEvent> for EpisodeList {
                 CmdResult::None
             }
 
+            Event::Keyboard(keyevent)
+                if keyevent == keys.podcast_keys.download_all_unplayed.get() =>
+            {
+                return Some(Msg::Podcast(PCMsg::EpisodeDownloadUnplayedEstimate));
+            }
+
             Event::Keyboard(keyevent)
                 if keyevent == keys.podcast_keys.delete_local_episode.get() =>
             {
@@ -429,41 +692,56 @@ impl Model {
     }
 
     pub fn podcast_add(&mut self, url: String) {
-        let feed = PodcastFeed::new(None, url, None);
-        let tx_to_main = self.tx_to_main.clone();
-
-        crate::podcast::check_feed(
-            feed,
-            usize::from(
-                self.config_server
-                    .read()
-                    .settings
-                    .podcast
-                    .max_download_retries,
-            ),
-            &self.taskpool,
-            move |msg| {
-                let _ = tx_to_main.send(Msg::Podcast(PCMsg::SyncResult(msg)));
-            },
-        );
+        self.command(TuiCmd::Podcast(PodcastCmd::Add(url)));
     }
     pub fn podcast_sync_feeds_and_episodes(&mut self) {
+        let podcasts = &self.podcast.podcasts;
+        // the category may not exist anymore, like after its last feed got removed
+        if let Some(category) = &self.podcast.category {
+            if !feed_categories(podcasts).contains(&category.as_str()) {
+                self.podcast.category = None;
+            }
+        }
+        let category = self.podcast.category.as_deref();
+        let hide_explicit = self.config_server.read().settings.podcast.hide_explicit;
+        self.podcast.shown_feeds = podcasts
+            .iter()
+            .enumerate()
+            .filter(|(_, pod)| category.is_none_or(|v| pod.category.as_deref() == Some(v)))
+            .filter(|(_, pod)| !(hide_explicit && pod.explicit == Some(true)))
+            .map(|(idx, _)| idx)
+            .collect();
+        // keep the selected podcast in the shown feeds, otherwise its episodes would still be shown
+        let selected = self.podcast.podcasts_index;
+        if self.podcast.list_index(selected).is_none() {
+            self.podcast.podcasts_index = self.podcast.feed_index(0).unwrap_or_default();
+        }
+
         let mut table: TableBuilder = TableBuilder::default();
 
-        for (idx, record) in self.podcast.podcasts.iter().enumerate() {
+        for (idx, pod_index) in self.podcast.shown_feeds.iter().enumerate() {
+            let Some(record) = podcasts.get(*pod_index) else {
+                continue;
+            };
             if idx > 0 {
                 table.add_row();
             }
             let new = record.num_unplayed();
             let total = record.episodes.len();
+            let marker = explicit_marker(record.explicit);
             if new > 0 {
-                table.add_col(TextSpan::new(format!("{} ({new}/{total})", record.title)).bold());
+                table.add_col(
+                    TextSpan::new(format!("{marker}{} ({new}/{total})", record.title)).bold(),
+                );
                 continue;
             }
 
-            table.add_col(TextSpan::new(format!("{} ({new}/{total})", record.title)));
+            table.add_col(TextSpan::new(format!(
+                "{marker}{} ({new}/{total})",
+                record.title
+            )));
         }
-        if self.podcast.podcasts.is_empty() {
+        if self.podcast.shown_feeds.is_empty() {
             table.add_col(TextSpan::from("empty feeds list"));
         }
 
@@ -475,9 +753,58 @@ impl Model {
                 tuirealm::AttrValue::Table(table),
             )
             .ok();
+        self.podcast_sync_categories();
         if let Err(e) = self.podcast_sync_episodes() {
             self.mount_error_popup(e.context("podcast sync episodes"));
         }
+        self.podcast_feed_info_sync();
+    }
+
+    /// Display all feed categories as tabs, with the selected one highlighted.
+    fn podcast_sync_categories(&mut self) {
+        let highlight = self.config_tui.read().settings.theme.library_highlight();
+        let selected = self.podcast.category.as_deref();
+
+        let all = std::iter::once((None, "All"));
+        let categories = feed_categories(&self.podcast.podcasts)
+            .into_iter()
+            .map(|v| (Some(v), v));
+        let spans: Vec<PropValue> = all
+            .chain(categories)
+            .map(|(category, label)| {
+                let span = TextSpan::new(format!(" {label} "));
+                if category == selected {
+                    span.fg(highlight).bold().reversed()
+                } else {
+                    span
+                }
+            })
+            .map(PropValue::TextSpan)
+            .collect();
+
+        self.app
+            .attr(
+                &Id::PodcastCategories,
+                Attribute::Text,
+                AttrValue::Payload(PropPayload::Vec(spans)),
+            )
+            .ok();
+    }
+
+    /// Select the row of the selected podcast in the feeds list, if it is shown.
+    fn podcast_select_feed_row(&mut self) {
+        let Some(list_index) = self.podcast.list_index(self.podcast.podcasts_index) else {
+            return;
+        };
+        assert!(
+            self.app
+                .attr(
+                    &Id::Podcast,
+                    Attribute::Value,
+                    AttrValue::Payload(PropPayload::One(PropValue::Usize(list_index))),
+                )
+                .is_ok()
+        );
     }
 
     pub fn podcast_sync_episodes(&mut self) -> Result<()> {
@@ -504,6 +831,7 @@ impl Model {
             .get(self.podcast.podcasts_index)
             .ok_or_else(|| anyhow!("get podcast selected failed."))?;
         // let episodes = self.db_podcast.get_episodes(podcast_selected.id, true)?;
+        let new_window = self.config_server.read().settings.podcast.new_episodes;
         let mut table: TableBuilder = TableBuilder::default();
 
         for (idx, record) in podcast_selected.episodes.iter().enumerate() {
@@ -511,17 +839,37 @@ impl Model {
                 table.add_row();
             }
 
-            let mut title = record.title.clone();
-            // if let Some(_) = record.path {
+            let badge = if record.played {
+                TextSpan::new("   ")
+            } else if record.is_new(new_window) {
+                TextSpan::new("NEW").fg(Color::LightGreen).bold()
+            } else {
+                TextSpan::new(" ● ").fg(Color::Cyan)
+            };
+
+            let mut title = format!("{}{}", explicit_marker(record.explicit), record.title);
             if record.path.is_some() {
                 title = format!("[D] {title}");
             }
-            if record.played {
-                table.add_col(TextSpan::new(title).strikethrough());
-                continue;
-            }
+            let title = if record.played {
+                TextSpan::new(title).strikethrough()
+            } else {
+                TextSpan::new(title).bold()
+            };
 
-            table.add_col(TextSpan::new(title).bold());
+            let duration =
+                TextSpan::new(format!("[{}]", record.format_duration())).fg(Color::LightYellow);
+            let pubdate = record.pubdate.map_or_else(
+                || " ".repeat(10),
+                |pubdate| pubdate.format("%F").to_string(),
+            );
+            let pubdate = TextSpan::new(pubdate).fg(Color::LightBlue);
+
+            table
+                .add_col(badge)
+                .add_col(title)
+                .add_col(duration)
+                .add_col(pubdate);
         }
         if podcast_selected.episodes.is_empty() {
             table.add_col(TextSpan::from("empty episodes list"));
@@ -542,19 +890,21 @@ impl Model {
         if self.podcast.podcasts.is_empty() {
             return Ok(());
         }
-        let podcast_selected = self
+        let ep = self
             .podcast
             .podcasts
             .get_mut(self.podcast.podcasts_index)
-            .ok_or_else(|| anyhow!("get podcast selected failed."))?;
-        let ep = podcast_selected
+            .ok_or_else(|| anyhow!("get podcast selected failed."))?
             .episodes
             .get_mut(index)
             .ok_or_else(|| anyhow!("get episode selected failed"))?;
+        // shown right away, the server reports the change to all clients afterwards
         ep.played = !ep.played;
-        self.podcast
-            .db_podcast
-            .set_played_status(ep.id, ep.played)?;
+        let cmd = PodcastCmd::MarkPlayed {
+            id: ep.id,
+            played: ep.played,
+        };
+        self.command(TuiCmd::Podcast(cmd));
         self.podcast_sync_feeds_and_episodes();
 
         Ok(())
@@ -569,229 +919,420 @@ impl Model {
         if let Ok(idx) = self.podcast_get_episode_index() {
             ep_index = idx;
         }
-        let podcast_selected = self
+        let podcast = self
             .podcast
             .podcasts
             .get_mut(self.podcast.podcasts_index)
             .ok_or_else(|| anyhow!("get podcast selected failed."))?;
-        let played = podcast_selected
+        let played = !podcast
             .episodes
             .get(ep_index)
             .ok_or_else(|| anyhow!("get first episode failed."))?
             .played;
-        let mut epid_vec = Vec::new();
-        for ep in &mut podcast_selected.episodes {
-            epid_vec.push(ep.id);
-            ep.played = !played;
+        for ep in &mut podcast.episodes {
+            ep.played = played;
         }
-        self.podcast
-            .db_podcast
-            .set_all_played_status(&epid_vec, !played)?;
+        let cmd = PodcastCmd::MarkAllPlayed {
+            id: podcast.id,
+            played,
+        };
+        self.command(TuiCmd::Podcast(cmd));
         self.podcast_sync_feeds_and_episodes();
 
         Ok(())
     }
 
-    /// Handles the application logic for adding a new podcast, or
-    /// synchronizing data from the RSS feed of an existing podcast.
-    /// `pod_id` will be None if a new podcast is being added (i.e.,
-    /// the database has not given it an id yet).
-    pub fn add_or_sync_data(&mut self, pod: &PodcastNoId, pod_id: Option<i64>) -> Result<()> {
-        if let Some(id) = pod_id {
-            self.podcast.db_podcast.update_podcast(id, pod)?;
-        } else {
-            self.podcast.db_podcast.insert_podcast(pod)?;
+    /// Show the list of all other feeds to merge the feed at `list_index` into.
+    pub fn podcast_feed_merge_show(&mut self, list_index: usize) {
+        let podcasts = &self.podcast.podcasts;
+        let Some(source) = self
+            .podcast
+            .feed_index(list_index)
+            .and_then(|index| podcasts.get(index))
+        else {
+            return;
+        };
+
+        let targets: Vec<&Podcast> = podcasts.iter().filter(|v| v.id != source.id).collect();
+        if targets.is_empty() {
+            self.show_message_timeout_label_help("No other feed to merge into", None, None, None);
+            return;
         }
 
-        self.podcast.podcasts = self.podcast.db_podcast.get_podcasts()?;
+        let source_id = source.id;
+        let source_title = source.title.clone();
+        let rows: Vec<(String, String)> = targets
+            .iter()
+            .map(|v| (v.title.clone(), v.url.clone()))
+            .collect();
+        let target_ids = targets.iter().map(|v| v.id).collect();
+
+        self.mount_feed_merge_table(&source_title, &rows);
+        self.podcast.merge_targets = Some((source_id, target_ids));
+    }
+
+    /// Ask to confirm merging into the feed at `index` of the merge list.
+    pub fn podcast_feed_merge_select(&mut self, index: usize) {
+        let Some((source, targets)) = self.podcast.merge_targets.take() else {
+            return;
+        };
+        let Some(target) = targets.get(index).copied() else {
+            return;
+        };
+
+        let title = |id| {
+            self.podcast
+                .podcast_index(id)
+                .and_then(|index| self.podcast.podcasts.get(index))
+                .map(|pod| pod.title.clone())
+                .unwrap_or_default()
+        };
+        let (source_title, target_title) = (title(source), title(target));
+
+        self.mount_feed_merge_confirm(&source_title, &target_title);
+        self.podcast.pending_merge = Some((source, target));
+    }
+
+    /// Let the server merge the feed `source` into `target`, see [`podcast_feed_merged`](Self::podcast_feed_merged).
+    pub fn podcast_feed_merge(&mut self, source: PodcastId, target: PodcastId) {
+        self.command(TuiCmd::Podcast(PodcastCmd::Merge { source, target }));
+    }
+
+    /// Select the feed `target` after the server merged another feed into it.
+    pub fn podcast_feed_merged(&mut self, target: PodcastId, result: &PodcastMergeResult) {
+        if let Some(index) = self.podcast.podcast_index(target) {
+            self.podcast.podcasts_index = index;
+        }
         self.podcast_sync_feeds_and_episodes();
+        self.podcast_select_feed_row();
+        self.episode_update_playlist();
+        self.show_message_timeout_label_help(
+            format!(
+                "Merged feeds: {} episodes matched, {} downloads moved",
+                result.episodes, result.files
+            ),
+            None,
+            None,
+            None,
+        );
+    }
+
+    /// Show the settings of the feed at `list_index` for editing.
+    pub fn podcast_feed_settings_show(&mut self, list_index: usize) -> Result<()> {
+        let Some(pod_id) = self
+            .podcast
+            .feed_index(list_index)
+            .and_then(|index| self.podcast.podcasts.get(index))
+            .map(|pod| pod.id)
+        else {
+            return Ok(());
+        };
+
+        let settings = self.podcast.db_podcast.get_feed_settings(pod_id)?;
+        self.mount_feed_settings(pod_id, settings);
 
         Ok(())
     }
 
+    /// Let the server store the edited settings of the feed `pod_id` and apply them right away.
+    pub fn podcast_feed_settings_save(&mut self, pod_id: PodcastId, settings: FeedSettings) {
+        self.command(TuiCmd::Podcast(PodcastCmd::SetFeedSettings(
+            pod_id, settings,
+        )));
+    }
+
     /// Synchronize RSS feed data for one or more podcasts.
     pub fn podcast_refresh_feeds(&mut self, index: Option<usize>) -> Result<()> {
-        // We pull out the data we need here first, so we can
-        // stop borrowing the podcast list as quickly as possible.
-        // Slightly less efficient (two loops instead of
-        // one), but then it won't block other tasks that
-        // need to access the list.
-
-        let mut pod_data = Vec::new();
-        match index {
-            // just grab one podcast
-            Some(i) => {
-                if self.podcast.podcasts.is_empty() {
-                    return Ok(());
-                }
-                let pod_selected = self
-                    .podcast
+        let id = match index {
+            Some(index) => Some(
+                self.podcast
                     .podcasts
-                    .get(i)
-                    .ok_or_else(|| anyhow!("get podcast selected failed."))?;
-                let pcf = PodcastFeed::new(
-                    Some(pod_selected.id),
-                    pod_selected.url.clone(),
-                    Some(pod_selected.title.clone()),
-                );
-                pod_data.push(pcf);
-            }
+                    .get(index)
+                    .ok_or_else(|| anyhow!("get podcast selected failed."))?
+                    .id,
+            ),
+            None => None,
+        };
+        self.command(TuiCmd::Podcast(PodcastCmd::Refresh(id)));
+        Ok(())
+    }
+
+    /// Show the info of the feed at `list_index`.
+    pub fn podcast_feed_info_show(&mut self, list_index: usize) {
+        let Some(podcast) = self
+            .podcast
+            .feed_index(list_index)
+            .and_then(|index| self.podcast.podcasts.get(index))
+        else {
+            return;
+        };
+
+        let pod_id = podcast.id;
+        let info = feed_info(podcast);
+        self.mount_feed_info(pod_id, &info);
+        self.podcast.feed_info = Some(pod_id);
+    }
 
-            // get all of 'em!
+    /// Re-check the feed `pod_id`, the feed info popup is updated once it is done.
+    pub fn podcast_feed_info_refresh(&mut self, pod_id: PodcastId) -> Result<()> {
+        let Some(index) = self.podcast.podcast_index(pod_id) else {
+            return Ok(());
+        };
+
+        self.podcast_refresh_feeds(Some(index))
+    }
+
+    /// Update the feed info popup, if shown, with the current data of its feed.
+    fn podcast_feed_info_sync(&mut self) {
+        let Some(pod_id) = self.podcast.feed_info else {
+            return;
+        };
+
+        let info = self
+            .podcast
+            .podcast_index(pod_id)
+            .and_then(|index| self.podcast.podcasts.get(index))
+            .map(feed_info);
+        match info {
+            Some(info) => self.update_feed_info(&info),
+            // the feed got removed
             None => {
-                pod_data = self
-                    .podcast
-                    .podcasts
-                    .iter()
-                    .map(|pod| {
-                        PodcastFeed::new(Some(pod.id), pod.url.clone(), Some(pod.title.clone()))
-                    })
-                    .collect();
+                self.umount_feed_info();
+                self.podcast.feed_info = None;
             }
         }
-        for feed in pod_data {
-            let tx_to_main = self.tx_to_main.clone();
-
-            crate::podcast::check_feed(
-                feed,
-                usize::from(
-                    self.config_server
-                        .read()
-                        .settings
-                        .podcast
-                        .max_download_retries,
-                ),
-                &self.taskpool,
-                move |msg| {
-                    let _ = tx_to_main.send(Msg::Podcast(PCMsg::SyncResult(msg)));
-                },
-            );
+    }
+
+    /// Sync played status and positions with the sync server, if one is configured.
+    pub fn podcast_gpodder_sync(&mut self) {
+        if self.config_server.read().settings.podcast.sync.is_none() {
+            return;
         }
-        // self.update_tracker_notif();
-        self.podcast_sync_feeds_and_episodes();
+
+        self.command(TuiCmd::Podcast(PodcastCmd::Sync));
+    }
+
+    /// Add the episode at `ep_index` to the playlist as configured in `behavior.episode_enter`.
+    ///
+    /// When downloading first, the episode is added once [`DownloadDone`](termusiclib::player::PodcastProgress::DownloadDone) is received.
+    pub fn episode_enter(&mut self, ep_index: usize) -> Result<()> {
+        let action = self.config_tui.read().settings.behavior.episode_enter;
+        if action == EpisodeEnterAction::Stream || self.podcast.podcasts.is_empty() {
+            return self.playlist_add_episode(ep_index);
+        }
+
+        let ep = self
+            .podcast
+            .podcasts
+            .get(self.podcast.podcasts_index)
+            .ok_or_else(|| anyhow!("get podcast selected failed."))?
+            .episodes
+            .get(ep_index)
+            .ok_or_else(|| anyhow!("get episode selected failed."))?;
+
+        // already downloaded episodes are played from their file, if it still exists
+        if ep.path.is_some() {
+            return self.playlist_add_episode(ep_index);
+        }
+
+        let ep_id = ep.id;
+        let is_downloading = self.download_tracker.contains(&ep.url);
+        self.podcast.play_after_download.insert(ep_id);
+
+        if !is_downloading {
+            self.episode_download(Some(ep_index))?;
+        }
+
         Ok(())
     }
 
-    pub fn episode_download(&mut self, index: Option<usize>) -> Result<()> {
-        if self.podcast.podcasts.is_empty() {
-            return Ok(());
+    /// Run `action` from the episode menu on the episode at `ep_index`.
+    pub fn episode_menu_action(
+        &mut self,
+        ep_index: usize,
+        action: EpisodeMenuAction,
+    ) -> Result<()> {
+        match action {
+            EpisodeMenuAction::Play => self.episode_enter(ep_index),
+            EpisodeMenuAction::Stream => self.playlist_add_episode(ep_index),
+            EpisodeMenuAction::Download => self.episode_download(Some(ep_index)),
+            EpisodeMenuAction::MarkPlayed => self.episode_mark_played(ep_index),
+            EpisodeMenuAction::DeleteFile => self.episode_delete_file(ep_index),
+            EpisodeMenuAction::CopyUrl => {
+                let url = self.episode_selected(ep_index)?.url.clone();
+                copy_to_clipboard(&url)?;
+                self.show_message_timeout_label_help("Copied episode URL", None, None, None);
+                Ok(())
+            }
+            EpisodeMenuAction::OpenLink => {
+                let url = self.episode_selected(ep_index)?.url.clone();
+                self.open_external(Path::new(&url))
+            }
+            EpisodeMenuAction::Info => {
+                let info = self.episode_info(ep_index)?;
+                self.mount_episode_info(&info);
+                Ok(())
+            }
         }
-        let podcast_selected = self
+    }
+
+    /// Get the episode at `ep_index` of the current podcast.
+    fn episode_selected(&self, ep_index: usize) -> Result<&Episode> {
+        self.podcast
+            .podcasts
+            .get(self.podcast.podcasts_index)
+            .ok_or_else(|| anyhow!("get podcast selected failed."))?
+            .episodes
+            .get(ep_index)
+            .ok_or_else(|| anyhow!("get episode selected failed."))
+    }
+
+    /// Get the details of the episode at `ep_index` as rows of `(name, value)`.
+    fn episode_info(&self, ep_index: usize) -> Result<Vec<(&'static str, String)>> {
+        let podcast = self
             .podcast
             .podcasts
-            .get_mut(self.podcast.podcasts_index)
+            .get(self.podcast.podcasts_index)
             .ok_or_else(|| anyhow!("get podcast selected failed."))?;
+        let ep = self.episode_selected(ep_index)?;
 
-        let pod_title;
-        let mut ep_data = Vec::new();
-        {
-            pod_title = podcast_selected.title.clone();
-
-            // if we are selecting one specific episode, just grab that
-            // one; otherwise, loop through them all
-            match index {
-                Some(idx) => {
-                    // grab just the relevant data we need
-
-                    let ep = podcast_selected
-                        .episodes
-                        .get_mut(idx)
-                        .ok_or_else(|| anyhow!("get episode selected failed"))?;
-                    let data = EpData {
-                        id: ep.id,
-                        pod_id: ep.pod_id,
-                        title: ep.title.clone(),
-                        url: ep.url.clone(),
-                        pubdate: ep.pubdate,
-                        file_path: None,
-                    };
-                    if ep.path.is_none() && !self.download_tracker.contains(&ep.url) {
-                        ep_data.push(data);
-                    }
-                }
-                None => {
-                    // grab just the relevant data we need
-                    ep_data = podcast_selected
-                        .episodes
-                        .iter()
-                        .filter_map(|ep| {
-                            if ep.path.is_none() && !self.download_tracker.contains(&ep.url) {
-                                Some(EpData {
-                                    id: ep.id,
-                                    pod_id: ep.pod_id,
-                                    title: ep.title.clone(),
-                                    url: ep.url.clone(),
-                                    pubdate: ep.pubdate,
-                                    file_path: None,
-                                })
-                            } else {
-                                None
-                            }
-                        })
-                        .collect();
-                }
-            }
+        let position = ep.last_position.filter(|v| *v > 0).map_or_else(
+            || "-".to_string(),
+            |v| format!("{:02}:{:02}:{:02}", v / 3600, (v / 60) % 60, v % 60),
+        );
+
+        Ok(vec![
+            ("Title", ep.title.clone()),
+            ("Feed", podcast.title.clone()),
+            (
+                "Published",
+                ep.pubdate
+                    .map_or_else(|| "-".to_string(), |v| v.format("%F %R").to_string()),
+            ),
+            ("Duration", ep.format_duration()),
+            ("Played", if ep.played { "yes" } else { "no" }.to_string()),
+            ("Position", position),
+            (
+                "File",
+                ep.path
+                    .as_ref()
+                    .map_or_else(|| "-".to_string(), |v| v.display().to_string()),
+            ),
+            ("URL", ep.url.clone()),
+        ])
+    }
+
+    pub fn episode_download(&mut self, index: Option<usize>) -> Result<()> {
+        if self.podcast.podcasts.is_empty() {
+            return Ok(());
         }
 
+        let podcast = self
+            .podcast
+            .podcasts
+            .get(self.podcast.podcasts_index)
+            .ok_or_else(|| anyhow!("get podcast selected failed."))?;
+        let episodes = match index {
+            Some(index) => std::slice::from_ref(
+                podcast
+                    .episodes
+                    .get(index)
+                    .ok_or_else(|| anyhow!("get episode selected failed."))?,
+            ),
+            None => podcast.episodes.as_slice(),
+        };
         // check against episodes currently being downloaded -- so we
         // don't needlessly download them again
-        // ep_data.retain(|ep| !self.download_tracker.contains(&ep.id));
-
-        if !ep_data.is_empty() {
-            // add directory for podcast, create if it does not exist
-            let dir_name = sanitize_with_options(
-                &pod_title,
-                Options {
-                    truncate: true,
-                    windows: true, // for simplicity, we'll just use Windows-friendly paths for everyone
-                    replacement: "",
-                },
-            );
-            match crate::utils::create_podcast_dir(&self.config_server.read(), dir_name) {
-                Ok(path) => {
-                    let tx_to_main = self.tx_to_main.clone();
-                    // for ep in ep_data.iter() {
-                    //     self.download_tracker.insert(ep.id);
-                    // }
-                    download_list(
-                        ep_data,
-                        &path,
-                        usize::from(
-                            self.config_server
-                                .read()
-                                .settings
-                                .podcast
-                                .max_download_retries,
-                        ),
-                        &self.taskpool,
-                        move |msg| {
-                            let _ = tx_to_main.send(Msg::Podcast(PCMsg::DLResult(msg)));
-                        },
-                    );
-                }
-                Err(_) => bail!("Could not create dir: {pod_title}"),
-            }
+        let ids: Vec<EpisodeId> = episodes
+            .iter()
+            .filter(|ep| ep.path.is_none() && !self.download_tracker.contains(&ep.url))
+            .map(|ep| ep.id)
+            .collect();
+
+        if !ids.is_empty() {
+            self.command(TuiCmd::Podcast(PodcastCmd::Download(ids)));
         }
-
-        // self.podcast_sync_feeds_and_episodes();
         Ok(())
     }
 
-    pub fn episode_download_complete(&mut self, ep_data: EpData) -> Result<()> {
-        let file_path = ep_data.file_path.unwrap();
-        let res = self.podcast.db_podcast.insert_file(ep_data.id, &file_path);
-        if res.is_err() {
-            bail!(
-                "Could not add episode file to database: {}",
-                file_path.to_string_lossy()
+    /// Estimate the size of all unplayed, not downloaded episodes of the current podcast in the background,
+    /// which asks for confirmation via [`PCMsg::EpisodeDownloadUnplayedShow`] once done.
+    ///
+    /// Requires that the current thread has a entered runtime
+    pub fn episode_download_unplayed_estimate(&mut self) -> Result<()> {
+        if self.podcast.podcasts.is_empty() {
+            return Ok(());
+        }
+
+        let pod_index = self.podcast.podcasts_index;
+        let pod_id = self
+            .podcast
+            .podcasts
+            .get(pod_index)
+            .ok_or_else(|| anyhow!("get podcast selected failed."))?
+            .id;
+        let (ids, urls): (Vec<EpisodeId>, Vec<String>) = self
+            .podcast
+            .podcasts
+            .get(pod_index)
+            .ok_or_else(|| anyhow!("get podcast selected failed."))?
+            .episodes
+            .iter()
+            .filter(|ep| {
+                !ep.played && ep.path.is_none() && !self.download_tracker.contains(&ep.url)
+            })
+            .map(|ep| (ep.id, ep.url.clone()))
+            .unzip();
+
+        if ids.is_empty() {
+            self.show_message_timeout_label_help(
+                "No unplayed episodes to download",
+                None,
+                None,
+                None,
             );
+            return Ok(());
         }
 
-        let podcasts = self.podcast.db_podcast.get_podcasts()?;
-        self.podcast.podcasts = podcasts;
+        self.show_message_timeout_label_help(
+            format!("Estimating the size of {} episodes...", ids.len()),
+            None,
+            None,
+            None,
+        );
+
+        let tx = self.tx_to_main.clone();
+        Handle::current().spawn(async move {
+            let size = estimate_remote_size(urls).await;
+            let _ = tx.send(Msg::Podcast(PCMsg::EpisodeDownloadUnplayedShow(
+                UnplayedDownload { pod_id, ids, size },
+            )));
+        });
 
-        self.podcast_sync_feeds_and_episodes();
-        self.episode_update_playlist();
+        Ok(())
+    }
+
+    /// Download the episodes that were confirmed after [`episode_download_unplayed_estimate`](Self::episode_download_unplayed_estimate).
+    pub fn episode_download_unplayed(&mut self, download: &UnplayedDownload) -> Result<()> {
+        // the podcast may have been removed while waiting for the confirmation
+        let podcast = self
+            .podcast
+            .podcast_index(download.pod_id)
+            .and_then(|index| self.podcast.podcasts.get(index))
+            .ok_or_else(|| anyhow!("get podcast to download failed."))?;
+
+        // some may have been downloaded while waiting for the confirmation
+        let ids: Vec<EpisodeId> = podcast
+            .episodes
+            .iter()
+            .filter(|ep| download.ids.contains(&ep.id))
+            .filter(|ep| ep.path.is_none() && !self.download_tracker.contains(&ep.url))
+            .map(|ep| ep.id)
+            .collect();
+
+        if !ids.is_empty() {
+            self.command(TuiCmd::Podcast(PodcastCmd::Download(ids)));
+        }
         Ok(())
     }
 
@@ -801,39 +1342,36 @@ impl Model {
         if self.podcast.podcasts.is_empty() {
             return Ok(());
         }
-        let podcast_selected = self
+        let to_trash = self.delete_to_trash();
+        let pod_index = self.podcast.podcasts_index;
+        let ep = self
             .podcast
             .podcasts
-            .get_mut(self.podcast.podcasts_index)
-            .ok_or_else(|| anyhow!("get podcast selected failed."))?;
-
-        let ep = podcast_selected
+            .get_mut(pod_index)
+            .ok_or_else(|| anyhow!("get podcast selected failed."))?
             .episodes
             .get_mut(ep_index)
             .ok_or_else(|| anyhow!("get episode selected failed"))?;
 
-        if ep.path.is_some() {
-            let title = &ep.title;
-            let path = ep.path.clone().unwrap();
-            match std::fs::remove_file(path) {
-                Ok(()) => {
-                    self.podcast.db_podcast.remove_file(ep.id).map_err(|e| {
-                        anyhow!(format!("Could not remove file from db: {title} {e}"))
-                    })?;
-                    ep.path = None;
-                }
-                Err(e) => {
-                    // Repeat the same thing in case the local file is missing. Update db
-                    self.podcast.db_podcast.remove_file(ep.id).map_err(|e| {
-                        anyhow!(format!("Could not remove file from db: {title} {e}"))
-                    })?;
-                    ep.path = None;
-                    bail!(format!("Error deleting \"{title}\": {e}"));
-                }
+        let mut trashed = None;
+        if let Some(path) = ep.path.take() {
+            let title = ep.title.clone();
+            let ep_id = ep.id;
+            let res = delete_path(&path, to_trash);
+            // update the db even if the local file is missing
+            self.command(TuiCmd::Podcast(PodcastCmd::RemoveFiles(vec![ep_id])));
+            if let Err(e) = res {
+                bail!(format!("Error deleting \"{title}\": {e:#}"));
+            }
+            if to_trash {
+                trashed = Some(TrashedItems::Episodes(vec![(ep_id, path)]));
             }
         }
         self.podcast_sync_feeds_and_episodes();
         self.episode_update_playlist();
+        if let Some(trashed) = trashed {
+            self.trash_offer_undo(trashed);
+        }
         Ok(())
     }
 
@@ -842,9 +1380,17 @@ impl Model {
         self.playlist_sync();
     }
 
-    pub fn podcast_delete_files(&mut self, pod_index: usize) -> Result<()> {
+    /// Delete all downloaded files of the podcast at `pod_index`.
+    ///
+    /// Files moved to the trash are added to `trashed`, even if deleting another file failed.
+    pub fn podcast_delete_files(
+        &mut self,
+        pod_index: usize,
+        trashed: &mut Vec<(EpisodeId, PathBuf)>,
+    ) -> Result<()> {
         let mut eps_to_remove = Vec::new();
         let mut success = true;
+        let to_trash = self.delete_to_trash();
         {
             let podcast_selected = self
                 .podcast
@@ -853,19 +1399,26 @@ impl Model {
                 .ok_or_else(|| anyhow!("failed to find the podcast selected for deletion."))?;
 
             for ep in &mut podcast_selected.episodes {
-                if ep.path.is_some() {
-                    match std::fs::remove_file(ep.path.as_ref().unwrap()) {
+                if let Some(path) = ep.path.take() {
+                    match delete_path(&path, to_trash) {
                         Ok(()) => {
                             eps_to_remove.push(ep.id);
-                            ep.path = None;
+                            if to_trash {
+                                trashed.push((ep.id, path));
+                            }
+                        }
+                        Err(_) => {
+                            ep.path = Some(path);
+                            success = false;
                         }
-                        Err(_) => success = false,
                     }
                 }
             }
         }
 
-        self.podcast.db_podcast.remove_files(&eps_to_remove)?;
+        if !eps_to_remove.is_empty() {
+            self.command(TuiCmd::Podcast(PodcastCmd::RemoveFiles(eps_to_remove)));
+        }
         if !success {
             bail!("Error happened when deleting a file. Please verify that it is accessible.");
         }
@@ -879,18 +1432,21 @@ impl Model {
         }
 
         let len = self.podcast.podcasts.len();
+        let mut trashed = Vec::new();
 
         for index in 0..len {
-            self.podcast_delete_files(index).ok();
+            self.podcast_delete_files(index, &mut trashed).ok();
         }
 
-        self.podcast.db_podcast.clear_db()?;
-
-        self.podcast.podcasts = Vec::new();
+        self.command(TuiCmd::Podcast(PodcastCmd::RemoveAll));
+        self.podcast.podcasts.clear();
         self.podcast.podcasts_index = 0;
 
         self.podcast_sync_feeds_and_episodes();
         self.episode_update_playlist();
+        if !trashed.is_empty() {
+            self.trash_offer_undo(TrashedItems::Episodes(trashed));
+        }
         Ok(())
     }
 
@@ -899,23 +1455,77 @@ impl Model {
             return Ok(());
         }
 
+        let mut trashed = Vec::new();
+        let mut res = Ok(());
         if let Ok(feed_index) = self.podcast_get_feed_index() {
-            self.podcast_delete_files(feed_index)?;
-            let podcast_selected = self.podcast.podcasts.remove(feed_index);
-            self.podcast
-                .db_podcast
-                .remove_podcast(podcast_selected.id)?;
+            res = self.podcast_delete_files(feed_index, &mut trashed);
+            // keep the feed if not all files could be deleted, to not lose track of them
+            if res.is_ok() {
+                let podcast = self.podcast.podcasts.remove(feed_index);
+                self.command(TuiCmd::Podcast(PodcastCmd::Remove(podcast.id)));
+                self.podcast.podcasts_index = self.podcast.podcasts_index.saturating_sub(1);
+            }
         }
 
-        self.podcast.podcasts_index = self.podcast.podcasts_index.saturating_sub(1);
         self.podcast_sync_feeds_and_episodes();
         self.episode_update_playlist();
-        Ok(())
+        if !trashed.is_empty() {
+            self.trash_offer_undo(TrashedItems::Episodes(trashed));
+        }
+        res
+    }
+
+    /// Switch to the next [`FeedSortOrder`], save it and sort the feeds with it, keeping the selected feed selected.
+    pub fn podcast_cycle_feed_sort(&mut self) -> Result<()> {
+        let selected = self
+            .podcast
+            .podcasts
+            .get(self.podcast.podcasts_index)
+            .map(|pod| pod.id);
+
+        let mut config_server = self.config_server.write();
+        let order = config_server.settings.podcast.feed_sort.next();
+        config_server.settings.podcast.feed_sort = order;
+        let res = instance_lock::ensure_held().and_then(|()| {
+            ServerConfigVersionedDefaulted::save_config_path(&config_server.settings)
+        });
+        drop(config_server);
+
+        sort_podcasts(&mut self.podcast.podcasts, order);
+        if let Some(index) = selected.and_then(|id| self.podcast.podcast_index(id)) {
+            self.podcast.podcasts_index = index;
+        }
+        self.podcast_sync_feeds_and_episodes();
+        self.podcast_select_feed_row();
+        self.show_message_timeout_label_help(
+            format!("Feeds order: {}", order.as_str()),
+            None,
+            None,
+            None,
+        );
+
+        res.context("Error while saving config")
+    }
+
+    /// Only show the feeds of the next category, showing all feeds again after the last category.
+    pub fn podcast_cycle_category(&mut self) {
+        let categories = feed_categories(&self.podcast.podcasts);
+        self.podcast.category = next_category(&categories, self.podcast.category.as_deref());
+
+        self.podcast_sync_feeds_and_episodes();
+        self.podcast_select_feed_row();
+        let msg = format!(
+            "Feeds category: {}",
+            self.podcast.category.as_deref().unwrap_or("All")
+        );
+        self.show_message_timeout_label_help(msg, None, None, None);
     }
 
     fn podcast_get_feed_index(&self) -> Result<usize> {
-        if let Ok(State::One(StateValue::Usize(feed_index))) = self.app.state(&Id::Podcast) {
-            return Ok(feed_index);
+        if let Ok(State::One(StateValue::Usize(list_index))) = self.app.state(&Id::Podcast) {
+            if let Some(feed_index) = self.podcast.feed_index(list_index) {
+                return Ok(feed_index);
+            }
         }
         Err(anyhow!("cannot get feed index"))
     }
@@ -931,6 +1541,7 @@ impl Model {
         if self.podcast.podcasts.is_empty() {
             return Ok(());
         }
+        let mut newly_played = None;
         if let Some(track) = self.playback.current_track() {
             if let Some(podcast_data) = track.as_podcast() {
                 let url = podcast_data.url();
@@ -939,9 +1550,7 @@ impl Model {
                         if ep.url == url {
                             if !ep.played {
                                 ep.played = true;
-                                self.podcast
-                                    .db_podcast
-                                    .set_played_status(ep.id, ep.played)?;
+                                newly_played = Some(ep.id);
                             }
                             break 'outer;
                         }
@@ -949,6 +1558,9 @@ impl Model {
                 }
             }
         }
+        if let Some(id) = newly_played {
+            self.command(TuiCmd::Podcast(PodcastCmd::MarkPlayed { id, played: true }));
+        }
 
         self.podcast_sync_feeds_and_episodes();
 
@@ -1051,15 +1663,13 @@ impl Model {
     }
 
     pub fn podcast_locate_episode(&mut self, pod_index: usize, ep_index: usize) {
-        assert!(
-            self.app
-                .attr(
-                    &Id::Podcast,
-                    Attribute::Value,
-                    AttrValue::Payload(PropPayload::One(PropValue::Usize(pod_index))),
-                )
-                .is_ok()
-        );
+        // the podcast may not be shown with the selected category
+        if self.podcast.list_index(pod_index).is_none() {
+            self.podcast.category = None;
+            self.podcast_sync_feeds_and_episodes();
+        }
+        self.podcast.podcasts_index = pod_index;
+        self.podcast_select_feed_row();
         self.podcast_sync_episodes().ok();
         assert!(
             self.app
@@ -1074,11 +1684,10 @@ impl Model {
         self.lyric_update();
     }
 
-    #[allow(clippy::cast_possible_wrap)]
-    pub fn podcast_find_by_ep_id(&mut self, ep_id: usize) -> Result<(usize, usize)> {
+    pub fn podcast_find_by_ep_id(&mut self, ep_id: EpisodeId) -> Result<(usize, usize)> {
         for (podcast_index, podcast) in self.podcast.podcasts.iter().enumerate() {
             for (episode_index, episode) in podcast.episodes.iter().enumerate() {
-                if episode.id == ep_id as i64 {
+                if episode.id == ep_id {
                     // Need to set podcast index here, otherwise the wrong episodes will be added
                     self.podcast.podcasts_index = podcast_index;
                     return Ok((podcast_index, episode_index));
@@ -1088,10 +1697,9 @@ impl Model {
         bail!("Cannot find ep_id")
     }
 
-    #[allow(clippy::cast_possible_wrap)]
-    pub fn podcast_find_by_pod_id(&mut self, pod_id: usize) -> Result<usize> {
+    pub fn podcast_find_by_pod_id(&mut self, pod_id: PodcastId) -> Result<usize> {
         for (podcast_index, podcast) in self.podcast.podcasts.iter().enumerate() {
-            if podcast.id == pod_id as i64 {
+            if podcast.id == pod_id {
                 // Need to set podcast index here
                 self.podcast.podcasts_index = podcast_index;
                 return Ok(podcast_index);
@@ -1152,3 +1760,65 @@ fn parse_itunes_item(v: &Value) -> Option<(String, String)> {
     let url = v.get("feedUrl")?.as_str()?.to_owned();
     Some((title, url))
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tuirealm::props::TextSpan;
+
+    use super::{align_episode_row, fit_to_width, next_category};
+
+    #[test]
+    fn should_fit_to_width() {
+        assert_eq!(fit_to_width("title", 8), "title   ");
+        assert_eq!(fit_to_width("long title", 4), "long");
+        // double-width characters are not split
+        assert_eq!(fit_to_width("日本語", 5), "日本 ");
+        assert_eq!(fit_to_width("title", 0), "");
+    }
+
+    #[test]
+    fn should_cycle_categories() {
+        let categories = ["News", "Technology"];
+        assert_eq!(next_category(&categories, None).as_deref(), Some("News"));
+        assert_eq!(
+            next_category(&categories, Some("News")).as_deref(),
+            Some("Technology")
+        );
+        assert_eq!(next_category(&categories, Some("Technology")), None);
+        // a category that does not exist anymore goes back to all feeds
+        assert_eq!(next_category(&categories, Some("Comedy")), None);
+        assert_eq!(next_category(&[], None), None);
+    }
+
+    #[test]
+    fn should_align_episode_row() {
+        let row = [
+            TextSpan::new("NEW"),
+            TextSpan::new("Episode"),
+            TextSpan::new("[00:10:00]"),
+            TextSpan::new("2024-01-01"),
+        ];
+        let to_string = |row: Vec<TextSpan>| -> String {
+            row.into_iter().map(|v| v.content).collect::<String>()
+        };
+
+        let narrow = to_string(align_episode_row(&row, 20));
+        assert_eq!(narrow, "NEW Episode         ");
+
+        let medium = to_string(align_episode_row(&row, 50));
+        assert_eq!(medium.chars().count(), 50);
+        assert!(medium.ends_with(" [00:10:00]"));
+
+        let wide = to_string(align_episode_row(&row, 70));
+        assert_eq!(wide.chars().count(), 70);
+        assert!(wide.ends_with(" [00:10:00] 2024-01-01"));
+
+        // rows which are not episodes are kept as-is
+        let empty = [TextSpan::new("empty episodes list")];
+        assert_eq!(
+            to_string(align_episode_row(&empty, 70)),
+            "empty episodes list"
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use termusiclib::player::{AudioInfo, StreamInfo, audio_info};
+
+use super::InfoPopup;
+use crate::ui::ids::Id;
+use crate::ui::model::Model;
+use crate::ui::msg::{Msg, PlayerMsg};
+
+/// Get how the server outputs audio as rows of `(name, value)`.
+#[allow(clippy::cast_precision_loss)] // sizes and volumes are far below the precision of f32
+fn audio_info_rows(info: &AudioInfo) -> Vec<(&'static str, String)> {
+    let or_dash = |v: Vec<String>| {
+        if v.is_empty() {
+            "-".to_string()
+        } else {
+            v.join(", ")
+        }
+    };
+    let stream_info: StreamInfo = info.stream_info.clone().unwrap_or_default().into();
+    let device = if info.device.is_empty() {
+        "-".to_string()
+    } else {
+        info.device.clone()
+    };
+
+    let mut output = Vec::new();
+    if info.sample_rate != 0 {
+        output.push(format!("{} Hz", info.sample_rate));
+    }
+    if info.channels != 0 {
+        output.push(format!("{} channels", info.channels));
+    }
+
+    let mut buffer = Vec::new();
+    if info.buffer_frames != 0 {
+        buffer.push(format!("{} frames output", info.buffer_frames));
+    }
+    if info.decoded_buffer_size != 0 {
+        buffer.push(format!("{} KiB decoded", info.decoded_buffer_size / 1024));
+    }
+
+    let mut track = Vec::new();
+    if let Some(codec) = &stream_info.codec {
+        track.push(codec.clone());
+    }
+    if let Some(sample_rate) = stream_info.sample_rate {
+        track.push(format!("{sample_rate} Hz"));
+    }
+    if let Some(bitrate) = stream_info.bitrate {
+        track.push(format!("{} kbps", bitrate / 1000));
+    }
+
+    let resampling = match info.optional_resampling {
+        Some(audio_info::OptionalResampling::Resampling(true)) => format!(
+            "yes, {} Hz to {} Hz",
+            stream_info.sample_rate.unwrap_or_default(),
+            info.sample_rate
+        ),
+        Some(audio_info::OptionalResampling::Resampling(false)) => "no".to_string(),
+        None => "-".to_string(),
+    };
+
+    let replay_gain = info
+        .optional_replay_gain
+        .map(|audio_info::OptionalReplayGain::ReplayGainDb(db)| db);
+    let replay_gain_text = match replay_gain {
+        _ if info.replay_gain_mode == "off" => "off".to_string(),
+        Some(db) => format!("{db:+.2} dB ({} gain)", info.replay_gain_mode),
+        None => format!(
+            "none, the track has no gain tags ({} gain)",
+            info.replay_gain_mode
+        ),
+    };
+
+    // the volume is applied as a linear factor
+    let volume_db = (info.volume != 0).then(|| 20.0 * (info.volume as f32 / 100.0).log10());
+    let volume = match volume_db {
+        Some(db) => format!("{}% ({db:+.2} dB)", info.volume),
+        None => format!("{}% (muted)", info.volume),
+    };
+    let total = match volume_db {
+        Some(db) => format!("{:+.2} dB", db + replay_gain.unwrap_or_default()),
+        None => "muted".to_string(),
+    };
+
+    vec![
+        ("Backend", info.backend.clone()),
+        ("Device", device),
+        ("Output", or_dash(output)),
+        ("Buffer", or_dash(buffer)),
+        ("Track", or_dash(track)),
+        ("Resampling", resampling),
+        ("ReplayGain", replay_gain_text),
+        ("Volume", volume),
+        ("Applied gain", total),
+    ]
+}
+
+impl Model {
+    /// Mount the audio output info of the server, which is refreshed with the `audio_info` key.
+    pub fn mount_audio_info(&mut self, info: &AudioInfo) {
+        let refresh_key = self
+            .config_tui
+            .read()
+            .settings
+            .keys
+            .player_keys
+            .audio_info
+            .clone();
+        let title = format!("Audio info (<{refresh_key}> to refresh)");
+        assert!(
+            self.app
+                .remount(
+                    Id::AudioInfoPopup,
+                    Box::new(
+                        InfoPopup::new(
+                            self.config_tui.clone(),
+                            &title,
+                            &audio_info_rows(info),
+                            Msg::Player(PlayerMsg::AudioInfoClose)
+                        )
+                        .action_key(refresh_key, Msg::Player(PlayerMsg::AudioInfoShow))
+                    ),
+                    vec![]
+                )
+                .is_ok()
+        );
+        assert!(self.app.active(&Id::AudioInfoPopup).is_ok());
+    }
+
+    pub fn umount_audio_info(&mut self) {
+        if self.app.mounted(&Id::AudioInfoPopup) {
+            assert!(self.app.umount(&Id::AudioInfoPopup).is_ok());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use termusiclib::player::{AudioInfo, StreamInfo, audio_info};
+
+    use super::audio_info_rows;
+
+    #[test]
+    fn should_show_gain_and_resampling() {
+        let info = AudioInfo {
+            backend: "rusty".to_string(),
+            device: "pipewire".to_string(),
+            sample_rate: 48000,
+            channels: 2,
+            buffer_frames: 0,
+            decoded_buffer_size: 768 * 1024,
+            optional_resampling: Some(audio_info::OptionalResampling::Resampling(true)),
+            stream_info: Some(
+                StreamInfo {
+                    codec: Some("flac".to_string()),
+                    sample_rate: Some(44100),
+                    bitrate: Some(912_000),
+                }
+                .into(),
+            ),
+            replay_gain_mode: "track".to_string(),
+            optional_replay_gain: Some(audio_info::OptionalReplayGain::ReplayGainDb(-6.5)),
+            volume: 50,
+        };
+
+        assert_eq!(
+            audio_info_rows(&info),
+            vec![
+                ("Backend", "rusty".to_string()),
+                ("Device", "pipewire".to_string()),
+                ("Output", "48000 Hz, 2 channels".to_string()),
+                ("Buffer", "768 KiB decoded".to_string()),
+                ("Track", "flac, 44100 Hz, 912 kbps".to_string()),
+                ("Resampling", "yes, 44100 Hz to 48000 Hz".to_string()),
+                ("ReplayGain", "-6.50 dB (track gain)".to_string()),
+                ("Volume", "50% (-6.02 dB)".to_string()),
+                ("Applied gain", "-12.52 dB".to_string()),
+            ]
+        );
+
+        let info = AudioInfo {
+            replay_gain_mode: "off".to_string(),
+            optional_replay_gain: None,
+            volume: 0,
+            ..AudioInfo::default()
+        };
+        let rows = audio_info_rows(&info);
+        assert_eq!(rows[1], ("Device", "-".to_string()));
+        assert_eq!(rows[6], ("ReplayGain", "off".to_string()));
+        assert_eq!(rows[8], ("Applied gain", "muted".to_string()));
+    }
+}
@@ -0,0 +1,267 @@
+use termusiclib::backup::{self, BackupInfo};
+use termusiclib::config::SharedTuiSettings;
+use termusiclib::utils::get_app_data_path;
+use tui_realm_stdlib::Table;
+use tuirealm::{
+    Component, Event, MockComponent, State, StateValue,
+    command::{Cmd, CmdResult, Direction, Position},
+    event::{Key, KeyEvent},
+    props::{Alignment, BorderType, Borders, TableBuilder, TextSpan},
+};
+
+use super::{YNConfirm, YNConfirmStyle};
+use crate::ui::ids::Id;
+use crate::ui::model::{Model, UserEvent};
+use crate::ui::msg::{DBMsg, Msg};
+
+/// The database maintenance popup, listing all backups with a entry to create a new one as the first row.
+#[derive(MockComponent)]
+pub struct DatabaseBackupPopup {
+    component: Table,
+    config: SharedTuiSettings,
+}
+
+impl DatabaseBackupPopup {
+    pub fn new(config: SharedTuiSettings, backups: &[BackupInfo]) -> Self {
+        let component = {
+            let config = config.read();
+            Table::default()
+                .background(config.settings.theme.library_background())
+                .foreground(config.settings.theme.library_foreground())
+                .borders(
+                    Borders::default()
+                        .color(config.settings.theme.library_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .title(" Enter to create or restore a backup: ", Alignment::Left)
+                .scroll(true)
+                .highlighted_color(config.settings.theme.library_highlight())
+                .highlighted_str(&config.settings.theme.style.library.highlight_symbol)
+                .rewind(false)
+                .step(4)
+                .row_height(1)
+                .headers([" Backup ", " Databases "])
+                .column_spacing(3)
+                .widths(&[40, 60])
+                .table(Self::build_table(backups))
+        };
+
+        Self { component, config }
+    }
+
+    fn build_table(backups: &[BackupInfo]) -> Vec<Vec<TextSpan>> {
+        let mut table = TableBuilder::default();
+
+        table
+            .add_col(TextSpan::new("Create new backup").bold())
+            .add_col(TextSpan::new(backup::DATABASE_FILES.join(", ")));
+
+        for backup in backups {
+            table
+                .add_row()
+                .add_col(TextSpan::new(&backup.name))
+                .add_col(TextSpan::new(backup.databases.join(", ")));
+        }
+
+        table.build()
+    }
+}
+
+impl Component<Msg, UserEvent> for DatabaseBackupPopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let config = self.config.clone();
+        let keys = &config.read().settings.keys;
+        let cmd_result = match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                return Some(Msg::DataBase(DBMsg::BackupsCloseCancel));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.quit.get() => {
+                return Some(Msg::DataBase(DBMsg::BackupsCloseCancel));
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => self.perform(Cmd::Move(Direction::Down)),
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.down.get() => {
+                self.perform(Cmd::Move(Direction::Down))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.up.get() => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                ..
+            }) => self.perform(Cmd::Scroll(Direction::Down)),
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp, ..
+            }) => self.perform(Cmd::Scroll(Direction::Up)),
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.goto_top.get() => {
+                self.perform(Cmd::GoTo(Position::Begin))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.goto_bottom.get() => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => {
+                match self.state() {
+                    State::One(StateValue::Usize(0)) => {
+                        return Some(Msg::DataBase(DBMsg::BackupCreate));
+                    }
+                    // the first row is "Create new backup"
+                    State::One(StateValue::Usize(index)) => {
+                        return Some(Msg::DataBase(DBMsg::RestoreConfirmShow(index - 1)));
+                    }
+                    _ => (),
+                }
+                CmdResult::None
+            }
+            _ => CmdResult::None,
+        };
+        match cmd_result {
+            CmdResult::None => None,
+            _ => Some(Msg::ForceRedraw),
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct DatabaseRestoreConfirmPopup {
+    component: YNConfirm,
+    index: usize,
+}
+
+impl DatabaseRestoreConfirmPopup {
+    pub fn new(config: SharedTuiSettings, backup: &BackupInfo, index: usize) -> Self {
+        let component = YNConfirm::new_with_cb(
+            config,
+            format!(
+                " Restore backup {}? This replaces the current databases! ",
+                backup.name
+            ),
+            |config| YNConfirmStyle {
+                foreground_color: config.settings.theme.important_popup_foreground(),
+                background_color: config.settings.theme.important_popup_background(),
+                border_color: config.settings.theme.important_popup_border(),
+                title_alignment: Alignment::Left,
+            },
+        );
+
+        Self { component, index }
+    }
+}
+
+impl Component<Msg, UserEvent> for DatabaseRestoreConfirmPopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(
+            ev,
+            Msg::DataBase(DBMsg::Restore(self.index)),
+            Msg::DataBase(DBMsg::RestoreConfirmCancel),
+        )
+    }
+}
+
+impl Model {
+    /// Load all backups and mount the [`DatabaseBackupPopup`].
+    pub fn mount_database_backups(&mut self) {
+        let backups = get_app_data_path().and_then(|data_dir| backup::list_backups(&data_dir));
+        match backups {
+            Ok(backups) => self.dw.backups = backups,
+            Err(err) => {
+                self.mount_error_popup(err.context("list backups"));
+                return;
+            }
+        }
+
+        assert!(
+            self.app
+                .remount(
+                    Id::DatabaseBackupPopup,
+                    Box::new(DatabaseBackupPopup::new(
+                        self.config_tui.clone(),
+                        &self.dw.backups
+                    )),
+                    vec![]
+                )
+                .is_ok()
+        );
+        assert!(self.app.active(&Id::DatabaseBackupPopup).is_ok());
+    }
+
+    pub fn umount_database_backups(&mut self) {
+        if self.app.mounted(&Id::DatabaseBackupPopup) {
+            assert!(self.app.umount(&Id::DatabaseBackupPopup).is_ok());
+        }
+        self.dw.backups = Vec::new();
+    }
+
+    /// Mount the [`DatabaseRestoreConfirmPopup`] for the backup at `index`.
+    pub fn mount_database_restore_confirm(&mut self, index: usize) {
+        let Some(backup) = self.dw.backups.get(index) else {
+            return;
+        };
+
+        assert!(
+            self.app
+                .remount(
+                    Id::DatabaseRestoreConfirmPopup,
+                    Box::new(DatabaseRestoreConfirmPopup::new(
+                        self.config_tui.clone(),
+                        backup,
+                        index
+                    )),
+                    vec![]
+                )
+                .is_ok()
+        );
+        assert!(self.app.active(&Id::DatabaseRestoreConfirmPopup).is_ok());
+    }
+
+    pub fn umount_database_restore_confirm(&mut self) {
+        if self.app.mounted(&Id::DatabaseRestoreConfirmPopup) {
+            assert!(self.app.umount(&Id::DatabaseRestoreConfirmPopup).is_ok());
+        }
+    }
+
+    /// Create a new backup and refresh the backup list.
+    pub fn database_create_backup(&mut self) {
+        let res = get_app_data_path().and_then(|data_dir| backup::create_backup(&data_dir));
+        match res {
+            Ok(backup) => {
+                self.show_message_timeout_label_help(
+                    format!("created backup {}", backup.name),
+                    None,
+                    None,
+                    None,
+                );
+                self.mount_database_backups();
+            }
+            Err(err) => self.mount_error_popup(err.context("create backup")),
+        }
+    }
+
+    /// Restore the backup at `index` and reload everything that was loaded from the databases.
+    pub fn database_restore_backup(&mut self, index: usize) {
+        let Some(name) = self.dw.backups.get(index).map(|v| v.name.clone()) else {
+            return;
+        };
+        self.umount_database_restore_confirm();
+        self.umount_database_backups();
+
+        let res = get_app_data_path().and_then(|data_dir| backup::restore_backup(&data_dir, &name));
+        if let Err(err) = res {
+            self.mount_error_popup(err.context("restore backup"));
+            return;
+        }
+
+        if let Err(err) = self.podcast.reload(&self.config_server.read()) {
+            self.mount_error_popup(err.context("podcast reload"));
+        }
+        self.podcast_sync_feeds_and_episodes();
+        self.database_reload();
+
+        self.show_message_timeout_label_help(format!("restored backup {name}"), None, None, None);
+    }
+}
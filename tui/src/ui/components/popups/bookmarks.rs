@@ -0,0 +1,291 @@
+use std::time::Duration;
+
+use termusiclib::config::{SharedTuiSettings, TuiOverlay};
+use termusiclib::player::Bookmarks;
+use tui_realm_stdlib::Table;
+use tuirealm::{
+    Component, Event, MockComponent, State, StateValue,
+    command::{Cmd, CmdResult, Direction, Position},
+    event::{Key, KeyEvent, KeyModifiers},
+    props::{Alignment, BorderType, Borders, InputType, TableBuilder, TextSpan},
+};
+
+use crate::ui::components::vendored::tui_realm_stdlib_input::Input;
+use crate::ui::ids::Id;
+use crate::ui::model::{Model, UserEvent};
+use crate::ui::msg::{Msg, PlayerMsg};
+
+#[derive(MockComponent)]
+pub struct BookmarkListPopup {
+    component: Table,
+    config: SharedTuiSettings,
+    /// The bookmark id of each row
+    ids: Vec<i64>,
+}
+
+impl BookmarkListPopup {
+    /// Create the popup with the `bookmarks` of the current track, or while still loading if `None`.
+    pub fn new(config: SharedTuiSettings, bookmarks: Option<&Bookmarks>) -> Self {
+        let bookmarks = bookmarks.map(|v| v.bookmarks.as_slice());
+        let ids: Vec<i64> = bookmarks
+            .map(|v| v.iter().map(|bookmark| bookmark.id).collect())
+            .unwrap_or_default();
+
+        let component = {
+            let config = config.read();
+            let keys = &config.settings.keys;
+            let title = if bookmarks.is_some() {
+                format!(
+                    " Enter to jump, <{}> to add at the current position, <{}> to delete: ",
+                    keys.library_keys.add_root, keys.playlist_keys.delete
+                )
+            } else {
+                " Loading bookmarks... ".to_string()
+            };
+
+            Table::default()
+                .background(config.settings.theme.library_background())
+                .foreground(config.settings.theme.library_foreground())
+                .borders(
+                    Borders::default()
+                        .color(config.settings.theme.library_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .title(title, Alignment::Left)
+                .scroll(true)
+                .highlighted_color(config.settings.theme.library_highlight())
+                .highlighted_str(&config.settings.theme.style.library.highlight_symbol)
+                .rewind(false)
+                .step(4)
+                .row_height(1)
+                .headers([" Position ", " Name "])
+                .column_spacing(1)
+                .widths(&[15, 85])
+                .table(Self::build_table(bookmarks.unwrap_or_default()))
+        };
+
+        Self {
+            component,
+            config,
+            ids,
+        }
+    }
+
+    fn build_table(bookmarks: &[termusiclib::player::Bookmark]) -> Vec<Vec<TextSpan>> {
+        let mut table = TableBuilder::default();
+
+        for (idx, bookmark) in bookmarks.iter().enumerate() {
+            if idx > 0 {
+                table.add_row();
+            }
+
+            let position = bookmark.position.map(Duration::from).unwrap_or_default();
+            table
+                .add_col(TextSpan::new(format_position(position)))
+                .add_col(TextSpan::new(&bookmark.name).bold());
+        }
+        if bookmarks.is_empty() {
+            table
+                .add_col(TextSpan::new(""))
+                .add_col(TextSpan::new("No bookmarks yet").italic());
+        }
+
+        table.build()
+    }
+
+    /// Get the id of the currently selected bookmark.
+    fn selected_id(&self) -> Option<i64> {
+        if let State::One(StateValue::Usize(index)) = self.state() {
+            return self.ids.get(index).copied();
+        }
+
+        None
+    }
+}
+
+/// Format a bookmark position as `HH:MM:SS`.
+fn format_position(position: Duration) -> String {
+    let secs = position.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3600,
+        (secs / 60) % 60,
+        secs % 60
+    )
+}
+
+impl Component<Msg, UserEvent> for BookmarkListPopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let config = self.config.clone();
+        let keys = &config.read().settings.keys;
+        let cmd_result = match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                return Some(Msg::Player(PlayerMsg::BookmarksClose));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.quit.get() => {
+                return Some(Msg::Player(PlayerMsg::BookmarksClose));
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => self.perform(Cmd::Move(Direction::Down)),
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.down.get() => {
+                self.perform(Cmd::Move(Direction::Down))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.up.get() => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.goto_top.get() => {
+                self.perform(Cmd::GoTo(Position::Begin))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.goto_bottom.get() => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.library_keys.add_root.get() => {
+                return Some(Msg::Player(PlayerMsg::BookmarkNewShow));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.playlist_keys.delete.get() => {
+                if let Some(id) = self.selected_id() {
+                    return Some(Msg::Player(PlayerMsg::BookmarkRemove(id)));
+                }
+                CmdResult::None
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => {
+                if let Some(id) = self.selected_id() {
+                    return Some(Msg::Player(PlayerMsg::BookmarkJump(id)));
+                }
+                CmdResult::None
+            }
+            _ => CmdResult::None,
+        };
+        match cmd_result {
+            CmdResult::None => None,
+            _ => Some(Msg::ForceRedraw),
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct BookmarkNewPopup {
+    component: Input,
+}
+
+impl BookmarkNewPopup {
+    pub fn new(config: &TuiOverlay) -> Self {
+        let config = &config.settings;
+        Self {
+            component: Input::default()
+                .foreground(config.theme.library_foreground())
+                .background(config.theme.library_background())
+                .borders(
+                    Borders::default()
+                        .color(config.theme.library_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .input_type(InputType::Text)
+                .title(
+                    " Name of the bookmark, may be empty: (Enter to confirm) ",
+                    Alignment::Left,
+                ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for BookmarkNewPopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let cmd_result = match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => self.perform(Cmd::Move(Direction::Left)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => self.perform(Cmd::Move(Direction::Right)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => self.perform(Cmd::GoTo(Position::Begin)),
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete, ..
+            }) => self.perform(Cmd::Cancel),
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                ..
+            }) => self.perform(Cmd::Delete),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch),
+                modifiers: KeyModifiers::SHIFT | KeyModifiers::NONE,
+            }) => self.perform(Cmd::Type(ch)),
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                return Some(Msg::Player(PlayerMsg::BookmarkNewCloseCancel));
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => match self.component.state() {
+                State::One(StateValue::String(input_string)) => {
+                    return Some(Msg::Player(PlayerMsg::BookmarkNewCloseOk(input_string)));
+                }
+                _ => CmdResult::None,
+            },
+            _ => CmdResult::None,
+        };
+        match cmd_result {
+            CmdResult::None => None,
+            _ => Some(Msg::ForceRedraw),
+        }
+    }
+}
+
+impl Model {
+    /// Mount the bookmarks of the current track, `None` while they are still requested from the server.
+    pub fn mount_bookmarks(&mut self, bookmarks: Option<&Bookmarks>) {
+        assert!(
+            self.app
+                .remount(
+                    Id::BookmarkPopup,
+                    Box::new(BookmarkListPopup::new(self.config_tui.clone(), bookmarks)),
+                    vec![]
+                )
+                .is_ok()
+        );
+        // keep the focus on the name input, if the list got refreshed while it is open
+        if !self.app.mounted(&Id::BookmarkNewPopup) {
+            assert!(self.app.active(&Id::BookmarkPopup).is_ok());
+        }
+    }
+
+    pub fn umount_bookmarks(&mut self) {
+        self.umount_bookmark_new();
+        if self.app.mounted(&Id::BookmarkPopup) {
+            assert!(self.app.umount(&Id::BookmarkPopup).is_ok());
+        }
+    }
+
+    pub fn mount_bookmark_new(&mut self) {
+        assert!(
+            self.app
+                .remount(
+                    Id::BookmarkNewPopup,
+                    Box::new(BookmarkNewPopup::new(&self.config_tui.read())),
+                    vec![]
+                )
+                .is_ok()
+        );
+        assert!(self.app.active(&Id::BookmarkNewPopup).is_ok());
+    }
+
+    /// Unmount the name input for a new bookmark and return to the list of bookmarks.
+    pub fn umount_bookmark_new(&mut self) {
+        if self.app.mounted(&Id::BookmarkNewPopup) {
+            assert!(self.app.umount(&Id::BookmarkNewPopup).is_ok());
+        }
+        if self.app.mounted(&Id::BookmarkPopup) {
+            assert!(self.app.active(&Id::BookmarkPopup).is_ok());
+        }
+    }
+}
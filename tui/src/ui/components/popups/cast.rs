@@ -0,0 +1,167 @@
+use termusiclib::config::SharedTuiSettings;
+use termusiclib::player::{CastRenderers, cast_renderers};
+use tui_realm_stdlib::Table;
+use tuirealm::{
+    Component, Event, MockComponent, State, StateValue,
+    command::{Cmd, CmdResult, Direction, Position},
+    event::{Key, KeyEvent},
+    props::{Alignment, BorderType, Borders, TableBuilder, TextSpan},
+};
+
+use crate::ui::ids::Id;
+use crate::ui::model::{Model, UserEvent};
+use crate::ui::msg::{Msg, PlayerMsg};
+
+#[derive(MockComponent)]
+pub struct CastRendererPopup {
+    component: Table,
+    config: SharedTuiSettings,
+    /// The renderer id of each row, `None` for playing on the server
+    ids: Vec<Option<String>>,
+}
+
+impl CastRendererPopup {
+    /// Create the popup with the found `renderers`, or while still searching if `None`.
+    pub fn new(config: SharedTuiSettings, renderers: Option<&CastRenderers>) -> Self {
+        let current = renderers
+            .and_then(|v| v.optional_current.as_ref())
+            .map(|cast_renderers::OptionalCurrent::Current(id)| id.as_str());
+
+        let mut ids = vec![None];
+        let mut names = vec!["This device".to_string()];
+        for renderer in renderers
+            .map(|v| v.renderers.as_slice())
+            .unwrap_or_default()
+        {
+            ids.push(Some(renderer.id.clone()));
+            names.push(renderer.name.clone());
+        }
+        let selected = ids
+            .iter()
+            .position(|v| v.as_deref() == current)
+            .unwrap_or_default();
+
+        let title = match renderers {
+            None => " Searching for network speakers... ",
+            Some(v) if v.renderers.is_empty() => " No network speakers found ",
+            Some(_) => " Enter to cast to: ",
+        };
+
+        let component = {
+            let config = config.read();
+            Table::default()
+                .background(config.settings.theme.library_background())
+                .foreground(config.settings.theme.library_foreground())
+                .borders(
+                    Borders::default()
+                        .color(config.settings.theme.library_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .title(title, Alignment::Left)
+                .scroll(true)
+                .highlighted_color(config.settings.theme.library_highlight())
+                .highlighted_str(&config.settings.theme.style.library.highlight_symbol)
+                .rewind(false)
+                .step(4)
+                .row_height(1)
+                .headers(["", " Name "])
+                .column_spacing(1)
+                .widths(&[5, 95])
+                .table(Self::build_table(&names, selected))
+                .selected_line(selected)
+        };
+
+        Self {
+            component,
+            config,
+            ids,
+        }
+    }
+
+    fn build_table(names: &[String], current: usize) -> Vec<Vec<TextSpan>> {
+        let mut table = TableBuilder::default();
+
+        for (idx, name) in names.iter().enumerate() {
+            if idx > 0 {
+                table.add_row();
+            }
+
+            let mark = if idx == current { "*" } else { "" };
+            table
+                .add_col(TextSpan::new(mark))
+                .add_col(TextSpan::new(name).bold());
+        }
+
+        table.build()
+    }
+}
+
+impl Component<Msg, UserEvent> for CastRendererPopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let config = self.config.clone();
+        let keys = &config.read().settings.keys;
+        let cmd_result = match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                return Some(Msg::Player(PlayerMsg::CastCloseCancel));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.quit.get() => {
+                return Some(Msg::Player(PlayerMsg::CastCloseCancel));
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => self.perform(Cmd::Move(Direction::Down)),
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.down.get() => {
+                self.perform(Cmd::Move(Direction::Down))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.up.get() => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.goto_top.get() => {
+                self.perform(Cmd::GoTo(Position::Begin))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.goto_bottom.get() => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => {
+                if let State::One(StateValue::Usize(index)) = self.state() {
+                    if let Some(id) = self.ids.get(index) {
+                        return Some(Msg::Player(PlayerMsg::CastCloseOk(id.clone())));
+                    }
+                }
+                CmdResult::None
+            }
+            _ => CmdResult::None,
+        };
+        match cmd_result {
+            CmdResult::None => None,
+            _ => Some(Msg::ForceRedraw),
+        }
+    }
+}
+
+impl Model {
+    /// Mount the list of network renderers, `None` while the server still searches for them.
+    pub fn mount_cast_renderers(&mut self, renderers: Option<&CastRenderers>) {
+        assert!(
+            self.app
+                .remount(
+                    Id::CastRendererPopup,
+                    Box::new(CastRendererPopup::new(self.config_tui.clone(), renderers)),
+                    vec![]
+                )
+                .is_ok()
+        );
+        assert!(self.app.active(&Id::CastRendererPopup).is_ok());
+    }
+
+    pub fn umount_cast_renderers(&mut self) {
+        if self.app.mounted(&Id::CastRendererPopup) {
+            assert!(self.app.umount(&Id::CastRendererPopup).is_ok());
+        }
+    }
+}
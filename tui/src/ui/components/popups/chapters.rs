@@ -0,0 +1,160 @@
+use std::time::Duration;
+
+use termusiclib::config::SharedTuiSettings;
+use termusiclib::podcast::episode::Chapter;
+use tui_realm_stdlib::Table;
+use tuirealm::{
+    Component, Event, MockComponent, State, StateValue,
+    command::{Cmd, CmdResult, Direction, Position},
+    event::{Key, KeyEvent},
+    props::{Alignment, BorderType, Borders, TableBuilder, TextSpan},
+};
+
+use crate::ui::ids::Id;
+use crate::ui::model::{Model, UserEvent};
+use crate::ui::msg::{Msg, PlayerMsg};
+
+#[derive(MockComponent)]
+pub struct ChapterListPopup {
+    component: Table,
+    config: SharedTuiSettings,
+}
+
+impl ChapterListPopup {
+    pub fn new(config: SharedTuiSettings, chapters: &[Chapter], current: usize) -> Self {
+        let component = {
+            let config = config.read();
+            Table::default()
+                .background(config.settings.theme.library_background())
+                .foreground(config.settings.theme.library_foreground())
+                .borders(
+                    Borders::default()
+                        .color(config.settings.theme.library_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .title(" Enter to jump to chapter: ", Alignment::Left)
+                .scroll(true)
+                .highlighted_color(config.settings.theme.library_highlight())
+                .highlighted_str(&config.settings.theme.style.library.highlight_symbol)
+                .rewind(false)
+                .step(4)
+                .row_height(1)
+                .headers([" Start ", " Title "])
+                .column_spacing(3)
+                .widths(&[15, 85])
+                .table(Self::build_table(chapters))
+                .selected_line(current)
+        };
+
+        Self { component, config }
+    }
+
+    fn build_table(chapters: &[Chapter]) -> Vec<Vec<TextSpan>> {
+        let mut table = TableBuilder::default();
+
+        for (idx, chapter) in chapters.iter().enumerate() {
+            if idx > 0 {
+                table.add_row();
+            }
+
+            table
+                .add_col(TextSpan::new(format_start(chapter.start)))
+                .add_col(TextSpan::new(&chapter.title).bold());
+        }
+
+        table.build()
+    }
+}
+
+/// Format a chapter start as `HH:MM:SS`.
+fn format_start(start: Duration) -> String {
+    let secs = start.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3600,
+        (secs / 60) % 60,
+        secs % 60
+    )
+}
+
+impl Component<Msg, UserEvent> for ChapterListPopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let config = self.config.clone();
+        let keys = &config.read().settings.keys;
+        let cmd_result = match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                return Some(Msg::Player(PlayerMsg::ChaptersCloseCancel));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.quit.get() => {
+                return Some(Msg::Player(PlayerMsg::ChaptersCloseCancel));
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => self.perform(Cmd::Move(Direction::Down)),
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.down.get() => {
+                self.perform(Cmd::Move(Direction::Down))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.up.get() => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                ..
+            }) => self.perform(Cmd::Scroll(Direction::Down)),
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp, ..
+            }) => self.perform(Cmd::Scroll(Direction::Up)),
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.goto_top.get() => {
+                self.perform(Cmd::GoTo(Position::Begin))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.goto_bottom.get() => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => {
+                if let State::One(StateValue::Usize(index)) = self.state() {
+                    return Some(Msg::Player(PlayerMsg::ChaptersCloseOk(index)));
+                }
+                CmdResult::None
+            }
+            _ => CmdResult::None,
+        };
+        match cmd_result {
+            CmdResult::None => None,
+            _ => Some(Msg::ForceRedraw),
+        }
+    }
+}
+
+impl Model {
+    /// Mount the chapter list of the current track, with the currently playing chapter selected.
+    pub fn mount_chapter_list(&mut self) {
+        let chapters = self.playback.chapters();
+        let current = self.playback.current_chapter_index().unwrap_or_default();
+
+        assert!(
+            self.app
+                .remount(
+                    Id::ChapterListPopup,
+                    Box::new(ChapterListPopup::new(
+                        self.config_tui.clone(),
+                        chapters,
+                        current
+                    )),
+                    vec![]
+                )
+                .is_ok()
+        );
+        assert!(self.app.active(&Id::ChapterListPopup).is_ok());
+    }
+
+    pub fn umount_chapter_list(&mut self) {
+        if self.app.mounted(&Id::ChapterListPopup) {
+            assert!(self.app.umount(&Id::ChapterListPopup).is_ok());
+        }
+    }
+}
@@ -46,6 +46,36 @@ impl Component<Msg, UserEvent> for DeleteConfirmRadioPopup {
     }
 }
 
+/// Component for a "Moved to trash. Undo? Y/N" popup, shown after a deletion went to the trash
+#[derive(MockComponent)]
+pub struct UndoDeletePopup {
+    component: YNConfirm,
+}
+
+impl UndoDeletePopup {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        let component =
+            YNConfirm::new_with_cb(config, " Moved to trash. Undo? ", |config| YNConfirmStyle {
+                foreground_color: config.settings.theme.important_popup_foreground(),
+                background_color: config.settings.theme.important_popup_background(),
+                border_color: config.settings.theme.important_popup_border(),
+                title_alignment: Alignment::Left,
+            });
+
+        Self { component }
+    }
+}
+
+impl Component<Msg, UserEvent> for UndoDeletePopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(
+            ev,
+            Msg::DeleteConfirm(DeleteConfirmMsg::UndoOk),
+            Msg::DeleteConfirm(DeleteConfirmMsg::UndoCancel),
+        )
+    }
+}
+
 /// Component for a "Are you sure to delete? Write DELETE" popup
 ///
 /// Also see [`DeleteConfirmRadioPopup`]
@@ -162,4 +192,26 @@ impl Model {
         );
         assert!(self.app.active(&Id::DeleteConfirmInputPopup).is_ok());
     }
+
+    /// Mount a [`UndoDeletePopup`] with [`DeleteConfirmMsg::UndoOk`] and [`DeleteConfirmMsg::UndoCancel`]
+    /// as [`Id::UndoDeletePopup`].
+    pub fn mount_undo_delete_popup(&mut self) {
+        assert!(
+            self.app
+                .remount(
+                    Id::UndoDeletePopup,
+                    Box::new(UndoDeletePopup::new(self.config_tui.clone())),
+                    vec![]
+                )
+                .is_ok()
+        );
+        assert!(self.app.active(&Id::UndoDeletePopup).is_ok());
+    }
+
+    /// Unmount the [`UndoDeletePopup`], if mounted.
+    pub fn umount_undo_delete_popup(&mut self) {
+        if self.app.mounted(&Id::UndoDeletePopup) {
+            let _drop = self.app.umount(&Id::UndoDeletePopup);
+        }
+    }
 }
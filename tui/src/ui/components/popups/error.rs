@@ -21,44 +21,171 @@
  * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
  * SOFTWARE.
  */
+use std::collections::VecDeque;
+use std::time::Instant;
+
 use termusiclib::config::SharedTuiSettings;
-use tui_realm_stdlib::Paragraph;
+use termusiclib::config::v2::tui::keys::Keys;
+use tui_realm_stdlib::Textarea;
 use tuirealm::{
     Component, Event, MockComponent,
+    command::{Cmd, Direction, Position},
     event::{Key, KeyEvent},
-    props::{Alignment, BorderType, Borders, Color, TextModifiers, TextSpan},
+    props::{Alignment, BorderType, Borders, Color, TextSpan},
 };
 
 use crate::ui::ids::Id;
 use crate::ui::model::{Model, UserEvent};
 use crate::ui::msg::{ErrorPopupMsg, Msg};
 
+/// Get the lines of `err` to display, with the full cause chain like anyhow's `{:?}` without the backtrace.
+fn error_lines(err: &anyhow::Error) -> Vec<String> {
+    let mut lines: Vec<String> = err.to_string().lines().map(str::to_string).collect();
+
+    let mut causes = err.chain().skip(1).peekable();
+    if causes.peek().is_some() {
+        lines.push(String::new());
+        lines.push("Caused by:".to_string());
+        for (idx, cause) in causes.enumerate() {
+            for (line_idx, line) in cause.to_string().lines().enumerate() {
+                if line_idx == 0 {
+                    lines.push(format!("    {idx}: {line}"));
+                } else {
+                    lines.push(format!("       {line}"));
+                }
+            }
+        }
+    }
+
+    lines
+}
+
+/// A error shown in a [`ErrorPopup`], kept in the [`ErrorHistory`].
+#[derive(Debug, Clone)]
+pub struct ErrorEntry {
+    pub lines: Vec<String>,
+    pub time: Instant,
+}
+
+/// The most recent errors, newest last.
+#[derive(Debug, Default)]
+pub struct ErrorHistory {
+    items: VecDeque<ErrorEntry>,
+}
+
+impl ErrorHistory {
+    /// The most errors kept, the oldest are dropped first.
+    const MAX: usize = 50;
+
+    pub fn push(&mut self, lines: Vec<String>) {
+        if self.items.len() >= Self::MAX {
+            self.items.pop_front();
+        }
+        self.items.push_back(ErrorEntry {
+            lines,
+            time: Instant::now(),
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Iterate over the errors, newest first.
+    pub fn iter_newest(&self) -> impl Iterator<Item = &ErrorEntry> {
+        self.items.iter().rev()
+    }
+}
+
+/// Format how long ago `time` was, like `5m ago`.
+fn format_ago(time: Instant) -> String {
+    let secs = time.elapsed().as_secs();
+    match secs {
+        0..60 => format!("{secs}s ago"),
+        60..3600 => format!("{}m ago", secs / 60),
+        _ => format!("{}h ago", secs / 3600),
+    }
+}
+
+/// Create the scrollable red text area shared by the error popups.
+fn error_textarea(title: &str, rows: Vec<TextSpan>) -> Textarea {
+    Textarea::default()
+        .borders(
+            Borders::default()
+                .color(Color::Red)
+                .modifiers(BorderType::Rounded),
+        )
+        .title(title, Alignment::Center)
+        .foreground(Color::Red)
+        .step(4)
+        .text_rows(rows)
+}
+
+/// Handle the keys shared by the error popups, scrolling `component` or closing with `on_close`.
+fn error_textarea_on(
+    component: &mut Textarea,
+    keys: &Keys,
+    ev: &Event<UserEvent>,
+    on_close: Msg,
+) -> Option<Msg> {
+    let Event::Keyboard(keyevent) = ev else {
+        return None;
+    };
+    let cmd = match keyevent {
+        KeyEvent {
+            code: Key::Enter | Key::Esc,
+            ..
+        } => return Some(on_close),
+        key if *key == keys.quit.get() || *key == keys.escape.get() => return Some(on_close),
+        KeyEvent {
+            code: Key::Down, ..
+        } => Cmd::Move(Direction::Down),
+        KeyEvent { code: Key::Up, .. } => Cmd::Move(Direction::Up),
+        KeyEvent {
+            code: Key::PageDown,
+            ..
+        } => Cmd::Scroll(Direction::Down),
+        KeyEvent {
+            code: Key::PageUp, ..
+        } => Cmd::Scroll(Direction::Up),
+        KeyEvent {
+            code: Key::Home, ..
+        } => Cmd::GoTo(Position::Begin),
+        KeyEvent { code: Key::End, .. } => Cmd::GoTo(Position::End),
+        key if *key == keys.navigation_keys.down.get() => Cmd::Move(Direction::Down),
+        key if *key == keys.navigation_keys.up.get() => Cmd::Move(Direction::Up),
+        key if *key == keys.navigation_keys.goto_top.get() => Cmd::GoTo(Position::Begin),
+        key if *key == keys.navigation_keys.goto_bottom.get() => Cmd::GoTo(Position::End),
+        _ => return None,
+    };
+    component.perform(cmd);
+
+    // "Textarea::perform" currently always returns "CmdResult::None", so always redraw on event
+    Some(Msg::ForceRedraw)
+}
+
+/// Show a single error with its full cause chain, scrollable if it does not fit.
 #[derive(MockComponent)]
 pub struct ErrorPopup {
-    component: Paragraph,
+    component: Textarea,
     config: SharedTuiSettings,
 }
 
 impl ErrorPopup {
-    pub fn new<E: Into<anyhow::Error>>(config: SharedTuiSettings, msg: E) -> Self {
-        let msg = msg.into();
-        error!("Displaying error popup: {msg:?}");
-        // TODO: Consider changing to ":?" to output "Caused By" (and possibly backtrace) OR do a custom printing (copied from anyhow) once more than 4 lines can be displayed in height
-        let msg = format!("{msg:#}");
+    pub fn new(config: SharedTuiSettings, lines: &[String]) -> Self {
+        let rows = lines
+            .iter()
+            .enumerate()
+            .map(|(idx, line)| {
+                let span = TextSpan::new(line);
+                // the error itself is more important than its causes
+                if idx == 0 { span.bold() } else { span }
+            })
+            .collect();
+
         Self {
-            component: Paragraph::default()
-                .borders(
-                    Borders::default()
-                        .color(Color::Red)
-                        .modifiers(BorderType::Rounded),
-                )
-                .title(" Error ", Alignment::Center)
-                .foreground(Color::Red)
-                // .background(Color::Black)
-                .modifiers(TextModifiers::BOLD)
-                .alignment(Alignment::Center)
-                .text([TextSpan::from(msg)]/* &msg.lines().map(|v| TextSpan::from(v)).collect::<Vec<_>>() */),
-                config
+            component: error_textarea(" Error ", rows),
+            config,
         }
     }
 }
@@ -67,39 +194,141 @@ impl Component<Msg, UserEvent> for ErrorPopup {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
         let config = self.config.clone();
         let keys = &config.read().settings.keys;
-        match ev {
-            Event::Keyboard(KeyEvent {
-                code: Key::Enter | Key::Esc,
-                ..
-            }) => Some(Msg::ErrorPopup(ErrorPopupMsg::Close)),
-            Event::Keyboard(key) if key == keys.quit.get() => {
-                Some(Msg::ErrorPopup(ErrorPopupMsg::Close))
-            }
-            Event::Keyboard(key) if key == keys.escape.get() => {
-                Some(Msg::ErrorPopup(ErrorPopupMsg::Close))
+        error_textarea_on(
+            &mut self.component,
+            keys,
+            &ev,
+            Msg::ErrorPopup(ErrorPopupMsg::Close),
+        )
+    }
+}
+
+/// Show all errors of the [`ErrorHistory`], newest first.
+#[derive(MockComponent)]
+pub struct ErrorHistoryPopup {
+    component: Textarea,
+    config: SharedTuiSettings,
+}
+
+impl ErrorHistoryPopup {
+    pub fn new(config: SharedTuiSettings, history: &ErrorHistory) -> Self {
+        let mut rows = Vec::new();
+        for (idx, entry) in history.iter_newest().enumerate() {
+            if idx > 0 {
+                rows.push(TextSpan::new(""));
             }
-            _ => None,
+            rows.push(TextSpan::new(format!("[{}]", format_ago(entry.time))).bold());
+            rows.extend(entry.lines.iter().map(TextSpan::new));
+        }
+        if rows.is_empty() {
+            rows.push(TextSpan::new("No errors so far"));
         }
+
+        Self {
+            component: error_textarea(" Recent errors ", rows),
+            config,
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ErrorHistoryPopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let config = self.config.clone();
+        let keys = &config.read().settings.keys;
+        error_textarea_on(
+            &mut self.component,
+            keys,
+            &ev,
+            Msg::ErrorPopup(ErrorPopupMsg::HistoryClose),
+        )
     }
 }
 
 impl Model {
-    /// Mount error and give focus to it
+    /// Mount error and give focus to it, also adding it to the [`ErrorHistory`]
     // This should likely be refactored to be "std::error::Error", but see https://github.com/dtolnay/anyhow/issues/63 on why it was easier this way
     pub fn mount_error_popup<E: Into<anyhow::Error>>(&mut self, err: E) {
+        let err = err.into();
+        error!("Displaying error popup: {err:?}");
+        let lines = error_lines(&err);
+
         assert!(
             self.app
                 .remount(
                     Id::ErrorPopup,
-                    Box::new(ErrorPopup::new(self.config_tui.clone(), err)),
+                    Box::new(ErrorPopup::new(self.config_tui.clone(), &lines)),
                     vec![]
                 )
                 .is_ok()
         );
         assert!(self.app.active(&Id::ErrorPopup).is_ok());
+
+        self.error_history.push(lines);
     }
 
     pub fn umount_error_popup(&mut self) {
         self.app.umount(&Id::ErrorPopup).ok();
     }
+
+    pub fn mount_error_history_popup(&mut self) {
+        assert!(
+            self.app
+                .remount(
+                    Id::ErrorHistoryPopup,
+                    Box::new(ErrorHistoryPopup::new(
+                        self.config_tui.clone(),
+                        &self.error_history
+                    )),
+                    vec![]
+                )
+                .is_ok()
+        );
+        assert!(self.app.active(&Id::ErrorHistoryPopup).is_ok());
+    }
+
+    pub fn umount_error_history_popup(&mut self) {
+        self.app.umount(&Id::ErrorHistoryPopup).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::{Context, anyhow};
+    use pretty_assertions::assert_eq;
+
+    use super::{ErrorHistory, error_lines};
+
+    #[test]
+    fn should_show_cause_chain() {
+        let err = Err::<(), _>(anyhow!("file not found\nsecond line"))
+            .context("read config")
+            .context("load settings")
+            .unwrap_err();
+
+        assert_eq!(
+            error_lines(&err),
+            [
+                "load settings",
+                "",
+                "Caused by:",
+                "    0: read config",
+                "    1: file not found",
+                "       second line",
+            ]
+        );
+        assert_eq!(error_lines(&anyhow!("single")), ["single"]);
+    }
+
+    #[test]
+    fn should_drop_oldest_errors() {
+        let mut history = ErrorHistory::default();
+        for idx in 0..=ErrorHistory::MAX {
+            history.push(vec![idx.to_string()]);
+        }
+
+        let newest: Vec<&str> = history.iter_newest().map(|v| v.lines[0].as_str()).collect();
+        assert_eq!(newest.len(), ErrorHistory::MAX);
+        assert_eq!(newest.first(), Some(&"50"));
+        assert_eq!(newest.last(), Some(&"1"));
+    }
 }
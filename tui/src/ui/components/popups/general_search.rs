@@ -2,6 +2,7 @@ use std::path::Path;
 
 use anyhow::{Result, anyhow, bail};
 use termusiclib::config::{SharedTuiSettings, TuiOverlay};
+use termusiclib::ids::{EpisodeId, PodcastId};
 use termusiclib::track::MediaTypes;
 use tui_realm_stdlib::Table;
 use tui_realm_treeview::TREE_INITIAL_NODE;
@@ -521,9 +522,8 @@ impl Model {
         Ok(())
     }
 
-    #[allow(clippy::cast_possible_wrap)]
     pub fn general_search_after_episode_add_playlist(&mut self) -> Result<()> {
-        let episode_id: usize = self.general_search_get_info(2)?.parse()?;
+        let episode_id = EpisodeId(self.general_search_get_info(2)?.parse()?);
         if let Ok((_podcast_idx, episode_idx)) = self.podcast_find_by_ep_id(episode_id) {
             self.playlist_add_episode(episode_idx)?;
         }
@@ -531,7 +531,7 @@ impl Model {
     }
 
     pub fn general_search_after_episode_select(&mut self) -> Result<()> {
-        let episode_id: usize = self.general_search_get_info(2)?.parse()?;
+        let episode_id = EpisodeId(self.general_search_get_info(2)?.parse()?);
         if let Ok((podcast_idx, episode_idx)) = self.podcast_find_by_ep_id(episode_id) {
             self.podcast_locate_episode(podcast_idx, episode_idx);
         }
@@ -539,7 +539,7 @@ impl Model {
     }
 
     pub fn general_search_after_podcast_select(&mut self) -> Result<()> {
-        let pod_id: usize = self.general_search_get_info(2)?.parse()?;
+        let pod_id = PodcastId(self.general_search_get_info(2)?.parse()?);
         if let Ok(podcast_idx) = self.podcast_find_by_pod_id(pod_id) {
             self.podcast_locate_episode(podcast_idx, 0);
         }
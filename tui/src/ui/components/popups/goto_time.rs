@@ -0,0 +1,181 @@
+use std::time::Duration;
+
+use termusiclib::config::TuiOverlay;
+use termusiclib::track::DurationFmtShort;
+use tuirealm::{
+    Component, Event, MockComponent, State, StateValue,
+    command::{Cmd, CmdResult, Direction, Position},
+    event::{Key, KeyEvent, KeyModifiers},
+    props::{Alignment, AttrValue, Attribute, BorderType, Borders, InputType},
+};
+
+use crate::ui::components::vendored::tui_realm_stdlib_input::Input;
+use crate::ui::ids::Id;
+use crate::ui::model::{Model, UserEvent};
+use crate::ui::msg::{Msg, PlayerMsg};
+
+/// Enter a time to seek the current track to, as `mm:ss` or `hh:mm:ss`.
+#[derive(MockComponent)]
+pub struct GotoTimePopup {
+    component: Input,
+    /// Duration of the current track, if known
+    total: Option<Duration>,
+}
+
+impl GotoTimePopup {
+    pub fn new(config: &TuiOverlay, total: Option<Duration>) -> Self {
+        let config = &config.settings;
+        Self {
+            component: Input::default()
+                .foreground(config.theme.fallback_foreground())
+                .background(config.theme.fallback_background())
+                .borders(
+                    Borders::default()
+                        .color(config.theme.fallback_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .input_type(InputType::Text)
+                .title(Self::title(total), Alignment::Left),
+            total,
+        }
+    }
+
+    fn title(total: Option<Duration>) -> String {
+        match total {
+            Some(total) => format!(" Go to time (of {}): ", DurationFmtShort(total)),
+            None => " Go to time: ".to_string(),
+        }
+    }
+
+    /// Check `input` and get the position to seek to, otherwise show what is wrong in the title.
+    fn submit(&mut self, input: &str) -> Option<Duration> {
+        let error = match parse_time(input) {
+            Some(position) if self.total.is_none_or(|total| position <= total) => {
+                return Some(position);
+            }
+            Some(_) => " Past the end of the track, try again: ",
+            None => " Expected mm:ss or hh:mm:ss, try again: ",
+        };
+        self.component.attr(
+            Attribute::Title,
+            AttrValue::Title((error.to_string(), Alignment::Left)),
+        );
+
+        None
+    }
+}
+
+/// Parse a time as `mm:ss` or `hh:mm:ss`, where the first part may be larger than usual, like `90:00`.
+fn parse_time(input: &str) -> Option<Duration> {
+    let parts = input
+        .trim()
+        .split(':')
+        // small enough to not overflow below
+        .map(|v| v.parse::<u32>().ok().map(u64::from))
+        .collect::<Option<Vec<_>>>()?;
+
+    let secs = match parts[..] {
+        [minutes, seconds] if seconds < 60 => minutes * 60 + seconds,
+        [hours, minutes, seconds] if minutes < 60 && seconds < 60 => {
+            hours * 3600 + minutes * 60 + seconds
+        }
+        _ => return None,
+    };
+
+    Some(Duration::from_secs(secs))
+}
+
+impl Component<Msg, UserEvent> for GotoTimePopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let cmd_result = match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => self.perform(Cmd::Move(Direction::Left)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => self.perform(Cmd::Move(Direction::Right)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => self.perform(Cmd::GoTo(Position::Begin)),
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete, ..
+            }) => self.perform(Cmd::Cancel),
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                ..
+            }) => self.perform(Cmd::Delete),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch),
+                modifiers: KeyModifiers::SHIFT | KeyModifiers::NONE,
+            }) => self.perform(Cmd::Type(ch)),
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                return Some(Msg::Player(PlayerMsg::GotoTimeCloseCancel));
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => match self.component.state() {
+                State::One(StateValue::String(input_string)) => {
+                    if let Some(position) = self.submit(&input_string) {
+                        return Some(Msg::Player(PlayerMsg::GotoTimeCloseOk(position)));
+                    }
+                    CmdResult::Changed(State::None)
+                }
+                _ => CmdResult::None,
+            },
+            _ => CmdResult::None,
+        };
+        match cmd_result {
+            CmdResult::None => None,
+            _ => Some(Msg::ForceRedraw),
+        }
+    }
+}
+
+impl Model {
+    /// Mount the input to seek the current track to a time, limited to `total` if known.
+    pub fn mount_goto_time(&mut self, total: Option<Duration>) {
+        assert!(
+            self.app
+                .remount(
+                    Id::GotoTimePopup,
+                    Box::new(GotoTimePopup::new(&self.config_tui.read(), total)),
+                    vec![]
+                )
+                .is_ok()
+        );
+        assert!(self.app.active(&Id::GotoTimePopup).is_ok());
+    }
+
+    pub fn umount_goto_time(&mut self) {
+        if self.app.mounted(&Id::GotoTimePopup) {
+            assert!(self.app.umount(&Id::GotoTimePopup).is_ok());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use super::parse_time;
+
+    #[test]
+    fn should_parse_times() {
+        let secs = |v| Some(Duration::from_secs(v));
+
+        assert_eq!(parse_time("01:30"), secs(90));
+        assert_eq!(parse_time(" 90:00 "), secs(5400));
+        assert_eq!(parse_time("1:02:03"), secs(3723));
+        assert_eq!(parse_time("0:60"), None);
+        assert_eq!(parse_time("1:60:00"), None);
+        assert_eq!(parse_time("90"), None);
+        assert_eq!(parse_time("1:2:3:4"), None);
+        assert_eq!(parse_time("a:00"), None);
+        assert_eq!(parse_time(""), None);
+    }
+}
@@ -1,7 +1,7 @@
 use std::fmt::Write as _;
 
 use termusiclib::config::SharedTuiSettings;
-use termusiclib::config::v2::tui::keys::KeyBinding;
+use termusiclib::config::v2::tui::keys::{KeyBinding, Keys};
 use tui_realm_stdlib::Table;
 use tuirealm::{
     Component, Event, MockComponent,
@@ -34,7 +34,423 @@ impl HelpPopup {
     fn comment(text: &str) -> TextSpan {
         TextSpan::new(text)
     }
+    /// Get the rows of the help, without the actions hidden in guest mode if `guest`.
     #[allow(clippy::too_many_lines)]
+    fn table(keys: &Keys, guest: bool) -> tuirealm::props::Table {
+        let mut table = TableBuilder::default();
+        table
+            .add_col(TextSpan::new("Global").bold().fg(Color::LightYellow))
+            .add_row()
+            .add_col(Self::key(&[&keys.escape, &keys.quit]))
+            .add_col(Self::comment("Exit"))
+            .add_row()
+            .add_col(TextSpan::new("<TAB>, <SHIFT+TAB>").bold().fg(Color::Cyan))
+            .add_col(TextSpan::from("Switch focus"))
+            .add_row()
+            .add_col(Self::key(&[
+                &keys.navigation_keys.left,
+                &keys.navigation_keys.right,
+                &keys.navigation_keys.up,
+                &keys.navigation_keys.down,
+                &keys.navigation_keys.goto_top,
+                &keys.navigation_keys.goto_bottom,
+            ]))
+            .add_col(Self::comment("Move cursor(vim style by default)"))
+            .add_row()
+            .add_col(Self::key(&[
+                &keys.player_keys.seek_forward,
+                &keys.player_keys.seek_backward,
+            ]))
+            .add_col(Self::comment("Seek forward/backward 5 seconds"))
+            .add_row()
+            .add_col(Self::key(&[
+                &keys.lyric_keys.adjust_offset_forwards,
+                &keys.lyric_keys.adjust_offset_backwards,
+            ]))
+            .add_col(Self::comment("Seek forward/backward 1 second for lyrics"))
+            .add_row()
+            .add_col(Self::key(&[
+                &keys.player_keys.speed_up,
+                &keys.player_keys.speed_down,
+            ]))
+            .add_col(Self::comment("Playback speed up/down 10 percent"))
+            .add_row()
+            .add_col(Self::key(&[&keys.player_keys.track_speed]))
+            .add_col(Self::comment("Keep the speed for the current track"))
+            .add_row()
+            .add_col(Self::key(&[&keys.player_keys.ab_repeat]))
+            .add_col(Self::comment("A-B repeat: set start, set end, stop"))
+            .add_row()
+            .add_col(Self::key(&[&keys.player_keys.bookmarks]))
+            .add_col(Self::comment("Bookmarks of the current track"))
+            .add_row()
+            .add_col(Self::key(&[&keys.player_keys.toggle_prefetch]))
+            .add_col(Self::comment("Toggle gapless playback"))
+            .add_row()
+            .add_col(Self::key(&[
+                &keys.lyric_keys.adjust_offset_forwards,
+                &keys.lyric_keys.adjust_offset_backwards,
+            ]))
+            .add_col(Self::comment("Before 10 seconds,adjust offset of lyrics"))
+            .add_row()
+            .add_col(Self::key(&[&keys.lyric_keys.cycle_frames]))
+            .add_col(Self::comment("Switch lyrics if more than 1 available"))
+            .add_row();
+        if !guest {
+            table
+                .add_col(Self::key(&[&keys.lyric_keys.capture_caption]))
+                .add_col(Self::comment("Insert lyric caption at current position"))
+                .add_row()
+                .add_col(Self::key(&[&keys.lyric_keys.open_editor]))
+                .add_col(Self::comment("Open editor for synced lyrics"))
+                .add_row();
+        }
+        table
+            .add_col(Self::key(&[
+                &keys.player_keys.next_track,
+                &keys.player_keys.previous_track,
+                &keys.player_keys.toggle_pause,
+            ]))
+            .add_col(Self::comment("Next/Previous/Pause current track"))
+            .add_row()
+            .add_col(Self::key(&[
+                &keys.player_keys.volume_up,
+                // &keys.player_keys.volume_plus_2,
+                &keys.player_keys.volume_down,
+                // &keys.player_keys.volume_minus_2,
+            ]))
+            .add_col(Self::comment("Increase/Decrease volume"))
+            .add_row();
+        if !guest {
+            table
+                .add_col(Self::key(&[&keys.select_view_keys.open_config]))
+                .add_col(Self::comment("Open Config Editor(all configuration)"))
+                .add_row();
+        }
+        table
+            .add_col(Self::key(&[&keys.select_view_keys.open_error_history]))
+            .add_col(Self::comment("Show recent errors"))
+            .add_row()
+            .add_col(Self::key(&[&keys.player_keys.save_playlist]))
+            .add_col(Self::comment("Save Playlist to m3u"))
+            .add_row()
+            .add_col(Self::key(&[&keys.player_keys.chapters]))
+            .add_col(Self::comment("Jump to a chapter of the current track"))
+            .add_row()
+            .add_col(Self::key(&[
+                &keys.player_keys.previous_chapter,
+                &keys.player_keys.next_chapter,
+            ]))
+            .add_col(Self::comment("Previous/Next chapter"))
+            .add_row()
+            .add_col(Self::key(&[&keys.player_keys.goto_time]))
+            .add_col(Self::comment("Go to a time in the current track"))
+            .add_row()
+            .add_col(Self::key(&[&keys.player_keys.cast]))
+            .add_col(Self::comment("Cast to a network speaker"))
+            .add_row()
+            .add_col(Self::key(&[&keys.player_keys.audio_info]))
+            .add_col(Self::comment("Show audio output info"))
+            .add_row()
+            .add_col(Self::key(&[&keys.select_view_keys.view_library]))
+            .add_col(Self::comment("Switch layout to treeview"))
+            .add_row()
+            .add_col(Self::key(&[&keys.select_view_keys.view_database]))
+            .add_col(Self::comment("Switch layout to database"))
+            .add_row()
+            .add_col(Self::key(&[&keys.select_view_keys.view_podcasts]))
+            .add_col(Self::comment("Switch layout to podcast"))
+            .add_row()
+            .add_col(Self::key(&[&keys.select_view_keys.view_radio]))
+            .add_col(Self::comment("Switch layout to radio"))
+            .add_row()
+            .add_col(Self::key(&[
+                &keys.move_cover_art_keys.move_left,
+                &keys.move_cover_art_keys.move_right,
+            ]))
+            .add_col(Self::comment("Move album cover left/right"))
+            .add_row()
+            .add_col(Self::key(&[
+                &keys.move_cover_art_keys.move_up,
+                &keys.move_cover_art_keys.move_down,
+            ]))
+            .add_col(Self::comment("Move album cover up/down"))
+            .add_row()
+            .add_col(Self::key(&[
+                &keys.move_cover_art_keys.increase_size,
+                &keys.move_cover_art_keys.decrease_size,
+            ]))
+            .add_col(Self::comment("Zoom in/out album cover"))
+            .add_row()
+            .add_col(Self::key(&[&keys.move_cover_art_keys.toggle_hide]))
+            .add_col(Self::comment("Hide/Show album cover"))
+            .add_row()
+            .add_col(TextSpan::new("Library").bold().fg(Color::LightYellow))
+            .add_row()
+            .add_col(Self::key(&[
+                &keys.library_keys.load_track,
+                &keys.library_keys.load_dir,
+            ]))
+            .add_col(Self::comment("Add one/all tracks to playlist"))
+            .add_row();
+        if !guest {
+            table
+                .add_col(Self::key(&[&keys.library_keys.delete]))
+                .add_col(Self::comment("Delete track or folder"))
+                .add_row();
+        }
+        table
+            .add_col(Self::key(&[&keys.library_keys.youtube_search]))
+            .add_col(Self::comment("Search or download track from youtube"))
+            .add_row();
+        if !guest {
+            table
+                .add_col(Self::key(&[&keys.library_keys.open_tag_editor]))
+                .add_col(Self::comment("Open tag editor for tag and lyric download"))
+                .add_row()
+                .add_col(Self::key(&[&keys.library_keys.mark]))
+                .add_col(Self::comment(
+                    "Mark track in library/database, tag editor then edits all marked",
+                ))
+                .add_row();
+        }
+        table
+            .add_col(Self::key(&[&keys.library_keys.organize]))
+            .add_col(Self::comment(
+                "Move library files by their tags, with preview",
+            ))
+            .add_row()
+            .add_col(Self::key(&[&keys.library_keys.toggle_audiobook]))
+            .add_col(Self::comment(
+                "Toggle audiobook mode, which resumes and keeps the speed per directory",
+            ))
+            .add_row()
+            .add_col(Self::key(&[
+                &keys.library_keys.track_menu,
+                &keys.playlist_keys.track_menu,
+            ]))
+            .add_col(Self::comment(
+                "Open actions menu for track in library/playlist",
+            ))
+            .add_row()
+            .add_col(Self::key(&[
+                &keys.library_keys.cycle_rating,
+                &keys.playlist_keys.cycle_rating,
+            ]))
+            .add_col(Self::comment("Cycle rating of track in library/playlist"))
+            .add_row()
+            .add_col(Self::key(&[
+                &keys.library_keys.toggle_favorite,
+                &keys.playlist_keys.toggle_favorite,
+            ]))
+            .add_col(Self::comment(
+                "Toggle favorite of track in library/playlist",
+            ))
+            .add_row();
+        if !guest {
+            table
+                .add_col(Self::key(&[
+                    &keys.library_keys.yank,
+                    &keys.library_keys.paste,
+                ]))
+                .add_col(Self::comment("Yank and Paste files"))
+                .add_row();
+        }
+        table
+            .add_col(TextSpan::new("<Enter>").bold().fg(Color::Cyan))
+            .add_col(TextSpan::from("Open sub directory as root"))
+            .add_row()
+            .add_col(TextSpan::new("<Backspace>").bold().fg(Color::Cyan))
+            .add_col(TextSpan::from("Go back to parent directory"))
+            .add_row()
+            .add_col(Self::key(&[&keys.library_keys.search]))
+            .add_col(Self::comment("Search in library"))
+            .add_row()
+            .add_col(Self::key(&[&keys.library_keys.cycle_root]))
+            .add_col(Self::comment("Switch among several root folders"))
+            .add_row()
+            .add_col(Self::key(&[&keys.library_keys.add_root]))
+            .add_col(Self::comment("Add new root folder"))
+            .add_row();
+        if !guest {
+            table
+                .add_col(Self::key(&[&keys.library_keys.remove_root]))
+                .add_col(Self::comment("Remove current root from root folder list"))
+                .add_row();
+        }
+        table
+            .add_col(Self::key(&[&keys.library_keys.rescan]))
+            .add_col(Self::comment("Rescan all root folders"))
+            .add_row()
+            .add_col(Self::key(&[&keys.library_keys.cycle_filter]))
+            .add_col(Self::comment("Cycle filter: audio/not in db/missing tags"))
+            .add_row()
+            .add_col(Self::key(&[&keys.library_keys.open_external]))
+            .add_col(Self::comment("Open with external program"))
+            .add_row()
+            .add_col(TextSpan::new("Playlist").bold().fg(Color::LightYellow))
+            .add_row();
+        if guest {
+            table
+                .add_col(Self::key(&[&keys.playlist_keys.delete]))
+                .add_col(Self::comment("Delete track from playlist"))
+                .add_row();
+        } else {
+            table
+                .add_col(Self::key(&[
+                    &keys.playlist_keys.delete,
+                    &keys.playlist_keys.delete_all,
+                ]))
+                .add_col(Self::comment("Delete one/all tracks from playlist"))
+                .add_row();
+        }
+        table
+            .add_col(Self::key(&[&keys.playlist_keys.play_selected]))
+            .add_col(Self::comment("Play selected"))
+            .add_row()
+            .add_col(Self::key(&[&keys.playlist_keys.shuffle]))
+            .add_col(Self::comment("Randomize playlist"))
+            .add_row()
+            .add_col(Self::key(&[&keys.playlist_keys.cycle_loop_mode]))
+            .add_col(Self::comment("Loop mode cycle"))
+            .add_row()
+            .add_col(Self::key(&[&keys.playlist_keys.search]))
+            .add_col(Self::comment("Search in playlist"))
+            .add_row()
+            .add_col(Self::key(&[
+                &keys.playlist_keys.swap_down,
+                &keys.playlist_keys.swap_up,
+            ]))
+            .add_col(Self::comment("Swap track down/up in playlist"))
+            .add_row()
+            .add_col(Self::key(&[
+                &keys.playlist_keys.add_random_songs,
+                &keys.playlist_keys.add_random_album,
+            ]))
+            .add_col(Self::comment("Select random tracks/albums to playlist"))
+            .add_row()
+            .add_col(Self::key(&[
+                &keys.playlist_keys.export,
+                &keys.playlist_keys.import,
+            ]))
+            .add_col(Self::comment("Export/import playlist file"))
+            .add_row()
+            .add_col(Self::key(&[
+                &keys.playlist_keys.save_queue,
+                &keys.playlist_keys.restore_queue,
+            ]))
+            .add_col(Self::comment("Save/restore queue on the server"))
+            .add_row()
+            .add_col(Self::key(&[&keys.playlist_keys.toggle_auto_queue]))
+            .add_col(Self::comment("Toggle auto-queue from the library"))
+            .add_row()
+            .add_col(Self::key(&[&keys.playlist_keys.queues]))
+            .add_col(Self::comment("Switch, create or delete named queues"))
+            .add_row()
+            .add_col(TextSpan::new("Database").bold().fg(Color::LightYellow))
+            .add_row()
+            .add_col(Self::key(&[
+                &keys.database_keys.add_selected,
+                &keys.database_keys.add_all,
+            ]))
+            .add_col(Self::comment("Add one/all track(s) to playlist"))
+            .add_row()
+            .add_col(Self::key(&[&keys.database_keys.maintenance]))
+            .add_col(Self::comment("Create / restore database backups"))
+            .add_row()
+            .add_col(Self::key(&[&keys.database_keys.preview]))
+            .add_col(Self::comment("Preview the selected track"))
+            .add_row()
+            // TODO: add search key to database
+            .add_col(Self::key(&[&keys.library_keys.search]))
+            .add_col(Self::comment("Search in database"))
+            .add_row()
+            .add_col(TextSpan::new("Podcast").bold().fg(Color::LightYellow))
+            .add_row()
+            .add_col(Self::key(&[&keys.podcast_keys.search]))
+            .add_col(Self::comment("Feeds: search for new feeds"))
+            .add_row();
+        if !guest {
+            table
+                .add_col(Self::key(&[
+                    &keys.podcast_keys.delete_feed,
+                    &keys.podcast_keys.delete_all_feeds,
+                ]))
+                .add_col(Self::comment("Feeds : delete one/all feeds"))
+                .add_row();
+        }
+        table
+            .add_col(Self::key(&[
+                &keys.podcast_keys.refresh_feed,
+                &keys.podcast_keys.refresh_all_feeds,
+            ]))
+            .add_col(Self::comment("Feeds : refresh one/all feeds"))
+            .add_row()
+            .add_col(Self::key(&[&keys.podcast_keys.cycle_feed_sort]))
+            .add_col(Self::comment("Feeds : cycle sort order"))
+            .add_row()
+            .add_col(Self::key(&[&keys.podcast_keys.cycle_category]))
+            .add_col(Self::comment("Feeds : cycle category"))
+            .add_row()
+            .add_col(Self::key(&[
+                &keys.podcast_keys.mark_played,
+                &keys.podcast_keys.mark_all_played,
+            ]))
+            .add_col(Self::comment("Episode: Mark one/all episodes played"))
+            .add_row()
+            .add_col(Self::key(&[&keys.podcast_keys.stream_episode]))
+            .add_col(Self::comment("Episode: Stream episode"))
+            .add_row()
+            .add_col(Self::key(&[&keys.podcast_keys.download_episode]))
+            .add_col(Self::comment("Episode: Download episode"))
+            .add_row()
+            .add_col(Self::key(&[&keys.podcast_keys.download_all_unplayed]))
+            .add_col(Self::comment("Episode: Download all unplayed"))
+            .add_row();
+        if !guest {
+            table
+                .add_col(Self::key(&[&keys.podcast_keys.delete_local_episode]))
+                .add_col(Self::comment("Episode: delete episode local file"))
+                .add_row();
+        }
+        table
+            .add_col(Self::key(&[&keys.podcast_keys.episode_menu]))
+            .add_col(Self::comment("Episode: menu of all actions"))
+            .add_row();
+        if !guest {
+            table
+                .add_col(Self::key(&[&keys.podcast_keys.feed_settings]))
+                .add_col(Self::comment("Feed: auto-download and retention settings"))
+                .add_row();
+        }
+        table
+            .add_col(Self::key(&[&keys.podcast_keys.feed_info]))
+            .add_col(Self::comment("Feed: author, last check and download size"))
+            .add_row();
+        if !guest {
+            table
+                .add_col(Self::key(&[&keys.podcast_keys.merge_feed]))
+                .add_col(Self::comment("Feed: merge into another feed"))
+                .add_row();
+        }
+        table
+            .add_col(Self::key(&[&keys.library_keys.search]))
+            .add_col(Self::comment("Search through added Feeds / Episodes"))
+            .add_row()
+            .add_col(TextSpan::new("Radio").bold().fg(Color::LightYellow))
+            .add_row()
+            .add_col(Self::key(&[&keys.radio_keys.search]))
+            .add_col(Self::comment("Search for new stations"))
+            .add_row()
+            .add_col(Self::key(&[&keys.radio_keys.toggle_favorite]))
+            .add_col(Self::comment("Add / remove station from favorites"))
+            .add_row()
+            .add_col(Self::key(&[&keys.navigation_keys.right]))
+            .add_col(Self::comment("Add station to playlist"));
+
+        table.build()
+    }
+
     pub fn new(config: SharedTuiSettings) -> Self {
         let component = {
             let config = config.read();
@@ -57,225 +473,7 @@ impl HelpPopup {
                 .headers(["Key", "Function"])
                 .column_spacing(3)
                 .widths(&[40, 60])
-                .table(
-                    TableBuilder::default()
-                        .add_col(TextSpan::new("Global").bold().fg(Color::LightYellow))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.escape, &keys.quit]))
-                        .add_col(Self::comment("Exit"))
-                        .add_row()
-                        .add_col(TextSpan::new("<TAB>, <SHIFT+TAB>").bold().fg(Color::Cyan))
-                        .add_col(TextSpan::from("Switch focus"))
-                        .add_row()
-                        .add_col(Self::key(&[
-                            &keys.navigation_keys.left,
-                            &keys.navigation_keys.right,
-                            &keys.navigation_keys.up,
-                            &keys.navigation_keys.down,
-                            &keys.navigation_keys.goto_top,
-                            &keys.navigation_keys.goto_bottom,
-                        ]))
-                        .add_col(Self::comment("Move cursor(vim style by default)"))
-                        .add_row()
-                        .add_col(Self::key(&[
-                            &keys.player_keys.seek_forward,
-                            &keys.player_keys.seek_backward,
-                        ]))
-                        .add_col(Self::comment("Seek forward/backward 5 seconds"))
-                        .add_row()
-                        .add_col(Self::key(&[
-                            &keys.lyric_keys.adjust_offset_forwards,
-                            &keys.lyric_keys.adjust_offset_backwards,
-                        ]))
-                        .add_col(Self::comment("Seek forward/backward 1 second for lyrics"))
-                        .add_row()
-                        .add_col(Self::key(&[
-                            &keys.player_keys.speed_up,
-                            &keys.player_keys.speed_down,
-                        ]))
-                        .add_col(Self::comment("Playback speed up/down 10 percent"))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.player_keys.toggle_prefetch]))
-                        .add_col(Self::comment("Toggle gapless playback"))
-                        .add_row()
-                        .add_col(Self::key(&[
-                            &keys.lyric_keys.adjust_offset_forwards,
-                            &keys.lyric_keys.adjust_offset_backwards,
-                        ]))
-                        .add_col(Self::comment("Before 10 seconds,adjust offset of lyrics"))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.lyric_keys.cycle_frames]))
-                        .add_col(Self::comment("Switch lyrics if more than 1 available"))
-                        .add_row()
-                        .add_col(Self::key(&[
-                            &keys.player_keys.next_track,
-                            &keys.player_keys.previous_track,
-                            &keys.player_keys.toggle_pause,
-                        ]))
-                        .add_col(Self::comment("Next/Previous/Pause current track"))
-                        .add_row()
-                        .add_col(Self::key(&[
-                            &keys.player_keys.volume_up,
-                            // &keys.player_keys.volume_plus_2,
-                            &keys.player_keys.volume_down,
-                            // &keys.player_keys.volume_minus_2,
-                        ]))
-                        .add_col(Self::comment("Increase/Decrease volume"))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.select_view_keys.open_config]))
-                        .add_col(Self::comment("Open Config Editor(all configuration)"))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.player_keys.save_playlist]))
-                        .add_col(Self::comment("Save Playlist to m3u"))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.select_view_keys.view_library]))
-                        .add_col(Self::comment("Switch layout to treeview"))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.select_view_keys.view_database]))
-                        .add_col(Self::comment("Switch layout to database"))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.select_view_keys.view_podcasts]))
-                        .add_col(Self::comment("Switch layout to podcast"))
-                        .add_row()
-                        .add_col(Self::key(&[
-                            &keys.move_cover_art_keys.move_left,
-                            &keys.move_cover_art_keys.move_right,
-                        ]))
-                        .add_col(Self::comment("Move album cover left/right"))
-                        .add_row()
-                        .add_col(Self::key(&[
-                            &keys.move_cover_art_keys.move_up,
-                            &keys.move_cover_art_keys.move_down,
-                        ]))
-                        .add_col(Self::comment("Move album cover up/down"))
-                        .add_row()
-                        .add_col(Self::key(&[
-                            &keys.move_cover_art_keys.increase_size,
-                            &keys.move_cover_art_keys.decrease_size,
-                        ]))
-                        .add_col(Self::comment("Zoom in/out album cover"))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.move_cover_art_keys.toggle_hide]))
-                        .add_col(Self::comment("Hide/Show album cover"))
-                        .add_row()
-                        .add_col(TextSpan::new("Library").bold().fg(Color::LightYellow))
-                        .add_row()
-                        .add_col(Self::key(&[
-                            &keys.library_keys.load_track,
-                            &keys.library_keys.load_dir,
-                        ]))
-                        .add_col(Self::comment("Add one/all tracks to playlist"))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.library_keys.delete]))
-                        .add_col(Self::comment("Delete track or folder"))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.library_keys.youtube_search]))
-                        .add_col(Self::comment("Search or download track from youtube"))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.library_keys.open_tag_editor]))
-                        .add_col(Self::comment("Open tag editor for tag and lyric download"))
-                        .add_row()
-                        .add_col(Self::key(&[
-                            &keys.library_keys.yank,
-                            &keys.library_keys.paste,
-                        ]))
-                        .add_col(Self::comment("Yank and Paste files"))
-                        .add_row()
-                        .add_col(TextSpan::new("<Enter>").bold().fg(Color::Cyan))
-                        .add_col(TextSpan::from("Open sub directory as root"))
-                        .add_row()
-                        .add_col(TextSpan::new("<Backspace>").bold().fg(Color::Cyan))
-                        .add_col(TextSpan::from("Go back to parent directory"))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.library_keys.search]))
-                        .add_col(Self::comment("Search in library"))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.library_keys.cycle_root]))
-                        .add_col(Self::comment("Switch among several root folders"))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.library_keys.add_root]))
-                        .add_col(Self::comment("Add new root folder"))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.library_keys.remove_root]))
-                        .add_col(Self::comment("Remove current root from root folder list"))
-                        .add_row()
-                        .add_col(TextSpan::new("Playlist").bold().fg(Color::LightYellow))
-                        .add_row()
-                        .add_col(Self::key(&[
-                            &keys.playlist_keys.delete,
-                            &keys.playlist_keys.delete_all,
-                        ]))
-                        .add_col(Self::comment("Delete one/all tracks from playlist"))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.playlist_keys.play_selected]))
-                        .add_col(Self::comment("Play selected"))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.playlist_keys.shuffle]))
-                        .add_col(Self::comment("Randomize playlist"))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.playlist_keys.cycle_loop_mode]))
-                        .add_col(Self::comment("Loop mode cycle"))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.playlist_keys.search]))
-                        .add_col(Self::comment("Search in playlist"))
-                        .add_row()
-                        .add_col(Self::key(&[
-                            &keys.playlist_keys.swap_down,
-                            &keys.playlist_keys.swap_up,
-                        ]))
-                        .add_col(Self::comment("Swap track down/up in playlist"))
-                        .add_row()
-                        .add_col(Self::key(&[
-                            &keys.playlist_keys.add_random_songs,
-                            &keys.playlist_keys.add_random_album,
-                        ]))
-                        .add_col(Self::comment("Select random tracks/albums to playlist"))
-                        .add_row()
-                        .add_col(TextSpan::new("Database").bold().fg(Color::LightYellow))
-                        .add_row()
-                        .add_col(Self::key(&[
-                            &keys.database_keys.add_selected,
-                            &keys.database_keys.add_all,
-                        ]))
-                        .add_col(Self::comment("Add one/all track(s) to playlist"))
-                        .add_row()
-                        // TODO: add search key to database
-                        .add_col(Self::key(&[&keys.library_keys.search]))
-                        .add_col(Self::comment("Search in database"))
-                        .add_row()
-                        .add_col(TextSpan::new("Podcast").bold().fg(Color::LightYellow))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.podcast_keys.search]))
-                        .add_col(Self::comment("Feeds: search for new feeds"))
-                        .add_row()
-                        .add_col(Self::key(&[
-                            &keys.podcast_keys.delete_feed,
-                            &keys.podcast_keys.delete_all_feeds,
-                        ]))
-                        .add_col(Self::comment("Feeds : delete one/all feeds"))
-                        .add_row()
-                        .add_col(Self::key(&[
-                            &keys.podcast_keys.refresh_feed,
-                            &keys.podcast_keys.refresh_all_feeds,
-                        ]))
-                        .add_col(Self::comment("Feeds : refresh one/all feeds"))
-                        .add_row()
-                        .add_col(Self::key(&[
-                            &keys.podcast_keys.mark_played,
-                            &keys.podcast_keys.mark_all_played,
-                        ]))
-                        .add_col(Self::comment("Episode: Mark one/all episodes played"))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.podcast_keys.download_episode]))
-                        .add_col(Self::comment("Episode: Download episode"))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.podcast_keys.delete_local_episode]))
-                        .add_col(Self::comment("Episode: delete episode local file"))
-                        .add_row()
-                        .add_col(Self::key(&[&keys.library_keys.search]))
-                        .add_col(Self::comment("Search through added Feeds / Episodes"))
-                        .build(),
-                )
+                .table(Self::table(keys, config.guest))
         };
 
         Self { component, config }
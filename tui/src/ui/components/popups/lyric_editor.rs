@@ -0,0 +1,302 @@
+use std::path::Path;
+
+use termusiclib::config::{SharedTuiSettings, TuiOverlay};
+use termusiclib::songtag::lrc::Caption;
+use tui_realm_stdlib::Table;
+use tuirealm::{
+    Component, Event, MockComponent, State, StateValue,
+    command::{Cmd, CmdResult, Direction, Position},
+    event::{Key, KeyEvent, KeyModifiers},
+    props::{Alignment, BorderType, Borders, InputType, TableBuilder, TextSpan},
+};
+
+use crate::ui::components::vendored::tui_realm_stdlib_input::Input;
+use crate::ui::ids::Id;
+use crate::ui::model::{Model, UserEvent};
+use crate::ui::msg::{LyricEditorMsg, LyricMsg, Msg};
+
+/// The captions of the selected lyric frame of the current track, to fix their text.
+#[derive(MockComponent)]
+pub struct LyricEditorPopup {
+    component: Table,
+    config: SharedTuiSettings,
+    /// The amount of captions, as the table has a hint row if there are none
+    len: usize,
+}
+
+impl LyricEditorPopup {
+    pub fn new(config: SharedTuiSettings, captions: &[Caption], selected: usize) -> Self {
+        let component = {
+            let config = config.read();
+            let title = format!(
+                " Enter: edit | Del: remove | <{}>: add at current position ",
+                config.settings.keys.lyric_keys.capture_caption
+            );
+            Table::default()
+                .background(config.settings.theme.library_background())
+                .foreground(config.settings.theme.library_foreground())
+                .borders(
+                    Borders::default()
+                        .color(config.settings.theme.library_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .title(title, Alignment::Left)
+                .scroll(true)
+                .highlighted_color(config.settings.theme.library_highlight())
+                .highlighted_str(&config.settings.theme.style.library.highlight_symbol)
+                .rewind(false)
+                .step(4)
+                .row_height(1)
+                .headers([" Time ", " Text "])
+                .column_spacing(3)
+                .widths(&[15, 85])
+                .table(Self::build_table(captions))
+                .selected_line(selected)
+        };
+
+        Self {
+            component,
+            config,
+            len: captions.len(),
+        }
+    }
+
+    fn build_table(captions: &[Caption]) -> Vec<Vec<TextSpan>> {
+        let mut table = TableBuilder::default();
+
+        for (idx, caption) in captions.iter().enumerate() {
+            if idx > 0 {
+                table.add_row();
+            }
+
+            table
+                .add_col(TextSpan::new(caption.display_time().to_string()))
+                .add_col(TextSpan::new(caption.text()).bold());
+        }
+        if captions.is_empty() {
+            table.add_col(TextSpan::new("")).add_col(
+                TextSpan::new("No synced lyrics yet, add captions during playback").italic(),
+            );
+        }
+
+        table.build()
+    }
+
+    /// Get the index of the selected caption, if there are any.
+    fn selected(&self) -> Option<usize> {
+        match self.state() {
+            State::One(StateValue::Usize(index)) if index < self.len => Some(index),
+            _ => None,
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for LyricEditorPopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let config = self.config.clone();
+        let keys = &config.read().settings.keys;
+        let cmd_result = match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                return Some(Msg::LyricEditor(LyricEditorMsg::Close));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.quit.get() => {
+                return Some(Msg::LyricEditor(LyricEditorMsg::Close));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.lyric_keys.capture_caption.get() => {
+                return Some(Msg::LyricMessage(LyricMsg::Capture));
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => self.perform(Cmd::Move(Direction::Down)),
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.down.get() => {
+                self.perform(Cmd::Move(Direction::Down))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.up.get() => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                ..
+            }) => self.perform(Cmd::Scroll(Direction::Down)),
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp, ..
+            }) => self.perform(Cmd::Scroll(Direction::Up)),
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.goto_top.get() => {
+                self.perform(Cmd::GoTo(Position::Begin))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.goto_bottom.get() => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => {
+                if let Some(index) = self.selected() {
+                    return Some(Msg::LyricEditor(LyricEditorMsg::EditShow(index)));
+                }
+                CmdResult::None
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete, ..
+            }) => {
+                if let Some(index) = self.selected() {
+                    return Some(Msg::LyricEditor(LyricEditorMsg::Remove(index)));
+                }
+                CmdResult::None
+            }
+            _ => CmdResult::None,
+        };
+        match cmd_result {
+            CmdResult::None => None,
+            _ => Some(Msg::ForceRedraw),
+        }
+    }
+}
+
+/// Input to edit the text of a single caption of the [`LyricEditorPopup`].
+#[derive(MockComponent)]
+pub struct LyricCaptionInputPopup {
+    component: Input,
+    index: usize,
+}
+
+impl LyricCaptionInputPopup {
+    pub fn new(config: &TuiOverlay, index: usize, text: &str) -> Self {
+        let settings = &config.settings;
+        Self {
+            index,
+            component: Input::default()
+                .foreground(settings.theme.fallback_foreground())
+                .background(settings.theme.fallback_background())
+                .borders(
+                    Borders::default()
+                        .color(settings.theme.fallback_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .input_type(InputType::Text)
+                .value(text)
+                .title(" Caption text: (Enter to confirm) ", Alignment::Left),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for LyricCaptionInputPopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let cmd_result = match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => self.perform(Cmd::Move(Direction::Left)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => self.perform(Cmd::Move(Direction::Right)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => self.perform(Cmd::GoTo(Position::Begin)),
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete, ..
+            }) => self.perform(Cmd::Cancel),
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                ..
+            }) => self.perform(Cmd::Delete),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch),
+                modifiers: KeyModifiers::SHIFT | KeyModifiers::NONE,
+            }) => self.perform(Cmd::Type(ch)),
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                return Some(Msg::LyricEditor(LyricEditorMsg::EditCloseCancel));
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => {
+                if let State::One(StateValue::String(text)) = self.component.state() {
+                    return Some(Msg::LyricEditor(LyricEditorMsg::EditCloseOk(
+                        self.index, text,
+                    )));
+                }
+                CmdResult::None
+            }
+            _ => CmdResult::None,
+        };
+        match cmd_result {
+            CmdResult::None => None,
+            _ => Some(Msg::ForceRedraw),
+        }
+    }
+}
+
+impl Model {
+    /// Get the captions of the selected lyric frame of the current track, if they are loaded.
+    fn lyric_editor_captions(&self) -> &[Caption] {
+        let for_current = |path: &Path| {
+            self.playback
+                .current_track()
+                .and_then(|v| v.as_track())
+                .is_some_and(|v| v.path() == path)
+        };
+
+        self.current_track_lyric
+            .as_ref()
+            .filter(|extra| for_current(extra.for_track.as_path()))
+            .and_then(|extra| extra.data.parsed_lyrics.as_ref())
+            .map(|v| v.captions.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Get the index of the caption shown at the current position.
+    pub fn lyric_editor_current_index(&self) -> usize {
+        let Ok(time) = i64::try_from(self.playback.current_track_pos().as_millis()) else {
+            return 0;
+        };
+
+        self.current_track_lyric
+            .as_ref()
+            .and_then(|extra| extra.data.parsed_lyrics.as_ref())
+            .and_then(|v| v.get_index(time))
+            .unwrap_or_default()
+    }
+
+    /// Mount the lyric editor for the current track, with the caption at `selected` selected.
+    pub fn mount_lyric_editor(&mut self, selected: usize) {
+        let captions = self.lyric_editor_captions();
+        let selected = selected.min(captions.len().saturating_sub(1));
+        let popup = LyricEditorPopup::new(self.config_tui.clone(), captions, selected);
+        assert!(
+            self.app
+                .remount(Id::LyricEditorPopup, Box::new(popup), vec![])
+                .is_ok()
+        );
+        assert!(self.app.active(&Id::LyricEditorPopup).is_ok());
+    }
+
+    pub fn umount_lyric_editor(&mut self) {
+        if self.app.mounted(&Id::LyricEditorPopup) {
+            assert!(self.app.umount(&Id::LyricEditorPopup).is_ok());
+        }
+    }
+
+    /// Mount the input to edit the text of the caption at `index`.
+    pub fn mount_lyric_caption_input(&mut self, index: usize) {
+        let Some(caption) = self.lyric_editor_captions().get(index) else {
+            return;
+        };
+        let popup = LyricCaptionInputPopup::new(&self.config_tui.read(), index, caption.text());
+        assert!(
+            self.app
+                .remount(Id::LyricCaptionInputPopup, Box::new(popup), vec![])
+                .is_ok()
+        );
+        assert!(self.app.active(&Id::LyricCaptionInputPopup).is_ok());
+    }
+
+    pub fn umount_lyric_caption_input(&mut self) {
+        if self.app.mounted(&Id::LyricCaptionInputPopup) {
+            assert!(self.app.umount(&Id::LyricCaptionInputPopup).is_ok());
+        }
+    }
+}
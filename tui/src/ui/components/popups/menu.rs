@@ -0,0 +1,245 @@
+//! Reusable popups to choose one of a list of actions and to show details as rows of `(name, value)`.
+
+use termusiclib::config::SharedTuiSettings;
+use termusiclib::config::v2::tui::keys::KeyBinding;
+use tui_realm_stdlib::Table;
+use tuirealm::{
+    Component, Event, MockComponent, State, StateValue,
+    command::{Cmd, CmdResult, Direction, Position},
+    event::{Key, KeyEvent},
+    props::{Alignment, BorderType, Borders, TableBuilder, TextSpan},
+};
+
+use crate::ui::model::UserEvent;
+use crate::ui::msg::Msg;
+
+/// A single action of a [`MenuPopup`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MenuItem {
+    pub label: &'static str,
+    /// The key which runs the same action outside of the menu, if any
+    pub key: Option<String>,
+    /// The message sent when the action is chosen
+    pub msg: Msg,
+}
+
+impl MenuItem {
+    pub fn new(label: &'static str, msg: Msg) -> Self {
+        Self {
+            label,
+            key: None,
+            msg,
+        }
+    }
+
+    /// Show `key` next to the label.
+    #[must_use]
+    pub fn key(mut self, key: &KeyBinding) -> Self {
+        self.key = Some(format!("<{key}>"));
+        self
+    }
+}
+
+/// A list of actions with the keys running them, to run one with `Enter`.
+#[derive(MockComponent)]
+pub struct MenuPopup {
+    component: Table,
+    config: SharedTuiSettings,
+    items: Vec<MenuItem>,
+    on_cancel: Msg,
+    /// Extra key to close the menu, usually the one which opened it
+    close_key: Option<KeyBinding>,
+}
+
+impl MenuPopup {
+    pub fn new(config: SharedTuiSettings, items: Vec<MenuItem>, on_cancel: Msg) -> Self {
+        let component = {
+            let config = config.read();
+            let mut table = TableBuilder::default();
+            for (idx, item) in items.iter().enumerate() {
+                if idx > 0 {
+                    table.add_row();
+                }
+                table
+                    .add_col(TextSpan::new(item.label).bold())
+                    .add_col(TextSpan::new(item.key.as_deref().unwrap_or_default()));
+            }
+
+            Table::default()
+                .background(config.settings.theme.library_background())
+                .foreground(config.settings.theme.library_foreground())
+                .borders(
+                    Borders::default()
+                        .color(config.settings.theme.library_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .title(" Enter to run action: ", Alignment::Left)
+                .scroll(false)
+                .highlighted_color(config.settings.theme.library_highlight())
+                .highlighted_str(&config.settings.theme.style.library.highlight_symbol)
+                .rewind(true)
+                .row_height(1)
+                .headers([" Action ", " Key "])
+                .column_spacing(3)
+                .widths(&[65, 35])
+                .table(table.build())
+        };
+
+        Self {
+            component,
+            config,
+            items,
+            on_cancel,
+            close_key: None,
+        }
+    }
+
+    /// Also close the menu with `key`.
+    #[must_use]
+    pub fn close_key(mut self, key: KeyBinding) -> Self {
+        self.close_key = Some(key);
+        self
+    }
+}
+
+impl Component<Msg, UserEvent> for MenuPopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let config = self.config.clone();
+        let keys = &config.read().settings.keys;
+        let cmd_result = match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                return Some(self.on_cancel.clone());
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.quit.get() => {
+                return Some(self.on_cancel.clone());
+            }
+            Event::Keyboard(keyevent)
+                if self
+                    .close_key
+                    .as_ref()
+                    .is_some_and(|key| keyevent == key.get()) =>
+            {
+                return Some(self.on_cancel.clone());
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => self.perform(Cmd::Move(Direction::Down)),
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.down.get() => {
+                self.perform(Cmd::Move(Direction::Down))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.up.get() => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.goto_top.get() => {
+                self.perform(Cmd::GoTo(Position::Begin))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.goto_bottom.get() => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => {
+                if let State::One(StateValue::Usize(index)) = self.state() {
+                    if let Some(item) = self.items.get(index) {
+                        return Some(item.msg.clone());
+                    }
+                }
+                CmdResult::None
+            }
+            _ => CmdResult::None,
+        };
+        match cmd_result {
+            CmdResult::None => None,
+            _ => Some(Msg::ForceRedraw),
+        }
+    }
+}
+
+/// Details shown as rows of `(name, value)`, closed with `Esc` or `Enter`.
+#[derive(MockComponent)]
+pub struct InfoPopup {
+    component: Table,
+    config: SharedTuiSettings,
+    on_close: Msg,
+    /// Extra key to send a message on, like to refresh the shown details
+    action_key: Option<(KeyBinding, Msg)>,
+}
+
+impl InfoPopup {
+    pub fn new(
+        config: SharedTuiSettings,
+        title: &str,
+        info: &[(&str, String)],
+        on_close: Msg,
+    ) -> Self {
+        let component = {
+            let config = config.read();
+            Table::default()
+                .background(config.settings.theme.library_background())
+                .foreground(config.settings.theme.library_foreground())
+                .borders(
+                    Borders::default()
+                        .color(config.settings.theme.library_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .title(format!(" {title}: "), Alignment::Left)
+                .scroll(false)
+                .row_height(1)
+                .column_spacing(3)
+                .widths(&[15, 85])
+                .table(Self::build_table(info))
+        };
+
+        Self {
+            component,
+            config,
+            on_close,
+            action_key: None,
+        }
+    }
+
+    /// Also send `msg` on `key`.
+    #[must_use]
+    pub fn action_key(mut self, key: KeyBinding, msg: Msg) -> Self {
+        self.action_key = Some((key, msg));
+        self
+    }
+
+    /// Build the table content of `info`, also to update the rows of a mounted popup.
+    pub fn build_table(info: &[(&str, String)]) -> Vec<Vec<TextSpan>> {
+        let mut table = TableBuilder::default();
+        for (idx, (name, value)) in info.iter().enumerate() {
+            if idx > 0 {
+                table.add_row();
+            }
+            table
+                .add_col(TextSpan::new(*name).bold())
+                .add_col(TextSpan::new(value));
+        }
+
+        table.build()
+    }
+}
+
+impl Component<Msg, UserEvent> for InfoPopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let config = self.config.clone();
+        let keys = &config.read().settings.keys;
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Esc | Key::Enter,
+                ..
+            }) => Some(self.on_close.clone()),
+            Event::Keyboard(keyevent) if keyevent == keys.quit.get() => Some(self.on_close.clone()),
+            Event::Keyboard(keyevent) => self
+                .action_key
+                .as_ref()
+                .filter(|(key, _)| keyevent == key.get())
+                .map(|(_, msg)| msg.clone()),
+            _ => None,
+        }
+    }
+}
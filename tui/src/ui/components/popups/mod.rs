@@ -1,28 +1,62 @@
 #![allow(clippy::module_name_repetitions)]
 
+mod audio_info;
+mod backup;
+mod bookmarks;
+mod cast;
+mod chapters;
 mod deleteconfirm;
 mod error;
 pub mod general_search;
+mod goto_time;
 mod help;
-mod message;
+mod lyric_editor;
+mod menu;
 mod mock_yn_confirm;
+mod organize;
 mod podcast;
+mod queue;
 mod quit;
+mod radio;
 mod saveplaylist;
+mod track_menu;
 pub mod youtube_search;
 
 #[allow(unused_imports)]
-pub use deleteconfirm::{DeleteConfirmInputPopup, DeleteConfirmRadioPopup};
+pub use backup::{DatabaseBackupPopup, DatabaseRestoreConfirmPopup};
 #[allow(unused_imports)]
-pub use error::ErrorPopup;
+pub use bookmarks::{BookmarkListPopup, BookmarkNewPopup};
+#[allow(unused_imports)]
+pub use cast::CastRendererPopup;
+#[allow(unused_imports)]
+pub use chapters::ChapterListPopup;
+#[allow(unused_imports)]
+pub use deleteconfirm::{DeleteConfirmInputPopup, DeleteConfirmRadioPopup, UndoDeletePopup};
+#[allow(unused_imports)]
+pub use error::{ErrorHistory, ErrorHistoryPopup, ErrorPopup};
+#[allow(unused_imports)]
+pub use goto_time::GotoTimePopup;
 #[allow(unused_imports)]
 pub use help::HelpPopup;
 #[allow(unused_imports)]
-pub use message::MessagePopup;
+pub use lyric_editor::{LyricCaptionInputPopup, LyricEditorPopup};
+#[allow(unused_imports)]
+pub use menu::{InfoPopup, MenuItem, MenuPopup};
 pub use mock_yn_confirm::{YNConfirm, YNConfirmStyle};
 #[allow(unused_imports)]
-pub use podcast::{FeedDeleteConfirmRadioPopup, PodcastAddPopup, PodcastSearchTablePopup};
+pub use organize::OrganizePopup;
+#[allow(unused_imports)]
+pub use podcast::{
+    EpisodeDownloadConfirmPopup, FeedDeleteConfirmRadioPopup, FeedMergeConfirmPopup,
+    FeedMergeTablePopup, FeedSettingsPopup, PodcastAddPopup, PodcastSearchTablePopup,
+};
+#[allow(unused_imports)]
+pub use queue::{QueueNewPopup, QueueSelectPopup};
 #[allow(unused_imports)]
 pub use quit::QuitPopup;
 #[allow(unused_imports)]
-pub use saveplaylist::{SavePlaylistConfirmPopup, SavePlaylistPopup};
+pub use radio::RadioSearchPopup;
+#[allow(unused_imports)]
+pub use saveplaylist::{
+    ImportPlaylistPopup, PlaylistFormatPopup, SavePlaylistConfirmPopup, SavePlaylistPopup,
+};
@@ -0,0 +1,236 @@
+use std::fmt::Write as _;
+
+use termusiclib::config::SharedTuiSettings;
+use termusiclib::player::OrganizeResult;
+use tui_realm_stdlib::Table;
+use tuirealm::{
+    Component, Event, MockComponent,
+    command::{Cmd, CmdResult, Direction, Position},
+    event::{Key, KeyEvent},
+    props::{Alignment, BorderType, Borders, TableBuilder, TextSpan},
+};
+
+use crate::ui::ids::Id;
+use crate::ui::model::{Model, UserEvent};
+use crate::ui::msg::{LIMsg, Msg};
+use crate::ui::tui_cmd::TuiCmd;
+
+/// Preview of organizing the library, listing which tracks would be moved where and which are skipped.
+#[derive(MockComponent)]
+pub struct OrganizePopup {
+    component: Table,
+    config: SharedTuiSettings,
+    /// Whether there are moves to apply
+    can_apply: bool,
+}
+
+impl OrganizePopup {
+    /// Create the popup while waiting for the server, with `title` saying what it is doing.
+    pub fn loading(config: SharedTuiSettings, title: &str) -> Self {
+        Self::new(config, title, Vec::new(), false)
+    }
+
+    /// Create the popup with the tracks that would be moved and skipped.
+    pub fn preview(config: SharedTuiSettings, preview: &OrganizeResult) -> Self {
+        let title = if preview.moves.is_empty() {
+            " All tracks are already organized, Esc to close: ".to_string()
+        } else {
+            format!(
+                " Enter to move {} tracks, Esc to cancel: ",
+                preview.moves.len()
+            )
+        };
+
+        Self::new(
+            config,
+            &title,
+            Self::build_table(preview),
+            !preview.moves.is_empty(),
+        )
+    }
+
+    fn new(
+        config: SharedTuiSettings,
+        title: &str,
+        table: Vec<Vec<TextSpan>>,
+        can_apply: bool,
+    ) -> Self {
+        let component = {
+            let config = config.read();
+
+            Table::default()
+                .background(config.settings.theme.library_background())
+                .foreground(config.settings.theme.library_foreground())
+                .borders(
+                    Borders::default()
+                        .color(config.settings.theme.library_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .title(title, Alignment::Left)
+                .scroll(true)
+                .highlighted_color(config.settings.theme.library_highlight())
+                .highlighted_str(&config.settings.theme.style.library.highlight_symbol)
+                .rewind(false)
+                .step(4)
+                .row_height(1)
+                .headers(["", " From ", " To "])
+                .column_spacing(1)
+                .widths(&[6, 47, 47])
+                .table(table)
+        };
+
+        Self {
+            component,
+            config,
+            can_apply,
+        }
+    }
+
+    fn build_table(preview: &OrganizeResult) -> Vec<Vec<TextSpan>> {
+        let mut table = TableBuilder::default();
+
+        let rows = preview
+            .moves
+            .iter()
+            .map(|mv| ("", mv.from.as_str(), mv.to.as_str()))
+            .chain(
+                preview
+                    .skipped
+                    .iter()
+                    .map(|skipped| ("skip", skipped.path.as_str(), skipped.reason.as_str())),
+            );
+        for (idx, (mark, from, to)) in rows.enumerate() {
+            if idx > 0 {
+                table.add_row();
+            }
+
+            table
+                .add_col(TextSpan::new(mark).bold())
+                .add_col(TextSpan::new(from))
+                .add_col(TextSpan::new(to));
+        }
+
+        table.build()
+    }
+}
+
+impl Component<Msg, UserEvent> for OrganizePopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let config = self.config.clone();
+        let keys = &config.read().settings.keys;
+        let cmd_result = match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                return Some(Msg::Library(LIMsg::OrganizeClose));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.quit.get() => {
+                return Some(Msg::Library(LIMsg::OrganizeClose));
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => self.perform(Cmd::Move(Direction::Down)),
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.down.get() => {
+                self.perform(Cmd::Move(Direction::Down))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.up.get() => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.goto_top.get() => {
+                self.perform(Cmd::GoTo(Position::Begin))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.goto_bottom.get() => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) if self.can_apply => {
+                return Some(Msg::Library(LIMsg::OrganizeApply));
+            }
+            _ => CmdResult::None,
+        };
+        match cmd_result {
+            CmdResult::None => None,
+            _ => Some(Msg::ForceRedraw),
+        }
+    }
+}
+
+impl Model {
+    fn mount_organize(&mut self, popup: OrganizePopup) {
+        assert!(
+            self.app
+                .remount(Id::OrganizePopup, Box::new(popup), vec![])
+                .is_ok()
+        );
+        assert!(self.app.active(&Id::OrganizePopup).is_ok());
+    }
+
+    pub fn umount_organize(&mut self) {
+        if self.app.mounted(&Id::OrganizePopup) {
+            assert!(self.app.umount(&Id::OrganizePopup).is_ok());
+        }
+    }
+
+    /// Request a preview of organizing the library from the server, see `player.organize_pattern`.
+    pub fn organize_preview(&mut self) {
+        self.mount_organize(OrganizePopup::loading(
+            self.config_tui.clone(),
+            " Reading the tags of the library... ",
+        ));
+        self.command(TuiCmd::OrganizeLibrary { dry_run: true });
+    }
+
+    /// Let the server move the tracks of the preview.
+    pub fn organize_apply(&mut self) {
+        self.mount_organize(OrganizePopup::loading(
+            self.config_tui.clone(),
+            " Moving tracks... ",
+        ));
+        self.command(TuiCmd::OrganizeLibrary { dry_run: false });
+    }
+
+    /// Show the preview of organizing the library, or the outcome after it got applied.
+    pub fn handle_organize(&mut self, dry_run: bool, result: Result<OrganizeResult, String>) {
+        // the preview may have been closed while the server was reading the tags
+        if dry_run && !self.app.mounted(&Id::OrganizePopup) {
+            return;
+        }
+        let result = match result {
+            Ok(v) => v,
+            Err(err) => {
+                self.umount_organize();
+                self.mount_error_popup(anyhow::anyhow!(err).context("organize library"));
+                return;
+            }
+        };
+
+        if dry_run {
+            self.mount_organize(OrganizePopup::preview(self.config_tui.clone(), &result));
+            return;
+        }
+
+        self.umount_organize();
+        self.library_reload_with_node_focus(None);
+
+        if result.failed.is_empty() {
+            self.show_toast(
+                "Organize library",
+                &format!("Moved {} tracks", result.moves.len()),
+                Some(5),
+            );
+            return;
+        }
+
+        let mut text = format!(
+            "Moved {} tracks, {} could not be moved:\n",
+            result.moves.len(),
+            result.failed.len()
+        );
+        for failed in &result.failed {
+            let _ = writeln!(text, "{}: {}", failed.path, failed.reason);
+        }
+        self.mount_error_popup(anyhow::anyhow!(text));
+    }
+}
@@ -1,18 +1,20 @@
 use termusiclib::config::{SharedTuiSettings, TuiOverlay};
+use termusiclib::ids::PodcastId;
+use termusiclib::podcast::feed_settings::FeedSettings;
 use tui_realm_stdlib::Table;
 use tuirealm::{
-    Component, Event, MockComponent, State, StateValue,
+    AttrValue, Attribute, Component, Event, MockComponent, State, StateValue,
     command::{Cmd, CmdResult, Direction, Position},
     event::{Key, KeyEvent, KeyModifiers},
     props::{Alignment, BorderType, Borders, InputType, TableBuilder, TextSpan},
 };
 
-use super::{YNConfirm, YNConfirmStyle};
+use super::{InfoPopup, MenuItem, MenuPopup, YNConfirm, YNConfirmStyle};
 use crate::ui::components::popups::DeleteConfirmInputPopup;
 use crate::ui::components::vendored::tui_realm_stdlib_input::Input;
 use crate::ui::ids::Id;
 use crate::ui::model::{Model, UserEvent};
-use crate::ui::msg::{Msg, PCMsg};
+use crate::ui::msg::{EpisodeMenuAction, Msg, PCMsg, UnplayedDownload};
 
 #[derive(MockComponent)]
 pub struct PodcastAddPopup {
@@ -118,6 +120,341 @@ impl Component<Msg, UserEvent> for FeedDeleteConfirmRadioPopup {
     }
 }
 
+#[derive(MockComponent)]
+pub struct EpisodeDownloadConfirmPopup {
+    component: YNConfirm,
+}
+
+impl EpisodeDownloadConfirmPopup {
+    pub fn new(config: SharedTuiSettings, download: &UnplayedDownload) -> Self {
+        let title = format!(
+            " Download {} unplayed episodes ({})? ",
+            download.ids.len(),
+            download.size
+        );
+        let component = YNConfirm::new_with_cb(config, title, |config| YNConfirmStyle {
+            foreground_color: config.settings.theme.library_foreground(),
+            background_color: config.settings.theme.library_background(),
+            border_color: config.settings.theme.library_border(),
+            title_alignment: Alignment::Left,
+        });
+
+        Self { component }
+    }
+}
+
+impl Component<Msg, UserEvent> for EpisodeDownloadConfirmPopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(
+            ev,
+            Msg::Podcast(PCMsg::EpisodeDownloadUnplayedCloseOk),
+            Msg::Podcast(PCMsg::EpisodeDownloadUnplayedCloseCancel),
+        )
+    }
+}
+
+#[derive(MockComponent)]
+pub struct FeedSettingsPopup {
+    component: Table,
+    config: SharedTuiSettings,
+    /// The feed the settings are for
+    pod_id: PodcastId,
+    settings: FeedSettings,
+}
+
+impl FeedSettingsPopup {
+    pub fn new(config: SharedTuiSettings, pod_id: PodcastId, settings: FeedSettings) -> Self {
+        let component = {
+            let config = config.read();
+            Table::default()
+                .background(config.settings.theme.library_background())
+                .foreground(config.settings.theme.library_foreground())
+                .borders(
+                    Borders::default()
+                        .color(config.settings.theme.library_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .title(
+                    " Left/Right to change, 0 to disable, Enter to save: ",
+                    Alignment::Left,
+                )
+                .scroll(false)
+                .highlighted_color(config.settings.theme.library_highlight())
+                .highlighted_str(&config.settings.theme.style.library.highlight_symbol)
+                .rewind(true)
+                .row_height(1)
+                .column_spacing(3)
+                .widths(&[60, 40])
+                .table(Self::build_table(&settings))
+        };
+
+        Self {
+            component,
+            config,
+            pod_id,
+            settings,
+        }
+    }
+
+    fn build_table(settings: &FeedSettings) -> Vec<Vec<TextSpan>> {
+        let value = |value: Option<u32>, unit: &str| match value {
+            Some(value) => format!("{value}{unit}"),
+            None => "off".to_string(),
+        };
+
+        TableBuilder::default()
+            .add_col(TextSpan::new("Auto-download newest episodes").bold())
+            .add_col(TextSpan::new(value(settings.auto_download, "")))
+            .add_row()
+            .add_col(TextSpan::new("Keep downloaded episodes").bold())
+            .add_col(TextSpan::new(value(settings.keep_downloaded, "")))
+            .add_row()
+            .add_col(TextSpan::new("Skip episodes older than").bold())
+            .add_col(TextSpan::new(value(settings.max_age_days, " days")))
+            .add_row()
+            .add_col(TextSpan::new("Skip intro of episodes").bold())
+            .add_col(TextSpan::new(value(settings.skip_intro, "s")))
+            .add_row()
+            .add_col(TextSpan::new("Skip outro of episodes").bold())
+            .add_col(TextSpan::new(value(settings.skip_outro, "s")))
+            .build()
+    }
+
+    /// Get the setting of the selected row, and by how much it changes per step.
+    fn selected(&mut self) -> Option<(&mut Option<u32>, i64)> {
+        let State::One(StateValue::Usize(index)) = self.component.state() else {
+            return None;
+        };
+
+        match index {
+            0 => Some((&mut self.settings.auto_download, 1)),
+            1 => Some((&mut self.settings.keep_downloaded, 1)),
+            2 => Some((&mut self.settings.max_age_days, 1)),
+            3 => Some((&mut self.settings.skip_intro, 5)),
+            4 => Some((&mut self.settings.skip_outro, 5)),
+            _ => None,
+        }
+    }
+
+    /// Change the setting of the selected row by `steps`, where `0` disables it.
+    fn change(&mut self, steps: i64) -> CmdResult {
+        let Some((value, step)) = self.selected() else {
+            return CmdResult::None;
+        };
+
+        let new = i64::from(value.unwrap_or(0)).saturating_add(steps * step);
+        *value = u32::try_from(new).ok().filter(|v| *v > 0);
+        self.update_table()
+    }
+
+    /// Disable the setting of the selected row.
+    fn disable(&mut self) -> CmdResult {
+        let Some((value, _)) = self.selected() else {
+            return CmdResult::None;
+        };
+
+        *value = None;
+        self.update_table()
+    }
+
+    fn update_table(&mut self) -> CmdResult {
+        self.component.attr(
+            Attribute::Content,
+            AttrValue::Table(Self::build_table(&self.settings)),
+        );
+
+        CmdResult::Changed(self.component.state())
+    }
+}
+
+impl Component<Msg, UserEvent> for FeedSettingsPopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let config = self.config.clone();
+        let keys = &config.read().settings.keys;
+        let cmd_result = match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                return Some(Msg::Podcast(PCMsg::FeedSettingsCloseCancel));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.quit.get() => {
+                return Some(Msg::Podcast(PCMsg::FeedSettingsCloseCancel));
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => {
+                return Some(Msg::Podcast(PCMsg::FeedSettingsCloseOk(
+                    self.pod_id,
+                    self.settings,
+                )));
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => self.perform(Cmd::Move(Direction::Down)),
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.down.get() => {
+                self.perform(Cmd::Move(Direction::Down))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.up.get() => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => self.change(-1),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => self.change(1),
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.left.get() => {
+                self.change(-1)
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.right.get() => {
+                self.change(1)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('0'),
+                ..
+            }) => self.disable(),
+            _ => CmdResult::None,
+        };
+        match cmd_result {
+            CmdResult::None => None,
+            _ => Some(Msg::ForceRedraw),
+        }
+    }
+}
+
+/// List of the feeds to merge a feed into, see [`podcast_feed_merge`](Model::podcast_feed_merge).
+#[derive(MockComponent)]
+pub struct FeedMergeTablePopup {
+    component: Table,
+    config: SharedTuiSettings,
+}
+
+impl FeedMergeTablePopup {
+    pub fn new(
+        config: SharedTuiSettings,
+        source_title: &str,
+        targets: &[(String, String)],
+    ) -> Self {
+        let component = {
+            let config = config.read();
+            let mut table = TableBuilder::default();
+            for (idx, (title, url)) in targets.iter().enumerate() {
+                if idx > 0 {
+                    table.add_row();
+                }
+                table
+                    .add_col(TextSpan::new(title).bold())
+                    .add_col(TextSpan::new(url));
+            }
+
+            Table::default()
+                .background(config.settings.theme.library_background())
+                .foreground(config.settings.theme.library_foreground())
+                .borders(
+                    Borders::default()
+                        .color(config.settings.theme.library_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .title(format!(" Merge \"{source_title}\" into: "), Alignment::Left)
+                .scroll(true)
+                .highlighted_color(config.settings.theme.library_highlight())
+                .highlighted_str(&config.settings.theme.style.library.highlight_symbol)
+                .rewind(false)
+                .step(4)
+                .row_height(1)
+                .headers([" Name ", " url "])
+                .column_spacing(3)
+                .widths(&[40, 60])
+                .table(table.build())
+        };
+
+        Self { component, config }
+    }
+}
+
+impl Component<Msg, UserEvent> for FeedMergeTablePopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let config = self.config.clone();
+        let keys = &config.read().settings.keys;
+        let cmd_result = match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                return Some(Msg::Podcast(PCMsg::FeedMergeSelectCancel));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.quit.get() => {
+                return Some(Msg::Podcast(PCMsg::FeedMergeSelectCancel));
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => self.perform(Cmd::Move(Direction::Down)),
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.down.get() => {
+                self.perform(Cmd::Move(Direction::Down))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.up.get() => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                ..
+            }) => self.perform(Cmd::Scroll(Direction::Down)),
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp, ..
+            }) => self.perform(Cmd::Scroll(Direction::Up)),
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.goto_top.get() => {
+                self.perform(Cmd::GoTo(Position::Begin))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.goto_bottom.get() => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => {
+                if let State::One(StateValue::Usize(index)) = self.state() {
+                    return Some(Msg::Podcast(PCMsg::FeedMergeSelect(index)));
+                }
+                CmdResult::None
+            }
+            _ => CmdResult::None,
+        };
+        match cmd_result {
+            CmdResult::None => None,
+            _ => Some(Msg::ForceRedraw),
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct FeedMergeConfirmPopup {
+    component: YNConfirm,
+}
+
+impl FeedMergeConfirmPopup {
+    pub fn new(config: SharedTuiSettings, source_title: &str, target_title: &str) -> Self {
+        let title = format!(" Merge \"{source_title}\" into \"{target_title}\" and remove it? ");
+        let component = YNConfirm::new_with_cb(config, title, |config| YNConfirmStyle {
+            foreground_color: config.settings.theme.library_foreground(),
+            background_color: config.settings.theme.library_background(),
+            border_color: config.settings.theme.library_border(),
+            title_alignment: Alignment::Left,
+        });
+
+        Self { component }
+    }
+}
+
+impl Component<Msg, UserEvent> for FeedMergeConfirmPopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        self.component.on(
+            ev,
+            Msg::Podcast(PCMsg::FeedMergeCloseOk),
+            Msg::Podcast(PCMsg::FeedMergeCloseCancel),
+        )
+    }
+}
+
 #[derive(MockComponent)]
 pub struct PodcastSearchTablePopup {
     component: Table,
@@ -240,6 +577,226 @@ impl Model {
             assert!(self.app.umount(&Id::FeedDeleteConfirmRadioPopup).is_ok());
         }
     }
+    pub fn mount_episode_download_confirm(&mut self, download: &UnplayedDownload) {
+        assert!(
+            self.app
+                .remount(
+                    Id::EpisodeDownloadConfirmPopup,
+                    Box::new(EpisodeDownloadConfirmPopup::new(
+                        self.config_tui.clone(),
+                        download
+                    )),
+                    vec![]
+                )
+                .is_ok()
+        );
+        assert!(self.app.active(&Id::EpisodeDownloadConfirmPopup).is_ok());
+    }
+
+    pub fn umount_episode_download_confirm(&mut self) {
+        if self.app.mounted(&Id::EpisodeDownloadConfirmPopup) {
+            assert!(self.app.umount(&Id::EpisodeDownloadConfirmPopup).is_ok());
+        }
+    }
+
+    pub fn mount_feed_merge_table(&mut self, source_title: &str, targets: &[(String, String)]) {
+        assert!(
+            self.app
+                .remount(
+                    Id::FeedMergeTablePopup,
+                    Box::new(FeedMergeTablePopup::new(
+                        self.config_tui.clone(),
+                        source_title,
+                        targets
+                    )),
+                    vec![]
+                )
+                .is_ok()
+        );
+        assert!(self.app.active(&Id::FeedMergeTablePopup).is_ok());
+    }
+
+    pub fn umount_feed_merge_table(&mut self) {
+        if self.app.mounted(&Id::FeedMergeTablePopup) {
+            assert!(self.app.umount(&Id::FeedMergeTablePopup).is_ok());
+        }
+    }
+
+    pub fn mount_feed_merge_confirm(&mut self, source_title: &str, target_title: &str) {
+        assert!(
+            self.app
+                .remount(
+                    Id::FeedMergeConfirmPopup,
+                    Box::new(FeedMergeConfirmPopup::new(
+                        self.config_tui.clone(),
+                        source_title,
+                        target_title
+                    )),
+                    vec![]
+                )
+                .is_ok()
+        );
+        assert!(self.app.active(&Id::FeedMergeConfirmPopup).is_ok());
+    }
+
+    pub fn umount_feed_merge_confirm(&mut self) {
+        if self.app.mounted(&Id::FeedMergeConfirmPopup) {
+            assert!(self.app.umount(&Id::FeedMergeConfirmPopup).is_ok());
+        }
+    }
+
+    pub fn mount_episode_menu(&mut self, ep_index: usize) {
+        let config = self.config_tui.read();
+        let keys = &config.settings.keys.podcast_keys;
+        let items = EpisodeMenuAction::ALL
+            .into_iter()
+            .filter(|action| {
+                !config.guest
+                    || !Msg::Podcast(PCMsg::EpisodeMenuCloseOk(ep_index, *action)).is_destructive()
+            })
+            .map(|action| {
+                let item = MenuItem::new(
+                    action.label(),
+                    Msg::Podcast(PCMsg::EpisodeMenuCloseOk(ep_index, action)),
+                );
+                match action {
+                    EpisodeMenuAction::Play => MenuItem {
+                        key: Some("<ENTER>".to_string()),
+                        ..item
+                    },
+                    EpisodeMenuAction::Stream => item.key(&keys.stream_episode),
+                    EpisodeMenuAction::Download => item.key(&keys.download_episode),
+                    EpisodeMenuAction::MarkPlayed => item.key(&keys.mark_played),
+                    EpisodeMenuAction::DeleteFile => item.key(&keys.delete_local_episode),
+                    EpisodeMenuAction::CopyUrl
+                    | EpisodeMenuAction::OpenLink
+                    | EpisodeMenuAction::Info => item,
+                }
+            })
+            .collect();
+        let close_key = keys.episode_menu.clone();
+        drop(config);
+
+        assert!(
+            self.app
+                .remount(
+                    Id::EpisodeMenuPopup,
+                    Box::new(
+                        MenuPopup::new(
+                            self.config_tui.clone(),
+                            items,
+                            Msg::Podcast(PCMsg::EpisodeMenuCloseCancel)
+                        )
+                        .close_key(close_key)
+                    ),
+                    vec![]
+                )
+                .is_ok()
+        );
+        assert!(self.app.active(&Id::EpisodeMenuPopup).is_ok());
+    }
+
+    pub fn umount_episode_menu(&mut self) {
+        if self.app.mounted(&Id::EpisodeMenuPopup) {
+            assert!(self.app.umount(&Id::EpisodeMenuPopup).is_ok());
+        }
+    }
+
+    pub fn mount_episode_info(&mut self, info: &[(&str, String)]) {
+        assert!(
+            self.app
+                .remount(
+                    Id::EpisodeInfoPopup,
+                    Box::new(InfoPopup::new(
+                        self.config_tui.clone(),
+                        "Episode info",
+                        info,
+                        Msg::Podcast(PCMsg::EpisodeInfoClose)
+                    )),
+                    vec![]
+                )
+                .is_ok()
+        );
+        assert!(self.app.active(&Id::EpisodeInfoPopup).is_ok());
+    }
+
+    pub fn umount_episode_info(&mut self) {
+        if self.app.mounted(&Id::EpisodeInfoPopup) {
+            assert!(self.app.umount(&Id::EpisodeInfoPopup).is_ok());
+        }
+    }
+
+    /// Mount the info of the feed `pod_id`, which is re-checked with the `refresh_feed` key.
+    pub fn mount_feed_info(&mut self, pod_id: PodcastId, info: &[(&str, String)]) {
+        let refresh_key = self
+            .config_tui
+            .read()
+            .settings
+            .keys
+            .podcast_keys
+            .refresh_feed
+            .clone();
+        let title = format!("Feed info (<{refresh_key}> to re-check)");
+        assert!(
+            self.app
+                .remount(
+                    Id::FeedInfoPopup,
+                    Box::new(
+                        InfoPopup::new(
+                            self.config_tui.clone(),
+                            &title,
+                            info,
+                            Msg::Podcast(PCMsg::FeedInfoClose)
+                        )
+                        .action_key(refresh_key, Msg::Podcast(PCMsg::FeedInfoRefresh(pod_id)))
+                    ),
+                    vec![]
+                )
+                .is_ok()
+        );
+        assert!(self.app.active(&Id::FeedInfoPopup).is_ok());
+    }
+
+    /// Replace the rows of the mounted feed info popup with `info`.
+    pub fn update_feed_info(&mut self, info: &[(&str, String)]) {
+        self.app
+            .attr(
+                &Id::FeedInfoPopup,
+                Attribute::Content,
+                AttrValue::Table(InfoPopup::build_table(info)),
+            )
+            .ok();
+    }
+
+    pub fn umount_feed_info(&mut self) {
+        if self.app.mounted(&Id::FeedInfoPopup) {
+            assert!(self.app.umount(&Id::FeedInfoPopup).is_ok());
+        }
+    }
+
+    pub fn mount_feed_settings(&mut self, pod_id: PodcastId, settings: FeedSettings) {
+        assert!(
+            self.app
+                .remount(
+                    Id::FeedSettingsPopup,
+                    Box::new(FeedSettingsPopup::new(
+                        self.config_tui.clone(),
+                        pod_id,
+                        settings
+                    )),
+                    vec![]
+                )
+                .is_ok()
+        );
+        assert!(self.app.active(&Id::FeedSettingsPopup).is_ok());
+    }
+
+    pub fn umount_feed_settings(&mut self) {
+        if self.app.mounted(&Id::FeedSettingsPopup) {
+            assert!(self.app.umount(&Id::FeedSettingsPopup).is_ok());
+        }
+    }
+
     pub fn mount_feed_delete_confirm_input(&mut self) {
         assert!(
             self.app
@@ -0,0 +1,280 @@
+use termusiclib::config::{SharedTuiSettings, TuiOverlay};
+use termusiclib::player::PlayQueues;
+use tui_realm_stdlib::Table;
+use tuirealm::{
+    Component, Event, MockComponent, State, StateValue,
+    command::{Cmd, CmdResult, Direction, Position},
+    event::{Key, KeyEvent, KeyModifiers},
+    props::{Alignment, BorderType, Borders, InputType, TableBuilder, TextSpan},
+};
+
+use crate::ui::components::vendored::tui_realm_stdlib_input::Input;
+use crate::ui::ids::Id;
+use crate::ui::model::{Model, UserEvent};
+use crate::ui::msg::{Msg, PLMsg};
+
+#[derive(MockComponent)]
+pub struct QueueSelectPopup {
+    component: Table,
+    config: SharedTuiSettings,
+    /// The queue name of each row
+    names: Vec<String>,
+}
+
+impl QueueSelectPopup {
+    /// Create the popup with the stored `queues`, or while still loading if `None`.
+    pub fn new(config: SharedTuiSettings, queues: Option<&PlayQueues>) -> Self {
+        let names: Vec<String> = queues
+            .map(|v| v.queues.iter().map(|queue| queue.name.clone()).collect())
+            .unwrap_or_default();
+        let tracks: Vec<u64> = queues
+            .map(|v| v.queues.iter().map(|queue| queue.tracks).collect())
+            .unwrap_or_default();
+        let selected = queues
+            .and_then(|v| names.iter().position(|name| *name == v.active))
+            .unwrap_or_default();
+
+        let component = {
+            let config = config.read();
+            let keys = &config.settings.keys;
+            let title = if queues.is_some() {
+                format!(
+                    " Enter to switch queue, <{}> to create, <{}> to delete: ",
+                    keys.library_keys.add_root, keys.playlist_keys.delete
+                )
+            } else {
+                " Loading queues... ".to_string()
+            };
+
+            Table::default()
+                .background(config.settings.theme.library_background())
+                .foreground(config.settings.theme.library_foreground())
+                .borders(
+                    Borders::default()
+                        .color(config.settings.theme.library_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .title(title, Alignment::Left)
+                .scroll(true)
+                .highlighted_color(config.settings.theme.library_highlight())
+                .highlighted_str(&config.settings.theme.style.library.highlight_symbol)
+                .rewind(false)
+                .step(4)
+                .row_height(1)
+                .headers(["", " Name ", " Tracks "])
+                .column_spacing(1)
+                .widths(&[5, 75, 20])
+                .table(Self::build_table(&names, &tracks, selected))
+                .selected_line(selected)
+        };
+
+        Self {
+            component,
+            config,
+            names,
+        }
+    }
+
+    fn build_table(names: &[String], tracks: &[u64], active: usize) -> Vec<Vec<TextSpan>> {
+        let mut table = TableBuilder::default();
+
+        for (idx, (name, tracks)) in names.iter().zip(tracks).enumerate() {
+            if idx > 0 {
+                table.add_row();
+            }
+
+            let mark = if idx == active { "*" } else { "" };
+            table
+                .add_col(TextSpan::new(mark))
+                .add_col(TextSpan::new(name).bold())
+                .add_col(TextSpan::new(tracks.to_string()));
+        }
+
+        table.build()
+    }
+
+    /// Get the name of the currently selected queue.
+    fn selected_name(&self) -> Option<String> {
+        if let State::One(StateValue::Usize(index)) = self.state() {
+            return self.names.get(index).cloned();
+        }
+
+        None
+    }
+}
+
+impl Component<Msg, UserEvent> for QueueSelectPopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let config = self.config.clone();
+        let keys = &config.read().settings.keys;
+        let cmd_result = match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                return Some(Msg::Playlist(PLMsg::QueuesClose));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.quit.get() => {
+                return Some(Msg::Playlist(PLMsg::QueuesClose));
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => self.perform(Cmd::Move(Direction::Down)),
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.down.get() => {
+                self.perform(Cmd::Move(Direction::Down))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.up.get() => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.goto_top.get() => {
+                self.perform(Cmd::GoTo(Position::Begin))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.goto_bottom.get() => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.library_keys.add_root.get() => {
+                return Some(Msg::Playlist(PLMsg::QueueNewShow));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.playlist_keys.delete.get() => {
+                if let Some(name) = self.selected_name() {
+                    return Some(Msg::Playlist(PLMsg::QueueDelete(name)));
+                }
+                CmdResult::None
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => {
+                if let Some(name) = self.selected_name() {
+                    return Some(Msg::Playlist(PLMsg::QueueSwitch(name)));
+                }
+                CmdResult::None
+            }
+            _ => CmdResult::None,
+        };
+        match cmd_result {
+            CmdResult::None => None,
+            _ => Some(Msg::ForceRedraw),
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct QueueNewPopup {
+    component: Input,
+}
+
+impl QueueNewPopup {
+    pub fn new(config: &TuiOverlay) -> Self {
+        let config = &config.settings;
+        Self {
+            component: Input::default()
+                .foreground(config.theme.library_foreground())
+                .background(config.theme.library_background())
+                .borders(
+                    Borders::default()
+                        .color(config.theme.library_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .input_type(InputType::Text)
+                .title(
+                    " Name of the new queue: (Enter to confirm) ",
+                    Alignment::Left,
+                ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for QueueNewPopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let cmd_result = match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => self.perform(Cmd::Move(Direction::Left)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => self.perform(Cmd::Move(Direction::Right)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => self.perform(Cmd::GoTo(Position::Begin)),
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete, ..
+            }) => self.perform(Cmd::Cancel),
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                ..
+            }) => self.perform(Cmd::Delete),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch),
+                modifiers: KeyModifiers::SHIFT | KeyModifiers::NONE,
+            }) => self.perform(Cmd::Type(ch)),
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                return Some(Msg::Playlist(PLMsg::QueueNewCloseCancel));
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => match self.component.state() {
+                State::One(StateValue::String(input_string)) => {
+                    return Some(Msg::Playlist(PLMsg::QueueNewCloseOk(input_string)));
+                }
+                _ => CmdResult::None,
+            },
+            _ => CmdResult::None,
+        };
+        match cmd_result {
+            CmdResult::None => None,
+            _ => Some(Msg::ForceRedraw),
+        }
+    }
+}
+
+impl Model {
+    /// Mount the list of stored queues, `None` while they are still requested from the server.
+    pub fn mount_queues(&mut self, queues: Option<&PlayQueues>) {
+        assert!(
+            self.app
+                .remount(
+                    Id::QueuePopup,
+                    Box::new(QueueSelectPopup::new(self.config_tui.clone(), queues)),
+                    vec![]
+                )
+                .is_ok()
+        );
+        // keep the focus on the name input, if the list got refreshed while it is open
+        if !self.app.mounted(&Id::QueueNewPopup) {
+            assert!(self.app.active(&Id::QueuePopup).is_ok());
+        }
+    }
+
+    pub fn umount_queues(&mut self) {
+        self.umount_queue_new();
+        if self.app.mounted(&Id::QueuePopup) {
+            assert!(self.app.umount(&Id::QueuePopup).is_ok());
+        }
+    }
+
+    pub fn mount_queue_new(&mut self) {
+        assert!(
+            self.app
+                .remount(
+                    Id::QueueNewPopup,
+                    Box::new(QueueNewPopup::new(&self.config_tui.read())),
+                    vec![]
+                )
+                .is_ok()
+        );
+        assert!(self.app.active(&Id::QueueNewPopup).is_ok());
+    }
+
+    /// Unmount the name input for a new queue and return to the list of queues.
+    pub fn umount_queue_new(&mut self) {
+        if self.app.mounted(&Id::QueueNewPopup) {
+            assert!(self.app.umount(&Id::QueueNewPopup).is_ok());
+        }
+        if self.app.mounted(&Id::QueuePopup) {
+            assert!(self.app.active(&Id::QueuePopup).is_ok());
+        }
+    }
+}
@@ -22,40 +22,148 @@
  * SOFTWARE.
  */
 use termusiclib::config::SharedTuiSettings;
-use tuirealm::{Component, Event, MockComponent, props::Alignment};
+use tui_realm_stdlib::Radio;
+use tuirealm::{
+    AttrValue, Attribute, Component, Event, MockComponent, State, StateValue,
+    command::{Cmd, CmdResult, Direction},
+    event::{Key, KeyEvent},
+    props::{Alignment, BorderType, Borders, PropPayload, PropValue},
+};
 
-use super::{YNConfirm, YNConfirmStyle};
 use crate::ui::ids::Id;
 use crate::ui::model::{Model, UserEvent};
 use crate::ui::msg::{Msg, QuitPopupMsg};
 
+/// Ask whether to quit, and whether to keep the server running in the background.
+///
+/// The choice from the last quit is selected by default.
 #[derive(MockComponent)]
 pub struct QuitPopup {
-    component: YNConfirm,
+    component: Radio,
+    config: SharedTuiSettings,
+    /// The remembered choice, used for `y` and `Don't ask again`
+    stop_server: bool,
 }
 
 impl QuitPopup {
+    // ordering is 0 = No, 1 = Keep server running, 2 = Stop server, 3 = Don't ask again
+    const CHOICES: [&str; 4] = [
+        "No",
+        "Keep server running",
+        "Stop server",
+        "Don't ask again",
+    ];
+
     pub fn new(config: SharedTuiSettings) -> Self {
-        let component = YNConfirm::new_with_cb(config, " Are sure you want to quit? ", |config| {
-            YNConfirmStyle {
-                foreground_color: config.settings.theme.important_popup_foreground(),
-                background_color: config.settings.theme.important_popup_background(),
-                border_color: config.settings.theme.important_popup_border(),
-                title_alignment: Alignment::Center,
-            }
-        });
+        let (component, stop_server) = {
+            let config = config.read();
+            let stop_server = config.settings.behavior.quit_server_on_exit;
+            let component = Radio::default()
+                .foreground(config.settings.theme.important_popup_foreground())
+                .background(config.settings.theme.important_popup_background())
+                .borders(
+                    Borders::default()
+                        .color(config.settings.theme.important_popup_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .title(" Are sure you want to quit? ", Alignment::Center)
+                .rewind(true)
+                .choices(Self::CHOICES)
+                .value(Self::choice_index(stop_server));
+
+            (component, stop_server)
+        };
+
+        Self {
+            component,
+            config,
+            stop_server,
+        }
+    }
 
-        Self { component }
+    fn choice_index(stop_server: bool) -> usize {
+        if stop_server { 2 } else { 1 }
     }
 }
 
 impl Component<Msg, UserEvent> for QuitPopup {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
-        self.component.on(
-            ev,
-            Msg::QuitPopup(QuitPopupMsg::CloseOk),
-            Msg::QuitPopup(QuitPopupMsg::CloseCancel),
-        )
+        let config = self.config.clone();
+        let keys = &config.read().settings.keys;
+        let cmd_result = match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                return Some(Msg::QuitPopup(QuitPopupMsg::CloseCancel));
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => self.perform(Cmd::Move(Direction::Left)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => self.perform(Cmd::Move(Direction::Right)),
+
+            Event::Keyboard(key) if key == keys.navigation_keys.left.get() => {
+                self.perform(Cmd::Move(Direction::Left))
+            }
+            Event::Keyboard(key) if key == keys.navigation_keys.right.get() => {
+                self.perform(Cmd::Move(Direction::Right))
+            }
+            Event::Keyboard(key) if key == keys.navigation_keys.up.get() => {
+                self.perform(Cmd::Move(Direction::Left))
+            }
+            Event::Keyboard(key) if key == keys.navigation_keys.down.get() => {
+                self.perform(Cmd::Move(Direction::Right))
+            }
+            Event::Keyboard(key) if key == keys.quit.get() => {
+                return Some(Msg::QuitPopup(QuitPopupMsg::CloseCancel));
+            }
+            Event::Keyboard(key) if key == keys.escape.get() => {
+                return Some(Msg::QuitPopup(QuitPopupMsg::CloseCancel));
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('y'),
+                ..
+            }) => {
+                self.component.attr(
+                    Attribute::Value,
+                    AttrValue::Payload(PropPayload::One(PropValue::Usize(Self::choice_index(
+                        self.stop_server,
+                    )))),
+                );
+                self.perform(Cmd::Submit)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('n'),
+                ..
+            }) => {
+                self.component.attr(
+                    Attribute::Value,
+                    AttrValue::Payload(PropPayload::One(PropValue::Usize(0))),
+                );
+                self.perform(Cmd::Submit)
+            }
+
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => self.perform(Cmd::Submit),
+            _ => return None,
+        };
+
+        match cmd_result {
+            CmdResult::Submit(State::One(StateValue::Usize(0))) => {
+                Some(Msg::QuitPopup(QuitPopupMsg::CloseCancel))
+            }
+            CmdResult::Submit(State::One(StateValue::Usize(1))) => {
+                Some(Msg::QuitPopup(QuitPopupMsg::CloseOk { stop_server: false }))
+            }
+            CmdResult::Submit(State::One(StateValue::Usize(2))) => {
+                Some(Msg::QuitPopup(QuitPopupMsg::CloseOk { stop_server: true }))
+            }
+            CmdResult::Submit(State::One(StateValue::Usize(3))) => {
+                Some(Msg::QuitPopup(QuitPopupMsg::CloseOkDontAsk))
+            }
+            CmdResult::None => None,
+            _ => Some(Msg::ForceRedraw),
+        }
     }
 }
 
@@ -0,0 +1,106 @@
+use termusiclib::config::TuiOverlay;
+use tuirealm::{
+    Component, Event, MockComponent, State, StateValue,
+    command::{Cmd, CmdResult, Direction, Position},
+    event::{Key, KeyEvent, KeyModifiers},
+    props::{Alignment, BorderType, Borders, InputType},
+};
+
+use crate::ui::components::vendored::tui_realm_stdlib_input::Input;
+use crate::ui::ids::Id;
+use crate::ui::model::{Model, UserEvent};
+use crate::ui::msg::{Msg, RadioMsg};
+
+#[derive(MockComponent)]
+pub struct RadioSearchPopup {
+    component: Input,
+}
+
+impl RadioSearchPopup {
+    pub fn new(config: &TuiOverlay) -> Self {
+        let config = &config.settings;
+        Self {
+            component: Input::default()
+                .foreground(config.theme.library_foreground())
+                .background(config.theme.library_background())
+                .borders(
+                    Borders::default()
+                        .color(config.theme.library_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .input_type(InputType::Text)
+                .title(
+                    " Search stations by name, tag:<tag> or country:<name> : (Enter to confirm) ",
+                    Alignment::Left,
+                ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for RadioSearchPopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let cmd_result = match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => self.perform(Cmd::Move(Direction::Left)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => self.perform(Cmd::Move(Direction::Right)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => self.perform(Cmd::GoTo(Position::Begin)),
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete, ..
+            }) => self.perform(Cmd::Cancel),
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                ..
+            }) => self.perform(Cmd::Delete),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch),
+                modifiers: KeyModifiers::SHIFT | KeyModifiers::NONE,
+            }) => self.perform(Cmd::Type(ch)),
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                return Some(Msg::Radio(RadioMsg::SearchPopupCloseCancel));
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => match self.component.state() {
+                State::One(StateValue::String(input_string)) => {
+                    return Some(Msg::Radio(RadioMsg::SearchPopupCloseOk(input_string)));
+                }
+                _ => CmdResult::None,
+            },
+            _ => CmdResult::None,
+        };
+        match cmd_result {
+            CmdResult::None => None,
+            _ => Some(Msg::ForceRedraw),
+        }
+    }
+}
+
+impl Model {
+    pub fn mount_radio_search_popup(&mut self) {
+        assert!(
+            self.app
+                .remount(
+                    Id::RadioSearchPopup,
+                    Box::new(RadioSearchPopup::new(&self.config_tui.read())),
+                    vec![]
+                )
+                .is_ok()
+        );
+
+        assert!(self.app.active(&Id::RadioSearchPopup).is_ok());
+    }
+
+    pub fn umount_radio_search_popup(&mut self) {
+        if self.app.mounted(&Id::RadioSearchPopup) {
+            assert!(self.app.umount(&Id::RadioSearchPopup).is_ok());
+        }
+    }
+}
@@ -1,27 +1,31 @@
 use anyhow::Result;
 use termusiclib::config::{SharedTuiSettings, TuiOverlay};
+use termusiclib::playlist::PlaylistFormat;
+use tui_realm_stdlib::Table;
 use tuirealm::{
     Component, Event, MockComponent, State, StateValue,
     command::{Cmd, CmdResult, Direction, Position},
     event::{Key, KeyEvent, KeyModifiers},
-    props::{Alignment, BorderType, Borders, InputType},
+    props::{Alignment, BorderType, Borders, InputType, TableBuilder, TextSpan},
 };
 
 use super::{YNConfirm, YNConfirmStyle};
 use crate::ui::components::vendored::tui_realm_stdlib_input::Input;
 use crate::ui::ids::Id;
 use crate::ui::model::{Model, UserEvent};
-use crate::ui::msg::{Msg, SavePlaylistMsg};
+use crate::ui::msg::{ImportPlaylistMsg, Msg, SavePlaylistMsg};
 
 #[derive(MockComponent)]
 pub struct SavePlaylistPopup {
     component: Input,
+    format: PlaylistFormat,
 }
 
 impl SavePlaylistPopup {
-    pub fn new(config: &TuiOverlay) -> Self {
+    pub fn new(config: &TuiOverlay, format: PlaylistFormat) -> Self {
         let settings = &config.settings;
         Self {
+            format,
             component: Input::default()
                 .foreground(settings.theme.fallback_foreground())
                 .background(settings.theme.fallback_background())
@@ -32,7 +36,10 @@ impl SavePlaylistPopup {
                 )
                 // .invalid_style(Style::default().fg(Color::Red))
                 .input_type(InputType::Text)
-                .title(" Save Playlist as: (Enter to confirm) ", Alignment::Left),
+                .title(
+                    format!(" Save Playlist as {format}: (Enter to confirm) "),
+                    Alignment::Left,
+                ),
         }
     }
 }
@@ -78,6 +85,7 @@ impl Component<Msg, UserEvent> for SavePlaylistPopup {
                 State::One(StateValue::String(input_string)) => {
                     return Some(Msg::SavePlaylist(SavePlaylistMsg::PopupCloseOk(
                         input_string,
+                        self.format,
                     )));
                 }
                 _ => CmdResult::None,
@@ -86,7 +94,7 @@ impl Component<Msg, UserEvent> for SavePlaylistPopup {
         };
         match cmd_result {
             CmdResult::Submit(State::One(StateValue::String(input_string))) => Some(
-                Msg::SavePlaylist(SavePlaylistMsg::PopupUpdate(input_string)),
+                Msg::SavePlaylist(SavePlaylistMsg::PopupUpdate(input_string, self.format)),
             ),
             CmdResult::None => None,
             _ => Some(Msg::ForceRedraw),
@@ -98,10 +106,11 @@ impl Component<Msg, UserEvent> for SavePlaylistPopup {
 pub struct SavePlaylistConfirmPopup {
     component: YNConfirm,
     filename: String,
+    format: PlaylistFormat,
 }
 
 impl SavePlaylistConfirmPopup {
-    pub fn new(config: SharedTuiSettings, filename: &str) -> Self {
+    pub fn new(config: SharedTuiSettings, filename: &str, format: PlaylistFormat) -> Self {
         let component = YNConfirm::new_with_cb(config, " Playlist exists. Overwrite? ", |config| {
             YNConfirmStyle {
                 foreground_color: config.settings.theme.important_popup_foreground(),
@@ -114,6 +123,7 @@ impl SavePlaylistConfirmPopup {
         Self {
             component,
             filename: filename.to_string(),
+            format,
         }
     }
 }
@@ -122,25 +132,191 @@ impl Component<Msg, UserEvent> for SavePlaylistConfirmPopup {
     fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
         self.component.on(
             ev,
-            Msg::SavePlaylist(SavePlaylistMsg::ConfirmCloseOk(self.filename.clone())),
+            Msg::SavePlaylist(SavePlaylistMsg::ConfirmCloseOk(
+                self.filename.clone(),
+                self.format,
+            )),
             Msg::SavePlaylist(SavePlaylistMsg::ConfirmCloseCancel),
         )
     }
 }
 
+/// Choose the format to export the playlist in.
+#[derive(MockComponent)]
+pub struct PlaylistFormatPopup {
+    component: Table,
+    config: SharedTuiSettings,
+}
+
+impl PlaylistFormatPopup {
+    pub fn new(config: SharedTuiSettings) -> Self {
+        let component = {
+            let config = config.read();
+            let mut table = TableBuilder::default();
+            for (idx, format) in PlaylistFormat::ALL.into_iter().enumerate() {
+                if idx > 0 {
+                    table.add_row();
+                }
+
+                table
+                    .add_col(TextSpan::new(format.to_string()).bold())
+                    .add_col(TextSpan::new(format!(".{}", format.extension())));
+            }
+
+            Table::default()
+                .background(config.settings.theme.library_background())
+                .foreground(config.settings.theme.library_foreground())
+                .borders(
+                    Borders::default()
+                        .color(config.settings.theme.library_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .title(" Export Playlist as: (Enter to confirm) ", Alignment::Left)
+                .scroll(false)
+                .highlighted_color(config.settings.theme.library_highlight())
+                .highlighted_str(&config.settings.theme.style.library.highlight_symbol)
+                .rewind(true)
+                .row_height(1)
+                .headers([" Format ", " Extension "])
+                .column_spacing(3)
+                .widths(&[50, 50])
+                .table(table.build())
+        };
+
+        Self { component, config }
+    }
+}
+
+impl Component<Msg, UserEvent> for PlaylistFormatPopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let config = self.config.clone();
+        let keys = &config.read().settings.keys;
+        let cmd_result = match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                return Some(Msg::SavePlaylist(SavePlaylistMsg::FormatCloseCancel));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.quit.get() => {
+                return Some(Msg::SavePlaylist(SavePlaylistMsg::FormatCloseCancel));
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => self.perform(Cmd::Move(Direction::Down)),
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.down.get() => {
+                self.perform(Cmd::Move(Direction::Down))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.up.get() => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => {
+                if let State::One(StateValue::Usize(index)) = self.state() {
+                    if let Some(format) = PlaylistFormat::ALL.get(index) {
+                        return Some(Msg::SavePlaylist(SavePlaylistMsg::FormatCloseOk(*format)));
+                    }
+                }
+                CmdResult::None
+            }
+            _ => CmdResult::None,
+        };
+        match cmd_result {
+            CmdResult::None => None,
+            _ => Some(Msg::ForceRedraw),
+        }
+    }
+}
+
+/// Enter the path of a playlist file to import.
+#[derive(MockComponent)]
+pub struct ImportPlaylistPopup {
+    component: Input,
+}
+
+impl ImportPlaylistPopup {
+    pub fn new(config: &TuiOverlay) -> Self {
+        let settings = &config.settings;
+        Self {
+            component: Input::default()
+                .foreground(settings.theme.fallback_foreground())
+                .background(settings.theme.fallback_background())
+                .borders(
+                    Borders::default()
+                        .color(settings.theme.fallback_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .input_type(InputType::Text)
+                .title(
+                    " Import Playlist or url list (.txt) from: (relative to the library folder, Enter to confirm) ",
+                    Alignment::Left,
+                ),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ImportPlaylistPopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let cmd_result = match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => self.perform(Cmd::Move(Direction::Left)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => self.perform(Cmd::Move(Direction::Right)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => self.perform(Cmd::GoTo(Position::Begin)),
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete, ..
+            }) => self.perform(Cmd::Cancel),
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                ..
+            }) => self.perform(Cmd::Delete),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch),
+                modifiers: KeyModifiers::SHIFT | KeyModifiers::NONE,
+            }) => self.perform(Cmd::Type(ch)),
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                return Some(Msg::ImportPlaylist(ImportPlaylistMsg::PopupCloseCancel));
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => match self.component.state() {
+                State::One(StateValue::String(input_string)) => {
+                    return Some(Msg::ImportPlaylist(ImportPlaylistMsg::PopupCloseOk(
+                        input_string,
+                    )));
+                }
+                _ => CmdResult::None,
+            },
+            _ => CmdResult::None,
+        };
+        match cmd_result {
+            CmdResult::None => None,
+            _ => Some(Msg::ForceRedraw),
+        }
+    }
+}
+
 impl Model {
-    pub fn mount_save_playlist(&mut self) -> Result<()> {
+    pub fn mount_save_playlist(&mut self, format: PlaylistFormat) -> Result<()> {
         assert!(
             self.app
                 .remount(
                     Id::SavePlaylistPopup,
-                    Box::new(SavePlaylistPopup::new(&self.config_tui.read())),
+                    Box::new(SavePlaylistPopup::new(&self.config_tui.read(), format)),
                     vec![]
                 )
                 .is_ok()
         );
 
-        self.remount_save_playlist_label("")?;
+        self.remount_save_playlist_label("", format)?;
         assert!(self.app.active(&Id::SavePlaylistPopup).is_ok());
         Ok(())
     }
@@ -152,14 +328,15 @@ impl Model {
         }
     }
 
-    pub fn mount_save_playlist_confirm(&mut self, filename: &str) {
+    pub fn mount_save_playlist_confirm(&mut self, filename: &str, format: PlaylistFormat) {
         assert!(
             self.app
                 .remount(
                     Id::SavePlaylistConfirm,
                     Box::new(SavePlaylistConfirmPopup::new(
                         self.config_tui.clone(),
-                        filename
+                        filename,
+                        format
                     )),
                     vec![]
                 )
@@ -173,4 +350,42 @@ impl Model {
             assert!(self.app.umount(&Id::SavePlaylistConfirm).is_ok());
         }
     }
+
+    pub fn mount_playlist_format(&mut self) {
+        assert!(
+            self.app
+                .remount(
+                    Id::PlaylistFormatPopup,
+                    Box::new(PlaylistFormatPopup::new(self.config_tui.clone())),
+                    vec![]
+                )
+                .is_ok()
+        );
+        assert!(self.app.active(&Id::PlaylistFormatPopup).is_ok());
+    }
+
+    pub fn umount_playlist_format(&mut self) {
+        if self.app.mounted(&Id::PlaylistFormatPopup) {
+            assert!(self.app.umount(&Id::PlaylistFormatPopup).is_ok());
+        }
+    }
+
+    pub fn mount_import_playlist(&mut self) {
+        assert!(
+            self.app
+                .remount(
+                    Id::ImportPlaylistPopup,
+                    Box::new(ImportPlaylistPopup::new(&self.config_tui.read())),
+                    vec![]
+                )
+                .is_ok()
+        );
+        assert!(self.app.active(&Id::ImportPlaylistPopup).is_ok());
+    }
+
+    pub fn umount_import_playlist(&mut self) {
+        if self.app.mounted(&Id::ImportPlaylistPopup) {
+            assert!(self.app.umount(&Id::ImportPlaylistPopup).is_ok());
+        }
+    }
 }
@@ -0,0 +1,148 @@
+use std::path::Path;
+
+use anyhow::{Result, anyhow};
+use termusiclib::utils::{filetype_supported, is_playlist};
+
+use super::{InfoPopup, MenuItem, MenuPopup};
+use crate::ui::ids::Id;
+use crate::ui::model::Model;
+use crate::ui::msg::{Msg, TrackMenuAction, TrackMenuMsg, TrackMenuTarget};
+
+impl Model {
+    /// Get whether `target` is a track which exists as a local file.
+    pub fn track_menu_is_local_track(&self, target: &TrackMenuTarget) -> Result<bool> {
+        let res = match target {
+            TrackMenuTarget::Library(path) => {
+                path.is_file() && filetype_supported(path) && !is_playlist(path)
+            }
+            TrackMenuTarget::Playlist(index) => self
+                .playback
+                .playlist
+                .tracks()
+                .get(*index)
+                .ok_or_else(|| anyhow!("Track {index} not in playlist"))?
+                .path()
+                .is_some(),
+        };
+
+        Ok(res)
+    }
+
+    pub fn mount_track_menu(&mut self, target: TrackMenuTarget) -> Result<()> {
+        let local_track = self.track_menu_is_local_track(&target)?;
+        let library = matches!(target, TrackMenuTarget::Library(_));
+
+        let config = self.config_tui.read();
+        let keys = &config.settings.keys;
+        let items = TrackMenuAction::ALL
+            .into_iter()
+            .filter(|action| action.is_available(library, local_track))
+            .filter(|action| {
+                !config.guest
+                    || !Msg::TrackMenu(TrackMenuMsg::CloseOk(target.clone(), *action))
+                        .is_destructive()
+            })
+            .map(|action| {
+                let item = MenuItem::new(
+                    action.label(),
+                    Msg::TrackMenu(TrackMenuMsg::CloseOk(target.clone(), action)),
+                );
+                match action {
+                    TrackMenuAction::PlayNow if !library => {
+                        item.key(&keys.playlist_keys.play_selected)
+                    }
+                    TrackMenuAction::Add => item.key(&keys.library_keys.load_track),
+                    TrackMenuAction::Remove => item.key(&keys.playlist_keys.delete),
+                    TrackMenuAction::TagEditor if library => {
+                        item.key(&keys.library_keys.open_tag_editor)
+                    }
+                    TrackMenuAction::CycleRating if library => {
+                        item.key(&keys.library_keys.cycle_rating)
+                    }
+                    TrackMenuAction::CycleRating => item.key(&keys.playlist_keys.cycle_rating),
+                    TrackMenuAction::ToggleFavorite if library => {
+                        item.key(&keys.library_keys.toggle_favorite)
+                    }
+                    TrackMenuAction::ToggleFavorite => {
+                        item.key(&keys.playlist_keys.toggle_favorite)
+                    }
+                    _ => item,
+                }
+            })
+            .collect();
+        let close_key = if library {
+            keys.library_keys.track_menu.clone()
+        } else {
+            keys.playlist_keys.track_menu.clone()
+        };
+        drop(config);
+
+        self.app.remount(
+            Id::TrackMenuPopup,
+            Box::new(
+                MenuPopup::new(
+                    self.config_tui.clone(),
+                    items,
+                    Msg::TrackMenu(TrackMenuMsg::CloseCancel),
+                )
+                .close_key(close_key),
+            ),
+            vec![],
+        )?;
+        self.app.active(&Id::TrackMenuPopup)?;
+
+        Ok(())
+    }
+
+    pub fn umount_track_menu(&mut self) {
+        if self.app.mounted(&Id::TrackMenuPopup) {
+            assert!(self.app.umount(&Id::TrackMenuPopup).is_ok());
+        }
+    }
+
+    pub fn mount_track_info(&mut self, info: &[(&str, String)]) {
+        assert!(
+            self.app
+                .remount(
+                    Id::TrackInfoPopup,
+                    Box::new(InfoPopup::new(
+                        self.config_tui.clone(),
+                        "Track info",
+                        info,
+                        Msg::TrackMenu(TrackMenuMsg::InfoClose)
+                    )),
+                    vec![]
+                )
+                .is_ok()
+        );
+        assert!(self.app.active(&Id::TrackInfoPopup).is_ok());
+    }
+
+    pub fn umount_track_info(&mut self) {
+        if self.app.mounted(&Id::TrackInfoPopup) {
+            assert!(self.app.umount(&Id::TrackInfoPopup).is_ok());
+        }
+    }
+
+    /// Get the path, or the url if there is no local file, of `target`.
+    pub fn track_menu_location(&self, target: &TrackMenuTarget) -> Result<String> {
+        match target {
+            TrackMenuTarget::Library(path) => Ok(path.to_string_lossy().to_string()),
+            TrackMenuTarget::Playlist(index) => {
+                let track = self
+                    .playback
+                    .playlist
+                    .tracks()
+                    .get(*index)
+                    .ok_or_else(|| anyhow!("Track {index} not in playlist"))?;
+                let location = track
+                    .path()
+                    .map(Path::to_string_lossy)
+                    .or_else(|| track.url().map(Into::into))
+                    .unwrap_or_default();
+
+                Ok(location.to_string())
+            }
+        }
+    }
+}
@@ -1,22 +1,40 @@
+use std::borrow::Cow;
 use std::ops::Div;
 use std::time::Duration;
 
 use termusiclib::config::TuiOverlay;
-use termusiclib::player::RunningStatus;
-use termusiclib::track::DurationFmtShort;
+use termusiclib::config::v2::server::LoopMode;
+use termusiclib::new_database::ScanProgress;
+use termusiclib::player::{AbRepeat, RunningStatus, StreamInfo, TrackWaveform};
 use termusiclib::track::MediaTypesSimple;
-use tuirealm::props::{Alignment, BorderType, Borders, PropPayload, PropValue};
-use tuirealm::{AttrValue, Attribute, Component, Event, MockComponent};
+use termusiclib::track::{DurationFmtShort, Track};
+use termusiclib::waveform;
+use tui_realm_stdlib::Label;
+use tuirealm::command::{Cmd, CmdResult};
+use tuirealm::props::{
+    Alignment, BorderSides, BorderType, Borders, Color, PropPayload, PropValue, Style,
+    TextModifiers,
+};
+use tuirealm::ratatui::layout::Rect;
+use tuirealm::{AttrValue, Attribute, Component, Event, Frame, MockComponent, State};
 
 use crate::ui::Model;
 use crate::ui::components::vendored::tui_realm_stdlib_progressbar::ProgressBar;
 use crate::ui::ids::Id;
 use crate::ui::model::UserEvent;
 use crate::ui::msg::Msg;
+use crate::ui::tui_cmd::TuiCmd;
+
+/// The attribute of the [`Progress`] with the waveform levels of the current track, as a [`PropPayload::Vec`] of [`PropValue::U8`].
+const WAVEFORM_ATTR: &str = "waveform";
+
+/// Blocks from empty to a full cell, in eighths.
+const BARS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
 
-#[derive(MockComponent)]
 pub struct Progress {
     component: ProgressBar,
+    /// The waveform of the current track, shown instead of the plain progress bar if not empty
+    waveform: Vec<u8>,
 }
 
 impl Progress {
@@ -37,8 +55,116 @@ impl Progress {
                     Alignment::Center,
                 )
                 .progress(0.0),
+            waveform: Vec::new(),
+        }
+    }
+
+    /// Draw the waveform with the played part highlighted, plus the label on top of it.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn view_waveform(&self, render: &mut Frame<'_>, area: Rect) {
+        let foreground = self
+            .component
+            .query(Attribute::Foreground)
+            .map_or(Color::Reset, AttrValue::unwrap_color);
+        let background = self
+            .component
+            .query(Attribute::Background)
+            .map_or(Color::Reset, AttrValue::unwrap_color);
+        let borders = self
+            .component
+            .query(Attribute::Borders)
+            .map_or_else(Borders::default, AttrValue::unwrap_borders);
+        let title = self
+            .component
+            .query(Attribute::Title)
+            .map(AttrValue::unwrap_title);
+        let label = self
+            .component
+            .query(Attribute::Text)
+            .map(AttrValue::unwrap_string)
+            .unwrap_or_default();
+        let progress = self
+            .component
+            .query(Attribute::Value)
+            .map_or(0.0, |v| v.unwrap_payload().unwrap_one().unwrap_f64());
+
+        let block = tui_realm_stdlib::utils::get_block(borders, title.as_ref(), true, None);
+        let inner = block.inner(area);
+        render.render_widget(block, area);
+        if inner.is_empty() {
+            return;
+        }
+
+        let style = Style::default().fg(foreground).bg(background);
+        let levels = waveform::resample(&self.waveform, usize::from(inner.width));
+        // progress is always in range 0.0..=1.0
+        let played = (f64::from(inner.width) * progress) as u16;
+        let buf = render.buffer_mut();
+        for (x, level) in (inner.left()..inner.right()).zip(levels) {
+            let style = if x - inner.x < played {
+                style
+            } else {
+                style.add_modifier(TextModifiers::DIM)
+            };
+            for row in 0..inner.height {
+                buf[(x, inner.bottom() - 1 - row)]
+                    .set_char(waveform_cell(level, inner.height, row))
+                    .set_style(style);
+            }
+        }
+
+        let label_len = u16::try_from(label.chars().count()).unwrap_or(u16::MAX);
+        buf.set_stringn(
+            inner.x + inner.width.saturating_sub(label_len) / 2,
+            inner.y + inner.height / 2,
+            label,
+            usize::from(inner.width),
+            style.add_modifier(TextModifiers::BOLD),
+        );
+    }
+}
+
+/// Get the character of `row` (counted from the bottom) of a waveform bar for `level` that is `height` rows high.
+fn waveform_cell(level: u8, height: u16, row: u16) -> char {
+    let eighths = (u32::from(level) * u32::from(height) * 8 + 127) / 255;
+    let filled = eighths.saturating_sub(u32::from(row) * 8).min(8);
+
+    // always in range, as "filled" is at most 8
+    BARS[usize::try_from(filled).unwrap_or_default()]
+}
+
+impl MockComponent for Progress {
+    fn view(&mut self, render: &mut Frame<'_>, area: Rect) {
+        let display = self.component.query(Attribute::Display);
+        if self.waveform.is_empty() || display == Some(AttrValue::Flag(false)) {
+            self.component.view(render, area);
+        } else {
+            self.view_waveform(render, area);
         }
     }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.component.query(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        if attr == Attribute::Custom(WAVEFORM_ATTR) {
+            if let AttrValue::Payload(PropPayload::Vec(levels)) = value {
+                self.waveform = levels.into_iter().map(PropValue::unwrap_u8).collect();
+            }
+            return;
+        }
+
+        self.component.attr(attr, value);
+    }
+
+    fn state(&self) -> State {
+        self.component.state()
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        self.component.perform(cmd)
+    }
 }
 
 impl Component<Msg, UserEvent> for Progress {
@@ -47,29 +173,154 @@ impl Component<Msg, UserEvent> for Progress {
     }
 }
 
+/// A single line in the footer with the progress of a running library scan.
+#[derive(MockComponent)]
+pub struct LibraryScanProgress {
+    component: ProgressBar,
+}
+
+impl LibraryScanProgress {
+    #[allow(clippy::cast_precision_loss)] // track counts are far below the precision of f64
+    pub fn new(config: &TuiOverlay, progress: ScanProgress) -> Self {
+        let errors = if progress.errors > 0 {
+            format!(", {} errors", progress.errors)
+        } else {
+            String::new()
+        };
+        let label = format!(
+            "Scanning {} / {}{errors}",
+            progress.processed(),
+            progress.seen
+        );
+
+        Self {
+            component: ProgressBar::default()
+                .borders(Borders::default().sides(BorderSides::NONE))
+                .background(config.settings.theme.progress_background())
+                .foreground(config.settings.theme.progress_foreground())
+                .label(label)
+                .progress((progress.processed() as f64 / progress.seen.max(1) as f64).min(1.0)),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for LibraryScanProgress {
+    fn on(&mut self, _ev: Event<UserEvent>) -> Option<Msg> {
+        None
+    }
+}
+
+/// A single line below the [`Progress`] with the previous and next track of the playlist.
+///
+/// Only shown if enabled with `theme.style.progress.show_queue_preview`.
+#[derive(MockComponent)]
+pub struct QueuePreview {
+    component: Label,
+}
+
+impl QueuePreview {
+    pub fn new(config: &TuiOverlay) -> Self {
+        Self {
+            component: Label::default()
+                .alignment(Alignment::Center)
+                .background(config.settings.theme.progress_background())
+                .foreground(config.settings.theme.progress_foreground())
+                .text(""),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for QueuePreview {
+    fn on(&mut self, _ev: Event<UserEvent>) -> Option<Msg> {
+        None
+    }
+}
+
+/// Format `track` like `Artist - Title`, or `-` if there is no track.
+fn queue_preview_track(track: Option<&Track>) -> Cow<'_, str> {
+    let Some(track) = track else {
+        return "-".into();
+    };
+    let title: Cow<'_, str> = track.title().map_or_else(|| track.id_str(), Into::into);
+
+    match track.artist() {
+        Some(artist) => format!("{artist} - {title}").into(),
+        None => title,
+    }
+}
+
+/// Format the `previous` and `next` track like `prev: … | next: …`.
+fn queue_preview_format(
+    previous: Option<&Track>,
+    next: Option<&Track>,
+    loop_mode: LoopMode,
+) -> String {
+    let next = if loop_mode == LoopMode::Random {
+        "random".into()
+    } else {
+        queue_preview_track(next)
+    };
+
+    format!("prev: {} | next: {next}", queue_preview_track(previous))
+}
+
+/// Format the known parts of `info` like `mp3 44.1kHz 320kbps`, or `None` if nothing is known.
+fn stream_info_format(info: &StreamInfo) -> Option<String> {
+    let parts: Vec<String> = [
+        info.codec.clone(),
+        info.sample_rate
+            .map(|v| format!("{}kHz", f64::from(v) / 1000.0)),
+        info.bitrate.map(|v| format!("{}kbps", v / 1000)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    (!parts.is_empty()).then(|| parts.join(" "))
+}
+
+/// Format the points of `ab_repeat` like `A-B 01:30 - 01:45`, or `None` if none are set.
+fn ab_repeat_format(ab_repeat: &AbRepeat) -> Option<String> {
+    match (ab_repeat.a, ab_repeat.b) {
+        (Some(a), Some(b)) => Some(format!(
+            "A-B {} - {}",
+            DurationFmtShort(a),
+            DurationFmtShort(b)
+        )),
+        (Some(a), None) => Some(format!("A-B {} - ?", DurationFmtShort(a))),
+        _ => None,
+    }
+}
+
 #[allow(clippy::cast_precision_loss)] // speed is never realisitcally expected to be above i16::MAX
 fn title_format(
     status: RunningStatus,
     title: Option<&str>,
+    stream_info: &StreamInfo,
     volume: u16,
     speed: i32,
     gapless: bool,
 ) -> String {
     let gapless = if gapless { "True" } else { "False" };
+    let stream_info = stream_info_format(stream_info)
+        .map(|v| format!(" | {v}"))
+        .unwrap_or_default();
 
     if let Some(title) = title {
         format!(
-            " Status: {} {:^.20} | Volume: {} | Speed: {:^.1} | Gapless: {} ",
+            " Status: {} {:^.20}{} | Volume: {} | Speed: {:^.1} | Gapless: {} ",
             status,
             title,
+            stream_info,
             volume,
             speed as f32 / 10.0,
             gapless,
         )
     } else {
         format!(
-            " Status: {} | Volume: {} | Speed: {:^.1} | Gapless: {} ",
+            " Status: {}{} | Volume: {} | Speed: {:^.1} | Gapless: {} ",
             status,
+            stream_info,
             volume,
             speed as f32 / 10.0,
             gapless,
@@ -88,7 +339,40 @@ impl Model {
                 )
                 .is_ok()
         );
+        assert!(
+            self.app
+                .remount(
+                    Id::QueuePreview,
+                    Box::new(QueuePreview::new(&self.config_tui.read())),
+                    Vec::new()
+                )
+                .is_ok()
+        );
         self.progress_update_title();
+        self.queue_preview_update();
+        self.progress_update_waveform();
+    }
+
+    /// Update the [`QueuePreview`] component's text.
+    ///
+    /// This needs to be run if one of the following changes:
+    /// - the current track
+    /// - the tracks in the playlist
+    /// - the loop mode
+    pub fn queue_preview_update(&mut self) {
+        let playlist = &self.playback.playlist;
+        let text = if playlist.current_track().is_some() {
+            queue_preview_format(
+                playlist.previous_track(),
+                playlist.next_track(),
+                playlist.loop_mode(),
+            )
+        } else {
+            String::new()
+        };
+        self.app
+            .attr(&Id::QueuePreview, Attribute::Text, AttrValue::String(text))
+            .ok();
     }
 
     /// Update the [`Progress`] component's title.
@@ -103,11 +387,13 @@ impl Model {
         let config_server = self.config_server.read();
         let player = &config_server.settings.player;
 
+        let stream_info = self.playback.stream_info();
         let progress_title = if let Some(track) = self.playback.current_track() {
             match track.media_type() {
                 MediaTypesSimple::Music | MediaTypesSimple::LiveRadio => title_format(
                     self.playback.status(),
                     None,
+                    stream_info,
                     player.volume,
                     player.speed,
                     player.gapless,
@@ -115,6 +401,7 @@ impl Model {
                 MediaTypesSimple::Podcast => title_format(
                     self.playback.status(),
                     Some(track.title().unwrap_or("Unknown title")),
+                    stream_info,
                     player.volume,
                     player.speed,
                     player.gapless,
@@ -124,6 +411,7 @@ impl Model {
             title_format(
                 self.playback.status(),
                 None,
+                stream_info,
                 player.volume,
                 player.speed,
                 player.gapless,
@@ -141,6 +429,56 @@ impl Model {
         self.force_redraw();
     }
 
+    /// Clear the waveform of the [`Progress`] and request the one of the current track, if enabled.
+    ///
+    /// This needs to be run if the current track changes.
+    pub fn progress_update_waveform(&mut self) {
+        self.progress_set_waveform(Vec::new());
+
+        let enabled = self.waveform_enabled();
+        let has_waveform = self
+            .playback
+            .current_track()
+            .is_some_and(|track| track.media_type() != MediaTypesSimple::LiveRadio);
+        if enabled && has_waveform {
+            self.command(TuiCmd::GetWaveform);
+        }
+    }
+
+    /// Show `waveform` in the [`Progress`], if it is of the current track.
+    ///
+    /// Empty levels are ignored, as the server sends the waveform once it is generated.
+    pub fn progress_waveform_received(&mut self, waveform: TrackWaveform) {
+        let is_current = self
+            .playback
+            .current_track()
+            .is_some_and(|track| track.as_track_source() == waveform.trackid);
+        if is_current && !waveform.levels.is_empty() && self.waveform_enabled() {
+            self.progress_set_waveform(waveform.levels);
+        }
+    }
+
+    fn waveform_enabled(&self) -> bool {
+        self.config_tui
+            .read()
+            .settings
+            .theme
+            .style
+            .progress
+            .show_waveform
+    }
+
+    fn progress_set_waveform(&mut self, levels: Vec<u8>) {
+        let levels = levels.into_iter().map(PropValue::U8).collect();
+        self.app
+            .attr(
+                &Id::Progress,
+                Attribute::Custom(WAVEFORM_ATTR),
+                AttrValue::Payload(PropPayload::Vec(levels)),
+            )
+            .ok();
+    }
+
     /// Handle progress updates.
     ///
     /// Updates all places where progress updates need to be populated to.
@@ -172,7 +510,7 @@ impl Model {
             )
             .ok();
 
-        let text = if self.playback.is_stopped() {
+        let mut text = if self.playback.is_stopped() {
             DurationFmtShort::fmt_empty().to_string()
         } else if total_duration.is_zero() {
             format!("{}", DurationFmtShort(self.playback.current_track_pos()),)
@@ -183,9 +521,96 @@ impl Model {
                 DurationFmtShort(total_duration),
             )
         };
+        if let Some(ab_repeat) = ab_repeat_format(&self.playback.ab_repeat()) {
+            text = format!("{text}    [{ab_repeat}]");
+        }
 
         let _ = self
             .app
             .attr(&Id::Progress, Attribute::Text, AttrValue::String(text));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+    use termusiclib::config::v2::server::LoopMode;
+    use termusiclib::player::{AbRepeat, StreamInfo};
+    use termusiclib::track::Track;
+
+    use super::{ab_repeat_format, queue_preview_format, stream_info_format, waveform_cell};
+
+    #[test]
+    fn should_format_known_stream_info() {
+        assert_eq!(stream_info_format(&StreamInfo::default()), None);
+
+        let info = StreamInfo {
+            codec: Some("mp3".to_string()),
+            sample_rate: Some(44100),
+            bitrate: Some(320_000),
+        };
+        assert_eq!(
+            stream_info_format(&info).as_deref(),
+            Some("mp3 44.1kHz 320kbps")
+        );
+
+        let info = StreamInfo {
+            codec: None,
+            sample_rate: Some(48000),
+            bitrate: None,
+        };
+        assert_eq!(stream_info_format(&info).as_deref(), Some("48kHz"));
+    }
+
+    #[test]
+    fn should_format_ab_repeat() {
+        assert_eq!(ab_repeat_format(&AbRepeat::default()), None);
+
+        let mut ab_repeat = AbRepeat::default();
+        ab_repeat.set_a(Duration::from_secs(90));
+        assert_eq!(
+            ab_repeat_format(&ab_repeat).as_deref(),
+            Some("A-B 01:30 - ?")
+        );
+
+        ab_repeat.set_b(Duration::from_secs(105));
+        assert_eq!(
+            ab_repeat_format(&ab_repeat).as_deref(),
+            Some("A-B 01:30 - 01:45")
+        );
+    }
+
+    #[test]
+    fn should_draw_waveform_cells() {
+        // silence
+        assert_eq!(waveform_cell(0, 3, 0), ' ');
+        // loudest fills all rows
+        assert_eq!(waveform_cell(255, 3, 0), '█');
+        assert_eq!(waveform_cell(255, 3, 2), '█');
+        // half of 3 rows is 12 eighths
+        assert_eq!(waveform_cell(128, 3, 0), '█');
+        assert_eq!(waveform_cell(128, 3, 1), '▄');
+        assert_eq!(waveform_cell(128, 3, 2), ' ');
+    }
+
+    #[test]
+    fn should_format_queue_preview() {
+        let previous = Track::new_radio("http://example.com/previous");
+        let next = Track::new_radio("http://example.com/next");
+
+        assert_eq!(
+            queue_preview_format(Some(&previous), Some(&next), LoopMode::Playlist),
+            "prev: http://example.com/previous | next: http://example.com/next"
+        );
+        assert_eq!(
+            queue_preview_format(None, None, LoopMode::Playlist),
+            "prev: - | next: -"
+        );
+        assert_eq!(
+            queue_preview_format(Some(&previous), None, LoopMode::Random),
+            "prev: http://example.com/previous | next: random"
+        );
+    }
+}
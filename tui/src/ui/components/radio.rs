@@ -0,0 +1,336 @@
+use anyhow::{Result, bail};
+use termusiclib::config::SharedTuiSettings;
+use termusiclib::new_database::radio_ops;
+use termusiclib::player::playlist_helpers::{PlaylistAddTrack, PlaylistTrackSource};
+use termusiclib::radio_browser::{RadioStation, StationSearch, search_stations};
+use tokio::runtime::Handle;
+use tui_realm_stdlib::List;
+use tuirealm::command::{Cmd, CmdResult, Direction, Position};
+use tuirealm::props::{Alignment, BorderType, Borders, TableBuilder, TextSpan};
+use tuirealm::{
+    AttrValue, Attribute, Component, Event, MockComponent, State, StateValue,
+    event::{Key, KeyEvent, KeyModifiers},
+};
+
+use crate::ui::Model;
+use crate::ui::ids::Id;
+use crate::ui::model::UserEvent;
+use crate::ui::msg::{Msg, RadioList, RadioMsg};
+use crate::ui::tui_cmd::{PlaylistCmd, TuiCmd};
+
+/// A list of radio stations, used for both the favorites and the search results.
+#[derive(MockComponent)]
+pub struct RadioStationList {
+    component: List,
+    list: RadioList,
+    on_key_tab: Msg,
+    on_key_backtab: Msg,
+    config: SharedTuiSettings,
+}
+
+impl RadioStationList {
+    pub fn new(
+        config: SharedTuiSettings,
+        list: RadioList,
+        on_key_tab: Msg,
+        on_key_backtab: Msg,
+    ) -> Self {
+        let title = match list {
+            RadioList::Favorites => " Favorite Stations: ",
+            RadioList::SearchResults => " Search Results: ",
+        };
+        let component = {
+            let config = config.read();
+            List::default()
+                .borders(
+                    Borders::default()
+                        .modifiers(BorderType::Rounded)
+                        .color(config.settings.theme.library_border()),
+                )
+                .background(config.settings.theme.library_background())
+                .foreground(config.settings.theme.library_foreground())
+                .title(title, Alignment::Left)
+                .scroll(true)
+                .highlighted_color(config.settings.theme.library_highlight())
+                .highlighted_str(&config.settings.theme.style.library.highlight_symbol)
+                .rewind(false)
+                .step(4)
+                .scroll(true)
+                .rows(
+                    TableBuilder::default()
+                        .add_col(TextSpan::from("Empty"))
+                        .build(),
+                )
+        };
+
+        Self {
+            component,
+            list,
+            on_key_tab,
+            on_key_backtab,
+            config,
+        }
+    }
+
+    /// Move down, or blur to the next component if already at the last row.
+    fn move_down(&mut self) -> Option<CmdResult> {
+        if let Some(AttrValue::Table(t)) = self.query(Attribute::Content) {
+            if let State::One(StateValue::Usize(index)) = self.state() {
+                if index >= t.len() - 1 {
+                    return None;
+                }
+            }
+        }
+        Some(self.perform(Cmd::Move(Direction::Down)))
+    }
+}
+
+impl Component<Msg, UserEvent> for RadioStationList {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let config = self.config.clone();
+        let keys = &config.read().settings.keys;
+        let cmd_result = match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Down,
+                modifiers: KeyModifiers::NONE,
+            }) => match self.move_down() {
+                Some(res) => res,
+                None => return Some(self.on_key_tab.clone()),
+            },
+            Event::Keyboard(KeyEvent {
+                code: Key::Up,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::Move(Direction::Up)),
+            Event::Keyboard(key) if key == keys.navigation_keys.down.get() => {
+                match self.move_down() {
+                    Some(res) => res,
+                    None => return Some(self.on_key_tab.clone()),
+                }
+            }
+            Event::Keyboard(key) if key == keys.navigation_keys.up.get() => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::Scroll(Direction::Down)),
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::Scroll(Direction::Up)),
+            Event::Keyboard(key) if key == keys.navigation_keys.goto_top.get() => {
+                self.perform(Cmd::GoTo(Position::Begin))
+            }
+            Event::Keyboard(key) if key == keys.navigation_keys.goto_bottom.get() => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::GoTo(Position::Begin)),
+            Event::Keyboard(KeyEvent {
+                code: Key::End,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::GoTo(Position::End)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Tab,
+                modifiers: KeyModifiers::NONE,
+            }) => return Some(self.on_key_tab.clone()),
+            Event::Keyboard(KeyEvent {
+                code: Key::BackTab,
+                modifiers: KeyModifiers::SHIFT,
+            }) => return Some(self.on_key_backtab.clone()),
+
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter | Key::Right,
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                if let State::One(StateValue::Usize(index)) = self.state() {
+                    return Some(Msg::Radio(RadioMsg::PlaylistAdd(self.list, index)));
+                }
+                CmdResult::None
+            }
+            Event::Keyboard(key) if key == keys.navigation_keys.right.get() => {
+                if let State::One(StateValue::Usize(index)) = self.state() {
+                    return Some(Msg::Radio(RadioMsg::PlaylistAdd(self.list, index)));
+                }
+                CmdResult::None
+            }
+
+            Event::Keyboard(keyevent) if keyevent == keys.radio_keys.search.get() => {
+                return Some(Msg::Radio(RadioMsg::SearchPopupShow));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.radio_keys.toggle_favorite.get() => {
+                if let State::One(StateValue::Usize(index)) = self.state() {
+                    return Some(Msg::Radio(RadioMsg::ToggleFavorite(self.list, index)));
+                }
+                CmdResult::None
+            }
+            _ => CmdResult::None,
+        };
+        match cmd_result {
+            CmdResult::None => None,
+            _ => Some(Msg::ForceRedraw),
+        }
+    }
+}
+
+/// Get the country, codec and bitrate of `station` as a single string, skipping unknown values.
+fn station_details(station: &RadioStation) -> String {
+    let bitrate = (station.bitrate > 0).then(|| format!("{}kbps", station.bitrate));
+    let details: Vec<&str> = [
+        Some(station.country.as_str()),
+        Some(station.codec.as_str()),
+        bitrate.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|v| !v.is_empty())
+    .collect();
+
+    details.join(", ")
+}
+
+impl Model {
+    /// Start searching stations for `input` in the background, the result is sent as a [`RadioMsg`].
+    pub fn radio_search(&mut self, input: &str) {
+        let search = StationSearch::parse(input);
+        let tx = self.tx_to_main.clone();
+
+        // this will work for now as the tui loop is a async function, and this function is called on the same thread
+        Handle::current().spawn(async move {
+            let msg = match search_stations(&search).await {
+                Ok(stations) => RadioMsg::SearchSuccess(stations),
+                Err(err) => RadioMsg::SearchError(format!("Searching stations failed: {err:#}")),
+            };
+            tx.send(Msg::Radio(msg)).ok();
+        });
+
+        self.show_message_timeout_label_help("Searching stations...", None, None, None);
+    }
+
+    /// Reload the favorites from the database and display them.
+    pub fn radio_sync_favorites(&mut self) -> Result<()> {
+        self.radio.favorites = radio_ops::get_all_radio_favorites(&self.db.get_connection())?;
+
+        let mut table = TableBuilder::default();
+        for (idx, station) in self.radio.favorites.iter().enumerate() {
+            if idx > 0 {
+                table.add_row();
+            }
+            table.add_col(TextSpan::new(format!(
+                "{} ({})",
+                station.name,
+                station_details(station)
+            )));
+        }
+        if self.radio.favorites.is_empty() {
+            table.add_col(TextSpan::from("no favorite stations"));
+        }
+
+        self.app
+            .attr(
+                &Id::RadioFavorites,
+                Attribute::Content,
+                AttrValue::Table(table.build()),
+            )
+            .ok();
+
+        // the favorite markers in the search results may have changed
+        self.radio_sync_search_results();
+
+        Ok(())
+    }
+
+    /// Display the search results, with favorites in bold.
+    pub fn radio_sync_search_results(&mut self) {
+        let mut table = TableBuilder::default();
+        for (idx, station) in self.radio.search_results.iter().enumerate() {
+            if idx > 0 {
+                table.add_row();
+            }
+            let span = TextSpan::new(format!("{} ({})", station.name, station_details(station)));
+            if self.radio.is_favorite(&station.uuid) {
+                table.add_col(span.bold());
+            } else {
+                table.add_col(span);
+            }
+        }
+        if self.radio.search_results.is_empty() {
+            table.add_col(TextSpan::from("no search results"));
+        }
+
+        self.app
+            .attr(
+                &Id::RadioSearchResults,
+                Attribute::Content,
+                AttrValue::Table(table.build()),
+            )
+            .ok();
+    }
+
+    /// Add the station at `index` in `list` to the favorites, or remove it if it already is one.
+    pub fn radio_toggle_favorite(&mut self, list: RadioList, index: usize) -> Result<()> {
+        let Some(station) = self.radio.station(list, index).cloned() else {
+            return Ok(());
+        };
+
+        if self.radio.is_favorite(&station.uuid) {
+            radio_ops::delete_radio_favorite(&self.db.get_connection(), &station.uuid)?;
+            let msg = format!("Removed \"{}\" from favorites", station.name);
+            self.show_message_timeout_label_help(msg, None, None, None);
+        } else {
+            radio_ops::insert_radio_favorite(&self.db.get_connection(), &station)?;
+            let msg = format!("Added \"{}\" to favorites", station.name);
+            self.show_message_timeout_label_help(msg, None, None, None);
+        }
+
+        self.radio_sync_favorites()
+    }
+
+    /// Add the stream of the station at `index` in `list` to the playlist.
+    pub fn radio_add_playlist(&mut self, list: RadioList, index: usize) -> Result<()> {
+        let Some(station) = self.radio.station(list, index).cloned() else {
+            return Ok(());
+        };
+        if station.url.is_empty() {
+            bail!("Station \"{}\" has no stream url", station.name);
+        }
+
+        let source = PlaylistTrackSource::Url(station.url);
+        self.command(TuiCmd::Playlist(PlaylistCmd::AddTrack(
+            PlaylistAddTrack::new_single(
+                u64::try_from(self.playback.playlist.len()).unwrap(),
+                source,
+            ),
+        )));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use termusiclib::radio_browser::RadioStation;
+
+    use super::station_details;
+
+    #[test]
+    fn should_format_station_details() {
+        let mut station = RadioStation {
+            uuid: "uuid".to_string(),
+            name: "Some Radio".to_string(),
+            url: "http://example.com/radio.mp3".to_string(),
+            tags: String::new(),
+            country: "Germany".to_string(),
+            codec: "MP3".to_string(),
+            bitrate: 128,
+        };
+        assert_eq!(station_details(&station), "Germany, MP3, 128kbps");
+
+        station.country = String::new();
+        station.bitrate = 0;
+        assert_eq!(station_details(&station), "MP3");
+    }
+}
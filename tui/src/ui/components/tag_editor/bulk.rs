@@ -0,0 +1,613 @@
+//! Edit the tags of all marked tracks at once, instead of opening them one at a time.
+//!
+//! Tracks are marked with `keys.library.mark` in the library and database views, the tag editor key then opens
+//! [`BulkTagEditorPopup`] for all of them.
+
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use lofty::picture::{Picture, PictureType};
+use termusiclib::config::{SharedTuiSettings, TuiOverlay};
+use tui_realm_stdlib::Table;
+use tuirealm::{
+    Component, Event, MockComponent, State, StateValue,
+    command::{Cmd, CmdResult, Direction, Position},
+    event::{Key, KeyEvent, KeyModifiers},
+    props::{Alignment, BorderType, Borders, InputType, TableBuilder, TextSpan},
+};
+
+use super::TETrack;
+use crate::ui::components::vendored::tui_realm_stdlib_input::Input;
+use crate::ui::components::vendored::tui_realm_stdlib_progressbar::ProgressBar;
+use crate::ui::ids::{Id, IdTagEditor};
+use crate::ui::model::{Model, TxToMain, UserEvent};
+use crate::ui::msg::{Msg, TEMsg};
+
+/// A tag which can be set for all marked tracks at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BulkTagField {
+    Artist,
+    Album,
+    Genre,
+    /// Path to a image file, embedded as the front cover
+    Picture,
+}
+
+impl BulkTagField {
+    /// All fields, in the order they are shown in.
+    const ALL: [Self; 4] = [Self::Artist, Self::Album, Self::Genre, Self::Picture];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Artist => "Artist",
+            Self::Album => "Album",
+            Self::Genre => "Genre",
+            Self::Picture => "Album art (image file)",
+        }
+    }
+}
+
+/// The tags to set on all marked tracks, `None` keeps the tag of each track as it is.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BulkTagChanges {
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub genre: Option<String>,
+    pub picture: Option<PathBuf>,
+}
+
+impl BulkTagChanges {
+    /// Get the value to set for `field`, if it is changed.
+    #[must_use]
+    pub fn get(&self, field: BulkTagField) -> Option<String> {
+        match field {
+            BulkTagField::Artist => self.artist.clone(),
+            BulkTagField::Album => self.album.clone(),
+            BulkTagField::Genre => self.genre.clone(),
+            BulkTagField::Picture => self.picture.as_ref().map(|v| v.display().to_string()),
+        }
+    }
+
+    /// Set the value for `field`, where a empty `value` keeps the tag as it is.
+    pub fn set(&mut self, field: BulkTagField, value: &str) {
+        let value = value.trim();
+        let value = (!value.is_empty()).then(|| value.to_string());
+        match field {
+            BulkTagField::Artist => self.artist = value,
+            BulkTagField::Album => self.album = value,
+            BulkTagField::Genre => self.genre = value,
+            BulkTagField::Picture => self.picture = value.map(PathBuf::from),
+        }
+    }
+
+    /// Whether no tag is changed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Set the changed tags on `track`, with `picture` being the loaded [`picture`](Self::picture).
+    fn apply_to(&self, track: &mut TETrack, picture: Option<&Picture>) {
+        if let Some(artist) = &self.artist {
+            track.set_artist(artist);
+        }
+        if let Some(album) = &self.album {
+            track.set_album(album);
+        }
+        if let Some(genre) = &self.genre {
+            track.set_genre(genre);
+        }
+        if let Some(picture) = picture {
+            track.set_picture(picture.clone());
+        }
+    }
+}
+
+/// The marked tracks being edited and the tags to set on them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BulkTagEdit {
+    pub tracks: Vec<PathBuf>,
+    pub changes: BulkTagChanges,
+}
+
+/// Load the image at `path` as a front cover.
+fn load_picture(path: &Path) -> Result<Picture> {
+    let mut reader =
+        BufReader::new(File::open(path).with_context(|| format!("open {}", path.display()))?);
+    let mut picture = Picture::from_reader(&mut reader)
+        .with_context(|| format!("{} is not a supported image", path.display()))?;
+    picture.set_pic_type(PictureType::CoverFront);
+
+    Ok(picture)
+}
+
+/// Save `changes` to every track in `tracks`, sending the progress to `tx`.
+///
+/// Returns the tracks that failed, with their error.
+fn save_bulk_tags(
+    tracks: &[PathBuf],
+    changes: &BulkTagChanges,
+    picture: Option<&Picture>,
+    tx: &TxToMain,
+) -> Vec<(PathBuf, String)> {
+    let mut failed = Vec::new();
+
+    for (idx, path) in tracks.iter().enumerate() {
+        let res = TETrack::read_metadata_from_file(path.as_path()).and_then(|mut track| {
+            changes.apply_to(&mut track, picture);
+            track.save_tag()
+        });
+        if let Err(err) = res {
+            warn!("Saving the tags of {} failed: {err:#}", path.display());
+            failed.push((path.clone(), format!("{err:#}")));
+        }
+
+        let _ = tx.send(Msg::TagEditor(TEMsg::BulkProgress(idx + 1, tracks.len())));
+    }
+
+    failed
+}
+
+/// The fields to set on all marked tracks, with a last row to apply them.
+#[derive(MockComponent)]
+pub struct BulkTagEditorPopup {
+    component: Table,
+    config: SharedTuiSettings,
+}
+
+impl BulkTagEditorPopup {
+    pub fn new(config: SharedTuiSettings, edit: &BulkTagEdit) -> Self {
+        let component = {
+            let config = config.read();
+            Table::default()
+                .background(config.settings.theme.library_background())
+                .foreground(config.settings.theme.library_foreground())
+                .borders(
+                    Borders::default()
+                        .color(config.settings.theme.library_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .title(
+                    format!(
+                        " Edit the tags of {} marked tracks, Enter to change: ",
+                        edit.tracks.len()
+                    ),
+                    Alignment::Left,
+                )
+                .scroll(true)
+                .highlighted_color(config.settings.theme.library_highlight())
+                .highlighted_str(&config.settings.theme.style.library.highlight_symbol)
+                .rewind(true)
+                .row_height(1)
+                .column_spacing(3)
+                .widths(&[35, 65])
+                .table(Self::build_table(edit))
+        };
+
+        Self { component, config }
+    }
+
+    fn build_table(edit: &BulkTagEdit) -> Vec<Vec<TextSpan>> {
+        let mut table = TableBuilder::default();
+
+        for field in BulkTagField::ALL {
+            let value = edit
+                .changes
+                .get(field)
+                .unwrap_or_else(|| "(keep)".to_string());
+            table
+                .add_col(TextSpan::new(field.name()).bold())
+                .add_col(TextSpan::new(value))
+                .add_row();
+        }
+        table
+            .add_col(TextSpan::new(format!("Save to all {} tracks", edit.tracks.len())).bold())
+            .add_col(TextSpan::new(""));
+
+        table.build()
+    }
+}
+
+impl Component<Msg, UserEvent> for BulkTagEditorPopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let config = self.config.clone();
+        let keys = &config.read().settings.keys;
+        let cmd_result = match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                return Some(Msg::TagEditor(TEMsg::BulkClose));
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.quit.get() => {
+                return Some(Msg::TagEditor(TEMsg::BulkClose));
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => self.perform(Cmd::Move(Direction::Down)),
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.down.get() => {
+                self.perform(Cmd::Move(Direction::Down))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.up.get() => {
+                self.perform(Cmd::Move(Direction::Up))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.goto_top.get() => {
+                self.perform(Cmd::GoTo(Position::Begin))
+            }
+            Event::Keyboard(keyevent) if keyevent == keys.navigation_keys.goto_bottom.get() => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => {
+                if let State::One(StateValue::Usize(index)) = self.state() {
+                    return Some(Msg::TagEditor(
+                        BulkTagField::ALL
+                            .get(index)
+                            .map_or(TEMsg::BulkSave, |field| TEMsg::BulkFieldShow(*field)),
+                    ));
+                }
+                CmdResult::None
+            }
+            _ => CmdResult::None,
+        };
+        match cmd_result {
+            CmdResult::None => None,
+            _ => Some(Msg::ForceRedraw),
+        }
+    }
+}
+
+/// Input for the new value of a [`BulkTagField`].
+#[derive(MockComponent)]
+pub struct BulkTagFieldPopup {
+    component: Input,
+    field: BulkTagField,
+}
+
+impl BulkTagFieldPopup {
+    pub fn new(config: &TuiOverlay, field: BulkTagField, value: Option<String>) -> Self {
+        let config = &config.settings;
+        Self {
+            component: Input::default()
+                .foreground(config.theme.library_foreground())
+                .background(config.theme.library_background())
+                .borders(
+                    Borders::default()
+                        .color(config.theme.library_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .input_type(InputType::Text)
+                .value(value.unwrap_or_default())
+                .title(
+                    format!(" {}: (Enter to confirm, empty to keep) ", field.name()),
+                    Alignment::Left,
+                ),
+            field,
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for BulkTagFieldPopup {
+    fn on(&mut self, ev: Event<UserEvent>) -> Option<Msg> {
+        let cmd_result = match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => self.perform(Cmd::Move(Direction::Left)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => self.perform(Cmd::Move(Direction::Right)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => self.perform(Cmd::GoTo(Position::Begin)),
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete, ..
+            }) => self.perform(Cmd::Cancel),
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                ..
+            }) => self.perform(Cmd::Delete),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch),
+                modifiers: KeyModifiers::SHIFT | KeyModifiers::NONE,
+            }) => self.perform(Cmd::Type(ch)),
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                return Some(Msg::TagEditor(TEMsg::BulkFieldCloseCancel));
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => match self.component.state() {
+                State::One(StateValue::String(input_string)) => {
+                    return Some(Msg::TagEditor(TEMsg::BulkFieldCloseOk(
+                        self.field,
+                        input_string,
+                    )));
+                }
+                _ => CmdResult::None,
+            },
+            _ => CmdResult::None,
+        };
+        match cmd_result {
+            CmdResult::None => None,
+            _ => Some(Msg::ForceRedraw),
+        }
+    }
+}
+
+/// How many of the marked tracks are saved already.
+#[derive(MockComponent)]
+pub struct BulkTagProgressPopup {
+    component: ProgressBar,
+}
+
+impl BulkTagProgressPopup {
+    #[allow(clippy::cast_precision_loss)] // track counts are far below the precision of f64
+    pub fn new(config: &TuiOverlay, done: usize, total: usize) -> Self {
+        let config = &config.settings;
+        Self {
+            component: ProgressBar::default()
+                .borders(
+                    Borders::default()
+                        .color(config.theme.progress_border())
+                        .modifiers(BorderType::Rounded),
+                )
+                .background(config.theme.progress_background())
+                .foreground(config.theme.progress_foreground())
+                .title(" Saving tags ", Alignment::Center)
+                .label(format!("{done} / {total}"))
+                .progress(done as f64 / total.max(1) as f64),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for BulkTagProgressPopup {
+    fn on(&mut self, _ev: Event<UserEvent>) -> Option<Msg> {
+        None
+    }
+}
+
+impl Model {
+    /// Mark `path` to edit its tags together with the other marked tracks, or unmark it if it already is.
+    pub fn tageditor_toggle_mark(&mut self, path: PathBuf) {
+        if path.is_dir() {
+            self.mount_error_popup(anyhow::anyhow!(
+                "{} is a directory, only tracks can be marked",
+                path.display()
+            ));
+            return;
+        }
+
+        let name = path
+            .file_name()
+            .map(|v| v.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let text = if let Some(idx) = self.tageditor_marked.iter().position(|v| *v == path) {
+            self.tageditor_marked.remove(idx);
+            format!("Unmarked \"{name}\"")
+        } else {
+            self.tageditor_marked.push(path);
+            format!("Marked \"{name}\"")
+        };
+
+        let text = format!(
+            "{text}, {} tracks marked for the tag editor",
+            self.tageditor_marked.len()
+        );
+        self.show_toast("Tag editor", &text, Some(3));
+    }
+
+    /// Start editing the tags of all marked tracks.
+    pub fn mount_bulk_tageditor(&mut self) {
+        let edit = BulkTagEdit {
+            tracks: self.tageditor_marked.clone(),
+            changes: BulkTagChanges::default(),
+        };
+        self.remount_bulk_tageditor(edit);
+    }
+
+    fn remount_bulk_tageditor(&mut self, edit: BulkTagEdit) {
+        assert!(
+            self.app
+                .remount(
+                    Id::TagEditor(IdTagEditor::BulkEditor),
+                    Box::new(BulkTagEditorPopup::new(self.config_tui.clone(), &edit)),
+                    vec![]
+                )
+                .is_ok()
+        );
+        assert!(
+            self.app
+                .active(&Id::TagEditor(IdTagEditor::BulkEditor))
+                .is_ok()
+        );
+        self.tageditor_bulk = Some(edit);
+    }
+
+    pub fn umount_bulk_tageditor(&mut self) {
+        self.umount_bulk_tageditor_field();
+        if self.app.mounted(&Id::TagEditor(IdTagEditor::BulkEditor)) {
+            assert!(
+                self.app
+                    .umount(&Id::TagEditor(IdTagEditor::BulkEditor))
+                    .is_ok()
+            );
+        }
+        self.tageditor_bulk = None;
+    }
+
+    pub fn mount_bulk_tageditor_field(&mut self, field: BulkTagField) {
+        let value = self
+            .tageditor_bulk
+            .as_ref()
+            .and_then(|v| v.changes.get(field));
+        assert!(
+            self.app
+                .remount(
+                    Id::TagEditor(IdTagEditor::BulkField),
+                    Box::new(BulkTagFieldPopup::new(
+                        &self.config_tui.read(),
+                        field,
+                        value
+                    )),
+                    vec![]
+                )
+                .is_ok()
+        );
+        assert!(
+            self.app
+                .active(&Id::TagEditor(IdTagEditor::BulkField))
+                .is_ok()
+        );
+    }
+
+    pub fn umount_bulk_tageditor_field(&mut self) {
+        if self.app.mounted(&Id::TagEditor(IdTagEditor::BulkField)) {
+            assert!(
+                self.app
+                    .umount(&Id::TagEditor(IdTagEditor::BulkField))
+                    .is_ok()
+            );
+        }
+        if self.app.mounted(&Id::TagEditor(IdTagEditor::BulkEditor)) {
+            assert!(
+                self.app
+                    .active(&Id::TagEditor(IdTagEditor::BulkEditor))
+                    .is_ok()
+            );
+        }
+    }
+
+    /// Set `field` to `value` for all marked tracks, once saved.
+    pub fn bulk_tageditor_set_field(&mut self, field: BulkTagField, value: &str) {
+        self.umount_bulk_tageditor_field();
+        let Some(mut edit) = self.tageditor_bulk.take() else {
+            return;
+        };
+        edit.changes.set(field, value);
+        self.remount_bulk_tageditor(edit);
+    }
+
+    /// Save the changed tags to all marked tracks in the background, showing the progress.
+    pub fn bulk_tageditor_save(&mut self) {
+        let Some(edit) = self.tageditor_bulk.clone() else {
+            return;
+        };
+        if edit.changes.is_empty() {
+            self.mount_error_popup(anyhow::anyhow!(
+                "No tag is changed, select a field and press Enter to change it"
+            ));
+            return;
+        }
+        let picture = match edit
+            .changes
+            .picture
+            .as_deref()
+            .map(load_picture)
+            .transpose()
+        {
+            Ok(v) => v,
+            Err(err) => {
+                self.mount_error_popup(err.context("load album art"));
+                return;
+            }
+        };
+
+        self.umount_bulk_tageditor();
+        self.mount_bulk_tageditor_progress(0, edit.tracks.len());
+
+        let tx = self.tx_to_main.clone();
+        std::thread::Builder::new()
+            .name("tag editor bulk save".to_string())
+            .spawn(move || {
+                let failed = save_bulk_tags(&edit.tracks, &edit.changes, picture.as_ref(), &tx);
+                let _ = tx.send(Msg::TagEditor(TEMsg::BulkDone(edit.tracks.len(), failed)));
+            })
+            .expect("Failed to spawn thread");
+    }
+
+    pub fn mount_bulk_tageditor_progress(&mut self, done: usize, total: usize) {
+        assert!(
+            self.app
+                .remount(
+                    Id::TagEditor(IdTagEditor::BulkProgress),
+                    Box::new(BulkTagProgressPopup::new(
+                        &self.config_tui.read(),
+                        done,
+                        total
+                    )),
+                    vec![]
+                )
+                .is_ok()
+        );
+    }
+
+    /// Report the result of [`bulk_tageditor_save`](Self::bulk_tageditor_save), the marks are kept if any track failed.
+    pub fn bulk_tageditor_done(&mut self, total: usize, failed: &[(PathBuf, String)]) {
+        if self.app.mounted(&Id::TagEditor(IdTagEditor::BulkProgress)) {
+            assert!(
+                self.app
+                    .umount(&Id::TagEditor(IdTagEditor::BulkProgress))
+                    .is_ok()
+            );
+        }
+        self.library_reload_with_node_focus(None);
+
+        if failed.is_empty() {
+            self.tageditor_marked.clear();
+            self.show_toast(
+                "Tag editor",
+                &format!("Saved the tags of {total} tracks"),
+                Some(5),
+            );
+            return;
+        }
+
+        let mut text = format!(
+            "Saving the tags failed for {} of {total} tracks:\n",
+            failed.len()
+        );
+        for (path, err) in failed {
+            let _ = writeln!(text, "{}: {err}", path.display());
+        }
+        self.mount_error_popup(anyhow::anyhow!(text));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{BulkTagChanges, BulkTagField};
+
+    #[test]
+    fn should_keep_empty_fields() {
+        let mut changes = BulkTagChanges::default();
+        assert!(changes.is_empty());
+
+        changes.set(BulkTagField::Album, " Some Album ");
+        changes.set(BulkTagField::Picture, "/covers/front.jpg");
+        changes.set(BulkTagField::Genre, "  ");
+        assert_eq!(
+            changes,
+            BulkTagChanges {
+                artist: None,
+                album: Some("Some Album".to_string()),
+                genre: None,
+                picture: Some(PathBuf::from("/covers/front.jpg")),
+            }
+        );
+        assert_eq!(changes.get(BulkTagField::Artist), None);
+
+        changes.set(BulkTagField::Album, "");
+        changes.set(BulkTagField::Picture, "");
+        assert!(changes.is_empty());
+    }
+}
@@ -1,4 +1,5 @@
 /// -- modules
+mod bulk;
 mod te_counter_delete_lyric;
 mod te_footer;
 mod te_input;
@@ -10,6 +11,7 @@ mod update;
 mod view;
 
 // -- exports
+pub use bulk::{BulkTagEdit, BulkTagField};
 pub use te_counter_delete_lyric::TECounterDelete;
 pub use te_input::*;
 pub use te_select_lyric::TESelectLyric;
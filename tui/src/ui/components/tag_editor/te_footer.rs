@@ -18,6 +18,13 @@ impl TEFooter {
                     TextSpan::new(format!("<{}>", config.settings.keys.config_keys.save))
                         .bold()
                         .fg(config.settings.theme.library_highlight()),
+                    TextSpan::new(" Edit lyric: ").fg(config.settings.theme.library_foreground()),
+                    TextSpan::new(format!(
+                        "<{}>",
+                        config.settings.keys.config_keys.open_editor
+                    ))
+                    .bold()
+                    .fg(config.settings.theme.library_highlight()),
                     TextSpan::new(" Exit: ").fg(config.settings.theme.library_foreground()),
                     TextSpan::new(format!("<{}>", config.settings.keys.escape))
                         .bold()
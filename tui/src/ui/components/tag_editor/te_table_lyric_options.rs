@@ -1,6 +1,6 @@
 use anyhow::{Context, Result, anyhow};
 use termusiclib::config::SharedTuiSettings;
-use termusiclib::songtag::{SongTag, SongtagSearchResult, search};
+use termusiclib::songtag::{IdentifyFile, ServiceProvider, SongTag, SongtagSearchResult, search};
 use tokio::runtime::Handle;
 use tui_realm_stdlib::Table;
 use tuirealm::command::{Cmd, CmdResult, Direction, Position};
@@ -24,6 +24,7 @@ impl TETableLyricOptions {
     pub fn new(config: SharedTuiSettings) -> Self {
         let component = {
             let config = config.read();
+            let title = Self::title(&config.settings.lyrics.providers);
             Table::default()
                 .borders(
                     Borders::default()
@@ -32,7 +33,7 @@ impl TETableLyricOptions {
                 )
                 .foreground(config.settings.theme.library_foreground())
                 .background(config.settings.theme.library_background())
-                .title(" Search Results ", Alignment::Left)
+                .title(title, Alignment::Left)
                 .scroll(true)
                 .highlighted_color(config.settings.theme.library_highlight())
                 .highlighted_str("\u{1f680}")
@@ -54,6 +55,21 @@ impl TETableLyricOptions {
 
         Self { component, config }
     }
+
+    /// Get the title, showing the order the providers are searched in.
+    fn title(providers: &[ServiceProvider]) -> String {
+        if providers.is_empty() {
+            return " Search Results (no lyric providers enabled) ".to_string();
+        }
+
+        let order = providers
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(" > ");
+
+        format!(" Search Results ({order}) ")
+    }
 }
 
 impl Component<Msg, UserEvent> for TETableLyricOptions {
@@ -175,6 +191,8 @@ impl Model {
             let url = match record.url() {
                 Some(termusiclib::songtag::UrlTypes::Protected) => "Copyright Protected",
                 Some(_) => "Downloadable",
+                None if record.service_provider() == ServiceProvider::Lrclib => "Lyrics only",
+                None if record.service_provider() == ServiceProvider::MusicBrainz => "Tags only",
                 None => "No URL",
             };
 
@@ -240,9 +258,27 @@ impl Model {
 
         let songtag_tx = self.tx_to_main.clone();
         let tracker_handle = self.download_tracker.clone();
+        let (providers, acoustid_key) = {
+            let config = self.config_tui.read();
+            (
+                config.settings.lyrics.providers.clone(),
+                config.settings.lyrics.acoustid_key.clone(),
+            )
+        };
+        let file = self
+            .tageditor_song
+            .as_ref()
+            .map(|song| song.path().to_path_buf());
 
         handle.spawn(async move {
-            search(&search_str, move |msg| {
+            let identify = file
+                .as_deref()
+                .filter(|_| !acoustid_key.is_empty())
+                .map(|path| IdentifyFile {
+                    path,
+                    acoustid_key: &acoustid_key,
+                });
+            search(&search_str, &providers, identify, move |msg| {
                 let _ = songtag_tx.send(Msg::TagEditor(TEMsg::SearchLyricResult(msg)));
             })
             .await;
@@ -353,11 +389,14 @@ impl Model {
             let tracker_id = song_tag.id().to_string();
             let tracker = self.download_tracker.clone();
             let tx_to_main = self.tx_to_main.clone();
+            let providers = self.config_tui.read().settings.lyrics.providers.clone();
             tracker.increase_one(tracker_id.clone());
 
             let jh = tokio::task::spawn(async move {
-                let (lyric_string, artwork) =
-                    tokio::join!(song_tag.fetch_lyric(), song_tag.fetch_photo());
+                let (lyric_string, artwork) = tokio::join!(
+                    song_tag.fetch_lyric_with_fallback(&providers),
+                    song_tag.fetch_photo()
+                );
 
                 if let Ok(Some(lyric_string)) = lyric_string {
                     song.set_lyric(&lyric_string, lang_ext, None::<String>);
@@ -1,11 +1,15 @@
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
 use termusiclib::config::SharedTuiSettings;
+use termusiclib::songtag::lrc::Lyric;
 use tui_realm_stdlib::Textarea;
 use tuirealm::command::{Cmd, Direction, Position};
 use tuirealm::event::{Key, KeyEvent, KeyModifiers};
 use tuirealm::props::{Alignment, BorderType, Borders, TextSpan};
 use tuirealm::{Component, Event, MockComponent};
 
-use crate::ui::model::UserEvent;
+use crate::ui::model::{Model, UserEvent};
 use crate::ui::msg::{Msg, TEMsg, TFMsg};
 
 #[derive(MockComponent)]
@@ -43,6 +47,9 @@ impl Component<Msg, UserEvent> for TETextareaLyric {
             Event::Keyboard(keyevent) if keyevent == keys.config_keys.save.get() => {
                 return Some(Msg::TagEditor(TEMsg::Save));
             }
+            Event::Keyboard(keyevent) if keyevent == keys.config_keys.open_editor.get() => {
+                return Some(Msg::TagEditor(TEMsg::EditLyric));
+            }
             Event::Keyboard(KeyEvent { code: Key::Tab, .. }) => {
                 return Some(Msg::TagEditor(TEMsg::Focus(TFMsg::TextareaLyricBlurDown)));
             }
@@ -97,3 +104,45 @@ impl Component<Msg, UserEvent> for TETextareaLyric {
         Some(Msg::ForceRedraw)
     }
 }
+
+impl Model {
+    /// Edit the selected lyric of the current track in the user's editor, then parse and save it.
+    ///
+    /// If the track does not have any lyric yet, a new one is added.
+    pub fn te_edit_lyric_external(&mut self) -> Result<()> {
+        let Some(mut song) = self.tageditor_song.clone() else {
+            return Ok(());
+        };
+
+        let (text, lang) = song.lyric_selected().map_or_else(
+            || (String::new(), "eng".to_string()),
+            |v| (v.text.clone(), v.lang.clone()),
+        );
+
+        // a new file only accessible by the current user, removed when dropped
+        let tmp_file = tempfile::Builder::new()
+            .prefix("termusic-lyric-")
+            .suffix(".lrc")
+            .tempfile()
+            .context("create temporary lyric file")?;
+        let tmp_path = tmp_file.path();
+        std::fs::write(tmp_path, &text)
+            .with_context(|| format!("write \"{}\"", tmp_path.display()))?;
+
+        self.edit_in_editor(tmp_path)?;
+        let edited = std::fs::read_to_string(tmp_path)
+            .with_context(|| format!("read \"{}\"", tmp_path.display()))?;
+
+        if edited == text {
+            return Ok(());
+        }
+
+        song.set_lyric(edited.as_str(), lang, None::<String>);
+        song.set_parsed_lyrics(Lyric::from_str(&edited).ok());
+        song.save_tag()?;
+        // the unwrap should also never happen as all components should be properly mounted
+        self.init_by_song(song).unwrap();
+
+        Ok(())
+    }
+}
@@ -21,6 +21,9 @@ use termusiclib::{
 
 use crate::ui::model::ExtraLyricData;
 
+/// Prefix of the description of lyric frames created by [`TETrack::lyric_capture`].
+const SYNCED_PREFIX: &str = "Synced";
+
 /// Track data for the Tag-Editor with helper functions
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TETrack {
@@ -207,6 +210,126 @@ impl TETrack {
         }
     }
 
+    /// Insert a caption at `time_pos` into the selected lyric frame.
+    ///
+    /// If the selected frame is plain (unsynced) lyrics, a new frame to sync them into is created and selected,
+    /// keeping the plain frame. The text of the caption is the next line of the plain frame, if any.
+    ///
+    /// Returns the index of the new caption, see [`Lyric::insert_caption`].
+    pub fn lyric_capture(&mut self, time_pos: Duration) -> usize {
+        let is_synced = self
+            .lyric_parsed
+            .as_ref()
+            .is_some_and(|v| !v.captions.is_empty())
+            || self
+                .lyric_selected()
+                .is_some_and(|v| v.description.starts_with(SYNCED_PREFIX));
+        if !is_synced {
+            self.lyric_select_synced_frame();
+        }
+
+        let captions = self.lyric_parsed.as_ref().map_or(0, |v| v.captions.len());
+        let text = self
+            .lyric_plain_source()
+            .and_then(|source| Lyric::plain_lines(&source.text).into_iter().nth(captions))
+            .unwrap_or_default();
+
+        let index = self
+            .lyric_parsed
+            .get_or_insert_default()
+            .insert_caption(time_pos, &text);
+        self.lyric_write_parsed();
+
+        index
+    }
+
+    /// Set the text of the caption at `index` of the selected lyric frame.
+    pub fn lyric_set_caption_text(&mut self, index: usize, text: &str) -> Option<()> {
+        self.lyric_parsed
+            .as_mut()?
+            .captions
+            .get_mut(index)?
+            .set_text(text);
+        self.lyric_write_parsed();
+
+        Some(())
+    }
+
+    /// Remove the caption at `index` of the selected lyric frame.
+    pub fn lyric_remove_caption(&mut self, index: usize) -> Option<()> {
+        let lyric = self.lyric_parsed.as_mut()?;
+        if index >= lyric.captions.len() {
+            return None;
+        }
+        lyric.captions.remove(index);
+        self.lyric_write_parsed();
+
+        Some(())
+    }
+
+    /// Select the frame to sync the selected plain frame into, creating it if it does not exist yet.
+    fn lyric_select_synced_frame(&mut self) {
+        let (lang, description) = self.lyric_selected().map_or_else(
+            || ("eng".to_string(), SYNCED_PREFIX.to_string()),
+            |v| {
+                (
+                    v.lang.clone(),
+                    format!("{SYNCED_PREFIX} {}", v.description)
+                        .trim_end()
+                        .to_string(),
+                )
+            },
+        );
+
+        if let Some(idx) = self
+            .lyric_frames
+            .iter()
+            .position(|v| v.description == description)
+        {
+            self.lyric_selected_idx = idx;
+            self.lyric_parsed = Lyric::from_str(&self.lyric_frames[idx].text).ok();
+        } else {
+            self.lyric_frames.push(Lyrics {
+                text: String::new(),
+                lang,
+                description,
+            });
+            self.lyric_selected_idx = self.lyric_frames.len() - 1;
+            self.lyric_parsed = Some(Lyric::default());
+        }
+    }
+
+    /// Get the plain lyric frame the selected frame is synced from, see [`lyric_capture`](Self::lyric_capture).
+    fn lyric_plain_source(&self) -> Option<&Lyrics> {
+        let description = self
+            .lyric_selected()?
+            .description
+            .strip_prefix(SYNCED_PREFIX)?
+            .trim_start();
+
+        self.lyric_frames
+            .iter()
+            .enumerate()
+            .find(|(idx, v)| *idx != self.lyric_selected_idx && v.description == description)
+            .map(|(_, v)| v)
+    }
+
+    /// Write the parsed lyric back into the selected frame as LRC text.
+    fn lyric_write_parsed(&mut self) {
+        let Some(lyric) = &self.lyric_parsed else {
+            return;
+        };
+        if let Some(frame) = self.lyric_frames.get_mut(self.lyric_selected_idx) {
+            frame.text = lyric.as_lrc_text();
+        }
+    }
+
+    /// Get the parsed lyric of the selected frame.
+    #[must_use]
+    pub fn lyric_parsed(&self) -> Option<&Lyric> {
+        self.lyric_parsed.as_ref()
+    }
+
     /// Convert the current instance to only [`ExtraLyricData`].
     pub fn into_extra_lyric_data(self) -> ExtraLyricData {
         ExtraLyricData {
@@ -357,3 +480,73 @@ impl TETrack {
         Ok(res)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use id3::frame::Lyrics;
+    use lofty::file::FileType;
+    use pretty_assertions::assert_eq;
+
+    use super::TETrack;
+
+    fn track_with_lyrics(frames: Vec<Lyrics>) -> TETrack {
+        TETrack {
+            path: PathBuf::from("/music/track.mp3"),
+            artist: None,
+            title: None,
+            album: None,
+            genre: None,
+            picture: None,
+            lyric_selected_idx: 0,
+            lyric_frames: frames,
+            lyric_parsed: None,
+            file_type: FileType::Mpeg,
+        }
+    }
+
+    #[test]
+    fn should_sync_plain_lyrics() {
+        let mut track = track_with_lyrics(vec![Lyrics {
+            lang: "eng".to_string(),
+            description: "Lyrics".to_string(),
+            text: "First line\nSecond line\n".to_string(),
+        }]);
+
+        track.lyric_capture(Duration::from_secs(1));
+        track.lyric_capture(Duration::from_secs(3));
+        track.lyric_capture(Duration::from_secs(5));
+
+        let frames = track.lyric_frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].text, "First line\nSecond line\n");
+        assert_eq!(track.lyric_selected_index(), 1);
+        assert_eq!(frames[1].description, "Synced Lyrics");
+        assert_eq!(
+            frames[1].text,
+            "[00:01.00]First line\n[00:03.00]Second line\n[00:05.00]\n"
+        );
+
+        track.lyric_set_caption_text(2, "Third line").unwrap();
+        track.lyric_remove_caption(0).unwrap();
+        assert!(track.lyric_remove_caption(5).is_none());
+        assert_eq!(
+            track.lyric_frames()[1].text,
+            "[00:03.00]Second line\n[00:05.00]Third line\n"
+        );
+    }
+
+    #[test]
+    fn should_capture_without_lyrics() {
+        let mut track = track_with_lyrics(Vec::new());
+
+        track.lyric_capture(Duration::from_millis(1500));
+
+        let frames = track.lyric_frames();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].description, "Synced");
+        assert_eq!(frames[0].text, "[00:01.50]\n");
+    }
+}
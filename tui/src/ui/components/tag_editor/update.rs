@@ -9,7 +9,11 @@ impl Model {
     pub fn update_tageditor(&mut self, msg: TEMsg) {
         match msg {
             TEMsg::Open(node_id) => {
-                self.mount_tageditor(&node_id);
+                if self.tageditor_marked.is_empty() {
+                    self.mount_tageditor(&node_id);
+                } else {
+                    self.mount_bulk_tageditor();
+                }
             }
             TEMsg::Close => {
                 if let Some(s) = self.tageditor_song.clone() {
@@ -53,6 +57,11 @@ impl Model {
                     self.mount_error_popup(e.context("rename song by tag"));
                 }
             }
+            TEMsg::EditLyric => {
+                if let Err(e) = self.te_edit_lyric_external() {
+                    self.mount_error_popup(e.context("edit lyric in editor"));
+                }
+            }
             TEMsg::Focus(msg) => self.update_tag_editor_focus(msg),
 
             TEMsg::SearchLyricResult(msg) => self.te_update_lyric_results(msg),
@@ -60,6 +69,15 @@ impl Model {
             TEMsg::TrackDownloadPreError(err) => {
                 self.mount_error_popup(anyhow!(err));
             }
+
+            TEMsg::ToggleMark(path) => self.tageditor_toggle_mark(path),
+            TEMsg::BulkFieldShow(field) => self.mount_bulk_tageditor_field(field),
+            TEMsg::BulkFieldCloseCancel => self.umount_bulk_tageditor_field(),
+            TEMsg::BulkFieldCloseOk(field, value) => self.bulk_tageditor_set_field(field, &value),
+            TEMsg::BulkSave => self.bulk_tageditor_save(),
+            TEMsg::BulkClose => self.umount_bulk_tageditor(),
+            TEMsg::BulkProgress(done, total) => self.mount_bulk_tageditor_progress(done, total),
+            TEMsg::BulkDone(total, failed) => self.bulk_tageditor_done(total, &failed),
         }
     }
 
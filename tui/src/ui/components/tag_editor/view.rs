@@ -15,7 +15,6 @@ use crate::ui::components::{
 };
 use crate::ui::ids::{Id, IdTagEditor};
 use crate::ui::model::Model;
-use crate::ui::utils::{draw_area_in_absolute, draw_area_top_right_absolute};
 
 impl Model {
     #[allow(clippy::too_many_lines)]
@@ -111,16 +110,8 @@ impl Model {
                         chunks_row4_right[1],
                     );
 
-                    if self.app.mounted(&Id::MessagePopup) {
-                        let popup = draw_area_top_right_absolute(f.area(), 25, 4);
-                        f.render_widget(Clear, popup);
-                        self.app.view(&Id::MessagePopup, f, popup);
-                    }
-                    if self.app.mounted(&Id::ErrorPopup) {
-                        let popup = draw_area_in_absolute(f.area(), 50, 4);
-                        f.render_widget(Clear, popup);
-                        self.app.view(&Id::ErrorPopup, f, popup);
-                    }
+                    Self::view_toasts(f, &mut self.app);
+                    Self::view_error_popups(f, &mut self.app);
                 }
             })
             .expect("Expected to draw without error");
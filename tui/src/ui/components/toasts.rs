@@ -0,0 +1,193 @@
+//! The toast area, which shows short-lived notifications stacked in the top-right corner.
+//!
+//! Toasts never take focus and expire on their own, use the [error popup](super::popups::ErrorPopup) for messages which need to be acknowledged.
+
+use std::time::Duration;
+
+use tokio::runtime::Handle;
+use tokio::time::sleep;
+use tui_realm_stdlib::List;
+use tuirealm::{
+    Component, Event, MockComponent,
+    props::{Alignment, BorderType, Borders, Color, TableBuilder, TextSpan},
+};
+
+use crate::ui::ids::Id;
+use crate::ui::model::{Model, UserEvent};
+use crate::ui::msg::{Msg, NotificationMsg};
+
+/// A short-lived notification, see [`Toasts`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Toast {
+    pub id: u64,
+    pub title: String,
+    pub text: String,
+}
+
+/// The toasts currently shown, oldest first.
+#[derive(Debug, Default)]
+pub struct Toasts {
+    items: Vec<Toast>,
+    next_id: u64,
+}
+
+impl Toasts {
+    /// The most toasts shown at once, the oldest are dropped first.
+    const MAX: usize = 4;
+
+    /// Add a toast and get the id to [`remove`](Self::remove) it with.
+    ///
+    /// A toast with the same title and text is replaced, so that the same notification is only shown once.
+    pub fn push(&mut self, title: String, text: String) -> u64 {
+        self.items.retain(|v| v.title != title || v.text != text);
+        if self.items.len() >= Self::MAX {
+            self.items.drain(..=self.items.len() - Self::MAX);
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.items.push(Toast { id, title, text });
+
+        id
+    }
+
+    /// Remove the toast with `id`, returns `false` if it was already removed or replaced.
+    pub fn remove(&mut self, id: u64) -> bool {
+        let len = self.items.len();
+        self.items.retain(|v| v.id != id);
+
+        self.items.len() != len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Toast> {
+        self.items.iter()
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ToastArea {
+    component: List,
+}
+
+impl ToastArea {
+    pub fn new(toasts: &Toasts) -> Self {
+        let mut rows = TableBuilder::default();
+        for (idx, toast) in toasts.iter().enumerate() {
+            if idx > 0 {
+                rows.add_row();
+            }
+            rows.add_col(TextSpan::new(&toast.title).fg(Color::Cyan).bold())
+                .add_row()
+                .add_col(TextSpan::new(&toast.text).fg(Color::Green).bold());
+        }
+
+        Self {
+            component: List::default()
+                .borders(
+                    Borders::default()
+                        .color(Color::Cyan)
+                        .modifiers(BorderType::Rounded),
+                )
+                .title(" Notifications ", Alignment::Center)
+                .rows(rows.build()),
+        }
+    }
+}
+
+impl Component<Msg, UserEvent> for ToastArea {
+    fn on(&mut self, _ev: Event<UserEvent>) -> Option<Msg> {
+        None
+    }
+}
+
+impl Model {
+    /// Show a toast with `title` and `text`, and remove it again after `time_out` or 10 seconds.
+    ///
+    /// This function requires to run in a tokio context.
+    pub fn show_toast(&mut self, title: &str, text: &str, time_out: Option<u64>) {
+        let id = self.toasts.push(title.to_string(), text.to_string());
+        self.remount_toasts();
+
+        let tx = self.tx_to_main.clone();
+        let delay = time_out.unwrap_or(10);
+        Handle::current().spawn(async move {
+            sleep(Duration::from_secs(delay)).await;
+
+            let _ = tx.send(Msg::Notification(NotificationMsg::ToastExpire(id)));
+        });
+    }
+
+    /// Remove the toast with `id`, if it is still shown.
+    pub fn expire_toast(&mut self, id: u64) {
+        if self.toasts.remove(id) {
+            self.remount_toasts();
+        }
+    }
+
+    fn remount_toasts(&mut self) {
+        if self.toasts.is_empty() {
+            self.app.umount(&Id::Toasts).ok();
+            return;
+        }
+
+        // not made active, so that toasts never take the focus
+        assert!(
+            self.app
+                .remount(Id::Toasts, Box::new(ToastArea::new(&self.toasts)), vec![])
+                .is_ok()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::Toasts;
+
+    fn titles(toasts: &Toasts) -> Vec<&str> {
+        toasts.iter().map(|v| v.title.as_str()).collect()
+    }
+
+    #[test]
+    fn should_stack_and_expire() {
+        let mut toasts = Toasts::default();
+        let first = toasts.push("first".to_string(), "text".to_string());
+        let second = toasts.push("second".to_string(), "text".to_string());
+        assert_eq!(titles(&toasts), ["first", "second"]);
+
+        assert!(toasts.remove(first));
+        assert!(!toasts.remove(first));
+        assert_eq!(titles(&toasts), ["second"]);
+
+        assert!(toasts.remove(second));
+        assert!(toasts.is_empty());
+    }
+
+    #[test]
+    fn should_replace_same_toast() {
+        let mut toasts = Toasts::default();
+        let old = toasts.push("title".to_string(), "text".to_string());
+        toasts.push("other".to_string(), "text".to_string());
+        let new = toasts.push("title".to_string(), "text".to_string());
+        assert_eq!(titles(&toasts), ["other", "title"]);
+
+        // the expiry of the replaced toast does not remove the new one
+        assert!(!toasts.remove(old));
+        assert!(toasts.remove(new));
+    }
+
+    #[test]
+    fn should_drop_oldest_above_max() {
+        let mut toasts = Toasts::default();
+        for idx in 0..=Toasts::MAX {
+            toasts.push(idx.to_string(), "text".to_string());
+        }
+
+        assert_eq!(titles(&toasts), ["1", "2", "3", "4"]);
+    }
+}
@@ -0,0 +1,233 @@
+//! Headless rendering tests for the major views.
+//!
+//! Each view is rendered with fixture data into a [`TestBackend`] and the resulting buffer is
+//! compared line-by-line against a textual snapshot. Only the cell symbols are compared, so theme
+//! color changes do not invalidate the snapshots, but layout changes do.
+
+use std::path::PathBuf;
+
+use pretty_assertions::assert_eq;
+use termusiclib::config::{
+    ServerOverlay, SharedTuiSettings, TuiOverlay, new_shared_server_settings,
+    new_shared_tui_settings,
+};
+use tui_realm_treeview::{Node, Tree};
+use tuirealm::props::{TableBuilder, TextSpan};
+use tuirealm::ratatui::Terminal;
+use tuirealm::ratatui::backend::TestBackend;
+use tuirealm::ratatui::buffer::Buffer;
+use tuirealm::{AttrValue, Attribute, MockComponent};
+
+use super::config_editor::{ExitConfirmation, MusicDir};
+use super::{EpisodeList, FeedsList, MusicLibrary, Playlist};
+use crate::CombinedSettings;
+use crate::ui::msg::{Msg, PCMsg};
+
+/// Create the TUI settings used by all snapshots.
+///
+/// The highlight symbols are replaced with single-width characters, as the defaults are emoji whose
+/// width depends on the unicode tables in use.
+fn fixture_config() -> SharedTuiSettings {
+    let mut overlay = TuiOverlay::default();
+    overlay.settings.theme.style.library.highlight_symbol = ">".into();
+    overlay.settings.theme.style.playlist.highlight_symbol = ">".into();
+
+    new_shared_tui_settings(overlay)
+}
+
+/// Create the combined settings used by config editor snapshots.
+fn fixture_combined_config() -> CombinedSettings {
+    let mut server = ServerOverlay::default();
+    server.settings.player.music_dirs =
+        vec![PathBuf::from("/music/one"), PathBuf::from("/music/two")];
+
+    CombinedSettings {
+        server: new_shared_server_settings(server),
+        tui: fixture_config(),
+    }
+}
+
+/// Render `component` into a `width`x`height` [`TestBackend`] and return the buffer as lines.
+fn render_lines(component: &mut dyn MockComponent, width: u16, height: u16) -> Vec<String> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal
+        .draw(|frame| component.view(frame, frame.area()))
+        .unwrap();
+
+    buffer_lines(terminal.backend().buffer())
+}
+
+/// Convert the symbols of `buffer` into one [`String`] per row.
+fn buffer_lines(buffer: &Buffer) -> Vec<String> {
+    (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn should_render_playlist() {
+    let mut playlist = Playlist::new(fixture_config());
+    playlist.attr(
+        Attribute::Content,
+        AttrValue::Table(
+            TableBuilder::default()
+                .add_col(TextSpan::new("[ 03:25 ]"))
+                .add_col(TextSpan::new("Artist A"))
+                .add_col(TextSpan::new("First Song").bold())
+                .add_col(TextSpan::new("Album A"))
+                .add_row()
+                .add_col(TextSpan::new("[ 04:10 ]"))
+                .add_col(TextSpan::new("Artist B"))
+                .add_col(TextSpan::new("Second Song").bold())
+                .add_col(TextSpan::new("Album B"))
+                .build(),
+        ),
+    );
+
+    assert_eq!(
+        render_lines(&mut playlist, 80, 6),
+        [
+            "╭ Playlist ────────────────────────────────────────────────────────────────────╮",
+            "│ Duration   Artist            Title                Album                      │",
+            "│>[ 03:25 ]  Artist A          First Song           Album A                    │",
+            "│ [ 04:10 ]  Artist B          Second Song          Album B                    │",
+            "│                                                                              │",
+            "╰──────────────────────────────────────────────────────────────────────────────╯",
+        ]
+    );
+}
+
+#[test]
+fn should_render_library() {
+    let mut root = Node::new("/music".to_string(), "music".to_string());
+    root.add_child(Node::new(
+        "/music/Album A".to_string(),
+        "Album A".to_string(),
+    ));
+    root.add_child(Node::new(
+        "/music/Album B".to_string(),
+        "Album B".to_string(),
+    ));
+    let mut library = MusicLibrary::new(&Tree::new(root), None, fixture_config());
+
+    let lines = render_lines(&mut library, 30, 6);
+
+    // the indent and open / close glyphs are owned by "tui-realm-treeview",
+    // so only the frame and the order of the nodes are pinned here
+    assert_eq!(lines[0], "╭ Library ───────────────────╮");
+    assert_eq!(lines[5], "╰────────────────────────────╯");
+    assert!(lines[1].contains("music"), "{lines:#?}");
+    assert!(lines[2].contains("Album A"), "{lines:#?}");
+    assert!(lines[3].contains("Album B"), "{lines:#?}");
+    assert_eq!(lines[4], "│                            │");
+}
+
+#[test]
+fn should_render_podcast_feeds() {
+    let mut feeds = FeedsList::new(
+        fixture_config(),
+        Msg::Podcast(PCMsg::PodcastBlurDown),
+        Msg::Podcast(PCMsg::PodcastBlurUp),
+    );
+    feeds.attr(
+        Attribute::Content,
+        AttrValue::Table(
+            TableBuilder::default()
+                .add_col(TextSpan::new("Rust Weekly"))
+                .add_row()
+                .add_col(TextSpan::new("Linux Unplugged"))
+                .build(),
+        ),
+    );
+
+    assert_eq!(
+        render_lines(&mut feeds, 30, 5),
+        [
+            "╭ Podcast Feeds: ────────────╮",
+            "│>Rust Weekly                │",
+            "│ Linux Unplugged            │",
+            "│                            │",
+            "╰────────────────────────────╯",
+        ]
+    );
+}
+
+#[test]
+fn should_render_podcast_episodes() {
+    let mut episodes = EpisodeList::new(
+        fixture_config(),
+        Msg::Podcast(PCMsg::EpisodeBlurDown),
+        Msg::Podcast(PCMsg::EpisodeBlurUp),
+    );
+    episodes.attr(
+        Attribute::Content,
+        AttrValue::Table(
+            TableBuilder::default()
+                .add_col(TextSpan::new("NEW").bold())
+                .add_col(TextSpan::new("[D] Episode Two").bold())
+                .add_col(TextSpan::new("[01:02:03]"))
+                .add_col(TextSpan::new("2024-01-15"))
+                .add_row()
+                .add_col(TextSpan::new("   "))
+                .add_col(TextSpan::new("Episode One").strikethrough())
+                .add_col(TextSpan::new("[--:--:--]"))
+                .add_col(TextSpan::new("2024-01-08"))
+                .build(),
+        ),
+    );
+
+    assert_eq!(
+        render_lines(&mut episodes, 70, 5),
+        [
+            "╭ Episodes: ─────────────────────────────────────────────────────────╮",
+            "│>NEW [D] Episode Two                           [01:02:03] 2024-01-15│",
+            "│     Episode One                               [--:--:--] 2024-01-08│",
+            "│                                                                    │",
+            "╰────────────────────────────────────────────────────────────────────╯",
+        ]
+    );
+
+    // too narrow for the pubdate, the title takes its space
+    assert_eq!(
+        render_lines(&mut episodes, 50, 5),
+        [
+            "╭ Episodes: ─────────────────────────────────────╮",
+            "│>NEW [D] Episode Two                  [01:02:03]│",
+            "│     Episode One                      [--:--:--]│",
+            "│                                                │",
+            "╰────────────────────────────────────────────────╯",
+        ]
+    );
+}
+
+#[test]
+fn should_render_config_editor_music_dir() {
+    let mut music_dir = MusicDir::new(fixture_combined_config());
+
+    assert_eq!(
+        render_lines(&mut music_dir, 50, 3),
+        [
+            "╭ Root Music Directory:(use ; to separate) ──────╮",
+            "│/music/one;/music/two                           │",
+            "╰────────────────────────────────────────────────╯",
+        ]
+    );
+}
+
+#[test]
+fn should_render_config_editor_radio() {
+    let mut exit_confirmation = ExitConfirmation::new(fixture_config());
+
+    assert_eq!(
+        render_lines(&mut exit_confirmation, 30, 3),
+        [
+            "╭ Show exit confirmation? ───╮",
+            "│ Yes │ No                   │",
+            "╰────────────────────────────╯",
+        ]
+    );
+}
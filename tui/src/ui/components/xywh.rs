@@ -1,5 +1,7 @@
 //! SPDX-License-Identifier: MIT
 
+use std::path::Path;
+
 #[cfg(any(
     feature = "cover-viuer-iterm",
     feature = "cover-viuer-kitty",
@@ -7,19 +9,15 @@
 ))]
 use std::io::Write;
 
-#[cfg(any(
-    feature = "cover-viuer-iterm",
-    feature = "cover-viuer-kitty",
-    feature = "cover-viuer-sixel"
-))]
-use anyhow::Context;
-use anyhow::Result;
-use bytes::Buf;
+use anyhow::{Context, Result};
 use image::DynamicImage;
 use lofty::picture::Picture;
-use termusiclib::track::MediaTypes;
+use termusiclib::artwork_cache::ArtworkCache;
+use termusiclib::config::v2::tui::config_extra::TuiConfigVersionedDefaulted;
+use termusiclib::track::{MediaTypes, Track};
 use tokio::runtime::Handle;
 
+use crate::instance_lock;
 use crate::ui::ids::{Id, IdConfigEditor, IdTagEditor};
 use crate::ui::model::{Model, TxToMain, ViuerSupported};
 use crate::ui::msg::{CoverDLResult, ImageWrapper, Msg, XYWHMsg};
@@ -46,10 +44,12 @@ impl Model {
     }
     pub fn xywh_zoom_in(&mut self) {
         self.xywh.zoom_in();
+        self.xywh_save_size();
         self.update_photo().ok();
     }
     pub fn xywh_zoom_out(&mut self) {
         self.xywh.zoom_out();
+        self.xywh_save_size();
         self.update_photo().ok();
     }
     pub fn xywh_toggle_hide(&mut self) {
@@ -57,16 +57,40 @@ impl Model {
         let mut config_tui = self.config_tui.write();
 
         // dont save value if cli has overwritten it, but still allow runtime changing
-        if let Some(current) = config_tui.coverart_hidden_overwrite {
+        let save = if let Some(current) = config_tui.coverart_hidden_overwrite {
             config_tui.coverart_hidden_overwrite = Some(!current);
             info!("Not saving coverart.hidden as it is overwritten by cli!");
+            false
         } else {
             config_tui.settings.coverart.hidden = !config_tui.settings.coverart.hidden;
-        }
+            true
+        };
 
         drop(config_tui);
+        if save {
+            self.xywh_save_config();
+        }
         self.update_photo().ok();
     }
+
+    /// Keep the current cover size for the next start.
+    fn xywh_save_size(&mut self) {
+        self.config_tui.write().settings.coverart.size_scale = self.xywh.size_scale();
+        self.xywh_save_config();
+    }
+
+    /// Save the changed cover settings to the config.
+    fn xywh_save_config(&mut self) {
+        let res = instance_lock::ensure_held()
+            .and_then(|()| {
+                TuiConfigVersionedDefaulted::save_config_path(&self.config_tui.read().settings)
+            })
+            .context("save tui settings");
+
+        if let Err(err) = res {
+            self.mount_error_popup(err);
+        }
+    }
     fn should_not_show_photo(&self) -> bool {
         if self.app.mounted(&Id::HelpPopup) {
             return true;
@@ -87,18 +111,19 @@ impl Model {
             return true;
         }
 
-        if self.playback.is_stopped() {
+        if self.app.mounted(&Id::ConfigEditor(IdConfigEditor::Header)) {
             return true;
         }
 
-        if self.app.mounted(&Id::ConfigEditor(IdConfigEditor::Header)) {
+        // there is no room for it
+        if self.is_compact_layout() {
             return true;
         }
 
         false
     }
 
-    /// Get and show a image for the current playing media
+    /// Get and show a image for the current playing media, or for the album selected in the database view
     ///
     /// Requires that the current thread has a entered runtime
     #[allow(clippy::cast_possible_truncation)]
@@ -111,6 +136,13 @@ impl Model {
         if self.should_not_show_photo() {
             return Ok(());
         }
+        // shown even if nothing is playing
+        if let Some(path) = self.database_album_cover().map(Path::to_path_buf) {
+            return self.show_album_cover(&path);
+        }
+        if self.playback.is_stopped() {
+            return Ok(());
+        }
         let Some(track) = self.playback.current_track() else {
             return Ok(());
         };
@@ -161,69 +193,67 @@ impl Model {
         Ok(())
     }
 
-    /// Fetch the given url as a image and send events when done or error.
-    async fn fetch_podcast_image(tx: TxToMain, url: String) {
-        match reqwest::get(&url).await {
-            Ok(result) => {
-                if result.status() != reqwest::StatusCode::OK {
-                    tx.send(Msg::Xywh(XYWHMsg::CoverDLResult(
-                        CoverDLResult::FetchPhotoErr(format!(
-                            "Error non-OK Status code: {}",
-                            result.status()
-                        )),
-                    )))
-                    .ok();
-                    return;
-                }
+    /// Show the cover of the library track at `path`, from its tags or its directory.
+    fn show_album_cover(&mut self, path: &Path) -> Result<()> {
+        let picture = match Track::read_track_from_path(path).and_then(|v| v.get_picture()) {
+            Ok(v) => v,
+            Err(err) => {
+                error!(
+                    "Getting the cover for \"{}\" failed! Error: {}",
+                    path.display(),
+                    err
+                );
+                return Ok(());
+            }
+        };
+        if let Some(picture) = picture {
+            if let Ok(image) = image::load_from_memory(picture.data()) {
+                self.show_image(&image)?;
+            }
+        }
 
-                let mut reader = {
-                    let bytes = match result.bytes().await {
-                        Ok(v) => v,
-                        Err(err) => {
-                            tx.send(Msg::Xywh(XYWHMsg::CoverDLResult(
-                                CoverDLResult::FetchPhotoErr(format!(
-                                    "Error in reqest::Response::bytes: {err}"
-                                )),
-                            )))
-                            .ok();
-                            return;
-                        }
-                    };
-
-                    bytes.reader()
-                };
+        Ok(())
+    }
 
-                let picture = match Picture::from_reader(&mut reader) {
-                    Ok(v) => v,
-                    Err(e) => {
-                        tx.send(Msg::Xywh(XYWHMsg::CoverDLResult(
-                            CoverDLResult::FetchPhotoErr(format!(
-                                "Error in picture from_reader: {e}"
-                            )),
-                        )))
-                        .ok();
-                        return;
-                    }
-                };
+    /// Fetch the given url as a image, from the artwork cache if possible, and send events when done or error.
+    async fn fetch_podcast_image(tx: TxToMain, url: String) {
+        let data = match ArtworkCache::new_default() {
+            Ok(cache) => cache.fetch(&url).await,
+            Err(err) => Err(err),
+        };
+        let data = match data {
+            Ok(v) => v,
+            Err(err) => {
+                tx.send(Msg::Xywh(XYWHMsg::CoverDLResult(
+                    CoverDLResult::FetchPhotoErr(format!("Error fetching artwork: {err:#}")),
+                )))
+                .ok();
+                return;
+            }
+        };
 
-                match image::load_from_memory(picture.data()) {
-                    Ok(image) => {
-                        let image_wrapper = ImageWrapper { data: image };
-                        tx.send(Msg::Xywh(XYWHMsg::CoverDLResult(
-                            CoverDLResult::FetchPhotoSuccess(image_wrapper),
-                        )))
-                        .ok()
-                    }
-                    Err(e) => tx
-                        .send(Msg::Xywh(XYWHMsg::CoverDLResult(
-                            CoverDLResult::FetchPhotoErr(format!("Error in load_from_memory: {e}")),
-                        )))
-                        .ok(),
-                }
+        let picture = match Picture::from_reader(&mut data.as_slice()) {
+            Ok(v) => v,
+            Err(e) => {
+                tx.send(Msg::Xywh(XYWHMsg::CoverDLResult(
+                    CoverDLResult::FetchPhotoErr(format!("Error in picture from_reader: {e}")),
+                )))
+                .ok();
+                return;
+            }
+        };
+
+        match image::load_from_memory(picture.data()) {
+            Ok(image) => {
+                let image_wrapper = ImageWrapper { data: image };
+                tx.send(Msg::Xywh(XYWHMsg::CoverDLResult(
+                    CoverDLResult::FetchPhotoSuccess(image_wrapper),
+                )))
+                .ok()
             }
             Err(e) => tx
                 .send(Msg::Xywh(XYWHMsg::CoverDLResult(
-                    CoverDLResult::FetchPhotoErr(format!("Error in ureq get: {e}")),
+                    CoverDLResult::FetchPhotoErr(format!("Error in load_from_memory: {e}")),
                 )))
                 .ok(),
         };
@@ -239,11 +269,7 @@ impl Model {
             ViuerSupported::NotSupported => {
                 #[cfg(all(feature = "cover-ueberzug", not(target_os = "windows")))]
                 if let Some(instance) = self.ueberzug_instance.as_mut() {
-                    let mut cache_file = dirs::cache_dir().unwrap_or_else(std::env::temp_dir);
-                    cache_file.push("termusic");
-                    if !cache_file.exists() {
-                        std::fs::create_dir_all(&cache_file)?;
-                    }
+                    let mut cache_file = termusiclib::utils::get_app_cache_path()?;
                     cache_file.push("termusic_cover.jpg");
                     img.save(&cache_file)?;
                     if !cache_file.exists() {
@@ -2,6 +2,11 @@
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
 pub enum Id {
+    AudioInfoPopup,
+    BookmarkNewPopup,
+    BookmarkPopup,
+    CastRendererPopup,
+    ChapterListPopup,
     ConfigEditor(IdConfigEditor),
     DBListCriteria,
     DBListSearchResult,
@@ -10,30 +15,57 @@ pub enum Id {
     DeleteConfirmInputPopup,
     DownloadSpinner,
     Episode,
+    ErrorHistoryPopup,
     ErrorPopup,
     GeneralSearchInput,
     GeneralSearchTable,
     GlobalListener,
+    GotoTimePopup,
     HelpPopup,
     Label,
     Library,
+    LibraryScanProgress,
     Lyric,
-    MessagePopup,
+    LyricCaptionInputPopup,
+    LyricEditorPopup,
+    OrganizePopup,
+    Toasts,
     Playlist,
     Podcast,
     PodcastAddPopup,
+    PodcastCategories,
     PodcastSearchTablePopup,
+    QueueNewPopup,
+    QueuePopup,
+    RadioFavorites,
+    RadioSearchPopup,
+    RadioSearchResults,
     FeedDeleteConfirmRadioPopup,
     FeedDeleteConfirmInputPopup,
+    EpisodeDownloadConfirmPopup,
+    EpisodeMenuPopup,
+    EpisodeInfoPopup,
+    FeedInfoPopup,
+    FeedMergeTablePopup,
+    FeedMergeConfirmPopup,
+    FeedSettingsPopup,
     Progress,
+    QueuePreview,
     QuitPopup,
     SavePlaylistPopup,
     SavePlaylistLabel,
     SavePlaylistConfirm,
+    PlaylistFormatPopup,
+    ImportPlaylistPopup,
+    TrackMenuPopup,
+    TrackInfoPopup,
     TagEditor(IdTagEditor),
+    UndoDeletePopup,
     YoutubeSearchInputPopup,
     YoutubeSearchTablePopup,
     DatabaseAddConfirmPopup,
+    DatabaseBackupPopup,
+    DatabaseRestoreConfirmPopup,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
@@ -47,6 +79,10 @@ pub enum IdTagEditor {
     SelectLyric,
     TableLyricOptions,
     TextareaLyric,
+
+    BulkEditor,
+    BulkField,
+    BulkProgress,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
@@ -122,6 +158,7 @@ pub enum IdCEGeneral {
     ExitConfirmation,
     AlbumPhotoAlign,
     ExtraYtdlpArgs,
+    LibraryScanPolicy,
     SaveLastPosition,
     SeekStep,
 
@@ -130,6 +167,8 @@ pub enum IdCEGeneral {
     PlayerProtocol,
     PlayerUDSPath,
     PlayerBackend,
+    PlayerOutputDevice,
+    PlayerCrossfade,
     PlayerUseDiscord,
     PlayerUseMpris,
 
@@ -161,10 +200,12 @@ pub enum IdKeyGlobal {
     LayoutTreeview,
     LayoutDatabase,
     LayoutPodcast,
+    LayoutRadio,
 
     Quit,
     Config,
     Help,
+    ErrorHistory,
     SavePlaylist,
 
     Up,
@@ -182,12 +223,23 @@ pub enum IdKeyGlobal {
     PlayerSeekBackward,
     PlayerSpeedUp,
     PlayerSpeedDown,
+    PlayerTrackSpeed,
+    PlayerAbRepeat,
+    PlayerBookmarks,
     PlayerVolumeUp,
     PlayerVolumeDown,
+    PlayerChapters,
+    PlayerNextChapter,
+    PlayerPreviousChapter,
+    PlayerGotoTime,
+    PlayerCast,
+    PlayerAudioInfo,
 
     LyricAdjustForward,
     LyricAdjustBackward,
     LyricCycle,
+    LyricCapture,
+    LyricEditor,
 
     XywhMoveUp,
     XywhMoveDown,
@@ -202,6 +254,9 @@ pub enum IdKeyGlobal {
 pub enum IdKeyOther {
     LibraryAddRoot,
     LibraryRemoveRoot,
+    LibraryRescan,
+    LibraryCycleFilter,
+    LibraryOpenExternal,
     LibrarySwitchRoot,
     LibraryDelete,
     LibraryLoadDir,
@@ -210,6 +265,12 @@ pub enum IdKeyOther {
     LibrarySearch,
     LibrarySearchYoutube,
     LibraryTagEditor,
+    LibraryTrackMenu,
+    LibraryCycleRating,
+    LibraryToggleFavorite,
+    LibraryMark,
+    LibraryOrganize,
+    LibraryToggleAudiobook,
 
     PlaylistShuffle,
     PlaylistModeCycle,
@@ -221,19 +282,41 @@ pub enum IdKeyOther {
     PlaylistDeleteAll,
     PlaylistAddRandomAlbum,
     PlaylistAddRandomTracks,
+    PlaylistExport,
+    PlaylistImport,
+    PlaylistTrackMenu,
+    PlaylistSaveQueue,
+    PlaylistRestoreQueue,
+    PlaylistToggleAutoQueue,
+    PlaylistQueues,
+    PlaylistCycleRating,
+    PlaylistToggleFavorite,
 
     DatabaseAddAll,
     DatabaseAddSelected,
+    DatabaseMaintenance,
+    DatabasePreview,
 
     PodcastSearchAddFeed,
     PodcastMarkPlayed,
     PodcastMarkAllPlayed,
+    PodcastEpStream,
     PodcastEpDownload,
+    PodcastEpDownloadAllUnplayed,
     PodcastEpDeleteFile,
     PodcastDeleteFeed,
     PodcastDeleteAllFeeds,
     PodcastRefreshFeed,
     PodcastRefreshAllFeeds,
+    PodcastCycleFeedSort,
+    PodcastCycleCategory,
+    PodcastEpisodeMenu,
+    PodcastFeedSettings,
+    PodcastFeedInfo,
+    PodcastMergeFeed,
+
+    RadioSearch,
+    RadioToggleFavorite,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
@@ -2,9 +2,8 @@ use anyhow::Result;
 use futures_util::StreamExt;
 use sysinfo::Pid;
 use sysinfo::System;
-use termusiclib::player::music_player_client::MusicPlayerClient;
+use termusiclib::player::auth::AuthClient;
 use tokio::sync::mpsc::{self};
-use tonic::transport::Channel;
 use tuirealm::application::PollStrategy;
 use tuirealm::{Application, Update};
 
@@ -33,7 +32,7 @@ pub struct UI {
 
 impl UI {
     /// Create a new [`UI`] instance
-    pub async fn new(config: CombinedSettings, client: MusicPlayerClient<Channel>) -> Result<Self> {
+    pub async fn new(config: CombinedSettings, client: AuthClient) -> Result<Self> {
         let mut playback = Playback::new(client);
 
         let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
@@ -71,6 +70,8 @@ impl UI {
             .send(TuiCmd::Playlist(PlaylistCmd::SelfReloadPlaylist));
         // initial request for all the progress states / options
         self.model.request_progress();
+        self.model.command(TuiCmd::GetCapabilities);
+        self.model.command(TuiCmd::Playlist(PlaylistCmd::GetQueues));
 
         // Main loop
         while !self.model.quit {
@@ -0,0 +1,179 @@
+//! Opening files with a user-configured external program or the user's editor, and copying text to the clipboard.
+
+use std::ffi::OsString;
+use std::io::Write as _;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::atomic::Ordering;
+
+use anyhow::{Context, Result, bail};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+
+use super::Model;
+
+/// Placeholder for the path of the file to open.
+const PLACEHOLDER_PATH: &str = "{path}";
+/// Placeholder for the directory containing the file to open.
+const PLACEHOLDER_DIR: &str = "{dir}";
+
+/// Split `template` into arguments and replace the placeholders in each with `path`.
+///
+/// No shell is involved, so `path` is always passed as-is, regardless of the characters it contains.
+fn build_args(template: &str, path: &Path) -> Result<Vec<OsString>> {
+    let dir = path.parent().unwrap_or(path);
+    let args: Vec<OsString> = shell_words::split(template)
+        .context("parse command")?
+        .into_iter()
+        .map(|arg| match arg.as_str() {
+            // keep non-utf8 paths intact if the argument is only the placeholder
+            PLACEHOLDER_PATH => path.as_os_str().to_os_string(),
+            PLACEHOLDER_DIR => dir.as_os_str().to_os_string(),
+            _ => arg
+                .replace(PLACEHOLDER_PATH, &path.to_string_lossy())
+                .replace(PLACEHOLDER_DIR, &dir.to_string_lossy())
+                .into(),
+        })
+        .collect();
+
+    if args.is_empty() {
+        bail!("Command is empty");
+    }
+
+    Ok(args)
+}
+
+/// Start `template` for `path` in the background, without waiting for it to finish.
+pub fn spawn_external(template: &str, path: &Path) -> Result<()> {
+    let args = build_args(template, path)?;
+
+    let mut child = Command::new(&args[0])
+        .args(&args[1..])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("spawn {:?}", args[0]))?;
+
+    // reap the process once it exits, so that it does not linger as a zombie
+    std::thread::Builder::new()
+        .name("external open".to_string())
+        .spawn(move || {
+            let _ = child.wait();
+        })?;
+
+    Ok(())
+}
+
+/// Copy `text` to the system clipboard via the terminal, using the OSC 52 escape sequence.
+///
+/// Works over ssh, but only if the terminal supports and allows it.
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    write!(stdout, "\x1b]52;c;{}\x07", STANDARD.encode(text)).context("write to terminal")?;
+    stdout.flush().context("flush terminal")?;
+
+    Ok(())
+}
+
+/// Get the command template for the user's editor, from `$VISUAL` or `$EDITOR`.
+///
+/// Falls back to `vi` (or `notepad` on windows) if neither is set.
+fn editor_template() -> String {
+    let editor = ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|v| !v.trim().is_empty())
+        .unwrap_or_else(|| {
+            if cfg!(target_os = "windows") {
+                "notepad".to_string()
+            } else {
+                "vi".to_string()
+            }
+        });
+
+    format!("{editor} {PLACEHOLDER_PATH}")
+}
+
+impl Model {
+    /// Open `path` with the program configured in `external_open.command`.
+    pub fn open_external(&self, path: &Path) -> Result<()> {
+        let template = self
+            .config_tui
+            .read()
+            .settings
+            .external_open
+            .command
+            .clone();
+
+        spawn_external(&template, path)
+    }
+
+    /// Open `path` in the user's editor and wait until it exits.
+    ///
+    /// The terminal is handed over to the editor for that time and restored afterwards.
+    pub fn edit_in_editor(&mut self, path: &Path) -> Result<()> {
+        let args = build_args(&editor_template(), path)?;
+
+        // stop reading terminal events, otherwise the editor and the event listener would compete for input
+        let _ = self.app.lock_ports();
+        let _ = self.terminal.disable_raw_mode();
+        let _ = self.terminal.leave_alternate_screen();
+        crate::TERMINAL_ALTERNATE_MODE.store(false, Ordering::SeqCst);
+
+        let status = Command::new(&args[0]).args(&args[1..]).status();
+
+        let _ = self.terminal.enable_raw_mode();
+        let _ = self.terminal.enter_alternate_screen();
+        // required as "enter_alternate_screen" always enabled mouse-capture
+        let _ = self.terminal.disable_mouse_capture();
+        // also resets the previous buffer, so that everything is drawn again
+        let _ = self.terminal.clear_screen();
+        crate::TERMINAL_ALTERNATE_MODE.store(true, Ordering::SeqCst);
+        let _ = self.app.unlock_ports();
+        self.force_redraw();
+
+        let status = status.with_context(|| format!("spawn {:?}", args[0]))?;
+        if !status.success() {
+            bail!("Editor exited with {status}");
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use super::build_args;
+
+    #[test]
+    fn should_replace_placeholders() {
+        let path = Path::new("/music/some dir/it's a \"track\".mp3");
+
+        assert_eq!(
+            build_args("app --file={path} '{dir}'", path).unwrap(),
+            [
+                OsString::from("app"),
+                OsString::from("--file=/music/some dir/it's a \"track\".mp3"),
+                OsString::from("/music/some dir"),
+            ]
+        );
+        assert_eq!(
+            build_args("xdg-open {path}", path).unwrap(),
+            [OsString::from("xdg-open"), path.as_os_str().to_os_string()]
+        );
+    }
+
+    #[test]
+    fn should_error_on_invalid_template() {
+        let path = Path::new("/music/track.mp3");
+
+        assert!(build_args("", path).is_err());
+        assert!(build_args("app 'unterminated", path).is_err());
+    }
+}
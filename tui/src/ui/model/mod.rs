@@ -1,3 +1,5 @@
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::atomic::Ordering;
@@ -5,43 +7,51 @@ use std::time::Duration;
 
 use anyhow::{Context, Result, anyhow, bail};
 use id3::frame::Lyrics as Id3Lyrics;
+use termusiclib::backup::BackupInfo;
 use termusiclib::config::v2::server::ScanDepth;
+use termusiclib::config::v2::server::metadata::ScanPolicy;
 #[allow(unused_imports)]
 use termusiclib::config::v2::tui::CoverArtProtocol;
 use termusiclib::config::v2::tui::keys::Keys;
 use termusiclib::config::v2::tui::theme::ThemeWrap;
 use termusiclib::config::{ServerOverlay, SharedServerSettings, SharedTuiSettings, TuiOverlay};
-use termusiclib::new_database::Database;
-use termusiclib::new_database::track_ops::TrackRead;
+use termusiclib::ids::{EpisodeId, PodcastId};
+use termusiclib::new_database::track_ops::{TrackPlayStats, TrackRead};
+use termusiclib::new_database::{Database, DirStats};
 use termusiclib::player::playlist_helpers::PlaylistTrackSource;
-use termusiclib::player::{PlaylistTracks, RunningStatus};
+use termusiclib::player::{AbRepeat, PlaylistTracks, RunningStatus, StreamInfo};
+use termusiclib::podcast::episode::Chapter;
+use termusiclib::podcast::manager::load_podcasts;
 use termusiclib::podcast::{Podcast, PodcastFeed, db::Database as DBPod};
+use termusiclib::radio_browser::RadioStation;
 use termusiclib::songtag::SongTag;
 use termusiclib::songtag::lrc::Lyric;
-use termusiclib::taskpool::TaskPool;
 use termusiclib::track::{LyricData, MediaTypesSimple, Track};
-use termusiclib::utils::get_app_config_path;
+use termusiclib::utils::get_app_data_path;
 use termusiclib::xywh;
 use tokio::sync::mpsc::{UnboundedSender, unbounded_channel};
 use tui_realm_treeview::Tree;
 use tuirealm::terminal::{CrosstermTerminalAdapter, TerminalBridge};
 
-use super::components::TETrack;
+use super::components::{BulkTagEdit, TETrack};
 use super::tui_cmd::TuiCmd;
 use crate::CombinedSettings;
 use crate::ui::Application;
+use crate::ui::components::{ErrorHistory, LibraryFilter, Toasts};
 use crate::ui::ids::Id;
 use crate::ui::model::ports::stream_events::{PortStreamEvents, WrappedStreamEvents};
 use crate::ui::model::youtube_options::YoutubeOptions;
-use crate::ui::msg::{Msg, SearchCriteria};
+use crate::ui::msg::{Msg, RadioList, SearchCriteria, UnplayedDownload};
 #[cfg(all(feature = "cover-ueberzug", not(target_os = "windows")))]
 use crate::ui::ueberzug::UeInstance;
 pub use download_tracker::DownloadTracker;
 pub use user_events::UserEvent;
 
 mod download_tracker;
+pub mod external_open;
 mod playlist;
 mod ports;
+pub mod trash;
 mod update;
 mod user_events;
 mod view;
@@ -52,6 +62,7 @@ pub enum TermusicLayout {
     TreeView,
     DataBase,
     Podcast,
+    Radio,
 }
 
 #[derive(Debug, PartialEq, Clone, Copy, Eq)]
@@ -71,6 +82,10 @@ pub struct MusicLibraryData {
     pub tree: Tree<String>,
     /// The Node that a yank & paste was started on
     pub yanked_node_id: Option<String>,
+    /// Cached statistics for directories, `None` if they are currently being computed
+    pub dir_stats: HashMap<PathBuf, Option<DirStats>>,
+    /// Which files are shown in the tree
+    pub filter: LibraryFilter,
 }
 
 /// All data specific to the Database Widget / View
@@ -82,6 +97,12 @@ pub struct DatabaseWidgetData {
     pub search_results: Vec<String>,
     /// Results of the critea results search `(criteria -> search_results -> this)`
     pub search_tracks: Vec<TrackRead>,
+    /// Play statistics for each of `search_tracks`, only for [`SearchCriteria::PlayStats`]
+    pub search_tracks_stats: Vec<TrackPlayStats>,
+    /// Backups listed in the maintenance popup
+    pub backups: Vec<BackupInfo>,
+    /// The first track of the album selected in view `Result`, whose cover is shown instead of the one of the playing track
+    pub album_cover: Option<PathBuf>,
 }
 
 impl DatabaseWidgetData {
@@ -92,6 +113,8 @@ impl DatabaseWidgetData {
         // Reset instead of ".clear" as "clear" does not remove capacity and might not be used again and could potentially be large
         self.search_results = Vec::new();
         self.search_tracks = Vec::new();
+        self.search_tracks_stats = Vec::new();
+        self.album_cover = None;
     }
 }
 
@@ -100,12 +123,99 @@ impl DatabaseWidgetData {
 pub struct PodcastWidgetData {
     /// Loaded and displayed Podcast list
     pub podcasts: Vec<Podcast>,
+    /// Podcast Database, only read from as the server writes it
+    pub db_podcast: DBPod,
     /// Selected podcast index
     pub podcasts_index: usize,
-    /// Podcast Database
-    pub db_podcast: DBPod,
     /// Podcast search results
     pub search_results: Option<Vec<PodcastFeed>>,
+    /// The category to only show feeds of, `None` to show all feeds
+    pub category: Option<String>,
+    /// The podcast index of each row in the feeds list
+    pub shown_feeds: Vec<usize>,
+    /// Unplayed episodes waiting for the download to be confirmed
+    pub pending_download: Option<UnplayedDownload>,
+    /// Episodes to add to the playlist once their download finished
+    pub play_after_download: HashSet<EpisodeId>,
+    /// The feed shown in the feed info popup, to update it once a re-check finished
+    pub feed_info: Option<PodcastId>,
+    /// The feed to merge and the feeds listed to merge it into, while the merge list is shown
+    pub merge_targets: Option<(PodcastId, Vec<PodcastId>)>,
+    /// The feed to merge and the feed to merge it into, waiting for the merge to be confirmed
+    pub pending_merge: Option<(PodcastId, PodcastId)>,
+}
+
+impl PodcastWidgetData {
+    /// Reload all podcasts from the database, after the server changed them.
+    ///
+    /// The selected podcast stays selected, even if its index changed.
+    pub fn reload(&mut self, config: &ServerOverlay) -> Result<()> {
+        let selected = self.podcasts.get(self.podcasts_index).map(|pod| pod.id);
+        self.podcasts = load_podcasts(&self.db_podcast, config)?;
+        if let Some(index) = selected.and_then(|id| self.podcast_index(id)) {
+            self.podcasts_index = index;
+        }
+
+        Ok(())
+    }
+
+    /// Get the index of the podcast with `id`.
+    #[must_use]
+    pub fn podcast_index(&self, id: PodcastId) -> Option<usize> {
+        self.podcasts.iter().position(|pod| pod.id == id)
+    }
+
+    /// Get the podcast and episode index of the episode with `id`.
+    #[must_use]
+    pub fn episode_index(&self, id: EpisodeId) -> Option<(usize, usize)> {
+        self.podcasts
+            .iter()
+            .enumerate()
+            .find_map(|(pod_index, pod)| {
+                pod.episodes
+                    .iter()
+                    .position(|ep| ep.id == id)
+                    .map(|ep_index| (pod_index, ep_index))
+            })
+    }
+
+    /// Get the podcast index of the row `list_index` in the feeds list.
+    #[must_use]
+    pub fn feed_index(&self, list_index: usize) -> Option<usize> {
+        self.shown_feeds.get(list_index).copied()
+    }
+
+    /// Get the row in the feeds list of the podcast at `podcast_index`, if it is shown.
+    #[must_use]
+    pub fn list_index(&self, podcast_index: usize) -> Option<usize> {
+        self.shown_feeds.iter().position(|v| *v == podcast_index)
+    }
+}
+
+/// All data specific to the Radio Widget / View
+#[derive(Debug, Default)]
+pub struct RadioWidgetData {
+    /// Favorite stations, as loaded from the database
+    pub favorites: Vec<RadioStation>,
+    /// Results of the last station search
+    pub search_results: Vec<RadioStation>,
+}
+
+impl RadioWidgetData {
+    /// Get the station at `index` in `list`.
+    #[must_use]
+    pub fn station(&self, list: RadioList, index: usize) -> Option<&RadioStation> {
+        match list {
+            RadioList::Favorites => self.favorites.get(index),
+            RadioList::SearchResults => self.search_results.get(index),
+        }
+    }
+
+    /// Check whether the station with `uuid` is a favorite.
+    #[must_use]
+    pub fn is_favorite(&self, uuid: &str) -> bool {
+        self.favorites.iter().any(|v| v.uuid == uuid)
+    }
 }
 
 /// All data specific to the Config Editor Widget / View
@@ -133,6 +243,16 @@ pub struct Playback {
     /// The current track, if there is one. Does not need to be in the playlist.
     current_track: Option<Track>,
     current_track_pos: Duration,
+    /// The section of the current track the server loops
+    ab_repeat: AbRepeat,
+    /// Chapters of the current track, sorted by start
+    chapters: Vec<Chapter>,
+    /// Codec, sample rate and bitrate of the current track, as reported by the backend
+    stream_info: StreamInfo,
+    /// Extensions of the files the backend can play, empty if not known yet
+    supported_extensions: Vec<String>,
+    /// The name of the stored queue the server plays, `None` if not known yet
+    queue: Option<String>,
 }
 
 impl Playback {
@@ -142,6 +262,11 @@ impl Playback {
             status: RunningStatus::default(),
             current_track: None,
             current_track_pos: Duration::ZERO,
+            ab_repeat: AbRepeat::default(),
+            chapters: Vec::new(),
+            stream_info: StreamInfo::default(),
+            supported_extensions: Vec::new(),
+            queue: None,
         }
     }
 
@@ -197,6 +322,77 @@ impl Playback {
         self.current_track_pos = pos;
     }
 
+    #[must_use]
+    pub fn ab_repeat(&self) -> AbRepeat {
+        self.ab_repeat
+    }
+
+    pub fn set_ab_repeat(&mut self, ab_repeat: AbRepeat) {
+        self.ab_repeat = ab_repeat;
+    }
+
+    #[must_use]
+    pub fn queue(&self) -> Option<&str> {
+        self.queue.as_deref()
+    }
+
+    pub fn set_queue(&mut self, queue: Option<String>) {
+        self.queue = queue;
+    }
+
+    #[must_use]
+    pub fn chapters(&self) -> &[Chapter] {
+        &self.chapters
+    }
+
+    pub fn set_chapters(&mut self, chapters: Vec<Chapter>) {
+        self.chapters = chapters;
+    }
+
+    /// Get the index of the chapter at the current position, which is the last one starting at or before it.
+    #[must_use]
+    pub fn current_chapter_index(&self) -> Option<usize> {
+        self.chapters
+            .iter()
+            .rposition(|v| v.start <= self.current_track_pos)
+    }
+
+    #[must_use]
+    pub fn stream_info(&self) -> &StreamInfo {
+        &self.stream_info
+    }
+
+    pub fn set_stream_info(&mut self, stream_info: StreamInfo) {
+        self.stream_info = stream_info;
+    }
+
+    pub fn set_supported_extensions(&mut self, extensions: Vec<String>) {
+        self.supported_extensions = extensions;
+    }
+
+    /// Check if the backend can play `track`.
+    ///
+    /// Only music files are checked, and everything is playable until the capabilities of the backend are known.
+    #[must_use]
+    pub fn is_playable(&self, track: &Track) -> bool {
+        let Some(track_data) = track.as_track() else {
+            return true;
+        };
+        if self.supported_extensions.is_empty() {
+            return true;
+        }
+
+        track_data
+            .audio_path()
+            .extension()
+            .and_then(OsStr::to_str)
+            .is_some_and(|ext| {
+                self.supported_extensions
+                    .iter()
+                    .any(|v| v.eq_ignore_ascii_case(ext))
+            })
+    }
+
     /// Load Tracks from a GRPC response.
     ///
     /// Returns `(Position, Tracks[])`.
@@ -308,9 +504,13 @@ pub struct Model {
     pub library: MusicLibraryData,
     pub dw: DatabaseWidgetData,
     pub podcast: PodcastWidgetData,
+    pub radio: RadioWidgetData,
     pub config_editor: ConfigEditorData,
 
     pub tageditor_song: Option<TETrack>,
+    /// Tracks marked to edit their tags all at once
+    pub tageditor_marked: Vec<PathBuf>,
+    pub tageditor_bulk: Option<BulkTagEdit>,
     pub current_track_lyric: Option<ExtraLyricData>,
     pub playback: Playback,
 
@@ -322,10 +522,13 @@ pub struct Model {
     youtube_options: YoutubeOptions,
     pub songtag_options: Vec<SongTag>,
     pub download_tracker: DownloadTracker,
-    /// Taskpool to limit number of active network requests
-    ///
-    /// Currently only used for podcast sync & download
-    pub taskpool: TaskPool,
+    /// Youtube urls being downloaded, to add to the playlist once their download finished
+    pub enqueue_after_download: HashSet<String>,
+    /// The items moved to the trash by the last deletion, to be able to undo it
+    pub last_trashed: Option<trash::TrashedItems>,
+    pub toasts: Toasts,
+    /// The most recent errors shown in the error popup
+    pub error_history: ErrorHistory,
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -399,23 +602,16 @@ impl Model {
         let db_criteria = SearchCriteria::Artist;
         let terminal = TerminalBridge::new_crossterm().expect("Could not initialize terminal");
 
-        let db_path = get_app_config_path().expect("failed to get podcast db path.");
-
-        let db_podcast = DBPod::new(&db_path).expect("error connecting to podcast db.");
-
-        let podcasts = db_podcast
-            .get_podcasts()
-            .expect("failed to get podcasts from db.");
-        let taskpool = TaskPool::new(usize::from(
-            config_server
-                .read()
-                .settings
-                .podcast
-                .concurrent_downloads_max
-                .get(),
-        ));
         let (tx_to_main, rx_to_main) = unbounded_channel();
 
+        // the server owns the podcast database and already created it before accepting connections
+        let db_podcast = get_app_data_path()
+            .map(|path| DBPod::open_read_only(&path))
+            .expect("error getting app data path.")
+            .expect("error connecting to podcast db.");
+        let podcasts =
+            load_podcasts(&db_podcast, &config_server.read()).expect("error loading podcasts.");
+
         let stream_update_port = PortStreamEvents::new(stream_updates);
 
         let app = Self::init_app(&tree, &config_tui, rx_to_main, stream_update_port);
@@ -434,6 +630,11 @@ impl Model {
             &path,
             ScanDepth::Limited(2),
             None,
+            (
+                LibraryFilter::default(),
+                config_server.read().parental.clone(),
+                db.clone(),
+            ),
         );
 
         Self {
@@ -444,11 +645,15 @@ impl Model {
             config_server,
             config_tui,
             tageditor_song: None,
+            tageditor_marked: Vec::new(),
+            tageditor_bulk: None,
 
             library: MusicLibraryData {
                 tree_path: path,
                 tree,
                 yanked_node_id: None,
+                dir_stats: HashMap::new(),
+                filter: LibraryFilter::default(),
             },
             youtube_options: YoutubeOptions::default(),
             #[cfg(all(feature = "cover-ueberzug", not(target_os = "windows")))]
@@ -461,13 +666,24 @@ impl Model {
                 criteria: db_criteria,
                 search_results: Vec::new(),
                 search_tracks: Vec::new(),
+                search_tracks_stats: Vec::new(),
+                backups: Vec::new(),
+                album_cover: None,
             },
             podcast: PodcastWidgetData {
                 podcasts,
                 podcasts_index: 0,
                 db_podcast,
                 search_results: None,
+                category: None,
+                shown_feeds: Vec::new(),
+                pending_download: None,
+                play_after_download: HashSet::new(),
+                feed_info: None,
+                merge_targets: None,
+                pending_merge: None,
             },
+            radio: RadioWidgetData::default(),
             config_editor: ConfigEditorData {
                 themes: Vec::new(),
                 theme: ce_theme,
@@ -475,13 +691,16 @@ impl Model {
                 layout: ConfigEditorLayout::General,
                 config_changed: false,
             },
-            taskpool,
             tx_to_main,
             download_tracker,
+            enqueue_after_download: HashSet::new(),
             current_track_lyric: None,
             playback: Playback::new(),
             cmd_to_server_tx,
             xywh,
+            last_trashed: None,
+            toasts: Toasts::default(),
+            error_history: ErrorHistory::default(),
         }
     }
 
@@ -511,15 +730,21 @@ impl Model {
             self.mount_error_popup(e.context("theme save"));
         }
         self.mount_label_help();
-        if let Err(err) =
-            self.db
-                .scan_path(&self.library.tree_path, &self.config_server.read(), false)
-        {
+        let config_server = self.config_server.read();
+        if config_server.settings.metadata.scan_policy == ScanPolicy::Manual {
+            info!("Skipping startup scan because of the manual scan policy");
+        } else if let Err(err) = self.db.scan_path(
+            &self.library.tree_path,
+            &config_server,
+            false,
+            Some(self.library_scan_progress_fn()),
+        ) {
             error!(
                 "Error scanning path {:#?}: {err:#?}",
                 self.library.tree_path.display()
             );
         }
+        drop(config_server);
         self.playlist_sync();
     }
 
@@ -571,6 +796,8 @@ impl Model {
             self.mount_error_popup(e.context("update_photo"));
         }
         self.progress_update_title();
+        self.progress_update_waveform();
+        self.queue_preview_update();
         self.lyric_update_title();
         self.lyric_update();
         self.update_playing_song();
@@ -1,10 +1,10 @@
-use std::fmt::Write as _;
 use std::path::Path;
 
 use anyhow::{Context, Result, bail};
 use pathdiff::diff_paths;
 use termusiclib::player::PlaylistRemoveTrackInfo;
 use termusiclib::player::playlist_helpers::{PlaylistAddTrack, PlaylistTrackSource};
+use termusiclib::playlist::{self, PlaylistEntry, PlaylistFormat, PlaylistValue};
 use termusiclib::podcast::db::Database as DBPod;
 use termusiclib::track::MediaTypes;
 use termusiclib::utils::get_parent_folder;
@@ -60,6 +60,11 @@ impl TUIPlaylist {
         self.loop_mode
     }
 
+    #[must_use]
+    pub fn loop_mode(&self) -> LoopMode {
+        self.loop_mode
+    }
+
     /// Set a specific [`LoopMode`].
     pub fn set_loop_mode(&mut self, new_mode: LoopMode) {
         self.loop_mode = new_mode;
@@ -125,14 +130,15 @@ impl TUIPlaylist {
         let at_index = usize::try_from(tracks.at_index).unwrap();
         if at_index >= self.len() {
             // insert tracks at the end
-            for track_location in tracks.tracks {
-                let track = match &track_location {
+            for (track_location, metadata) in tracks.into_iter_with_metadata() {
+                let mut track = match &track_location {
                     PlaylistTrackSource::Path(path) => Self::track_from_path(path)?,
                     PlaylistTrackSource::Url(uri) => Self::track_from_uri(uri),
                     PlaylistTrackSource::PodcastUrl(uri) => {
                         Self::track_from_podcasturi(uri, db_pod)?
                     }
                 };
+                track.fill_missing(metadata.title, metadata.duration);
 
                 self.tracks.push(track);
             }
@@ -141,12 +147,13 @@ impl TUIPlaylist {
         }
         let mut at_index = at_index;
         // insert tracks at position
-        for track_location in tracks.tracks {
-            let track = match &track_location {
+        for (track_location, metadata) in tracks.into_iter_with_metadata() {
+            let mut track = match &track_location {
                 PlaylistTrackSource::Path(path) => Self::track_from_path(path)?,
                 PlaylistTrackSource::Url(uri) => Self::track_from_uri(uri),
                 PlaylistTrackSource::PodcastUrl(uri) => Self::track_from_podcasturi(uri, db_pod)?,
             };
+            track.fill_missing(metadata.title, metadata.duration);
 
             self.tracks.insert(at_index, track);
             at_index += 1;
@@ -220,6 +227,32 @@ impl TUIPlaylist {
         self.tracks.get(idx)
     }
 
+    /// Get the track before the current track in the playlist, wrapping around at the start.
+    pub fn previous_track(&self) -> Option<&Track> {
+        let idx = self.current_track_idx?;
+        let idx = if idx == 0 {
+            self.tracks.len().checked_sub(1)?
+        } else {
+            idx - 1
+        };
+
+        self.tracks.get(idx)
+    }
+
+    /// Get the track the server will play after the current track in the current [`LoopMode`].
+    ///
+    /// Returns `None` in [`LoopMode::Random`], as the next track is not known yet.
+    pub fn next_track(&self) -> Option<&Track> {
+        let idx = self.current_track_idx?;
+        let idx = match self.loop_mode {
+            LoopMode::Single => idx,
+            LoopMode::Playlist => (idx + 1) % self.tracks.len().max(1),
+            LoopMode::Random => return None,
+        };
+
+        self.tracks.get(idx)
+    }
+
     /// Completely overwrite the tracks in this playlist.
     pub fn set_tracks(&mut self, tracks: Vec<Track>) {
         self.tracks = tracks;
@@ -227,47 +260,73 @@ impl TUIPlaylist {
         self.current_track_idx.take();
     }
 
-    // TODO: move "save_m3u" to server-side
-    /// Export the current playlist to a `.m3u` playlist file.
+    // TODO: move "save" to server-side
+    /// Export the current playlist to a playlist file in the given `format`.
     ///
     /// # Errors
     ///
     /// Error could happen when writing file to local disk.
-    pub fn save_m3u(&self, filename: &Path) -> Result<()> {
+    pub fn save(&self, filename: &Path, format: PlaylistFormat) -> Result<()> {
         if self.tracks.is_empty() {
             bail!("Unable to save since the playlist is empty.");
         }
 
         let parent_folder = get_parent_folder(filename);
 
-        let m3u = self.get_m3u_file(&parent_folder);
+        let content = playlist::encode(format, &self.get_playlist_entries(&parent_folder));
 
-        std::fs::write(filename, m3u)?;
+        std::fs::write(filename, content)?;
         Ok(())
     }
 
-    /// Generate the m3u's file content.
+    /// Generate the entries to write to a playlist file.
     ///
     /// All Paths are relative to the `parent_folder` directory.
-    fn get_m3u_file(&self, parent_folder: &Path) -> String {
-        let mut m3u = String::from("#EXTM3U\n");
+    fn get_playlist_entries(&self, parent_folder: &Path) -> Vec<PlaylistEntry> {
+        let mut entries = Vec::with_capacity(self.tracks.len());
         for track in &self.tracks {
-            let file = match track.inner() {
+            let value = match track.inner() {
                 MediaTypes::Track(track_data) => {
                     let path_relative = diff_paths(track_data.path(), parent_folder);
 
-                    path_relative.map_or_else(
-                        || track_data.path().to_string_lossy(),
-                        |v| v.to_string_lossy().to_string().into(),
+                    PlaylistValue::Path(
+                        path_relative.unwrap_or_else(|| track_data.path().to_path_buf()),
                     )
                 }
-                MediaTypes::Radio(radio_track_data) => radio_track_data.url().into(),
-                MediaTypes::Podcast(podcast_track_data) => podcast_track_data.url().into(),
+                MediaTypes::Radio(radio_track_data) => {
+                    match PlaylistValue::try_from_str(radio_track_data.url()) {
+                        Ok(v) => v,
+                        Err(err) => {
+                            warn!("Skipping radio with invalid url: {err:#}");
+                            continue;
+                        }
+                    }
+                }
+                MediaTypes::Podcast(podcast_track_data) => {
+                    match PlaylistValue::try_from_str(podcast_track_data.url()) {
+                        Ok(v) => v,
+                        Err(err) => {
+                            warn!("Skipping episode with invalid url: {err:#}");
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            // "Artist - Title" is the common convention for titles in playlist files
+            let title = match (track.artist(), track.title()) {
+                (Some(artist), Some(title)) => Some(format!("{artist} - {title}")),
+                (None, Some(title)) => Some(title.to_string()),
+                _ => None,
             };
 
-            let _ = writeln!(m3u, "{file}");
+            entries.push(PlaylistEntry {
+                value,
+                title,
+                duration: track.duration(),
+            });
         }
-        m3u
+        entries
     }
 
     /// Check that the given `info` track source matches the given `track_inner` types.
@@ -316,3 +375,48 @@ impl TUIPlaylist {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use termusiclib::config::v2::server::LoopMode;
+    use termusiclib::track::Track;
+
+    use super::TUIPlaylist;
+
+    fn url(track: Option<&Track>) -> Option<&str> {
+        track.and_then(Track::url)
+    }
+
+    #[test]
+    fn should_get_previous_and_next_track() {
+        let mut playlist = TUIPlaylist::default();
+        assert_eq!(url(playlist.previous_track()), None);
+        assert_eq!(url(playlist.next_track()), None);
+
+        playlist.set_tracks(vec![
+            Track::new_radio("a"),
+            Track::new_radio("b"),
+            Track::new_radio("c"),
+        ]);
+        playlist.set_loop_mode(LoopMode::Playlist);
+        // no current track yet
+        assert_eq!(url(playlist.next_track()), None);
+
+        playlist.set_current_track_index(1).unwrap();
+        assert_eq!(url(playlist.previous_track()), Some("a"));
+        assert_eq!(url(playlist.next_track()), Some("c"));
+
+        // wraps around at both ends
+        playlist.set_current_track_index(0).unwrap();
+        assert_eq!(url(playlist.previous_track()), Some("c"));
+        playlist.set_current_track_index(2).unwrap();
+        assert_eq!(url(playlist.next_track()), Some("a"));
+
+        playlist.set_loop_mode(LoopMode::Single);
+        assert_eq!(url(playlist.next_track()), Some("c"));
+
+        playlist.set_loop_mode(LoopMode::Random);
+        assert_eq!(url(playlist.next_track()), None);
+    }
+}
@@ -2,7 +2,7 @@ use std::{fmt::Debug, pin::Pin};
 
 use anyhow::{Context, Result};
 use futures_util::Stream;
-use termusiclib::player::{PlayerProgress, StreamUpdates, UpdateEvents};
+use termusiclib::player::{AbRepeat, PlayerProgress, StreamUpdates, UpdateEvents};
 use tokio_stream::StreamExt;
 use tuirealm::{
     Event,
@@ -69,6 +69,7 @@ fn is_progress(ev: &Result<UpdateEvents>) -> bool {
             == std::mem::discriminant(&UpdateEvents::Progress(PlayerProgress {
                 position: None,
                 total_duration: None,
+                ab_repeat: AbRepeat::default(),
             }))
     } else {
         false
@@ -0,0 +1,153 @@
+//! Deleting files via the OS trash, with the possibility to undo the last deletion.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use termusiclib::ids::EpisodeId;
+
+use super::Model;
+use crate::ui::tui_cmd::{PodcastCmd, TuiCmd};
+
+/// Whether restoring items from the trash is supported on the current platform.
+///
+/// See [`trash::os_limited`].
+pub const RESTORE_SUPPORTED: bool = cfg!(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+));
+
+/// Items moved to the trash by the last deletion, kept to be able to undo it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrashedItems {
+    /// Files or directories from the music library.
+    Library(Vec<PathBuf>),
+    /// Downloaded podcast episode files, with the id of the episode they belonged to.
+    Episodes(Vec<(EpisodeId, PathBuf)>),
+}
+
+impl TrashedItems {
+    /// Get all paths that were trashed.
+    fn paths(&self) -> Vec<&Path> {
+        match self {
+            TrashedItems::Library(paths) => paths.iter().map(PathBuf::as_path).collect(),
+            TrashedItems::Episodes(episodes) => {
+                episodes.iter().map(|(_, path)| path.as_path()).collect()
+            }
+        }
+    }
+}
+
+/// Delete the file or directory at `path`, either by moving it to the trash or permanently.
+pub fn delete_path(path: &Path, to_trash: bool) -> Result<()> {
+    if to_trash {
+        return trash::delete(path)
+            .with_context(|| format!("move \"{}\" to trash", path.display()));
+    }
+
+    if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+    .with_context(|| format!("delete \"{}\"", path.display()))
+}
+
+/// Restore all `paths` from the trash to their original location.
+///
+/// If a path was trashed multiple times, the latest one is restored.
+#[cfg(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+fn restore_paths(paths: &[&Path]) -> Result<()> {
+    let mut items = trash::os_limited::list().context("list trash")?;
+    // newest first, so that "position" finds the latest deletion
+    items.sort_unstable_by_key(|v| std::cmp::Reverse(v.time_deleted));
+
+    let mut to_restore = Vec::with_capacity(paths.len());
+    for path in paths {
+        let pos = items
+            .iter()
+            .position(|v| v.original_path() == *path)
+            .with_context(|| format!("\"{}\" not found in trash", path.display()))?;
+        to_restore.push(items.remove(pos));
+    }
+
+    trash::os_limited::restore_all(to_restore).context("restore from trash")?;
+
+    Ok(())
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+)))]
+fn restore_paths(_paths: &[&Path]) -> Result<()> {
+    anyhow::bail!("Restoring from the trash is not supported on this platform");
+}
+
+impl Model {
+    /// Get whether deleted files should be moved to the trash, see `BehaviorSettings::delete_to_trash`.
+    pub fn delete_to_trash(&self) -> bool {
+        self.config_tui.read().settings.behavior.delete_to_trash
+    }
+
+    /// Remember `items` as the last trashed items and offer to undo the deletion.
+    ///
+    /// Does nothing if restoring is not supported on the current platform.
+    pub fn trash_offer_undo(&mut self, items: TrashedItems) {
+        if !RESTORE_SUPPORTED {
+            return;
+        }
+
+        self.last_trashed = Some(items);
+        self.mount_undo_delete_popup();
+    }
+
+    /// Restore the last trashed items and add them back to the views.
+    pub fn trash_undo(&mut self) -> Result<()> {
+        let Some(items) = self.last_trashed.take() else {
+            return Ok(());
+        };
+
+        restore_paths(&items.paths())?;
+
+        match items {
+            TrashedItems::Library(paths) => {
+                let focus_node = paths.first().map(|v| v.to_string_lossy().to_string());
+                self.library_reload_with_node_focus(focus_node);
+            }
+            TrashedItems::Episodes(episodes) => {
+                // the views are updated once the server reports the podcasts as changed
+                for (id, path) in episodes {
+                    // the files of removed feeds are only restored on disk, their episodes are gone
+                    let known = self
+                        .podcast
+                        .podcasts
+                        .iter()
+                        .any(|pod| pod.episodes.iter().any(|ep| ep.id == id));
+                    if known {
+                        self.command(TuiCmd::Podcast(PodcastCmd::RestoreFile(id, path)));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,21 +1,24 @@
 use std::path::Path;
 use std::time::Duration;
 
-use anyhow::{Result, anyhow};
-use termusiclib::player::{PlayerProgress, RunningStatus, UpdateEvents, UpdatePlaylistEvents};
-use termusiclib::podcast::{PodcastDLResult, PodcastSyncResult};
+use anyhow::{Context, Result, anyhow};
+use termusiclib::config::v2::tui::config_extra::TuiConfigVersionedDefaulted;
+use termusiclib::player::{
+    AbRepeat, PlayerProgress, PodcastProgress, RunningStatus, StreamInfo, UpdateEvents,
+    UpdatePlaylistEvents,
+};
 use termusiclib::track::MediaTypesSimple;
-use tokio::runtime::Handle;
-use tokio::time::sleep;
 use tuirealm::Update;
 use tuirealm::props::{AttrValue, Attribute};
 
+use crate::instance_lock;
 use crate::ui::ids::Id;
 use crate::ui::model::youtube_options::YTDLMsg;
 use crate::ui::msg::{
-    CoverDLResult, DBMsg, DeleteConfirmMsg, ErrorPopupMsg, GSMsg, HelpPopupMsg, LIMsg, LyricMsg,
-    MainLayoutMsg, Msg, NotificationMsg, PCMsg, PLMsg, PlayerMsg, QuitPopupMsg, SavePlaylistMsg,
-    ServerReqResponse, XYWHMsg, YSMsg,
+    CoverDLResult, DBMsg, DeleteConfirmMsg, ErrorPopupMsg, GSMsg, HelpPopupMsg, ImportPlaylistMsg,
+    LIMsg, LyricEditorMsg, LyricMsg, MainLayoutMsg, Msg, NotificationMsg, PCMsg, PLMsg, PlayerMsg,
+    QuitPopupMsg, RadioMsg, SavePlaylistMsg, ServerReqResponse, TEMsg, TrackMenuMsg, XYWHMsg,
+    YSMsg,
 };
 use crate::ui::tui_cmd::TuiCmd;
 use crate::ui::{Model, model::TermusicLayout};
@@ -26,6 +29,16 @@ impl Update<Msg> for Model {
         let msg = msg?;
         // Set redraw
         self.redraw = true;
+        // the actions are also hidden, but their keys still work
+        if msg.is_destructive() && self.config_tui.read().guest {
+            self.show_message_timeout_label_help(
+                "Not available in guest mode",
+                None,
+                None,
+                Some(3),
+            );
+            return None;
+        }
         // Match message
         match msg {
             Msg::ConfigEditor(msg) => self.update_config_editor(msg),
@@ -69,9 +82,13 @@ impl Update<Msg> for Model {
             Msg::Layout(msg) => self.update_layout(msg),
 
             Msg::SavePlaylist(msg) => self.update_save_playlist(msg),
+            Msg::ImportPlaylist(msg) => self.update_import_playlist(msg),
+            Msg::TrackMenu(msg) => self.update_track_menu(msg),
 
             Msg::Podcast(msg) => self.update_podcast(msg),
+            Msg::Radio(msg) => self.update_radio(msg),
             Msg::LyricMessage(msg) => self.update_lyric_msg(msg),
+            Msg::LyricEditor(msg) => self.update_lyric_editor(msg),
             Msg::Notification(msg) => self.update_notification_msg(msg),
             Msg::Xywh(msg) => self.update_xywh_msg(msg),
             Msg::ServerReqResponse(msg) => self.update_server_resp_msg(msg),
@@ -100,6 +117,8 @@ impl Model {
                     self.umount_error_popup();
                 }
             }
+            ErrorPopupMsg::HistoryShow => self.mount_error_history_popup(),
+            ErrorPopupMsg::HistoryClose => self.umount_error_history_popup(),
         }
 
         None
@@ -135,14 +154,49 @@ impl Model {
             QuitPopupMsg::CloseCancel => {
                 self.app.umount(&Id::QuitPopup).ok();
             }
-            QuitPopupMsg::CloseOk => {
-                self.quit = true;
+            QuitPopupMsg::CloseOk { stop_server } => {
+                let changed = {
+                    let mut config = self.config_tui.write();
+                    let behavior = &mut config.settings.behavior;
+                    let changed = behavior.quit_server_on_exit != *stop_server;
+                    behavior.quit_server_on_exit = *stop_server;
+                    changed
+                };
+
+                if changed {
+                    self.quit_save_behavior();
+                } else {
+                    self.quit = true;
+                }
+            }
+            QuitPopupMsg::CloseOkDontAsk => {
+                self.config_tui.write().settings.behavior.confirm_quit = false;
+                self.quit_save_behavior();
             }
         }
 
         None
     }
 
+    /// Save the changed quit behavior to the config, then quit.
+    ///
+    /// If saving fails, the error is shown instead of quitting.
+    fn quit_save_behavior(&mut self) {
+        let res = instance_lock::ensure_held()
+            .and_then(|()| {
+                TuiConfigVersionedDefaulted::save_config_path(&self.config_tui.read().settings)
+            })
+            .context("save tui settings");
+
+        match res {
+            Ok(()) => self.quit = true,
+            Err(err) => {
+                self.app.umount(&Id::QuitPopup).ok();
+                self.mount_error_popup(err);
+            }
+        }
+    }
+
     /// Handle all [`XYWHMsg`] messages. Sub-function for [`update`](Self::update).
     fn update_xywh_msg(&mut self, msg: XYWHMsg) -> Option<Msg> {
         match msg {
@@ -178,11 +232,16 @@ impl Model {
                 self.lyric_adjust_delay(offset);
                 None
             }
+            LyricMsg::Capture => {
+                self.lyric_capture();
+                None
+            }
             LyricMsg::TextAreaBlurUp => self.app.active(&Id::Playlist).ok(),
             LyricMsg::TextAreaBlurDown => match self.layout {
                 TermusicLayout::TreeView => self.app.active(&Id::Library).ok(),
                 TermusicLayout::DataBase => self.app.active(&Id::DBListCriteria).ok(),
                 TermusicLayout::Podcast => self.app.active(&Id::Podcast).ok(),
+                TermusicLayout::Radio => self.app.active(&Id::RadioFavorites).ok(),
             },
         };
         None
@@ -191,12 +250,7 @@ impl Model {
     /// Handle all [`NotificationMsg`] messages. Sub-function for [`update`](Self::update).
     fn update_notification_msg(&mut self, msg: NotificationMsg) -> Option<Msg> {
         match msg {
-            NotificationMsg::MessageShow((title, text)) => {
-                self.mount_message(&title, &text);
-            }
-            NotificationMsg::MessageHide((title, text)) => {
-                self.umount_message(&title, &text);
-            }
+            NotificationMsg::ToastExpire(id) => self.expire_toast(id),
         }
 
         None
@@ -231,10 +285,10 @@ impl Model {
             }
             PCMsg::PodcastAddPopupCloseCancel => self.umount_podcast_add_popup(),
 
-            PCMsg::SyncResult(msg) => self.podcast_handle_sync_result(msg),
-            PCMsg::DLResult(msg) => self.podcast_handle_dl_result(msg),
-
             PCMsg::PodcastSelected(index) => {
+                let Some(index) = self.podcast.feed_index(index) else {
+                    return None;
+                };
                 self.podcast.podcasts_index = index;
                 if let Err(e) = self.podcast_sync_episodes() {
                     self.mount_error_popup(e.context("podcast sync episodes"));
@@ -242,6 +296,11 @@ impl Model {
             }
             PCMsg::DescriptionUpdate => self.lyric_update(),
             PCMsg::EpisodeAdd(index) => {
+                if let Err(e) = self.episode_enter(index) {
+                    self.mount_error_popup(e.context("podcast playlist add episode"));
+                }
+            }
+            PCMsg::EpisodeStream(index) => {
                 if let Err(e) = self.playlist_add_episode(index) {
                     self.mount_error_popup(e.context("podcast playlist add episode"));
                 }
@@ -257,6 +316,9 @@ impl Model {
                 }
             }
             PCMsg::PodcastRefreshOne(index) => {
+                let Some(index) = self.podcast.feed_index(index) else {
+                    return None;
+                };
                 if let Err(e) = self.podcast_refresh_feeds(Some(index)) {
                     self.mount_error_popup(e.context("podcast refresh feeds one"));
                 }
@@ -265,6 +327,7 @@ impl Model {
                 if let Err(e) = self.podcast_refresh_feeds(None) {
                     self.mount_error_popup(e.context("podcast refresh feeds all"));
                 }
+                self.podcast_gpodder_sync();
             }
 
             PCMsg::EpisodeDownload(index) => {
@@ -273,6 +336,36 @@ impl Model {
                 }
             }
 
+            PCMsg::EpisodeDownloadUnplayedEstimate => {
+                if let Err(e) = self.episode_download_unplayed_estimate() {
+                    self.mount_error_popup(e.context("podcast episode download unplayed"));
+                }
+            }
+            PCMsg::EpisodeDownloadUnplayedShow(download) => {
+                self.mount_episode_download_confirm(&download);
+                self.podcast.pending_download = Some(download);
+            }
+            PCMsg::EpisodeDownloadUnplayedCloseOk => {
+                self.umount_episode_download_confirm();
+                if let Some(download) = self.podcast.pending_download.take() {
+                    if let Err(e) = self.episode_download_unplayed(&download) {
+                        self.mount_error_popup(e.context("podcast episode download unplayed"));
+                    }
+                }
+            }
+            PCMsg::EpisodeMenuShow(index) => self.mount_episode_menu(index),
+            PCMsg::EpisodeMenuCloseOk(index, action) => {
+                self.umount_episode_menu();
+                if let Err(e) = self.episode_menu_action(index, action) {
+                    self.mount_error_popup(e.context("podcast episode menu"));
+                }
+            }
+            PCMsg::EpisodeMenuCloseCancel => self.umount_episode_menu(),
+            PCMsg::EpisodeInfoClose => self.umount_episode_info(),
+            PCMsg::EpisodeDownloadUnplayedCloseCancel => {
+                self.umount_episode_download_confirm();
+                self.podcast.pending_download = None;
+            }
             PCMsg::EpisodeDeleteFile(index) => {
                 if let Err(e) = self.episode_delete_file(index) {
                     self.mount_error_popup(e.context("podcast episode delete"));
@@ -294,6 +387,51 @@ impl Model {
                 }
             }
             PCMsg::FeedsDeleteCloseCancel => self.umount_feed_delete_confirm_input(),
+            PCMsg::FeedsCycleSort => {
+                if let Err(e) = self.podcast_cycle_feed_sort() {
+                    self.mount_error_popup(e.context("podcast cycle feeds order"));
+                }
+            }
+            PCMsg::FeedsCycleCategory => self.podcast_cycle_category(),
+            PCMsg::FeedSettingsShow(index) => {
+                if let Err(e) = self.podcast_feed_settings_show(index) {
+                    self.mount_error_popup(e.context("podcast feed settings"));
+                }
+            }
+            PCMsg::FeedSettingsCloseOk(pod_id, settings) => {
+                self.umount_feed_settings();
+                self.podcast_feed_settings_save(pod_id, settings);
+            }
+            PCMsg::FeedSettingsCloseCancel => self.umount_feed_settings(),
+            PCMsg::FeedInfoShow(index) => self.podcast_feed_info_show(index),
+            PCMsg::FeedInfoRefresh(pod_id) => {
+                if let Err(e) = self.podcast_feed_info_refresh(pod_id) {
+                    self.mount_error_popup(e.context("podcast refresh feed"));
+                }
+            }
+            PCMsg::FeedInfoClose => {
+                self.umount_feed_info();
+                self.podcast.feed_info = None;
+            }
+            PCMsg::FeedMergeShow(index) => self.podcast_feed_merge_show(index),
+            PCMsg::FeedMergeSelect(index) => {
+                self.umount_feed_merge_table();
+                self.podcast_feed_merge_select(index);
+            }
+            PCMsg::FeedMergeSelectCancel => {
+                self.umount_feed_merge_table();
+                self.podcast.merge_targets = None;
+            }
+            PCMsg::FeedMergeCloseOk => {
+                self.umount_feed_merge_confirm();
+                if let Some((source, target)) = self.podcast.pending_merge.take() {
+                    self.podcast_feed_merge(source, target);
+                }
+            }
+            PCMsg::FeedMergeCloseCancel => {
+                self.umount_feed_merge_confirm();
+                self.podcast.pending_merge = None;
+            }
             PCMsg::SearchItunesCloseCancel => self.umount_podcast_search_table(),
             PCMsg::SearchItunesCloseOk(index) => {
                 if let Some(vec) = &self.podcast.search_results {
@@ -311,10 +449,56 @@ impl Model {
         None
     }
 
-    /// Handle all cases for [`PodcastSyncResult`].
-    fn podcast_handle_sync_result(&mut self, msg: PodcastSyncResult) {
+    /// Handle all [`RadioMsg`] messages. Sub-function for [`update`](Self::update).
+    fn update_radio(&mut self, msg: RadioMsg) -> Option<Msg> {
         match msg {
-            PodcastSyncResult::FetchPodcastStart(url) => {
+            RadioMsg::FavoritesBlurDown => {
+                self.app.active(&Id::RadioSearchResults).ok();
+            }
+            RadioMsg::FavoritesBlurUp => {
+                self.app.active(&Id::Lyric).ok();
+            }
+            RadioMsg::SearchResultsBlurDown => {
+                self.app.active(&Id::Playlist).ok();
+            }
+            RadioMsg::SearchResultsBlurUp => {
+                self.app.active(&Id::RadioFavorites).ok();
+            }
+            RadioMsg::SearchPopupShow => self.mount_radio_search_popup(),
+            RadioMsg::SearchPopupCloseOk(input) => {
+                self.umount_radio_search_popup();
+                if !input.trim().is_empty() {
+                    self.radio_search(&input);
+                }
+            }
+            RadioMsg::SearchPopupCloseCancel => self.umount_radio_search_popup(),
+            RadioMsg::SearchSuccess(stations) => {
+                let msg = format!("Found {} stations", stations.len());
+                self.radio.search_results = stations;
+                self.radio_sync_search_results();
+                self.show_message_timeout_label_help(msg, None, None, None);
+                self.app.active(&Id::RadioSearchResults).ok();
+            }
+            RadioMsg::SearchError(e) => self.mount_error_popup(anyhow!(e)),
+            RadioMsg::PlaylistAdd(list, index) => {
+                if let Err(e) = self.radio_add_playlist(list, index) {
+                    self.mount_error_popup(e.context("radio add to playlist"));
+                }
+            }
+            RadioMsg::ToggleFavorite(list, index) => {
+                if let Err(e) = self.radio_toggle_favorite(list, index) {
+                    self.mount_error_popup(e.context("radio toggle favorite"));
+                }
+            }
+        }
+        None
+    }
+
+    /// Handle all cases for [`PodcastProgress`] reported by the server.
+    #[allow(clippy::too_many_lines)]
+    fn podcast_handle_progress(&mut self, progress: PodcastProgress) {
+        match progress {
+            PodcastProgress::FetchStart(url) => {
                 self.download_tracker.increase_one(url);
                 self.show_message_timeout_label_help(
                     self.download_tracker.message_sync_start(),
@@ -323,47 +507,49 @@ impl Model {
                     None,
                 );
             }
-            PodcastSyncResult::SyncData((id, pod)) => {
-                self.download_tracker.decrease_one(&pod.url);
+            PodcastProgress::FetchDone {
+                url,
+                added,
+                merged_into,
+            } => {
+                self.download_tracker.decrease_one(&url);
+                let msg = match merged_into {
+                    Some(title) => format!("Feed was already subscribed, merged into \"{title}\""),
+                    None if added => self.download_tracker.message_feeds_added(),
+                    None => self.download_tracker.message_sync_success(),
+                };
+                self.show_message_timeout_label_help(msg, None, None, None);
+            }
+            PodcastProgress::FetchFailed { url, title } => {
+                self.download_tracker.decrease_one(&url);
+                self.mount_error_popup(anyhow!("Error happened with feed: {title:?}"));
                 self.show_message_timeout_label_help(
-                    self.download_tracker.message_sync_success(),
+                    self.download_tracker.message_feed_sync_failed(),
                     None,
                     None,
                     None,
                 );
-                if let Err(e) = self.add_or_sync_data(&pod, Some(id)) {
-                    self.mount_error_popup(e.context("add or sync data"));
-                }
             }
-            PodcastSyncResult::NewData(pod) => {
-                self.download_tracker.decrease_one(&pod.url);
+            PodcastProgress::SyncStart => {
                 self.show_message_timeout_label_help(
-                    self.download_tracker.message_feeds_added(),
+                    "Syncing podcast positions...",
                     None,
                     None,
                     None,
                 );
-                if let Err(e) = self.add_or_sync_data(&pod, None) {
-                    self.mount_error_popup(e.context("add or sync data"));
-                }
             }
-            PodcastSyncResult::Error(feed) => {
-                self.download_tracker.decrease_one(&feed.url);
-                self.mount_error_popup(anyhow!("Error happened with feed: {:?}", feed.title));
+            PodcastProgress::SyncDone { pushed, changed } => {
                 self.show_message_timeout_label_help(
-                    self.download_tracker.message_feed_sync_failed(),
+                    format!("Podcast sync done: uploaded {pushed}, updated {changed} episode(s)"),
                     None,
                     None,
                     None,
                 );
             }
-        }
-    }
-
-    /// Handle all cases for [`PodcastDLResult`].
-    fn podcast_handle_dl_result(&mut self, msg: PodcastDLResult) {
-        match msg {
-            PodcastDLResult::DLStart(ep_data) => {
+            PodcastProgress::SyncFailed(e) => {
+                self.mount_error_popup(anyhow!("Podcast sync failed: {e}"));
+            }
+            PodcastProgress::DownloadStart(ep_data) => {
                 self.download_tracker.increase_one(&ep_data.url);
                 self.show_message_timeout_label_help(
                     self.download_tracker.message_download_start(&ep_data.title),
@@ -372,9 +558,10 @@ impl Model {
                     None,
                 );
             }
-            PodcastDLResult::DLComplete(ep_data) => {
-                if let Err(e) = self.episode_download_complete(ep_data.clone()) {
-                    self.mount_error_popup(e.context("podcast episode download complete"));
+            PodcastProgress::DownloadDone(ep_data) => {
+                // the episode list itself is reloaded once the server reports the podcasts as changed
+                if self.podcast.play_after_download.remove(&ep_data.id) {
+                    self.playlist_add_podcast_url(ep_data.url.clone());
                 }
                 self.download_tracker.decrease_one(&ep_data.url);
                 self.show_message_timeout_label_help(
@@ -384,8 +571,9 @@ impl Model {
                     None,
                 );
             }
-            PodcastDLResult::DLResponseError(ep_data) => {
+            PodcastProgress::DownloadResponseError(ep_data) => {
                 self.download_tracker.decrease_one(&ep_data.url);
+                self.podcast.play_after_download.remove(&ep_data.id);
                 self.mount_error_popup(anyhow!("download failed for episode: {}", ep_data.title));
                 self.show_message_timeout_label_help(
                     self.download_tracker
@@ -395,8 +583,9 @@ impl Model {
                     None,
                 );
             }
-            PodcastDLResult::DLFileCreateError(ep_data) => {
+            PodcastProgress::DownloadFileCreateError(ep_data) => {
                 self.download_tracker.decrease_one(&ep_data.url);
+                self.podcast.play_after_download.remove(&ep_data.id);
                 self.mount_error_popup(anyhow!("download failed for episode: {}", ep_data.title));
                 self.show_message_timeout_label_help(
                     self.download_tracker
@@ -406,8 +595,9 @@ impl Model {
                     None,
                 );
             }
-            PodcastDLResult::DLFileWriteError(ep_data) => {
+            PodcastProgress::DownloadFileWriteError(ep_data) => {
                 self.download_tracker.decrease_one(&ep_data.url);
+                self.podcast.play_after_download.remove(&ep_data.id);
                 self.mount_error_popup(anyhow!("download failed for episode: {}", ep_data.title));
                 self.show_message_timeout_label_help(
                     self.download_tracker
@@ -450,12 +640,132 @@ impl Model {
                 }
                 self.command(TuiCmd::SeekBackward);
             }
+            PlayerMsg::ChaptersShow => {
+                if self.playback.chapters().is_empty() {
+                    self.show_message_timeout_label_help(
+                        "the current track has no chapters",
+                        None,
+                        None,
+                        None,
+                    );
+                    return None;
+                }
+                self.mount_chapter_list();
+            }
+            PlayerMsg::ChaptersCloseCancel => {
+                self.umount_chapter_list();
+            }
+            PlayerMsg::ChaptersCloseOk(index) => {
+                self.umount_chapter_list();
+                if let Some(chapter) = self.playback.chapters().get(index) {
+                    self.command(TuiCmd::SeekTo(chapter.start));
+                }
+            }
+            PlayerMsg::BookmarksShow => {
+                if self.is_radio() {
+                    self.show_message_timeout_label_help(
+                        "live radio can not be bookmarked",
+                        None,
+                        None,
+                        None,
+                    );
+                    return None;
+                }
+                self.mount_bookmarks(None);
+                self.command(TuiCmd::GetBookmarks);
+            }
+            PlayerMsg::BookmarksClose => {
+                self.umount_bookmarks();
+            }
+            PlayerMsg::BookmarkJump(id) => {
+                self.umount_bookmarks();
+                self.command(TuiCmd::JumpToBookmark(id));
+            }
+            PlayerMsg::BookmarkRemove(id) => {
+                self.command(TuiCmd::RemoveBookmark(id));
+            }
+            PlayerMsg::BookmarkNewShow => {
+                self.mount_bookmark_new();
+            }
+            PlayerMsg::BookmarkNewCloseCancel => {
+                self.umount_bookmark_new();
+            }
+            PlayerMsg::BookmarkNewCloseOk(name) => {
+                self.umount_bookmark_new();
+                self.command(TuiCmd::AddBookmark(name));
+            }
+            PlayerMsg::NextChapter | PlayerMsg::PreviousChapter => {
+                if self.playback.chapters().is_empty() {
+                    self.show_message_timeout_label_help(
+                        "the current track has no chapters",
+                        None,
+                        None,
+                        None,
+                    );
+                    return None;
+                }
+                self.command(TuiCmd::JumpChapter(msg == PlayerMsg::NextChapter));
+            }
+            PlayerMsg::GotoTimeShow => {
+                if self.is_radio() {
+                    self.show_message_timeout_label_help(
+                        "seek is not available for live radio",
+                        None,
+                        None,
+                        None,
+                    );
+                    return None;
+                }
+                let Some(track) = self.playback.current_track() else {
+                    self.show_message_timeout_label_help("nothing is playing", None, None, None);
+                    return None;
+                };
+                self.mount_goto_time(track.duration());
+            }
+            PlayerMsg::GotoTimeCloseCancel => {
+                self.umount_goto_time();
+            }
+            PlayerMsg::GotoTimeCloseOk(position) => {
+                self.umount_goto_time();
+                self.command(TuiCmd::SeekTo(position));
+            }
+            PlayerMsg::CastShow => {
+                self.mount_cast_renderers(None);
+                self.command(TuiCmd::GetCastRenderers);
+            }
+            PlayerMsg::CastCloseCancel => {
+                self.umount_cast_renderers();
+            }
+            PlayerMsg::CastCloseOk(id) => {
+                self.umount_cast_renderers();
+                self.command(TuiCmd::SetCastRenderer(id));
+            }
+            PlayerMsg::AudioInfoShow => {
+                self.command(TuiCmd::GetAudioInfo);
+            }
+            PlayerMsg::AudioInfoClose => {
+                self.umount_audio_info();
+            }
             PlayerMsg::SpeedUp => {
                 self.command(TuiCmd::SpeedUp);
             }
             PlayerMsg::SpeedDown => {
                 self.command(TuiCmd::SpeedDown);
             }
+            PlayerMsg::ToggleTrackSpeed => {
+                self.command(TuiCmd::ToggleTrackSpeed);
+            }
+            PlayerMsg::CycleAbRepeat => {
+                let ab_repeat = self.playback.ab_repeat();
+                let cmd = if ab_repeat.a.is_none() {
+                    TuiCmd::SetAbRepeatA
+                } else if ab_repeat.b.is_none() {
+                    TuiCmd::SetAbRepeatB
+                } else {
+                    TuiCmd::ClearAbRepeat
+                };
+                self.command(cmd);
+            }
             PlayerMsg::VolumeUp => {
                 self.command(TuiCmd::VolumeUp);
             }
@@ -472,6 +782,7 @@ impl Model {
 
     /// Switch the main view / layout.
     fn update_layout(&mut self, msg: MainLayoutMsg) -> Option<Msg> {
+        let had_album_cover = self.database_album_cover().is_some();
         match msg {
             MainLayoutMsg::DataBase => {
                 let mut need_to_set_focus = true;
@@ -564,6 +875,50 @@ impl Model {
                 self.lyric_update_title();
                 self.lyric_update();
             }
+            MainLayoutMsg::Radio => {
+                let mut need_to_set_focus = true;
+                if let Ok(Some(AttrValue::Flag(true))) =
+                    self.app.query(&Id::RadioFavorites, Attribute::Focus)
+                {
+                    need_to_set_focus = false;
+                }
+
+                if let Ok(Some(AttrValue::Flag(true))) =
+                    self.app.query(&Id::RadioSearchResults, Attribute::Focus)
+                {
+                    need_to_set_focus = false;
+                }
+                if let Ok(Some(AttrValue::Flag(true))) =
+                    self.app.query(&Id::Playlist, Attribute::Focus)
+                {
+                    need_to_set_focus = false;
+                }
+
+                if let Ok(Some(AttrValue::Flag(true))) =
+                    self.app.query(&Id::Lyric, Attribute::Focus)
+                {
+                    need_to_set_focus = false;
+                }
+
+                if need_to_set_focus {
+                    self.app.active(&Id::RadioFavorites).ok();
+                }
+
+                self.layout = TermusicLayout::Radio;
+                if let Err(e) = self.radio_sync_favorites() {
+                    self.mount_error_popup(e.context("radio load favorites"));
+                }
+                self.playlist_switch_layout();
+                self.lyric_update_title();
+                self.lyric_update();
+            }
+        }
+
+        // the album cover is only shown in the database view
+        if had_album_cover != self.database_album_cover().is_some() {
+            if let Err(e) = self.update_photo() {
+                self.mount_error_popup(e.context("update_photo"));
+            }
         }
 
         None
@@ -591,6 +946,9 @@ impl Model {
             DBMsg::SearchTrack(index) => {
                 self.database_update_search_tracks(index);
             }
+            DBMsg::SearchResultHighlighted(index) => {
+                self.database_update_album_cover(index);
+            }
             DBMsg::AddPlaylist(index) => {
                 if !self.dw.search_tracks.is_empty() {
                     if let Some(track) = self.dw.search_tracks.get(index) {
@@ -628,6 +986,43 @@ impl Model {
             DBMsg::AddAllResultsConfirmCancel => {
                 self.umount_results_add_confirm_database();
             }
+
+            DBMsg::BackupsShow => {
+                self.mount_database_backups();
+            }
+            DBMsg::BackupsCloseCancel => {
+                self.umount_database_backups();
+            }
+            DBMsg::BackupCreate => {
+                self.database_create_backup();
+            }
+            DBMsg::RestoreConfirmShow(index) => {
+                self.mount_database_restore_confirm(index);
+            }
+            DBMsg::RestoreConfirmCancel => {
+                self.umount_database_restore_confirm();
+            }
+            DBMsg::Restore(index) => {
+                self.database_restore_backup(index);
+            }
+            DBMsg::ToggleMark(index) => {
+                if let Some(track) = self.dw.search_tracks.get(index) {
+                    let file = track.as_pathbuf();
+                    self.update_tageditor(TEMsg::ToggleMark(file));
+                }
+            }
+            DBMsg::OpenTagEditor(index) => {
+                if let Some(track) = self.dw.search_tracks.get(index) {
+                    let file = track.as_pathbuf();
+                    self.update_tageditor(TEMsg::Open(file.to_string_lossy().to_string()));
+                }
+            }
+            DBMsg::PreviewTrack(index) => {
+                if let Some(track) = self.dw.search_tracks.get(index) {
+                    let file = track.as_pathbuf();
+                    self.command(TuiCmd::PreviewTrack(file));
+                }
+            }
         }
         None
     }
@@ -663,6 +1058,23 @@ impl Model {
                     self.mount_error_popup(e.context("library remove root"));
                 }
             }
+            LIMsg::Rescan => self.library_rescan(),
+            LIMsg::CycleFilter => self.library_cycle_filter(),
+            LIMsg::OpenExternal(node) => {
+                if let Err(e) = self.open_external(Path::new(&node)) {
+                    self.mount_error_popup(e.context("open external"));
+                }
+            }
+            LIMsg::TreeSelectionChanged => self.library_show_dir_stats(),
+            LIMsg::OrganizeShow => self.organize_preview(),
+            LIMsg::OrganizeApply => self.organize_apply(),
+            LIMsg::OrganizeClose => self.umount_organize(),
+            LIMsg::ToggleAudiobook(node) => self.library_toggle_audiobook(Path::new(&node)),
+            LIMsg::ScanProgress(progress) => self.library_show_scan_progress(progress),
+            LIMsg::DirStatsReady(path, stats) => {
+                self.library.dir_stats.insert(path, Some(stats));
+                self.library_show_dir_stats();
+            }
             LIMsg::TreeNodeReady(vec, focus_node) => {
                 self.library_apply_as_tree(vec, focus_node);
             }
@@ -753,7 +1165,14 @@ impl Model {
                     None,
                 );
             }
-            YTDLMsg::Completed(_url, file) => {
+            YTDLMsg::Completed(url, file) => {
+                if self.enqueue_after_download.remove(&*url) {
+                    if let Some(file) = &file {
+                        if let Err(e) = self.playlist_add(Path::new(file)) {
+                            self.mount_error_popup(e.context("add downloaded video"));
+                        }
+                    }
+                }
                 if self.download_tracker.visible() {
                     return;
                 }
@@ -917,6 +1336,16 @@ impl Model {
                     self.mount_error_popup(e.context("library delete song"));
                 }
             }
+            DeleteConfirmMsg::UndoOk => {
+                self.umount_undo_delete_popup();
+                if let Err(e) = self.trash_undo() {
+                    self.mount_error_popup(e.context("undo delete"));
+                }
+            }
+            DeleteConfirmMsg::UndoCancel => {
+                self.umount_undo_delete_popup();
+                self.last_trashed = None;
+            }
         }
         None
     }
@@ -946,11 +1375,14 @@ impl Model {
                 self.config_server.write().settings.player.loop_mode =
                     self.playback.playlist.cycle_loop_mode();
                 self.playlist_update_title();
+                self.queue_preview_update();
             }
             PLMsg::PlaylistTableBlurDown => match self.layout {
                 TermusicLayout::TreeView => assert!(self.app.active(&Id::Library).is_ok()),
                 TermusicLayout::DataBase => assert!(self.app.active(&Id::DBListCriteria).is_ok()),
-                TermusicLayout::Podcast => assert!(self.app.active(&Id::Lyric).is_ok()),
+                TermusicLayout::Podcast | TermusicLayout::Radio => {
+                    assert!(self.app.active(&Id::Lyric).is_ok());
+                }
             },
             PLMsg::NextSong => {
                 self.command(TuiCmd::SkipNext);
@@ -971,12 +1403,45 @@ impl Model {
             PLMsg::AddRandomTracks => {
                 self.playlist_add_random_tracks();
             }
+            PLMsg::SaveQueue => {
+                self.playlist_save_queue();
+            }
+            PLMsg::RestoreQueue => {
+                self.playlist_restore_queue();
+            }
+            PLMsg::ToggleAutoQueue => {
+                self.playlist_toggle_auto_queue();
+            }
+            PLMsg::QueuesShow => {
+                self.playlist_show_queues();
+            }
+            PLMsg::QueuesClose => {
+                self.umount_queues();
+            }
+            PLMsg::QueueSwitch(name) => {
+                self.playlist_switch_queue(name.clone());
+            }
+            PLMsg::QueueDelete(name) => {
+                self.playlist_delete_queue(name.clone());
+            }
+            PLMsg::QueueNewShow => {
+                self.mount_queue_new();
+            }
+            PLMsg::QueueNewCloseCancel => {
+                self.umount_queue_new();
+            }
+            PLMsg::QueueNewCloseOk(name) => {
+                self.playlist_create_queue(name.clone());
+            }
             PLMsg::PlaylistTableBlurUp => match self.layout {
                 TermusicLayout::TreeView => assert!(self.app.active(&Id::Library).is_ok()),
                 TermusicLayout::DataBase => {
                     assert!(self.app.active(&Id::DBListSearchTracks).is_ok());
                 }
                 TermusicLayout::Podcast => assert!(self.app.active(&Id::Episode).is_ok()),
+                TermusicLayout::Radio => {
+                    assert!(self.app.active(&Id::RadioSearchResults).is_ok());
+                }
             },
         }
     }
@@ -984,13 +1449,11 @@ impl Model {
     // show a popup for playing song
     pub fn update_playing_song(&mut self) {
         if let Some(track) = self.playback.current_track() {
+            let title = track.format_title(&self.config_server.read().settings.player.title_format);
+            self.show_toast("Currently Playing", &title, None);
             if self.layout == TermusicLayout::Podcast {
-                let title = track.title().unwrap_or("Unknown Episode");
-                self.update_show_message_timeout("Currently Playing", title, None);
                 return;
             }
-            let name = track.title().map_or_else(|| track.id_str(), Into::into);
-            self.update_show_message_timeout("Currently Playing", &name, None);
 
             // TODO: is there a better way to update only a single / 2 columns (prev/next) instead of re-doing the whole playist; OR a way to decide at draw-time?
             // sync playlist to update any dynamic parts added to the columns (like current playing symbol)
@@ -998,30 +1461,6 @@ impl Model {
         }
     }
 
-    /// Show a message with a `title` and `text`, and hide it again after `time_out` or 10 seconds.
-    ///
-    /// This function requires to run in a tokio context.
-    pub fn update_show_message_timeout(&self, title: &str, text: &str, time_out: Option<u64>) {
-        let title_string = title.to_string();
-        let text_string = text.to_string();
-        let tx = self.tx_to_main.clone();
-        let delay = time_out.unwrap_or(10);
-
-        Handle::current().spawn(async move {
-            let _ = tx.send(Msg::Notification(NotificationMsg::MessageShow((
-                title_string.clone(),
-                text_string.clone(),
-            ))));
-
-            sleep(Duration::from_secs(delay)).await;
-
-            let _ = tx.send(Msg::Notification(NotificationMsg::MessageHide((
-                title_string,
-                text_string,
-            ))));
-        });
-    }
-
     pub fn update_layout_for_current_track(&mut self) {
         if let Some(track) = self.playback.current_track() {
             match track.media_type() {
@@ -1031,12 +1470,11 @@ impl Model {
                     }
                     self.update_layout(MainLayoutMsg::Podcast);
                 }
-                MediaTypesSimple::Music | MediaTypesSimple::LiveRadio => match self.layout {
-                    TermusicLayout::TreeView | TermusicLayout::DataBase => {}
-                    TermusicLayout::Podcast => {
+                MediaTypesSimple::Music | MediaTypesSimple::LiveRadio => {
+                    if self.layout == TermusicLayout::Podcast {
                         self.update_layout(MainLayoutMsg::TreeView);
                     }
-                },
+                }
             }
         }
     }
@@ -1044,34 +1482,113 @@ impl Model {
     /// Handle & update [`SavePlaylistMsg`] related components.
     fn update_save_playlist(&mut self, msg: SavePlaylistMsg) -> Option<Msg> {
         match msg {
-            SavePlaylistMsg::PopupShow => {
-                if let Err(e) = self.mount_save_playlist() {
+            SavePlaylistMsg::PopupShow(format) => {
+                if let Err(e) = self.mount_save_playlist(format) {
                     self.mount_error_popup(e.context("mount save playlist"));
                 }
             }
             SavePlaylistMsg::PopupCloseCancel => {
                 self.umount_save_playlist();
             }
-            SavePlaylistMsg::PopupCloseOk(filename) => {
+            SavePlaylistMsg::PopupCloseOk(filename, format) => {
                 self.umount_save_playlist();
-                if let Err(e) = self.playlist_save_m3u_before(&filename) {
-                    self.mount_error_popup(e.context("save m3u playlist before"));
+                if let Err(e) = self.playlist_save_before(&filename, format) {
+                    self.mount_error_popup(e.context("save playlist before"));
                 }
             }
-            SavePlaylistMsg::PopupUpdate(filename) => {
-                if let Err(e) = self.remount_save_playlist_label(&filename) {
+            SavePlaylistMsg::PopupUpdate(filename, format) => {
+                if let Err(e) = self.remount_save_playlist_label(&filename, format) {
                     self.mount_error_popup(e.context("remount save playlist label"));
                 }
             }
             SavePlaylistMsg::ConfirmCloseCancel => {
                 self.umount_save_playlist_confirm();
             }
-            SavePlaylistMsg::ConfirmCloseOk(filename) => {
-                if let Err(e) = self.playlist_save_m3u(Path::new(&filename)) {
-                    self.mount_error_popup(e.context("save m3u playlist"));
+            SavePlaylistMsg::ConfirmCloseOk(filename, format) => {
+                if let Err(e) = self.playlist_save(Path::new(&filename), format) {
+                    self.mount_error_popup(e.context("save playlist"));
                 }
                 self.umount_save_playlist_confirm();
             }
+            SavePlaylistMsg::FormatShow => {
+                self.mount_playlist_format();
+            }
+            SavePlaylistMsg::FormatCloseCancel => {
+                self.umount_playlist_format();
+            }
+            SavePlaylistMsg::FormatCloseOk(format) => {
+                self.umount_playlist_format();
+                if let Err(e) = self.mount_save_playlist(format) {
+                    self.mount_error_popup(e.context("mount save playlist"));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Handle & update [`ImportPlaylistMsg`] related components.
+    fn update_import_playlist(&mut self, msg: ImportPlaylistMsg) -> Option<Msg> {
+        match msg {
+            ImportPlaylistMsg::PopupShow => {
+                self.mount_import_playlist();
+            }
+            ImportPlaylistMsg::PopupCloseCancel => {
+                self.umount_import_playlist();
+            }
+            ImportPlaylistMsg::PopupCloseOk(path) => {
+                self.umount_import_playlist();
+                if let Err(e) = self.playlist_import(&path) {
+                    self.mount_error_popup(e.context("import playlist"));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Handle & update [`TrackMenuMsg`] related components.
+    fn update_track_menu(&mut self, msg: TrackMenuMsg) -> Option<Msg> {
+        match msg {
+            TrackMenuMsg::Show(target) => {
+                if let Err(e) = self.mount_track_menu(target) {
+                    self.mount_error_popup(e.context("track menu"));
+                }
+            }
+            TrackMenuMsg::CloseOk(target, action) => {
+                self.umount_track_menu();
+                if let Err(e) = self.track_menu_action(&target, action) {
+                    self.mount_error_popup(e.context("track menu"));
+                }
+            }
+            TrackMenuMsg::Run(target, action) => {
+                if let Err(e) = self.track_menu_action(&target, action) {
+                    self.mount_error_popup(e.context("track menu"));
+                }
+            }
+            TrackMenuMsg::CloseCancel => self.umount_track_menu(),
+            TrackMenuMsg::InfoClose => self.umount_track_info(),
+        }
+
+        None
+    }
+
+    /// Handle all [`LyricEditorMsg`] messages. Sub-function for [`update`](Self::update).
+    fn update_lyric_editor(&mut self, msg: LyricEditorMsg) -> Option<Msg> {
+        match msg {
+            LyricEditorMsg::Show => self.mount_lyric_editor(self.lyric_editor_current_index()),
+            LyricEditorMsg::Close => self.umount_lyric_editor(),
+            LyricEditorMsg::EditShow(index) => self.mount_lyric_caption_input(index),
+            LyricEditorMsg::EditCloseCancel => {
+                self.umount_lyric_caption_input();
+                self.app.active(&Id::LyricEditorPopup).ok();
+            }
+            LyricEditorMsg::EditCloseOk(index, text) => {
+                self.umount_lyric_caption_input();
+                self.app.active(&Id::LyricEditorPopup).ok();
+                self.lyric_edit_caption(index, &text);
+            }
+            LyricEditorMsg::Remove(index) => self.lyric_remove_caption(index),
         }
 
         None
@@ -1082,6 +1599,7 @@ impl Model {
         match msg {
             ServerReqResponse::GetProgress(response) => {
                 let pprogress: PlayerProgress = response.progress.unwrap_or_default().into();
+                self.playback.set_ab_repeat(pprogress.ab_repeat);
                 self.progress_update(
                     pprogress.position,
                     pprogress.total_duration.unwrap_or_default(),
@@ -1094,6 +1612,71 @@ impl Model {
                 // "GetProgress" is, as of ~termusic 0.11.0~0.12.0, only called initially or having missed events, so everything should be reloaded.
                 self.player_update_current_track_after();
             }
+            ServerReqResponse::OutputDevices(output_devices) => {
+                self.config_editor_set_output_devices(output_devices.devices);
+            }
+            ServerReqResponse::Capabilities(capabilities) => {
+                if capabilities.guest {
+                    self.config_tui.write().guest = true;
+                }
+                self.playback
+                    .set_supported_extensions(capabilities.extensions);
+                self.playlist_sync();
+            }
+            ServerReqResponse::AutoQueue(enabled) => {
+                self.handle_auto_queue_toggled(enabled);
+            }
+            ServerReqResponse::CastRenderers(renderers) => {
+                // the popup may have been closed while searching
+                if self.app.mounted(&Id::CastRendererPopup) {
+                    self.mount_cast_renderers(Some(&renderers));
+                }
+            }
+            ServerReqResponse::AudioInfo(info) => {
+                self.mount_audio_info(&info);
+            }
+            ServerReqResponse::Waveform(waveform) => {
+                self.progress_waveform_received(waveform);
+            }
+            ServerReqResponse::ChapterJump(jump) => {
+                let text = match jump.chapter {
+                    Some(chapter) => format!(
+                        "Chapter {}/{}: {}",
+                        jump.index + 1,
+                        jump.total,
+                        chapter.title
+                    ),
+                    None => "no chapter to jump to".to_string(),
+                };
+                self.show_message_timeout_label_help(text, None, None, Some(3));
+            }
+            ServerReqResponse::TrackSpeed(track_speed) => {
+                #[allow(clippy::cast_precision_loss)] // speed is at most "MAX_SPEED"
+                let speed = track_speed.speed as f32 / 10.0;
+                let text = if track_speed.pinned {
+                    format!("Track speed kept at {speed:.1}x")
+                } else {
+                    format!("Track plays at the global speed of {speed:.1}x")
+                };
+                self.show_message_timeout_label_help(text, None, None, Some(3));
+            }
+            ServerReqResponse::Queues(queues) => {
+                self.handle_queues(&queues);
+            }
+            ServerReqResponse::Bookmarks(bookmarks) => {
+                if self.app.mounted(&Id::BookmarkPopup) {
+                    self.mount_bookmarks(Some(&bookmarks));
+                }
+            }
+            ServerReqResponse::Organize { dry_run, result } => {
+                self.handle_organize(dry_run, result);
+            }
+            ServerReqResponse::PodcastMerged { target, result } => {
+                self.podcast_feed_merged(target, &result);
+            }
+            ServerReqResponse::PodcastError(err) => {
+                self.mount_error_popup(anyhow!(err).context("podcast"));
+            }
             ServerReqResponse::FullPlaylist(playlist_tracks) => {
                 info!("Processing Playlist from server");
                 let current_track_index = playlist_tracks.current_track_index;
@@ -1141,15 +1724,26 @@ impl Model {
                 // handle "no more tracks / stopped" in this
                 if self.playback.is_stopped() {
                     self.playback.clear_current_track();
+                    self.playback.set_chapters(Vec::new());
+                    self.playback.set_stream_info(StreamInfo::default());
                     self.lyric_update_title();
                     self.lyric_update();
+                    self.playback.set_ab_repeat(AbRepeat::default());
                     self.progress_update(Some(Duration::ZERO), Duration::ZERO);
+                    self.progress_update_waveform();
                 }
 
                 self.progress_update_title();
             }
             UpdateEvents::TrackChanged(track_changed_info) => {
+                self.playback.set_chapters(track_changed_info.chapters);
+                let stream_info_changed =
+                    *self.playback.stream_info() != track_changed_info.stream_info;
+                self.playback
+                    .set_stream_info(track_changed_info.stream_info);
+
                 if let Some(progress) = track_changed_info.progress {
+                    self.playback.set_ab_repeat(progress.ab_repeat);
                     self.progress_update(
                         progress.position,
                         progress.total_duration.unwrap_or_default(),
@@ -1170,12 +1764,17 @@ impl Model {
                     // matching that the current track is actually radio, is in the function itself.
                     self.lyric_update_for_radio("");
                 }
+
+                if stream_info_changed {
+                    self.progress_update_title();
+                }
             }
             UpdateEvents::GaplessChanged { gapless } => {
                 self.config_server.write().settings.player.gapless = gapless;
                 self.progress_update_title();
             }
             UpdateEvents::Progress(progress) => {
+                self.playback.set_ab_repeat(progress.ab_repeat);
                 self.progress_update(
                     progress.position,
                     progress.total_duration.unwrap_or_default(),
@@ -1186,6 +1785,28 @@ impl Model {
                     self.mount_error_popup(err);
                 }
             }
+            UpdateEvents::PodcastsChanged => {
+                // changed by the server, by this or another client
+                if let Err(err) = self.podcast.reload(&self.config_server.read()) {
+                    self.mount_error_popup(err.context("podcast reload"));
+                }
+                self.podcast_sync_feeds_and_episodes();
+                self.playlist_sync();
+            }
+            UpdateEvents::PodcastProgress(progress) => {
+                self.podcast_handle_progress(progress);
+            }
+            UpdateEvents::LibraryChanged => {
+                // the server already updated the database, only the view needs to be re-read
+                self.library.dir_stats.clear();
+                self.library_scan_dir(&self.library.tree_path, None);
+            }
+            UpdateEvents::TrackFavoriteChanged { .. } => {
+                // the favorite flag is read from the database whenever it is shown
+            }
+            UpdateEvents::WaveformChanged(waveform) => {
+                self.progress_waveform_received(waveform);
+            }
         }
 
         None
@@ -1212,6 +1833,12 @@ impl Model {
             UpdatePlaylistEvents::PlaylistShuffled(shuffled) => {
                 self.handle_playlist_shuffled(shuffled)?;
             }
+            UpdatePlaylistEvents::PlaylistReplaced(replaced) => {
+                self.handle_playlist_replaced(replaced)?;
+            }
+            UpdatePlaylistEvents::PlaylistQueueSwitched(switched) => {
+                self.handle_playlist_queue_switched(switched)?;
+            }
         }
 
         Ok(())
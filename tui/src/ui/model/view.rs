@@ -2,32 +2,46 @@ use std::path::Path;
 use std::time::Duration;
 
 use anyhow::{Result, bail};
+use termusiclib::common::const_unknown::{UNKNOWN_ARTIST, UNKNOWN_TITLE};
 use termusiclib::config::SharedTuiSettings;
+use termusiclib::playlist::PlaylistFormat;
 use termusiclib::utils::get_parent_folder;
 use tokio::runtime::Handle;
 use tokio::sync::mpsc::UnboundedReceiver;
 use tui_realm_treeview::Tree;
 use tuirealm::EventListenerCfg;
 use tuirealm::props::{AttrValue, Attribute, Color, PropPayload, PropValue, TextSpan};
-use tuirealm::ratatui::layout::{Constraint, Layout};
-use tuirealm::ratatui::widgets::Clear;
+use tuirealm::ratatui::layout::{Constraint, Layout, Rect, Size};
+use tuirealm::ratatui::style::{Style, Stylize};
+use tuirealm::ratatui::text::Line;
+use tuirealm::ratatui::widgets::{Clear, Paragraph, Wrap};
 use tuirealm::{Frame, State, StateValue};
 
 use crate::ui::Application;
 use crate::ui::components::{
     DBListCriteria, DBListSearchResult, DBListSearchTracks, DownloadSpinner, EpisodeList,
-    FeedsList, Footer, GSInputPopup, GSTablePopup, GlobalListener, LabelSpan, Lyric, MusicLibrary,
-    Playlist, Progress, Source,
+    FeedCategoryTabs, FeedsList, Footer, GSInputPopup, GSTablePopup, GlobalListener, LabelSpan,
+    Lyric, MusicLibrary, Playlist, Progress, QueuePreview, RadioStationList, Source,
 };
 use crate::ui::ids::{Id, IdConfigEditor, IdTagEditor};
 use crate::ui::model::ports::rx_main::PortRxMain;
 use crate::ui::model::ports::stream_events::PortStreamEvents;
 use crate::ui::model::{Model, TermusicLayout, UserEvent};
-use crate::ui::msg::{DBMsg, Msg, PCMsg};
+use crate::ui::msg::{DBMsg, Msg, PCMsg, RadioList, RadioMsg};
 use crate::ui::utils::{
     draw_area_in_absolute, draw_area_in_relative, draw_area_top_right_absolute,
 };
 
+/// Terminals narrower than this use the [compact layout](Model::view_layout_compact).
+const COMPACT_LAYOUT_MAX_WIDTH: u16 = 60;
+/// Terminals lower than this use the [compact layout](Model::view_layout_compact).
+const COMPACT_LAYOUT_MAX_HEIGHT: u16 = 15;
+
+/// Get whether a terminal of `size` is too small for the full layouts.
+fn is_compact_size(size: Size) -> bool {
+    size.width < COMPACT_LAYOUT_MAX_WIDTH || size.height < COMPACT_LAYOUT_MAX_HEIGHT
+}
+
 impl Model {
     pub fn init_app(
         tree: &Tree<String>,
@@ -113,6 +127,11 @@ impl Model {
             Box::new(Progress::new(&config.read())),
             Vec::new(),
         )?;
+        app.mount(
+            Id::QueuePreview,
+            Box::new(QueuePreview::new(&config.read())),
+            Vec::new(),
+        )?;
         app.mount(Id::Lyric, Box::new(Lyric::new(config.clone())), Vec::new())?;
 
         app.mount(
@@ -124,6 +143,11 @@ impl Model {
             )),
             Vec::new(),
         )?;
+        app.mount(
+            Id::PodcastCategories,
+            Box::new(FeedCategoryTabs::new(&config.read())),
+            Vec::new(),
+        )?;
 
         app.mount(
             Id::Episode,
@@ -134,6 +158,26 @@ impl Model {
             )),
             Vec::new(),
         )?;
+        app.mount(
+            Id::RadioFavorites,
+            Box::new(RadioStationList::new(
+                config.clone(),
+                RadioList::Favorites,
+                Msg::Radio(RadioMsg::FavoritesBlurDown),
+                Msg::Radio(RadioMsg::FavoritesBlurUp),
+            )),
+            Vec::new(),
+        )?;
+        app.mount(
+            Id::RadioSearchResults,
+            Box::new(RadioStationList::new(
+                config.clone(),
+                RadioList::SearchResults,
+                Msg::Radio(RadioMsg::SearchResultsBlurDown),
+                Msg::Radio(RadioMsg::SearchResultsBlurUp),
+            )),
+            Vec::new(),
+        )?;
         app.mount(
             Id::DownloadSpinner,
             Box::new(DownloadSpinner::new(&config.read())),
@@ -159,21 +203,75 @@ impl Model {
                 return;
             }
 
+            if self.is_compact_layout() {
+                self.view_layout_compact();
+                return;
+            }
+
             match self.layout {
                 TermusicLayout::TreeView => self.view_layout_treeview(),
                 TermusicLayout::DataBase => self.view_layout_database(),
                 TermusicLayout::Podcast => self.view_layout_podcast(),
+                TermusicLayout::Radio => self.view_layout_radio(),
             }
         }
     }
 
+    /// Get whether the terminal is currently too small for the full layouts, and the compact layout is used.
+    ///
+    /// This is checked on every draw, so the full layout is restored as soon as the terminal is large enough again.
+    pub fn is_compact_layout(&self) -> bool {
+        self.terminal.raw().size().is_ok_and(is_compact_size)
+    }
+
+    /// Draw the condensed layout for small terminals, showing only what is playing, the progress and the volume,
+    /// instead of clipping the panes of the full layouts.
+    fn view_layout_compact(&mut self) {
+        let (title, artist) = match self.playback.current_track() {
+            Some(track) if !self.playback.is_stopped() => (
+                track.title().unwrap_or(UNKNOWN_TITLE).to_string(),
+                track.artist().unwrap_or(UNKNOWN_ARTIST).to_string(),
+            ),
+            _ => ("No track is playing".to_string(), String::new()),
+        };
+        let volume = self.config_server.read().settings.player.volume;
+        let (foreground, highlight) = {
+            let config = self.config_tui.read();
+            (
+                config.settings.theme.library_foreground(),
+                config.settings.theme.library_highlight(),
+            )
+        };
+        let info = Paragraph::new(vec![
+            Line::from(title).style(Style::new().fg(highlight).bold()),
+            Line::from(artist).style(Style::new().fg(foreground)),
+            Line::from(format!("Volume: {volume}")).style(Style::new().fg(foreground)),
+        ])
+        .centered()
+        .wrap(Wrap { trim: true });
+
+        self.terminal
+            .raw_mut()
+            .draw(|f| {
+                let [info_area, progress] =
+                    Layout::vertical([Constraint::Min(1), Constraint::Length(3)]).areas(f.area());
+
+                f.render_widget(info, info_area);
+                self.app.view(&Id::Progress, f, progress);
+
+                Self::view_popups(f, &mut self.app);
+            })
+            .expect("Expected to draw without error");
+    }
+
     fn view_layout_podcast(&mut self) {
+        let queue_preview = self.show_queue_preview();
         self.terminal
             .raw_mut()
             .draw(|f| {
                 let [chunks_main, progress, _bottom_help] = Layout::vertical([
                     Constraint::Min(2),
-                    Constraint::Length(3),
+                    Constraint::Length(Self::progress_height(queue_preview)),
                     Constraint::Length(1),
                 ])
                 .areas(f.area());
@@ -188,12 +286,51 @@ impl Model {
                     Layout::vertical([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
                         .areas(center_right);
 
-                self.app.view(&Id::Podcast, f, left_podcasts);
+                let [left_categories, left_feeds] =
+                    Layout::vertical([Constraint::Length(1), Constraint::Min(2)])
+                        .areas(left_podcasts);
+
+                self.app.view(&Id::PodcastCategories, f, left_categories);
+                self.app.view(&Id::Podcast, f, left_feeds);
                 self.app.view(&Id::Episode, f, left_episodes);
 
                 self.app.view(&Id::Playlist, f, right_playlist);
                 self.app.view(&Id::Lyric, f, right_lyric);
-                self.app.view(&Id::Progress, f, progress);
+                Self::view_progress(f, &mut self.app, progress, queue_preview);
+
+                Self::view_layout_commons(f, &mut self.app, self.download_tracker.visible());
+            })
+            .expect("Expected to draw without error");
+    }
+
+    fn view_layout_radio(&mut self) {
+        let queue_preview = self.show_queue_preview();
+        self.terminal
+            .raw_mut()
+            .draw(|f| {
+                let [chunks_main, _bottom_help] =
+                    Layout::vertical([Constraint::Min(2), Constraint::Length(1)]).areas(f.area());
+                let [center_left, center_right] =
+                    Layout::horizontal([Constraint::Ratio(1, 3), Constraint::Ratio(2, 3)])
+                        .areas(chunks_main);
+
+                let [left_favorites, left_search_results] =
+                    Layout::vertical([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
+                        .areas(center_left);
+                let [right_playlist, right_progress, right_lyric] = Layout::vertical([
+                    Constraint::Min(2),
+                    Constraint::Length(Self::progress_height(queue_preview)),
+                    Constraint::Length(4),
+                ])
+                .areas(center_right);
+
+                self.app.view(&Id::RadioFavorites, f, left_favorites);
+                self.app
+                    .view(&Id::RadioSearchResults, f, left_search_results);
+
+                self.app.view(&Id::Playlist, f, right_playlist);
+                Self::view_progress(f, &mut self.app, right_progress, queue_preview);
+                self.app.view(&Id::Lyric, f, right_lyric);
 
                 Self::view_layout_commons(f, &mut self.app, self.download_tracker.visible());
             })
@@ -201,6 +338,7 @@ impl Model {
     }
 
     fn view_layout_database(&mut self) {
+        let queue_preview = self.show_queue_preview();
         self.terminal
             .raw_mut()
             .draw(|f| {
@@ -219,7 +357,7 @@ impl Model {
                 .areas(chunks_main_left);
                 let [right_playlist, right_progress, right_lyric] = Layout::vertical([
                     Constraint::Min(2),
-                    Constraint::Length(3),
+                    Constraint::Length(Self::progress_height(queue_preview)),
                     Constraint::Length(4),
                 ])
                 .areas(chunks_main_right);
@@ -231,7 +369,7 @@ impl Model {
                     .view(&Id::DBListSearchTracks, f, left_search_tracks);
 
                 self.app.view(&Id::Playlist, f, right_playlist);
-                self.app.view(&Id::Progress, f, right_progress);
+                Self::view_progress(f, &mut self.app, right_progress, queue_preview);
                 self.app.view(&Id::Lyric, f, right_lyric);
 
                 Self::view_layout_commons(f, &mut self.app, self.download_tracker.visible());
@@ -240,6 +378,7 @@ impl Model {
     }
 
     fn view_layout_treeview(&mut self) {
+        let queue_preview = self.show_queue_preview();
         self.terminal
             .raw_mut()
             .draw(|f| {
@@ -250,7 +389,7 @@ impl Model {
                         .areas(chunks_main);
                 let [right_playlist, right_progress, right_lyric] = Layout::vertical([
                     Constraint::Min(2),
-                    Constraint::Length(3),
+                    Constraint::Length(Self::progress_height(queue_preview)),
                     Constraint::Length(4),
                 ])
                 .areas(right);
@@ -258,7 +397,7 @@ impl Model {
                 self.app.view(&Id::Library, f, left_library);
 
                 self.app.view(&Id::Playlist, f, right_playlist);
-                self.app.view(&Id::Progress, f, right_progress);
+                Self::view_progress(f, &mut self.app, right_progress, queue_preview);
                 self.app.view(&Id::Lyric, f, right_lyric);
 
                 Self::view_layout_commons(f, &mut self.app, self.download_tracker.visible());
@@ -266,6 +405,39 @@ impl Model {
             .expect("Expected to draw without error");
     }
 
+    /// Get whether the [`QueuePreview`] is shown below the [`Progress`].
+    fn show_queue_preview(&self) -> bool {
+        self.config_tui
+            .read()
+            .settings
+            .theme
+            .style
+            .progress
+            .show_queue_preview
+    }
+
+    /// Get the height of the [`Progress`], including the [`QueuePreview`] if shown.
+    fn progress_height(queue_preview: bool) -> u16 {
+        3 + u16::from(queue_preview)
+    }
+
+    /// Draw the [`Progress`] in `area`, with the [`QueuePreview`] in the last line if shown.
+    fn view_progress(
+        f: &mut Frame<'_>,
+        app: &mut Application<Id, Msg, UserEvent>,
+        area: Rect,
+        queue_preview: bool,
+    ) {
+        if queue_preview {
+            let [progress, preview] =
+                Layout::vertical([Constraint::Length(3), Constraint::Length(1)]).areas(area);
+            app.view(&Id::Progress, f, progress);
+            app.view(&Id::QueuePreview, f, preview);
+        } else {
+            app.view(&Id::Progress, f, area);
+        }
+    }
+
     /// Draw the footer in the last line.
     fn view_common_footer(
         f: &mut Frame<'_>,
@@ -275,7 +447,7 @@ impl Model {
         let [_content, bottom_label] =
             Layout::vertical([Constraint::Min(2), Constraint::Length(1)]).areas(f.area());
 
-        if downloading_visible {
+        let label_area = if downloading_visible {
             let [_spacer, spinner, remainder] = Layout::horizontal([
                 Constraint::Length(1),
                 Constraint::Length(1),
@@ -284,16 +456,26 @@ impl Model {
             .areas(bottom_label);
 
             app.view(&Id::DownloadSpinner, f, spinner);
-            app.view(&Id::Label, f, remainder);
+            remainder
+        } else {
+            bottom_label
+        };
+
+        if app.mounted(&Id::LibraryScanProgress) {
+            let [label, scan] =
+                Layout::horizontal([Constraint::Min(10), Constraint::Length(40)]).areas(label_area);
+
+            app.view(&Id::Label, f, label);
+            app.view(&Id::LibraryScanProgress, f, scan);
         } else {
-            app.view(&Id::Label, f, bottom_label);
+            app.view(&Id::Label, f, label_area);
         }
     }
 
     /// Draw any popup.
     fn view_popups(f: &mut Frame<'_>, app: &mut Application<Id, Msg, UserEvent>) {
         if app.mounted(&Id::QuitPopup) {
-            let popup = draw_area_in_absolute(f.area(), 30, 3);
+            let popup = draw_area_in_absolute(f.area(), 70, 3);
             f.render_widget(Clear, popup);
             app.view(&Id::QuitPopup, f, popup);
         } else if app.mounted(&Id::HelpPopup) {
@@ -308,10 +490,45 @@ impl Model {
             let popup = draw_area_in_absolute(f.area(), 72, 3);
             f.render_widget(Clear, popup);
             app.view(&Id::DeleteConfirmInputPopup, f, popup);
+        } else if app.mounted(&Id::UndoDeletePopup) {
+            let popup = draw_area_in_absolute(f.area(), 30, 3);
+            f.render_widget(Clear, popup);
+            app.view(&Id::UndoDeletePopup, f, popup);
         } else if app.mounted(&Id::FeedDeleteConfirmRadioPopup) {
             let popup = draw_area_in_absolute(f.area(), 60, 3);
             f.render_widget(Clear, popup);
             app.view(&Id::FeedDeleteConfirmRadioPopup, f, popup);
+        } else if app.mounted(&Id::EpisodeDownloadConfirmPopup) {
+            let popup = draw_area_in_absolute(f.area(), 76, 3);
+            f.render_widget(Clear, popup);
+            app.view(&Id::EpisodeDownloadConfirmPopup, f, popup);
+        } else if app.mounted(&Id::EpisodeMenuPopup) {
+            // one row per action, plus the header and borders
+            let popup = draw_area_in_absolute(f.area(), 50, 11);
+            f.render_widget(Clear, popup);
+            app.view(&Id::EpisodeMenuPopup, f, popup);
+        } else if app.mounted(&Id::EpisodeInfoPopup) {
+            let popup = draw_area_in_absolute(f.area(), 100, 10);
+            f.render_widget(Clear, popup);
+            app.view(&Id::EpisodeInfoPopup, f, popup);
+        } else if app.mounted(&Id::FeedInfoPopup) {
+            // one row per detail, plus the borders
+            let popup = draw_area_in_absolute(f.area(), 100, 11);
+            f.render_widget(Clear, popup);
+            app.view(&Id::FeedInfoPopup, f, popup);
+        } else if app.mounted(&Id::FeedMergeTablePopup) {
+            let popup = draw_area_in_relative(f.area(), 65, 68);
+            f.render_widget(Clear, popup);
+            app.view(&Id::FeedMergeTablePopup, f, popup);
+        } else if app.mounted(&Id::FeedMergeConfirmPopup) {
+            let popup = draw_area_in_absolute(f.area(), 100, 3);
+            f.render_widget(Clear, popup);
+            app.view(&Id::FeedMergeConfirmPopup, f, popup);
+        } else if app.mounted(&Id::FeedSettingsPopup) {
+            // one row per setting, plus the borders
+            let popup = draw_area_in_absolute(f.area(), 60, 5);
+            f.render_widget(Clear, popup);
+            app.view(&Id::FeedSettingsPopup, f, popup);
         } else if app.mounted(&Id::FeedDeleteConfirmInputPopup) {
             let popup = draw_area_in_absolute(f.area(), 60, 3);
             f.render_widget(Clear, popup);
@@ -334,6 +551,72 @@ impl Model {
             let popup = draw_area_in_relative(f.area(), 65, 68);
             f.render_widget(Clear, popup);
             app.view(&Id::YoutubeSearchTablePopup, f, popup);
+        } else if app.mounted(&Id::ChapterListPopup) {
+            let popup = draw_area_in_relative(f.area(), 50, 60);
+            f.render_widget(Clear, popup);
+            app.view(&Id::ChapterListPopup, f, popup);
+        } else if app.mounted(&Id::GotoTimePopup) {
+            let popup = draw_area_in_absolute(f.area(), 50, 3);
+            f.render_widget(Clear, popup);
+            app.view(&Id::GotoTimePopup, f, popup);
+        } else if app.mounted(&Id::AudioInfoPopup) {
+            // one row per detail, plus the borders
+            let popup = draw_area_in_absolute(f.area(), 100, 11);
+            f.render_widget(Clear, popup);
+            app.view(&Id::AudioInfoPopup, f, popup);
+        } else if app.mounted(&Id::CastRendererPopup) {
+            let popup = draw_area_in_relative(f.area(), 50, 40);
+            f.render_widget(Clear, popup);
+            app.view(&Id::CastRendererPopup, f, popup);
+        } else if app.mounted(&Id::QueuePopup) {
+            let popup = draw_area_in_relative(f.area(), 50, 40);
+            f.render_widget(Clear, popup);
+            app.view(&Id::QueuePopup, f, popup);
+
+            if app.mounted(&Id::QueueNewPopup) {
+                let popup = draw_area_in_absolute(f.area(), 60, 3);
+                f.render_widget(Clear, popup);
+                app.view(&Id::QueueNewPopup, f, popup);
+            }
+        } else if app.mounted(&Id::BookmarkPopup) {
+            let popup = draw_area_in_relative(f.area(), 50, 40);
+            f.render_widget(Clear, popup);
+            app.view(&Id::BookmarkPopup, f, popup);
+
+            if app.mounted(&Id::BookmarkNewPopup) {
+                let popup = draw_area_in_absolute(f.area(), 60, 3);
+                f.render_widget(Clear, popup);
+                app.view(&Id::BookmarkNewPopup, f, popup);
+            }
+        } else if app.mounted(&Id::OrganizePopup) {
+            let popup = draw_area_in_relative(f.area(), 80, 60);
+            f.render_widget(Clear, popup);
+            app.view(&Id::OrganizePopup, f, popup);
+        } else if app.mounted(&Id::TagEditor(IdTagEditor::BulkEditor)) {
+            // one row per field and the save row, plus the borders
+            let popup = draw_area_in_absolute(f.area(), 80, 7);
+            f.render_widget(Clear, popup);
+            app.view(&Id::TagEditor(IdTagEditor::BulkEditor), f, popup);
+
+            if app.mounted(&Id::TagEditor(IdTagEditor::BulkField)) {
+                let popup = draw_area_in_absolute(f.area(), 76, 3);
+                f.render_widget(Clear, popup);
+                app.view(&Id::TagEditor(IdTagEditor::BulkField), f, popup);
+            }
+        } else if app.mounted(&Id::TagEditor(IdTagEditor::BulkProgress)) {
+            let popup = draw_area_in_absolute(f.area(), 60, 3);
+            f.render_widget(Clear, popup);
+            app.view(&Id::TagEditor(IdTagEditor::BulkProgress), f, popup);
+        } else if app.mounted(&Id::LyricEditorPopup) {
+            let popup = draw_area_in_relative(f.area(), 60, 60);
+            f.render_widget(Clear, popup);
+            app.view(&Id::LyricEditorPopup, f, popup);
+
+            if app.mounted(&Id::LyricCaptionInputPopup) {
+                let popup = draw_area_in_absolute(f.area(), 60, 3);
+                f.render_widget(Clear, popup);
+                app.view(&Id::LyricCaptionInputPopup, f, popup);
+            }
         } else if app.mounted(&Id::PodcastSearchTablePopup) {
             let popup = draw_area_in_relative(f.area(), 65, 68);
             f.render_widget(Clear, popup);
@@ -349,25 +632,85 @@ impl Model {
             let popup = draw_area_in_absolute(f.area(), 40, 3);
             f.render_widget(Clear, popup);
             app.view(&Id::SavePlaylistConfirm, f, popup);
+        } else if app.mounted(&Id::PlaylistFormatPopup) {
+            let popup = draw_area_in_absolute(f.area(), 40, 8);
+            f.render_widget(Clear, popup);
+            app.view(&Id::PlaylistFormatPopup, f, popup);
+        } else if app.mounted(&Id::ImportPlaylistPopup) {
+            let popup = draw_area_in_absolute(f.area(), 80, 3);
+            f.render_widget(Clear, popup);
+            app.view(&Id::ImportPlaylistPopup, f, popup);
+        } else if app.mounted(&Id::TrackMenuPopup) {
+            // one row per action, plus the header and borders
+            let popup = draw_area_in_absolute(f.area(), 50, 11);
+            f.render_widget(Clear, popup);
+            app.view(&Id::TrackMenuPopup, f, popup);
+        } else if app.mounted(&Id::TrackInfoPopup) {
+            let popup = draw_area_in_absolute(f.area(), 100, 7);
+            f.render_widget(Clear, popup);
+            app.view(&Id::TrackInfoPopup, f, popup);
         } else if app.mounted(&Id::PodcastAddPopup) {
             let popup = draw_area_in_absolute(f.area(), 65, 3);
             f.render_widget(Clear, popup);
             app.view(&Id::PodcastAddPopup, f, popup);
+        } else if app.mounted(&Id::RadioSearchPopup) {
+            let popup = draw_area_in_absolute(f.area(), 80, 3);
+            f.render_widget(Clear, popup);
+            app.view(&Id::RadioSearchPopup, f, popup);
         } else if app.mounted(&Id::DatabaseAddConfirmPopup) {
             let popup = draw_area_in_absolute(f.area(), 60, 3);
             f.render_widget(Clear, popup);
             app.view(&Id::DatabaseAddConfirmPopup, f, popup);
-        }
-        if app.mounted(&Id::MessagePopup) {
-            let popup = draw_area_top_right_absolute(f.area(), 25, 4);
+        } else if app.mounted(&Id::DatabaseBackupPopup) {
+            let popup = draw_area_in_relative(f.area(), 50, 60);
             f.render_widget(Clear, popup);
-            app.view(&Id::MessagePopup, f, popup);
+            app.view(&Id::DatabaseBackupPopup, f, popup);
+
+            if app.mounted(&Id::DatabaseRestoreConfirmPopup) {
+                let popup = draw_area_in_absolute(f.area(), 60, 3);
+                f.render_widget(Clear, popup);
+                app.view(&Id::DatabaseRestoreConfirmPopup, f, popup);
+            }
         }
-        if app.mounted(&Id::ErrorPopup) {
-            let popup = draw_area_in_absolute(f.area(), 50, 4);
+        Self::view_toasts(f, app);
+        Self::view_error_popups(f, app);
+    }
+
+    /// Draw the error history and error popup, on top of everything.
+    pub fn view_error_popups(f: &mut Frame<'_>, app: &mut Application<Id, Msg, UserEvent>) {
+        if app.mounted(&Id::ErrorHistoryPopup) {
+            let popup = draw_area_in_relative(f.area(), 80, 70);
             f.render_widget(Clear, popup);
-            app.view(&Id::ErrorPopup, f, popup);
+            app.view(&Id::ErrorHistoryPopup, f, popup);
         }
+        let Ok(Some(AttrValue::Payload(PropPayload::Vec(rows)))) =
+            app.query(&Id::ErrorPopup, Attribute::Text)
+        else {
+            return;
+        };
+        // one row per line, plus the borders; longer errors can be scrolled
+        let height = u16::try_from(rows.len() + 2)
+            .unwrap_or(u16::MAX)
+            .clamp(4, 14);
+        let popup = draw_area_in_absolute(f.area(), 60, height);
+        f.render_widget(Clear, popup);
+        app.view(&Id::ErrorPopup, f, popup);
+    }
+
+    /// Draw the toasts in the top-right corner, on top of everything except the error popup.
+    pub fn view_toasts(f: &mut Frame<'_>, app: &mut Application<Id, Msg, UserEvent>) {
+        let Ok(Some(AttrValue::Table(rows))) = app.query(&Id::Toasts, Attribute::Content) else {
+            return;
+        };
+        // one row per line, plus the borders
+        let height = u16::try_from(rows.len() + 2)
+            .unwrap_or(u16::MAX)
+            .min(f.area().height.saturating_sub(2));
+        let width = 40.min(f.area().width.saturating_sub(2));
+
+        let popup = draw_area_top_right_absolute(f.area(), width, height);
+        f.render_widget(Clear, popup);
+        app.view(&Id::Toasts, f, popup);
     }
 
     /// Draw common things, like the bottom label and popups.
@@ -436,7 +779,11 @@ impl Model {
             .expect("Expected to remount without error");
     }
 
-    pub fn remount_save_playlist_label(&mut self, filename: &str) -> Result<()> {
+    pub fn remount_save_playlist_label(
+        &mut self,
+        filename: &str,
+        format: PlaylistFormat,
+    ) -> Result<()> {
         let current_node: String = match self.app.state(&Id::Library).ok().unwrap() {
             State::One(StateValue::String(id)) => id,
             _ => bail!("Invalid node selected in library"),
@@ -463,7 +810,7 @@ impl Model {
                             .fg(config.settings.theme.fallback_foreground())
                             .bold(),
                         TextSpan::new(filename).fg(Color::Cyan).bold(),
-                        TextSpan::new(".m3u")
+                        TextSpan::new(format!(".{}", format.extension()))
                             .fg(config.settings.theme.fallback_foreground())
                             .bold(),
                     ],
@@ -241,34 +241,12 @@ impl Model {
         }
     }
 
-    #[allow(clippy::too_many_lines)]
     pub fn youtube_dl(&mut self, url: &str) -> Result<()> {
         let mut path: PathBuf = std::env::temp_dir();
         if let Ok(State::One(StateValue::String(node_id))) = self.app.state(&Id::Library) {
             path = get_parent_folder(Path::new(&node_id)).to_path_buf();
         }
-        let config_tui = self.config_tui.read();
-        let mut args = vec![
-            Arg::new("--extract-audio"),
-            // Arg::new_with_arg("--audio-format", "vorbis"),
-            Arg::new_with_arg("--audio-format", "mp3"),
-            Arg::new("--add-metadata"),
-            Arg::new("--embed-thumbnail"),
-            Arg::new_with_arg("--metadata-from-title", "%(artist) - %(title)s"),
-            #[cfg(target_os = "windows")]
-            Arg::new("--restrict-filenames"),
-            Arg::new("--write-sub"),
-            Arg::new("--all-subs"),
-            Arg::new_with_arg("--convert-subs", "lrc"),
-            Arg::new_with_arg("--output", "%(title).90s.%(ext)s"),
-        ];
-        let extra_args = parse_args(&config_tui.settings.ytdlp.extra_args)
-            .context("Parsing config `extra_ytdlp_args`")?;
-        let mut extra_args_parsed = convert_to_args(extra_args);
-        if !extra_args_parsed.is_empty() {
-            args.append(&mut extra_args_parsed);
-        }
-
+        let args = ytdlp_args(&self.config_tui.read().settings.ytdlp.extra_args)?;
         let ytd = YoutubeDL::new(&path, args, url)?;
         let tx = self.tx_to_main.clone();
 
@@ -295,16 +273,13 @@ impl Model {
                     if let Some(file_fullname) =
                         extract_filepath(result.output(), &path.to_string_lossy())
                     {
+                        post_process_download(&path, &file_fullname);
+
                         tx.send(Msg::YoutubeSearch(YSMsg::Download(YTDLMsg::Completed(
                             url,
-                            Some(file_fullname.clone()),
+                            Some(file_fullname),
                         ))))
                         .ok();
-
-                        // here we remove downloaded live_chat.json file
-                        remove_downloaded_json(&path, &file_fullname);
-
-                        embed_downloaded_lrc(&path, &file_fullname);
                     } else {
                         tx.send(Msg::YoutubeSearch(YSMsg::Download(YTDLMsg::Completed(
                             url, None,
@@ -331,6 +306,54 @@ impl Model {
     }
 }
 
+/// Download `url` with yt-dlp into `dir` and wait until it is done, like [`Model::youtube_dl`] without the TUI messages.
+///
+/// Returns the path of the downloaded file, if it could be found in the output of yt-dlp.
+pub fn youtube_dl_blocking(dir: &Path, url: &str, extra_args: &str) -> Result<Option<String>> {
+    let ytd = YoutubeDL::new(dir, ytdlp_args(extra_args)?, url)?;
+    let result = ytd.download()?;
+
+    let file_fullname = extract_filepath(result.output(), &dir.to_string_lossy());
+    if let Some(file_fullname) = &file_fullname {
+        post_process_download(dir, file_fullname);
+    }
+
+    Ok(file_fullname)
+}
+
+/// Get the arguments for yt-dlp to download the audio of a video, with `extra_args` from the config appended.
+fn ytdlp_args(extra_args: &str) -> Result<Vec<Arg>> {
+    let mut args = vec![
+        Arg::new("--extract-audio"),
+        // Arg::new_with_arg("--audio-format", "vorbis"),
+        Arg::new_with_arg("--audio-format", "mp3"),
+        Arg::new("--add-metadata"),
+        Arg::new("--embed-thumbnail"),
+        Arg::new_with_arg("--metadata-from-title", "%(artist) - %(title)s"),
+        #[cfg(target_os = "windows")]
+        Arg::new("--restrict-filenames"),
+        Arg::new("--write-sub"),
+        Arg::new("--all-subs"),
+        Arg::new_with_arg("--convert-subs", "lrc"),
+        Arg::new_with_arg("--output", "%(title).90s.%(ext)s"),
+    ];
+    let extra_args = parse_args(extra_args).context("Parsing config `extra_ytdlp_args`")?;
+    let mut extra_args_parsed = convert_to_args(extra_args);
+    if !extra_args_parsed.is_empty() {
+        args.append(&mut extra_args_parsed);
+    }
+
+    Ok(args)
+}
+
+/// Clean up after the download of `file_fullname` into `path` finished.
+fn post_process_download(path: &Path, file_fullname: &str) {
+    // here we remove downloaded live_chat.json file
+    remove_downloaded_json(path, file_fullname);
+
+    embed_downloaded_lrc(path, file_fullname);
+}
+
 pub type YTDLMsgURL = Arc<str>;
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -1,14 +1,25 @@
 //! This Module contains all TUI-specific message types.
 
 use std::path::PathBuf;
+use std::time::Duration;
 
 use image::DynamicImage;
 use termusiclib::config::v2::tui::{keys::KeyBinding, theme::styles::ColorTermusic};
-use termusiclib::player::{GetProgressResponse, PlaylistTracks, UpdateEvents};
-use termusiclib::podcast::{PodcastDLResult, PodcastFeed, PodcastSyncResult};
+use termusiclib::ids::{EpisodeId, PodcastId};
+use termusiclib::new_database::{DirStats, ScanProgress};
+use termusiclib::player::{
+    AudioInfo, Bookmarks, Capabilities, CastRenderers, ChapterJump, GetProgressResponse,
+    OrganizeResult, OutputDevices, PlayQueues, PlaylistTracks, PodcastMergeResult, TrackSpeed,
+    TrackWaveform, UpdateEvents,
+};
+use termusiclib::playlist::PlaylistFormat;
+use termusiclib::podcast::PodcastFeed;
+use termusiclib::podcast::feed_settings::FeedSettings;
+use termusiclib::podcast::space::RemoteSize;
+use termusiclib::radio_browser::RadioStation;
 use termusiclib::songtag::{SongtagSearchResult, TrackDLMsg};
 
-use crate::ui::components::TETrack;
+use crate::ui::components::{BulkTagField, TETrack};
 use crate::ui::ids::{IdCEGeneral, IdCETheme, IdConfigEditor, IdKey, IdKeyGlobal, IdKeyOther};
 use crate::ui::model::youtube_options::{YTDLMsg, YoutubeData, YoutubeOptions};
 
@@ -25,11 +36,15 @@ pub enum Msg {
     Player(PlayerMsg),
     Playlist(PLMsg),
     Podcast(PCMsg),
+    Radio(RadioMsg),
     SavePlaylist(SavePlaylistMsg),
+    ImportPlaylist(ImportPlaylistMsg),
+    TrackMenu(TrackMenuMsg),
     TagEditor(TEMsg),
     YoutubeSearch(YSMsg),
     Xywh(XYWHMsg),
     LyricMessage(LyricMsg),
+    LyricEditor(LyricEditorMsg),
     DeleteConfirm(DeleteConfirmMsg),
     QuitPopup(QuitPopupMsg),
     HelpPopup(HelpPopupMsg),
@@ -50,6 +65,67 @@ pub enum Msg {
     StreamUpdate(UpdateEvents),
 }
 
+impl Msg {
+    /// Get whether the message deletes, removes or moves something, or changes files or the config,
+    /// which guests are not allowed to.
+    ///
+    /// For confirmed actions, this is the message showing the confirmation.
+    /// For editors, this is both the message opening them and the ones saving their changes.
+    pub fn is_destructive(&self) -> bool {
+        matches!(
+            self,
+            Msg::DeleteConfirm(DeleteConfirmMsg::Show)
+                | Msg::ConfigEditor(
+                    ConfigEditorMsg::Open
+                        | ConfigEditorMsg::CloseOk
+                        | ConfigEditorMsg::ConfigSaveOk
+                )
+                | Msg::TagEditor(
+                    TEMsg::Open(_)
+                        | TEMsg::Save
+                        | TEMsg::CounterDeleteOk
+                        | TEMsg::Embed(_)
+                        | TEMsg::EditLyric
+                        | TEMsg::ToggleMark(_)
+                        | TEMsg::BulkSave
+                )
+                | Msg::TrackMenu(
+                    TrackMenuMsg::CloseOk(_, TrackMenuAction::TagEditor)
+                        | TrackMenuMsg::Run(_, TrackMenuAction::TagEditor)
+                )
+                | Msg::LyricMessage(LyricMsg::Capture)
+                | Msg::LyricEditor(
+                    LyricEditorMsg::Show
+                        | LyricEditorMsg::EditCloseOk(..)
+                        | LyricEditorMsg::Remove(_)
+                )
+                | Msg::Library(LIMsg::Paste | LIMsg::RemoveRoot | LIMsg::OrganizeApply)
+                | Msg::Playlist(
+                    PLMsg::DeleteAll
+                        | PLMsg::SaveQueue
+                        | PLMsg::RestoreQueue
+                        | PLMsg::QueueDelete(_)
+                )
+                | Msg::Podcast(
+                    PCMsg::EpisodeDeleteFile(_)
+                        | PCMsg::EpisodeMenuCloseOk(_, EpisodeMenuAction::DeleteFile)
+                        | PCMsg::FeedDeleteShow
+                        | PCMsg::FeedsDeleteShow
+                        | PCMsg::FeedMergeShow(_)
+                        | PCMsg::FeedSettingsShow(_)
+                        | PCMsg::FeedSettingsCloseOk(..)
+                )
+                | Msg::DataBase(
+                    DBMsg::RestoreConfirmShow(_)
+                        | DBMsg::Restore(_)
+                        | DBMsg::OpenTagEditor(_)
+                        | DBMsg::ToggleMark(_)
+                )
+                | Msg::Player(PlayerMsg::BookmarkRemove(_))
+        )
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MainLayoutMsg {
     /// Switch to the Music library view
@@ -58,6 +134,8 @@ pub enum MainLayoutMsg {
     DataBase,
     /// Switch to the Podcast view
     Podcast,
+    /// Switch to the Radio view
+    Radio,
 }
 
 /// Player relates messages
@@ -69,19 +147,68 @@ pub enum PlayerMsg {
     VolumeDown,
     SpeedUp,
     SpeedDown,
+    /// Keep the current speed for the current track, or use the global speed for it again
+    ToggleTrackSpeed,
+    /// Set the start, then the end of the section of the current track to loop, and then stop looping it
+    CycleAbRepeat,
     SeekForward,
     SeekBackward,
+    /// Open the list of chapters of the current track
+    ChaptersShow,
+    ChaptersCloseCancel,
+    /// Jump to the chapter at the given index
+    ChaptersCloseOk(usize),
+    NextChapter,
+    PreviousChapter,
+    /// Open the list of bookmarks of the current track, which requests them first
+    BookmarksShow,
+    BookmarksClose,
+    /// Seek to the bookmark with the given id
+    BookmarkJump(i64),
+    /// Remove the bookmark with the given id
+    BookmarkRemove(i64),
+    /// Open the input for the name of a new bookmark at the current position
+    BookmarkNewShow,
+    BookmarkNewCloseCancel,
+    BookmarkNewCloseOk(String),
+    /// Open the input to seek the current track to a time
+    GotoTimeShow,
+    GotoTimeCloseCancel,
+    /// Seek the current track to the given position
+    GotoTimeCloseOk(Duration),
+    /// Open the list of network renderers to cast to, which starts a search for them
+    CastShow,
+    CastCloseCancel,
+    /// Cast to the renderer with the given id, `None` to play on the server again
+    CastCloseOk(Option<String>),
+    /// Open or refresh the info about how the server outputs audio
+    AudioInfoShow,
+    AudioInfoClose,
 }
 
 /// Save Playlist Popup related messages
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SavePlaylistMsg {
+    /// Show the popup to enter the filename to save the playlist as, in the given format
+    PopupShow(PlaylistFormat),
+    PopupCloseCancel,
+    PopupUpdate(String, PlaylistFormat),
+    PopupCloseOk(String, PlaylistFormat),
+    ConfirmCloseCancel,
+    ConfirmCloseOk(String, PlaylistFormat),
+    /// Show the popup to choose the format to export the playlist in
+    FormatShow,
+    FormatCloseCancel,
+    FormatCloseOk(PlaylistFormat),
+}
+
+/// Import Playlist Popup related messages
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportPlaylistMsg {
     PopupShow,
     PopupCloseCancel,
-    PopupUpdate(String),
+    /// Import the playlist file at the entered path
     PopupCloseOk(String),
-    ConfirmCloseCancel,
-    ConfirmCloseOk(String),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -118,14 +245,20 @@ pub enum DeleteConfirmMsg {
     CloseCancel,
     CloseOk,
     Show,
+    /// Restore the items moved to the trash by the last deletion
+    UndoOk,
+    /// Keep the items in the trash
+    UndoCancel,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum QuitPopupMsg {
     /// Closes the Quit Popup, if it was shown without quitting.
     CloseCancel,
-    /// Always will directly quit.
-    CloseOk,
+    /// Always will directly quit, remembering whether to stop the server as `behavior.quit_server_on_exit`.
+    CloseOk { stop_server: bool },
+    /// Disable `behavior.confirm_quit` in the saved config, then quit with the remembered server choice.
+    CloseOkDontAsk,
     /// Either shows the Quit Dialog if enabled, or if dialog is disabled, directly quits
     Show,
 }
@@ -139,17 +272,35 @@ pub enum HelpPopupMsg {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ErrorPopupMsg {
     Close,
+    /// Open the list of recent errors
+    HistoryShow,
+    HistoryClose,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum LyricMsg {
     Cycle,
     AdjustDelay(i64),
+    /// Insert a caption at the current position into the lyrics of the current track
+    Capture,
 
     TextAreaBlurUp,
     TextAreaBlurDown,
 }
 
+/// Messages of the lyric editor, to fix the synced lyrics of the current track
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LyricEditorMsg {
+    Show,
+    Close,
+    /// Edit the text of the caption at the index
+    EditShow(usize),
+    EditCloseCancel,
+    EditCloseOk(usize, String),
+    /// Remove the caption at the index
+    Remove(usize),
+}
+
 /// Basically a Tree Node, but without having to include `tui-realm-treeview` as another dependency for lib
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RecVec<T, V> {
@@ -168,6 +319,23 @@ pub enum LIMsg {
     SwitchRoot,
     AddRoot,
     RemoveRoot,
+    Rescan,
+    CycleFilter,
+    /// Open the given node with the configured external program.
+    OpenExternal(String),
+    /// The selected node in the tree changed.
+    TreeSelectionChanged,
+    /// The statistics for a directory are ready.
+    DirStatsReady(PathBuf, DirStats),
+    /// Preview moving the library tracks to the path of `player.organize_pattern`.
+    OrganizeShow,
+    /// Move the tracks of the preview.
+    OrganizeApply,
+    OrganizeClose,
+    /// Toggle whether the directory of the given node is played as a audiobook.
+    ToggleAudiobook(String),
+    /// The progress of a database scan started by this TUI.
+    ScanProgress(ScanProgress),
 
     /// A requested node is ready from loading.
     /// `(Tree, FocusNode)`
@@ -216,7 +384,10 @@ pub const GENERAL_FOCUS_ORDER: &[IdCEGeneral] = &[
     IdCEGeneral::PlayerProtocol,
     IdCEGeneral::PlayerUDSPath,
     IdCEGeneral::PlayerBackend,
+    IdCEGeneral::PlayerOutputDevice,
+    IdCEGeneral::PlayerCrossfade,
     IdCEGeneral::ExtraYtdlpArgs,
+    IdCEGeneral::LibraryScanPolicy,
 ];
 
 /// This array defines the order the IDs listed are displayed and which gains next / previous focus.
@@ -254,10 +425,12 @@ pub const KFGLOBAL_FOCUS_ORDER: &[IdKey] = &[
     IdKey::Global(IdKeyGlobal::LayoutTreeview),
     IdKey::Global(IdKeyGlobal::LayoutDatabase),
     IdKey::Global(IdKeyGlobal::LayoutPodcast),
+    IdKey::Global(IdKeyGlobal::LayoutRadio),
     // general global keys
     IdKey::Global(IdKeyGlobal::Quit),
     IdKey::Global(IdKeyGlobal::Config),
     IdKey::Global(IdKeyGlobal::Help),
+    IdKey::Global(IdKeyGlobal::ErrorHistory),
     IdKey::Global(IdKeyGlobal::SavePlaylist),
     // global navigation
     IdKey::Global(IdKeyGlobal::Up),
@@ -273,14 +446,25 @@ pub const KFGLOBAL_FOCUS_ORDER: &[IdKey] = &[
     IdKey::Global(IdKeyGlobal::PlayerPrevious),
     IdKey::Global(IdKeyGlobal::PlayerSeekForward),
     IdKey::Global(IdKeyGlobal::PlayerSeekBackward),
+    IdKey::Global(IdKeyGlobal::PlayerChapters),
+    IdKey::Global(IdKeyGlobal::PlayerNextChapter),
+    IdKey::Global(IdKeyGlobal::PlayerPreviousChapter),
+    IdKey::Global(IdKeyGlobal::PlayerGotoTime),
+    IdKey::Global(IdKeyGlobal::PlayerCast),
+    IdKey::Global(IdKeyGlobal::PlayerAudioInfo),
     IdKey::Global(IdKeyGlobal::PlayerSpeedUp),
     IdKey::Global(IdKeyGlobal::PlayerSpeedDown),
+    IdKey::Global(IdKeyGlobal::PlayerTrackSpeed),
+    IdKey::Global(IdKeyGlobal::PlayerAbRepeat),
+    IdKey::Global(IdKeyGlobal::PlayerBookmarks),
     IdKey::Global(IdKeyGlobal::PlayerVolumeUp),
     IdKey::Global(IdKeyGlobal::PlayerVolumeDown),
     // lyric controls
     IdKey::Global(IdKeyGlobal::LyricAdjustForward),
     IdKey::Global(IdKeyGlobal::LyricAdjustBackward),
     IdKey::Global(IdKeyGlobal::LyricCycle),
+    IdKey::Global(IdKeyGlobal::LyricCapture),
+    IdKey::Global(IdKeyGlobal::LyricEditor),
     // coverart display adjustments
     IdKey::Global(IdKeyGlobal::XywhMoveUp),
     IdKey::Global(IdKeyGlobal::XywhMoveDown),
@@ -296,6 +480,9 @@ pub const KFOTHER_FOCUS_ORDER: &[IdKey] = &[
     // library keys
     IdKey::Other(IdKeyOther::LibraryAddRoot),
     IdKey::Other(IdKeyOther::LibraryRemoveRoot),
+    IdKey::Other(IdKeyOther::LibraryRescan),
+    IdKey::Other(IdKeyOther::LibraryCycleFilter),
+    IdKey::Other(IdKeyOther::LibraryOpenExternal),
     IdKey::Other(IdKeyOther::LibrarySwitchRoot),
     IdKey::Other(IdKeyOther::LibraryDelete),
     IdKey::Other(IdKeyOther::LibraryLoadDir),
@@ -304,6 +491,12 @@ pub const KFOTHER_FOCUS_ORDER: &[IdKey] = &[
     IdKey::Other(IdKeyOther::LibrarySearch),
     IdKey::Other(IdKeyOther::LibrarySearchYoutube),
     IdKey::Other(IdKeyOther::LibraryTagEditor),
+    IdKey::Other(IdKeyOther::LibraryTrackMenu),
+    IdKey::Other(IdKeyOther::LibraryCycleRating),
+    IdKey::Other(IdKeyOther::LibraryToggleFavorite),
+    IdKey::Other(IdKeyOther::LibraryMark),
+    IdKey::Other(IdKeyOther::LibraryOrganize),
+    IdKey::Other(IdKeyOther::LibraryToggleAudiobook),
     // playlist keys
     IdKey::Other(IdKeyOther::PlaylistShuffle),
     IdKey::Other(IdKeyOther::PlaylistModeCycle),
@@ -315,19 +508,41 @@ pub const KFOTHER_FOCUS_ORDER: &[IdKey] = &[
     IdKey::Other(IdKeyOther::PlaylistDeleteAll),
     IdKey::Other(IdKeyOther::PlaylistAddRandomAlbum),
     IdKey::Other(IdKeyOther::PlaylistAddRandomTracks),
+    IdKey::Other(IdKeyOther::PlaylistExport),
+    IdKey::Other(IdKeyOther::PlaylistImport),
+    IdKey::Other(IdKeyOther::PlaylistTrackMenu),
+    IdKey::Other(IdKeyOther::PlaylistSaveQueue),
+    IdKey::Other(IdKeyOther::PlaylistRestoreQueue),
+    IdKey::Other(IdKeyOther::PlaylistToggleAutoQueue),
+    IdKey::Other(IdKeyOther::PlaylistQueues),
+    IdKey::Other(IdKeyOther::PlaylistCycleRating),
+    IdKey::Other(IdKeyOther::PlaylistToggleFavorite),
     // database keys
     IdKey::Other(IdKeyOther::DatabaseAddAll),
     IdKey::Other(IdKeyOther::DatabaseAddSelected),
+    IdKey::Other(IdKeyOther::DatabaseMaintenance),
+    IdKey::Other(IdKeyOther::DatabasePreview),
     // podcast keys
     IdKey::Other(IdKeyOther::PodcastSearchAddFeed),
     IdKey::Other(IdKeyOther::PodcastMarkPlayed),
     IdKey::Other(IdKeyOther::PodcastMarkAllPlayed),
+    IdKey::Other(IdKeyOther::PodcastEpStream),
     IdKey::Other(IdKeyOther::PodcastEpDownload),
+    IdKey::Other(IdKeyOther::PodcastEpDownloadAllUnplayed),
     IdKey::Other(IdKeyOther::PodcastEpDeleteFile),
     IdKey::Other(IdKeyOther::PodcastDeleteFeed),
     IdKey::Other(IdKeyOther::PodcastDeleteAllFeeds),
     IdKey::Other(IdKeyOther::PodcastRefreshFeed),
     IdKey::Other(IdKeyOther::PodcastRefreshAllFeeds),
+    IdKey::Other(IdKeyOther::PodcastCycleFeedSort),
+    IdKey::Other(IdKeyOther::PodcastCycleCategory),
+    IdKey::Other(IdKeyOther::PodcastEpisodeMenu),
+    IdKey::Other(IdKeyOther::PodcastFeedSettings),
+    IdKey::Other(IdKeyOther::PodcastFeedInfo),
+    IdKey::Other(IdKeyOther::PodcastMergeFeed),
+    // radio keys
+    IdKey::Other(IdKeyOther::RadioSearch),
+    IdKey::Other(IdKeyOther::RadioToggleFavorite),
 ];
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -354,11 +569,31 @@ pub enum DBMsg {
     SearchResultBlurUp,
     /// Serarch Tracks (for view `Tracks`) from a `Result`(view) index
     SearchTrack(usize),
+    /// The selection in view `Result` moved to the given index
+    SearchResultHighlighted(usize),
     SearchTracksBlurDown,
     SearchTracksBlurUp,
 
     AddAllResultsConfirmShow,
     AddAllResultsConfirmCancel,
+
+    /// Open the database maintenance popup, listing all backups
+    BackupsShow,
+    BackupsCloseCancel,
+    /// Create a new backup of the databases
+    BackupCreate,
+    /// Ask to confirm restoring the backup at the given index
+    RestoreConfirmShow(usize),
+    RestoreConfirmCancel,
+    /// Restore the backup at the given index
+    Restore(usize),
+
+    /// Mark or unmark a single Track Result (from view `Tracks`) for the tag editor
+    ToggleMark(usize),
+    /// Open the tag editor for a single Track Result (from view `Tracks`), or all marked tracks
+    OpenTagEditor(usize),
+    /// Play a short preview of a single Track Result (from view `Tracks`)
+    PreviewTrack(usize),
 }
 
 /// Playlist Library View messages
@@ -392,6 +627,23 @@ pub enum PLMsg {
     AddRandomAlbum,
     /// Start choosing random tracks to be added to the playlist
     AddRandomTracks,
+    /// Store the queue in the server's database
+    SaveQueue,
+    /// Replace the queue with the one stored in the server's database
+    RestoreQueue,
+    /// Toggle the server keeping the queue filled with tracks from the library
+    ToggleAutoQueue,
+    /// Show the named queues stored on the server
+    QueuesShow,
+    QueuesClose,
+    /// Switch the server to the queue with the given name
+    QueueSwitch(String),
+    /// Delete the queue with the given name
+    QueueDelete(String),
+    QueueNewShow,
+    QueueNewCloseCancel,
+    /// Create a queue with the given name
+    QueueNewCloseOk(String),
 }
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum GSMsg {
@@ -463,6 +715,8 @@ pub enum TEMsg {
 
     Focus(TFMsg),
     Save,
+    /// Edit the selected lyric in the user's editor and save it afterwards.
+    EditLyric,
     Search,
     SelectLyricOk(usize),
 
@@ -472,6 +726,20 @@ pub enum TEMsg {
     ///
     /// `(ErrorAsString)`
     TrackDownloadPreError(String),
+
+    /// Mark or unmark a track to edit its tags together with other marked tracks.
+    ToggleMark(PathBuf),
+    /// Show the input to change a field for all marked tracks.
+    BulkFieldShow(BulkTagField),
+    BulkFieldCloseCancel,
+    BulkFieldCloseOk(BulkTagField, String),
+    /// Save the changed fields to all marked tracks.
+    BulkSave,
+    BulkClose,
+    /// `(saved, total)` tracks of the running bulk save
+    BulkProgress(usize, usize),
+    /// The bulk save of `param1` tracks has finished, with the tracks that failed and their error.
+    BulkDone(usize, Vec<(PathBuf, String)>),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -507,13 +775,28 @@ pub enum PCMsg {
     PodcastAddPopupCloseCancel,
     PodcastSelected(usize),
     DescriptionUpdate,
+    /// Add the episode as configured in `behavior.episode_enter`
     EpisodeAdd(usize),
+    /// Add the episode to the playlist for streaming
+    EpisodeStream(usize),
     EpisodeMarkPlayed(usize),
     EpisodeMarkAllPlayed,
     PodcastRefreshOne(usize),
     PodcastRefreshAll,
     EpisodeDownload(usize),
     EpisodeDeleteFile(usize),
+    /// Estimate the size of all unplayed, not downloaded episodes of the current feed
+    EpisodeDownloadUnplayedEstimate,
+    /// The estimate is done, ask for confirmation before downloading
+    EpisodeDownloadUnplayedShow(UnplayedDownload),
+    EpisodeDownloadUnplayedCloseOk,
+    EpisodeDownloadUnplayedCloseCancel,
+    /// Show the menu of all actions for the episode at the index
+    EpisodeMenuShow(usize),
+    /// Run the action chosen in the menu on the episode at the index
+    EpisodeMenuCloseOk(usize, EpisodeMenuAction),
+    EpisodeMenuCloseCancel,
+    EpisodeInfoClose,
 
     FeedDeleteShow,
     FeedDeleteCloseOk,
@@ -521,32 +804,223 @@ pub enum PCMsg {
     FeedsDeleteShow,
     FeedsDeleteCloseOk,
     FeedsDeleteCloseCancel,
+    /// Switch to the next order of the feeds list
+    FeedsCycleSort,
+    /// Only show the feeds of the next category
+    FeedsCycleCategory,
+    /// Edit the settings of the feed at the index
+    FeedSettingsShow(usize),
+    FeedSettingsCloseOk(PodcastId, FeedSettings),
+    FeedSettingsCloseCancel,
+    /// Show the info of the feed at the index
+    FeedInfoShow(usize),
+    /// Re-check the feed shown in the info popup
+    FeedInfoRefresh(PodcastId),
+    FeedInfoClose,
+    /// Choose the feed to merge the feed at the index into
+    FeedMergeShow(usize),
+    /// Merge into the feed at the index of the merge list, after confirming
+    FeedMergeSelect(usize),
+    FeedMergeSelectCancel,
+    FeedMergeCloseOk,
+    FeedMergeCloseCancel,
 
     SearchItunesCloseCancel,
     SearchItunesCloseOk(usize),
     SearchSuccess(Vec<PodcastFeed>),
     SearchError(String),
+}
+
+/// Unplayed episodes of a feed to download once confirmed, see [`PCMsg::EpisodeDownloadUnplayedShow`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnplayedDownload {
+    pub pod_id: PodcastId,
+    pub ids: Vec<EpisodeId>,
+    /// The estimated total size of the episodes
+    pub size: RemoteSize,
+}
+
+/// The actions offered in the episode menu, see [`PCMsg::EpisodeMenuShow`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EpisodeMenuAction {
+    /// Like `Enter`, as configured in `behavior.episode_enter`
+    Play,
+    Stream,
+    Download,
+    /// Toggle the played status
+    MarkPlayed,
+    DeleteFile,
+    CopyUrl,
+    OpenLink,
+    Info,
+}
+
+impl EpisodeMenuAction {
+    /// All actions, in the order they are listed in the menu.
+    pub const ALL: [Self; 8] = [
+        Self::Play,
+        Self::Stream,
+        Self::Download,
+        Self::MarkPlayed,
+        Self::DeleteFile,
+        Self::CopyUrl,
+        Self::OpenLink,
+        Self::Info,
+    ];
 
-    SyncResult(PodcastSyncResult),
-    DLResult(PodcastDLResult),
+    /// The text to show for the action in the menu.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Play => "Play",
+            Self::Stream => "Stream",
+            Self::Download => "Download",
+            Self::MarkPlayed => "Mark played / unplayed",
+            Self::DeleteFile => "Delete downloaded file",
+            Self::CopyUrl => "Copy URL",
+            Self::OpenLink => "Open link",
+            Self::Info => "Info",
+        }
+    }
+}
+
+/// The entry a track menu is for, see [`TrackMenuMsg::Show`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TrackMenuTarget {
+    /// A file or directory in the music library
+    Library(PathBuf),
+    /// The playlist entry at the index
+    Playlist(usize),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TrackMenuMsg {
+    /// Show the menu of all actions available for the target
+    Show(TrackMenuTarget),
+    /// Run the action chosen in the menu on the target
+    CloseOk(TrackMenuTarget, TrackMenuAction),
+    /// Run the action on the target without the menu, like from its own key
+    Run(TrackMenuTarget, TrackMenuAction),
+    CloseCancel,
+    InfoClose,
+}
+
+/// The actions offered in the track menu, see [`TrackMenuMsg::Show`].
+///
+/// Not all actions are available for every target.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrackMenuAction {
+    PlayNow,
+    /// Play after the current track
+    PlayNext,
+    /// Add to the end of the playlist
+    Add,
+    /// Remove from the playlist
+    Remove,
+    TagEditor,
+    Info,
+    /// Go to the next rating, see [`TrackRating::next_rating`](termusiclib::new_database::track_ops::TrackRating::next_rating)
+    CycleRating,
+    ToggleFavorite,
+    /// Toggle whether the track is never played by shuffle or auto-queue
+    ToggleAutoplay,
+    /// Open the containing directory with `external_open.command`
+    OpenDirectory,
+    /// Copy the path, or the url if there is no local file
+    CopyPath,
+}
+
+impl TrackMenuAction {
+    /// All actions, in the order they are listed in the menu.
+    pub const ALL: [Self; 11] = [
+        Self::PlayNow,
+        Self::PlayNext,
+        Self::Add,
+        Self::Remove,
+        Self::TagEditor,
+        Self::Info,
+        Self::CycleRating,
+        Self::ToggleFavorite,
+        Self::ToggleAutoplay,
+        Self::OpenDirectory,
+        Self::CopyPath,
+    ];
+
+    /// The text to show for the action in the menu.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::PlayNow => "Play now",
+            Self::PlayNext => "Play next",
+            Self::Add => "Add to playlist",
+            Self::Remove => "Remove from playlist",
+            Self::TagEditor => "Tag editor",
+            Self::Info => "File info",
+            Self::CycleRating => "Cycle rating",
+            Self::ToggleFavorite => "Toggle favorite",
+            Self::ToggleAutoplay => "Toggle never auto-play",
+            Self::OpenDirectory => "Open directory",
+            Self::CopyPath => "Copy path",
+        }
+    }
+
+    /// Get whether the action is available for a entry in the library (or playlist if `false`),
+    /// which is a playable local file if `local_track`.
+    pub fn is_available(self, library: bool, local_track: bool) -> bool {
+        match self {
+            Self::PlayNow | Self::PlayNext => !library || local_track,
+            Self::Add => library,
+            Self::Remove => !library,
+            Self::TagEditor
+            | Self::Info
+            | Self::CycleRating
+            | Self::ToggleFavorite
+            | Self::ToggleAutoplay => local_track,
+            Self::OpenDirectory => library || local_track,
+            Self::CopyPath => true,
+        }
+    }
+}
+
+/// The station lists in the Radio view.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RadioList {
+    Favorites,
+    SearchResults,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RadioMsg {
+    FavoritesBlurDown,
+    FavoritesBlurUp,
+
+    SearchResultsBlurDown,
+    SearchResultsBlurUp,
+
+    SearchPopupShow,
+    SearchPopupCloseOk(String),
+    SearchPopupCloseCancel,
+    SearchSuccess(Vec<RadioStation>),
+    SearchError(String),
+
+    /// Add the station at the index in the list to the playlist
+    PlaylistAdd(RadioList, usize),
+    /// Add the station at the index in the list to the favorites, or remove it if it already is one
+    ToggleFavorite(RadioList, usize),
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum NotificationMsg {
-    /// Show a status message in the TUI.
-    ///
-    /// `((Title, Text))`
-    MessageShow((String, String)),
-    /// Hide a status message in the TUI.
-    ///
-    /// `((Title, Text))`
-    MessageHide((String, String)),
+    /// Remove the toast with the id, once it timed out.
+    ToastExpire(u64),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SearchCriteria {
     Artist,
     Album,
+    /// Favorite and rated tracks
+    Favorite,
+    /// Most played, recently played and most skipped tracks
+    PlayStats,
 
     // TODO: the values below are current unused
     Genre,
@@ -560,6 +1034,8 @@ impl SearchCriteria {
         match self {
             SearchCriteria::Artist => "artist",
             SearchCriteria::Album => "album",
+            SearchCriteria::Favorite => "favorite",
+            SearchCriteria::PlayStats => "play statistics",
             SearchCriteria::Genre => "genre",
             SearchCriteria::Directory => "directory",
             SearchCriteria::Playlist => "playlist",
@@ -571,15 +1047,52 @@ impl SearchCriteria {
 pub enum ServerReqResponse {
     GetProgress(GetProgressResponse),
     FullPlaylist(PlaylistTracks),
+    OutputDevices(OutputDevices),
+    /// What the backend of the server can play
+    Capabilities(Capabilities),
+    /// The new auto-queue state after toggling it
+    AutoQueue(bool),
+    /// The network renderers found by the server, empty if the search failed
+    CastRenderers(CastRenderers),
+    /// How the server currently outputs audio
+    AudioInfo(AudioInfo),
+    /// The chapter the server jumped to
+    ChapterJump(ChapterJump),
+    /// The speed override of the current track after toggling it
+    TrackSpeed(TrackSpeed),
+    /// The named queues stored on the server
+    Queues(PlayQueues),
+    /// The bookmarks of the current track
+    Bookmarks(Bookmarks),
+    /// The waveform of a track, with empty levels if it is still generated
+    Waveform(TrackWaveform),
+    /// The tracks moved by organizing the library, or which would be moved in a `dry_run`
+    Organize {
+        dry_run: bool,
+        result: Result<OrganizeResult, String>,
+    },
+    /// The amount of episodes and downloads moved by merging into the podcast `target`
+    PodcastMerged {
+        target: PodcastId,
+        result: PodcastMergeResult,
+    },
+    /// A podcast request failed, like a download that would not fit
+    PodcastError(String),
 }
 
 impl Eq for ServerReqResponse {}
 
 #[cfg(test)]
 mod tests {
+    use termusiclib::ids::PodcastId;
+    use termusiclib::podcast::feed_settings::FeedSettings;
+
     use crate::ui::ids::IdKey;
 
-    use super::{KFGLOBAL_FOCUS_ORDER, KFOTHER_FOCUS_ORDER};
+    use super::{
+        ConfigEditorMsg, DeleteConfirmMsg, KFGLOBAL_FOCUS_ORDER, KFOTHER_FOCUS_ORDER,
+        LyricEditorMsg, Msg, PCMsg, PLMsg, TEMsg, TrackMenuAction,
+    };
 
     // ensure that assumptions about "KFGLOBAL_FOCUS_ORDER[0]" can be made correctly
     #[test]
@@ -618,4 +1131,64 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn track_menu_should_only_offer_applicable_actions() {
+        let available = |library, local_track| -> Vec<TrackMenuAction> {
+            TrackMenuAction::ALL
+                .into_iter()
+                .filter(|action| action.is_available(library, local_track))
+                .collect()
+        };
+
+        assert_eq!(
+            available(true, false),
+            [
+                TrackMenuAction::Add,
+                TrackMenuAction::OpenDirectory,
+                TrackMenuAction::CopyPath
+            ]
+        );
+        assert_eq!(
+            available(false, false),
+            [
+                TrackMenuAction::PlayNow,
+                TrackMenuAction::PlayNext,
+                TrackMenuAction::Remove,
+                TrackMenuAction::CopyPath
+            ]
+        );
+        assert_eq!(available(true, true).len(), 10);
+        assert!(!available(true, true).contains(&TrackMenuAction::Remove));
+    }
+
+    #[test]
+    fn should_only_deny_destructive_actions() {
+        assert!(Msg::DeleteConfirm(DeleteConfirmMsg::Show).is_destructive());
+        assert!(Msg::Playlist(PLMsg::DeleteAll).is_destructive());
+        // removing a single track is fine, unlike clearing the whole playlist
+        assert!(!Msg::Playlist(PLMsg::Delete(0)).is_destructive());
+        // undoing a deletion restores the files
+        assert!(!Msg::DeleteConfirm(DeleteConfirmMsg::UndoOk).is_destructive());
+        assert!(!Msg::Playlist(PLMsg::Shuffle).is_destructive());
+
+        // editors save to files or the config
+        assert!(Msg::TagEditor(TEMsg::Save).is_destructive());
+        assert!(Msg::TagEditor(TEMsg::CounterDeleteOk).is_destructive());
+        assert!(Msg::TagEditor(TEMsg::BulkSave).is_destructive());
+        assert!(Msg::LyricEditor(LyricEditorMsg::EditCloseOk(0, String::new())).is_destructive());
+        assert!(Msg::LyricEditor(LyricEditorMsg::Remove(0)).is_destructive());
+        assert!(Msg::ConfigEditor(ConfigEditorMsg::ConfigSaveOk).is_destructive());
+        assert!(
+            Msg::Podcast(PCMsg::FeedSettingsCloseOk(
+                PodcastId(1),
+                FeedSettings::default()
+            ))
+            .is_destructive()
+        );
+        // closing them without saving is fine
+        assert!(!Msg::TagEditor(TEMsg::Close).is_destructive());
+        assert!(!Msg::ConfigEditor(ConfigEditorMsg::CloseCancel).is_destructive());
+        assert!(!Msg::Podcast(PCMsg::FeedSettingsCloseCancel).is_destructive());
+    }
 }
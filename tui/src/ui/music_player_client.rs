@@ -1,24 +1,34 @@
+use std::path::Path;
+use std::time::Duration;
+
 use anyhow::{Context, Result};
-use termusiclib::config::v2::server::LoopMode;
-use termusiclib::player::music_player_client::MusicPlayerClient;
+use termusiclib::config::v2::server::{CrossfadeSettings, LoopMode};
+use termusiclib::ids::{EpisodeId, PodcastId};
+use termusiclib::player::auth::AuthClient;
 use termusiclib::player::playlist_helpers::{
     PlaylistAddTrack, PlaylistPlaySpecific, PlaylistRemoveTrackType, PlaylistSwapTrack,
 };
 use termusiclib::player::{
-    Empty, GetProgressResponse, PlayerProgress, PlaylistSwapTracks, PlaylistTracks,
-    PlaylistTracksToAdd, PlaylistTracksToRemove, RunningStatus,
+    AudioInfo, Bookmark, BookmarkAdd, BookmarkId, Bookmarks, Capabilities, CastRenderer,
+    CastRenderers, ChapterJump, CrossfadeState, Empty, GetProgressResponse, OrganizeRequest,
+    OrganizeResult, OutputDevice, OutputDevices, PlayQueueName, PlayQueues, PlayerProgress,
+    PlaylistSwapTracks, PlaylistTracks, PlaylistTracksToAdd, PlaylistTracksToRemove, PodcastAdd,
+    PodcastEpisodeFile, PodcastEpisodeIds, PodcastEpisodePlayed, PodcastFeedId, PodcastFeedPlayed,
+    PodcastFeedSettings, PodcastMerge, PodcastMergeResult, PodcastRefresh, RunningStatus,
+    TrackFavorite, TrackPreview, TrackSpeed, TrackWaveform, cast_renderer, output_device,
+    podcast_refresh,
 };
+use termusiclib::podcast::feed_settings::FeedSettings;
 use tokio_stream::{Stream, StreamExt as _};
-use tonic::transport::Channel;
 
 /// Handle TUI Requests to the server.
 #[derive(Debug)]
 pub struct Playback {
-    client: MusicPlayerClient<Channel>,
+    client: AuthClient,
 }
 
 impl Playback {
-    pub fn new(client: MusicPlayerClient<Channel>) -> Self {
+    pub fn new(client: AuthClient) -> Self {
         Self { client }
     }
 
@@ -93,6 +103,15 @@ impl Playback {
         Ok(response.speed)
     }
 
+    /// Keep the current speed for the current track, or use the global speed for it again.
+    pub async fn toggle_track_speed(&mut self) -> Result<TrackSpeed> {
+        let request = tonic::Request::new(Empty {});
+        let response = self.client.toggle_track_speed(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {response:?}");
+        Ok(response)
+    }
+
     pub async fn toggle_gapless(&mut self) -> Result<bool> {
         let request = tonic::Request::new(Empty {});
         let response = self.client.toggle_gapless(request).await?;
@@ -109,6 +128,89 @@ impl Playback {
         Ok(response.into())
     }
 
+    pub async fn seek_to(&mut self, position: Duration) -> Result<PlayerProgress> {
+        let request = tonic::Request::new(termusiclib::player::Duration::from(position));
+        let response = self.client.seek_to(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {response:?}");
+        Ok(response.into())
+    }
+
+    /// Set the start of the section of the current track to loop at the current position.
+    pub async fn set_ab_repeat_a(&mut self) -> Result<PlayerProgress> {
+        let request = tonic::Request::new(Empty {});
+        let response = self.client.set_ab_repeat_a(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {response:?}");
+        Ok(response.into())
+    }
+
+    /// Set the end of the section of the current track to loop at the current position.
+    pub async fn set_ab_repeat_b(&mut self) -> Result<PlayerProgress> {
+        let request = tonic::Request::new(Empty {});
+        let response = self.client.set_ab_repeat_b(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {response:?}");
+        Ok(response.into())
+    }
+
+    /// Stop looping a section of the current track.
+    pub async fn clear_ab_repeat(&mut self) -> Result<PlayerProgress> {
+        let request = tonic::Request::new(Empty {});
+        let response = self.client.clear_ab_repeat(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {response:?}");
+        Ok(response.into())
+    }
+
+    /// Get the bookmarks of the current track.
+    pub async fn get_bookmarks(&mut self) -> Result<Bookmarks> {
+        let request = tonic::Request::new(Empty {});
+        let response = self.client.get_bookmarks(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {response:?}");
+        Ok(response)
+    }
+
+    /// Bookmark the current position of the current track as `name`.
+    pub async fn add_bookmark(&mut self, name: String) -> Result<Bookmark> {
+        let request = tonic::Request::new(BookmarkAdd { name });
+        let response = self.client.add_bookmark(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {response:?}");
+        Ok(response)
+    }
+
+    /// Seek to the bookmark with `id`, which has to be of the current track.
+    pub async fn jump_to_bookmark(&mut self, id: i64) -> Result<PlayerProgress> {
+        let request = tonic::Request::new(BookmarkId { id });
+        let response = self.client.jump_to_bookmark(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {response:?}");
+        Ok(response.into())
+    }
+
+    pub async fn remove_bookmark(&mut self, id: i64) -> Result<()> {
+        let request = tonic::Request::new(BookmarkId { id });
+        let response = self.client.remove_bookmark(request).await?;
+        info!("Got response from server: {response:?}");
+
+        Ok(())
+    }
+
+    /// Jump to the next chapter of the current track if `forward`, otherwise back.
+    pub async fn jump_chapter(&mut self, forward: bool) -> Result<ChapterJump> {
+        let request = tonic::Request::new(Empty {});
+        let response = if forward {
+            self.client.next_chapter(request).await?
+        } else {
+            self.client.previous_chapter(request).await?
+        };
+        let response = response.into_inner();
+        info!("Got response from server: {response:?}");
+        Ok(response)
+    }
+
     pub async fn seek_backward(&mut self) -> Result<PlayerProgress> {
         let request = tonic::Request::new(Empty {});
         let response = self.client.seek_backward(request).await?;
@@ -125,6 +227,122 @@ impl Playback {
         Ok(())
     }
 
+    pub async fn rescan_library(&mut self) -> Result<()> {
+        let request = tonic::Request::new(Empty {});
+        let response = self.client.rescan_library(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {response:?}");
+        Ok(())
+    }
+
+    /// Mark or unmark the library track at `path` as favorite.
+    pub async fn set_track_favorite(&mut self, path: &Path, favorite: bool) -> Result<()> {
+        let request = tonic::Request::new(TrackFavorite {
+            path: path.to_string_lossy().to_string(),
+            favorite,
+        });
+        let response = self.client.set_track_favorite(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {response:?}");
+        Ok(())
+    }
+
+    /// Play a short part of the library track at `path`, without changing the playlist.
+    pub async fn preview_track(&mut self, path: &Path) -> Result<()> {
+        let request = tonic::Request::new(TrackPreview {
+            path: path.to_string_lossy().to_string(),
+        });
+        let response = self.client.preview_track(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {response:?}");
+        Ok(())
+    }
+
+    pub async fn organize_library(&mut self, dry_run: bool) -> Result<OrganizeResult> {
+        let request = tonic::Request::new(OrganizeRequest { dry_run });
+        let response = self.client.organize_library(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {response:?}");
+        Ok(response)
+    }
+
+    pub async fn get_output_devices(&mut self) -> Result<OutputDevices> {
+        let request = tonic::Request::new(Empty {});
+        let response = self.client.get_output_devices(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {response:?}");
+        Ok(response)
+    }
+
+    pub async fn get_capabilities(&mut self) -> Result<Capabilities> {
+        let request = tonic::Request::new(Empty {});
+        let response = self.client.get_capabilities(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {response:?}");
+        Ok(response)
+    }
+
+    pub async fn set_output_device(&mut self, name: Option<String>) -> Result<()> {
+        let request = tonic::Request::new(OutputDevice {
+            optional_name: name.map(output_device::OptionalName::Name),
+        });
+        let response = self.client.set_output_device(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {response:?}");
+        Ok(())
+    }
+
+    pub async fn get_cast_renderers(&mut self) -> Result<CastRenderers> {
+        let request = tonic::Request::new(Empty {});
+        let response = self.client.get_cast_renderers(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {response:?}");
+        Ok(response)
+    }
+
+    pub async fn set_cast_renderer(&mut self, id: Option<String>) -> Result<()> {
+        let request = tonic::Request::new(CastRenderer {
+            optional_id: id.map(cast_renderer::OptionalId::Id),
+        });
+        let response = self.client.set_cast_renderer(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {response:?}");
+        Ok(())
+    }
+
+    pub async fn get_audio_info(&mut self) -> Result<AudioInfo> {
+        let request = tonic::Request::new(Empty {});
+        let response = self.client.get_audio_info(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {response:?}");
+        Ok(response)
+    }
+
+    /// Get the waveform of the current track, `None` if no track is playing.
+    ///
+    /// The levels are empty if the server still has to generate it, which it sends as an update once done.
+    pub async fn get_waveform(&mut self) -> Result<Option<TrackWaveform>> {
+        let request = tonic::Request::new(Empty {});
+        let response = self.client.get_waveform(request).await?;
+        let response = response.into_inner();
+        if response.id.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(response.try_into()?))
+    }
+
+    pub async fn set_crossfade(&mut self, crossfade: CrossfadeSettings) -> Result<CrossfadeState> {
+        let request = tonic::Request::new(CrossfadeState {
+            enabled: crossfade.enabled,
+            secs: u32::from(crossfade.secs.get()),
+        });
+        let response = self.client.set_crossfade(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {response:?}");
+        Ok(response)
+    }
+
     pub async fn play_specific(&mut self, info: PlaylistPlaySpecific) -> Result<()> {
         let request = tonic::Request::new(info.into());
         let response = self.client.play_specific(request).await?;
@@ -199,4 +417,182 @@ impl Playback {
 
         Ok(())
     }
+
+    pub async fn save_queue(&mut self) -> Result<()> {
+        let request = tonic::Request::new(Empty {});
+        let response = self.client.save_queue(request).await?;
+        info!("Got response from server: {response:?}");
+
+        Ok(())
+    }
+
+    pub async fn restore_queue(&mut self) -> Result<()> {
+        let request = tonic::Request::new(Empty {});
+        let response = self.client.restore_queue(request).await?;
+        info!("Got response from server: {response:?}");
+
+        Ok(())
+    }
+
+    /// Toggle auto-queue on the server, returns the new state.
+    pub async fn toggle_auto_queue(&mut self) -> Result<bool> {
+        let request = tonic::Request::new(Empty {});
+        let response = self.client.toggle_auto_queue(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {response:?}");
+        Ok(response.enabled)
+    }
+
+    pub async fn get_queues(&mut self) -> Result<PlayQueues> {
+        let request = tonic::Request::new(Empty {});
+        let response = self.client.get_queues(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {response:?}");
+        Ok(response)
+    }
+
+    pub async fn create_queue(&mut self, name: String) -> Result<()> {
+        let request = tonic::Request::new(PlayQueueName { name });
+        let response = self.client.create_queue(request).await?;
+        info!("Got response from server: {response:?}");
+
+        Ok(())
+    }
+
+    pub async fn switch_queue(&mut self, name: String) -> Result<()> {
+        let request = tonic::Request::new(PlayQueueName { name });
+        let response = self.client.switch_queue(request).await?;
+        info!("Got response from server: {response:?}");
+
+        Ok(())
+    }
+
+    pub async fn delete_queue(&mut self, name: String) -> Result<()> {
+        let request = tonic::Request::new(PlayQueueName { name });
+        let response = self.client.delete_queue(request).await?;
+        info!("Got response from server: {response:?}");
+
+        Ok(())
+    }
+
+    pub async fn add_podcast(&mut self, url: String) -> Result<()> {
+        let request = tonic::Request::new(PodcastAdd { url });
+        let response = self.client.add_podcast(request).await?;
+        info!("Got response from server: {response:?}");
+
+        Ok(())
+    }
+
+    /// Fetch new episodes of the podcast `id`, or of all podcasts if `None`.
+    pub async fn refresh_podcasts(&mut self, id: Option<PodcastId>) -> Result<()> {
+        let request = tonic::Request::new(PodcastRefresh {
+            optional_id: id.map(|v| podcast_refresh::OptionalId::Id(v.0)),
+        });
+        let response = self.client.refresh_podcasts(request).await?;
+        info!("Got response from server: {response:?}");
+
+        Ok(())
+    }
+
+    pub async fn remove_podcast(&mut self, id: PodcastId) -> Result<()> {
+        let request = tonic::Request::new(PodcastFeedId { id: id.0 });
+        let response = self.client.remove_podcast(request).await?;
+        info!("Got response from server: {response:?}");
+
+        Ok(())
+    }
+
+    pub async fn remove_all_podcasts(&mut self) -> Result<()> {
+        let request = tonic::Request::new(Empty {});
+        let response = self.client.remove_all_podcasts(request).await?;
+        info!("Got response from server: {response:?}");
+
+        Ok(())
+    }
+
+    pub async fn mark_episode_played(&mut self, id: EpisodeId, played: bool) -> Result<()> {
+        let request = tonic::Request::new(PodcastEpisodePlayed { id: id.0, played });
+        let response = self.client.mark_episode_played(request).await?;
+        info!("Got response from server: {response:?}");
+
+        Ok(())
+    }
+
+    /// Set the played status of all episodes of the podcast `id`.
+    pub async fn mark_feed_played(&mut self, id: PodcastId, played: bool) -> Result<()> {
+        let request = tonic::Request::new(PodcastFeedPlayed { id: id.0, played });
+        let response = self.client.mark_feed_played(request).await?;
+        info!("Got response from server: {response:?}");
+
+        Ok(())
+    }
+
+    pub async fn download_episodes(&mut self, ids: &[EpisodeId]) -> Result<()> {
+        let request = tonic::Request::new(PodcastEpisodeIds {
+            ids: ids.iter().map(|v| v.0).collect(),
+        });
+        let response = self.client.download_episodes(request).await?;
+        info!("Got response from server: {response:?}");
+
+        Ok(())
+    }
+
+    /// Let the server forget the downloaded files of the episodes `ids`, after they got deleted.
+    pub async fn remove_episode_files(&mut self, ids: &[EpisodeId]) -> Result<()> {
+        let request = tonic::Request::new(PodcastEpisodeIds {
+            ids: ids.iter().map(|v| v.0).collect(),
+        });
+        let response = self.client.remove_episode_files(request).await?;
+        info!("Got response from server: {response:?}");
+
+        Ok(())
+    }
+
+    /// Let the server record `path` as the downloaded file of the episode `id` again.
+    pub async fn restore_episode_file(&mut self, id: EpisodeId, path: &Path) -> Result<()> {
+        let request = tonic::Request::new(PodcastEpisodeFile {
+            id: id.0,
+            path: path.to_string_lossy().to_string(),
+        });
+        let response = self.client.restore_episode_file(request).await?;
+        info!("Got response from server: {response:?}");
+
+        Ok(())
+    }
+
+    pub async fn merge_podcasts(
+        &mut self,
+        source: PodcastId,
+        target: PodcastId,
+    ) -> Result<PodcastMergeResult> {
+        let request = tonic::Request::new(PodcastMerge {
+            source: source.0,
+            target: target.0,
+        });
+        let response = self.client.merge_podcasts(request).await?;
+        let response = response.into_inner();
+        info!("Got response from server: {response:?}");
+        Ok(response)
+    }
+
+    pub async fn set_feed_settings(
+        &mut self,
+        id: PodcastId,
+        settings: &FeedSettings,
+    ) -> Result<()> {
+        let request = tonic::Request::new(PodcastFeedSettings::new(id, settings));
+        let response = self.client.set_feed_settings(request).await?;
+        info!("Got response from server: {response:?}");
+
+        Ok(())
+    }
+
+    /// Sync played status and positions with the sync server configured on the server.
+    pub async fn sync_podcasts(&mut self) -> Result<()> {
+        let request = tonic::Request::new(Empty {});
+        let response = self.client.sync_podcasts(request).await?;
+        info!("Got response from server: {response:?}");
+
+        Ok(())
+    }
 }
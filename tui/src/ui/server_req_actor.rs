@@ -6,7 +6,7 @@ use crate::ui::{
     model::TxToMain,
     msg::{Msg, ServerReqResponse},
     music_player_client::Playback,
-    tui_cmd::{PlaylistCmd, TuiCmd},
+    tui_cmd::{PlaylistCmd, PodcastCmd, TuiCmd},
 };
 
 /// Actor that handles all requests to the Server via GRPC.
@@ -62,6 +62,49 @@ impl ServerRequestActor {
                 // result will be populated back via UpdateStream
                 let _ = self.client_handle.seek_backward().await?;
             }
+            TuiCmd::SeekTo(position) => {
+                // result will be populated back via UpdateStream
+                let _ = self.client_handle.seek_to(position).await?;
+            }
+            TuiCmd::SetAbRepeatA => {
+                // result will be populated back via UpdateStream
+                let _ = self.client_handle.set_ab_repeat_a().await?;
+            }
+            TuiCmd::SetAbRepeatB => {
+                // result will be populated back via UpdateStream
+                let _ = self.client_handle.set_ab_repeat_b().await?;
+            }
+            TuiCmd::ClearAbRepeat => {
+                // result will be populated back via UpdateStream
+                let _ = self.client_handle.clear_ab_repeat().await?;
+            }
+            TuiCmd::GetBookmarks => {
+                let res = self.client_handle.get_bookmarks().await?;
+
+                self.send_response(Msg::ServerReqResponse(ServerReqResponse::Bookmarks(res)));
+            }
+            TuiCmd::AddBookmark(name) => {
+                self.client_handle.add_bookmark(name).await?;
+                let res = self.client_handle.get_bookmarks().await?;
+
+                self.send_response(Msg::ServerReqResponse(ServerReqResponse::Bookmarks(res)));
+            }
+            TuiCmd::JumpToBookmark(id) => {
+                // result will be populated back via UpdateStream
+                let _ = self.client_handle.jump_to_bookmark(id).await?;
+            }
+            TuiCmd::RemoveBookmark(id) => {
+                self.client_handle.remove_bookmark(id).await?;
+                let res = self.client_handle.get_bookmarks().await?;
+
+                self.send_response(Msg::ServerReqResponse(ServerReqResponse::Bookmarks(res)));
+            }
+            TuiCmd::JumpChapter(forward) => {
+                // the new position will be populated back via UpdateStream
+                let res = self.client_handle.jump_chapter(forward).await?;
+
+                self.send_response(Msg::ServerReqResponse(ServerReqResponse::ChapterJump(res)));
+            }
             TuiCmd::VolumeUp => {
                 // result will be populated back via UpdateStream
                 let _ = self.client_handle.volume_up().await?;
@@ -78,6 +121,12 @@ impl ServerRequestActor {
                 // result will be populated back via UpdateStream
                 let _ = self.client_handle.speed_down().await?;
             }
+            TuiCmd::ToggleTrackSpeed => {
+                // the new speed will be populated back via UpdateStream
+                let res = self.client_handle.toggle_track_speed().await?;
+
+                self.send_response(Msg::ServerReqResponse(ServerReqResponse::TrackSpeed(res)));
+            }
             TuiCmd::SkipNext => {
                 // result will be populated back via UpdateStream
                 self.client_handle.skip_next().await?;
@@ -102,7 +151,76 @@ impl ServerRequestActor {
             TuiCmd::ReloadConfig => {
                 self.client_handle.reload_config().await?;
             }
+            TuiCmd::RescanLibrary => {
+                self.client_handle.rescan_library().await?;
+            }
+            TuiCmd::SetTrackFavorite { path, favorite } => {
+                // the change will be populated back via UpdateStream
+                self.client_handle
+                    .set_track_favorite(&path, favorite)
+                    .await?;
+            }
+            TuiCmd::PreviewTrack(path) => {
+                self.client_handle.preview_track(&path).await?;
+            }
+            TuiCmd::OrganizeLibrary { dry_run } => {
+                let res = self.client_handle.organize_library(dry_run).await;
+
+                // always respond, so the popup does not keep loading
+                self.send_response(Msg::ServerReqResponse(ServerReqResponse::Organize {
+                    dry_run,
+                    result: res.map_err(|err| format!("{err:#}")),
+                }));
+            }
+            TuiCmd::GetOutputDevices => {
+                let res = self.client_handle.get_output_devices().await?;
+
+                self.send_response(Msg::ServerReqResponse(ServerReqResponse::OutputDevices(
+                    res,
+                )));
+            }
+            TuiCmd::SetOutputDevice(name) => {
+                self.client_handle.set_output_device(name).await?;
+            }
+            TuiCmd::SetCrossfade(crossfade) => {
+                let _ = self.client_handle.set_crossfade(crossfade).await?;
+            }
+            TuiCmd::GetCapabilities => {
+                let res = self.client_handle.get_capabilities().await?;
+
+                self.send_response(Msg::ServerReqResponse(ServerReqResponse::Capabilities(res)));
+            }
+            TuiCmd::GetCastRenderers => {
+                let res = self.client_handle.get_cast_renderers().await;
+
+                // always respond, so the popup does not keep searching
+                self.send_response(Msg::ServerReqResponse(ServerReqResponse::CastRenderers(
+                    res.as_ref().cloned().unwrap_or_default(),
+                )));
+                res?;
+            }
+            TuiCmd::SetCastRenderer(id) => {
+                self.client_handle.set_cast_renderer(id).await?;
+            }
+            TuiCmd::GetAudioInfo => {
+                let res = self.client_handle.get_audio_info().await?;
+
+                self.send_response(Msg::ServerReqResponse(ServerReqResponse::AudioInfo(res)));
+            }
+            TuiCmd::GetWaveform => {
+                if let Some(res) = self.client_handle.get_waveform().await? {
+                    self.send_response(Msg::ServerReqResponse(ServerReqResponse::Waveform(res)));
+                }
+            }
             TuiCmd::Playlist(playlist_cmd) => self.handle_playlist_cmd(playlist_cmd).await?,
+            TuiCmd::Podcast(podcast_cmd) => {
+                // errors like a full disk have to be shown, not only logged
+                if let Err(err) = self.handle_podcast_cmd(podcast_cmd).await {
+                    self.send_response(Msg::ServerReqResponse(ServerReqResponse::PodcastError(
+                        format!("{err:#}"),
+                    )));
+                }
+            }
         }
 
         Ok(())
@@ -149,6 +267,44 @@ impl ServerRequestActor {
                 // result will be populated back via UpdateStream
                 self.client_handle.remove_deleted_tracks().await?;
             }
+            PlaylistCmd::SaveQueue => {
+                self.client_handle.save_queue().await?;
+            }
+            PlaylistCmd::RestoreQueue => {
+                // result will be populated back via UpdateStream
+                self.client_handle.restore_queue().await?;
+            }
+            PlaylistCmd::ToggleAutoQueue => {
+                let enabled = self.client_handle.toggle_auto_queue().await?;
+
+                self.send_response(Msg::ServerReqResponse(ServerReqResponse::AutoQueue(
+                    enabled,
+                )));
+            }
+            PlaylistCmd::GetQueues => {
+                let res = self.client_handle.get_queues().await?;
+
+                self.send_response(Msg::ServerReqResponse(ServerReqResponse::Queues(res)));
+            }
+            PlaylistCmd::CreateQueue(name) => {
+                self.client_handle.create_queue(name).await?;
+                let res = self.client_handle.get_queues().await?;
+
+                self.send_response(Msg::ServerReqResponse(ServerReqResponse::Queues(res)));
+            }
+            PlaylistCmd::SwitchQueue(name) => {
+                // the new playlist will be populated back via UpdateStream
+                self.client_handle.switch_queue(name).await?;
+                let res = self.client_handle.get_queues().await?;
+
+                self.send_response(Msg::ServerReqResponse(ServerReqResponse::Queues(res)));
+            }
+            PlaylistCmd::DeleteQueue(name) => {
+                self.client_handle.delete_queue(name).await?;
+                let res = self.client_handle.get_queues().await?;
+
+                self.send_response(Msg::ServerReqResponse(ServerReqResponse::Queues(res)));
+            }
             PlaylistCmd::SelfReloadPlaylist => {
                 let tracks = self.client_handle.get_playlist().await?;
 
@@ -161,6 +317,43 @@ impl ServerRequestActor {
         Ok(())
     }
 
+    /// Handle Podcast requests.
+    ///
+    /// Changed podcasts will be populated back via UpdateStream.
+    async fn handle_podcast_cmd(&mut self, cmd: PodcastCmd) -> Result<()> {
+        match cmd {
+            PodcastCmd::Add(url) => self.client_handle.add_podcast(url).await?,
+            PodcastCmd::Refresh(id) => self.client_handle.refresh_podcasts(id).await?,
+            PodcastCmd::Remove(id) => self.client_handle.remove_podcast(id).await?,
+            PodcastCmd::RemoveAll => self.client_handle.remove_all_podcasts().await?,
+            PodcastCmd::MarkPlayed { id, played } => {
+                self.client_handle.mark_episode_played(id, played).await?;
+            }
+            PodcastCmd::MarkAllPlayed { id, played } => {
+                self.client_handle.mark_feed_played(id, played).await?;
+            }
+            PodcastCmd::Download(ids) => self.client_handle.download_episodes(&ids).await?,
+            PodcastCmd::RemoveFiles(ids) => self.client_handle.remove_episode_files(&ids).await?,
+            PodcastCmd::RestoreFile(id, path) => {
+                self.client_handle.restore_episode_file(id, &path).await?;
+            }
+            PodcastCmd::Merge { source, target } => {
+                let res = self.client_handle.merge_podcasts(source, target).await?;
+
+                self.send_response(Msg::ServerReqResponse(ServerReqResponse::PodcastMerged {
+                    target,
+                    result: res,
+                }));
+            }
+            PodcastCmd::SetFeedSettings(id, settings) => {
+                self.client_handle.set_feed_settings(id, &settings).await?;
+            }
+            PodcastCmd::Sync => self.client_handle.sync_podcasts().await?,
+        }
+
+        Ok(())
+    }
+
     #[inline]
     fn send_response(&self, msg: Msg) {
         let _ = self.tx_main.send(msg);
@@ -1,6 +1,12 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use termusiclib::config::v2::server::CrossfadeSettings;
+use termusiclib::ids::{EpisodeId, PodcastId};
 use termusiclib::player::playlist_helpers::{
     PlaylistAddTrack, PlaylistPlaySpecific, PlaylistRemoveTrackIndexed, PlaylistSwapTrack,
 };
+use termusiclib::podcast::feed_settings::FeedSettings;
 
 #[allow(clippy::doc_link_with_quotes)]
 /// Enum for Commands to send to the [`MusicPlayerClient` "Actor"](crate::ui::music_player_client).
@@ -12,10 +18,30 @@ pub enum TuiCmd {
     // Pause,
     SeekForward,
     SeekBackward,
+    /// Seek to a absolute position in the current track
+    SeekTo(Duration),
+    /// Jump to the next chapter of the current track if `true`, otherwise back
+    JumpChapter(bool),
+    /// Set the start of the section of the current track to loop at the current position
+    SetAbRepeatA,
+    /// Set the end of the section of the current track to loop at the current position
+    SetAbRepeatB,
+    /// Stop looping a section of the current track
+    ClearAbRepeat,
+    /// Request the bookmarks of the current track
+    GetBookmarks,
+    /// Bookmark the current position of the current track with the given name
+    AddBookmark(String),
+    /// Seek to the bookmark with the given id
+    JumpToBookmark(i64),
+    /// Remove the bookmark with the given id
+    RemoveBookmark(i64),
     VolumeUp,
     VolumeDown,
     SpeedUp,
     SpeedDown,
+    /// Keep the current speed for the current track, or use the global speed for it again
+    ToggleTrackSpeed,
     SkipNext,
     SkipPrevious,
     ToggleGapless,
@@ -23,8 +49,37 @@ pub enum TuiCmd {
 
     GetProgress,
     ReloadConfig,
+    RescanLibrary,
+    /// Mark or unmark the library track at `path` as favorite
+    SetTrackFavorite {
+        path: PathBuf,
+        favorite: bool,
+    },
+    /// Play a short part of the library track at the path, without changing the playlist
+    PreviewTrack(PathBuf),
+    /// Let the server move the library tracks to the path of its organize pattern, or only list them in a `dry_run`
+    OrganizeLibrary {
+        dry_run: bool,
+    },
+    /// Request the audio output devices of the server's backend
+    GetOutputDevices,
+    /// Switch the server's audio output device, `None` for the default device
+    SetOutputDevice(Option<String>),
+    /// Change the server's crossfade between music tracks
+    SetCrossfade(CrossfadeSettings),
+    /// Request what the server's backend can play
+    GetCapabilities,
+    /// Let the server search for network renderers to cast to
+    GetCastRenderers,
+    /// Cast the server's playback to a renderer, `None` to play on the server again
+    SetCastRenderer(Option<String>),
+    /// Request how the server currently outputs audio
+    GetAudioInfo,
+    /// Request the waveform of the current track
+    GetWaveform,
 
     Playlist(PlaylistCmd),
+    Podcast(PodcastCmd),
 }
 
 /// Enum for Commands to send specificly for Playlist
@@ -37,8 +92,54 @@ pub enum PlaylistCmd {
     SwapTrack(PlaylistSwapTrack),
     Shuffle,
     RemoveDeletedItems,
+    /// Store the server's queue in its database
+    SaveQueue,
+    /// Replace the server's queue with the one stored in its database
+    RestoreQueue,
+    /// Toggle keeping the server's queue filled with tracks from the library
+    ToggleAutoQueue,
+    /// Request the named queues stored on the server
+    GetQueues,
+    /// Create a new empty named queue on the server
+    CreateQueue(String),
+    /// Store the server's queue and switch it to the named queue
+    SwitchQueue(String),
+    /// Delete a named queue on the server
+    DeleteQueue(String),
 
     /// Re-Request the playlist tracks and state
     #[allow(dead_code)] // replace with "expect" on 1.81 upgrade
     SelfReloadPlaylist,
 }
+
+/// Enum for Commands to send specificly for Podcasts, as only the server writes the podcast database
+#[derive(Clone, Debug)]
+pub enum PodcastCmd {
+    /// Fetch and subscribe to the feed at the url
+    Add(String),
+    /// Fetch new episodes of the podcast, or of all podcasts if `None`
+    Refresh(Option<PodcastId>),
+    /// Unsubscribe from the podcast, its downloaded files are kept
+    Remove(PodcastId),
+    /// Unsubscribe from all podcasts, their downloaded files are kept
+    RemoveAll,
+    /// Set the played status of the episode
+    MarkPlayed { id: EpisodeId, played: bool },
+    /// Set the played status of all episodes of the podcast
+    MarkAllPlayed { id: PodcastId, played: bool },
+    /// Download the episodes
+    Download(Vec<EpisodeId>),
+    /// Forget the downloaded files of the episodes, after they got deleted
+    RemoveFiles(Vec<EpisodeId>),
+    /// Record the path as the downloaded file of the episode again
+    RestoreFile(EpisodeId, PathBuf),
+    /// Merge the podcast `source` into `target` and remove `source`
+    Merge {
+        source: PodcastId,
+        target: PodcastId,
+    },
+    /// Store the settings of the podcast and apply them right away
+    SetFeedSettings(PodcastId, FeedSettings),
+    /// Sync played status and positions with the configured sync server
+    Sync,
+}